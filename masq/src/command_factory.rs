@@ -7,16 +7,26 @@ use crate::commands::commands_common::Command;
 use crate::commands::configuration_command::ConfigurationCommand;
 use crate::commands::connection_status_command::ConnectionStatusCommand;
 use crate::commands::crash_command::CrashCommand;
+use crate::commands::database_backup_command::DatabaseBackupCommand;
 use crate::commands::descriptor_command::DescriptorCommand;
+use crate::commands::export_wallet_command::ExportWalletCommand;
+use crate::commands::financials_command::adjust::FinancialsAdjustCommand;
+use crate::commands::financials_command::history::FinancialsHistoryCommand;
 use crate::commands::financials_command::FinancialsCommand;
 use crate::commands::generate_wallets_command::GenerateWalletsCommand;
+use crate::commands::logs_command::LogsCommand;
 use crate::commands::recover_wallets_command::RecoverWalletsCommand;
+use crate::commands::reload_config_command::ReloadConfigCommand;
 use crate::commands::scan_command::ScanCommand;
 use crate::commands::set_configuration_command::SetConfigurationCommand;
+use crate::commands::set_log_level_command::SetLogLevelCommand;
 use crate::commands::setup_command::SetupCommand;
 use crate::commands::shutdown_command::ShutdownCommand;
 use crate::commands::start_command::StartCommand;
+use crate::commands::transfer_command::TransferCommand;
+use crate::commands::wait_for_command::WaitForCommand;
 use crate::commands::wallet_addresses_command::WalletAddressesCommand;
+use crate::commands::wallet_balances_command::WalletBalancesCommand;
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum CommandFactoryError {
@@ -51,7 +61,27 @@ impl CommandFactory for CommandFactoryReal {
                 Ok(command) => Box::new(command),
                 Err(msg) => return Err(CommandSyntax(msg)),
             },
+            "database-backup" => match DatabaseBackupCommand::new(pieces) {
+                Ok(command) => Box::new(command),
+                Err(msg) => return Err(CommandSyntax(msg)),
+            },
             "descriptor" => Box::new(DescriptorCommand::new()),
+            "export-wallet" => match ExportWalletCommand::new(pieces) {
+                Ok(command) => Box::new(command),
+                Err(msg) => return Err(CommandSyntax(msg)),
+            },
+            "financials" if pieces.get(1).map(String::as_str) == Some("history") => {
+                match FinancialsHistoryCommand::new(pieces) {
+                    Ok(command) => Box::new(command),
+                    Err(msg) => return Err(CommandSyntax(msg)),
+                }
+            }
+            "financials" if pieces.get(1).map(String::as_str) == Some("adjust") => {
+                match FinancialsAdjustCommand::new(pieces) {
+                    Ok(command) => Box::new(command),
+                    Err(msg) => return Err(CommandSyntax(msg)),
+                }
+            }
             "financials" => match FinancialsCommand::new(pieces) {
                 Ok(command) => Box::new(command),
                 Err(msg) => return Err(CommandSyntax(msg)),
@@ -60,10 +90,15 @@ impl CommandFactory for CommandFactoryReal {
                 Ok(command) => Box::new(command),
                 Err(msg) => return Err(CommandSyntax(msg)),
             },
+            "logs" => match LogsCommand::new(pieces) {
+                Ok(command) => Box::new(command),
+                Err(msg) => return Err(CommandSyntax(msg)),
+            },
             "recover-wallets" => match RecoverWalletsCommand::new(pieces) {
                 Ok(command) => Box::new(command),
                 Err(msg) => return Err(CommandSyntax(msg)),
             },
+            "reload-config" => Box::new(ReloadConfigCommand::new()),
             "scan" => match ScanCommand::new(pieces) {
                 Ok(command) => Box::new(command),
                 Err(msg) => return Err(CommandSyntax(msg)),
@@ -72,6 +107,10 @@ impl CommandFactory for CommandFactoryReal {
                 Ok(command) => Box::new(command),
                 Err(msg) => return Err(CommandSyntax(msg)),
             },
+            "set-log-level" => match SetLogLevelCommand::new(pieces) {
+                Ok(command) => Box::new(command),
+                Err(msg) => return Err(CommandSyntax(msg)),
+            },
             "set-password" => match ChangePasswordCommand::new_set(pieces) {
                 Ok(command) => Box::new(command),
                 Err(msg) => return Err(CommandSyntax(msg)),
@@ -82,10 +121,22 @@ impl CommandFactory for CommandFactoryReal {
             },
             "shutdown" => Box::new(ShutdownCommand::new()),
             "start" => Box::new(StartCommand::new()),
+            "transfer" => match TransferCommand::new(pieces) {
+                Ok(command) => Box::new(command),
+                Err(msg) => return Err(CommandSyntax(msg)),
+            },
+            "wait-for" => match WaitForCommand::new(pieces) {
+                Ok(command) => Box::new(command),
+                Err(msg) => return Err(CommandSyntax(msg)),
+            },
             "wallet-addresses" => match WalletAddressesCommand::new(pieces) {
                 Ok(command) => Box::new(command),
                 Err(msg) => return Err(CommandSyntax(msg)),
             },
+            "wallet-balances" => match WalletBalancesCommand::new(pieces) {
+                Ok(command) => Box::new(command),
+                Err(msg) => return Err(CommandSyntax(msg)),
+            },
             unrecognized => return Err(UnrecognizedSubcommand(unrecognized.to_string())),
         };
         Ok(boxed_command)
@@ -4,6 +4,7 @@ use crate::command_context::CommandContextReal;
 use crate::command_context::{CommandContext, ContextError};
 use crate::commands::commands_common::{Command, CommandError};
 use crate::communications::broadcast_handler::BroadcastHandle;
+use crate::communications::connection_manager::UiEndpoint;
 use crate::terminal::terminal_interface::TerminalWrapper;
 use masq_lib::utils::ExpectValue;
 
@@ -12,7 +13,7 @@ pub trait CommandProcessorFactory {
         &self,
         terminal_interface: Option<TerminalWrapper>,
         generic_broadcast_handle: Box<dyn BroadcastHandle>,
-        ui_port: u16,
+        endpoint: UiEndpoint,
     ) -> Result<Box<dyn CommandProcessor>, CommandError>;
 }
 
@@ -24,9 +25,9 @@ impl CommandProcessorFactory for CommandProcessorFactoryReal {
         &self,
         terminal_interface: Option<TerminalWrapper>,
         generic_broadcast_handle: Box<dyn BroadcastHandle>,
-        ui_port: u16,
+        endpoint: UiEndpoint,
     ) -> Result<Box<dyn CommandProcessor>, CommandError> {
-        match CommandContextReal::new(ui_port, terminal_interface, generic_broadcast_handle) {
+        match CommandContextReal::new(endpoint, terminal_interface, generic_broadcast_handle) {
             Ok(context) => Ok(Box::new(CommandProcessorReal { context })),
             Err(ContextError::ConnectionRefused(s)) => Err(CommandError::ConnectionProblem(s)),
             Err(e) => panic!("Unexpected error: {:?}", e),
@@ -107,7 +108,7 @@ mod tests {
         let subject = CommandProcessorFactoryReal::new();
         let broadcast_handle = BroadcastHandleInactive;
 
-        let result = subject.make(None, Box::new(broadcast_handle), ui_port);
+        let result = subject.make(None, Box::new(broadcast_handle), UiEndpoint::local(ui_port));
 
         match result.err() {
             Some(CommandError::ConnectionProblem(_)) => (),
@@ -152,7 +153,11 @@ mod tests {
         let p_f = CommandProcessorFactoryReal::new();
         let stop_handle = server.start();
         let mut processor = p_f
-            .make(Some(terminal_interface), generic_broadcast_handle, ui_port)
+            .make(
+                Some(terminal_interface),
+                generic_broadcast_handle,
+                UiEndpoint::local(ui_port),
+            )
             .unwrap();
         processor
             .process(Box::new(CheckPasswordCommand {
@@ -0,0 +1,213 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::command_context::CommandContext;
+use crate::commands::commands_common::{transaction, Command, CommandError};
+use clap::{App, Arg, SubCommand};
+use masq_lib::messages::{UiDatabaseBackupRequest, UiDatabaseBackupResponse};
+use masq_lib::{as_any_ref_in_trait_impl, short_writeln};
+
+// A large database, encrypted, can take a while
+pub const DATABASE_BACKUP_COMMAND_TIMEOUT_MILLIS: u64 = 30000;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct DatabaseBackupCommand {
+    pub path: String,
+    pub db_password_opt: Option<String>,
+}
+
+impl DatabaseBackupCommand {
+    pub fn new(pieces: &[String]) -> Result<Self, String> {
+        let matches = match database_backup_subcommand().get_matches_from_safe(pieces) {
+            Ok(matches) => matches,
+            Err(e) => return Err(format!("{}", e)),
+        };
+        Ok(Self {
+            path: matches
+                .value_of("path")
+                .expect("path is not properly required")
+                .to_string(),
+            db_password_opt: matches.value_of("db-password").map(|s| s.to_string()),
+        })
+    }
+}
+
+impl Command for DatabaseBackupCommand {
+    fn execute(&self, context: &mut dyn CommandContext) -> Result<(), CommandError> {
+        let input = UiDatabaseBackupRequest {
+            path: self.path.clone(),
+            db_password_opt: self.db_password_opt.clone(),
+        };
+        let output: UiDatabaseBackupResponse =
+            transaction(input, context, DATABASE_BACKUP_COMMAND_TIMEOUT_MILLIS)?;
+        short_writeln!(
+            context.stdout(),
+            "Database backed up to {} ({} bytes, {})",
+            output.path,
+            output.size_bytes,
+            if output.encrypted {
+                "encrypted"
+            } else {
+                "not encrypted"
+            }
+        );
+        Ok(())
+    }
+
+    as_any_ref_in_trait_impl!();
+}
+
+const DATABASE_BACKUP_SUBCOMMAND_ABOUT: &str =
+    "Writes a consistent copy of the Node's database while the Node keeps running. Restore it \
+     later with MASQNode --restore-database.";
+const PATH_ARG_HELP: &str =
+    "Absolute path, on the Node's machine, of the backup file to write. It must not exist yet.";
+const DB_PASSWORD_ARG_HELP: &str =
+    "The current database password. If given, the backup is encrypted with it, and the same \
+     password will be needed to restore it.";
+
+pub fn database_backup_subcommand() -> App<'static, 'static> {
+    SubCommand::with_name("database-backup")
+        .about(DATABASE_BACKUP_SUBCOMMAND_ABOUT)
+        .arg(
+            Arg::with_name("path")
+                .help(PATH_ARG_HELP)
+                .value_name("PATH")
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("db-password")
+                .help(DB_PASSWORD_ARG_HELP)
+                .long("db-password")
+                .value_name("DB-PASSWORD")
+                .takes_value(true)
+                .required(false)
+                .case_insensitive(false),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_context::ContextError;
+    use crate::command_factory::{CommandFactory, CommandFactoryReal};
+    use crate::test_utils::mocks::CommandContextMock;
+    use masq_lib::messages::ToMessageBody;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(DATABASE_BACKUP_COMMAND_TIMEOUT_MILLIS, 30000);
+        assert_eq!(
+            DATABASE_BACKUP_SUBCOMMAND_ABOUT,
+            "Writes a consistent copy of the Node's database while the Node keeps running. \
+             Restore it later with MASQNode --restore-database."
+        );
+        assert_eq!(
+            PATH_ARG_HELP,
+            "Absolute path, on the Node's machine, of the backup file to write. It must not \
+             exist yet."
+        );
+        assert_eq!(
+            DB_PASSWORD_ARG_HELP,
+            "The current database password. If given, the backup is encrypted with it, and the \
+             same password will be needed to restore it."
+        );
+    }
+
+    #[test]
+    fn command_factory_makes_database_backup_command() {
+        let subject = CommandFactoryReal::new();
+
+        let result = subject
+            .make(&[
+                "database-backup".to_string(),
+                "/backups/node.db".to_string(),
+                "--db-password".to_string(),
+                "password".to_string(),
+            ])
+            .unwrap();
+
+        let command: &DatabaseBackupCommand = result.as_any().downcast_ref().unwrap();
+        assert_eq!(
+            command,
+            &DatabaseBackupCommand {
+                path: "/backups/node.db".to_string(),
+                db_password_opt: Some("password".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn path_is_required() {
+        let result = DatabaseBackupCommand::new(&["database-backup".to_string()]);
+
+        let msg = result.err().unwrap();
+        assert_eq!(
+            msg.contains("The following required arguments were not provided:"),
+            true,
+            "{}",
+            msg
+        );
+    }
+
+    #[test]
+    fn execute_reports_the_backup() {
+        let transact_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut context = CommandContextMock::new()
+            .transact_params(&transact_params_arc)
+            .transact_result(Ok(UiDatabaseBackupResponse {
+                path: "/backups/node.db".to_string(),
+                encrypted: true,
+                size_bytes: 123456,
+            }
+            .tmb(0)));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = DatabaseBackupCommand {
+            path: "/backups/node.db".to_string(),
+            db_password_opt: Some("password".to_string()),
+        };
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        let transact_params = transact_params_arc.lock().unwrap();
+        assert_eq!(
+            *transact_params,
+            vec![(
+                UiDatabaseBackupRequest {
+                    path: "/backups/node.db".to_string(),
+                    db_password_opt: Some("password".to_string()),
+                }
+                .tmb(0),
+                DATABASE_BACKUP_COMMAND_TIMEOUT_MILLIS
+            )]
+        );
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            "Database backed up to /backups/node.db (123456 bytes, encrypted)\n"
+        );
+        assert_eq!(stderr_arc.lock().unwrap().get_string(), String::new());
+    }
+
+    #[test]
+    fn execute_handles_error_from_node() {
+        let mut context = CommandContextMock::new().transact_result(Err(
+            ContextError::PayloadError(4644, "Bad password; can't encrypt backup".to_string()),
+        ));
+        let subject = DatabaseBackupCommand {
+            path: "/backups/node.db".to_string(),
+            db_password_opt: Some("wrong".to_string()),
+        };
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(
+            result,
+            Err(CommandError::Payload(
+                4644,
+                "Bad password; can't encrypt backup".to_string()
+            ))
+        );
+    }
+}
@@ -0,0 +1,371 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::command_context::CommandContext;
+use crate::commands::commands_common::{transaction, Command, CommandError};
+use clap::{App, Arg, SubCommand};
+use masq_lib::messages::{UiExportWalletRequest, UiExportWalletResponse, WalletExportFormat};
+use masq_lib::{as_any_ref_in_trait_impl, short_writeln};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::str::FromStr;
+
+// The key derivation that protects the keystore is deliberately slow
+pub const EXPORT_WALLET_COMMAND_TIMEOUT_MILLIS: u64 = 10000;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ExportWalletCommand {
+    pub db_password: String,
+    pub format: WalletExportFormat,
+    pub passphrase: String,
+    pub output_opt: Option<String>,
+}
+
+impl ExportWalletCommand {
+    pub fn new(pieces: &[String]) -> Result<Self, String> {
+        let matches = match export_wallet_subcommand().get_matches_from_safe(pieces) {
+            Ok(matches) => matches,
+            Err(e) => return Err(format!("{}", e)),
+        };
+        let format = matches
+            .value_of("format")
+            .map(|format| {
+                WalletExportFormat::from_str(format)
+                    .expect("clap schema does not restrict format properly")
+            })
+            .expect("format is not properly defaulted");
+        Ok(Self {
+            db_password: matches
+                .value_of("db-password")
+                .expect("db-password is not properly required")
+                .to_string(),
+            format,
+            passphrase: matches
+                .value_of("passphrase")
+                .expect("passphrase is not properly required")
+                .to_string(),
+            output_opt: matches.value_of("output").map(|s| s.to_string()),
+        })
+    }
+
+    fn write_to_file(path: &str, exported: &str) -> Result<(), CommandError> {
+        // Never overwrite: the file being replaced might be the only copy of some other key
+        OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(path)
+            .and_then(|mut file| file.write_all(exported.as_bytes()))
+            .map_err(|e| CommandError::Other(format!("Could not write '{}': {}", path, e)))
+    }
+}
+
+impl Command for ExportWalletCommand {
+    fn execute(&self, context: &mut dyn CommandContext) -> Result<(), CommandError> {
+        let input = UiExportWalletRequest {
+            db_password: self.db_password.clone(),
+            format: self.format,
+            passphrase: self.passphrase.clone(),
+        };
+        let output: UiExportWalletResponse =
+            transaction(input, context, EXPORT_WALLET_COMMAND_TIMEOUT_MILLIS)?;
+        match &self.output_opt {
+            Some(path) => {
+                Self::write_to_file(path, &output.exported)?;
+                short_writeln!(
+                    context.stdout(),
+                    "Consuming wallet {} exported to {}",
+                    output.wallet_address,
+                    path
+                );
+            }
+            None => short_writeln!(context.stdout(), "{}", output.exported),
+        }
+        Ok(())
+    }
+
+    as_any_ref_in_trait_impl!();
+}
+
+const EXPORT_WALLET_SUBCOMMAND_ABOUT: &str =
+    "Exports the consuming wallet's private key, encrypted with a passphrase of your choosing, in a \
+     format other Ethereum wallets can import.";
+const DB_PASSWORD_ARG_HELP: &str =
+    "The current database password (a password must be set to use this command).";
+const FORMAT_ARG_HELP: &str =
+    "The export format. 'keystore' is a Web3 Secret Storage (keystore v3) JSON file.";
+const PASSPHRASE_ARG_HELP: &str =
+    "The passphrase the exported key will be encrypted with. It does not need to be the same as \
+     the database password.";
+const OUTPUT_ARG_HELP: &str =
+    "A file to write the export to; it must not exist yet. Without this, the export is printed.";
+
+pub fn export_wallet_subcommand() -> App<'static, 'static> {
+    SubCommand::with_name("export-wallet")
+        .about(EXPORT_WALLET_SUBCOMMAND_ABOUT)
+        .arg(
+            Arg::with_name("db-password")
+                .help(DB_PASSWORD_ARG_HELP)
+                .value_name("DB-PASSWORD")
+                .required(true)
+                .case_insensitive(false),
+        )
+        .arg(
+            Arg::with_name("format")
+                .help(FORMAT_ARG_HELP)
+                .long("format")
+                .value_name("FORMAT")
+                .takes_value(true)
+                .possible_values(&["keystore"])
+                .case_insensitive(true)
+                .default_value("keystore"),
+        )
+        .arg(
+            Arg::with_name("passphrase")
+                .help(PASSPHRASE_ARG_HELP)
+                .long("passphrase")
+                .value_name("PASSPHRASE")
+                .takes_value(true)
+                .required(true)
+                .case_insensitive(false),
+        )
+        .arg(
+            Arg::with_name("output")
+                .help(OUTPUT_ARG_HELP)
+                .long("output")
+                .value_name("FILE")
+                .takes_value(true)
+                .required(false),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_context::ContextError;
+    use crate::command_factory::{CommandFactory, CommandFactoryReal};
+    use crate::test_utils::mocks::CommandContextMock;
+    use masq_lib::messages::ToMessageBody;
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+    use std::fs;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(EXPORT_WALLET_COMMAND_TIMEOUT_MILLIS, 10000);
+        assert_eq!(
+            EXPORT_WALLET_SUBCOMMAND_ABOUT,
+            "Exports the consuming wallet's private key, encrypted with a passphrase of your \
+             choosing, in a format other Ethereum wallets can import."
+        );
+        assert_eq!(
+            DB_PASSWORD_ARG_HELP,
+            "The current database password (a password must be set to use this command)."
+        );
+        assert_eq!(
+            FORMAT_ARG_HELP,
+            "The export format. 'keystore' is a Web3 Secret Storage (keystore v3) JSON file."
+        );
+        assert_eq!(
+            PASSPHRASE_ARG_HELP,
+            "The passphrase the exported key will be encrypted with. It does not need to be the \
+             same as the database password."
+        );
+        assert_eq!(
+            OUTPUT_ARG_HELP,
+            "A file to write the export to; it must not exist yet. Without this, the export is \
+             printed."
+        );
+    }
+
+    #[test]
+    fn command_factory_applies_defaults() {
+        let subject = CommandFactoryReal::new();
+
+        let result = subject
+            .make(&[
+                "export-wallet".to_string(),
+                "password".to_string(),
+                "--passphrase".to_string(),
+                "Open Sesame".to_string(),
+            ])
+            .unwrap();
+
+        let command: &ExportWalletCommand = result.as_any().downcast_ref().unwrap();
+        assert_eq!(
+            command,
+            &ExportWalletCommand {
+                db_password: "password".to_string(),
+                format: WalletExportFormat::Keystore,
+                passphrase: "Open Sesame".to_string(),
+                output_opt: None,
+            }
+        );
+    }
+
+    #[test]
+    fn passphrase_is_required() {
+        let result = ExportWalletCommand::new(&[
+            "export-wallet".to_string(),
+            "password".to_string(),
+            "--format".to_string(),
+            "keystore".to_string(),
+        ]);
+
+        let msg = result.err().unwrap();
+        assert_eq!(
+            msg.contains("The following required arguments were not provided:"),
+            true,
+            "{}",
+            msg
+        );
+    }
+
+    #[test]
+    fn unknown_format_is_rejected() {
+        let result = ExportWalletCommand::new(&[
+            "export-wallet".to_string(),
+            "password".to_string(),
+            "--passphrase".to_string(),
+            "Open Sesame".to_string(),
+            "--format".to_string(),
+            "mnemonic".to_string(),
+        ]);
+
+        let msg = result.err().unwrap();
+        assert_eq!(msg.contains("isn't a valid value"), true, "{}", msg);
+    }
+
+    #[test]
+    fn execute_prints_export_without_output_file() {
+        let transact_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut context = CommandContextMock::new()
+            .transact_params(&transact_params_arc)
+            .transact_result(Ok(UiExportWalletResponse {
+                wallet_address: "0x0123456789012345678901234567890123456789".to_string(),
+                format: WalletExportFormat::Keystore,
+                exported: "{\"version\":3}".to_string(),
+            }
+            .tmb(0)));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = ExportWalletCommand {
+            db_password: "password".to_string(),
+            format: WalletExportFormat::Keystore,
+            passphrase: "Open Sesame".to_string(),
+            output_opt: None,
+        };
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        let transact_params = transact_params_arc.lock().unwrap();
+        assert_eq!(
+            *transact_params,
+            vec![(
+                UiExportWalletRequest {
+                    db_password: "password".to_string(),
+                    format: WalletExportFormat::Keystore,
+                    passphrase: "Open Sesame".to_string(),
+                }
+                .tmb(0),
+                EXPORT_WALLET_COMMAND_TIMEOUT_MILLIS
+            )]
+        );
+        assert_eq!(stdout_arc.lock().unwrap().get_string(), "{\"version\":3}\n");
+        assert_eq!(stderr_arc.lock().unwrap().get_string(), String::new());
+    }
+
+    #[test]
+    fn execute_writes_export_to_output_file() {
+        let home_dir = ensure_node_home_directory_exists(
+            "export_wallet_command",
+            "execute_writes_export_to_output_file",
+        );
+        let output_path = home_dir.join("keystore.json");
+        let mut context = CommandContextMock::new().transact_result(Ok(UiExportWalletResponse {
+            wallet_address: "0x0123456789012345678901234567890123456789".to_string(),
+            format: WalletExportFormat::Keystore,
+            exported: "{\"version\":3}".to_string(),
+        }
+        .tmb(0)));
+        let stdout_arc = context.stdout_arc();
+        let subject = ExportWalletCommand {
+            db_password: "password".to_string(),
+            format: WalletExportFormat::Keystore,
+            passphrase: "Open Sesame".to_string(),
+            output_opt: Some(output_path.to_str().unwrap().to_string()),
+        };
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(fs::read_to_string(&output_path).unwrap(), "{\"version\":3}");
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            format!(
+                "Consuming wallet 0x0123456789012345678901234567890123456789 exported to {}\n",
+                output_path.to_str().unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn execute_refuses_to_overwrite_existing_file() {
+        let home_dir = ensure_node_home_directory_exists(
+            "export_wallet_command",
+            "execute_refuses_to_overwrite_existing_file",
+        );
+        let output_path = home_dir.join("keystore.json");
+        fs::write(&output_path, "precious").unwrap();
+        let mut context = CommandContextMock::new().transact_result(Ok(UiExportWalletResponse {
+            wallet_address: "0x0123456789012345678901234567890123456789".to_string(),
+            format: WalletExportFormat::Keystore,
+            exported: "{\"version\":3}".to_string(),
+        }
+        .tmb(0)));
+        let subject = ExportWalletCommand {
+            db_password: "password".to_string(),
+            format: WalletExportFormat::Keystore,
+            passphrase: "Open Sesame".to_string(),
+            output_opt: Some(output_path.to_str().unwrap().to_string()),
+        };
+
+        let result = subject.execute(&mut context);
+
+        match result {
+            Err(CommandError::Other(msg)) => assert_eq!(
+                msg.starts_with(&format!(
+                    "Could not write '{}': ",
+                    output_path.to_str().unwrap()
+                )),
+                true,
+                "{}",
+                msg
+            ),
+            x => panic!("Expected CommandError::Other, got {:?}", x),
+        }
+        assert_eq!(fs::read_to_string(&output_path).unwrap(), "precious");
+    }
+
+    #[test]
+    fn execute_handles_error_from_node() {
+        let mut context = CommandContextMock::new().transact_result(Err(
+            ContextError::PayloadError(4644, "Bad password; can't export wallets".to_string()),
+        ));
+        let subject = ExportWalletCommand {
+            db_password: "wrong".to_string(),
+            format: WalletExportFormat::Keystore,
+            passphrase: "Open Sesame".to_string(),
+            output_opt: None,
+        };
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(
+            result,
+            Err(CommandError::Payload(
+                4644,
+                "Bad password; can't export wallets".to_string()
+            ))
+        );
+    }
+}
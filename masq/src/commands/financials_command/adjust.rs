@@ -0,0 +1,508 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::command_context::CommandContext;
+use crate::commands::commands_common::{
+    transaction, Command, CommandError, STANDARD_COMMAND_TIMEOUT_MILLIS,
+};
+use clap::{App, Arg, ArgGroup, SubCommand};
+use masq_lib::messages::{
+    LedgerSide, UiAdjustBalanceRequest, UiAdjustBalanceResponse, UiForgiveDebtRequest,
+    UiForgiveDebtResponse,
+};
+use masq_lib::short_writeln;
+use thousands::Separable;
+
+const ADJUST_SUBCOMMAND_ABOUT: &str =
+    "Corrects the balance of a payable or receivable account by hand, or forgives what a debtor owes. \
+     Every adjustment is recorded in the audit trail together with its operator and reason. Only valid \
+     if Node is already running.";
+const DB_PASSWORD_ARG_HELP: &str =
+    "The current database password (a password must be set to use this command).";
+const WALLET_ARG_HELP: &str = "The wallet whose account is to be adjusted.";
+const PAYABLE_ARG_HELP: &str = "Adjusts what this Node owes to the wallet.";
+const RECEIVABLE_ARG_HELP: &str = "Adjusts what the wallet owes to this Node.";
+const CHANGE_ARG_HELP: &str =
+    "Signed amount in gwei to add to the balance, e.g. -1500 to lower it by 1,500 gwei.";
+const FORGIVE_ARG_HELP: &str =
+    "Writes off the wallet's whole debt to this Node and lifts its delinquency ban, if any.";
+const OPERATOR_ARG_HELP: &str = "Who is making the adjustment, for the audit trail.";
+const REASON_ARG_HELP: &str = "Why the adjustment is made, for the audit trail.";
+
+pub fn financials_adjust_subcommand() -> App<'static, 'static> {
+    SubCommand::with_name("adjust")
+        .about(ADJUST_SUBCOMMAND_ABOUT)
+        .arg(
+            Arg::with_name("db-password")
+                .help(DB_PASSWORD_ARG_HELP)
+                .value_name("DB-PASSWORD")
+                .required(true)
+                .case_insensitive(false),
+        )
+        .arg(
+            Arg::with_name("wallet")
+                .help(WALLET_ARG_HELP)
+                .value_name("WALLET")
+                .long("wallet")
+                .short("w")
+                .required(true)
+                .case_insensitive(false)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("payable")
+                .help(PAYABLE_ARG_HELP)
+                .long("payable")
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name("receivable")
+                .help(RECEIVABLE_ARG_HELP)
+                .long("receivable")
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name("forgive")
+                .help(FORGIVE_ARG_HELP)
+                .long("forgive")
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name("change")
+                .help(CHANGE_ARG_HELP)
+                .value_name("GWEI")
+                .long("change")
+                .short("c")
+                .takes_value(true)
+                .allow_hyphen_values(true)
+                .required_unless("forgive")
+                .conflicts_with("forgive")
+                .validator(validate_change),
+        )
+        .arg(
+            Arg::with_name("operator")
+                .help(OPERATOR_ARG_HELP)
+                .value_name("OPERATOR")
+                .long("operator")
+                .required(true)
+                .case_insensitive(false)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("reason")
+                .help(REASON_ARG_HELP)
+                .value_name("REASON")
+                .long("reason")
+                .required(true)
+                .case_insensitive(false)
+                .takes_value(true),
+        )
+        .group(
+            ArgGroup::with_name("ledger-action")
+                .args(&["payable", "receivable", "forgive"])
+                .required(true),
+        )
+}
+
+fn validate_change(change: String) -> Result<(), String> {
+    match change.parse::<i64>() {
+        Ok(0) => Err("Change must not be zero".to_string()),
+        Ok(_) => Ok(()),
+        Err(_) => Err(format!(
+            "Change '{}' is not a whole, signed number of gwei",
+            change
+        )),
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum LedgerAction {
+    Adjust {
+        ledger: LedgerSide,
+        change_gwei: i64,
+    },
+    Forgive,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct FinancialsAdjustCommand {
+    db_password: String,
+    wallet: String,
+    action: LedgerAction,
+    operator: String,
+    reason: String,
+}
+
+impl Command for FinancialsAdjustCommand {
+    fn execute(&self, context: &mut dyn CommandContext) -> Result<(), CommandError> {
+        let result = match self.action {
+            LedgerAction::Adjust {
+                ledger,
+                change_gwei,
+            } => self.adjust(context, ledger, change_gwei),
+            LedgerAction::Forgive => self.forgive(context),
+        };
+        if let Err(e) = &result {
+            short_writeln!(context.stderr(), "Ledger adjustment failed: {:?}", e);
+        }
+        result
+    }
+}
+
+impl FinancialsAdjustCommand {
+    // Expects the pieces of the whole command line, beginning with "financials"
+    pub fn new(pieces: &[String]) -> Result<Self, String> {
+        let matches = match financials_adjust_subcommand().get_matches_from_safe(&pieces[1..]) {
+            Ok(matches) => matches,
+            Err(e) => return Err(e.to_string()),
+        };
+        let action = if matches.is_present("forgive") {
+            LedgerAction::Forgive
+        } else {
+            LedgerAction::Adjust {
+                ledger: if matches.is_present("payable") {
+                    LedgerSide::Payable
+                } else {
+                    LedgerSide::Receivable
+                },
+                change_gwei: matches
+                    .value_of("change")
+                    .expect("change is not properly required")
+                    .parse()
+                    .expect("clap validation failed"),
+            }
+        };
+        let required = |name: &str| {
+            matches
+                .value_of(name)
+                .unwrap_or_else(|| panic!("{} is not properly required", name))
+                .to_string()
+        };
+        Ok(Self {
+            db_password: required("db-password"),
+            wallet: required("wallet"),
+            action,
+            operator: required("operator"),
+            reason: required("reason"),
+        })
+    }
+
+    fn adjust(
+        &self,
+        context: &mut dyn CommandContext,
+        ledger: LedgerSide,
+        change_gwei: i64,
+    ) -> Result<(), CommandError> {
+        let input = UiAdjustBalanceRequest {
+            db_password: self.db_password.clone(),
+            ledger,
+            wallet: self.wallet.clone(),
+            change_gwei,
+            operator: self.operator.clone(),
+            reason: self.reason.clone(),
+        };
+        let response: UiAdjustBalanceResponse =
+            transaction(input, context, STANDARD_COMMAND_TIMEOUT_MILLIS)?;
+        short_writeln!(
+            context.stdout(),
+            "The {} balance of {} went from {} to {} gwei",
+            match ledger {
+                LedgerSide::Payable => "payable",
+                LedgerSide::Receivable => "receivable",
+            },
+            self.wallet,
+            response.balance_before_gwei.separate_with_commas(),
+            response.balance_after_gwei.separate_with_commas()
+        );
+        Ok(())
+    }
+
+    fn forgive(&self, context: &mut dyn CommandContext) -> Result<(), CommandError> {
+        let input = UiForgiveDebtRequest {
+            db_password: self.db_password.clone(),
+            wallet: self.wallet.clone(),
+            operator: self.operator.clone(),
+            reason: self.reason.clone(),
+        };
+        let response: UiForgiveDebtResponse =
+            transaction(input, context, STANDARD_COMMAND_TIMEOUT_MILLIS)?;
+        short_writeln!(
+            context.stdout(),
+            "Forgave {} a debt of {} gwei{}",
+            self.wallet,
+            response.forgiven_gwei.separate_with_commas(),
+            if response.ban_lifted {
+                "; its delinquency ban is lifted"
+            } else {
+                ""
+            }
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_context::ContextError::PayloadError;
+    use crate::command_factory::{CommandFactory, CommandFactoryReal};
+    use crate::commands::commands_common::CommandError::Payload;
+    use crate::test_utils::mocks::CommandContextMock;
+    use masq_lib::constants::BAD_PASSWORD_ERROR;
+    use masq_lib::messages::ToMessageBody;
+    use masq_lib::utils::slice_of_strs_to_vec_of_strings;
+    use std::sync::{Arc, Mutex};
+
+    const WALLET: &str = "0x0123456789012345678901234567890123456789";
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(
+            ADJUST_SUBCOMMAND_ABOUT,
+            "Corrects the balance of a payable or receivable account by hand, or forgives what a debtor owes. \
+             Every adjustment is recorded in the audit trail together with its operator and reason. Only valid \
+             if Node is already running."
+        );
+        assert_eq!(
+            CHANGE_ARG_HELP,
+            "Signed amount in gwei to add to the balance, e.g. -1500 to lower it by 1,500 gwei."
+        );
+        assert_eq!(
+            FORGIVE_ARG_HELP,
+            "Writes off the wallet's whole debt to this Node and lifts its delinquency ban, if any."
+        );
+    }
+
+    #[test]
+    fn command_factory_produces_adjust_command_that_corrects_payable() {
+        let transact_params_arc = Arc::new(Mutex::new(vec![]));
+        let factory = CommandFactoryReal::new();
+        let mut context = CommandContextMock::new()
+            .transact_params(&transact_params_arc)
+            .transact_result(Ok(UiAdjustBalanceResponse {
+                balance_before_gwei: 5_000_000,
+                balance_after_gwei: 3_500_000,
+            }
+            .tmb(0)));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = factory
+            .make(&slice_of_strs_to_vec_of_strings(&[
+                "financials",
+                "adjust",
+                "password",
+                "--wallet",
+                WALLET,
+                "--payable",
+                "--change",
+                "-1500000",
+                "--operator",
+                "alice",
+                "--reason",
+                "charged twice",
+            ]))
+            .unwrap();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            *transact_params_arc.lock().unwrap(),
+            vec![(
+                UiAdjustBalanceRequest {
+                    db_password: "password".to_string(),
+                    ledger: LedgerSide::Payable,
+                    wallet: WALLET.to_string(),
+                    change_gwei: -1_500_000,
+                    operator: "alice".to_string(),
+                    reason: "charged twice".to_string(),
+                }
+                .tmb(0),
+                STANDARD_COMMAND_TIMEOUT_MILLIS
+            )]
+        );
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            format!(
+                "The payable balance of {} went from 5,000,000 to 3,500,000 gwei\n",
+                WALLET
+            )
+        );
+        assert_eq!(stderr_arc.lock().unwrap().get_string(), String::new());
+    }
+
+    #[test]
+    fn adjust_command_forgives_debt() {
+        let transact_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut context = CommandContextMock::new()
+            .transact_params(&transact_params_arc)
+            .transact_result(Ok(UiForgiveDebtResponse {
+                forgiven_gwei: 12_345,
+                ban_lifted: true,
+            }
+            .tmb(0)));
+        let stdout_arc = context.stdout_arc();
+        let subject = FinancialsAdjustCommand::new(&slice_of_strs_to_vec_of_strings(&[
+            "financials",
+            "adjust",
+            "password",
+            "--wallet",
+            WALLET,
+            "--forgive",
+            "--operator",
+            "bob",
+            "--reason",
+            "goodwill",
+        ]))
+        .unwrap();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            *transact_params_arc.lock().unwrap(),
+            vec![(
+                UiForgiveDebtRequest {
+                    db_password: "password".to_string(),
+                    wallet: WALLET.to_string(),
+                    operator: "bob".to_string(),
+                    reason: "goodwill".to_string(),
+                }
+                .tmb(0),
+                STANDARD_COMMAND_TIMEOUT_MILLIS
+            )]
+        );
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            format!(
+                "Forgave {} a debt of 12,345 gwei; its delinquency ban is lifted\n",
+                WALLET
+            )
+        );
+    }
+
+    #[test]
+    fn adjust_command_takes_receivable_change() {
+        let result = FinancialsAdjustCommand::new(&slice_of_strs_to_vec_of_strings(&[
+            "financials",
+            "adjust",
+            "password",
+            "-w",
+            WALLET,
+            "--receivable",
+            "-c",
+            "250",
+            "--operator",
+            "alice",
+            "--reason",
+            "missed payment",
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            result,
+            FinancialsAdjustCommand {
+                db_password: "password".to_string(),
+                wallet: WALLET.to_string(),
+                action: LedgerAction::Adjust {
+                    ledger: LedgerSide::Receivable,
+                    change_gwei: 250
+                },
+                operator: "alice".to_string(),
+                reason: "missed payment".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn adjust_command_rejects_inconsistent_arguments() {
+        let attempt = |args: &[&str]| {
+            let mut pieces = vec![
+                "financials",
+                "adjust",
+                "password",
+                "--wallet",
+                WALLET,
+                "--operator",
+                "alice",
+                "--reason",
+                "whatever",
+            ];
+            pieces.extend_from_slice(args);
+            FinancialsAdjustCommand::new(&slice_of_strs_to_vec_of_strings(&pieces)).unwrap_err()
+        };
+
+        let no_action = attempt(&[]);
+        let no_change = attempt(&["--payable"]);
+        let both_ledgers = attempt(&["--payable", "--receivable", "--change", "5"]);
+        let change_and_forgive = attempt(&["--forgive", "--change", "5"]);
+
+        assert!(
+            no_action.contains("<--payable|--receivable|--forgive>"),
+            "{}",
+            no_action
+        );
+        assert!(no_change.contains("--change <GWEI>"), "{}", no_change);
+        assert!(
+            both_ledgers.contains("cannot be used with"),
+            "{}",
+            both_ledgers
+        );
+        assert!(
+            change_and_forgive.contains("cannot be used with"),
+            "{}",
+            change_and_forgive
+        );
+    }
+
+    #[test]
+    fn validate_change_rejects_zero_and_non_numbers() {
+        assert_eq!(validate_change("-12".to_string()), Ok(()));
+        assert_eq!(
+            validate_change("0".to_string()),
+            Err("Change must not be zero".to_string())
+        );
+        assert_eq!(
+            validate_change("1.5".to_string()),
+            Err("Change '1.5' is not a whole, signed number of gwei".to_string())
+        );
+    }
+
+    #[test]
+    fn adjust_command_reports_refusal() {
+        let mut context = CommandContextMock::new().transact_result(Err(PayloadError(
+            BAD_PASSWORD_ERROR,
+            "Bad password; can't adjust the ledger".to_string(),
+        )));
+        let stderr_arc = context.stderr_arc();
+        let subject = FinancialsAdjustCommand::new(&slice_of_strs_to_vec_of_strings(&[
+            "financials",
+            "adjust",
+            "wrong",
+            "--wallet",
+            WALLET,
+            "--forgive",
+            "--operator",
+            "bob",
+            "--reason",
+            "goodwill",
+        ]))
+        .unwrap();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(
+            result,
+            Err(Payload(
+                BAD_PASSWORD_ERROR,
+                "Bad password; can't adjust the ledger".to_string()
+            ))
+        );
+        assert_eq!(
+            stderr_arc.lock().unwrap().get_string(),
+            format!(
+                "Ledger adjustment failed: Payload({}, \"Bad password; can't adjust the ledger\")\n",
+                BAD_PASSWORD_ERROR
+            )
+        );
+    }
+}
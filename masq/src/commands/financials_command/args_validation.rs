@@ -1,5 +1,7 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
+use crate::commands::financials_command::adjust::financials_adjust_subcommand;
+use crate::commands::financials_command::history::financials_history_subcommand;
 use crate::commands::financials_command::parsing_and_value_dressing::restricted::{
     parse_masq_range_to_gwei, parse_time_params,
 };
@@ -92,6 +94,8 @@ pub fn financials_subcommand() -> App<'static, 'static> {
                 .possible_values(&["balance", "age"])
                 .required(false),
         )
+        .subcommand(financials_history_subcommand())
+        .subcommand(financials_adjust_subcommand())
         .groups(&[
             ArgGroup::with_name("at_least_one_query")
                 .args(&["receivable", "payable", "top"])
@@ -0,0 +1,385 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::command_context::CommandContext;
+use crate::commands::commands_common::{
+    transaction, Command, CommandError, STANDARD_COMMAND_TIMEOUT_MILLIS,
+};
+use crate::commands::financials_command::parsing_and_value_dressing::restricted::{
+    parse_time_params, split_time_range,
+};
+use crate::commands::financials_command::pretty_print_utils::restricted::render_financials_history;
+use clap::{App, Arg, SubCommand};
+use masq_lib::messages::UiFinancialsHistoryResponse;
+use masq_lib::messages::{HistoryGranularity, UiFinancialsHistoryRequest};
+use masq_lib::short_writeln;
+
+const HISTORY_SUBCOMMAND_ABOUT: &str =
+    "Displays what this Node earned and spent for routing and exit services over time. Only valid if Node is already running.";
+const AGE_ARG_HELP: &str = "Limits the history to a span of time given as a range of ages in seconds, <MIN-AGE>-<MAX-AGE>, counted back from now. \
+ The default is the last week.";
+const GRANULARITY_ARG_HELP: &str =
+    "Length of the periods the history is summed over: hour, day or week. Weeks begin on Monday, UTC.";
+const WALLET_ARG_HELP: &str =
+    "Restricts the history to the earnings from and spending to a single wallet.";
+const GWEI_HELP: &str =
+    "Orders money values rendering in gwei of MASQ instead of whole MASQs as the default.";
+
+pub fn financials_history_subcommand() -> App<'static, 'static> {
+    SubCommand::with_name("history")
+        .about(HISTORY_SUBCOMMAND_ABOUT)
+        .arg(
+            Arg::with_name("age")
+                .help(AGE_ARG_HELP)
+                .value_name("AGE")
+                .long("age")
+                .short("a")
+                .required(false)
+                .case_insensitive(false)
+                .takes_value(true)
+                .default_value("0-604800")
+                .validator(validate_age_range),
+        )
+        .arg(
+            Arg::with_name("granularity")
+                .help(GRANULARITY_ARG_HELP)
+                .value_name("GRANULARITY")
+                .long("granularity")
+                .short("r")
+                .required(false)
+                .case_insensitive(false)
+                .takes_value(true)
+                .default_value("day")
+                .possible_values(&["hour", "day", "week"]),
+        )
+        .arg(
+            Arg::with_name("wallet")
+                .help(WALLET_ARG_HELP)
+                .value_name("WALLET")
+                .long("wallet")
+                .short("w")
+                .required(false)
+                .case_insensitive(false)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("gwei")
+                .help(GWEI_HELP)
+                .value_name("GWEI")
+                .long("gwei")
+                .short("g")
+                .case_insensitive(false)
+                .takes_value(false)
+                .required(false),
+        )
+}
+
+fn validate_age_range(range: String) -> Result<(), String> {
+    if !range.contains('-') {
+        return Err(format!(
+            "Age range '{}' is not in the format <MIN-AGE>-<MAX-AGE>",
+            range
+        ));
+    }
+    let (min_age, max_age) = split_time_range(&range);
+    let (min_age, max_age) = parse_time_params(min_age, max_age)?;
+    if min_age > max_age {
+        Err(format!(
+            "Age range '{}' is reversed; the minimum must not exceed the maximum",
+            range
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct FinancialsHistoryCommand {
+    min_age_s: u64,
+    max_age_s: u64,
+    granularity: HistoryGranularity,
+    wallet_opt: Option<String>,
+    gwei_precision: bool,
+}
+
+impl Command for FinancialsHistoryCommand {
+    fn execute(&self, context: &mut dyn CommandContext) -> Result<(), CommandError> {
+        let input = UiFinancialsHistoryRequest {
+            min_age_s: self.min_age_s,
+            max_age_s: self.max_age_s,
+            granularity: self.granularity,
+            wallet_opt: self.wallet_opt.clone(),
+        };
+        let output: Result<UiFinancialsHistoryResponse, CommandError> =
+            transaction(input, context, STANDARD_COMMAND_TIMEOUT_MILLIS);
+        match output {
+            Ok(response) => {
+                render_financials_history(
+                    context.stdout(),
+                    &response.periods,
+                    self.granularity,
+                    self.gwei_precision,
+                );
+                Ok(())
+            }
+            Err(e) => {
+                short_writeln!(
+                    context.stderr(),
+                    "Financials history retrieval failed: {:?}",
+                    e
+                );
+                Err(e)
+            }
+        }
+    }
+}
+
+impl FinancialsHistoryCommand {
+    // Expects the pieces of the whole command line, beginning with "financials"
+    pub fn new(pieces: &[String]) -> Result<Self, String> {
+        let matches = match financials_history_subcommand().get_matches_from_safe(&pieces[1..]) {
+            Ok(matches) => matches,
+            Err(e) => return Err(e.to_string()),
+        };
+        let (min_age, max_age) = split_time_range(matches.value_of("age").expect("defaulted"));
+        let (min_age_s, max_age_s) =
+            parse_time_params(min_age, max_age).expect("clap validation failed");
+        let granularity =
+            HistoryGranularity::try_from(matches.value_of("granularity").expect("defaulted"))
+                .expect("clap validation failed");
+        Ok(Self {
+            min_age_s,
+            max_age_s,
+            granularity,
+            wallet_opt: matches.value_of("wallet").map(|wallet| wallet.to_string()),
+            gwei_precision: matches.is_present("gwei"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_context::ContextError::ConnectionDropped;
+    use crate::command_factory::{CommandFactory, CommandFactoryReal};
+    use crate::commands::commands_common::CommandError::ConnectionProblem;
+    use crate::test_utils::mocks::CommandContextMock;
+    use masq_lib::messages::{ToMessageBody, UiFinancialsHistoryPeriod};
+    use masq_lib::utils::slice_of_strs_to_vec_of_strings;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(
+            HISTORY_SUBCOMMAND_ABOUT,
+            "Displays what this Node earned and spent for routing and exit services over time. Only valid if Node is already running."
+        );
+        assert_eq!(
+            AGE_ARG_HELP,
+            "Limits the history to a span of time given as a range of ages in seconds, <MIN-AGE>-<MAX-AGE>, counted back from now. \
+ The default is the last week."
+        );
+        assert_eq!(
+            GRANULARITY_ARG_HELP,
+            "Length of the periods the history is summed over: hour, day or week. Weeks begin on Monday, UTC."
+        );
+        assert_eq!(
+            WALLET_ARG_HELP,
+            "Restricts the history to the earnings from and spending to a single wallet."
+        );
+    }
+
+    #[test]
+    fn command_factory_produces_history_command_with_defaults() {
+        let transact_params_arc = Arc::new(Mutex::new(vec![]));
+        let factory = CommandFactoryReal::new();
+        let mut context = CommandContextMock::new()
+            .transact_params(&transact_params_arc)
+            .transact_result(Ok(UiFinancialsHistoryResponse { periods: vec![] }.tmb(0)));
+        let subject = factory
+            .make(&slice_of_strs_to_vec_of_strings(&["financials", "history"]))
+            .unwrap();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            *transact_params_arc.lock().unwrap(),
+            vec![(
+                UiFinancialsHistoryRequest {
+                    min_age_s: 0,
+                    max_age_s: 604_800,
+                    granularity: HistoryGranularity::Day,
+                    wallet_opt: None,
+                }
+                .tmb(0),
+                STANDARD_COMMAND_TIMEOUT_MILLIS
+            )]
+        );
+    }
+
+    #[test]
+    fn history_command_takes_all_arguments() {
+        let result = FinancialsHistoryCommand::new(&slice_of_strs_to_vec_of_strings(&[
+            "financials",
+            "history",
+            "--age",
+            "3600-86400",
+            "--granularity",
+            "hour",
+            "--wallet",
+            "0x0123456789012345678901234567890123456789",
+            "--gwei",
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            result,
+            FinancialsHistoryCommand {
+                min_age_s: 3_600,
+                max_age_s: 86_400,
+                granularity: HistoryGranularity::Hour,
+                wallet_opt: Some("0x0123456789012345678901234567890123456789".to_string()),
+                gwei_precision: true,
+            }
+        );
+    }
+
+    #[test]
+    fn validate_age_range_rejects_bad_ranges() {
+        assert_eq!(validate_age_range("0-3600".to_string()), Ok(()));
+        assert_eq!(
+            validate_age_range("3600".to_string()),
+            Err("Age range '3600' is not in the format <MIN-AGE>-<MAX-AGE>".to_string())
+        );
+        assert_eq!(
+            validate_age_range("7200-3600".to_string()),
+            Err(
+                "Age range '7200-3600' is reversed; the minimum must not exceed the maximum"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            validate_age_range("0-abc".to_string()),
+            Err("Non numeric value 'abc', it must be a valid integer".to_string())
+        );
+    }
+
+    #[test]
+    fn history_command_complains_about_unknown_granularity() {
+        let result = FinancialsHistoryCommand::new(&slice_of_strs_to_vec_of_strings(&[
+            "financials",
+            "history",
+            "--granularity",
+            "fortnight",
+        ]));
+
+        let err = result.unwrap_err();
+        assert!(
+            err.contains("'fortnight' isn't a valid value for '--granularity <GRANULARITY>'"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn history_command_sends_request_and_renders_periods() {
+        let transact_params_arc = Arc::new(Mutex::new(vec![]));
+        let expected_response = UiFinancialsHistoryResponse {
+            periods: vec![
+                UiFinancialsHistoryPeriod {
+                    start_s: 1_704_067_200,
+                    earned_routing_gwei: 1_200_000_000,
+                    earned_exit_gwei: 34_000,
+                    spent_routing_gwei: 0,
+                    spent_exit_gwei: 5_670_000_000,
+                },
+                UiFinancialsHistoryPeriod {
+                    start_s: 1_704_672_000,
+                    earned_routing_gwei: 0,
+                    earned_exit_gwei: 0,
+                    spent_routing_gwei: 8_000_000_000,
+                    spent_exit_gwei: 0,
+                },
+            ],
+        };
+        let mut context = CommandContextMock::new()
+            .transact_params(&transact_params_arc)
+            .transact_result(Ok(expected_response.tmb(31)));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = FinancialsHistoryCommand::new(&slice_of_strs_to_vec_of_strings(&[
+            "financials",
+            "history",
+            "--granularity",
+            "week",
+        ]))
+        .unwrap();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        let transact_params = transact_params_arc.lock().unwrap();
+        assert_eq!(
+            *transact_params,
+            vec![(
+                UiFinancialsHistoryRequest {
+                    min_age_s: 0,
+                    max_age_s: 604_800,
+                    granularity: HistoryGranularity::Week,
+                    wallet_opt: None,
+                }
+                .tmb(0),
+                STANDARD_COMMAND_TIMEOUT_MILLIS
+            )]
+        );
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            "\nEarnings and spending by week in MASQ\n\n\
+             Period start (UTC)   Earned routing   Earned exit   Spent routing   Spent exit\n\
+             2024-01-01 00:00     1.20             < 0.01        < 0.01          5.67      \n\
+             2024-01-08 00:00     < 0.01           < 0.01        8.00            < 0.01    \n"
+        );
+        assert_eq!(stderr_arc.lock().unwrap().get_string(), String::new());
+    }
+
+    #[test]
+    fn history_command_reports_empty_history() {
+        let mut context = CommandContextMock::new()
+            .transact_result(Ok(UiFinancialsHistoryResponse { periods: vec![] }.tmb(31)));
+        let stdout_arc = context.stdout_arc();
+        let subject = FinancialsHistoryCommand::new(&slice_of_strs_to_vec_of_strings(&[
+            "financials",
+            "history",
+            "--gwei",
+        ]))
+        .unwrap();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            "\nEarnings and spending by day in gwei\n\nNothing earned or spent in this span\n"
+        );
+    }
+
+    #[test]
+    fn history_command_handles_transaction_failure() {
+        let mut context =
+            CommandContextMock::new().transact_result(Err(ConnectionDropped("Booga".to_string())));
+        let stderr_arc = context.stderr_arc();
+        let subject = FinancialsHistoryCommand::new(&slice_of_strs_to_vec_of_strings(&[
+            "financials",
+            "history",
+        ]))
+        .unwrap();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Err(ConnectionProblem("Booga".to_string())));
+        assert_eq!(
+            stderr_arc.lock().unwrap().get_string(),
+            "Financials history retrieval failed: ConnectionProblem(\"Booga\")\n"
+        );
+    }
+}
@@ -1,7 +1,9 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
+pub mod adjust;
 pub mod args_validation;
 pub mod data_structures;
+pub mod history;
 pub mod parsing_and_value_dressing;
 pub mod pretty_print_utils;
 #[cfg(test)]
@@ -21,13 +23,13 @@ use crate::commands::financials_command::parsing_and_value_dressing::restricted:
 use crate::commands::financials_command::pretty_print_utils::restricted::process_gwei_into_requested_format;
 use crate::commands::financials_command::pretty_print_utils::restricted::{
     financial_status_totals_title, main_title_for_tops_opt, no_records_found, prepare_metadata,
-    render_accounts_generic, subtitle_for_tops, title_for_custom_query,
-    triple_or_single_blank_line, StringValuesFormattableAccount,
+    render_accounts_generic, render_fee_bumps, spending_budget_title, subtitle_for_tops,
+    title_for_custom_query, triple_or_single_blank_line, StringValuesFormattableAccount,
 };
 use clap::ArgMatches;
 use masq_lib::messages::{
     CustomQueries, QueryResults, RangeQuery, TopRecordsConfig, UiFinancialStatistics,
-    UiFinancialsRequest, UiFinancialsResponse,
+    UiFinancialsRequest, UiFinancialsResponse, UiSpendingBudget,
 };
 use masq_lib::short_writeln;
 use masq_lib::utils::ExpectValue;
@@ -104,6 +106,13 @@ impl FinancialsCommand {
         if let Some(ref stats) = response.stats_opt {
             self.process_financial_statistics(stdout, stats, self.gwei_precision)
         };
+        if let Some(ref budget) = response.spending_budget_opt {
+            self.process_spending_budget(stdout, budget, self.gwei_precision)
+        };
+        match response.fee_bumps_opt {
+            Some(ref fee_bumps) if !fee_bumps.is_empty() => render_fee_bumps(stdout, fee_bumps),
+            _ => (),
+        }
         if let Some(results) = response.query_results_opt {
             self.process_queried_records(
                 stdout,
@@ -137,6 +146,41 @@ impl FinancialsCommand {
         );
     }
 
+    fn process_spending_budget(
+        &self,
+        stdout: &mut dyn Write,
+        budget: &UiSpendingBudget,
+        gwei_flag: bool,
+    ) {
+        let remaining = |limit_gwei: u64, spent_gwei: u64| match limit_gwei {
+            0 => "Unlimited".to_string(),
+            _ => {
+                process_gwei_into_requested_format(limit_gwei.saturating_sub(spent_gwei), gwei_flag)
+            }
+        };
+        spending_budget_title(stdout, gwei_flag);
+        dump_parameter_line(
+            stdout,
+            "Spent today:",
+            &process_gwei_into_requested_format(budget.daily_spent_gwei, gwei_flag),
+        );
+        dump_parameter_line(
+            stdout,
+            "Remaining today:",
+            &remaining(budget.daily_limit_gwei, budget.daily_spent_gwei),
+        );
+        dump_parameter_line(
+            stdout,
+            "Spent this month:",
+            &process_gwei_into_requested_format(budget.monthly_spent_gwei, gwei_flag),
+        );
+        dump_parameter_line(
+            stdout,
+            "Remaining this month:",
+            &remaining(budget.monthly_limit_gwei, budget.monthly_spent_gwei),
+        );
+    }
+
     fn process_queried_records(
         &self,
         stdout: &mut dyn Write,
@@ -325,7 +369,7 @@ mod tests {
     use atty::Stream;
     use masq_lib::messages::{
         ToMessageBody, TopRecordsOrdering, UiFinancialStatistics, UiFinancialsResponse,
-        UiPayableAccount, UiReceivableAccount,
+        UiPayableAccount, UiPendingPayableFeeBump, UiReceivableAccount, UiSpendingBudget,
     };
     use masq_lib::ui_gateway::MessageBody;
     use masq_lib::utils::slice_of_strs_to_vec_of_strings;
@@ -336,6 +380,8 @@ mod tests {
         UiFinancialsResponse {
             stats_opt: None,
             query_results_opt: None,
+            fee_bumps_opt: None,
+            spending_budget_opt: None,
         }
         .tmb(0)
     }
@@ -913,6 +959,8 @@ mod tests {
                 total_paid_receivable_gwei: 1_278_766_555_456,
             }),
             query_results_opt: None,
+            fee_bumps_opt: None,
+            spending_budget_opt: None,
         };
         let mut context = CommandContextMock::new()
             .transact_params(&transact_params_arc)
@@ -1067,9 +1115,122 @@ mod tests {
                     receivable_opt: None,
                 }
             }),
+            fee_bumps_opt: None,
+            spending_budget_opt: None,
         }
     }
 
+    #[test]
+    fn financials_command_prints_fee_bumps_after_statistics() {
+        let response = UiFinancialsResponse {
+            stats_opt: Some(UiFinancialStatistics {
+                total_unpaid_and_pending_payable_gwei: 116688,
+                total_paid_payable_gwei: 55555,
+                total_unpaid_receivable_gwei: 221144,
+                total_paid_receivable_gwei: 66555,
+            }),
+            query_results_opt: None,
+            fee_bumps_opt: Some(vec![
+                UiPendingPayableFeeBump {
+                    replaced_tx_hash:
+                        "0x0290db1d56121112f4d45c1c3f36348644f6afd20b759b762f1dba9c4949066e"
+                            .to_string(),
+                    replacement_tx_hash:
+                        "0x5e6f7a8b9c0d1e2f3a4b5c6d7e8f9a0b1c2d3e4f5a6b7c8d9e0f1a2b3c4d5e6f"
+                            .to_string(),
+                    gas_price_wei: 1_200_000_000,
+                    age_s: 3600,
+                },
+                UiPendingPayableFeeBump {
+                    replaced_tx_hash:
+                        "0x5e6f7a8b9c0d1e2f3a4b5c6d7e8f9a0b1c2d3e4f5a6b7c8d9e0f1a2b3c4d5e6f"
+                            .to_string(),
+                    replacement_tx_hash:
+                        "0xabcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789"
+                            .to_string(),
+                    gas_price_wei: 1_440_000_000,
+                    age_s: 15,
+                },
+            ]),
+            spending_budget_opt: None,
+        };
+        let args = slice_of_strs_to_vec_of_strings(&["financials", "--gwei"]);
+        let mut context = CommandContextMock::new().transact_result(Ok(response.tmb(31)));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = FinancialsCommand::new(&args).unwrap();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            "\
+            \n\
+            Financial status totals in gwei\n\
+            \n\
+            Unpaid and pending payable:       116,688\n\
+            Paid payable:                     55,555\n\
+            Unpaid receivable:                221,144\n\
+            Paid receivable:                  66,555\n\
+            \n\
+            Fee bumps of stuck pending payables\n\
+            \n\
+            #   Replaced tx                                                          Replacement tx                                                       Gas price [wei]   Age [s]\n\
+            1   0x0290db1d56121112f4d45c1c3f36348644f6afd20b759b762f1dba9c4949066e   0x5e6f7a8b9c0d1e2f3a4b5c6d7e8f9a0b1c2d3e4f5a6b7c8d9e0f1a2b3c4d5e6f   1,200,000,000     3,600  \n\
+            2   0x5e6f7a8b9c0d1e2f3a4b5c6d7e8f9a0b1c2d3e4f5a6b7c8d9e0f1a2b3c4d5e6f   0xabcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789   1,440,000,000     15     \n"
+        );
+        assert_eq!(stderr_arc.lock().unwrap().get_string(), String::new());
+    }
+
+    #[test]
+    fn financials_command_prints_spending_budget_after_statistics() {
+        let response = UiFinancialsResponse {
+            stats_opt: Some(UiFinancialStatistics {
+                total_unpaid_and_pending_payable_gwei: 3_250_000_000,
+                total_paid_payable_gwei: 0,
+                total_unpaid_receivable_gwei: 0,
+                total_paid_receivable_gwei: 0,
+            }),
+            query_results_opt: None,
+            fee_bumps_opt: Some(vec![]),
+            spending_budget_opt: Some(UiSpendingBudget {
+                daily_limit_gwei: 5_000_000_000,
+                daily_spent_gwei: 1_250_000_000,
+                monthly_limit_gwei: 0,
+                monthly_spent_gwei: 3_250_000_000,
+            }),
+        };
+        let args = slice_of_strs_to_vec_of_strings(&["financials"]);
+        let mut context = CommandContextMock::new().transact_result(Ok(response.tmb(31)));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = FinancialsCommand::new(&args).unwrap();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            "\
+            \n\
+            Financial status totals in MASQ\n\
+            \n\
+            Unpaid and pending payable:       3.25\n\
+            Paid payable:                     < 0.01\n\
+            Unpaid receivable:                < 0.01\n\
+            Paid receivable:                  < 0.01\n\
+            \n\
+            Spending budget in MASQ\n\
+            \n\
+            Spent today:                      1.25\n\
+            Remaining today:                  3.75\n\
+            Spent this month:                 3.25\n\
+            Remaining this month:             Unlimited\n"
+        );
+        assert_eq!(stderr_arc.lock().unwrap().get_string(), String::new());
+    }
+
     #[test]
     fn financials_command_stats_and_top_records_default_units_as_masq() {
         let transact_params_arc = Arc::new(Mutex::new(vec![]));
@@ -1363,6 +1524,8 @@ mod tests {
                     balance_gwei: 5050330000,
                 }]),
             }),
+            fee_bumps_opt: None,
+            spending_budget_opt: None,
         };
         let args = slice_of_strs_to_vec_of_strings(&[
             "financials",
@@ -1438,6 +1601,8 @@ mod tests {
                 payable_opt: Some(vec![]),
                 receivable_opt: Some(vec![]),
             }),
+            fee_bumps_opt: None,
+            spending_budget_opt: None,
         };
         let args = slice_of_strs_to_vec_of_strings(&["financials", "--top", "10"]);
         let mut context = CommandContextMock::new()
@@ -1515,6 +1680,8 @@ mod tests {
                 payable_opt: None,
                 receivable_opt: None,
             }),
+            fee_bumps_opt: None,
+            spending_budget_opt: None,
         };
         let args = slice_of_strs_to_vec_of_strings(&[
             "financials",
@@ -1628,6 +1795,8 @@ mod tests {
                     },
                 ]),
             }),
+            fee_bumps_opt: None,
+            spending_budget_opt: None,
         };
         let args = slice_of_strs_to_vec_of_strings(&["financials", "--no-stats", "--top", "7"]);
         let mut context = CommandContextMock::new()
@@ -1709,6 +1878,8 @@ mod tests {
                 ]),
                 receivable_opt: None,
             }),
+            fee_bumps_opt: None,
+            spending_budget_opt: None,
         };
         let args = slice_of_strs_to_vec_of_strings(&[
             "financials",
@@ -1785,6 +1956,8 @@ mod tests {
                     },
                 ]),
             }),
+            fee_bumps_opt: None,
+            spending_budget_opt: None,
         };
         let args = slice_of_strs_to_vec_of_strings(&[
             "financials",
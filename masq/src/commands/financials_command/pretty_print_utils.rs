@@ -9,12 +9,16 @@ pub(in crate::commands::financials_command) mod restricted {
     };
     use crate::commands::financials_command::FinancialsCommand;
     use masq_lib::constants::WALLET_ADDRESS_LENGTH;
-    use masq_lib::messages::{UiPayableAccount, UiReceivableAccount};
+    use masq_lib::messages::{
+        HistoryGranularity, UiFinancialsHistoryPeriod, UiPayableAccount, UiPendingPayableFeeBump,
+        UiReceivableAccount,
+    };
     use masq_lib::short_writeln;
     use masq_lib::utils::to_string;
     use std::fmt::{Debug, Display};
     use std::io::Write;
     use thousands::Separable;
+    use time::OffsetDateTime;
 
     pub trait StringValuesFormattableAccount {
         fn convert_to_strings(&self, ordinal_num: usize, is_gwei: bool) -> Vec<String>;
@@ -55,6 +59,14 @@ pub(in crate::commands::financials_command) mod restricted {
         );
     }
 
+    pub fn spending_budget_title(stdout: &mut dyn Write, is_gwei: bool) {
+        short_writeln!(
+            stdout,
+            "\nSpending budget in {}\n",
+            &gwei_or_masq_balance(is_gwei)[9..13]
+        );
+    }
+
     pub fn main_title_for_tops_opt(fin_com: &FinancialsCommand, stdout: &mut dyn Write) {
         if let Some(tr_config) = fin_com.top_records_opt.as_ref() {
             short_writeln!(stdout, "Up to {} top accounts\n", tr_config.count)
@@ -106,6 +118,96 @@ pub(in crate::commands::financials_command) mod restricted {
         });
     }
 
+    pub fn render_fee_bumps(stdout: &mut dyn Write, fee_bumps: &[UiPendingPayableFeeBump]) {
+        short_writeln!(stdout, "\nFee bumps of stuck pending payables\n");
+        let headings = vec![
+            "#".to_string(),
+            "Replaced tx".to_string(),
+            "Replacement tx".to_string(),
+            "Gas price [wei]".to_string(),
+            "Age [s]".to_string(),
+        ];
+        let rows = fee_bumps
+            .iter()
+            .enumerate()
+            .map(|(idx, fee_bump)| {
+                vec![
+                    (idx + 1).to_string(),
+                    fee_bump.replaced_tx_hash.clone(),
+                    fee_bump.replacement_tx_hash.clone(),
+                    fee_bump.gas_price_wei.separate_with_commas(),
+                    fee_bump.age_s.separate_with_commas(),
+                ]
+            })
+            .collect::<Vec<_>>();
+        let widths = rows
+            .iter()
+            .fold(widths_of_str_values(&headings), |acc, row| {
+                yield_bigger_values_from_vecs(acc, &widths_of_str_values(row))
+            });
+        write_column_formatted(stdout, &zip_them(&headings, &widths));
+        rows.iter()
+            .for_each(|row| write_column_formatted(stdout, &zip_them(row, &widths)));
+    }
+
+    pub fn render_financials_history(
+        stdout: &mut dyn Write,
+        periods: &[UiFinancialsHistoryPeriod],
+        granularity: HistoryGranularity,
+        is_gwei: bool,
+    ) {
+        let granularity_name = match granularity {
+            HistoryGranularity::Hour => "hour",
+            HistoryGranularity::Day => "day",
+            HistoryGranularity::Week => "week",
+        };
+        short_writeln!(
+            stdout,
+            "\nEarnings and spending by {} in {}\n",
+            granularity_name,
+            &gwei_or_masq_units(is_gwei)[1..5]
+        );
+        if periods.is_empty() {
+            short_writeln!(stdout, "Nothing earned or spent in this span");
+            return;
+        }
+        let headings = vec![
+            "Period start (UTC)".to_string(),
+            "Earned routing".to_string(),
+            "Earned exit".to_string(),
+            "Spent routing".to_string(),
+            "Spent exit".to_string(),
+        ];
+        let rows = periods
+            .iter()
+            .map(|period| {
+                let start = OffsetDateTime::from_unix_timestamp(period.start_s as i64)
+                    .expect("Node sent a period start out of range");
+                vec![
+                    format!(
+                        "{}-{:02}-{:02} {:02}:00",
+                        start.year(),
+                        start.month() as u8,
+                        start.day(),
+                        start.hour()
+                    ),
+                    process_gwei_into_requested_format(period.earned_routing_gwei, is_gwei),
+                    process_gwei_into_requested_format(period.earned_exit_gwei, is_gwei),
+                    process_gwei_into_requested_format(period.spent_routing_gwei, is_gwei),
+                    process_gwei_into_requested_format(period.spent_exit_gwei, is_gwei),
+                ]
+            })
+            .collect::<Vec<_>>();
+        let widths = rows
+            .iter()
+            .fold(widths_of_str_values(&headings), |acc, row| {
+                yield_bigger_values_from_vecs(acc, &widths_of_str_values(row))
+            });
+        write_column_formatted(stdout, &zip_them(&headings, &widths));
+        rows.iter()
+            .for_each(|row| write_column_formatted(stdout, &zip_them(row, &widths)));
+    }
+
     pub fn process_gwei_into_requested_format<N>(gwei: N, should_stay_gwei: bool) -> String
     where
         N: From<u32> + Separable + Display,
@@ -0,0 +1,321 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::command_context::CommandContext;
+use crate::commands::commands_common::{
+    transaction, Command, CommandError, STANDARD_COMMAND_TIMEOUT_MILLIS,
+};
+use crate::terminal::terminal_interface::TerminalWrapper;
+use clap::{App, Arg, ArgGroup, SubCommand};
+use masq_lib::messages::{UiLogStreamBroadcast, UiLogStreamRequest, UiLogStreamResponse};
+use masq_lib::{as_any_ref_in_trait_impl, short_writeln};
+use std::io::Write;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct LogsCommand {
+    pub follow: bool,
+    pub level: String,
+    pub module_opt: Option<String>,
+}
+
+impl LogsCommand {
+    pub fn new(pieces: &[String]) -> Result<Self, String> {
+        let matches = match logs_subcommand().get_matches_from_safe(pieces) {
+            Ok(matches) => matches,
+            Err(e) => return Err(format!("{}", e)),
+        };
+        Ok(Self {
+            follow: matches.is_present("follow"),
+            level: matches
+                .value_of("level")
+                .expect("level has no default")
+                .to_lowercase(),
+            module_opt: matches.value_of("module").map(|s| s.to_string()),
+        })
+    }
+
+    pub fn handle_broadcast(
+        body: UiLogStreamBroadcast,
+        stdout: &mut dyn Write,
+        term_interface: &TerminalWrapper,
+    ) {
+        let _lock = term_interface.lock();
+        short_writeln!(stdout, ">>  {}: {}: {}", body.level, body.module, body.msg);
+        stdout.flush().expect("flush failed");
+    }
+}
+
+impl Command for LogsCommand {
+    fn execute(&self, context: &mut dyn CommandContext) -> Result<(), CommandError> {
+        let input = UiLogStreamRequest {
+            follow: self.follow,
+            level: self.level.clone(),
+            module_opt: self.module_opt.clone(),
+        };
+        let _: UiLogStreamResponse = transaction(input, context, STANDARD_COMMAND_TIMEOUT_MILLIS)?;
+        if self.follow {
+            short_writeln!(
+                context.stdout(),
+                "Following {} logs from {}; 'logs --stop' ends the stream",
+                self.level,
+                match &self.module_opt {
+                    Some(module) => format!("modules matching '{}'", module),
+                    None => "all modules".to_string(),
+                }
+            );
+        } else {
+            short_writeln!(context.stdout(), "No longer following logs");
+        }
+        Ok(())
+    }
+
+    as_any_ref_in_trait_impl!();
+}
+
+const LOGS_SUBCOMMAND_ABOUT: &str =
+    "Streams the Node's debug or trace log messages to this masq session as they are written. \
+     Info-level messages and above are shown to every UI anyway. Use with the interactive mode; \
+     the stream ends when masq disconnects.";
+const FOLLOW_ARG_HELP: &str = "Starts streaming, replacing any stream this session already has.";
+const STOP_ARG_HELP: &str = "Stops streaming.";
+const LEVEL_ARG_HELP: &str = "The most verbose level to stream.";
+const MODULE_ARG_HELP: &str =
+    "Stream only from modules whose names begin with this, such as hopper or node::neighborhood. \
+     Case-insensitive.";
+
+pub fn logs_subcommand() -> App<'static, 'static> {
+    SubCommand::with_name("logs")
+        .about(LOGS_SUBCOMMAND_ABOUT)
+        .arg(
+            Arg::with_name("follow")
+                .help(FOLLOW_ARG_HELP)
+                .long("follow")
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name("stop")
+                .help(STOP_ARG_HELP)
+                .long("stop")
+                .takes_value(false),
+        )
+        .group(
+            ArgGroup::with_name("mode")
+                .args(&["follow", "stop"])
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("level")
+                .help(LEVEL_ARG_HELP)
+                .long("level")
+                .value_name("LEVEL")
+                .takes_value(true)
+                .possible_values(&["debug", "trace"])
+                .case_insensitive(true)
+                .default_value("debug"),
+        )
+        .arg(
+            Arg::with_name("module")
+                .help(MODULE_ARG_HELP)
+                .long("module")
+                .value_name("MODULE")
+                .takes_value(true)
+                .required(false),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_context::ContextError;
+    use crate::command_factory::{CommandFactory, CommandFactoryReal};
+    use crate::test_utils::mocks::{CommandContextMock, TerminalPassiveMock};
+    use masq_lib::constants::LOG_LEVEL_ERROR;
+    use masq_lib::messages::ToMessageBody;
+    use masq_lib::test_utils::fake_stream_holder::ByteArrayWriter;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(
+            LOGS_SUBCOMMAND_ABOUT,
+            "Streams the Node's debug or trace log messages to this masq session as they are \
+             written. Info-level messages and above are shown to every UI anyway. Use with the \
+             interactive mode; the stream ends when masq disconnects."
+        );
+        assert_eq!(
+            FOLLOW_ARG_HELP,
+            "Starts streaming, replacing any stream this session already has."
+        );
+        assert_eq!(STOP_ARG_HELP, "Stops streaming.");
+        assert_eq!(LEVEL_ARG_HELP, "The most verbose level to stream.");
+        assert_eq!(
+            MODULE_ARG_HELP,
+            "Stream only from modules whose names begin with this, such as hopper or \
+             node::neighborhood. Case-insensitive."
+        );
+    }
+
+    #[test]
+    fn command_factory_makes_logs_command() {
+        let subject = CommandFactoryReal::new();
+
+        let result = subject
+            .make(&[
+                "logs".to_string(),
+                "--follow".to_string(),
+                "--level".to_string(),
+                "TRACE".to_string(),
+                "--module".to_string(),
+                "hopper".to_string(),
+            ])
+            .unwrap();
+
+        let command: &LogsCommand = result.as_any().downcast_ref().unwrap();
+        assert_eq!(
+            command,
+            &LogsCommand {
+                follow: true,
+                level: "trace".to_string(),
+                module_opt: Some("hopper".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn stop_needs_no_other_arguments() {
+        let result = LogsCommand::new(&["logs".to_string(), "--stop".to_string()]);
+
+        assert_eq!(
+            result,
+            Ok(LogsCommand {
+                follow: false,
+                level: "debug".to_string(),
+                module_opt: None,
+            })
+        );
+    }
+
+    #[test]
+    fn follow_or_stop_is_required() {
+        let result = LogsCommand::new(&["logs".to_string()]);
+
+        let msg = result.err().unwrap();
+        assert_eq!(
+            msg.contains("The following required arguments were not provided:"),
+            true,
+            "{}",
+            msg
+        );
+    }
+
+    #[test]
+    fn follow_and_stop_cannot_both_be_given() {
+        let result = LogsCommand::new(&[
+            "logs".to_string(),
+            "--follow".to_string(),
+            "--stop".to_string(),
+        ]);
+
+        let msg = result.err().unwrap();
+        assert_eq!(msg.contains("cannot be used with"), true, "{}", msg);
+    }
+
+    #[test]
+    fn execute_starts_following() {
+        let transact_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut context = CommandContextMock::new()
+            .transact_params(&transact_params_arc)
+            .transact_result(Ok(UiLogStreamResponse {}.tmb(0)));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = LogsCommand {
+            follow: true,
+            level: "debug".to_string(),
+            module_opt: Some("hopper".to_string()),
+        };
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        let transact_params = transact_params_arc.lock().unwrap();
+        assert_eq!(
+            *transact_params,
+            vec![(
+                UiLogStreamRequest {
+                    follow: true,
+                    level: "debug".to_string(),
+                    module_opt: Some("hopper".to_string()),
+                }
+                .tmb(0),
+                STANDARD_COMMAND_TIMEOUT_MILLIS
+            )]
+        );
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            "Following debug logs from modules matching 'hopper'; 'logs --stop' ends the stream\n"
+        );
+        assert_eq!(stderr_arc.lock().unwrap().get_string(), String::new());
+    }
+
+    #[test]
+    fn execute_stops_following() {
+        let mut context =
+            CommandContextMock::new().transact_result(Ok(UiLogStreamResponse {}.tmb(0)));
+        let stdout_arc = context.stdout_arc();
+        let subject = LogsCommand {
+            follow: false,
+            level: "debug".to_string(),
+            module_opt: None,
+        };
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            "No longer following logs\n"
+        );
+    }
+
+    #[test]
+    fn execute_handles_error_from_node() {
+        let mut context = CommandContextMock::new().transact_result(Err(
+            ContextError::PayloadError(LOG_LEVEL_ERROR, "Bad level".to_string()),
+        ));
+        let subject = LogsCommand {
+            follow: true,
+            level: "info".to_string(),
+            module_opt: None,
+        };
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(
+            result,
+            Err(CommandError::Payload(
+                LOG_LEVEL_ERROR,
+                "Bad level".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn handle_broadcast_prints_the_log_line() {
+        let mut stdout = ByteArrayWriter::new();
+        let term_interface = TerminalWrapper::new(Arc::new(TerminalPassiveMock::new()));
+
+        LogsCommand::handle_broadcast(
+            UiLogStreamBroadcast {
+                msg: "Received a CORES package".to_string(),
+                level: "DEBUG".to_string(),
+                module: "Hopper".to_string(),
+            },
+            &mut stdout,
+            &term_interface,
+        );
+
+        assert_eq!(
+            stdout.get_string(),
+            ">>  DEBUG: Hopper: Received a CORES package\n"
+        );
+    }
+}
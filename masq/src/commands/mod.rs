@@ -6,13 +6,21 @@ pub mod commands_common;
 pub mod configuration_command;
 pub mod connection_status_command;
 pub mod crash_command;
+pub mod database_backup_command;
 pub mod descriptor_command;
+pub mod export_wallet_command;
 pub mod financials_command;
 pub mod generate_wallets_command;
+pub mod logs_command;
 pub mod recover_wallets_command;
+pub mod reload_config_command;
 pub mod scan_command;
 pub mod set_configuration_command;
+pub mod set_log_level_command;
 pub mod setup_command;
 pub mod shutdown_command;
 pub mod start_command;
+pub mod transfer_command;
+pub mod wait_for_command;
 pub mod wallet_addresses_command;
+pub mod wallet_balances_command;
@@ -7,9 +7,12 @@ use crate::commands::commands_common::{
 use clap::{App, Arg, ArgGroup, SubCommand};
 use itertools::{Either, Itertools};
 use masq_lib::as_any_ref_in_trait_impl;
-use masq_lib::messages::{UiRecoverSeedSpec, UiRecoverWalletsRequest, UiRecoverWalletsResponse};
+use masq_lib::messages::{
+    UiKeystoreSpec, UiRecoverSeedSpec, UiRecoverWalletsRequest, UiRecoverWalletsResponse,
+};
 use masq_lib::short_writeln;
 use masq_lib::utils::to_string;
+use std::fs;
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct SeedSpec {
@@ -18,11 +21,21 @@ pub struct SeedSpec {
     passphrase_opt: Option<String>,
 }
 
+#[derive(Debug, PartialEq, Eq)]
+pub enum ConsumingSpec {
+    PrivateKey(String),
+    DerivationPath(String),
+    Keystore {
+        keystore_json: String,
+        passphrase: String,
+    },
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct RecoverWalletsCommand {
     db_password: String,
     seed_spec_opt: Option<SeedSpec>,
-    consuming: Either<String, String>,
+    consuming: ConsumingSpec,
     earning: Either<String, String>,
 }
 
@@ -61,12 +74,23 @@ impl RecoverWalletsCommand {
         };
         let consuming_wallet_derivation_path_opt = matches.value_of("consuming-path");
         let consuming_wallet_key_opt = matches.value_of("consuming-key");
+        let keystore_opt = matches.value_of("keystore");
         let consuming = match (
             consuming_wallet_derivation_path_opt,
             consuming_wallet_key_opt,
+            keystore_opt,
         ) {
-            (Some(cwdp), None) => Either::Right(cwdp.to_string()),
-            (None, Some(ewpk)) => Either::Left(ewpk.to_string()),
+            (Some(cwdp), None, None) => ConsumingSpec::DerivationPath(cwdp.to_string()),
+            (None, Some(cwpk), None) => ConsumingSpec::PrivateKey(cwpk.to_string()),
+            (None, None, Some(keystore_file)) => ConsumingSpec::Keystore {
+                keystore_json: fs::read_to_string(keystore_file).map_err(|e| {
+                    format!("Could not read keystore file '{}': {}", keystore_file, e)
+                })?,
+                passphrase: matches
+                    .value_of("keystore-passphrase")
+                    .expect("keystore-passphrase not properly required")
+                    .to_string(),
+            },
             x => panic!(
                 "Consuming-wallet parameters are not properly required by clap: {:?}",
                 x
@@ -98,12 +122,22 @@ impl Command for RecoverWalletsCommand {
                     mnemonic_passphrase_opt: seed_spec.passphrase_opt.clone(),
                 }),
             consuming_derivation_path_opt: match &self.consuming {
-                Either::Left(_) => None,
-                Either::Right(path) => Some(path.clone()),
+                ConsumingSpec::DerivationPath(path) => Some(path.clone()),
+                _ => None,
             },
             consuming_private_key_opt: match &self.consuming {
-                Either::Left(key) => Some(key.clone()),
-                Either::Right(_) => None,
+                ConsumingSpec::PrivateKey(key) => Some(key.clone()),
+                _ => None,
+            },
+            consuming_keystore_opt: match &self.consuming {
+                ConsumingSpec::Keystore {
+                    keystore_json,
+                    passphrase,
+                } => Some(UiKeystoreSpec {
+                    keystore_json: keystore_json.clone(),
+                    passphrase: passphrase.clone(),
+                }),
+                _ => None,
             },
             earning_derivation_path_opt: match &self.earning {
                 Either::Left(_) => None,
@@ -138,6 +172,10 @@ const CONSUMING_PATH_ARG_HELP: &str =
      Remember to put it in double quotes; otherwise the single quotes will cause problems.";
 const CONSUMING_KEY_ARG_HELP: &str =
     "The private key of the consuming wallet. Represent it as a 64-character string of hexadecimal digits.";
+const KEYSTORE_ARG_HELP: &str =
+    "Path to a Web3 Secret Storage (keystore v3) JSON file holding the private key of the consuming wallet.";
+const KEYSTORE_PASSPHRASE_ARG_HELP: &str =
+    "The passphrase that unlocks the keystore file. Required with --keystore.";
 const EARNING_PATH_ARG_HELP: &str =
     "Derivation path that was used to generate the earning wallet from which your bills will be paid. \
      Can be the same as consuming-path. Remember to put it in double quotes; otherwise the single \
@@ -210,6 +248,25 @@ pub fn recover_wallets_subcommand() -> App<'static, 'static> {
                 .required(false)
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("keystore")
+                .help(KEYSTORE_ARG_HELP)
+                .long("keystore")
+                .value_name("KEYSTORE-FILE")
+                .required(false)
+                .takes_value(true)
+                .requires("keystore-passphrase"),
+        )
+        .arg(
+            Arg::with_name("keystore-passphrase")
+                .help(KEYSTORE_PASSPHRASE_ARG_HELP)
+                .long("keystore-passphrase")
+                .value_name("KEYSTORE-PASSPHRASE")
+                .required(false)
+                .case_insensitive(false)
+                .takes_value(true)
+                .requires("keystore"),
+        )
         .arg(
             Arg::with_name("earning-path")
                 .help(EARNING_PATH_ARG_HELP)
@@ -230,6 +287,7 @@ pub fn recover_wallets_subcommand() -> App<'static, 'static> {
             ArgGroup::with_name("consuming")
                 .arg("consuming-path")
                 .arg("consuming-key")
+                .arg("keystore")
                 .required(true),
         )
         .group(
@@ -246,6 +304,7 @@ mod tests {
     use crate::command_factory::{CommandFactory, CommandFactoryError, CommandFactoryReal};
     use crate::test_utils::mocks::CommandContextMock;
     use masq_lib::messages::{ToMessageBody, UiRecoverWalletsRequest, UiRecoverWalletsResponse};
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
     use std::sync::{Arc, Mutex};
 
     #[test]
@@ -282,6 +341,15 @@ mod tests {
             "The private key of the consuming wallet. Represent it as a 64-character string of \
              hexadecimal digits."
         );
+        assert_eq!(
+            KEYSTORE_ARG_HELP,
+            "Path to a Web3 Secret Storage (keystore v3) JSON file holding the private key of the \
+             consuming wallet."
+        );
+        assert_eq!(
+            KEYSTORE_PASSPHRASE_ARG_HELP,
+            "The passphrase that unlocks the keystore file. Required with --keystore."
+        );
         assert_eq!(
             EARNING_PATH_ARG_HELP,
             "Derivation path that was used to generate the earning wallet from which your bills \
@@ -343,7 +411,7 @@ mod tests {
                     passphrase_opt: Some("booga".to_string()),
                     language: "English".to_string(),
                 }),
-                consuming: Either::Right ("m/60'/44'/0'/100/0/200".to_string()),
+                consuming: ConsumingSpec::DerivationPath ("m/60'/44'/0'/100/0/200".to_string()),
                 earning: Either::Right ("m/60'/44'/0'/100/0/201".to_string())
             }
         )
@@ -372,7 +440,7 @@ mod tests {
             &RecoverWalletsCommand {
                 db_password: "password".to_string(),
                 seed_spec_opt: None,
-                consuming: Either::Left(
+                consuming: ConsumingSpec::PrivateKey(
                     "0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF".to_string()
                 ),
                 earning: Either::Left("0x0123456789012345678901234567890123456789".to_string()),
@@ -380,6 +448,124 @@ mod tests {
         )
     }
 
+    #[test]
+    fn testing_command_factory_with_keystore_and_address() {
+        let home_dir = ensure_node_home_directory_exists(
+            "recover_wallets_command",
+            "testing_command_factory_with_keystore_and_address",
+        );
+        let keystore_path = home_dir.join("keystore.json");
+        fs::write(&keystore_path, "{\"version\": 3}").unwrap();
+        let subject = CommandFactoryReal::new();
+
+        let result = subject
+            .make(&[
+                "recover-wallets".to_string(),
+                "--db-password".to_string(),
+                "password".to_string(),
+                "--keystore".to_string(),
+                keystore_path.to_str().unwrap().to_string(),
+                "--keystore-passphrase".to_string(),
+                "Open Sesame".to_string(),
+                "--earning-address".to_string(),
+                "0x0123456789012345678901234567890123456789".to_string(),
+            ])
+            .unwrap();
+
+        let recover_wallets_command: &RecoverWalletsCommand =
+            result.as_any().downcast_ref().unwrap();
+        assert_eq!(
+            recover_wallets_command,
+            &RecoverWalletsCommand {
+                db_password: "password".to_string(),
+                seed_spec_opt: None,
+                consuming: ConsumingSpec::Keystore {
+                    keystore_json: "{\"version\": 3}".to_string(),
+                    passphrase: "Open Sesame".to_string(),
+                },
+                earning: Either::Left("0x0123456789012345678901234567890123456789".to_string()),
+            }
+        )
+    }
+
+    #[test]
+    fn keystore_requires_passphrase() {
+        let result = RecoverWalletsCommand::new(&[
+            "recover-wallets".to_string(),
+            "--db-password".to_string(),
+            "password".to_string(),
+            "--keystore".to_string(),
+            "keystore.json".to_string(),
+            "--earning-address".to_string(),
+            "0x0123456789012345678901234567890123456789".to_string(),
+        ]);
+
+        let msg = result.err().unwrap();
+        assert_eq!(
+            msg.contains("The following required arguments were not provided:"),
+            true,
+            "{}",
+            msg
+        );
+    }
+
+    #[test]
+    fn constructor_complains_about_unreadable_keystore_file() {
+        let home_dir = ensure_node_home_directory_exists(
+            "recover_wallets_command",
+            "constructor_complains_about_unreadable_keystore_file",
+        );
+        let keystore_path = home_dir.join("nonexistent.json");
+
+        let result = RecoverWalletsCommand::new(&[
+            "recover-wallets".to_string(),
+            "--db-password".to_string(),
+            "password".to_string(),
+            "--keystore".to_string(),
+            keystore_path.to_str().unwrap().to_string(),
+            "--keystore-passphrase".to_string(),
+            "Open Sesame".to_string(),
+            "--earning-address".to_string(),
+            "0x0123456789012345678901234567890123456789".to_string(),
+        ]);
+
+        let msg = result.err().unwrap();
+        assert_eq!(
+            msg.starts_with(&format!(
+                "Could not read keystore file '{}': ",
+                keystore_path.to_str().unwrap()
+            )),
+            true,
+            "{}",
+            msg
+        );
+    }
+
+    #[test]
+    fn consuming_wallet_must_be_specified_only_one_way() {
+        let result = RecoverWalletsCommand::new(&[
+            "recover-wallets".to_string(),
+            "--db-password".to_string(),
+            "password".to_string(),
+            "--consuming-key".to_string(),
+            "0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF".to_string(),
+            "--keystore".to_string(),
+            "keystore.json".to_string(),
+            "--keystore-passphrase".to_string(),
+            "Open Sesame".to_string(),
+            "--earning-address".to_string(),
+            "0x0123456789012345678901234567890123456789".to_string(),
+        ]);
+
+        let msg = result.err().unwrap();
+        assert_eq!(
+            msg.contains("cannot be used with one or more of the other specified arguments"),
+            true,
+            "{}",
+            msg
+        );
+    }
+
     #[test]
     fn constructor_handles_bad_syntax() {
         let result = RecoverWalletsCommand::new(&[
@@ -426,7 +612,7 @@ mod tests {
                     language: "English".to_string(),
                     passphrase_opt: None,
                 }),
-                consuming: Either::Right("ooga".to_string()),
+                consuming: ConsumingSpec::DerivationPath("ooga".to_string()),
                 earning: Either::Right("booga".to_string()),
             }
         )
@@ -509,7 +695,7 @@ mod tests {
                 language: "English".to_string(),
                 passphrase_opt: Some("booga".to_string()),
             }),
-            consuming: Either::Right("consuming path".to_string()),
+            consuming: ConsumingSpec::DerivationPath("consuming path".to_string()),
             earning: Either::Right("earning path".to_string()),
         };
 
@@ -529,6 +715,7 @@ mod tests {
                     }),
                     consuming_derivation_path_opt: Some("consuming path".to_string()),
                     consuming_private_key_opt: None,
+                    consuming_keystore_opt: None,
                     earning_derivation_path_opt: Some("earning path".to_string()),
                     earning_address_opt: None,
                 }
@@ -556,7 +743,7 @@ mod tests {
         let subject = RecoverWalletsCommand {
             db_password: "password".to_string(),
             seed_spec_opt: None,
-            consuming: Either::Left("consuming private key".to_string()),
+            consuming: ConsumingSpec::PrivateKey("consuming private key".to_string()),
             earning: Either::Left("earning address".to_string()),
         };
 
@@ -572,6 +759,7 @@ mod tests {
                     seed_spec_opt: None,
                     consuming_derivation_path_opt: None,
                     consuming_private_key_opt: Some("consuming private key".to_string()),
+                    consuming_keystore_opt: None,
                     earning_derivation_path_opt: None,
                     earning_address_opt: Some("earning address".to_string()),
                 }
@@ -587,4 +775,51 @@ mod tests {
             "Wallets were successfully recovered\n"
         );
     }
+
+    #[test]
+    fn execute_works_with_keystore_and_address() {
+        let transact_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut context = CommandContextMock::new()
+            .transact_params(&transact_params_arc)
+            .transact_result(Ok(UiRecoverWalletsResponse {}.tmb(4321)));
+        let stdout_arc = context.stdout_arc();
+        let subject = RecoverWalletsCommand {
+            db_password: "password".to_string(),
+            seed_spec_opt: None,
+            consuming: ConsumingSpec::Keystore {
+                keystore_json: "keystore json".to_string(),
+                passphrase: "Open Sesame".to_string(),
+            },
+            earning: Either::Left("earning address".to_string()),
+        };
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        let transact_params = transact_params_arc.lock().unwrap();
+        assert_eq!(
+            *transact_params,
+            vec![(
+                UiRecoverWalletsRequest {
+                    db_password: "password".to_string(),
+                    seed_spec_opt: None,
+                    consuming_derivation_path_opt: None,
+                    consuming_private_key_opt: None,
+                    consuming_keystore_opt: Some(UiKeystoreSpec {
+                        keystore_json: "keystore json".to_string(),
+                        passphrase: "Open Sesame".to_string(),
+                    }),
+                    earning_derivation_path_opt: None,
+                    earning_address_opt: Some("earning address".to_string()),
+                }
+                .tmb(0),
+                1000
+            )]
+        );
+        let stdout = stdout_arc.lock().unwrap();
+        assert_eq!(
+            &stdout.get_string(),
+            "Wallets were successfully recovered\n"
+        );
+    }
 }
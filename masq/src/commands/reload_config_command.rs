@@ -0,0 +1,203 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::command_context::CommandContext;
+use crate::commands::commands_common::{
+    transaction, Command, CommandError, STANDARD_COMMAND_TIMEOUT_MILLIS,
+};
+use clap::{App, SubCommand};
+use masq_lib::messages::{UiReloadConfigRequest, UiReloadConfigResponse};
+use masq_lib::{as_any_ref_in_trait_impl, short_writeln};
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ReloadConfigCommand {}
+
+impl ReloadConfigCommand {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Command for ReloadConfigCommand {
+    fn execute(&self, context: &mut dyn CommandContext) -> Result<(), CommandError> {
+        let output: UiReloadConfigResponse = transaction(
+            UiReloadConfigRequest {},
+            context,
+            STANDARD_COMMAND_TIMEOUT_MILLIS,
+        )?;
+        if output.applied.is_empty()
+            && output.failed.is_empty()
+            && output.restart_required.is_empty()
+        {
+            short_writeln!(context.stdout(), "No configuration changes found");
+            return Ok(());
+        }
+        if !output.applied.is_empty() {
+            short_writeln!(
+                context.stdout(),
+                "Now in force: {}",
+                output.applied.join(", ")
+            );
+        }
+        output.failed.iter().for_each(|failure| {
+            short_writeln!(
+                context.stderr(),
+                "Not put in force, and will be tried again at the next reload: {} ({})",
+                failure.parameter,
+                failure.message
+            )
+        });
+        if !output.restart_required.is_empty() {
+            short_writeln!(
+                context.stdout(),
+                "Changed, but only in force after a restart: {}",
+                output.restart_required.join(", ")
+            );
+        }
+        Ok(())
+    }
+
+    as_any_ref_in_trait_impl!();
+}
+
+const RELOAD_CONFIG_SUBCOMMAND_ABOUT: &str =
+    "Makes the running Node read its config file and environment again. Changes to blockchain-service-url, \
+     dns-servers, log-level, min-hops, payment-thresholds, rate-pack and scan-intervals are put in force \
+     at once; other changes are listed, and wait for a restart. Sending the Node SIGHUP does the same.";
+
+pub fn reload_config_subcommand() -> App<'static, 'static> {
+    SubCommand::with_name("reload-config").about(RELOAD_CONFIG_SUBCOMMAND_ABOUT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_context::ContextError;
+    use crate::command_factory::{CommandFactory, CommandFactoryReal};
+    use crate::test_utils::mocks::CommandContextMock;
+    use masq_lib::constants::{CONFIGURATOR_WRITE_ERROR, CONFIG_RELOAD_ERROR};
+    use masq_lib::messages::{ToMessageBody, UiReloadConfigFailure};
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(
+            RELOAD_CONFIG_SUBCOMMAND_ABOUT,
+            "Makes the running Node read its config file and environment again. Changes to \
+             blockchain-service-url, dns-servers, log-level, min-hops, payment-thresholds, \
+             rate-pack and scan-intervals are put in force at once; other changes are listed, \
+             and wait for a restart. Sending the Node SIGHUP does the same."
+        );
+    }
+
+    #[test]
+    fn command_factory_makes_reload_config_command() {
+        let subject = CommandFactoryReal::new();
+
+        let result = subject.make(&["reload-config".to_string()]).unwrap();
+
+        let command: &ReloadConfigCommand = result.as_any().downcast_ref().unwrap();
+        assert_eq!(command, &ReloadConfigCommand {});
+    }
+
+    #[test]
+    fn execute_reports_what_was_applied_and_what_waits_for_a_restart() {
+        let transact_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut context = CommandContextMock::new()
+            .transact_params(&transact_params_arc)
+            .transact_result(Ok(UiReloadConfigResponse {
+                applied: vec!["dns-servers".to_string(), "rate-pack".to_string()],
+                failed: vec![],
+                restart_required: vec!["clandestine-port".to_string()],
+            }
+            .tmb(0)));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = ReloadConfigCommand::new();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        let transact_params = transact_params_arc.lock().unwrap();
+        assert_eq!(
+            *transact_params,
+            vec![(
+                UiReloadConfigRequest {}.tmb(0),
+                STANDARD_COMMAND_TIMEOUT_MILLIS
+            )]
+        );
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            "Now in force: dns-servers, rate-pack\n\
+             Changed, but only in force after a restart: clandestine-port\n"
+        );
+        assert_eq!(stderr_arc.lock().unwrap().get_string(), String::new());
+    }
+
+    #[test]
+    fn execute_reports_changes_the_node_could_not_put_in_force() {
+        let mut context = CommandContextMock::new().transact_result(Ok(UiReloadConfigResponse {
+            applied: vec!["log-level".to_string()],
+            failed: vec![UiReloadConfigFailure {
+                parameter: "min-hops".to_string(),
+                code: CONFIGURATOR_WRITE_ERROR,
+                message: "min hops: TransactionError".to_string(),
+            }],
+            restart_required: vec![],
+        }
+        .tmb(0)));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = ReloadConfigCommand::new();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            "Now in force: log-level\n"
+        );
+        assert_eq!(
+            stderr_arc.lock().unwrap().get_string(),
+            "Not put in force, and will be tried again at the next reload: min-hops \
+             (min hops: TransactionError)\n"
+        );
+    }
+
+    #[test]
+    fn execute_says_so_when_nothing_changed() {
+        let mut context = CommandContextMock::new().transact_result(Ok(UiReloadConfigResponse {
+            applied: vec![],
+            failed: vec![],
+            restart_required: vec![],
+        }
+        .tmb(0)));
+        let stdout_arc = context.stdout_arc();
+        let subject = ReloadConfigCommand::new();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            "No configuration changes found\n"
+        );
+    }
+
+    #[test]
+    fn execute_handles_error_from_node() {
+        let mut context = CommandContextMock::new().transact_result(Err(
+            ContextError::PayloadError(CONFIG_RELOAD_ERROR, "rate-pack: Bad format".to_string()),
+        ));
+        let subject = ReloadConfigCommand::new();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(
+            result,
+            Err(CommandError::Payload(
+                CONFIG_RELOAD_ERROR,
+                "rate-pack: Bad format".to_string()
+            ))
+        );
+    }
+}
@@ -25,7 +25,7 @@ pub fn scan_subcommand() -> App<'static, 'static> {
             Arg::with_name("name")
                 .help(SCAN_SUBCOMMAND_HELP)
                 .index(1)
-                .possible_values(&["payables", "receivables", "pendingpayables"])
+                .possible_values(&["payables", "receivables", "pendingpayables", "feebumps"])
                 .required(true)
                 .case_insensitive(true),
         )
@@ -105,6 +105,7 @@ mod tests {
         scan_command_for_name("payables", ScanType::Payables);
         scan_command_for_name("receivables", ScanType::Receivables);
         scan_command_for_name("pendingpayables", ScanType::PendingPayables);
+        scan_command_for_name("feebumps", ScanType::FeeBumps);
     }
 
     fn scan_command_for_name(name: &str, scan_type: ScanType) {
@@ -5,6 +5,7 @@ use masq_lib::as_any_ref_in_trait_impl;
 use masq_lib::messages::{UiSetConfigurationRequest, UiSetConfigurationResponse};
 use masq_lib::shared_schema::gas_price_arg;
 use masq_lib::shared_schema::min_hops_arg;
+use masq_lib::shared_schema::{bandwidth_limits_arg, data_quota_arg, spending_budget_arg};
 use masq_lib::short_writeln;
 use masq_lib::utils::ExpectValue;
 use std::num::IntErrorKind;
@@ -77,8 +78,11 @@ pub fn set_configurationify<'a>(shared_schema_arg: Arg<'a, 'a>) -> Arg<'a, 'a> {
 pub fn set_configuration_subcommand() -> App<'static, 'static> {
     SubCommand::with_name("set-configuration")
         .about(SET_CONFIGURATION_ABOUT)
+        .arg(set_configurationify(bandwidth_limits_arg()))
+        .arg(set_configurationify(data_quota_arg()))
         .arg(set_configurationify(gas_price_arg()))
         .arg(set_configurationify(min_hops_arg()))
+        .arg(set_configurationify(spending_budget_arg()))
         .arg(
             Arg::with_name("start-block")
                 .help(START_BLOCK_HELP)
@@ -90,7 +94,14 @@ pub fn set_configuration_subcommand() -> App<'static, 'static> {
         )
         .group(
             ArgGroup::with_name("parameter")
-                .args(&["gas-price", "min-hops", "start-block"])
+                .args(&[
+                    "bandwidth-limits",
+                    "data-quota",
+                    "gas-price",
+                    "min-hops",
+                    "spending-budget",
+                    "start-block",
+                ])
                 .required(true),
         )
 }
@@ -160,6 +171,9 @@ mod tests {
         test_command_execution("--start-block", "123456");
         test_command_execution("--gas-price", "123456");
         test_command_execution("--min-hops", "6");
+        test_command_execution("--bandwidth-limits", "100|200|300|400|50");
+        test_command_execution("--data-quota", "1000|20000");
+        test_command_execution("--spending-budget", "3000000|50000000|60|90");
     }
 
     #[test]
@@ -167,6 +181,9 @@ mod tests {
         set_configuration_command_throws_err_for_missing_value("--start-block");
         set_configuration_command_throws_err_for_missing_value("--gas-price");
         set_configuration_command_throws_err_for_missing_value("--min-hops");
+        set_configuration_command_throws_err_for_missing_value("--bandwidth-limits");
+        set_configuration_command_throws_err_for_missing_value("--data-quota");
+        set_configuration_command_throws_err_for_missing_value("--spending-budget");
     }
 
     #[test]
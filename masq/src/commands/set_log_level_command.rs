@@ -0,0 +1,270 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::command_context::CommandContext;
+use crate::commands::commands_common::{
+    transaction, Command, CommandError, STANDARD_COMMAND_TIMEOUT_MILLIS,
+};
+use clap::{App, Arg, SubCommand};
+use masq_lib::messages::{UiModuleLogLevel, UiSetLogLevelRequest, UiSetLogLevelResponse};
+use masq_lib::{as_any_ref_in_trait_impl, short_writeln};
+
+const LOG_LEVELS: [&str; 6] = ["off", "error", "warn", "info", "debug", "trace"];
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct SetLogLevelCommand {
+    pub level_opt: Option<String>,
+    pub module_levels: Vec<(String, String)>,
+}
+
+impl SetLogLevelCommand {
+    pub fn new(pieces: &[String]) -> Result<Self, String> {
+        let matches = match set_log_level_subcommand().get_matches_from_safe(pieces) {
+            Ok(matches) => matches,
+            Err(e) => return Err(format!("{}", e)),
+        };
+        let level_opt = matches.value_of("level").map(|s| s.to_lowercase());
+        let module_levels = match matches.values_of("module") {
+            Some(values) => values
+                .map(|pair| {
+                    let (module, level) = pair.split_once('=').expect("validator failed");
+                    (module.to_string(), level.to_lowercase())
+                })
+                .collect(),
+            None => vec![],
+        };
+        if level_opt.is_none() && module_levels.is_empty() {
+            return Err("Specify a global level, at least one --module level, or both".to_string());
+        }
+        Ok(Self {
+            level_opt,
+            module_levels,
+        })
+    }
+}
+
+impl Command for SetLogLevelCommand {
+    fn execute(&self, context: &mut dyn CommandContext) -> Result<(), CommandError> {
+        let input = UiSetLogLevelRequest {
+            level_opt: self.level_opt.clone(),
+            module_levels: self
+                .module_levels
+                .iter()
+                .map(|(module, level)| UiModuleLogLevel {
+                    module: module.clone(),
+                    level: level.clone(),
+                })
+                .collect(),
+        };
+        let output: UiSetLogLevelResponse =
+            transaction(input, context, STANDARD_COMMAND_TIMEOUT_MILLIS)?;
+        short_writeln!(context.stdout(), "Log levels now: {}", output.log_spec);
+        Ok(())
+    }
+
+    as_any_ref_in_trait_impl!();
+}
+
+fn validate_module_level(pair: String) -> Result<(), String> {
+    match pair.split_once('=') {
+        Some((module, level))
+            if !module.is_empty() && LOG_LEVELS.contains(&level.to_lowercase().as_str()) =>
+        {
+            Ok(())
+        }
+        _ => Err(format!(
+            "'{}' should look like <MODULE>=<LEVEL>, where LEVEL is one of {}",
+            pair,
+            LOG_LEVELS.join(", ")
+        )),
+    }
+}
+
+const SET_LOG_LEVEL_SUBCOMMAND_ABOUT: &str =
+    "Changes the levels at which the running Node writes its log, without restarting it. \
+     Changes last until the Node shuts down.";
+const LEVEL_ARG_HELP: &str =
+    "New level for every module that has no level of its own. If omitted, the global level stays \
+     as it is.";
+const MODULE_ARG_HELP: &str =
+    "Level for one module, as <MODULE>=<LEVEL>; for example, neighborhood=trace or \
+     node::proxy_server=debug. Module names are case-insensitive and match as prefixes. May be \
+     given more than once.";
+
+pub fn set_log_level_subcommand() -> App<'static, 'static> {
+    SubCommand::with_name("set-log-level")
+        .about(SET_LOG_LEVEL_SUBCOMMAND_ABOUT)
+        .arg(
+            Arg::with_name("level")
+                .help(LEVEL_ARG_HELP)
+                .value_name("LEVEL")
+                .possible_values(&LOG_LEVELS)
+                .case_insensitive(true)
+                .required(false),
+        )
+        .arg(
+            Arg::with_name("module")
+                .help(MODULE_ARG_HELP)
+                .long("module")
+                .value_name("MODULE=LEVEL")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .validator(validate_module_level)
+                .required(false),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_context::ContextError;
+    use crate::command_factory::{CommandFactory, CommandFactoryReal};
+    use crate::test_utils::mocks::CommandContextMock;
+    use masq_lib::constants::LOG_LEVEL_ERROR;
+    use masq_lib::messages::ToMessageBody;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(
+            LOG_LEVELS,
+            ["off", "error", "warn", "info", "debug", "trace"]
+        );
+        assert_eq!(
+            SET_LOG_LEVEL_SUBCOMMAND_ABOUT,
+            "Changes the levels at which the running Node writes its log, without restarting it. \
+             Changes last until the Node shuts down."
+        );
+        assert_eq!(
+            LEVEL_ARG_HELP,
+            "New level for every module that has no level of its own. If omitted, the global \
+             level stays as it is."
+        );
+        assert_eq!(
+            MODULE_ARG_HELP,
+            "Level for one module, as <MODULE>=<LEVEL>; for example, neighborhood=trace or \
+             node::proxy_server=debug. Module names are case-insensitive and match as prefixes. \
+             May be given more than once."
+        );
+    }
+
+    #[test]
+    fn command_factory_makes_set_log_level_command() {
+        let subject = CommandFactoryReal::new();
+
+        let result = subject
+            .make(&[
+                "set-log-level".to_string(),
+                "Warn".to_string(),
+                "--module".to_string(),
+                "node::neighborhood=TRACE".to_string(),
+                "--module".to_string(),
+                "hopper=debug".to_string(),
+            ])
+            .unwrap();
+
+        let command: &SetLogLevelCommand = result.as_any().downcast_ref().unwrap();
+        assert_eq!(
+            command,
+            &SetLogLevelCommand {
+                level_opt: Some("warn".to_string()),
+                module_levels: vec![
+                    ("node::neighborhood".to_string(), "trace".to_string()),
+                    ("hopper".to_string(), "debug".to_string()),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn some_level_is_required() {
+        let result = SetLogLevelCommand::new(&["set-log-level".to_string()]);
+
+        assert_eq!(
+            result,
+            Err("Specify a global level, at least one --module level, or both".to_string())
+        );
+    }
+
+    #[test]
+    fn module_levels_are_validated() {
+        let result = SetLogLevelCommand::new(&[
+            "set-log-level".to_string(),
+            "--module".to_string(),
+            "hopper=loud".to_string(),
+        ]);
+
+        let msg = result.err().unwrap();
+        assert_eq!(
+            msg.contains(
+                "'hopper=loud' should look like <MODULE>=<LEVEL>, where LEVEL is one of off, \
+                 error, warn, info, debug, trace"
+            ),
+            true,
+            "{}",
+            msg
+        );
+    }
+
+    #[test]
+    fn execute_reports_the_new_levels() {
+        let transact_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut context = CommandContextMock::new()
+            .transact_params(&transact_params_arc)
+            .transact_result(Ok(UiSetLogLevelResponse {
+                log_spec: "info, hopper=debug".to_string(),
+            }
+            .tmb(0)));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let subject = SetLogLevelCommand {
+            level_opt: None,
+            module_levels: vec![("hopper".to_string(), "debug".to_string())],
+        };
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        let transact_params = transact_params_arc.lock().unwrap();
+        assert_eq!(
+            *transact_params,
+            vec![(
+                UiSetLogLevelRequest {
+                    level_opt: None,
+                    module_levels: vec![UiModuleLogLevel {
+                        module: "hopper".to_string(),
+                        level: "debug".to_string(),
+                    }],
+                }
+                .tmb(0),
+                STANDARD_COMMAND_TIMEOUT_MILLIS
+            )]
+        );
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            "Log levels now: info, hopper=debug\n"
+        );
+        assert_eq!(stderr_arc.lock().unwrap().get_string(), String::new());
+    }
+
+    #[test]
+    fn execute_handles_error_from_node() {
+        let mut context = CommandContextMock::new().transact_result(Err(
+            ContextError::PayloadError(LOG_LEVEL_ERROR, "Not started".to_string()),
+        ));
+        let subject = SetLogLevelCommand {
+            level_opt: Some("debug".to_string()),
+            module_levels: vec![],
+        };
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(
+            result,
+            Err(CommandError::Payload(
+                LOG_LEVEL_ERROR,
+                "Not started".to_string()
+            ))
+        );
+    }
+}
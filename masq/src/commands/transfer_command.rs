@@ -0,0 +1,475 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::command_context::CommandContext;
+use crate::commands::commands_common::{transaction, Command, CommandError};
+use clap::{App, Arg, SubCommand};
+use masq_lib::constants::WEIS_IN_GWEI;
+use masq_lib::messages::{TransferCurrency, UiTransferRequest, UiTransferResponse};
+use masq_lib::{as_any_ref_in_trait_impl, short_writeln};
+use std::str::FromStr;
+use thousands::Separable;
+
+// The Node looks up the balances and the nonce before it can sign anything
+pub const TRANSFER_COMMAND_TIMEOUT_MILLIS: u64 = 15000;
+
+const GWEI_DECIMALS: usize = 9;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct TransferCommand {
+    pub db_password: String,
+    pub recipient: String,
+    pub currency: TransferCurrency,
+    pub amount_gwei_opt: Option<u64>,
+    pub dry_run: bool,
+}
+
+impl TransferCommand {
+    pub fn new(pieces: &[String]) -> Result<Self, String> {
+        let matches = match transfer_subcommand().get_matches_from_safe(pieces) {
+            Ok(matches) => matches,
+            Err(e) => return Err(format!("{}", e)),
+        };
+        let currency = matches
+            .value_of("currency")
+            .map(|currency| {
+                TransferCurrency::from_str(currency)
+                    .expect("clap schema does not restrict currency properly")
+            })
+            .expect("currency is not properly defaulted");
+        let amount = matches.value_of("amount").expect("amount is not required");
+        let amount_gwei_opt = if amount.eq_ignore_ascii_case("all") {
+            None
+        } else {
+            Some(whole_units_to_gwei(amount).expect("clap schema does not validate amount"))
+        };
+        Ok(Self {
+            db_password: matches
+                .value_of("db-password")
+                .expect("db-password is not properly required")
+                .to_string(),
+            recipient: matches
+                .value_of("to")
+                .expect("to is not properly required")
+                .to_string(),
+            currency,
+            amount_gwei_opt,
+            dry_run: matches.is_present("dry-run"),
+        })
+    }
+
+    fn gwei_to_whole_units(gwei: u64) -> String {
+        let gweis_in_unit = WEIS_IN_GWEI as u64;
+        format!(
+            "{}.{:09}",
+            (gwei / gweis_in_unit).separate_with_commas(),
+            gwei % gweis_in_unit
+        )
+    }
+
+    fn currency_name(currency: TransferCurrency) -> &'static str {
+        match currency {
+            TransferCurrency::Masq => "MASQ",
+            TransferCurrency::Native => "of transaction fee currency",
+        }
+    }
+}
+
+fn whole_units_to_gwei(amount: &str) -> Result<u64, String> {
+    let error = || {
+        format!(
+            "Amount must be a positive number of whole units or 'all', not '{}'",
+            amount
+        )
+    };
+    let (whole, fraction) = match amount.split_once('.') {
+        Some((whole, fraction)) => (whole, fraction),
+        None => (amount, ""),
+    };
+    if whole.is_empty() && fraction.is_empty()
+        || fraction.len() > GWEI_DECIMALS
+        || !whole
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+    {
+        return Err(error());
+    }
+    let whole_gwei = if whole.is_empty() {
+        0
+    } else {
+        whole
+            .parse::<u64>()
+            .map_err(|_| error())?
+            .checked_mul(WEIS_IN_GWEI as u64)
+            .ok_or_else(error)?
+    };
+    let fraction_gwei = format!("{:0<9}", fraction)
+        .parse::<u64>()
+        .expect("digits are digits");
+    match whole_gwei.checked_add(fraction_gwei) {
+        Some(0) | None => Err(error()),
+        Some(gwei) => Ok(gwei),
+    }
+}
+
+fn validate_amount(amount: String) -> Result<(), String> {
+    if amount.eq_ignore_ascii_case("all") {
+        Ok(())
+    } else {
+        whole_units_to_gwei(&amount).map(|_| ())
+    }
+}
+
+const TRANSFER_SUBCOMMAND_ABOUT: &str =
+    "Transfers MASQ or transaction fee currency from the consuming wallet to another address. \
+     Use --dry-run to see what the transfer would cost without sending anything.";
+const DB_PASSWORD_ARG_HELP: &str =
+    "The current database password (a password must be set to use this command).";
+const TO_ARG_HELP: &str = "The address of the wallet the funds should go to.";
+const AMOUNT_ARG_HELP: &str =
+    "How much to transfer, in whole units with up to nine decimal places (e.g. 12.5), or 'all' \
+     to sweep the whole balance; for the transaction fee currency, that means everything but the \
+     fee.";
+const CURRENCY_ARG_HELP: &str =
+    "What to transfer: 'masq' or 'native' for the currency transaction fees are paid in.";
+const DRY_RUN_ARG_HELP: &str = "Only estimate the transfer, don't send it.";
+
+pub fn transfer_subcommand() -> App<'static, 'static> {
+    SubCommand::with_name("transfer")
+        .about(TRANSFER_SUBCOMMAND_ABOUT)
+        .arg(
+            Arg::with_name("db-password")
+                .help(DB_PASSWORD_ARG_HELP)
+                .value_name("DB-PASSWORD")
+                .required(true)
+                .case_insensitive(false),
+        )
+        .arg(
+            Arg::with_name("to")
+                .help(TO_ARG_HELP)
+                .long("to")
+                .value_name("ADDRESS")
+                .required(true)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("amount")
+                .help(AMOUNT_ARG_HELP)
+                .long("amount")
+                .value_name("AMOUNT")
+                .required(true)
+                .takes_value(true)
+                .validator(validate_amount),
+        )
+        .arg(
+            Arg::with_name("currency")
+                .help(CURRENCY_ARG_HELP)
+                .long("currency")
+                .value_name("CURRENCY")
+                .takes_value(true)
+                .possible_values(&["masq", "native"])
+                .case_insensitive(true)
+                .default_value("masq"),
+        )
+        .arg(
+            Arg::with_name("dry-run")
+                .help(DRY_RUN_ARG_HELP)
+                .long("dry-run")
+                .takes_value(false),
+        )
+}
+
+impl Command for TransferCommand {
+    fn execute(&self, context: &mut dyn CommandContext) -> Result<(), CommandError> {
+        let input = UiTransferRequest {
+            db_password: self.db_password.clone(),
+            recipient: self.recipient.clone(),
+            currency: self.currency,
+            amount_gwei_opt: self.amount_gwei_opt,
+            dry_run: self.dry_run,
+        };
+        let msg: UiTransferResponse = transaction(input, context, TRANSFER_COMMAND_TIMEOUT_MILLIS)?;
+        let stdout = context.stdout();
+        match &msg.transaction_hash_opt {
+            Some(hash) => short_writeln!(stdout, "Transfer sent in transaction {}", hash),
+            None => short_writeln!(stdout, "Dry run, nothing was sent"),
+        }
+        short_writeln!(
+            stdout,
+            "    Amount:          {} {}",
+            Self::gwei_to_whole_units(msg.amount_gwei),
+            Self::currency_name(msg.currency)
+        );
+        short_writeln!(stdout, "    Recipient:       {}", msg.recipient);
+        short_writeln!(
+            stdout,
+            "    Estimated fee:   {} (gas price {} gwei)",
+            Self::gwei_to_whole_units(msg.fee_estimate_gwei),
+            msg.gas_price_gwei.separate_with_commas()
+        );
+        short_writeln!(stdout, "    Nonce:           {}", msg.nonce);
+        Ok(())
+    }
+    as_any_ref_in_trait_impl!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_context::ContextError;
+    use crate::command_factory::{CommandFactory, CommandFactoryReal};
+    use crate::test_utils::mocks::CommandContextMock;
+    use masq_lib::messages::ToMessageBody;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(TRANSFER_COMMAND_TIMEOUT_MILLIS, 15000);
+        assert_eq!(GWEI_DECIMALS, 9);
+        assert_eq!(
+            TRANSFER_SUBCOMMAND_ABOUT,
+            "Transfers MASQ or transaction fee currency from the consuming wallet to another \
+             address. Use --dry-run to see what the transfer would cost without sending anything."
+        );
+        assert_eq!(
+            DB_PASSWORD_ARG_HELP,
+            "The current database password (a password must be set to use this command)."
+        );
+        assert_eq!(
+            TO_ARG_HELP,
+            "The address of the wallet the funds should go to."
+        );
+        assert_eq!(
+            AMOUNT_ARG_HELP,
+            "How much to transfer, in whole units with up to nine decimal places (e.g. 12.5), or \
+             'all' to sweep the whole balance; for the transaction fee currency, that means \
+             everything but the fee."
+        );
+        assert_eq!(
+            CURRENCY_ARG_HELP,
+            "What to transfer: 'masq' or 'native' for the currency transaction fees are paid in."
+        );
+        assert_eq!(
+            DRY_RUN_ARG_HELP,
+            "Only estimate the transfer, don't send it."
+        );
+    }
+
+    #[test]
+    fn whole_units_are_converted_to_gwei() {
+        assert_eq!(whole_units_to_gwei("12"), Ok(12_000_000_000));
+        assert_eq!(whole_units_to_gwei("12.5"), Ok(12_500_000_000));
+        assert_eq!(whole_units_to_gwei(".000000001"), Ok(1));
+        assert_eq!(whole_units_to_gwei("0.123456789"), Ok(123_456_789));
+        assert_eq!(whole_units_to_gwei("7."), Ok(7_000_000_000));
+    }
+
+    #[test]
+    fn invalid_amounts_are_rejected() {
+        vec![
+            "",
+            ".",
+            "0",
+            "0.0",
+            "-1",
+            "1,5",
+            "1.0000000001",
+            "abc",
+            "1e3",
+            "99999999999999999999",
+        ]
+        .into_iter()
+        .for_each(|amount| {
+            assert_eq!(
+                validate_amount(amount.to_string()),
+                Err(format!(
+                    "Amount must be a positive number of whole units or 'all', not '{}'",
+                    amount
+                )),
+                "{}",
+                amount
+            )
+        });
+        assert_eq!(validate_amount("ALL".to_string()), Ok(()));
+    }
+
+    #[test]
+    fn transfer_command_is_parsed_with_defaults() {
+        let result = TransferCommand::new(&[
+            "transfer".to_string(),
+            "bonkers".to_string(),
+            "--to".to_string(),
+            "0x0123456789012345678901234567890123456789".to_string(),
+            "--amount".to_string(),
+            "all".to_string(),
+        ]);
+
+        assert_eq!(
+            result,
+            Ok(TransferCommand {
+                db_password: "bonkers".to_string(),
+                recipient: "0x0123456789012345678901234567890123456789".to_string(),
+                currency: TransferCurrency::Masq,
+                amount_gwei_opt: None,
+                dry_run: false,
+            })
+        );
+    }
+
+    #[test]
+    fn transfer_command_requires_amount() {
+        let result = TransferCommand::new(&[
+            "transfer".to_string(),
+            "bonkers".to_string(),
+            "--to".to_string(),
+            "0x0123456789012345678901234567890123456789".to_string(),
+        ]);
+
+        let msg = result.unwrap_err();
+        assert!(
+            msg.contains("The following required arguments were not provided"),
+            "{}",
+            msg
+        );
+    }
+
+    #[test]
+    fn transfer_command_sends_funds_and_prints_the_transaction() {
+        let transact_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut context = CommandContextMock::new()
+            .transact_params(&transact_params_arc)
+            .transact_result(Ok(UiTransferResponse {
+                transaction_hash_opt: Some("0xabcdef".to_string()),
+                recipient: "0x0123456789012345678901234567890123456789".to_string(),
+                currency: TransferCurrency::Native,
+                amount_gwei: 1_500_000_000,
+                fee_estimate_gwei: 21_000,
+                gas_price_gwei: 1,
+                nonce: 42,
+            }
+            .tmb(0)));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let factory = CommandFactoryReal::new();
+        let subject = factory
+            .make(&[
+                "transfer".to_string(),
+                "bonkers".to_string(),
+                "--to".to_string(),
+                "0x0123456789012345678901234567890123456789".to_string(),
+                "--amount".to_string(),
+                "1.5".to_string(),
+                "--currency".to_string(),
+                "NATIVE".to_string(),
+            ])
+            .unwrap();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            "Transfer sent in transaction 0xabcdef\n\
+             \x20   Amount:          1.500000000 of transaction fee currency\n\
+             \x20   Recipient:       0x0123456789012345678901234567890123456789\n\
+             \x20   Estimated fee:   0.000021000 (gas price 1 gwei)\n\
+             \x20   Nonce:           42\n"
+        );
+        assert_eq!(stderr_arc.lock().unwrap().get_string(), String::new());
+        let transact_params = transact_params_arc.lock().unwrap();
+        assert_eq!(
+            *transact_params,
+            vec![(
+                UiTransferRequest {
+                    db_password: "bonkers".to_string(),
+                    recipient: "0x0123456789012345678901234567890123456789".to_string(),
+                    currency: TransferCurrency::Native,
+                    amount_gwei_opt: Some(1_500_000_000),
+                    dry_run: false,
+                }
+                .tmb(0),
+                TRANSFER_COMMAND_TIMEOUT_MILLIS
+            )]
+        )
+    }
+
+    #[test]
+    fn transfer_command_in_dry_run_prints_the_estimate() {
+        let transact_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut context = CommandContextMock::new()
+            .transact_params(&transact_params_arc)
+            .transact_result(Ok(UiTransferResponse {
+                transaction_hash_opt: None,
+                recipient: "0x0123456789012345678901234567890123456789".to_string(),
+                currency: TransferCurrency::Masq,
+                amount_gwei: 1_234_567_000_000_000,
+                fee_estimate_gwei: 3_000_000,
+                gas_price_gwei: 50,
+                nonce: 7,
+            }
+            .tmb(0)));
+        let stdout_arc = context.stdout_arc();
+        let subject = TransferCommand::new(&[
+            "transfer".to_string(),
+            "bonkers".to_string(),
+            "--to".to_string(),
+            "0x0123456789012345678901234567890123456789".to_string(),
+            "--amount".to_string(),
+            "all".to_string(),
+            "--dry-run".to_string(),
+        ])
+        .unwrap();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            "Dry run, nothing was sent\n\
+             \x20   Amount:          1,234,567.000000000 MASQ\n\
+             \x20   Recipient:       0x0123456789012345678901234567890123456789\n\
+             \x20   Estimated fee:   0.003000000 (gas price 50 gwei)\n\
+             \x20   Nonce:           7\n"
+        );
+        let transact_params = transact_params_arc.lock().unwrap();
+        assert_eq!(
+            transact_params[0].0,
+            UiTransferRequest {
+                db_password: "bonkers".to_string(),
+                recipient: "0x0123456789012345678901234567890123456789".to_string(),
+                currency: TransferCurrency::Masq,
+                amount_gwei_opt: None,
+                dry_run: true,
+            }
+            .tmb(0)
+        );
+    }
+
+    #[test]
+    fn transfer_command_handles_error_from_node() {
+        let mut context = CommandContextMock::new().transact_result(Err(
+            ContextError::PayloadError(4644, "Insufficient MASQ balance".to_string()),
+        ));
+        let stdout_arc = context.stdout_arc();
+        let subject = TransferCommand::new(&[
+            "transfer".to_string(),
+            "bonkers".to_string(),
+            "--to".to_string(),
+            "0x0123456789012345678901234567890123456789".to_string(),
+            "--amount".to_string(),
+            "1000".to_string(),
+        ])
+        .unwrap();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(
+            result,
+            Err(CommandError::Payload(
+                4644,
+                "Insufficient MASQ balance".to_string()
+            ))
+        );
+        assert_eq!(stdout_arc.lock().unwrap().get_string(), String::new());
+    }
+}
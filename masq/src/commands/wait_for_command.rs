@@ -0,0 +1,334 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::command_context::CommandContext;
+use crate::commands::commands_common::{
+    transaction, Command, CommandError, STANDARD_COMMAND_TIMEOUT_MILLIS,
+};
+use clap::{value_t, App, Arg, SubCommand};
+use masq_lib::constants::NODE_NOT_RUNNING_ERROR;
+use masq_lib::messages::{
+    UiConnectionStage, UiConnectionStatusRequest, UiConnectionStatusResponse,
+};
+use masq_lib::{as_any_ref_in_trait_impl, short_writeln};
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub const WAIT_FOR_POLL_INTERVAL_MILLIS: u64 = 1000;
+const DEFAULT_WAIT_FOR_TIMEOUT_SECS: &str = "60";
+const CONNECTION_STAGES: [&str; 3] = ["NotConnected", "ConnectedToNeighbor", "RouteFound"];
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum WaitCondition {
+    // Satisfied once the Node has reached this stage or gone beyond it
+    ConnectionStatus(UiConnectionStage),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct WaitForCommand {
+    pub condition: WaitCondition,
+    pub timeout: Duration,
+    pub poll_interval: Duration,
+}
+
+impl WaitForCommand {
+    pub fn new(pieces: &[String]) -> Result<Self, String> {
+        let matches = match wait_for_subcommand().get_matches_from_safe(pieces) {
+            Ok(matches) => matches,
+            Err(e) => return Err(format!("{}", e)),
+        };
+        let condition = parse_condition(matches.value_of("condition").expect("required"))
+            .expect("validator failed");
+        let timeout_secs = value_t!(matches, "timeout", u64).expect("timeout is defaulted");
+        Ok(Self {
+            condition,
+            timeout: Duration::from_secs(timeout_secs),
+            poll_interval: Duration::from_millis(WAIT_FOR_POLL_INTERVAL_MILLIS),
+        })
+    }
+
+    fn is_satisfied(&self, context: &mut dyn CommandContext) -> Result<bool, CommandError> {
+        match &self.condition {
+            WaitCondition::ConnectionStatus(wanted) => {
+                let output: Result<UiConnectionStatusResponse, CommandError> = transaction(
+                    UiConnectionStatusRequest {},
+                    context,
+                    STANDARD_COMMAND_TIMEOUT_MILLIS,
+                );
+                match output {
+                    Ok(response) => Ok(stage_rank(&response.stage) >= stage_rank(wanted)),
+                    // The Node may still be on its way up
+                    Err(CommandError::Payload(code, _)) if code == NODE_NOT_RUNNING_ERROR => {
+                        Ok(false)
+                    }
+                    Err(e) => Err(e),
+                }
+            }
+        }
+    }
+}
+
+impl Command for WaitForCommand {
+    fn execute(&self, context: &mut dyn CommandContext) -> Result<(), CommandError> {
+        let deadline = Instant::now() + self.timeout;
+        loop {
+            if self.is_satisfied(context)? {
+                short_writeln!(context.stdout(), "{}", describe(&self.condition));
+                return Ok(());
+            }
+            if Instant::now() + self.poll_interval > deadline {
+                let msg = format!(
+                    "Gave up after {} seconds waiting for {}",
+                    self.timeout.as_secs(),
+                    condition_text(&self.condition)
+                );
+                short_writeln!(context.stderr(), "{}", msg);
+                return Err(CommandError::Other(msg));
+            }
+            thread::sleep(self.poll_interval);
+        }
+    }
+
+    as_any_ref_in_trait_impl!();
+}
+
+fn stage_rank(stage: &UiConnectionStage) -> usize {
+    match stage {
+        UiConnectionStage::NotConnected => 0,
+        UiConnectionStage::ConnectedToNeighbor => 1,
+        UiConnectionStage::RouteFound => 2,
+    }
+}
+
+fn condition_text(condition: &WaitCondition) -> String {
+    match condition {
+        WaitCondition::ConnectionStatus(stage) => format!("connection-status={:?}", stage),
+    }
+}
+
+fn describe(condition: &WaitCondition) -> String {
+    format!("Reached {}", condition_text(condition))
+}
+
+fn parse_condition(condition: &str) -> Result<WaitCondition, String> {
+    let complaint = || {
+        format!(
+            "'{}' isn't a condition masq can wait for; try connection-status=<STAGE>, where STAGE \
+             is one of {}",
+            condition,
+            CONNECTION_STAGES.join(", ")
+        )
+    };
+    match condition.split_once('=') {
+        Some((subject, value)) if subject == "connection-status" => {
+            let stage = match value.to_lowercase().as_str() {
+                "notconnected" => UiConnectionStage::NotConnected,
+                "connectedtoneighbor" => UiConnectionStage::ConnectedToNeighbor,
+                "routefound" => UiConnectionStage::RouteFound,
+                _ => return Err(complaint()),
+            };
+            Ok(WaitCondition::ConnectionStatus(stage))
+        }
+        _ => Err(complaint()),
+    }
+}
+
+fn validate_condition(condition: String) -> Result<(), String> {
+    parse_condition(&condition).map(|_| ())
+}
+
+fn validate_timeout(timeout: String) -> Result<(), String> {
+    match timeout.parse::<u64>() {
+        Ok(secs) if secs > 0 => Ok(()),
+        _ => Err(format!("'{}' is not a whole number of seconds", timeout)),
+    }
+}
+
+const WAIT_FOR_SUBCOMMAND_ABOUT: &str =
+    "Waits until the Node reaches some condition, checking every second. Mostly useful in \
+     scripts run with 'masq --script', where a command that times out stops the script.";
+const CONDITION_ARG_HELP: &str =
+    "What to wait for. connection-status=<STAGE> waits until the Node has reached that \
+     connection stage (NotConnected, ConnectedToNeighbor or RouteFound) or gone beyond it.";
+const TIMEOUT_ARG_HELP: &str = "How many seconds to wait before giving up.";
+
+pub fn wait_for_subcommand() -> App<'static, 'static> {
+    SubCommand::with_name("wait-for")
+        .about(WAIT_FOR_SUBCOMMAND_ABOUT)
+        .arg(
+            Arg::with_name("condition")
+                .help(CONDITION_ARG_HELP)
+                .value_name("CONDITION")
+                .required(true)
+                .validator(validate_condition),
+        )
+        .arg(
+            Arg::with_name("timeout")
+                .help(TIMEOUT_ARG_HELP)
+                .long("timeout")
+                .value_name("SECONDS")
+                .takes_value(true)
+                .default_value(DEFAULT_WAIT_FOR_TIMEOUT_SECS)
+                .validator(validate_timeout),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_context::ContextError;
+    use crate::command_factory::{CommandFactory, CommandFactoryReal};
+    use crate::test_utils::mocks::CommandContextMock;
+    use masq_lib::messages::ToMessageBody;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(WAIT_FOR_POLL_INTERVAL_MILLIS, 1000);
+        assert_eq!(DEFAULT_WAIT_FOR_TIMEOUT_SECS, "60");
+        assert_eq!(
+            CONNECTION_STAGES,
+            ["NotConnected", "ConnectedToNeighbor", "RouteFound"]
+        );
+        assert_eq!(
+            WAIT_FOR_SUBCOMMAND_ABOUT,
+            "Waits until the Node reaches some condition, checking every second. Mostly useful \
+             in scripts run with 'masq --script', where a command that times out stops the \
+             script."
+        );
+        assert_eq!(
+            CONDITION_ARG_HELP,
+            "What to wait for. connection-status=<STAGE> waits until the Node has reached that \
+             connection stage (NotConnected, ConnectedToNeighbor or RouteFound) or gone beyond \
+             it."
+        );
+        assert_eq!(
+            TIMEOUT_ARG_HELP,
+            "How many seconds to wait before giving up."
+        );
+    }
+
+    #[test]
+    fn command_factory_makes_wait_for_command() {
+        let subject = CommandFactoryReal::new();
+
+        let result = subject
+            .make(&[
+                "wait-for".to_string(),
+                "connection-status=connectedToNeighbor".to_string(),
+                "--timeout".to_string(),
+                "30".to_string(),
+            ])
+            .unwrap();
+
+        let command: &WaitForCommand = result.as_any().downcast_ref().unwrap();
+        assert_eq!(
+            command,
+            &WaitForCommand {
+                condition: WaitCondition::ConnectionStatus(UiConnectionStage::ConnectedToNeighbor),
+                timeout: Duration::from_secs(30),
+                poll_interval: Duration::from_millis(WAIT_FOR_POLL_INTERVAL_MILLIS),
+            }
+        );
+    }
+
+    #[test]
+    fn unknown_conditions_are_rejected() {
+        let result = WaitForCommand::new(&["wait-for".to_string(), "balance=100".to_string()]);
+
+        let msg = result.err().unwrap();
+        assert_eq!(
+            msg.contains(
+                "'balance=100' isn't a condition masq can wait for; try \
+                 connection-status=<STAGE>, where STAGE is one of NotConnected, \
+                 ConnectedToNeighbor, RouteFound"
+            ),
+            true,
+            "{}",
+            msg
+        );
+    }
+
+    fn make_subject(stage: UiConnectionStage, timeout_millis: u64) -> WaitForCommand {
+        WaitForCommand {
+            condition: WaitCondition::ConnectionStatus(stage),
+            timeout: Duration::from_millis(timeout_millis),
+            poll_interval: Duration::from_millis(1),
+        }
+    }
+
+    #[test]
+    fn execute_polls_until_the_stage_is_reached_or_passed() {
+        let transact_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut context = CommandContextMock::new()
+            .transact_params(&transact_params_arc)
+            .transact_result(Err(ContextError::PayloadError(
+                NODE_NOT_RUNNING_ERROR,
+                "Not yet".to_string(),
+            )))
+            .transact_result(Ok(UiConnectionStatusResponse {
+                stage: UiConnectionStage::NotConnected,
+            }
+            .tmb(0)))
+            .transact_result(Ok(UiConnectionStatusResponse {
+                stage: UiConnectionStage::RouteFound,
+            }
+            .tmb(0)));
+        let stdout_arc = context.stdout_arc();
+        let subject = make_subject(UiConnectionStage::ConnectedToNeighbor, 10_000);
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        let transact_params = transact_params_arc.lock().unwrap();
+        assert_eq!(
+            *transact_params,
+            vec![
+                (
+                    UiConnectionStatusRequest {}.tmb(0),
+                    STANDARD_COMMAND_TIMEOUT_MILLIS
+                );
+                3
+            ]
+        );
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            "Reached connection-status=ConnectedToNeighbor\n"
+        );
+    }
+
+    #[test]
+    fn execute_gives_up_at_the_timeout() {
+        let mut context = CommandContextMock::new();
+        for _ in 0..1000 {
+            context = context.transact_result(Ok(UiConnectionStatusResponse {
+                stage: UiConnectionStage::NotConnected,
+            }
+            .tmb(0)));
+        }
+        let stderr_arc = context.stderr_arc();
+        let subject = make_subject(UiConnectionStage::RouteFound, 0);
+
+        let result = subject.execute(&mut context);
+
+        let msg = "Gave up after 0 seconds waiting for connection-status=RouteFound".to_string();
+        assert_eq!(result, Err(CommandError::Other(msg.clone())));
+        assert_eq!(
+            stderr_arc.lock().unwrap().get_string(),
+            format!("{}\n", msg)
+        );
+    }
+
+    #[test]
+    fn execute_stops_at_other_errors() {
+        let mut context = CommandContextMock::new()
+            .transact_result(Err(ContextError::ConnectionDropped("Booga".to_string())));
+        let subject = make_subject(UiConnectionStage::RouteFound, 10_000);
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(
+            result,
+            Err(CommandError::ConnectionProblem("Booga".to_string()))
+        );
+    }
+}
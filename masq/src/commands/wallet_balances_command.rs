@@ -0,0 +1,215 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::command_context::CommandContext;
+use crate::commands::commands_common::{transaction, Command, CommandError};
+use clap::{App, Arg, SubCommand};
+use masq_lib::constants::WEIS_IN_GWEI;
+use masq_lib::messages::{UiWalletBalances, UiWalletBalancesRequest, UiWalletBalancesResponse};
+use masq_lib::{as_any_ref_in_trait_impl, short_writeln};
+use std::io::Write;
+use thousands::Separable;
+
+// Both wallets have to be looked up on the blockchain, which may take a while
+pub const WALLET_BALANCES_COMMAND_TIMEOUT_MILLIS: u64 = 10000;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct WalletBalancesCommand {
+    pub db_password: String,
+}
+
+impl WalletBalancesCommand {
+    pub fn new(pieces: &[String]) -> Result<Self, String> {
+        let matches = match wallet_balances_subcommand().get_matches_from_safe(pieces) {
+            Ok(matches) => matches,
+            Err(e) => return Err(format!("{}", e)),
+        };
+        Ok(Self {
+            db_password: matches
+                .value_of("db-password")
+                .expect("db-password is not properly required")
+                .to_string(),
+        })
+    }
+
+    fn write_wallet(stdout: &mut dyn Write, title: &str, wallet: &UiWalletBalances) {
+        short_writeln!(stdout, "{} {}", title, wallet.address);
+        short_writeln!(
+            stdout,
+            "    MASQ:                       {}",
+            Self::gwei_to_whole_units(wallet.masq_balance_gwei)
+        );
+        short_writeln!(
+            stdout,
+            "    Transaction fee currency:   {}",
+            Self::gwei_to_whole_units(wallet.transaction_fee_balance_gwei)
+        );
+    }
+
+    fn gwei_to_whole_units(gwei: u64) -> String {
+        let gweis_in_unit = WEIS_IN_GWEI as u64;
+        format!(
+            "{}.{:09}",
+            (gwei / gweis_in_unit).separate_with_commas(),
+            gwei % gweis_in_unit
+        )
+    }
+}
+
+const WALLET_BALANCES_SUBCOMMAND_ABOUT: &str =
+    "Looks up the MASQ and transaction fee currency balances of the consuming and earning wallets \
+     on the blockchain, together with the gas price in use and an estimate of how many payables \
+     the consuming wallet can still afford to pay.";
+const DB_PASSWORD_ARG_HELP: &str =
+    "The current database password (a password must be set to use this command).";
+
+pub fn wallet_balances_subcommand() -> App<'static, 'static> {
+    SubCommand::with_name("wallet-balances")
+        .about(WALLET_BALANCES_SUBCOMMAND_ABOUT)
+        .arg(
+            Arg::with_name("db-password")
+                .help(DB_PASSWORD_ARG_HELP)
+                .value_name("DB-PASSWORD")
+                .required(true)
+                .case_insensitive(false),
+        )
+}
+
+impl Command for WalletBalancesCommand {
+    fn execute(&self, context: &mut dyn CommandContext) -> Result<(), CommandError> {
+        let input = UiWalletBalancesRequest {
+            db_password: self.db_password.clone(),
+        };
+        let msg: UiWalletBalancesResponse =
+            transaction(input, context, WALLET_BALANCES_COMMAND_TIMEOUT_MILLIS)?;
+        let stdout = context.stdout();
+        Self::write_wallet(stdout, "Consuming wallet", &msg.consuming_wallet);
+        Self::write_wallet(stdout, "Earning wallet  ", &msg.earning_wallet);
+        short_writeln!(
+            stdout,
+            "Gas price:                      {} gwei",
+            msg.gas_price_gwei.separate_with_commas()
+        );
+        short_writeln!(
+            stdout,
+            "Affordable payables:            {} (each grown up to the debt threshold)",
+            msg.affordable_payables.separate_with_commas()
+        );
+        Ok(())
+    }
+    as_any_ref_in_trait_impl!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_context::ContextError;
+    use crate::command_factory::{CommandFactory, CommandFactoryReal};
+    use crate::commands::commands_common::{Command, CommandError};
+    use crate::test_utils::mocks::CommandContextMock;
+    use masq_lib::messages::ToMessageBody;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(WALLET_BALANCES_COMMAND_TIMEOUT_MILLIS, 10000);
+        assert_eq!(
+            WALLET_BALANCES_SUBCOMMAND_ABOUT,
+            "Looks up the MASQ and transaction fee currency balances of the consuming and earning \
+             wallets on the blockchain, together with the gas price in use and an estimate of how \
+             many payables the consuming wallet can still afford to pay."
+        );
+        assert_eq!(
+            DB_PASSWORD_ARG_HELP,
+            "The current database password (a password must be set to use this command)."
+        );
+    }
+
+    #[test]
+    fn wallet_balances_command_prints_balances_of_both_wallets() {
+        let transact_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut context = CommandContextMock::new()
+            .transact_params(&transact_params_arc)
+            .transact_result(Ok(UiWalletBalancesResponse {
+                consuming_wallet: UiWalletBalances {
+                    address: "0x464654jhkjhk6".to_string(),
+                    transaction_fee_balance_gwei: 1_500_000_000,
+                    masq_balance_gwei: 25_000_000_001,
+                },
+                earning_wallet: UiWalletBalances {
+                    address: "0x454654klljkjk".to_string(),
+                    transaction_fee_balance_gwei: 123_456_789,
+                    masq_balance_gwei: 1_234_567_890_000_000,
+                },
+                gas_price_gwei: 50,
+                affordable_payables: 2,
+            }
+            .tmb(0)));
+        let stdout_arc = context.stdout_arc();
+        let stderr_arc = context.stderr_arc();
+        let factory = CommandFactoryReal::new();
+        let subject = factory
+            .make(&["wallet-balances".to_string(), "bonkers".to_string()])
+            .unwrap();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            stdout_arc.lock().unwrap().get_string(),
+            "Consuming wallet 0x464654jhkjhk6\n\
+             \x20   MASQ:                       25.000000001\n\
+             \x20   Transaction fee currency:   1.500000000\n\
+             Earning wallet   0x454654klljkjk\n\
+             \x20   MASQ:                       1,234,567.890000000\n\
+             \x20   Transaction fee currency:   0.123456789\n\
+             Gas price:                      50 gwei\n\
+             Affordable payables:            2 (each grown up to the debt threshold)\n"
+        );
+        assert_eq!(stderr_arc.lock().unwrap().get_string(), String::new());
+        let transact_params = transact_params_arc.lock().unwrap();
+        assert_eq!(
+            *transact_params,
+            vec![(
+                UiWalletBalancesRequest {
+                    db_password: "bonkers".to_string(),
+                }
+                .tmb(0),
+                WALLET_BALANCES_COMMAND_TIMEOUT_MILLIS
+            )]
+        )
+    }
+
+    #[test]
+    fn wallet_balances_command_requires_password() {
+        let result = WalletBalancesCommand::new(&["wallet-balances".to_string()]);
+
+        let msg = result.unwrap_err();
+        assert!(
+            msg.contains("The following required arguments were not provided"),
+            "{}",
+            msg
+        );
+    }
+
+    #[test]
+    fn wallet_balances_command_handles_error_from_node() {
+        let mut context = CommandContextMock::new().transact_result(Err(
+            ContextError::PayloadError(4644, "Blockchain error: Query failed".to_string()),
+        ));
+        let stdout_arc = context.stdout_arc();
+        let subject =
+            WalletBalancesCommand::new(&["wallet-balances".to_string(), "bonkers".to_string()])
+                .unwrap();
+
+        let result = subject.execute(&mut context);
+
+        assert_eq!(
+            result,
+            Err(CommandError::Payload(
+                4644,
+                "Blockchain error: Query failed".to_string()
+            ))
+        );
+        assert_eq!(stdout_arc.lock().unwrap().get_string(), String::new());
+    }
+}
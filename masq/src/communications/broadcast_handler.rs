@@ -1,13 +1,14 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
 use crate::commands::change_password_command::ChangePasswordCommand;
+use crate::commands::logs_command::LogsCommand;
 use crate::commands::setup_command::SetupCommand;
 use crate::notifications::crashed_notification::CrashNotifier;
 use crate::terminal::terminal_interface::TerminalWrapper;
 use crossbeam_channel::{unbounded, RecvError, Sender};
 use masq_lib::messages::{
-    FromMessageBody, UiConnectionChangeBroadcast, UiLogBroadcast, UiNewPasswordBroadcast,
-    UiNodeCrashedBroadcast, UiSetupBroadcast, UiUndeliveredFireAndForget,
+    FromMessageBody, UiConnectionChangeBroadcast, UiLogBroadcast, UiLogStreamBroadcast,
+    UiNewPasswordBroadcast, UiNodeCrashedBroadcast, UiSetupBroadcast, UiUndeliveredFireAndForget,
 };
 use masq_lib::ui_gateway::MessageBody;
 use masq_lib::utils::ExpectValue;
@@ -91,6 +92,8 @@ impl BroadcastHandlerReal {
             Ok(message_body) => {
                 if let Ok((body, _)) = UiLogBroadcast::fmb(message_body.clone()) {
                     handle_ui_log_broadcast(body, stdout, terminal_interface)
+                } else if let Ok((body, _)) = UiLogStreamBroadcast::fmb(message_body.clone()) {
+                    LogsCommand::handle_broadcast(body, stdout, terminal_interface)
                 } else if let Ok((body, _)) = UiSetupBroadcast::fmb(message_body.clone()) {
                     SetupCommand::handle_broadcast(body, stdout, terminal_interface);
                 } else if let Ok((body, _)) = UiNodeCrashedBroadcast::fmb(message_body.clone()) {
@@ -264,6 +267,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn broadcast_of_log_stream_triggers_correct_handler() {
+        let (factory, handle) = TestStreamFactory::new();
+        let subject = BroadcastHandlerReal::new(Some(TerminalWrapper::new(Arc::new(
+            TerminalPassiveMock::new(),
+        ))))
+        .start(Box::new(factory));
+        let message = masq_lib::messages::UiLogStreamBroadcast {
+            msg: "Received a CORES package".to_string(),
+            level: "DEBUG".to_string(),
+            module: "Hopper".to_string(),
+        }
+        .tmb(0);
+
+        subject.send(message);
+
+        let stdout = handle.stdout_so_far();
+        assert_eq!(stdout, ">>  DEBUG: Hopper: Received a CORES package\n");
+        assert_eq!(
+            handle.stderr_so_far(),
+            "".to_string(),
+            "stderr: '{}'",
+            stdout
+        );
+    }
+
     #[test]
     fn broadcast_of_crashed_triggers_correct_handler() {
         let (factory, handle) = TestStreamFactory::new();
@@ -5,6 +5,7 @@ use crate::communications::broadcast_handler::{
 };
 use crate::communications::client_listener_thread::{ClientListener, ClientListenerError};
 use crate::communications::node_conversation::{NodeConversation, NodeConversationTermination};
+use crate::communications::tls_tunnel::{open_tunnel, TlsTrust};
 use crossbeam_channel::{unbounded, RecvTimeoutError};
 use crossbeam_channel::{Receiver, RecvError, Sender};
 use masq_lib::messages::{CrashReason, FromMessageBody, ToMessageBody, UiNodeCrashedBroadcast};
@@ -17,6 +18,7 @@ use std::net::TcpStream;
 use std::thread;
 use std::thread::JoinHandle;
 use std::time::Duration;
+use websocket::header::Headers;
 use websocket::sender::Writer;
 use websocket::sync::Client;
 use websocket::ws::sender::Sender as WsSender;
@@ -58,6 +60,33 @@ impl RedirectOrder {
     }
 }
 
+// Where masq finds the Daemon or Node. Redirects keep the host, TLS and token and change the port.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UiEndpoint {
+    pub host: String,
+    pub port: u16,
+    pub tls_opt: Option<TlsTrust>,
+    pub token_opt: Option<String>,
+}
+
+impl UiEndpoint {
+    pub fn local(port: u16) -> Self {
+        Self {
+            host: localhost().to_string(),
+            port,
+            tls_opt: None,
+            token_opt: None,
+        }
+    }
+
+    fn with_port(&self, port: u16) -> Self {
+        Self {
+            port,
+            ..self.clone()
+        }
+    }
+}
+
 pub struct ConnectionManager {
     demand_tx: Sender<Demand>,
     conversation_return_rx: Receiver<NodeConversation>,
@@ -83,13 +112,14 @@ impl ConnectionManager {
 
     pub fn connect(
         &mut self,
-        port: u16,
+        endpoint: UiEndpoint,
         generic_broadcast_handle: Box<dyn BroadcastHandle>,
         timeout_millis: u64,
     ) -> Result<(), ClientListenerError> {
         let (demand_tx, demand_rx) = unbounded();
         let (listener_to_manager_tx, listener_to_manager_rx) = unbounded();
-        let talker_half = make_client_listener(port, listener_to_manager_tx, timeout_millis)?;
+        let talker_half = make_client_listener(&endpoint, listener_to_manager_tx, timeout_millis)?;
+        let port = endpoint.port;
         let (conversation_return_tx, conversation_return_rx) = unbounded();
         let (redirect_order_tx, redirect_order_rx) = unbounded();
         let (redirect_response_tx, redirect_response_rx) = unbounded();
@@ -101,6 +131,7 @@ impl ConnectionManager {
         self.redirect_response_rx = redirect_response_rx;
         self.active_port_response_rx = active_port_response_rx;
         let inner = CmsInner {
+            endpoint,
             active_port: Some(port),
             daemon_port: port,
             node_port: None,
@@ -154,14 +185,11 @@ impl ConnectionManager {
 }
 
 fn make_client_listener(
-    port: u16,
+    endpoint: &UiEndpoint,
     listener_to_manager_tx: Sender<Result<MessageBody, ClientListenerError>>,
     timeout_millis: u64,
 ) -> Result<Writer<TcpStream>, ClientListenerError> {
-    let url = format!("ws://{}:{}", localhost(), port);
-    let builder = ClientBuilder::new(url.as_str()).expect("Bad URL");
-    let result = builder.add_protocol(NODE_UI_PROTOCOL);
-    let result = match connect_insecure_timeout(result, timeout_millis) {
+    let result = match connect_with_timeout(endpoint.clone(), timeout_millis) {
         Err(RecvTimeoutError::Disconnected) => return Err(ClientListenerError::Closed),
         Err(RecvTimeoutError::Timeout) => return Err(ClientListenerError::Timeout),
         Ok(r) => r,
@@ -177,19 +205,43 @@ fn make_client_listener(
 }
 
 //hack a time-out around connection attempt to the Node or Daemon. Leaks a thread if the attempt times out
-fn connect_insecure_timeout(
-    mut builder: ClientBuilder<'static>,
+fn connect_with_timeout(
+    endpoint: UiEndpoint,
     timeout_millis: u64,
 ) -> Result<WebSocketResult<Client<TcpStream>>, RecvTimeoutError> {
     let (tx, rx) = unbounded();
     thread::spawn(move || {
-        let result = builder.connect_insecure();
-        let _ = tx.send(result);
+        let _ = tx.send(connect(&endpoint));
     });
     rx.recv_timeout(Duration::from_millis(timeout_millis))
 }
 
+fn connect(endpoint: &UiEndpoint) -> WebSocketResult<Client<TcpStream>> {
+    let scheme = if endpoint.tls_opt.is_some() {
+        "wss"
+    } else {
+        "ws"
+    };
+    let url = format!("{}://{}:{}", scheme, endpoint.host, endpoint.port);
+    let mut headers = Headers::new();
+    if let Some(token) = &endpoint.token_opt {
+        headers.set_raw(
+            "Authorization",
+            vec![format!("Bearer {}", token).into_bytes()],
+        );
+    }
+    let mut builder = ClientBuilder::new(url.as_str())
+        .expect("Bad URL")
+        .add_protocol(NODE_UI_PROTOCOL)
+        .custom_headers(&headers);
+    match &endpoint.tls_opt {
+        Some(trust) => builder.connect_on(open_tunnel(&endpoint.host, endpoint.port, trust)?),
+        None => builder.connect_insecure(),
+    }
+}
+
 struct CmsInner {
+    endpoint: UiEndpoint,
     active_port: Option<u16>,
     daemon_port: u16,
     node_port: Option<u16>,
@@ -377,7 +429,7 @@ impl ConnectionManagerThread {
         };
         let (listener_to_manager_tx, listener_to_manager_rx) = unbounded();
         let talker_half = match make_client_listener(
-            redirect_order.port,
+            &inner.endpoint.with_port(redirect_order.port),
             listener_to_manager_tx,
             redirect_order.timeout_millis,
         ) {
@@ -450,7 +502,9 @@ impl ConnectionManagerThread {
         let (listener_to_manager_tx, listener_to_manager_rx) = unbounded();
         inner.listener_to_manager_rx = listener_to_manager_rx;
         match make_client_listener(
-            inner.active_port.expect("Active port disappeared!"),
+            &inner
+                .endpoint
+                .with_port(inner.active_port.expect("Active port disappeared!")),
             listener_to_manager_tx,
             FALLBACK_TIMEOUT_MILLIS,
         ) {
@@ -611,7 +665,11 @@ mod tests {
         thread::sleep(Duration::from_millis(500)); // let the server get started
         let mut subject = ConnectionManager::new();
         subject
-            .connect(port, Box::new(BroadcastHandleMock::new()), 1000)
+            .connect(
+                UiEndpoint::local(port),
+                Box::new(BroadcastHandleMock::new()),
+                1000,
+            )
             .unwrap();
         (subject, stop_handle)
     }
@@ -1178,6 +1236,8 @@ mod tests {
                     total_paid_receivable_gwei: 32,
                 }),
                 query_results_opt: None,
+                fee_bumps_opt: None,
+                spending_budget_opt: None,
             }
             .tmb(1),
         );
@@ -1201,7 +1261,11 @@ mod tests {
         let broadcast_handler = BroadcastHandleMock::new().send_params(&send_params_arc);
         let mut subject = ConnectionManager::new();
         subject
-            .connect(daemon_port, Box::new(broadcast_handler), 1000)
+            .connect(
+                UiEndpoint::local(daemon_port),
+                Box::new(broadcast_handler),
+                1000,
+            )
             .unwrap();
         let conversation = subject.start_conversation();
 
@@ -1219,7 +1283,9 @@ mod tests {
                     total_unpaid_receivable_gwei: 29,
                     total_paid_receivable_gwei: 32,
                 }),
-                query_results_opt: None
+                query_results_opt: None,
+                fee_bumps_opt: None,
+                spending_budget_opt: None,
             }
         );
         assert_eq!(context_id, 1);
@@ -1454,7 +1520,11 @@ mod tests {
         let mut subject = ConnectionManager::new();
         thread::sleep(Duration::from_millis(500)); // let the server get started
         subject
-            .connect(port, Box::new(BroadcastHandleMock::new()), 1000)
+            .connect(
+                UiEndpoint::local(port),
+                Box::new(BroadcastHandleMock::new()),
+                1000,
+            )
             .unwrap();
         let conversation1 = subject.start_conversation();
         let conversation2 = subject.start_conversation();
@@ -1478,6 +1548,7 @@ mod tests {
 
     fn make_inner() -> CmsInner {
         CmsInner {
+            endpoint: UiEndpoint::local(0),
             active_port: Some(0),
             daemon_port: 0,
             node_port: None,
@@ -3,3 +3,4 @@ pub mod broadcast_handler;
 mod client_listener_thread;
 pub mod connection_manager;
 pub mod node_conversation;
+pub mod tls_tunnel;
@@ -0,0 +1,244 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+// The synchronous websocket client has to split its stream into a reader and a writer that run on
+// different threads, which a TLS stream can't do. So a TLS connection to a remote Node is made
+// here and relayed, decrypted, over a loopback TCP connection that the websocket client can split.
+
+use openssl::ssl::{SslConnector, SslMethod, SslStream, SslVerifyMode};
+use openssl::x509::X509;
+use std::fs;
+use std::io;
+use std::io::{ErrorKind, Read, Write};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+const RELAY_POLL_MILLIS: u64 = 10;
+const RELAY_BUFFER_SIZE: usize = 16384;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TlsTrust {
+    // The Node's certificate must chain to a root the system trusts, and name the host
+    SystemRoots,
+    // The Node's certificate must be exactly this one; for the Node's self-signed certificate
+    PinnedCertificate(PathBuf),
+}
+
+// Returns the loopback end of the relay; reads and writes on it go to and from the Node over TLS
+pub fn open_tunnel(host: &str, port: u16, trust: &TlsTrust) -> io::Result<TcpStream> {
+    let connector = make_connector(trust)?;
+    let remote_stream = TcpStream::connect((host, port))?;
+    let tls_stream = connector
+        .connect(host, remote_stream)
+        .map_err(|e| io::Error::new(ErrorKind::ConnectionRefused, format!("{:?}", e)))?;
+    let listener = TcpListener::bind(SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0))?;
+    let local_stream = TcpStream::connect(listener.local_addr()?)?;
+    let (relay_stream, _) = listener.accept()?;
+    thread::spawn(move || relay(relay_stream, tls_stream));
+    Ok(local_stream)
+}
+
+fn make_connector(trust: &TlsTrust) -> io::Result<SslConnector> {
+    let mut builder = SslConnector::builder(SslMethod::tls()).map_err(to_io_error)?;
+    if let TlsTrust::PinnedCertificate(path) = trust {
+        let pinned_der = read_certificate_der(path)?;
+        builder.set_verify_callback(SslVerifyMode::PEER, move |_, context| {
+            if context.error_depth() > 0 {
+                return true;
+            }
+            match context.current_cert().map(|cert| cert.to_der()) {
+                Some(Ok(der)) => der == pinned_der,
+                _ => false,
+            }
+        });
+    }
+    Ok(builder.build())
+}
+
+fn read_certificate_der(path: &Path) -> io::Result<Vec<u8>> {
+    let pem = fs::read(path)?;
+    X509::from_pem(&pem)
+        .and_then(|cert| cert.to_der())
+        .map_err(|e| {
+            io::Error::new(
+                ErrorKind::InvalidData,
+                format!("{} holds no PEM certificate: {:?}", path.display(), e),
+            )
+        })
+}
+
+fn to_io_error(e: openssl::error::ErrorStack) -> io::Error {
+    io::Error::new(ErrorKind::Other, format!("{:?}", e))
+}
+
+// Both streams wait briefly for reads, so one thread can shuttle traffic both ways
+fn relay(mut local: TcpStream, mut remote: SslStream<TcpStream>) {
+    let poll_interval = Some(Duration::from_millis(RELAY_POLL_MILLIS));
+    if local.set_read_timeout(poll_interval).is_err()
+        || remote.get_ref().set_read_timeout(poll_interval).is_err()
+    {
+        return;
+    }
+    let mut buffer = [0u8; RELAY_BUFFER_SIZE];
+    loop {
+        let outbound = pass_along(&mut local, &mut remote, &mut buffer);
+        let inbound = pass_along(&mut remote, &mut local, &mut buffer);
+        if !(outbound && inbound) {
+            break;
+        }
+    }
+    let _ = remote.shutdown();
+    let _ = local.shutdown(std::net::Shutdown::Both);
+}
+
+// False once either side has closed or failed
+fn pass_along(from: &mut dyn Read, to: &mut dyn Write, buffer: &mut [u8]) -> bool {
+    match from.read(buffer) {
+        Ok(0) => false,
+        Ok(len) => to
+            .write_all(&buffer[..len])
+            .and_then(|_| to.flush())
+            .is_ok(),
+        Err(e) if e.kind() == ErrorKind::WouldBlock || e.kind() == ErrorKind::TimedOut => true,
+        Err(e) if e.kind() == ErrorKind::Interrupted => true,
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+    use openssl::asn1::Asn1Time;
+    use openssl::bn::BigNum;
+    use openssl::ec::{EcGroup, EcKey};
+    use openssl::hash::MessageDigest;
+    use openssl::nid::Nid;
+    use openssl::pkey::{PKey, Private};
+    use openssl::ssl::SslAcceptor;
+    use openssl::x509::X509NameBuilder;
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(RELAY_POLL_MILLIS, 10);
+        assert_eq!(RELAY_BUFFER_SIZE, 16384);
+    }
+
+    fn make_identity(common_name: &str) -> (X509, PKey<Private>) {
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        let key = PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap();
+        let mut name = X509NameBuilder::new().unwrap();
+        name.append_entry_by_text("CN", common_name).unwrap();
+        let name = name.build();
+        let mut builder = X509::builder().unwrap();
+        builder.set_version(2).unwrap();
+        let serial = BigNum::from_u32(1).unwrap().to_asn1_integer().unwrap();
+        builder.set_serial_number(&serial).unwrap();
+        builder.set_subject_name(&name).unwrap();
+        builder.set_issuer_name(&name).unwrap();
+        builder.set_pubkey(&key).unwrap();
+        builder
+            .set_not_before(&Asn1Time::days_from_now(0).unwrap())
+            .unwrap();
+        builder
+            .set_not_after(&Asn1Time::days_from_now(1).unwrap())
+            .unwrap();
+        builder.sign(&key, MessageDigest::sha256()).unwrap();
+        (builder.build(), key)
+    }
+
+    // Accepts one TLS connection and echoes what it receives, uppercased
+    fn start_echo_server(cert: X509, key: PKey<Private>) -> u16 {
+        let mut acceptor = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls()).unwrap();
+        acceptor.set_certificate(&cert).unwrap();
+        acceptor.set_private_key(&key).unwrap();
+        let acceptor = acceptor.build();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut tls_stream = match acceptor.accept(stream) {
+                Ok(tls_stream) => tls_stream,
+                Err(_) => return,
+            };
+            let mut buffer = [0u8; 64];
+            while let Ok(len) = tls_stream.read(&mut buffer) {
+                if len == 0 {
+                    break;
+                }
+                let reply = buffer[..len].to_ascii_uppercase();
+                tls_stream.write_all(&reply).unwrap();
+            }
+        });
+        port
+    }
+
+    fn write_pem(test_name: &str, cert: &X509) -> PathBuf {
+        let path = ensure_node_home_directory_exists("tls_tunnel", test_name).join("cert.pem");
+        fs::write(&path, cert.to_pem().unwrap()).unwrap();
+        path
+    }
+
+    #[test]
+    fn tunnel_relays_traffic_to_a_node_with_the_pinned_certificate() {
+        let (cert, key) = make_identity("MASQ Node UI");
+        let cert_path = write_pem(
+            "tunnel_relays_traffic_to_a_node_with_the_pinned_certificate",
+            &cert,
+        );
+        let port = start_echo_server(cert, key);
+
+        let mut stream =
+            open_tunnel("127.0.0.1", port, &TlsTrust::PinnedCertificate(cert_path)).unwrap();
+
+        stream.write_all(b"booga").unwrap();
+        let mut buffer = [0u8; 5];
+        stream.read_exact(&mut buffer).unwrap();
+        assert_eq!(&buffer, b"BOOGA");
+    }
+
+    #[test]
+    fn tunnel_refuses_a_node_with_some_other_certificate() {
+        let (cert, key) = make_identity("MASQ Node UI");
+        let (impostor_cert, _) = make_identity("MASQ Node UI");
+        let cert_path = write_pem(
+            "tunnel_refuses_a_node_with_some_other_certificate",
+            &impostor_cert,
+        );
+        let port = start_echo_server(cert, key);
+
+        let result = open_tunnel("127.0.0.1", port, &TlsTrust::PinnedCertificate(cert_path));
+
+        assert_eq!(result.err().unwrap().kind(), ErrorKind::ConnectionRefused);
+    }
+
+    #[test]
+    fn tunnel_refuses_a_self_signed_node_unless_its_certificate_is_pinned() {
+        let (cert, key) = make_identity("localhost");
+        let port = start_echo_server(cert, key);
+
+        let result = open_tunnel("localhost", port, &TlsTrust::SystemRoots);
+
+        assert_eq!(result.err().unwrap().kind(), ErrorKind::ConnectionRefused);
+    }
+
+    #[test]
+    fn pinned_certificate_must_be_pem() {
+        let path =
+            ensure_node_home_directory_exists("tls_tunnel", "pinned_certificate_must_be_pem")
+                .join("cert.pem");
+        fs::write(&path, "not a certificate").unwrap();
+
+        let result = make_connector(&TlsTrust::PinnedCertificate(path.clone()));
+
+        let error = result.err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        assert_eq!(
+            error
+                .to_string()
+                .starts_with(&format!("{} holds no PEM certificate", path.display())),
+            true
+        );
+    }
+}
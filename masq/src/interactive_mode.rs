@@ -7,6 +7,7 @@ use crate::non_interactive_mode::handle_command_common;
 use crate::schema::app;
 use crate::terminal::line_reader::TerminalEvent;
 use crate::terminal::terminal_interface::TerminalWrapper;
+use clap::App;
 use masq_lib::command::StdStreams;
 use masq_lib::short_writeln;
 use masq_lib::utils::ExpectValue;
@@ -68,6 +69,11 @@ fn handle_args(
                 return event;
             }
         }
+        [first, command] if first == "help" => {
+            let _lock = command_processor.terminal_wrapper_ref().lock();
+            write_command_help(app(), command, streams);
+            return InteractiveEvent::Continue;
+        }
         _ => (),
     }
     let _ = handle_command_common(command_factory, command_processor, args, streams.stderr);
@@ -110,6 +116,27 @@ fn handle_help_or_version(
     InteractiveEvent::Continue
 }
 
+fn write_command_help(app: App<'static, 'static>, command: &str, streams: &mut StdStreams<'_>) {
+    match app
+        .p
+        .subcommands
+        .into_iter()
+        .find(|subcommand| subcommand.p.meta.name == command)
+    {
+        Some(subcommand) => {
+            subcommand
+                .write_help(&mut streams.stdout)
+                .expect("masq help set incorrectly");
+            short_writeln!(streams.stdout, "");
+        }
+        None => short_writeln!(
+            streams.stderr,
+            "There is no command named '{}'; 'help' alone lists them all",
+            command
+        ),
+    }
+}
+
 fn pass_args_or_print_messages(
     streams: &mut StdStreams<'_>,
     read_line_result: TerminalEvent,
@@ -159,7 +186,7 @@ mod tests {
     use crate::command_factory::CommandFactoryError;
     use crate::interactive_mode::{
         go_interactive, handle_args, handle_help_or_version, handle_terminal_event,
-        pass_args_or_print_messages, InteractiveEvent,
+        pass_args_or_print_messages, write_command_help, InteractiveEvent,
     };
     use crate::terminal::line_reader::TerminalEvent;
     use crate::terminal::line_reader::TerminalEvent::{Break, Continue, Error};
@@ -167,12 +194,53 @@ mod tests {
     use crate::test_utils::mocks::{
         CommandFactoryMock, CommandProcessorMock, TerminalActiveMock, TerminalPassiveMock,
     };
+    use clap::{App, Arg, SubCommand};
     use crossbeam_channel::bounded;
     use masq_lib::test_utils::fake_stream_holder::{ByteArrayWriter, FakeStreamHolder};
     use std::sync::{Arc, Mutex};
     use std::thread;
     use std::time::{Duration, Instant};
 
+    fn make_help_app() -> App<'static, 'static> {
+        App::new("masq")
+            .subcommand(SubCommand::with_name("descriptor").about("Shows the descriptor"))
+            .subcommand(
+                SubCommand::with_name("shutdown")
+                    .about("Shuts down the Node")
+                    .arg(
+                        Arg::with_name("force")
+                            .long("force")
+                            .help("Without waiting"),
+                    ),
+            )
+    }
+
+    #[test]
+    fn help_for_a_command_shows_just_that_command() {
+        let mut stream_holder = FakeStreamHolder::new();
+
+        write_command_help(make_help_app(), "shutdown", &mut stream_holder.streams());
+
+        let stdout = stream_holder.stdout.get_string();
+        assert_eq!(stdout.contains("Shuts down the Node"), true, "{}", stdout);
+        assert_eq!(stdout.contains("--force"), true, "{}", stdout);
+        assert_eq!(stdout.contains("Shows the descriptor"), false, "{}", stdout);
+        assert_eq!(stream_holder.stderr.get_string(), "");
+    }
+
+    #[test]
+    fn help_for_an_unknown_command_complains() {
+        let mut stream_holder = FakeStreamHolder::new();
+
+        write_command_help(make_help_app(), "booga", &mut stream_holder.streams());
+
+        assert_eq!(stream_holder.stdout.get_string(), "");
+        assert_eq!(
+            stream_holder.stderr.get_string(),
+            "There is no command named 'booga'; 'help' alone lists them all\n"
+        );
+    }
+
     #[test]
     fn interactive_mode_works_for_unrecognized_command() {
         let make_params_arc = Arc::new(Mutex::new(vec![]));
@@ -1,7 +1,11 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
+use crate::communications::connection_manager::UiEndpoint;
+use crate::communications::tls_tunnel::TlsTrust;
 use crate::schema::app;
 use clap::{value_t, ArgMatches};
+use masq_lib::utils::localhost;
+use std::path::PathBuf;
 
 #[allow(clippy::upper_case_acronyms)]
 pub trait NIClapFactory {
@@ -19,16 +23,32 @@ impl NIClapFactory for NIClapFactoryReal {
 }
 
 pub trait NonInteractiveClap {
-    fn non_interactive_initial_clap_operations(&self, args: &[String]) -> u16;
+    fn non_interactive_initial_clap_operations(&self, args: &[String]) -> UiEndpoint;
 }
 
 pub struct NonInteractiveClapReal;
 
 //partly tested by integration tests
 impl NonInteractiveClap for NonInteractiveClapReal {
-    fn non_interactive_initial_clap_operations(&self, args: &[String]) -> u16 {
+    fn non_interactive_initial_clap_operations(&self, args: &[String]) -> UiEndpoint {
         let matches = handle_help_or_version_if_required(args);
-        value_t!(matches, "ui-port", u16).expect("ui-port is not properly defaulted")
+        let tls_opt = if matches.is_present("tls") {
+            Some(match matches.value_of("tls-cert") {
+                Some(cert_file) => TlsTrust::PinnedCertificate(PathBuf::from(cert_file)),
+                None => TlsTrust::SystemRoots,
+            })
+        } else {
+            None
+        };
+        UiEndpoint {
+            host: matches
+                .value_of("ui-host")
+                .map(|host| host.to_string())
+                .unwrap_or_else(|| localhost().to_string()),
+            port: value_t!(matches, "ui-port", u16).expect("ui-port is not properly defaulted"),
+            tls_opt,
+            token_opt: matches.value_of("token").map(|token| token.to_string()),
+        }
     }
 }
 
@@ -51,7 +71,7 @@ mod tests {
                 .collect::<Vec<String>>(),
         );
 
-        assert_eq!(result, DEFAULT_UI_PORT)
+        assert_eq!(result, UiEndpoint::local(DEFAULT_UI_PORT))
     }
 
     #[test]
@@ -63,6 +83,59 @@ mod tests {
                 .collect::<Vec<String>>(),
         );
 
-        assert_eq!(result, 10000)
+        assert_eq!(result, UiEndpoint::local(10000))
+    }
+
+    #[test]
+    fn non_interactive_clap_real_describes_a_remote_node() {
+        let result = NonInteractiveClapReal.non_interactive_initial_clap_operations(
+            &vec![
+                "masq",
+                "--ui-host",
+                "node.example.com",
+                "--ui-port",
+                "5336",
+                "--tls",
+                "--tls-cert",
+                "ui_tls_cert.pem",
+                "--token",
+                "let-me-in",
+                "descriptor",
+            ]
+            .iter()
+            .map(to_string)
+            .collect::<Vec<String>>(),
+        );
+
+        assert_eq!(
+            result,
+            UiEndpoint {
+                host: "node.example.com".to_string(),
+                port: 5336,
+                tls_opt: Some(TlsTrust::PinnedCertificate(PathBuf::from(
+                    "ui_tls_cert.pem"
+                ))),
+                token_opt: Some("let-me-in".to_string()),
+            }
+        )
+    }
+
+    #[test]
+    fn non_interactive_clap_real_trusts_system_roots_without_a_pinned_certificate() {
+        let result = NonInteractiveClapReal.non_interactive_initial_clap_operations(
+            &vec![
+                "masq",
+                "--ui-host",
+                "node.example.com",
+                "--tls",
+                "descriptor",
+            ]
+            .iter()
+            .map(to_string)
+            .collect::<Vec<String>>(),
+        );
+
+        assert_eq!(result.tls_opt, Some(TlsTrust::SystemRoots));
+        assert_eq!(result.token_opt, None);
     }
 }
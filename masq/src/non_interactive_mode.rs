@@ -11,11 +11,15 @@ use crate::communications::broadcast_handler::{
 };
 use crate::interactive_mode::go_interactive;
 use crate::non_interactive_clap::{NIClapFactory, NIClapFactoryReal};
+use crate::terminal::line_reader::split_quoted_line;
 use crate::terminal::terminal_interface::TerminalWrapper;
 use masq_lib::command::{Command, StdStreams};
 use masq_lib::short_writeln;
+use std::fs;
 use std::io::Write;
-use std::ops::Not;
+
+// Options of masq itself, ahead of any subcommand, that take no value
+const VALUELESS_OPTIONS: [&str; 1] = ["--tls"];
 
 pub struct Main {
     non_interactive_clap_factory: Box<dyn NIClapFactory>,
@@ -38,24 +42,42 @@ impl Main {
         }
     }
 
+    // The subcommand is the first argument that is neither an option nor an option's value
     fn extract_subcommand(args: &[String]) -> Option<Vec<String>> {
-        let original_args = args.iter();
-        let one_item_shifted_forth = args.iter().skip(1);
-        original_args
-            .zip(one_item_shifted_forth)
-            .enumerate()
-            .find(|(_index, (left, right))| Self::both_do_not_start_with_two_dashes(left, right))
-            .map(|(index, _)| args.iter().skip(index + 1).cloned().collect())
+        let mut index = 1;
+        while let Some(arg) = args.get(index) {
+            if !arg.starts_with("--") {
+                return Some(args[index..].to_vec());
+            }
+            index += if arg.contains('=') || VALUELESS_OPTIONS.contains(&arg.as_str()) {
+                1
+            } else {
+                2
+            };
+        }
+        None
     }
 
-    fn both_do_not_start_with_two_dashes(
-        one_program_arg: &&String,
-        program_arg_next_to_the_previous: &&String,
-    ) -> bool {
-        [one_program_arg, program_arg_next_to_the_previous]
-            .iter()
-            .any(|arg| arg.starts_with("--"))
-            .not()
+    // Like the subcommand, --script has to come ahead of it to belong to masq
+    fn extract_script(args: &[String]) -> Option<String> {
+        let mut index = 1;
+        while let Some(arg) = args.get(index) {
+            if !arg.starts_with("--") {
+                return None;
+            }
+            if arg == "--script" {
+                return args.get(index + 1).cloned();
+            }
+            if let Some(script) = arg.strip_prefix("--script=") {
+                return Some(script.to_string());
+            }
+            index += if arg.contains('=') || VALUELESS_OPTIONS.contains(&arg.as_str()) {
+                1
+            } else {
+                2
+            };
+        }
+        None
     }
 
     fn populate_non_interactive_dependencies() -> (Box<dyn BroadcastHandle>, Option<TerminalWrapper>)
@@ -81,14 +103,31 @@ impl Main {
 
 impl Command<u8> for Main {
     fn go(&mut self, streams: &mut StdStreams<'_>, args: &[String]) -> u8 {
-        let ui_port = self
+        let endpoint = self
             .non_interactive_clap_factory
             .make()
             .non_interactive_initial_clap_operations(args);
         let subcommand_opt = Self::extract_subcommand(args);
-        let (generic_broadcast_handle, terminal_interface) = match subcommand_opt {
-            Some(_) => Self::populate_non_interactive_dependencies(),
-            None => match Self::populate_interactive_dependencies(StreamFactoryReal) {
+        let script_opt = match (Self::extract_script(args), &subcommand_opt) {
+            (Some(_), Some(_)) => {
+                short_writeln!(
+                    streams.stderr,
+                    "A --script can't be run along with a command; put the command in the script"
+                );
+                return bool_into_numeric_code(false);
+            }
+            (Some(script), None) => match read_script(&script) {
+                Ok(lines) => Some(lines),
+                Err(e) => {
+                    short_writeln!(streams.stderr, "Can't read script {}: {}", script, e);
+                    return bool_into_numeric_code(false);
+                }
+            },
+            (None, _) => None,
+        };
+        let (generic_broadcast_handle, terminal_interface) = match (&subcommand_opt, &script_opt) {
+            (Some(_), _) | (_, Some(_)) => Self::populate_non_interactive_dependencies(),
+            (None, None) => match Self::populate_interactive_dependencies(StreamFactoryReal) {
                 Ok(tuple) => tuple,
                 Err(error) => {
                     short_writeln!(streams.stderr, "Pre-configuration error: {}", error);
@@ -99,7 +138,7 @@ impl Command<u8> for Main {
         let mut command_processor = match self.processor_factory.make(
             terminal_interface,
             generic_broadcast_handle,
-            ui_port,
+            endpoint,
         ) {
             Ok(processor) => processor,
             Err(error) => {
@@ -112,14 +151,22 @@ impl Command<u8> for Main {
             }
         };
 
-        let result = match subcommand_opt {
-            Some(command_parts) => handle_command_common(
+        let result = match (subcommand_opt, script_opt) {
+            (Some(command_parts), _) => handle_command_common(
                 &*self.command_factory,
                 &mut *command_processor,
                 &command_parts,
                 streams.stderr,
             ),
-            None => go_interactive(&*self.command_factory, &mut *command_processor, streams),
+            (None, Some(lines)) => run_script(
+                &*self.command_factory,
+                &mut *command_processor,
+                &lines,
+                streams.stderr,
+            ),
+            (None, None) => {
+                go_interactive(&*self.command_factory, &mut *command_processor, streams)
+            }
         };
         command_processor.close();
         bool_into_numeric_code(result)
@@ -134,6 +181,33 @@ fn bool_into_numeric_code(bool_flag: bool) -> u8 {
     }
 }
 
+// Numbered lines of the script that hold commands; blank lines and # comments are dropped
+fn read_script(script: &str) -> std::io::Result<Vec<(usize, String)>> {
+    Ok(fs::read_to_string(script)?
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim().to_string()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .collect())
+}
+
+// Runs commands one after another, like a shell script under 'set -e': the first failure ends it
+fn run_script(
+    command_factory: &dyn CommandFactory,
+    processor: &mut dyn CommandProcessor,
+    lines: &[(usize, String)],
+    stderr: &mut dyn Write,
+) -> bool {
+    lines.iter().all(|(line_number, line)| {
+        let command_parts = split_quoted_line(line.clone());
+        let success = handle_command_common(command_factory, processor, &command_parts, stderr);
+        if !success {
+            short_writeln!(stderr, "Script stopped at line {}: {}", line_number, line);
+        }
+        success
+    })
+}
+
 pub fn handle_command_common(
     command_factory: &dyn CommandFactory,
     processor: &mut dyn CommandProcessor,
@@ -168,6 +242,7 @@ mod tests {
     use crate::commands::commands_common::CommandError;
     use crate::commands::commands_common::CommandError::Transmission;
     use crate::commands::setup_command::SetupCommand;
+    use crate::communications::connection_manager::UiEndpoint;
     use crate::terminal::line_reader::TerminalEvent;
     use crate::test_utils::mocks::{
         CommandContextMock, CommandFactoryMock, CommandProcessorFactoryMock, CommandProcessorMock,
@@ -176,6 +251,7 @@ mod tests {
     use masq_lib::intentionally_blank;
     use masq_lib::messages::{ToMessageBody, UiNewPasswordBroadcast, UiShutdownRequest};
     use masq_lib::test_utils::fake_stream_holder::FakeStreamHolder;
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
     use masq_lib::utils::to_string;
     use std::any::Any;
     use std::sync::{Arc, Mutex};
@@ -234,8 +310,8 @@ mod tests {
             ]
         );
         let mut p_make_params = p_make_params_arc.lock().unwrap();
-        let (terminal_interface, broadcast_handle, ui_port) = p_make_params.pop().unwrap();
-        assert_eq!(ui_port, 5333);
+        let (terminal_interface, broadcast_handle, endpoint) = p_make_params.pop().unwrap();
+        assert_eq!(endpoint, UiEndpoint::local(5333));
         assert!(terminal_interface.is_none());
         assert!(broadcast_handle
             .as_any()
@@ -447,8 +523,8 @@ mod tests {
         let c_make_params = c_make_params_arc.lock().unwrap();
         assert_eq!(*c_make_params, vec![vec!["setup".to_string(),],]);
         let mut p_make_params = p_make_params_arc.lock().unwrap();
-        let (terminal_interface, broadcast_handle, ui_port) = p_make_params.pop().unwrap();
-        assert_eq!(ui_port, 10000);
+        let (terminal_interface, broadcast_handle, endpoint) = p_make_params.pop().unwrap();
+        assert_eq!(endpoint, UiEndpoint::local(10000));
         assert!(terminal_interface.is_none());
         assert!(broadcast_handle
             .as_any()
@@ -513,6 +589,185 @@ mod tests {
         )
     }
 
+    #[test]
+    fn extract_subcommands_skips_remote_connection_options() {
+        let args = vec![
+            "masq",
+            "--ui-host",
+            "node.example.com",
+            "--tls",
+            "--token=let-me-in",
+            "--tls-cert",
+            "cert.pem",
+            "descriptor",
+        ]
+        .iter()
+        .map(to_string)
+        .collect::<Vec<String>>();
+
+        let result = Main::extract_subcommand(&args);
+
+        assert_eq!(result, Some(vec!["descriptor".to_string()]))
+    }
+
+    #[test]
+    fn extract_subcommands_finds_no_subcommand_after_options_alone() {
+        let args = vec!["masq", "--tls", "--ui-port", "5336"]
+            .iter()
+            .map(to_string)
+            .collect::<Vec<String>>();
+
+        let result = Main::extract_subcommand(&args);
+
+        assert_eq!(result, None)
+    }
+
+    #[test]
+    fn extract_script_finds_the_script_among_masq_s_options() {
+        let args = |args: &[&str]| args.iter().map(to_string).collect::<Vec<String>>();
+
+        assert_eq!(
+            Main::extract_script(&args(&["masq", "--tls", "--script", "start.masq"])),
+            Some("start.masq".to_string())
+        );
+        assert_eq!(
+            Main::extract_script(&args(&["masq", "--ui-port", "5336", "--script=start.masq"])),
+            Some("start.masq".to_string())
+        );
+        assert_eq!(
+            Main::extract_script(&args(&["masq", "setup", "--script", "start.masq"])),
+            None
+        );
+        assert_eq!(Main::extract_script(&args(&["masq", "--tls"])), None);
+    }
+
+    #[test]
+    fn script_runs_its_commands_in_order_until_one_fails() {
+        let home_dir = ensure_node_home_directory_exists(
+            "non_interactive_mode",
+            "script_runs_its_commands_in_order_until_one_fails",
+        );
+        let script = home_dir.join("start.masq");
+        fs::write(
+            &script,
+            "# brings the Node up\n\nsetup --neighborhood-mode 'zero-hop'\n  start\n\
+             wait-for connection-status=RouteFound\nshutdown\n",
+        )
+        .unwrap();
+        let c_make_params_arc = Arc::new(Mutex::new(vec![]));
+        let command_factory = CommandFactoryMock::new()
+            .make_params(&c_make_params_arc)
+            .make_result(Ok(Box::new(FakeCommand::new("setup"))))
+            .make_result(Ok(Box::new(FakeCommand::new("start"))))
+            .make_result(Ok(Box::new(FakeCommand::new("wait-for"))));
+        let processor = CommandProcessorMock::new()
+            .process_result(Ok(()))
+            .process_result(Ok(()))
+            .process_result(Err(CommandError::Other("Gave up".to_string())));
+        let close_params_arc = Arc::new(Mutex::new(vec![]));
+        let processor = processor.close_params(&close_params_arc);
+        let p_make_params_arc = Arc::new(Mutex::new(vec![]));
+        let processor_factory = CommandProcessorFactoryMock::new()
+            .make_params(&p_make_params_arc)
+            .make_result(Ok(Box::new(processor)));
+        let mut subject = Main {
+            non_interactive_clap_factory: Box::new(NIClapFactoryMock {}),
+            command_factory: Box::new(command_factory),
+            processor_factory: Box::new(processor_factory),
+        };
+        let mut stream_holder = FakeStreamHolder::new();
+
+        let result = subject.go(
+            &mut stream_holder.streams(),
+            &[
+                "masq".to_string(),
+                "--script".to_string(),
+                script.to_str().unwrap().to_string(),
+            ],
+        );
+
+        assert_eq!(result, 1);
+        let c_make_params = c_make_params_arc.lock().unwrap();
+        assert_eq!(
+            *c_make_params,
+            vec![
+                vec![
+                    "setup".to_string(),
+                    "--neighborhood-mode".to_string(),
+                    "zero-hop".to_string()
+                ],
+                vec!["start".to_string()],
+                vec![
+                    "wait-for".to_string(),
+                    "connection-status=RouteFound".to_string()
+                ],
+            ]
+        );
+        let (terminal_interface, _, _) = p_make_params_arc.lock().unwrap().remove(0);
+        assert!(terminal_interface.is_none());
+        assert_eq!(
+            stream_holder.stderr.get_string(),
+            "Gave up\nScript stopped at line 5: wait-for connection-status=RouteFound\n"
+        );
+        assert_eq!(close_params_arc.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn script_and_command_together_are_refused() {
+        let mut subject = Main {
+            non_interactive_clap_factory: Box::new(NIClapFactoryMock {}),
+            command_factory: Box::new(CommandFactoryMock::new()),
+            processor_factory: Box::new(CommandProcessorFactoryMock::new()),
+        };
+        let mut stream_holder = FakeStreamHolder::new();
+
+        let result = subject.go(
+            &mut stream_holder.streams(),
+            &["masq", "--script", "start.masq", "shutdown"]
+                .iter()
+                .map(to_string)
+                .collect::<Vec<String>>(),
+        );
+
+        assert_eq!(result, 1);
+        assert_eq!(
+            stream_holder.stderr.get_string(),
+            "A --script can't be run along with a command; put the command in the script\n"
+        );
+    }
+
+    #[test]
+    fn unreadable_script_is_reported() {
+        let home_dir = ensure_node_home_directory_exists(
+            "non_interactive_mode",
+            "unreadable_script_is_reported",
+        );
+        let script = home_dir.join("missing.masq");
+        let mut subject = Main {
+            non_interactive_clap_factory: Box::new(NIClapFactoryMock {}),
+            command_factory: Box::new(CommandFactoryMock::new()),
+            processor_factory: Box::new(CommandProcessorFactoryMock::new()),
+        };
+        let mut stream_holder = FakeStreamHolder::new();
+
+        let result = subject.go(
+            &mut stream_holder.streams(),
+            &[
+                "masq".to_string(),
+                format!("--script={}", script.to_str().unwrap()),
+            ],
+        );
+
+        assert_eq!(result, 1);
+        let stderr = stream_holder.stderr.get_string();
+        assert_eq!(
+            stderr.starts_with(&format!("Can't read script {}: ", script.to_str().unwrap())),
+            true,
+            "{}",
+            stderr
+        );
+    }
+
     #[derive(Debug)]
     struct FakeCommand {
         output: String,
@@ -7,16 +7,24 @@ use crate::commands::check_password_command::check_password_subcommand;
 use crate::commands::configuration_command::configuration_subcommand;
 use crate::commands::connection_status_command::connection_status_subcommand;
 use crate::commands::crash_command::crash_subcommand;
+use crate::commands::database_backup_command::database_backup_subcommand;
 use crate::commands::descriptor_command::descriptor_subcommand;
+use crate::commands::export_wallet_command::export_wallet_subcommand;
 use crate::commands::financials_command::args_validation::financials_subcommand;
 use crate::commands::generate_wallets_command::generate_wallets_subcommand;
+use crate::commands::logs_command::logs_subcommand;
 use crate::commands::recover_wallets_command::recover_wallets_subcommand;
+use crate::commands::reload_config_command::reload_config_subcommand;
 use crate::commands::scan_command::scan_subcommand;
 use crate::commands::set_configuration_command::set_configuration_subcommand;
+use crate::commands::set_log_level_command::set_log_level_subcommand;
 use crate::commands::setup_command::setup_subcommand;
 use crate::commands::shutdown_command::shutdown_subcommand;
 use crate::commands::start_command::start_subcommand;
+use crate::commands::transfer_command::transfer_subcommand;
+use crate::commands::wait_for_command::wait_for_subcommand;
 use crate::commands::wallet_addresses_command::wallet_addresses_subcommand;
+use crate::commands::wallet_balances_command::wallet_balances_subcommand;
 use clap::{App, AppSettings, Arg};
 use lazy_static::lazy_static;
 use masq_lib::constants::{DEFAULT_UI_PORT, HIGHEST_USABLE_PORT, LOWEST_USABLE_INSECURE_PORT};
@@ -30,6 +38,22 @@ lazy_static! {
     static ref DEFAULT_UI_PORT_STRING: String = format!("{}", DEFAULT_UI_PORT);
 }
 
+const UI_HOST_HELP: &str =
+    "Host where the Node is listening for remote UIs, if it isn't on this machine. A remote Node \
+     is reached at its --ui-remote-port, which you give here as --ui-port.";
+const TLS_HELP: &str =
+    "Connect over TLS, as a Node's remote UI listener requires. Unless --tls-cert is given too, \
+     the Node's certificate must be signed by an authority this machine trusts.";
+const TLS_CERT_HELP: &str =
+    "Trust the Node only if it presents exactly this PEM certificate; for instance, the \
+     ui_tls_cert.pem a Node makes for itself in its data directory.";
+const TOKEN_HELP: &str =
+    "Token from the Node's --ui-access-file that grants this masq its access. Sent only over TLS.";
+const SCRIPT_HELP: &str =
+    "File of masq commands, one per line, to run one after another instead of starting interactive \
+     mode. Blank lines and lines starting with # are skipped. The first command that fails stops \
+     the script, and masq exits with a failure code.";
+
 const APP_NAME: &str = "masq";
 const APP_VERSION: &str = "1.0.0";
 const APP_AUTHOR: &str = "MASQ";
@@ -61,22 +85,66 @@ pub fn app() -> App<'static, 'static> {
                 .validator(validate_ui_port)
                 .help(UI_PORT_HELP.as_str()),
         )
+        .arg(
+            Arg::with_name("ui-host")
+                .long("ui-host")
+                .value_name("HOST")
+                .takes_value(true)
+                .help(UI_HOST_HELP),
+        )
+        .arg(
+            Arg::with_name("tls")
+                .long("tls")
+                .takes_value(false)
+                .help(TLS_HELP),
+        )
+        .arg(
+            Arg::with_name("tls-cert")
+                .long("tls-cert")
+                .value_name("CERT-FILE")
+                .takes_value(true)
+                .requires("tls")
+                .help(TLS_CERT_HELP),
+        )
+        .arg(
+            Arg::with_name("token")
+                .long("token")
+                .value_name("TOKEN")
+                .takes_value(true)
+                .requires("tls")
+                .help(TOKEN_HELP),
+        )
+        .arg(
+            Arg::with_name("script")
+                .long("script")
+                .value_name("FILE")
+                .takes_value(true)
+                .help(SCRIPT_HELP),
+        )
         .subcommand(change_password_subcommand())
         .subcommand(check_password_subcommand())
         .subcommand(crash_subcommand())
         .subcommand(configuration_subcommand())
         .subcommand(connection_status_subcommand())
+        .subcommand(database_backup_subcommand())
         .subcommand(descriptor_subcommand())
+        .subcommand(export_wallet_subcommand())
         .subcommand(financials_subcommand())
         .subcommand(generate_wallets_subcommand())
+        .subcommand(logs_subcommand())
         .subcommand(recover_wallets_subcommand())
+        .subcommand(reload_config_subcommand())
         .subcommand(scan_subcommand())
         .subcommand(set_configuration_subcommand())
+        .subcommand(set_log_level_subcommand())
         .subcommand(set_password_subcommand())
         .subcommand(setup_subcommand())
         .subcommand(shutdown_subcommand())
         .subcommand(start_subcommand())
+        .subcommand(transfer_subcommand())
+        .subcommand(wait_for_subcommand())
         .subcommand(wallet_addresses_subcommand())
+        .subcommand(wallet_balances_subcommand())
 }
 
 fn validate_ui_port(port: String) -> Result<(), String> {
@@ -112,5 +180,47 @@ mod tests {
             DEFAULT_UI_PORT_STRING.to_string(),
             format!("{}", DEFAULT_UI_PORT)
         );
+        assert_eq!(
+            UI_HOST_HELP,
+            "Host where the Node is listening for remote UIs, if it isn't on this machine. A \
+             remote Node is reached at its --ui-remote-port, which you give here as --ui-port."
+        );
+        assert_eq!(
+            TLS_HELP,
+            "Connect over TLS, as a Node's remote UI listener requires. Unless --tls-cert is given \
+             too, the Node's certificate must be signed by an authority this machine trusts."
+        );
+        assert_eq!(
+            TLS_CERT_HELP,
+            "Trust the Node only if it presents exactly this PEM certificate; for instance, the \
+             ui_tls_cert.pem a Node makes for itself in its data directory."
+        );
+        assert_eq!(
+            TOKEN_HELP,
+            "Token from the Node's --ui-access-file that grants this masq its access. Sent only \
+             over TLS."
+        );
+        assert_eq!(
+            SCRIPT_HELP,
+            "File of masq commands, one per line, to run one after another instead of starting \
+             interactive mode. Blank lines and lines starting with # are skipped. The first \
+             command that fails stops the script, and masq exits with a failure code."
+        );
+    }
+
+    #[test]
+    fn token_and_pinned_certificate_require_tls() {
+        let token_result = app().get_matches_from_safe(vec!["masq", "--token", "abc", "setup"]);
+        let cert_result =
+            app().get_matches_from_safe(vec!["masq", "--tls-cert", "cert.pem", "setup"]);
+
+        assert_eq!(
+            token_result.err().unwrap().kind,
+            clap::ErrorKind::MissingRequiredArgument
+        );
+        assert_eq!(
+            cert_result.err().unwrap().kind,
+            clap::ErrorKind::MissingRequiredArgument
+        );
     }
 }
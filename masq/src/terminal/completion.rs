@@ -0,0 +1,297 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::terminal::line_reader::split_quoted_line;
+use clap::App;
+use linefeed::complete::{Completer, Completion};
+use linefeed::prompter::Prompter;
+
+// Commands interactive mode handles itself, rather than handing them to the command factory
+const SPECIAL_COMMANDS: [&str; 3] = ["exit", "help", "version"];
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct OptionSpec {
+    long: String,
+    takes_value: bool,
+    values: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct CommandSpec {
+    name: String,
+    options: Vec<OptionSpec>,
+    positional_values: Vec<String>,
+}
+
+// Knows what can be typed where because it's built from the same clap schema that parses commands
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct CommandCompleter {
+    commands: Vec<CommandSpec>,
+}
+
+impl CommandCompleter {
+    pub fn from_app(app: &App) -> Self {
+        let mut commands: Vec<CommandSpec> = app.p.subcommands.iter().map(command_spec).collect();
+        SPECIAL_COMMANDS.iter().for_each(|name| {
+            commands.push(CommandSpec {
+                name: name.to_string(),
+                options: vec![],
+                positional_values: vec![],
+            })
+        });
+        commands.sort_by(|a, b| a.name.cmp(&b.name));
+        Self { commands }
+    }
+
+    // words_before: the complete words already typed; partial: the beginning of the one being typed
+    pub fn candidates(&self, words_before: &[String], partial: &str) -> Vec<String> {
+        let choices: Vec<&str> = match words_before.first() {
+            None => self.command_names(),
+            Some(first) if first == "help" && words_before.len() == 1 => self.command_names(),
+            Some(first) => match self.commands.iter().find(|spec| &spec.name == first) {
+                None => vec![],
+                Some(spec) => Self::choices_within(spec, &words_before[1..], partial),
+            },
+        };
+        choices
+            .into_iter()
+            .filter(|choice| choice.starts_with(partial))
+            .map(|choice| choice.to_string())
+            .collect()
+    }
+
+    fn command_names(&self) -> Vec<&str> {
+        self.commands
+            .iter()
+            .map(|spec| spec.name.as_str())
+            .collect()
+    }
+
+    fn choices_within<'a>(spec: &'a CommandSpec, args: &[String], partial: &str) -> Vec<&'a str> {
+        let pending_option_opt = args
+            .last()
+            .and_then(|last| spec.options.iter().find(|opt| &opt.long == last))
+            .filter(|opt| opt.takes_value);
+        match pending_option_opt {
+            Some(option) => option.values.iter().map(|v| v.as_str()).collect(),
+            None if partial.starts_with('-') => Self::option_names(spec),
+            None => spec
+                .positional_values
+                .iter()
+                .map(|v| v.as_str())
+                .chain(Self::option_names(spec))
+                .collect(),
+        }
+    }
+
+    fn option_names(spec: &CommandSpec) -> Vec<&str> {
+        spec.options.iter().map(|opt| opt.long.as_str()).collect()
+    }
+}
+
+impl<Term: linefeed::Terminal> Completer<Term> for CommandCompleter {
+    fn complete(
+        &self,
+        word: &str,
+        prompter: &Prompter<Term>,
+        start: usize,
+        _end: usize,
+    ) -> Option<Vec<Completion>> {
+        let words_before = split_quoted_line(prompter.buffer()[..start].to_string());
+        let completions: Vec<Completion> = self
+            .candidates(&words_before, word)
+            .into_iter()
+            .map(Completion::simple)
+            .collect();
+        Some(completions)
+    }
+}
+
+fn command_spec(subcommand: &App) -> CommandSpec {
+    let parser = &subcommand.p;
+    let valued_options = parser.opts.iter().filter_map(|opt| {
+        opt.s.long.map(|long| OptionSpec {
+            long: format!("--{}", long),
+            takes_value: true,
+            values: strings(&opt.v.possible_vals),
+        })
+    });
+    let flags = parser.flags.iter().filter_map(|flag| {
+        flag.s.long.map(|long| OptionSpec {
+            long: format!("--{}", long),
+            takes_value: false,
+            values: vec![],
+        })
+    });
+    let mut options: Vec<OptionSpec> = valued_options
+        .chain(flags)
+        .filter(|opt| opt.long != "--help" && opt.long != "--version")
+        .collect();
+    options.sort_by(|a, b| a.long.cmp(&b.long));
+    let positional_values = parser
+        .positionals
+        .values()
+        .flat_map(|pos| strings(&pos.v.possible_vals))
+        .collect();
+    CommandSpec {
+        name: parser.meta.name.clone(),
+        options,
+        positional_values,
+    }
+}
+
+fn strings(values_opt: &Option<Vec<&str>>) -> Vec<String> {
+    values_opt
+        .as_ref()
+        .map(|values| values.iter().map(|v| v.to_string()).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::scan_command::scan_subcommand;
+    use clap::{Arg, SubCommand};
+    use linefeed::memory::MemoryTerminal;
+    use linefeed::{Interface, ReadResult};
+    use std::sync::Arc;
+
+    fn make_subject() -> CommandCompleter {
+        let app = App::new("masq")
+            .subcommand(scan_subcommand())
+            .subcommand(
+                SubCommand::with_name("setup")
+                    .arg(
+                        Arg::with_name("chain")
+                            .long("chain")
+                            .takes_value(true)
+                            .possible_values(&["base-mainnet", "polygon-amoy", "polygon-mainnet"]),
+                    )
+                    .arg(
+                        Arg::with_name("neighborhood-mode")
+                            .long("neighborhood-mode")
+                            .takes_value(true)
+                            .possible_values(&["consume-only", "originate-only", "standard"]),
+                    )
+                    .arg(
+                        Arg::with_name("db-password")
+                            .long("db-password")
+                            .takes_value(true),
+                    )
+                    .arg(Arg::with_name("fake-public-key").long("fake-public-key")),
+            )
+            .subcommand(SubCommand::with_name("shutdown"));
+        CommandCompleter::from_app(&app)
+    }
+
+    fn words(words: &[&str]) -> Vec<String> {
+        words.iter().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(SPECIAL_COMMANDS, ["exit", "help", "version"]);
+    }
+
+    #[test]
+    fn first_word_completes_to_commands_including_the_special_ones() {
+        let subject = make_subject();
+
+        assert_eq!(
+            subject.candidates(&[], ""),
+            words(&["exit", "help", "scan", "setup", "shutdown", "version"])
+        );
+        assert_eq!(
+            subject.candidates(&[], "s"),
+            words(&["scan", "setup", "shutdown"])
+        );
+        assert_eq!(subject.candidates(&[], "se"), words(&["setup"]));
+        assert_eq!(subject.candidates(&[], "booga"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn help_completes_to_commands() {
+        let subject = make_subject();
+
+        let result = subject.candidates(&words(&["help"]), "sh");
+
+        assert_eq!(result, words(&["shutdown"]));
+    }
+
+    #[test]
+    fn dashes_complete_to_the_command_s_options() {
+        let subject = make_subject();
+
+        assert_eq!(
+            subject.candidates(&words(&["setup"]), "--"),
+            words(&[
+                "--chain",
+                "--db-password",
+                "--fake-public-key",
+                "--neighborhood-mode"
+            ])
+        );
+        assert_eq!(
+            subject.candidates(&words(&["setup", "--fake-public-key"]), "--n"),
+            words(&["--neighborhood-mode"])
+        );
+    }
+
+    #[test]
+    fn an_option_with_possible_values_completes_to_them() {
+        let subject = make_subject();
+
+        assert_eq!(
+            subject.candidates(&words(&["setup", "--chain"]), "polygon"),
+            words(&["polygon-amoy", "polygon-mainnet"])
+        );
+        assert_eq!(
+            subject.candidates(&words(&["setup", "--neighborhood-mode"]), ""),
+            words(&["consume-only", "originate-only", "standard"])
+        );
+        assert_eq!(
+            subject.candidates(&words(&["setup", "--db-password"]), ""),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn positional_possible_values_complete_along_with_options() {
+        let subject = make_subject();
+
+        assert_eq!(
+            subject.candidates(&words(&["scan"]), ""),
+            words(&["payables", "receivables", "pendingpayables", "feebumps"])
+        );
+        assert_eq!(
+            subject.candidates(&words(&["scan"]), "p"),
+            words(&["payables", "pendingpayables"])
+        );
+    }
+
+    #[test]
+    fn unknown_commands_complete_to_nothing() {
+        let subject = make_subject();
+
+        let result = subject.candidates(&words(&["booga"]), "");
+
+        assert_eq!(result, Vec::<String>::new());
+    }
+
+    #[test]
+    fn completer_works_from_the_line_being_edited() {
+        let terminal = MemoryTerminal::new();
+        let interface = Interface::with_term("test", terminal.clone()).unwrap();
+        interface.set_completer(Arc::new(make_subject()));
+        terminal.push_input("setup --chain 'base-mainnet' --neigh\tor\t\n");
+
+        let result = interface.read_line().unwrap();
+
+        match result {
+            ReadResult::Input(line) => assert_eq!(
+                line,
+                "setup --chain 'base-mainnet' --neighborhood-mode originate-only "
+            ),
+            _ => panic!("expected a line of input"),
+        }
+    }
+}
@@ -8,6 +8,7 @@ use masq_lib::short_writeln;
 use std::error::Error;
 use std::fmt::Debug;
 use std::io::Write;
+use std::path::PathBuf;
 
 //most of the events depend on the default linefeed signal handlers which ignore them unless you explicitly set the opposite
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -19,8 +20,33 @@ pub enum TerminalEvent {
     EoF,
 }
 
+//these take passwords, mnemonic phrases or keys, none of which may end up in the history file
+const SECRET_TAKING_COMMANDS: [&str; 12] = [
+    "change-password",
+    "set-password",
+    "check-password",
+    "configuration",
+    "wallet-addresses",
+    "wallet-balances",
+    "recover-wallets",
+    "generate-wallets",
+    "transfer",
+    "export-wallet",
+    "financials adjust",
+    "database-backup",
+];
+//and any other command line is kept out of it if it carries one of these in an option name
+const SECRET_OPTION_WORDS: [&str; 5] = [
+    "password",
+    "passphrase",
+    "mnemonic",
+    "private-key",
+    "consuming-key",
+];
+
 pub struct TerminalReal {
     interface: Box<dyn InterfaceWrapper>,
+    history_file_opt: Option<PathBuf>,
 }
 
 impl MasqTerminal for TerminalReal {
@@ -81,12 +107,22 @@ impl MasqTerminal for TerminalReal {
 
 impl TerminalReal {
     pub fn new(interface: Box<dyn InterfaceWrapper>) -> Self {
-        Self { interface }
+        Self {
+            interface,
+            history_file_opt: None,
+        }
+    }
+
+    pub fn with_history_file(mut self, history_file: PathBuf) -> Self {
+        self.history_file_opt = Some(history_file);
+        self
     }
 
     fn process_command_line(&self, line: String) -> TerminalEvent {
-        self.add_history(line.clone());
-        let args = split_quoted_line(line);
+        let args = split_quoted_line(line.clone());
+        if !carries_secrets(&args) {
+            self.add_history(line);
+        }
         TerminalEvent::CommandLine(args)
     }
 
@@ -100,7 +136,11 @@ impl TerminalReal {
     }
 
     fn add_history(&self, line: String) {
-        self.interface.add_history(line)
+        self.interface.add_history(line);
+        if let Some(history_file) = &self.history_file_opt {
+            //a history that can't be saved costs the user nothing in this session
+            let _ = self.interface.save_history(history_file);
+        }
     }
 
     fn dispatch_error_msg<E: Error>(error: E) -> TerminalEvent {
@@ -108,7 +148,18 @@ impl TerminalReal {
     }
 }
 
-fn split_quoted_line(input: String) -> Vec<String> {
+fn carries_secrets(args: &[String]) -> bool {
+    let is_secret_taking_command = SECRET_TAKING_COMMANDS.iter().any(|command| {
+        let words = command.split(' ').collect::<Vec<&str>>();
+        args.len() >= words.len() && args.iter().zip(words).all(|(arg, word)| arg == word)
+    });
+    is_secret_taking_command
+        || args.iter().any(|arg| {
+            arg.starts_with("--") && SECRET_OPTION_WORDS.iter().any(|word| arg.contains(word))
+        })
+}
+
+pub(crate) fn split_quoted_line(input: String) -> Vec<String> {
     let mut active_single = false;
     let mut active_double = false;
     let mut pieces: Vec<String> = vec![];
@@ -194,6 +245,102 @@ mod tests {
         )
     }
 
+    #[test]
+    fn read_line_saves_the_history_after_each_command_line_if_there_is_a_history_file() {
+        let add_history_unique_params_arc = Arc::new(Mutex::new(vec![]));
+        let save_history_params_arc = Arc::new(Mutex::new(vec![]));
+        let history_file = PathBuf::from("/home/booga/masq_history");
+        let subject = TerminalReal::new(Box::new(
+            InterfaceRawMock::new()
+                .read_line_result(Ok(ReadResult::Input("descriptor".to_string())))
+                .read_line_result(Ok(ReadResult::Input("shutdown".to_string())))
+                .add_history_unique_params(&add_history_unique_params_arc)
+                .save_history_params(&save_history_params_arc)
+                .save_history_result(Err(std::io::Error::from(ErrorKind::PermissionDenied)))
+                .save_history_result(Ok(())),
+        ))
+        .with_history_file(history_file.clone());
+
+        let first_result = subject.read_line();
+        let second_result = subject.read_line();
+
+        assert_eq!(
+            first_result,
+            TerminalEvent::CommandLine(vec!["descriptor".to_string()])
+        );
+        assert_eq!(
+            second_result,
+            TerminalEvent::CommandLine(vec!["shutdown".to_string()])
+        );
+        let add_history_unique_params = add_history_unique_params_arc.lock().unwrap();
+        assert_eq!(
+            *add_history_unique_params,
+            vec!["descriptor".to_string(), "shutdown".to_string()]
+        );
+        let save_history_params = save_history_params_arc.lock().unwrap();
+        assert_eq!(
+            *save_history_params,
+            vec![history_file.clone(), history_file]
+        )
+    }
+
+    #[test]
+    fn read_line_keeps_command_lines_that_carry_secrets_out_of_the_history() {
+        let add_history_unique_params_arc = Arc::new(Mutex::new(vec![]));
+        let save_history_params_arc = Arc::new(Mutex::new(vec![]));
+        let secret_lines = vec![
+            "change-password oldPassword newPassword",
+            "set-password booga",
+            "check-password booga",
+            "configuration booga",
+            "wallet-addresses booga",
+            "wallet-balances booga",
+            "recover-wallets --db-password booga --mnemonic-phrase \"one two three\"",
+            "generate-wallets --db-password booga",
+            "transfer booga 0x0123456789012345678901234567890123456789 1",
+            "export-wallet booga --passphrase wooga",
+            "financials adjust booga --wallet 0x01 --payable --change 5",
+            "database-backup /tmp/backup booga",
+            "setup --db-password booga",
+            "setup --consuming-private-key 0123456789ABCDEF",
+        ];
+        let mut interface = InterfaceRawMock::new()
+            .add_history_unique_params(&add_history_unique_params_arc)
+            .save_history_params(&save_history_params_arc)
+            .save_history_result(Ok(()))
+            .save_history_result(Ok(()));
+        for line in secret_lines.iter() {
+            interface = interface.read_line_result(Ok(ReadResult::Input(line.to_string())));
+        }
+        interface = interface
+            .read_line_result(Ok(ReadResult::Input("financials history".to_string())))
+            .read_line_result(Ok(ReadResult::Input("setup --ip 1.2.3.4".to_string())));
+        let history_file = PathBuf::from("/home/booga/masq_history");
+        let subject = TerminalReal::new(Box::new(interface)).with_history_file(history_file);
+
+        let results = (0..secret_lines.len() + 2)
+            .map(|_| subject.read_line())
+            .collect::<Vec<TerminalEvent>>();
+
+        assert_eq!(
+            results[0],
+            TerminalEvent::CommandLine(vec![
+                "change-password".to_string(),
+                "oldPassword".to_string(),
+                "newPassword".to_string()
+            ])
+        );
+        let add_history_unique_params = add_history_unique_params_arc.lock().unwrap();
+        assert_eq!(
+            *add_history_unique_params,
+            vec![
+                "financials history".to_string(),
+                "setup --ip 1.2.3.4".to_string()
+            ]
+        );
+        assert_eq!(save_history_params_arc.lock().unwrap().len(), 2);
+    }
+
     #[test]
     fn read_line_works_when_signal_quit_is_hit() {
         let subject = TerminalReal::new(Box::new(
@@ -1,5 +1,6 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
+pub mod completion;
 pub mod integration_test_utils;
 pub mod line_reader;
 pub mod secondary_infrastructure;
@@ -1,8 +1,11 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
+use crate::terminal::completion::CommandCompleter;
 use crate::terminal::line_reader::TerminalEvent;
 use linefeed::{Interface, ReadResult, Signal, Writer};
 use masq_lib::command::StdStreams;
+use std::path::Path;
+use std::sync::Arc;
 
 #[cfg(test)]
 mod test_cfg {
@@ -56,6 +59,9 @@ pub trait InterfaceWrapper: Send + Sync {
     fn set_buffer(&self, text: &str) -> std::io::Result<()>;
     fn set_prompt(&self, prompt: &str) -> std::io::Result<()>;
     fn set_report_signal(&self, signal: Signal, set: bool);
+    fn set_completer(&self, completer: Arc<CommandCompleter>);
+    fn load_history(&self, path: &Path) -> std::io::Result<()>;
+    fn save_history(&self, path: &Path) -> std::io::Result<()>;
 }
 
 impl<U: linefeed::Terminal> InterfaceWrapper for Interface<U> {
@@ -91,6 +97,18 @@ impl<U: linefeed::Terminal> InterfaceWrapper for Interface<U> {
     fn set_report_signal(&self, signal: Signal, set: bool) {
         self.set_report_signal(signal, set)
     }
+
+    fn set_completer(&self, completer: Arc<CommandCompleter>) {
+        self.set_completer(completer);
+    }
+
+    fn load_history(&self, path: &Path) -> std::io::Result<()> {
+        self.load_history(path)
+    }
+
+    fn save_history(&self, path: &Path) -> std::io::Result<()> {
+        self.save_history(path)
+    }
 }
 
 pub trait ChainedConstructors {
@@ -1,5 +1,6 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
+use crate::terminal::completion::CommandCompleter;
 use crate::terminal::line_reader::{TerminalEvent, TerminalReal};
 use crate::terminal::secondary_infrastructure::{
     ChainedConstructors, InterfaceWrapper, MasqTerminal, WriterLock,
@@ -7,13 +8,16 @@ use crate::terminal::secondary_infrastructure::{
 use linefeed::{Interface, Signal};
 use masq_lib::command::StdStreams;
 use masq_lib::constants::MASQ_PROMPT;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 #[cfg(not(test))]
 mod prod_cfg {
+    pub use crate::schema::app;
     pub use crate::terminal::integration_test_utils::{
         IntegrationTestTerminal, MASQ_TEST_INTEGRATION_KEY, MASQ_TEST_INTEGRATION_VALUE,
     };
+    pub use dirs::data_local_dir;
     pub use linefeed::DefaultTerminal;
 }
 
@@ -23,6 +27,15 @@ mod test_cfg {
     pub use masq_lib::intentionally_blank;
 }
 
+pub const HISTORY_FILE_NAME: &str = "masq_history";
+
+//what the interface needs to know beyond the terminal itself
+#[derive(Default)]
+pub struct TerminalSettings {
+    pub completer: Arc<CommandCompleter>,
+    pub history_file_opt: Option<PathBuf>,
+}
+
 //Unlike the linefeed library is designed to be used, I stick with using the system stdout handles for writing into them instead of the custom handles provided from linefeed.
 //I take benefits from linefeed's synchronization abilities, and other handy stuff it offers, while the implementation stays simpler than if I'd had to
 //distribute the nonstandard, custom handles over a lot of places in our code.
@@ -69,12 +82,18 @@ impl TerminalWrapper {
             )))
         } else {
             //we have no positive test aimed at this (only negative and as an integration test)
-            Self::configure_interface_generic(Box::new(prod_cfg::DefaultTerminal::new))
+            let settings = TerminalSettings {
+                completer: Arc::new(CommandCompleter::from_app(&prod_cfg::app())),
+                history_file_opt: prod_cfg::data_local_dir()
+                    .map(|dir| dir.join("MASQ").join(HISTORY_FILE_NAME)),
+            };
+            Self::configure_interface_generic(Box::new(prod_cfg::DefaultTerminal::new), settings)
         }
     }
 
     fn configure_interface_generic<F, TerminalType>(
         terminal_creator_of_certain_type: Box<F>,
+        settings: TerminalSettings,
     ) -> Result<Self, String>
     where
         F: FnOnce() -> std::io::Result<TerminalType>,
@@ -83,6 +102,7 @@ impl TerminalWrapper {
         Ok(Self::new(Arc::new(interface_configurator(
             terminal_creator_of_certain_type,
             Box::new(Interface::with_term),
+            settings,
         )?)))
     }
 }
@@ -93,6 +113,7 @@ impl TerminalWrapper {
 fn interface_configurator<Term, Itf, TermConstructor, ItfConstructor>(
     construct_typed_terminal: Box<TermConstructor>,
     construct_interface: Box<ItfConstructor>,
+    settings: TerminalSettings,
 ) -> Result<TerminalReal, String>
 where
     TermConstructor: FnOnce() -> std::io::Result<Term>,
@@ -106,12 +127,45 @@ where
         .map(Box::new)
         .map_err(|e| format!("Preparing terminal interface: {}", e))?;
 
-    set_all_settable_parameters(interface.as_mut())?;
+    set_all_settable_parameters(interface.as_mut(), settings.completer)?;
+
+    match settings.history_file_opt {
+        Some(history_file) => {
+            //a missing or unreadable history just means starting with an empty one
+            if let Some(dir) = history_file.parent() {
+                let _ = std::fs::create_dir_all(dir);
+            }
+            let _ = restrict_history_file(&history_file);
+            let _ = interface.load_history(&history_file);
+            Ok(TerminalReal::new(interface).with_history_file(history_file))
+        }
+        None => Ok(TerminalReal::new(interface)),
+    }
+}
 
-    Ok(TerminalReal::new(interface))
+//the history is nobody's business but its user's; linefeed keeps the permissions of the file it rewrites
+fn restrict_history_file(history_file: &Path) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        let file = std::fs::OpenOptions::new()
+            .append(true)
+            .create(true)
+            .mode(0o600)
+            .open(history_file)?;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = history_file;
+        Ok(())
+    }
 }
 
-fn set_all_settable_parameters<I>(interface: &mut I) -> Result<(), String>
+fn set_all_settable_parameters<I>(
+    interface: &mut I,
+    completer: Arc<CommandCompleter>,
+) -> Result<(), String>
 where
     I: InterfaceWrapper + ?Sized,
 {
@@ -122,8 +176,7 @@ where
     //according to linefeed's docs we await no failure here
     interface.set_report_signal(Signal::Interrupt, true);
 
-    //here we can add another parameter to be configured,
-    //such as "completer" (see linefeed library)
+    interface.set_completer(completer);
 
     Ok(())
 }
@@ -131,7 +184,10 @@ where
 #[cfg(test)]
 impl TerminalWrapper {
     pub fn configure_interface() -> Result<Self, String> {
-        Self::configure_interface_generic(Box::new(Self::result_wrapper_for_in_memory_terminal))
+        Self::configure_interface_generic(
+            Box::new(Self::result_wrapper_for_in_memory_terminal),
+            TerminalSettings::default(),
+        )
     }
 
     pub fn result_wrapper_for_in_memory_terminal() -> std::io::Result<test_cfg::MemoryTerminal> {
@@ -144,7 +200,9 @@ mod tests {
     use super::*;
     use crate::test_utils::mocks::{InterfaceRawMock, StdoutBlender, TerminalActiveMock};
     use crossbeam_channel::unbounded;
+    use linefeed::memory::MemoryTerminal;
     use linefeed::DefaultTerminal;
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
     use std::io::{Error, Write};
     use std::sync::{Barrier, Mutex};
     use std::thread;
@@ -244,6 +302,7 @@ mod tests {
         let result = interface_configurator(
             Box::new(constructor_of_default_terminal_resulting_in_immediate_error),
             Box::new(Interface::with_term),
+            TerminalSettings::default(),
         );
 
         let err_message = if let Err(e) = result {
@@ -272,8 +331,11 @@ mod tests {
         let terminal_type =
             move || -> std::io::Result<test_cfg::MemoryTerminal> { Ok(term_mock_clone) };
 
-        let result =
-            interface_configurator(Box::new(terminal_type), Box::new(Interface::with_term));
+        let result = interface_configurator(
+            Box::new(terminal_type),
+            Box::new(Interface::with_term),
+            TerminalSettings::default(),
+        );
 
         assert!(result.is_ok())
     }
@@ -283,6 +345,7 @@ mod tests {
         let result = interface_configurator(
             Box::new(TerminalWrapper::result_wrapper_for_in_memory_terminal),
             Box::new(constructor_of_interface_raw_resulting_in_early_error),
+            TerminalSettings::default(),
         );
 
         let err_message = if let Err(e) = result {
@@ -317,6 +380,7 @@ mod tests {
                     .set_prompt_params(&set_prompt_params_arc)
                     .set_prompt_result(Err(Error::from_raw_os_error(10))))
             }),
+            TerminalSettings::default(),
         );
 
         let err_message = if let Err(e) = result {
@@ -343,10 +407,128 @@ mod tests {
                     .set_report_signal_params(&set_report_signal_arc)
                     .set_prompt_result(Ok(())))
             }),
+            TerminalSettings::default(),
         );
 
         assert!(result.is_ok());
         let set_report_signal = set_report_signal_arc.lock().unwrap();
         assert_eq!(*set_report_signal, vec![(Signal::Interrupt, true)])
     }
+
+    #[test]
+    fn configure_interface_installs_the_completer_and_the_history() {
+        let home_dir = ensure_node_home_directory_exists(
+            "terminal_interface",
+            "configure_interface_installs_the_completer_and_the_history",
+        );
+        let history_file = home_dir.join("MASQ").join(HISTORY_FILE_NAME);
+        let set_completer_params_arc = Arc::new(Mutex::new(vec![]));
+        let load_history_params_arc = Arc::new(Mutex::new(vec![]));
+        let completer = Arc::new(CommandCompleter::default());
+        let settings = TerminalSettings {
+            completer: completer.clone(),
+            history_file_opt: Some(history_file.clone()),
+        };
+
+        let result = interface_configurator(
+            Box::new(TerminalWrapper::result_wrapper_for_in_memory_terminal),
+            Box::new(|_name, _terminal| {
+                Ok(InterfaceRawMock::new()
+                    .set_prompt_result(Ok(()))
+                    .set_completer_params(&set_completer_params_arc)
+                    .load_history_params(&load_history_params_arc)
+                    .load_history_result(Err(Error::from(std::io::ErrorKind::NotFound))))
+            }),
+            settings,
+        );
+
+        assert!(result.is_ok());
+        let set_completer_params = set_completer_params_arc.lock().unwrap();
+        assert_eq!(set_completer_params.len(), 1);
+        assert!(Arc::ptr_eq(&set_completer_params[0], &completer));
+        let load_history_params = load_history_params_arc.lock().unwrap();
+        assert_eq!(*load_history_params, vec![history_file]);
+        assert!(home_dir.join("MASQ").is_dir());
+    }
+
+    #[test]
+    fn history_written_by_one_session_is_there_for_the_next() {
+        let home_dir = ensure_node_home_directory_exists(
+            "terminal_interface",
+            "history_written_by_one_session_is_there_for_the_next",
+        );
+        let history_file = home_dir.join(HISTORY_FILE_NAME);
+        let make_session = |terminal: MemoryTerminal| {
+            interface_configurator(
+                Box::new(move || Ok(terminal)),
+                Box::new(Interface::with_term),
+                TerminalSettings {
+                    completer: Arc::new(CommandCompleter::default()),
+                    history_file_opt: Some(history_file.clone()),
+                },
+            )
+            .unwrap()
+        };
+        let first_terminal = MemoryTerminal::new();
+        first_terminal.push_input("descriptor\n");
+        let first_session = make_session(first_terminal);
+        let _ = first_session.read_line();
+        let second_terminal = MemoryTerminal::new();
+        //the up arrow recalls the last line
+        second_terminal.push_input("\x1b[A\n");
+        let second_session = make_session(second_terminal);
+
+        let result = second_session.read_line();
+
+        assert_eq!(
+            result,
+            TerminalEvent::CommandLine(vec!["descriptor".to_string()])
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn history_file_is_readable_and_writable_only_by_its_owner() {
+        use std::os::unix::fs::PermissionsExt;
+        let home_dir = ensure_node_home_directory_exists(
+            "terminal_interface",
+            "history_file_is_readable_and_writable_only_by_its_owner",
+        );
+        let new_history_file = home_dir.join("new").join(HISTORY_FILE_NAME);
+        let old_history_file = home_dir.join(HISTORY_FILE_NAME);
+        std::fs::write(&old_history_file, "descriptor\n").unwrap();
+        std::fs::set_permissions(&old_history_file, std::fs::Permissions::from_mode(0o644))
+            .unwrap();
+        let make_session = |history_file: &PathBuf| {
+            let terminal = MemoryTerminal::new();
+            terminal.push_input("shutdown\n");
+            let session = interface_configurator(
+                Box::new(move || Ok(terminal)),
+                Box::new(Interface::with_term),
+                TerminalSettings {
+                    completer: Arc::new(CommandCompleter::default()),
+                    history_file_opt: Some(history_file.clone()),
+                },
+            )
+            .unwrap();
+            let _ = session.read_line();
+        };
+
+        make_session(&new_history_file);
+        make_session(&old_history_file);
+
+        let mode_of =
+            |path: &PathBuf| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode_of(&new_history_file), 0o600);
+        assert_eq!(mode_of(&old_history_file), 0o600);
+        assert_eq!(
+            std::fs::read_to_string(&old_history_file).unwrap(),
+            "descriptor\nshutdown\n"
+        );
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(HISTORY_FILE_NAME, "masq_history");
+    }
 }
@@ -6,7 +6,9 @@ use crate::command_processor::{CommandProcessor, CommandProcessorFactory};
 use crate::commands::commands_common::CommandError::Transmission;
 use crate::commands::commands_common::{Command, CommandError};
 use crate::communications::broadcast_handler::{BroadcastHandle, StreamFactory};
+use crate::communications::connection_manager::UiEndpoint;
 use crate::non_interactive_clap::{NIClapFactory, NonInteractiveClap};
+use crate::terminal::completion::CommandCompleter;
 use crate::terminal::line_reader::TerminalEvent;
 use crate::terminal::secondary_infrastructure::{InterfaceWrapper, MasqTerminal, WriterLock};
 use crate::terminal::terminal_interface::TerminalWrapper;
@@ -20,6 +22,7 @@ use masq_lib::ui_gateway::MessageBody;
 use std::cell::RefCell;
 use std::fmt::Arguments;
 use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use std::{io, thread};
@@ -222,7 +225,15 @@ impl CommandProcessorMock {
 
 #[derive(Default)]
 pub struct CommandProcessorFactoryMock {
-    make_params: Arc<Mutex<Vec<(Option<TerminalWrapper>, Box<dyn BroadcastHandle>, u16)>>>,
+    make_params: Arc<
+        Mutex<
+            Vec<(
+                Option<TerminalWrapper>,
+                Box<dyn BroadcastHandle>,
+                UiEndpoint,
+            )>,
+        >,
+    >,
     make_results: RefCell<Vec<Result<Box<dyn CommandProcessor>, CommandError>>>,
 }
 
@@ -231,12 +242,12 @@ impl CommandProcessorFactory for CommandProcessorFactoryMock {
         &self,
         terminal_interface: Option<TerminalWrapper>,
         generic_broadcast_handle: Box<dyn BroadcastHandle>,
-        ui_port: u16,
+        endpoint: UiEndpoint,
     ) -> Result<Box<dyn CommandProcessor>, CommandError> {
         self.make_params.lock().unwrap().push((
             terminal_interface,
             generic_broadcast_handle,
-            ui_port,
+            endpoint,
         ));
         self.make_results.borrow_mut().remove(0)
     }
@@ -249,7 +260,15 @@ impl CommandProcessorFactoryMock {
 
     pub fn make_params(
         mut self,
-        params: &Arc<Mutex<Vec<(Option<TerminalWrapper>, Box<dyn BroadcastHandle>, u16)>>>,
+        params: &Arc<
+            Mutex<
+                Vec<(
+                    Option<TerminalWrapper>,
+                    Box<dyn BroadcastHandle>,
+                    UiEndpoint,
+                )>,
+            >,
+        >,
     ) -> Self {
         self.make_params = params.clone();
         self
@@ -272,8 +291,8 @@ impl NIClapFactory for NIClapFactoryMock {
 pub struct NonInteractiveClapMock;
 
 impl NonInteractiveClap for NonInteractiveClapMock {
-    fn non_interactive_initial_clap_operations(&self, _args: &[String]) -> u16 {
-        DEFAULT_UI_PORT
+    fn non_interactive_initial_clap_operations(&self, _args: &[String]) -> UiEndpoint {
+        UiEndpoint::local(DEFAULT_UI_PORT)
     }
 }
 
@@ -607,6 +626,11 @@ pub struct InterfaceRawMock {
     set_buffer_params: Arc<Mutex<Vec<String>>>,
     set_buffer_results: Arc<Mutex<Vec<std::io::Result<()>>>>,
     lock_writer_append_results: Arc<Mutex<Vec<std::io::Result<Box<WriterInactive>>>>>, //for testing the outer result not the structure when ok
+    set_completer_params: Arc<Mutex<Vec<Arc<CommandCompleter>>>>,
+    load_history_params: Arc<Mutex<Vec<PathBuf>>>,
+    load_history_results: Arc<Mutex<Vec<std::io::Result<()>>>>,
+    save_history_params: Arc<Mutex<Vec<PathBuf>>>,
+    save_history_results: Arc<Mutex<Vec<std::io::Result<()>>>>,
 }
 
 impl InterfaceWrapper for InterfaceRawMock {
@@ -655,6 +679,26 @@ impl InterfaceWrapper for InterfaceRawMock {
             .unwrap()
             .push((signal, set))
     }
+
+    fn set_completer(&self, completer: Arc<CommandCompleter>) {
+        self.set_completer_params.lock().unwrap().push(completer)
+    }
+
+    fn load_history(&self, path: &Path) -> io::Result<()> {
+        self.load_history_params
+            .lock()
+            .unwrap()
+            .push(path.to_path_buf());
+        self.load_history_results.lock().unwrap().remove(0)
+    }
+
+    fn save_history(&self, path: &Path) -> io::Result<()> {
+        self.save_history_params
+            .lock()
+            .unwrap()
+            .push(path.to_path_buf());
+        self.save_history_results.lock().unwrap().remove(0)
+    }
 }
 
 impl InterfaceRawMock {
@@ -669,6 +713,11 @@ impl InterfaceRawMock {
             set_buffer_params: Arc::new(Mutex::new(vec![])),
             set_buffer_results: Arc::new(Mutex::new(vec![])),
             lock_writer_append_results: Arc::new(Mutex::new(vec![])),
+            set_completer_params: Arc::new(Mutex::new(vec![])),
+            load_history_params: Arc::new(Mutex::new(vec![])),
+            load_history_results: Arc::new(Mutex::new(vec![])),
+            save_history_params: Arc::new(Mutex::new(vec![])),
+            save_history_results: Arc::new(Mutex::new(vec![])),
         }
     }
     pub fn read_line_result(self, result: std::io::Result<ReadResult>) -> Self {
@@ -709,4 +758,29 @@ impl InterfaceRawMock {
         self.lock_writer_append_results.lock().unwrap().push(result);
         self
     }
+
+    pub fn set_completer_params(mut self, params: &Arc<Mutex<Vec<Arc<CommandCompleter>>>>) -> Self {
+        self.set_completer_params = params.clone();
+        self
+    }
+
+    pub fn load_history_params(mut self, params: &Arc<Mutex<Vec<PathBuf>>>) -> Self {
+        self.load_history_params = params.clone();
+        self
+    }
+
+    pub fn load_history_result(self, result: std::io::Result<()>) -> Self {
+        self.load_history_results.lock().unwrap().push(result);
+        self
+    }
+
+    pub fn save_history_params(mut self, params: &Arc<Mutex<Vec<PathBuf>>>) -> Self {
+        self.save_history_params = params.clone();
+        self
+    }
+
+    pub fn save_history_result(self, result: std::io::Result<()>) -> Self {
+        self.save_history_results.lock().unwrap().push(result);
+        self
+    }
 }
@@ -19,6 +19,11 @@ pub const CHAINS: [BlockchainRecord; 7] = [
         literal_identifier: POLYGON_MAINNET_FULL_IDENTIFIER,
         contract: POLYGON_MAINNET_CONTRACT_ADDRESS,
         contract_creation_block: POLYGON_MAINNET_CONTRACT_CREATION_BLOCK,
+        confirmation_depth: 64,
+        gas_limit_const_part: 70_000,
+        native_transfer_gas_limit: 21_000,
+        disperse_contract_opt: None,
+        mainnet: true,
     },
     BlockchainRecord {
         self_id: Chain::EthMainnet,
@@ -26,6 +31,11 @@ pub const CHAINS: [BlockchainRecord; 7] = [
         literal_identifier: ETH_MAINNET_FULL_IDENTIFIER,
         contract: ETH_MAINNET_CONTRACT_ADDRESS,
         contract_creation_block: ETH_MAINNET_CONTRACT_CREATION_BLOCK,
+        confirmation_depth: 12,
+        gas_limit_const_part: 55_000,
+        native_transfer_gas_limit: 21_000,
+        disperse_contract_opt: None,
+        mainnet: true,
     },
     BlockchainRecord {
         self_id: Chain::BaseMainnet,
@@ -33,6 +43,11 @@ pub const CHAINS: [BlockchainRecord; 7] = [
         literal_identifier: BASE_MAINNET_FULL_IDENTIFIER,
         contract: BASE_MAINNET_CONTRACT_ADDRESS,
         contract_creation_block: BASE_MAINNET_CONTRACT_CREATION_BLOCK,
+        confirmation_depth: 10,
+        gas_limit_const_part: 70_000,
+        native_transfer_gas_limit: 21_000,
+        disperse_contract_opt: None,
+        mainnet: true,
     },
     BlockchainRecord {
         self_id: Chain::BaseSepolia,
@@ -40,6 +55,11 @@ pub const CHAINS: [BlockchainRecord; 7] = [
         literal_identifier: BASE_SEPOLIA_FULL_IDENTIFIER,
         contract: BASE_SEPOLIA_TESTNET_CONTRACT_ADDRESS,
         contract_creation_block: BASE_SEPOLIA_CONTRACT_CREATION_BLOCK,
+        confirmation_depth: 5,
+        gas_limit_const_part: 70_000,
+        native_transfer_gas_limit: 21_000,
+        disperse_contract_opt: None,
+        mainnet: false,
     },
     BlockchainRecord {
         self_id: Chain::PolyAmoy,
@@ -47,6 +67,11 @@ pub const CHAINS: [BlockchainRecord; 7] = [
         literal_identifier: POLYGON_AMOY_FULL_IDENTIFIER,
         contract: POLYGON_AMOY_TESTNET_CONTRACT_ADDRESS,
         contract_creation_block: POLYGON_AMOY_CONTRACT_CREATION_BLOCK,
+        confirmation_depth: 10,
+        gas_limit_const_part: 70_000,
+        native_transfer_gas_limit: 21_000,
+        disperse_contract_opt: None,
+        mainnet: false,
     },
     BlockchainRecord {
         self_id: Chain::EthRopsten,
@@ -54,6 +79,11 @@ pub const CHAINS: [BlockchainRecord; 7] = [
         literal_identifier: ETH_ROPSTEN_FULL_IDENTIFIER,
         contract: ETH_ROPSTEN_TESTNET_CONTRACT_ADDRESS,
         contract_creation_block: ETH_ROPSTEN_CONTRACT_CREATION_BLOCK,
+        confirmation_depth: 12,
+        gas_limit_const_part: 55_000,
+        native_transfer_gas_limit: 21_000,
+        disperse_contract_opt: None,
+        mainnet: false,
     },
     BlockchainRecord {
         self_id: Chain::Dev,
@@ -61,6 +91,11 @@ pub const CHAINS: [BlockchainRecord; 7] = [
         literal_identifier: DEV_CHAIN_FULL_IDENTIFIER,
         contract: MULTINODE_TESTNET_CONTRACT_ADDRESS,
         contract_creation_block: MULTINODE_TESTNET_CONTRACT_CREATION_BLOCK,
+        confirmation_depth: 0,
+        gas_limit_const_part: 55_000,
+        native_transfer_gas_limit: 21_000,
+        disperse_contract_opt: None,
+        mainnet: false,
     },
 ];
 
@@ -71,6 +106,16 @@ pub struct BlockchainRecord {
     pub literal_identifier: &'static str,
     pub contract: Address,
     pub contract_creation_block: u64,
+    // Number of blocks that must be built on top of the one carrying an inbound payment
+    // before the payment is trusted enough to be credited against the debtor
+    pub confirmation_depth: u64,
+    // Gas a payment through the contract costs before its data bytes are paid for
+    pub gas_limit_const_part: u64,
+    pub native_transfer_gas_limit: u64,
+    // A disperse-style contract that pays many creditors in one transaction; payables are
+    // sent one transaction per creditor where there's none
+    pub disperse_contract_opt: Option<Address>,
+    pub mainnet: bool,
 }
 
 const POLYGON_MAINNET_CONTRACT_ADDRESS: Address = H160([
@@ -196,6 +241,11 @@ mod tests {
                 literal_identifier: "eth-mainnet",
                 contract: ETH_MAINNET_CONTRACT_ADDRESS,
                 contract_creation_block: ETH_MAINNET_CONTRACT_CREATION_BLOCK,
+                confirmation_depth: 12,
+                gas_limit_const_part: 55_000,
+                native_transfer_gas_limit: 21_000,
+                disperse_contract_opt: None,
+                mainnet: true,
             }
         );
     }
@@ -212,6 +262,11 @@ mod tests {
                 literal_identifier: "eth-ropsten",
                 contract: ETH_ROPSTEN_TESTNET_CONTRACT_ADDRESS,
                 contract_creation_block: ETH_ROPSTEN_CONTRACT_CREATION_BLOCK,
+                confirmation_depth: 12,
+                gas_limit_const_part: 55_000,
+                native_transfer_gas_limit: 21_000,
+                disperse_contract_opt: None,
+                mainnet: false,
             }
         );
     }
@@ -228,6 +283,11 @@ mod tests {
                 literal_identifier: "polygon-mainnet",
                 contract: POLYGON_MAINNET_CONTRACT_ADDRESS,
                 contract_creation_block: POLYGON_MAINNET_CONTRACT_CREATION_BLOCK,
+                confirmation_depth: 64,
+                gas_limit_const_part: 70_000,
+                native_transfer_gas_limit: 21_000,
+                disperse_contract_opt: None,
+                mainnet: true,
             }
         );
     }
@@ -244,6 +304,11 @@ mod tests {
                 literal_identifier: "polygon-amoy",
                 contract: POLYGON_AMOY_TESTNET_CONTRACT_ADDRESS,
                 contract_creation_block: POLYGON_AMOY_CONTRACT_CREATION_BLOCK,
+                confirmation_depth: 10,
+                gas_limit_const_part: 70_000,
+                native_transfer_gas_limit: 21_000,
+                disperse_contract_opt: None,
+                mainnet: false,
             }
         );
     }
@@ -260,6 +325,11 @@ mod tests {
                 literal_identifier: "base-mainnet",
                 contract: BASE_MAINNET_CONTRACT_ADDRESS,
                 contract_creation_block: BASE_MAINNET_CONTRACT_CREATION_BLOCK,
+                confirmation_depth: 10,
+                gas_limit_const_part: 70_000,
+                native_transfer_gas_limit: 21_000,
+                disperse_contract_opt: None,
+                mainnet: true,
             }
         );
     }
@@ -276,6 +346,11 @@ mod tests {
                 literal_identifier: "base-sepolia",
                 contract: BASE_SEPOLIA_TESTNET_CONTRACT_ADDRESS,
                 contract_creation_block: BASE_SEPOLIA_CONTRACT_CREATION_BLOCK,
+                confirmation_depth: 5,
+                gas_limit_const_part: 70_000,
+                native_transfer_gas_limit: 21_000,
+                disperse_contract_opt: None,
+                mainnet: false,
             }
         );
     }
@@ -292,6 +367,11 @@ mod tests {
                 literal_identifier: "dev",
                 contract: MULTINODE_TESTNET_CONTRACT_ADDRESS,
                 contract_creation_block: MULTINODE_TESTNET_CONTRACT_CREATION_BLOCK,
+                confirmation_depth: 0,
+                gas_limit_const_part: 55_000,
+                native_transfer_gas_limit: 21_000,
+                disperse_contract_opt: None,
+                mainnet: false,
             }
         );
     }
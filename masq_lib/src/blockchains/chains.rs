@@ -1,6 +1,7 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
 use crate::blockchains::blockchain_records::{BlockchainRecord, CHAINS};
+use crate::blockchains::custom_chains::{custom_chain_record_opt, find_custom_record_opt};
 use crate::constants::{
     BASE_MAINNET_FULL_IDENTIFIER, BASE_SEPOLIA_FULL_IDENTIFIER, DEFAULT_CHAIN,
     DEV_CHAIN_FULL_IDENTIFIER, ETH_MAINNET_FULL_IDENTIFIER, ETH_ROPSTEN_FULL_IDENTIFIER,
@@ -17,6 +18,8 @@ pub enum Chain {
     BaseMainnet,
     BaseSepolia,
     Dev,
+    // Defined by the user in the chains file of the data directory; keyed by its chain ID
+    Custom(u64),
 }
 
 impl Default for Chain {
@@ -41,6 +44,8 @@ impl From<&str> for Chain {
             Chain::EthRopsten
         } else if str == DEV_CHAIN_FULL_IDENTIFIER {
             Chain::Dev
+        } else if let Some(chain) = chain_from_chain_identifier_opt(str) {
+            chain
         } else {
             panic!("Clap let in a wrong value for chain: '{}'; if this happens we need to track down the slit", str)
         }
@@ -49,21 +54,21 @@ impl From<&str> for Chain {
 
 impl Chain {
     pub fn rec(&self) -> &BlockchainRecord {
-        CHAINS
-            .iter()
-            .find(|b| &b.self_id == self)
+        self.rec_opt()
             .unwrap_or_else(|| panic!("BlockchainRecord for '{:?}' doesn't exist", self))
-        //untested panic - but works as an expect()
     }
 
+    // A custom chain nobody registered in this process can't be vouched for, so it doesn't
+    // pass for a mainnet
     pub fn is_mainnet(&self) -> bool {
-        Self::mainnets()
-            .iter()
-            .any(|mainnet_chain| mainnet_chain == self)
+        self.rec_opt().map(|record| record.mainnet).unwrap_or(false)
     }
 
-    fn mainnets() -> &'static [Chain] {
-        &[Chain::PolyMainnet, Chain::BaseMainnet, Chain::EthMainnet]
+    fn rec_opt(&self) -> Option<&'static BlockchainRecord> {
+        match self {
+            Chain::Custom(chain_id) => custom_chain_record_opt(*chain_id),
+            _ => CHAINS.iter().find(|b| &b.self_id == self),
+        }
     }
 }
 
@@ -75,7 +80,7 @@ pub fn chain_from_chain_identifier_opt(identifier: &str) -> Option<Chain> {
 fn return_record_opt_standard_impl(
     closure: &dyn Fn(&&BlockchainRecord) -> bool,
 ) -> Option<&BlockchainRecord> {
-    return_record_opt_body(closure, &CHAINS)
+    return_record_opt_body(closure, &CHAINS).or_else(|| find_custom_record_opt(closure))
 }
 
 fn return_record_opt_body<'a>(
@@ -143,6 +148,11 @@ mod tests {
             literal_identifier: "",
             contract: Default::default(),
             contract_creation_block: 0,
+            confirmation_depth: 0,
+            gas_limit_const_part: 0,
+            native_transfer_gas_limit: 0,
+            disperse_contract_opt: None,
+            mainnet: false,
         }
     }
 
@@ -0,0 +1,547 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::blockchains::blockchain_records::{BlockchainRecord, CHAINS};
+use crate::blockchains::chains::Chain;
+use ethereum_types::Address;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_derive::Deserialize;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::RwLock;
+
+// Lives in the data directory, next to config.toml
+pub const CUSTOM_CHAINS_FILE_NAME: &str = "chains.toml";
+
+lazy_static! {
+    // Records are leaked so that they can be handed out as &'static, just like the official
+    // ones. Nothing is ever taken out, and the file is read once or twice in a process, so
+    // the leak stays as small as the chains file.
+    static ref CUSTOM_CHAINS: RwLock<Vec<&'static BlockchainRecord>> = RwLock::new(vec![]);
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct CustomChainSpec {
+    pub chain_id: u64,
+    pub identifier: String,
+    pub contract_address: String,
+    pub contract_creation_block: u64,
+    pub confirmation_depth: u64,
+    pub gas_limit_const_part: u64,
+    pub native_transfer_gas_limit: u64,
+    #[serde(default)]
+    pub disperse_contract_address: Option<String>,
+    pub mainnet: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ChainsFile {
+    #[serde(default)]
+    chain: Vec<CustomChainSpec>,
+}
+
+// A missing chains file is no error: it just means there are no custom chains
+pub fn load_custom_chains(data_directory: &Path) -> Result<Vec<Chain>, String> {
+    let path = data_directory.join(CUSTOM_CHAINS_FILE_NAME);
+    if !path.is_file() {
+        return Ok(vec![]);
+    }
+    let contents = fs::read_to_string(&path)
+        .map_err(|e| format!("Couldn't read {}: {}", path.display(), e))?;
+    let specs = parse_custom_chains(&contents)
+        .map_err(|e| format!("Couldn't parse {}: {}", path.display(), e))?;
+    register_custom_chains(specs).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+pub fn parse_custom_chains(contents: &str) -> Result<Vec<CustomChainSpec>, String> {
+    toml::from_str::<ChainsFile>(contents)
+        .map(|file| file.chain)
+        .map_err(|e| e.to_string())
+}
+
+// Either all the specs are registered or none of them is. Registering a chain again with the
+// very same values is allowed, so a chains file can be read more than once in a process.
+pub fn register_custom_chains(specs: Vec<CustomChainSpec>) -> Result<Vec<Chain>, String> {
+    let mut registry = CUSTOM_CHAINS
+        .write()
+        .expect("Custom chain registry is poisoned");
+    let mut records: Vec<BlockchainRecord> = vec![];
+    for spec in specs {
+        let record = record_from_spec(spec, &registry)?;
+        check_against_official_chains(&record)?;
+        check_against_others(&record, records.iter())?;
+        if !registry.iter().any(|registered| **registered == record) {
+            check_against_others(&record, registry.iter().copied())?;
+        }
+        records.push(record);
+    }
+    Ok(records
+        .into_iter()
+        .map(|record| {
+            let chain = record.self_id;
+            if !registry.iter().any(|registered| **registered == record) {
+                registry.push(Box::leak(Box::new(record)));
+            }
+            chain
+        })
+        .collect())
+}
+
+pub fn custom_chain_record_opt(chain_id: u64) -> Option<&'static BlockchainRecord> {
+    find_custom_record_opt(&|record: &&BlockchainRecord| record.num_chain_id == chain_id)
+}
+
+pub fn find_custom_record_opt(
+    closure: &dyn Fn(&&BlockchainRecord) -> bool,
+) -> Option<&'static BlockchainRecord> {
+    CUSTOM_CHAINS
+        .read()
+        .expect("Custom chain registry is poisoned")
+        .iter()
+        .copied()
+        .find(|record| closure(record))
+}
+
+// Official identifiers already follow this pattern; a custom one becomes the name of a
+// directory, so nothing more exotic is let in
+pub fn is_valid_chain_identifier(identifier: &str) -> bool {
+    Regex::new("^[a-z0-9]+(-[a-z0-9]+)*$")
+        .expect("Failed to compile regular expression")
+        .is_match(identifier)
+}
+
+fn record_from_spec(
+    spec: CustomChainSpec,
+    registry: &[&'static BlockchainRecord],
+) -> Result<BlockchainRecord, String> {
+    let name = spec.identifier;
+    if !is_valid_chain_identifier(&name) {
+        return Err(format!(
+            "Chain identifier '{}' must be lowercase letters and digits, in groups joined by hyphens",
+            name
+        ));
+    }
+    let contract = parse_address(&name, "contract address", &spec.contract_address)?;
+    let disperse_contract_opt = match &spec.disperse_contract_address {
+        Some(address) => Some(parse_address(&name, "disperse contract address", address)?),
+        None => None,
+    };
+    if spec.gas_limit_const_part == 0 || spec.native_transfer_gas_limit == 0 {
+        return Err(format!("Chain '{}' must have nonzero gas limits", name));
+    }
+    // Reading the same chains file again mustn't leak its identifiers all over again
+    let literal_identifier: &'static str = match registry
+        .iter()
+        .find(|registered| registered.literal_identifier == name)
+    {
+        Some(registered) => registered.literal_identifier,
+        None => Box::leak(name.into_boxed_str()),
+    };
+    Ok(BlockchainRecord {
+        self_id: Chain::Custom(spec.chain_id),
+        num_chain_id: spec.chain_id,
+        literal_identifier,
+        contract,
+        contract_creation_block: spec.contract_creation_block,
+        confirmation_depth: spec.confirmation_depth,
+        gas_limit_const_part: spec.gas_limit_const_part,
+        native_transfer_gas_limit: spec.native_transfer_gas_limit,
+        disperse_contract_opt,
+        mainnet: spec.mainnet,
+    })
+}
+
+fn parse_address(name: &str, field: &str, address: &str) -> Result<Address, String> {
+    match address.strip_prefix("0x") {
+        Some(hex) if hex.len() == 40 => Address::from_str(hex).ok(),
+        _ => None,
+    }
+    .ok_or_else(|| {
+        format!(
+            "Chain '{}' has {} '{}'; it must be 0x followed by 40 hex digits",
+            name, field, address
+        )
+    })
+}
+
+fn check_against_official_chains(record: &BlockchainRecord) -> Result<(), String> {
+    match CHAINS.iter().find(|official| {
+        official.num_chain_id == record.num_chain_id
+            || official.literal_identifier == record.literal_identifier
+    }) {
+        Some(official) => Err(format!(
+            "Chain '{}' ({}) clashes with the official chain '{}' ({})",
+            record.literal_identifier,
+            record.num_chain_id,
+            official.literal_identifier,
+            official.num_chain_id
+        )),
+        None => Ok(()),
+    }
+}
+
+fn check_against_others<'a>(
+    record: &BlockchainRecord,
+    others: impl Iterator<Item = &'a BlockchainRecord>,
+) -> Result<(), String> {
+    others
+        .filter(|other| {
+            other.num_chain_id == record.num_chain_id
+                || other.literal_identifier == record.literal_identifier
+        })
+        .map(|other| {
+            Err(format!(
+                "Chain '{}' ({}) clashes with chain '{}' ({}), defined before",
+                record.literal_identifier,
+                record.num_chain_id,
+                other.literal_identifier,
+                other.num_chain_id
+            ))
+        })
+        .next()
+        .unwrap_or(Ok(()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchains::chains::chain_from_chain_identifier_opt;
+    use crate::test_utils::utils::ensure_node_home_directory_exists;
+    use ethereum_types::H160;
+    use std::fs::File;
+    use std::io::Write;
+
+    // Every test registers its own chain IDs and identifiers, because the registry is shared
+    // by the whole test process
+    fn make_spec(chain_id: u64, identifier: &str) -> CustomChainSpec {
+        CustomChainSpec {
+            chain_id,
+            identifier: identifier.to_string(),
+            contract_address: "0x00000000000000000000000000000000000000ab".to_string(),
+            contract_creation_block: 100,
+            confirmation_depth: 3,
+            gas_limit_const_part: 60_000,
+            native_transfer_gas_limit: 25_000,
+            disperse_contract_address: None,
+            mainnet: false,
+        }
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(CUSTOM_CHAINS_FILE_NAME, "chains.toml");
+    }
+
+    #[test]
+    fn parse_custom_chains_reads_every_field() {
+        let contents = r#"
+            [[chain]]
+            chain-id = 31337
+            identifier = "hardhat-devnet"
+            contract-address = "0x5FbDB2315678afecb367f032d93F642f64180aa3"
+            contract-creation-block = 1
+            confirmation-depth = 0
+            gas-limit-const-part = 55000
+            native-transfer-gas-limit = 21000
+            mainnet = false
+
+            [[chain]]
+            chain-id = 424242
+            identifier = "shiny-l2"
+            contract-address = "0x00000000000000000000000000000000000000ab"
+            contract-creation-block = 500
+            confirmation-depth = 20
+            gas-limit-const-part = 80000
+            native-transfer-gas-limit = 30000
+            disperse-contract-address = "0xD152f549545093347A162Dce210e7293f1452150"
+            mainnet = true
+        "#;
+
+        let result = parse_custom_chains(contents).unwrap();
+
+        assert_eq!(
+            result,
+            vec![
+                CustomChainSpec {
+                    chain_id: 31337,
+                    identifier: "hardhat-devnet".to_string(),
+                    contract_address: "0x5FbDB2315678afecb367f032d93F642f64180aa3".to_string(),
+                    contract_creation_block: 1,
+                    confirmation_depth: 0,
+                    gas_limit_const_part: 55_000,
+                    native_transfer_gas_limit: 21_000,
+                    disperse_contract_address: None,
+                    mainnet: false,
+                },
+                CustomChainSpec {
+                    chain_id: 424242,
+                    identifier: "shiny-l2".to_string(),
+                    contract_address: "0x00000000000000000000000000000000000000ab".to_string(),
+                    contract_creation_block: 500,
+                    confirmation_depth: 20,
+                    gas_limit_const_part: 80_000,
+                    native_transfer_gas_limit: 30_000,
+                    disperse_contract_address: Some(
+                        "0xD152f549545093347A162Dce210e7293f1452150".to_string()
+                    ),
+                    mainnet: true,
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_custom_chains_complains_about_a_missing_field() {
+        let contents = r#"
+            [[chain]]
+            chain-id = 31337
+            identifier = "hardhat-devnet"
+        "#;
+
+        let result = parse_custom_chains(contents);
+
+        assert!(
+            result.as_ref().unwrap_err().contains("missing field"),
+            "{:?}",
+            result
+        );
+    }
+
+    #[test]
+    fn parse_custom_chains_accepts_an_empty_file() {
+        assert_eq!(parse_custom_chains(""), Ok(vec![]));
+    }
+
+    #[test]
+    fn registered_chain_has_a_record_and_can_be_found_by_its_identifier() {
+        let mut spec = make_spec(9_000_001, "registered-devnet");
+        spec.disperse_contract_address =
+            Some("0x00000000000000000000000000000000000000cd".to_string());
+        spec.mainnet = true;
+
+        let result = register_custom_chains(vec![spec]);
+
+        assert_eq!(result, Ok(vec![Chain::Custom(9_000_001)]));
+        let record = Chain::Custom(9_000_001).rec();
+        assert_eq!(
+            record,
+            &BlockchainRecord {
+                self_id: Chain::Custom(9_000_001),
+                num_chain_id: 9_000_001,
+                literal_identifier: "registered-devnet",
+                contract: H160([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xab]),
+                contract_creation_block: 100,
+                confirmation_depth: 3,
+                gas_limit_const_part: 60_000,
+                native_transfer_gas_limit: 25_000,
+                disperse_contract_opt: Some(H160([
+                    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xcd
+                ])),
+                mainnet: true,
+            }
+        );
+        assert_eq!(
+            chain_from_chain_identifier_opt("registered-devnet"),
+            Some(Chain::Custom(9_000_001))
+        );
+        assert_eq!(Chain::from("registered-devnet"), Chain::Custom(9_000_001));
+        assert_eq!(Chain::Custom(9_000_001).is_mainnet(), true);
+    }
+
+    #[test]
+    fn registering_the_same_chain_twice_is_harmless() {
+        let spec = make_spec(9_000_002, "twice-devnet");
+        register_custom_chains(vec![spec.clone()]).unwrap();
+
+        let result = register_custom_chains(vec![spec]);
+
+        assert_eq!(result, Ok(vec![Chain::Custom(9_000_002)]));
+        let count = CUSTOM_CHAINS
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|record| record.num_chain_id == 9_000_002)
+            .count();
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn registering_the_same_chain_twice_reuses_its_identifier() {
+        let spec = make_spec(9_000_012, "reused-devnet");
+        register_custom_chains(vec![spec.clone()]).unwrap();
+        let registered = Chain::Custom(9_000_012).rec().literal_identifier;
+        let registry = CUSTOM_CHAINS.read().unwrap().clone();
+
+        let result = record_from_spec(spec, &registry).unwrap();
+
+        assert!(std::ptr::eq(result.literal_identifier, registered));
+    }
+
+    #[test]
+    fn redefining_a_registered_chain_is_rejected() {
+        register_custom_chains(vec![make_spec(9_000_003, "redefined-devnet")]).unwrap();
+        let mut changed = make_spec(9_000_003, "redefined-devnet");
+        changed.confirmation_depth = 99;
+
+        let result = register_custom_chains(vec![changed]);
+
+        assert_eq!(
+            result,
+            Err("Chain 'redefined-devnet' (9000003) clashes with chain 'redefined-devnet' (9000003), defined before".to_string())
+        );
+        assert_eq!(Chain::Custom(9_000_003).rec().confirmation_depth, 3);
+    }
+
+    #[test]
+    fn nothing_is_registered_if_one_spec_is_bad() {
+        let good = make_spec(9_000_004, "good-devnet");
+        let mut bad = make_spec(9_000_005, "bad-devnet");
+        bad.gas_limit_const_part = 0;
+
+        let result = register_custom_chains(vec![good, bad]);
+
+        assert_eq!(
+            result,
+            Err("Chain 'bad-devnet' must have nonzero gas limits".to_string())
+        );
+        assert_eq!(custom_chain_record_opt(9_000_004), None);
+        assert_eq!(chain_from_chain_identifier_opt("good-devnet"), None);
+    }
+
+    #[test]
+    fn duplicates_within_one_file_are_rejected() {
+        let result = register_custom_chains(vec![
+            make_spec(9_000_006, "first-devnet"),
+            make_spec(9_000_007, "first-devnet"),
+        ]);
+
+        assert_eq!(
+            result,
+            Err("Chain 'first-devnet' (9000007) clashes with chain 'first-devnet' (9000006), defined before".to_string())
+        );
+    }
+
+    #[test]
+    fn official_chains_cannot_be_redefined() {
+        let by_id = register_custom_chains(vec![make_spec(137, "not-polygon")]);
+        let by_identifier = register_custom_chains(vec![make_spec(9_000_008, "base-mainnet")]);
+
+        assert_eq!(
+            by_id,
+            Err(
+                "Chain 'not-polygon' (137) clashes with the official chain 'polygon-mainnet' (137)"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            by_identifier,
+            Err("Chain 'base-mainnet' (9000008) clashes with the official chain 'base-mainnet' (8453)".to_string())
+        );
+    }
+
+    #[test]
+    fn malformed_values_are_rejected() {
+        let mut bad_identifier = make_spec(9_000_009, "Bad_Devnet");
+        let mut bad_address = make_spec(9_000_010, "bad-address-devnet");
+        bad_address.contract_address = "0x1234".to_string();
+        let mut bad_disperse_address = make_spec(9_000_011, "bad-disperse-devnet");
+        bad_disperse_address.disperse_contract_address = Some("0xnothex".to_string());
+
+        let identifier_result = register_custom_chains(vec![bad_identifier.clone()]);
+        bad_identifier.identifier = "-devnet".to_string();
+        let hyphen_result = register_custom_chains(vec![bad_identifier]);
+        let address_result = register_custom_chains(vec![bad_address]);
+        let disperse_address_result = register_custom_chains(vec![bad_disperse_address]);
+
+        assert_eq!(
+            identifier_result,
+            Err("Chain identifier 'Bad_Devnet' must be lowercase letters and digits, in groups joined by hyphens".to_string())
+        );
+        assert_eq!(
+            hyphen_result,
+            Err("Chain identifier '-devnet' must be lowercase letters and digits, in groups joined by hyphens".to_string())
+        );
+        assert_eq!(
+            address_result,
+            Err("Chain 'bad-address-devnet' has contract address '0x1234'; it must be 0x followed by 40 hex digits".to_string())
+        );
+        assert_eq!(
+            disperse_address_result,
+            Err("Chain 'bad-disperse-devnet' has disperse contract address '0xnothex'; it must be 0x followed by 40 hex digits".to_string())
+        );
+    }
+
+    #[test]
+    fn load_custom_chains_without_a_file_finds_none() {
+        let data_dir = ensure_node_home_directory_exists(
+            "custom_chains",
+            "load_custom_chains_without_a_file_finds_none",
+        );
+
+        let result = load_custom_chains(&data_dir);
+
+        assert_eq!(result, Ok(vec![]));
+    }
+
+    #[test]
+    fn load_custom_chains_registers_what_is_in_the_file() {
+        let data_dir = ensure_node_home_directory_exists(
+            "custom_chains",
+            "load_custom_chains_registers_what_is_in_the_file",
+        );
+        {
+            let mut file = File::create(data_dir.join(CUSTOM_CHAINS_FILE_NAME)).unwrap();
+            file.write_all(
+                b"[[chain]]\n\
+                  chain-id = 9000011\n\
+                  identifier = \"loaded-devnet\"\n\
+                  contract-address = \"0x00000000000000000000000000000000000000cd\"\n\
+                  contract-creation-block = 7\n\
+                  confirmation-depth = 1\n\
+                  gas-limit-const-part = 50000\n\
+                  native-transfer-gas-limit = 21000\n\
+                  mainnet = false\n",
+            )
+            .unwrap();
+        }
+
+        let result = load_custom_chains(&data_dir);
+
+        assert_eq!(result, Ok(vec![Chain::Custom(9_000_011)]));
+        assert_eq!(
+            Chain::Custom(9_000_011).rec().literal_identifier,
+            "loaded-devnet"
+        );
+        assert_eq!(Chain::Custom(9_000_011).is_mainnet(), false);
+    }
+
+    #[test]
+    fn load_custom_chains_names_the_file_it_cannot_parse() {
+        let data_dir = ensure_node_home_directory_exists(
+            "custom_chains",
+            "load_custom_chains_names_the_file_it_cannot_parse",
+        );
+        let path = data_dir.join(CUSTOM_CHAINS_FILE_NAME);
+        {
+            let mut file = File::create(&path).unwrap();
+            file.write_all(b"[[chain]]\nchain-id = \"many\"\n").unwrap();
+        }
+
+        let result = load_custom_chains(&data_dir);
+
+        let message = result.unwrap_err();
+        assert!(
+            message.starts_with(&format!("Couldn't parse {}: ", path.display())),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    fn unregistered_custom_chain_is_not_a_mainnet() {
+        assert_eq!(Chain::Custom(9_000_999).is_mainnet(), false);
+    }
+}
@@ -5,7 +5,7 @@ use crate::data_version::DataVersion;
 use const_format::concatcp;
 
 pub const DEFAULT_CHAIN: Chain = Chain::PolyMainnet;
-pub const CURRENT_SCHEMA_VERSION: usize = 10;
+pub const CURRENT_SCHEMA_VERSION: usize = 21;
 
 pub const HIGHEST_RANDOM_CLANDESTINE_PORT: u16 = 9999;
 pub const HTTP_PORT: u16 = 80;
@@ -39,10 +39,10 @@ pub const MULTINODE_TESTNET_CONTRACT_CREATION_BLOCK: u64 = 0;
 
 // If you're adding a new constant here,
 // please add it to the test: check_limits_of_data_versions_const()
-pub const CLIENT_REQUEST_PAYLOAD_CURRENT_VERSION: DataVersion = DataVersion { major: 0, minor: 1 };
-pub const CLIENT_RESPONSE_PAYLOAD_CURRENT_VERSION: DataVersion = DataVersion { major: 0, minor: 1 };
+pub const CLIENT_REQUEST_PAYLOAD_CURRENT_VERSION: DataVersion = DataVersion { major: 0, minor: 2 };
+pub const CLIENT_RESPONSE_PAYLOAD_CURRENT_VERSION: DataVersion = DataVersion { major: 0, minor: 2 };
 pub const DNS_RESOLVER_FAILURE_CURRENT_VERSION: DataVersion = DataVersion { major: 0, minor: 1 };
-pub const GOSSIP_CURRENT_VERSION: DataVersion = DataVersion { major: 0, minor: 1 };
+pub const GOSSIP_CURRENT_VERSION: DataVersion = DataVersion { major: 0, minor: 2 };
 pub const GOSSIP_FAILURE_CURRENT_VERSION: DataVersion = DataVersion { major: 0, minor: 1 };
 pub const NODE_RECORD_INNER_CURRENT_VERSION: DataVersion = DataVersion { major: 0, minor: 1 };
 
@@ -65,6 +65,10 @@ pub const UNRECOGNIZED_PARAMETER: u64 = CONFIGURATOR_PREFIX | 11;
 pub const NON_PARSABLE_VALUE: u64 = CONFIGURATOR_PREFIX | 12;
 pub const MISSING_DATA: u64 = CONFIGURATOR_PREFIX | 13;
 pub const UNKNOWN_ERROR: u64 = CONFIGURATOR_PREFIX | 14;
+pub const KEYSTORE_ERROR: u64 = CONFIGURATOR_PREFIX | 15;
+pub const DATABASE_BACKUP_ERROR: u64 = CONFIGURATOR_PREFIX | 16;
+pub const LOG_LEVEL_ERROR: u64 = CONFIGURATOR_PREFIX | 17;
+pub const CONFIG_RELOAD_ERROR: u64 = CONFIGURATOR_PREFIX | 18;
 
 //moved from masq_lib/messages
 pub const UI_NODE_COMMUNICATION_PREFIX: u64 = 0x8000_0000_0000_0000;
@@ -75,12 +79,20 @@ pub const UNMARSHAL_ERROR: u64 = UI_NODE_COMMUNICATION_PREFIX | 4;
 pub const SETUP_ERROR: u64 = UI_NODE_COMMUNICATION_PREFIX | 5;
 pub const TIMEOUT_ERROR: u64 = UI_NODE_COMMUNICATION_PREFIX | 6;
 pub const SCAN_ERROR: u64 = UI_NODE_COMMUNICATION_PREFIX | 7;
+pub const UNAUTHORIZED_OPCODE_ERROR: u64 = UI_NODE_COMMUNICATION_PREFIX | 8;
 
 //accountant
 pub const ACCOUNTANT_PREFIX: u64 = 0x0040_0000_0000_0000;
 pub const REQUEST_WITH_NO_VALUES: u64 = ACCOUNTANT_PREFIX | 1;
 pub const REQUEST_WITH_MUTUALLY_EXCLUSIVE_PARAMS: u64 = ACCOUNTANT_PREFIX | 2;
 pub const VALUE_EXCEEDS_ALLOWED_LIMIT: u64 = ACCOUNTANT_PREFIX | 3;
+pub const INVALID_HISTORY_REQUEST: u64 = ACCOUNTANT_PREFIX | 4;
+pub const LEDGER_ADJUSTMENT_ERROR: u64 = ACCOUNTANT_PREFIX | 5;
+
+//blockchain bridge
+pub const BLOCKCHAIN_BRIDGE_PREFIX: u64 = 0x0020_0000_0000_0000;
+pub const BLOCKCHAIN_QUERY_ERROR: u64 = BLOCKCHAIN_BRIDGE_PREFIX | 1;
+pub const FUNDS_TRANSFER_ERROR: u64 = BLOCKCHAIN_BRIDGE_PREFIX | 2;
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
@@ -149,6 +161,10 @@ mod tests {
         assert_eq!(NON_PARSABLE_VALUE, CONFIGURATOR_PREFIX | 12);
         assert_eq!(MISSING_DATA, CONFIGURATOR_PREFIX | 13);
         assert_eq!(UNKNOWN_ERROR, CONFIGURATOR_PREFIX | 14);
+        assert_eq!(KEYSTORE_ERROR, CONFIGURATOR_PREFIX | 15);
+        assert_eq!(DATABASE_BACKUP_ERROR, CONFIGURATOR_PREFIX | 16);
+        assert_eq!(LOG_LEVEL_ERROR, CONFIGURATOR_PREFIX | 17);
+        assert_eq!(CONFIG_RELOAD_ERROR, CONFIGURATOR_PREFIX | 18);
         assert_eq!(UI_NODE_COMMUNICATION_PREFIX, 0x8000_0000_0000_0000);
         assert_eq!(NODE_LAUNCH_ERROR, UI_NODE_COMMUNICATION_PREFIX | 1);
         assert_eq!(NODE_NOT_RUNNING_ERROR, UI_NODE_COMMUNICATION_PREFIX | 2);
@@ -157,6 +173,7 @@ mod tests {
         assert_eq!(SETUP_ERROR, UI_NODE_COMMUNICATION_PREFIX | 5);
         assert_eq!(TIMEOUT_ERROR, UI_NODE_COMMUNICATION_PREFIX | 6);
         assert_eq!(SCAN_ERROR, UI_NODE_COMMUNICATION_PREFIX | 7);
+        assert_eq!(UNAUTHORIZED_OPCODE_ERROR, UI_NODE_COMMUNICATION_PREFIX | 8);
         assert_eq!(ACCOUNTANT_PREFIX, 0x0040_0000_0000_0000);
         assert_eq!(REQUEST_WITH_NO_VALUES, ACCOUNTANT_PREFIX | 1);
         assert_eq!(
@@ -164,6 +181,11 @@ mod tests {
             ACCOUNTANT_PREFIX | 2
         );
         assert_eq!(VALUE_EXCEEDS_ALLOWED_LIMIT, ACCOUNTANT_PREFIX | 3);
+        assert_eq!(INVALID_HISTORY_REQUEST, ACCOUNTANT_PREFIX | 4);
+        assert_eq!(LEDGER_ADJUSTMENT_ERROR, ACCOUNTANT_PREFIX | 5);
+        assert_eq!(BLOCKCHAIN_BRIDGE_PREFIX, 0x0020_0000_0000_0000);
+        assert_eq!(BLOCKCHAIN_QUERY_ERROR, BLOCKCHAIN_BRIDGE_PREFIX | 1);
+        assert_eq!(FUNDS_TRANSFER_ERROR, BLOCKCHAIN_BRIDGE_PREFIX | 2);
         assert_eq!(CENTRAL_DELIMITER, '@');
         assert_eq!(CHAIN_IDENTIFIER_DELIMITER, ':');
         assert_eq!(POLYGON_FAMILY, "polygon");
@@ -179,17 +201,17 @@ mod tests {
         assert_eq!(DEV_CHAIN_FULL_IDENTIFIER, "dev");
         assert_eq!(
             CLIENT_REQUEST_PAYLOAD_CURRENT_VERSION,
-            DataVersion { major: 0, minor: 1 }
+            DataVersion { major: 0, minor: 2 }
         );
         assert_eq!(
             CLIENT_RESPONSE_PAYLOAD_CURRENT_VERSION,
-            DataVersion { major: 0, minor: 1 }
+            DataVersion { major: 0, minor: 2 }
         );
         assert_eq!(
             DNS_RESOLVER_FAILURE_CURRENT_VERSION,
             DataVersion { major: 0, minor: 1 }
         );
-        assert_eq!(GOSSIP_CURRENT_VERSION, DataVersion { major: 0, minor: 1 });
+        assert_eq!(GOSSIP_CURRENT_VERSION, DataVersion { major: 0, minor: 2 });
         assert_eq!(
             GOSSIP_FAILURE_CURRENT_VERSION,
             DataVersion { major: 0, minor: 1 }
@@ -8,7 +8,8 @@ use crate::constants::{
 use crate::data_version::DataVersion;
 use crate::messages::SerializableLogLevel;
 #[cfg(not(feature = "log_recipient_test"))]
-use crate::messages::{ToMessageBody, UiLogBroadcast};
+use crate::messages::{ToMessageBody, UiLogBroadcast, UiLogStreamBroadcast};
+use crate::redaction::compose_log_message;
 #[cfg(feature = "log_recipient_test")]
 use crate::test_utils::utils::MutexIncrementInset;
 #[cfg(not(feature = "log_recipient_test"))]
@@ -22,6 +23,7 @@ use log::Level;
 use log::Metadata;
 #[allow(unused_imports)]
 use log::Record;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Mutex;
 use std::{io, thread};
 use time::format_description::parse;
@@ -38,6 +40,78 @@ pub const TIME_FORMATTING_STRING: &str =
 
 lazy_static! {
     pub static ref LOG_RECIPIENT_OPT: Mutex<Option<Recipient<NodeToUiMessage>>> = Mutex::new(None);
+    static ref LOG_STREAM_SUBSCRIPTIONS: Mutex<Vec<LogStreamSubscription>> = Mutex::new(vec![]);
+}
+
+// Most verbose level any UI has subscribed to, as a Level discriminant; 0 means no subscriptions.
+// Lets generic_log skip the subscription list for the overwhelming majority of messages.
+static LOG_STREAM_LEVEL_LIMIT: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogStreamSubscription {
+    pub client_id: u64,
+    pub level: Level,
+    pub module_opt: Option<String>,
+}
+
+impl LogStreamSubscription {
+    pub fn new(client_id: u64, level: Level, module_opt: Option<&str>) -> Self {
+        Self {
+            client_id,
+            level,
+            module_opt: module_opt.map(log_target),
+        }
+    }
+
+    fn admits(&self, level: Level, target: &str) -> bool {
+        level <= self.level
+            && match &self.module_opt {
+                Some(module) => target.starts_with(module.as_str()),
+                None => true,
+            }
+    }
+}
+
+// Replaces any subscription the same client already has
+pub fn subscribe_to_log_stream(subscription: LogStreamSubscription) {
+    let mut subscriptions = LOG_STREAM_SUBSCRIPTIONS
+        .lock()
+        .expect("log stream subscriptions poisoned");
+    subscriptions.retain(|s| s.client_id != subscription.client_id);
+    subscriptions.push(subscription);
+    reset_log_stream_level_limit(&subscriptions);
+}
+
+pub fn unsubscribe_from_log_stream(client_id: u64) -> bool {
+    let mut subscriptions = LOG_STREAM_SUBSCRIPTIONS
+        .lock()
+        .expect("log stream subscriptions poisoned");
+    let before = subscriptions.len();
+    subscriptions.retain(|s| s.client_id != client_id);
+    reset_log_stream_level_limit(&subscriptions);
+    subscriptions.len() < before
+}
+
+fn reset_log_stream_level_limit(subscriptions: &[LogStreamSubscription]) {
+    let limit = subscriptions
+        .iter()
+        .map(|s| s.level as usize)
+        .max()
+        .unwrap_or(0);
+    LOG_STREAM_LEVEL_LIMIT.store(limit, Ordering::Relaxed);
+}
+
+// The name under which a Logger's records are filtered, both by the log specification and by
+// log stream subscriptions: "ProxyServer", "proxy_server" and "node::proxy_server" all become
+// "proxyserver".
+pub fn log_target(name: &str) -> String {
+    name.rsplit("::")
+        .next()
+        .unwrap_or(name)
+        .chars()
+        .filter(|c| !matches!(c, '_' | '-' | ' '))
+        .flat_map(|c| c.to_lowercase())
+        .collect()
 }
 
 #[cfg(not(feature = "log_recipient_test"))]
@@ -55,6 +129,7 @@ pub fn prepare_log_recipient(recipient: Recipient<NodeToUiMessage>) {
 #[derive(Clone)]
 pub struct Logger {
     name: String,
+    target: String,
     #[cfg(not(feature = "no_test_share"))]
     level_limit: Level,
 }
@@ -111,6 +186,7 @@ impl Logger {
     pub fn new(name: &str) -> Logger {
         Logger {
             name: String::from(name),
+            target: log_target(name),
             #[cfg(not(feature = "no_test_share"))]
             level_limit: Level::Trace,
         }
@@ -155,7 +231,7 @@ impl Logger {
     where
         F: FnOnce() -> String,
     {
-        let msg = log_function();
+        let msg = compose_log_message(Level::Error, log_function);
         self.log(Level::Error, msg.clone());
         panic!("{}", msg);
     }
@@ -184,23 +260,40 @@ impl Logger {
     where
         F: FnOnce() -> String,
     {
-        match (self.level_enabled(level), level.le(&UI_MESSAGE_LOG_LEVEL)) {
+        let broadcast = level.le(&UI_MESSAGE_LOG_LEVEL);
+        let stream = !broadcast && Self::stream_wanted(level);
+        match (self.level_enabled(level), broadcast || stream) {
             (true, true) => {
-                let msg = log_function();
+                let msg = compose_log_message(level, log_function);
                 self.log(level, msg.clone());
-                Self::transmit(msg, level.into());
+                self.distribute(msg, level, broadcast);
+            }
+            (true, false) => self.log(level, compose_log_message(level, log_function)),
+            (false, true) => {
+                self.distribute(compose_log_message(level, log_function), level, broadcast)
             }
-            (true, false) => self.log(level, log_function()),
-            (false, true) => Self::transmit(log_function(), level.into()),
             _ => {}
         }
     }
 
+    fn distribute(&self, msg: String, level: Level, broadcast: bool) {
+        if broadcast {
+            Self::transmit(msg, level.into())
+        } else {
+            self.stream(msg, level)
+        }
+    }
+
+    fn stream_wanted(level: Level) -> bool {
+        level as usize <= LOG_STREAM_LEVEL_LIMIT.load(Ordering::Relaxed)
+    }
+
     pub fn log(&self, level: Level, msg: String) {
         logger().log(
             &Record::builder()
                 .args(format_args!("{}", msg))
                 .module_path(Some(&self.name))
+                .target(&self.target)
                 .level(level)
                 .build(),
         );
@@ -248,12 +341,49 @@ impl Logger {
             recipient.try_send(actix_msg).expect("UiGateway is dead")
         }
     }
+
+    #[cfg(not(feature = "log_recipient_test"))]
+    fn stream(&self, msg: String, level: Level) {
+        let client_ids = LOG_STREAM_SUBSCRIPTIONS
+            .lock()
+            .expect("log stream subscriptions poisoned")
+            .iter()
+            .filter(|s| s.admits(level, &self.target))
+            .map(|s| s.client_id)
+            .collect::<Vec<u64>>();
+        if client_ids.is_empty() {
+            return;
+        }
+        if let Some(recipient) = LOG_RECIPIENT_OPT
+            .lock()
+            .expect("log recipient mutex poisoned")
+            .as_ref()
+        {
+            client_ids.into_iter().for_each(|client_id| {
+                let actix_msg = NodeToUiMessage {
+                    target: MessageTarget::ClientId(client_id),
+                    body: UiLogStreamBroadcast {
+                        msg: msg.clone(),
+                        level: level.to_string(),
+                        module: self.name.clone(),
+                    }
+                    .tmb(0),
+                };
+                recipient.try_send(actix_msg).expect("UiGateway is dead")
+            })
+        }
+    }
 }
 
 #[cfg(feature = "no_test_share")]
 impl Logger {
     pub fn level_enabled(&self, level: Level) -> bool {
-        logger().enabled(&Metadata::builder().level(level).target(&self.name).build())
+        logger().enabled(
+            &Metadata::builder()
+                .level(level)
+                .target(&self.target)
+                .build(),
+        )
     }
 }
 
@@ -304,6 +434,8 @@ lazy_static! {
 #[cfg(feature = "log_recipient_test")]
 impl Logger {
     pub fn transmit(_msg: String, _log_level: SerializableLogLevel) {}
+
+    fn stream(&self, _msg: String, _level: Level) {}
 }
 
 #[cfg(feature = "log_recipient_test")]
@@ -335,13 +467,15 @@ mod tests {
         DNS_RESOLVER_FAILURE_CURRENT_VERSION, GOSSIP_CURRENT_VERSION,
         GOSSIP_FAILURE_CURRENT_VERSION, NODE_RECORD_INNER_CURRENT_VERSION,
     };
-    use crate::messages::{ToMessageBody, UiLogBroadcast};
+    use crate::messages::{ToMessageBody, UiLogBroadcast, UiLogStreamBroadcast};
+    use crate::redaction::{redaction_token, set_redacted_levels_for_test_thread, MarkSensitive};
     use crate::test_utils::logging::init_test_logging;
     use crate::test_utils::logging::TestLogHandler;
     use crate::ui_gateway::{MessageBody, MessagePath, MessageTarget};
     use actix::{Actor, AsyncContext, Context, Handler, Message, System};
     use crossbeam_channel::{unbounded, Sender};
     use regex::Regex;
+    use std::net::{IpAddr, Ipv4Addr};
     use std::panic::{catch_unwind, AssertUnwindSafe};
     use std::sync::{Arc, Mutex, MutexGuard};
     use std::thread;
@@ -739,6 +873,104 @@ mod tests {
         TestLogHandler::new().exists_log_containing("WARN: test: This is a warn log.");
     }
 
+    #[test]
+    fn generic_log_streams_verbose_messages_only_to_subscribers_that_want_them() {
+        init_test_logging();
+        let _guard = TEST_LOG_RECIPIENT_GUARD.lock().unwrap();
+        let hopper_logger = Logger::new("Hopper");
+        let neighborhood_logger = Logger::new("Neighborhood");
+        let system = System::new("streaming");
+        let ui_gateway_recording_arc = Arc::new(Mutex::new(vec![]));
+        let ui_gateway = TestUiGateway::new(4, &ui_gateway_recording_arc);
+        let recipient = ui_gateway.start().recipient();
+        {
+            LOG_RECIPIENT_OPT.lock().unwrap().replace(recipient);
+        }
+        subscribe_to_log_stream(LogStreamSubscription::new(
+            1234,
+            Level::Debug,
+            Some("node::hopper"),
+        ));
+        subscribe_to_log_stream(LogStreamSubscription::new(2345, Level::Trace, None));
+
+        hopper_logger.trace(|| "Hopper trace".to_string());
+        hopper_logger.debug(|| "Hopper debug".to_string());
+        neighborhood_logger.debug(|| "Neighborhood debug".to_string());
+
+        system.run(); //shut down after receiving the expected count of messages
+        unsubscribe_from_log_stream(1234);
+        unsubscribe_from_log_stream(2345);
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        let stream_msg = |client_id: u64, msg: &str, level: &str, module: &str| NodeToUiMessage {
+            target: MessageTarget::ClientId(client_id),
+            body: UiLogStreamBroadcast {
+                msg: msg.to_string(),
+                level: level.to_string(),
+                module: module.to_string(),
+            }
+            .tmb(0),
+        };
+        assert_eq!(
+            *ui_gateway_recording,
+            vec![
+                stream_msg(2345, "Hopper trace", "TRACE", "Hopper"),
+                stream_msg(1234, "Hopper debug", "DEBUG", "Hopper"),
+                stream_msg(2345, "Hopper debug", "DEBUG", "Hopper"),
+                stream_msg(2345, "Neighborhood debug", "DEBUG", "Neighborhood"),
+            ]
+        );
+    }
+
+    #[test]
+    fn subscriptions_replace_earlier_ones_and_set_the_level_limit() {
+        let _guard = TEST_LOG_RECIPIENT_GUARD.lock().unwrap();
+
+        subscribe_to_log_stream(LogStreamSubscription::new(1234, Level::Trace, None));
+        let trace_wanted = Logger::stream_wanted(Level::Trace);
+        subscribe_to_log_stream(LogStreamSubscription::new(1234, Level::Debug, None));
+        let trace_wanted_after_replacement = Logger::stream_wanted(Level::Trace);
+        let debug_wanted_after_replacement = Logger::stream_wanted(Level::Debug);
+        let first_removal = unsubscribe_from_log_stream(1234);
+        let second_removal = unsubscribe_from_log_stream(1234);
+
+        assert_eq!(trace_wanted, true);
+        assert_eq!(trace_wanted_after_replacement, false);
+        assert_eq!(debug_wanted_after_replacement, true);
+        assert_eq!(first_removal, true);
+        assert_eq!(second_removal, false);
+        assert_eq!(Logger::stream_wanted(Level::Debug), false);
+    }
+
+    #[test]
+    fn sensitive_values_never_reach_the_log_at_redacted_levels() {
+        init_test_logging();
+        let _guard = prepare_test_environment();
+        set_redacted_levels_for_test_thread(&[Level::Warn]);
+        let logger = Logger::new("sensitive_values_never_reach_the_log");
+        let ip = IpAddr::V4(Ipv4Addr::new(12, 34, 56, 78));
+
+        warning!(logger, "Warning about {}", ip.sensitive());
+        debug!(logger, "Debugging {}", "unredacted.example.com".sensitive());
+
+        let tlh = TestLogHandler::new();
+        tlh.exists_log_containing(&format!(
+            "WARN: sensitive_values_never_reach_the_log: Warning about {}",
+            redaction_token("12.34.56.78")
+        ));
+        tlh.exists_log_containing(
+            "DEBUG: sensitive_values_never_reach_the_log: Debugging unredacted.example.com",
+        );
+        tlh.exists_no_log_containing("12.34.56.78");
+    }
+
+    #[test]
+    fn log_target_normalizes_names() {
+        assert_eq!(log_target("ProxyServer"), "proxyserver".to_string());
+        assert_eq!(log_target("proxy_server"), "proxyserver".to_string());
+        assert_eq!(log_target("node::proxy_server"), "proxyserver".to_string());
+        assert_eq!(log_target("Hopper"), "hopper".to_string());
+    }
+
     #[test]
     fn log_file_heading_print_right_format() {
         let heading_result = Logger::log_file_heading();
@@ -1011,6 +1243,7 @@ mod tests {
     fn make_logger_at_level(level: Level) -> Logger {
         Logger {
             name: "test".to_string(),
+            target: "test".to_string(),
             #[cfg(not(feature = "no_test_share"))]
             level_limit: level,
         }
@@ -647,9 +647,76 @@ pub struct UiFinancialsResponse {
     pub stats_opt: Option<UiFinancialStatistics>,
     #[serde(rename = "queryResultsOpt")]
     pub query_results_opt: Option<QueryResults>,
+    #[serde(rename = "feeBumpsOpt")]
+    pub fee_bumps_opt: Option<Vec<UiPendingPayableFeeBump>>,
+    #[serde(rename = "spendingBudgetOpt")]
+    pub spending_budget_opt: Option<UiSpendingBudget>,
 }
 conversation_message!(UiFinancialsResponse, "financials");
 
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiFinancialsHistoryRequest {
+    #[serde(rename = "minAgeS")]
+    pub min_age_s: u64,
+    #[serde(rename = "maxAgeS")]
+    pub max_age_s: u64,
+    pub granularity: HistoryGranularity,
+    #[serde(rename = "walletOpt")]
+    pub wallet_opt: Option<String>,
+}
+conversation_message!(UiFinancialsHistoryRequest, "financialsHistory");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum HistoryGranularity {
+    Hour,
+    Day,
+    Week,
+}
+
+impl HistoryGranularity {
+    pub fn period_s(&self) -> u64 {
+        match self {
+            Self::Hour => 3_600,
+            Self::Day => 86_400,
+            Self::Week => 604_800,
+        }
+    }
+}
+
+impl TryFrom<&str> for HistoryGranularity {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Ok(match value {
+            "hour" => Self::Hour,
+            "day" => Self::Day,
+            "week" => Self::Week,
+            x => return Err(format!("Unrecognized granularity: '{}'", x)),
+        })
+    }
+}
+
+// Periods with no traffic at all are left out
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiFinancialsHistoryResponse {
+    pub periods: Vec<UiFinancialsHistoryPeriod>,
+}
+conversation_message!(UiFinancialsHistoryResponse, "financialsHistory");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct UiFinancialsHistoryPeriod {
+    #[serde(rename = "startS")]
+    pub start_s: u64,
+    #[serde(rename = "earnedRoutingGwei")]
+    pub earned_routing_gwei: u64,
+    #[serde(rename = "earnedExitGwei")]
+    pub earned_exit_gwei: u64,
+    #[serde(rename = "spentRoutingGwei")]
+    pub spent_routing_gwei: u64,
+    #[serde(rename = "spentExitGwei")]
+    pub spent_exit_gwei: u64,
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub struct UiFinancialStatistics {
     #[serde(rename = "totalUnpaidAndPendingPayableGwei")]
@@ -681,6 +748,31 @@ pub struct UiPayableAccount {
     pub pending_payable_hash_opt: Option<String>,
 }
 
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct UiPendingPayableFeeBump {
+    #[serde(rename = "replacedTxHash")]
+    pub replaced_tx_hash: String,
+    #[serde(rename = "replacementTxHash")]
+    pub replacement_tx_hash: String,
+    #[serde(rename = "gasPriceWei")]
+    pub gas_price_wei: u64,
+    #[serde(rename = "ageS")]
+    pub age_s: u64,
+}
+
+// A limit of zero means the period is not capped
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub struct UiSpendingBudget {
+    #[serde(rename = "dailyLimitGwei")]
+    pub daily_limit_gwei: u64,
+    #[serde(rename = "dailySpentGwei")]
+    pub daily_spent_gwei: u64,
+    #[serde(rename = "monthlyLimitGwei")]
+    pub monthly_limit_gwei: u64,
+    #[serde(rename = "monthlySpentGwei")]
+    pub monthly_spent_gwei: u64,
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub struct UiReceivableAccount {
     pub wallet: String,
@@ -690,6 +782,70 @@ pub struct UiReceivableAccount {
     pub balance_gwei: i64,
 }
 
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum LedgerSide {
+    #[serde(rename = "payable")]
+    Payable,
+    #[serde(rename = "receivable")]
+    Receivable,
+}
+
+impl TryFrom<&str> for LedgerSide {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Ok(match value {
+            "payable" => Self::Payable,
+            "receivable" => Self::Receivable,
+            x => return Err(format!("Unrecognized ledger: '{}'", x)),
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiAdjustBalanceRequest {
+    #[serde(rename = "dbPassword")]
+    pub db_password: String,
+    pub ledger: LedgerSide,
+    pub wallet: String,
+    // Positive raises the balance, negative lowers it; a payable can't go below zero
+    #[serde(rename = "changeGwei")]
+    pub change_gwei: i64,
+    // Both are kept in the audit trail; neither may be blank
+    pub operator: String,
+    pub reason: String,
+}
+conversation_message!(UiAdjustBalanceRequest, "adjustBalance");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub struct UiAdjustBalanceResponse {
+    #[serde(rename = "balanceBeforeGwei")]
+    pub balance_before_gwei: i64,
+    #[serde(rename = "balanceAfterGwei")]
+    pub balance_after_gwei: i64,
+}
+conversation_message!(UiAdjustBalanceResponse, "adjustBalance");
+
+// Writes off what the wallet owes this Node and lifts its delinquency ban, if any
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiForgiveDebtRequest {
+    #[serde(rename = "dbPassword")]
+    pub db_password: String,
+    pub wallet: String,
+    pub operator: String,
+    pub reason: String,
+}
+conversation_message!(UiForgiveDebtRequest, "forgiveDebt");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub struct UiForgiveDebtResponse {
+    #[serde(rename = "forgivenGwei")]
+    pub forgiven_gwei: u64,
+    #[serde(rename = "banLifted")]
+    pub ban_lifted: bool,
+}
+conversation_message!(UiForgiveDebtResponse, "forgiveDebt");
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
 pub struct UiGenerateSeedSpec {
     #[serde(rename = "mnemonicPhraseSizeOpt")]
@@ -757,6 +913,14 @@ pub struct UiRecoverSeedSpec {
     pub mnemonic_passphrase_opt: Option<String>,
 }
 
+// A Web3 Secret Storage (keystore v3) file and the passphrase that unlocks it
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiKeystoreSpec {
+    #[serde(rename = "keystoreJson")]
+    pub keystore_json: String,
+    pub passphrase: String,
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
 pub struct UiRecoverWalletsRequest {
     #[serde(rename = "dbPassword")]
@@ -767,6 +931,8 @@ pub struct UiRecoverWalletsRequest {
     pub consuming_derivation_path_opt: Option<String>,
     #[serde(rename = "consumingPrivateKeyOpt")]
     pub consuming_private_key_opt: Option<String>,
+    #[serde(rename = "consumingKeystoreOpt")]
+    pub consuming_keystore_opt: Option<UiKeystoreSpec>,
     #[serde(rename = "earningDerivationPathOpt")]
     pub earning_derivation_path_opt: Option<String>,
     #[serde(rename = "earningAddressOpt")]
@@ -783,6 +949,7 @@ pub enum ScanType {
     Payables,
     Receivables,
     PendingPayables,
+    FeeBumps,
 }
 
 impl FromStr for ScanType {
@@ -793,6 +960,7 @@ impl FromStr for ScanType {
             s if &s.to_lowercase() == "payables" => Ok(ScanType::Payables),
             s if &s.to_lowercase() == "receivables" => Ok(ScanType::Receivables),
             s if &s.to_lowercase() == "pendingpayables" => Ok(ScanType::PendingPayables),
+            s if &s.to_lowercase() == "feebumps" => Ok(ScanType::FeeBumps),
             s => Err(format!("Unrecognized ScanType: '{}'", s)),
         }
     }
@@ -846,6 +1014,218 @@ pub struct UiWalletAddressesResponse {
 }
 conversation_message!(UiWalletAddressesResponse, "walletAddresses");
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiWalletBalancesRequest {
+    #[serde(rename = "dbPassword")]
+    pub db_password: String,
+}
+conversation_message!(UiWalletBalancesRequest, "walletBalances");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiWalletBalances {
+    pub address: String,
+    #[serde(rename = "transactionFeeBalanceGwei")]
+    pub transaction_fee_balance_gwei: u64,
+    #[serde(rename = "masqBalanceGwei")]
+    pub masq_balance_gwei: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiWalletBalancesResponse {
+    #[serde(rename = "consumingWallet")]
+    pub consuming_wallet: UiWalletBalances,
+    #[serde(rename = "earningWallet")]
+    pub earning_wallet: UiWalletBalances,
+    #[serde(rename = "gasPriceGwei")]
+    pub gas_price_gwei: u64,
+    // How many payables of the size of the debt threshold the consuming wallet can pay off,
+    // considering both its MASQ and its transaction fee balance
+    #[serde(rename = "affordablePayables")]
+    pub affordable_payables: u64,
+}
+conversation_message!(UiWalletBalancesResponse, "walletBalances");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum TransferCurrency {
+    #[serde(rename = "masq")]
+    Masq,
+    // The currency the transaction fees are paid in (ETH, MATIC...)
+    #[serde(rename = "native")]
+    Native,
+}
+
+impl FromStr for TransferCurrency {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            s if &s.to_lowercase() == "masq" => Ok(TransferCurrency::Masq),
+            s if &s.to_lowercase() == "native" => Ok(TransferCurrency::Native),
+            s => Err(format!("Unrecognized currency: '{}'", s)),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiTransferRequest {
+    #[serde(rename = "dbPassword")]
+    pub db_password: String,
+    pub recipient: String,
+    pub currency: TransferCurrency,
+    // None means the whole balance; for the native currency, the balance less the fee
+    #[serde(rename = "amountGweiOpt")]
+    pub amount_gwei_opt: Option<u64>,
+    #[serde(rename = "dryRun")]
+    pub dry_run: bool,
+}
+conversation_message!(UiTransferRequest, "transfer");
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UiTransferResponse {
+    // None for a dry run
+    #[serde(rename = "transactionHashOpt")]
+    pub transaction_hash_opt: Option<String>,
+    pub recipient: String,
+    pub currency: TransferCurrency,
+    #[serde(rename = "amountGwei")]
+    pub amount_gwei: u64,
+    #[serde(rename = "feeEstimateGwei")]
+    pub fee_estimate_gwei: u64,
+    #[serde(rename = "gasPriceGwei")]
+    pub gas_price_gwei: u64,
+    pub nonce: u64,
+}
+conversation_message!(UiTransferResponse, "transfer");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum WalletExportFormat {
+    #[serde(rename = "keystore")]
+    Keystore,
+}
+
+impl FromStr for WalletExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            s if &s.to_lowercase() == "keystore" => Ok(WalletExportFormat::Keystore),
+            s => Err(format!("Unrecognized wallet export format: '{}'", s)),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiExportWalletRequest {
+    #[serde(rename = "dbPassword")]
+    pub db_password: String,
+    pub format: WalletExportFormat,
+    // The export is re-encrypted with this rather than with the database password
+    pub passphrase: String,
+}
+conversation_message!(UiExportWalletRequest, "exportWallet");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiExportWalletResponse {
+    #[serde(rename = "walletAddress")]
+    pub wallet_address: String,
+    pub format: WalletExportFormat,
+    pub exported: String,
+}
+conversation_message!(UiExportWalletResponse, "exportWallet");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiDatabaseBackupRequest {
+    // Absolute path, on the Node's machine, of a file that must not yet exist
+    pub path: String,
+    // If present, must be the database password; the backup is then encrypted with it
+    #[serde(rename = "dbPasswordOpt")]
+    pub db_password_opt: Option<String>,
+}
+conversation_message!(UiDatabaseBackupRequest, "databaseBackup");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiDatabaseBackupResponse {
+    pub path: String,
+    pub encrypted: bool,
+    #[serde(rename = "sizeBytes")]
+    pub size_bytes: u64,
+}
+conversation_message!(UiDatabaseBackupResponse, "databaseBackup");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiModuleLogLevel {
+    // Logger name, case-insensitive, such as "neighborhood" or "node::proxy_server"; matches as a prefix
+    pub module: String,
+    // "off", "error", "warn", "info", "debug" or "trace"
+    pub level: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiSetLogLevelRequest {
+    // New level for every module without a level of its own; if absent, the global level is unchanged
+    #[serde(rename = "levelOpt")]
+    pub level_opt: Option<String>,
+    // Added to, or replacing, the per-module levels already in force
+    #[serde(rename = "moduleLevels")]
+    pub module_levels: Vec<UiModuleLogLevel>,
+}
+conversation_message!(UiSetLogLevelRequest, "setLogLevel");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiSetLogLevelResponse {
+    // The complete specification now in force, such as "info, hopper=debug"
+    #[serde(rename = "logSpec")]
+    pub log_spec: String,
+}
+conversation_message!(UiSetLogLevelResponse, "setLogLevel");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiLogStreamRequest {
+    // true to start (or replace) this client's subscription, false to end it
+    pub follow: bool,
+    // "debug" or "trace": the most verbose level to stream
+    pub level: String,
+    // If present, only loggers whose names begin with this are streamed
+    #[serde(rename = "moduleOpt")]
+    pub module_opt: Option<String>,
+}
+conversation_message!(UiLogStreamRequest, "logStream");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiLogStreamResponse {}
+conversation_message!(UiLogStreamResponse, "logStream");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiLogStreamBroadcast {
+    pub msg: String,
+    pub level: String,
+    pub module: String,
+}
+fire_and_forget_message!(UiLogStreamBroadcast, "logStream");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiReloadConfigRequest {}
+conversation_message!(UiReloadConfigRequest, "reloadConfig");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiReloadConfigResponse {
+    // Names of the parameters whose new values are now in force
+    pub applied: Vec<String>,
+    // Parameters whose new values couldn't be put in force; they keep their old values
+    pub failed: Vec<UiReloadConfigFailure>,
+    // Names of the parameters that changed but won't take effect until the Node is restarted
+    #[serde(rename = "restartRequired")]
+    pub restart_required: Vec<String>,
+}
+conversation_message!(UiReloadConfigResponse, "reloadConfig");
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct UiReloadConfigFailure {
+    pub parameter: String,
+    pub code: u64,
+    pub message: String,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1164,6 +1544,8 @@ mod tests {
             "rECEIVABLES",
             "PendingPayables",
             "pENDINGpAYABLES",
+            "FeeBumps",
+            "fEEbUMPS",
         ]
         .into_iter()
         .map(|s| ScanType::from_str(s).unwrap())
@@ -1178,6 +1560,8 @@ mod tests {
                 ScanType::Receivables,
                 ScanType::PendingPayables,
                 ScanType::PendingPayables,
+                ScanType::FeeBumps,
+                ScanType::FeeBumps,
             ]
         )
     }
@@ -1192,6 +1576,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn transfer_currency_from_string_happy_path() {
+        let result: Vec<TransferCurrency> = vec!["masq", "MASQ", "native", "nATIVE"]
+            .into_iter()
+            .map(|s| TransferCurrency::from_str(s).unwrap())
+            .collect();
+
+        assert_eq!(
+            result,
+            vec![
+                TransferCurrency::Masq,
+                TransferCurrency::Masq,
+                TransferCurrency::Native,
+                TransferCurrency::Native,
+            ]
+        )
+    }
+
+    #[test]
+    fn transfer_currency_from_string_error() {
+        let result = TransferCurrency::from_str("dogecoin");
+
+        assert_eq!(result, Err("Unrecognized currency: 'dogecoin'".to_string()));
+    }
+
+    #[test]
+    fn wallet_export_format_from_string() {
+        assert_eq!(
+            WalletExportFormat::from_str("KeyStore"),
+            Ok(WalletExportFormat::Keystore)
+        );
+        assert_eq!(
+            WalletExportFormat::from_str("mnemonic"),
+            Err("Unrecognized wallet export format: 'mnemonic'".to_string())
+        );
+    }
+
+    #[test]
+    fn recover_wallets_request_without_keystore_is_still_understood() {
+        let json = r#"{"dbPassword":"password","seedSpecOpt":null,"consumingDerivationPathOpt":null,"consumingPrivateKeyOpt":"0123","earningDerivationPathOpt":null,"earningAddressOpt":"0x0123"}"#;
+
+        let result: UiRecoverWalletsRequest = serde_json::from_str(json).unwrap();
+
+        assert_eq!(result.consuming_keystore_opt, None);
+        assert_eq!(result.consuming_private_key_opt, Some("0123".to_string()));
+    }
+
     #[test]
     fn top_records_ordering_from_str() {
         assert_eq!(
@@ -1211,4 +1642,22 @@ mod tests {
             Err("Unrecognized ordering: 'upside-down'".to_string())
         );
     }
+
+    #[test]
+    fn history_granularity_from_str_and_period() {
+        let result = ["hour", "day", "week", "fortnight"]
+            .into_iter()
+            .map(|name| HistoryGranularity::try_from(name).map(|g| (g, g.period_s())))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            result,
+            vec![
+                Ok((HistoryGranularity::Hour, 3_600)),
+                Ok((HistoryGranularity::Day, 86_400)),
+                Ok((HistoryGranularity::Week, 604_800)),
+                Err("Unrecognized granularity: 'fortnight'".to_string())
+            ]
+        );
+    }
 }
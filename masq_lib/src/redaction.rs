@@ -0,0 +1,235 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+// Log redaction. Values that identify people or machines--IP addresses, hostnames, public keys--
+// are wrapped with .sensitive() where they are logged. When the level of the message being logged
+// is one of the redacted levels, such a value is written as a token derived from a hash of it,
+// salted afresh every time the process starts: the same value gets the same token throughout a
+// session, so a log can still be followed, but tokens can't be compared across sessions or
+// reversed by hashing likely values.
+
+use lazy_static::lazy_static;
+use log::Level;
+use std::cell::Cell;
+use std::collections::hash_map::RandomState;
+use std::fmt::{Debug, Display, Formatter};
+use std::hash::BuildHasher;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+pub const REDACTION_TOKEN_PREFIX: &str = "<redacted:";
+
+// Bit (1 << level) is set for every redacted Level
+static REDACTED_LEVELS: AtomicUsize = AtomicUsize::new(0);
+
+lazy_static! {
+    static ref SESSION_SALT: RandomState = RandomState::new();
+}
+
+thread_local! {
+    static REDACTING: Cell<bool> = const { Cell::new(false) };
+}
+
+#[cfg(not(feature = "no_test_share"))]
+thread_local! {
+    // Lets a test redact its own thread's messages without affecting tests running alongside it
+    static TEST_REDACTED_LEVELS_OPT: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+pub fn set_redacted_levels(levels: &[Level]) {
+    REDACTED_LEVELS.store(level_mask(levels), Ordering::Relaxed)
+}
+
+pub fn redacted_levels() -> Vec<Level> {
+    let mask = current_mask();
+    [
+        Level::Error,
+        Level::Warn,
+        Level::Info,
+        Level::Debug,
+        Level::Trace,
+    ]
+    .into_iter()
+    .filter(|level| mask & (1 << *level as usize) != 0)
+    .collect()
+}
+
+#[cfg(not(feature = "no_test_share"))]
+pub fn set_redacted_levels_for_test_thread(levels: &[Level]) {
+    TEST_REDACTED_LEVELS_OPT.with(|opt| opt.set(Some(level_mask(levels))))
+}
+
+#[cfg(not(feature = "no_test_share"))]
+fn current_mask() -> usize {
+    TEST_REDACTED_LEVELS_OPT
+        .with(|opt| opt.get())
+        .unwrap_or_else(|| REDACTED_LEVELS.load(Ordering::Relaxed))
+}
+
+#[cfg(feature = "no_test_share")]
+fn current_mask() -> usize {
+    REDACTED_LEVELS.load(Ordering::Relaxed)
+}
+
+pub fn is_redacted_level(level: Level) -> bool {
+    let mask = current_mask();
+    mask & (1 << level as usize) != 0
+}
+
+// True while a message for a redacted level is being composed on this thread
+pub fn is_redacting() -> bool {
+    REDACTING.with(|redacting| redacting.get())
+}
+
+pub fn redaction_token(value: &str) -> String {
+    let hash = SESSION_SALT.hash_one(value);
+    format!("{}{:08x}>", REDACTION_TOKEN_PREFIX, hash as u32)
+}
+
+// Composes a log message, redacting the Sensitive values in it if the level calls for that
+pub fn compose_log_message<F>(level: Level, log_function: F) -> String
+where
+    F: FnOnce() -> String,
+{
+    if !is_redacted_level(level) {
+        return log_function();
+    }
+    let _guard = RedactionGuard::engage();
+    log_function()
+}
+
+struct RedactionGuard {
+    previous: bool,
+}
+
+impl RedactionGuard {
+    fn engage() -> Self {
+        Self {
+            previous: REDACTING.with(|redacting| redacting.replace(true)),
+        }
+    }
+}
+
+impl Drop for RedactionGuard {
+    fn drop(&mut self) {
+        let previous = self.previous;
+        REDACTING.with(|redacting| redacting.set(previous))
+    }
+}
+
+fn level_mask(levels: &[Level]) -> usize {
+    levels
+        .iter()
+        .fold(0, |mask, level| mask | (1 << *level as usize))
+}
+
+pub struct Sensitive<T>(pub T);
+
+impl<T: Display> Display for Sensitive<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if is_redacting() {
+            f.write_str(&redaction_token(&self.0.to_string()))
+        } else {
+            Display::fmt(&self.0, f)
+        }
+    }
+}
+
+impl<T: Debug> Debug for Sensitive<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if is_redacting() {
+            f.write_str(&redaction_token(&format!("{:?}", self.0)))
+        } else {
+            Debug::fmt(&self.0, f)
+        }
+    }
+}
+
+pub trait MarkSensitive {
+    fn sensitive(&self) -> Sensitive<&Self>;
+}
+
+impl<T: ?Sized> MarkSensitive for T {
+    fn sensitive(&self) -> Sensitive<&Self> {
+        Sensitive(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+    use std::thread;
+
+    #[test]
+    fn sensitive_values_are_shown_when_not_redacting() {
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4)), 5678);
+
+        let result = compose_log_message(Level::Info, || {
+            format!("{} {:?}", socket_addr.sensitive(), "host.com".sensitive())
+        });
+
+        assert_eq!(result, "1.2.3.4:5678 \"host.com\"".to_string());
+    }
+
+    #[test]
+    fn sensitive_values_are_hashed_at_redacted_levels_only() {
+        set_redacted_levels_for_test_thread(&[Level::Info, Level::Warn]);
+        let ip = IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4));
+
+        let info = compose_log_message(Level::Info, || {
+            format!("from {} to {}", ip.sensitive(), "host.com".sensitive())
+        });
+        let warn = compose_log_message(Level::Warn, || format!("from {}", ip.sensitive()));
+        let debug = compose_log_message(Level::Debug, || format!("from {}", ip.sensitive()));
+
+        assert_eq!(
+            info,
+            format!(
+                "from {} to {}",
+                redaction_token("1.2.3.4"),
+                redaction_token("host.com")
+            )
+        );
+        assert_eq!(warn, format!("from {}", redaction_token("1.2.3.4")));
+        assert_eq!(debug, "from 1.2.3.4".to_string());
+        assert_eq!(is_redacting(), false);
+    }
+
+    #[test]
+    fn redaction_tokens_are_stable_within_a_session_and_distinct_for_distinct_values() {
+        let first = redaction_token("1.2.3.4");
+        let second = redaction_token("1.2.3.4");
+        let other = redaction_token("4.3.2.1");
+
+        assert_eq!(first, second);
+        assert_ne!(first, other);
+        assert_eq!(first.starts_with(REDACTION_TOKEN_PREFIX), true);
+        assert_eq!(first.len(), REDACTION_TOKEN_PREFIX.len() + 9);
+        assert_eq!(first.contains("1.2.3.4"), false);
+    }
+
+    #[test]
+    fn redaction_is_restored_even_if_composition_panics() {
+        let result = thread::spawn(|| {
+            set_redacted_levels_for_test_thread(&[Level::Error]);
+            let _ = std::panic::catch_unwind(|| {
+                compose_log_message(Level::Error, || panic!("composition failed"))
+            });
+            is_redacting()
+        })
+        .join()
+        .unwrap();
+
+        assert_eq!(result, false);
+    }
+
+    #[test]
+    fn redacted_levels_are_reported_for_the_test_thread() {
+        set_redacted_levels_for_test_thread(&[Level::Trace, Level::Error]);
+
+        let result = redacted_levels();
+
+        assert_eq!(result, vec![Level::Error, Level::Trace]);
+        assert_eq!(is_redacted_level(Level::Trace), true);
+        assert_eq!(is_redacted_level(Level::Warn), false);
+    }
+}
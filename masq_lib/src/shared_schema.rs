@@ -18,7 +18,14 @@ pub const BLOCKCHAIN_SERVICE_HELP: &str =
      https://polygon-mainnet.infura.io/v3/YOUR-PROJECT-ID";
 pub const CHAIN_HELP: &str =
     "The blockchain network MASQ Node will configure itself to use. You must ensure the \
-    Ethereum client specified by --blockchain-service-url communicates with the same blockchain network.";
+    Ethereum client specified by --blockchain-service-url communicates with the same blockchain network. \
+    Besides the official chains, any chain defined in chains.toml in the data directory can be named here.";
+pub const CLANDESTINE_TLS_HELP: &str =
+    "If 'on', this Node advertises TLS support in its Gossip and wraps the clandestine connections it \
+    opens to other Nodes that advertise it in TLS 1.3, using a self-signed certificate bound to its public \
+    key and insisting that the Node at the other end prove the same. Connections to Nodes that don't \
+    advertise TLS, and connections from Nodes that don't use it, stay in the clear, so a Node with TLS 'on' \
+    can share a network with Nodes that have it 'off'. Default is 'off'.";
 pub const CONFIG_FILE_HELP: &str =
     "Optional TOML file containing configuration that doesn't often change. Should contain only \
      scalar items, string or numeric, whose names are exactly the same as the command-line parameters \
@@ -33,6 +40,13 @@ pub const CONSUMING_PRIVATE_KEY_HELP: &str = "The private key for the Ethereum w
      make sure you haven't already set up a consuming wallet with a derivation path, and make sure that you always \
      supply exactly the same private key every time you run the Node. A consuming private key is 64 case-insensitive \
      hexadecimal digits.";
+pub const CONSUMING_KEYSTORE_HELP: &str = "Path to a Web3 Secret Storage (keystore v3) JSON file holding the \
+     private key for the consuming wallet, as an alternative to --consuming-private-key. It is unlocked with \
+     --consuming-keystore-passphrase, and the same rules apply: don't combine it with a consuming wallet set up \
+     with a derivation path, and always supply the same key every time you run the Node.";
+pub const CONSUMING_KEYSTORE_PASSPHRASE_HELP: &str = "The passphrase that unlocks the file given by \
+     --consuming-keystore. Like the private key itself, this value is very sensitive: the environment is a \
+     better place for it than the command line or the config file.";
 pub const DATA_DIRECTORY_HELP: &str =
     "Directory in which the Node will store its persistent state, including at least its database \
     and by default its configuration file as well.\nNote: any existing database in the data directory \
@@ -50,6 +64,16 @@ pub const EARNING_WALLET_HELP: &str =
      (case-insensitive). If you already have a derivation-path earning wallet, don't supply this. \
      If you have supplied an earning wallet address before, either don't supply it again or be \
      careful to supply exactly the same one you supplied before.";
+pub const EXTERNAL_SIGNER_HELP: &str = "The endpoint of an external signer, such as Clef, that holds the consuming \
+     wallet's private key so the Node never sees it. Use an http:// or https:// URL for a signer listening over HTTP, \
+     or ipc:// followed by the path of its Unix socket. Requires --external-signer-account, and can't be combined \
+     with --consuming-private-key or --consuming-keystore.";
+pub const EXTERNAL_SIGNER_ACCOUNT_HELP: &str = "The address of the consuming wallet whose key is held by the \
+     signer given with --external-signer: 0x followed by 40 hexadecimal digits. The signer will be asked to sign \
+     every transaction the Node sends from this address.";
+pub const EXTERNAL_SIGNER_TIMEOUT_HELP: &str = "How many seconds the Node waits for an ipc:// external signer \
+     to answer a signing request before giving up on it. Leave room for someone to approve the request at the \
+     signer. Default is 300.";
 pub const IP_ADDRESS_HELP: &str = "The public IP address of your MASQ Node: that is, the IPv4 \
      address at which other Nodes can contact yours. If you're running your Node behind \
      a router, this will be the IP address of the router. If this IP address starts with 192.168 or 10.0, \
@@ -61,6 +85,11 @@ pub const LOG_LEVEL_HELP: &str =
      generates a lot of log traffic. This will both consume your disk space and degrade your Node's performance. \
      You should probably not specify a level higher than the default unless you have security concerns about \
      persistent logs being kept on your computer: if your Node crashes, it's good to know why.";
+pub const LOG_REDACTION_HELP: &str =
+    "The log levels at which IP addresses, hostnames and public keys are replaced, in the logfile and in \
+     messages to UIs, by tokens like <redacted:1a2b3c4d>. The same value always gets the same token while \
+     the Node runs, but tokens change every time it starts. Supply a comma-separated list of levels, such as \
+     'error,warn,info', or 'all'. By default nothing is redacted.";
 pub const NEIGHBORS_HELP: &str = "One or more Node descriptors for running Nodes in the MASQ \
      One or more Node descriptors for active Nodes in the MASQ Network to which you'd like your Node to connect \
      on startup. A Node descriptor looks similar to one of these:\n\n\
@@ -111,6 +140,12 @@ pub const MAPPING_PROTOCOL_HELP: &str =
     public IP address with the --ip parameter. If the Node communicates successfully with your router, \
     it will remember the protocol it used, and on its next run it will try that protocol first, unless \
     you specify a different protocol on the command line.";
+pub const MASQUERADER_HELP: &str =
+    "How this Node disguises the traffic it sends to other Nodes. With 'json' (the default), \
+    clandestine data looks like a stream of JSON objects. With 'websocket', the Node opens each connection \
+    it initiates with an HTTP/1.1 WebSocket upgrade handshake and then sends its data as WebSocket frames. \
+    The masquerade is negotiated per connection: every Node can read both, Nodes that are asked to upgrade \
+    answer in WebSocket frames, and if a neighbor doesn't accept the upgrade, the connection falls back to json.";
 pub const MIN_HOPS_HELP: &str =
     "The Node is a system that routes data through multiple Nodes to enhance security and privacy. \
     However, the level of anonymity and security provided depends on the number of hops specified \
@@ -136,6 +171,11 @@ pub const REAL_USER_HELP: &str =
      run with root privilege after bootstrapping, you might want to use this if you start the Node as root, or if \
      you start the Node using pkexec or some other method that doesn't populate the SUDO_xxx variables. Use a value \
      like <uid>:<gid>:<home directory>.";
+pub const ROUTE_LIFETIME_HELP: &str =
+    "How many minutes streams to the same domain keep sharing a route. Streams to different domains get \
+     routes that avoid each other's relay and exit Nodes wherever the network allows it. When a domain's route \
+     reaches this age, the next new stream to it gets a fresh route; streams already open keep theirs. \
+     The default is 10 minutes.";
 pub const SCANS_HELP: &str =
     "The Node, when running, performs various periodic scans, including scanning for payables that need to be paid, \
     for pending payables that have arrived (and are no longer pending), for incoming receivables that need to be \
@@ -156,6 +196,32 @@ pub const RATE_PACK_HELP: &str = "\
      while the Node acts as the exit Node.\n\n\
      4. Exit Service Rate: This parameter indicates an amount of MASQ in wei demanded to provide services, unpacking and \
      repacking 1 CORES package, while the Node acts as the exit Node.";
+pub const BANDWIDTH_LIMITS_HELP: &str = "\
+     These five parameters cap the bandwidth your Node will spend on other Nodes' traffic, in kilobytes (1,000 bytes) \
+     per second. A value of 0 means no cap. These are ever present values, defaulted to 0 if left unspecified. The \
+     parameters must be always supplied all together, delimited by vertical bars and in the right order.\n\n\
+     1. Upload: Total rate at which the Node will write relayed traffic to its neighbors.\n\n\
+     2. Download: Total rate at which the Node will read responses from servers while acting as an exit Node.\n\n\
+     3. Relay: Rate for traffic the Node relays to other Nodes, applied on top of the upload cap.\n\n\
+     4. Exit: Rate for traffic the Node fetches as an exit Node, applied on top of the download cap.\n\n\
+     5. Per Neighbor: Rate at which the Node will write relayed traffic to any single neighbor.";
+pub const DATA_QUOTA_HELP: &str = "\
+     These two parameters limit how much data your Node will route for other Nodes, in megabytes (1,000,000 bytes). \
+     A value of 0 means no limit. When either limit is reached, the Node stops offering to route data until the \
+     period (UTC) renews. These are ever present values, defaulted to 0 if left unspecified. The parameters must be \
+     always supplied all together, delimited by vertical bars and in the right order.\n\n\
+     1. Daily: Megabytes the Node will relay or fetch per calendar day.\n\n\
+     2. Monthly: Megabytes the Node will relay or fetch per calendar month.";
+pub const FEE_BUMPING_HELP: &str = "\
+     These three parameters control how the Node treats its own payment transactions that are stuck waiting \
+     for a receipt because they were priced too low for the current gas market. These are ever present values, defaulted \
+     if left unspecified. The parameters must be always supplied all together, delimited by vertical bars and in the right \
+     order.\n\n\
+     1. Bump After Sec: Amount of seconds a pending payment may go without a receipt, counted from its sending or from its \
+     last replacement, before the Node re-signs it with the same nonce and a higher gas price.\n\n\
+     2. Gas Price Increase Percent: How much higher, in percent, the gas price of the replacement transaction will be. \
+     Blockchain nodes usually refuse replacements cheaper than 110 % of the original, so the value must be at least 10.\n\n\
+     3. Max Bumps: How many times a single payment may be replaced. A value of 0 turns off the automatic replacement.";
 pub const PAYMENT_THRESHOLDS_HELP: &str = "\
      These are parameters that define thresholds to determine when and how much to pay other Nodes for routing and \
      exit services and the expectations the Node should have for receiving payments from other Nodes for routing and \
@@ -180,6 +246,20 @@ pub const PAYMENT_THRESHOLDS_HELP: &str = "\
      6. Unban Below gwei: When a delinquent Node has been banned due to non-payment, the receivables balance must be paid \
      below this level -- in gwei of MASQ -- to cause them to be unbanned. In most cases, you'll want this to be set the same \
      as Permanent Debt Allowed gwei.";
+pub const UI_ACCESS_FILE_HELP: &str =
+    "Path to a TOML file listing the tokens that UIs may present to the remote UI listener (see --ui-remote-port). \
+     Each [[token]] entry has a 'token' string and either an 'access' of 'admin' or 'read-only', an 'opcodes' list \
+     naming the messages that token may send, or both. Keep this file private: anyone with a token can use it.";
+pub const UI_REMOTE_PORT_HELP: &str =
+    "A port on all interfaces at which UIs on other computers may connect to the Node over TLS, presenting one of \
+     the tokens in --ui-access-file. If you don't specify this, UIs can connect only from this computer, on \
+     --ui-port.";
+pub const UI_TLS_CERT_HELP: &str =
+    "Path to a PEM file with the certificate (and any intermediate certificates) the remote UI listener presents. \
+     If you don't specify this and --ui-tls-key, the Node makes a self-signed certificate in its data directory, \
+     as ui_tls_cert.pem, for masq to trust with --tls.";
+pub const UI_TLS_KEY_HELP: &str =
+    "Path to a PEM file with the private key for the certificate in --ui-tls-cert.";
 pub const SCAN_INTERVALS_HELP:&str = "\
      These three intervals describe the length of three different scan cycles running automatically in the background \
      since the Node has connected to a qualified neighborhood that consists of neighbors enabling a complete 3-hop \
@@ -199,6 +279,17 @@ pub const SCAN_INTERVALS_HELP:&str = "\
      3. Receivable Scan Interval: Amount of seconds between two sequential cycles of scanning for payments on the \
      blockchain that have been sent by our creditors to us, which are credited against receivables recorded for services \
      provided.";
+pub const SPENDING_BUDGET_HELP: &str = "\
+     These four parameters cap how much your Node will spend on routing and exit services it consumes, in gwei \
+     of MASQ. A limit of 0 means no cap. When a limit is reached, the Node refuses to open new streams for its clients \
+     until the period (UTC) renews; streams already open are allowed to finish. Spending is counted from the moment the \
+     Node started. These are ever present values, defaulted to 0|0|80|95 if left unspecified. The parameters must be \
+     always supplied all together, delimited by vertical bars and in the right order.\n\n\
+     1. Daily gwei: Amount the Node may spend per calendar day.\n\n\
+     2. Monthly gwei: Amount the Node may spend per calendar month.\n\n\
+     3. First Warning Percent: Share of a limit, in percent, at which the Node logs a first warning.\n\n\
+     4. Second Warning Percent: Share of a limit, in percent, at which the Node logs a second warning. It must not be \
+     lower than the first one and must not exceed 100.";
 
 lazy_static! {
     pub static ref DEFAULT_UI_PORT_VALUE: String = DEFAULT_UI_PORT.to_string();
@@ -230,7 +321,7 @@ pub fn chain_arg<'a>() -> Arg<'a, 'a> {
         .value_name("CHAIN")
         .min_values(0)
         .max_values(1)
-        .possible_values(official_chain_names())
+        .validator(common_validators::validate_chain)
         .help(CHAIN_HELP)
 }
 
@@ -312,6 +403,22 @@ pub fn min_hops_arg<'a>() -> Arg<'a, 'a> {
         .help(MIN_HOPS_HELP)
 }
 
+pub fn bandwidth_limits_arg<'a>() -> Arg<'a, 'a> {
+    common_parameter_with_separate_u64_values("bandwidth-limits", BANDWIDTH_LIMITS_HELP)
+}
+
+pub fn data_quota_arg<'a>() -> Arg<'a, 'a> {
+    common_parameter_with_separate_u64_values("data-quota", DATA_QUOTA_HELP)
+}
+
+pub fn fee_bumping_arg<'a>() -> Arg<'a, 'a> {
+    common_parameter_with_separate_u64_values("fee-bumping", FEE_BUMPING_HELP)
+}
+
+pub fn spending_budget_arg<'a>() -> Arg<'a, 'a> {
+    common_parameter_with_separate_u64_values("spending-budget", SPENDING_BUDGET_HELP)
+}
+
 #[cfg(not(target_os = "windows"))]
 pub fn real_user_arg<'a>() -> Arg<'a, 'a> {
     Arg::with_name("real-user")
@@ -373,6 +480,14 @@ pub fn shared_app(head: App<'static, 'static>) -> App<'static, 'static> {
             .validator(common_validators::validate_clandestine_port)
             .help(&CLANDESTINE_PORT_HELP),
     )
+    .arg(
+        Arg::with_name("clandestine-tls")
+            .long("clandestine-tls")
+            .value_name("CLANDESTINE-TLS")
+            .takes_value(true)
+            .possible_values(&["on", "off"])
+            .help(CLANDESTINE_TLS_HELP),
+    )
     .arg(config_file_arg())
     .arg(
         Arg::with_name("consuming-private-key")
@@ -383,6 +498,22 @@ pub fn shared_app(head: App<'static, 'static>) -> App<'static, 'static> {
             .validator(common_validators::validate_private_key)
             .help(CONSUMING_PRIVATE_KEY_HELP),
     )
+    .arg(
+        Arg::with_name("consuming-keystore")
+            .long("consuming-keystore")
+            .value_name("KEYSTORE-FILE")
+            .min_values(0)
+            .max_values(1)
+            .help(CONSUMING_KEYSTORE_HELP),
+    )
+    .arg(
+        Arg::with_name("consuming-keystore-passphrase")
+            .long("consuming-keystore-passphrase")
+            .value_name("KEYSTORE-PASSPHRASE")
+            .min_values(0)
+            .max_values(1)
+            .help(CONSUMING_KEYSTORE_PASSPHRASE_HELP),
+    )
     .arg(
         Arg::with_name("crash-point")
             .long("crash-point")
@@ -408,6 +539,32 @@ pub fn shared_app(head: App<'static, 'static>) -> App<'static, 'static> {
         EARNING_WALLET_HELP,
         common_validators::validate_ethereum_address,
     ))
+    .arg(
+        Arg::with_name("external-signer")
+            .long("external-signer")
+            .value_name("SIGNER-URL")
+            .min_values(0)
+            .max_values(1)
+            .help(EXTERNAL_SIGNER_HELP),
+    )
+    .arg(
+        Arg::with_name("external-signer-account")
+            .long("external-signer-account")
+            .value_name("SIGNER-ACCOUNT")
+            .min_values(0)
+            .max_values(1)
+            .validator(common_validators::validate_ethereum_address)
+            .help(EXTERNAL_SIGNER_ACCOUNT_HELP),
+    )
+    .arg(
+        Arg::with_name("external-signer-timeout")
+            .long("external-signer-timeout")
+            .value_name("SECONDS")
+            .min_values(0)
+            .max_values(1)
+            .validator(common_validators::validate_non_zero_u16)
+            .help(EXTERNAL_SIGNER_TIMEOUT_HELP),
+    )
     .arg(
         Arg::with_name("fake-public-key")
             .long("fake-public-key")
@@ -436,6 +593,15 @@ pub fn shared_app(head: App<'static, 'static>) -> App<'static, 'static> {
             .case_insensitive(true)
             .help(LOG_LEVEL_HELP),
     )
+    .arg(
+        Arg::with_name("log-redaction")
+            .long("log-redaction")
+            .value_name("LEVELS")
+            .min_values(0)
+            .max_values(1)
+            .validator(common_validators::validate_log_redaction)
+            .help(LOG_REDACTION_HELP),
+    )
     .arg(
         Arg::with_name("mapping-protocol")
             .long("mapping-protocol")
@@ -446,6 +612,16 @@ pub fn shared_app(head: App<'static, 'static>) -> App<'static, 'static> {
             .case_insensitive(true)
             .help(MAPPING_PROTOCOL_HELP),
     )
+    .arg(
+        Arg::with_name("masquerader")
+            .long("masquerader")
+            .value_name("MASQUERADER")
+            .min_values(0)
+            .max_values(1)
+            .possible_values(&["json", "websocket"])
+            .case_insensitive(true)
+            .help(MASQUERADER_HELP),
+    )
     .arg(min_hops_arg())
     .arg(
         Arg::with_name("neighborhood-mode")
@@ -465,6 +641,15 @@ pub fn shared_app(head: App<'static, 'static>) -> App<'static, 'static> {
             .help(NEIGHBORS_HELP),
     )
     .arg(real_user_arg())
+    .arg(
+        Arg::with_name("route-lifetime")
+            .long("route-lifetime")
+            .value_name("MINUTES")
+            .min_values(0)
+            .max_values(1)
+            .validator(common_validators::validate_route_lifetime)
+            .help(ROUTE_LIFETIME_HELP),
+    )
     .arg(
         Arg::with_name("scans")
             .long("scans")
@@ -485,9 +670,48 @@ pub fn shared_app(head: App<'static, 'static>) -> App<'static, 'static> {
         "payment-thresholds",
         PAYMENT_THRESHOLDS_HELP,
     ))
+    .arg(bandwidth_limits_arg())
+    .arg(data_quota_arg())
+    .arg(fee_bumping_arg())
+    .arg(spending_budget_arg())
+    .arg(
+        Arg::with_name("ui-access-file")
+            .long("ui-access-file")
+            .value_name("FILE-PATH")
+            .min_values(0)
+            .max_values(1)
+            .help(UI_ACCESS_FILE_HELP),
+    )
+    .arg(
+        Arg::with_name("ui-remote-port")
+            .long("ui-remote-port")
+            .value_name("UI-REMOTE-PORT")
+            .min_values(0)
+            .max_values(1)
+            .validator(common_validators::validate_ui_port)
+            .help(UI_REMOTE_PORT_HELP),
+    )
+    .arg(
+        Arg::with_name("ui-tls-cert")
+            .long("ui-tls-cert")
+            .value_name("FILE-PATH")
+            .min_values(0)
+            .max_values(1)
+            .help(UI_TLS_CERT_HELP),
+    )
+    .arg(
+        Arg::with_name("ui-tls-key")
+            .long("ui-tls-key")
+            .value_name("FILE-PATH")
+            .min_values(0)
+            .max_values(1)
+            .help(UI_TLS_KEY_HELP),
+    )
 }
 
 pub mod common_validators {
+    use super::official_chain_names;
+    use crate::blockchains::custom_chains::is_valid_chain_identifier;
     use crate::constants::LOWEST_USABLE_INSECURE_PORT;
     use regex::Regex;
     use std::net::IpAddr;
@@ -611,6 +835,47 @@ pub mod common_validators {
         }
     }
 
+    pub fn validate_log_redaction(levels: String) -> Result<(), String> {
+        if levels.eq_ignore_ascii_case("all") {
+            return Ok(());
+        }
+        levels.split(',').try_for_each(|level| {
+            match ["error", "warn", "info", "debug", "trace"]
+                .contains(&level.trim().to_lowercase().as_str())
+            {
+                true => Ok(()),
+                false => Err(format!(
+                    "'{}' is not a log level; supply 'all' or some of error, warn, info, debug, trace separated by commas",
+                    level
+                )),
+            }
+        })
+    }
+
+    pub fn validate_route_lifetime(minutes: String) -> Result<(), String> {
+        match minutes.parse::<u64>() {
+            Ok(minutes) if minutes > 0 => Ok(()),
+            _ => Err(format!(
+                "'{}' is not a positive whole number of minutes",
+                minutes
+            )),
+        }
+    }
+
+    // Only the official chains are known this early; whether a custom chain is really defined
+    // can't be told before the data directory, with its chains file, is settled
+    pub fn validate_chain(chain: String) -> Result<(), String> {
+        if official_chain_names().contains(&chain.as_str()) || is_valid_chain_identifier(&chain) {
+            Ok(())
+        } else {
+            Err(format!(
+                "'{}' is neither an official chain ({}) nor a possible name for a custom one",
+                chain,
+                official_chain_names().join(", ")
+            ))
+        }
+    }
+
     pub fn validate_separate_u64_values(values_with_delimiters: String) -> Result<(), String> {
         values_with_delimiters.split('|').try_for_each(|segment| {
             segment
@@ -694,7 +959,16 @@ mod tests {
         assert_eq!(
             CHAIN_HELP,
             "The blockchain network MASQ Node will configure itself to use. You must ensure the \
-             Ethereum client specified by --blockchain-service-url communicates with the same blockchain network."
+             Ethereum client specified by --blockchain-service-url communicates with the same blockchain network. \
+             Besides the official chains, any chain defined in chains.toml in the data directory can be named here."
+        );
+        assert_eq!(
+            CLANDESTINE_TLS_HELP,
+            "If 'on', this Node advertises TLS support in its Gossip and wraps the clandestine connections it \
+             opens to other Nodes that advertise it in TLS 1.3, using a self-signed certificate bound to its public \
+             key and insisting that the Node at the other end prove the same. Connections to Nodes that don't \
+             advertise TLS, and connections from Nodes that don't use it, stay in the clear, so a Node with TLS 'on' \
+             can share a network with Nodes that have it 'off'. Default is 'off'."
         );
         assert_eq!(
             CONFIG_FILE_HELP,
@@ -715,6 +989,19 @@ mod tests {
              supply exactly the same private key every time you run the Node. A consuming private key is 64 case-insensitive \
              hexadecimal digits."
         );
+        assert_eq!(
+            CONSUMING_KEYSTORE_HELP,
+            "Path to a Web3 Secret Storage (keystore v3) JSON file holding the \
+             private key for the consuming wallet, as an alternative to --consuming-private-key. It is unlocked with \
+             --consuming-keystore-passphrase, and the same rules apply: don't combine it with a consuming wallet set up \
+             with a derivation path, and always supply the same key every time you run the Node."
+        );
+        assert_eq!(
+            CONSUMING_KEYSTORE_PASSPHRASE_HELP,
+            "The passphrase that unlocks the file given by \
+             --consuming-keystore. Like the private key itself, this value is very sensitive: the environment is a \
+             better place for it than the command line or the config file."
+        );
         assert_eq!(
             DATA_DIRECTORY_HELP,
             "Directory in which the Node will store its persistent state, including at \
@@ -741,6 +1028,25 @@ mod tests {
              If you have supplied an earning wallet address before, either don't supply it again or be \
              careful to supply exactly the same one you supplied before."
         );
+        assert_eq!(
+            EXTERNAL_SIGNER_HELP,
+            "The endpoint of an external signer, such as Clef, that holds the consuming \
+             wallet's private key so the Node never sees it. Use an http:// or https:// URL for a signer listening over HTTP, \
+             or ipc:// followed by the path of its Unix socket. Requires --external-signer-account, and can't be combined \
+             with --consuming-private-key or --consuming-keystore."
+        );
+        assert_eq!(
+            EXTERNAL_SIGNER_ACCOUNT_HELP,
+            "The address of the consuming wallet whose key is held by the \
+             signer given with --external-signer: 0x followed by 40 hexadecimal digits. The signer will be asked to sign \
+             every transaction the Node sends from this address."
+        );
+        assert_eq!(
+            EXTERNAL_SIGNER_TIMEOUT_HELP,
+            "How many seconds the Node waits for an ipc:// external signer \
+             to answer a signing request before giving up on it. Leave room for someone to approve the request at the \
+             signer. Default is 300."
+        );
         assert_eq!(
             IP_ADDRESS_HELP,
             "The public IP address of your MASQ Node: that is, the IPv4 \
@@ -756,6 +1062,13 @@ mod tests {
              generates a lot of log traffic. This will both consume your disk space and degrade your Node's performance. \
              You should probably not specify a level higher than the default unless you have security concerns about \
              persistent logs being kept on your computer: if your Node crashes, it's good to know why.");
+        assert_eq!(
+            LOG_REDACTION_HELP,
+            "The log levels at which IP addresses, hostnames and public keys are replaced, in the logfile and in \
+             messages to UIs, by tokens like <redacted:1a2b3c4d>. The same value always gets the same token while \
+             the Node runs, but tokens change every time it starts. Supply a comma-separated list of levels, such as \
+             'error,warn,info', or 'all'. By default nothing is redacted."
+        );
         assert_eq!(
             NEIGHBORS_HELP,
             "One or more Node descriptors for running Nodes in the MASQ \
@@ -808,6 +1121,14 @@ mod tests {
              it will remember the protocol it used, and on its next run it will try that protocol first, unless \
              you specify a different protocol on the command line."
         );
+        assert_eq!(
+            MASQUERADER_HELP,
+            "How this Node disguises the traffic it sends to other Nodes. With 'json' (the default), \
+             clandestine data looks like a stream of JSON objects. With 'websocket', the Node opens each connection \
+             it initiates with an HTTP/1.1 WebSocket upgrade handshake and then sends its data as WebSocket frames. \
+             The masquerade is negotiated per connection: every Node can read both, Nodes that are asked to upgrade \
+             answer in WebSocket frames, and if a neighbor doesn't accept the upgrade, the connection falls back to json."
+        );
         assert_eq!(
             MIN_HOPS_HELP,
             "The Node is a system that routes data through multiple Nodes to enhance security and privacy. \
@@ -837,6 +1158,35 @@ mod tests {
              you start the Node using pkexec or some other method that doesn't populate the SUDO_xxx variables. Use a value \
              like <uid>:<gid>:<home directory>."
         );
+        assert_eq!(
+            ROUTE_LIFETIME_HELP,
+            "How many minutes streams to the same domain keep sharing a route. Streams to different domains get \
+             routes that avoid each other's relay and exit Nodes wherever the network allows it. When a domain's route \
+             reaches this age, the next new stream to it gets a fresh route; streams already open keep theirs. \
+             The default is 10 minutes."
+        );
+        assert_eq!(
+            UI_ACCESS_FILE_HELP,
+            "Path to a TOML file listing the tokens that UIs may present to the remote UI listener (see --ui-remote-port). \
+             Each [[token]] entry has a 'token' string and either an 'access' of 'admin' or 'read-only', an 'opcodes' list \
+             naming the messages that token may send, or both. Keep this file private: anyone with a token can use it."
+        );
+        assert_eq!(
+            UI_REMOTE_PORT_HELP,
+            "A port on all interfaces at which UIs on other computers may connect to the Node over TLS, presenting one of \
+             the tokens in --ui-access-file. If you don't specify this, UIs can connect only from this computer, on \
+             --ui-port."
+        );
+        assert_eq!(
+            UI_TLS_CERT_HELP,
+            "Path to a PEM file with the certificate (and any intermediate certificates) the remote UI listener presents. \
+             If you don't specify this and --ui-tls-key, the Node makes a self-signed certificate in its data directory, \
+             as ui_tls_cert.pem, for masq to trust with --tls."
+        );
+        assert_eq!(
+            UI_TLS_KEY_HELP,
+            "Path to a PEM file with the private key for the certificate in --ui-tls-cert."
+        );
 
         assert_eq!(
             DEFAULT_UI_PORT_VALUE.to_string(),
@@ -884,6 +1234,38 @@ mod tests {
              4. Exit Service Rate: This parameter indicates an amount of MASQ in wei demanded to provide services, unpacking and \
              repacking 1 CORES package, while the Node acts as the exit Node."
         );
+        assert_eq!(
+            BANDWIDTH_LIMITS_HELP,
+            "These five parameters cap the bandwidth your Node will spend on other Nodes' traffic, in kilobytes (1,000 bytes) \
+             per second. A value of 0 means no cap. These are ever present values, defaulted to 0 if left unspecified. The \
+             parameters must be always supplied all together, delimited by vertical bars and in the right order.\n\n\
+             1. Upload: Total rate at which the Node will write relayed traffic to its neighbors.\n\n\
+             2. Download: Total rate at which the Node will read responses from servers while acting as an exit Node.\n\n\
+             3. Relay: Rate for traffic the Node relays to other Nodes, applied on top of the upload cap.\n\n\
+             4. Exit: Rate for traffic the Node fetches as an exit Node, applied on top of the download cap.\n\n\
+             5. Per Neighbor: Rate at which the Node will write relayed traffic to any single neighbor."
+        );
+        assert_eq!(
+            DATA_QUOTA_HELP,
+            "These two parameters limit how much data your Node will route for other Nodes, in megabytes (1,000,000 bytes). \
+             A value of 0 means no limit. When either limit is reached, the Node stops offering to route data until the \
+             period (UTC) renews. These are ever present values, defaulted to 0 if left unspecified. The parameters must be \
+             always supplied all together, delimited by vertical bars and in the right order.\n\n\
+             1. Daily: Megabytes the Node will relay or fetch per calendar day.\n\n\
+             2. Monthly: Megabytes the Node will relay or fetch per calendar month."
+        );
+        assert_eq!(
+            FEE_BUMPING_HELP,
+            "These three parameters control how the Node treats its own payment transactions that are stuck waiting \
+             for a receipt because they were priced too low for the current gas market. These are ever present values, defaulted \
+             if left unspecified. The parameters must be always supplied all together, delimited by vertical bars and in the right \
+             order.\n\n\
+             1. Bump After Sec: Amount of seconds a pending payment may go without a receipt, counted from its sending or from its \
+             last replacement, before the Node re-signs it with the same nonce and a higher gas price.\n\n\
+             2. Gas Price Increase Percent: How much higher, in percent, the gas price of the replacement transaction will be. \
+             Blockchain nodes usually refuse replacements cheaper than 110 % of the original, so the value must be at least 10.\n\n\
+             3. Max Bumps: How many times a single payment may be replaced. A value of 0 turns off the automatic replacement."
+        );
         assert_eq!(
             PAYMENT_THRESHOLDS_HELP,
             "These are parameters that define thresholds to determine when and how much to pay other Nodes for routing and \
@@ -929,6 +1311,19 @@ mod tests {
              3. Receivable Scan Interval: Amount of seconds between two sequential cycles of scanning for payments on the \
              blockchain that have been sent by our creditors to us, which are credited against receivables recorded for services \
              provided."
+        );
+        assert_eq!(
+            SPENDING_BUDGET_HELP,
+            "These four parameters cap how much your Node will spend on routing and exit services it consumes, in gwei \
+             of MASQ. A limit of 0 means no cap. When a limit is reached, the Node refuses to open new streams for its clients \
+             until the period (UTC) renews; streams already open are allowed to finish. Spending is counted from the moment the \
+             Node started. These are ever present values, defaulted to 0|0|80|95 if left unspecified. The parameters must be \
+             always supplied all together, delimited by vertical bars and in the right order.\n\n\
+             1. Daily gwei: Amount the Node may spend per calendar day.\n\n\
+             2. Monthly gwei: Amount the Node may spend per calendar month.\n\n\
+             3. First Warning Percent: Share of a limit, in percent, at which the Node logs a first warning.\n\n\
+             4. Second Warning Percent: Share of a limit, in percent, at which the Node logs a second warning. It must not be \
+             lower than the first one and must not exceed 100."
         )
     }
 
@@ -1068,6 +1463,73 @@ mod tests {
         assert_eq!(result, Err(String::from("0x0")));
     }
 
+    #[test]
+    fn validate_log_redaction_accepts_all_or_a_list_of_levels() {
+        assert_eq!(
+            common_validators::validate_log_redaction("ALL".to_string()),
+            Ok(())
+        );
+        assert_eq!(
+            common_validators::validate_log_redaction("error, Warn,info".to_string()),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn validate_log_redaction_rejects_unknown_levels() {
+        let result = common_validators::validate_log_redaction("warn,loud".to_string());
+
+        assert_eq!(
+            result,
+            Err("'loud' is not a log level; supply 'all' or some of error, warn, info, debug, trace separated by commas".to_string())
+        )
+    }
+
+    #[test]
+    fn validate_route_lifetime_accepts_positive_minutes() {
+        assert_eq!(
+            common_validators::validate_route_lifetime("15".to_string()),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn validate_route_lifetime_rejects_zero_and_junk() {
+        assert_eq!(
+            common_validators::validate_route_lifetime("0".to_string()),
+            Err("'0' is not a positive whole number of minutes".to_string())
+        );
+        assert_eq!(
+            common_validators::validate_route_lifetime("soon".to_string()),
+            Err("'soon' is not a positive whole number of minutes".to_string())
+        );
+    }
+
+    #[test]
+    fn validate_chain_accepts_official_chains_and_custom_identifiers() {
+        official_chain_names().iter().for_each(|name| {
+            assert_eq!(common_validators::validate_chain(name.to_string()), Ok(()))
+        });
+        assert_eq!(
+            common_validators::validate_chain("my-devnet-2".to_string()),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn validate_chain_rejects_what_cannot_name_a_chain() {
+        assert_eq!(
+            common_validators::validate_chain("Polygon Mainnet".to_string()),
+            Err(
+                "'Polygon Mainnet' is neither an official chain (polygon-mainnet, eth-mainnet, \
+                base-mainnet, base-sepolia, polygon-amoy, eth-ropsten, dev) nor a possible name \
+                for a custom one"
+                    .to_string()
+            )
+        );
+        assert!(common_validators::validate_chain("../etc".to_string()).is_err());
+    }
+
     #[test]
     fn validate_separate_u64_values_happy_path() {
         let result = common_validators::validate_separate_u64_values("4567|1111|444".to_string());
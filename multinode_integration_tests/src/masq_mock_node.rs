@@ -11,7 +11,7 @@ use masq_lib::test_utils::utils::TEST_DEFAULT_MULTINODE_CHAIN;
 use node_lib::hopper::live_cores_package::LiveCoresPackage;
 use node_lib::json_masquerader::JsonMasquerader;
 use node_lib::masquerader::{MasqueradeError, Masquerader};
-use node_lib::neighborhood::gossip::Gossip_0v1;
+use node_lib::neighborhood::gossip::Gossip_0v2;
 use node_lib::neighborhood::node_record::NodeRecord;
 use node_lib::sub_lib::cryptde::CryptData;
 use node_lib::sub_lib::cryptde::PublicKey;
@@ -147,6 +147,10 @@ impl MASQNode for MASQMockNode {
     fn routes_data(&self) -> bool {
         true // just a guess
     }
+
+    fn accepts_tls(&self) -> bool {
+        false
+    }
 }
 
 pub struct MutableMASQMockNode {
@@ -271,7 +275,7 @@ impl MASQMockNode {
     pub fn transmit_gossip(
         &self,
         transmit_port: u16,
-        gossip: Gossip_0v1,
+        gossip: Gossip_0v2,
         target_key: &PublicKey,
         target_addr: SocketAddr,
     ) -> Result<(), Error> {
@@ -422,7 +426,7 @@ impl MASQMockNode {
         }
     }
 
-    pub fn wait_for_gossip(&self, timeout: Duration) -> Option<(Gossip_0v1, IpAddr)> {
+    pub fn wait_for_gossip(&self, timeout: Duration) -> Option<(Gossip_0v2, IpAddr)> {
         let masquerader = JsonMasquerader::new();
         match self.wait_for_package(&masquerader, timeout) {
             Ok((from, _, package)) => {
@@ -436,7 +440,7 @@ impl MASQMockNode {
                 };
                 match incoming_cores_package.payload {
                     MessageType::Gossip(vd) => Some((
-                        Gossip_0v1::try_from(vd).expect("Couldn't deserialize Gossip"),
+                        Gossip_0v2::try_from(vd).expect("Couldn't deserialize Gossip"),
                         from.ip(),
                     )),
                     _ => panic!("Expected Gossip, got something else"),
@@ -210,6 +210,8 @@ pub trait MASQNode: Any {
     fn chain(&self) -> Chain;
     fn accepts_connections(&self) -> bool;
     fn routes_data(&self) -> bool;
+    // Whether this Node advertises that it will talk TLS on its clandestine port.
+    fn accepts_tls(&self) -> bool;
 }
 
 pub struct MASQNodeUtils {}
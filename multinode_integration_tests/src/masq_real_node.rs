@@ -135,6 +135,7 @@ pub struct NodeStartupConfig {
     pub scans_opt: Option<bool>,
     pub log_level_opt: Option<Level>,
     pub ui_port_opt: Option<u16>,
+    pub clandestine_tls: bool,
 }
 
 impl Default for NodeStartupConfig {
@@ -167,6 +168,7 @@ impl NodeStartupConfig {
             scans_opt: None,
             log_level_opt: None,
             ui_port_opt: None,
+            clandestine_tls: false,
         }
     }
 
@@ -223,6 +225,10 @@ impl NodeStartupConfig {
         }
         args.push("--chain".to_string());
         args.push(self.chain.rec().literal_identifier.to_string());
+        if self.clandestine_tls {
+            args.push("--clandestine-tls".to_string());
+            args.push("on".to_string());
+        }
 
         if let Some(ref db_password) = self.db_password_opt {
             args.push("--db-password".to_string());
@@ -430,6 +436,7 @@ pub struct NodeStartupConfigBuilder {
     log_level_opt: Option<Level>,
     ui_port_opt: Option<u16>,
     db_password: Option<String>,
+    clandestine_tls: bool,
 }
 
 impl NodeStartupConfigBuilder {
@@ -486,6 +493,7 @@ impl NodeStartupConfigBuilder {
             log_level_opt: None,
             ui_port_opt: None,
             db_password: Some("password".to_string()),
+            clandestine_tls: false,
         }
     }
 
@@ -512,6 +520,7 @@ impl NodeStartupConfigBuilder {
             log_level_opt: config.log_level_opt,
             ui_port_opt: config.ui_port_opt,
             db_password: config.db_password_opt.clone(),
+            clandestine_tls: config.clandestine_tls,
         }
     }
 
@@ -646,6 +655,11 @@ impl NodeStartupConfigBuilder {
         self
     }
 
+    pub fn clandestine_tls(mut self, value: bool) -> Self {
+        self.clandestine_tls = value;
+        self
+    }
+
     pub fn build(self) -> NodeStartupConfig {
         NodeStartupConfig {
             neighborhood_mode: self.neighborhood_mode,
@@ -669,6 +683,7 @@ impl NodeStartupConfigBuilder {
             scans_opt: self.scans_opt,
             log_level_opt: self.log_level_opt,
             ui_port_opt: self.ui_port_opt,
+            clandestine_tls: self.clandestine_tls,
         }
     }
 }
@@ -760,6 +775,10 @@ impl MASQNode for MASQRealNode {
     fn routes_data(&self) -> bool {
         self.guts.routes_data
     }
+
+    fn accepts_tls(&self) -> bool {
+        self.guts.accepts_connections && self.guts.startup_config.clandestine_tls
+    }
 }
 
 impl MASQRealNode {
@@ -1395,6 +1414,7 @@ mod tests {
             scans_opt: Some(false),
             log_level_opt: Some(Level::Info),
             ui_port_opt: Some(4321),
+            clandestine_tls: true,
         };
         let neighborhood_mode = "standard".to_string();
         let ip_addr = IpAddr::from_str("1.2.3.4").unwrap();
@@ -1458,6 +1478,7 @@ mod tests {
         assert_eq!(result.scans_opt, Some(false));
         assert_eq!(result.log_level_opt, Some(Level::Info));
         assert_eq!(result.ui_port_opt, Some(4321));
+        assert_eq!(result.clandestine_tls, true);
         assert_eq!(
             result.payment_thresholds,
             PaymentThresholds {
@@ -2,7 +2,7 @@
 use crate::masq_node::MASQNode;
 use masq_lib::blockchains::chains::Chain;
 use masq_lib::test_utils::utils::TEST_DEFAULT_MULTINODE_CHAIN;
-use node_lib::neighborhood::gossip::{GossipNodeRecord, Gossip_0v1};
+use node_lib::neighborhood::gossip::{GossipNodeRecord, Gossip_0v2};
 use node_lib::neighborhood::AccessibleGossipRecord;
 use node_lib::sub_lib::cryptde::PublicKey;
 use node_lib::sub_lib::cryptde_null::CryptDENull;
@@ -25,7 +25,7 @@ pub enum GossipType {
 /// "introducee" of an Introduction pair is already there or not; therefore, it may misidentify
 /// two-Node Standard Gossip as an Introduction. If you know the Gossip you're getting is Standard
 /// Gossip, even though it has two Nodes, use Standard::from(gossip.try_into().unwrap()) to wrap it.
-pub fn parse_gossip(gossip: &Gossip_0v1, sender: IpAddr) -> GossipType {
+pub fn parse_gossip(gossip: &Gossip_0v2, sender: IpAddr) -> GossipType {
     let agrs = gossip
         .node_records
         .iter()
@@ -61,7 +61,7 @@ pub trait MultinodeGossip {
     fn gnr(&self, key: &PublicKey) -> Option<GossipNodeRecord>;
     fn agr(&self, key: &PublicKey) -> Option<AccessibleGossipRecord>;
     fn agr_mut(&mut self, key: &PublicKey) -> Option<&mut AccessibleGossipRecord>;
-    fn render(&self) -> Gossip_0v1;
+    fn render(&self) -> Gossip_0v2;
 }
 
 #[derive(PartialEq, Eq, Clone, Debug)]
@@ -110,13 +110,13 @@ impl MultinodeGossip for SingleNode {
         }
     }
 
-    fn render(&self) -> Gossip_0v1 {
-        Gossip_0v1::new(vec![GossipNodeRecord::from(self.node.clone())])
+    fn render(&self) -> Gossip_0v2 {
+        Gossip_0v2::new(vec![GossipNodeRecord::from(self.node.clone())])
     }
 }
 
-impl From<Gossip_0v1> for SingleNode {
-    fn from(gossip: Gossip_0v1) -> Self {
+impl From<Gossip_0v2> for SingleNode {
+    fn from(gossip: Gossip_0v2) -> Self {
         let agrs: Vec<AccessibleGossipRecord> = gossip.try_into().unwrap();
         if agrs.len() != 1 {
             panic! ("Can't create SingleNode from Gossip with {} records, only from Gossip with 1 record", agrs.len())
@@ -197,12 +197,13 @@ impl MultinodeGossip for Introduction {
         }
     }
 
-    fn render(&self) -> Gossip_0v1 {
-        Gossip_0v1 {
+    fn render(&self) -> Gossip_0v2 {
+        Gossip_0v2 {
             node_records: vec![
                 GossipNodeRecord::from(self.introducer.clone()),
                 GossipNodeRecord::from(self.introducee.clone()),
             ],
+            ..Gossip_0v2::new(vec![])
         }
     }
 }
@@ -289,8 +290,8 @@ impl MultinodeGossip for Standard {
         }
     }
 
-    fn render(&self) -> Gossip_0v1 {
-        Gossip_0v1::new(
+    fn render(&self) -> Gossip_0v2 {
+        Gossip_0v2::new(
             self.nodes
                 .iter()
                 .map(|agr| GossipNodeRecord::from(agr.clone()))
@@ -6,7 +6,7 @@ use crate::masq_node_cluster::MASQNodeCluster;
 use crate::masq_real_node::{make_consuming_wallet_info, NodeStartupConfigBuilder};
 use crate::masq_real_node::{MASQRealNode, NodeStartupConfig};
 use crate::multinode_gossip::{Standard, StandardBuilder};
-use node_lib::neighborhood::gossip::Gossip_0v1;
+use node_lib::neighborhood::gossip::Gossip_0v2;
 use node_lib::neighborhood::gossip_producer::{GossipProducer, GossipProducerReal};
 use node_lib::neighborhood::neighborhood_database::NeighborhoodDatabase;
 use node_lib::neighborhood::node_record::{NodeRecord, NodeRecordMetadata};
@@ -166,8 +166,8 @@ fn make_and_send_final_setup_gossip(
             cloned_node.resign();
             gossip_db.add_node(cloned_node).unwrap();
         });
-    let gossip: Gossip_0v1 = GossipProducerReal::new()
-        .produce(&mut gossip_db, real_node.main_public_key())
+    let gossip: Gossip_0v2 = GossipProducerReal::new()
+        .produce(&mut gossip_db, real_node.main_public_key(), None)
         .unwrap();
     gossip_source_mock_node
         .transmit_multinode_gossip(real_node, &Standard::from(&gossip.try_into().unwrap()))
@@ -259,6 +259,7 @@ fn from_masq_node_to_node_record(masq_node: &dyn MASQNode) -> NodeRecord {
             last_update: time_t_timestamp(),
             node_addr_opt: agr.node_addr_opt.clone(),
             unreachable_hosts: Default::default(),
+            unverified: false,
         },
         signed_gossip: agr.signed_gossip.clone(),
         signature: agr.signature,
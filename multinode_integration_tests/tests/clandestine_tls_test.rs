@@ -0,0 +1,81 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use masq_lib::utils::index_of;
+use multinode_integration_tests_lib::masq_node::{MASQNode, MASQNodeUtils};
+use multinode_integration_tests_lib::masq_node_cluster::MASQNodeCluster;
+use multinode_integration_tests_lib::masq_real_node::{
+    make_consuming_wallet_info, MASQRealNode, NodeStartupConfigBuilder,
+};
+use node_lib::sub_lib::neighborhood::Hops;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn data_can_be_routed_through_a_cluster_whose_nodes_all_use_tls() {
+    let mut cluster = MASQNodeCluster::start().unwrap();
+    let first_node = cluster.start_real_node(
+        NodeStartupConfigBuilder::standard()
+            .min_hops(Hops::OneHop)
+            .chain(cluster.chain)
+            .consuming_wallet_info(make_consuming_wallet_info("first_node"))
+            .clandestine_tls(true)
+            .build(),
+    );
+    let nodes = (0..3)
+        .map(|i| {
+            cluster.start_real_node(
+                NodeStartupConfigBuilder::standard()
+                    .min_hops(Hops::OneHop)
+                    .neighbor(first_node.node_reference())
+                    .consuming_wallet_info(make_consuming_wallet_info(&format!("node_{i}")))
+                    .chain(cluster.chain)
+                    .clandestine_tls(true)
+                    .build(),
+            )
+        })
+        .collect::<Vec<MASQRealNode>>();
+    thread::sleep(Duration::from_millis(500 * (nodes.len() as u64)));
+
+    let last_node = nodes.last().unwrap();
+    let mut client = last_node.make_client(8080, 5000);
+    client.send_chunk(b"GET / HTTP/1.1\r\nHost: www.example.com\r\n\r\n");
+    let response = client.wait_for_chunk();
+
+    assert_eq!(
+        index_of(&response, &b"<h1>Example Domain</h1>"[..]).is_some(),
+        true,
+        "Actual response:\n{}",
+        String::from_utf8(response).unwrap()
+    );
+    MASQNodeUtils::wrote_log_containing(
+        last_node.name(),
+        r"Connecting to \d+\.\d+\.\d+\.\d+:\d+ over TLS",
+        Duration::from_secs(1),
+    );
+}
+
+#[test]
+fn node_with_tls_on_talks_in_the_clear_to_a_node_with_tls_off() {
+    let mut cluster = MASQNodeCluster::start().unwrap();
+    let plain_node = cluster.start_real_node(
+        NodeStartupConfigBuilder::standard()
+            .chain(cluster.chain)
+            .build(),
+    );
+    let tls_node = cluster.start_real_node(
+        NodeStartupConfigBuilder::standard()
+            .neighbor(plain_node.node_reference())
+            .chain(cluster.chain)
+            .clandestine_tls(true)
+            .build(),
+    );
+
+    MASQNodeUtils::wrote_log_containing(tls_node.name(), "Received Gossip", Duration::from_secs(5));
+    let tls_node_log = MASQNodeUtils::retrieve_logs(tls_node.name());
+    assert_eq!(
+        tls_node_log.contains("over TLS"),
+        false,
+        "TLS Node connected over TLS to a Node that doesn't advertise it:\n{}",
+        tls_node_log
+    );
+}
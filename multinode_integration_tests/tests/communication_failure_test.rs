@@ -21,7 +21,7 @@ use node_lib::sub_lib::hopper::{
     ExpiredCoresPackage, IncipientCoresPackage, MessageType, MessageTypeLite,
 };
 use node_lib::sub_lib::neighborhood::{Hops, RatePack, DEFAULT_RATE_PACK};
-use node_lib::sub_lib::proxy_client::{ClientResponsePayload_0v1, DnsResolveFailure_0v1};
+use node_lib::sub_lib::proxy_client::{ClientResponsePayload_0v2, DnsResolveFailure_0v1};
 use node_lib::sub_lib::route::Route;
 use node_lib::sub_lib::sequence_buffer::SequencedPacket;
 use node_lib::sub_lib::versioned_data::VersionedData;
@@ -443,9 +443,10 @@ fn make_package_for_client(
         MessageTypeLite::ClientResponse => {
             let client_response_vdata = VersionedData::new(
                 &node_lib::sub_lib::migrations::client_response_payload::MIGRATIONS,
-                &ClientResponsePayload_0v1 {
+                &ClientResponsePayload_0v2 {
                     stream_key,
                     sequenced_packet: sequenced_packet_opt.unwrap(),
+                    next_expected_sequence_opt: None,
                 },
             );
             MessageType::ClientResponse(client_response_vdata)
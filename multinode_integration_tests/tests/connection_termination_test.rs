@@ -23,8 +23,8 @@ use node_lib::sub_lib::cryptde::{decodex, CryptDE, PublicKey};
 use node_lib::sub_lib::cryptde_null::CryptDENull;
 use node_lib::sub_lib::dispatcher::Component;
 use node_lib::sub_lib::hopper::{IncipientCoresPackage, MessageType};
-use node_lib::sub_lib::proxy_client::ClientResponsePayload_0v1;
-use node_lib::sub_lib::proxy_server::{ClientRequestPayload_0v1, ProxyProtocol};
+use node_lib::sub_lib::proxy_client::ClientResponsePayload_0v2;
+use node_lib::sub_lib::proxy_server::{ClientRequestPayload_0v2, ProxyProtocol};
 use node_lib::sub_lib::route::{Route, RouteSegment};
 use node_lib::sub_lib::sequence_buffer::SequencedPacket;
 use node_lib::sub_lib::stream_key::StreamKey;
@@ -379,13 +379,14 @@ fn create_request_icp(
         .unwrap(),
         MessageType::ClientRequest(VersionedData::new(
             &node_lib::sub_lib::migrations::client_request_payload::MIGRATIONS,
-            &ClientRequestPayload_0v1 {
+            &ClientRequestPayload_0v2 {
                 stream_key,
                 sequenced_packet: SequencedPacket::new(Vec::from(HTTP_REQUEST), index, false),
                 target_hostname: Some(format!("{}", server.local_addr().ip())),
                 target_port: server.local_addr().port(),
                 protocol: ProxyProtocol::HTTP,
                 originator_public_key: originating_node.main_public_key().clone(),
+                next_expected_sequence_opt: None,
             },
         )),
         exit_node.main_public_key(),
@@ -425,13 +426,14 @@ fn create_meaningless_icp(
         .unwrap(),
         MessageType::ClientRequest(VersionedData::new(
             &node_lib::sub_lib::migrations::client_request_payload::MIGRATIONS,
-            &ClientRequestPayload_0v1 {
+            &ClientRequestPayload_0v2 {
                 stream_key,
                 sequenced_packet: SequencedPacket::new(Vec::from(HTTP_REQUEST), 0, false),
                 target_hostname: Some(format!("nowhere.com")),
                 target_port: socket_addr.port(),
                 protocol: ProxyProtocol::HTTP,
                 originator_public_key: originating_node.main_public_key().clone(),
+                next_expected_sequence_opt: None,
             },
         )),
         exit_node.main_public_key(),
@@ -471,9 +473,10 @@ fn create_server_drop_report(
         .unwrap();
     let payload = MessageType::ClientResponse(VersionedData::new(
         &node_lib::sub_lib::migrations::client_response_payload::MIGRATIONS,
-        &ClientResponsePayload_0v1 {
+        &ClientResponsePayload_0v2 {
             stream_key,
             sequenced_packet: SequencedPacket::new(vec![], 0, true),
+            next_expected_sequence_opt: None,
         },
     ));
 
@@ -515,13 +518,14 @@ fn create_client_drop_report(
     .unwrap();
     let payload = MessageType::ClientRequest(VersionedData::new(
         &node_lib::sub_lib::migrations::client_request_payload::MIGRATIONS,
-        &ClientRequestPayload_0v1 {
+        &ClientRequestPayload_0v2 {
             stream_key,
             sequenced_packet: SequencedPacket::new(vec![], 1, true),
             target_hostname: Some(String::from("doesnt.matter.com")),
             target_port: 80,
             protocol: ProxyProtocol::HTTP,
             originator_public_key: originating_node.main_public_key().clone(),
+            next_expected_sequence_opt: None,
         },
     ));
 
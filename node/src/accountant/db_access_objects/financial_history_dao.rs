@@ -0,0 +1,380 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::accountant::checked_conversion;
+use crate::accountant::db_access_objects::utils::{
+    from_time_t, to_time_t, DaoFactoryReal, VigilantRusqliteFlatten,
+};
+use crate::accountant::db_big_integer::big_int_divider::BigIntDivider;
+use crate::database::rusqlite_wrappers::ConnectionWrapper;
+use crate::sub_lib::wallet::Wallet;
+use rusqlite::Row;
+use std::time::SystemTime;
+
+// Charges are summed into buckets of this size; coarser views are built from them
+pub const HISTORY_BUCKET_S: i64 = 3_600;
+
+// The most the high/low integer pair in the database can hold
+const MAX_BUCKET_WEI: i128 = 0x3FFF_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HistoryDirection {
+    Earned,
+    Spent,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HistoryService {
+    Routing,
+    Exit,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum FinancialHistoryDaoError {
+    RecordingFailed(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FinancialHistoryRecord {
+    pub hour_start: SystemTime,
+    pub direction: HistoryDirection,
+    pub service: HistoryService,
+    pub wallet: Wallet,
+    pub amount_wei: u128,
+}
+
+pub trait FinancialHistoryDao {
+    fn record(
+        &self,
+        timestamp: SystemTime,
+        direction: HistoryDirection,
+        service: HistoryService,
+        wallet: &Wallet,
+        amount_wei: u128,
+    ) -> Result<(), FinancialHistoryDaoError>;
+
+    // Every bucket that overlaps the given span, oldest first
+    fn history(
+        &self,
+        from: SystemTime,
+        to: SystemTime,
+        wallet_opt: Option<&Wallet>,
+    ) -> Vec<FinancialHistoryRecord>;
+}
+
+pub trait FinancialHistoryDaoFactory {
+    fn make(&self) -> Box<dyn FinancialHistoryDao>;
+}
+
+impl FinancialHistoryDaoFactory for DaoFactoryReal {
+    fn make(&self) -> Box<dyn FinancialHistoryDao> {
+        Box::new(FinancialHistoryDaoReal::new(self.make_connection()))
+    }
+}
+
+#[derive(Debug)]
+pub struct FinancialHistoryDaoReal<'a> {
+    conn: Box<dyn ConnectionWrapper + 'a>,
+}
+
+impl FinancialHistoryDao for FinancialHistoryDaoReal<'_> {
+    fn record(
+        &self,
+        timestamp: SystemTime,
+        direction: HistoryDirection,
+        service: HistoryService,
+        wallet: &Wallet,
+        amount_wei: u128,
+    ) -> Result<(), FinancialHistoryDaoError> {
+        let hour_start = Self::hour_start(to_time_t(timestamp));
+        let direction = Self::direction_to_str(direction);
+        let service = Self::service_to_str(service);
+        let existing_opt = match self
+            .conn
+            .prepare(
+                "select rowid, amount_high_b, amount_low_b from financial_history \
+                 where hour_start = ? and direction = ? and service = ? and wallet_address = ?",
+            )
+            .and_then(|mut stm| {
+                stm.query_row(
+                    rusqlite::params![hour_start, direction, service, wallet],
+                    |row| {
+                        let rowid: i64 = Self::get_with_expect(row, 0);
+                        let high_b: i64 = Self::get_with_expect(row, 1);
+                        let low_b: i64 = Self::get_with_expect(row, 2);
+                        Ok((rowid, BigIntDivider::reconstitute(high_b, low_b)))
+                    },
+                )
+            }) {
+            Ok(existing) => Some(existing),
+            Err(rusqlite::Error::QueryReturnedNoRows) => None,
+            Err(e) => return Err(FinancialHistoryDaoError::RecordingFailed(e.to_string())),
+        };
+        let sum = existing_opt.map(|(_, sum)| sum).unwrap_or(0);
+        let new_sum = i128::try_from(amount_wei)
+            .ok()
+            .and_then(|amount| sum.checked_add(amount))
+            .filter(|new_sum| *new_sum <= MAX_BUCKET_WEI)
+            .ok_or_else(|| {
+                FinancialHistoryDaoError::RecordingFailed(format!(
+                    "Overflow adding {} wei to {} wei",
+                    amount_wei, sum
+                ))
+            })?;
+        let (high_b, low_b) = BigIntDivider::deconstruct(new_sum);
+        let result = match existing_opt {
+            Some((rowid, _)) => self
+                .conn
+                .prepare(
+                    "update financial_history set amount_high_b = ?, amount_low_b = ? \
+                         where rowid = ?",
+                )
+                .and_then(|mut stm| stm.execute(rusqlite::params![high_b, low_b, rowid])),
+            None => self
+                .conn
+                .prepare(
+                    "insert into financial_history (hour_start, direction, service, \
+                         wallet_address, amount_high_b, amount_low_b) values (?, ?, ?, ?, ?, ?)",
+                )
+                .and_then(|mut stm| {
+                    stm.execute(rusqlite::params![
+                        hour_start, direction, service, wallet, high_b, low_b
+                    ])
+                }),
+        };
+        match result {
+            Ok(1) => Ok(()),
+            Ok(x) => panic!("expected 1 changed row but got {}", x),
+            Err(e) => Err(FinancialHistoryDaoError::RecordingFailed(e.to_string())),
+        }
+    }
+
+    fn history(
+        &self,
+        from: SystemTime,
+        to: SystemTime,
+        wallet_opt: Option<&Wallet>,
+    ) -> Vec<FinancialHistoryRecord> {
+        let mut stm = self
+            .conn
+            .prepare(
+                "select hour_start, direction, service, wallet_address, amount_high_b, \
+                 amount_low_b from financial_history where hour_start >= ?1 and hour_start <= ?2 \
+                 and (?3 is null or wallet_address = ?3) order by hour_start, rowid",
+            )
+            .expect("Internal error");
+        stm.query_map(
+            rusqlite::params![Self::hour_start(to_time_t(from)), to_time_t(to), wallet_opt],
+            |row| {
+                let hour_start: i64 = Self::get_with_expect(row, 0);
+                let direction: String = Self::get_with_expect(row, 1);
+                let service: String = Self::get_with_expect(row, 2);
+                let wallet: Wallet = Self::get_with_expect(row, 3);
+                let amount_high_b: i64 = Self::get_with_expect(row, 4);
+                let amount_low_b: i64 = Self::get_with_expect(row, 5);
+                Ok(FinancialHistoryRecord {
+                    hour_start: from_time_t(hour_start),
+                    direction: Self::direction_from_str(&direction),
+                    service: Self::service_from_str(&service),
+                    wallet,
+                    amount_wei: checked_conversion::<i128, u128>(BigIntDivider::reconstitute(
+                        amount_high_b,
+                        amount_low_b,
+                    )),
+                })
+            },
+        )
+        .expect("rusqlite failure")
+        .vigilant_flatten()
+        .collect()
+    }
+}
+
+impl<'a> FinancialHistoryDaoReal<'a> {
+    pub fn new(conn: Box<dyn ConnectionWrapper + 'a>) -> Self {
+        Self { conn }
+    }
+
+    fn hour_start(time_t: i64) -> i64 {
+        time_t - time_t.rem_euclid(HISTORY_BUCKET_S)
+    }
+
+    fn get_with_expect<T: rusqlite::types::FromSql>(row: &Row, index: usize) -> T {
+        row.get(index).expect("database is corrupt")
+    }
+
+    fn direction_to_str(direction: HistoryDirection) -> &'static str {
+        match direction {
+            HistoryDirection::Earned => "earned",
+            HistoryDirection::Spent => "spent",
+        }
+    }
+
+    fn direction_from_str(direction: &str) -> HistoryDirection {
+        match direction {
+            "earned" => HistoryDirection::Earned,
+            "spent" => HistoryDirection::Spent,
+            x => panic!("database is corrupt: unknown direction '{}'", x),
+        }
+    }
+
+    fn service_to_str(service: HistoryService) -> &'static str {
+        match service {
+            HistoryService::Routing => "routing",
+            HistoryService::Exit => "exit",
+        }
+    }
+
+    fn service_from_str(service: &str) -> HistoryService {
+        match service {
+            "routing" => HistoryService::Routing,
+            "exit" => HistoryService::Exit,
+            x => panic!("database is corrupt: unknown service '{}'", x),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::db_initializer::{
+        DbInitializationConfig, DbInitializer, DbInitializerReal,
+    };
+    use crate::test_utils::make_wallet;
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+
+    fn make_subject(test_name: &str) -> FinancialHistoryDaoReal<'static> {
+        let home_dir = ensure_node_home_directory_exists("financial_history_dao", test_name);
+        let conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        FinancialHistoryDaoReal::new(conn)
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(HISTORY_BUCKET_S, 3_600);
+    }
+
+    #[test]
+    fn record_sums_charges_per_hour_direction_service_and_wallet() {
+        let subject = make_subject("record_sums_charges_per_hour_direction_service_and_wallet");
+        let wallet_a = make_wallet("wallet a");
+        let wallet_b = make_wallet("wallet b");
+        let hour = 190_000_800; // a whole hour
+        let record = |offset: i64, direction, service, wallet: &Wallet, amount| {
+            subject
+                .record(
+                    from_time_t(hour + offset),
+                    direction,
+                    service,
+                    wallet,
+                    amount,
+                )
+                .unwrap()
+        };
+        use HistoryDirection::*;
+        use HistoryService::*;
+
+        record(10, Earned, Routing, &wallet_a, 1_000);
+        record(3_599, Earned, Routing, &wallet_a, 2_000);
+        record(20, Earned, Exit, &wallet_a, 40);
+        record(30, Spent, Routing, &wallet_a, 50);
+        record(40, Earned, Routing, &wallet_b, 60);
+        record(3_600, Earned, Routing, &wallet_a, u64::MAX as u128 * 3);
+
+        let result = subject.history(from_time_t(hour), from_time_t(hour + 7_200), None);
+
+        let expected =
+            |offset, direction, service, wallet: &Wallet, amount_wei| FinancialHistoryRecord {
+                hour_start: from_time_t(hour + offset),
+                direction,
+                service,
+                wallet: wallet.clone(),
+                amount_wei,
+            };
+        assert_eq!(
+            result,
+            vec![
+                expected(0, Earned, Routing, &wallet_a, 3_000),
+                expected(0, Earned, Exit, &wallet_a, 40),
+                expected(0, Spent, Routing, &wallet_a, 50),
+                expected(0, Earned, Routing, &wallet_b, 60),
+                expected(3_600, Earned, Routing, &wallet_a, u64::MAX as u128 * 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn history_includes_the_bucket_the_span_begins_in_and_can_filter_by_wallet() {
+        let subject =
+            make_subject("history_includes_the_bucket_the_span_begins_in_and_can_filter_by_wallet");
+        let wallet_a = make_wallet("wallet a");
+        let wallet_b = make_wallet("wallet b");
+        let hour = 190_000_800;
+        [
+            (hour - 3_600, &wallet_a),
+            (hour, &wallet_a),
+            (hour, &wallet_b),
+            (hour + 3_600, &wallet_a),
+            (hour + 7_200, &wallet_a),
+        ]
+        .into_iter()
+        .for_each(|(time_t, wallet)| {
+            subject
+                .record(
+                    from_time_t(time_t),
+                    HistoryDirection::Spent,
+                    HistoryService::Exit,
+                    wallet,
+                    1,
+                )
+                .unwrap()
+        });
+
+        let result = subject.history(
+            from_time_t(hour + 1_800),
+            from_time_t(hour + 3_600),
+            Some(&wallet_a),
+        );
+
+        assert_eq!(
+            result
+                .into_iter()
+                .map(|record| (to_time_t(record.hour_start), record.wallet))
+                .collect::<Vec<_>>(),
+            vec![(hour, wallet_a.clone()), (hour + 3_600, wallet_a)]
+        );
+    }
+
+    #[test]
+    fn record_reports_overflow_of_a_bucket() {
+        let subject = make_subject("record_reports_overflow_of_a_bucket");
+        let wallet = make_wallet("wallet");
+        let timestamp = from_time_t(190_000_800);
+        subject
+            .record(
+                timestamp,
+                HistoryDirection::Earned,
+                HistoryService::Exit,
+                &wallet,
+                MAX_BUCKET_WEI as u128,
+            )
+            .unwrap();
+
+        let result = subject.record(
+            timestamp,
+            HistoryDirection::Earned,
+            HistoryService::Exit,
+            &wallet,
+            1,
+        );
+
+        assert_eq!(
+            result,
+            Err(FinancialHistoryDaoError::RecordingFailed(format!(
+                "Overflow adding 1 wei to {} wei",
+                MAX_BUCKET_WEI
+            )))
+        );
+    }
+}
@@ -0,0 +1,211 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::accountant::checked_conversion;
+use crate::accountant::db_access_objects::utils::{
+    from_time_t, to_time_t, DaoFactoryReal, VigilantRusqliteFlatten,
+};
+use crate::accountant::db_big_integer::big_int_divider::BigIntDivider;
+use crate::database::rusqlite_wrappers::ConnectionWrapper;
+use crate::sub_lib::wallet::Wallet;
+use masq_lib::messages::TransferCurrency;
+use rusqlite::Row;
+use std::str::FromStr;
+use std::time::SystemTime;
+use web3::types::H256;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum FundsTransferDaoError {
+    InsertionFailed(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FundsTransferRecord {
+    pub hash: H256,
+    pub recipient: Wallet,
+    pub currency: TransferCurrency,
+    pub amount_wei: u128,
+    pub gas_price_wei: u64,
+    pub nonce: u64,
+    pub timestamp: SystemTime,
+}
+
+pub trait FundsTransferDao {
+    fn record_transfer(&self, record: &FundsTransferRecord) -> Result<(), FundsTransferDaoError>;
+    fn transfers(&self) -> Vec<FundsTransferRecord>;
+}
+
+pub trait FundsTransferDaoFactory {
+    fn make(&self) -> Box<dyn FundsTransferDao>;
+}
+
+impl FundsTransferDaoFactory for DaoFactoryReal {
+    fn make(&self) -> Box<dyn FundsTransferDao> {
+        Box::new(FundsTransferDaoReal::new(self.make_connection()))
+    }
+}
+
+#[derive(Debug)]
+pub struct FundsTransferDaoReal<'a> {
+    conn: Box<dyn ConnectionWrapper + 'a>,
+}
+
+impl FundsTransferDao for FundsTransferDaoReal<'_> {
+    fn record_transfer(&self, record: &FundsTransferRecord) -> Result<(), FundsTransferDaoError> {
+        let amount_checked = checked_conversion::<u128, i128>(record.amount_wei);
+        let (amount_high_b, amount_low_b) = BigIntDivider::deconstruct(amount_checked);
+        match self.conn.prepare(
+            "insert into funds_transfer (transaction_hash, recipient_address, currency, \
+             amount_high_b, amount_low_b, gas_price_wei, nonce, transferred_at) \
+             values (?, ?, ?, ?, ?, ?, ?, ?)",
+        ) {
+            Ok(mut stm) => match stm.execute(rusqlite::params![
+                format!("{:?}", record.hash),
+                record.recipient,
+                Self::currency_to_str(record.currency),
+                amount_high_b,
+                amount_low_b,
+                checked_conversion::<u64, i64>(record.gas_price_wei),
+                checked_conversion::<u64, i64>(record.nonce),
+                to_time_t(record.timestamp),
+            ]) {
+                Ok(1) => Ok(()),
+                Ok(x) => panic!("expected 1 inserted row but got {}", x),
+                Err(e) => Err(FundsTransferDaoError::InsertionFailed(e.to_string())),
+            },
+            Err(e) => Err(FundsTransferDaoError::InsertionFailed(e.to_string())),
+        }
+    }
+
+    fn transfers(&self) -> Vec<FundsTransferRecord> {
+        let mut stm = self
+            .conn
+            .prepare(
+                "select transaction_hash, recipient_address, currency, amount_high_b, \
+                 amount_low_b, gas_price_wei, nonce, transferred_at from funds_transfer \
+                 order by rowid",
+            )
+            .expect("Internal error");
+        stm.query_map([], |row| {
+            let hash: String = Self::get_with_expect(row, 0);
+            let recipient: Wallet = Self::get_with_expect(row, 1);
+            let currency: String = Self::get_with_expect(row, 2);
+            let amount_high_b: i64 = Self::get_with_expect(row, 3);
+            let amount_low_b: i64 = Self::get_with_expect(row, 4);
+            let gas_price_wei: i64 = Self::get_with_expect(row, 5);
+            let nonce: i64 = Self::get_with_expect(row, 6);
+            let transferred_at: i64 = Self::get_with_expect(row, 7);
+            Ok(FundsTransferRecord {
+                hash: H256::from_str(&hash[2..]).expect("database is corrupt"),
+                recipient,
+                currency: TransferCurrency::from_str(&currency).expect("database is corrupt"),
+                amount_wei: checked_conversion::<i128, u128>(BigIntDivider::reconstitute(
+                    amount_high_b,
+                    amount_low_b,
+                )),
+                gas_price_wei: checked_conversion::<i64, u64>(gas_price_wei),
+                nonce: checked_conversion::<i64, u64>(nonce),
+                timestamp: from_time_t(transferred_at),
+            })
+        })
+        .expect("rusqlite failure")
+        .vigilant_flatten()
+        .collect()
+    }
+}
+
+impl<'a> FundsTransferDaoReal<'a> {
+    pub fn new(conn: Box<dyn ConnectionWrapper + 'a>) -> Self {
+        Self { conn }
+    }
+
+    fn get_with_expect<T: rusqlite::types::FromSql>(row: &Row, index: usize) -> T {
+        row.get(index).expect("database is corrupt")
+    }
+
+    fn currency_to_str(currency: TransferCurrency) -> &'static str {
+        match currency {
+            TransferCurrency::Masq => "masq",
+            TransferCurrency::Native => "native",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::test_utils::make_tx_hash;
+    use crate::database::db_initializer::{
+        DbInitializationConfig, DbInitializer, DbInitializerReal,
+    };
+    use crate::test_utils::make_wallet;
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+
+    #[test]
+    fn record_transfer_and_transfers_round_trip() {
+        let home_dir = ensure_node_home_directory_exists(
+            "funds_transfer_dao",
+            "record_transfer_and_transfers_round_trip",
+        );
+        let conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let subject = FundsTransferDaoReal::new(conn);
+        let masq_transfer = FundsTransferRecord {
+            hash: make_tx_hash(123),
+            recipient: make_wallet("cold storage"),
+            currency: TransferCurrency::Masq,
+            amount_wei: u64::MAX as u128 * 1000,
+            gas_price_wei: 1_500_000_000,
+            nonce: 14,
+            timestamp: from_time_t(190_000_000),
+        };
+        let native_transfer = FundsTransferRecord {
+            hash: make_tx_hash(456),
+            recipient: make_wallet("exchange"),
+            currency: TransferCurrency::Native,
+            amount_wei: 123_456_789,
+            gas_price_wei: 2_000_000_000,
+            nonce: 15,
+            timestamp: from_time_t(190_001_000),
+        };
+
+        let first_result = subject.record_transfer(&masq_transfer);
+        let second_result = subject.record_transfer(&native_transfer);
+
+        assert_eq!(first_result, Ok(()));
+        assert_eq!(second_result, Ok(()));
+        assert_eq!(subject.transfers(), vec![masq_transfer, native_transfer]);
+    }
+
+    #[test]
+    fn record_transfer_refuses_a_duplicate_hash() {
+        let home_dir = ensure_node_home_directory_exists(
+            "funds_transfer_dao",
+            "record_transfer_refuses_a_duplicate_hash",
+        );
+        let conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let subject = FundsTransferDaoReal::new(conn);
+        let transfer = FundsTransferRecord {
+            hash: make_tx_hash(123),
+            recipient: make_wallet("cold storage"),
+            currency: TransferCurrency::Native,
+            amount_wei: 1_000,
+            gas_price_wei: 1_000_000_000,
+            nonce: 1,
+            timestamp: from_time_t(190_000_000),
+        };
+        subject.record_transfer(&transfer).unwrap();
+
+        let result = subject.record_transfer(&transfer);
+
+        assert_eq!(
+            result,
+            Err(FundsTransferDaoError::InsertionFailed(
+                "UNIQUE constraint failed: funds_transfer.transaction_hash".to_string()
+            ))
+        );
+        assert_eq!(subject.transfers(), vec![transfer]);
+    }
+}
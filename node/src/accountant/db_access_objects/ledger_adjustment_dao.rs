@@ -0,0 +1,375 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::accountant::db_access_objects::utils::{to_time_t, DaoFactoryReal};
+use crate::accountant::db_big_integer::big_int_divider::BigIntDivider;
+use crate::database::rusqlite_wrappers::TransactionSafeWrapper;
+use crate::sub_lib::wallet::Wallet;
+use std::time::SystemTime;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AdjustedLedger {
+    Payable,
+    Receivable,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AdjustmentKind {
+    Correction,
+    Forgiveness,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BalanceChange {
+    pub before_wei: i128,
+    pub after_wei: i128,
+}
+
+// A balance change made inside a transaction that has been left open, so that the record of
+// it can go into the same transaction
+#[derive(Debug)]
+pub struct UncommittedBalanceChange<'txn> {
+    pub change: BalanceChange,
+    pub txn: TransactionSafeWrapper<'txn>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LedgerAdjustment {
+    pub timestamp: SystemTime,
+    pub operator: String,
+    pub reason: String,
+    pub ledger: AdjustedLedger,
+    pub kind: AdjustmentKind,
+    pub wallet: Wallet,
+    pub change: BalanceChange,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum LedgerAdjustmentDaoError {
+    RecordingFailed(String),
+}
+
+// The table behind it refuses updates and deletes; adjustments can only be added. An adjustment
+// is recorded in the transaction that changed the balance, and that transaction is committed only
+// if the record is written: on failure it's dropped, which rolls the balance change back.
+pub trait LedgerAdjustmentDao {
+    fn record(
+        &self,
+        adjustment: &LedgerAdjustment,
+        txn: TransactionSafeWrapper,
+    ) -> Result<(), LedgerAdjustmentDaoError>;
+}
+
+pub trait LedgerAdjustmentDaoFactory {
+    fn make(&self) -> Box<dyn LedgerAdjustmentDao>;
+}
+
+impl LedgerAdjustmentDaoFactory for DaoFactoryReal {
+    fn make(&self) -> Box<dyn LedgerAdjustmentDao> {
+        Box::new(LedgerAdjustmentDaoReal::new())
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct LedgerAdjustmentDaoReal {}
+
+impl LedgerAdjustmentDao for LedgerAdjustmentDaoReal {
+    fn record(
+        &self,
+        adjustment: &LedgerAdjustment,
+        txn: TransactionSafeWrapper,
+    ) -> Result<(), LedgerAdjustmentDaoError> {
+        let (before_high_b, before_low_b) =
+            BigIntDivider::deconstruct(adjustment.change.before_wei);
+        let (after_high_b, after_low_b) = BigIntDivider::deconstruct(adjustment.change.after_wei);
+        let result = txn
+            .prepare(
+                "insert into ledger_adjustment (timestamp, operator, reason, ledger, kind, \
+                 wallet_address, balance_before_high_b, balance_before_low_b, \
+                 balance_after_high_b, balance_after_low_b) values (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .and_then(|mut stm| {
+                stm.execute(rusqlite::params![
+                    to_time_t(adjustment.timestamp),
+                    adjustment.operator,
+                    adjustment.reason,
+                    Self::ledger_to_str(adjustment.ledger),
+                    Self::kind_to_str(adjustment.kind),
+                    adjustment.wallet,
+                    before_high_b,
+                    before_low_b,
+                    after_high_b,
+                    after_low_b
+                ])
+            });
+        match result {
+            Ok(1) => txn
+                .commit()
+                .map_err(|e| LedgerAdjustmentDaoError::RecordingFailed(e.to_string())),
+            Ok(x) => panic!("expected 1 changed row but got {}", x),
+            Err(e) => Err(LedgerAdjustmentDaoError::RecordingFailed(e.to_string())),
+        }
+    }
+}
+
+impl LedgerAdjustmentDaoReal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn ledger_to_str(ledger: AdjustedLedger) -> &'static str {
+        match ledger {
+            AdjustedLedger::Payable => "payable",
+            AdjustedLedger::Receivable => "receivable",
+        }
+    }
+
+    fn kind_to_str(kind: AdjustmentKind) -> &'static str {
+        match kind {
+            AdjustmentKind::Correction => "correction",
+            AdjustmentKind::Forgiveness => "forgiveness",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::accountant::db_access_objects::payable_dao::{PayableDao, PayableDaoReal};
+    use crate::accountant::db_access_objects::utils::from_time_t;
+    use crate::database::db_initializer::{
+        DbInitializationConfig, DbInitializer, DbInitializerReal, DATABASE_FILE,
+    };
+    use crate::database::rusqlite_wrappers::{ConnectionWrapper, ConnectionWrapperReal};
+    use crate::test_utils::make_wallet;
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+    use rusqlite::{Connection, OpenFlags};
+
+    type AdjustmentRow = (i64, String, String, String, String, Wallet, i128, i128);
+
+    fn read_adjustments(conn: &dyn ConnectionWrapper) -> Vec<AdjustmentRow> {
+        let mut stm = conn
+            .prepare(
+                "select timestamp, operator, reason, ledger, kind, wallet_address, \
+                 balance_before_high_b, balance_before_low_b, balance_after_high_b, \
+                 balance_after_low_b from ledger_adjustment order by rowid",
+            )
+            .unwrap();
+        stm.query_map([], |row| {
+            Ok((
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                row.get(4)?,
+                row.get(5)?,
+                BigIntDivider::reconstitute(row.get(6)?, row.get(7)?),
+                BigIntDivider::reconstitute(row.get(8)?, row.get(9)?),
+            ))
+        })
+        .unwrap()
+        .map(|row| row.unwrap())
+        .collect()
+    }
+
+    #[test]
+    fn record_appends_adjustments() {
+        let home_dir = ensure_node_home_directory_exists(
+            "ledger_adjustment_dao",
+            "record_appends_adjustments",
+        );
+        let mut conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let subject = LedgerAdjustmentDaoReal::new();
+        let wallet_a = make_wallet("wallet a");
+        let wallet_b = make_wallet("wallet b");
+        let correction = LedgerAdjustment {
+            timestamp: from_time_t(190_000_000),
+            operator: "alice".to_string(),
+            reason: "double-charged by a bug".to_string(),
+            ledger: AdjustedLedger::Payable,
+            kind: AdjustmentKind::Correction,
+            wallet: wallet_a.clone(),
+            change: BalanceChange {
+                before_wei: u64::MAX as i128 * 5,
+                after_wei: 4_000_000,
+            },
+        };
+        let forgiveness = LedgerAdjustment {
+            timestamp: from_time_t(190_000_100),
+            operator: "bob".to_string(),
+            reason: "will never pay".to_string(),
+            ledger: AdjustedLedger::Receivable,
+            kind: AdjustmentKind::Forgiveness,
+            wallet: wallet_b.clone(),
+            change: BalanceChange {
+                before_wei: 123_456_789,
+                after_wei: 0,
+            },
+        };
+
+        subject
+            .record(&correction, conn.transaction().unwrap())
+            .unwrap();
+        subject
+            .record(&forgiveness, conn.transaction().unwrap())
+            .unwrap();
+
+        assert_eq!(
+            read_adjustments(conn.as_ref()),
+            vec![
+                (
+                    190_000_000,
+                    "alice".to_string(),
+                    "double-charged by a bug".to_string(),
+                    "payable".to_string(),
+                    "correction".to_string(),
+                    wallet_a,
+                    u64::MAX as i128 * 5,
+                    4_000_000
+                ),
+                (
+                    190_000_100,
+                    "bob".to_string(),
+                    "will never pay".to_string(),
+                    "receivable".to_string(),
+                    "forgiveness".to_string(),
+                    wallet_b,
+                    123_456_789,
+                    0
+                )
+            ]
+        );
+    }
+
+    #[test]
+    fn record_reports_database_error() {
+        let home_dir = ensure_node_home_directory_exists(
+            "ledger_adjustment_dao",
+            "record_reports_database_error",
+        );
+        {
+            DbInitializerReal::default()
+                .initialize(&home_dir, DbInitializationConfig::test_default())
+                .unwrap();
+        }
+        let read_only_conn = Connection::open_with_flags(
+            home_dir.join(DATABASE_FILE),
+            OpenFlags::SQLITE_OPEN_READ_ONLY,
+        )
+        .unwrap();
+        let mut read_only_conn = ConnectionWrapperReal::new(read_only_conn);
+        let subject = LedgerAdjustmentDaoReal::new();
+        let txn = read_only_conn.transaction().unwrap();
+
+        let result = subject.record(
+            &LedgerAdjustment {
+                timestamp: from_time_t(190_000_000),
+                operator: "alice".to_string(),
+                reason: "typo".to_string(),
+                ledger: AdjustedLedger::Receivable,
+                kind: AdjustmentKind::Correction,
+                wallet: make_wallet("wallet"),
+                change: BalanceChange {
+                    before_wei: 1,
+                    after_wei: 2,
+                },
+            },
+            txn,
+        );
+
+        assert_eq!(
+            result,
+            Err(LedgerAdjustmentDaoError::RecordingFailed(
+                "attempt to write a readonly database".to_string()
+            ))
+        );
+    }
+
+    fn make_payable_correction(wallet: &Wallet, change: BalanceChange) -> LedgerAdjustment {
+        LedgerAdjustment {
+            timestamp: from_time_t(190_000_000),
+            operator: "alice".to_string(),
+            reason: "double-charged by a bug".to_string(),
+            ledger: AdjustedLedger::Payable,
+            kind: AdjustmentKind::Correction,
+            wallet: wallet.clone(),
+            change,
+        }
+    }
+
+    #[test]
+    fn recording_an_adjustment_commits_the_balance_change() {
+        let home_dir = ensure_node_home_directory_exists(
+            "ledger_adjustment_dao",
+            "recording_an_adjustment_commits_the_balance_change",
+        );
+        let db_initializer = DbInitializerReal::default();
+        let wallet = make_wallet("wallet");
+        let mut payable_dao = PayableDaoReal::new(
+            db_initializer
+                .initialize(&home_dir, DbInitializationConfig::test_default())
+                .unwrap(),
+        );
+        payable_dao
+            .more_money_payable(SystemTime::UNIX_EPOCH, &wallet, 5_000)
+            .unwrap();
+        let subject = LedgerAdjustmentDaoReal::new();
+        let UncommittedBalanceChange { change, txn } = payable_dao
+            .adjust_balance(&wallet, -1_000)
+            .unwrap()
+            .unwrap();
+
+        let result = subject.record(&make_payable_correction(&wallet, change), txn);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            payable_dao.account_status(&wallet).unwrap().balance_wei,
+            4_000
+        );
+        let conn = db_initializer
+            .initialize(&home_dir, DbInitializationConfig::panic_on_migration())
+            .unwrap();
+        assert_eq!(read_adjustments(conn.as_ref()).len(), 1);
+    }
+
+    #[test]
+    fn balance_change_is_rolled_back_if_the_adjustment_cant_be_recorded() {
+        let home_dir = ensure_node_home_directory_exists(
+            "ledger_adjustment_dao",
+            "balance_change_is_rolled_back_if_the_adjustment_cant_be_recorded",
+        );
+        let db_initializer = DbInitializerReal::default();
+        let wallet = make_wallet("wallet");
+        let mut payable_dao = PayableDaoReal::new(
+            db_initializer
+                .initialize(&home_dir, DbInitializationConfig::test_default())
+                .unwrap(),
+        );
+        payable_dao
+            .more_money_payable(SystemTime::UNIX_EPOCH, &wallet, 5_000)
+            .unwrap();
+        {
+            let conn = Connection::open(home_dir.join(DATABASE_FILE)).unwrap();
+            conn.execute("drop table ledger_adjustment", []).unwrap();
+        }
+        let subject = LedgerAdjustmentDaoReal::new();
+        let UncommittedBalanceChange { change, txn } = payable_dao
+            .adjust_balance(&wallet, -1_000)
+            .unwrap()
+            .unwrap();
+
+        let result = subject.record(&make_payable_correction(&wallet, change), txn);
+
+        assert_eq!(
+            result,
+            Err(LedgerAdjustmentDaoError::RecordingFailed(
+                "no such table: ledger_adjustment".to_string()
+            ))
+        );
+        assert_eq!(
+            payable_dao.account_status(&wallet).unwrap().balance_wei,
+            5_000
+        );
+    }
+}
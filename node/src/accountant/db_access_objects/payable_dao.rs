@@ -5,6 +5,9 @@ use crate::accountant::db_big_integer::big_int_db_processor::KeyVariants::{
 };
 use crate::accountant::db_big_integer::big_int_db_processor::{BigIntDbProcessor, BigIntDbProcessorReal, BigIntSqlConfig, DisplayableRusqliteParamPair, ParamByUse, SQLParamsBuilder, TableNameDAO, WeiChange, WeiChangeDirection};
 use crate::accountant::db_big_integer::big_int_divider::BigIntDivider;
+use crate::accountant::db_access_objects::ledger_adjustment_dao::{
+    BalanceChange, UncommittedBalanceChange,
+};
 use crate::accountant::db_access_objects::utils;
 use crate::accountant::db_access_objects::utils::{
     sum_i128_values_from_table, to_time_t, AssemblerFeeder, CustomQuery, DaoFactoryReal,
@@ -20,9 +23,7 @@ use crate::sub_lib::wallet::Wallet;
 #[cfg(test)]
 use ethereum_types::{BigEndianHash, U256};
 use masq_lib::utils::ExpectValue;
-#[cfg(test)]
-use rusqlite::OptionalExtension;
-use rusqlite::{Error, Row};
+use rusqlite::{Error, OptionalExtension, Row};
 use std::fmt::Debug;
 use std::str::FromStr;
 use std::time::SystemTime;
@@ -33,6 +34,8 @@ use web3::types::H256;
 pub enum PayableDaoError {
     SignConversion(u128),
     RusqliteError(String),
+    // Carries the balance the refused change would have left
+    BalanceBelowZero(i128),
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -67,6 +70,13 @@ pub trait PayableDao: Debug + Send {
 
     fn total(&self) -> u128;
 
+    // None if the wallet has no payable to adjust. The change isn't committed yet.
+    fn adjust_balance(
+        &mut self,
+        wallet: &Wallet,
+        change_wei: i128,
+    ) -> Result<Option<UncommittedBalanceChange<'_>>, PayableDaoError>;
+
     #[cfg(test)]
     fn account_status(&self, wallet: &Wallet) -> Option<PayableAccount>;
 }
@@ -148,30 +158,18 @@ impl PayableDao for PayableDaoReal {
         &self,
         confirmed_payables: &[PendingPayableFingerprint],
     ) -> Result<(), PayableDaoError> {
-        confirmed_payables.iter().try_for_each(|pending_payable_fingerprint| {
-
-            let main_sql = "update payable set \
-                    balance_high_b = balance_high_b + :balance_high_b, balance_low_b = balance_low_b + :balance_low_b, \
-                    last_paid_timestamp = :last_paid, pending_payable_rowid = null where pending_payable_rowid = :rowid";
-            let update_clause_with_compensated_overflow = "update payable set \
-                    balance_high_b = :balance_high_b, balance_low_b = :balance_low_b, last_paid_timestamp = :last_paid, \
-                    pending_payable_rowid = null where pending_payable_rowid = :rowid";
-
-            let i64_rowid = checked_conversion::<u64, i64>(pending_payable_fingerprint.rowid);
-            let last_paid = to_time_t(pending_payable_fingerprint.timestamp);
-            let params = SQLParamsBuilder::default()
-                .key( PendingPayableRowid(&i64_rowid))
-                .wei_change(WeiChange::new( "balance", pending_payable_fingerprint.amount, WeiChangeDirection::Subtraction))
-                .other_params(vec![ParamByUse::BeforeAndAfterOverflow(DisplayableRusqliteParamPair::new(":last_paid", &last_paid))])
-                .build();
-
-            self.big_int_db_processor.execute(Either::Left(self.conn.as_ref()), BigIntSqlConfig::new(
-                main_sql,
-                update_clause_with_compensated_overflow,
-                params))?;
-
-            Ok(())
-        })
+        confirmed_payables
+            .iter()
+            .try_for_each(|pending_payable_fingerprint| {
+                let batch_payments = self.batch_payments(pending_payable_fingerprint.rowid)?;
+                if batch_payments.is_empty() {
+                    self.confirm_transaction(pending_payable_fingerprint)
+                } else {
+                    batch_payments.iter().try_for_each(|(wallet, amount)| {
+                        self.confirm_batch_payment(pending_payable_fingerprint, wallet, *amount)
+                    })
+                }
+            })
     }
 
     fn non_pending_payables(&self) -> Vec<PayableAccount> {
@@ -258,6 +256,63 @@ impl PayableDao for PayableDaoReal {
         })
     }
 
+    fn adjust_balance(
+        &mut self,
+        wallet: &Wallet,
+        change_wei: i128,
+    ) -> Result<Option<UncommittedBalanceChange<'_>>, PayableDaoError> {
+        let txn = self
+            .conn
+            .transaction()
+            .map_err(|e| PayableDaoError::RusqliteError(e.to_string()))?;
+        let before_wei = match txn
+            .prepare("select balance_high_b, balance_low_b from payable where wallet_address = ?")
+            .and_then(|mut stm| {
+                stm.query_row([&wallet], |row| {
+                    Ok(BigIntDivider::reconstitute(row.get(0)?, row.get(1)?))
+                })
+                .optional()
+            }) {
+            Ok(Some(balance)) => balance,
+            Ok(None) => return Ok(None),
+            Err(e) => return Err(PayableDaoError::RusqliteError(e.to_string())),
+        };
+        let after_wei = before_wei + change_wei;
+        if after_wei < 0 {
+            return Err(PayableDaoError::BalanceBelowZero(after_wei));
+        }
+        let main_sql = "update payable set balance_high_b = balance_high_b + :balance_high_b, \
+                balance_low_b = balance_low_b + :balance_low_b where wallet_address = :wallet";
+        let update_clause_with_compensated_overflow = "update payable set \
+                balance_high_b = :balance_high_b, balance_low_b = :balance_low_b where wallet_address = :wallet";
+        let direction = if change_wei < 0 {
+            WeiChangeDirection::Subtraction
+        } else {
+            WeiChangeDirection::Addition
+        };
+        let params = SQLParamsBuilder::default()
+            .key(WalletAddress(wallet))
+            .wei_change(WeiChange::new(
+                "balance",
+                change_wei.unsigned_abs(),
+                direction,
+            ))
+            .build();
+
+        self.big_int_db_processor.execute(
+            Either::Right(&txn),
+            BigIntSqlConfig::new(main_sql, update_clause_with_compensated_overflow, params),
+        )?;
+
+        Ok(Some(UncommittedBalanceChange {
+            change: BalanceChange {
+                before_wei,
+                after_wei,
+            },
+            txn,
+        }))
+    }
+
     #[cfg(test)]
     fn account_status(&self, wallet: &Wallet) -> Option<PayableAccount> {
         let stm = "\
@@ -311,6 +366,106 @@ impl PayableDaoReal {
         }
     }
 
+    fn confirm_transaction(
+        &self,
+        pending_payable_fingerprint: &PendingPayableFingerprint,
+    ) -> Result<(), PayableDaoError> {
+        let main_sql = "update payable set \
+                balance_high_b = balance_high_b + :balance_high_b, balance_low_b = balance_low_b + :balance_low_b, \
+                last_paid_timestamp = :last_paid, pending_payable_rowid = null where pending_payable_rowid = :rowid";
+        let update_clause_with_compensated_overflow = "update payable set \
+                balance_high_b = :balance_high_b, balance_low_b = :balance_low_b, last_paid_timestamp = :last_paid, \
+                pending_payable_rowid = null where pending_payable_rowid = :rowid";
+
+        let i64_rowid = checked_conversion::<u64, i64>(pending_payable_fingerprint.rowid);
+        let last_paid = to_time_t(pending_payable_fingerprint.timestamp);
+        let params = SQLParamsBuilder::default()
+            .key(PendingPayableRowid(&i64_rowid))
+            .wei_change(WeiChange::new(
+                "balance",
+                pending_payable_fingerprint.amount,
+                WeiChangeDirection::Subtraction,
+            ))
+            .other_params(vec![ParamByUse::BeforeAndAfterOverflow(
+                DisplayableRusqliteParamPair::new(":last_paid", &last_paid),
+            )])
+            .build();
+
+        self.big_int_db_processor.execute(
+            Either::Left(self.conn.as_ref()),
+            BigIntSqlConfig::new(main_sql, update_clause_with_compensated_overflow, params),
+        )?;
+
+        Ok(())
+    }
+
+    // Shares of a transaction that paid several creditors through a disperse contract are
+    // kept aside by the pending payable DAO
+    fn batch_payments(&self, rowid: u64) -> Result<Vec<(Wallet, u128)>, PayableDaoError> {
+        let mut stm = self
+            .conn
+            .prepare(
+                "select wallet_address, amount_high_b, amount_low_b from \
+                 pending_payable_batch_payment where pending_payable_rowid = ? order by rowid",
+            )
+            .expect("Internal error");
+        stm.query_map([checked_conversion::<u64, i64>(rowid)], |row| {
+            let wallet: Wallet = row.get(0)?;
+            let high_bytes: i64 = row.get(1)?;
+            let low_bytes: i64 = row.get(2)?;
+            Ok((
+                wallet,
+                checked_conversion::<i128, u128>(BigIntDivider::reconstitute(
+                    high_bytes, low_bytes,
+                )),
+            ))
+        })
+        .and_then(|rows| rows.collect::<rusqlite::Result<Vec<(Wallet, u128)>>>())
+        .map_err(|e| PayableDaoError::RusqliteError(e.to_string()))
+    }
+
+    fn confirm_batch_payment(
+        &self,
+        pending_payable_fingerprint: &PendingPayableFingerprint,
+        wallet: &Wallet,
+        amount: u128,
+    ) -> Result<(), PayableDaoError> {
+        let main_sql = "update payable set \
+                balance_high_b = balance_high_b + :balance_high_b, balance_low_b = balance_low_b + :balance_low_b, \
+                last_paid_timestamp = :last_paid, pending_payable_rowid = null \
+                where wallet_address = :wallet and pending_payable_rowid = :rowid";
+        let update_clause_with_compensated_overflow = "update payable set \
+                balance_high_b = :balance_high_b, balance_low_b = :balance_low_b, last_paid_timestamp = :last_paid, \
+                pending_payable_rowid = null where wallet_address = :wallet and pending_payable_rowid = :rowid";
+
+        let i64_rowid = checked_conversion::<u64, i64>(pending_payable_fingerprint.rowid);
+        let last_paid = to_time_t(pending_payable_fingerprint.timestamp);
+        let params = SQLParamsBuilder::default()
+            .key(WalletAddress(wallet))
+            .wei_change(WeiChange::new(
+                "balance",
+                amount,
+                WeiChangeDirection::Subtraction,
+            ))
+            .other_params(vec![
+                ParamByUse::BeforeAndAfterOverflow(DisplayableRusqliteParamPair::new(
+                    ":rowid", &i64_rowid,
+                )),
+                ParamByUse::BeforeAndAfterOverflow(DisplayableRusqliteParamPair::new(
+                    ":last_paid",
+                    &last_paid,
+                )),
+            ])
+            .build();
+
+        self.big_int_db_processor.execute(
+            Either::Left(self.conn.as_ref()),
+            BigIntSqlConfig::new(main_sql, update_clause_with_compensated_overflow, params),
+        )?;
+
+        Ok(())
+    }
+
     fn create_payable_account(row: &Row) -> rusqlite::Result<PayableAccount> {
         let wallet_result: Result<Wallet, Error> = row.get(0);
         let balance_high_bytes_result = row.get(1);
@@ -544,7 +699,8 @@ mod tests {
     use crate::accountant::db_access_objects::utils::{from_time_t, now_time_t, to_time_t};
     use crate::accountant::gwei_to_wei;
     use crate::accountant::db_access_objects::payable_dao::mark_pending_payable_associated_functions::explanatory_extension;
-    use crate::accountant::test_utils::{assert_account_creation_fn_fails_on_finding_wrong_columns_and_value_types, make_pending_payable_fingerprint, trick_rusqlite_with_read_only_conn};
+    use crate::accountant::test_utils::{commit_balance_change, assert_account_creation_fn_fails_on_finding_wrong_columns_and_value_types, make_pending_payable_fingerprint, trick_rusqlite_with_read_only_conn};
+    use crate::accountant::db_access_objects::pending_payable_dao::{PendingPayableDao, PendingPayableDaoReal};
     use crate::blockchain::test_utils::make_tx_hash;
     use crate::database::rusqlite_wrappers::ConnectionWrapperReal;
     use crate::database::db_initializer::{
@@ -580,6 +736,94 @@ mod tests {
         assert_eq!(to_time_t(status.last_paid_timestamp), to_time_t(now));
     }
 
+    #[test]
+    fn adjust_balance_moves_payable_both_ways_and_reports_the_change() {
+        let home_dir = ensure_node_home_directory_exists(
+            "payable_dao",
+            "adjust_balance_moves_payable_both_ways_and_reports_the_change",
+        );
+        let wallet = make_wallet("booga");
+        let unknown_wallet = make_wallet("unknown");
+        let boxed_conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let mut subject = PayableDaoReal::new(boxed_conn);
+        let initial_balance = u64::MAX as u128 + 1_000;
+        subject
+            .more_money_payable(SystemTime::UNIX_EPOCH, &wallet, initial_balance)
+            .unwrap();
+
+        let decrease = commit_balance_change(subject.adjust_balance(&wallet, -2_000).unwrap());
+        let increase = commit_balance_change(subject.adjust_balance(&wallet, 500).unwrap());
+        let unknown = commit_balance_change(subject.adjust_balance(&unknown_wallet, 500).unwrap());
+
+        let initial_balance = initial_balance as i128;
+        assert_eq!(
+            decrease,
+            Some(BalanceChange {
+                before_wei: initial_balance,
+                after_wei: initial_balance - 2_000
+            })
+        );
+        assert_eq!(
+            increase,
+            Some(BalanceChange {
+                before_wei: initial_balance - 2_000,
+                after_wei: initial_balance - 1_500
+            })
+        );
+        assert_eq!(unknown, None);
+        assert_eq!(
+            subject.account_status(&wallet).unwrap().balance_wei,
+            initial_balance as u128 - 1_500
+        );
+        assert_eq!(subject.account_status(&unknown_wallet), None);
+    }
+
+    #[test]
+    fn adjust_balance_refuses_to_push_payable_below_zero() {
+        let home_dir = ensure_node_home_directory_exists(
+            "payable_dao",
+            "adjust_balance_refuses_to_push_payable_below_zero",
+        );
+        let wallet = make_wallet("booga");
+        let boxed_conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let mut subject = PayableDaoReal::new(boxed_conn);
+        subject
+            .more_money_payable(SystemTime::UNIX_EPOCH, &wallet, 1_000)
+            .unwrap();
+
+        let result = subject
+            .adjust_balance(&wallet, -1_001)
+            .map(commit_balance_change);
+
+        assert_eq!(result, Err(PayableDaoError::BalanceBelowZero(-1)));
+        assert_eq!(subject.account_status(&wallet).unwrap().balance_wei, 1_000);
+    }
+
+    #[test]
+    fn adjust_balance_is_rolled_back_if_its_change_is_not_committed() {
+        let home_dir = ensure_node_home_directory_exists(
+            "payable_dao",
+            "adjust_balance_is_rolled_back_if_its_change_is_not_committed",
+        );
+        let wallet = make_wallet("booga");
+        let boxed_conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let mut subject = PayableDaoReal::new(boxed_conn);
+        subject
+            .more_money_payable(SystemTime::UNIX_EPOCH, &wallet, 1_000)
+            .unwrap();
+
+        let uncommitted = subject.adjust_balance(&wallet, -400).unwrap();
+        drop(uncommitted);
+
+        assert_eq!(subject.account_status(&wallet).unwrap().balance_wei, 1_000);
+    }
+
     #[test]
     fn more_money_payable_works_for_existing_address_without_overflow() {
         //asserting on correctness of the main sql clause
@@ -1149,6 +1393,69 @@ mod tests {
         assert_eq!(account_2_opt, None);
     }
 
+    #[test]
+    fn transaction_confirmed_settles_each_payable_of_a_batch_transaction_by_its_share() {
+        let home_dir = ensure_node_home_directory_exists(
+            "payable_dao",
+            "transaction_confirmed_settles_each_payable_of_a_batch_transaction_by_its_share",
+        );
+        let initializer = DbInitializerReal::default();
+        let subject = PayableDaoReal::new(
+            initializer
+                .initialize(&home_dir, DbInitializationConfig::test_default())
+                .unwrap(),
+        );
+        let pending_payable_dao = PendingPayableDaoReal::new(
+            initializer
+                .initialize(&home_dir, DbInitializationConfig::test_default())
+                .unwrap(),
+        );
+        let wallet_1 = make_wallet("creditor_1");
+        let wallet_2 = make_wallet("creditor_2");
+        let wallet_3 = make_wallet("creditor_3");
+        let batch_hash = make_tx_hash(0xbbb);
+        let paid_at = from_time_t(190_000_000);
+        [(&wallet_1, 5_000), (&wallet_2, 7_000), (&wallet_3, 9_000)]
+            .into_iter()
+            .for_each(|(wallet, amount)| {
+                subject
+                    .more_money_payable(from_time_t(180_000_000), wallet, amount)
+                    .unwrap()
+            });
+        pending_payable_dao
+            .insert_new_fingerprints(&[(batch_hash, 11_000)], paid_at)
+            .unwrap();
+        pending_payable_dao
+            .insert_batch_payments(&[(
+                batch_hash,
+                vec![(wallet_1.clone(), 4_000), (wallet_2.clone(), 7_000)],
+            )])
+            .unwrap();
+        subject
+            .mark_pending_payables_rowids(&[(&wallet_1, 1), (&wallet_2, 1)])
+            .unwrap();
+        let fingerprint = pending_payable_dao.return_all_errorless_fingerprints()[0].clone();
+        // Debts keep growing while the transaction is on its way
+        subject
+            .more_money_payable(from_time_t(195_000_000), &wallet_1, 1_500)
+            .unwrap();
+
+        let result = subject.transactions_confirmed(&[fingerprint]);
+
+        assert_eq!(result, Ok(()));
+        let balance_and_timestamp = |wallet: &Wallet| {
+            let account = subject.account_status(wallet).unwrap();
+            assert_eq!(account.pending_payable_opt, None);
+            (account.balance_wei, account.last_paid_timestamp)
+        };
+        assert_eq!(balance_and_timestamp(&wallet_1), (2_500, paid_at));
+        assert_eq!(balance_and_timestamp(&wallet_2), (0, paid_at));
+        assert_eq!(
+            balance_and_timestamp(&wallet_3),
+            (9_000, from_time_t(180_000_000))
+        );
+    }
+
     #[test]
     fn non_pending_payables_should_return_an_empty_vec_when_the_database_is_empty() {
         let home_dir = ensure_node_home_directory_exists(
@@ -1665,7 +1972,10 @@ mod tests {
     }
 
     fn payable_read_only_conn(path: &Path) -> Connection {
-        trick_rusqlite_with_read_only_conn(path, DbInitializerReal::create_payable_table)
+        trick_rusqlite_with_read_only_conn(path, |conn| {
+            DbInitializerReal::create_payable_table(conn);
+            DbInitializerReal::create_pending_payable_batch_payment_table(conn)
+        })
     }
 
     fn custom_query_test_body_for_payable<F>(test_name: &str, main_setup_fn: F) -> PayableDaoReal
@@ -4,12 +4,12 @@ use crate::accountant::db_access_objects::utils::{
     from_time_t, to_time_t, DaoFactoryReal, VigilantRusqliteFlatten,
 };
 use crate::accountant::db_big_integer::big_int_divider::BigIntDivider;
-use crate::accountant::{checked_conversion, comma_joined_stringifiable};
+use crate::accountant::{checked_conversion, comma_joined_stringifiable, PendingPayableId};
 use crate::blockchain::blockchain_bridge::PendingPayableFingerprint;
 use crate::database::rusqlite_wrappers::ConnectionWrapper;
 use crate::sub_lib::wallet::Wallet;
 use masq_lib::utils::ExpectValue;
-use rusqlite::Row;
+use rusqlite::{OptionalExtension, Row};
 use std::collections::HashSet;
 use std::fmt::Debug;
 use std::str::FromStr;
@@ -41,9 +41,25 @@ pub trait PendingPayableDao {
         hashes_and_amounts: &[(H256, u128)],
         batch_wide_timestamp: SystemTime,
     ) -> Result<(), PendingPayableDaoError>;
+    fn insert_batch_payments(
+        &self,
+        batch_payments: &[(H256, Vec<(Wallet, u128)>)],
+    ) -> Result<(), PendingPayableDaoError>;
     fn delete_fingerprints(&self, ids: &[u64]) -> Result<(), PendingPayableDaoError>;
     fn increment_scan_attempts(&self, ids: &[u64]) -> Result<(), PendingPayableDaoError>;
     fn mark_failures(&self, ids: &[u64]) -> Result<(), PendingPayableDaoError>;
+    fn insert_nonces(
+        &self,
+        hashes_and_nonces: &[(H256, u64)],
+        gas_price_wei: u64,
+    ) -> Result<(), PendingPayableDaoError>;
+    // Fingerprints sent before their nonces were recorded are left out
+    fn stuck_transactions(&self, ids: &[PendingPayableId]) -> Vec<StuckTransaction>;
+    fn fee_bumps(&self) -> Vec<PendingPayableFeeBump>;
+    fn record_fee_bump(
+        &mut self,
+        fee_bump: &PendingPayableFeeBump,
+    ) -> Result<(), PendingPayableDaoError>;
 }
 
 impl PendingPayableDao for PendingPayableDaoReal<'_> {
@@ -165,7 +181,62 @@ impl PendingPayableDao for PendingPayableDaoReal<'_> {
         }
     }
 
+    // Each creditor paid by a single batch transaction gets its share attached to the fingerprint
+    // of that transaction, it's what the payable is settled by once the transaction is confirmed
+    fn insert_batch_payments(
+        &self,
+        batch_payments: &[(H256, Vec<(Wallet, u128)>)],
+    ) -> Result<(), PendingPayableDaoError> {
+        let mut stm = self
+            .conn
+            .prepare(
+                "insert into pending_payable_batch_payment (pending_payable_rowid, wallet_address, \
+                 amount_high_b, amount_low_b) select rowid, ?, ?, ? from pending_payable where \
+                 transaction_hash = ?",
+            )
+            .expect("Internal error");
+        batch_payments
+            .iter()
+            .flat_map(|(hash, payments)| payments.iter().map(move |payment| (hash, payment)))
+            .try_for_each(|(hash, (wallet, amount))| {
+                let (high_bytes, low_bytes) =
+                    BigIntDivider::deconstruct(checked_conversion::<u128, i128>(*amount));
+                match stm.execute(rusqlite::params![
+                    wallet,
+                    high_bytes,
+                    low_bytes,
+                    format!("{:?}", hash)
+                ]) {
+                    Ok(1) => Ok(()),
+                    Ok(_) => Err(PendingPayableDaoError::InsertionFailed(format!(
+                        "no fingerprint for transaction {:?} paying {}",
+                        hash, wallet
+                    ))),
+                    Err(e) => Err(PendingPayableDaoError::InsertionFailed(e.to_string())),
+                }
+            })
+    }
+
     fn delete_fingerprints(&self, ids: &[u64]) -> Result<(), PendingPayableDaoError> {
+        for table in [
+            "pending_payable_fee_bump",
+            "pending_payable_batch_payment",
+            "pending_payable_nonce",
+        ] {
+            let sql = format!(
+                "delete from {} where pending_payable_rowid in ({})",
+                table,
+                Self::serialize_ids(ids)
+            );
+            if let Err(e) = self
+                .conn
+                .prepare(&sql)
+                .expect("delete command wrong")
+                .execute([])
+            {
+                return Err(PendingPayableDaoError::RecordDeletion(e.to_string()));
+            }
+        }
         let sql = format!(
             "delete from pending_payable where rowid in ({})",
             Self::serialize_ids(ids)
@@ -222,6 +293,150 @@ impl PendingPayableDao for PendingPayableDaoReal<'_> {
             Err(e) => Err(PendingPayableDaoError::ErrorMarkFailed(e.to_string())),
         }
     }
+
+    // A stuck transaction can be sent again only as long as we know the nonce it went out with
+    fn insert_nonces(
+        &self,
+        hashes_and_nonces: &[(H256, u64)],
+        gas_price_wei: u64,
+    ) -> Result<(), PendingPayableDaoError> {
+        let mut stm = self
+            .conn
+            .prepare(
+                "insert into pending_payable_nonce (pending_payable_rowid, nonce, gas_price_wei) \
+                 select rowid, ?, ? from pending_payable where transaction_hash = ?",
+            )
+            .expect("Internal error");
+        hashes_and_nonces.iter().try_for_each(|(hash, nonce)| {
+            match stm.execute(rusqlite::params![
+                checked_conversion::<u64, i64>(*nonce),
+                checked_conversion::<u64, i64>(gas_price_wei),
+                format!("{:?}", hash)
+            ]) {
+                Ok(1) => Ok(()),
+                Ok(_) => Err(PendingPayableDaoError::InsertionFailed(format!(
+                    "no fingerprint for transaction {:?} with nonce {}",
+                    hash, nonce
+                ))),
+                Err(e) => Err(PendingPayableDaoError::InsertionFailed(e.to_string())),
+            }
+        })
+    }
+
+    fn stuck_transactions(&self, ids: &[PendingPayableId]) -> Vec<StuckTransaction> {
+        ids.iter()
+            .flat_map(|id| {
+                let rowid = checked_conversion::<u64, i64>(id.rowid);
+                let (nonce, original_gas_price_wei, amount) = self
+                    .conn
+                    .prepare(
+                        "select nonce, gas_price_wei, amount_high_b, amount_low_b from \
+                         pending_payable_nonce join pending_payable on \
+                         pending_payable_rowid = pending_payable.rowid where pending_payable.rowid = ?",
+                    )
+                    .expect("Internal error")
+                    .query_row([rowid], |row| {
+                        let nonce: i64 = Self::get_with_expect(row, 0);
+                        let gas_price_wei: i64 = Self::get_with_expect(row, 1);
+                        let amount_high_bytes: i64 = Self::get_with_expect(row, 2);
+                        let amount_low_bytes: i64 = Self::get_with_expect(row, 3);
+                        Ok((
+                            checked_conversion::<i64, u64>(nonce),
+                            checked_conversion::<i64, u64>(gas_price_wei),
+                            checked_conversion::<i128, u128>(BigIntDivider::reconstitute(
+                                amount_high_bytes,
+                                amount_low_bytes,
+                            )),
+                        ))
+                    })
+                    .optional()
+                    .expect("rusqlite failure")?;
+                let payments = self.payments_of(rowid, amount);
+                if payments.is_empty() {
+                    return None;
+                }
+                Some(StuckTransaction {
+                    rowid: id.rowid,
+                    hash: id.hash,
+                    nonce,
+                    gas_price_wei: self
+                        .latest_bumped_gas_price_wei(rowid)
+                        .unwrap_or(original_gas_price_wei),
+                    payments,
+                })
+            })
+            .collect()
+    }
+
+    fn fee_bumps(&self) -> Vec<PendingPayableFeeBump> {
+        let mut stm = self
+            .conn
+            .prepare(
+                "select pending_payable_rowid, replaced_hash, replacement_hash, gas_price_wei, \
+                 bumped_at from pending_payable_fee_bump order by rowid",
+            )
+            .expect("Internal error");
+        stm.query_map([], |row| {
+            let pending_payable_rowid: u64 = Self::get_with_expect(row, 0);
+            let replaced_hash: String = Self::get_with_expect(row, 1);
+            let replacement_hash: String = Self::get_with_expect(row, 2);
+            let gas_price_wei: i64 = Self::get_with_expect(row, 3);
+            let bumped_at: i64 = Self::get_with_expect(row, 4);
+            Ok(PendingPayableFeeBump {
+                pending_payable_rowid,
+                replaced_hash: Self::parse_hash(&replaced_hash),
+                replacement_hash: Self::parse_hash(&replacement_hash),
+                gas_price_wei: checked_conversion::<i64, u64>(gas_price_wei),
+                timestamp: from_time_t(bumped_at),
+            })
+        })
+        .expect("rusqlite failure")
+        .vigilant_flatten()
+        .collect()
+    }
+
+    fn record_fee_bump(
+        &mut self,
+        fee_bump: &PendingPayableFeeBump,
+    ) -> Result<(), PendingPayableDaoError> {
+        // The fingerprint keeps following the transaction that is still able to make it into
+        // a block, its predecessors stay only as the history
+        let txn = self
+            .conn
+            .transaction()
+            .map_err(|e| PendingPayableDaoError::UpdateFailed(e.to_string()))?;
+        let rowid = checked_conversion::<u64, i64>(fee_bump.pending_payable_rowid);
+        let replaced_hash = format!("{:?}", fee_bump.replaced_hash);
+        let replacement_hash = format!("{:?}", fee_bump.replacement_hash);
+        match txn.execute(
+            "update pending_payable set transaction_hash = ? where rowid = ? and \
+             transaction_hash = ? and process_error is null",
+            &[&replacement_hash, &rowid, &replaced_hash],
+        ) {
+            Ok(1) => (),
+            Ok(_) => {
+                return Err(PendingPayableDaoError::UpdateFailed(format!(
+                    "no errorless fingerprint {} with hash {}",
+                    rowid, replaced_hash
+                )))
+            }
+            Err(e) => return Err(PendingPayableDaoError::UpdateFailed(e.to_string())),
+        }
+        txn.execute(
+            "insert into pending_payable_fee_bump (pending_payable_rowid, replaced_hash, \
+             replacement_hash, gas_price_wei, bumped_at) values (?, ?, ?, ?, ?)",
+            &[
+                &rowid,
+                &replaced_hash,
+                &replacement_hash,
+                &checked_conversion::<u64, i64>(fee_bump.gas_price_wei),
+                &to_time_t(fee_bump.timestamp),
+            ],
+        )
+        .map_err(|e| PendingPayableDaoError::InsertionFailed(e.to_string()))?;
+        txn.commit()
+            .map_err(|e| PendingPayableDaoError::UpdateFailed(e.to_string()))
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -239,6 +454,25 @@ impl PendingPayable {
     }
 }
 
+// What it takes to send a stuck transaction again, even after the blockchain node has forgotten it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StuckTransaction {
+    pub rowid: u64,
+    pub hash: H256,
+    pub nonce: u64,
+    pub gas_price_wei: u64,
+    pub payments: Vec<(Wallet, u128)>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PendingPayableFeeBump {
+    pub pending_payable_rowid: u64,
+    pub replaced_hash: H256,
+    pub replacement_hash: H256,
+    pub gas_price_wei: u64,
+    pub timestamp: SystemTime,
+}
+
 #[derive(Debug)]
 pub struct PendingPayableDaoReal<'a> {
     conn: Box<dyn ConnectionWrapper + 'a>,
@@ -253,6 +487,67 @@ impl<'a> PendingPayableDaoReal<'a> {
         row.get(index).expect("database is corrupt")
     }
 
+    fn parse_hash(hash_str: &str) -> H256 {
+        H256::from_str(&hash_str[2..]).unwrap_or_else(|e| {
+            panic!(
+                "Invalid hash format (\"{}\": {:?}) - database corrupt",
+                hash_str, e
+            )
+        })
+    }
+
+    fn latest_bumped_gas_price_wei(&self, rowid: i64) -> Option<u64> {
+        self.conn
+            .prepare(
+                "select gas_price_wei from pending_payable_fee_bump where pending_payable_rowid = ? \
+                 order by rowid desc limit 1",
+            )
+            .expect("Internal error")
+            .query_row([rowid], |row| row.get::<usize, i64>(0))
+            .optional()
+            .expect("rusqlite failure")
+            .map(checked_conversion::<i64, u64>)
+    }
+
+    // A batch transaction has its shares recorded, a plain one pays the whole amount to the single
+    // payable waiting for it
+    fn payments_of(&self, rowid: i64, amount: u128) -> Vec<(Wallet, u128)> {
+        let shares = self
+            .conn
+            .prepare(
+                "select wallet_address, amount_high_b, amount_low_b from \
+                 pending_payable_batch_payment where pending_payable_rowid = ? order by rowid",
+            )
+            .expect("Internal error")
+            .query_map([rowid], |row| {
+                let wallet: Wallet = Self::get_with_expect(row, 0);
+                let high_bytes: i64 = Self::get_with_expect(row, 1);
+                let low_bytes: i64 = Self::get_with_expect(row, 2);
+                Ok((
+                    wallet,
+                    checked_conversion::<i128, u128>(BigIntDivider::reconstitute(
+                        high_bytes, low_bytes,
+                    )),
+                ))
+            })
+            .expect("rusqlite failure")
+            .vigilant_flatten()
+            .collect::<Vec<(Wallet, u128)>>();
+        if !shares.is_empty() {
+            return shares;
+        }
+        self.conn
+            .prepare("select wallet_address from payable where pending_payable_rowid = ?")
+            .expect("Internal error")
+            .query_map([rowid], |row| {
+                let wallet: Wallet = Self::get_with_expect(row, 0);
+                Ok((wallet, amount))
+            })
+            .expect("rusqlite failure")
+            .vigilant_flatten()
+            .collect()
+    }
+
     fn serialize_ids(ids: &[u64]) -> String {
         comma_joined_stringifiable(ids, |id| id.to_string())
     }
@@ -272,10 +567,12 @@ impl PendingPayableDaoFactory for DaoFactoryReal {
 mod tests {
     use crate::accountant::checked_conversion;
     use crate::accountant::db_access_objects::pending_payable_dao::{
-        PendingPayableDao, PendingPayableDaoError, PendingPayableDaoReal,
+        PendingPayableDao, PendingPayableDaoError, PendingPayableDaoReal, PendingPayableFeeBump,
+        StuckTransaction,
     };
     use crate::accountant::db_access_objects::utils::from_time_t;
     use crate::accountant::db_big_integer::big_int_divider::BigIntDivider;
+    use crate::accountant::PendingPayableId;
     use crate::blockchain::blockchain_bridge::PendingPayableFingerprint;
     use crate::blockchain::test_utils::make_tx_hash;
     use crate::database::db_initializer::{
@@ -283,8 +580,11 @@ mod tests {
     };
     use crate::database::rusqlite_wrappers::ConnectionWrapperReal;
     use crate::database::test_utils::ConnectionWrapperMock;
+    use crate::sub_lib::wallet::Wallet;
+    use crate::test_utils::make_wallet;
     use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
     use rusqlite::{Connection, OpenFlags};
+    use std::path::Path;
     use std::str::FromStr;
     use std::time::SystemTime;
     use web3::types::H256;
@@ -863,4 +1163,382 @@ mod tests {
 
         let _ = subject.mark_failures(&[10, 20]);
     }
+
+    #[test]
+    fn record_fee_bump_redirects_the_fingerprint_and_keeps_the_history() {
+        let home_dir = ensure_node_home_directory_exists(
+            "pending_payable_dao",
+            "record_fee_bump_redirects_the_fingerprint_and_keeps_the_history",
+        );
+        let conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let mut subject = PendingPayableDaoReal::new(conn);
+        let timestamp = from_time_t(190_000_000);
+        subject
+            .insert_new_fingerprints(
+                &[(make_tx_hash(111), 1234), (make_tx_hash(222), 5678)],
+                timestamp,
+            )
+            .unwrap();
+        let first_bump = PendingPayableFeeBump {
+            pending_payable_rowid: 2,
+            replaced_hash: make_tx_hash(222),
+            replacement_hash: make_tx_hash(333),
+            gas_price_wei: 1_200_000_000,
+            timestamp: from_time_t(190_001_000),
+        };
+        let second_bump = PendingPayableFeeBump {
+            pending_payable_rowid: 2,
+            replaced_hash: make_tx_hash(333),
+            replacement_hash: make_tx_hash(444),
+            gas_price_wei: 1_440_000_000,
+            timestamp: from_time_t(190_002_000),
+        };
+
+        let first_result = subject.record_fee_bump(&first_bump);
+        let second_result = subject.record_fee_bump(&second_bump);
+
+        assert_eq!(first_result, Ok(()));
+        assert_eq!(second_result, Ok(()));
+        assert_eq!(subject.fee_bumps(), vec![first_bump, second_bump]);
+        let fingerprints = subject.return_all_errorless_fingerprints();
+        assert_eq!(
+            fingerprints
+                .iter()
+                .map(|fingerprint| (fingerprint.rowid, fingerprint.hash, fingerprint.timestamp))
+                .collect::<Vec<_>>(),
+            vec![
+                (1, make_tx_hash(111), timestamp),
+                (2, make_tx_hash(444), timestamp)
+            ]
+        );
+    }
+
+    #[test]
+    fn record_fee_bump_refuses_a_fingerprint_that_already_moved_on() {
+        let home_dir = ensure_node_home_directory_exists(
+            "pending_payable_dao",
+            "record_fee_bump_refuses_a_fingerprint_that_already_moved_on",
+        );
+        let conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let mut subject = PendingPayableDaoReal::new(conn);
+        subject
+            .insert_new_fingerprints(&[(make_tx_hash(111), 1234)], from_time_t(190_000_000))
+            .unwrap();
+        let fee_bump = PendingPayableFeeBump {
+            pending_payable_rowid: 1,
+            replaced_hash: make_tx_hash(999),
+            replacement_hash: make_tx_hash(333),
+            gas_price_wei: 1_200_000_000,
+            timestamp: from_time_t(190_001_000),
+        };
+
+        let result = subject.record_fee_bump(&fee_bump);
+
+        assert_eq!(
+            result,
+            Err(PendingPayableDaoError::UpdateFailed(format!(
+                "no errorless fingerprint 1 with hash {:?}",
+                make_tx_hash(999)
+            )))
+        );
+        assert_eq!(subject.fee_bumps(), vec![]);
+        assert_eq!(
+            subject.return_all_errorless_fingerprints()[0].hash,
+            make_tx_hash(111)
+        );
+    }
+
+    #[test]
+    fn delete_fingerprints_removes_also_their_fee_bumps() {
+        let home_dir = ensure_node_home_directory_exists(
+            "pending_payable_dao",
+            "delete_fingerprints_removes_also_their_fee_bumps",
+        );
+        let conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let mut subject = PendingPayableDaoReal::new(conn);
+        subject
+            .insert_new_fingerprints(
+                &[(make_tx_hash(111), 1234), (make_tx_hash(222), 5678)],
+                from_time_t(190_000_000),
+            )
+            .unwrap();
+        let make_bump = |rowid: u64, replaced: u32, replacement: u32| PendingPayableFeeBump {
+            pending_payable_rowid: rowid,
+            replaced_hash: make_tx_hash(replaced),
+            replacement_hash: make_tx_hash(replacement),
+            gas_price_wei: 1_000_000_000,
+            timestamp: from_time_t(190_001_000),
+        };
+        subject.record_fee_bump(&make_bump(1, 111, 333)).unwrap();
+        let remaining_bump = make_bump(2, 222, 444);
+        subject.record_fee_bump(&remaining_bump).unwrap();
+
+        let result = subject.delete_fingerprints(&[1]);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(subject.fee_bumps(), vec![remaining_bump]);
+    }
+
+    #[test]
+    fn insert_batch_payments_attaches_shares_to_the_fingerprint_of_their_transaction() {
+        let home_dir = ensure_node_home_directory_exists(
+            "pending_payable_dao",
+            "insert_batch_payments_attaches_shares_to_the_fingerprint_of_their_transaction",
+        );
+        let conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let subject = PendingPayableDaoReal::new(conn);
+        let batch_hash = make_tx_hash(222);
+        let huge_share = u64::MAX as u128 * 1_000;
+        subject
+            .insert_new_fingerprints(
+                &[(make_tx_hash(111), 1234), (batch_hash, 5678 + huge_share)],
+                from_time_t(190_000_000),
+            )
+            .unwrap();
+
+        let result = subject.insert_batch_payments(&[(
+            batch_hash,
+            vec![(make_wallet("abc"), 5678), (make_wallet("def"), huge_share)],
+        )]);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            read_batch_payments(&home_dir),
+            vec![
+                (2, make_wallet("abc"), 5678),
+                (2, make_wallet("def"), huge_share)
+            ]
+        );
+    }
+
+    #[test]
+    fn insert_batch_payments_complains_about_a_transaction_without_fingerprint() {
+        let home_dir = ensure_node_home_directory_exists(
+            "pending_payable_dao",
+            "insert_batch_payments_complains_about_a_transaction_without_fingerprint",
+        );
+        let conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let subject = PendingPayableDaoReal::new(conn);
+
+        let result =
+            subject.insert_batch_payments(&[(make_tx_hash(333), vec![(make_wallet("abc"), 5678)])]);
+
+        assert_eq!(
+            result,
+            Err(PendingPayableDaoError::InsertionFailed(format!(
+                "no fingerprint for transaction {:?} paying {}",
+                make_tx_hash(333),
+                make_wallet("abc")
+            )))
+        );
+        assert_eq!(read_batch_payments(&home_dir), vec![]);
+    }
+
+    #[test]
+    fn delete_fingerprints_removes_also_their_batch_payments() {
+        let home_dir = ensure_node_home_directory_exists(
+            "pending_payable_dao",
+            "delete_fingerprints_removes_also_their_batch_payments",
+        );
+        let conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let subject = PendingPayableDaoReal::new(conn);
+        subject
+            .insert_new_fingerprints(
+                &[(make_tx_hash(111), 300), (make_tx_hash(222), 700)],
+                from_time_t(190_000_000),
+            )
+            .unwrap();
+        subject
+            .insert_batch_payments(&[
+                (
+                    make_tx_hash(111),
+                    vec![(make_wallet("abc"), 100), (make_wallet("def"), 200)],
+                ),
+                (
+                    make_tx_hash(222),
+                    vec![(make_wallet("ghi"), 300), (make_wallet("jkl"), 400)],
+                ),
+            ])
+            .unwrap();
+
+        let result = subject.delete_fingerprints(&[1]);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            read_batch_payments(&home_dir),
+            vec![(2, make_wallet("ghi"), 300), (2, make_wallet("jkl"), 400)]
+        );
+    }
+
+    #[test]
+    fn stuck_transactions_are_put_together_from_the_recorded_nonces() {
+        let home_dir = ensure_node_home_directory_exists(
+            "pending_payable_dao",
+            "stuck_transactions_are_put_together_from_the_recorded_nonces",
+        );
+        let conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let mut subject = PendingPayableDaoReal::new(conn);
+        let (plain_hash, batch_hash, unrecorded_hash) =
+            (make_tx_hash(111), make_tx_hash(222), make_tx_hash(333));
+        subject
+            .insert_new_fingerprints(
+                &[
+                    (plain_hash, 1234),
+                    (batch_hash, 700),
+                    (unrecorded_hash, 999),
+                ],
+                from_time_t(190_000_000),
+            )
+            .unwrap();
+        insert_payable(&home_dir, &make_wallet("plain"), 1);
+        subject
+            .insert_batch_payments(&[(
+                batch_hash,
+                vec![(make_wallet("abc"), 300), (make_wallet("def"), 400)],
+            )])
+            .unwrap();
+        let insert_nonces_result =
+            subject.insert_nonces(&[(plain_hash, 14), (batch_hash, 15)], 1_000_000_000);
+        let replacement_hash = make_tx_hash(444);
+        subject
+            .record_fee_bump(&PendingPayableFeeBump {
+                pending_payable_rowid: 2,
+                replaced_hash: batch_hash,
+                replacement_hash,
+                gas_price_wei: 1_200_000_000,
+                timestamp: from_time_t(190_001_000),
+            })
+            .unwrap();
+
+        let result = subject.stuck_transactions(&[
+            PendingPayableId::new(1, plain_hash),
+            PendingPayableId::new(2, replacement_hash),
+            PendingPayableId::new(3, unrecorded_hash),
+        ]);
+
+        assert_eq!(insert_nonces_result, Ok(()));
+        assert_eq!(
+            result,
+            vec![
+                StuckTransaction {
+                    rowid: 1,
+                    hash: plain_hash,
+                    nonce: 14,
+                    gas_price_wei: 1_000_000_000,
+                    payments: vec![(make_wallet("plain"), 1234)],
+                },
+                StuckTransaction {
+                    rowid: 2,
+                    hash: replacement_hash,
+                    nonce: 15,
+                    gas_price_wei: 1_200_000_000,
+                    payments: vec![(make_wallet("abc"), 300), (make_wallet("def"), 400)],
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn insert_nonces_complains_about_a_transaction_without_fingerprint() {
+        let home_dir = ensure_node_home_directory_exists(
+            "pending_payable_dao",
+            "insert_nonces_complains_about_a_transaction_without_fingerprint",
+        );
+        let conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let subject = PendingPayableDaoReal::new(conn);
+
+        let result = subject.insert_nonces(&[(make_tx_hash(333), 14)], 1_000_000_000);
+
+        assert_eq!(
+            result,
+            Err(PendingPayableDaoError::InsertionFailed(format!(
+                "no fingerprint for transaction {:?} with nonce 14",
+                make_tx_hash(333)
+            )))
+        );
+    }
+
+    #[test]
+    fn delete_fingerprints_removes_also_their_nonces() {
+        let home_dir = ensure_node_home_directory_exists(
+            "pending_payable_dao",
+            "delete_fingerprints_removes_also_their_nonces",
+        );
+        let conn = DbInitializerReal::default()
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        let subject = PendingPayableDaoReal::new(conn);
+        subject
+            .insert_new_fingerprints(
+                &[(make_tx_hash(111), 300), (make_tx_hash(222), 700)],
+                from_time_t(190_000_000),
+            )
+            .unwrap();
+        subject
+            .insert_nonces(&[(make_tx_hash(111), 14), (make_tx_hash(222), 15)], 1_000)
+            .unwrap();
+
+        let result = subject.delete_fingerprints(&[1]);
+
+        assert_eq!(result, Ok(()));
+        let conn = Connection::open(home_dir.join(DATABASE_FILE)).unwrap();
+        let remaining: Vec<(u64, u64)> = conn
+            .prepare("select pending_payable_rowid, nonce from pending_payable_nonce")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0).unwrap(), row.get(1).unwrap())))
+            .unwrap()
+            .flatten()
+            .collect();
+        assert_eq!(remaining, vec![(2, 15)]);
+    }
+
+    fn insert_payable(home_dir: &Path, wallet: &Wallet, pending_payable_rowid: u64) {
+        let conn = Connection::open(home_dir.join(DATABASE_FILE)).unwrap();
+        conn.execute(
+            "insert into payable (wallet_address, balance_high_b, balance_low_b, \
+             last_paid_timestamp, pending_payable_rowid) values (?, 0, 1234, 0, ?)",
+            rusqlite::params![wallet, pending_payable_rowid],
+        )
+        .unwrap();
+    }
+
+    fn read_batch_payments(home_dir: &Path) -> Vec<(u64, Wallet, u128)> {
+        let conn = Connection::open(home_dir.join(DATABASE_FILE)).unwrap();
+        let mut stm = conn
+            .prepare(
+                "select pending_payable_rowid, wallet_address, amount_high_b, amount_low_b \
+                 from pending_payable_batch_payment order by rowid",
+            )
+            .unwrap();
+        stm.query_map([], |row| {
+            let rowid: u64 = row.get(0).unwrap();
+            let wallet: Wallet = row.get(1).unwrap();
+            let high_bytes: i64 = row.get(2).unwrap();
+            let low_bytes: i64 = row.get(3).unwrap();
+            Ok((
+                rowid,
+                wallet,
+                BigIntDivider::reconstitute(high_bytes, low_bytes) as u128,
+            ))
+        })
+        .unwrap()
+        .flatten()
+        .collect()
+    }
 }
@@ -1,6 +1,9 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
 use crate::accountant::checked_conversion;
+use crate::accountant::db_access_objects::ledger_adjustment_dao::{
+    BalanceChange, UncommittedBalanceChange,
+};
 use crate::accountant::db_access_objects::receivable_dao::ReceivableDaoError::RusqliteError;
 use crate::accountant::db_access_objects::utils;
 use crate::accountant::db_access_objects::utils::{
@@ -28,7 +31,9 @@ use masq_lib::utils::ExpectValue;
 use rusqlite::OptionalExtension;
 use rusqlite::Row;
 use rusqlite::{named_params, Error};
+use std::str::FromStr;
 use std::time::SystemTime;
+use web3::types::H256;
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum ReceivableDaoError {
@@ -50,6 +55,26 @@ pub struct ReceivableAccount {
     pub last_received_timestamp: SystemTime,
 }
 
+// An inbound payment that was seen on the blockchain but is still young enough to be undone by
+// a reorganization
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingReceivable {
+    pub transaction: BlockchainTransaction,
+    pub credited: bool,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ReceivablesReconciliation {
+    // Payments that have just reached the confirmation depth
+    pub to_credit: Vec<BlockchainTransaction>,
+    // Credited payments whose block was replaced by a reorganization
+    pub to_roll_back: Vec<BlockchainTransaction>,
+    // Payments yet below the confirmation depth
+    pub to_hold: Vec<BlockchainTransaction>,
+    // Pending payments that vanished, or credited ones buried too deep to be reorganized anymore
+    pub to_release: Vec<BlockchainTransaction>,
+}
+
 pub trait ReceivableDao {
     fn more_money_receivable(
         &self,
@@ -64,6 +89,14 @@ pub trait ReceivableDao {
         transactions: &[BlockchainTransaction],
     ) -> TransactionSafeWrapper;
 
+    fn pending_receivables(&self) -> Vec<PendingReceivable>;
+
+    fn reconcile_received_payments(
+        &mut self,
+        now: SystemTime,
+        reconciliation: &ReceivablesReconciliation,
+    ) -> TransactionSafeWrapper<'_>;
+
     fn new_delinquencies(
         &self,
         now: SystemTime,
@@ -76,7 +109,13 @@ pub trait ReceivableDao {
 
     fn total(&self) -> i128;
 
-    // Test-only method but because of shares with multi-node tests #[cfg(test)] cannot be applied
+    // None if the wallet has no receivable to adjust. The change isn't committed yet.
+    fn adjust_balance(
+        &mut self,
+        wallet: &Wallet,
+        change_wei: i128,
+    ) -> Result<Option<UncommittedBalanceChange<'_>>, ReceivableDaoError>;
+
     fn account_status(&self, wallet: &Wallet) -> Option<ReceivableAccount>;
 
     as_any_ref_in_trait!();
@@ -187,6 +226,66 @@ impl ReceivableDao for ReceivableDaoReal {
         }
     }
 
+    fn pending_receivables(&self) -> Vec<PendingReceivable> {
+        let sql = "select transaction_hash, log_index, block_number, block_hash, wallet_address, \
+        amount_high_b, amount_low_b, credited from pending_receivable order by block_number asc, rowid asc";
+        self.conn
+            .prepare(sql)
+            .expect("Couldn't prepare statement")
+            .query_map([], Self::create_pending_receivable)
+            .expect("Couldn't retrieve pending receivables: database corruption")
+            .vigilant_flatten()
+            .collect()
+    }
+
+    fn reconcile_received_payments(
+        &mut self,
+        timestamp: SystemTime,
+        reconciliation: &ReceivablesReconciliation,
+    ) -> TransactionSafeWrapper<'_> {
+        // Everything goes in one txn together with the start block update that follows, so that
+        // a crash in between cannot credit or revert anything twice
+        let reconciliation_result = match self.conn.transaction() {
+            Ok(txn) => {
+                let big_int_db_processor = &*self.big_int_db_processor;
+                let logger = &self.logger;
+
+                Self::process_received_payments_and_return_txn(
+                    big_int_db_processor,
+                    &reconciliation.to_credit,
+                    timestamp,
+                    txn,
+                    logger,
+                )
+                .and_then(|txn| {
+                    Self::revert_received_payments_and_return_txn(
+                        big_int_db_processor,
+                        &reconciliation.to_roll_back,
+                        txn,
+                    )
+                })
+                .and_then(|txn| {
+                    Self::update_pending_receivables_and_return_txn(reconciliation, txn)
+                })
+            }
+            Err(e) => Err(ReceivableDaoError::from(e)),
+        };
+
+        match reconciliation_result {
+            Ok(txn) => txn,
+            Err(e) => {
+                Self::log_more_money_received_error_with_roll_back(
+                    &self.logger,
+                    &reconciliation.to_credit,
+                );
+                panic!(
+                    "Database corruption suspected during reconciliation of received payments: {:?}",
+                    e
+                )
+            }
+        }
+    }
+
     fn new_delinquencies(
         &self,
         now: SystemTime,
@@ -291,6 +390,61 @@ impl ReceivableDao for ReceivableDaoReal {
         )
     }
 
+    fn adjust_balance(
+        &mut self,
+        wallet: &Wallet,
+        change_wei: i128,
+    ) -> Result<Option<UncommittedBalanceChange<'_>>, ReceivableDaoError> {
+        let txn = self
+            .conn
+            .transaction()
+            .map_err(|e| RusqliteError(e.to_string()))?;
+        let before_wei = match txn
+            .prepare(
+                "select balance_high_b, balance_low_b from receivable where wallet_address = ?",
+            )
+            .and_then(|mut stm| {
+                stm.query_row([&wallet], |row| {
+                    Ok(BigIntDivider::reconstitute(row.get(0)?, row.get(1)?))
+                })
+                .optional()
+            }) {
+            Ok(Some(balance)) => balance,
+            Ok(None) => return Ok(None),
+            Err(e) => return Err(RusqliteError(e.to_string())),
+        };
+        let main_sql = "update receivable set balance_high_b = balance_high_b + :balance_high_b, \
+                 balance_low_b = balance_low_b + :balance_low_b where wallet_address = :wallet";
+        let update_clause_with_compensated_overflow = "update receivable set \
+                 balance_high_b = :balance_high_b, balance_low_b = :balance_low_b where wallet_address = :wallet";
+        let direction = if change_wei < 0 {
+            WeiChangeDirection::Subtraction
+        } else {
+            WeiChangeDirection::Addition
+        };
+        let params = SQLParamsBuilder::default()
+            .key(WalletAddress(wallet))
+            .wei_change(WeiChange::new(
+                "balance",
+                change_wei.unsigned_abs(),
+                direction,
+            ))
+            .build();
+
+        self.big_int_db_processor.execute(
+            Either::Right(&txn),
+            BigIntSqlConfig::new(main_sql, update_clause_with_compensated_overflow, params),
+        )?;
+
+        Ok(Some(UncommittedBalanceChange {
+            change: BalanceChange {
+                before_wei,
+                after_wei: before_wei + change_wei,
+            },
+            txn,
+        }))
+    }
+
     fn account_status(&self, wallet: &Wallet) -> Option<ReceivableAccount> {
         let mut stmt = self
             .conn
@@ -370,6 +524,110 @@ impl ReceivableDaoReal {
         }
     }
 
+    fn revert_received_payments_and_return_txn<'txn>(
+        big_int_db_processor: &dyn BigIntDbProcessor<ReceivableDaoReal>,
+        reverted_payments: &[BlockchainTransaction],
+        txn: TransactionSafeWrapper<'txn>,
+    ) -> Result<TransactionSafeWrapper<'txn>, ReceivableDaoError> {
+        let main_sql = "update receivable set balance_high_b = balance_high_b + :balance_high_b, \
+                 balance_low_b = balance_low_b + :balance_low_b where wallet_address = :wallet";
+        let update_clause_with_compensated_overflow =
+            "update receivable set balance_high_b = :balance_high_b, \
+                 balance_low_b = :balance_low_b where wallet_address = :wallet";
+
+        reverted_payments
+            .iter()
+            .try_for_each(|reverted_payment| {
+                let params = SQLParamsBuilder::default()
+                    .key(WalletAddress(&reverted_payment.from))
+                    .wei_change(WeiChange::new(
+                        "balance",
+                        reverted_payment.wei_amount,
+                        WeiChangeDirection::Addition,
+                    ))
+                    .build();
+
+                match big_int_db_processor.execute(
+                    Either::Right(&txn),
+                    BigIntSqlConfig::new(main_sql, update_clause_with_compensated_overflow, params),
+                ) {
+                    Ok(_) => Ok(()),
+                    Err(BigIntDatabaseError::General(err_msg)) => {
+                        Err(ReceivableDaoError::RusqliteError(err_msg))
+                    }
+                    // Payments from unknown debtors were never credited to anyone
+                    Err(BigIntDatabaseError::RowChangeMismatch { .. }) => Ok(()),
+                }
+            })
+            .map(|_| txn)
+    }
+
+    fn update_pending_receivables_and_return_txn<'txn>(
+        reconciliation: &ReceivablesReconciliation,
+        txn: TransactionSafeWrapper<'txn>,
+    ) -> Result<TransactionSafeWrapper<'txn>, ReceivableDaoError> {
+        let delete_sql =
+            "delete from pending_receivable where transaction_hash = ? and log_index = ?";
+        let upsert_sql = "insert into pending_receivable (transaction_hash, log_index, block_number, \
+        block_hash, wallet_address, amount_high_b, amount_low_b, credited) values (?, ?, ?, ?, ?, ?, ?, ?) \
+        on conflict (transaction_hash, log_index) do update set block_number = excluded.block_number, \
+        block_hash = excluded.block_hash, credited = excluded.credited";
+        let delete = |transaction: &BlockchainTransaction| {
+            txn.execute(
+                delete_sql,
+                &[
+                    &format!("{:?}", transaction.transaction_hash),
+                    &checked_conversion::<u64, i64>(transaction.log_index),
+                ],
+            )
+        };
+        let upsert = |transaction: &BlockchainTransaction, credited: bool| {
+            let (amount_high_b, amount_low_b) =
+                BigIntDivider::deconstruct(checked_conversion::<u128, i128>(
+                    transaction.wei_amount,
+                ));
+            txn.execute(
+                upsert_sql,
+                &[
+                    &format!("{:?}", transaction.transaction_hash),
+                    &checked_conversion::<u64, i64>(transaction.log_index),
+                    &checked_conversion::<u64, i64>(transaction.block_number),
+                    &format!("{:?}", transaction.block_hash),
+                    &transaction.from,
+                    &amount_high_b,
+                    &amount_low_b,
+                    &credited,
+                ],
+            )
+        };
+
+        reconciliation
+            .to_roll_back
+            .iter()
+            .try_for_each(|transaction| delete(transaction).map(|_| ()))
+            .and_then(|_| {
+                reconciliation
+                    .to_credit
+                    .iter()
+                    .try_for_each(|transaction| upsert(transaction, true).map(|_| ()))
+            })
+            .and_then(|_| {
+                reconciliation
+                    .to_hold
+                    .iter()
+                    .try_for_each(|transaction| upsert(transaction, false).map(|_| ()))
+            })
+            .and_then(|_| {
+                reconciliation
+                    .to_release
+                    .iter()
+                    .try_for_each(|transaction| delete(transaction).map(|_| ()))
+            })
+            .map_err(ReceivableDaoError::from)?;
+
+        Ok(txn)
+    }
+
     fn verify_possibly_unknown_wallet(
         txn: &TransactionSafeWrapper,
         logger: &Logger,
@@ -424,6 +682,39 @@ impl ReceivableDaoReal {
         }
     }
 
+    fn create_pending_receivable(row: &Row) -> rusqlite::Result<PendingReceivable> {
+        let transaction_hash: String = row.get(0)?;
+        let log_index: i64 = row.get(1)?;
+        let block_number: i64 = row.get(2)?;
+        let block_hash: String = row.get(3)?;
+        let from: Wallet = row.get(4)?;
+        let amount_high_b: i64 = row.get(5)?;
+        let amount_low_b: i64 = row.get(6)?;
+        let credited: bool = row.get(7)?;
+        let parse_hash = |hash_str: &str| {
+            H256::from_str(&hash_str[2..]).unwrap_or_else(|e| {
+                panic!(
+                    "Invalid hash format (\"{}\": {:?}) - database corrupt",
+                    hash_str, e
+                )
+            })
+        };
+        Ok(PendingReceivable {
+            transaction: BlockchainTransaction {
+                block_number: checked_conversion::<i64, u64>(block_number),
+                block_hash: parse_hash(&block_hash),
+                transaction_hash: parse_hash(&transaction_hash),
+                log_index: checked_conversion::<i64, u64>(log_index),
+                from,
+                wei_amount: checked_conversion::<i128, u128>(BigIntDivider::reconstitute(
+                    amount_high_b,
+                    amount_low_b,
+                )),
+            },
+            credited,
+        })
+    }
+
     fn stm_assembler_of_receivable_cq(feeder: AssemblerFeeder) -> String {
         format!(
             "select
@@ -498,7 +789,7 @@ mod tests {
     use crate::accountant::gwei_to_wei;
     use crate::accountant::test_utils::{
         assert_account_creation_fn_fails_on_finding_wrong_columns_and_value_types,
-        make_receivable_account, trick_rusqlite_with_read_only_conn,
+        commit_balance_change, make_receivable_account, trick_rusqlite_with_read_only_conn,
     };
     use crate::database::db_initializer::{DbInitializationConfig, DbInitializer, DATABASE_FILE};
     use crate::database::db_initializer::{DbInitializerReal, ExternalData};
@@ -571,6 +862,9 @@ mod tests {
         );
         let payments = vec![BlockchainTransaction {
             block_number: 42u64,
+            block_hash: H256::from_low_u64_be(42),
+            transaction_hash: H256::from_low_u64_be(1),
+            log_index: 0,
             from: make_wallet("some_address"),
             wei_amount: u128::MAX,
         }];
@@ -596,6 +890,9 @@ mod tests {
 
         let payments = vec![BlockchainTransaction {
             block_number: 42u64,
+            block_hash: H256::from_low_u64_be(42),
+            transaction_hash: H256::from_low_u64_be(2),
+            log_index: 0,
             from: make_wallet("some_address"),
             wei_amount: 18446744073709551615,
         }];
@@ -628,6 +925,50 @@ mod tests {
         assert_eq!(to_time_t(status.last_received_timestamp), payment_time_t);
     }
 
+    #[test]
+    fn adjust_balance_moves_receivable_across_zero_and_reports_the_change() {
+        let home_dir = ensure_node_home_directory_exists(
+            "receivable_dao",
+            "adjust_balance_moves_receivable_across_zero_and_reports_the_change",
+        );
+        let wallet = make_wallet("booga");
+        let unknown_wallet = make_wallet("unknown");
+        let mut subject = ReceivableDaoReal::new(
+            DbInitializerReal::default()
+                .initialize(&home_dir, DbInitializationConfig::test_default())
+                .unwrap(),
+        );
+        subject
+            .more_money_receivable(SystemTime::UNIX_EPOCH, &wallet, 1_234)
+            .unwrap();
+
+        let decrease = commit_balance_change(subject.adjust_balance(&wallet, -2_000).unwrap());
+        let increase =
+            commit_balance_change(subject.adjust_balance(&wallet, u64::MAX as i128).unwrap());
+        let unknown = commit_balance_change(subject.adjust_balance(&unknown_wallet, 500).unwrap());
+
+        assert_eq!(
+            decrease,
+            Some(BalanceChange {
+                before_wei: 1_234,
+                after_wei: -766
+            })
+        );
+        assert_eq!(
+            increase,
+            Some(BalanceChange {
+                before_wei: -766,
+                after_wei: u64::MAX as i128 - 766
+            })
+        );
+        assert_eq!(unknown, None);
+        assert_eq!(
+            subject.account_status(&wallet).unwrap().balance_wei,
+            u64::MAX as i128 - 766
+        );
+        assert_eq!(subject.account_status(&unknown_wallet), None);
+    }
+
     #[test]
     fn more_money_receivable_works_for_existing_address_without_overflow() {
         //testing correctness of the main SQL
@@ -797,11 +1138,17 @@ mod tests {
                 from: debtor1.clone(),
                 wei_amount: first_newly_received,
                 block_number: 35_u64,
+                block_hash: H256::from_low_u64_be(35),
+                transaction_hash: H256::from_low_u64_be(11),
+                log_index: 0,
             },
             BlockchainTransaction {
                 from: debtor2.clone(),
                 wei_amount: second_newly_received,
                 block_number: 57_u64,
+                block_hash: H256::from_low_u64_be(57),
+                transaction_hash: H256::from_low_u64_be(12),
+                log_index: 0,
             },
         ];
 
@@ -824,6 +1171,138 @@ mod tests {
         );
     }
 
+    #[test]
+    fn reconcile_received_payments_credits_rolls_back_holds_and_releases_in_one_transaction() {
+        let home_dir = ensure_node_home_directory_exists(
+            "receivable_dao",
+            "reconcile_received_payments_credits_rolls_back_holds_and_releases_in_one_transaction",
+        );
+        let debtor_1 = make_wallet("debtor_1");
+        let debtor_2 = make_wallet("debtor_2");
+        let debtor_3 = make_wallet("debtor_3");
+        let previous_timestamp = SystemTime::UNIX_EPOCH;
+        let payment_time = from_time_t(1_700_000_000);
+        let mut subject = ReceivableDaoReal::new(
+            DbInitializerReal::default()
+                .initialize(&home_dir, DbInitializationConfig::test_default())
+                .unwrap(),
+        );
+        subject
+            .more_money_receivable(previous_timestamp, &debtor_1, 10_000)
+            .unwrap();
+        subject
+            .more_money_receivable(previous_timestamp, &debtor_2, 20_000)
+            .unwrap();
+        let make_payment = |id: u64, from: &Wallet, wei_amount: u128| BlockchainTransaction {
+            block_number: 100 + id,
+            block_hash: H256::from_low_u64_be(100 + id),
+            transaction_hash: H256::from_low_u64_be(id),
+            log_index: 0,
+            from: from.clone(),
+            wei_amount,
+        };
+        let credited_payment = make_payment(1, &debtor_1, 4_000);
+        let payment_to_roll_back = make_payment(2, &debtor_2, 5_000);
+        let held_payment = make_payment(3, &debtor_3, 6_000);
+        let released_payment = make_payment(4, &debtor_3, 7_000);
+        {
+            let txn = subject.reconcile_received_payments(
+                previous_timestamp,
+                &ReceivablesReconciliation {
+                    to_credit: vec![payment_to_roll_back.clone()],
+                    to_hold: vec![released_payment.clone()],
+                    ..Default::default()
+                },
+            );
+            txn.commit().unwrap();
+        }
+        assert_eq!(
+            subject.account_status(&debtor_2).unwrap().balance_wei,
+            15_000
+        );
+
+        let txn = subject.reconcile_received_payments(
+            payment_time,
+            &ReceivablesReconciliation {
+                to_credit: vec![credited_payment.clone()],
+                to_roll_back: vec![payment_to_roll_back],
+                to_hold: vec![held_payment.clone()],
+                to_release: vec![released_payment],
+            },
+        );
+
+        txn.commit().unwrap();
+        let status_1 = subject.account_status(&debtor_1).unwrap();
+        assert_eq!(status_1.balance_wei, 6_000);
+        assert_eq!(to_time_t(status_1.last_received_timestamp), 1_700_000_000);
+        let status_2 = subject.account_status(&debtor_2).unwrap();
+        assert_eq!(status_2.balance_wei, 20_000);
+        assert_eq!(status_2.last_received_timestamp, previous_timestamp);
+        assert_eq!(subject.account_status(&debtor_3), None);
+        assert_eq!(
+            subject.pending_receivables(),
+            vec![
+                PendingReceivable {
+                    transaction: credited_payment,
+                    credited: true
+                },
+                PendingReceivable {
+                    transaction: held_payment,
+                    credited: false
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn reconcile_received_payments_moves_a_held_payment_into_another_block() {
+        let home_dir = ensure_node_home_directory_exists(
+            "receivable_dao",
+            "reconcile_received_payments_moves_a_held_payment_into_another_block",
+        );
+        let mut subject = ReceivableDaoReal::new(
+            DbInitializerReal::default()
+                .initialize(&home_dir, DbInitializationConfig::test_default())
+                .unwrap(),
+        );
+        let original = BlockchainTransaction {
+            block_number: 200,
+            block_hash: H256::from_low_u64_be(200),
+            transaction_hash: H256::from_low_u64_be(45),
+            log_index: 3,
+            from: make_wallet("debtor"),
+            wei_amount: u128::MAX >> 2,
+        };
+        let moved = BlockchainTransaction {
+            block_number: 201,
+            block_hash: H256::from_low_u64_be(999),
+            ..original.clone()
+        };
+        let hold = |subject: &mut ReceivableDaoReal, payment: &BlockchainTransaction| {
+            subject
+                .reconcile_received_payments(
+                    SystemTime::now(),
+                    &ReceivablesReconciliation {
+                        to_hold: vec![payment.clone()],
+                        ..Default::default()
+                    },
+                )
+                .commit()
+                .unwrap()
+        };
+        hold(&mut subject, &original);
+
+        hold(&mut subject, &moved);
+
+        assert_eq!(
+            subject.pending_receivables(),
+            vec![PendingReceivable {
+                transaction: moved,
+                credited: false
+            }]
+        );
+    }
+
     #[test]
     fn more_money_received_ignores_unknown_address_without_affecting_the_good_ones() {
         init_test_logging();
@@ -862,16 +1341,25 @@ mod tests {
         subject.logger = logger;
         let transaction_1 = BlockchainTransaction {
             block_number: 4444,
+            block_hash: H256::from_low_u64_be(4444),
+            transaction_hash: H256::from_low_u64_be(3),
+            log_index: 0,
             from: first_tracked_wallet.clone(),
             wei_amount: 1111,
         };
         let transaction_2 = BlockchainTransaction {
             block_number: 4446,
+            block_hash: H256::from_low_u64_be(4446),
+            transaction_hash: H256::from_low_u64_be(4),
+            log_index: 0,
             from: unknown_wallet.clone(),
             wei_amount: 2222,
         };
         let transaction_3 = BlockchainTransaction {
             block_number: 4450,
+            block_hash: H256::from_low_u64_be(4450),
+            transaction_hash: H256::from_low_u64_be(5),
+            log_index: 0,
             from: second_tracked_wallet.clone(),
             wei_amount: 9999,
         };
@@ -933,6 +1421,9 @@ mod tests {
         let mut subject = ReceivableDaoReal::new(Box::new(conn));
         let transaction = BlockchainTransaction {
             block_number: 123_456,
+            block_hash: H256::from_low_u64_be(123_456),
+            transaction_hash: H256::from_low_u64_be(6),
+            log_index: 0,
             from: wallet,
             wei_amount: 45_678,
         };
@@ -980,6 +1471,9 @@ mod tests {
         };
         let transaction = BlockchainTransaction {
             block_number: 123_456,
+            block_hash: H256::from_low_u64_be(123_456),
+            transaction_hash: H256::from_low_u64_be(7),
+            log_index: 0,
             from: make_wallet("abc"),
             wei_amount: 1,
         };
@@ -1046,11 +1540,17 @@ mod tests {
         subject.logger = Logger::new(test_name);
         let first_transaction = BlockchainTransaction {
             block_number: 123_456,
+            block_hash: H256::from_low_u64_be(123_456),
+            transaction_hash: H256::from_low_u64_be(8),
+            log_index: 0,
             from: first_wallet.clone(),
             wei_amount: 45_678,
         };
         let second_transaction = BlockchainTransaction {
             block_number: 789_123,
+            block_hash: H256::from_low_u64_be(789_123),
+            transaction_hash: H256::from_low_u64_be(9),
+            log_index: 0,
             from: second_wallet,
             wei_amount: 111_222,
         };
@@ -1149,6 +1649,9 @@ mod tests {
         .unwrap();
         let suspect = BlockchainTransaction {
             block_number: 1234,
+            block_hash: H256::from_low_u64_be(1234),
+            transaction_hash: H256::from_low_u64_be(10),
+            log_index: 0,
             from: wallet,
             wei_amount: 1_000_000_000,
         };
@@ -3,6 +3,7 @@
 use std::fmt::Debug;
 
 const OPCODE_FINANCIALS: &str = "financials";
+const OPCODE_FINANCIALS_HISTORY: &str = "financialsHistory";
 
 fn fits_in_0_to_i64max_for_u64<N>(num: &N) -> bool
 where
@@ -25,13 +26,18 @@ where
 
 pub(in crate::accountant) mod visibility_restricted_module {
     use crate::accountant::db_access_objects::utils::CustomQuery;
-    use crate::accountant::financials::{fits_in_0_to_i64max_for_u64, OPCODE_FINANCIALS};
+    use crate::accountant::financials::{
+        fits_in_0_to_i64max_for_u64, OPCODE_FINANCIALS, OPCODE_FINANCIALS_HISTORY,
+    };
+    use crate::sub_lib::wallet::Wallet;
     use masq_lib::constants::{
-        REQUEST_WITH_MUTUALLY_EXCLUSIVE_PARAMS, REQUEST_WITH_NO_VALUES, VALUE_EXCEEDS_ALLOWED_LIMIT,
+        INVALID_HISTORY_REQUEST, REQUEST_WITH_MUTUALLY_EXCLUSIVE_PARAMS, REQUEST_WITH_NO_VALUES,
+        VALUE_EXCEEDS_ALLOWED_LIMIT,
     };
-    use masq_lib::messages::UiFinancialsRequest;
+    use masq_lib::messages::{UiFinancialsHistoryRequest, UiFinancialsRequest};
     use masq_lib::ui_gateway::{MessageBody, MessagePath};
     use std::fmt::{Debug, Display};
+    use std::str::FromStr;
 
     pub fn check_query_is_within_tech_limits<N>(
         query: &CustomQuery<N>,
@@ -106,15 +112,61 @@ pub(in crate::accountant) mod visibility_restricted_module {
             Ok(())
         }
     }
+
+    pub fn financials_history_entry_check(
+        msg: &UiFinancialsHistoryRequest,
+        context_id: u64,
+    ) -> Result<Option<Wallet>, MessageBody> {
+        let err = |code, message: String| {
+            Err(MessageBody {
+                opcode: OPCODE_FINANCIALS_HISTORY.to_string(),
+                path: MessagePath::Conversation(context_id),
+                payload: Err((code, message)),
+            })
+        };
+        if msg.max_age_s > i64::MAX as u64 {
+            return err(
+                VALUE_EXCEEDS_ALLOWED_LIMIT,
+                format!(
+                    "Max age requested too big. Should be less than or equal to {}, not: {}",
+                    i64::MAX,
+                    msg.max_age_s
+                ),
+            );
+        }
+        if msg.min_age_s > msg.max_age_s {
+            return err(
+                INVALID_HISTORY_REQUEST,
+                format!(
+                    "Min age {} is greater than max age {}",
+                    msg.min_age_s, msg.max_age_s
+                ),
+            );
+        }
+        match msg.wallet_opt.as_ref() {
+            None => Ok(None),
+            Some(address) => match Wallet::from_str(address) {
+                Ok(wallet) => Ok(Some(wallet)),
+                Err(_) => err(
+                    INVALID_HISTORY_REQUEST,
+                    format!("Invalid wallet address: '{}'", address),
+                ),
+            },
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::visibility_restricted_module::check_query_is_within_tech_limits;
+    use super::visibility_restricted_module::{
+        check_query_is_within_tech_limits, financials_history_entry_check,
+    };
     use crate::accountant::db_access_objects::utils::CustomQuery;
     use crate::accountant::financials::fits_in_0_to_i64max_for_u64;
-    use masq_lib::constants::VALUE_EXCEEDS_ALLOWED_LIMIT;
+    use crate::test_utils::make_wallet;
+    use masq_lib::constants::{INVALID_HISTORY_REQUEST, VALUE_EXCEEDS_ALLOWED_LIMIT};
     use masq_lib::messages::TopRecordsOrdering::Age;
+    use masq_lib::messages::{HistoryGranularity, UiFinancialsHistoryRequest};
     use masq_lib::ui_gateway::{MessageBody, MessagePath};
     use std::fmt::{Debug, Display};
     use std::time::SystemTime;
@@ -256,4 +308,72 @@ mod tests {
 
         let _ = check_query_is_within_tech_limits(&query, "payable", 1234);
     }
+
+    fn make_history_request(
+        min_age_s: u64,
+        max_age_s: u64,
+        wallet_opt: Option<&str>,
+    ) -> UiFinancialsHistoryRequest {
+        UiFinancialsHistoryRequest {
+            min_age_s,
+            max_age_s,
+            granularity: HistoryGranularity::Day,
+            wallet_opt: wallet_opt.map(|address| address.to_string()),
+        }
+    }
+
+    fn history_error(code: u64, message: &str) -> MessageBody {
+        MessageBody {
+            opcode: "financialsHistory".to_string(),
+            path: MessagePath::Conversation(4321),
+            payload: Err((code, message.to_string())),
+        }
+    }
+
+    #[test]
+    fn financials_history_entry_check_accepts_sound_request_and_parses_wallet() {
+        let wallet = make_wallet("counterparty");
+        let address = wallet.to_string();
+
+        let without_wallet =
+            financials_history_entry_check(&make_history_request(0, i64::MAX as u64, None), 4321);
+        let with_wallet = financials_history_entry_check(
+            &make_history_request(3_600, 3_600, Some(&address)),
+            4321,
+        );
+
+        assert_eq!(without_wallet, Ok(None));
+        assert_eq!(with_wallet, Ok(Some(wallet)));
+    }
+
+    #[test]
+    fn financials_history_entry_check_rejects_faulty_requests() {
+        let result = [
+            make_history_request(0, i64::MAX as u64 + 1, None),
+            make_history_request(7_200, 3_600, None),
+            make_history_request(0, 3_600, Some("booga")),
+        ]
+        .iter()
+        .map(|request| financials_history_entry_check(request, 4321))
+        .collect::<Vec<_>>();
+
+        assert_eq!(
+            result,
+            vec![
+                Err(history_error(
+                    VALUE_EXCEEDS_ALLOWED_LIMIT,
+                    "Max age requested too big. Should be less than or equal to \
+                     9223372036854775807, not: 9223372036854775808"
+                )),
+                Err(history_error(
+                    INVALID_HISTORY_REQUEST,
+                    "Min age 7200 is greater than max age 3600"
+                )),
+                Err(history_error(
+                    INVALID_HISTORY_REQUEST,
+                    "Invalid wallet address: 'booga'"
+                )),
+            ]
+        );
+    }
 }
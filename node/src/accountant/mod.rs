@@ -5,27 +5,43 @@ pub mod db_big_integer;
 pub mod financials;
 pub mod payment_adjuster;
 pub mod scanners;
+pub mod spending_budget;
 
 #[cfg(test)]
 pub mod test_utils;
 
 use core::fmt::Debug;
-use masq_lib::constants::{SCAN_ERROR, WEIS_IN_GWEI};
+use masq_lib::constants::{
+    BAD_PASSWORD_ERROR, CONFIGURATOR_READ_ERROR, LEDGER_ADJUSTMENT_ERROR, SCAN_ERROR, WEIS_IN_GWEI,
+};
 use std::cell::{Ref, RefCell};
 
+use crate::accountant::db_access_objects::banned_dao::BannedDao;
+use crate::accountant::db_access_objects::financial_history_dao::{
+    FinancialHistoryDao, HistoryDirection, HistoryService,
+};
+use crate::accountant::db_access_objects::ledger_adjustment_dao::{
+    AdjustedLedger, AdjustmentKind, LedgerAdjustment, LedgerAdjustmentDao,
+};
 use crate::accountant::db_access_objects::payable_dao::{PayableDao, PayableDaoError};
-use crate::accountant::db_access_objects::pending_payable_dao::PendingPayableDao;
+use crate::accountant::db_access_objects::pending_payable_dao::{
+    PendingPayableDao, PendingPayableFeeBump, StuckTransaction,
+};
 use crate::accountant::db_access_objects::receivable_dao::{ReceivableDao, ReceivableDaoError};
 use crate::accountant::db_access_objects::utils::{
-    remap_payable_accounts, remap_receivable_accounts, CustomQuery, DaoFactoryReal,
+    remap_payable_accounts, remap_receivable_accounts, to_time_t, CustomQuery, DaoFactoryReal,
 };
 use crate::accountant::financials::visibility_restricted_module::{
-    check_query_is_within_tech_limits, financials_entry_check,
+    check_query_is_within_tech_limits, financials_entry_check, financials_history_entry_check,
 };
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::msgs::{
     BlockchainAgentWithContextMessage, QualifiedPayablesMessage,
 };
+use crate::accountant::scanners::scanners_utils::pending_payable_scanner_utils::{
+    last_activity, select_stuck_transactions,
+};
 use crate::accountant::scanners::{BeginScanError, ScanSchedulers, Scanners};
+use crate::accountant::spending_budget::SpendingTracker;
 use crate::blockchain::blockchain_bridge::{
     PendingPayableFingerprint, PendingPayableFingerprintSeeds, RetrieveTransactions,
 };
@@ -35,16 +51,21 @@ use crate::blockchain::blockchain_interface::data_structures::{
 };
 use crate::bootstrapper::BootstrapperConfig;
 use crate::database::db_initializer::DbInitializationConfig;
+use crate::database::rusqlite_wrappers::TransactionSafeWrapper;
+use crate::db_config::persistent_configuration::{
+    PersistentConfiguration, PersistentConfigurationReal,
+};
 use crate::sub_lib::accountant::AccountantSubs;
 use crate::sub_lib::accountant::DaoFactories;
 use crate::sub_lib::accountant::FinancialStatistics;
 use crate::sub_lib::accountant::ReportExitServiceProvidedMessage;
 use crate::sub_lib::accountant::ReportRoutingServiceProvidedMessage;
 use crate::sub_lib::accountant::ReportServicesConsumedMessage;
-use crate::sub_lib::accountant::{MessageIdGenerator, MessageIdGeneratorReal};
+use crate::sub_lib::accountant::{FeeBumping, MessageIdGenerator, MessageIdGeneratorReal};
 use crate::sub_lib::blockchain_bridge::OutboundPaymentsInstructions;
 use crate::sub_lib::neighborhood::{ConfigChange, ConfigChangeMsg};
 use crate::sub_lib::peer_actors::{BindMessage, StartMessage};
+use crate::sub_lib::proxy_server::SpendingBudgetStatusMsg;
 use crate::sub_lib::utils::{handle_ui_crash_request, NODE_MAILBOX_CAPACITY};
 use crate::sub_lib::wallet::Wallet;
 use actix::Actor;
@@ -61,21 +82,32 @@ use masq_lib::logger::Logger;
 use masq_lib::messages::UiFinancialsResponse;
 use masq_lib::messages::{FromMessageBody, ToMessageBody, UiFinancialsRequest};
 use masq_lib::messages::{
-    QueryResults, ScanType, UiFinancialStatistics, UiPayableAccount, UiReceivableAccount,
-    UiScanRequest,
+    HistoryGranularity, UiFinancialsHistoryPeriod, UiFinancialsHistoryRequest,
+    UiFinancialsHistoryResponse,
+};
+use masq_lib::messages::{
+    LedgerSide, UiAdjustBalanceRequest, UiAdjustBalanceResponse, UiForgiveDebtRequest,
+    UiForgiveDebtResponse,
+};
+use masq_lib::messages::{
+    QueryResults, ScanType, UiFinancialStatistics, UiPayableAccount, UiPendingPayableFeeBump,
+    UiReceivableAccount, UiScanRequest, UiScanResponse,
 };
 use masq_lib::ui_gateway::MessageTarget::ClientId;
 use masq_lib::ui_gateway::{MessageBody, MessagePath};
 use masq_lib::ui_gateway::{NodeFromUiMessage, NodeToUiMessage};
 use masq_lib::utils::ExpectValue;
 use std::any::type_name;
+use std::collections::BTreeMap;
 #[cfg(test)]
 use std::default::Default;
 use std::fmt::Display;
 use std::ops::{Div, Mul};
 use std::path::Path;
 use std::rc::Rc;
-use std::time::SystemTime;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use thousands::Separable;
 use web3::types::{TransactionReceipt, H256};
 
 pub const CRASH_KEY: &str = "ACCOUNTANT";
@@ -88,6 +120,13 @@ pub struct Accountant {
     payable_dao: Box<dyn PayableDao>,
     receivable_dao: Box<dyn ReceivableDao>,
     pending_payable_dao: Box<dyn PendingPayableDao>,
+    financial_history_dao: Box<dyn FinancialHistoryDao>,
+    banned_dao: Box<dyn BannedDao>,
+    ledger_adjustment_dao: Box<dyn LedgerAdjustmentDao>,
+    persistent_config: Box<dyn PersistentConfiguration>,
+    fee_bumping: FeeBumping,
+    spending_tracker: SpendingTracker,
+    when_pending_too_long_sec: u64,
     crashable: bool,
     scanners: Scanners,
     scan_schedulers: ScanSchedulers,
@@ -96,9 +135,11 @@ pub struct Accountant {
     qualified_payables_sub_opt: Option<Recipient<QualifiedPayablesMessage>>,
     retrieve_transactions_sub_opt: Option<Recipient<RetrieveTransactions>>,
     request_transaction_receipts_subs_opt: Option<Recipient<RequestTransactionReceipts>>,
+    request_fee_bumps_sub_opt: Option<Recipient<RequestFeeBumps>>,
     report_inbound_payments_sub_opt: Option<Recipient<ReceivedPayments>>,
     report_sent_payables_sub_opt: Option<Recipient<SentPayables>>,
     ui_message_sub_opt: Option<Recipient<NodeToUiMessage>>,
+    spending_budget_status_sub_opt: Option<Recipient<SpendingBudgetStatusMsg>>,
     message_id_generator: Box<dyn MessageIdGenerator>,
     logger: Logger,
 }
@@ -125,9 +166,19 @@ pub struct ReceivedPayments {
     pub timestamp: SystemTime,
     pub payments: Vec<BlockchainTransaction>,
     pub new_start_block: u64,
+    pub scanned_blocks_opt: Option<ScannedBlocks>,
     pub response_skeleton_opt: Option<ResponseSkeleton>,
 }
 
+// Boundaries of a finished scan for received payments, with the chain head as it was seen at
+// the time. Needed for telling confirmed payments from those still exposed to a reorganization.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ScannedBlocks {
+    pub from: u64,
+    pub to: u64,
+    pub latest: u64,
+}
+
 #[derive(Debug, Message, PartialEq)]
 pub struct SentPayables {
     pub payment_procedure_result: Result<Vec<ProcessedPayableFallible>, PayableTransactionError>,
@@ -282,6 +333,7 @@ impl Handler<ScanError> for Accountant {
             ScanType::Receivables => {
                 self.scanners.receivable.mark_as_ended(&self.logger);
             }
+            ScanType::FeeBumps => (),
         };
         if let Some(response_skeleton) = scan_error.response_skeleton_opt {
             let error_msg = NodeToUiMessage {
@@ -351,6 +403,8 @@ pub trait SkeletonOptHolder {
 #[derive(Debug, PartialEq, Eq, Message, Clone)]
 pub struct RequestTransactionReceipts {
     pub pending_payable: Vec<PendingPayableFingerprint>,
+    // Transactions replaced by fee bumps; any of them may still be the one that gets mined
+    pub replaced_transactions: Vec<PendingPayableId>,
     pub response_skeleton_opt: Option<ResponseSkeleton>,
 }
 
@@ -370,6 +424,7 @@ impl Handler<ReportTransactionReceipts> for Accountant {
     type Result = ();
 
     fn handle(&mut self, msg: ReportTransactionReceipts, _ctx: &mut Self::Context) -> Self::Result {
+        let stuck_transactions = self.select_stuck_transactions_among_unconfirmed(&msg);
         if let Some(node_to_ui_msg) = self.scanners.pending_payable.finish_scan(msg, &self.logger) {
             self.ui_message_sub_opt
                 .as_ref()
@@ -377,6 +432,37 @@ impl Handler<ReportTransactionReceipts> for Accountant {
                 .try_send(node_to_ui_msg)
                 .expect("UIGateway is dead");
         }
+        if !stuck_transactions.is_empty() {
+            self.request_fee_bumps(stuck_transactions, None)
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Message, Clone)]
+pub struct RequestFeeBumps {
+    pub consuming_wallet: Wallet,
+    pub stuck_transactions: Vec<StuckTransaction>,
+    pub gas_price_increase_percent: u64,
+    pub response_skeleton_opt: Option<ResponseSkeleton>,
+}
+
+impl SkeletonOptHolder for RequestFeeBumps {
+    fn skeleton_opt(&self) -> Option<ResponseSkeleton> {
+        self.response_skeleton_opt
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Message, Clone)]
+pub struct ReportFeeBumps {
+    pub fee_bumps: Vec<PendingPayableFeeBump>,
+    pub response_skeleton_opt: Option<ResponseSkeleton>,
+}
+
+impl Handler<ReportFeeBumps> for Accountant {
+    type Result = ();
+
+    fn handle(&mut self, msg: ReportFeeBumps, _ctx: &mut Self::Context) -> Self::Result {
+        self.handle_report_fee_bumps(msg)
     }
 }
 
@@ -398,6 +484,13 @@ impl Handler<NodeFromUiMessage> for Accountant {
         let client_id = msg.client_id;
         if let Ok((request, context_id)) = UiFinancialsRequest::fmb(msg.body.clone()) {
             self.handle_financials(&request, client_id, context_id)
+        } else if let Ok((request, context_id)) = UiFinancialsHistoryRequest::fmb(msg.body.clone())
+        {
+            self.handle_financials_history(&request, client_id, context_id)
+        } else if let Ok((request, context_id)) = UiAdjustBalanceRequest::fmb(msg.body.clone()) {
+            self.handle_adjust_balance(&request, client_id, context_id)
+        } else if let Ok((request, context_id)) = UiForgiveDebtRequest::fmb(msg.body.clone()) {
+            self.handle_forgive_debt(&request, client_id, context_id)
         } else if let Ok((body, context_id)) = UiScanRequest::fmb(msg.body.clone()) {
             self.handle_externally_triggered_scan(
                 ctx,
@@ -422,10 +515,22 @@ impl Accountant {
         let payable_dao = dao_factories.payable_dao_factory.make();
         let pending_payable_dao = dao_factories.pending_payable_dao_factory.make();
         let receivable_dao = dao_factories.receivable_dao_factory.make();
+        let financial_history_dao = dao_factories.financial_history_dao_factory.make();
+        let banned_dao = dao_factories.banned_dao_factory.make();
+        let persistent_config = Box::new(PersistentConfigurationReal::from(
+            dao_factories.config_dao_factory.make(),
+        ));
+        let ledger_adjustment_dao = dao_factories.ledger_adjustment_dao_factory.make();
+        let when_pending_too_long_sec = config.when_pending_too_long_sec;
         let scanners = Scanners::new(
             dao_factories,
             Rc::new(payment_thresholds),
             config.when_pending_too_long_sec,
+            config
+                .blockchain_bridge_config
+                .chain
+                .rec()
+                .confirmation_depth,
             Rc::clone(&financial_statistics),
         );
 
@@ -436,6 +541,13 @@ impl Accountant {
             payable_dao,
             receivable_dao,
             pending_payable_dao,
+            financial_history_dao,
+            banned_dao,
+            ledger_adjustment_dao,
+            persistent_config,
+            fee_bumping: config.fee_bumping,
+            spending_tracker: SpendingTracker::new(config.spending_budget, SystemTime::now()),
+            when_pending_too_long_sec,
             scanners,
             crashable: config.crash_point == CrashPoint::Message,
             scan_schedulers: ScanSchedulers::new(scan_intervals),
@@ -446,7 +558,9 @@ impl Accountant {
             retrieve_transactions_sub_opt: None,
             report_inbound_payments_sub_opt: None,
             request_transaction_receipts_subs_opt: None,
+            request_fee_bumps_sub_opt: None,
             ui_message_sub_opt: None,
+            spending_budget_status_sub_opt: None,
             message_id_generator: Box::new(MessageIdGeneratorReal::default()),
             logger: Logger::new("Accountant"),
         }
@@ -464,6 +578,7 @@ impl Accountant {
             report_inbound_payments: recipient!(addr, ReceivedPayments),
             init_pending_payable_fingerprints: recipient!(addr, PendingPayableFingerprintSeeds),
             report_transaction_receipts: recipient!(addr, ReportTransactionReceipts),
+            report_fee_bumps: recipient!(addr, ReportFeeBumps),
             report_sent_payments: recipient!(addr, SentPayables),
             scan_errors: recipient!(addr, ScanError),
             ui_message_sub: recipient!(addr, NodeFromUiMessage),
@@ -481,6 +596,7 @@ impl Accountant {
         timestamp: SystemTime,
         payload_size: usize,
         wallet: &Wallet,
+        service: HistoryService,
     ) {
         let byte_charge = byte_rate as u128 * (payload_size as u128);
         let total_charge = service_rate as u128 + byte_charge;
@@ -488,7 +604,7 @@ impl Accountant {
             match self.receivable_dao
                 .as_ref()
                 .more_money_receivable(timestamp,wallet, total_charge) {
-                Ok(_) => (),
+                Ok(_) => self.record_history(timestamp, HistoryDirection::Earned, service, wallet, total_charge),
                 Err(ReceivableDaoError::SignConversion(_)) => error! (
                     self.logger,
                     "Overflow error recording service provided for {}: service rate {}, byte rate {}, payload size {}. Skipping",
@@ -515,15 +631,28 @@ impl Accountant {
         timestamp: SystemTime,
         payload_size: usize,
         wallet: &Wallet,
-    ) {
+        service: HistoryService,
+    ) -> u128 {
         let byte_charge = byte_rate as u128 * (payload_size as u128);
         let total_charge = service_rate as u128 + byte_charge;
         if !self.our_wallet(wallet) {
-            match self.payable_dao
+            match self
+                .payable_dao
                 .as_ref()
-                .more_money_payable(timestamp, wallet,total_charge){
-                Ok(_) => (),
-                Err(PayableDaoError::SignConversion(_)) => error! (
+                .more_money_payable(timestamp, wallet, total_charge)
+            {
+                Ok(_) => {
+                    self.record_history(
+                        timestamp,
+                        HistoryDirection::Spent,
+                        service,
+                        wallet,
+                        total_charge,
+                    );
+                    total_charge
+                }
+                Err(PayableDaoError::SignConversion(_)) => {
+                    error! (
                     self.logger,
                     "Overflow error recording consumed services from {}: total charge {}, service rate {}, byte rate {}, payload size {}. Skipping",
                     wallet,
@@ -531,15 +660,45 @@ impl Accountant {
                     service_rate,
                     byte_rate,
                     payload_size
+                );
+                    0
+                }
+                Err(e) => panic!(
+                    "Recording services consumed from {} but has hit fatal database error: {:?}",
+                    wallet, e
                 ),
-                Err(e) => panic!("Recording services consumed from {} but has hit fatal database error: {:?}", wallet, e)
-            };
+            }
         } else {
             warning!(
                 self.logger,
                 "Declining to record a payable against our wallet {} for service we provided",
                 wallet
             );
+            0
+        }
+    }
+
+    fn record_history(
+        &self,
+        timestamp: SystemTime,
+        direction: HistoryDirection,
+        service: HistoryService,
+        wallet: &Wallet,
+        charge: u128,
+    ) {
+        if let Err(e) = self
+            .financial_history_dao
+            .record(timestamp, direction, service, wallet, charge)
+        {
+            error!(
+                self.logger,
+                "Failed to record {:?} {:?} history of {} wei for {}: {:?}",
+                direction,
+                service,
+                charge,
+                wallet,
+                e
+            )
         }
     }
 
@@ -569,27 +728,81 @@ impl Accountant {
                 .blockchain_bridge
                 .request_transaction_receipts,
         );
+        self.request_fee_bumps_sub_opt = Some(msg.peer_actors.blockchain_bridge.request_fee_bumps);
+        self.spending_budget_status_sub_opt =
+            Some(msg.peer_actors.proxy_server.spending_budget_status);
         info!(self.logger, "Accountant bound");
     }
 
     fn handle_config_change_msg(&mut self, msg: ConfigChangeMsg) {
-        if let ConfigChange::UpdateWallets(wallet_pair) = msg.change {
-            if self.earning_wallet != wallet_pair.earning_wallet {
+        match msg.change {
+            ConfigChange::UpdateWallets(wallet_pair) => {
+                if self.earning_wallet != wallet_pair.earning_wallet {
+                    info!(
+                        self.logger,
+                        "Earning Wallet has been updated: {}", wallet_pair.earning_wallet
+                    );
+                    self.earning_wallet = wallet_pair.earning_wallet;
+                }
+                if self.consuming_wallet_opt != Some(wallet_pair.consuming_wallet.clone()) {
+                    info!(
+                        self.logger,
+                        "Consuming Wallet has been updated: {}", wallet_pair.consuming_wallet
+                    );
+                    self.consuming_wallet_opt = Some(wallet_pair.consuming_wallet);
+                }
+            }
+            ConfigChange::UpdatePaymentThresholds(payment_thresholds) => {
+                let payment_thresholds = Rc::new(payment_thresholds);
+                self.scanners
+                    .payable
+                    .update_payment_thresholds(Rc::clone(&payment_thresholds));
+                self.scanners
+                    .pending_payable
+                    .update_payment_thresholds(Rc::clone(&payment_thresholds));
+                self.scanners
+                    .receivable
+                    .update_payment_thresholds(Rc::clone(&payment_thresholds));
                 info!(
                     self.logger,
-                    "Earning Wallet has been updated: {}", wallet_pair.earning_wallet
+                    "Payment thresholds have been updated: {:?}", payment_thresholds
                 );
-                self.earning_wallet = wallet_pair.earning_wallet;
             }
-            if self.consuming_wallet_opt != Some(wallet_pair.consuming_wallet.clone()) {
+            ConfigChange::UpdateScanIntervals(scan_intervals) => {
+                [
+                    (ScanType::Payables, scan_intervals.payable_scan_interval),
+                    (
+                        ScanType::PendingPayables,
+                        scan_intervals.pending_payable_scan_interval,
+                    ),
+                    (
+                        ScanType::Receivables,
+                        scan_intervals.receivable_scan_interval,
+                    ),
+                ]
+                .into_iter()
+                .for_each(|(scan_type, interval)| {
+                    if let Some(scheduler) = self.scan_schedulers.schedulers.get_mut(&scan_type) {
+                        scheduler.set_interval(interval)
+                    }
+                });
                 info!(
                     self.logger,
-                    "Consuming Wallet has been updated: {}", wallet_pair.consuming_wallet
+                    "Scan intervals have been updated; they apply from each scanner's next round: {:?}",
+                    scan_intervals
                 );
-                self.consuming_wallet_opt = Some(wallet_pair.consuming_wallet);
             }
-        } else {
-            trace!(self.logger, "Ignored irrelevant message: {:?}", msg);
+            ConfigChange::UpdateSpendingBudget(spending_budget) => {
+                info!(
+                    self.logger,
+                    "Spending budget has been updated: {}", spending_budget
+                );
+                let status_opt = self
+                    .spending_tracker
+                    .update_budget(spending_budget, SystemTime::now());
+                self.report_spending_budget_status(status_opt)
+            }
+            _ => trace!(self.logger, "Ignored irrelevant message: {:?}", msg),
         }
     }
 
@@ -615,6 +828,7 @@ impl Accountant {
             msg.timestamp,
             msg.payload_size,
             &msg.paying_wallet,
+            HistoryService::Routing,
         );
     }
 
@@ -636,6 +850,7 @@ impl Accountant {
             msg.timestamp,
             msg.payload_size,
             &msg.paying_wallet,
+            HistoryService::Exit,
         );
     }
 
@@ -656,29 +871,49 @@ impl Accountant {
             msg.exit.earning_wallet,
             msg.exit.payload_size
         );
-        self.record_service_consumed(
+        let exit_charge = self.record_service_consumed(
             msg.exit.service_rate,
             msg.exit.byte_rate,
             msg.timestamp,
             msg.exit.payload_size,
             &msg.exit.earning_wallet,
+            HistoryService::Exit,
         );
-        msg.routing.iter().for_each(|routing_service| {
-            debug!(
-                self.logger,
-                "MsgId {}: Accruing debt to {} for consuming {} routed bytes",
-                msg_id,
-                routing_service.earning_wallet,
-                msg.routing_payload_size
-            );
-            self.record_service_consumed(
-                routing_service.service_rate,
-                routing_service.byte_rate,
-                msg.timestamp,
-                msg.routing_payload_size,
-                &routing_service.earning_wallet,
-            );
-        })
+        let routing_charge = msg
+            .routing
+            .iter()
+            .map(|routing_service| {
+                debug!(
+                    self.logger,
+                    "MsgId {}: Accruing debt to {} for consuming {} routed bytes",
+                    msg_id,
+                    routing_service.earning_wallet,
+                    msg.routing_payload_size
+                );
+                self.record_service_consumed(
+                    routing_service.service_rate,
+                    routing_service.byte_rate,
+                    msg.timestamp,
+                    msg.routing_payload_size,
+                    &routing_service.earning_wallet,
+                    HistoryService::Routing,
+                )
+            })
+            .fold(0_u128, |sum, charge| sum.saturating_add(charge));
+        let status_opt = self
+            .spending_tracker
+            .record_spending(exit_charge.saturating_add(routing_charge), msg.timestamp);
+        self.report_spending_budget_status(status_opt)
+    }
+
+    fn report_spending_budget_status(&self, status_opt: Option<SpendingBudgetStatusMsg>) {
+        if let Some(status) = status_opt {
+            self.spending_budget_status_sub_opt
+                .as_ref()
+                .expect("ProxyServer is unbound")
+                .try_send(status)
+                .expect("ProxyServer is dead")
+        }
     }
 
     fn handle_payable_payment_setup(&mut self, msg: BlockchainAgentWithContextMessage) {
@@ -725,13 +960,340 @@ impl Accountant {
             Ok(results_opt) => results_opt,
             Err(message_body) => return message_body,
         };
+        let fee_bumps_opt = self.process_fee_bumps(msg);
+        let spending_budget_opt = msg
+            .stats_required
+            .then(|| self.spending_tracker.report(SystemTime::now()));
         UiFinancialsResponse {
             stats_opt,
             query_results_opt,
+            fee_bumps_opt,
+            spending_budget_opt,
+        }
+        .tmb(context_id)
+    }
+
+    fn handle_financials_history(
+        &self,
+        msg: &UiFinancialsHistoryRequest,
+        client_id: u64,
+        context_id: u64,
+    ) {
+        let body = self.compute_financials_history(msg, context_id, SystemTime::now());
+        self.ui_message_sub_opt
+            .as_ref()
+            .expect("UiGateway not bound")
+            .try_send(NodeToUiMessage {
+                target: ClientId(client_id),
+                body,
+            })
+            .expect("UiGateway is dead");
+    }
+
+    fn compute_financials_history(
+        &self,
+        msg: &UiFinancialsHistoryRequest,
+        context_id: u64,
+        now: SystemTime,
+    ) -> MessageBody {
+        let wallet_opt = match financials_history_entry_check(msg, context_id) {
+            Ok(wallet_opt) => wallet_opt,
+            Err(message_body) => return message_body,
+        };
+        let back_by = |age_s: u64| {
+            now.checked_sub(Duration::from_secs(age_s))
+                .unwrap_or(UNIX_EPOCH)
+        };
+        let records = self.financial_history_dao.history(
+            back_by(msg.max_age_s),
+            back_by(msg.min_age_s),
+            wallet_opt.as_ref(),
+        );
+        let mut periods: BTreeMap<u64, [u128; 4]> = BTreeMap::new();
+        records.into_iter().for_each(|record| {
+            let start_s = period_start(msg.granularity, to_time_t(record.hour_start));
+            let index = match (record.direction, record.service) {
+                (HistoryDirection::Earned, HistoryService::Routing) => 0,
+                (HistoryDirection::Earned, HistoryService::Exit) => 1,
+                (HistoryDirection::Spent, HistoryService::Routing) => 2,
+                (HistoryDirection::Spent, HistoryService::Exit) => 3,
+            };
+            periods.entry(start_s).or_default()[index] += record.amount_wei;
+        });
+        UiFinancialsHistoryResponse {
+            periods: periods
+                .into_iter()
+                .map(|(start_s, sums_wei)| UiFinancialsHistoryPeriod {
+                    start_s,
+                    earned_routing_gwei: wei_to_gwei(sums_wei[0]),
+                    earned_exit_gwei: wei_to_gwei(sums_wei[1]),
+                    spent_routing_gwei: wei_to_gwei(sums_wei[2]),
+                    spent_exit_gwei: wei_to_gwei(sums_wei[3]),
+                })
+                .collect(),
         }
         .tmb(context_id)
     }
 
+    fn handle_adjust_balance(
+        &mut self,
+        msg: &UiAdjustBalanceRequest,
+        client_id: u64,
+        context_id: u64,
+    ) {
+        let result = self
+            .adjust_balance(msg, SystemTime::now())
+            .map(|response| response.tmb(context_id));
+        self.respond_to_ledger_adjustment(msg.opcode(), result, client_id, context_id)
+    }
+
+    fn handle_forgive_debt(&mut self, msg: &UiForgiveDebtRequest, client_id: u64, context_id: u64) {
+        let result = self
+            .forgive_debt(msg, SystemTime::now())
+            .map(|response| response.tmb(context_id));
+        self.respond_to_ledger_adjustment(msg.opcode(), result, client_id, context_id)
+    }
+
+    fn respond_to_ledger_adjustment(
+        &self,
+        opcode: &str,
+        result: Result<MessageBody, (u64, String)>,
+        client_id: u64,
+        context_id: u64,
+    ) {
+        let body = match result {
+            Ok(body) => body,
+            Err((code, message)) => {
+                warning!(self.logger, "Ledger adjustment refused: {}", message);
+                MessageBody {
+                    opcode: opcode.to_string(),
+                    path: MessagePath::Conversation(context_id),
+                    payload: Err((code, message)),
+                }
+            }
+        };
+        self.ui_message_sub_opt
+            .as_ref()
+            .expect("UiGateway not bound")
+            .try_send(NodeToUiMessage {
+                target: ClientId(client_id),
+                body,
+            })
+            .expect("UiGateway is dead");
+    }
+
+    fn adjust_balance(
+        &mut self,
+        msg: &UiAdjustBalanceRequest,
+        now: SystemTime,
+    ) -> Result<UiAdjustBalanceResponse, (u64, String)> {
+        let wallet = self.ledger_adjustment_entry_check(
+            &msg.db_password,
+            &msg.wallet,
+            &msg.operator,
+            &msg.reason,
+        )?;
+        if msg.change_gwei == 0 {
+            return Err((
+                LEDGER_ADJUSTMENT_ERROR,
+                "A change of zero would adjust nothing".to_string(),
+            ));
+        }
+        let change_wei = gwei_to_wei::<i128, i64>(msg.change_gwei);
+        let (ledger, uncommitted_opt) = match msg.ledger {
+            LedgerSide::Payable => (
+                AdjustedLedger::Payable,
+                self.payable_dao
+                    .adjust_balance(&wallet, change_wei)
+                    .map_err(|e| match e {
+                        PayableDaoError::BalanceBelowZero(balance_wei) => (
+                            LEDGER_ADJUSTMENT_ERROR,
+                            format!(
+                                "The payable to {} can't go below zero, yet the change would \
+                                 make it {} wei",
+                                wallet, balance_wei
+                            ),
+                        ),
+                        e => (
+                            LEDGER_ADJUSTMENT_ERROR,
+                            format!("Adjusting the payable to {} failed: {:?}", wallet, e),
+                        ),
+                    })?,
+            ),
+            LedgerSide::Receivable => (
+                AdjustedLedger::Receivable,
+                self.receivable_dao
+                    .adjust_balance(&wallet, change_wei)
+                    .map_err(|e| {
+                        (
+                            LEDGER_ADJUSTMENT_ERROR,
+                            format!("Adjusting the receivable from {} failed: {:?}", wallet, e),
+                        )
+                    })?,
+            ),
+        };
+        let uncommitted = uncommitted_opt.ok_or_else(|| {
+            (
+                LEDGER_ADJUSTMENT_ERROR,
+                format!("No {} account for {}", ledger_name(ledger), wallet),
+            )
+        })?;
+        let change = uncommitted.change;
+        Self::record_ledger_adjustment(
+            self.ledger_adjustment_dao.as_ref(),
+            &self.logger,
+            LedgerAdjustment {
+                timestamp: now,
+                operator: msg.operator.clone(),
+                reason: msg.reason.clone(),
+                ledger,
+                kind: AdjustmentKind::Correction,
+                wallet,
+                change,
+            },
+            uncommitted.txn,
+        )?;
+        Ok(UiAdjustBalanceResponse {
+            balance_before_gwei: wei_to_gwei(change.before_wei),
+            balance_after_gwei: wei_to_gwei(change.after_wei),
+        })
+    }
+
+    fn forgive_debt(
+        &mut self,
+        msg: &UiForgiveDebtRequest,
+        now: SystemTime,
+    ) -> Result<UiForgiveDebtResponse, (u64, String)> {
+        let wallet = self.ledger_adjustment_entry_check(
+            &msg.db_password,
+            &msg.wallet,
+            &msg.operator,
+            &msg.reason,
+        )?;
+        let debt_wei = match self.receivable_dao.account_status(&wallet) {
+            Some(account) if account.balance_wei > 0 => account.balance_wei,
+            _ => {
+                return Err((
+                    LEDGER_ADJUSTMENT_ERROR,
+                    format!("{} owes nothing to forgive", wallet),
+                ))
+            }
+        };
+        let uncommitted = self
+            .receivable_dao
+            .adjust_balance(&wallet, -debt_wei)
+            .map_err(|e| {
+                (
+                    LEDGER_ADJUSTMENT_ERROR,
+                    format!("Forgiving the debt of {} failed: {:?}", wallet, e),
+                )
+            })?
+            .expect("receivable vanished");
+        Self::record_ledger_adjustment(
+            self.ledger_adjustment_dao.as_ref(),
+            &self.logger,
+            LedgerAdjustment {
+                timestamp: now,
+                operator: msg.operator.clone(),
+                reason: msg.reason.clone(),
+                ledger: AdjustedLedger::Receivable,
+                kind: AdjustmentKind::Forgiveness,
+                wallet: wallet.clone(),
+                change: uncommitted.change,
+            },
+            uncommitted.txn,
+        )?;
+        let ban_lifted = self.banned_dao.ban_list().contains(&wallet);
+        if ban_lifted {
+            self.banned_dao.unban(&wallet)
+        }
+        Ok(UiForgiveDebtResponse {
+            forgiven_gwei: wei_to_gwei(debt_wei),
+            ban_lifted,
+        })
+    }
+
+    fn ledger_adjustment_entry_check(
+        &self,
+        db_password: &str,
+        wallet: &str,
+        operator: &str,
+        reason: &str,
+    ) -> Result<Wallet, (u64, String)> {
+        match self
+            .persistent_config
+            .check_password(Some(db_password.to_string()))
+        {
+            Ok(true) => (),
+            Ok(false) => {
+                return Err((
+                    BAD_PASSWORD_ERROR,
+                    "Bad password; can't adjust the ledger".to_string(),
+                ))
+            }
+            Err(e) => {
+                return Err((
+                    CONFIGURATOR_READ_ERROR,
+                    format!("Error checking password: {:?}", e),
+                ))
+            }
+        }
+        if operator.trim().is_empty() || reason.trim().is_empty() {
+            return Err((
+                LEDGER_ADJUSTMENT_ERROR,
+                "The audit trail needs both the operator and the reason".to_string(),
+            ));
+        }
+        Wallet::from_str(wallet).map_err(|_| {
+            (
+                LEDGER_ADJUSTMENT_ERROR,
+                format!("Invalid wallet address: '{}'", wallet),
+            )
+        })
+    }
+
+    // The balance change is committed along with its record, or not at all
+    fn record_ledger_adjustment(
+        ledger_adjustment_dao: &dyn LedgerAdjustmentDao,
+        logger: &Logger,
+        adjustment: LedgerAdjustment,
+        txn: TransactionSafeWrapper,
+    ) -> Result<(), (u64, String)> {
+        let ledger = ledger_name(adjustment.ledger);
+        match ledger_adjustment_dao.record(&adjustment, txn) {
+            Ok(()) => {
+                info!(
+                    logger,
+                    "{} changed the {} balance of {} from {} to {} wei{}; reason: {}",
+                    adjustment.operator,
+                    ledger,
+                    adjustment.wallet,
+                    adjustment.change.before_wei,
+                    adjustment.change.after_wei,
+                    match adjustment.kind {
+                        AdjustmentKind::Correction => "",
+                        AdjustmentKind::Forgiveness => ", forgiving the debt",
+                    },
+                    adjustment.reason
+                );
+                Ok(())
+            }
+            Err(e) => {
+                let message = format!(
+                    "The {} balance of {} was left at {} wei, because the audit trail \
+                     failed to record its change to {} wei: {:?}",
+                    ledger,
+                    adjustment.wallet,
+                    adjustment.change.before_wei,
+                    adjustment.change.after_wei,
+                    e
+                );
+                error!(logger, "{}", message);
+                Err((LEDGER_ADJUSTMENT_ERROR, message))
+            }
+        }
+    }
+
     fn request_payable_accounts_by_specific_mode(
         &self,
         mode: CustomQuery<u64>,
@@ -766,6 +1328,29 @@ impl Accountant {
         }
     }
 
+    fn process_fee_bumps(&self, msg: &UiFinancialsRequest) -> Option<Vec<UiPendingPayableFeeBump>> {
+        if msg.stats_required {
+            let now = SystemTime::now();
+            Some(
+                self.pending_payable_dao
+                    .fee_bumps()
+                    .into_iter()
+                    .map(|fee_bump| UiPendingPayableFeeBump {
+                        replaced_tx_hash: format!("{:?}", fee_bump.replaced_hash),
+                        replacement_tx_hash: format!("{:?}", fee_bump.replacement_hash),
+                        gas_price_wei: fee_bump.gas_price_wei,
+                        age_s: now
+                            .duration_since(fee_bump.timestamp)
+                            .map(|age| age.as_secs())
+                            .unwrap_or(0),
+                    })
+                    .collect(),
+            )
+        } else {
+            None
+        }
+    }
+
     fn process_top_records_query(&self, msg: &UiFinancialsRequest) -> Option<QueryResults> {
         msg.top_records_opt.map(|config| {
             let payable = self
@@ -929,6 +1514,142 @@ impl Accountant {
             ScanType::Receivables => {
                 self.handle_request_of_scan_for_receivable(Some(response_skeleton))
             }
+            ScanType::FeeBumps => self.handle_request_of_fee_bumps(response_skeleton),
+        }
+    }
+
+    fn handle_request_of_fee_bumps(&mut self, response_skeleton: ResponseSkeleton) {
+        if self.consuming_wallet_opt.is_none() {
+            return BeginScanError::NoConsumingWalletFound.handle_error(
+                &self.logger,
+                ScanType::FeeBumps,
+                true,
+            );
+        }
+        let fingerprints = self.pending_payable_dao.return_all_errorless_fingerprints();
+        let stuck_transactions = if fingerprints.is_empty() {
+            vec![]
+        } else {
+            select_stuck_transactions(
+                &fingerprints,
+                &self.pending_payable_dao.fee_bumps(),
+                &self.fee_bumping,
+                SystemTime::now(),
+                true,
+            )
+        };
+        if stuck_transactions.is_empty() {
+            BeginScanError::NothingToProcess.handle_error(&self.logger, ScanType::FeeBumps, true)
+        } else {
+            self.request_fee_bumps(stuck_transactions, Some(response_skeleton))
+        }
+    }
+
+    fn select_stuck_transactions_among_unconfirmed(
+        &self,
+        msg: &ReportTransactionReceipts,
+    ) -> Vec<PendingPayableId> {
+        if self.fee_bumping.max_bumps == 0 || self.consuming_wallet_opt.is_none() {
+            return vec![];
+        }
+        let now = SystemTime::now();
+        let age_sec = |since: SystemTime| {
+            now.duration_since(since)
+                .map(|age| age.as_secs())
+                .unwrap_or(0)
+        };
+        // Nothing sent later than the original can be idle for longer than it
+        let old_enough = msg
+            .fingerprints_with_receipts
+            .iter()
+            .filter(|(receipt_opt, _)| receipt_opt.is_none())
+            .map(|(_, fingerprint)| fingerprint)
+            .filter(|fingerprint| age_sec(fingerprint.timestamp) >= self.fee_bumping.bump_after_sec)
+            .collect::<Vec<_>>();
+        if old_enough.is_empty() {
+            return vec![];
+        }
+        let fee_bumps = self.pending_payable_dao.fee_bumps();
+        let unconfirmed = old_enough
+            .into_iter()
+            .filter(|fingerprint| {
+                age_sec(last_activity(fingerprint, &fee_bumps)) <= self.when_pending_too_long_sec
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        select_stuck_transactions(&unconfirmed, &fee_bumps, &self.fee_bumping, now, false)
+    }
+
+    fn request_fee_bumps(
+        &self,
+        stuck_ids: Vec<PendingPayableId>,
+        response_skeleton_opt: Option<ResponseSkeleton>,
+    ) {
+        debug!(
+            self.logger,
+            "Requesting fee bumps for stuck transactions: {}",
+            PendingPayableId::serialize_hashes_to_string(&stuck_ids)
+        );
+        let stuck_transactions = self.pending_payable_dao.stuck_transactions(&stuck_ids);
+        if stuck_transactions.len() < stuck_ids.len() {
+            let unrecorded = stuck_ids
+                .into_iter()
+                .filter(|id| {
+                    !stuck_transactions
+                        .iter()
+                        .any(|stuck| stuck.rowid == id.rowid)
+                })
+                .collect::<Vec<_>>();
+            warning!(
+                self.logger,
+                "Cannot replace stuck transactions {} as their nonces are not on record",
+                PendingPayableId::serialize_hashes_to_string(&unrecorded)
+            )
+        }
+        self.request_fee_bumps_sub_opt
+            .as_ref()
+            .expect("BlockchainBridge is unbound")
+            .try_send(RequestFeeBumps {
+                consuming_wallet: self
+                    .consuming_wallet_opt
+                    .clone()
+                    .expectv("consuming wallet"),
+                stuck_transactions,
+                gas_price_increase_percent: self.fee_bumping.gas_price_increase_percent,
+                response_skeleton_opt,
+            })
+            .expect("BlockchainBridge is dead")
+    }
+
+    fn handle_report_fee_bumps(&mut self, msg: ReportFeeBumps) {
+        msg.fee_bumps.iter().for_each(|fee_bump| {
+            match self.pending_payable_dao.record_fee_bump(fee_bump) {
+                Ok(()) => info!(
+                    self.logger,
+                    "Replaced stuck transaction {:?} with {:?} at gas price {} wei",
+                    fee_bump.replaced_hash,
+                    fee_bump.replacement_hash,
+                    fee_bump.gas_price_wei.separate_with_commas()
+                ),
+                Err(e) => error!(
+                    self.logger,
+                    "Failed to record the replacement of stuck transaction {:?} with {:?} due to \
+                    {:?}; the confirmation will keep following the replaced one",
+                    fee_bump.replaced_hash,
+                    fee_bump.replacement_hash,
+                    e
+                ),
+            }
+        });
+        if let Some(response_skeleton) = msg.response_skeleton_opt {
+            self.ui_message_sub_opt
+                .as_ref()
+                .expect("UIGateway is not bound")
+                .try_send(NodeToUiMessage {
+                    target: ClientId(response_skeleton.client_id),
+                    body: UiScanResponse {}.tmb(response_skeleton.context_id),
+                })
+                .expect("UIGateway is dead");
         }
     }
 
@@ -937,28 +1658,82 @@ impl Accountant {
             comma_joined_stringifiable(fingerprints_data, |(hash, _)| format!("{:?}", hash))
         }
 
-        match self
+        if let Err(e) = self
             .pending_payable_dao
             .insert_new_fingerprints(&msg.hashes_and_balances, msg.batch_wide_timestamp)
         {
-            Ok(_) => debug!(
-                self.logger,
-                "Saved new pending payable fingerprints for: {}",
-                serialize_hashes(&msg.hashes_and_balances)
-            ),
-            Err(e) => error!(
+            error!(
                 self.logger,
                 "Failed to process new pending payable fingerprints due to '{:?}', \
                  disabling the automated confirmation for all these transactions: {}",
                 e,
                 serialize_hashes(&msg.hashes_and_balances)
-            ),
+            );
+            return;
+        }
+        debug!(
+            self.logger,
+            "Saved new pending payable fingerprints for: {}",
+            serialize_hashes(&msg.hashes_and_balances)
+        );
+        if !msg.batch_payments.is_empty() {
+            self.insert_batch_payments(&msg.batch_payments)
+        }
+        if !msg.hashes_and_nonces.is_empty() {
+            if let Err(e) = self
+                .pending_payable_dao
+                .insert_nonces(&msg.hashes_and_nonces, msg.gas_price_wei)
+            {
+                error!(
+                    self.logger,
+                    "Failed to record the nonces of transactions {} due to '{:?}', they can't be \
+                     replaced by fee bumps",
+                    serialize_hashes(&msg.hashes_and_balances),
+                    e
+                )
+            }
         }
     }
 
-    fn financial_statistics(&self) -> Ref<'_, FinancialStatistics> {
-        self.financial_statistics.borrow()
-    }
+    // Without its shares, a confirmed batch transaction would be subtracted from each of
+    // the payables it paid as a whole, so its fingerprint is rather put out of play
+    fn insert_batch_payments(&self, batch_payments: &[(H256, Vec<(Wallet, u128)>)]) {
+        let e = match self
+            .pending_payable_dao
+            .insert_batch_payments(batch_payments)
+        {
+            Ok(()) => return,
+            Err(e) => e,
+        };
+        let hashes = batch_payments
+            .iter()
+            .map(|(hash, _)| *hash)
+            .collect::<Vec<H256>>();
+        error!(
+            self.logger,
+            "Failed to record how the transactions {} split among their creditors due to '{:?}', \
+             disabling their automated confirmation",
+            comma_joined_stringifiable(&hashes, |hash| format!("{:?}", hash)),
+            e
+        );
+        let rowids = self
+            .pending_payable_dao
+            .fingerprints_rowids(&hashes)
+            .rowid_results
+            .into_iter()
+            .map(|(rowid, _)| rowid)
+            .collect::<Vec<u64>>();
+        if let Err(e) = self.pending_payable_dao.mark_failures(&rowids) {
+            error!(
+                self.logger,
+                "Failed to mark the fingerprints {:?} as failures due to '{:?}'", rowids, e
+            )
+        }
+    }
+
+    fn financial_statistics(&self) -> Ref<'_, FinancialStatistics> {
+        self.financial_statistics.borrow()
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -1021,6 +1796,25 @@ pub fn gwei_to_wei<T: Mul<Output = T> + From<u32> + From<S>, S>(gwei: S) -> T {
     (T::from(gwei)).mul(T::from(WEIS_IN_GWEI as u32))
 }
 
+// Weeks begin on Monday, 00:00 UTC
+fn period_start(granularity: HistoryGranularity, time_t: i64) -> u64 {
+    let time_s = checked_conversion::<i64, u64>(time_t);
+    match granularity {
+        HistoryGranularity::Week => {
+            let day = time_s / 86_400;
+            day.saturating_sub((day + 3) % 7) * 86_400
+        }
+        _ => time_s - time_s % granularity.period_s(),
+    }
+}
+
+fn ledger_name(ledger: AdjustedLedger) -> &'static str {
+    match ledger {
+        AdjustedLedger::Payable => "payable",
+        AdjustedLedger::Receivable => "receivable",
+    }
+}
+
 pub fn wei_to_gwei<T: TryFrom<S>, S: Display + Copy + Div<Output = S> + From<u32>>(wei: S) -> T {
     checked_conversion::<S, T>(wei.div(S::from(WEIS_IN_GWEI as u32)))
 }
@@ -1028,6 +1822,12 @@ pub fn wei_to_gwei<T: TryFrom<S>, S: Display + Copy + Div<Output = S> + From<u32
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::accountant::db_access_objects::financial_history_dao::{
+        FinancialHistoryDaoError, FinancialHistoryRecord,
+    };
+    use crate::accountant::db_access_objects::ledger_adjustment_dao::{
+        BalanceChange, LedgerAdjustmentDaoError,
+    };
     use crate::accountant::db_access_objects::payable_dao::{
         PayableAccount, PayableDaoError, PayableDaoFactory,
     };
@@ -1039,29 +1839,35 @@ mod tests {
     use crate::accountant::payment_adjuster::Adjustment;
     use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::test_utils::BlockchainAgentMock;
     use crate::accountant::scanners::test_utils::protect_payables_in_test;
-    use crate::accountant::scanners::BeginScanError;
+    use crate::accountant::scanners::{
+        BeginScanError, PayableScanner, PendingPayableScanner, ReceivableScanner,
+    };
     use crate::accountant::test_utils::DaoWithDestination::{
         ForAccountantBody, ForPayableScanner, ForPendingPayableScanner, ForReceivableScanner,
     };
     use crate::accountant::test_utils::{
         bc_from_earning_wallet, bc_from_wallets, make_payable_account, make_payables,
-        BannedDaoFactoryMock, ConfigDaoFactoryMock, MessageIdGeneratorMock, NullScanner,
-        PayableDaoFactoryMock, PayableDaoMock, PayableScannerBuilder, PaymentAdjusterMock,
-        PendingPayableDaoFactoryMock, PendingPayableDaoMock, ReceivableDaoFactoryMock,
-        ReceivableDaoMock, ScannerMock,
+        BannedDaoFactoryMock, ConfigDaoFactoryMock, FinancialHistoryDaoFactoryMock,
+        FinancialHistoryDaoMock, LedgerAdjustmentDaoFactoryMock, LedgerAdjustmentDaoMock,
+        MessageIdGeneratorMock, NullScanner, PayableDaoFactoryMock, PayableDaoMock,
+        PayableScannerBuilder, PaymentAdjusterMock, PendingPayableDaoFactoryMock,
+        PendingPayableDaoMock, ReceivableDaoFactoryMock, ReceivableDaoMock, ScannerMock,
     };
-    use crate::accountant::test_utils::{AccountantBuilder, BannedDaoMock};
+    use crate::accountant::test_utils::{AccountantBuilder, BannedDaoMock, FundsTransferDaoMock};
     use crate::accountant::Accountant;
     use crate::blockchain::blockchain_bridge::BlockchainBridge;
-    use crate::blockchain::test_utils::{make_tx_hash, BlockchainInterfaceMock};
+    use crate::blockchain::test_utils::{
+        make_stuck_transaction, make_tx_hash, BlockchainInterfaceMock,
+    };
     use crate::database::rusqlite_wrappers::TransactionSafeWrapper;
     use crate::database::test_utils::transaction_wrapper_mock::TransactionInnerWrapperMockBuilder;
     use crate::db_config::config_dao::ConfigDaoRecord;
     use crate::db_config::mocks::ConfigDaoMock;
+    use crate::db_config::persistent_configuration::PersistentConfigError;
     use crate::match_every_type_id;
     use crate::sub_lib::accountant::{
         ExitServiceConsumed, PaymentThresholds, RoutingServiceConsumed, ScanIntervals,
-        DEFAULT_EARNING_WALLET, DEFAULT_PAYMENT_THRESHOLDS,
+        SpendingBudget, DEFAULT_EARNING_WALLET, DEFAULT_FEE_BUMPING, DEFAULT_PAYMENT_THRESHOLDS,
     };
     use crate::sub_lib::blockchain_bridge::OutboundPaymentsInstructions;
     use crate::sub_lib::neighborhood::ConfigChange;
@@ -1084,14 +1890,16 @@ mod tests {
     use ethsign_crypto::Keccak256;
     use itertools::Itertools;
     use log::Level;
+    use masq_lib::blockchains::chains::Chain;
     use masq_lib::constants::{
-        REQUEST_WITH_MUTUALLY_EXCLUSIVE_PARAMS, REQUEST_WITH_NO_VALUES, SCAN_ERROR,
-        VALUE_EXCEEDS_ALLOWED_LIMIT,
+        INVALID_HISTORY_REQUEST, REQUEST_WITH_MUTUALLY_EXCLUSIVE_PARAMS, REQUEST_WITH_NO_VALUES,
+        SCAN_ERROR, VALUE_EXCEEDS_ALLOWED_LIMIT,
     };
     use masq_lib::messages::TopRecordsOrdering::{Age, Balance};
     use masq_lib::messages::{
         CustomQueries, RangeQuery, ScanType, TopRecordsConfig, UiFinancialStatistics,
         UiMessageError, UiPayableAccount, UiReceivableAccount, UiScanRequest, UiScanResponse,
+        UiSpendingBudget,
     };
     use masq_lib::test_utils::logging::init_test_logging;
     use masq_lib::test_utils::logging::TestLogHandler;
@@ -1132,6 +1940,8 @@ mod tests {
         let receivable_dao_factory_params_arc = Arc::new(Mutex::new(vec![]));
         let banned_dao_factory_params_arc = Arc::new(Mutex::new(vec![]));
         let config_dao_factory_params_arc = Arc::new(Mutex::new(vec![]));
+        let financial_history_dao_factory_params_arc = Arc::new(Mutex::new(vec![]));
+        let ledger_adjustment_dao_factory_params_arc = Arc::new(Mutex::new(vec![]));
         let payable_dao_factory = PayableDaoFactoryMock::new()
             .make_params(&payable_dao_factory_params_arc)
             .make_result(PayableDaoMock::new()) // For Accountant
@@ -1148,10 +1958,18 @@ mod tests {
             .make_result(ReceivableDaoMock::new()); // For Receivable Scanner
         let banned_dao_factory = BannedDaoFactoryMock::new()
             .make_params(&banned_dao_factory_params_arc)
+            .make_result(BannedDaoMock::new()) // For Accountant
             .make_result(BannedDaoMock::new()); // For Receivable Scanner
         let config_dao_factory = ConfigDaoFactoryMock::new()
             .make_params(&config_dao_factory_params_arc)
+            .make_result(ConfigDaoMock::new()) // For Accountant
             .make_result(ConfigDaoMock::new()); // For receivable scanner
+        let financial_history_dao_factory = FinancialHistoryDaoFactoryMock::new()
+            .make_params(&financial_history_dao_factory_params_arc)
+            .make_result(FinancialHistoryDaoMock::new()); // For Accountant
+        let ledger_adjustment_dao_factory = LedgerAdjustmentDaoFactoryMock::new()
+            .make_params(&ledger_adjustment_dao_factory_params_arc)
+            .make_result(LedgerAdjustmentDaoMock::new()); // For Accountant
 
         let _ = Accountant::new(
             config,
@@ -1161,6 +1979,8 @@ mod tests {
                 receivable_dao_factory: Box::new(receivable_dao_factory),
                 banned_dao_factory: Box::new(banned_dao_factory),
                 config_dao_factory: Box::new(config_dao_factory),
+                financial_history_dao_factory: Box::new(financial_history_dao_factory),
+                ledger_adjustment_dao_factory: Box::new(ledger_adjustment_dao_factory),
             },
         );
 
@@ -1176,8 +1996,16 @@ mod tests {
             *receivable_dao_factory_params_arc.lock().unwrap(),
             vec![(), ()]
         );
-        assert_eq!(*banned_dao_factory_params_arc.lock().unwrap(), vec![()]);
-        assert_eq!(*config_dao_factory_params_arc.lock().unwrap(), vec![()]);
+        assert_eq!(*banned_dao_factory_params_arc.lock().unwrap(), vec![(), ()]);
+        assert_eq!(*config_dao_factory_params_arc.lock().unwrap(), vec![(), ()]);
+        assert_eq!(
+            *financial_history_dao_factory_params_arc.lock().unwrap(),
+            vec![()]
+        );
+        assert_eq!(
+            *ledger_adjustment_dao_factory_params_arc.lock().unwrap(),
+            vec![()]
+        );
     }
 
     #[test]
@@ -1200,10 +2028,22 @@ mod tests {
                 .make_result(ReceivableDaoMock::new()) // For Accountant
                 .make_result(ReceivableDaoMock::new()), // For Scanner
         );
-        let banned_dao_factory =
-            Box::new(BannedDaoFactoryMock::new().make_result(BannedDaoMock::new()));
-        let config_dao_factory =
-            Box::new(ConfigDaoFactoryMock::new().make_result(ConfigDaoMock::new()));
+        let banned_dao_factory = Box::new(
+            BannedDaoFactoryMock::new()
+                .make_result(BannedDaoMock::new()) // For Accountant
+                .make_result(BannedDaoMock::new()), // For Scanner
+        );
+        let config_dao_factory = Box::new(
+            ConfigDaoFactoryMock::new()
+                .make_result(ConfigDaoMock::new()) // For Accountant
+                .make_result(ConfigDaoMock::new()), // For Scanner
+        );
+        let financial_history_dao_factory = Box::new(
+            FinancialHistoryDaoFactoryMock::new().make_result(FinancialHistoryDaoMock::new()),
+        );
+        let ledger_adjustment_dao_factory = Box::new(
+            LedgerAdjustmentDaoFactoryMock::new().make_result(LedgerAdjustmentDaoMock::new()),
+        );
 
         let result = Accountant::new(
             bootstrapper_config,
@@ -1213,6 +2053,8 @@ mod tests {
                 receivable_dao_factory,
                 banned_dao_factory,
                 config_dao_factory,
+                financial_history_dao_factory,
+                ledger_adjustment_dao_factory,
             },
         );
 
@@ -1301,6 +2143,113 @@ mod tests {
         );
     }
 
+    #[test]
+    fn accountant_hands_new_payment_thresholds_to_every_scanner() {
+        let payment_thresholds = PaymentThresholds {
+            debt_threshold_gwei: 5_000_000,
+            maturity_threshold_sec: 2_000,
+            payment_grace_period_sec: 3_000,
+            permanent_debt_allowed_gwei: 400_000,
+            threshold_interval_sec: 20_000,
+            unban_below_gwei: 400_000,
+        };
+        assert_handling_of_config_change_msg(
+            ConfigChangeMsg {
+                change: ConfigChange::UpdatePaymentThresholds(payment_thresholds),
+            },
+            |subject: &Accountant| {
+                let payable_scanner = subject
+                    .scanners
+                    .payable
+                    .as_any()
+                    .downcast_ref::<PayableScanner>()
+                    .unwrap();
+                let pending_payable_scanner = subject
+                    .scanners
+                    .pending_payable
+                    .as_any()
+                    .downcast_ref::<PendingPayableScanner>()
+                    .unwrap();
+                let receivable_scanner = subject
+                    .scanners
+                    .receivable
+                    .as_any()
+                    .downcast_ref::<ReceivableScanner>()
+                    .unwrap();
+                assert_eq!(
+                    payable_scanner.common.payment_thresholds.as_ref(),
+                    &payment_thresholds
+                );
+                assert_eq!(
+                    pending_payable_scanner.common.payment_thresholds.as_ref(),
+                    &payment_thresholds
+                );
+                assert_eq!(
+                    receivable_scanner.common.payment_thresholds.as_ref(),
+                    &payment_thresholds
+                );
+                TestLogHandler::new().exists_log_containing(&format!(
+                    "INFO: ConfigChange: Payment thresholds have been updated: {:?}",
+                    payment_thresholds
+                ));
+            },
+        );
+    }
+
+    #[test]
+    fn accountant_hands_new_scan_intervals_to_the_scan_schedulers() {
+        let scan_intervals = ScanIntervals {
+            payable_scan_interval: Duration::from_secs(111),
+            pending_payable_scan_interval: Duration::from_secs(222),
+            receivable_scan_interval: Duration::from_secs(333),
+        };
+        assert_handling_of_config_change_msg(
+            ConfigChangeMsg {
+                change: ConfigChange::UpdateScanIntervals(scan_intervals),
+            },
+            |subject: &Accountant| {
+                let interval_of = |scan_type: ScanType| {
+                    subject
+                        .scan_schedulers
+                        .schedulers
+                        .get(&scan_type)
+                        .unwrap()
+                        .interval()
+                };
+                assert_eq!(interval_of(ScanType::Payables), Duration::from_secs(111));
+                assert_eq!(
+                    interval_of(ScanType::PendingPayables),
+                    Duration::from_secs(222)
+                );
+                assert_eq!(interval_of(ScanType::Receivables), Duration::from_secs(333));
+                TestLogHandler::new().exists_log_containing(
+                    "INFO: ConfigChange: Scan intervals have been updated; they apply from each scanner's next round",
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn accountant_hands_new_spending_budget_to_the_spending_tracker() {
+        let spending_budget = SpendingBudget {
+            daily_gwei: 3_000_000,
+            monthly_gwei: 50_000_000,
+            first_warning_percent: 60,
+            second_warning_percent: 90,
+        };
+        assert_handling_of_config_change_msg(
+            ConfigChangeMsg {
+                change: ConfigChange::UpdateSpendingBudget(spending_budget),
+            },
+            |subject: &Accountant| {
+                assert_eq!(subject.spending_tracker.budget(), spending_budget);
+                TestLogHandler::new().exists_log_containing(
+                    "INFO: ConfigChange: Spending budget has been updated: 3000000|50000000|60|90",
+                );
+            },
+        );
+    }
+
     fn assert_handling_of_config_change_msg<A>(msg: ConfigChangeMsg, assertions: A)
     where
         A: FnOnce(&Accountant),
@@ -1372,13 +2321,14 @@ mod tests {
             pending_payable_scan_interval: Duration::from_secs(100),
         });
         config.suppress_initial_scans = true;
+        config.blockchain_bridge_config.chain = Chain::Dev;
         let subject = AccountantBuilder::default()
             .bootstrapper_config(config)
-            .config_dao(
+            .config_daos(vec![ForReceivableScanner(
                 ConfigDaoMock::new()
                     .get_result(Ok(ConfigDaoRecord::new("start_block", None, false)))
                     .set_result(Ok(())),
-            )
+            )])
             .build();
         let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
         let subject_addr = subject.start();
@@ -1389,6 +2339,7 @@ mod tests {
             timestamp: SystemTime::now(),
             payments: vec![],
             new_start_block: 1234567,
+            scanned_blocks_opt: None,
             response_skeleton_opt: Some(ResponseSkeleton {
                 client_id: 1234,
                 context_id: 4321,
@@ -1730,7 +2681,8 @@ mod tests {
             process_error: None,
         };
         let pending_payable_dao = PendingPayableDaoMock::default()
-            .return_all_errorless_fingerprints_result(vec![fingerprint.clone()]);
+            .return_all_errorless_fingerprints_result(vec![fingerprint.clone()])
+            .fee_bumps_result(vec![]);
         let subject = AccountantBuilder::default()
             .consuming_wallet(make_paying_wallet(b"consuming"))
             .bootstrapper_config(config)
@@ -1760,6 +2712,7 @@ mod tests {
             blockchain_bridge_recording.get_record::<RequestTransactionReceipts>(0),
             &RequestTransactionReceipts {
                 pending_payable: vec![fingerprint],
+                replaced_transactions: vec![],
                 response_skeleton_opt: Some(ResponseSkeleton {
                     client_id: 1234,
                     context_id: 4321,
@@ -1788,7 +2741,8 @@ mod tests {
             process_error: None,
         };
         let pending_payable_dao = PendingPayableDaoMock::default()
-            .return_all_errorless_fingerprints_result(vec![fingerprint]);
+            .return_all_errorless_fingerprints_result(vec![fingerprint])
+            .fee_bumps_result(vec![]);
         let subject = AccountantBuilder::default()
             .bootstrapper_config(config)
             .consuming_wallet(make_paying_wallet(b"consuming"))
@@ -1863,1606 +2817,2252 @@ mod tests {
     }
 
     #[test]
-    fn accountant_calls_payable_dao_to_mark_pending_payable() {
-        let fingerprints_rowids_params_arc = Arc::new(Mutex::new(vec![]));
-        let mark_pending_payables_rowids_params_arc = Arc::new(Mutex::new(vec![]));
-        let expected_wallet = make_wallet("paying_you");
-        let expected_hash = H256::from("transaction_hash".keccak256());
-        let expected_rowid = 45623;
-        let pending_payable_dao = PendingPayableDaoMock::default()
-            .fingerprints_rowids_params(&fingerprints_rowids_params_arc)
-            .fingerprints_rowids_result(TransactionHashes {
-                rowid_results: vec![(expected_rowid, expected_hash)],
-                no_rowid_results: vec![],
-            });
-        let payable_dao = PayableDaoMock::new()
-            .mark_pending_payables_rowids_params(&mark_pending_payables_rowids_params_arc)
-            .mark_pending_payables_rowids_result(Ok(()));
-        let system = System::new("accountant_calls_payable_dao_to_mark_pending_payable");
-        let accountant = AccountantBuilder::default()
-            .bootstrapper_config(bc_from_earning_wallet(make_wallet("some_wallet_address")))
-            .payable_daos(vec![ForPayableScanner(payable_dao)])
-            .pending_payable_daos(vec![ForPayableScanner(pending_payable_dao)])
+    fn report_transaction_receipts_requests_fee_bumps_for_transactions_stuck_for_too_long() {
+        let mut config = bc_from_earning_wallet(make_wallet("earning_wallet"));
+        config.suppress_initial_scans = true;
+        config.fee_bumping = FeeBumping {
+            bump_after_sec: 1_800,
+            gas_price_increase_percent: 15,
+            max_bumps: 3,
+        };
+        let consuming_wallet = make_paying_wallet(b"consuming");
+        let now = SystemTime::now();
+        let stuck_fingerprint = PendingPayableFingerprint {
+            rowid: 45,
+            timestamp: now.checked_sub(Duration::from_secs(2_000)).unwrap(),
+            hash: make_tx_hash(0x45),
+            attempt: 5,
+            amount: 1_000_000,
+            process_error: None,
+        };
+        let fresh_fingerprint = PendingPayableFingerprint {
+            rowid: 46,
+            timestamp: now.checked_sub(Duration::from_secs(100)).unwrap(),
+            hash: make_tx_hash(0x46),
+            attempt: 1,
+            amount: 2_000_000,
+            process_error: None,
+        };
+        let stuck_transactions_params_arc = Arc::new(Mutex::new(vec![]));
+        let stuck_transaction = make_stuck_transaction(45);
+        let pending_payable_dao_for_accountant = PendingPayableDaoMock::default()
+            .fee_bumps_result(vec![])
+            .stuck_transactions_params(&stuck_transactions_params_arc)
+            .stuck_transactions_result(vec![stuck_transaction.clone()]);
+        let pending_payable_dao_for_scanner =
+            PendingPayableDaoMock::default().increment_scan_attempts_result(Ok(()));
+        let subject = AccountantBuilder::default()
+            .bootstrapper_config(config)
+            .consuming_wallet(consuming_wallet.clone())
+            .pending_payable_daos(vec![
+                ForAccountantBody(pending_payable_dao_for_accountant),
+                ForPendingPayableScanner(pending_payable_dao_for_scanner),
+            ])
             .build();
-        let expected_payable = PendingPayable::new(expected_wallet.clone(), expected_hash.clone());
-        let sent_payable = SentPayables {
-            payment_procedure_result: Ok(vec![Ok(expected_payable.clone())]),
+        let (blockchain_bridge, _, blockchain_bridge_recording_arc) = make_recorder();
+        let subject_addr = subject.start();
+        let system = System::new("test");
+        let peer_actors = peer_actors_builder()
+            .blockchain_bridge(blockchain_bridge)
+            .build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+        let report_transaction_receipts = ReportTransactionReceipts {
+            fingerprints_with_receipts: vec![
+                (None, stuck_fingerprint.clone()),
+                (None, fresh_fingerprint),
+            ],
             response_skeleton_opt: None,
         };
-        let subject = accountant.start();
 
-        subject
-            .try_send(sent_payable)
-            .expect("unexpected actix error");
+        subject_addr.try_send(report_transaction_receipts).unwrap();
 
         System::current().stop();
         system.run();
-        let fingerprints_rowids_params = fingerprints_rowids_params_arc.lock().unwrap();
-        assert_eq!(*fingerprints_rowids_params, vec![vec![expected_hash]]);
-        let mark_pending_payables_rowids_params =
-            mark_pending_payables_rowids_params_arc.lock().unwrap();
+        let stuck_transactions_params = stuck_transactions_params_arc.lock().unwrap();
         assert_eq!(
-            *mark_pending_payables_rowids_params,
-            vec![vec![(expected_wallet, expected_rowid)]]
+            *stuck_transactions_params,
+            vec![vec![PendingPayableId::new(
+                stuck_fingerprint.rowid,
+                stuck_fingerprint.hash
+            )]]
+        );
+        let blockchain_bridge_recording = blockchain_bridge_recording_arc.lock().unwrap();
+        assert_eq!(blockchain_bridge_recording.len(), 1);
+        assert_eq!(
+            blockchain_bridge_recording.get_record::<RequestFeeBumps>(0),
+            &RequestFeeBumps {
+                consuming_wallet,
+                stuck_transactions: vec![stuck_transaction],
+                gas_price_increase_percent: 15,
+                response_skeleton_opt: None,
+            }
         );
     }
 
     #[test]
-    fn accountant_sends_initial_payable_payments_msg_when_qualified_payable_found() {
-        let (blockchain_bridge, _, blockchain_bridge_recording_arc) = make_recorder();
-        let now = SystemTime::now();
-        let payment_thresholds = PaymentThresholds::default();
-        let (qualified_payables, _, all_non_pending_payables) =
-            make_payables(now, &payment_thresholds);
-        let payable_dao =
-            PayableDaoMock::new().non_pending_payables_result(all_non_pending_payables);
-        let system = System::new(
-            "accountant_sends_initial_payable_payments_msg_when_qualified_payable_found",
-        );
+    fn report_transaction_receipts_measures_the_pending_time_from_the_last_fee_bump() {
+        let mut config = bc_from_earning_wallet(make_wallet("earning_wallet"));
+        config.suppress_initial_scans = true;
+        config.when_pending_too_long_sec = 10_000;
+        config.fee_bumping = FeeBumping {
+            bump_after_sec: 1_800,
+            gas_price_increase_percent: 15,
+            max_bumps: 3,
+        };
         let consuming_wallet = make_paying_wallet(b"consuming");
-        let mut subject = AccountantBuilder::default()
-            .bootstrapper_config(bc_from_earning_wallet(make_wallet("some_wallet_address")))
+        let now = SystemTime::now();
+        let make_fingerprint = |rowid: u64| PendingPayableFingerprint {
+            rowid,
+            timestamp: now.checked_sub(Duration::from_secs(12_000)).unwrap(),
+            hash: make_tx_hash(rowid as u32),
+            attempt: 20,
+            amount: 1_000_000,
+            process_error: None,
+        };
+        let recently_bumped_fingerprint = make_fingerprint(45);
+        let never_bumped_fingerprint = make_fingerprint(46);
+        let fee_bump = PendingPayableFeeBump {
+            pending_payable_rowid: 45,
+            replaced_hash: make_tx_hash(0x44),
+            replacement_hash: make_tx_hash(45),
+            gas_price_wei: 1_150_000_000,
+            timestamp: now.checked_sub(Duration::from_secs(3_000)).unwrap(),
+        };
+        let stuck_transactions_params_arc = Arc::new(Mutex::new(vec![]));
+        let stuck_transaction = make_stuck_transaction(45);
+        let pending_payable_dao_for_accountant = PendingPayableDaoMock::default()
+            .fee_bumps_result(vec![fee_bump])
+            .stuck_transactions_params(&stuck_transactions_params_arc)
+            .stuck_transactions_result(vec![stuck_transaction.clone()]);
+        let pending_payable_dao_for_scanner =
+            PendingPayableDaoMock::default().increment_scan_attempts_result(Ok(()));
+        let subject = AccountantBuilder::default()
+            .bootstrapper_config(config)
             .consuming_wallet(consuming_wallet.clone())
-            .payable_daos(vec![ForPayableScanner(payable_dao)])
+            .pending_payable_daos(vec![
+                ForAccountantBody(pending_payable_dao_for_accountant),
+                ForPendingPayableScanner(pending_payable_dao_for_scanner),
+            ])
             .build();
-        subject.scanners.pending_payable = Box::new(NullScanner::new());
-        subject.scanners.receivable = Box::new(NullScanner::new());
-        let accountant_addr = subject.start();
-        let accountant_subs = Accountant::make_subs_from(&accountant_addr);
+        let (blockchain_bridge, _, blockchain_bridge_recording_arc) = make_recorder();
+        let subject_addr = subject.start();
+        let system = System::new("test");
         let peer_actors = peer_actors_builder()
             .blockchain_bridge(blockchain_bridge)
             .build();
-        send_bind_message!(accountant_subs, peer_actors);
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
 
-        send_start_message!(accountant_subs);
+        subject_addr
+            .try_send(ReportTransactionReceipts {
+                fingerprints_with_receipts: vec![
+                    (None, recently_bumped_fingerprint.clone()),
+                    (None, never_bumped_fingerprint),
+                ],
+                response_skeleton_opt: None,
+            })
+            .unwrap();
 
         System::current().stop();
         system.run();
-        let blockchain_bridge_recorder = blockchain_bridge_recording_arc.lock().unwrap();
-        assert_eq!(blockchain_bridge_recorder.len(), 1);
-        let message = blockchain_bridge_recorder.get_record::<QualifiedPayablesMessage>(0);
+        let stuck_transactions_params = stuck_transactions_params_arc.lock().unwrap();
         assert_eq!(
-            message,
-            &QualifiedPayablesMessage {
-                protected_qualified_payables: protect_payables_in_test(qualified_payables),
+            *stuck_transactions_params,
+            vec![vec![PendingPayableId::new(
+                recently_bumped_fingerprint.rowid,
+                recently_bumped_fingerprint.hash
+            )]]
+        );
+        let blockchain_bridge_recording = blockchain_bridge_recording_arc.lock().unwrap();
+        assert_eq!(
+            blockchain_bridge_recording.get_record::<RequestFeeBumps>(0),
+            &RequestFeeBumps {
                 consuming_wallet,
+                stuck_transactions: vec![stuck_transaction],
+                gas_price_increase_percent: 15,
                 response_skeleton_opt: None,
             }
         );
     }
 
     #[test]
-    fn accountant_requests_blockchain_bridge_to_scan_for_received_payments() {
-        init_test_logging();
-        let (blockchain_bridge, _, blockchain_bridge_recording_arc) = make_recorder();
-        let earning_wallet = make_wallet("someearningwallet");
-        let system =
-            System::new("accountant_requests_blockchain_bridge_to_scan_for_received_payments");
-        let receivable_dao = ReceivableDaoMock::new()
-            .new_delinquencies_result(vec![])
-            .paid_delinquencies_result(vec![]);
-        let mut subject = AccountantBuilder::default()
-            .bootstrapper_config(bc_from_earning_wallet(earning_wallet.clone()))
-            .receivable_daos(vec![ForReceivableScanner(receivable_dao)])
+    fn report_transaction_receipts_requests_no_fee_bumps_if_they_are_disabled() {
+        let mut config = bc_from_earning_wallet(make_wallet("earning_wallet"));
+        config.suppress_initial_scans = true;
+        config.fee_bumping = FeeBumping {
+            bump_after_sec: 1_800,
+            gas_price_increase_percent: 15,
+            max_bumps: 0,
+        };
+        let stuck_fingerprint = PendingPayableFingerprint {
+            rowid: 45,
+            timestamp: SystemTime::now()
+                .checked_sub(Duration::from_secs(2_000))
+                .unwrap(),
+            hash: make_tx_hash(0x45),
+            attempt: 5,
+            amount: 1_000_000,
+            process_error: None,
+        };
+        let pending_payable_dao_for_scanner =
+            PendingPayableDaoMock::default().increment_scan_attempts_result(Ok(()));
+        let subject = AccountantBuilder::default()
+            .bootstrapper_config(config)
+            .consuming_wallet(make_paying_wallet(b"consuming"))
+            .pending_payable_daos(vec![ForPendingPayableScanner(
+                pending_payable_dao_for_scanner,
+            )])
             .build();
-        subject.scanners.pending_payable = Box::new(NullScanner::new());
-        subject.scanners.payable = Box::new(NullScanner::new());
-        let accountant_addr = subject.start();
-        let accountant_subs = Accountant::make_subs_from(&accountant_addr);
+        let (blockchain_bridge, _, blockchain_bridge_recording_arc) = make_recorder();
+        let subject_addr = subject.start();
+        let system = System::new("test");
         let peer_actors = peer_actors_builder()
             .blockchain_bridge(blockchain_bridge)
             .build();
-        send_bind_message!(accountant_subs, peer_actors);
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
 
-        send_start_message!(accountant_subs);
+        subject_addr
+            .try_send(ReportTransactionReceipts {
+                fingerprints_with_receipts: vec![(None, stuck_fingerprint)],
+                response_skeleton_opt: None,
+            })
+            .unwrap();
 
         System::current().stop();
         system.run();
-        let blockchain_bridge_recorder = blockchain_bridge_recording_arc.lock().unwrap();
-        assert_eq!(blockchain_bridge_recorder.len(), 1);
-        let retrieve_transactions_msg =
-            blockchain_bridge_recorder.get_record::<RetrieveTransactions>(0);
-        assert_eq!(
-            retrieve_transactions_msg,
-            &RetrieveTransactions {
-                recipient: earning_wallet.clone(),
-                response_skeleton_opt: None,
-            }
-        );
+        let blockchain_bridge_recording = blockchain_bridge_recording_arc.lock().unwrap();
+        assert_eq!(blockchain_bridge_recording.len(), 0);
     }
 
     #[test]
-    fn accountant_processes_msg_with_received_payments_using_receivables_dao_and_then_updates_start_block(
-    ) {
-        let more_money_received_params_arc = Arc::new(Mutex::new(vec![]));
-        let commit_params_arc = Arc::new(Mutex::new(vec![]));
-        let get_params_arc = Arc::new(Mutex::new(vec![]));
-        let set_by_guest_transaction_params_arc = Arc::new(Mutex::new(vec![]));
-        let now = SystemTime::now();
-        let earning_wallet = make_wallet("earner3000");
-        let expected_receivable_1 = BlockchainTransaction {
-            block_number: 7,
-            from: make_wallet("wallet0"),
-            wei_amount: 456,
-        };
-        let expected_receivable_2 = BlockchainTransaction {
-            block_number: 13,
-            from: make_wallet("wallet1"),
-            wei_amount: 10000,
+    fn fee_bumps_scan_request_from_ui_forces_bumps_of_all_unconfirmed_transactions() {
+        let mut config = bc_from_earning_wallet(make_wallet("earning_wallet"));
+        config.suppress_initial_scans = true;
+        let consuming_wallet = make_paying_wallet(b"consuming");
+        let fingerprint = PendingPayableFingerprint {
+            rowid: 789,
+            timestamp: SystemTime::now(),
+            hash: make_tx_hash(0x789),
+            attempt: 1,
+            amount: 1_000_000,
+            process_error: None,
         };
-        let transaction_id = ArbitraryIdStamp::new();
-        let txn_inner_builder = TransactionInnerWrapperMockBuilder::default()
-            .commit_params(&commit_params_arc)
-            .commit_result(Ok(()))
-            .set_arbitrary_id_stamp(transaction_id);
-        let wrapped_transaction = TransactionSafeWrapper::new_with_builder(txn_inner_builder);
-        let receivable_dao = ReceivableDaoMock::new()
-            .more_money_received_params(&more_money_received_params_arc)
-            .more_money_received_result(wrapped_transaction);
-        let config_dao = ConfigDaoMock::new()
-            .get_params(&get_params_arc)
-            .get_result(Ok(ConfigDaoRecord::new("start_block", None, false)))
-            .set_by_guest_transaction_params(&set_by_guest_transaction_params_arc)
-            .set_by_guest_transaction_result(Ok(()));
-        let accountant = AccountantBuilder::default()
-            .bootstrapper_config(bc_from_earning_wallet(earning_wallet.clone()))
-            .receivable_daos(vec![ForReceivableScanner(receivable_dao)])
-            .config_dao(config_dao)
+        let stuck_transactions_params_arc = Arc::new(Mutex::new(vec![]));
+        let stuck_transaction = make_stuck_transaction(789);
+        let pending_payable_dao = PendingPayableDaoMock::default()
+            .return_all_errorless_fingerprints_result(vec![fingerprint.clone()])
+            .fee_bumps_result(vec![])
+            .stuck_transactions_params(&stuck_transactions_params_arc)
+            .stuck_transactions_result(vec![stuck_transaction.clone()]);
+        let subject = AccountantBuilder::default()
+            .bootstrapper_config(config)
+            .consuming_wallet(consuming_wallet.clone())
+            .pending_payable_daos(vec![ForAccountantBody(pending_payable_dao)])
             .build();
-        let system = System::new("accountant_uses_receivables_dao_to_process_received_payments");
-        let subject = accountant.start();
+        let (blockchain_bridge, _, blockchain_bridge_recording_arc) = make_recorder();
+        let subject_addr = subject.start();
+        let system = System::new("test");
+        let peer_actors = peer_actors_builder()
+            .blockchain_bridge(blockchain_bridge)
+            .build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+        let ui_message = NodeFromUiMessage {
+            client_id: 1234,
+            body: UiScanRequest {
+                scan_type: ScanType::FeeBumps,
+            }
+            .tmb(4321),
+        };
 
-        subject
-            .try_send(ReceivedPayments {
-                timestamp: now,
-                payments: vec![expected_receivable_1.clone(), expected_receivable_2.clone()],
-                new_start_block: 123456789u64,
-                response_skeleton_opt: None,
-            })
-            .expect("unexpected actix error");
+        subject_addr.try_send(ui_message).unwrap();
 
         System::current().stop();
         system.run();
-        let more_money_received_params = more_money_received_params_arc.lock().unwrap();
+        let stuck_transactions_params = stuck_transactions_params_arc.lock().unwrap();
         assert_eq!(
-            *more_money_received_params,
-            vec![(now, vec![expected_receivable_1, expected_receivable_2])]
+            *stuck_transactions_params,
+            vec![vec![PendingPayableId::new(789, fingerprint.hash)]]
         );
-        let commit_params = commit_params_arc.lock().unwrap();
-        assert_eq!(*commit_params, vec![()]);
-        let set_by_guest_transaction_params = set_by_guest_transaction_params_arc.lock().unwrap();
+        let blockchain_bridge_recording = blockchain_bridge_recording_arc.lock().unwrap();
         assert_eq!(
-            *set_by_guest_transaction_params,
-            vec![(
-                transaction_id,
-                "start_block".to_string(),
-                Some("123456789".to_string())
-            )]
-        )
+            blockchain_bridge_recording.get_record::<RequestFeeBumps>(0),
+            &RequestFeeBumps {
+                consuming_wallet,
+                stuck_transactions: vec![stuck_transaction],
+                gas_price_increase_percent: DEFAULT_FEE_BUMPING.gas_price_increase_percent,
+                response_skeleton_opt: Some(ResponseSkeleton {
+                    client_id: 1234,
+                    context_id: 4321,
+                }),
+            }
+        );
     }
 
     #[test]
-    fn accountant_scans_after_startup() {
+    fn fee_bumps_scan_request_from_ui_is_refused_without_consuming_wallet() {
         init_test_logging();
-        let pending_payable_params_arc = Arc::new(Mutex::new(vec![]));
-        let payable_params_arc = Arc::new(Mutex::new(vec![]));
-        let new_delinquencies_params_arc = Arc::new(Mutex::new(vec![]));
-        let paid_delinquencies_params_arc = Arc::new(Mutex::new(vec![]));
-        let (blockchain_bridge, _, _) = make_recorder();
-        let earning_wallet = make_wallet("earning");
-        let system = System::new("accountant_scans_after_startup");
-        let config = bc_from_wallets(make_wallet("buy"), earning_wallet.clone());
-        let payable_dao = PayableDaoMock::new()
-            .non_pending_payables_params(&payable_params_arc)
-            .non_pending_payables_result(vec![]);
-        let pending_payable_dao = PendingPayableDaoMock::default()
-            .return_all_errorless_fingerprints_params(&pending_payable_params_arc)
-            .return_all_errorless_fingerprints_result(vec![]);
-        let receivable_dao = ReceivableDaoMock::new()
-            .new_delinquencies_parameters(&new_delinquencies_params_arc)
-            .new_delinquencies_result(vec![])
-            .paid_delinquencies_parameters(&paid_delinquencies_params_arc)
-            .paid_delinquencies_result(vec![]);
+        let test_name = "fee_bumps_scan_request_from_ui_is_refused_without_consuming_wallet";
+        let mut config = bc_from_earning_wallet(make_wallet("earning_wallet"));
+        config.suppress_initial_scans = true;
         let subject = AccountantBuilder::default()
             .bootstrapper_config(config)
-            .payable_daos(vec![ForPayableScanner(payable_dao)])
-            .pending_payable_daos(vec![ForPendingPayableScanner(pending_payable_dao)])
-            .receivable_daos(vec![ForReceivableScanner(receivable_dao)])
+            .logger(Logger::new(test_name))
             .build();
+        let (blockchain_bridge, _, blockchain_bridge_recording_arc) = make_recorder();
+        let subject_addr = subject.start();
+        let system = System::new("test");
         let peer_actors = peer_actors_builder()
             .blockchain_bridge(blockchain_bridge)
             .build();
-        let subject_addr: Addr<Accountant> = subject.start();
-        let subject_subs = Accountant::make_subs_from(&subject_addr);
-        send_bind_message!(subject_subs, peer_actors);
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+        let ui_message = NodeFromUiMessage {
+            client_id: 1234,
+            body: UiScanRequest {
+                scan_type: ScanType::FeeBumps,
+            }
+            .tmb(4321),
+        };
 
-        send_start_message!(subject_subs);
+        subject_addr.try_send(ui_message).unwrap();
 
         System::current().stop();
         system.run();
-        let payable_params = payable_params_arc.lock().unwrap();
-        let pending_payable_params = pending_payable_params_arc.lock().unwrap();
-        //proof of calling pieces of scan_for_delinquencies()
-        let mut new_delinquencies_params = new_delinquencies_params_arc.lock().unwrap();
-        let (captured_timestamp, captured_curves) = new_delinquencies_params.remove(0);
-        let paid_delinquencies_params = paid_delinquencies_params_arc.lock().unwrap();
-        assert_eq!(*payable_params, vec![()]);
-        assert_eq!(*pending_payable_params, vec![()]);
-        assert!(new_delinquencies_params.is_empty());
-        assert!(
-            captured_timestamp < SystemTime::now()
-                && captured_timestamp >= from_time_t(to_time_t(SystemTime::now()) - 5)
-        );
-        assert_eq!(captured_curves, PaymentThresholds::default());
-        assert_eq!(paid_delinquencies_params.len(), 1);
-        assert_eq!(paid_delinquencies_params[0], PaymentThresholds::default());
-        let tlh = TestLogHandler::new();
-        tlh.exists_log_containing("INFO: Accountant: Scanning for payables");
-        tlh.exists_log_containing("INFO: Accountant: Scanning for pending payable");
-        tlh.exists_log_containing(&format!(
-            "INFO: Accountant: Scanning for receivables to {}",
-            earning_wallet
+        let blockchain_bridge_recording = blockchain_bridge_recording_arc.lock().unwrap();
+        assert_eq!(blockchain_bridge_recording.len(), 0);
+        TestLogHandler::new().exists_log_containing(&format!(
+            "INFO: {test_name}: Cannot initiate FeeBumps scan because no consuming wallet was found."
         ));
-        tlh.exists_log_containing("INFO: Accountant: Scanning for delinquencies");
     }
 
     #[test]
-    fn periodical_scanning_for_receivables_and_delinquencies_works() {
+    fn fee_bumps_scan_request_from_ui_with_nothing_to_bump_is_logged() {
         init_test_logging();
-        let test_name = "periodical_scanning_for_receivables_and_delinquencies_works";
-        let begin_scan_params_arc = Arc::new(Mutex::new(vec![]));
-        let notify_later_receivable_params_arc = Arc::new(Mutex::new(vec![]));
-        let system = System::new(test_name);
-        SystemKillerActor::new(Duration::from_secs(10)).start(); // a safety net for GitHub Actions
-        let receivable_scanner = ScannerMock::new()
-            .begin_scan_params(&begin_scan_params_arc)
-            .begin_scan_result(Err(BeginScanError::NothingToProcess))
-            .begin_scan_result(Ok(RetrieveTransactions {
-                recipient: make_wallet("some_recipient"),
-                response_skeleton_opt: None,
-            }))
-            .stop_the_system_after_last_msg();
-        let earning_wallet = make_wallet("earning");
-        let mut config = bc_from_earning_wallet(earning_wallet.clone());
-        config.scan_intervals_opt = Some(ScanIntervals {
-            payable_scan_interval: Duration::from_secs(100),
-            receivable_scan_interval: Duration::from_millis(99),
-            pending_payable_scan_interval: Duration::from_secs(100),
-        });
-        let mut subject = AccountantBuilder::default()
+        let test_name = "fee_bumps_scan_request_from_ui_with_nothing_to_bump_is_logged";
+        let mut config = bc_from_earning_wallet(make_wallet("earning_wallet"));
+        config.suppress_initial_scans = true;
+        let pending_payable_dao =
+            PendingPayableDaoMock::default().return_all_errorless_fingerprints_result(vec![]);
+        let subject = AccountantBuilder::default()
             .bootstrapper_config(config)
+            .consuming_wallet(make_paying_wallet(b"consuming"))
             .logger(Logger::new(test_name))
+            .pending_payable_daos(vec![ForAccountantBody(pending_payable_dao)])
             .build();
-        subject.scanners.payable = Box::new(NullScanner::new()); // Skipping
-        subject.scanners.pending_payable = Box::new(NullScanner::new()); // Skipping
-        subject.scanners.receivable = Box::new(receivable_scanner);
-        subject.scan_schedulers.update_scheduler(
-            ScanType::Receivables,
-            Some(Box::new(
-                NotifyLaterHandleMock::default()
-                    .notify_later_params(&notify_later_receivable_params_arc)
-                    .capture_msg_and_let_it_fly_on(),
-            )),
-            None,
-        );
+        let (blockchain_bridge, _, blockchain_bridge_recording_arc) = make_recorder();
         let subject_addr = subject.start();
-        let subject_subs = Accountant::make_subs_from(&subject_addr);
-        let peer_actors = peer_actors_builder().build();
-        send_bind_message!(subject_subs, peer_actors);
+        let system = System::new("test");
+        let peer_actors = peer_actors_builder()
+            .blockchain_bridge(blockchain_bridge)
+            .build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+        let ui_message = NodeFromUiMessage {
+            client_id: 1234,
+            body: UiScanRequest {
+                scan_type: ScanType::FeeBumps,
+            }
+            .tmb(4321),
+        };
 
-        send_start_message!(subject_subs);
+        subject_addr.try_send(ui_message).unwrap();
 
-        let time_before = SystemTime::now();
+        System::current().stop();
         system.run();
-        let time_after = SystemTime::now();
-        let notify_later_receivable_params = notify_later_receivable_params_arc.lock().unwrap();
+        let blockchain_bridge_recording = blockchain_bridge_recording_arc.lock().unwrap();
+        assert_eq!(blockchain_bridge_recording.len(), 0);
         TestLogHandler::new().exists_log_containing(&format!(
-            "DEBUG: {test_name}: There was nothing to process during Receivables scan."
+            "INFO: {test_name}: There was nothing to process during FeeBumps scan."
         ));
-        let mut begin_scan_params = begin_scan_params_arc.lock().unwrap();
-        let (
-            first_attempt_wallet,
-            first_attempt_timestamp,
-            first_attempt_response_skeleton_opt,
-            first_attempt_logger,
-        ) = begin_scan_params.remove(0);
-        let (
-            second_attempt_wallet,
-            second_attempt_timestamp,
-            second_attempt_response_skeleton_opt,
-            second_attempt_logger,
-        ) = begin_scan_params.remove(0);
-        assert_eq!(first_attempt_wallet, second_attempt_wallet);
-        assert_eq!(second_attempt_wallet, earning_wallet);
-        assert!(time_before <= first_attempt_timestamp);
-        assert!(first_attempt_timestamp <= second_attempt_timestamp);
-        assert!(second_attempt_timestamp <= time_after);
-        assert_eq!(first_attempt_response_skeleton_opt, None);
-        assert_eq!(second_attempt_response_skeleton_opt, None);
-        debug!(first_attempt_logger, "first attempt");
-        debug!(second_attempt_logger, "second attempt");
-        let tlh = TestLogHandler::new();
-        tlh.exists_log_containing(&format!("DEBUG: {test_name}: first attempt"));
-        tlh.exists_log_containing(&format!("DEBUG: {test_name}: second attempt"));
-        assert_eq!(
-            *notify_later_receivable_params,
-            vec![
-                (
-                    ScanForReceivables {
-                        response_skeleton_opt: None
-                    },
-                    Duration::from_millis(99)
-                ),
-                (
-                    ScanForReceivables {
-                        response_skeleton_opt: None
-                    },
-                    Duration::from_millis(99)
-                ),
-            ]
-        )
     }
 
     #[test]
-    fn periodical_scanning_for_pending_payable_works() {
+    fn report_fee_bumps_records_them_and_sends_scan_response_to_ui_gateway() {
         init_test_logging();
-        let test_name = "periodical_scanning_for_pending_payable_works";
-        let begin_scan_params_arc = Arc::new(Mutex::new(vec![]));
-        let notify_later_pending_payable_params_arc = Arc::new(Mutex::new(vec![]));
-        let system = System::new(test_name);
-        SystemKillerActor::new(Duration::from_secs(10)).start(); // a safety net for GitHub Actions
-        let consuming_wallet = make_paying_wallet(b"consuming");
-        let pending_payable_scanner = ScannerMock::new()
-            .begin_scan_params(&begin_scan_params_arc)
-            .begin_scan_result(Err(BeginScanError::NothingToProcess))
-            .begin_scan_result(Ok(RequestTransactionReceipts {
-                pending_payable: vec![],
-                response_skeleton_opt: None,
-            }))
-            .stop_the_system_after_last_msg();
-        let mut config = make_bc_with_defaults();
-        config.scan_intervals_opt = Some(ScanIntervals {
-            payable_scan_interval: Duration::from_secs(100),
-            receivable_scan_interval: Duration::from_secs(100),
-            pending_payable_scan_interval: Duration::from_millis(98),
-        });
-        let mut subject = AccountantBuilder::default()
-            .consuming_wallet(consuming_wallet.clone())
-            .bootstrapper_config(config)
+        let test_name = "report_fee_bumps_records_them_and_sends_scan_response_to_ui_gateway";
+        let record_fee_bump_params_arc = Arc::new(Mutex::new(vec![]));
+        let pending_payable_dao = PendingPayableDaoMock::default()
+            .record_fee_bump_params(&record_fee_bump_params_arc)
+            .record_fee_bump_result(Ok(()))
+            .record_fee_bump_result(Err(PendingPayableDaoError::UpdateFailed(
+                "no such fingerprint".to_string(),
+            )));
+        let subject = AccountantBuilder::default()
             .logger(Logger::new(test_name))
+            .pending_payable_daos(vec![ForAccountantBody(pending_payable_dao)])
             .build();
-        subject.scanners.payable = Box::new(NullScanner::new()); //skipping
-        subject.scanners.pending_payable = Box::new(pending_payable_scanner);
-        subject.scanners.receivable = Box::new(NullScanner::new()); //skipping
-        subject.scan_schedulers.update_scheduler(
-            ScanType::PendingPayables,
-            Some(Box::new(
-                NotifyLaterHandleMock::default()
-                    .notify_later_params(&notify_later_pending_payable_params_arc)
-                    .capture_msg_and_let_it_fly_on(),
-            )),
-            None,
-        );
-        let subject_addr: Addr<Accountant> = subject.start();
-        let subject_subs = Accountant::make_subs_from(&subject_addr);
-        let peer_actors = peer_actors_builder().build();
-        send_bind_message!(subject_subs, peer_actors);
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let subject_addr = subject.start();
+        let system = System::new("test");
+        let peer_actors = peer_actors_builder().ui_gateway(ui_gateway).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+        let fee_bump_1 = PendingPayableFeeBump {
+            pending_payable_rowid: 12,
+            replaced_hash: make_tx_hash(0x12),
+            replacement_hash: make_tx_hash(0x120),
+            gas_price_wei: 12_345_678_900,
+            timestamp: from_time_t(1_700_000_000),
+        };
+        let fee_bump_2 = PendingPayableFeeBump {
+            pending_payable_rowid: 13,
+            replaced_hash: make_tx_hash(0x13),
+            replacement_hash: make_tx_hash(0x130),
+            gas_price_wei: 2_000_000_000,
+            timestamp: from_time_t(1_700_000_100),
+        };
 
-        send_start_message!(subject_subs);
+        subject_addr
+            .try_send(ReportFeeBumps {
+                fee_bumps: vec![fee_bump_1.clone(), fee_bump_2.clone()],
+                response_skeleton_opt: Some(ResponseSkeleton {
+                    client_id: 1234,
+                    context_id: 4321,
+                }),
+            })
+            .unwrap();
 
-        let time_before = SystemTime::now();
+        System::current().stop();
         system.run();
-        let time_after = SystemTime::now();
-        let notify_later_pending_payable_params =
-            notify_later_pending_payable_params_arc.lock().unwrap();
-        TestLogHandler::new().exists_log_containing(&format!(
-            "DEBUG: {test_name}: There was nothing to process during PendingPayables scan."
-        ));
-        let mut begin_scan_params = begin_scan_params_arc.lock().unwrap();
-        let (
-            first_attempt_wallet,
-            first_attempt_timestamp,
-            first_attempt_response_skeleton_opt,
-            first_attempt_logger,
-        ) = begin_scan_params.remove(0);
-        let (
-            second_attempt_wallet,
-            second_attempt_timestamp,
-            second_attempt_response_skeleton_opt,
-            second_attempt_logger,
-        ) = begin_scan_params.remove(0);
-        assert_eq!(first_attempt_wallet, second_attempt_wallet);
-        assert_eq!(second_attempt_wallet, consuming_wallet);
-        assert!(time_before <= first_attempt_timestamp);
-        assert!(first_attempt_timestamp <= second_attempt_timestamp);
-        assert!(second_attempt_timestamp <= time_after);
-        assert_eq!(first_attempt_response_skeleton_opt, None);
-        assert_eq!(second_attempt_response_skeleton_opt, None);
-        debug!(first_attempt_logger, "first attempt");
-        debug!(second_attempt_logger, "second attempt");
-        let tlh = TestLogHandler::new();
-        tlh.exists_log_containing(&format!("DEBUG: {test_name}: first attempt"));
-        tlh.exists_log_containing(&format!("DEBUG: {test_name}: second attempt"));
+        let record_fee_bump_params = record_fee_bump_params_arc.lock().unwrap();
+        assert_eq!(*record_fee_bump_params, vec![fee_bump_1, fee_bump_2]);
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
         assert_eq!(
-            *notify_later_pending_payable_params,
-            vec![
-                (
-                    ScanForPendingPayables {
-                        response_skeleton_opt: None
-                    },
-                    Duration::from_millis(98)
-                ),
-                (
-                    ScanForPendingPayables {
-                        response_skeleton_opt: None
-                    },
-                    Duration::from_millis(98)
-                ),
-            ]
-        )
+            ui_gateway_recording.get_record::<NodeToUiMessage>(0),
+            &NodeToUiMessage {
+                target: ClientId(1234),
+                body: UiScanResponse {}.tmb(4321),
+            }
+        );
+        let log_handler = TestLogHandler::new();
+        log_handler.exists_log_containing(&format!(
+            "INFO: {test_name}: Replaced stuck transaction {:?} with {:?} at gas price \
+            12,345,678,900 wei",
+            make_tx_hash(0x12),
+            make_tx_hash(0x120)
+        ));
+        log_handler.exists_log_containing(&format!(
+            "ERROR: {test_name}: Failed to record the replacement of stuck transaction {:?} \
+            with {:?} due to UpdateFailed(\"no such fingerprint\"); the confirmation will keep \
+            following the replaced one",
+            make_tx_hash(0x13),
+            make_tx_hash(0x130)
+        ));
     }
 
     #[test]
-    fn periodical_scanning_for_payable_works() {
-        init_test_logging();
-        let test_name = "periodical_scanning_for_payable_works";
-        let begin_scan_params_arc = Arc::new(Mutex::new(vec![]));
-        let notify_later_payables_params_arc = Arc::new(Mutex::new(vec![]));
-        let system = System::new(test_name);
-        SystemKillerActor::new(Duration::from_secs(10)).start(); // a safety net for GitHub Actions
-        let consuming_wallet = make_paying_wallet(b"consuming");
-        let payable_scanner = ScannerMock::new()
-            .begin_scan_params(&begin_scan_params_arc)
-            .begin_scan_result(Err(BeginScanError::NothingToProcess))
-            .begin_scan_result(Ok(QualifiedPayablesMessage {
-                protected_qualified_payables: protect_payables_in_test(vec![make_payable_account(
-                    123,
-                )]),
-                consuming_wallet: consuming_wallet.clone(),
-                response_skeleton_opt: None,
-            }))
-            .stop_the_system_after_last_msg();
-        let mut config = bc_from_earning_wallet(make_wallet("hi"));
-        config.scan_intervals_opt = Some(ScanIntervals {
-            payable_scan_interval: Duration::from_millis(97),
-            receivable_scan_interval: Duration::from_secs(100), // We'll never run this scanner
-            pending_payable_scan_interval: Duration::from_secs(100), // We'll never run this scanner
-        });
-        let mut subject = AccountantBuilder::default()
-            .bootstrapper_config(config)
-            .consuming_wallet(consuming_wallet.clone())
-            .logger(Logger::new(test_name))
+    fn accountant_calls_payable_dao_to_mark_pending_payable() {
+        let fingerprints_rowids_params_arc = Arc::new(Mutex::new(vec![]));
+        let mark_pending_payables_rowids_params_arc = Arc::new(Mutex::new(vec![]));
+        let expected_wallet = make_wallet("paying_you");
+        let expected_hash = H256::from("transaction_hash".keccak256());
+        let expected_rowid = 45623;
+        let pending_payable_dao = PendingPayableDaoMock::default()
+            .fingerprints_rowids_params(&fingerprints_rowids_params_arc)
+            .fingerprints_rowids_result(TransactionHashes {
+                rowid_results: vec![(expected_rowid, expected_hash)],
+                no_rowid_results: vec![],
+            });
+        let payable_dao = PayableDaoMock::new()
+            .mark_pending_payables_rowids_params(&mark_pending_payables_rowids_params_arc)
+            .mark_pending_payables_rowids_result(Ok(()));
+        let system = System::new("accountant_calls_payable_dao_to_mark_pending_payable");
+        let accountant = AccountantBuilder::default()
+            .bootstrapper_config(bc_from_earning_wallet(make_wallet("some_wallet_address")))
+            .payable_daos(vec![ForPayableScanner(payable_dao)])
+            .pending_payable_daos(vec![ForPayableScanner(pending_payable_dao)])
             .build();
-        subject.scanners.payable = Box::new(payable_scanner);
-        subject.scanners.pending_payable = Box::new(NullScanner::new()); //skipping
-        subject.scanners.receivable = Box::new(NullScanner::new()); //skipping
-        subject.scan_schedulers.update_scheduler(
-            ScanType::Payables,
-            Some(Box::new(
-                NotifyLaterHandleMock::default()
-                    .notify_later_params(&notify_later_payables_params_arc)
-                    .capture_msg_and_let_it_fly_on(),
-            )),
-            None,
-        );
-        let subject_addr = subject.start();
-        let subject_subs = Accountant::make_subs_from(&subject_addr);
-        let peer_actors = peer_actors_builder().build();
-        send_bind_message!(subject_subs, peer_actors);
+        let expected_payable = PendingPayable::new(expected_wallet.clone(), expected_hash.clone());
+        let sent_payable = SentPayables {
+            payment_procedure_result: Ok(vec![Ok(expected_payable.clone())]),
+            response_skeleton_opt: None,
+        };
+        let subject = accountant.start();
 
-        send_start_message!(subject_subs);
+        subject
+            .try_send(sent_payable)
+            .expect("unexpected actix error");
 
-        let time_before = SystemTime::now();
+        System::current().stop();
         system.run();
-        let time_after = SystemTime::now();
-        //the second attempt is the one where the queue is empty and System::current.stop() ends the cycle
-        let notify_later_payables_params = notify_later_payables_params_arc.lock().unwrap();
-        TestLogHandler::new().exists_log_containing(&format!(
-            "DEBUG: {test_name}: There was nothing to process during Payables scan."
-        ));
-        let mut begin_scan_params = begin_scan_params_arc.lock().unwrap();
-        let (
-            first_attempt_wallet,
-            first_attempt_timestamp,
-            first_attempt_response_skeleton_opt,
-            first_attempt_logger,
-        ) = begin_scan_params.remove(0);
-        let (
-            second_attempt_wallet,
-            second_attempt_timestamp,
-            second_attempt_response_skeleton_opt,
-            second_attempt_logger,
-        ) = begin_scan_params.remove(0);
-        assert_eq!(first_attempt_wallet, second_attempt_wallet);
-        assert_eq!(second_attempt_wallet, consuming_wallet);
-        assert!(time_before <= first_attempt_timestamp);
-        assert!(first_attempt_timestamp <= second_attempt_timestamp);
-        assert!(second_attempt_timestamp <= time_after);
-        assert_eq!(first_attempt_response_skeleton_opt, None);
-        assert_eq!(second_attempt_response_skeleton_opt, None);
-        debug!(first_attempt_logger, "first attempt");
-        debug!(second_attempt_logger, "second attempt");
-        let tlh = TestLogHandler::new();
-        tlh.exists_log_containing(&format!("DEBUG: {test_name}: first attempt"));
-        tlh.exists_log_containing(&format!("DEBUG: {test_name}: second attempt"));
+        let fingerprints_rowids_params = fingerprints_rowids_params_arc.lock().unwrap();
+        assert_eq!(*fingerprints_rowids_params, vec![vec![expected_hash]]);
+        let mark_pending_payables_rowids_params =
+            mark_pending_payables_rowids_params_arc.lock().unwrap();
         assert_eq!(
-            *notify_later_payables_params,
-            vec![
-                (
-                    ScanForPayables {
-                        response_skeleton_opt: None
-                    },
-                    Duration::from_millis(97)
-                ),
-                (
-                    ScanForPayables {
-                        response_skeleton_opt: None
-                    },
-                    Duration::from_millis(97)
-                ),
-            ]
-        )
+            *mark_pending_payables_rowids_params,
+            vec![vec![(expected_wallet, expected_rowid)]]
+        );
     }
 
     #[test]
-    fn payable_scan_is_not_initiated_if_consuming_wallet_is_not_found() {
-        init_test_logging();
-        let test_name = "payable_scan_is_not_initiated_if_consuming_wallet_is_not_found";
-        let mut subject = AccountantBuilder::default().build();
-        subject.consuming_wallet_opt = None;
-        subject.logger = Logger::new(test_name);
-
-        subject.handle_request_of_scan_for_payable(None);
-
-        let has_scan_started = subject.scanners.payable.scan_started_at().is_some();
-        assert_eq!(has_scan_started, false);
-        TestLogHandler::new().exists_log_containing(&format!(
-            "DEBUG: {test_name}: Cannot initiate Payables scan because no consuming wallet was found."
-        ));
-    }
-
-    #[test]
-    fn pending_payable_scan_is_not_initiated_if_consuming_wallet_is_not_found() {
-        init_test_logging();
-        let test_name = "pending_payable_scan_is_not_initiated_if_consuming_wallet_is_not_found";
-        let mut subject = AccountantBuilder::default().build();
-        subject.consuming_wallet_opt = None;
-        subject.logger = Logger::new(test_name);
-
-        subject.handle_request_of_scan_for_pending_payable(None);
-
-        let has_scan_started = subject.scanners.pending_payable.scan_started_at().is_some();
-        assert_eq!(has_scan_started, false);
-        TestLogHandler::new().exists_log_containing(&format!(
-            "DEBUG: {test_name}: Cannot initiate PendingPayables scan because no consuming wallet was found."
-        ));
-    }
-
-    #[test]
-    fn start_message_triggers_no_scans_in_suppress_mode() {
-        init_test_logging();
-        let test_name = "start_message_triggers_no_scans_in_suppress_mode";
-        let system = System::new(test_name);
-        let mut config = bc_from_earning_wallet(make_wallet("hi"));
-        config.scan_intervals_opt = Some(ScanIntervals {
-            payable_scan_interval: Duration::from_millis(100),
-            receivable_scan_interval: Duration::from_millis(100),
-            pending_payable_scan_interval: Duration::from_millis(100),
-        });
-        config.suppress_initial_scans = true;
-        let peer_actors = peer_actors_builder().build();
-        let subject = AccountantBuilder::default()
-            .bootstrapper_config(config)
-            .logger(Logger::new(test_name))
-            .build();
-        let subject_addr = subject.start();
-        let subject_subs = Accountant::make_subs_from(&subject_addr);
-        send_bind_message!(subject_subs, peer_actors);
-
-        send_start_message!(subject_subs);
-
-        System::current().stop();
-        assert_eq!(system.run(), 0);
-        // no panics because of recalcitrant DAOs; therefore DAOs were not called; therefore test passes
-        TestLogHandler::new().exists_log_containing(
-            &format!("{test_name}: Started with --scans off; declining to begin database and blockchain scans"),
-        );
-    }
-
-    #[test]
-    fn scan_for_payables_message_does_not_trigger_payment_for_balances_below_the_curve() {
-        init_test_logging();
-        let consuming_wallet = make_paying_wallet(b"consuming wallet");
-        let payment_thresholds = PaymentThresholds {
-            threshold_interval_sec: 2_592_000,
-            debt_threshold_gwei: 1_000_000_000,
-            payment_grace_period_sec: 86_400,
-            maturity_threshold_sec: 86_400,
-            permanent_debt_allowed_gwei: 10_000_000,
-            unban_below_gwei: 10_000_000,
-        };
-        let config = bc_from_earning_wallet(make_wallet("mine"));
-        let now = to_time_t(SystemTime::now());
-        let accounts = vec![
-            // below minimum balance, to the right of time intersection (inside buffer zone)
-            PayableAccount {
-                wallet: make_wallet("wallet0"),
-                balance_wei: gwei_to_wei(payment_thresholds.permanent_debt_allowed_gwei - 1),
-                last_paid_timestamp: from_time_t(
-                    now - checked_conversion::<u64, i64>(
-                        payment_thresholds.threshold_interval_sec + 10,
-                    ),
-                ),
-                pending_payable_opt: None,
-            },
-            // above balance intersection, to the left of minimum time (outside buffer zone)
-            PayableAccount {
-                wallet: make_wallet("wallet1"),
-                balance_wei: gwei_to_wei(payment_thresholds.debt_threshold_gwei + 1),
-                last_paid_timestamp: from_time_t(
-                    now - checked_conversion::<u64, i64>(
-                        payment_thresholds.maturity_threshold_sec - 10,
-                    ),
-                ),
-                pending_payable_opt: None,
-            },
-            // above minimum balance, to the right of minimum time (not in buffer zone, below the curve)
-            PayableAccount {
-                wallet: make_wallet("wallet2"),
-                balance_wei: gwei_to_wei(payment_thresholds.permanent_debt_allowed_gwei + 55),
-                last_paid_timestamp: from_time_t(
-                    now - checked_conversion::<u64, i64>(
-                        payment_thresholds.maturity_threshold_sec + 15,
-                    ),
-                ),
-                pending_payable_opt: None,
-            },
-        ];
-        let payable_dao = PayableDaoMock::new()
-            .non_pending_payables_result(accounts.clone())
-            .non_pending_payables_result(vec![]);
-        let (blockchain_bridge, _, blockchain_bridge_recordings_arc) = make_recorder();
+    fn accountant_sends_initial_payable_payments_msg_when_qualified_payable_found() {
+        let (blockchain_bridge, _, blockchain_bridge_recording_arc) = make_recorder();
+        let now = SystemTime::now();
+        let payment_thresholds = PaymentThresholds::default();
+        let (qualified_payables, _, all_non_pending_payables) =
+            make_payables(now, &payment_thresholds);
+        let payable_dao =
+            PayableDaoMock::new().non_pending_payables_result(all_non_pending_payables);
         let system = System::new(
-            "scan_for_payable_message_does_not_trigger_payment_for_balances_below_the_curve",
+            "accountant_sends_initial_payable_payments_msg_when_qualified_payable_found",
         );
-        let blockchain_bridge_addr: Addr<Recorder> = blockchain_bridge.start();
-        let outbound_payments_instructions_sub =
-            blockchain_bridge_addr.recipient::<OutboundPaymentsInstructions>();
+        let consuming_wallet = make_paying_wallet(b"consuming");
         let mut subject = AccountantBuilder::default()
-            .bootstrapper_config(config)
+            .bootstrapper_config(bc_from_earning_wallet(make_wallet("some_wallet_address")))
+            .consuming_wallet(consuming_wallet.clone())
             .payable_daos(vec![ForPayableScanner(payable_dao)])
             .build();
-        subject.outbound_payments_instructions_sub_opt = Some(outbound_payments_instructions_sub);
+        subject.scanners.pending_payable = Box::new(NullScanner::new());
+        subject.scanners.receivable = Box::new(NullScanner::new());
+        let accountant_addr = subject.start();
+        let accountant_subs = Accountant::make_subs_from(&accountant_addr);
+        let peer_actors = peer_actors_builder()
+            .blockchain_bridge(blockchain_bridge)
+            .build();
+        send_bind_message!(accountant_subs, peer_actors);
 
-        let _result = subject.scanners.payable.begin_scan(
-            consuming_wallet,
-            SystemTime::now(),
-            None,
-            &subject.logger,
-        );
+        send_start_message!(accountant_subs);
 
         System::current().stop();
         system.run();
-        let blockchain_bridge_recordings = blockchain_bridge_recordings_arc.lock().unwrap();
-        assert_eq!(blockchain_bridge_recordings.len(), 0);
+        let blockchain_bridge_recorder = blockchain_bridge_recording_arc.lock().unwrap();
+        assert_eq!(blockchain_bridge_recorder.len(), 1);
+        let message = blockchain_bridge_recorder.get_record::<QualifiedPayablesMessage>(0);
+        assert_eq!(
+            message,
+            &QualifiedPayablesMessage {
+                protected_qualified_payables: protect_payables_in_test(qualified_payables),
+                consuming_wallet,
+                response_skeleton_opt: None,
+            }
+        );
     }
 
     #[test]
-    fn scan_for_payable_message_triggers_payment_for_balances_over_the_curve() {
+    fn accountant_requests_blockchain_bridge_to_scan_for_received_payments() {
         init_test_logging();
-        let mut config = bc_from_earning_wallet(make_wallet("mine"));
-        let consuming_wallet = make_paying_wallet(b"consuming");
-        config.scan_intervals_opt = Some(ScanIntervals {
-            pending_payable_scan_interval: Duration::from_secs(50_000),
-            payable_scan_interval: Duration::from_secs(50_000),
-            receivable_scan_interval: Duration::from_secs(50_000),
-        });
-        let now = to_time_t(SystemTime::now());
-        let qualified_payables = vec![
-            // slightly above minimum balance, to the right of the curve (time intersection)
-            PayableAccount {
-                wallet: make_wallet("wallet0"),
-                balance_wei: gwei_to_wei(
-                    DEFAULT_PAYMENT_THRESHOLDS.permanent_debt_allowed_gwei + 1,
-                ),
-                last_paid_timestamp: from_time_t(
-                    now - checked_conversion::<u64, i64>(
-                        DEFAULT_PAYMENT_THRESHOLDS.threshold_interval_sec
-                            + DEFAULT_PAYMENT_THRESHOLDS.maturity_threshold_sec
-                            + 10,
-                    ),
-                ),
-                pending_payable_opt: None,
-            },
-            // slightly above the curve (balance intersection), to the right of minimum time
-            PayableAccount {
-                wallet: make_wallet("wallet1"),
-                balance_wei: gwei_to_wei(DEFAULT_PAYMENT_THRESHOLDS.debt_threshold_gwei + 1),
-                last_paid_timestamp: from_time_t(
-                    now - checked_conversion::<u64, i64>(
-                        DEFAULT_PAYMENT_THRESHOLDS.maturity_threshold_sec + 10,
-                    ),
-                ),
-                pending_payable_opt: None,
-            },
-        ];
-        let payable_dao =
-            PayableDaoMock::default().non_pending_payables_result(qualified_payables.clone());
-        let (blockchain_bridge, _, blockchain_bridge_recordings_arc) = make_recorder();
-        let blockchain_bridge = blockchain_bridge
-            .system_stop_conditions(match_every_type_id!(QualifiedPayablesMessage));
+        let (blockchain_bridge, _, blockchain_bridge_recording_arc) = make_recorder();
+        let earning_wallet = make_wallet("someearningwallet");
         let system =
-            System::new("scan_for_payable_message_triggers_payment_for_balances_over_the_curve");
-        let peer_actors = peer_actors_builder()
-            .blockchain_bridge(blockchain_bridge)
-            .build();
+            System::new("accountant_requests_blockchain_bridge_to_scan_for_received_payments");
+        let receivable_dao = ReceivableDaoMock::new()
+            .new_delinquencies_result(vec![])
+            .paid_delinquencies_result(vec![]);
         let mut subject = AccountantBuilder::default()
-            .bootstrapper_config(config)
-            .consuming_wallet(consuming_wallet.clone())
-            .payable_daos(vec![ForPayableScanner(payable_dao)])
+            .bootstrapper_config(bc_from_earning_wallet(earning_wallet.clone()))
+            .receivable_daos(vec![ForReceivableScanner(receivable_dao)])
             .build();
         subject.scanners.pending_payable = Box::new(NullScanner::new());
-        subject.scanners.receivable = Box::new(NullScanner::new());
-        let subject_addr = subject.start();
-        let accountant_subs = Accountant::make_subs_from(&subject_addr);
+        subject.scanners.payable = Box::new(NullScanner::new());
+        let accountant_addr = subject.start();
+        let accountant_subs = Accountant::make_subs_from(&accountant_addr);
+        let peer_actors = peer_actors_builder()
+            .blockchain_bridge(blockchain_bridge)
+            .build();
         send_bind_message!(accountant_subs, peer_actors);
 
         send_start_message!(accountant_subs);
 
+        System::current().stop();
         system.run();
-        let blockchain_bridge_recordings = blockchain_bridge_recordings_arc.lock().unwrap();
-        let message = blockchain_bridge_recordings.get_record::<QualifiedPayablesMessage>(0);
+        let blockchain_bridge_recorder = blockchain_bridge_recording_arc.lock().unwrap();
+        assert_eq!(blockchain_bridge_recorder.len(), 1);
+        let retrieve_transactions_msg =
+            blockchain_bridge_recorder.get_record::<RetrieveTransactions>(0);
         assert_eq!(
-            message,
-            &QualifiedPayablesMessage {
-                protected_qualified_payables: protect_payables_in_test(qualified_payables),
-                consuming_wallet,
+            retrieve_transactions_msg,
+            &RetrieveTransactions {
+                recipient: earning_wallet.clone(),
                 response_skeleton_opt: None,
             }
         );
     }
 
     #[test]
-    fn accountant_does_not_initiate_another_scan_if_one_is_already_running() {
-        init_test_logging();
-        let test_name = "accountant_does_not_initiate_another_scan_if_one_is_already_running";
-        let payable_dao = PayableDaoMock::default();
-        let (blockchain_bridge, _, blockchain_bridge_recording) = make_recorder();
-        let blockchain_bridge_addr = blockchain_bridge
-            .system_stop_conditions(match_every_type_id!(
-                QualifiedPayablesMessage,
-                QualifiedPayablesMessage
-            ))
-            .start();
-        let pps_for_blockchain_bridge_sub = blockchain_bridge_addr.clone().recipient();
-        let last_paid_timestamp = to_time_t(SystemTime::now())
-            - DEFAULT_PAYMENT_THRESHOLDS.maturity_threshold_sec as i64
-            - 1;
-        let payable_account = PayableAccount {
-            wallet: make_wallet("scan_for_payables"),
-            balance_wei: gwei_to_wei(DEFAULT_PAYMENT_THRESHOLDS.debt_threshold_gwei + 1),
-            last_paid_timestamp: from_time_t(last_paid_timestamp),
-            pending_payable_opt: None,
-        };
-        let payable_dao = payable_dao
-            .non_pending_payables_result(vec![payable_account.clone()])
-            .non_pending_payables_result(vec![payable_account]);
-        let config = bc_from_earning_wallet(make_wallet("mine"));
-        let system = System::new(test_name);
-        let mut subject = AccountantBuilder::default()
-            .consuming_wallet(make_paying_wallet(b"consuming"))
-            .logger(Logger::new(test_name))
-            .payable_daos(vec![ForPayableScanner(payable_dao)])
-            .bootstrapper_config(config)
-            .build();
-        let message_before = ScanForPayables {
-            response_skeleton_opt: Some(ResponseSkeleton {
-                client_id: 111,
-                context_id: 222,
-            }),
-        };
-        let message_after = ScanForPayables {
-            response_skeleton_opt: Some(ResponseSkeleton {
-                client_id: 333,
-                context_id: 444,
-            }),
-        };
-        subject.qualified_payables_sub_opt = Some(pps_for_blockchain_bridge_sub);
-        let addr = subject.start();
-        addr.try_send(message_before.clone()).unwrap();
-
-        addr.try_send(ScanForPayables {
-            response_skeleton_opt: None,
-        })
-        .unwrap();
-
-        // We ignored the second ScanForPayables message because the first message meant a scan
-        // was already in progress; now let's make it look like that scan has ended so that we
-        // can prove the next message will start another one.
-        addr.try_send(AssertionsMessage {
-            assertions: Box::new(|accountant: &mut Accountant| {
-                accountant
-                    .scanners
-                    .payable
-                    .mark_as_ended(&Logger::new("irrelevant"))
-            }),
-        })
-        .unwrap();
-        addr.try_send(message_after.clone()).unwrap();
-        system.run();
-        let recording = blockchain_bridge_recording.lock().unwrap();
-        let messages_received = recording.len();
-        assert_eq!(messages_received, 2);
-        let first_message: &QualifiedPayablesMessage = recording.get_record(0);
-        assert_eq!(
-            first_message.response_skeleton_opt,
-            message_before.response_skeleton_opt
-        );
-        let second_message: &QualifiedPayablesMessage = recording.get_record(1);
-        assert_eq!(
-            second_message.response_skeleton_opt,
-            message_after.response_skeleton_opt
-        );
-        TestLogHandler::new().exists_log_containing(&format!(
-            "DEBUG: {}: Payables scan was already initiated",
-            test_name
-        ));
-    }
-
-    #[test]
-    fn scan_for_pending_payables_finds_still_pending_payables() {
-        init_test_logging();
-        let (blockchain_bridge, _, blockchain_bridge_recording_arc) = make_recorder();
-        let blockchain_bridge_addr = blockchain_bridge
-            .system_stop_conditions(match_every_type_id!(RequestTransactionReceipts))
-            .start();
-        let payable_fingerprint_1 = PendingPayableFingerprint {
-            rowid: 555,
-            timestamp: from_time_t(210_000_000),
-            hash: make_tx_hash(45678),
-            attempt: 1,
-            amount: 4444,
-            process_error: None,
+    fn accountant_processes_msg_with_received_payments_using_receivables_dao_and_then_updates_start_block(
+    ) {
+        let more_money_received_params_arc = Arc::new(Mutex::new(vec![]));
+        let commit_params_arc = Arc::new(Mutex::new(vec![]));
+        let get_params_arc = Arc::new(Mutex::new(vec![]));
+        let set_by_guest_transaction_params_arc = Arc::new(Mutex::new(vec![]));
+        let now = SystemTime::now();
+        let earning_wallet = make_wallet("earner3000");
+        let expected_receivable_1 = BlockchainTransaction {
+            block_number: 7,
+            block_hash: H256::from_low_u64_be(7),
+            transaction_hash: H256::from_low_u64_be(1),
+            log_index: 0,
+            from: make_wallet("wallet0"),
+            wei_amount: 456,
         };
-        let payable_fingerprint_2 = PendingPayableFingerprint {
-            rowid: 550,
-            timestamp: from_time_t(210_000_100),
-            hash: make_tx_hash(112233),
-            attempt: 2,
-            amount: 7999,
-            process_error: None,
+        let expected_receivable_2 = BlockchainTransaction {
+            block_number: 13,
+            block_hash: H256::from_low_u64_be(13),
+            transaction_hash: H256::from_low_u64_be(2),
+            log_index: 0,
+            from: make_wallet("wallet1"),
+            wei_amount: 10000,
         };
-        let pending_payable_dao = PendingPayableDaoMock::default()
-            .return_all_errorless_fingerprints_result(vec![
-                payable_fingerprint_1.clone(),
-                payable_fingerprint_2.clone(),
-            ]);
-        let config = bc_from_earning_wallet(make_wallet("mine"));
-        let system = System::new("pending payable scan");
-        let mut subject = AccountantBuilder::default()
-            .consuming_wallet(make_paying_wallet(b"consuming"))
-            .pending_payable_daos(vec![ForPendingPayableScanner(pending_payable_dao)])
-            .bootstrapper_config(config)
+        let transaction_id = ArbitraryIdStamp::new();
+        let txn_inner_builder = TransactionInnerWrapperMockBuilder::default()
+            .commit_params(&commit_params_arc)
+            .commit_result(Ok(()))
+            .set_arbitrary_id_stamp(transaction_id);
+        let wrapped_transaction = TransactionSafeWrapper::new_with_builder(txn_inner_builder);
+        let receivable_dao = ReceivableDaoMock::new()
+            .more_money_received_params(&more_money_received_params_arc)
+            .more_money_received_result(wrapped_transaction);
+        let config_dao = ConfigDaoMock::new()
+            .get_params(&get_params_arc)
+            .get_result(Ok(ConfigDaoRecord::new("start_block", None, false)))
+            .set_by_guest_transaction_params(&set_by_guest_transaction_params_arc)
+            .set_by_guest_transaction_result(Ok(()));
+        let mut bootstrapper_config = bc_from_earning_wallet(earning_wallet.clone());
+        // A chain credited without waiting for confirmations
+        bootstrapper_config.blockchain_bridge_config.chain = Chain::Dev;
+        let accountant = AccountantBuilder::default()
+            .bootstrapper_config(bootstrapper_config)
+            .receivable_daos(vec![ForReceivableScanner(receivable_dao)])
+            .config_daos(vec![ForReceivableScanner(config_dao)])
             .build();
+        let system = System::new("accountant_uses_receivables_dao_to_process_received_payments");
+        let subject = accountant.start();
 
-        subject.request_transaction_receipts_subs_opt = Some(blockchain_bridge_addr.recipient());
-        let account_addr = subject.start();
-
-        let _ = account_addr
-            .try_send(ScanForPendingPayables {
+        subject
+            .try_send(ReceivedPayments {
+                timestamp: now,
+                payments: vec![expected_receivable_1.clone(), expected_receivable_2.clone()],
+                new_start_block: 123456789u64,
+                scanned_blocks_opt: None,
                 response_skeleton_opt: None,
             })
-            .unwrap();
+            .expect("unexpected actix error");
 
+        System::current().stop();
         system.run();
-        let blockchain_bridge_recording = blockchain_bridge_recording_arc.lock().unwrap();
-        assert_eq!(blockchain_bridge_recording.len(), 1);
-        let received_msg = blockchain_bridge_recording.get_record::<RequestTransactionReceipts>(0);
+        let more_money_received_params = more_money_received_params_arc.lock().unwrap();
         assert_eq!(
-            received_msg,
-            &RequestTransactionReceipts {
-                pending_payable: vec![payable_fingerprint_1, payable_fingerprint_2],
-                response_skeleton_opt: None,
-            }
+            *more_money_received_params,
+            vec![(now, vec![expected_receivable_1, expected_receivable_2])]
         );
-        let log_handler = TestLogHandler::new();
-        log_handler.exists_log_containing("DEBUG: Accountant: Found 2 pending payables to process");
+        let commit_params = commit_params_arc.lock().unwrap();
+        assert_eq!(*commit_params, vec![()]);
+        let set_by_guest_transaction_params = set_by_guest_transaction_params_arc.lock().unwrap();
+        assert_eq!(
+            *set_by_guest_transaction_params,
+            vec![(
+                transaction_id,
+                "start_block".to_string(),
+                Some("123456789".to_string())
+            )]
+        )
     }
 
     #[test]
-    fn report_routing_service_provided_message_is_received() {
+    fn accountant_scans_after_startup() {
         init_test_logging();
-        let now = SystemTime::now();
-        let bootstrapper_config = bc_from_earning_wallet(make_wallet("hi"));
-        let more_money_receivable_parameters_arc = Arc::new(Mutex::new(vec![]));
-        let payable_dao_mock = PayableDaoMock::new().non_pending_payables_result(vec![]);
-        let receivable_dao_mock = ReceivableDaoMock::new()
-            .more_money_receivable_parameters(&more_money_receivable_parameters_arc)
-            .more_money_receivable_result(Ok(()));
+        let pending_payable_params_arc = Arc::new(Mutex::new(vec![]));
+        let payable_params_arc = Arc::new(Mutex::new(vec![]));
+        let new_delinquencies_params_arc = Arc::new(Mutex::new(vec![]));
+        let paid_delinquencies_params_arc = Arc::new(Mutex::new(vec![]));
+        let (blockchain_bridge, _, _) = make_recorder();
+        let earning_wallet = make_wallet("earning");
+        let system = System::new("accountant_scans_after_startup");
+        let config = bc_from_wallets(make_wallet("buy"), earning_wallet.clone());
+        let payable_dao = PayableDaoMock::new()
+            .non_pending_payables_params(&payable_params_arc)
+            .non_pending_payables_result(vec![]);
+        let pending_payable_dao = PendingPayableDaoMock::default()
+            .return_all_errorless_fingerprints_params(&pending_payable_params_arc)
+            .return_all_errorless_fingerprints_result(vec![]);
+        let receivable_dao = ReceivableDaoMock::new()
+            .new_delinquencies_parameters(&new_delinquencies_params_arc)
+            .new_delinquencies_result(vec![])
+            .paid_delinquencies_parameters(&paid_delinquencies_params_arc)
+            .paid_delinquencies_result(vec![]);
         let subject = AccountantBuilder::default()
-            .bootstrapper_config(bootstrapper_config)
-            .payable_daos(vec![ForAccountantBody(payable_dao_mock)])
-            .receivable_daos(vec![ForAccountantBody(receivable_dao_mock)])
+            .bootstrapper_config(config)
+            .payable_daos(vec![ForPayableScanner(payable_dao)])
+            .pending_payable_daos(vec![ForPendingPayableScanner(pending_payable_dao)])
+            .receivable_daos(vec![ForReceivableScanner(receivable_dao)])
+            .build();
+        let peer_actors = peer_actors_builder()
+            .blockchain_bridge(blockchain_bridge)
             .build();
-        let system = System::new("report_routing_service_message_is_received");
         let subject_addr: Addr<Accountant> = subject.start();
-        subject_addr
-            .try_send(BindMessage {
-                peer_actors: peer_actors_builder().build(),
-            })
-            .unwrap();
+        let subject_subs = Accountant::make_subs_from(&subject_addr);
+        send_bind_message!(subject_subs, peer_actors);
 
-        let paying_wallet = make_wallet("booga");
-        subject_addr
-            .try_send(ReportRoutingServiceProvidedMessage {
-                timestamp: now,
-                paying_wallet: paying_wallet.clone(),
-                payload_size: 1234,
-                service_rate: 42,
-                byte_rate: 24,
-            })
-            .unwrap();
+        send_start_message!(subject_subs);
 
-        System::current().stop_with_code(0);
+        System::current().stop();
         system.run();
-        let more_money_receivable_parameters = more_money_receivable_parameters_arc.lock().unwrap();
-        assert_eq!(
-            more_money_receivable_parameters[0],
-            (now, make_wallet("booga"), (1 * 42) + (1234 * 24))
+        let payable_params = payable_params_arc.lock().unwrap();
+        let pending_payable_params = pending_payable_params_arc.lock().unwrap();
+        //proof of calling pieces of scan_for_delinquencies()
+        let mut new_delinquencies_params = new_delinquencies_params_arc.lock().unwrap();
+        let (captured_timestamp, captured_curves) = new_delinquencies_params.remove(0);
+        let paid_delinquencies_params = paid_delinquencies_params_arc.lock().unwrap();
+        assert_eq!(*payable_params, vec![()]);
+        assert_eq!(*pending_payable_params, vec![()]);
+        assert!(new_delinquencies_params.is_empty());
+        assert!(
+            captured_timestamp < SystemTime::now()
+                && captured_timestamp >= from_time_t(to_time_t(SystemTime::now()) - 5)
         );
-        TestLogHandler::new().exists_log_containing(&format!(
-            "DEBUG: Accountant: Charging routing of 1234 bytes to wallet {}",
-            paying_wallet
+        assert_eq!(captured_curves, PaymentThresholds::default());
+        assert_eq!(paid_delinquencies_params.len(), 1);
+        assert_eq!(paid_delinquencies_params[0], PaymentThresholds::default());
+        let tlh = TestLogHandler::new();
+        tlh.exists_log_containing("INFO: Accountant: Scanning for payables");
+        tlh.exists_log_containing("INFO: Accountant: Scanning for pending payable");
+        tlh.exists_log_containing(&format!(
+            "INFO: Accountant: Scanning for receivables to {}",
+            earning_wallet
         ));
+        tlh.exists_log_containing("INFO: Accountant: Scanning for delinquencies");
     }
 
     #[test]
-    fn report_routing_service_provided_message_is_received_from_our_consuming_wallet() {
+    fn periodical_scanning_for_receivables_and_delinquencies_works() {
         init_test_logging();
-        let consuming_wallet = make_wallet("our consuming wallet");
-        let config = bc_from_wallets(consuming_wallet.clone(), make_wallet("our earning wallet"));
-        let more_money_receivable_parameters_arc = Arc::new(Mutex::new(vec![]));
-        let payable_dao_mock = PayableDaoMock::new().non_pending_payables_result(vec![]);
-        let receivable_dao_mock = ReceivableDaoMock::new()
-            .more_money_receivable_parameters(&more_money_receivable_parameters_arc);
-        let subject = AccountantBuilder::default()
+        let test_name = "periodical_scanning_for_receivables_and_delinquencies_works";
+        let begin_scan_params_arc = Arc::new(Mutex::new(vec![]));
+        let notify_later_receivable_params_arc = Arc::new(Mutex::new(vec![]));
+        let system = System::new(test_name);
+        SystemKillerActor::new(Duration::from_secs(10)).start(); // a safety net for GitHub Actions
+        let receivable_scanner = ScannerMock::new()
+            .begin_scan_params(&begin_scan_params_arc)
+            .begin_scan_result(Err(BeginScanError::NothingToProcess))
+            .begin_scan_result(Ok(RetrieveTransactions {
+                recipient: make_wallet("some_recipient"),
+                response_skeleton_opt: None,
+            }))
+            .stop_the_system_after_last_msg();
+        let earning_wallet = make_wallet("earning");
+        let mut config = bc_from_earning_wallet(earning_wallet.clone());
+        config.scan_intervals_opt = Some(ScanIntervals {
+            payable_scan_interval: Duration::from_secs(100),
+            receivable_scan_interval: Duration::from_millis(99),
+            pending_payable_scan_interval: Duration::from_secs(100),
+        });
+        let mut subject = AccountantBuilder::default()
             .bootstrapper_config(config)
-            .payable_daos(vec![ForAccountantBody(payable_dao_mock)])
-            .receivable_daos(vec![ForAccountantBody(receivable_dao_mock)])
+            .logger(Logger::new(test_name))
             .build();
-        let system = System::new("report_routing_service_message_is_received");
-        let subject_addr: Addr<Accountant> = subject.start();
-        subject_addr
-            .try_send(BindMessage {
-                peer_actors: peer_actors_builder().build(),
-            })
-            .unwrap();
+        subject.scanners.payable = Box::new(NullScanner::new()); // Skipping
+        subject.scanners.pending_payable = Box::new(NullScanner::new()); // Skipping
+        subject.scanners.receivable = Box::new(receivable_scanner);
+        subject.scan_schedulers.update_scheduler(
+            ScanType::Receivables,
+            Some(Box::new(
+                NotifyLaterHandleMock::default()
+                    .notify_later_params(&notify_later_receivable_params_arc)
+                    .capture_msg_and_let_it_fly_on(),
+            )),
+            None,
+        );
+        let subject_addr = subject.start();
+        let subject_subs = Accountant::make_subs_from(&subject_addr);
+        let peer_actors = peer_actors_builder().build();
+        send_bind_message!(subject_subs, peer_actors);
 
-        subject_addr
-            .try_send(ReportRoutingServiceProvidedMessage {
-                timestamp: SystemTime::now(),
-                paying_wallet: consuming_wallet.clone(),
-                payload_size: 1234,
-                service_rate: 42,
-                byte_rate: 24,
-            })
-            .unwrap();
+        send_start_message!(subject_subs);
 
-        System::current().stop_with_code(0);
+        let time_before = SystemTime::now();
         system.run();
-        assert!(more_money_receivable_parameters_arc
-            .lock()
-            .unwrap()
-            .is_empty());
-
+        let time_after = SystemTime::now();
+        let notify_later_receivable_params = notify_later_receivable_params_arc.lock().unwrap();
         TestLogHandler::new().exists_log_containing(&format!(
-            "WARN: Accountant: Declining to record a receivable against our wallet {} for service we provided",
-            consuming_wallet,
+            "DEBUG: {test_name}: There was nothing to process during Receivables scan."
         ));
+        let mut begin_scan_params = begin_scan_params_arc.lock().unwrap();
+        let (
+            first_attempt_wallet,
+            first_attempt_timestamp,
+            first_attempt_response_skeleton_opt,
+            first_attempt_logger,
+        ) = begin_scan_params.remove(0);
+        let (
+            second_attempt_wallet,
+            second_attempt_timestamp,
+            second_attempt_response_skeleton_opt,
+            second_attempt_logger,
+        ) = begin_scan_params.remove(0);
+        assert_eq!(first_attempt_wallet, second_attempt_wallet);
+        assert_eq!(second_attempt_wallet, earning_wallet);
+        assert!(time_before <= first_attempt_timestamp);
+        assert!(first_attempt_timestamp <= second_attempt_timestamp);
+        assert!(second_attempt_timestamp <= time_after);
+        assert_eq!(first_attempt_response_skeleton_opt, None);
+        assert_eq!(second_attempt_response_skeleton_opt, None);
+        debug!(first_attempt_logger, "first attempt");
+        debug!(second_attempt_logger, "second attempt");
+        let tlh = TestLogHandler::new();
+        tlh.exists_log_containing(&format!("DEBUG: {test_name}: first attempt"));
+        tlh.exists_log_containing(&format!("DEBUG: {test_name}: second attempt"));
+        assert_eq!(
+            *notify_later_receivable_params,
+            vec![
+                (
+                    ScanForReceivables {
+                        response_skeleton_opt: None
+                    },
+                    Duration::from_millis(99)
+                ),
+                (
+                    ScanForReceivables {
+                        response_skeleton_opt: None
+                    },
+                    Duration::from_millis(99)
+                ),
+            ]
+        )
     }
 
     #[test]
-    fn report_routing_service_provided_message_is_received_from_our_earning_wallet() {
+    fn periodical_scanning_for_pending_payable_works() {
         init_test_logging();
-        let earning_wallet = make_wallet("our earning wallet");
-        let config = bc_from_earning_wallet(earning_wallet.clone());
-        let more_money_receivable_parameters_arc = Arc::new(Mutex::new(vec![]));
-        let payable_dao_mock = PayableDaoMock::new().non_pending_payables_result(vec![]);
-        let receivable_dao_mock = ReceivableDaoMock::new()
-            .more_money_receivable_parameters(&more_money_receivable_parameters_arc);
-        let subject = AccountantBuilder::default()
+        let test_name = "periodical_scanning_for_pending_payable_works";
+        let begin_scan_params_arc = Arc::new(Mutex::new(vec![]));
+        let notify_later_pending_payable_params_arc = Arc::new(Mutex::new(vec![]));
+        let system = System::new(test_name);
+        SystemKillerActor::new(Duration::from_secs(10)).start(); // a safety net for GitHub Actions
+        let consuming_wallet = make_paying_wallet(b"consuming");
+        let pending_payable_scanner = ScannerMock::new()
+            .begin_scan_params(&begin_scan_params_arc)
+            .begin_scan_result(Err(BeginScanError::NothingToProcess))
+            .begin_scan_result(Ok(RequestTransactionReceipts {
+                pending_payable: vec![],
+                replaced_transactions: vec![],
+                response_skeleton_opt: None,
+            }))
+            .stop_the_system_after_last_msg();
+        let mut config = make_bc_with_defaults();
+        config.scan_intervals_opt = Some(ScanIntervals {
+            payable_scan_interval: Duration::from_secs(100),
+            receivable_scan_interval: Duration::from_secs(100),
+            pending_payable_scan_interval: Duration::from_millis(98),
+        });
+        let mut subject = AccountantBuilder::default()
+            .consuming_wallet(consuming_wallet.clone())
             .bootstrapper_config(config)
-            .payable_daos(vec![ForAccountantBody(payable_dao_mock)])
-            .receivable_daos(vec![ForAccountantBody(receivable_dao_mock)])
+            .logger(Logger::new(test_name))
             .build();
-        let system = System::new("report_routing_service_message_is_received");
+        subject.scanners.payable = Box::new(NullScanner::new()); //skipping
+        subject.scanners.pending_payable = Box::new(pending_payable_scanner);
+        subject.scanners.receivable = Box::new(NullScanner::new()); //skipping
+        subject.scan_schedulers.update_scheduler(
+            ScanType::PendingPayables,
+            Some(Box::new(
+                NotifyLaterHandleMock::default()
+                    .notify_later_params(&notify_later_pending_payable_params_arc)
+                    .capture_msg_and_let_it_fly_on(),
+            )),
+            None,
+        );
         let subject_addr: Addr<Accountant> = subject.start();
-        subject_addr
-            .try_send(BindMessage {
-                peer_actors: peer_actors_builder().build(),
-            })
-            .unwrap();
+        let subject_subs = Accountant::make_subs_from(&subject_addr);
+        let peer_actors = peer_actors_builder().build();
+        send_bind_message!(subject_subs, peer_actors);
 
-        subject_addr
-            .try_send(ReportRoutingServiceProvidedMessage {
-                timestamp: SystemTime::now(),
-                paying_wallet: earning_wallet.clone(),
-                payload_size: 1234,
-                service_rate: 42,
-                byte_rate: 24,
-            })
-            .unwrap();
+        send_start_message!(subject_subs);
 
-        System::current().stop_with_code(0);
+        let time_before = SystemTime::now();
         system.run();
-        assert!(more_money_receivable_parameters_arc
-            .lock()
-            .unwrap()
-            .is_empty());
-
+        let time_after = SystemTime::now();
+        let notify_later_pending_payable_params =
+            notify_later_pending_payable_params_arc.lock().unwrap();
         TestLogHandler::new().exists_log_containing(&format!(
-            "WARN: Accountant: Declining to record a receivable against our wallet {} for service we provided",
-            earning_wallet,
+            "DEBUG: {test_name}: There was nothing to process during PendingPayables scan."
         ));
+        let mut begin_scan_params = begin_scan_params_arc.lock().unwrap();
+        let (
+            first_attempt_wallet,
+            first_attempt_timestamp,
+            first_attempt_response_skeleton_opt,
+            first_attempt_logger,
+        ) = begin_scan_params.remove(0);
+        let (
+            second_attempt_wallet,
+            second_attempt_timestamp,
+            second_attempt_response_skeleton_opt,
+            second_attempt_logger,
+        ) = begin_scan_params.remove(0);
+        assert_eq!(first_attempt_wallet, second_attempt_wallet);
+        assert_eq!(second_attempt_wallet, consuming_wallet);
+        assert!(time_before <= first_attempt_timestamp);
+        assert!(first_attempt_timestamp <= second_attempt_timestamp);
+        assert!(second_attempt_timestamp <= time_after);
+        assert_eq!(first_attempt_response_skeleton_opt, None);
+        assert_eq!(second_attempt_response_skeleton_opt, None);
+        debug!(first_attempt_logger, "first attempt");
+        debug!(second_attempt_logger, "second attempt");
+        let tlh = TestLogHandler::new();
+        tlh.exists_log_containing(&format!("DEBUG: {test_name}: first attempt"));
+        tlh.exists_log_containing(&format!("DEBUG: {test_name}: second attempt"));
+        assert_eq!(
+            *notify_later_pending_payable_params,
+            vec![
+                (
+                    ScanForPendingPayables {
+                        response_skeleton_opt: None
+                    },
+                    Duration::from_millis(98)
+                ),
+                (
+                    ScanForPendingPayables {
+                        response_skeleton_opt: None
+                    },
+                    Duration::from_millis(98)
+                ),
+            ]
+        )
     }
 
     #[test]
-    fn report_exit_service_provided_message_is_received() {
+    fn periodical_scanning_for_payable_works() {
         init_test_logging();
-        let now = SystemTime::now();
-        let config = bc_from_earning_wallet(make_wallet("hi"));
-        let more_money_receivable_parameters_arc = Arc::new(Mutex::new(vec![]));
-        let payable_dao_mock = PayableDaoMock::new().non_pending_payables_result(vec![]);
-        let receivable_dao_mock = ReceivableDaoMock::new()
-            .more_money_receivable_parameters(&more_money_receivable_parameters_arc)
-            .more_money_receivable_result(Ok(()));
-        let subject = AccountantBuilder::default()
-            .bootstrapper_config(config)
-            .payable_daos(vec![ForAccountantBody(payable_dao_mock)])
-            .receivable_daos(vec![ForAccountantBody(receivable_dao_mock)])
-            .build();
-        let system = System::new("report_exit_service_provided_message_is_received");
-        let subject_addr: Addr<Accountant> = subject.start();
-        subject_addr
-            .try_send(BindMessage {
-                peer_actors: peer_actors_builder().build(),
-            })
-            .unwrap();
+        let test_name = "periodical_scanning_for_payable_works";
+        let begin_scan_params_arc = Arc::new(Mutex::new(vec![]));
+        let notify_later_payables_params_arc = Arc::new(Mutex::new(vec![]));
+        let system = System::new(test_name);
+        SystemKillerActor::new(Duration::from_secs(10)).start(); // a safety net for GitHub Actions
+        let consuming_wallet = make_paying_wallet(b"consuming");
+        let payable_scanner = ScannerMock::new()
+            .begin_scan_params(&begin_scan_params_arc)
+            .begin_scan_result(Err(BeginScanError::NothingToProcess))
+            .begin_scan_result(Ok(QualifiedPayablesMessage {
+                protected_qualified_payables: protect_payables_in_test(vec![make_payable_account(
+                    123,
+                )]),
+                consuming_wallet: consuming_wallet.clone(),
+                response_skeleton_opt: None,
+            }))
+            .stop_the_system_after_last_msg();
+        let mut config = bc_from_earning_wallet(make_wallet("hi"));
+        config.scan_intervals_opt = Some(ScanIntervals {
+            payable_scan_interval: Duration::from_millis(97),
+            receivable_scan_interval: Duration::from_secs(100), // We'll never run this scanner
+            pending_payable_scan_interval: Duration::from_secs(100), // We'll never run this scanner
+        });
+        let mut subject = AccountantBuilder::default()
+            .bootstrapper_config(config)
+            .consuming_wallet(consuming_wallet.clone())
+            .logger(Logger::new(test_name))
+            .build();
+        subject.scanners.payable = Box::new(payable_scanner);
+        subject.scanners.pending_payable = Box::new(NullScanner::new()); //skipping
+        subject.scanners.receivable = Box::new(NullScanner::new()); //skipping
+        subject.scan_schedulers.update_scheduler(
+            ScanType::Payables,
+            Some(Box::new(
+                NotifyLaterHandleMock::default()
+                    .notify_later_params(&notify_later_payables_params_arc)
+                    .capture_msg_and_let_it_fly_on(),
+            )),
+            None,
+        );
+        let subject_addr = subject.start();
+        let subject_subs = Accountant::make_subs_from(&subject_addr);
+        let peer_actors = peer_actors_builder().build();
+        send_bind_message!(subject_subs, peer_actors);
 
-        let paying_wallet = make_wallet("booga");
-        subject_addr
-            .try_send(ReportExitServiceProvidedMessage {
-                timestamp: now,
-                paying_wallet: paying_wallet.clone(),
-                payload_size: 1234,
-                service_rate: 42,
-                byte_rate: 24,
-            })
-            .unwrap();
+        send_start_message!(subject_subs);
 
-        System::current().stop();
+        let time_before = SystemTime::now();
         system.run();
-        let more_money_receivable_parameters = more_money_receivable_parameters_arc.lock().unwrap();
-        assert_eq!(
-            more_money_receivable_parameters[0],
-            (now, make_wallet("booga"), (1 * 42) + (1234 * 24))
-        );
+        let time_after = SystemTime::now();
+        //the second attempt is the one where the queue is empty and System::current.stop() ends the cycle
+        let notify_later_payables_params = notify_later_payables_params_arc.lock().unwrap();
         TestLogHandler::new().exists_log_containing(&format!(
-            "DEBUG: Accountant: Charging exit service for 1234 bytes to wallet {}",
-            paying_wallet
+            "DEBUG: {test_name}: There was nothing to process during Payables scan."
         ));
+        let mut begin_scan_params = begin_scan_params_arc.lock().unwrap();
+        let (
+            first_attempt_wallet,
+            first_attempt_timestamp,
+            first_attempt_response_skeleton_opt,
+            first_attempt_logger,
+        ) = begin_scan_params.remove(0);
+        let (
+            second_attempt_wallet,
+            second_attempt_timestamp,
+            second_attempt_response_skeleton_opt,
+            second_attempt_logger,
+        ) = begin_scan_params.remove(0);
+        assert_eq!(first_attempt_wallet, second_attempt_wallet);
+        assert_eq!(second_attempt_wallet, consuming_wallet);
+        assert!(time_before <= first_attempt_timestamp);
+        assert!(first_attempt_timestamp <= second_attempt_timestamp);
+        assert!(second_attempt_timestamp <= time_after);
+        assert_eq!(first_attempt_response_skeleton_opt, None);
+        assert_eq!(second_attempt_response_skeleton_opt, None);
+        debug!(first_attempt_logger, "first attempt");
+        debug!(second_attempt_logger, "second attempt");
+        let tlh = TestLogHandler::new();
+        tlh.exists_log_containing(&format!("DEBUG: {test_name}: first attempt"));
+        tlh.exists_log_containing(&format!("DEBUG: {test_name}: second attempt"));
+        assert_eq!(
+            *notify_later_payables_params,
+            vec![
+                (
+                    ScanForPayables {
+                        response_skeleton_opt: None
+                    },
+                    Duration::from_millis(97)
+                ),
+                (
+                    ScanForPayables {
+                        response_skeleton_opt: None
+                    },
+                    Duration::from_millis(97)
+                ),
+            ]
+        )
     }
 
     #[test]
-    fn report_exit_service_provided_message_is_received_from_our_consuming_wallet() {
+    fn payable_scan_is_not_initiated_if_consuming_wallet_is_not_found() {
         init_test_logging();
-        let consuming_wallet = make_wallet("my consuming wallet");
-        let config = bc_from_wallets(consuming_wallet.clone(), make_wallet("my earning wallet"));
-        let more_money_receivable_parameters_arc = Arc::new(Mutex::new(vec![]));
-        let payable_dao_mock = PayableDaoMock::new().non_pending_payables_result(vec![]);
-        let receivable_dao_mock = ReceivableDaoMock::new()
-            .more_money_receivable_parameters(&more_money_receivable_parameters_arc);
-        let subject = AccountantBuilder::default()
-            .bootstrapper_config(config)
-            .payable_daos(vec![ForAccountantBody(payable_dao_mock)])
-            .receivable_daos(vec![ForAccountantBody(receivable_dao_mock)])
-            .build();
-        let system = System::new("report_exit_service_provided_message_is_received");
-        let subject_addr: Addr<Accountant> = subject.start();
-        subject_addr
-            .try_send(BindMessage {
-                peer_actors: peer_actors_builder().build(),
-            })
-            .unwrap();
-
-        subject_addr
-            .try_send(ReportExitServiceProvidedMessage {
-                timestamp: SystemTime::now(),
-                paying_wallet: consuming_wallet.clone(),
-                payload_size: 1234,
-                service_rate: 42,
-                byte_rate: 24,
-            })
-            .unwrap();
+        let test_name = "payable_scan_is_not_initiated_if_consuming_wallet_is_not_found";
+        let mut subject = AccountantBuilder::default().build();
+        subject.consuming_wallet_opt = None;
+        subject.logger = Logger::new(test_name);
 
-        System::current().stop();
-        system.run();
-        assert!(more_money_receivable_parameters_arc
-            .lock()
-            .unwrap()
-            .is_empty());
+        subject.handle_request_of_scan_for_payable(None);
 
+        let has_scan_started = subject.scanners.payable.scan_started_at().is_some();
+        assert_eq!(has_scan_started, false);
         TestLogHandler::new().exists_log_containing(&format!(
-            "WARN: Accountant: Declining to record a receivable against our wallet {} for service we provided",
-            consuming_wallet
+            "DEBUG: {test_name}: Cannot initiate Payables scan because no consuming wallet was found."
         ));
     }
 
     #[test]
-    fn report_exit_service_provided_message_is_received_from_our_earning_wallet() {
+    fn pending_payable_scan_is_not_initiated_if_consuming_wallet_is_not_found() {
         init_test_logging();
-        let earning_wallet = make_wallet("my earning wallet");
-        let config = bc_from_earning_wallet(earning_wallet.clone());
-        let more_money_receivable_parameters_arc = Arc::new(Mutex::new(vec![]));
-        let payable_dao_mock = PayableDaoMock::new().non_pending_payables_result(vec![]);
-        let receivable_dao_mock = ReceivableDaoMock::new()
-            .more_money_receivable_parameters(&more_money_receivable_parameters_arc);
-        let subject = AccountantBuilder::default()
-            .bootstrapper_config(config)
-            .payable_daos(vec![ForAccountantBody(payable_dao_mock)])
-            .receivable_daos(vec![ForAccountantBody(receivable_dao_mock)])
-            .build();
-        let system = System::new("report_exit_service_provided_message_is_received");
-        let subject_addr: Addr<Accountant> = subject.start();
-        subject_addr
-            .try_send(BindMessage {
-                peer_actors: peer_actors_builder().build(),
-            })
-            .unwrap();
-
-        subject_addr
-            .try_send(ReportExitServiceProvidedMessage {
-                timestamp: SystemTime::now(),
-                paying_wallet: earning_wallet.clone(),
-                payload_size: 1234,
-                service_rate: 42,
-                byte_rate: 24,
-            })
-            .unwrap();
+        let test_name = "pending_payable_scan_is_not_initiated_if_consuming_wallet_is_not_found";
+        let mut subject = AccountantBuilder::default().build();
+        subject.consuming_wallet_opt = None;
+        subject.logger = Logger::new(test_name);
 
-        System::current().stop();
-        system.run();
-        assert!(more_money_receivable_parameters_arc
-            .lock()
-            .unwrap()
-            .is_empty());
+        subject.handle_request_of_scan_for_pending_payable(None);
 
+        let has_scan_started = subject.scanners.pending_payable.scan_started_at().is_some();
+        assert_eq!(has_scan_started, false);
         TestLogHandler::new().exists_log_containing(&format!(
-            "WARN: Accountant: Declining to record a receivable against our wallet {} for service we provided",
-            earning_wallet,
+            "DEBUG: {test_name}: Cannot initiate PendingPayables scan because no consuming wallet was found."
         ));
     }
 
     #[test]
-    fn report_services_consumed_message_is_received() {
+    fn start_message_triggers_no_scans_in_suppress_mode() {
         init_test_logging();
-        let config = make_bc_with_defaults();
-        let more_money_payable_params_arc = Arc::new(Mutex::new(vec![]));
-        let payable_dao_mock = PayableDaoMock::new()
-            .more_money_payable_params(more_money_payable_params_arc.clone())
-            .more_money_payable_result(Ok(()))
-            .more_money_payable_result(Ok(()))
-            .more_money_payable_result(Ok(()));
-        let mut subject = AccountantBuilder::default()
+        let test_name = "start_message_triggers_no_scans_in_suppress_mode";
+        let system = System::new(test_name);
+        let mut config = bc_from_earning_wallet(make_wallet("hi"));
+        config.scan_intervals_opt = Some(ScanIntervals {
+            payable_scan_interval: Duration::from_millis(100),
+            receivable_scan_interval: Duration::from_millis(100),
+            pending_payable_scan_interval: Duration::from_millis(100),
+        });
+        config.suppress_initial_scans = true;
+        let peer_actors = peer_actors_builder().build();
+        let subject = AccountantBuilder::default()
             .bootstrapper_config(config)
-            .payable_daos(vec![ForAccountantBody(payable_dao_mock)])
+            .logger(Logger::new(test_name))
             .build();
-        subject.message_id_generator = Box::new(MessageIdGeneratorMock::default().id_result(123));
-        let system = System::new("report_services_consumed_message_is_received");
-        let subject_addr: Addr<Accountant> = subject.start();
-        subject_addr
-            .try_send(BindMessage {
-                peer_actors: peer_actors_builder().build(),
-            })
-            .unwrap();
-        let earning_wallet_exit = make_wallet("exit");
-        let earning_wallet_routing_1 = make_wallet("routing 1");
-        let earning_wallet_routing_2 = make_wallet("routing 2");
-        let timestamp = SystemTime::now();
+        let subject_addr = subject.start();
+        let subject_subs = Accountant::make_subs_from(&subject_addr);
+        send_bind_message!(subject_subs, peer_actors);
 
-        subject_addr
-            .try_send(ReportServicesConsumedMessage {
-                timestamp,
-                exit: ExitServiceConsumed {
-                    earning_wallet: earning_wallet_exit.clone(),
-                    payload_size: 1200,
-                    service_rate: 120,
-                    byte_rate: 30,
-                },
-                routing_payload_size: 3456,
-                routing: vec![
-                    RoutingServiceConsumed {
-                        earning_wallet: earning_wallet_routing_1.clone(),
-                        service_rate: 42,
-                        byte_rate: 24,
-                    },
-                    RoutingServiceConsumed {
-                        earning_wallet: earning_wallet_routing_2.clone(),
-                        service_rate: 52,
-                        byte_rate: 33,
-                    },
-                ],
-            })
-            .unwrap();
+        send_start_message!(subject_subs);
 
         System::current().stop();
-        system.run();
-        let more_money_payable_params = more_money_payable_params_arc.lock().unwrap();
-        assert_eq!(
-            more_money_payable_params
-                .iter()
-                .map(|(timestamp, wallet, amount)| (timestamp, wallet, amount))
-                .collect::<Vec<_>>(),
-            vec![
-                (&timestamp, &earning_wallet_exit, &((1 * 120) + (1200 * 30))),
-                (
-                    &timestamp,
-                    &earning_wallet_routing_1,
-                    &((1 * 42) + (3456 * 24))
-                ),
-                (
-                    &timestamp,
-                    &earning_wallet_routing_2,
-                    &((1 * 52) + (3456 * 33))
-                )
-            ]
+        assert_eq!(system.run(), 0);
+        // no panics because of recalcitrant DAOs; therefore DAOs were not called; therefore test passes
+        TestLogHandler::new().exists_log_containing(
+            &format!("{test_name}: Started with --scans off; declining to begin database and blockchain scans"),
         );
-        let test_log_handler = TestLogHandler::new();
-
-        test_log_handler.exists_log_containing(&format!(
-            "DEBUG: Accountant: MsgId 123: Accruing debt to {} for consuming 1200 exited bytes",
-            earning_wallet_exit
-        ));
-        test_log_handler.exists_log_containing(&format!(
-            "DEBUG: Accountant: MsgId 123: Accruing debt to {} for consuming 3456 routed bytes",
-            earning_wallet_routing_1
-        ));
-        test_log_handler.exists_log_containing(&format!(
-            "DEBUG: Accountant: MsgId 123: Accruing debt to {} for consuming 3456 routed bytes",
-            earning_wallet_routing_2
-        ));
     }
 
-    fn assert_that_we_do_not_charge_our_own_wallet_for_consumed_services(
-        config: BootstrapperConfig,
-        message: ReportServicesConsumedMessage,
-    ) -> Arc<Mutex<Vec<(SystemTime, Wallet, u128)>>> {
-        let more_money_payable_parameters_arc = Arc::new(Mutex::new(vec![]));
-        let payable_dao_mock = PayableDaoMock::new()
-            .non_pending_payables_result(vec![])
-            .more_money_payable_result(Ok(()))
-            .more_money_payable_params(more_money_payable_parameters_arc.clone());
-        let subject = AccountantBuilder::default()
+    #[test]
+    fn scan_for_payables_message_does_not_trigger_payment_for_balances_below_the_curve() {
+        init_test_logging();
+        let consuming_wallet = make_paying_wallet(b"consuming wallet");
+        let payment_thresholds = PaymentThresholds {
+            threshold_interval_sec: 2_592_000,
+            debt_threshold_gwei: 1_000_000_000,
+            payment_grace_period_sec: 86_400,
+            maturity_threshold_sec: 86_400,
+            permanent_debt_allowed_gwei: 10_000_000,
+            unban_below_gwei: 10_000_000,
+        };
+        let config = bc_from_earning_wallet(make_wallet("mine"));
+        let now = to_time_t(SystemTime::now());
+        let accounts = vec![
+            // below minimum balance, to the right of time intersection (inside buffer zone)
+            PayableAccount {
+                wallet: make_wallet("wallet0"),
+                balance_wei: gwei_to_wei(payment_thresholds.permanent_debt_allowed_gwei - 1),
+                last_paid_timestamp: from_time_t(
+                    now - checked_conversion::<u64, i64>(
+                        payment_thresholds.threshold_interval_sec + 10,
+                    ),
+                ),
+                pending_payable_opt: None,
+            },
+            // above balance intersection, to the left of minimum time (outside buffer zone)
+            PayableAccount {
+                wallet: make_wallet("wallet1"),
+                balance_wei: gwei_to_wei(payment_thresholds.debt_threshold_gwei + 1),
+                last_paid_timestamp: from_time_t(
+                    now - checked_conversion::<u64, i64>(
+                        payment_thresholds.maturity_threshold_sec - 10,
+                    ),
+                ),
+                pending_payable_opt: None,
+            },
+            // above minimum balance, to the right of minimum time (not in buffer zone, below the curve)
+            PayableAccount {
+                wallet: make_wallet("wallet2"),
+                balance_wei: gwei_to_wei(payment_thresholds.permanent_debt_allowed_gwei + 55),
+                last_paid_timestamp: from_time_t(
+                    now - checked_conversion::<u64, i64>(
+                        payment_thresholds.maturity_threshold_sec + 15,
+                    ),
+                ),
+                pending_payable_opt: None,
+            },
+        ];
+        let payable_dao = PayableDaoMock::new()
+            .non_pending_payables_result(accounts.clone())
+            .non_pending_payables_result(vec![]);
+        let (blockchain_bridge, _, blockchain_bridge_recordings_arc) = make_recorder();
+        let system = System::new(
+            "scan_for_payable_message_does_not_trigger_payment_for_balances_below_the_curve",
+        );
+        let blockchain_bridge_addr: Addr<Recorder> = blockchain_bridge.start();
+        let outbound_payments_instructions_sub =
+            blockchain_bridge_addr.recipient::<OutboundPaymentsInstructions>();
+        let mut subject = AccountantBuilder::default()
             .bootstrapper_config(config)
-            .payable_daos(vec![ForAccountantBody(payable_dao_mock)])
+            .payable_daos(vec![ForPayableScanner(payable_dao)])
             .build();
-        let system = System::new("test");
-        let subject_addr: Addr<Accountant> = subject.start();
-        subject_addr
-            .try_send(BindMessage {
-                peer_actors: peer_actors_builder().build(),
-            })
-            .unwrap();
+        subject.outbound_payments_instructions_sub_opt = Some(outbound_payments_instructions_sub);
 
-        subject_addr.try_send(message).unwrap();
+        let _result = subject.scanners.payable.begin_scan(
+            consuming_wallet,
+            SystemTime::now(),
+            None,
+            &subject.logger,
+        );
 
         System::current().stop();
         system.run();
-        more_money_payable_parameters_arc
+        let blockchain_bridge_recordings = blockchain_bridge_recordings_arc.lock().unwrap();
+        assert_eq!(blockchain_bridge_recordings.len(), 0);
     }
 
     #[test]
-    fn routing_service_consumed_is_reported_for_our_consuming_wallet() {
+    fn scan_for_payable_message_triggers_payment_for_balances_over_the_curve() {
         init_test_logging();
-        let consuming_wallet = make_wallet("the consuming wallet");
-        let config = bc_from_wallets(consuming_wallet.clone(), make_wallet("the earning wallet"));
-        let foreign_wallet = make_wallet("exit wallet");
-        let timestamp = SystemTime::now();
-        let report_message = ReportServicesConsumedMessage {
-            timestamp,
-            exit: ExitServiceConsumed {
-                earning_wallet: foreign_wallet.clone(),
-                payload_size: 1234,
-                service_rate: 45,
-                byte_rate: 10,
+        let mut config = bc_from_earning_wallet(make_wallet("mine"));
+        let consuming_wallet = make_paying_wallet(b"consuming");
+        config.scan_intervals_opt = Some(ScanIntervals {
+            pending_payable_scan_interval: Duration::from_secs(50_000),
+            payable_scan_interval: Duration::from_secs(50_000),
+            receivable_scan_interval: Duration::from_secs(50_000),
+        });
+        let now = to_time_t(SystemTime::now());
+        let qualified_payables = vec![
+            // slightly above minimum balance, to the right of the curve (time intersection)
+            PayableAccount {
+                wallet: make_wallet("wallet0"),
+                balance_wei: gwei_to_wei(
+                    DEFAULT_PAYMENT_THRESHOLDS.permanent_debt_allowed_gwei + 1,
+                ),
+                last_paid_timestamp: from_time_t(
+                    now - checked_conversion::<u64, i64>(
+                        DEFAULT_PAYMENT_THRESHOLDS.threshold_interval_sec
+                            + DEFAULT_PAYMENT_THRESHOLDS.maturity_threshold_sec
+                            + 10,
+                    ),
+                ),
+                pending_payable_opt: None,
             },
-            routing_payload_size: 3333,
-            routing: vec![RoutingServiceConsumed {
-                earning_wallet: consuming_wallet.clone(),
-                service_rate: 42,
-                byte_rate: 6,
-            }],
-        };
+            // slightly above the curve (balance intersection), to the right of minimum time
+            PayableAccount {
+                wallet: make_wallet("wallet1"),
+                balance_wei: gwei_to_wei(DEFAULT_PAYMENT_THRESHOLDS.debt_threshold_gwei + 1),
+                last_paid_timestamp: from_time_t(
+                    now - checked_conversion::<u64, i64>(
+                        DEFAULT_PAYMENT_THRESHOLDS.maturity_threshold_sec + 10,
+                    ),
+                ),
+                pending_payable_opt: None,
+            },
+        ];
+        let payable_dao =
+            PayableDaoMock::default().non_pending_payables_result(qualified_payables.clone());
+        let (blockchain_bridge, _, blockchain_bridge_recordings_arc) = make_recorder();
+        let blockchain_bridge = blockchain_bridge
+            .system_stop_conditions(match_every_type_id!(QualifiedPayablesMessage));
+        let system =
+            System::new("scan_for_payable_message_triggers_payment_for_balances_over_the_curve");
+        let peer_actors = peer_actors_builder()
+            .blockchain_bridge(blockchain_bridge)
+            .build();
+        let mut subject = AccountantBuilder::default()
+            .bootstrapper_config(config)
+            .consuming_wallet(consuming_wallet.clone())
+            .payable_daos(vec![ForPayableScanner(payable_dao)])
+            .build();
+        subject.scanners.pending_payable = Box::new(NullScanner::new());
+        subject.scanners.receivable = Box::new(NullScanner::new());
+        let subject_addr = subject.start();
+        let accountant_subs = Accountant::make_subs_from(&subject_addr);
+        send_bind_message!(accountant_subs, peer_actors);
 
-        let more_money_payable_params_arc =
-            assert_that_we_do_not_charge_our_own_wallet_for_consumed_services(
-                config,
-                report_message,
-            );
+        send_start_message!(accountant_subs);
 
-        let more_money_payable_params = more_money_payable_params_arc.lock().unwrap();
+        system.run();
+        let blockchain_bridge_recordings = blockchain_bridge_recordings_arc.lock().unwrap();
+        let message = blockchain_bridge_recordings.get_record::<QualifiedPayablesMessage>(0);
         assert_eq!(
-            *more_money_payable_params,
-            //except processing the exit service there was no change in payables
-            vec![(timestamp, foreign_wallet, (45 + 10 * 1234))]
+            message,
+            &QualifiedPayablesMessage {
+                protected_qualified_payables: protect_payables_in_test(qualified_payables),
+                consuming_wallet,
+                response_skeleton_opt: None,
+            }
         );
-        TestLogHandler::new().exists_log_containing(&format!(
-            "WARN: Accountant: Declining to record a payable against our wallet {} for service we provided",
-            consuming_wallet
-        ));
     }
 
     #[test]
-    fn routing_service_consumed_is_reported_for_our_earning_wallet() {
+    fn accountant_does_not_initiate_another_scan_if_one_is_already_running() {
         init_test_logging();
-        let earning_wallet =
-            make_wallet("routing_service_consumed_is_reported_for_our_earning_wallet");
-        let foreign_wallet = make_wallet("exit wallet");
-        let config = bc_from_earning_wallet(earning_wallet.clone());
-        let timestamp = SystemTime::now();
-        let report_message = ReportServicesConsumedMessage {
-            timestamp,
-            exit: ExitServiceConsumed {
-                earning_wallet: foreign_wallet.clone(),
-                payload_size: 1234,
-                service_rate: 45,
-                byte_rate: 10,
-            },
-            routing_payload_size: 3333,
-            routing: vec![RoutingServiceConsumed {
-                earning_wallet: earning_wallet.clone(),
-                service_rate: 42,
-                byte_rate: 6,
-            }],
+        let test_name = "accountant_does_not_initiate_another_scan_if_one_is_already_running";
+        let payable_dao = PayableDaoMock::default();
+        let (blockchain_bridge, _, blockchain_bridge_recording) = make_recorder();
+        let blockchain_bridge_addr = blockchain_bridge
+            .system_stop_conditions(match_every_type_id!(
+                QualifiedPayablesMessage,
+                QualifiedPayablesMessage
+            ))
+            .start();
+        let pps_for_blockchain_bridge_sub = blockchain_bridge_addr.clone().recipient();
+        let last_paid_timestamp = to_time_t(SystemTime::now())
+            - DEFAULT_PAYMENT_THRESHOLDS.maturity_threshold_sec as i64
+            - 1;
+        let payable_account = PayableAccount {
+            wallet: make_wallet("scan_for_payables"),
+            balance_wei: gwei_to_wei(DEFAULT_PAYMENT_THRESHOLDS.debt_threshold_gwei + 1),
+            last_paid_timestamp: from_time_t(last_paid_timestamp),
+            pending_payable_opt: None,
+        };
+        let payable_dao = payable_dao
+            .non_pending_payables_result(vec![payable_account.clone()])
+            .non_pending_payables_result(vec![payable_account]);
+        let config = bc_from_earning_wallet(make_wallet("mine"));
+        let system = System::new(test_name);
+        let mut subject = AccountantBuilder::default()
+            .consuming_wallet(make_paying_wallet(b"consuming"))
+            .logger(Logger::new(test_name))
+            .payable_daos(vec![ForPayableScanner(payable_dao)])
+            .bootstrapper_config(config)
+            .build();
+        let message_before = ScanForPayables {
+            response_skeleton_opt: Some(ResponseSkeleton {
+                client_id: 111,
+                context_id: 222,
+            }),
+        };
+        let message_after = ScanForPayables {
+            response_skeleton_opt: Some(ResponseSkeleton {
+                client_id: 333,
+                context_id: 444,
+            }),
         };
+        subject.qualified_payables_sub_opt = Some(pps_for_blockchain_bridge_sub);
+        let addr = subject.start();
+        addr.try_send(message_before.clone()).unwrap();
 
-        let more_money_payable_params_arc =
-            assert_that_we_do_not_charge_our_own_wallet_for_consumed_services(
-                config,
-                report_message,
-            );
+        addr.try_send(ScanForPayables {
+            response_skeleton_opt: None,
+        })
+        .unwrap();
 
-        let more_money_payable_params = more_money_payable_params_arc.lock().unwrap();
+        // We ignored the second ScanForPayables message because the first message meant a scan
+        // was already in progress; now let's make it look like that scan has ended so that we
+        // can prove the next message will start another one.
+        addr.try_send(AssertionsMessage {
+            assertions: Box::new(|accountant: &mut Accountant| {
+                accountant
+                    .scanners
+                    .payable
+                    .mark_as_ended(&Logger::new("irrelevant"))
+            }),
+        })
+        .unwrap();
+        addr.try_send(message_after.clone()).unwrap();
+        system.run();
+        let recording = blockchain_bridge_recording.lock().unwrap();
+        let messages_received = recording.len();
+        assert_eq!(messages_received, 2);
+        let first_message: &QualifiedPayablesMessage = recording.get_record(0);
         assert_eq!(
-            *more_money_payable_params,
-            //except processing the exit service there was no change in payables
-            vec![(timestamp, foreign_wallet, (45 + 10 * 1234))]
+            first_message.response_skeleton_opt,
+            message_before.response_skeleton_opt
+        );
+        let second_message: &QualifiedPayablesMessage = recording.get_record(1);
+        assert_eq!(
+            second_message.response_skeleton_opt,
+            message_after.response_skeleton_opt
         );
         TestLogHandler::new().exists_log_containing(&format!(
-            "WARN: Accountant: Declining to record a payable against our wallet {} for service we provided",
-            earning_wallet
+            "DEBUG: {}: Payables scan was already initiated",
+            test_name
         ));
     }
 
     #[test]
-    fn exit_service_consumed_is_reported_for_our_consuming_wallet() {
+    fn scan_for_pending_payables_finds_still_pending_payables() {
         init_test_logging();
-        let consuming_wallet =
-            make_wallet("exit_service_consumed_is_reported_for_our_consuming_wallet");
-        let config = bc_from_wallets(consuming_wallet.clone(), make_wallet("own earning wallet"));
-        let report_message = ReportServicesConsumedMessage {
-            timestamp: SystemTime::now(),
-            exit: ExitServiceConsumed {
-                earning_wallet: consuming_wallet.clone(),
-                payload_size: 1234,
-                service_rate: 42,
-                byte_rate: 24,
-            },
-            routing_payload_size: 3333,
-            routing: vec![],
+        let (blockchain_bridge, _, blockchain_bridge_recording_arc) = make_recorder();
+        let blockchain_bridge_addr = blockchain_bridge
+            .system_stop_conditions(match_every_type_id!(RequestTransactionReceipts))
+            .start();
+        let payable_fingerprint_1 = PendingPayableFingerprint {
+            rowid: 555,
+            timestamp: from_time_t(210_000_000),
+            hash: make_tx_hash(45678),
+            attempt: 1,
+            amount: 4444,
+            process_error: None,
+        };
+        let payable_fingerprint_2 = PendingPayableFingerprint {
+            rowid: 550,
+            timestamp: from_time_t(210_000_100),
+            hash: make_tx_hash(112233),
+            attempt: 2,
+            amount: 7999,
+            process_error: None,
         };
+        let pending_payable_dao = PendingPayableDaoMock::default()
+            .return_all_errorless_fingerprints_result(vec![
+                payable_fingerprint_1.clone(),
+                payable_fingerprint_2.clone(),
+            ])
+            .fee_bumps_result(vec![]);
+        let config = bc_from_earning_wallet(make_wallet("mine"));
+        let system = System::new("pending payable scan");
+        let mut subject = AccountantBuilder::default()
+            .consuming_wallet(make_paying_wallet(b"consuming"))
+            .pending_payable_daos(vec![ForPendingPayableScanner(pending_payable_dao)])
+            .bootstrapper_config(config)
+            .build();
 
-        let more_money_payable_params_arc =
-            assert_that_we_do_not_charge_our_own_wallet_for_consumed_services(
-                config,
-                report_message,
-            );
+        subject.request_transaction_receipts_subs_opt = Some(blockchain_bridge_addr.recipient());
+        let account_addr = subject.start();
 
-        assert!(more_money_payable_params_arc.lock().unwrap().is_empty());
-        TestLogHandler::new().exists_log_containing(&format!(
-            "WARN: Accountant: Declining to record a payable against our wallet {} for service we provided",
-            consuming_wallet
-        ));
+        let _ = account_addr
+            .try_send(ScanForPendingPayables {
+                response_skeleton_opt: None,
+            })
+            .unwrap();
+
+        system.run();
+        let blockchain_bridge_recording = blockchain_bridge_recording_arc.lock().unwrap();
+        assert_eq!(blockchain_bridge_recording.len(), 1);
+        let received_msg = blockchain_bridge_recording.get_record::<RequestTransactionReceipts>(0);
+        assert_eq!(
+            received_msg,
+            &RequestTransactionReceipts {
+                pending_payable: vec![payable_fingerprint_1, payable_fingerprint_2],
+                replaced_transactions: vec![],
+                response_skeleton_opt: None,
+            }
+        );
+        let log_handler = TestLogHandler::new();
+        log_handler.exists_log_containing("DEBUG: Accountant: Found 2 pending payables to process");
     }
 
     #[test]
-    fn exit_service_consumed_is_reported_for_our_earning_wallet() {
+    fn report_routing_service_provided_message_is_received() {
         init_test_logging();
-        let earning_wallet = make_wallet("own earning wallet");
-        let config = bc_from_earning_wallet(earning_wallet.clone());
-        let report_message = ReportServicesConsumedMessage {
-            timestamp: SystemTime::now(),
-            exit: ExitServiceConsumed {
-                earning_wallet: earning_wallet.clone(),
+        let now = SystemTime::now();
+        let bootstrapper_config = bc_from_earning_wallet(make_wallet("hi"));
+        let more_money_receivable_parameters_arc = Arc::new(Mutex::new(vec![]));
+        let payable_dao_mock = PayableDaoMock::new().non_pending_payables_result(vec![]);
+        let receivable_dao_mock = ReceivableDaoMock::new()
+            .more_money_receivable_parameters(&more_money_receivable_parameters_arc)
+            .more_money_receivable_result(Ok(()));
+        let record_history_params_arc = Arc::new(Mutex::new(vec![]));
+        let financial_history_dao = FinancialHistoryDaoMock::new()
+            .record_params(&record_history_params_arc)
+            .record_result(Ok(()));
+        let subject = AccountantBuilder::default()
+            .bootstrapper_config(bootstrapper_config)
+            .payable_daos(vec![ForAccountantBody(payable_dao_mock)])
+            .receivable_daos(vec![ForAccountantBody(receivable_dao_mock)])
+            .financial_history_dao(financial_history_dao)
+            .build();
+        let system = System::new("report_routing_service_message_is_received");
+        let subject_addr: Addr<Accountant> = subject.start();
+        subject_addr
+            .try_send(BindMessage {
+                peer_actors: peer_actors_builder().build(),
+            })
+            .unwrap();
+
+        let paying_wallet = make_wallet("booga");
+        subject_addr
+            .try_send(ReportRoutingServiceProvidedMessage {
+                timestamp: now,
+                paying_wallet: paying_wallet.clone(),
                 payload_size: 1234,
                 service_rate: 42,
                 byte_rate: 24,
-            },
-            routing_payload_size: 3333,
-            routing: vec![],
-        };
-
-        let more_money_payable_params_arc =
-            assert_that_we_do_not_charge_our_own_wallet_for_consumed_services(
-                config,
-                report_message,
-            );
+            })
+            .unwrap();
 
-        assert!(more_money_payable_params_arc.lock().unwrap().is_empty());
+        System::current().stop_with_code(0);
+        system.run();
+        let more_money_receivable_parameters = more_money_receivable_parameters_arc.lock().unwrap();
+        assert_eq!(
+            more_money_receivable_parameters[0],
+            (now, make_wallet("booga"), (1 * 42) + (1234 * 24))
+        );
+        assert_eq!(
+            *record_history_params_arc.lock().unwrap(),
+            vec![(
+                now,
+                HistoryDirection::Earned,
+                HistoryService::Routing,
+                make_wallet("booga"),
+                (1 * 42) + (1234 * 24)
+            )]
+        );
         TestLogHandler::new().exists_log_containing(&format!(
-            "WARN: Accountant: Declining to record a payable against our wallet {} for service we provided",
-            earning_wallet
+            "DEBUG: Accountant: Charging routing of 1234 bytes to wallet {}",
+            paying_wallet
         ));
     }
 
     #[test]
-    #[should_panic(
-        expected = "Recording services provided for 0x000000000000000000000000000000626f6f6761 \
-    but has hit fatal database error: RusqliteError(\"we cannot help ourselves; this is baaad\")"
-    )]
-    fn record_service_provided_panics_on_fatal_errors() {
+    fn report_routing_service_provided_message_is_received_from_our_consuming_wallet() {
         init_test_logging();
-        let wallet = make_wallet("booga");
-        let receivable_dao = ReceivableDaoMock::new().more_money_receivable_result(Err(
-            ReceivableDaoError::RusqliteError(
-                "we cannot help ourselves; this is baaad".to_string(),
-            ),
-        ));
+        let consuming_wallet = make_wallet("our consuming wallet");
+        let config = bc_from_wallets(consuming_wallet.clone(), make_wallet("our earning wallet"));
+        let more_money_receivable_parameters_arc = Arc::new(Mutex::new(vec![]));
+        let payable_dao_mock = PayableDaoMock::new().non_pending_payables_result(vec![]);
+        let receivable_dao_mock = ReceivableDaoMock::new()
+            .more_money_receivable_parameters(&more_money_receivable_parameters_arc);
         let subject = AccountantBuilder::default()
-            .receivable_daos(vec![ForAccountantBody(receivable_dao)])
+            .bootstrapper_config(config)
+            .payable_daos(vec![ForAccountantBody(payable_dao_mock)])
+            .receivable_daos(vec![ForAccountantBody(receivable_dao_mock)])
             .build();
+        let system = System::new("report_routing_service_message_is_received");
+        let subject_addr: Addr<Accountant> = subject.start();
+        subject_addr
+            .try_send(BindMessage {
+                peer_actors: peer_actors_builder().build(),
+            })
+            .unwrap();
 
-        let _ = subject.record_service_provided(i64::MAX as u64, 1, SystemTime::now(), 2, &wallet);
-    }
+        subject_addr
+            .try_send(ReportRoutingServiceProvidedMessage {
+                timestamp: SystemTime::now(),
+                paying_wallet: consuming_wallet.clone(),
+                payload_size: 1234,
+                service_rate: 42,
+                byte_rate: 24,
+            })
+            .unwrap();
+
+        System::current().stop_with_code(0);
+        system.run();
+        assert!(more_money_receivable_parameters_arc
+            .lock()
+            .unwrap()
+            .is_empty());
+
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: Accountant: Declining to record a receivable against our wallet {} for service we provided",
+            consuming_wallet,
+        ));
+    }
 
     #[test]
-    fn record_service_provided_handles_overflow() {
+    fn report_routing_service_provided_message_is_received_from_our_earning_wallet() {
         init_test_logging();
-        let wallet = make_wallet("booga");
-        let receivable_dao = ReceivableDaoMock::new()
-            .more_money_receivable_result(Err(ReceivableDaoError::SignConversion(1234)));
+        let earning_wallet = make_wallet("our earning wallet");
+        let config = bc_from_earning_wallet(earning_wallet.clone());
+        let more_money_receivable_parameters_arc = Arc::new(Mutex::new(vec![]));
+        let payable_dao_mock = PayableDaoMock::new().non_pending_payables_result(vec![]);
+        let receivable_dao_mock = ReceivableDaoMock::new()
+            .more_money_receivable_parameters(&more_money_receivable_parameters_arc);
         let subject = AccountantBuilder::default()
-            .receivable_daos(vec![ForAccountantBody(receivable_dao)])
+            .bootstrapper_config(config)
+            .payable_daos(vec![ForAccountantBody(payable_dao_mock)])
+            .receivable_daos(vec![ForAccountantBody(receivable_dao_mock)])
             .build();
+        let system = System::new("report_routing_service_message_is_received");
+        let subject_addr: Addr<Accountant> = subject.start();
+        subject_addr
+            .try_send(BindMessage {
+                peer_actors: peer_actors_builder().build(),
+            })
+            .unwrap();
+
+        subject_addr
+            .try_send(ReportRoutingServiceProvidedMessage {
+                timestamp: SystemTime::now(),
+                paying_wallet: earning_wallet.clone(),
+                payload_size: 1234,
+                service_rate: 42,
+                byte_rate: 24,
+            })
+            .unwrap();
 
-        subject.record_service_provided(i64::MAX as u64, 1, SystemTime::now(), 2, &wallet);
+        System::current().stop_with_code(0);
+        system.run();
+        assert!(more_money_receivable_parameters_arc
+            .lock()
+            .unwrap()
+            .is_empty());
 
         TestLogHandler::new().exists_log_containing(&format!(
-            "ERROR: Accountant: Overflow error recording service provided for {}: service rate {}, byte rate 1, payload size 2. Skipping",
-            wallet,
-            i64::MAX as u64
+            "WARN: Accountant: Declining to record a receivable against our wallet {} for service we provided",
+            earning_wallet,
         ));
     }
 
     #[test]
-    fn record_service_consumed_handles_overflow() {
+    fn report_exit_service_provided_message_is_received() {
         init_test_logging();
-        let wallet = make_wallet("booga");
-        let payable_dao = PayableDaoMock::new()
-            .more_money_payable_result(Err(PayableDaoError::SignConversion(1234)));
+        let now = SystemTime::now();
+        let config = bc_from_earning_wallet(make_wallet("hi"));
+        let more_money_receivable_parameters_arc = Arc::new(Mutex::new(vec![]));
+        let payable_dao_mock = PayableDaoMock::new().non_pending_payables_result(vec![]);
+        let receivable_dao_mock = ReceivableDaoMock::new()
+            .more_money_receivable_parameters(&more_money_receivable_parameters_arc)
+            .more_money_receivable_result(Ok(()));
+        let record_history_params_arc = Arc::new(Mutex::new(vec![]));
+        let financial_history_dao = FinancialHistoryDaoMock::new()
+            .record_params(&record_history_params_arc)
+            .record_result(Ok(()));
         let subject = AccountantBuilder::default()
-            .payable_daos(vec![ForAccountantBody(payable_dao)])
+            .bootstrapper_config(config)
+            .payable_daos(vec![ForAccountantBody(payable_dao_mock)])
+            .receivable_daos(vec![ForAccountantBody(receivable_dao_mock)])
+            .financial_history_dao(financial_history_dao)
             .build();
-        let service_rate = i64::MAX as u64;
+        let system = System::new("report_exit_service_provided_message_is_received");
+        let subject_addr: Addr<Accountant> = subject.start();
+        subject_addr
+            .try_send(BindMessage {
+                peer_actors: peer_actors_builder().build(),
+            })
+            .unwrap();
 
-        subject.record_service_consumed(service_rate, 1, SystemTime::now(), 2, &wallet);
+        let paying_wallet = make_wallet("booga");
+        subject_addr
+            .try_send(ReportExitServiceProvidedMessage {
+                timestamp: now,
+                paying_wallet: paying_wallet.clone(),
+                payload_size: 1234,
+                service_rate: 42,
+                byte_rate: 24,
+            })
+            .unwrap();
 
+        System::current().stop();
+        system.run();
+        let more_money_receivable_parameters = more_money_receivable_parameters_arc.lock().unwrap();
+        assert_eq!(
+            more_money_receivable_parameters[0],
+            (now, make_wallet("booga"), (1 * 42) + (1234 * 24))
+        );
+        assert_eq!(
+            *record_history_params_arc.lock().unwrap(),
+            vec![(
+                now,
+                HistoryDirection::Earned,
+                HistoryService::Exit,
+                make_wallet("booga"),
+                (1 * 42) + (1234 * 24)
+            )]
+        );
         TestLogHandler::new().exists_log_containing(&format!(
-            "ERROR: Accountant: Overflow error recording consumed services from {}: total charge {}, service rate {}, byte rate 1, payload size 2. Skipping",
-            wallet,
-            i64::MAX as u64 + 1 * 2,
-            i64::MAX as u64
+            "DEBUG: Accountant: Charging exit service for 1234 bytes to wallet {}",
+            paying_wallet
         ));
     }
 
     #[test]
-    #[should_panic(
-        expected = "Recording services consumed from 0x000000000000000000000000000000626f6f6761 but \
-     has hit fatal database error: RusqliteError(\"we cannot help ourselves; this is baaad\")"
-    )]
-    fn record_service_consumed_panics_on_fatal_errors() {
+    fn report_exit_service_provided_message_is_received_from_our_consuming_wallet() {
         init_test_logging();
-        let wallet = make_wallet("booga");
-        let payable_dao = PayableDaoMock::new().more_money_payable_result(Err(
-            PayableDaoError::RusqliteError("we cannot help ourselves; this is baaad".to_string()),
-        ));
+        let consuming_wallet = make_wallet("my consuming wallet");
+        let config = bc_from_wallets(consuming_wallet.clone(), make_wallet("my earning wallet"));
+        let more_money_receivable_parameters_arc = Arc::new(Mutex::new(vec![]));
+        let payable_dao_mock = PayableDaoMock::new().non_pending_payables_result(vec![]);
+        let receivable_dao_mock = ReceivableDaoMock::new()
+            .more_money_receivable_parameters(&more_money_receivable_parameters_arc);
         let subject = AccountantBuilder::default()
-            .payable_daos(vec![ForAccountantBody(payable_dao)])
+            .bootstrapper_config(config)
+            .payable_daos(vec![ForAccountantBody(payable_dao_mock)])
+            .receivable_daos(vec![ForAccountantBody(receivable_dao_mock)])
             .build();
+        let system = System::new("report_exit_service_provided_message_is_received");
+        let subject_addr: Addr<Accountant> = subject.start();
+        subject_addr
+            .try_send(BindMessage {
+                peer_actors: peer_actors_builder().build(),
+            })
+            .unwrap();
+
+        subject_addr
+            .try_send(ReportExitServiceProvidedMessage {
+                timestamp: SystemTime::now(),
+                paying_wallet: consuming_wallet.clone(),
+                payload_size: 1234,
+                service_rate: 42,
+                byte_rate: 24,
+            })
+            .unwrap();
+
+        System::current().stop();
+        system.run();
+        assert!(more_money_receivable_parameters_arc
+            .lock()
+            .unwrap()
+            .is_empty());
 
-        let _ = subject.record_service_consumed(i64::MAX as u64, 1, SystemTime::now(), 2, &wallet);
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: Accountant: Declining to record a receivable against our wallet {} for service we provided",
+            consuming_wallet
+        ));
     }
 
     #[test]
-    #[should_panic(
-        expected = "panic message (processed with: node_lib::sub_lib::utils::crash_request_analyzer)"
-    )]
-    fn accountant_can_be_crashed_properly_but_not_improperly() {
-        let mut config = make_bc_with_defaults();
-        config.crash_point = CrashPoint::Message;
-        let accountant = AccountantBuilder::default()
+    fn report_exit_service_provided_message_is_received_from_our_earning_wallet() {
+        init_test_logging();
+        let earning_wallet = make_wallet("my earning wallet");
+        let config = bc_from_earning_wallet(earning_wallet.clone());
+        let more_money_receivable_parameters_arc = Arc::new(Mutex::new(vec![]));
+        let payable_dao_mock = PayableDaoMock::new().non_pending_payables_result(vec![]);
+        let receivable_dao_mock = ReceivableDaoMock::new()
+            .more_money_receivable_parameters(&more_money_receivable_parameters_arc);
+        let subject = AccountantBuilder::default()
             .bootstrapper_config(config)
+            .payable_daos(vec![ForAccountantBody(payable_dao_mock)])
+            .receivable_daos(vec![ForAccountantBody(receivable_dao_mock)])
             .build();
+        let system = System::new("report_exit_service_provided_message_is_received");
+        let subject_addr: Addr<Accountant> = subject.start();
+        subject_addr
+            .try_send(BindMessage {
+                peer_actors: peer_actors_builder().build(),
+            })
+            .unwrap();
 
-        prove_that_crash_request_handler_is_hooked_up(accountant, CRASH_KEY);
+        subject_addr
+            .try_send(ReportExitServiceProvidedMessage {
+                timestamp: SystemTime::now(),
+                paying_wallet: earning_wallet.clone(),
+                payload_size: 1234,
+                service_rate: 42,
+                byte_rate: 24,
+            })
+            .unwrap();
+
+        System::current().stop();
+        system.run();
+        assert!(more_money_receivable_parameters_arc
+            .lock()
+            .unwrap()
+            .is_empty());
+
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: Accountant: Declining to record a receivable against our wallet {} for service we provided",
+            earning_wallet,
+        ));
     }
 
     #[test]
-    fn pending_transaction_is_registered_and_monitored_until_it_gets_confirmed_or_canceled() {
+    fn report_services_consumed_message_is_received() {
         init_test_logging();
-        let build_blockchain_agent_params = Arc::new(Mutex::new(vec![]));
-        let mark_pending_payable_params_arc = Arc::new(Mutex::new(vec![]));
-        let transactions_confirmed_params_arc = Arc::new(Mutex::new(vec![]));
-        let get_transaction_receipt_params_arc = Arc::new(Mutex::new(vec![]));
-        let return_all_errorless_fingerprints_params_arc = Arc::new(Mutex::new(vec![]));
-        let non_pending_payables_params_arc = Arc::new(Mutex::new(vec![]));
-        let update_fingerprint_params_arc = Arc::new(Mutex::new(vec![]));
-        let mark_failure_params_arc = Arc::new(Mutex::new(vec![]));
-        let delete_record_params_arc = Arc::new(Mutex::new(vec![]));
-        let notify_later_scan_for_pending_payable_params_arc = Arc::new(Mutex::new(vec![]));
-        let notify_later_scan_for_pending_payable_arc_cloned =
-            notify_later_scan_for_pending_payable_params_arc.clone(); // because it moves into a closure
-        let pending_tx_hash_1 = make_tx_hash(0x7b);
-        let pending_tx_hash_2 = make_tx_hash(0x237);
-        let rowid_for_account_1 = 3;
-        let rowid_for_account_2 = 5;
-        let now = SystemTime::now();
-        let past_payable_timestamp_1 = now.sub(Duration::from_secs(
-            (DEFAULT_PAYMENT_THRESHOLDS.maturity_threshold_sec + 555) as u64,
-        ));
-        let past_payable_timestamp_2 = now.sub(Duration::from_secs(
-            (DEFAULT_PAYMENT_THRESHOLDS.maturity_threshold_sec + 50) as u64,
-        ));
-        let this_payable_timestamp_1 = now;
-        let this_payable_timestamp_2 = now.add(Duration::from_millis(50));
-        let payable_account_balance_1 =
-            gwei_to_wei(DEFAULT_PAYMENT_THRESHOLDS.debt_threshold_gwei + 10);
-        let payable_account_balance_2 =
-            gwei_to_wei(DEFAULT_PAYMENT_THRESHOLDS.debt_threshold_gwei + 666);
-        let wallet_account_1 = make_wallet("creditor1");
-        let wallet_account_2 = make_wallet("creditor2");
-        let transaction_receipt_tx_2_first_round = TransactionReceipt::default();
-        let transaction_receipt_tx_1_second_round = TransactionReceipt::default();
-        let transaction_receipt_tx_2_second_round = TransactionReceipt::default();
-        let mut transaction_receipt_tx_1_third_round = TransactionReceipt::default();
-        transaction_receipt_tx_1_third_round.status = Some(U64::from(0)); // failure
-        let transaction_receipt_tx_2_third_round = TransactionReceipt::default();
-        let mut transaction_receipt_tx_2_fourth_round = TransactionReceipt::default();
-        transaction_receipt_tx_2_fourth_round.status = Some(U64::from(1)); // confirmed
-        let agent = BlockchainAgentMock::default();
-        let blockchain_interface = BlockchainInterfaceMock::default()
-            .build_blockchain_agent_params(&build_blockchain_agent_params)
-            .build_blockchain_agent_result(Ok(Box::new(agent)))
+        let config = make_bc_with_defaults();
+        let more_money_payable_params_arc = Arc::new(Mutex::new(vec![]));
+        let payable_dao_mock = PayableDaoMock::new()
+            .more_money_payable_params(more_money_payable_params_arc.clone())
+            .more_money_payable_result(Ok(()))
+            .more_money_payable_result(Ok(()))
+            .more_money_payable_result(Ok(()));
+        let record_history_params_arc = Arc::new(Mutex::new(vec![]));
+        let financial_history_dao = FinancialHistoryDaoMock::new()
+            .record_params(&record_history_params_arc)
+            .record_result(Ok(()))
+            .record_result(Ok(()))
+            .record_result(Ok(()));
+        let mut subject = AccountantBuilder::default()
+            .bootstrapper_config(config)
+            .payable_daos(vec![ForAccountantBody(payable_dao_mock)])
+            .financial_history_dao(financial_history_dao)
+            .build();
+        subject.message_id_generator = Box::new(MessageIdGeneratorMock::default().id_result(123));
+        let system = System::new("report_services_consumed_message_is_received");
+        let subject_addr: Addr<Accountant> = subject.start();
+        subject_addr
+            .try_send(BindMessage {
+                peer_actors: peer_actors_builder().build(),
+            })
+            .unwrap();
+        let earning_wallet_exit = make_wallet("exit");
+        let earning_wallet_routing_1 = make_wallet("routing 1");
+        let earning_wallet_routing_2 = make_wallet("routing 2");
+        let timestamp = SystemTime::now();
+
+        subject_addr
+            .try_send(ReportServicesConsumedMessage {
+                timestamp,
+                exit: ExitServiceConsumed {
+                    earning_wallet: earning_wallet_exit.clone(),
+                    payload_size: 1200,
+                    service_rate: 120,
+                    byte_rate: 30,
+                },
+                routing_payload_size: 3456,
+                routing: vec![
+                    RoutingServiceConsumed {
+                        earning_wallet: earning_wallet_routing_1.clone(),
+                        service_rate: 42,
+                        byte_rate: 24,
+                    },
+                    RoutingServiceConsumed {
+                        earning_wallet: earning_wallet_routing_2.clone(),
+                        service_rate: 52,
+                        byte_rate: 33,
+                    },
+                ],
+            })
+            .unwrap();
+
+        System::current().stop();
+        system.run();
+        let more_money_payable_params = more_money_payable_params_arc.lock().unwrap();
+        assert_eq!(
+            more_money_payable_params
+                .iter()
+                .map(|(timestamp, wallet, amount)| (timestamp, wallet, amount))
+                .collect::<Vec<_>>(),
+            vec![
+                (&timestamp, &earning_wallet_exit, &((1 * 120) + (1200 * 30))),
+                (
+                    &timestamp,
+                    &earning_wallet_routing_1,
+                    &((1 * 42) + (3456 * 24))
+                ),
+                (
+                    &timestamp,
+                    &earning_wallet_routing_2,
+                    &((1 * 52) + (3456 * 33))
+                )
+            ]
+        );
+        assert_eq!(
+            *record_history_params_arc.lock().unwrap(),
+            vec![
+                (
+                    timestamp,
+                    HistoryDirection::Spent,
+                    HistoryService::Exit,
+                    earning_wallet_exit.clone(),
+                    (1 * 120) + (1200 * 30)
+                ),
+                (
+                    timestamp,
+                    HistoryDirection::Spent,
+                    HistoryService::Routing,
+                    earning_wallet_routing_1.clone(),
+                    (1 * 42) + (3456 * 24)
+                ),
+                (
+                    timestamp,
+                    HistoryDirection::Spent,
+                    HistoryService::Routing,
+                    earning_wallet_routing_2.clone(),
+                    (1 * 52) + (3456 * 33)
+                ),
+            ]
+        );
+        let test_log_handler = TestLogHandler::new();
+
+        test_log_handler.exists_log_containing(&format!(
+            "DEBUG: Accountant: MsgId 123: Accruing debt to {} for consuming 1200 exited bytes",
+            earning_wallet_exit
+        ));
+        test_log_handler.exists_log_containing(&format!(
+            "DEBUG: Accountant: MsgId 123: Accruing debt to {} for consuming 3456 routed bytes",
+            earning_wallet_routing_1
+        ));
+        test_log_handler.exists_log_containing(&format!(
+            "DEBUG: Accountant: MsgId 123: Accruing debt to {} for consuming 3456 routed bytes",
+            earning_wallet_routing_2
+        ));
+    }
+
+    #[test]
+    fn spending_budget_status_goes_to_proxy_server_when_consumed_services_exhaust_or_renew_it() {
+        let consuming_wallet = make_wallet("our consuming wallet");
+        let mut config = bc_from_wallets(consuming_wallet.clone(), make_wallet("earning"));
+        config.spending_budget = SpendingBudget {
+            daily_gwei: 2,
+            monthly_gwei: 0,
+            first_warning_percent: 80,
+            second_warning_percent: 95,
+        };
+        let payable_dao = PayableDaoMock::new()
+            .more_money_payable_result(Ok(()))
+            .more_money_payable_result(Ok(()))
+            .more_money_payable_result(Ok(()));
+        let financial_history_dao = FinancialHistoryDaoMock::new()
+            .record_result(Ok(()))
+            .record_result(Ok(()))
+            .record_result(Ok(()));
+        let subject = AccountantBuilder::default()
+            .bootstrapper_config(config)
+            .payable_daos(vec![ForAccountantBody(payable_dao)])
+            .financial_history_dao(financial_history_dao)
+            .build();
+        let (proxy_server, _, proxy_server_recording_arc) = make_recorder();
+        let proxy_server = proxy_server.system_stop_conditions(match_every_type_id!(
+            SpendingBudgetStatusMsg,
+            SpendingBudgetStatusMsg
+        ));
+        let system = System::new(
+            "spending_budget_status_goes_to_proxy_server_when_consumed_services_exhaust_or_renew_it",
+        );
+        let subject_addr = subject.start();
+        let peer_actors = peer_actors_builder().proxy_server(proxy_server).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+        let timestamp = SystemTime::now();
+        let consumed = |exit_wallet: Wallet| ReportServicesConsumedMessage {
+            timestamp,
+            exit: ExitServiceConsumed {
+                earning_wallet: exit_wallet,
+                payload_size: 0,
+                service_rate: WEIS_IN_GWEI as u64,
+                byte_rate: 0,
+            },
+            routing_payload_size: 0,
+            routing: vec![RoutingServiceConsumed {
+                earning_wallet: consuming_wallet.clone(),
+                service_rate: WEIS_IN_GWEI as u64,
+                byte_rate: 0,
+            }],
+        };
+        subject_addr
+            .try_send(consumed(make_wallet("exit 1")))
+            .unwrap();
+        subject_addr
+            .try_send(consumed(make_wallet("exit 2")))
+            .unwrap();
+        subject_addr
+            .try_send(ConfigChangeMsg {
+                change: ConfigChange::UpdateSpendingBudget(SpendingBudget::default()),
+            })
+            .unwrap();
+
+        system.run();
+        let proxy_server_recording = proxy_server_recording_arc.lock().unwrap();
+        let exhausted_until = proxy_server_recording
+            .get_record::<SpendingBudgetStatusMsg>(0)
+            .exhausted_until_opt
+            .unwrap();
+        assert!(exhausted_until > timestamp);
+        assert!(exhausted_until <= timestamp + Duration::from_secs(86_400));
+        assert_eq!(
+            proxy_server_recording.get_record::<SpendingBudgetStatusMsg>(1),
+            &SpendingBudgetStatusMsg {
+                exhausted_until_opt: None
+            }
+        );
+        assert_eq!(proxy_server_recording.len(), 2);
+    }
+
+    fn assert_that_we_do_not_charge_our_own_wallet_for_consumed_services(
+        config: BootstrapperConfig,
+        message: ReportServicesConsumedMessage,
+    ) -> Arc<Mutex<Vec<(SystemTime, Wallet, u128)>>> {
+        let more_money_payable_parameters_arc = Arc::new(Mutex::new(vec![]));
+        let payable_dao_mock = PayableDaoMock::new()
+            .non_pending_payables_result(vec![])
+            .more_money_payable_result(Ok(()))
+            .more_money_payable_params(more_money_payable_parameters_arc.clone());
+        let subject = AccountantBuilder::default()
+            .bootstrapper_config(config)
+            .payable_daos(vec![ForAccountantBody(payable_dao_mock)])
+            .financial_history_dao(FinancialHistoryDaoMock::new().record_result(Ok(())))
+            .build();
+        let system = System::new("test");
+        let subject_addr: Addr<Accountant> = subject.start();
+        subject_addr
+            .try_send(BindMessage {
+                peer_actors: peer_actors_builder().build(),
+            })
+            .unwrap();
+
+        subject_addr.try_send(message).unwrap();
+
+        System::current().stop();
+        system.run();
+        more_money_payable_parameters_arc
+    }
+
+    #[test]
+    fn routing_service_consumed_is_reported_for_our_consuming_wallet() {
+        init_test_logging();
+        let consuming_wallet = make_wallet("the consuming wallet");
+        let config = bc_from_wallets(consuming_wallet.clone(), make_wallet("the earning wallet"));
+        let foreign_wallet = make_wallet("exit wallet");
+        let timestamp = SystemTime::now();
+        let report_message = ReportServicesConsumedMessage {
+            timestamp,
+            exit: ExitServiceConsumed {
+                earning_wallet: foreign_wallet.clone(),
+                payload_size: 1234,
+                service_rate: 45,
+                byte_rate: 10,
+            },
+            routing_payload_size: 3333,
+            routing: vec![RoutingServiceConsumed {
+                earning_wallet: consuming_wallet.clone(),
+                service_rate: 42,
+                byte_rate: 6,
+            }],
+        };
+
+        let more_money_payable_params_arc =
+            assert_that_we_do_not_charge_our_own_wallet_for_consumed_services(
+                config,
+                report_message,
+            );
+
+        let more_money_payable_params = more_money_payable_params_arc.lock().unwrap();
+        assert_eq!(
+            *more_money_payable_params,
+            //except processing the exit service there was no change in payables
+            vec![(timestamp, foreign_wallet, (45 + 10 * 1234))]
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: Accountant: Declining to record a payable against our wallet {} for service we provided",
+            consuming_wallet
+        ));
+    }
+
+    #[test]
+    fn routing_service_consumed_is_reported_for_our_earning_wallet() {
+        init_test_logging();
+        let earning_wallet =
+            make_wallet("routing_service_consumed_is_reported_for_our_earning_wallet");
+        let foreign_wallet = make_wallet("exit wallet");
+        let config = bc_from_earning_wallet(earning_wallet.clone());
+        let timestamp = SystemTime::now();
+        let report_message = ReportServicesConsumedMessage {
+            timestamp,
+            exit: ExitServiceConsumed {
+                earning_wallet: foreign_wallet.clone(),
+                payload_size: 1234,
+                service_rate: 45,
+                byte_rate: 10,
+            },
+            routing_payload_size: 3333,
+            routing: vec![RoutingServiceConsumed {
+                earning_wallet: earning_wallet.clone(),
+                service_rate: 42,
+                byte_rate: 6,
+            }],
+        };
+
+        let more_money_payable_params_arc =
+            assert_that_we_do_not_charge_our_own_wallet_for_consumed_services(
+                config,
+                report_message,
+            );
+
+        let more_money_payable_params = more_money_payable_params_arc.lock().unwrap();
+        assert_eq!(
+            *more_money_payable_params,
+            //except processing the exit service there was no change in payables
+            vec![(timestamp, foreign_wallet, (45 + 10 * 1234))]
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: Accountant: Declining to record a payable against our wallet {} for service we provided",
+            earning_wallet
+        ));
+    }
+
+    #[test]
+    fn exit_service_consumed_is_reported_for_our_consuming_wallet() {
+        init_test_logging();
+        let consuming_wallet =
+            make_wallet("exit_service_consumed_is_reported_for_our_consuming_wallet");
+        let config = bc_from_wallets(consuming_wallet.clone(), make_wallet("own earning wallet"));
+        let report_message = ReportServicesConsumedMessage {
+            timestamp: SystemTime::now(),
+            exit: ExitServiceConsumed {
+                earning_wallet: consuming_wallet.clone(),
+                payload_size: 1234,
+                service_rate: 42,
+                byte_rate: 24,
+            },
+            routing_payload_size: 3333,
+            routing: vec![],
+        };
+
+        let more_money_payable_params_arc =
+            assert_that_we_do_not_charge_our_own_wallet_for_consumed_services(
+                config,
+                report_message,
+            );
+
+        assert!(more_money_payable_params_arc.lock().unwrap().is_empty());
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: Accountant: Declining to record a payable against our wallet {} for service we provided",
+            consuming_wallet
+        ));
+    }
+
+    #[test]
+    fn exit_service_consumed_is_reported_for_our_earning_wallet() {
+        init_test_logging();
+        let earning_wallet = make_wallet("own earning wallet");
+        let config = bc_from_earning_wallet(earning_wallet.clone());
+        let report_message = ReportServicesConsumedMessage {
+            timestamp: SystemTime::now(),
+            exit: ExitServiceConsumed {
+                earning_wallet: earning_wallet.clone(),
+                payload_size: 1234,
+                service_rate: 42,
+                byte_rate: 24,
+            },
+            routing_payload_size: 3333,
+            routing: vec![],
+        };
+
+        let more_money_payable_params_arc =
+            assert_that_we_do_not_charge_our_own_wallet_for_consumed_services(
+                config,
+                report_message,
+            );
+
+        assert!(more_money_payable_params_arc.lock().unwrap().is_empty());
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: Accountant: Declining to record a payable against our wallet {} for service we provided",
+            earning_wallet
+        ));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Recording services provided for 0x000000000000000000000000000000626f6f6761 \
+    but has hit fatal database error: RusqliteError(\"we cannot help ourselves; this is baaad\")"
+    )]
+    fn record_service_provided_panics_on_fatal_errors() {
+        init_test_logging();
+        let wallet = make_wallet("booga");
+        let receivable_dao = ReceivableDaoMock::new().more_money_receivable_result(Err(
+            ReceivableDaoError::RusqliteError(
+                "we cannot help ourselves; this is baaad".to_string(),
+            ),
+        ));
+        let subject = AccountantBuilder::default()
+            .receivable_daos(vec![ForAccountantBody(receivable_dao)])
+            .build();
+
+        let _ = subject.record_service_provided(
+            i64::MAX as u64,
+            1,
+            SystemTime::now(),
+            2,
+            &wallet,
+            HistoryService::Exit,
+        );
+    }
+
+    #[test]
+    fn record_service_provided_handles_overflow() {
+        init_test_logging();
+        let wallet = make_wallet("booga");
+        let receivable_dao = ReceivableDaoMock::new()
+            .more_money_receivable_result(Err(ReceivableDaoError::SignConversion(1234)));
+        let subject = AccountantBuilder::default()
+            .receivable_daos(vec![ForAccountantBody(receivable_dao)])
+            .build();
+
+        subject.record_service_provided(
+            i64::MAX as u64,
+            1,
+            SystemTime::now(),
+            2,
+            &wallet,
+            HistoryService::Exit,
+        );
+
+        TestLogHandler::new().exists_log_containing(&format!(
+            "ERROR: Accountant: Overflow error recording service provided for {}: service rate {}, byte rate 1, payload size 2. Skipping",
+            wallet,
+            i64::MAX as u64
+        ));
+    }
+
+    #[test]
+    fn record_service_consumed_handles_overflow() {
+        init_test_logging();
+        let wallet = make_wallet("booga");
+        let payable_dao = PayableDaoMock::new()
+            .more_money_payable_result(Err(PayableDaoError::SignConversion(1234)));
+        let subject = AccountantBuilder::default()
+            .payable_daos(vec![ForAccountantBody(payable_dao)])
+            .build();
+        let service_rate = i64::MAX as u64;
+
+        subject.record_service_consumed(
+            service_rate,
+            1,
+            SystemTime::now(),
+            2,
+            &wallet,
+            HistoryService::Exit,
+        );
+
+        TestLogHandler::new().exists_log_containing(&format!(
+            "ERROR: Accountant: Overflow error recording consumed services from {}: total charge {}, service rate {}, byte rate 1, payload size 2. Skipping",
+            wallet,
+            i64::MAX as u64 + 1 * 2,
+            i64::MAX as u64
+        ));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Recording services consumed from 0x000000000000000000000000000000626f6f6761 but \
+     has hit fatal database error: RusqliteError(\"we cannot help ourselves; this is baaad\")"
+    )]
+    fn record_service_consumed_panics_on_fatal_errors() {
+        init_test_logging();
+        let wallet = make_wallet("booga");
+        let payable_dao = PayableDaoMock::new().more_money_payable_result(Err(
+            PayableDaoError::RusqliteError("we cannot help ourselves; this is baaad".to_string()),
+        ));
+        let subject = AccountantBuilder::default()
+            .payable_daos(vec![ForAccountantBody(payable_dao)])
+            .build();
+
+        let _ = subject.record_service_consumed(
+            i64::MAX as u64,
+            1,
+            SystemTime::now(),
+            2,
+            &wallet,
+            HistoryService::Exit,
+        );
+    }
+
+    #[test]
+    fn failure_to_record_financial_history_is_logged_and_the_charge_still_counts() {
+        init_test_logging();
+        let test_name = "failure_to_record_financial_history_is_logged_and_the_charge_still_counts";
+        let wallet = make_wallet("booga");
+        let payable_dao = PayableDaoMock::new().more_money_payable_result(Ok(()));
+        let financial_history_dao = FinancialHistoryDaoMock::new().record_result(Err(
+            FinancialHistoryDaoError::RecordingFailed("disk full".to_string()),
+        ));
+        let subject = AccountantBuilder::default()
+            .logger(Logger::new(test_name))
+            .payable_daos(vec![ForAccountantBody(payable_dao)])
+            .financial_history_dao(financial_history_dao)
+            .build();
+
+        let result = subject.record_service_consumed(
+            10,
+            2,
+            SystemTime::now(),
+            8,
+            &wallet,
+            HistoryService::Routing,
+        );
+
+        assert_eq!(result, 26);
+        TestLogHandler::new().exists_log_containing(&format!(
+            "ERROR: {}: Failed to record Spent Routing history of 26 wei for {}: \
+             RecordingFailed(\"disk full\")",
+            test_name, wallet
+        ));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "panic message (processed with: node_lib::sub_lib::utils::crash_request_analyzer)"
+    )]
+    fn accountant_can_be_crashed_properly_but_not_improperly() {
+        let mut config = make_bc_with_defaults();
+        config.crash_point = CrashPoint::Message;
+        let accountant = AccountantBuilder::default()
+            .bootstrapper_config(config)
+            .build();
+
+        prove_that_crash_request_handler_is_hooked_up(accountant, CRASH_KEY);
+    }
+
+    #[test]
+    fn pending_transaction_is_registered_and_monitored_until_it_gets_confirmed_or_canceled() {
+        init_test_logging();
+        let build_blockchain_agent_params = Arc::new(Mutex::new(vec![]));
+        let mark_pending_payable_params_arc = Arc::new(Mutex::new(vec![]));
+        let transactions_confirmed_params_arc = Arc::new(Mutex::new(vec![]));
+        let get_transaction_receipt_params_arc = Arc::new(Mutex::new(vec![]));
+        let return_all_errorless_fingerprints_params_arc = Arc::new(Mutex::new(vec![]));
+        let non_pending_payables_params_arc = Arc::new(Mutex::new(vec![]));
+        let update_fingerprint_params_arc = Arc::new(Mutex::new(vec![]));
+        let mark_failure_params_arc = Arc::new(Mutex::new(vec![]));
+        let delete_record_params_arc = Arc::new(Mutex::new(vec![]));
+        let notify_later_scan_for_pending_payable_params_arc = Arc::new(Mutex::new(vec![]));
+        let notify_later_scan_for_pending_payable_arc_cloned =
+            notify_later_scan_for_pending_payable_params_arc.clone(); // because it moves into a closure
+        let pending_tx_hash_1 = make_tx_hash(0x7b);
+        let pending_tx_hash_2 = make_tx_hash(0x237);
+        let rowid_for_account_1 = 3;
+        let rowid_for_account_2 = 5;
+        let now = SystemTime::now();
+        let past_payable_timestamp_1 = now.sub(Duration::from_secs(
+            (DEFAULT_PAYMENT_THRESHOLDS.maturity_threshold_sec + 555) as u64,
+        ));
+        let past_payable_timestamp_2 = now.sub(Duration::from_secs(
+            (DEFAULT_PAYMENT_THRESHOLDS.maturity_threshold_sec + 50) as u64,
+        ));
+        let this_payable_timestamp_1 = now;
+        let this_payable_timestamp_2 = now.add(Duration::from_millis(50));
+        let payable_account_balance_1 =
+            gwei_to_wei(DEFAULT_PAYMENT_THRESHOLDS.debt_threshold_gwei + 10);
+        let payable_account_balance_2 =
+            gwei_to_wei(DEFAULT_PAYMENT_THRESHOLDS.debt_threshold_gwei + 666);
+        let wallet_account_1 = make_wallet("creditor1");
+        let wallet_account_2 = make_wallet("creditor2");
+        let transaction_receipt_tx_2_first_round = TransactionReceipt::default();
+        let transaction_receipt_tx_1_second_round = TransactionReceipt::default();
+        let transaction_receipt_tx_2_second_round = TransactionReceipt::default();
+        let mut transaction_receipt_tx_1_third_round = TransactionReceipt::default();
+        transaction_receipt_tx_1_third_round.status = Some(U64::from(0)); // failure
+        let transaction_receipt_tx_2_third_round = TransactionReceipt::default();
+        let mut transaction_receipt_tx_2_fourth_round = TransactionReceipt::default();
+        transaction_receipt_tx_2_fourth_round.status = Some(U64::from(1)); // confirmed
+        let agent = BlockchainAgentMock::default();
+        let blockchain_interface = BlockchainInterfaceMock::default()
+            .build_blockchain_agent_params(&build_blockchain_agent_params)
+            .build_blockchain_agent_result(Ok(Box::new(agent)))
             // because we cannot have both, resolution on the high level and also of what's inside blockchain interface,
             // there is one component missing in this wholesome test - the part where we send a request for
             // a fingerprint of that payable in the DB - this happens inside send_raw_transaction()
@@ -3492,594 +5092,1446 @@ mod tests {
         let blockchain_bridge = BlockchainBridge::new(
             Box::new(blockchain_interface),
             Box::new(persistent_config),
+            Box::new(FundsTransferDaoMock::default()),
             false,
         );
-        let account_1 = PayableAccount {
-            wallet: wallet_account_1.clone(),
-            balance_wei: payable_account_balance_1,
-            last_paid_timestamp: past_payable_timestamp_1,
-            pending_payable_opt: None,
+        let account_1 = PayableAccount {
+            wallet: wallet_account_1.clone(),
+            balance_wei: payable_account_balance_1,
+            last_paid_timestamp: past_payable_timestamp_1,
+            pending_payable_opt: None,
+        };
+        let account_2 = PayableAccount {
+            wallet: wallet_account_2.clone(),
+            balance_wei: payable_account_balance_2,
+            last_paid_timestamp: past_payable_timestamp_2,
+            pending_payable_opt: None,
+        };
+        let pending_payable_scan_interval = 200; // should be slightly less than 1/5 of the time until shutting the system
+        let payable_dao_for_payable_scanner = PayableDaoMock::new()
+            .non_pending_payables_params(&non_pending_payables_params_arc)
+            .non_pending_payables_result(vec![account_1, account_2])
+            .mark_pending_payables_rowids_params(&mark_pending_payable_params_arc)
+            .mark_pending_payables_rowids_result(Ok(()));
+        let payable_dao_for_pending_payable_scanner = PayableDaoMock::new()
+            .transactions_confirmed_params(&transactions_confirmed_params_arc)
+            .transactions_confirmed_result(Ok(()));
+        let mut bootstrapper_config = bc_from_earning_wallet(make_wallet("some_wallet_address"));
+        bootstrapper_config.scan_intervals_opt = Some(ScanIntervals {
+            payable_scan_interval: Duration::from_secs(1_000_000), // we don't care about this scan
+            receivable_scan_interval: Duration::from_secs(1_000_000), // we don't care about this scan
+            pending_payable_scan_interval: Duration::from_millis(pending_payable_scan_interval),
+        });
+        let fingerprint_1_first_round = PendingPayableFingerprint {
+            rowid: rowid_for_account_1,
+            timestamp: this_payable_timestamp_1,
+            hash: pending_tx_hash_1,
+            attempt: 1,
+            amount: payable_account_balance_1,
+            process_error: None,
+        };
+        let fingerprint_2_first_round = PendingPayableFingerprint {
+            rowid: rowid_for_account_2,
+            timestamp: this_payable_timestamp_2,
+            hash: pending_tx_hash_2,
+            attempt: 1,
+            amount: payable_account_balance_2,
+            process_error: None,
+        };
+        let fingerprint_1_second_round = PendingPayableFingerprint {
+            attempt: 2,
+            ..fingerprint_1_first_round.clone()
+        };
+        let fingerprint_2_second_round = PendingPayableFingerprint {
+            attempt: 2,
+            ..fingerprint_2_first_round.clone()
+        };
+        let fingerprint_1_third_round = PendingPayableFingerprint {
+            attempt: 3,
+            ..fingerprint_1_first_round.clone()
+        };
+        let fingerprint_2_third_round = PendingPayableFingerprint {
+            attempt: 3,
+            ..fingerprint_2_first_round.clone()
+        };
+        let fingerprint_2_fourth_round = PendingPayableFingerprint {
+            attempt: 4,
+            ..fingerprint_2_first_round.clone()
+        };
+        let pending_payable_dao_for_payable_scanner = PendingPayableDaoMock::default()
+            .fingerprints_rowids_result(TransactionHashes {
+                rowid_results: vec![
+                    (rowid_for_account_1, pending_tx_hash_1),
+                    (rowid_for_account_2, pending_tx_hash_2),
+                ],
+                no_rowid_results: vec![],
+            });
+        let mut pending_payable_dao_for_pending_payable_scanner = PendingPayableDaoMock::new()
+            .return_all_errorless_fingerprints_params(&return_all_errorless_fingerprints_params_arc)
+            .return_all_errorless_fingerprints_result(vec![])
+            .return_all_errorless_fingerprints_result(vec![
+                fingerprint_1_first_round,
+                fingerprint_2_first_round,
+            ])
+            .return_all_errorless_fingerprints_result(vec![
+                fingerprint_1_second_round,
+                fingerprint_2_second_round,
+            ])
+            .return_all_errorless_fingerprints_result(vec![
+                fingerprint_1_third_round,
+                fingerprint_2_third_round,
+            ])
+            .return_all_errorless_fingerprints_result(vec![fingerprint_2_fourth_round.clone()])
+            .fee_bumps_result(vec![])
+            .fee_bumps_result(vec![])
+            .fee_bumps_result(vec![])
+            .fee_bumps_result(vec![])
+            .fingerprints_rowids_result(TransactionHashes {
+                rowid_results: vec![
+                    (rowid_for_account_1, pending_tx_hash_1),
+                    (rowid_for_account_2, pending_tx_hash_2),
+                ],
+                no_rowid_results: vec![],
+            })
+            .increment_scan_attempts_params(&update_fingerprint_params_arc)
+            .increment_scan_attempts_result(Ok(()))
+            .increment_scan_attempts_result(Ok(()))
+            .increment_scan_attempts_result(Ok(()))
+            .mark_failures_params(&mark_failure_params_arc)
+            // we don't have a better solution yet, so we mark this down
+            .mark_failures_result(Ok(()))
+            .delete_fingerprints_params(&delete_record_params_arc)
+            // this is used during confirmation of the successful one
+            .delete_fingerprints_result(Ok(()));
+        pending_payable_dao_for_pending_payable_scanner
+            .have_return_all_errorless_fingerprints_shut_down_the_system = true;
+        let consuming_wallet_for_accountant = consuming_wallet.clone();
+        let accountant_addr = Arbiter::builder()
+            .stop_system_on_panic(true)
+            .start(move |_| {
+                let mut subject = AccountantBuilder::default()
+                    .consuming_wallet(consuming_wallet_for_accountant)
+                    .bootstrapper_config(bootstrapper_config)
+                    .payable_daos(vec![
+                        ForPayableScanner(payable_dao_for_payable_scanner),
+                        ForPendingPayableScanner(payable_dao_for_pending_payable_scanner),
+                    ])
+                    .pending_payable_daos(vec![
+                        ForPayableScanner(pending_payable_dao_for_payable_scanner),
+                        ForPendingPayableScanner(pending_payable_dao_for_pending_payable_scanner),
+                    ])
+                    .build();
+                subject.scanners.receivable = Box::new(NullScanner::new());
+                let notify_later_half_mock = NotifyLaterHandleMock::default()
+                    .notify_later_params(&notify_later_scan_for_pending_payable_arc_cloned)
+                    .capture_msg_and_let_it_fly_on();
+                subject.scan_schedulers.update_scheduler(
+                    ScanType::PendingPayables,
+                    Some(Box::new(notify_later_half_mock)),
+                    None,
+                );
+                subject
+            });
+        let mut peer_actors = peer_actors_builder().build();
+        let accountant_subs = Accountant::make_subs_from(&accountant_addr);
+        peer_actors.accountant = accountant_subs.clone();
+        let blockchain_bridge_addr = blockchain_bridge.start();
+        let blockchain_bridge_subs = BlockchainBridge::make_subs_from(&blockchain_bridge_addr);
+        peer_actors.blockchain_bridge = blockchain_bridge_subs.clone();
+        send_bind_message!(accountant_subs, peer_actors);
+        send_bind_message!(blockchain_bridge_subs, peer_actors);
+
+        send_start_message!(accountant_subs);
+
+        assert_eq!(system.run(), 0);
+        let mut mark_pending_payable_params = mark_pending_payable_params_arc.lock().unwrap();
+        let mut one_set_of_mark_pending_payable_params = mark_pending_payable_params.remove(0);
+        assert!(mark_pending_payable_params.is_empty());
+        let first_payable = one_set_of_mark_pending_payable_params.remove(0);
+        assert_eq!(first_payable.0, wallet_account_1);
+        assert_eq!(first_payable.1, rowid_for_account_1);
+        let second_payable = one_set_of_mark_pending_payable_params.remove(0);
+        assert!(
+            one_set_of_mark_pending_payable_params.is_empty(),
+            "{:?}",
+            one_set_of_mark_pending_payable_params
+        );
+        assert_eq!(second_payable.0, wallet_account_2);
+        assert_eq!(second_payable.1, rowid_for_account_2);
+        let return_all_errorless_fingerprints_params =
+            return_all_errorless_fingerprints_params_arc.lock().unwrap();
+        // it varies with machines and sometimes we manage more cycles than necessary
+        assert!(return_all_errorless_fingerprints_params.len() >= 5);
+        let non_pending_payables_params = non_pending_payables_params_arc.lock().unwrap();
+        assert_eq!(*non_pending_payables_params, vec![()]); // because we disabled further scanning for payables
+        let get_transaction_receipt_params = get_transaction_receipt_params_arc.lock().unwrap();
+        assert_eq!(
+            *get_transaction_receipt_params,
+            vec![
+                pending_tx_hash_1,
+                pending_tx_hash_2,
+                pending_tx_hash_1,
+                pending_tx_hash_2,
+                pending_tx_hash_1,
+                pending_tx_hash_2,
+                pending_tx_hash_2,
+            ]
+        );
+        let build_blockchain_agent_params = build_blockchain_agent_params.lock().unwrap();
+        assert_eq!(
+            *build_blockchain_agent_params,
+            vec![(consuming_wallet, persistent_config_id_stamp)]
+        );
+        let update_fingerprints_params = update_fingerprint_params_arc.lock().unwrap();
+        assert_eq!(
+            *update_fingerprints_params,
+            vec![
+                vec![rowid_for_account_1, rowid_for_account_2],
+                vec![rowid_for_account_1, rowid_for_account_2],
+                vec![rowid_for_account_2]
+            ]
+        );
+        let mark_failure_params = mark_failure_params_arc.lock().unwrap();
+        assert_eq!(*mark_failure_params, vec![vec![rowid_for_account_1]]);
+        let delete_record_params = delete_record_params_arc.lock().unwrap();
+        assert_eq!(*delete_record_params, vec![vec![rowid_for_account_2]]);
+        let transaction_confirmed_params = transactions_confirmed_params_arc.lock().unwrap();
+        assert_eq!(
+            *transaction_confirmed_params,
+            vec![vec![fingerprint_2_fourth_round.clone()]]
+        );
+        let expected_scan_pending_payable_msg_and_interval = (
+            ScanForPendingPayables {
+                response_skeleton_opt: None,
+            },
+            Duration::from_millis(pending_payable_scan_interval),
+        );
+        let mut notify_later_check_for_confirmation =
+            notify_later_scan_for_pending_payable_params_arc
+                .lock()
+                .unwrap();
+        // it varies with machines and sometimes we manage more cycles than necessary
+        let vector_of_first_five_cycles = notify_later_check_for_confirmation
+            .drain(0..=4)
+            .collect_vec();
+        assert_eq!(
+            vector_of_first_five_cycles,
+            vec![
+                expected_scan_pending_payable_msg_and_interval.clone(),
+                expected_scan_pending_payable_msg_and_interval.clone(),
+                expected_scan_pending_payable_msg_and_interval.clone(),
+                expected_scan_pending_payable_msg_and_interval.clone(),
+                expected_scan_pending_payable_msg_and_interval,
+            ]
+        );
+        let log_handler = TestLogHandler::new();
+        log_handler.exists_log_containing(
+            "WARN: Accountant: Broken transactions 0x00000000000000000000000000000000000000000000000\
+            0000000000000007b marked as an error. You should take over the care of those to make sure \
+            your debts are going to be settled properly. At the moment, there is no automated process \
+            fixing that without your assistance");
+        log_handler.exists_log_matching("INFO: Accountant: Transaction 0x000000000000000000000000000\
+        0000000000000000000000000000000000237 has been added to the blockchain; detected locally at \
+        attempt 4 at \\d{2,}ms after its sending");
+        log_handler.exists_log_containing(
+            "INFO: Accountant: Transactions 0x000000000000000000000000\
+        0000000000000000000000000000000000000237 completed their confirmation process succeeding",
+        );
+    }
+
+    #[test]
+    fn accountant_receives_reported_transaction_receipts_and_processes_them_all() {
+        let transactions_confirmed_params_arc = Arc::new(Mutex::new(vec![]));
+        let payable_dao = PayableDaoMock::default()
+            .transactions_confirmed_params(&transactions_confirmed_params_arc)
+            .transactions_confirmed_result(Ok(()));
+        let pending_payable_dao =
+            PendingPayableDaoMock::default().delete_fingerprints_result(Ok(()));
+        let subject = AccountantBuilder::default()
+            .payable_daos(vec![ForPendingPayableScanner(payable_dao)])
+            .pending_payable_daos(vec![ForPendingPayableScanner(pending_payable_dao)])
+            .build();
+        let subject_addr = subject.start();
+        let transaction_hash_1 = make_tx_hash(4545);
+        let mut transaction_receipt_1 = TransactionReceipt::default();
+        transaction_receipt_1.transaction_hash = transaction_hash_1;
+        transaction_receipt_1.status = Some(U64::from(1)); //success
+        let fingerprint_1 = PendingPayableFingerprint {
+            rowid: 5,
+            timestamp: from_time_t(200_000_000),
+            hash: transaction_hash_1,
+            attempt: 2,
+            amount: 444,
+            process_error: None,
+        };
+        let transaction_hash_2 = make_tx_hash(3333333);
+        let mut transaction_receipt_2 = TransactionReceipt::default();
+        transaction_receipt_2.transaction_hash = transaction_hash_2;
+        transaction_receipt_2.status = Some(U64::from(1)); //success
+        let fingerprint_2 = PendingPayableFingerprint {
+            rowid: 10,
+            timestamp: from_time_t(199_780_000),
+            hash: Default::default(),
+            attempt: 15,
+            amount: 1212,
+            process_error: None,
+        };
+        let msg = ReportTransactionReceipts {
+            fingerprints_with_receipts: vec![
+                (Some(transaction_receipt_1), fingerprint_1.clone()),
+                (Some(transaction_receipt_2), fingerprint_2.clone()),
+            ],
+            response_skeleton_opt: None,
+        };
+
+        subject_addr.try_send(msg).unwrap();
+
+        let system = System::new("processing reported receipts");
+        System::current().stop();
+        system.run();
+        let transactions_confirmed_params = transactions_confirmed_params_arc.lock().unwrap();
+        assert_eq!(
+            *transactions_confirmed_params,
+            vec![vec![fingerprint_1, fingerprint_2]]
+        );
+    }
+
+    #[test]
+    fn accountant_handles_inserting_new_fingerprints() {
+        init_test_logging();
+        let insert_fingerprint_params_arc = Arc::new(Mutex::new(vec![]));
+        let pending_payable_dao = PendingPayableDaoMock::default()
+            .insert_fingerprints_params(&insert_fingerprint_params_arc)
+            .insert_fingerprints_result(Ok(()));
+        let subject = AccountantBuilder::default()
+            .pending_payable_daos(vec![ForAccountantBody(pending_payable_dao)])
+            .build();
+        let accountant_addr = subject.start();
+        let accountant_subs = Accountant::make_subs_from(&accountant_addr);
+        let timestamp = SystemTime::now();
+        let hash_1 = make_tx_hash(0x6c81c);
+        let amount_1 = 12345;
+        let hash_2 = make_tx_hash(0x1b207);
+        let amount_2 = 87654;
+        let init_params = vec![(hash_1, amount_1), (hash_2, amount_2)];
+        let init_fingerprints_msg = PendingPayableFingerprintSeeds {
+            batch_wide_timestamp: timestamp,
+            hashes_and_balances: init_params.clone(),
+            batch_payments: vec![],
+            hashes_and_nonces: vec![],
+            gas_price_wei: 1_000_000_000,
+        };
+
+        let _ = accountant_subs
+            .init_pending_payable_fingerprints
+            .try_send(init_fingerprints_msg)
+            .unwrap();
+
+        let system = System::new("ordering payment fingerprint test");
+        System::current().stop();
+        assert_eq!(system.run(), 0);
+        let insert_fingerprint_params = insert_fingerprint_params_arc.lock().unwrap();
+        assert_eq!(
+            *insert_fingerprint_params,
+            vec![(vec![(hash_1, amount_1), (hash_2, amount_2)], timestamp)]
+        );
+        TestLogHandler::new().exists_log_containing(
+            "DEBUG: Accountant: Saved new pending payable fingerprints for: \
+             0x000000000000000000000000000000000000000000000000000000000006c81c, 0x000000000000000000000000000000000000000000000000000000000001b207",
+        );
+    }
+
+    #[test]
+    fn payable_fingerprint_insertion_clearly_failed_and_we_log_it_at_least() {
+        //despite it doesn't end so here this event would be a cause of a later panic
+        init_test_logging();
+        let insert_fingerprint_params_arc = Arc::new(Mutex::new(vec![]));
+        let pending_payable_dao = PendingPayableDaoMock::default()
+            .insert_fingerprints_params(&insert_fingerprint_params_arc)
+            .insert_fingerprints_result(Err(PendingPayableDaoError::InsertionFailed(
+                "Crashed".to_string(),
+            )));
+        let amount = 2345;
+        let transaction_hash = make_tx_hash(0x1c8);
+        let subject = AccountantBuilder::default()
+            .pending_payable_daos(vec![ForAccountantBody(pending_payable_dao)])
+            .build();
+        let timestamp = SystemTime::now();
+        let report_new_fingerprints = PendingPayableFingerprintSeeds {
+            batch_wide_timestamp: timestamp,
+            hashes_and_balances: vec![(transaction_hash, amount)],
+            batch_payments: vec![],
+            hashes_and_nonces: vec![(transaction_hash, 5)],
+            gas_price_wei: 1_000_000_000,
         };
-        let account_2 = PayableAccount {
-            wallet: wallet_account_2.clone(),
-            balance_wei: payable_account_balance_2,
-            last_paid_timestamp: past_payable_timestamp_2,
-            pending_payable_opt: None,
+
+        let _ = subject.handle_new_pending_payable_fingerprints(report_new_fingerprints);
+
+        let insert_fingerprint_params = insert_fingerprint_params_arc.lock().unwrap();
+        assert_eq!(
+            *insert_fingerprint_params,
+            vec![(vec![(transaction_hash, amount)], timestamp)]
+        );
+        TestLogHandler::new().exists_log_containing("ERROR: Accountant: Failed to process \
+         new pending payable fingerprints due to 'InsertionFailed(\"Crashed\")', disabling the automated \
+          confirmation for all these transactions: 0x00000000000000000000000000000000000000000000000000000000000001c8");
+    }
+
+    #[test]
+    fn accountant_records_nonces_of_new_transactions_for_their_possible_fee_bumps() {
+        let insert_nonces_params_arc = Arc::new(Mutex::new(vec![]));
+        let pending_payable_dao = PendingPayableDaoMock::default()
+            .insert_fingerprints_result(Ok(()))
+            .insert_nonces_params(&insert_nonces_params_arc)
+            .insert_nonces_result(Ok(()));
+        let subject = AccountantBuilder::default()
+            .pending_payable_daos(vec![ForAccountantBody(pending_payable_dao)])
+            .build();
+        let hash_1 = make_tx_hash(0xe01);
+        let hash_2 = make_tx_hash(0xe02);
+        let seeds = PendingPayableFingerprintSeeds {
+            batch_wide_timestamp: SystemTime::now(),
+            hashes_and_balances: vec![(hash_1, 4_000), (hash_2, 5_500)],
+            batch_payments: vec![],
+            hashes_and_nonces: vec![(hash_1, 14), (hash_2, 15)],
+            gas_price_wei: 2_000_000_000,
         };
-        let pending_payable_scan_interval = 200; // should be slightly less than 1/5 of the time until shutting the system
-        let payable_dao_for_payable_scanner = PayableDaoMock::new()
-            .non_pending_payables_params(&non_pending_payables_params_arc)
-            .non_pending_payables_result(vec![account_1, account_2])
-            .mark_pending_payables_rowids_params(&mark_pending_payable_params_arc)
-            .mark_pending_payables_rowids_result(Ok(()));
-        let payable_dao_for_pending_payable_scanner = PayableDaoMock::new()
-            .transactions_confirmed_params(&transactions_confirmed_params_arc)
-            .transactions_confirmed_result(Ok(()));
-        let mut bootstrapper_config = bc_from_earning_wallet(make_wallet("some_wallet_address"));
-        bootstrapper_config.scan_intervals_opt = Some(ScanIntervals {
-            payable_scan_interval: Duration::from_secs(1_000_000), // we don't care about this scan
-            receivable_scan_interval: Duration::from_secs(1_000_000), // we don't care about this scan
-            pending_payable_scan_interval: Duration::from_millis(pending_payable_scan_interval),
-        });
-        let fingerprint_1_first_round = PendingPayableFingerprint {
-            rowid: rowid_for_account_1,
-            timestamp: this_payable_timestamp_1,
-            hash: pending_tx_hash_1,
-            attempt: 1,
-            amount: payable_account_balance_1,
-            process_error: None,
+
+        subject.handle_new_pending_payable_fingerprints(seeds);
+
+        let insert_nonces_params = insert_nonces_params_arc.lock().unwrap();
+        assert_eq!(
+            *insert_nonces_params,
+            vec![(vec![(hash_1, 14), (hash_2, 15)], 2_000_000_000)]
+        );
+    }
+
+    #[test]
+    fn accountant_logs_failure_to_record_nonces_of_new_transactions() {
+        init_test_logging();
+        let hash = make_tx_hash(0xe03);
+        let pending_payable_dao = PendingPayableDaoMock::default()
+            .insert_fingerprints_result(Ok(()))
+            .insert_nonces_result(Err(PendingPayableDaoError::InsertionFailed(
+                "Crashed".to_string(),
+            )));
+        let subject = AccountantBuilder::default()
+            .pending_payable_daos(vec![ForAccountantBody(pending_payable_dao)])
+            .build();
+        let seeds = PendingPayableFingerprintSeeds {
+            batch_wide_timestamp: SystemTime::now(),
+            hashes_and_balances: vec![(hash, 9_500)],
+            batch_payments: vec![],
+            hashes_and_nonces: vec![(hash, 14)],
+            gas_price_wei: 2_000_000_000,
         };
-        let fingerprint_2_first_round = PendingPayableFingerprint {
-            rowid: rowid_for_account_2,
-            timestamp: this_payable_timestamp_2,
-            hash: pending_tx_hash_2,
-            attempt: 1,
-            amount: payable_account_balance_2,
-            process_error: None,
+
+        subject.handle_new_pending_payable_fingerprints(seeds);
+
+        TestLogHandler::new().exists_log_containing(
+            "ERROR: Accountant: Failed to record the nonces of transactions \
+             0x0000000000000000000000000000000000000000000000000000000000000e03 due to \
+             'InsertionFailed(\"Crashed\")', they can't be replaced by fee bumps",
+        );
+    }
+
+    #[test]
+    fn accountant_records_how_batch_transactions_split_among_creditors() {
+        let insert_batch_payments_params_arc = Arc::new(Mutex::new(vec![]));
+        let pending_payable_dao = PendingPayableDaoMock::default()
+            .insert_fingerprints_result(Ok(()))
+            .insert_batch_payments_params(&insert_batch_payments_params_arc)
+            .insert_batch_payments_result(Ok(()));
+        let subject = AccountantBuilder::default()
+            .pending_payable_daos(vec![ForAccountantBody(pending_payable_dao)])
+            .build();
+        let hash = make_tx_hash(0xd15);
+        let batch_payments = vec![(
+            hash,
+            vec![
+                (make_wallet("creditor1"), 4_000),
+                (make_wallet("creditor2"), 5_500),
+            ],
+        )];
+        let seeds = PendingPayableFingerprintSeeds {
+            batch_wide_timestamp: SystemTime::now(),
+            hashes_and_balances: vec![(hash, 9_500)],
+            batch_payments: batch_payments.clone(),
+            hashes_and_nonces: vec![],
+            gas_price_wei: 1_000_000_000,
         };
-        let fingerprint_1_second_round = PendingPayableFingerprint {
-            attempt: 2,
-            ..fingerprint_1_first_round.clone()
+
+        subject.handle_new_pending_payable_fingerprints(seeds);
+
+        let insert_batch_payments_params = insert_batch_payments_params_arc.lock().unwrap();
+        assert_eq!(*insert_batch_payments_params, vec![batch_payments]);
+    }
+
+    #[test]
+    fn accountant_marks_batch_transaction_as_failure_if_its_split_among_creditors_is_not_recorded()
+    {
+        init_test_logging();
+        let fingerprints_rowids_params_arc = Arc::new(Mutex::new(vec![]));
+        let mark_failures_params_arc = Arc::new(Mutex::new(vec![]));
+        let hash = make_tx_hash(0xd16);
+        let pending_payable_dao = PendingPayableDaoMock::default()
+            .insert_fingerprints_result(Ok(()))
+            .insert_batch_payments_result(Err(PendingPayableDaoError::InsertionFailed(
+                "Crashed".to_string(),
+            )))
+            .fingerprints_rowids_params(&fingerprints_rowids_params_arc)
+            .fingerprints_rowids_result(TransactionHashes {
+                rowid_results: vec![(7, hash)],
+                no_rowid_results: vec![],
+            })
+            .mark_failures_params(&mark_failures_params_arc)
+            .mark_failures_result(Ok(()));
+        let subject = AccountantBuilder::default()
+            .pending_payable_daos(vec![ForAccountantBody(pending_payable_dao)])
+            .build();
+        let seeds = PendingPayableFingerprintSeeds {
+            batch_wide_timestamp: SystemTime::now(),
+            hashes_and_balances: vec![(hash, 9_500)],
+            batch_payments: vec![(
+                hash,
+                vec![
+                    (make_wallet("creditor1"), 4_000),
+                    (make_wallet("creditor2"), 5_500),
+                ],
+            )],
+            hashes_and_nonces: vec![],
+            gas_price_wei: 1_000_000_000,
+        };
+
+        subject.handle_new_pending_payable_fingerprints(seeds);
+
+        let fingerprints_rowids_params = fingerprints_rowids_params_arc.lock().unwrap();
+        assert_eq!(*fingerprints_rowids_params, vec![vec![hash]]);
+        let mark_failures_params = mark_failures_params_arc.lock().unwrap();
+        assert_eq!(*mark_failures_params, vec![vec![7]]);
+        TestLogHandler::new().exists_log_containing(
+            "ERROR: Accountant: Failed to record how the transactions \
+             0x0000000000000000000000000000000000000000000000000000000000000d16 split among their \
+             creditors due to 'InsertionFailed(\"Crashed\")', disabling their automated confirmation",
+        );
+    }
+
+    const EXAMPLE_RESPONSE_SKELETON: ResponseSkeleton = ResponseSkeleton {
+        client_id: 1234,
+        context_id: 4321,
+    };
+
+    const EXAMPLE_ERROR_MSG: &str = "My tummy hurts";
+
+    #[test]
+    fn handling_scan_error_for_externally_triggered_payables() {
+        assert_scan_error_is_handled_properly(
+            "handling_scan_error_for_externally_triggered_payables",
+            ScanError {
+                scan_type: ScanType::Payables,
+                response_skeleton_opt: Some(EXAMPLE_RESPONSE_SKELETON),
+                msg: EXAMPLE_ERROR_MSG.to_string(),
+            },
+        );
+    }
+
+    #[test]
+    fn handling_scan_error_for_externally_triggered_pending_payables() {
+        assert_scan_error_is_handled_properly(
+            "handling_scan_error_for_externally_triggered_pending_payables",
+            ScanError {
+                scan_type: ScanType::PendingPayables,
+                response_skeleton_opt: Some(EXAMPLE_RESPONSE_SKELETON),
+                msg: EXAMPLE_ERROR_MSG.to_string(),
+            },
+        );
+    }
+
+    #[test]
+    fn handling_scan_error_for_externally_triggered_receivables() {
+        assert_scan_error_is_handled_properly(
+            "handling_scan_error_for_externally_triggered_receivables",
+            ScanError {
+                scan_type: ScanType::Receivables,
+                response_skeleton_opt: Some(EXAMPLE_RESPONSE_SKELETON),
+                msg: EXAMPLE_ERROR_MSG.to_string(),
+            },
+        );
+    }
+
+    #[test]
+    fn handling_scan_error_for_internally_triggered_payables() {
+        assert_scan_error_is_handled_properly(
+            "handling_scan_error_for_internally_triggered_payables",
+            ScanError {
+                scan_type: ScanType::Payables,
+                response_skeleton_opt: None,
+                msg: EXAMPLE_ERROR_MSG.to_string(),
+            },
+        );
+    }
+
+    #[test]
+    fn handling_scan_error_for_internally_triggered_pending_payables() {
+        assert_scan_error_is_handled_properly(
+            "handling_scan_error_for_internally_triggered_pending_payables",
+            ScanError {
+                scan_type: ScanType::PendingPayables,
+                response_skeleton_opt: None,
+                msg: EXAMPLE_ERROR_MSG.to_string(),
+            },
+        );
+    }
+
+    #[test]
+    fn handling_scan_error_for_internally_triggered_receivables() {
+        assert_scan_error_is_handled_properly(
+            "handling_scan_error_for_internally_triggered_receivables",
+            ScanError {
+                scan_type: ScanType::Receivables,
+                response_skeleton_opt: None,
+                msg: EXAMPLE_ERROR_MSG.to_string(),
+            },
+        );
+    }
+
+    #[test]
+    fn financials_request_with_nothing_to_respond_to_is_refused() {
+        let system = System::new("test");
+        let subject = AccountantBuilder::default()
+            .bootstrapper_config(bc_from_earning_wallet(make_wallet("some_wallet_address")))
+            .build();
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let subject_addr = subject.start();
+        let peer_actors = peer_actors_builder().ui_gateway(ui_gateway).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+        let ui_message = NodeFromUiMessage {
+            client_id: 1234,
+            body: UiFinancialsRequest {
+                stats_required: false,
+                top_records_opt: None,
+                custom_queries_opt: None,
+            }
+            .tmb(2222),
         };
-        let fingerprint_2_second_round = PendingPayableFingerprint {
-            attempt: 2,
-            ..fingerprint_2_first_round.clone()
+
+        subject_addr.try_send(ui_message).unwrap();
+
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        let response = ui_gateway_recording.get_record::<NodeToUiMessage>(0);
+        assert_eq!(response.target, ClientId(1234));
+        let error = UiFinancialsResponse::fmb(response.body.clone()).unwrap_err();
+        let err_message_body = match error {
+            UiMessageError::PayloadError(payload) => payload,
+            x => panic!("we expected error message in the payload but got: {:?}", x),
         };
-        let fingerprint_1_third_round = PendingPayableFingerprint {
-            attempt: 3,
-            ..fingerprint_1_first_round.clone()
+        let (err_code, err_message) = err_message_body.payload.unwrap_err();
+        assert_eq!(err_code, REQUEST_WITH_NO_VALUES);
+        assert_eq!(
+            err_message,
+            "Empty requests with missing queries not to be processed"
+        );
+        assert!(matches!(err_message_body.path, Conversation(2222)));
+    }
+
+    #[test]
+    fn financials_request_allows_only_one_kind_of_view_into_books_at_a_time() {
+        let subject = AccountantBuilder::default()
+            .bootstrapper_config(bc_from_earning_wallet(make_wallet("some_wallet_address")))
+            .build();
+        let request = UiFinancialsRequest {
+            stats_required: false,
+            top_records_opt: Some(TopRecordsConfig {
+                count: 13,
+                ordered_by: Age,
+            }),
+            custom_queries_opt: Some(CustomQueries {
+                payable_opt: Some(RangeQuery {
+                    min_age_s: 5000,
+                    max_age_s: 11000,
+                    min_amount_gwei: 1_454_050_000,
+                    max_amount_gwei: 555_000_000_000,
+                }),
+                receivable_opt: None,
+            }),
         };
-        let fingerprint_2_third_round = PendingPayableFingerprint {
-            attempt: 3,
-            ..fingerprint_2_first_round.clone()
+
+        let result = subject.compute_financials(&request, 4567);
+
+        assert_eq!(
+            result,
+            MessageBody {
+                opcode: "financials".to_string(),
+                path: Conversation(4567),
+                payload: Err((
+                    REQUEST_WITH_MUTUALLY_EXCLUSIVE_PARAMS,
+                    "Requesting top records and the more customized subset of \
+             records is not allowed both at the same time"
+                        .to_string()
+                ))
+            }
+        );
+    }
+
+    #[test]
+    fn financials_request_produces_financials_response() {
+        let payable_dao = PayableDaoMock::new().total_result(264_567_894_578);
+        let receivable_dao = ReceivableDaoMock::new().total_result(987_654_328_996);
+        let pending_payable_dao = PendingPayableDaoMock::default().fee_bumps_result(vec![]);
+        let system = System::new("test");
+        let subject = AccountantBuilder::default()
+            .bootstrapper_config(make_bc_with_defaults())
+            .payable_daos(vec![ForAccountantBody(payable_dao)])
+            .receivable_daos(vec![ForAccountantBody(receivable_dao)])
+            .pending_payable_daos(vec![ForAccountantBody(pending_payable_dao)])
+            .build();
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let subject_addr = subject.start();
+        let peer_actors = peer_actors_builder().ui_gateway(ui_gateway).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+        let ui_message = NodeFromUiMessage {
+            client_id: 1234,
+            body: UiFinancialsRequest {
+                stats_required: true,
+                top_records_opt: None,
+                custom_queries_opt: None,
+            }
+            .tmb(2222),
         };
-        let fingerprint_2_fourth_round = PendingPayableFingerprint {
-            attempt: 4,
-            ..fingerprint_2_first_round.clone()
+
+        subject_addr.try_send(ui_message).unwrap();
+
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        let response = ui_gateway_recording.get_record::<NodeToUiMessage>(0);
+        assert_eq!(response.target, ClientId(1234));
+        let (body, context_id) = UiFinancialsResponse::fmb(response.body.clone()).unwrap();
+        assert_eq!(context_id, 2222);
+        assert_eq!(
+            body,
+            UiFinancialsResponse {
+                stats_opt: Some(UiFinancialStatistics {
+                    total_unpaid_and_pending_payable_gwei: 264,
+                    total_paid_payable_gwei: 0,
+                    total_unpaid_receivable_gwei: 987,
+                    total_paid_receivable_gwei: 0,
+                }),
+                query_results_opt: None,
+                fee_bumps_opt: Some(vec![]),
+                spending_budget_opt: Some(UiSpendingBudget {
+                    daily_limit_gwei: 0,
+                    daily_spent_gwei: 0,
+                    monthly_limit_gwei: 0,
+                    monthly_spent_gwei: 0,
+                }),
+            }
+        )
+    }
+
+    #[test]
+    fn financials_history_request_with_faulty_age_range_is_answered_with_error() {
+        let system = System::new("test");
+        let subject = AccountantBuilder::default()
+            .bootstrapper_config(make_bc_with_defaults())
+            .build();
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let subject_addr = subject.start();
+        let peer_actors = peer_actors_builder().ui_gateway(ui_gateway).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+        let ui_message = NodeFromUiMessage {
+            client_id: 1234,
+            body: UiFinancialsHistoryRequest {
+                min_age_s: 7_200,
+                max_age_s: 3_600,
+                granularity: HistoryGranularity::Hour,
+                wallet_opt: None,
+            }
+            .tmb(2222),
         };
-        let pending_payable_dao_for_payable_scanner = PendingPayableDaoMock::default()
-            .fingerprints_rowids_result(TransactionHashes {
-                rowid_results: vec![
-                    (rowid_for_account_1, pending_tx_hash_1),
-                    (rowid_for_account_2, pending_tx_hash_2),
-                ],
-                no_rowid_results: vec![],
-            });
-        let mut pending_payable_dao_for_pending_payable_scanner = PendingPayableDaoMock::new()
-            .return_all_errorless_fingerprints_params(&return_all_errorless_fingerprints_params_arc)
-            .return_all_errorless_fingerprints_result(vec![])
-            .return_all_errorless_fingerprints_result(vec![
-                fingerprint_1_first_round,
-                fingerprint_2_first_round,
-            ])
-            .return_all_errorless_fingerprints_result(vec![
-                fingerprint_1_second_round,
-                fingerprint_2_second_round,
-            ])
-            .return_all_errorless_fingerprints_result(vec![
-                fingerprint_1_third_round,
-                fingerprint_2_third_round,
-            ])
-            .return_all_errorless_fingerprints_result(vec![fingerprint_2_fourth_round.clone()])
-            .fingerprints_rowids_result(TransactionHashes {
-                rowid_results: vec![
-                    (rowid_for_account_1, pending_tx_hash_1),
-                    (rowid_for_account_2, pending_tx_hash_2),
-                ],
-                no_rowid_results: vec![],
-            })
-            .increment_scan_attempts_params(&update_fingerprint_params_arc)
-            .increment_scan_attempts_result(Ok(()))
-            .increment_scan_attempts_result(Ok(()))
-            .increment_scan_attempts_result(Ok(()))
-            .mark_failures_params(&mark_failure_params_arc)
-            // we don't have a better solution yet, so we mark this down
-            .mark_failures_result(Ok(()))
-            .delete_fingerprints_params(&delete_record_params_arc)
-            // this is used during confirmation of the successful one
-            .delete_fingerprints_result(Ok(()));
-        pending_payable_dao_for_pending_payable_scanner
-            .have_return_all_errorless_fingerprints_shut_down_the_system = true;
-        let consuming_wallet_for_accountant = consuming_wallet.clone();
-        let accountant_addr = Arbiter::builder()
-            .stop_system_on_panic(true)
-            .start(move |_| {
-                let mut subject = AccountantBuilder::default()
-                    .consuming_wallet(consuming_wallet_for_accountant)
-                    .bootstrapper_config(bootstrapper_config)
-                    .payable_daos(vec![
-                        ForPayableScanner(payable_dao_for_payable_scanner),
-                        ForPendingPayableScanner(payable_dao_for_pending_payable_scanner),
-                    ])
-                    .pending_payable_daos(vec![
-                        ForPayableScanner(pending_payable_dao_for_payable_scanner),
-                        ForPendingPayableScanner(pending_payable_dao_for_pending_payable_scanner),
-                    ])
-                    .build();
-                subject.scanners.receivable = Box::new(NullScanner::new());
-                let notify_later_half_mock = NotifyLaterHandleMock::default()
-                    .notify_later_params(&notify_later_scan_for_pending_payable_arc_cloned)
-                    .capture_msg_and_let_it_fly_on();
-                subject.scan_schedulers.update_scheduler(
-                    ScanType::PendingPayables,
-                    Some(Box::new(notify_later_half_mock)),
-                    None,
-                );
-                subject
-            });
-        let mut peer_actors = peer_actors_builder().build();
-        let accountant_subs = Accountant::make_subs_from(&accountant_addr);
-        peer_actors.accountant = accountant_subs.clone();
-        let blockchain_bridge_addr = blockchain_bridge.start();
-        let blockchain_bridge_subs = BlockchainBridge::make_subs_from(&blockchain_bridge_addr);
-        peer_actors.blockchain_bridge = blockchain_bridge_subs.clone();
-        send_bind_message!(accountant_subs, peer_actors);
-        send_bind_message!(blockchain_bridge_subs, peer_actors);
 
-        send_start_message!(accountant_subs);
+        subject_addr.try_send(ui_message).unwrap();
 
-        assert_eq!(system.run(), 0);
-        let mut mark_pending_payable_params = mark_pending_payable_params_arc.lock().unwrap();
-        let mut one_set_of_mark_pending_payable_params = mark_pending_payable_params.remove(0);
-        assert!(mark_pending_payable_params.is_empty());
-        let first_payable = one_set_of_mark_pending_payable_params.remove(0);
-        assert_eq!(first_payable.0, wallet_account_1);
-        assert_eq!(first_payable.1, rowid_for_account_1);
-        let second_payable = one_set_of_mark_pending_payable_params.remove(0);
-        assert!(
-            one_set_of_mark_pending_payable_params.is_empty(),
-            "{:?}",
-            one_set_of_mark_pending_payable_params
-        );
-        assert_eq!(second_payable.0, wallet_account_2);
-        assert_eq!(second_payable.1, rowid_for_account_2);
-        let return_all_errorless_fingerprints_params =
-            return_all_errorless_fingerprints_params_arc.lock().unwrap();
-        // it varies with machines and sometimes we manage more cycles than necessary
-        assert!(return_all_errorless_fingerprints_params.len() >= 5);
-        let non_pending_payables_params = non_pending_payables_params_arc.lock().unwrap();
-        assert_eq!(*non_pending_payables_params, vec![()]); // because we disabled further scanning for payables
-        let get_transaction_receipt_params = get_transaction_receipt_params_arc.lock().unwrap();
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
         assert_eq!(
-            *get_transaction_receipt_params,
-            vec![
-                pending_tx_hash_1,
-                pending_tx_hash_2,
-                pending_tx_hash_1,
-                pending_tx_hash_2,
-                pending_tx_hash_1,
-                pending_tx_hash_2,
-                pending_tx_hash_2,
-            ]
+            ui_gateway_recording.get_record::<NodeToUiMessage>(0),
+            &NodeToUiMessage {
+                target: ClientId(1234),
+                body: MessageBody {
+                    opcode: "financialsHistory".to_string(),
+                    path: Conversation(2222),
+                    payload: Err((
+                        INVALID_HISTORY_REQUEST,
+                        "Min age 7200 is greater than max age 3600".to_string()
+                    )),
+                },
+            }
         );
-        let build_blockchain_agent_params = build_blockchain_agent_params.lock().unwrap();
-        assert_eq!(
-            *build_blockchain_agent_params,
-            vec![(consuming_wallet, persistent_config_id_stamp)]
+    }
+
+    #[test]
+    fn compute_financials_history_sums_hourly_buckets_into_requested_periods() {
+        let monday = 1_704_067_200; // 2024-01-01T00:00:00Z
+        let record =
+            |hour_start: i64, direction, service, amount_gwei: u128| FinancialHistoryRecord {
+                hour_start: from_time_t(hour_start),
+                direction,
+                service,
+                wallet: make_wallet("counterparty"),
+                amount_wei: gwei_to_wei(amount_gwei as u64),
+            };
+        use HistoryDirection::*;
+        use HistoryService::*;
+        let records = vec![
+            record(monday + 5 * 3_600, Earned, Routing, 2),
+            record(monday + 20 * 3_600, Earned, Exit, 3),
+            record(monday + 20 * 3_600, Earned, Routing, 10),
+            record(monday + 25 * 3_600, Spent, Routing, 4),
+            record(monday + 7 * 86_400, Spent, Exit, 5),
+        ];
+        let history_params_arc = Arc::new(Mutex::new(vec![]));
+        let financial_history_dao = FinancialHistoryDaoMock::new()
+            .history_params(&history_params_arc)
+            .history_result(records.clone())
+            .history_result(records);
+        let subject = AccountantBuilder::default()
+            .financial_history_dao(financial_history_dao)
+            .build();
+        let now = from_time_t(monday + 14 * 86_400);
+        let wallet = make_wallet("counterparty");
+        let request = |granularity, wallet_opt: Option<String>| UiFinancialsHistoryRequest {
+            min_age_s: 3_600,
+            max_age_s: 30 * 86_400,
+            granularity,
+            wallet_opt,
+        };
+
+        let by_day =
+            subject.compute_financials_history(&request(HistoryGranularity::Day, None), 1111, now);
+        let by_week = subject.compute_financials_history(
+            &request(HistoryGranularity::Week, Some(wallet.to_string())),
+            2222,
+            now,
         );
-        let update_fingerprints_params = update_fingerprint_params_arc.lock().unwrap();
+
+        let period = |start_s, earned_routing, earned_exit, spent_routing, spent_exit| {
+            UiFinancialsHistoryPeriod {
+                start_s,
+                earned_routing_gwei: earned_routing,
+                earned_exit_gwei: earned_exit,
+                spent_routing_gwei: spent_routing,
+                spent_exit_gwei: spent_exit,
+            }
+        };
+        let monday = monday as u64;
         assert_eq!(
-            *update_fingerprints_params,
-            vec![
-                vec![rowid_for_account_1, rowid_for_account_2],
-                vec![rowid_for_account_1, rowid_for_account_2],
-                vec![rowid_for_account_2]
-            ]
+            UiFinancialsHistoryResponse::fmb(by_day).unwrap(),
+            (
+                UiFinancialsHistoryResponse {
+                    periods: vec![
+                        period(monday, 12, 3, 0, 0),
+                        period(monday + 86_400, 0, 0, 4, 0),
+                        period(monday + 7 * 86_400, 0, 0, 0, 5),
+                    ]
+                },
+                1111
+            )
         );
-        let mark_failure_params = mark_failure_params_arc.lock().unwrap();
-        assert_eq!(*mark_failure_params, vec![vec![rowid_for_account_1]]);
-        let delete_record_params = delete_record_params_arc.lock().unwrap();
-        assert_eq!(*delete_record_params, vec![vec![rowid_for_account_2]]);
-        let transaction_confirmed_params = transactions_confirmed_params_arc.lock().unwrap();
         assert_eq!(
-            *transaction_confirmed_params,
-            vec![vec![fingerprint_2_fourth_round.clone()]]
-        );
-        let expected_scan_pending_payable_msg_and_interval = (
-            ScanForPendingPayables {
-                response_skeleton_opt: None,
-            },
-            Duration::from_millis(pending_payable_scan_interval),
+            UiFinancialsHistoryResponse::fmb(by_week).unwrap(),
+            (
+                UiFinancialsHistoryResponse {
+                    periods: vec![
+                        period(monday, 12, 3, 4, 0),
+                        period(monday + 7 * 86_400, 0, 0, 0, 5),
+                    ]
+                },
+                2222
+            )
         );
-        let mut notify_later_check_for_confirmation =
-            notify_later_scan_for_pending_payable_params_arc
-                .lock()
-                .unwrap();
-        // it varies with machines and sometimes we manage more cycles than necessary
-        let vector_of_first_five_cycles = notify_later_check_for_confirmation
-            .drain(0..=4)
-            .collect_vec();
+        let from = from_time_t(monday as i64 - 16 * 86_400);
+        let to = from_time_t(monday as i64 + 14 * 86_400 - 3_600);
         assert_eq!(
-            vector_of_first_five_cycles,
-            vec![
-                expected_scan_pending_payable_msg_and_interval.clone(),
-                expected_scan_pending_payable_msg_and_interval.clone(),
-                expected_scan_pending_payable_msg_and_interval.clone(),
-                expected_scan_pending_payable_msg_and_interval.clone(),
-                expected_scan_pending_payable_msg_and_interval,
-            ]
-        );
-        let log_handler = TestLogHandler::new();
-        log_handler.exists_log_containing(
-            "WARN: Accountant: Broken transactions 0x00000000000000000000000000000000000000000000000\
-            0000000000000007b marked as an error. You should take over the care of those to make sure \
-            your debts are going to be settled properly. At the moment, there is no automated process \
-            fixing that without your assistance");
-        log_handler.exists_log_matching("INFO: Accountant: Transaction 0x000000000000000000000000000\
-        0000000000000000000000000000000000237 has been added to the blockchain; detected locally at \
-        attempt 4 at \\d{2,}ms after its sending");
-        log_handler.exists_log_containing(
-            "INFO: Accountant: Transactions 0x000000000000000000000000\
-        0000000000000000000000000000000000000237 completed their confirmation process succeeding",
+            *history_params_arc.lock().unwrap(),
+            vec![(from, to, None), (from, to, Some(wallet))]
         );
     }
 
     #[test]
-    fn accountant_receives_reported_transaction_receipts_and_processes_them_all() {
-        let transactions_confirmed_params_arc = Arc::new(Mutex::new(vec![]));
-        let payable_dao = PayableDaoMock::default()
-            .transactions_confirmed_params(&transactions_confirmed_params_arc)
-            .transactions_confirmed_result(Ok(()));
-        let pending_payable_dao =
-            PendingPayableDaoMock::default().delete_fingerprints_result(Ok(()));
-        let subject = AccountantBuilder::default()
-            .payable_daos(vec![ForPendingPayableScanner(payable_dao)])
-            .pending_payable_daos(vec![ForPendingPayableScanner(pending_payable_dao)])
+    fn period_start_aligns_to_hour_day_and_monday() {
+        let sunday_evening = 1_704_052_800 + 1_234; // 2023-12-31T20:00:00Z and a bit
+
+        let result = [
+            HistoryGranularity::Hour,
+            HistoryGranularity::Day,
+            HistoryGranularity::Week,
+        ]
+        .into_iter()
+        .map(|granularity| period_start(granularity, sunday_evening))
+        .collect::<Vec<_>>();
+
+        assert_eq!(result, vec![1_704_052_800, 1_703_980_800, 1_703_462_400]);
+    }
+
+    #[test]
+    fn adjust_balance_request_corrects_payable_and_leaves_audit_trail() {
+        init_test_logging();
+        let check_password_params_arc = Arc::new(Mutex::new(vec![]));
+        let adjust_balance_params_arc = Arc::new(Mutex::new(vec![]));
+        let record_params_arc = Arc::new(Mutex::new(vec![]));
+        let wallet = make_wallet("creditor");
+        let txn_id_stamp = ArbitraryIdStamp::new();
+        let payable_dao = PayableDaoMock::new()
+            .adjust_balance_params(&adjust_balance_params_arc)
+            .adjust_balance_result(Ok(Some(BalanceChange {
+                before_wei: 5_000_000_000_000,
+                after_wei: 3_500_000_000_000,
+            })))
+            .adjust_balance_txn_id_stamp(txn_id_stamp);
+        let ledger_adjustment_dao = LedgerAdjustmentDaoMock::new()
+            .record_params(&record_params_arc)
+            .record_result(Ok(()));
+        let system = System::new("test");
+        let mut subject = AccountantBuilder::default()
+            .bootstrapper_config(make_bc_with_defaults())
+            .payable_daos(vec![ForAccountantBody(payable_dao)])
+            .ledger_adjustment_dao(ledger_adjustment_dao)
             .build();
+        subject.persistent_config = Box::new(
+            PersistentConfigurationMock::new()
+                .check_password_params(&check_password_params_arc)
+                .check_password_result(Ok(true)),
+        );
+        subject.logger =
+            Logger::new("adjust_balance_request_corrects_payable_and_leaves_audit_trail");
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
         let subject_addr = subject.start();
-        let transaction_hash_1 = make_tx_hash(4545);
-        let mut transaction_receipt_1 = TransactionReceipt::default();
-        transaction_receipt_1.transaction_hash = transaction_hash_1;
-        transaction_receipt_1.status = Some(U64::from(1)); //success
-        let fingerprint_1 = PendingPayableFingerprint {
-            rowid: 5,
-            timestamp: from_time_t(200_000_000),
-            hash: transaction_hash_1,
-            attempt: 2,
-            amount: 444,
-            process_error: None,
-        };
-        let transaction_hash_2 = make_tx_hash(3333333);
-        let mut transaction_receipt_2 = TransactionReceipt::default();
-        transaction_receipt_2.transaction_hash = transaction_hash_2;
-        transaction_receipt_2.status = Some(U64::from(1)); //success
-        let fingerprint_2 = PendingPayableFingerprint {
-            rowid: 10,
-            timestamp: from_time_t(199_780_000),
-            hash: Default::default(),
-            attempt: 15,
-            amount: 1212,
-            process_error: None,
-        };
-        let msg = ReportTransactionReceipts {
-            fingerprints_with_receipts: vec![
-                (Some(transaction_receipt_1), fingerprint_1.clone()),
-                (Some(transaction_receipt_2), fingerprint_2.clone()),
-            ],
-            response_skeleton_opt: None,
+        let peer_actors = peer_actors_builder().ui_gateway(ui_gateway).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+        let ui_message = NodeFromUiMessage {
+            client_id: 1234,
+            body: UiAdjustBalanceRequest {
+                db_password: "password".to_string(),
+                ledger: LedgerSide::Payable,
+                wallet: wallet.to_string(),
+                change_gwei: -1_500,
+                operator: "alice".to_string(),
+                reason: "charged twice for one stream".to_string(),
+            }
+            .tmb(2222),
         };
 
-        subject_addr.try_send(msg).unwrap();
+        subject_addr.try_send(ui_message).unwrap();
 
-        let system = System::new("processing reported receipts");
         System::current().stop();
         system.run();
-        let transactions_confirmed_params = transactions_confirmed_params_arc.lock().unwrap();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
         assert_eq!(
-            *transactions_confirmed_params,
-            vec![vec![fingerprint_1, fingerprint_2]]
+            ui_gateway_recording.get_record::<NodeToUiMessage>(0),
+            &NodeToUiMessage {
+                target: ClientId(1234),
+                body: UiAdjustBalanceResponse {
+                    balance_before_gwei: 5_000,
+                    balance_after_gwei: 3_500,
+                }
+                .tmb(2222),
+            }
+        );
+        assert_eq!(
+            *check_password_params_arc.lock().unwrap(),
+            vec![Some("password".to_string())]
+        );
+        assert_eq!(
+            *adjust_balance_params_arc.lock().unwrap(),
+            vec![(wallet.clone(), -1_500_000_000_000)]
+        );
+        let record_params = record_params_arc.lock().unwrap();
+        assert_eq!(record_params.len(), 1);
+        let (recorded_adjustment, recorded_in_txn) = record_params[0].clone();
+        assert_eq!(recorded_in_txn, txn_id_stamp);
+        assert_eq!(
+            LedgerAdjustment {
+                timestamp: SystemTime::UNIX_EPOCH,
+                ..recorded_adjustment
+            },
+            LedgerAdjustment {
+                timestamp: SystemTime::UNIX_EPOCH,
+                operator: "alice".to_string(),
+                reason: "charged twice for one stream".to_string(),
+                ledger: AdjustedLedger::Payable,
+                kind: AdjustmentKind::Correction,
+                wallet: wallet.clone(),
+                change: BalanceChange {
+                    before_wei: 5_000_000_000_000,
+                    after_wei: 3_500_000_000_000,
+                },
+            }
         );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "INFO: adjust_balance_request_corrects_payable_and_leaves_audit_trail: alice changed \
+             the payable balance of {} from 5000000000000 to 3500000000000 wei; reason: charged \
+             twice for one stream",
+            wallet
+        ));
     }
 
     #[test]
-    fn accountant_handles_inserting_new_fingerprints() {
+    fn ledger_adjustment_request_with_bad_password_is_answered_with_error() {
         init_test_logging();
-        let insert_fingerprint_params_arc = Arc::new(Mutex::new(vec![]));
-        let pending_payable_dao = PendingPayableDaoMock::default()
-            .insert_fingerprints_params(&insert_fingerprint_params_arc)
-            .insert_fingerprints_result(Ok(()));
-        let subject = AccountantBuilder::default()
-            .pending_payable_daos(vec![ForAccountantBody(pending_payable_dao)])
+        let system = System::new("test");
+        let mut subject = AccountantBuilder::default()
+            .bootstrapper_config(make_bc_with_defaults())
             .build();
-        let accountant_addr = subject.start();
-        let accountant_subs = Accountant::make_subs_from(&accountant_addr);
-        let timestamp = SystemTime::now();
-        let hash_1 = make_tx_hash(0x6c81c);
-        let amount_1 = 12345;
-        let hash_2 = make_tx_hash(0x1b207);
-        let amount_2 = 87654;
-        let init_params = vec![(hash_1, amount_1), (hash_2, amount_2)];
-        let init_fingerprints_msg = PendingPayableFingerprintSeeds {
-            batch_wide_timestamp: timestamp,
-            hashes_and_balances: init_params.clone(),
+        subject.persistent_config =
+            Box::new(PersistentConfigurationMock::new().check_password_result(Ok(false)));
+        subject.logger =
+            Logger::new("ledger_adjustment_request_with_bad_password_is_answered_with_error");
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let subject_addr = subject.start();
+        let peer_actors = peer_actors_builder().ui_gateway(ui_gateway).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+        let ui_message = NodeFromUiMessage {
+            client_id: 1234,
+            body: UiForgiveDebtRequest {
+                db_password: "wrong".to_string(),
+                wallet: make_wallet("debtor").to_string(),
+                operator: "alice".to_string(),
+                reason: "never coming back".to_string(),
+            }
+            .tmb(2222),
         };
 
-        let _ = accountant_subs
-            .init_pending_payable_fingerprints
-            .try_send(init_fingerprints_msg)
-            .unwrap();
+        subject_addr.try_send(ui_message).unwrap();
 
-        let system = System::new("ordering payment fingerprint test");
         System::current().stop();
-        assert_eq!(system.run(), 0);
-        let insert_fingerprint_params = insert_fingerprint_params_arc.lock().unwrap();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
         assert_eq!(
-            *insert_fingerprint_params,
-            vec![(vec![(hash_1, amount_1), (hash_2, amount_2)], timestamp)]
+            ui_gateway_recording.get_record::<NodeToUiMessage>(0),
+            &NodeToUiMessage {
+                target: ClientId(1234),
+                body: MessageBody {
+                    opcode: "forgiveDebt".to_string(),
+                    path: Conversation(2222),
+                    payload: Err((
+                        BAD_PASSWORD_ERROR,
+                        "Bad password; can't adjust the ledger".to_string()
+                    )),
+                },
+            }
         );
         TestLogHandler::new().exists_log_containing(
-            "DEBUG: Accountant: Saved new pending payable fingerprints for: \
-             0x000000000000000000000000000000000000000000000000000000000006c81c, 0x000000000000000000000000000000000000000000000000000000000001b207",
+            "WARN: ledger_adjustment_request_with_bad_password_is_answered_with_error: Ledger \
+             adjustment refused: Bad password; can't adjust the ledger",
         );
     }
 
+    fn make_adjust_balance_request(ledger: LedgerSide, change_gwei: i64) -> UiAdjustBalanceRequest {
+        UiAdjustBalanceRequest {
+            db_password: "password".to_string(),
+            ledger,
+            wallet: make_wallet("counterparty").to_string(),
+            change_gwei,
+            operator: "alice".to_string(),
+            reason: "typo in an import".to_string(),
+        }
+    }
+
+    fn accept_password(subject: &mut Accountant, times: usize) {
+        subject.persistent_config = Box::new((0..times).fold(
+            PersistentConfigurationMock::new(),
+            |persistent_config, _| persistent_config.check_password_result(Ok(true)),
+        ));
+    }
+
     #[test]
-    fn payable_fingerprint_insertion_clearly_failed_and_we_log_it_at_least() {
-        //despite it doesn't end so here this event would be a cause of a later panic
-        init_test_logging();
-        let insert_fingerprint_params_arc = Arc::new(Mutex::new(vec![]));
-        let pending_payable_dao = PendingPayableDaoMock::default()
-            .insert_fingerprints_params(&insert_fingerprint_params_arc)
-            .insert_fingerprints_result(Err(PendingPayableDaoError::InsertionFailed(
-                "Crashed".to_string(),
-            )));
-        let amount = 2345;
-        let transaction_hash = make_tx_hash(0x1c8);
-        let subject = AccountantBuilder::default()
-            .pending_payable_daos(vec![ForAccountantBody(pending_payable_dao)])
+    fn ledger_adjustment_password_check_failure_is_reported() {
+        let mut subject = AccountantBuilder::default().build();
+        subject.persistent_config = Box::new(
+            PersistentConfigurationMock::new()
+                .check_password_result(Err(PersistentConfigError::NotPresent)),
+        );
+
+        let result = subject.adjust_balance(
+            &make_adjust_balance_request(LedgerSide::Receivable, 10),
+            SystemTime::now(),
+        );
+
+        assert_eq!(
+            result,
+            Err((
+                CONFIGURATOR_READ_ERROR,
+                "Error checking password: NotPresent".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn ledger_adjustment_demands_operator_reason_valid_wallet_and_nonzero_change() {
+        let mut subject = AccountantBuilder::default().build();
+        accept_password(&mut subject, 4);
+        let mut blank_operator = make_adjust_balance_request(LedgerSide::Payable, 10);
+        blank_operator.operator = "  ".to_string();
+        let mut blank_reason = make_adjust_balance_request(LedgerSide::Payable, 10);
+        blank_reason.reason = "".to_string();
+        let mut bad_wallet = make_adjust_balance_request(LedgerSide::Payable, 10);
+        bad_wallet.wallet = "booga".to_string();
+        let no_change = make_adjust_balance_request(LedgerSide::Payable, 0);
+
+        let results = [blank_operator, blank_reason, bad_wallet, no_change]
+            .iter()
+            .map(|request| subject.adjust_balance(request, SystemTime::now()))
+            .collect::<Vec<_>>();
+
+        let missing_audit = (
+            LEDGER_ADJUSTMENT_ERROR,
+            "The audit trail needs both the operator and the reason".to_string(),
+        );
+        assert_eq!(
+            results,
+            vec![
+                Err(missing_audit.clone()),
+                Err(missing_audit),
+                Err((
+                    LEDGER_ADJUSTMENT_ERROR,
+                    "Invalid wallet address: 'booga'".to_string()
+                )),
+                Err((
+                    LEDGER_ADJUSTMENT_ERROR,
+                    "A change of zero would adjust nothing".to_string()
+                ))
+            ]
+        );
+    }
+
+    #[test]
+    fn adjust_balance_refuses_to_push_payable_below_zero() {
+        let ledger_adjustment_record_params_arc = Arc::new(Mutex::new(vec![]));
+        let payable_dao = PayableDaoMock::new()
+            .adjust_balance_result(Err(PayableDaoError::BalanceBelowZero(-4_000_000_000)));
+        let mut subject = AccountantBuilder::default()
+            .payable_daos(vec![ForAccountantBody(payable_dao)])
+            .ledger_adjustment_dao(
+                LedgerAdjustmentDaoMock::new().record_params(&ledger_adjustment_record_params_arc),
+            )
             .build();
-        let timestamp = SystemTime::now();
-        let report_new_fingerprints = PendingPayableFingerprintSeeds {
-            batch_wide_timestamp: timestamp,
-            hashes_and_balances: vec![(transaction_hash, amount)],
-        };
+        accept_password(&mut subject, 1);
 
-        let _ = subject.handle_new_pending_payable_fingerprints(report_new_fingerprints);
+        let result = subject.adjust_balance(
+            &make_adjust_balance_request(LedgerSide::Payable, -10),
+            SystemTime::now(),
+        );
 
-        let insert_fingerprint_params = insert_fingerprint_params_arc.lock().unwrap();
         assert_eq!(
-            *insert_fingerprint_params,
-            vec![(vec![(transaction_hash, amount)], timestamp)]
+            result,
+            Err((
+                LEDGER_ADJUSTMENT_ERROR,
+                format!(
+                    "The payable to {} can't go below zero, yet the change would make it \
+                     -4000000000 wei",
+                    make_wallet("counterparty")
+                )
+            ))
         );
-        TestLogHandler::new().exists_log_containing("ERROR: Accountant: Failed to process \
-         new pending payable fingerprints due to 'InsertionFailed(\"Crashed\")', disabling the automated \
-          confirmation for all these transactions: 0x00000000000000000000000000000000000000000000000000000000000001c8");
+        assert!(ledger_adjustment_record_params_arc
+            .lock()
+            .unwrap()
+            .is_empty());
     }
 
-    const EXAMPLE_RESPONSE_SKELETON: ResponseSkeleton = ResponseSkeleton {
-        client_id: 1234,
-        context_id: 4321,
-    };
-
-    const EXAMPLE_ERROR_MSG: &str = "My tummy hurts";
-
     #[test]
-    fn handling_scan_error_for_externally_triggered_payables() {
-        assert_scan_error_is_handled_properly(
-            "handling_scan_error_for_externally_triggered_payables",
-            ScanError {
-                scan_type: ScanType::Payables,
-                response_skeleton_opt: Some(EXAMPLE_RESPONSE_SKELETON),
-                msg: EXAMPLE_ERROR_MSG.to_string(),
-            },
+    fn adjust_balance_of_unknown_receivable_is_refused() {
+        let receivable_dao = ReceivableDaoMock::new().adjust_balance_result(Ok(None));
+        let mut subject = AccountantBuilder::default()
+            .receivable_daos(vec![ForAccountantBody(receivable_dao)])
+            .build();
+        accept_password(&mut subject, 1);
+
+        let result = subject.adjust_balance(
+            &make_adjust_balance_request(LedgerSide::Receivable, 10),
+            SystemTime::now(),
         );
-    }
 
-    #[test]
-    fn handling_scan_error_for_externally_triggered_pending_payables() {
-        assert_scan_error_is_handled_properly(
-            "handling_scan_error_for_externally_triggered_pending_payables",
-            ScanError {
-                scan_type: ScanType::PendingPayables,
-                response_skeleton_opt: Some(EXAMPLE_RESPONSE_SKELETON),
-                msg: EXAMPLE_ERROR_MSG.to_string(),
-            },
+        assert_eq!(
+            result,
+            Err((
+                LEDGER_ADJUSTMENT_ERROR,
+                format!("No receivable account for {}", make_wallet("counterparty"))
+            ))
         );
     }
 
     #[test]
-    fn handling_scan_error_for_externally_triggered_receivables() {
-        assert_scan_error_is_handled_properly(
-            "handling_scan_error_for_externally_triggered_receivables",
-            ScanError {
-                scan_type: ScanType::Receivables,
-                response_skeleton_opt: Some(EXAMPLE_RESPONSE_SKELETON),
-                msg: EXAMPLE_ERROR_MSG.to_string(),
-            },
+    fn adjust_balance_is_rolled_back_if_its_audit_record_fails() {
+        init_test_logging();
+        let receivable_dao =
+            ReceivableDaoMock::new().adjust_balance_result(Ok(Some(BalanceChange {
+                before_wei: 1_000_000_000,
+                after_wei: 11_000_000_000,
+            })));
+        let ledger_adjustment_dao = LedgerAdjustmentDaoMock::new().record_result(Err(
+            LedgerAdjustmentDaoError::RecordingFailed("disk full".to_string()),
+        ));
+        let mut subject = AccountantBuilder::default()
+            .receivable_daos(vec![ForAccountantBody(receivable_dao)])
+            .ledger_adjustment_dao(ledger_adjustment_dao)
+            .build();
+        accept_password(&mut subject, 1);
+        subject.logger = Logger::new("adjust_balance_is_rolled_back_if_its_audit_record_fails");
+
+        let result = subject.adjust_balance(
+            &make_adjust_balance_request(LedgerSide::Receivable, 10),
+            SystemTime::now(),
         );
-    }
 
-    #[test]
-    fn handling_scan_error_for_internally_triggered_payables() {
-        assert_scan_error_is_handled_properly(
-            "handling_scan_error_for_internally_triggered_payables",
-            ScanError {
-                scan_type: ScanType::Payables,
-                response_skeleton_opt: None,
-                msg: EXAMPLE_ERROR_MSG.to_string(),
-            },
+        let expected_message = format!(
+            "The receivable balance of {} was left at 1000000000 wei, because the audit trail \
+             failed to record its change to 11000000000 wei: RecordingFailed(\"disk full\")",
+            make_wallet("counterparty")
+        );
+        assert_eq!(
+            result,
+            Err((LEDGER_ADJUSTMENT_ERROR, expected_message.clone()))
         );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "ERROR: adjust_balance_is_rolled_back_if_its_audit_record_fails: {}",
+            expected_message
+        ));
     }
 
     #[test]
-    fn handling_scan_error_for_internally_triggered_pending_payables() {
-        assert_scan_error_is_handled_properly(
-            "handling_scan_error_for_internally_triggered_pending_payables",
-            ScanError {
-                scan_type: ScanType::PendingPayables,
-                response_skeleton_opt: None,
-                msg: EXAMPLE_ERROR_MSG.to_string(),
+    fn forgive_debt_zeroes_receivable_and_lifts_delinquency_ban() {
+        let account_status_params_arc = Arc::new(Mutex::new(vec![]));
+        let adjust_balance_params_arc = Arc::new(Mutex::new(vec![]));
+        let unban_params_arc = Arc::new(Mutex::new(vec![]));
+        let record_params_arc = Arc::new(Mutex::new(vec![]));
+        let debtor = make_wallet("debtor");
+        let now = SystemTime::now();
+        let txn_id_stamp = ArbitraryIdStamp::new();
+        let receivable_dao = ReceivableDaoMock::new()
+            .account_status_params(&account_status_params_arc)
+            .account_status_result(Some(ReceivableAccount {
+                wallet: debtor.clone(),
+                balance_wei: 7_000_000_000_000,
+                last_received_timestamp: from_time_t(1_000_000),
+            }))
+            .adjust_balance_params(&adjust_balance_params_arc)
+            .adjust_balance_result(Ok(Some(BalanceChange {
+                before_wei: 7_000_000_000_000,
+                after_wei: 0,
+            })))
+            .adjust_balance_txn_id_stamp(txn_id_stamp);
+        let banned_dao = BannedDaoMock::new()
+            .ban_list_result(vec![make_wallet("someone else"), debtor.clone()])
+            .unban_parameters(&unban_params_arc);
+        let ledger_adjustment_dao = LedgerAdjustmentDaoMock::new()
+            .record_params(&record_params_arc)
+            .record_result(Ok(()));
+        let mut subject = AccountantBuilder::default()
+            .receivable_daos(vec![ForAccountantBody(receivable_dao)])
+            .banned_daos(vec![ForAccountantBody(banned_dao)])
+            .ledger_adjustment_dao(ledger_adjustment_dao)
+            .build();
+        accept_password(&mut subject, 1);
+
+        let result = subject.forgive_debt(
+            &UiForgiveDebtRequest {
+                db_password: "password".to_string(),
+                wallet: debtor.to_string(),
+                operator: "bob".to_string(),
+                reason: "goodwill".to_string(),
             },
+            now,
         );
-    }
 
-    #[test]
-    fn handling_scan_error_for_internally_triggered_receivables() {
-        assert_scan_error_is_handled_properly(
-            "handling_scan_error_for_internally_triggered_receivables",
-            ScanError {
-                scan_type: ScanType::Receivables,
-                response_skeleton_opt: None,
-                msg: EXAMPLE_ERROR_MSG.to_string(),
-            },
+        assert_eq!(
+            result,
+            Ok(UiForgiveDebtResponse {
+                forgiven_gwei: 7_000,
+                ban_lifted: true,
+            })
+        );
+        assert_eq!(
+            *account_status_params_arc.lock().unwrap(),
+            vec![debtor.clone()]
+        );
+        assert_eq!(
+            *adjust_balance_params_arc.lock().unwrap(),
+            vec![(debtor.clone(), -7_000_000_000_000)]
+        );
+        assert_eq!(*unban_params_arc.lock().unwrap(), vec![debtor.clone()]);
+        assert_eq!(
+            *record_params_arc.lock().unwrap(),
+            vec![(
+                LedgerAdjustment {
+                    timestamp: now,
+                    operator: "bob".to_string(),
+                    reason: "goodwill".to_string(),
+                    ledger: AdjustedLedger::Receivable,
+                    kind: AdjustmentKind::Forgiveness,
+                    wallet: debtor,
+                    change: BalanceChange {
+                        before_wei: 7_000_000_000_000,
+                        after_wei: 0,
+                    },
+                },
+                txn_id_stamp
+            )]
         );
     }
 
     #[test]
-    fn financials_request_with_nothing_to_respond_to_is_refused() {
-        let system = System::new("test");
-        let subject = AccountantBuilder::default()
-            .bootstrapper_config(bc_from_earning_wallet(make_wallet("some_wallet_address")))
+    fn forgive_debt_leaves_unbanned_debtor_alone() {
+        let unban_params_arc = Arc::new(Mutex::new(vec![]));
+        let debtor = make_wallet("debtor");
+        let receivable_dao = ReceivableDaoMock::new()
+            .account_status_result(Some(ReceivableAccount {
+                wallet: debtor.clone(),
+                balance_wei: 2_000_000_000,
+                last_received_timestamp: from_time_t(1_000_000),
+            }))
+            .adjust_balance_result(Ok(Some(BalanceChange {
+                before_wei: 2_000_000_000,
+                after_wei: 0,
+            })));
+        let banned_dao = BannedDaoMock::new()
+            .ban_list_result(vec![])
+            .unban_parameters(&unban_params_arc);
+        let mut subject = AccountantBuilder::default()
+            .receivable_daos(vec![ForAccountantBody(receivable_dao)])
+            .banned_daos(vec![ForAccountantBody(banned_dao)])
+            .ledger_adjustment_dao(LedgerAdjustmentDaoMock::new().record_result(Ok(())))
             .build();
-        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
-        let subject_addr = subject.start();
-        let peer_actors = peer_actors_builder().ui_gateway(ui_gateway).build();
-        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
-        let ui_message = NodeFromUiMessage {
-            client_id: 1234,
-            body: UiFinancialsRequest {
-                stats_required: false,
-                top_records_opt: None,
-                custom_queries_opt: None,
-            }
-            .tmb(2222),
-        };
-
-        subject_addr.try_send(ui_message).unwrap();
+        accept_password(&mut subject, 1);
+
+        let result = subject.forgive_debt(
+            &UiForgiveDebtRequest {
+                db_password: "password".to_string(),
+                wallet: debtor.to_string(),
+                operator: "bob".to_string(),
+                reason: "goodwill".to_string(),
+            },
+            SystemTime::now(),
+        );
 
-        System::current().stop();
-        system.run();
-        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
-        let response = ui_gateway_recording.get_record::<NodeToUiMessage>(0);
-        assert_eq!(response.target, ClientId(1234));
-        let error = UiFinancialsResponse::fmb(response.body.clone()).unwrap_err();
-        let err_message_body = match error {
-            UiMessageError::PayloadError(payload) => payload,
-            x => panic!("we expected error message in the payload but got: {:?}", x),
-        };
-        let (err_code, err_message) = err_message_body.payload.unwrap_err();
-        assert_eq!(err_code, REQUEST_WITH_NO_VALUES);
         assert_eq!(
-            err_message,
-            "Empty requests with missing queries not to be processed"
+            result,
+            Ok(UiForgiveDebtResponse {
+                forgiven_gwei: 2,
+                ban_lifted: false,
+            })
         );
-        assert!(matches!(err_message_body.path, Conversation(2222)));
+        assert!(unban_params_arc.lock().unwrap().is_empty());
     }
 
     #[test]
-    fn financials_request_allows_only_one_kind_of_view_into_books_at_a_time() {
-        let subject = AccountantBuilder::default()
-            .bootstrapper_config(bc_from_earning_wallet(make_wallet("some_wallet_address")))
+    fn forgive_debt_keeps_the_ban_if_the_forgiveness_cannot_be_recorded() {
+        let unban_params_arc = Arc::new(Mutex::new(vec![]));
+        let debtor = make_wallet("debtor");
+        let receivable_dao = ReceivableDaoMock::new()
+            .account_status_result(Some(ReceivableAccount {
+                wallet: debtor.clone(),
+                balance_wei: 2_000_000_000,
+                last_received_timestamp: from_time_t(1_000_000),
+            }))
+            .adjust_balance_result(Ok(Some(BalanceChange {
+                before_wei: 2_000_000_000,
+                after_wei: 0,
+            })));
+        let banned_dao = BannedDaoMock::new()
+            .ban_list_result(vec![debtor.clone()])
+            .unban_parameters(&unban_params_arc);
+        let mut subject = AccountantBuilder::default()
+            .receivable_daos(vec![ForAccountantBody(receivable_dao)])
+            .banned_daos(vec![ForAccountantBody(banned_dao)])
+            .ledger_adjustment_dao(LedgerAdjustmentDaoMock::new().record_result(Err(
+                LedgerAdjustmentDaoError::RecordingFailed("disk full".to_string()),
+            )))
             .build();
-        let request = UiFinancialsRequest {
-            stats_required: false,
-            top_records_opt: Some(TopRecordsConfig {
-                count: 13,
-                ordered_by: Age,
-            }),
-            custom_queries_opt: Some(CustomQueries {
-                payable_opt: Some(RangeQuery {
-                    min_age_s: 5000,
-                    max_age_s: 11000,
-                    min_amount_gwei: 1_454_050_000,
-                    max_amount_gwei: 555_000_000_000,
-                }),
-                receivable_opt: None,
-            }),
-        };
-
-        let result = subject.compute_financials(&request, 4567);
-
-        assert_eq!(
-            result,
-            MessageBody {
-                opcode: "financials".to_string(),
-                path: Conversation(4567),
-                payload: Err((
-                    REQUEST_WITH_MUTUALLY_EXCLUSIVE_PARAMS,
-                    "Requesting top records and the more customized subset of \
-             records is not allowed both at the same time"
-                        .to_string()
-                ))
-            }
+        accept_password(&mut subject, 1);
+
+        let result = subject.forgive_debt(
+            &UiForgiveDebtRequest {
+                db_password: "password".to_string(),
+                wallet: debtor.to_string(),
+                operator: "bob".to_string(),
+                reason: "goodwill".to_string(),
+            },
+            SystemTime::now(),
         );
+
+        assert_eq!(result.unwrap_err().0, LEDGER_ADJUSTMENT_ERROR);
+        assert!(unban_params_arc.lock().unwrap().is_empty());
     }
 
     #[test]
-    fn financials_request_produces_financials_response() {
-        let payable_dao = PayableDaoMock::new().total_result(264_567_894_578);
-        let receivable_dao = ReceivableDaoMock::new().total_result(987_654_328_996);
-        let system = System::new("test");
-        let subject = AccountantBuilder::default()
-            .bootstrapper_config(make_bc_with_defaults())
-            .payable_daos(vec![ForAccountantBody(payable_dao)])
+    fn forgive_debt_refuses_wallet_that_owes_nothing() {
+        let adjust_balance_params_arc = Arc::new(Mutex::new(vec![]));
+        let creditor = make_wallet("creditor");
+        let receivable_dao = ReceivableDaoMock::new()
+            .account_status_result(Some(ReceivableAccount {
+                wallet: creditor.clone(),
+                balance_wei: -5,
+                last_received_timestamp: from_time_t(1_000_000),
+            }))
+            .account_status_result(None)
+            .adjust_balance_params(&adjust_balance_params_arc);
+        let mut subject = AccountantBuilder::default()
             .receivable_daos(vec![ForAccountantBody(receivable_dao)])
             .build();
-        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
-        let subject_addr = subject.start();
-        let peer_actors = peer_actors_builder().ui_gateway(ui_gateway).build();
-        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
-        let ui_message = NodeFromUiMessage {
-            client_id: 1234,
-            body: UiFinancialsRequest {
-                stats_required: true,
-                top_records_opt: None,
-                custom_queries_opt: None,
-            }
-            .tmb(2222),
+        accept_password(&mut subject, 2);
+        let request = |wallet: &Wallet| UiForgiveDebtRequest {
+            db_password: "password".to_string(),
+            wallet: wallet.to_string(),
+            operator: "bob".to_string(),
+            reason: "goodwill".to_string(),
         };
+        let stranger = make_wallet("stranger");
 
-        subject_addr.try_send(ui_message).unwrap();
+        let results = [&creditor, &stranger]
+            .into_iter()
+            .map(|wallet| subject.forgive_debt(&request(wallet), SystemTime::now()))
+            .collect::<Vec<_>>();
 
-        System::current().stop();
-        system.run();
-        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
-        let response = ui_gateway_recording.get_record::<NodeToUiMessage>(0);
-        assert_eq!(response.target, ClientId(1234));
-        let (body, context_id) = UiFinancialsResponse::fmb(response.body.clone()).unwrap();
-        assert_eq!(context_id, 2222);
         assert_eq!(
-            body,
-            UiFinancialsResponse {
-                stats_opt: Some(UiFinancialStatistics {
-                    total_unpaid_and_pending_payable_gwei: 264,
-                    total_paid_payable_gwei: 0,
-                    total_unpaid_receivable_gwei: 987,
-                    total_paid_receivable_gwei: 0,
-                }),
-                query_results_opt: None,
-            }
-        )
+            results,
+            vec![
+                Err((
+                    LEDGER_ADJUSTMENT_ERROR,
+                    format!("{} owes nothing to forgive", creditor)
+                )),
+                Err((
+                    LEDGER_ADJUSTMENT_ERROR,
+                    format!("{} owes nothing to forgive", stranger)
+                ))
+            ]
+        );
+        assert!(adjust_balance_params_arc.lock().unwrap().is_empty());
     }
 
     #[test]
     fn compute_financials_processes_defaulted_request() {
         let payable_dao = PayableDaoMock::new().total_result(u64::MAX as u128 + 123456);
         let receivable_dao = ReceivableDaoMock::new().total_result((i64::MAX as i128) * 3);
+        let fee_bump = PendingPayableFeeBump {
+            pending_payable_rowid: 5,
+            replaced_hash: make_tx_hash(0x123),
+            replacement_hash: make_tx_hash(0x456),
+            gas_price_wei: 1_200_000_000,
+            timestamp: SystemTime::now()
+                .checked_sub(Duration::from_secs(1000))
+                .unwrap(),
+        };
+        let pending_payable_dao = PendingPayableDaoMock::default().fee_bumps_result(vec![fee_bump]);
         let subject = AccountantBuilder::default()
             .bootstrapper_config(bc_from_earning_wallet(make_wallet("some_wallet_address")))
             .payable_daos(vec![ForAccountantBody(payable_dao)])
             .receivable_daos(vec![ForAccountantBody(receivable_dao)])
+            .pending_payable_daos(vec![ForAccountantBody(pending_payable_dao)])
             .build();
         subject
             .financial_statistics
@@ -4098,8 +6550,26 @@ mod tests {
 
         let result = subject.compute_financials(&request, context_id);
 
+        let (mut response, actual_context_id) = UiFinancialsResponse::fmb(result).unwrap();
+        assert_eq!(actual_context_id, context_id);
+        let fee_bumps = response.fee_bumps_opt.take().unwrap();
+        assert_eq!(fee_bumps.len(), 1);
+        assert!(
+            fee_bumps[0].age_s >= 1000 && fee_bumps[0].age_s <= 1001,
+            "{}",
+            fee_bumps[0].age_s
+        );
         assert_eq!(
-            result,
+            fee_bumps[0],
+            UiPendingPayableFeeBump {
+                replaced_tx_hash: format!("{:?}", make_tx_hash(0x123)),
+                replacement_tx_hash: format!("{:?}", make_tx_hash(0x456)),
+                gas_price_wei: 1_200_000_000,
+                age_s: fee_bumps[0].age_s,
+            }
+        );
+        assert_eq!(
+            response,
             UiFinancialsResponse {
                 stats_opt: Some(UiFinancialStatistics {
                     total_unpaid_and_pending_payable_gwei: 18446744073,
@@ -4107,9 +6577,15 @@ mod tests {
                     total_unpaid_receivable_gwei: 27670116110,
                     total_paid_receivable_gwei: 4455656989
                 }),
-                query_results_opt: None
+                query_results_opt: None,
+                fee_bumps_opt: None,
+                spending_budget_opt: Some(UiSpendingBudget {
+                    daily_limit_gwei: 0,
+                    daily_spent_gwei: 0,
+                    monthly_limit_gwei: 0,
+                    monthly_spent_gwei: 0,
+                }),
             }
-            .tmb(context_id)
         )
     }
 
@@ -4180,6 +6656,8 @@ mod tests {
             computed_response,
             UiFinancialsResponse {
                 stats_opt: None,
+                fee_bumps_opt: None,
+                spending_budget_opt: None,
                 query_results_opt: Some(QueryResults {
                     payable_opt: Some(vec![UiPayableAccount {
                         wallet: make_wallet("abcd123").to_string(),
@@ -4256,6 +6734,8 @@ mod tests {
             response,
             UiFinancialsResponse {
                 stats_opt: None,
+                fee_bumps_opt: None,
+                spending_budget_opt: None,
                 query_results_opt: Some(QueryResults {
                     payable_opt: Some(vec![]),
                     receivable_opt: Some(vec![])
@@ -4346,6 +6826,8 @@ mod tests {
             computed_response,
             UiFinancialsResponse {
                 stats_opt: None,
+                fee_bumps_opt: None,
+                spending_budget_opt: None,
                 query_results_opt: Some(QueryResults {
                     payable_opt: Some(vec![UiPayableAccount {
                         wallet: make_wallet("abcd123").to_string(),
@@ -4706,6 +7188,7 @@ mod tests {
                 .scanners
                 .receivable
                 .mark_as_started(SystemTime::now()),
+            ScanType::FeeBumps => (),
         }
         let subject_addr = subject.start();
         let system = System::new("test");
@@ -4723,6 +7206,7 @@ mod tests {
                             actor.scanners.pending_payable.scan_started_at()
                         }
                         ScanType::Receivables => actor.scanners.receivable.scan_started_at(),
+                        ScanType::FeeBumps => None,
                     };
                     assert_eq!(scan_started_at_opt, None);
                 }),
@@ -9,9 +9,12 @@ use crate::test_utils::unshared_test_utils::arbitrary_id_stamp::ArbitraryIdStamp
 use crate::{arbitrary_id_stamp_in_trait_impl, set_arbitrary_id_stamp_in_mock_impl};
 use ethereum_types::U256;
 use std::cell::RefCell;
+use std::sync::{Arc, Mutex};
 
 #[derive(Default)]
 pub struct BlockchainAgentMock {
+    estimated_transaction_fee_total_params: Arc<Mutex<Vec<usize>>>,
+    estimated_transaction_fee_total_results: RefCell<Vec<u128>>,
     consuming_wallet_balances_results: RefCell<Vec<ConsumingWalletBalances>>,
     agreed_fee_per_computation_unit_results: RefCell<Vec<u64>>,
     consuming_wallet_result_opt: Option<Wallet>,
@@ -20,12 +23,20 @@ pub struct BlockchainAgentMock {
 }
 
 impl BlockchainAgent for BlockchainAgentMock {
-    fn estimated_transaction_fee_total(&self, _number_of_transactions: usize) -> u128 {
-        todo!("to be implemented by GH-711")
+    fn estimated_transaction_fee_total(&self, number_of_transactions: usize) -> u128 {
+        self.estimated_transaction_fee_total_params
+            .lock()
+            .unwrap()
+            .push(number_of_transactions);
+        self.estimated_transaction_fee_total_results
+            .borrow_mut()
+            .remove(0)
     }
 
     fn consuming_wallet_balances(&self) -> ConsumingWalletBalances {
-        todo!("to be implemented by GH-711")
+        self.consuming_wallet_balances_results
+            .borrow_mut()
+            .remove(0)
     }
 
     fn agreed_fee_per_computation_unit(&self) -> u64 {
@@ -50,6 +61,21 @@ impl BlockchainAgent for BlockchainAgentMock {
 }
 
 impl BlockchainAgentMock {
+    pub fn estimated_transaction_fee_total_params(
+        mut self,
+        params: &Arc<Mutex<Vec<usize>>>,
+    ) -> Self {
+        self.estimated_transaction_fee_total_params = params.clone();
+        self
+    }
+
+    pub fn estimated_transaction_fee_total_result(self, result: u128) -> Self {
+        self.estimated_transaction_fee_total_results
+            .borrow_mut()
+            .push(result);
+        self
+    }
+
     pub fn consuming_wallet_balances_result(self, result: ConsumingWalletBalances) -> Self {
         self.consuming_wallet_balances_results
             .borrow_mut()
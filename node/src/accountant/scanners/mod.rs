@@ -21,7 +21,9 @@ use crate::accountant::scanners::scanners_utils::pending_payable_scanner_utils::
     elapsed_in_ms, handle_none_status, handle_status_with_failure, handle_status_with_success,
     PendingPayableScanReport,
 };
-use crate::accountant::scanners::scanners_utils::receivable_scanner_utils::balance_and_age;
+use crate::accountant::scanners::scanners_utils::receivable_scanner_utils::{
+    balance_and_age, reconcile_received_payments, start_block_after_reconciliation,
+};
 use crate::accountant::PendingPayableId;
 use crate::accountant::{
     comma_joined_stringifiable, gwei_to_wei, Accountant, ReceivedPayments,
@@ -30,6 +32,8 @@ use crate::accountant::{
 };
 use crate::accountant::db_access_objects::banned_dao::BannedDao;
 use crate::blockchain::blockchain_bridge::{PendingPayableFingerprint, RetrieveTransactions};
+use crate::blockchain::blockchain_interface::data_structures::BlockchainTransaction;
+use crate::database::rusqlite_wrappers::TransactionSafeWrapper;
 use crate::sub_lib::accountant::{
     DaoFactories, FinancialStatistics, PaymentThresholds, ScanIntervals,
 };
@@ -69,6 +73,7 @@ impl Scanners {
         dao_factories: DaoFactories,
         payment_thresholds: Rc<PaymentThresholds>,
         when_pending_too_long_sec: u64,
+        confirmation_depth: u64,
         financial_statistics: Rc<RefCell<FinancialStatistics>>,
     ) -> Self {
         let payable = Box::new(PayableScanner::new(
@@ -93,6 +98,7 @@ impl Scanners {
             dao_factories.banned_dao_factory.make(),
             Box::new(persistent_configuration),
             Rc::clone(&payment_thresholds),
+            confirmation_depth,
             financial_statistics,
         ));
 
@@ -120,6 +126,7 @@ where
     fn scan_started_at(&self) -> Option<SystemTime>;
     fn mark_as_started(&mut self, timestamp: SystemTime);
     fn mark_as_ended(&mut self, logger: &Logger);
+    fn update_payment_thresholds(&mut self, payment_thresholds: Rc<PaymentThresholds>);
 
     as_any_ref_in_trait!();
     as_any_mut_in_trait!();
@@ -260,6 +267,10 @@ impl Scanner<QualifiedPayablesMessage, SentPayables> for PayableScanner {
 
     time_marking_methods!(Payables);
 
+    fn update_payment_thresholds(&mut self, payment_thresholds: Rc<PaymentThresholds>) {
+        self.common.payment_thresholds = payment_thresholds;
+    }
+
     as_any_ref_in_trait_impl!();
 }
 
@@ -381,14 +392,19 @@ impl PayableScanner {
         &'a self,
         sent_payables: &[&'a PendingPayable],
     ) -> (Vec<PendingPayableMetadata>, Vec<PendingPayableMetadata>) {
+        // A transaction paying through a disperse contract is shared by several payables
         let hashes = sent_payables
             .iter()
             .map(|pending_payable| pending_payable.hash)
+            .unique()
             .collect::<Vec<H256>>();
-        let mut sent_payables_hashmap = sent_payables
-            .iter()
-            .map(|payable| (payable.hash, &payable.recipient_wallet))
-            .collect::<HashMap<H256, &Wallet>>();
+        let mut sent_payables_hashmap: HashMap<H256, Vec<&Wallet>> = HashMap::new();
+        sent_payables.iter().for_each(|payable| {
+            sent_payables_hashmap
+                .entry(payable.hash)
+                .or_default()
+                .push(&payable.recipient_wallet)
+        });
 
         let transaction_hashes = self.pending_payable_dao.fingerprints_rowids(&hashes);
         let mut hashes_from_db = transaction_hashes
@@ -412,21 +428,23 @@ impl PayableScanner {
         let pending_payables_with_rowid = transaction_hashes
             .rowid_results
             .into_iter()
-            .map(|(rowid, hash)| {
-                let wallet = sent_payables_hashmap
+            .flat_map(|(rowid, hash)| {
+                sent_payables_hashmap
                     .remove(&hash)
-                    .expect("expect transaction hash, but it disappear");
-                PendingPayableMetadata::new(wallet, hash, Some(rowid))
+                    .expect("expect transaction hash, but it disappear")
+                    .into_iter()
+                    .map(move |wallet| PendingPayableMetadata::new(wallet, hash, Some(rowid)))
             })
             .collect_vec();
         let pending_payables_without_rowid = transaction_hashes
             .no_rowid_results
             .into_iter()
-            .map(|hash| {
-                let wallet = sent_payables_hashmap
+            .flat_map(|hash| {
+                sent_payables_hashmap
                     .remove(&hash)
-                    .expect("expect transaction hash, but it disappear");
-                PendingPayableMetadata::new(wallet, hash, None)
+                    .expect("expect transaction hash, but it disappear")
+                    .into_iter()
+                    .map(move |wallet| PendingPayableMetadata::new(wallet, hash, None))
             })
             .collect_vec();
 
@@ -592,8 +610,20 @@ impl Scanner<RequestTransactionReceipts, ReportTransactionReceipts> for PendingP
                     "Found {} pending payables to process",
                     filtered_pending_payable.len()
                 );
+                let replaced_transactions = self
+                    .pending_payable_dao
+                    .fee_bumps()
+                    .into_iter()
+                    .map(|fee_bump| {
+                        PendingPayableId::new(
+                            fee_bump.pending_payable_rowid,
+                            fee_bump.replaced_hash,
+                        )
+                    })
+                    .collect();
                 Ok(RequestTransactionReceipts {
                     pending_payable: filtered_pending_payable,
+                    replaced_transactions,
                     response_skeleton_opt,
                 })
             }
@@ -629,6 +659,10 @@ impl Scanner<RequestTransactionReceipts, ReportTransactionReceipts> for PendingP
 
     time_marking_methods!(PendingPayables);
 
+    fn update_payment_thresholds(&mut self, payment_thresholds: Rc<PaymentThresholds>) {
+        self.common.payment_thresholds = payment_thresholds;
+    }
+
     as_any_ref_in_trait_impl!();
 }
 
@@ -829,6 +863,7 @@ pub struct ReceivableScanner {
     pub receivable_dao: Box<dyn ReceivableDao>,
     pub banned_dao: Box<dyn BannedDao>,
     pub persistent_configuration: Box<dyn PersistentConfiguration>,
+    pub confirmation_depth: u64,
     pub financial_statistics: Rc<RefCell<FinancialStatistics>>,
 }
 
@@ -854,7 +889,9 @@ impl Scanner<RetrieveTransactions, ReceivedPayments> for ReceivableScanner {
     }
 
     fn finish_scan(&mut self, msg: ReceivedPayments, logger: &Logger) -> Option<NodeToUiMessage> {
-        if msg.payments.is_empty() {
+        if self.confirmation_depth > 0 {
+            self.reconcile_received_payments(&msg, logger)
+        } else if msg.payments.is_empty() {
             info!(
                 logger,
                 "No newly received payments were detected during the scanning process."
@@ -884,6 +921,10 @@ impl Scanner<RetrieveTransactions, ReceivedPayments> for ReceivableScanner {
 
     time_marking_methods!(Receivables);
 
+    fn update_payment_thresholds(&mut self, payment_thresholds: Rc<PaymentThresholds>) {
+        self.common.payment_thresholds = payment_thresholds;
+    }
+
     as_any_ref_in_trait_impl!();
     as_any_mut_in_trait_impl!();
 }
@@ -894,6 +935,7 @@ impl ReceivableScanner {
         banned_dao: Box<dyn BannedDao>,
         persistent_configuration: Box<dyn PersistentConfiguration>,
         payment_thresholds: Rc<PaymentThresholds>,
+        confirmation_depth: u64,
         financial_statistics: Rc<RefCell<FinancialStatistics>>,
     ) -> Self {
         Self {
@@ -901,21 +943,94 @@ impl ReceivableScanner {
             receivable_dao,
             banned_dao,
             persistent_configuration,
+            confirmation_depth,
             financial_statistics,
         }
     }
 
     fn handle_new_received_payments(&mut self, msg: &ReceivedPayments, logger: &Logger) {
-        let mut txn = self
+        let txn = self
             .receivable_dao
             .as_mut()
             .more_money_received(msg.timestamp, &msg.payments);
 
-        let new_start_block = msg.new_start_block;
-        match self
-            .persistent_configuration
-            .set_start_block_from_txn(Some(new_start_block), &mut txn)
-        {
+        Self::set_start_block_and_commit(
+            self.persistent_configuration.as_mut(),
+            msg.new_start_block,
+            txn,
+            logger,
+        );
+
+        let total_newly_paid_receivable = Self::sum_wei_amounts(&msg.payments);
+
+        self.financial_statistics
+            .borrow_mut()
+            .total_paid_receivable_wei += total_newly_paid_receivable;
+    }
+
+    fn reconcile_received_payments(&mut self, msg: &ReceivedPayments, logger: &Logger) {
+        let retained = self.receivable_dao.pending_receivables();
+        let (reconciliation, lowest_watched_block_opt) = reconcile_received_payments(
+            retained,
+            &msg.payments,
+            msg.scanned_blocks_opt,
+            self.confirmation_depth,
+        );
+        let new_start_block = start_block_after_reconciliation(
+            msg.new_start_block,
+            msg.scanned_blocks_opt,
+            self.confirmation_depth,
+            lowest_watched_block_opt,
+        );
+
+        let txn = self
+            .receivable_dao
+            .as_mut()
+            .reconcile_received_payments(msg.timestamp, &reconciliation);
+
+        Self::set_start_block_and_commit(
+            self.persistent_configuration.as_mut(),
+            new_start_block,
+            txn,
+            logger,
+        );
+
+        reconciliation.to_roll_back.iter().for_each(|payment| {
+            warning!(
+                logger,
+                "Payment of {} wei from {} in transaction {:?} disappeared from block {} \
+                ({:?}) in a chain reorganization; its credit was rolled back",
+                payment.wei_amount,
+                payment.from,
+                payment.transaction_hash,
+                payment.block_number,
+                payment.block_hash
+            )
+        });
+        if !reconciliation.to_hold.is_empty() {
+            info!(
+                logger,
+                "Holding {} received payment(s) until {} confirmations are reached",
+                reconciliation.to_hold.len(),
+                self.confirmation_depth
+            )
+        }
+
+        let total_newly_paid_receivable = Self::sum_wei_amounts(&reconciliation.to_credit);
+        let total_rolled_back_receivable = Self::sum_wei_amounts(&reconciliation.to_roll_back);
+        let mut financial_statistics = self.financial_statistics.borrow_mut();
+        financial_statistics.total_paid_receivable_wei =
+            (financial_statistics.total_paid_receivable_wei + total_newly_paid_receivable)
+                .saturating_sub(total_rolled_back_receivable);
+    }
+
+    fn set_start_block_and_commit(
+        persistent_configuration: &mut dyn PersistentConfiguration,
+        new_start_block: u64,
+        mut txn: TransactionSafeWrapper,
+        logger: &Logger,
+    ) {
+        match persistent_configuration.set_start_block_from_txn(Some(new_start_block), &mut txn) {
             Ok(()) => (),
             Err(e) => panic!(
                 "Attempt to set new start block to {} failed due to: {:?}",
@@ -929,15 +1044,12 @@ impl ReceivableScanner {
             }
             Err(e) => panic!("Commit of received transactions failed: {:?}", e),
         }
+    }
 
-        let total_newly_paid_receivable = msg
-            .payments
+    fn sum_wei_amounts(payments: &[BlockchainTransaction]) -> u128 {
+        payments
             .iter()
-            .fold(0, |so_far, now| so_far + now.wei_amount);
-
-        self.financial_statistics
-            .borrow_mut()
-            .total_paid_receivable_wei += total_newly_paid_receivable;
+            .fold(0, |so_far, now| so_far + now.wei_amount)
     }
 
     pub fn scan_for_delinquencies(&self, timestamp: SystemTime, logger: &Logger) {
@@ -1079,6 +1191,10 @@ pub trait ScanScheduler {
     fn interval(&self) -> Duration {
         intentionally_blank!()
     }
+    // Takes effect with the next scheduling; a scan already scheduled keeps its time
+    fn set_interval(&mut self, _interval: Duration) {
+        intentionally_blank!()
+    }
 
     as_any_ref_in_trait!();
     as_any_mut_in_trait!();
@@ -1093,6 +1209,9 @@ impl<T: Default + 'static> ScanScheduler for PeriodicalScanScheduler<T> {
     fn interval(&self) -> Duration {
         self.interval
     }
+    fn set_interval(&mut self, interval: Duration) {
+        self.interval = interval
+    }
 
     as_any_ref_in_trait_impl!();
     as_any_mut_in_trait_impl!();
@@ -1101,7 +1220,10 @@ impl<T: Default + 'static> ScanScheduler for PeriodicalScanScheduler<T> {
 mod tests {
     use crate::accountant::db_access_objects::payable_dao::{PayableAccount, PayableDaoError};
     use crate::accountant::db_access_objects::pending_payable_dao::{
-        PendingPayable, PendingPayableDaoError, TransactionHashes,
+        PendingPayable, PendingPayableDaoError, PendingPayableFeeBump, TransactionHashes,
+    };
+    use crate::accountant::db_access_objects::receivable_dao::{
+        PendingReceivable, ReceivablesReconciliation,
     };
     use crate::accountant::db_access_objects::utils::{from_time_t, to_time_t};
     use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::msgs::QualifiedPayablesMessage;
@@ -1115,14 +1237,15 @@ mod tests {
     use crate::accountant::test_utils::{
         make_custom_payment_thresholds, make_payable_account, make_payables,
         make_pending_payable_fingerprint, make_receivable_account, BannedDaoFactoryMock,
-        BannedDaoMock, ConfigDaoFactoryMock, PayableDaoFactoryMock, PayableDaoMock,
+        BannedDaoMock, ConfigDaoFactoryMock, FinancialHistoryDaoFactoryMock,
+        LedgerAdjustmentDaoFactoryMock, PayableDaoFactoryMock, PayableDaoMock,
         PayableScannerBuilder, PayableThresholdsGaugeMock, PendingPayableDaoFactoryMock,
         PendingPayableDaoMock, PendingPayableScannerBuilder, ReceivableDaoFactoryMock,
         ReceivableDaoMock, ReceivableScannerBuilder,
     };
     use crate::accountant::{
         gwei_to_wei, PendingPayableId, ReceivedPayments, ReportTransactionReceipts,
-        RequestTransactionReceipts, SentPayables, DEFAULT_PENDING_TOO_LONG_SEC,
+        RequestTransactionReceipts, ScannedBlocks, SentPayables, DEFAULT_PENDING_TOO_LONG_SEC,
     };
     use crate::blockchain::blockchain_bridge::{PendingPayableFingerprint, RetrieveTransactions};
     use crate::blockchain::blockchain_interface::data_structures::errors::PayableTransactionError;
@@ -1190,9 +1313,12 @@ mod tests {
                 receivable_dao_factory: Box::new(receivable_dao_factory),
                 banned_dao_factory: Box::new(banned_dao_factory),
                 config_dao_factory: Box::new(config_dao_factory),
+                financial_history_dao_factory: Box::new(FinancialHistoryDaoFactoryMock::new()),
+                ledger_adjustment_dao_factory: Box::new(LedgerAdjustmentDaoFactoryMock::new()),
             },
             Rc::clone(&payment_thresholds_rc),
             when_pending_too_long_sec,
+            7,
             Rc::new(RefCell::new(financial_statistics.clone())),
         );
 
@@ -1236,6 +1362,7 @@ mod tests {
             *receivable_scanner.financial_statistics.borrow(),
             financial_statistics
         );
+        assert_eq!(receivable_scanner.confirmation_depth, 7);
         assert_eq!(
             receivable_scanner.common.payment_thresholds.as_ref(),
             &payment_thresholds
@@ -1461,6 +1588,48 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn payable_scanner_marks_all_payables_paid_by_one_batch_transaction_as_pending() {
+        let fingerprints_rowids_params_arc = Arc::new(Mutex::new(vec![]));
+        let mark_pending_payables_params_arc = Arc::new(Mutex::new(vec![]));
+        let batch_hash = make_tx_hash(0xba7c);
+        let batch_rowid = 44;
+        let wallet_1 = make_wallet("creditor1");
+        let wallet_2 = make_wallet("creditor2");
+        let pending_payable_dao = PendingPayableDaoMock::default()
+            .fingerprints_rowids_params(&fingerprints_rowids_params_arc)
+            .fingerprints_rowids_result(TransactionHashes {
+                rowid_results: vec![(batch_rowid, batch_hash)],
+                no_rowid_results: vec![],
+            });
+        let payable_dao = PayableDaoMock::new()
+            .mark_pending_payables_rowids_params(&mark_pending_payables_params_arc)
+            .mark_pending_payables_rowids_result(Ok(()));
+        let mut subject = PayableScannerBuilder::new()
+            .payable_dao(payable_dao)
+            .pending_payable_dao(pending_payable_dao)
+            .build();
+        let sent_payable = SentPayables {
+            payment_procedure_result: Ok(vec![
+                Ok(PendingPayable::new(wallet_1.clone(), batch_hash)),
+                Ok(PendingPayable::new(wallet_2.clone(), batch_hash)),
+            ]),
+            response_skeleton_opt: None,
+        };
+        subject.mark_as_started(SystemTime::now());
+
+        let message_opt = subject.finish_scan(sent_payable, &Logger::new("test"));
+
+        assert_eq!(message_opt, None);
+        let fingerprints_rowids_params = fingerprints_rowids_params_arc.lock().unwrap();
+        assert_eq!(*fingerprints_rowids_params, vec![vec![batch_hash]]);
+        let mark_pending_payables_params = mark_pending_payables_params_arc.lock().unwrap();
+        assert_eq!(
+            *mark_pending_payables_params,
+            vec![vec![(wallet_1, batch_rowid), (wallet_2, batch_rowid)]]
+        );
+    }
+
     #[test]
     fn entries_must_be_kept_consistent_and_aligned() {
         let wallet_1 = make_wallet("abc");
@@ -2236,9 +2405,17 @@ mod tests {
             amount: 7999,
             process_error: None,
         };
-        let fingerprints = vec![payable_fingerprint_1, payable_fingerprint_2];
+        let fee_bump = PendingPayableFeeBump {
+            pending_payable_rowid: payable_fingerprint_2.rowid,
+            replaced_hash: make_tx_hash(112232),
+            replacement_hash: payable_fingerprint_2.hash,
+            gas_price_wei: 1_200_000_000,
+            timestamp: now,
+        };
+        let fingerprints = vec![payable_fingerprint_1, payable_fingerprint_2.clone()];
         let pending_payable_dao = PendingPayableDaoMock::new()
-            .return_all_errorless_fingerprints_result(fingerprints.clone());
+            .return_all_errorless_fingerprints_result(fingerprints.clone())
+            .fee_bumps_result(vec![fee_bump]);
         let mut pending_payable_scanner = PendingPayableScannerBuilder::new()
             .pending_payable_dao(pending_payable_dao)
             .build();
@@ -2257,6 +2434,10 @@ mod tests {
             result,
             Ok(RequestTransactionReceipts {
                 pending_payable: fingerprints,
+                replaced_transactions: vec![PendingPayableId::new(
+                    payable_fingerprint_2.rowid,
+                    make_tx_hash(112232)
+                )],
                 response_skeleton_opt: None
             })
         );
@@ -2280,7 +2461,8 @@ mod tests {
                 attempt: 1,
                 amount: 1_000_000,
                 process_error: None,
-            }]);
+            }])
+            .fee_bumps_result(vec![]);
         let mut subject = PendingPayableScannerBuilder::new()
             .pending_payable_dao(pending_payable_dao)
             .build();
@@ -3080,6 +3262,7 @@ mod tests {
             timestamp: SystemTime::now(),
             payments: vec![],
             new_start_block,
+            scanned_blocks_opt: None,
             response_skeleton_opt: None,
         };
 
@@ -3115,6 +3298,7 @@ mod tests {
             timestamp: now,
             payments: vec![],
             new_start_block,
+            scanned_blocks_opt: None,
             response_skeleton_opt: None,
         };
         // Not necessary, rather for preciseness
@@ -3154,11 +3338,17 @@ mod tests {
         let receivables = vec![
             BlockchainTransaction {
                 block_number: 4578910,
+                block_hash: H256::from_low_u64_be(4578910),
+                transaction_hash: H256::from_low_u64_be(1),
+                log_index: 0,
                 from: make_wallet("wallet_1"),
                 wei_amount: 45_780,
             },
             BlockchainTransaction {
                 block_number: 4569898,
+                block_hash: H256::from_low_u64_be(4569898),
+                transaction_hash: H256::from_low_u64_be(2),
+                log_index: 0,
                 from: make_wallet("wallet_2"),
                 wei_amount: 3_333_345,
             },
@@ -3167,6 +3357,7 @@ mod tests {
             timestamp: now,
             payments: receivables.clone(),
             new_start_block: 7890123,
+            scanned_blocks_opt: None,
             response_skeleton_opt: None,
         };
         subject.mark_as_started(SystemTime::now());
@@ -3194,6 +3385,127 @@ mod tests {
         );
     }
 
+    #[test]
+    fn receivable_scanner_with_confirmation_depth_holds_fresh_payments_and_rolls_back_lost_ones() {
+        init_test_logging();
+        let test_name = "receivable_scanner_with_confirmation_depth_holds_fresh_payments_and_rolls_back_lost_ones";
+        let now = SystemTime::now();
+        let reconcile_received_payments_params_arc = Arc::new(Mutex::new(vec![]));
+        let set_start_block_from_txn_params_arc = Arc::new(Mutex::new(vec![]));
+        let commit_params_arc = Arc::new(Mutex::new(vec![]));
+        let transaction_id = ArbitraryIdStamp::new();
+        let txn_inner_builder = TransactionInnerWrapperMockBuilder::default()
+            .commit_params(&commit_params_arc)
+            .commit_result(Ok(()))
+            .set_arbitrary_id_stamp(transaction_id);
+        let transaction = TransactionSafeWrapper::new_with_builder(txn_inner_builder);
+        let lost_payment = BlockchainTransaction {
+            block_number: 1_000,
+            block_hash: H256::from_low_u64_be(1_000),
+            transaction_hash: make_tx_hash(1),
+            log_index: 0,
+            from: make_wallet("wallet_1"),
+            wei_amount: 45_000,
+        };
+        let confirmed_payment = BlockchainTransaction {
+            block_number: 1_005,
+            block_hash: H256::from_low_u64_be(1_005),
+            transaction_hash: make_tx_hash(2),
+            log_index: 1,
+            from: make_wallet("wallet_2"),
+            wei_amount: 3_000_000,
+        };
+        let fresh_payment = BlockchainTransaction {
+            block_number: 1_018,
+            block_hash: H256::from_low_u64_be(1_018),
+            transaction_hash: make_tx_hash(3),
+            log_index: 0,
+            from: make_wallet("wallet_3"),
+            wei_amount: 777,
+        };
+        let receivable_dao = ReceivableDaoMock::new()
+            .pending_receivables_result(vec![
+                PendingReceivable {
+                    transaction: lost_payment.clone(),
+                    credited: true,
+                },
+                PendingReceivable {
+                    transaction: confirmed_payment.clone(),
+                    credited: false,
+                },
+            ])
+            .reconcile_received_payments_params(&reconcile_received_payments_params_arc)
+            .reconcile_received_payments_result(transaction);
+        let persistent_config = PersistentConfigurationMock::new()
+            .set_start_block_from_txn_params(&set_start_block_from_txn_params_arc)
+            .set_start_block_from_txn_result(Ok(()));
+        let mut subject = ReceivableScannerBuilder::new()
+            .receivable_dao(receivable_dao)
+            .persistent_configuration(persistent_config)
+            .confirmation_depth(10)
+            .build();
+        subject.financial_statistics.replace(FinancialStatistics {
+            total_paid_payable_wei: 0,
+            total_paid_receivable_wei: 100_000,
+        });
+        let msg = ReceivedPayments {
+            timestamp: now,
+            payments: vec![confirmed_payment.clone(), fresh_payment.clone()],
+            new_start_block: 1_021,
+            scanned_blocks_opt: Some(ScannedBlocks {
+                from: 990,
+                to: 1_020,
+                latest: 1_020,
+            }),
+            response_skeleton_opt: None,
+        };
+        subject.mark_as_started(SystemTime::now());
+
+        let message_opt = subject.finish_scan(msg, &Logger::new(test_name));
+
+        assert_eq!(message_opt, None);
+        assert_eq!(subject.scan_started_at(), None);
+        let reconcile_received_payments_params =
+            reconcile_received_payments_params_arc.lock().unwrap();
+        assert_eq!(
+            *reconcile_received_payments_params,
+            vec![(
+                now,
+                ReceivablesReconciliation {
+                    to_credit: vec![confirmed_payment],
+                    to_roll_back: vec![lost_payment],
+                    to_hold: vec![fresh_payment],
+                    to_release: vec![],
+                }
+            )]
+        );
+        let set_start_block_from_txn_params = set_start_block_from_txn_params_arc.lock().unwrap();
+        assert_eq!(
+            *set_start_block_from_txn_params,
+            vec![(Some(1_005u64), transaction_id)]
+        );
+        let commit_params = commit_params_arc.lock().unwrap();
+        assert_eq!(*commit_params, vec![()]);
+        assert_eq!(
+            subject
+                .financial_statistics
+                .borrow()
+                .total_paid_receivable_wei,
+            100_000 + 3_000_000 - 45_000
+        );
+        let log_handler = TestLogHandler::new();
+        log_handler.exists_log_containing(&format!(
+            "WARN: {test_name}: Payment of 45000 wei from {} in transaction {:?} disappeared \
+            from block 1000 ({:?}) in a chain reorganization; its credit was rolled back",
+            make_wallet("wallet_1"),
+            make_tx_hash(1),
+            H256::from_low_u64_be(1_000)
+        ));
+        log_handler.exists_log_containing(&format!(
+            "INFO: {test_name}: Holding 1 received payment(s) until 10 confirmations are reached"
+        ));
+    }
+
     #[test]
     #[should_panic(expected = "Attempt to set new start block to 7890123 failed due to: \
     DatabaseError(\"Fatigue\")")]
@@ -3215,6 +3527,9 @@ mod tests {
             .build();
         let receivables = vec![BlockchainTransaction {
             block_number: 4578910,
+            block_hash: H256::from_low_u64_be(4578910),
+            transaction_hash: H256::from_low_u64_be(3),
+            log_index: 0,
             from: make_wallet("abc"),
             wei_amount: 45_780,
         }];
@@ -3222,6 +3537,7 @@ mod tests {
             timestamp: now,
             payments: receivables,
             new_start_block: 7890123,
+            scanned_blocks_opt: None,
             response_skeleton_opt: None,
         };
         // Not necessary, rather for preciseness
@@ -3259,6 +3575,9 @@ mod tests {
             .build();
         let receivables = vec![BlockchainTransaction {
             block_number: 4578910,
+            block_hash: H256::from_low_u64_be(4578910),
+            transaction_hash: H256::from_low_u64_be(4),
+            log_index: 0,
             from: make_wallet("abc"),
             wei_amount: 45_780,
         }];
@@ -3266,6 +3585,7 @@ mod tests {
             timestamp: now,
             payments: receivables,
             new_start_block: 7890123,
+            scanned_blocks_opt: None,
             response_skeleton_opt: None,
         };
         // Not necessary, rather for preciseness
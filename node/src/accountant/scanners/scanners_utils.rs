@@ -307,10 +307,12 @@ pub mod payable_scanner_utils {
 }
 
 pub mod pending_payable_scanner_utils {
+    use crate::accountant::db_access_objects::pending_payable_dao::PendingPayableFeeBump;
     use crate::accountant::PendingPayableId;
     use crate::blockchain::blockchain_bridge::PendingPayableFingerprint;
+    use crate::sub_lib::accountant::FeeBumping;
     use masq_lib::logger::Logger;
-    use std::time::SystemTime;
+    use std::time::{Duration, SystemTime};
 
     #[derive(Debug, Default, PartialEq, Eq, Clone)]
     pub struct PendingPayableScanReport {
@@ -397,13 +399,62 @@ pub mod pending_payable_scanner_utils {
         scan_report.failures.push(fingerprint.into());
         scan_report
     }
+
+    // A transaction is stuck if nothing has happened to it, neither the original sending nor
+    // a previous bump, for longer than the configured time. A forced selection, requested by
+    // the user, doesn't wait for that; the limit on the number of bumps applies always.
+    // A replacement starts the waiting over, so it's measured from the latest bump if there is one
+    pub fn last_activity(
+        fingerprint: &PendingPayableFingerprint,
+        fee_bumps: &[PendingPayableFeeBump],
+    ) -> SystemTime {
+        fee_bumps
+            .iter()
+            .filter(|bump| bump.pending_payable_rowid == fingerprint.rowid)
+            .map(|bump| bump.timestamp)
+            .max()
+            .unwrap_or(fingerprint.timestamp)
+    }
+
+    pub fn select_stuck_transactions(
+        fingerprints: &[PendingPayableFingerprint],
+        fee_bumps: &[PendingPayableFeeBump],
+        fee_bumping: &FeeBumping,
+        now: SystemTime,
+        forced: bool,
+    ) -> Vec<PendingPayableId> {
+        let bump_after = Duration::from_secs(fee_bumping.bump_after_sec);
+        fingerprints
+            .iter()
+            .filter(|fingerprint| {
+                let previous_bumps = fee_bumps
+                    .iter()
+                    .filter(|bump| bump.pending_payable_rowid == fingerprint.rowid)
+                    .collect::<Vec<_>>();
+                if previous_bumps.len() as u64 >= fee_bumping.max_bumps {
+                    return false;
+                }
+                forced
+                    || now
+                        .duration_since(last_activity(fingerprint, fee_bumps))
+                        .map(|idle| idle >= bump_after)
+                        .unwrap_or(false)
+            })
+            .map(|fingerprint| PendingPayableId::new(fingerprint.rowid, fingerprint.hash))
+            .collect()
+    }
 }
 
 pub mod receivable_scanner_utils {
-    use crate::accountant::db_access_objects::receivable_dao::ReceivableAccount;
-    use crate::accountant::wei_to_gwei;
+    use crate::accountant::db_access_objects::receivable_dao::{
+        PendingReceivable, ReceivableAccount, ReceivablesReconciliation,
+    };
+    use crate::accountant::{wei_to_gwei, ScannedBlocks};
+    use crate::blockchain::blockchain_interface::data_structures::BlockchainTransaction;
+    use std::collections::{HashMap, HashSet};
     use std::time::{Duration, SystemTime};
     use thousands::Separable;
+    use web3::types::H256;
 
     pub fn balance_and_age(time: SystemTime, account: &ReceivableAccount) -> (String, Duration) {
         let balance = wei_to_gwei::<i64, i128>(account.balance_wei).separate_with_commas();
@@ -412,13 +463,135 @@ pub mod receivable_scanner_utils {
             .unwrap_or_else(|_| Duration::new(0, 0));
         (balance, age)
     }
+
+    // Compares the payments retained from earlier scans with what the latest scan found on the
+    // chain. Returns the changes to be made and the lowest block still needing to be watched.
+    pub fn reconcile_received_payments(
+        retained: Vec<PendingReceivable>,
+        scanned_payments: &[BlockchainTransaction],
+        scanned_blocks_opt: Option<ScannedBlocks>,
+        confirmation_depth: u64,
+    ) -> (ReceivablesReconciliation, Option<u64>) {
+        let is_confirmed = |block_number: u64| match scanned_blocks_opt {
+            Some(scanned_blocks) => block_number + confirmation_depth <= scanned_blocks.latest,
+            None => false,
+        };
+        // Credited payments stay watched for one more confirmation depth before we let them go
+        let is_buried = |block_number: u64| match scanned_blocks_opt {
+            Some(scanned_blocks) => block_number + 2 * confirmation_depth <= scanned_blocks.latest,
+            None => false,
+        };
+        let is_verifiable = |block_number: u64| match scanned_blocks_opt {
+            Some(scanned_blocks) => {
+                scanned_blocks.from <= block_number && block_number <= scanned_blocks.to
+            }
+            None => false,
+        };
+        let scanned_by_key: HashMap<(H256, u64), &BlockchainTransaction> = scanned_payments
+            .iter()
+            .map(|payment| (log_key(payment), payment))
+            .collect();
+        let mut watched_blocks: HashMap<(H256, u64), u64> = retained
+            .iter()
+            .map(|pending| {
+                (
+                    log_key(&pending.transaction),
+                    pending.transaction.block_number,
+                )
+            })
+            .collect();
+        let mut reconciliation = ReceivablesReconciliation::default();
+
+        let mut unchanged_keys = HashSet::new();
+        retained.into_iter().for_each(|pending| {
+            let key = log_key(&pending.transaction);
+            let block_number = pending.transaction.block_number;
+            match scanned_by_key.get(&key) {
+                Some(payment) if payment.block_hash == pending.transaction.block_hash => {
+                    unchanged_keys.insert(key);
+                    if !pending.credited && is_confirmed(block_number) {
+                        reconciliation.to_credit.push(pending.transaction.clone())
+                    }
+                    if is_buried(block_number) && (pending.credited || is_confirmed(block_number)) {
+                        reconciliation.to_release.push(pending.transaction)
+                    }
+                }
+                // Moved into another block; if not credited yet, the new record just replaces it
+                Some(_) if pending.credited => {
+                    reconciliation.to_roll_back.push(pending.transaction)
+                }
+                Some(_) => (),
+                None if !is_verifiable(block_number) => (),
+                None if pending.credited => reconciliation.to_roll_back.push(pending.transaction),
+                None => reconciliation.to_release.push(pending.transaction),
+            }
+        });
+
+        scanned_payments
+            .iter()
+            .filter(|payment| !unchanged_keys.contains(&log_key(payment)))
+            .for_each(|payment| {
+                if is_confirmed(payment.block_number) {
+                    reconciliation.to_credit.push(payment.clone());
+                    if is_buried(payment.block_number) {
+                        reconciliation.to_release.push(payment.clone())
+                    }
+                } else {
+                    reconciliation.to_hold.push(payment.clone())
+                }
+            });
+
+        reconciliation.to_roll_back.iter().for_each(|payment| {
+            watched_blocks.remove(&log_key(payment));
+        });
+        reconciliation
+            .to_credit
+            .iter()
+            .chain(reconciliation.to_hold.iter())
+            .for_each(|payment| {
+                watched_blocks.insert(log_key(payment), payment.block_number);
+            });
+        reconciliation.to_release.iter().for_each(|payment| {
+            watched_blocks.remove(&log_key(payment));
+        });
+
+        (reconciliation, watched_blocks.values().min().copied())
+    }
+
+    // Blocks still below the confirmation depth are scanned again next time so that a payment
+    // brought in by a reorganization is not missed
+    pub fn start_block_after_reconciliation(
+        new_start_block: u64,
+        scanned_blocks_opt: Option<ScannedBlocks>,
+        confirmation_depth: u64,
+        lowest_watched_block_opt: Option<u64>,
+    ) -> u64 {
+        let start_block = match scanned_blocks_opt {
+            Some(scanned_blocks) => new_start_block.min(scanned_blocks.to + 1).min(
+                scanned_blocks
+                    .from
+                    .max((scanned_blocks.latest + 1).saturating_sub(confirmation_depth)),
+            ),
+            None => new_start_block,
+        };
+        match lowest_watched_block_opt {
+            Some(lowest_watched_block) => start_block.min(lowest_watched_block),
+            None => start_block,
+        }
+    }
+
+    fn log_key(payment: &BlockchainTransaction) -> (H256, u64) {
+        (payment.transaction_hash, payment.log_index)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::accountant::db_access_objects::utils::{from_time_t, to_time_t};
     use crate::accountant::db_access_objects::payable_dao::{PayableAccount};
-    use crate::accountant::db_access_objects::receivable_dao::ReceivableAccount;
+    use crate::accountant::db_access_objects::receivable_dao::{
+        PendingReceivable, ReceivableAccount, ReceivablesReconciliation,
+    };
     use crate::accountant::scanners::scanners_utils::payable_scanner_utils::PayableTransactingErrorEnum::{
         LocallyCausedError, RemotelyCausedErrors,
     };
@@ -427,18 +600,136 @@ mod tests {
         payables_debug_summary, separate_errors, PayableThresholdsGauge,
         PayableThresholdsGaugeReal,
     };
-    use crate::accountant::scanners::scanners_utils::receivable_scanner_utils::balance_and_age;
-    use crate::accountant::{checked_conversion, gwei_to_wei, SentPayables};
+    use crate::accountant::scanners::scanners_utils::pending_payable_scanner_utils::select_stuck_transactions;
+    use crate::accountant::scanners::scanners_utils::receivable_scanner_utils::{
+        balance_and_age, reconcile_received_payments, start_block_after_reconciliation,
+    };
+    use crate::accountant::{
+        checked_conversion, gwei_to_wei, PendingPayableId, ScannedBlocks, SentPayables,
+    };
+    use crate::blockchain::blockchain_bridge::PendingPayableFingerprint;
     use crate::blockchain::test_utils::make_tx_hash;
-    use crate::sub_lib::accountant::PaymentThresholds;
+    use crate::sub_lib::accountant::{FeeBumping, PaymentThresholds};
     use crate::test_utils::make_wallet;
     use masq_lib::constants::WEIS_IN_GWEI;
     use masq_lib::logger::Logger;
     use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
-    use std::time::SystemTime;
-    use crate::accountant::db_access_objects::pending_payable_dao::PendingPayable;
+    use std::time::{Duration, SystemTime};
+    use crate::accountant::db_access_objects::pending_payable_dao::{PendingPayable, PendingPayableFeeBump};
     use crate::blockchain::blockchain_interface::data_structures::errors::{BlockchainError, PayableTransactionError};
-    use crate::blockchain::blockchain_interface::data_structures::{RpcPayablesFailure};
+    use crate::blockchain::blockchain_interface::data_structures::{BlockchainTransaction, RpcPayablesFailure};
+    use web3::types::H256;
+
+    fn make_fingerprint_for_bumping(
+        rowid: u64,
+        age_sec: u64,
+        now: SystemTime,
+    ) -> PendingPayableFingerprint {
+        PendingPayableFingerprint {
+            rowid,
+            timestamp: now.checked_sub(Duration::from_secs(age_sec)).unwrap(),
+            hash: make_tx_hash(rowid as u32),
+            attempt: 3,
+            amount: 1_000_000,
+            process_error: None,
+        }
+    }
+
+    fn make_fee_bump(rowid: u64, age_sec: u64, now: SystemTime) -> PendingPayableFeeBump {
+        PendingPayableFeeBump {
+            pending_payable_rowid: rowid,
+            replaced_hash: make_tx_hash(rowid as u32),
+            replacement_hash: make_tx_hash(rowid as u32 + 1000),
+            gas_price_wei: 1_200_000_000,
+            timestamp: now.checked_sub(Duration::from_secs(age_sec)).unwrap(),
+        }
+    }
+
+    #[test]
+    fn select_stuck_transactions_picks_those_idle_for_long_enough() {
+        let now = SystemTime::now();
+        let fee_bumping = FeeBumping {
+            bump_after_sec: 600,
+            gas_price_increase_percent: 20,
+            max_bumps: 2,
+        };
+        let fresh = make_fingerprint_for_bumping(1, 599, now);
+        let old = make_fingerprint_for_bumping(2, 600, now);
+        let old_but_recently_bumped = make_fingerprint_for_bumping(3, 5000, now);
+        let old_and_bumped_long_ago = make_fingerprint_for_bumping(4, 5000, now);
+        let old_and_bumped_out = make_fingerprint_for_bumping(5, 5000, now);
+        let fee_bumps = vec![
+            make_fee_bump(3, 100, now),
+            make_fee_bump(4, 700, now),
+            make_fee_bump(5, 3000, now),
+            make_fee_bump(5, 2000, now),
+        ];
+
+        let result = select_stuck_transactions(
+            &[
+                fresh,
+                old.clone(),
+                old_but_recently_bumped,
+                old_and_bumped_long_ago.clone(),
+                old_and_bumped_out,
+            ],
+            &fee_bumps,
+            &fee_bumping,
+            now,
+            false,
+        );
+
+        assert_eq!(
+            result,
+            vec![
+                PendingPayableId::new(old.rowid, old.hash),
+                PendingPayableId::new(old_and_bumped_long_ago.rowid, old_and_bumped_long_ago.hash)
+            ]
+        )
+    }
+
+    #[test]
+    fn forced_select_stuck_transactions_ignores_timing_but_not_the_limit_of_bumps() {
+        let now = SystemTime::now();
+        let fee_bumping = FeeBumping {
+            bump_after_sec: 600,
+            gas_price_increase_percent: 20,
+            max_bumps: 1,
+        };
+        let fresh = make_fingerprint_for_bumping(1, 5, now);
+        let bumped_out = make_fingerprint_for_bumping(2, 5000, now);
+        let fee_bumps = vec![make_fee_bump(2, 3000, now)];
+
+        let result = select_stuck_transactions(
+            &[fresh.clone(), bumped_out],
+            &fee_bumps,
+            &fee_bumping,
+            now,
+            true,
+        );
+
+        assert_eq!(result, vec![PendingPayableId::new(fresh.rowid, fresh.hash)])
+    }
+
+    #[test]
+    fn select_stuck_transactions_finds_nothing_if_bumping_is_off() {
+        let now = SystemTime::now();
+        let fee_bumping = FeeBumping {
+            bump_after_sec: 600,
+            gas_price_increase_percent: 20,
+            max_bumps: 0,
+        };
+
+        let result = select_stuck_transactions(
+            &[make_fingerprint_for_bumping(1, 5000, now)],
+            &[],
+            &fee_bumping,
+            now,
+            true,
+        );
+
+        assert_eq!(result, vec![])
+    }
 
     #[test]
     fn investigate_debt_extremes_picks_the_most_relevant_records() {
@@ -814,4 +1105,212 @@ mod tests {
             "Got 0 properly sent payables of an unknown number of attempts"
         )
     }
+
+    fn make_received_payment(id: u64, block_number: u64, block_hash: u64) -> BlockchainTransaction {
+        BlockchainTransaction {
+            block_number,
+            block_hash: H256::from_low_u64_be(block_hash),
+            transaction_hash: H256::from_low_u64_be(id),
+            log_index: 0,
+            from: make_wallet(&format!("debtor_{}", id)),
+            wei_amount: id as u128 * 1_000,
+        }
+    }
+
+    fn pending(transaction: &BlockchainTransaction, credited: bool) -> PendingReceivable {
+        PendingReceivable {
+            transaction: transaction.clone(),
+            credited,
+        }
+    }
+
+    #[test]
+    fn reconcile_received_payments_holds_shallow_payments_and_credits_deep_enough_ones() {
+        let scanned_blocks = ScannedBlocks {
+            from: 100,
+            to: 120,
+            latest: 120,
+        };
+        let fresh_shallow = make_received_payment(1, 115, 115);
+        let fresh_confirmed = make_received_payment(2, 105, 105);
+        let held_now_confirmed = make_received_payment(3, 110, 110);
+        let held_still_shallow = make_received_payment(4, 112, 112);
+        let retained = vec![
+            pending(&held_now_confirmed, false),
+            pending(&held_still_shallow, false),
+        ];
+        let scanned = vec![
+            fresh_shallow.clone(),
+            fresh_confirmed.clone(),
+            held_now_confirmed.clone(),
+            held_still_shallow.clone(),
+        ];
+
+        let (reconciliation, lowest_watched_block_opt) =
+            reconcile_received_payments(retained, &scanned, Some(scanned_blocks), 10);
+
+        assert_eq!(
+            reconciliation,
+            ReceivablesReconciliation {
+                to_credit: vec![held_now_confirmed, fresh_confirmed],
+                to_roll_back: vec![],
+                to_hold: vec![fresh_shallow],
+                to_release: vec![],
+            }
+        );
+        assert_eq!(lowest_watched_block_opt, Some(105))
+    }
+
+    #[test]
+    fn reconcile_received_payments_rolls_back_credited_payments_lost_in_reorganization() {
+        let scanned_blocks = ScannedBlocks {
+            from: 100,
+            to: 120,
+            latest: 125,
+        };
+        let credited_and_vanished = make_received_payment(1, 108, 108);
+        let credited_original = make_received_payment(2, 109, 109);
+        let credited_moved = make_received_payment(2, 111, 5555);
+        let pending_and_vanished = make_received_payment(3, 117, 117);
+        let pending_original = make_received_payment(4, 118, 118);
+        let pending_moved = make_received_payment(4, 119, 6666);
+        let retained = vec![
+            pending(&credited_and_vanished, true),
+            pending(&credited_original, true),
+            pending(&pending_and_vanished, false),
+            pending(&pending_original, false),
+        ];
+        let scanned = vec![credited_moved.clone(), pending_moved.clone()];
+
+        let (reconciliation, lowest_watched_block_opt) =
+            reconcile_received_payments(retained, &scanned, Some(scanned_blocks), 10);
+
+        assert_eq!(
+            reconciliation,
+            ReceivablesReconciliation {
+                to_credit: vec![credited_moved],
+                to_roll_back: vec![credited_and_vanished, credited_original],
+                to_hold: vec![pending_moved],
+                to_release: vec![pending_and_vanished],
+            }
+        );
+        assert_eq!(lowest_watched_block_opt, Some(111))
+    }
+
+    #[test]
+    fn reconcile_received_payments_releases_buried_payments_and_leaves_unverifiable_ones_alone() {
+        let scanned_blocks = ScannedBlocks {
+            from: 100,
+            to: 120,
+            latest: 130,
+        };
+        let credited_and_buried = make_received_payment(1, 105, 105);
+        let credited_still_watched = make_received_payment(2, 115, 115);
+        let pending_beyond_scanned_range = make_received_payment(3, 125, 125);
+        let found_already_buried = make_received_payment(4, 101, 101);
+        let retained = vec![
+            pending(&credited_and_buried, true),
+            pending(&credited_still_watched, true),
+            pending(&pending_beyond_scanned_range, false),
+        ];
+        let scanned = vec![
+            credited_and_buried.clone(),
+            credited_still_watched,
+            found_already_buried.clone(),
+        ];
+
+        let (reconciliation, lowest_watched_block_opt) =
+            reconcile_received_payments(retained, &scanned, Some(scanned_blocks), 10);
+
+        assert_eq!(
+            reconciliation,
+            ReceivablesReconciliation {
+                to_credit: vec![found_already_buried.clone()],
+                to_roll_back: vec![],
+                to_hold: vec![],
+                to_release: vec![credited_and_buried, found_already_buried],
+            }
+        );
+        assert_eq!(lowest_watched_block_opt, Some(115))
+    }
+
+    #[test]
+    fn reconcile_received_payments_holds_everything_if_the_scanned_range_is_unknown() {
+        let held = make_received_payment(1, 105, 105);
+        let fresh = make_received_payment(2, 99, 99);
+        let retained = vec![pending(&held, false)];
+
+        let (reconciliation, lowest_watched_block_opt) =
+            reconcile_received_payments(retained, &[fresh.clone()], None, 10);
+
+        assert_eq!(
+            reconciliation,
+            ReceivablesReconciliation {
+                to_credit: vec![],
+                to_roll_back: vec![],
+                to_hold: vec![fresh],
+                to_release: vec![],
+            }
+        );
+        assert_eq!(lowest_watched_block_opt, Some(99))
+    }
+
+    #[test]
+    fn start_block_after_reconciliation_keeps_unconfirmed_blocks_in_the_next_scan() {
+        let scanned_blocks = ScannedBlocks {
+            from: 100,
+            to: 120,
+            latest: 120,
+        };
+
+        let result = start_block_after_reconciliation(121, Some(scanned_blocks), 10, None);
+
+        assert_eq!(result, 111)
+    }
+
+    #[test]
+    fn start_block_after_reconciliation_goes_back_to_the_lowest_watched_block() {
+        let scanned_blocks = ScannedBlocks {
+            from: 100,
+            to: 120,
+            latest: 120,
+        };
+
+        let result = start_block_after_reconciliation(121, Some(scanned_blocks), 10, Some(104));
+
+        assert_eq!(result, 104)
+    }
+
+    #[test]
+    fn start_block_after_reconciliation_does_not_skip_blocks_beyond_the_scanned_range() {
+        let scanned_blocks = ScannedBlocks {
+            from: 100,
+            to: 150,
+            latest: 1000,
+        };
+
+        let result = start_block_after_reconciliation(1001, Some(scanned_blocks), 10, None);
+
+        assert_eq!(result, 151)
+    }
+
+    #[test]
+    fn start_block_after_reconciliation_never_goes_below_the_previous_start_block() {
+        let scanned_blocks = ScannedBlocks {
+            from: 118,
+            to: 120,
+            latest: 120,
+        };
+
+        let result = start_block_after_reconciliation(121, Some(scanned_blocks), 10, None);
+
+        assert_eq!(result, 118)
+    }
+
+    #[test]
+    fn start_block_after_reconciliation_without_scanned_range_relies_on_watched_blocks() {
+        let result = start_block_after_reconciliation(121, None, 10, Some(99));
+
+        assert_eq!(result, 99)
+    }
 }
@@ -0,0 +1,395 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::sub_lib::accountant::SpendingBudget;
+use crate::sub_lib::proxy_server::SpendingBudgetStatusMsg;
+use masq_lib::constants::WEIS_IN_GWEI;
+use masq_lib::logger::Logger;
+use masq_lib::messages::UiSpendingBudget;
+use std::time::SystemTime;
+use time::{Date, Month, OffsetDateTime};
+
+// Keeps the charges for consumed services per calendar day and month (UTC) and compares them
+// with the user's budget. The sums live in memory only, so they start over when the Node restarts.
+pub struct SpendingTracker {
+    budget: SpendingBudget,
+    daily: PeriodSpending,
+    monthly: PeriodSpending,
+    exhausted_until_opt: Option<SystemTime>,
+    logger: Logger,
+}
+
+impl SpendingTracker {
+    pub fn new(budget: SpendingBudget, now: SystemTime) -> Self {
+        let date = OffsetDateTime::from(now).date();
+        Self {
+            budget,
+            daily: PeriodSpending::new(Period::Day, date),
+            monthly: PeriodSpending::new(Period::Month, date),
+            exhausted_until_opt: None,
+            logger: Logger::new("SpendingTracker"),
+        }
+    }
+
+    pub fn budget(&self) -> SpendingBudget {
+        self.budget
+    }
+
+    // Returns a message for the ProxyServer only if the exhaustion status has changed
+    pub fn update_budget(
+        &mut self,
+        budget: SpendingBudget,
+        now: SystemTime,
+    ) -> Option<SpendingBudgetStatusMsg> {
+        self.budget = budget;
+        self.daily.warned_level = 0;
+        self.monthly.warned_level = 0;
+        self.record_spending(0, now)
+    }
+
+    pub fn record_spending(
+        &mut self,
+        charge_wei: u128,
+        now: SystemTime,
+    ) -> Option<SpendingBudgetStatusMsg> {
+        let date = OffsetDateTime::from(now).date();
+        let daily_limit_gwei = self.budget.daily_gwei;
+        let monthly_limit_gwei = self.budget.monthly_gwei;
+        self.daily.add(charge_wei, date);
+        self.monthly.add(charge_wei, date);
+        let warning_percents = [
+            self.budget.first_warning_percent,
+            self.budget.second_warning_percent,
+        ];
+        self.daily
+            .warn_if_crossing(daily_limit_gwei, warning_percents, &self.logger);
+        self.monthly
+            .warn_if_crossing(monthly_limit_gwei, warning_percents, &self.logger);
+        let exhausted_until_opt = [
+            self.daily.exhausted_until_opt(daily_limit_gwei),
+            self.monthly.exhausted_until_opt(monthly_limit_gwei),
+        ]
+        .into_iter()
+        .flatten()
+        .max();
+        if exhausted_until_opt == self.exhausted_until_opt {
+            return None;
+        }
+        match exhausted_until_opt {
+            Some(until) => warning!(
+                self.logger,
+                "Spending budget {} exhausted ({} wei today, {} wei this month); refusing new streams until {}",
+                self.budget,
+                self.daily.spent_wei,
+                self.monthly.spent_wei,
+                OffsetDateTime::from(until)
+            ),
+            None => info!(
+                self.logger,
+                "Spending budget {} available again", self.budget
+            ),
+        }
+        self.exhausted_until_opt = exhausted_until_opt;
+        Some(SpendingBudgetStatusMsg {
+            exhausted_until_opt,
+        })
+    }
+
+    pub fn report(&self, now: SystemTime) -> UiSpendingBudget {
+        let date = OffsetDateTime::from(now).date();
+        let to_gwei = |wei: u128| u64::try_from(wei / WEIS_IN_GWEI as u128).unwrap_or(u64::MAX);
+        UiSpendingBudget {
+            daily_limit_gwei: self.budget.daily_gwei,
+            daily_spent_gwei: to_gwei(self.daily.spent_wei_on(date)),
+            monthly_limit_gwei: self.budget.monthly_gwei,
+            monthly_spent_gwei: to_gwei(self.monthly.spent_wei_on(date)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Period {
+    Day,
+    Month,
+}
+
+struct PeriodSpending {
+    period: Period,
+    start: Date,
+    spent_wei: u128,
+    warned_level: usize,
+}
+
+impl PeriodSpending {
+    fn new(period: Period, date: Date) -> Self {
+        Self {
+            period,
+            start: Self::start_of(period, date),
+            spent_wei: 0,
+            warned_level: 0,
+        }
+    }
+
+    fn add(&mut self, charge_wei: u128, date: Date) {
+        self.roll_over(date);
+        self.spent_wei = self.spent_wei.saturating_add(charge_wei);
+    }
+
+    fn roll_over(&mut self, date: Date) {
+        let start = Self::start_of(self.period, date);
+        if start != self.start {
+            *self = Self::new(self.period, date)
+        }
+    }
+
+    fn spent_wei_on(&self, date: Date) -> u128 {
+        if Self::start_of(self.period, date) == self.start {
+            self.spent_wei
+        } else {
+            0
+        }
+    }
+
+    fn reached(&self, limit_gwei: u64, percent: u64) -> bool {
+        self.spent_wei.saturating_mul(100)
+            >= (limit_gwei as u128)
+                .saturating_mul(WEIS_IN_GWEI as u128)
+                .saturating_mul(percent as u128)
+    }
+
+    fn warn_if_crossing(&mut self, limit_gwei: u64, warning_percents: [u64; 2], logger: &Logger) {
+        if limit_gwei == 0 {
+            return;
+        }
+        let level = warning_percents
+            .iter()
+            .filter(|percent| self.reached(limit_gwei, **percent))
+            .count();
+        if level > self.warned_level {
+            warning!(
+                logger,
+                "Spending has reached {}% of the {} budget: {} of {} gwei",
+                warning_percents[level - 1],
+                self.name(),
+                self.spent_wei / WEIS_IN_GWEI as u128,
+                limit_gwei
+            );
+        }
+        self.warned_level = self.warned_level.max(level);
+    }
+
+    fn exhausted_until_opt(&self, limit_gwei: u64) -> Option<SystemTime> {
+        if limit_gwei == 0 || !self.reached(limit_gwei, 100) {
+            return None;
+        }
+        let next_start = match self.period {
+            Period::Day => self.start.next_day(),
+            Period::Month => match self.start.month() {
+                Month::December => {
+                    Date::from_calendar_date(self.start.year() + 1, Month::January, 1).ok()
+                }
+                month => Date::from_calendar_date(self.start.year(), month.next(), 1).ok(),
+            },
+        }
+        .expect("Calendar ran out of dates");
+        Some(next_start.midnight().assume_utc().into())
+    }
+
+    fn name(&self) -> &'static str {
+        match self.period {
+            Period::Day => "daily",
+            Period::Month => "monthly",
+        }
+    }
+
+    fn start_of(period: Period, date: Date) -> Date {
+        match period {
+            Period::Day => date,
+            Period::Month => date.replace_day(1).expect("Every month has a first day"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
+    use std::time::Duration;
+
+    const GWEI: u128 = WEIS_IN_GWEI as u128;
+
+    // 14 Nov 2023 22:13:20 UTC
+    fn mid_november() -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000)
+    }
+
+    fn utc(year: i32, month: Month, day: u8) -> SystemTime {
+        Date::from_calendar_date(year, month, day)
+            .unwrap()
+            .midnight()
+            .assume_utc()
+            .into()
+    }
+
+    fn budget(daily_gwei: u64, monthly_gwei: u64) -> SpendingBudget {
+        SpendingBudget {
+            daily_gwei,
+            monthly_gwei,
+            first_warning_percent: 50,
+            second_warning_percent: 90,
+        }
+    }
+
+    #[test]
+    fn unlimited_budget_is_never_exhausted() {
+        let mut subject = SpendingTracker::new(budget(0, 0), mid_november());
+
+        let result = subject.record_spending(u128::MAX / 2, mid_november());
+
+        assert_eq!(result, None);
+        assert_eq!(
+            subject.report(mid_november()),
+            UiSpendingBudget {
+                daily_limit_gwei: 0,
+                daily_spent_gwei: u64::MAX,
+                monthly_limit_gwei: 0,
+                monthly_spent_gwei: u64::MAX,
+            }
+        );
+    }
+
+    #[test]
+    fn exhausting_the_daily_budget_blocks_until_the_next_utc_midnight() {
+        let mut subject = SpendingTracker::new(budget(1_000, 0), mid_november());
+
+        let below = subject.record_spending(999 * GWEI, mid_november());
+        let reached = subject.record_spending(GWEI, mid_november());
+        let again = subject.record_spending(GWEI, mid_november());
+
+        assert_eq!(below, None);
+        assert_eq!(
+            reached,
+            Some(SpendingBudgetStatusMsg {
+                exhausted_until_opt: Some(utc(2023, Month::November, 15))
+            })
+        );
+        assert_eq!(again, None);
+    }
+
+    #[test]
+    fn new_day_lifts_the_daily_block_but_the_monthly_sum_carries_on() {
+        let next_day = mid_november() + Duration::from_secs(86_400);
+        let mut subject = SpendingTracker::new(budget(1_000, 5_000), mid_november());
+        subject.record_spending(1_000 * GWEI, mid_november());
+
+        let result = subject.record_spending(10 * GWEI, next_day);
+
+        assert_eq!(
+            result,
+            Some(SpendingBudgetStatusMsg {
+                exhausted_until_opt: None
+            })
+        );
+        assert_eq!(
+            subject.report(next_day),
+            UiSpendingBudget {
+                daily_limit_gwei: 1_000,
+                daily_spent_gwei: 10,
+                monthly_limit_gwei: 5_000,
+                monthly_spent_gwei: 1_010,
+            }
+        );
+    }
+
+    #[test]
+    fn exhausted_monthly_budget_outlasts_the_daily_one_and_december_rolls_into_january() {
+        let december = SystemTime::UNIX_EPOCH + Duration::from_secs(1_702_598_400); // 15 Dec 2023
+        let mut subject = SpendingTracker::new(budget(1_000, 1_000), december);
+
+        let result = subject.record_spending(1_000 * GWEI, december);
+
+        assert_eq!(
+            result,
+            Some(SpendingBudgetStatusMsg {
+                exhausted_until_opt: Some(utc(2024, Month::January, 1))
+            })
+        );
+    }
+
+    #[test]
+    fn raising_the_budget_lifts_the_block_and_lowering_it_can_impose_one() {
+        let mut subject = SpendingTracker::new(budget(1_000, 0), mid_november());
+        subject.record_spending(1_000 * GWEI, mid_november());
+
+        let raised = subject.update_budget(budget(2_000, 0), mid_november());
+        let lowered = subject.update_budget(budget(0, 500), mid_november());
+
+        assert_eq!(
+            raised,
+            Some(SpendingBudgetStatusMsg {
+                exhausted_until_opt: None
+            })
+        );
+        assert_eq!(
+            lowered,
+            Some(SpendingBudgetStatusMsg {
+                exhausted_until_opt: Some(utc(2023, Month::December, 1))
+            })
+        );
+        assert_eq!(subject.budget(), budget(0, 500));
+    }
+
+    #[test]
+    fn warnings_are_logged_once_per_level_and_period() {
+        init_test_logging();
+        let test_name = "warnings_are_logged_once_per_level_and_period";
+        let mut subject = SpendingTracker::new(budget(1_000, 0), mid_november());
+        subject.logger = Logger::new(test_name);
+
+        subject.record_spending(400 * GWEI, mid_november());
+        subject.record_spending(100 * GWEI, mid_november());
+        subject.record_spending(100 * GWEI, mid_november());
+        subject.record_spending(350 * GWEI, mid_november());
+
+        let tlh = TestLogHandler::new();
+        tlh.assert_logs_contain_in_order(vec![
+            &format!(
+                "WARN: {}: Spending has reached 50% of the daily budget: 500 of 1000 gwei",
+                test_name
+            ),
+            &format!(
+                "WARN: {}: Spending has reached 90% of the daily budget: 950 of 1000 gwei",
+                test_name
+            ),
+        ]);
+        tlh.exists_no_log_containing(&format!(
+            "{}: Spending has reached 50% of the daily budget: 600",
+            test_name
+        ));
+        tlh.exists_no_log_containing(&format!(
+            "{}: Spending has reached 50% of the monthly",
+            test_name
+        ));
+    }
+
+    #[test]
+    fn exhaustion_is_logged_with_its_end() {
+        init_test_logging();
+        let test_name = "exhaustion_is_logged_with_its_end";
+        let mut subject = SpendingTracker::new(budget(1_000, 0), mid_november());
+        subject.logger = Logger::new(test_name);
+
+        subject.record_spending(1_000 * GWEI, mid_november());
+        subject.update_budget(budget(0, 0), mid_november());
+
+        let tlh = TestLogHandler::new();
+        tlh.exists_log_containing(&format!(
+            "WARN: {}: Spending budget 1000|0|50|90 exhausted (1000000000000 wei today, 1000000000000 wei \
+            this month); refusing new streams until 2023-11-15 0:00:00.0 +00:00:00",
+            test_name
+        ));
+        tlh.exists_log_containing(&format!(
+            "INFO: {}: Spending budget 0|0|50|90 available again",
+            test_name
+        ));
+    }
+}
@@ -3,14 +3,27 @@
 #![cfg(test)]
 
 use crate::accountant::db_access_objects::banned_dao::{BannedDao, BannedDaoFactory};
+use crate::accountant::db_access_objects::financial_history_dao::{
+    FinancialHistoryDao, FinancialHistoryDaoError, FinancialHistoryDaoFactory,
+    FinancialHistoryRecord, HistoryDirection, HistoryService,
+};
+use crate::accountant::db_access_objects::funds_transfer_dao::{
+    FundsTransferDao, FundsTransferDaoError, FundsTransferRecord,
+};
+use crate::accountant::db_access_objects::ledger_adjustment_dao::{
+    BalanceChange, LedgerAdjustment, LedgerAdjustmentDao, LedgerAdjustmentDaoError,
+    LedgerAdjustmentDaoFactory, UncommittedBalanceChange,
+};
 use crate::accountant::db_access_objects::payable_dao::{
     PayableAccount, PayableDao, PayableDaoError, PayableDaoFactory,
 };
 use crate::accountant::db_access_objects::pending_payable_dao::{
-    PendingPayableDao, PendingPayableDaoError, PendingPayableDaoFactory, TransactionHashes,
+    PendingPayableDao, PendingPayableDaoError, PendingPayableDaoFactory, PendingPayableFeeBump,
+    StuckTransaction, TransactionHashes,
 };
 use crate::accountant::db_access_objects::receivable_dao::{
-    ReceivableAccount, ReceivableDao, ReceivableDaoError, ReceivableDaoFactory,
+    PendingReceivable, ReceivableAccount, ReceivableDao, ReceivableDaoError, ReceivableDaoFactory,
+    ReceivablesReconciliation,
 };
 use crate::accountant::db_access_objects::utils::{from_time_t, to_time_t, CustomQuery};
 use crate::accountant::payment_adjuster::{Adjustment, AnalysisError, PaymentAdjuster};
@@ -26,13 +39,15 @@ use crate::accountant::scanners::{
     ReceivableScanner, ScanSchedulers, Scanner,
 };
 use crate::accountant::{
-    gwei_to_wei, Accountant, ResponseSkeleton, SentPayables, DEFAULT_PENDING_TOO_LONG_SEC,
+    gwei_to_wei, Accountant, PendingPayableId, ResponseSkeleton, SentPayables,
+    DEFAULT_PENDING_TOO_LONG_SEC,
 };
 use crate::blockchain::blockchain_bridge::PendingPayableFingerprint;
 use crate::blockchain::blockchain_interface::data_structures::BlockchainTransaction;
 use crate::blockchain::test_utils::make_tx_hash;
 use crate::bootstrapper::BootstrapperConfig;
 use crate::database::rusqlite_wrappers::TransactionSafeWrapper;
+use crate::database::test_utils::transaction_wrapper_mock::TransactionInnerWrapperMockBuilder;
 use crate::db_config::config_dao::{ConfigDao, ConfigDaoFactory};
 use crate::db_config::mocks::ConfigDaoMock;
 use crate::sub_lib::accountant::{DaoFactories, FinancialStatistics};
@@ -42,6 +57,7 @@ use crate::sub_lib::utils::NotifyLaterHandle;
 use crate::sub_lib::wallet::Wallet;
 use crate::test_utils::make_wallet;
 use crate::test_utils::persistent_configuration_mock::PersistentConfigurationMock;
+use crate::test_utils::unshared_test_utils::arbitrary_id_stamp::ArbitraryIdStamp;
 use crate::test_utils::unshared_test_utils::make_bc_with_defaults;
 use actix::{Message, System};
 use ethereum_types::H256;
@@ -103,6 +119,8 @@ pub struct AccountantBuilder {
     pending_payable_dao_factory_opt: Option<PendingPayableDaoFactoryMock>,
     banned_dao_factory_opt: Option<BannedDaoFactoryMock>,
     config_dao_factory_opt: Option<ConfigDaoFactoryMock>,
+    financial_history_dao_factory_opt: Option<FinancialHistoryDaoFactoryMock>,
+    ledger_adjustment_dao_factory_opt: Option<LedgerAdjustmentDaoFactoryMock>,
 }
 
 impl Default for AccountantBuilder {
@@ -116,6 +134,8 @@ impl Default for AccountantBuilder {
             pending_payable_dao_factory_opt: None,
             banned_dao_factory_opt: None,
             config_dao_factory_opt: None,
+            financial_history_dao_factory_opt: None,
+            ledger_adjustment_dao_factory_opt: None,
         }
     }
 }
@@ -262,6 +282,16 @@ const RECEIVABLE_DAOS_ACCOUNTANT_INITIALIZATION_ORDER: [DestinationMarker; 2] =
     DestinationMarker::ReceivableScanner,
 ];
 
+const BANNED_DAOS_ACCOUNTANT_INITIALIZATION_ORDER: [DestinationMarker; 2] = [
+    DestinationMarker::AccountantBody,
+    DestinationMarker::ReceivableScanner,
+];
+
+const CONFIG_DAOS_ACCOUNTANT_INITIALIZATION_ORDER: [DestinationMarker; 2] = [
+    DestinationMarker::AccountantBody,
+    DestinationMarker::ReceivableScanner,
+];
+
 impl AccountantBuilder {
     pub fn bootstrapper_config(mut self, config: BootstrapperConfig) -> Self {
         self.config_opt = Some(config);
@@ -320,22 +350,43 @@ impl AccountantBuilder {
         )
     }
 
-    //TODO this method seems to be never used?
-    pub fn banned_dao(mut self, banned_dao: BannedDaoMock) -> Self {
-        match self.banned_dao_factory_opt {
-            None => {
-                self.banned_dao_factory_opt =
-                    Some(BannedDaoFactoryMock::new().make_result(banned_dao))
-            }
-            Some(banned_dao_factory) => {
-                self.banned_dao_factory_opt = Some(banned_dao_factory.make_result(banned_dao))
-            }
-        }
+    pub fn banned_daos(
+        mut self,
+        specially_configured_daos: Vec<DaoWithDestination<BannedDaoMock>>,
+    ) -> Self {
+        create_or_update_factory!(
+            specially_configured_daos,
+            BANNED_DAOS_ACCOUNTANT_INITIALIZATION_ORDER,
+            banned_dao_factory_opt,
+            BannedDaoFactoryMock,
+            BannedDao,
+            self
+        )
+    }
+
+    pub fn config_daos(
+        mut self,
+        specially_configured_daos: Vec<DaoWithDestination<ConfigDaoMock>>,
+    ) -> Self {
+        create_or_update_factory!(
+            specially_configured_daos,
+            CONFIG_DAOS_ACCOUNTANT_INITIALIZATION_ORDER,
+            config_dao_factory_opt,
+            ConfigDaoFactoryMock,
+            ConfigDao,
+            self
+        )
+    }
+
+    pub fn financial_history_dao(mut self, financial_history_dao: FinancialHistoryDaoMock) -> Self {
+        self.financial_history_dao_factory_opt =
+            Some(FinancialHistoryDaoFactoryMock::new().make_result(financial_history_dao));
         self
     }
 
-    pub fn config_dao(mut self, config_dao: ConfigDaoMock) -> Self {
-        self.config_dao_factory_opt = Some(ConfigDaoFactoryMock::new().make_result(config_dao));
+    pub fn ledger_adjustment_dao(mut self, ledger_adjustment_dao: LedgerAdjustmentDaoMock) -> Self {
+        self.ledger_adjustment_dao_factory_opt =
+            Some(LedgerAdjustmentDaoFactoryMock::new().make_result(ledger_adjustment_dao));
         self
     }
 
@@ -358,12 +409,22 @@ impl AccountantBuilder {
                 .make_result(PendingPayableDaoMock::new())
                 .make_result(PendingPayableDaoMock::new()),
         );
-        let banned_dao_factory = self
-            .banned_dao_factory_opt
-            .unwrap_or(BannedDaoFactoryMock::new().make_result(BannedDaoMock::new()));
-        let config_dao_factory = self
-            .config_dao_factory_opt
-            .unwrap_or(ConfigDaoFactoryMock::new().make_result(ConfigDaoMock::new()));
+        let banned_dao_factory = self.banned_dao_factory_opt.unwrap_or(
+            BannedDaoFactoryMock::new()
+                .make_result(BannedDaoMock::new())
+                .make_result(BannedDaoMock::new()),
+        );
+        let config_dao_factory = self.config_dao_factory_opt.unwrap_or(
+            ConfigDaoFactoryMock::new()
+                .make_result(ConfigDaoMock::new())
+                .make_result(ConfigDaoMock::new()),
+        );
+        let financial_history_dao_factory = self.financial_history_dao_factory_opt.unwrap_or(
+            FinancialHistoryDaoFactoryMock::new().make_result(FinancialHistoryDaoMock::new()),
+        );
+        let ledger_adjustment_dao_factory = self.ledger_adjustment_dao_factory_opt.unwrap_or(
+            LedgerAdjustmentDaoFactoryMock::new().make_result(LedgerAdjustmentDaoMock::new()),
+        );
         let mut accountant = Accountant::new(
             config,
             DaoFactories {
@@ -372,6 +433,8 @@ impl AccountantBuilder {
                 receivable_dao_factory: Box::new(receivable_dao_factory),
                 banned_dao_factory: Box::new(banned_dao_factory),
                 config_dao_factory: Box::new(config_dao_factory),
+                financial_history_dao_factory: Box::new(financial_history_dao_factory),
+                ledger_adjustment_dao_factory: Box::new(ledger_adjustment_dao_factory),
             },
         );
         if let Some(logger) = self.logger_opt {
@@ -491,6 +554,74 @@ impl BannedDaoFactoryMock {
     }
 }
 
+pub struct FinancialHistoryDaoFactoryMock {
+    make_params: Arc<Mutex<Vec<()>>>,
+    make_results: RefCell<Vec<Box<dyn FinancialHistoryDao>>>,
+}
+
+impl FinancialHistoryDaoFactory for FinancialHistoryDaoFactoryMock {
+    fn make(&self) -> Box<dyn FinancialHistoryDao> {
+        if self.make_results.borrow().len() == 0 {
+            panic!("FinancialHistoryDao Missing.")
+        };
+        self.make_params.lock().unwrap().push(());
+        self.make_results.borrow_mut().remove(0)
+    }
+}
+
+impl FinancialHistoryDaoFactoryMock {
+    pub fn new() -> Self {
+        Self {
+            make_params: Arc::new(Mutex::new(vec![])),
+            make_results: RefCell::new(vec![]),
+        }
+    }
+
+    pub fn make_params(mut self, params: &Arc<Mutex<Vec<()>>>) -> Self {
+        self.make_params = params.clone();
+        self
+    }
+
+    pub fn make_result(self, result: FinancialHistoryDaoMock) -> Self {
+        self.make_results.borrow_mut().push(Box::new(result));
+        self
+    }
+}
+
+pub struct LedgerAdjustmentDaoFactoryMock {
+    make_params: Arc<Mutex<Vec<()>>>,
+    make_results: RefCell<Vec<Box<dyn LedgerAdjustmentDao>>>,
+}
+
+impl LedgerAdjustmentDaoFactory for LedgerAdjustmentDaoFactoryMock {
+    fn make(&self) -> Box<dyn LedgerAdjustmentDao> {
+        if self.make_results.borrow().len() == 0 {
+            panic!("LedgerAdjustmentDao Missing.")
+        };
+        self.make_params.lock().unwrap().push(());
+        self.make_results.borrow_mut().remove(0)
+    }
+}
+
+impl LedgerAdjustmentDaoFactoryMock {
+    pub fn new() -> Self {
+        Self {
+            make_params: Arc::new(Mutex::new(vec![])),
+            make_results: RefCell::new(vec![]),
+        }
+    }
+
+    pub fn make_params(mut self, params: &Arc<Mutex<Vec<()>>>) -> Self {
+        self.make_params = params.clone();
+        self
+    }
+
+    pub fn make_result(self, result: LedgerAdjustmentDaoMock) -> Self {
+        self.make_results.borrow_mut().push(Box::new(result));
+        self
+    }
+}
+
 pub struct ConfigDaoFactoryMock {
     make_params: Arc<Mutex<Vec<()>>>,
     make_results: RefCell<Vec<Box<dyn ConfigDao>>>,
@@ -535,6 +666,9 @@ pub struct PayableDaoMock {
     custom_query_params: Arc<Mutex<Vec<CustomQuery<u64>>>>,
     custom_query_result: RefCell<Vec<Option<Vec<PayableAccount>>>>,
     total_results: RefCell<Vec<u128>>,
+    adjust_balance_params: Arc<Mutex<Vec<(Wallet, i128)>>>,
+    adjust_balance_results: RefCell<Vec<Result<Option<BalanceChange>, PayableDaoError>>>,
+    adjust_balance_txn_id_stamp_opt: Option<ArbitraryIdStamp>,
 }
 
 impl PayableDao for PayableDaoMock {
@@ -594,6 +728,24 @@ impl PayableDao for PayableDaoMock {
         self.total_results.borrow_mut().remove(0)
     }
 
+    fn adjust_balance(
+        &mut self,
+        wallet: &Wallet,
+        change_wei: i128,
+    ) -> Result<Option<UncommittedBalanceChange<'_>>, PayableDaoError> {
+        self.adjust_balance_params
+            .lock()
+            .unwrap()
+            .push((wallet.clone(), change_wei));
+        let txn_id_stamp_opt = self.adjust_balance_txn_id_stamp_opt;
+        self.adjust_balance_results
+            .borrow_mut()
+            .remove(0)
+            .map(|change_opt| {
+                change_opt.map(|change| make_uncommitted_balance_change(change, txn_id_stamp_opt))
+            })
+    }
+
     fn account_status(&self, _wallet: &Wallet) -> Option<PayableAccount> {
         //test-only trait member
         intentionally_blank!()
@@ -672,6 +824,25 @@ impl PayableDaoMock {
         self.total_results.borrow_mut().push(result);
         self
     }
+
+    pub fn adjust_balance_params(mut self, params: &Arc<Mutex<Vec<(Wallet, i128)>>>) -> Self {
+        self.adjust_balance_params = params.clone();
+        self
+    }
+
+    pub fn adjust_balance_result(
+        self,
+        result: Result<Option<BalanceChange>, PayableDaoError>,
+    ) -> Self {
+        self.adjust_balance_results.borrow_mut().push(result);
+        self
+    }
+
+    // Marks the transaction the balance change is made in
+    pub fn adjust_balance_txn_id_stamp(mut self, stamp: ArbitraryIdStamp) -> Self {
+        self.adjust_balance_txn_id_stamp_opt = Some(stamp);
+        self
+    }
 }
 
 #[derive(Debug, Default)]
@@ -680,6 +851,9 @@ pub struct ReceivableDaoMock {
     more_money_receivable_results: RefCell<Vec<Result<(), ReceivableDaoError>>>,
     more_money_received_parameters: Arc<Mutex<Vec<(SystemTime, Vec<BlockchainTransaction>)>>>,
     more_money_received_results: RefCell<Vec<TransactionSafeWrapper<'static>>>,
+    pending_receivables_results: RefCell<Vec<Vec<PendingReceivable>>>,
+    reconcile_received_payments_params: Arc<Mutex<Vec<(SystemTime, ReceivablesReconciliation)>>>,
+    reconcile_received_payments_results: RefCell<Vec<TransactionSafeWrapper<'static>>>,
     new_delinquencies_parameters: Arc<Mutex<Vec<(SystemTime, PaymentThresholds)>>>,
     new_delinquencies_results: RefCell<Vec<Vec<ReceivableAccount>>>,
     paid_delinquencies_parameters: Arc<Mutex<Vec<PaymentThresholds>>>,
@@ -687,6 +861,11 @@ pub struct ReceivableDaoMock {
     custom_query_params: Arc<Mutex<Vec<CustomQuery<i64>>>>,
     custom_query_result: RefCell<Vec<Option<Vec<ReceivableAccount>>>>,
     total_results: RefCell<Vec<i128>>,
+    adjust_balance_params: Arc<Mutex<Vec<(Wallet, i128)>>>,
+    adjust_balance_results: RefCell<Vec<Result<Option<BalanceChange>, ReceivableDaoError>>>,
+    adjust_balance_txn_id_stamp_opt: Option<ArbitraryIdStamp>,
+    account_status_params: Arc<Mutex<Vec<Wallet>>>,
+    account_status_results: RefCell<Vec<Option<ReceivableAccount>>>,
 }
 
 impl ReceivableDao for ReceivableDaoMock {
@@ -715,6 +894,24 @@ impl ReceivableDao for ReceivableDaoMock {
         self.more_money_received_results.borrow_mut().remove(0)
     }
 
+    fn pending_receivables(&self) -> Vec<PendingReceivable> {
+        self.pending_receivables_results.borrow_mut().remove(0)
+    }
+
+    fn reconcile_received_payments(
+        &mut self,
+        now: SystemTime,
+        reconciliation: &ReceivablesReconciliation,
+    ) -> TransactionSafeWrapper {
+        self.reconcile_received_payments_params
+            .lock()
+            .unwrap()
+            .push((now, reconciliation.clone()));
+        self.reconcile_received_payments_results
+            .borrow_mut()
+            .remove(0)
+    }
+
     fn new_delinquencies(
         &self,
         now: SystemTime,
@@ -744,9 +941,30 @@ impl ReceivableDao for ReceivableDaoMock {
         self.total_results.borrow_mut().remove(0)
     }
 
-    fn account_status(&self, _wallet: &Wallet) -> Option<ReceivableAccount> {
-        //test-only trait member
-        intentionally_blank!()
+    fn adjust_balance(
+        &mut self,
+        wallet: &Wallet,
+        change_wei: i128,
+    ) -> Result<Option<UncommittedBalanceChange<'_>>, ReceivableDaoError> {
+        self.adjust_balance_params
+            .lock()
+            .unwrap()
+            .push((wallet.clone(), change_wei));
+        let txn_id_stamp_opt = self.adjust_balance_txn_id_stamp_opt;
+        self.adjust_balance_results
+            .borrow_mut()
+            .remove(0)
+            .map(|change_opt| {
+                change_opt.map(|change| make_uncommitted_balance_change(change, txn_id_stamp_opt))
+            })
+    }
+
+    fn account_status(&self, wallet: &Wallet) -> Option<ReceivableAccount> {
+        self.account_status_params
+            .lock()
+            .unwrap()
+            .push(wallet.clone());
+        self.account_status_results.borrow_mut().remove(0)
     }
 }
 
@@ -781,6 +999,29 @@ impl ReceivableDaoMock {
         self
     }
 
+    pub fn pending_receivables_result(self, result: Vec<PendingReceivable>) -> Self {
+        self.pending_receivables_results.borrow_mut().push(result);
+        self
+    }
+
+    pub fn reconcile_received_payments_params(
+        mut self,
+        params: &Arc<Mutex<Vec<(SystemTime, ReceivablesReconciliation)>>>,
+    ) -> Self {
+        self.reconcile_received_payments_params = params.clone();
+        self
+    }
+
+    pub fn reconcile_received_payments_result(
+        self,
+        result: TransactionSafeWrapper<'static>,
+    ) -> Self {
+        self.reconcile_received_payments_results
+            .borrow_mut()
+            .push(result);
+        self
+    }
+
     pub fn new_delinquencies_parameters(
         mut self,
         parameters: &Arc<Mutex<Vec<(SystemTime, PaymentThresholds)>>>,
@@ -821,6 +1062,35 @@ impl ReceivableDaoMock {
         self.total_results.borrow_mut().push(result);
         self
     }
+
+    pub fn adjust_balance_params(mut self, params: &Arc<Mutex<Vec<(Wallet, i128)>>>) -> Self {
+        self.adjust_balance_params = params.clone();
+        self
+    }
+
+    pub fn adjust_balance_result(
+        self,
+        result: Result<Option<BalanceChange>, ReceivableDaoError>,
+    ) -> Self {
+        self.adjust_balance_results.borrow_mut().push(result);
+        self
+    }
+
+    // Marks the transaction the balance change is made in
+    pub fn adjust_balance_txn_id_stamp(mut self, stamp: ArbitraryIdStamp) -> Self {
+        self.adjust_balance_txn_id_stamp_opt = Some(stamp);
+        self
+    }
+
+    pub fn account_status_params(mut self, params: &Arc<Mutex<Vec<Wallet>>>) -> Self {
+        self.account_status_params = params.clone();
+        self
+    }
+
+    pub fn account_status_result(self, result: Option<ReceivableAccount>) -> Self {
+        self.account_status_results.borrow_mut().push(result);
+        self
+    }
 }
 
 #[derive(Debug, Default)]
@@ -872,6 +1142,181 @@ impl BannedDaoMock {
     }
 }
 
+#[derive(Default)]
+pub struct FundsTransferDaoMock {
+    record_transfer_params: Arc<Mutex<Vec<FundsTransferRecord>>>,
+    record_transfer_results: RefCell<Vec<Result<(), FundsTransferDaoError>>>,
+    transfers_results: RefCell<Vec<Vec<FundsTransferRecord>>>,
+}
+
+impl FundsTransferDao for FundsTransferDaoMock {
+    fn record_transfer(&self, record: &FundsTransferRecord) -> Result<(), FundsTransferDaoError> {
+        self.record_transfer_params
+            .lock()
+            .unwrap()
+            .push(record.clone());
+        self.record_transfer_results.borrow_mut().remove(0)
+    }
+
+    fn transfers(&self) -> Vec<FundsTransferRecord> {
+        self.transfers_results.borrow_mut().remove(0)
+    }
+}
+
+impl FundsTransferDaoMock {
+    pub fn record_transfer_params(mut self, params: &Arc<Mutex<Vec<FundsTransferRecord>>>) -> Self {
+        self.record_transfer_params = params.clone();
+        self
+    }
+
+    pub fn record_transfer_result(self, result: Result<(), FundsTransferDaoError>) -> Self {
+        self.record_transfer_results.borrow_mut().push(result);
+        self
+    }
+
+    pub fn transfers_result(self, result: Vec<FundsTransferRecord>) -> Self {
+        self.transfers_results.borrow_mut().push(result);
+        self
+    }
+}
+
+#[derive(Default)]
+pub struct FinancialHistoryDaoMock {
+    record_params: Arc<Mutex<Vec<(SystemTime, HistoryDirection, HistoryService, Wallet, u128)>>>,
+    record_results: RefCell<Vec<Result<(), FinancialHistoryDaoError>>>,
+    history_params: Arc<Mutex<Vec<(SystemTime, SystemTime, Option<Wallet>)>>>,
+    history_results: RefCell<Vec<Vec<FinancialHistoryRecord>>>,
+}
+
+impl FinancialHistoryDao for FinancialHistoryDaoMock {
+    fn record(
+        &self,
+        timestamp: SystemTime,
+        direction: HistoryDirection,
+        service: HistoryService,
+        wallet: &Wallet,
+        amount_wei: u128,
+    ) -> Result<(), FinancialHistoryDaoError> {
+        self.record_params.lock().unwrap().push((
+            timestamp,
+            direction,
+            service,
+            wallet.clone(),
+            amount_wei,
+        ));
+        self.record_results.borrow_mut().remove(0)
+    }
+
+    fn history(
+        &self,
+        from: SystemTime,
+        to: SystemTime,
+        wallet_opt: Option<&Wallet>,
+    ) -> Vec<FinancialHistoryRecord> {
+        self.history_params
+            .lock()
+            .unwrap()
+            .push((from, to, wallet_opt.cloned()));
+        self.history_results.borrow_mut().remove(0)
+    }
+}
+
+impl FinancialHistoryDaoMock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_params(
+        mut self,
+        params: &Arc<Mutex<Vec<(SystemTime, HistoryDirection, HistoryService, Wallet, u128)>>>,
+    ) -> Self {
+        self.record_params = params.clone();
+        self
+    }
+
+    pub fn record_result(self, result: Result<(), FinancialHistoryDaoError>) -> Self {
+        self.record_results.borrow_mut().push(result);
+        self
+    }
+
+    pub fn history_params(
+        mut self,
+        params: &Arc<Mutex<Vec<(SystemTime, SystemTime, Option<Wallet>)>>>,
+    ) -> Self {
+        self.history_params = params.clone();
+        self
+    }
+
+    pub fn history_result(self, result: Vec<FinancialHistoryRecord>) -> Self {
+        self.history_results.borrow_mut().push(result);
+        self
+    }
+}
+
+#[derive(Default)]
+pub struct LedgerAdjustmentDaoMock {
+    record_params: Arc<Mutex<Vec<(LedgerAdjustment, ArbitraryIdStamp)>>>,
+    record_results: RefCell<Vec<Result<(), LedgerAdjustmentDaoError>>>,
+}
+
+impl LedgerAdjustmentDao for LedgerAdjustmentDaoMock {
+    fn record(
+        &self,
+        adjustment: &LedgerAdjustment,
+        txn: TransactionSafeWrapper,
+    ) -> Result<(), LedgerAdjustmentDaoError> {
+        self.record_params
+            .lock()
+            .unwrap()
+            .push((adjustment.clone(), txn.arbitrary_id_stamp()));
+        self.record_results.borrow_mut().remove(0)
+    }
+}
+
+impl LedgerAdjustmentDaoMock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_params(
+        mut self,
+        params: &Arc<Mutex<Vec<(LedgerAdjustment, ArbitraryIdStamp)>>>,
+    ) -> Self {
+        self.record_params = params.clone();
+        self
+    }
+
+    pub fn record_result(self, result: Result<(), LedgerAdjustmentDaoError>) -> Self {
+        self.record_results.borrow_mut().push(result);
+        self
+    }
+}
+
+fn make_uncommitted_balance_change(
+    change: BalanceChange,
+    txn_id_stamp_opt: Option<ArbitraryIdStamp>,
+) -> UncommittedBalanceChange<'static> {
+    let builder = TransactionInnerWrapperMockBuilder::default();
+    let builder = match txn_id_stamp_opt {
+        Some(stamp) => builder.set_arbitrary_id_stamp(stamp),
+        None => builder,
+    };
+    UncommittedBalanceChange {
+        change,
+        txn: TransactionSafeWrapper::new_with_builder(builder),
+    }
+}
+
+// Commits a change made by a real DAO, as recording it would
+pub fn commit_balance_change(
+    uncommitted_opt: Option<UncommittedBalanceChange>,
+) -> Option<BalanceChange> {
+    uncommitted_opt.map(|uncommitted| {
+        uncommitted.txn.commit().unwrap();
+        uncommitted.change
+    })
+}
+
 pub fn bc_from_earning_wallet(earning_wallet: Wallet) -> BootstrapperConfig {
     let mut bc = make_bc_with_defaults();
     bc.earning_wallet = earning_wallet;
@@ -893,6 +1338,8 @@ pub struct PendingPayableDaoMock {
     delete_fingerprints_results: RefCell<Vec<Result<(), PendingPayableDaoError>>>,
     insert_new_fingerprints_params: Arc<Mutex<Vec<(Vec<(H256, u128)>, SystemTime)>>>,
     insert_new_fingerprints_results: RefCell<Vec<Result<(), PendingPayableDaoError>>>,
+    insert_batch_payments_params: Arc<Mutex<Vec<Vec<(H256, Vec<(Wallet, u128)>)>>>>,
+    insert_batch_payments_results: RefCell<Vec<Result<(), PendingPayableDaoError>>>,
     increment_scan_attempts_params: Arc<Mutex<Vec<Vec<u64>>>>,
     increment_scan_attempts_result: RefCell<Vec<Result<(), PendingPayableDaoError>>>,
     mark_failures_params: Arc<Mutex<Vec<Vec<u64>>>>,
@@ -900,6 +1347,13 @@ pub struct PendingPayableDaoMock {
     return_all_errorless_fingerprints_params: Arc<Mutex<Vec<()>>>,
     return_all_errorless_fingerprints_results: RefCell<Vec<Vec<PendingPayableFingerprint>>>,
     pub have_return_all_errorless_fingerprints_shut_down_the_system: bool,
+    insert_nonces_params: Arc<Mutex<Vec<(Vec<(H256, u64)>, u64)>>>,
+    insert_nonces_results: RefCell<Vec<Result<(), PendingPayableDaoError>>>,
+    stuck_transactions_params: Arc<Mutex<Vec<Vec<PendingPayableId>>>>,
+    stuck_transactions_results: RefCell<Vec<Vec<StuckTransaction>>>,
+    fee_bumps_results: RefCell<Vec<Vec<PendingPayableFeeBump>>>,
+    record_fee_bump_params: Arc<Mutex<Vec<PendingPayableFeeBump>>>,
+    record_fee_bump_results: RefCell<Vec<Result<(), PendingPayableDaoError>>>,
 }
 
 impl PendingPayableDao for PendingPayableDaoMock {
@@ -942,6 +1396,17 @@ impl PendingPayableDao for PendingPayableDaoMock {
         self.insert_new_fingerprints_results.borrow_mut().remove(0)
     }
 
+    fn insert_batch_payments(
+        &self,
+        batch_payments: &[(H256, Vec<(Wallet, u128)>)],
+    ) -> Result<(), PendingPayableDaoError> {
+        self.insert_batch_payments_params
+            .lock()
+            .unwrap()
+            .push(batch_payments.to_vec());
+        self.insert_batch_payments_results.borrow_mut().remove(0)
+    }
+
     fn delete_fingerprints(&self, ids: &[u64]) -> Result<(), PendingPayableDaoError> {
         self.delete_fingerprints_params
             .lock()
@@ -962,6 +1427,41 @@ impl PendingPayableDao for PendingPayableDaoMock {
         self.mark_failures_params.lock().unwrap().push(ids.to_vec());
         self.mark_failures_results.borrow_mut().remove(0)
     }
+
+    fn insert_nonces(
+        &self,
+        hashes_and_nonces: &[(H256, u64)],
+        gas_price_wei: u64,
+    ) -> Result<(), PendingPayableDaoError> {
+        self.insert_nonces_params
+            .lock()
+            .unwrap()
+            .push((hashes_and_nonces.to_vec(), gas_price_wei));
+        self.insert_nonces_results.borrow_mut().remove(0)
+    }
+
+    fn stuck_transactions(&self, ids: &[PendingPayableId]) -> Vec<StuckTransaction> {
+        self.stuck_transactions_params
+            .lock()
+            .unwrap()
+            .push(ids.to_vec());
+        self.stuck_transactions_results.borrow_mut().remove(0)
+    }
+
+    fn fee_bumps(&self) -> Vec<PendingPayableFeeBump> {
+        self.fee_bumps_results.borrow_mut().remove(0)
+    }
+
+    fn record_fee_bump(
+        &mut self,
+        fee_bump: &PendingPayableFeeBump,
+    ) -> Result<(), PendingPayableDaoError> {
+        self.record_fee_bump_params
+            .lock()
+            .unwrap()
+            .push(fee_bump.clone());
+        self.record_fee_bump_results.borrow_mut().remove(0)
+    }
 }
 
 impl PendingPayableDaoMock {
@@ -994,6 +1494,19 @@ impl PendingPayableDaoMock {
         self
     }
 
+    pub fn insert_batch_payments_params(
+        mut self,
+        params: &Arc<Mutex<Vec<Vec<(H256, Vec<(Wallet, u128)>)>>>>,
+    ) -> Self {
+        self.insert_batch_payments_params = params.clone();
+        self
+    }
+
+    pub fn insert_batch_payments_result(self, result: Result<(), PendingPayableDaoError>) -> Self {
+        self.insert_batch_payments_results.borrow_mut().push(result);
+        self
+    }
+
     pub fn delete_fingerprints_params(mut self, params: &Arc<Mutex<Vec<Vec<u64>>>>) -> Self {
         self.delete_fingerprints_params = params.clone();
         self
@@ -1046,6 +1559,50 @@ impl PendingPayableDaoMock {
             .push(result);
         self
     }
+
+    pub fn insert_nonces_params(
+        mut self,
+        params: &Arc<Mutex<Vec<(Vec<(H256, u64)>, u64)>>>,
+    ) -> Self {
+        self.insert_nonces_params = params.clone();
+        self
+    }
+
+    pub fn insert_nonces_result(self, result: Result<(), PendingPayableDaoError>) -> Self {
+        self.insert_nonces_results.borrow_mut().push(result);
+        self
+    }
+
+    pub fn stuck_transactions_params(
+        mut self,
+        params: &Arc<Mutex<Vec<Vec<PendingPayableId>>>>,
+    ) -> Self {
+        self.stuck_transactions_params = params.clone();
+        self
+    }
+
+    pub fn stuck_transactions_result(self, result: Vec<StuckTransaction>) -> Self {
+        self.stuck_transactions_results.borrow_mut().push(result);
+        self
+    }
+
+    pub fn fee_bumps_result(self, result: Vec<PendingPayableFeeBump>) -> Self {
+        self.fee_bumps_results.borrow_mut().push(result);
+        self
+    }
+
+    pub fn record_fee_bump_params(
+        mut self,
+        params: &Arc<Mutex<Vec<PendingPayableFeeBump>>>,
+    ) -> Self {
+        self.record_fee_bump_params = params.clone();
+        self
+    }
+
+    pub fn record_fee_bump_result(self, result: Result<(), PendingPayableDaoError>) -> Self {
+        self.record_fee_bump_results.borrow_mut().push(result);
+        self
+    }
 }
 
 pub struct PendingPayableDaoFactoryMock {
@@ -1187,6 +1744,7 @@ pub struct ReceivableScannerBuilder {
     banned_dao: BannedDaoMock,
     persistent_configuration: PersistentConfigurationMock,
     payment_thresholds: PaymentThresholds,
+    confirmation_depth: u64,
     financial_statistics: FinancialStatistics,
 }
 
@@ -1197,6 +1755,7 @@ impl ReceivableScannerBuilder {
             banned_dao: BannedDaoMock::new(),
             persistent_configuration: PersistentConfigurationMock::new(),
             payment_thresholds: PaymentThresholds::default(),
+            confirmation_depth: 0,
             financial_statistics: FinancialStatistics::default(),
         }
     }
@@ -1224,12 +1783,18 @@ impl ReceivableScannerBuilder {
         self
     }
 
+    pub fn confirmation_depth(mut self, confirmation_depth: u64) -> Self {
+        self.confirmation_depth = confirmation_depth;
+        self
+    }
+
     pub fn build(self) -> ReceivableScanner {
         ReceivableScanner::new(
             Box::new(self.receivable_dao),
             Box::new(self.banned_dao),
             Box::new(self.persistent_configuration),
             Rc::new(self.payment_thresholds),
+            self.confirmation_depth,
             Rc::new(RefCell::new(self.financial_statistics)),
         )
     }
@@ -1565,6 +2130,8 @@ where
         panic!("Called mark_as_ended() from NullScanner");
     }
 
+    fn update_payment_thresholds(&mut self, _payment_thresholds: Rc<PaymentThresholds>) {}
+
     as_any_ref_in_trait_impl!();
 }
 
@@ -1634,6 +2201,10 @@ where
     fn mark_as_ended(&mut self, _logger: &Logger) {
         intentionally_blank!()
     }
+
+    fn update_payment_thresholds(&mut self, _payment_thresholds: Rc<PaymentThresholds>) {
+        intentionally_blank!()
+    }
 }
 
 impl<BeginMessage, EndMessage> Default for ScannerMock<BeginMessage, EndMessage> {
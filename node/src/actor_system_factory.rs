@@ -11,12 +11,17 @@ use super::stream_handler_pool::StreamHandlerPoolSubs;
 use super::stream_messages::PoolBindMessage;
 use super::ui_gateway::UiGateway;
 use crate::accountant::db_access_objects::banned_dao::{BannedCacheLoader, BannedCacheLoaderReal};
-use crate::blockchain::blockchain_bridge::{BlockchainBridge, BlockchainBridgeSubsFactoryReal};
+use crate::blockchain::blockchain_bridge::{
+    BlockchainBridge, BlockchainBridgeSubsFactoryReal, BlockchainInterfaceFactory,
+    BlockchainInterfaceFactoryReal,
+};
 use crate::bootstrapper::CryptDEPair;
 use crate::database::db_initializer::DbInitializationConfig;
 use crate::database::db_initializer::{connection_or_panic, DbInitializer, DbInitializerReal};
 use crate::db_config::persistent_configuration::PersistentConfiguration;
+use crate::node_configurator::config_reload::ConfigReaderReal;
 use crate::node_configurator::configurator::Configurator;
+use crate::server_initializer::register_shutdown_subs;
 use crate::sub_lib::accountant::{AccountantSubs, AccountantSubsFactoryReal, DaoFactories};
 use crate::sub_lib::blockchain_bridge::BlockchainBridgeSubs;
 use crate::sub_lib::configurator::ConfiguratorSubs;
@@ -127,6 +132,7 @@ impl ActorSystemFactoryTools for ActorSystemFactoryToolsReal {
                         .exit_service_rate,
                     exit_byte_rate: config.neighborhood_config.mode.rate_pack().exit_byte_rate,
                     is_decentralized: config.neighborhood_config.mode.is_decentralized(),
+                    bandwidth_shaper: config.bandwidth_shaper.clone(),
                     crashable: is_crashable(&config),
                 }),
             )
@@ -186,6 +192,7 @@ impl ActorSystemFactoryTools for ActorSystemFactoryToolsReal {
         if let Some(subs) = proxy_client_subs_opt {
             send_bind_message!(subs, peer_actors);
         }
+        register_shutdown_subs(peer_actors.shutdown_subs());
         stream_handler_pool_subs
             .bind
             .try_send(PoolBindMessage {
@@ -408,15 +415,18 @@ impl ActorFactory for ActorFactoryReal {
             None
         };
         let crashable = is_crashable(config);
+        let route_lifetime = config.route_lifetime;
         let arbiter = Arbiter::builder().stop_system_on_panic(true);
         let addr: Addr<ProxyServer> = arbiter.start(move |_| {
-            ProxyServer::new(
+            let mut proxy_server = ProxyServer::new(
                 cryptdes.main,
                 cryptdes.alias,
                 is_decentralized,
                 consuming_wallet_balance,
                 crashable,
-            )
+            );
+            proxy_server.set_route_lifetime(route_lifetime);
+            proxy_server
         });
         ProxyServer::make_subs_from(&addr)
     }
@@ -452,6 +462,8 @@ impl ActorFactory for ActorFactoryReal {
         let receivable_dao_factory = Box::new(Accountant::dao_factory(data_directory));
         let banned_dao_factory = Box::new(Accountant::dao_factory(data_directory));
         let config_dao_factory = Box::new(Accountant::dao_factory(data_directory));
+        let financial_history_dao_factory = Box::new(Accountant::dao_factory(data_directory));
+        let ledger_adjustment_dao_factory = Box::new(Accountant::dao_factory(data_directory));
         Self::load_banned_cache(db_initializer, banned_cache_loader, data_directory);
         let arbiter = Arbiter::builder().stop_system_on_panic(true);
         let addr: Addr<Accountant> = arbiter.start(move |_| {
@@ -463,6 +475,8 @@ impl ActorFactory for ActorFactoryReal {
                     receivable_dao_factory,
                     banned_dao_factory,
                     config_dao_factory,
+                    financial_history_dao_factory,
+                    ledger_adjustment_dao_factory,
                 },
             )
         });
@@ -483,10 +497,20 @@ impl ActorFactory for ActorFactoryReal {
     ) -> StreamHandlerPoolSubs {
         let clandestine_discriminator_factories =
             config.clandestine_discriminator_factories.clone();
+        let clandestine_masquerader = config.clandestine_masquerader;
+        let clandestine_tls_opt = config.clandestine_tls_opt.clone();
+        let bandwidth_shaper = config.bandwidth_shaper.clone();
         let crashable = is_crashable(config);
         let arbiter = Arbiter::builder().stop_system_on_panic(true);
-        let addr: Addr<StreamHandlerPool> = arbiter
-            .start(move |_| StreamHandlerPool::new(clandestine_discriminator_factories, crashable));
+        let addr: Addr<StreamHandlerPool> = arbiter.start(move |_| {
+            StreamHandlerPool::new(
+                clandestine_discriminator_factories,
+                clandestine_masquerader,
+                clandestine_tls_opt,
+                bandwidth_shaper,
+                crashable,
+            )
+        });
         StreamHandlerPool::make_subs_from(&addr)
     }
 
@@ -505,28 +529,43 @@ impl ActorFactory for ActorFactoryReal {
             .blockchain_bridge_config
             .blockchain_service_url_opt
             .clone();
+        let external_signer_opt = config.blockchain_bridge_config.external_signer_opt.clone();
         let crashable = is_crashable(config);
         let data_directory = config.data_directory.clone();
         let chain = config.blockchain_bridge_config.chain;
         let arbiter = Arbiter::builder().stop_system_on_panic(true);
         let addr: Addr<BlockchainBridge> = arbiter.start(move |_| {
-            let blockchain_interface = BlockchainBridge::initialize_blockchain_interface(
-                blockchain_service_url_opt,
-                chain,
-            );
+            let blockchain_interface_factory =
+                BlockchainInterfaceFactoryReal::new(external_signer_opt, chain);
+            let blockchain_interface =
+                blockchain_interface_factory.make(blockchain_service_url_opt);
             let persistent_config =
                 BlockchainBridge::initialize_persistent_configuration(&data_directory);
-            BlockchainBridge::new(blockchain_interface, persistent_config, crashable)
+            let funds_transfer_dao =
+                BlockchainBridge::initialize_funds_transfer_dao(&data_directory);
+            let mut blockchain_bridge = BlockchainBridge::new(
+                blockchain_interface,
+                persistent_config,
+                funds_transfer_dao,
+                crashable,
+            );
+            blockchain_bridge
+                .set_blockchain_interface_factory(Box::new(blockchain_interface_factory));
+            blockchain_bridge
         });
         subs_factory.make(&addr)
     }
 
     fn make_and_start_configurator(&self, config: &BootstrapperConfig) -> ConfiguratorSubs {
         let data_directory = config.data_directory.clone();
+        let bandwidth_shaper = config.bandwidth_shaper.clone();
         let crashable = is_crashable(config);
         let arbiter = Arbiter::builder().stop_system_on_panic(true);
-        let addr: Addr<Configurator> =
-            arbiter.start(move |_| Configurator::new(data_directory, crashable));
+        let addr: Addr<Configurator> = arbiter.start(move |_| {
+            let mut configurator = Configurator::new(data_directory, bandwidth_shaper, crashable);
+            configurator.set_config_reader(Box::new(ConfigReaderReal::default()));
+            configurator
+        });
         ConfiguratorSubs {
             bind: recipient!(addr, BindMessage),
             node_from_ui_sub: recipient!(addr, NodeFromUiMessage),
@@ -626,10 +665,15 @@ mod tests {
     use crate::accountant::DEFAULT_PENDING_TOO_LONG_SEC;
     use crate::blockchain::blockchain_bridge::exportable_test_parts::test_blockchain_bridge_is_constructed_with_correctly_functioning_connections;
     use crate::bootstrapper::{Bootstrapper, RealUser};
+    use crate::masquerader::MasqueraderKind;
     use crate::node_test_utils::{
         make_stream_handler_pool_subs_from_recorder, start_recorder_refcell_opt,
     };
-    use crate::sub_lib::accountant::{PaymentThresholds, ScanIntervals};
+    use crate::proxy_server::route_isolation::DEFAULT_ROUTE_LIFETIME;
+    use crate::sub_lib::accountant::{
+        FeeBumping, PaymentThresholds, ScanIntervals, SpendingBudget,
+    };
+    use crate::sub_lib::bandwidth_shaper::BandwidthShaper;
     use crate::sub_lib::blockchain_bridge::BlockchainBridgeConfig;
     use crate::sub_lib::cryptde::{PlainData, PublicKey};
     use crate::sub_lib::cryptde_null::CryptDENull;
@@ -1144,14 +1188,25 @@ mod tests {
         let recordings = actor_factory.get_recordings();
         let config = BootstrapperConfig {
             log_level: LevelFilter::Off,
+            log_redaction_levels: vec![],
+            route_lifetime: DEFAULT_ROUTE_LIFETIME,
             crash_point: CrashPoint::None,
             dns_servers: vec![],
             scan_intervals_opt: Some(ScanIntervals::default()),
             suppress_initial_scans: false,
             clandestine_discriminator_factories: Vec::new(),
-            ui_gateway_config: UiGatewayConfig { ui_port: 5335 },
+            clandestine_masquerader: MasqueraderKind::Json,
+            clandestine_tls: false,
+            bandwidth_shaper: BandwidthShaper::default(),
+            spending_budget: SpendingBudget::default(),
+            clandestine_tls_opt: None,
+            ui_gateway_config: UiGatewayConfig {
+                ui_port: 5335,
+                remote_ui_opt: None,
+            },
             blockchain_bridge_config: BlockchainBridgeConfig {
                 blockchain_service_url_opt: None,
+                external_signer_opt: None,
                 chain: TEST_DEFAULT_CHAIN,
                 gas_price: 1,
             },
@@ -1176,6 +1231,7 @@ mod tests {
             },
             payment_thresholds_opt: Some(PaymentThresholds::default()),
             when_pending_too_long_sec: DEFAULT_PENDING_TOO_LONG_SEC,
+            fee_bumping: FeeBumping::default(),
         };
         let persistent_config = PersistentConfigurationMock::default()
             .chain_name_result("eth-ropsten".to_string())
@@ -1219,14 +1275,25 @@ mod tests {
         let parameters = actor_factory.make_parameters();
         let config = BootstrapperConfig {
             log_level: LevelFilter::Off,
+            log_redaction_levels: vec![],
+            route_lifetime: DEFAULT_ROUTE_LIFETIME,
             crash_point: CrashPoint::None,
             dns_servers: vec![],
             scan_intervals_opt: None,
             suppress_initial_scans: false,
             clandestine_discriminator_factories: Vec::new(),
-            ui_gateway_config: UiGatewayConfig { ui_port: 5335 },
+            clandestine_masquerader: MasqueraderKind::Json,
+            clandestine_tls: false,
+            bandwidth_shaper: BandwidthShaper::default(),
+            spending_budget: SpendingBudget::default(),
+            clandestine_tls_opt: None,
+            ui_gateway_config: UiGatewayConfig {
+            ui_port: 5335,
+            remote_ui_opt: None,
+        },
             blockchain_bridge_config: BlockchainBridgeConfig {
                 blockchain_service_url_opt: None,
+                external_signer_opt: None,
                 chain: TEST_DEFAULT_CHAIN,
                 gas_price: 1,
             },
@@ -1250,7 +1317,8 @@ mod tests {
                 min_hops: MIN_HOPS_FOR_TEST,
             },
             payment_thresholds_opt: Default::default(),
-            when_pending_too_long_sec: DEFAULT_PENDING_TOO_LONG_SEC
+            when_pending_too_long_sec: DEFAULT_PENDING_TOO_LONG_SEC,
+            fee_bumping: FeeBumping::default()
         };
         let add_mapping_params_arc = Arc::new(Mutex::new(vec![]));
         let mut subject = make_subject_with_null_setter();
@@ -1344,6 +1412,7 @@ mod tests {
             blockchain_bridge_param.blockchain_bridge_config,
             BlockchainBridgeConfig {
                 blockchain_service_url_opt: None,
+                external_signer_opt: None,
                 chain: TEST_DEFAULT_CHAIN,
                 gas_price: 1
             }
@@ -1522,14 +1591,25 @@ mod tests {
         let recordings = actor_factory.get_recordings();
         let config = BootstrapperConfig {
             log_level: LevelFilter::Off,
+            log_redaction_levels: vec![],
+            route_lifetime: DEFAULT_ROUTE_LIFETIME,
             crash_point: CrashPoint::None,
             dns_servers: vec![],
             scan_intervals_opt: None,
             suppress_initial_scans: false,
             clandestine_discriminator_factories: Vec::new(),
-            ui_gateway_config: UiGatewayConfig { ui_port: 5335 },
+            clandestine_masquerader: MasqueraderKind::Json,
+            clandestine_tls: false,
+            bandwidth_shaper: BandwidthShaper::default(),
+            spending_budget: SpendingBudget::default(),
+            clandestine_tls_opt: None,
+            ui_gateway_config: UiGatewayConfig {
+            ui_port: 5335,
+            remote_ui_opt: None,
+        },
             blockchain_bridge_config: BlockchainBridgeConfig {
                 blockchain_service_url_opt: None,
+                external_signer_opt: None,
                 chain: TEST_DEFAULT_CHAIN,
                 gas_price: 1,
             },
@@ -1549,7 +1629,8 @@ mod tests {
                 min_hops: MIN_HOPS_FOR_TEST,
             },
             payment_thresholds_opt: Default::default(),
-            when_pending_too_long_sec: DEFAULT_PENDING_TOO_LONG_SEC
+            when_pending_too_long_sec: DEFAULT_PENDING_TOO_LONG_SEC,
+            fee_bumping: FeeBumping::default()
         };
         let system = System::new("MASQNode");
         let mut subject = make_subject_with_null_setter();
@@ -1708,14 +1789,25 @@ mod tests {
         let parameters = actor_factory.make_parameters();
         let config = BootstrapperConfig {
             log_level: LevelFilter::Off,
+            log_redaction_levels: vec![],
+            route_lifetime: DEFAULT_ROUTE_LIFETIME,
             crash_point: CrashPoint::None,
             dns_servers: vec![],
             scan_intervals_opt: None,
             suppress_initial_scans: false,
             clandestine_discriminator_factories: Vec::new(),
-            ui_gateway_config: UiGatewayConfig { ui_port: 5335 },
+            clandestine_masquerader: MasqueraderKind::Json,
+            clandestine_tls: false,
+            bandwidth_shaper: BandwidthShaper::default(),
+            spending_budget: SpendingBudget::default(),
+            clandestine_tls_opt: None,
+            ui_gateway_config: UiGatewayConfig {
+                ui_port: 5335,
+                remote_ui_opt: None,
+            },
             blockchain_bridge_config: BlockchainBridgeConfig {
                 blockchain_service_url_opt: None,
+                external_signer_opt: None,
                 chain: TEST_DEFAULT_CHAIN,
                 gas_price: 1,
             },
@@ -1740,6 +1832,7 @@ mod tests {
             node_descriptor: Default::default(),
             payment_thresholds_opt: Default::default(),
             when_pending_too_long_sec: DEFAULT_PENDING_TOO_LONG_SEC,
+            fee_bumping: FeeBumping::default(),
         };
         let subject = make_subject_with_null_setter();
         let system = System::new("MASQNode");
@@ -1780,6 +1873,7 @@ mod tests {
                 )],
                 exit_service_rate: 50,
                 is_decentralized: true,
+                bandwidth_shaper: BandwidthShaper::default(),
                 crashable: true,
                 exit_byte_rate: 50,
             };
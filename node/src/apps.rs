@@ -54,6 +54,22 @@ pub fn app_config_dumper() -> App<'static, 'static> {
         .arg(real_user_arg())
 }
 
+pub fn app_database_restorer() -> App<'static, 'static> {
+    app_head()
+        .arg(chain_arg())
+        .arg(
+            Arg::with_name("restore-database")
+                .long("restore-database")
+                .value_name("BACKUP-FILE")
+                .required(true)
+                .takes_value(true)
+                .help(RESTORE_DATABASE_HELP),
+        )
+        .arg(data_directory_arg(DATA_DIRECTORY_DAEMON_HELP.as_str()))
+        .arg(db_password_arg(DB_PASSWORD_HELP))
+        .arg(real_user_arg())
+}
+
 lazy_static! {
     static ref DAEMON_UI_PORT_HELP: String = format!(
         "The port at which user interfaces will connect to the Daemon. (This is NOT the port at which \
@@ -66,6 +82,11 @@ lazy_static! {
 const DUMP_CONFIG_HELP: &str =
     "Dump the configuration of MASQ Node to stdout in JSON. Used chiefly by UIs.";
 
+const RESTORE_DATABASE_HELP: &str =
+    "Replace the database of MASQ Node with a backup made by the databaseBackup UI command. The Node \
+     must not be running. If the backup is encrypted, --db-password must be the password it was made \
+     with. Any database already in the data directory is kept beside it under another name.";
+
 const NODE_HELP_TEXT: &str = indoc!(
     r"ADDITIONAL HELP:
     If you want to start the MASQ Daemon to manage the MASQ Node and the MASQ UIs, try:
@@ -77,6 +98,10 @@ const NODE_HELP_TEXT: &str = indoc!(
 
         MASQNode --help --dump-config
 
+    If you want to put back a database you saved with the databaseBackup UI command, try:
+
+        MASQNode --help --restore-database
+
     MASQ Node listens for connections from other Nodes using the computer's
     network interface. Configuring the internet router for port forwarding is a necessary
     step for Node users to permit network communication between Nodes.
@@ -136,6 +161,13 @@ mod tests {
             DUMP_CONFIG_HELP,
             "Dump the configuration of MASQ Node to stdout in JSON. Used chiefly by UIs."
         );
+        assert_eq!(
+            RESTORE_DATABASE_HELP,
+            "Replace the database of MASQ Node with a backup made by the databaseBackup UI command. \
+             The Node must not be running. If the backup is encrypted, --db-password must be the \
+             password it was made with. Any database already in the data directory is kept beside \
+             it under another name."
+        );
         assert_eq!(
             NODE_HELP_TEXT,
             indoc!(
@@ -149,6 +181,10 @@ mod tests {
             
                       MASQNode --help --dump-config
             
+                  If you want to put back a database you saved with the databaseBackup UI command, try:
+            
+                      MASQNode --help --restore-database
+            
                   MASQ Node listens for connections from other Nodes using the computer's
                   network interface. Configuring the internet router for port forwarding is a necessary
                   step for Node users to permit network communication between Nodes.
@@ -1,28 +1,43 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
+use crate::accountant::db_access_objects::funds_transfer_dao::{
+    FundsTransferDao, FundsTransferDaoReal, FundsTransferRecord,
+};
 use crate::accountant::db_access_objects::payable_dao::PayableAccount;
+use crate::accountant::db_access_objects::pending_payable_dao::PendingPayableFeeBump;
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::BlockchainAgent;
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::msgs::{
     BlockchainAgentWithContextMessage, QualifiedPayablesMessage,
 };
 use crate::accountant::{
-    ReceivedPayments, ResponseSkeleton, ScanError, SentPayables, SkeletonOptHolder,
+    checked_conversion, gwei_to_wei, wei_to_gwei, ReceivedPayments, ResponseSkeleton, ScanError,
+    ScannedBlocks, SentPayables, SkeletonOptHolder,
+};
+use crate::accountant::{
+    PendingPayableId, ReportFeeBumps, ReportTransactionReceipts, RequestFeeBumps,
+    RequestTransactionReceipts,
 };
-use crate::accountant::{ReportTransactionReceipts, RequestTransactionReceipts};
 use crate::actor_system_factory::SubsFactory;
 use crate::blockchain::blockchain_interface::blockchain_interface_null::BlockchainInterfaceNull;
 use crate::blockchain::blockchain_interface::data_structures::errors::{
     BlockchainError, PayableTransactionError,
 };
-use crate::blockchain::blockchain_interface::data_structures::ProcessedPayableFallible;
+use crate::blockchain::blockchain_interface::data_structures::{
+    FundsTransferOrder, ProcessedPayableFallible,
+};
 use crate::blockchain::blockchain_interface::BlockchainInterface;
 use crate::blockchain::blockchain_interface_initializer::BlockchainInterfaceInitializer;
+use crate::blockchain::external_signer::ExternalSignerEndpoint;
 use crate::database::db_initializer::{DbInitializationConfig, DbInitializer, DbInitializerReal};
 use crate::db_config::config_dao::ConfigDaoReal;
 use crate::db_config::persistent_configuration::{
     PersistentConfiguration, PersistentConfigurationReal,
 };
-use crate::sub_lib::blockchain_bridge::{BlockchainBridgeSubs, OutboundPaymentsInstructions};
+use crate::sub_lib::accountant::PaymentThresholds;
+use crate::sub_lib::blockchain_bridge::{
+    BlockchainBridgeSubs, ConsumingWalletBalances, OutboundPaymentsInstructions,
+};
+use crate::sub_lib::neighborhood::{ConfigChange, ConfigChangeMsg};
 use crate::sub_lib::peer_actors::BindMessage;
 use crate::sub_lib::utils::{db_connection_launch_panic, handle_ui_crash_request};
 use crate::sub_lib::wallet::Wallet;
@@ -31,35 +46,78 @@ use actix::Context;
 use actix::Handler;
 use actix::Message;
 use actix::{Addr, Recipient};
-use itertools::Itertools;
 use masq_lib::blockchains::chains::Chain;
-use masq_lib::constants::DEFAULT_MAX_BLOCK_COUNT;
+use masq_lib::constants::{
+    BLOCKCHAIN_QUERY_ERROR, CONFIGURATOR_READ_ERROR, DEFAULT_MAX_BLOCK_COUNT, FUNDS_TRANSFER_ERROR,
+    MISSING_DATA, NON_PARSABLE_VALUE, WEIS_IN_GWEI,
+};
 use masq_lib::logger::Logger;
-use masq_lib::messages::ScanType;
-use masq_lib::ui_gateway::NodeFromUiMessage;
+use masq_lib::messages::{
+    FromMessageBody, ScanType, ToMessageBody, UiTransferRequest, UiTransferResponse,
+    UiWalletBalances, UiWalletBalancesRequest, UiWalletBalancesResponse,
+};
+use masq_lib::ui_gateway::{
+    MessageBody, MessagePath, MessageTarget, NodeFromUiMessage, NodeToUiMessage,
+};
 use masq_lib::utils::to_string;
 use regex::Regex;
 use std::path::Path;
+use std::str::FromStr;
 use std::time::SystemTime;
-use web3::types::{BlockNumber, TransactionReceipt, H256};
+use web3::types::{BlockNumber, TransactionReceipt, H256, U256};
 
 pub const CRASH_KEY: &str = "BLOCKCHAINBRIDGE";
 
+pub trait BlockchainInterfaceFactory {
+    fn make(&self, blockchain_service_url_opt: Option<String>) -> Box<dyn BlockchainInterface>;
+}
+
+pub struct BlockchainInterfaceFactoryReal {
+    external_signer_opt: Option<ExternalSignerEndpoint>,
+    chain: Chain,
+}
+
+impl BlockchainInterfaceFactory for BlockchainInterfaceFactoryReal {
+    fn make(&self, blockchain_service_url_opt: Option<String>) -> Box<dyn BlockchainInterface> {
+        BlockchainBridge::initialize_blockchain_interface(
+            blockchain_service_url_opt,
+            self.external_signer_opt.clone(),
+            self.chain,
+        )
+    }
+}
+
+impl BlockchainInterfaceFactoryReal {
+    pub fn new(external_signer_opt: Option<ExternalSignerEndpoint>, chain: Chain) -> Self {
+        Self {
+            external_signer_opt,
+            chain,
+        }
+    }
+}
+
 pub struct BlockchainBridge {
     blockchain_interface: Box<dyn BlockchainInterface>,
     logger: Logger,
     persistent_config: Box<dyn PersistentConfiguration>,
+    funds_transfer_dao: Box<dyn FundsTransferDao>,
+    // The payable scanner builds its agent ahead of sending, so a transfer made meanwhile could
+    // take the nonce the agent holds
+    last_transfer_nonce_opt: Option<U256>,
     sent_payable_subs_opt: Option<Recipient<SentPayables>>,
     payable_payments_setup_subs_opt: Option<Recipient<BlockchainAgentWithContextMessage>>,
     received_payments_subs_opt: Option<Recipient<ReceivedPayments>>,
     scan_error_subs_opt: Option<Recipient<ScanError>>,
+    ui_message_sub_opt: Option<Recipient<NodeToUiMessage>>,
     crashable: bool,
     pending_payable_confirmation: TransactionConfirmationTools,
+    blockchain_interface_factory_opt: Option<Box<dyn BlockchainInterfaceFactory>>,
 }
 
 struct TransactionConfirmationTools {
     new_pp_fingerprints_sub_opt: Option<Recipient<PendingPayableFingerprintSeeds>>,
     report_transaction_receipts_sub_opt: Option<Recipient<ReportTransactionReceipts>>,
+    report_fee_bumps_sub_opt: Option<Recipient<ReportFeeBumps>>,
 }
 
 impl Actor for BlockchainBridge {
@@ -76,11 +134,14 @@ impl Handler<BindMessage> for BlockchainBridge {
         self.pending_payable_confirmation
             .report_transaction_receipts_sub_opt =
             Some(msg.peer_actors.accountant.report_transaction_receipts);
+        self.pending_payable_confirmation.report_fee_bumps_sub_opt =
+            Some(msg.peer_actors.accountant.report_fee_bumps);
         self.payable_payments_setup_subs_opt =
             Some(msg.peer_actors.accountant.report_payable_payments_setup);
         self.sent_payable_subs_opt = Some(msg.peer_actors.accountant.report_sent_payments);
         self.received_payments_subs_opt = Some(msg.peer_actors.accountant.report_inbound_payments);
         self.scan_error_subs_opt = Some(msg.peer_actors.accountant.scan_errors);
+        self.ui_message_sub_opt = Some(msg.peer_actors.ui_gateway.node_to_ui_message_sub);
         // There's a multinode integration test looking for this message
         debug!(self.logger, "Received BindMessage");
     }
@@ -126,6 +187,14 @@ impl Handler<RequestTransactionReceipts> for BlockchainBridge {
     }
 }
 
+impl Handler<RequestFeeBumps> for BlockchainBridge {
+    type Result = ();
+
+    fn handle(&mut self, msg: RequestFeeBumps, _ctx: &mut Self::Context) {
+        self.handle_request_fee_bumps(msg)
+    }
+}
+
 impl Handler<QualifiedPayablesMessage> for BlockchainBridge {
     type Result = ();
 
@@ -150,6 +219,11 @@ impl Handler<OutboundPaymentsInstructions> for BlockchainBridge {
 pub struct PendingPayableFingerprintSeeds {
     pub batch_wide_timestamp: SystemTime,
     pub hashes_and_balances: Vec<(H256, u128)>,
+    // How the transactions paying several creditors at once split their amounts among them
+    pub batch_payments: Vec<(H256, Vec<(Wallet, u128)>)>,
+    // Needed to replace a stuck transaction with one paying a higher gas price
+    pub hashes_and_nonces: Vec<(H256, u64)>,
+    pub gas_price_wei: u64,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -164,11 +238,26 @@ pub struct PendingPayableFingerprint {
     pub process_error: Option<String>,
 }
 
+impl Handler<ConfigChangeMsg> for BlockchainBridge {
+    type Result = ();
+
+    fn handle(&mut self, msg: ConfigChangeMsg, _ctx: &mut Self::Context) -> Self::Result {
+        self.handle_config_change_msg(msg)
+    }
+}
+
 impl Handler<NodeFromUiMessage> for BlockchainBridge {
     type Result = ();
 
     fn handle(&mut self, msg: NodeFromUiMessage, _ctx: &mut Self::Context) -> Self::Result {
-        handle_ui_crash_request(msg, &self.logger, self.crashable, CRASH_KEY)
+        let client_id = msg.client_id;
+        if let Ok((request, context_id)) = UiWalletBalancesRequest::fmb(msg.body.clone()) {
+            self.handle_wallet_balances_request(request, client_id, context_id)
+        } else if let Ok((request, context_id)) = UiTransferRequest::fmb(msg.body.clone()) {
+            self.handle_transfer_request(request, client_id, context_id)
+        } else {
+            handle_ui_crash_request(msg, &self.logger, self.crashable, CRASH_KEY)
+        }
     }
 }
 
@@ -176,24 +265,38 @@ impl BlockchainBridge {
     pub fn new(
         blockchain_interface: Box<dyn BlockchainInterface>,
         persistent_config: Box<dyn PersistentConfiguration>,
+        funds_transfer_dao: Box<dyn FundsTransferDao>,
         crashable: bool,
     ) -> BlockchainBridge {
         BlockchainBridge {
             blockchain_interface,
             persistent_config,
+            funds_transfer_dao,
+            last_transfer_nonce_opt: None,
             sent_payable_subs_opt: None,
             payable_payments_setup_subs_opt: None,
             received_payments_subs_opt: None,
             scan_error_subs_opt: None,
+            ui_message_sub_opt: None,
             crashable,
             logger: Logger::new("BlockchainBridge"),
             pending_payable_confirmation: TransactionConfirmationTools {
                 new_pp_fingerprints_sub_opt: None,
                 report_transaction_receipts_sub_opt: None,
+                report_fee_bumps_sub_opt: None,
             },
+            blockchain_interface_factory_opt: None,
         }
     }
 
+    // Without a factory, the interface the Bridge was started with is the only one it will use
+    pub fn set_blockchain_interface_factory(
+        &mut self,
+        blockchain_interface_factory: Box<dyn BlockchainInterfaceFactory>,
+    ) {
+        self.blockchain_interface_factory_opt = Some(blockchain_interface_factory);
+    }
+
     pub fn initialize_persistent_configuration(
         data_directory: &Path,
     ) -> Box<dyn PersistentConfiguration> {
@@ -205,20 +308,51 @@ impl BlockchainBridge {
         Box::new(PersistentConfigurationReal::new(config_dao))
     }
 
+    pub fn initialize_funds_transfer_dao(data_directory: &Path) -> Box<dyn FundsTransferDao> {
+        Box::new(FundsTransferDaoReal::new(
+            DbInitializerReal::default()
+                .initialize(data_directory, DbInitializationConfig::panic_on_migration())
+                .unwrap_or_else(|err| db_connection_launch_panic(err, data_directory)),
+        ))
+    }
+
     pub fn initialize_blockchain_interface(
         blockchain_service_url_opt: Option<String>,
+        external_signer_opt: Option<ExternalSignerEndpoint>,
         chain: Chain,
     ) -> Box<dyn BlockchainInterface> {
         match blockchain_service_url_opt {
             Some(url) => {
                 // TODO if we decided to have interchangeably runtime switchable or simultaneously usable interfaces we will
                 // probably want to make BlockchainInterfaceInitializer a collaborator that's a part of the actor
-                BlockchainInterfaceInitializer {}.initialize_interface(&url, chain)
+                BlockchainInterfaceInitializer {}.initialize_interface(
+                    &url,
+                    external_signer_opt.as_ref(),
+                    chain,
+                )
             }
             None => Box::new(BlockchainInterfaceNull::default()),
         }
     }
 
+    fn handle_config_change_msg(&mut self, msg: ConfigChangeMsg) {
+        match msg.change {
+            ConfigChange::UpdateBlockchainServiceUrl(url) => {
+                match self.blockchain_interface_factory_opt.as_ref() {
+                    Some(factory) => {
+                        self.blockchain_interface = factory.make(Some(url));
+                        info!(self.logger, "Blockchain service URL has been updated");
+                    }
+                    None => warning!(
+                        self.logger,
+                        "Cannot switch to a new blockchain service URL without a restart"
+                    ),
+                }
+            }
+            _ => trace!(self.logger, "Ignored irrelevant message: {:?}", msg),
+        }
+    }
+
     pub fn make_subs_from(addr: &Addr<BlockchainBridge>) -> BlockchainBridgeSubs {
         BlockchainBridgeSubs {
             bind: recipient!(addr, BindMessage),
@@ -227,6 +361,8 @@ impl BlockchainBridge {
             retrieve_transactions: recipient!(addr, RetrieveTransactions),
             ui_sub: recipient!(addr, NodeFromUiMessage),
             request_transaction_receipts: recipient!(addr, RequestTransactionReceipts),
+            request_fee_bumps: recipient!(addr, RequestFeeBumps),
+            config_change_msg_sub: recipient!(addr, ConfigChangeMsg),
         }
     }
 
@@ -292,11 +428,12 @@ impl BlockchainBridge {
         };
         let use_unlimited_block_count_range = u64::MAX == max_block_count;
         let use_latest_block = u64::MAX == start_block_nbr;
-        let end_block = match self
+        let latest_block_result = self
             .blockchain_interface
             .lower_interface()
-            .get_block_number()
-        {
+            .get_block_number();
+        let latest_block_opt = latest_block_result.as_ref().ok().map(|eb| eb.as_u64());
+        let end_block = match latest_block_result {
             Ok(eb) => {
                 if use_unlimited_block_count_range || use_latest_block {
                     BlockNumber::Number(eb)
@@ -327,6 +464,16 @@ impl BlockchainBridge {
         } else {
             BlockNumber::Number(start_block_nbr.into())
         };
+        let scanned_blocks_opt = match (start_block, end_block, latest_block_opt) {
+            (BlockNumber::Number(from), BlockNumber::Number(to), Some(latest)) => {
+                Some(ScannedBlocks {
+                    from: from.as_u64(),
+                    to: to.as_u64(),
+                    latest,
+                })
+            }
+            _ => None,
+        };
         let retrieved_transactions =
             self.blockchain_interface
                 .retrieve_transactions(start_block, end_block, &msg.recipient);
@@ -343,6 +490,7 @@ impl BlockchainBridge {
                             timestamp: SystemTime::now(),
                             payments: transactions.transactions,
                             new_start_block: new_start_block_number.as_u64(),
+                            scanned_blocks_opt,
                             response_skeleton_opt: msg.response_skeleton_opt,
                         })
                         .expect("Accountant is dead.");
@@ -384,29 +532,23 @@ impl BlockchainBridge {
         msg: RequestTransactionReceipts,
     ) -> Result<(), String> {
         let init: (
-            Vec<Option<TransactionReceipt>>,
+            Vec<(Option<TransactionReceipt>, PendingPayableFingerprint)>,
             Option<(BlockchainError, H256)>,
         ) = (vec![], None);
-        let (vector_of_results, error_opt) = msg.pending_payable.iter().fold(
+        let replaced_transactions = msg.replaced_transactions;
+        let (pairs, error_opt) = msg.pending_payable.into_iter().fold(
             init,
-            |(mut ok_receipts, err_opt), current_fingerprint| match err_opt {
-                None => match self
-                    .blockchain_interface
-                    .get_transaction_receipt(current_fingerprint.hash)
-                {
-                    Ok(receipt_opt) => {
-                        ok_receipts.push(receipt_opt);
-                        (ok_receipts, None)
+            |(mut ok_pairs, err_opt), current_fingerprint| match err_opt {
+                None => match self.fetch_receipt(current_fingerprint, &replaced_transactions) {
+                    Ok(pair) => {
+                        ok_pairs.push(pair);
+                        (ok_pairs, None)
                     }
-                    Err(e) => (ok_receipts, Some((e, current_fingerprint.hash))),
+                    Err(e) => (ok_pairs, Some(e)),
                 },
-                _ => (ok_receipts, err_opt),
+                _ => (ok_pairs, err_opt),
             },
         );
-        let pairs = vector_of_results
-            .into_iter()
-            .zip(msg.pending_payable.into_iter())
-            .collect_vec();
         self.pending_payable_confirmation
             .report_transaction_receipts_sub_opt
             .as_ref()
@@ -426,6 +568,313 @@ impl BlockchainBridge {
         Ok(())
     }
 
+    // The fingerprint follows the latest replacement, yet any transaction it replaced may have
+    // been mined first; then the fingerprint is reported with the hash that actually made it
+    fn fetch_receipt(
+        &self,
+        fingerprint: PendingPayableFingerprint,
+        replaced_transactions: &[PendingPayableId],
+    ) -> Result<(Option<TransactionReceipt>, PendingPayableFingerprint), (BlockchainError, H256)>
+    {
+        let receipt_opt = self
+            .blockchain_interface
+            .get_transaction_receipt(fingerprint.hash)
+            .map_err(|e| (e, fingerprint.hash))?;
+        if receipt_opt.is_some() {
+            return Ok((receipt_opt, fingerprint));
+        }
+        for replaced in replaced_transactions
+            .iter()
+            .rev()
+            .filter(|replaced| replaced.rowid == fingerprint.rowid)
+        {
+            if let Some(receipt) = self
+                .blockchain_interface
+                .get_transaction_receipt(replaced.hash)
+                .map_err(|e| (e, replaced.hash))?
+            {
+                return Ok((
+                    Some(receipt),
+                    PendingPayableFingerprint {
+                        hash: replaced.hash,
+                        ..fingerprint
+                    },
+                ));
+            }
+        }
+        Ok((None, fingerprint))
+    }
+
+    fn handle_request_fee_bumps(&mut self, msg: RequestFeeBumps) {
+        let fee_bumps = msg
+            .stuck_transactions
+            .iter()
+            .flat_map(|stuck| {
+                match self.blockchain_interface.bump_transaction_fee(
+                    &msg.consuming_wallet,
+                    stuck,
+                    msg.gas_price_increase_percent,
+                ) {
+                    Ok(bumped) => Some(PendingPayableFeeBump {
+                        pending_payable_rowid: stuck.rowid,
+                        replaced_hash: stuck.hash,
+                        replacement_hash: bumped.hash,
+                        gas_price_wei: bumped.gas_price_wei,
+                        timestamp: SystemTime::now(),
+                    }),
+                    Err(e) => {
+                        warning!(
+                            self.logger,
+                            "Failed to replace stuck transaction {:?}: {}",
+                            stuck.hash,
+                            e
+                        );
+                        None
+                    }
+                }
+            })
+            .collect();
+        self.pending_payable_confirmation
+            .report_fee_bumps_sub_opt
+            .as_ref()
+            .expect("Accountant is unbound")
+            .try_send(ReportFeeBumps {
+                fee_bumps,
+                response_skeleton_opt: msg.response_skeleton_opt,
+            })
+            .expect("Accountant is dead");
+    }
+
+    fn handle_wallet_balances_request(
+        &self,
+        request: UiWalletBalancesRequest,
+        client_id: u64,
+        context_id: u64,
+    ) {
+        let body = match self.compute_wallet_balances(&request.db_password) {
+            Ok(response) => response.tmb(context_id),
+            Err((code, message)) => {
+                warning!(
+                    self.logger,
+                    "Failed to obtain wallet balances: {}, {}",
+                    code,
+                    message
+                );
+                MessageBody {
+                    opcode: request.opcode().to_string(),
+                    path: MessagePath::Conversation(context_id),
+                    payload: Err((code, message)),
+                }
+            }
+        };
+        self.ui_message_sub_opt
+            .as_ref()
+            .expect("UIGateway is unbound")
+            .try_send(NodeToUiMessage {
+                target: MessageTarget::ClientId(client_id),
+                body,
+            })
+            .expect("UIGateway is dead")
+    }
+
+    fn compute_wallet_balances(
+        &self,
+        db_password: &str,
+    ) -> Result<UiWalletBalancesResponse, (u64, String)> {
+        let (consuming_wallet, earning_wallet) = match (
+            self.persistent_config.consuming_wallet(db_password),
+            self.persistent_config.earning_wallet(),
+        ) {
+            (Ok(Some(consuming_wallet)), Ok(Some(earning_wallet))) => {
+                (consuming_wallet, earning_wallet)
+            }
+            (Err(ce), _) => {
+                return Err((
+                    CONFIGURATOR_READ_ERROR,
+                    format!("Consuming wallet error: {:?}", ce),
+                ))
+            }
+            (_, Err(ee)) => {
+                return Err((
+                    CONFIGURATOR_READ_ERROR,
+                    format!("Earning wallet error: {:?}", ee),
+                ))
+            }
+            _ => return Err((MISSING_DATA, "Wallet pair not yet configured".to_string())),
+        };
+        let payment_thresholds = self.persistent_config.payment_thresholds().map_err(|e| {
+            (
+                CONFIGURATOR_READ_ERROR,
+                format!("Payment thresholds error: {:?}", e),
+            )
+        })?;
+        let agent = self
+            .blockchain_interface
+            .build_blockchain_agent(&consuming_wallet, self.persistent_config.as_ref())
+            .map_err(|e| (BLOCKCHAIN_QUERY_ERROR, e.to_string()))?;
+        let consuming_wallet_balances = agent.consuming_wallet_balances();
+        let earning_wallet_balances = self.query_wallet_balances(&earning_wallet)?;
+        Ok(UiWalletBalancesResponse {
+            consuming_wallet: UiWalletBalances {
+                address: format!("{:?}", consuming_wallet.address()),
+                transaction_fee_balance_gwei: Self::saturating_wei_to_gwei(
+                    consuming_wallet_balances.transaction_fee_balance_in_minor_units,
+                ),
+                masq_balance_gwei: Self::saturating_wei_to_gwei(
+                    consuming_wallet_balances.masq_token_balance_in_minor_units,
+                ),
+            },
+            earning_wallet: earning_wallet_balances,
+            gas_price_gwei: agent.agreed_fee_per_computation_unit(),
+            affordable_payables: Self::affordable_payables(
+                &consuming_wallet_balances,
+                agent.estimated_transaction_fee_total(1),
+                &payment_thresholds,
+            ),
+        })
+    }
+
+    fn query_wallet_balances(&self, wallet: &Wallet) -> Result<UiWalletBalances, (u64, String)> {
+        let lower_interface = self.blockchain_interface.lower_interface();
+        let transaction_fee_balance = lower_interface
+            .get_transaction_fee_balance(wallet)
+            .map_err(|e| (BLOCKCHAIN_QUERY_ERROR, e.to_string()))?;
+        let masq_balance = lower_interface
+            .get_service_fee_balance(wallet)
+            .map_err(|e| (BLOCKCHAIN_QUERY_ERROR, e.to_string()))?;
+        Ok(UiWalletBalances {
+            address: format!("{:?}", wallet.address()),
+            transaction_fee_balance_gwei: Self::saturating_wei_to_gwei(transaction_fee_balance),
+            masq_balance_gwei: Self::saturating_wei_to_gwei(masq_balance),
+        })
+    }
+
+    // Every payable is assumed to have grown up to the debt threshold, which is when the payable
+    // scanner starts to qualify it for payment
+    fn affordable_payables(
+        balances: &ConsumingWalletBalances,
+        fee_per_transaction: u128,
+        payment_thresholds: &PaymentThresholds,
+    ) -> u64 {
+        let fee_per_transaction = U256::from(fee_per_transaction.max(1));
+        let masq_per_payable =
+            U256::from(payment_thresholds.debt_threshold_gwei.max(1)) * U256::from(WEIS_IN_GWEI);
+        let affordable = (balances.transaction_fee_balance_in_minor_units / fee_per_transaction)
+            .min(balances.masq_token_balance_in_minor_units / masq_per_payable);
+        affordable.min(U256::from(u64::MAX)).as_u64()
+    }
+
+    fn saturating_wei_to_gwei(wei: U256) -> u64 {
+        (wei / U256::from(WEIS_IN_GWEI))
+            .min(U256::from(u64::MAX))
+            .as_u64()
+    }
+
+    fn handle_transfer_request(
+        &mut self,
+        request: UiTransferRequest,
+        client_id: u64,
+        context_id: u64,
+    ) {
+        let body = match self.transfer_funds(&request) {
+            Ok(response) => response.tmb(context_id),
+            Err((code, message)) => {
+                warning!(
+                    self.logger,
+                    "Failed to transfer funds: {}, {}",
+                    code,
+                    message
+                );
+                MessageBody {
+                    opcode: request.opcode().to_string(),
+                    path: MessagePath::Conversation(context_id),
+                    payload: Err((code, message)),
+                }
+            }
+        };
+        self.ui_message_sub_opt
+            .as_ref()
+            .expect("UIGateway is unbound")
+            .try_send(NodeToUiMessage {
+                target: MessageTarget::ClientId(client_id),
+                body,
+            })
+            .expect("UIGateway is dead")
+    }
+
+    // Only the consuming wallet can be drained this way; the Node holds no key to the earning one
+    fn transfer_funds(
+        &mut self,
+        request: &UiTransferRequest,
+    ) -> Result<UiTransferResponse, (u64, String)> {
+        let consuming_wallet = match self
+            .persistent_config
+            .consuming_wallet(&request.db_password)
+        {
+            Ok(Some(wallet)) => wallet,
+            Ok(None) => {
+                return Err((
+                    MISSING_DATA,
+                    "Consuming wallet not yet configured".to_string(),
+                ))
+            }
+            Err(e) => {
+                return Err((
+                    CONFIGURATOR_READ_ERROR,
+                    format!("Consuming wallet error: {:?}", e),
+                ))
+            }
+        };
+        let recipient = Wallet::from_str(&request.recipient).map_err(|e| {
+            (
+                NON_PARSABLE_VALUE,
+                format!("Recipient address '{}': {:?}", request.recipient, e),
+            )
+        })?;
+        let agent = self
+            .blockchain_interface
+            .build_blockchain_agent(&consuming_wallet, self.persistent_config.as_ref())
+            .map_err(|e| (BLOCKCHAIN_QUERY_ERROR, e.to_string()))?;
+        let order = FundsTransferOrder {
+            recipient,
+            currency: request.currency,
+            amount_wei_opt: request.amount_gwei_opt.map(gwei_to_wei::<u128, _>),
+            dry_run: request.dry_run,
+        };
+        let outcome = self
+            .blockchain_interface
+            .transfer_funds(agent.as_ref(), &order)
+            .map_err(|e| (FUNDS_TRANSFER_ERROR, e.to_string()))?;
+        if let Some(hash) = outcome.hash_opt {
+            self.last_transfer_nonce_opt = Some(outcome.nonce);
+            let record = FundsTransferRecord {
+                hash,
+                recipient: order.recipient.clone(),
+                currency: order.currency,
+                amount_wei: outcome.amount_wei,
+                gas_price_wei: outcome.gas_price_wei,
+                nonce: checked_conversion::<U256, u64>(outcome.nonce),
+                timestamp: SystemTime::now(),
+            };
+            if let Err(e) = self.funds_transfer_dao.record_transfer(&record) {
+                // The transaction is on its way already, failing the request would only mislead
+                error!(
+                    self.logger,
+                    "Failed to record funds transfer {:?} in the history: {:?}", hash, e
+                )
+            }
+        }
+        Ok(UiTransferResponse {
+            transaction_hash_opt: outcome.hash_opt.map(|hash| format!("{:?}", hash)),
+            recipient: format!("{:?}", order.recipient.address()),
+            currency: order.currency,
+            amount_gwei: wei_to_gwei(outcome.amount_wei),
+            fee_estimate_gwei: wei_to_gwei(outcome.fee_estimate_wei),
+            gas_price_gwei: wei_to_gwei(outcome.gas_price_wei),
+            nonce: checked_conversion::<U256, u64>(outcome.nonce),
+        })
+    }
+
     fn handle_scan<M, F>(&mut self, handler: F, scan_type: ScanType, msg: M)
     where
         F: FnOnce(&mut BlockchainBridge, M) -> Result<(), String>,
@@ -454,6 +903,12 @@ impl BlockchainBridge {
         agent: Box<dyn BlockchainAgent>,
         affordable_accounts: Vec<PayableAccount>,
     ) -> Result<Vec<ProcessedPayableFallible>, PayableTransactionError> {
+        if let Some(transfer_nonce) = self.last_transfer_nonce_opt {
+            let pending_nonce = agent.pending_transaction_id();
+            if pending_nonce <= transfer_nonce {
+                return Err(PayableTransactionError::NonceTakenByTransfer(pending_nonce));
+            }
+        }
         let new_fingerprints_recipient = self.new_fingerprints_recipient();
 
         self.blockchain_interface.send_batch_of_payables(
@@ -511,25 +966,33 @@ impl SubsFactory<BlockchainBridge, BlockchainBridgeSubs> for BlockchainBridgeSub
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::accountant::db_access_objects::funds_transfer_dao::FundsTransferDaoError;
     use crate::accountant::db_access_objects::payable_dao::PayableAccount;
     use crate::accountant::db_access_objects::pending_payable_dao::PendingPayable;
     use crate::accountant::db_access_objects::utils::from_time_t;
     use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::test_utils::BlockchainAgentMock;
     use crate::accountant::scanners::test_utils::protect_payables_in_test;
-    use crate::accountant::test_utils::make_pending_payable_fingerprint;
+    use crate::accountant::test_utils::{
+        make_payable_account, make_pending_payable_fingerprint, FundsTransferDaoMock,
+    };
+    use crate::accountant::PendingPayableId;
     use crate::blockchain::blockchain_interface::blockchain_interface_null::BlockchainInterfaceNull;
     use crate::blockchain::blockchain_interface::data_structures::errors::{
-        BlockchainAgentBuildError, PayableTransactionError,
+        BlockchainAgentBuildError, FeeBumpError, FundsTransferError, PayableTransactionError,
     };
     use crate::blockchain::blockchain_interface::data_structures::{
-        BlockchainTransaction, RetrievedBlockchainTransactions,
+        BlockchainTransaction, FeeBumpedTransaction, FundsTransferOutcome,
+        RetrievedBlockchainTransactions,
     };
     use crate::blockchain::blockchain_interface::lower_level_interface::LatestBlockNumber;
     use crate::blockchain::blockchain_interface::test_utils::LowBlockchainIntMock;
-    use crate::blockchain::test_utils::{make_tx_hash, BlockchainInterfaceMock};
+    use crate::blockchain::test_utils::{
+        make_stuck_transaction, make_tx_hash, BlockchainInterfaceMock,
+    };
     use crate::db_config::persistent_configuration::PersistentConfigError;
     use crate::match_every_type_id;
     use crate::node_test_utils::check_timestamp;
+    use crate::sub_lib::accountant::DEFAULT_PAYMENT_THRESHOLDS;
     use crate::test_utils::persistent_configuration_mock::PersistentConfigurationMock;
     use crate::test_utils::recorder::{make_recorder, peer_actors_builder};
     use crate::test_utils::recorder_stop_conditions::StopCondition;
@@ -543,11 +1006,12 @@ mod tests {
     use actix::System;
     use ethereum_types::U64;
     use ethsign_crypto::Keccak256;
-    use masq_lib::messages::ScanType;
+    use masq_lib::messages::{ScanType, TransferCurrency};
     use masq_lib::test_utils::logging::init_test_logging;
     use masq_lib::test_utils::logging::TestLogHandler;
     use masq_lib::test_utils::utils::{ensure_node_home_directory_exists, TEST_DEFAULT_CHAIN};
     use std::any::TypeId;
+    use std::cell::RefCell;
     use std::path::Path;
     use std::sync::{Arc, Mutex};
     use std::time::{Duration, SystemTime};
@@ -570,9 +1034,96 @@ mod tests {
         assert_eq!(CRASH_KEY, "BLOCKCHAINBRIDGE");
     }
 
+    #[derive(Default)]
+    struct BlockchainInterfaceFactoryMock {
+        make_params: Arc<Mutex<Vec<Option<String>>>>,
+        make_results: RefCell<Vec<Box<dyn BlockchainInterface>>>,
+    }
+
+    impl BlockchainInterfaceFactory for BlockchainInterfaceFactoryMock {
+        fn make(&self, blockchain_service_url_opt: Option<String>) -> Box<dyn BlockchainInterface> {
+            self.make_params
+                .lock()
+                .unwrap()
+                .push(blockchain_service_url_opt);
+            self.make_results.borrow_mut().remove(0)
+        }
+    }
+
+    impl BlockchainInterfaceFactoryMock {
+        fn make_params(mut self, params: &Arc<Mutex<Vec<Option<String>>>>) -> Self {
+            self.make_params = params.clone();
+            self
+        }
+
+        fn make_result(self, result: Box<dyn BlockchainInterface>) -> Self {
+            self.make_results.borrow_mut().push(result);
+            self
+        }
+    }
+
+    #[test]
+    fn new_blockchain_service_url_gets_a_new_blockchain_interface() {
+        init_test_logging();
+        let test_name = "new_blockchain_service_url_gets_a_new_blockchain_interface";
+        let make_params_arc = Arc::new(Mutex::new(vec![]));
+        let factory = BlockchainInterfaceFactoryMock::default()
+            .make_params(&make_params_arc)
+            .make_result(Box::new(BlockchainInterfaceMock::default()));
+        let mut subject = BlockchainBridge::new(
+            Box::new(BlockchainInterfaceMock::default()),
+            Box::new(PersistentConfigurationMock::default()),
+            Box::new(FundsTransferDaoMock::default()),
+            false,
+        );
+        subject.set_blockchain_interface_factory(Box::new(factory));
+        subject.logger = Logger::new(test_name);
+
+        subject.handle_config_change_msg(ConfigChangeMsg {
+            change: ConfigChange::UpdateBlockchainServiceUrl(
+                "https://example.com:8545/secret-key".to_string(),
+            ),
+        });
+
+        let make_params = make_params_arc.lock().unwrap();
+        assert_eq!(
+            *make_params,
+            vec![Some("https://example.com:8545/secret-key".to_string())]
+        );
+        let tlh = TestLogHandler::new();
+        tlh.exists_log_containing(&format!(
+            "INFO: {}: Blockchain service URL has been updated",
+            test_name
+        ));
+        tlh.exists_no_log_containing("secret-key");
+    }
+
+    #[test]
+    fn new_blockchain_service_url_is_refused_without_an_interface_factory() {
+        init_test_logging();
+        let test_name = "new_blockchain_service_url_is_refused_without_an_interface_factory";
+        let mut subject = BlockchainBridge::new(
+            Box::new(BlockchainInterfaceMock::default()),
+            Box::new(PersistentConfigurationMock::default()),
+            Box::new(FundsTransferDaoMock::default()),
+            false,
+        );
+        subject.logger = Logger::new(test_name);
+
+        subject.handle_config_change_msg(ConfigChangeMsg {
+            change: ConfigChange::UpdateBlockchainServiceUrl("https://example.com".to_string()),
+        });
+
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: {}: Cannot switch to a new blockchain service URL without a restart",
+            test_name
+        ));
+    }
+
     #[test]
     fn blockchain_interface_null_as_result_of_missing_blockchain_service_url() {
-        let result = BlockchainBridge::initialize_blockchain_interface(None, TEST_DEFAULT_CHAIN);
+        let result =
+            BlockchainBridge::initialize_blockchain_interface(None, None, TEST_DEFAULT_CHAIN);
 
         result
             .as_any()
@@ -620,6 +1171,7 @@ mod tests {
         let subject = BlockchainBridge::new(
             Box::new(blockchain_interface),
             Box::new(persistent_configuration),
+            Box::new(FundsTransferDaoMock::default()),
             false,
         );
         let addr = subject.start();
@@ -681,6 +1233,7 @@ mod tests {
         let mut subject = BlockchainBridge::new(
             Box::new(blockchain_interface),
             Box::new(persistent_configuration),
+            Box::new(FundsTransferDaoMock::default()),
             false,
         );
         subject.logger = Logger::new(test_name);
@@ -754,6 +1307,7 @@ mod tests {
         let subject = BlockchainBridge::new(
             Box::new(blockchain_interface_mock),
             Box::new(PersistentConfigurationMock::default()),
+            Box::new(FundsTransferDaoMock::default()),
             false,
         );
         let addr = subject.start();
@@ -841,6 +1395,7 @@ mod tests {
         let subject = BlockchainBridge::new(
             Box::new(blockchain_interface_mock),
             Box::new(persistent_configuration_mock),
+            Box::new(FundsTransferDaoMock::default()),
             false,
         );
         let addr = subject.start();
@@ -911,6 +1466,7 @@ mod tests {
         let mut subject = BlockchainBridge::new(
             Box::new(blockchain_interface_mock),
             Box::new(persistent_configuration_mock),
+            Box::new(FundsTransferDaoMock::default()),
             false,
         );
         let checked_accounts = vec![PayableAccount {
@@ -937,6 +1493,54 @@ mod tests {
         );
     }
 
+    #[test]
+    fn process_payments_refuses_agent_whose_nonce_was_taken_by_a_transfer() {
+        let mut subject = BlockchainBridge::new(
+            Box::new(BlockchainInterfaceMock::default()),
+            Box::new(PersistentConfigurationMock::new()),
+            Box::new(FundsTransferDaoMock::default()),
+            false,
+        );
+        subject.last_transfer_nonce_opt = Some(U256::from(12));
+        let agent =
+            Box::new(BlockchainAgentMock::default().pending_transaction_id_result(U256::from(12)));
+
+        let result = subject.process_payments(agent, vec![make_payable_account(111)]);
+
+        assert_eq!(
+            result,
+            Err(PayableTransactionError::NonceTakenByTransfer(U256::from(
+                12
+            )))
+        );
+    }
+
+    #[test]
+    fn process_payments_goes_on_if_the_nonce_follows_the_last_transfer() {
+        let send_batch_of_payables_params_arc = Arc::new(Mutex::new(vec![]));
+        let blockchain_interface = BlockchainInterfaceMock::default()
+            .send_batch_of_payables_params(&send_batch_of_payables_params_arc)
+            .send_batch_of_payables_result(Ok(vec![]));
+        let mut subject = BlockchainBridge::new(
+            Box::new(blockchain_interface),
+            Box::new(PersistentConfigurationMock::new()),
+            Box::new(FundsTransferDaoMock::default()),
+            false,
+        );
+        subject.last_transfer_nonce_opt = Some(U256::from(12));
+        let (accountant, _, _) = make_recorder();
+        subject
+            .pending_payable_confirmation
+            .new_pp_fingerprints_sub_opt = Some(accountant.start().recipient());
+        let agent =
+            Box::new(BlockchainAgentMock::default().pending_transaction_id_result(U256::from(13)));
+
+        let result = subject.process_payments(agent, vec![make_payable_account(111)]);
+
+        assert_eq!(result, Ok(vec![]));
+        assert_eq!(send_batch_of_payables_params_arc.lock().unwrap().len(), 1);
+    }
+
     #[test]
     fn blockchain_bridge_processes_requests_for_transaction_receipts_when_all_were_ok() {
         let get_transaction_receipt_params_arc = Arc::new(Mutex::new(vec![]));
@@ -959,6 +1563,7 @@ mod tests {
         let subject = BlockchainBridge::new(
             Box::new(blockchain_interface_mock),
             Box::new(PersistentConfigurationMock::default()),
+            Box::new(FundsTransferDaoMock::default()),
             false,
         );
         let addr = subject.start();
@@ -970,6 +1575,7 @@ mod tests {
                 pending_payable_fingerprint_1.clone(),
                 pending_payable_fingerprint_2.clone(),
             ],
+            replaced_transactions: vec![],
             response_skeleton_opt: Some(ResponseSkeleton {
                 client_id: 1234,
                 context_id: 4321,
@@ -1004,6 +1610,629 @@ mod tests {
         assert_eq!(*get_transaction_receipt_params, vec![hash_1, hash_2])
     }
 
+    #[test]
+    fn blockchain_bridge_replaces_stuck_transactions_and_reports_only_the_successful_ones() {
+        init_test_logging();
+        let test_name =
+            "blockchain_bridge_replaces_stuck_transactions_and_reports_only_the_successful_ones";
+        let bump_transaction_fee_params_arc = Arc::new(Mutex::new(vec![]));
+        let (accountant, _, accountant_recording_arc) = make_recorder();
+        let consuming_wallet = make_paying_wallet(b"stuck");
+        let stuck_transaction_1 = make_stuck_transaction(45);
+        let stuck_transaction_2 = make_stuck_transaction(46);
+        let replacement_hash = make_tx_hash(333);
+        let blockchain_interface_mock = BlockchainInterfaceMock::default()
+            .bump_transaction_fee_params(&bump_transaction_fee_params_arc)
+            .bump_transaction_fee_result(Ok(FeeBumpedTransaction {
+                hash: replacement_hash,
+                gas_price_wei: 12_000_000_000,
+            }))
+            .bump_transaction_fee_result(Err(FeeBumpError::Sending("nonce too low".to_string())));
+        let mut subject = BlockchainBridge::new(
+            Box::new(blockchain_interface_mock),
+            Box::new(PersistentConfigurationMock::default()),
+            Box::new(FundsTransferDaoMock::default()),
+            false,
+        );
+        subject.logger = Logger::new(test_name);
+        let addr = subject.start();
+        let subject_subs = BlockchainBridge::make_subs_from(&addr);
+        let peer_actors = peer_actors_builder().accountant(accountant).build();
+        send_bind_message!(subject_subs, peer_actors);
+        let msg = RequestFeeBumps {
+            consuming_wallet: consuming_wallet.clone(),
+            stuck_transactions: vec![stuck_transaction_1.clone(), stuck_transaction_2.clone()],
+            gas_price_increase_percent: 20,
+            response_skeleton_opt: Some(ResponseSkeleton {
+                client_id: 12,
+                context_id: 34,
+            }),
+        };
+        let before = SystemTime::now();
+
+        let _ = addr.try_send(msg).unwrap();
+
+        let system = System::new(test_name);
+        System::current().stop();
+        system.run();
+        let after = SystemTime::now();
+        let accountant_recording = accountant_recording_arc.lock().unwrap();
+        assert_eq!(accountant_recording.len(), 1);
+        let received_message = accountant_recording.get_record::<ReportFeeBumps>(0);
+        assert_eq!(received_message.fee_bumps.len(), 1);
+        let fee_bump = &received_message.fee_bumps[0];
+        check_timestamp(before, fee_bump.timestamp, after);
+        assert_eq!(
+            fee_bump,
+            &PendingPayableFeeBump {
+                pending_payable_rowid: 45,
+                replaced_hash: stuck_transaction_1.hash,
+                replacement_hash,
+                gas_price_wei: 12_000_000_000,
+                timestamp: fee_bump.timestamp,
+            }
+        );
+        assert_eq!(
+            received_message.response_skeleton_opt,
+            Some(ResponseSkeleton {
+                client_id: 12,
+                context_id: 34,
+            })
+        );
+        let bump_transaction_fee_params = bump_transaction_fee_params_arc.lock().unwrap();
+        assert_eq!(
+            *bump_transaction_fee_params,
+            vec![
+                (consuming_wallet.clone(), stuck_transaction_1, 20),
+                (consuming_wallet, stuck_transaction_2.clone(), 20)
+            ]
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: {test_name}: Failed to replace stuck transaction {:?}: Sending phase: \
+            \"nonce too low\"",
+            stuck_transaction_2.hash
+        ));
+    }
+
+    #[test]
+    fn wallet_balances_request_is_answered_with_balances_of_both_wallets() {
+        let build_blockchain_agent_params_arc = Arc::new(Mutex::new(vec![]));
+        let estimated_transaction_fee_total_params_arc = Arc::new(Mutex::new(vec![]));
+        let get_transaction_fee_balance_params_arc = Arc::new(Mutex::new(vec![]));
+        let get_masq_balance_params_arc = Arc::new(Mutex::new(vec![]));
+        let consuming_wallet_params_arc = Arc::new(Mutex::new(vec![]));
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let consuming_wallet = make_paying_wallet(b"consuming");
+        let earning_wallet = make_wallet("earning");
+        let agent = BlockchainAgentMock::default()
+            .consuming_wallet_balances_result(ConsumingWalletBalances {
+                transaction_fee_balance_in_minor_units: U256::from(1_000_000_000_000_000_000_u128),
+                masq_token_balance_in_minor_units: U256::from(25_000_000_000_000_000_000_u128),
+            })
+            .agreed_fee_per_computation_unit_result(50)
+            .estimated_transaction_fee_total_params(&estimated_transaction_fee_total_params_arc)
+            .estimated_transaction_fee_total_result(3_000_000_000_000_000);
+        let lower_interface = LowBlockchainIntMock::default()
+            .get_transaction_fee_balance_params(&get_transaction_fee_balance_params_arc)
+            .get_transaction_fee_balance_result(Ok(U256::from(123_456_789_000_000_000_u128)))
+            .get_masq_balance_params(&get_masq_balance_params_arc)
+            .get_masq_balance_result(Ok(U256::from(500_000_000_000_000_000_000_u128)));
+        let blockchain_interface = BlockchainInterfaceMock::default()
+            .build_blockchain_agent_params(&build_blockchain_agent_params_arc)
+            .build_blockchain_agent_result(Ok(Box::new(agent)))
+            .lower_interface_results(Box::new(lower_interface));
+        let persistent_config = PersistentConfigurationMock::default()
+            .consuming_wallet_params(&consuming_wallet_params_arc)
+            .consuming_wallet_result(Ok(Some(consuming_wallet.clone())))
+            .earning_wallet_result(Ok(Some(earning_wallet.clone())))
+            .payment_thresholds_result(Ok(PaymentThresholds {
+                debt_threshold_gwei: 10_000_000_000,
+                ..*DEFAULT_PAYMENT_THRESHOLDS
+            }));
+        let subject = BlockchainBridge::new(
+            Box::new(blockchain_interface),
+            Box::new(persistent_config),
+            Box::new(FundsTransferDaoMock::default()),
+            false,
+        );
+        let addr = subject.start();
+        let subject_subs = BlockchainBridge::make_subs_from(&addr);
+        let peer_actors = peer_actors_builder().ui_gateway(ui_gateway).build();
+        send_bind_message!(subject_subs, peer_actors);
+
+        addr.try_send(NodeFromUiMessage {
+            client_id: 1234,
+            body: UiWalletBalancesRequest {
+                db_password: "password".to_string(),
+            }
+            .tmb(4321),
+        })
+        .unwrap();
+
+        let system = System::new("wallet balances");
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        assert_eq!(
+            ui_gateway_recording.get_record::<NodeToUiMessage>(0),
+            &NodeToUiMessage {
+                target: MessageTarget::ClientId(1234),
+                body: UiWalletBalancesResponse {
+                    consuming_wallet: UiWalletBalances {
+                        address: format!("{:?}", consuming_wallet.address()),
+                        transaction_fee_balance_gwei: 1_000_000_000,
+                        masq_balance_gwei: 25_000_000_000,
+                    },
+                    earning_wallet: UiWalletBalances {
+                        address: format!("{:?}", earning_wallet.address()),
+                        transaction_fee_balance_gwei: 123_456_789,
+                        masq_balance_gwei: 500_000_000_000,
+                    },
+                    gas_price_gwei: 50,
+                    affordable_payables: 2,
+                }
+                .tmb(4321),
+            }
+        );
+        let consuming_wallet_params = consuming_wallet_params_arc.lock().unwrap();
+        assert_eq!(*consuming_wallet_params, vec!["password".to_string()]);
+        let build_blockchain_agent_params = build_blockchain_agent_params_arc.lock().unwrap();
+        assert_eq!(build_blockchain_agent_params[0].0, consuming_wallet);
+        let estimated_transaction_fee_total_params =
+            estimated_transaction_fee_total_params_arc.lock().unwrap();
+        assert_eq!(*estimated_transaction_fee_total_params, vec![1]);
+        let get_transaction_fee_balance_params =
+            get_transaction_fee_balance_params_arc.lock().unwrap();
+        assert_eq!(
+            *get_transaction_fee_balance_params,
+            vec![earning_wallet.clone()]
+        );
+        let get_masq_balance_params = get_masq_balance_params_arc.lock().unwrap();
+        assert_eq!(*get_masq_balance_params, vec![earning_wallet]);
+    }
+
+    #[test]
+    fn affordable_payables_are_limited_by_the_scarcer_balance() {
+        let payment_thresholds = PaymentThresholds {
+            debt_threshold_gwei: 1_000_000_000,
+            ..*DEFAULT_PAYMENT_THRESHOLDS
+        };
+        let balances = |transaction_fee_wei: u128, masq_wei: u128| ConsumingWalletBalances {
+            transaction_fee_balance_in_minor_units: U256::from(transaction_fee_wei),
+            masq_token_balance_in_minor_units: U256::from(masq_wei),
+        };
+
+        let limited_by_transaction_fee = BlockchainBridge::affordable_payables(
+            &balances(3_999, 100_000_000_000_000_000_000),
+            1_000,
+            &payment_thresholds,
+        );
+        let limited_by_masq = BlockchainBridge::affordable_payables(
+            &balances(1_000_000, 7_500_000_000_000_000_000),
+            1_000,
+            &payment_thresholds,
+        );
+        let free_transactions = BlockchainBridge::affordable_payables(
+            &balances(0, 1_000_000_000_000_000_000),
+            0,
+            &payment_thresholds,
+        );
+
+        assert_eq!(limited_by_transaction_fee, 3);
+        assert_eq!(limited_by_masq, 7);
+        assert_eq!(free_transactions, 0);
+    }
+
+    #[test]
+    fn wallet_balances_request_reports_missing_wallets() {
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let persistent_config = PersistentConfigurationMock::default()
+            .consuming_wallet_result(Ok(None))
+            .earning_wallet_result(Ok(None));
+        let subject = BlockchainBridge::new(
+            Box::new(BlockchainInterfaceMock::default()),
+            Box::new(persistent_config),
+            Box::new(FundsTransferDaoMock::default()),
+            false,
+        );
+        let addr = subject.start();
+        let subject_subs = BlockchainBridge::make_subs_from(&addr);
+        let peer_actors = peer_actors_builder().ui_gateway(ui_gateway).build();
+        send_bind_message!(subject_subs, peer_actors);
+
+        addr.try_send(NodeFromUiMessage {
+            client_id: 1234,
+            body: UiWalletBalancesRequest {
+                db_password: "password".to_string(),
+            }
+            .tmb(4321),
+        })
+        .unwrap();
+
+        let system = System::new("wallet balances");
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        assert_eq!(
+            ui_gateway_recording.get_record::<NodeToUiMessage>(0),
+            &NodeToUiMessage {
+                target: MessageTarget::ClientId(1234),
+                body: MessageBody {
+                    opcode: "walletBalances".to_string(),
+                    path: MessagePath::Conversation(4321),
+                    payload: Err((MISSING_DATA, "Wallet pair not yet configured".to_string())),
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn wallet_balances_request_reports_failed_blockchain_query() {
+        init_test_logging();
+        let test_name = "wallet_balances_request_reports_failed_blockchain_query";
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let agent = BlockchainAgentMock::default().consuming_wallet_balances_result(
+            ConsumingWalletBalances {
+                transaction_fee_balance_in_minor_units: U256::from(1_000_000_000_u64),
+                masq_token_balance_in_minor_units: U256::from(1_000_000_000_u64),
+            },
+        );
+        let lower_interface = LowBlockchainIntMock::default().get_transaction_fee_balance_result(
+            Err(BlockchainError::QueryFailed("booga".to_string())),
+        );
+        let blockchain_interface = BlockchainInterfaceMock::default()
+            .build_blockchain_agent_result(Ok(Box::new(agent)))
+            .lower_interface_results(Box::new(lower_interface));
+        let persistent_config = PersistentConfigurationMock::default()
+            .consuming_wallet_result(Ok(Some(make_paying_wallet(b"consuming"))))
+            .earning_wallet_result(Ok(Some(make_wallet("earning"))))
+            .payment_thresholds_result(Ok(*DEFAULT_PAYMENT_THRESHOLDS));
+        let mut subject = BlockchainBridge::new(
+            Box::new(blockchain_interface),
+            Box::new(persistent_config),
+            Box::new(FundsTransferDaoMock::default()),
+            false,
+        );
+        subject.logger = Logger::new(test_name);
+        let addr = subject.start();
+        let subject_subs = BlockchainBridge::make_subs_from(&addr);
+        let peer_actors = peer_actors_builder().ui_gateway(ui_gateway).build();
+        send_bind_message!(subject_subs, peer_actors);
+
+        addr.try_send(NodeFromUiMessage {
+            client_id: 1234,
+            body: UiWalletBalancesRequest {
+                db_password: "password".to_string(),
+            }
+            .tmb(4321),
+        })
+        .unwrap();
+
+        let system = System::new(test_name);
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        assert_eq!(
+            ui_gateway_recording.get_record::<NodeToUiMessage>(0),
+            &NodeToUiMessage {
+                target: MessageTarget::ClientId(1234),
+                body: MessageBody {
+                    opcode: "walletBalances".to_string(),
+                    path: MessagePath::Conversation(4321),
+                    payload: Err((
+                        BLOCKCHAIN_QUERY_ERROR,
+                        "Blockchain error: Query failed: booga".to_string()
+                    )),
+                },
+            }
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: {test_name}: Failed to obtain wallet balances: {}, Blockchain error: Query \
+            failed: booga",
+            BLOCKCHAIN_QUERY_ERROR
+        ));
+    }
+
+    #[test]
+    fn transfer_request_sends_funds_records_the_transfer_and_answers_the_ui() {
+        let transfer_funds_params_arc = Arc::new(Mutex::new(vec![]));
+        let record_transfer_params_arc = Arc::new(Mutex::new(vec![]));
+        let consuming_wallet_params_arc = Arc::new(Mutex::new(vec![]));
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let consuming_wallet = make_paying_wallet(b"consuming");
+        let recipient = make_wallet("cold storage");
+        let agent_id_stamp = ArbitraryIdStamp::new();
+        let agent = BlockchainAgentMock::default().set_arbitrary_id_stamp(agent_id_stamp);
+        let blockchain_interface = BlockchainInterfaceMock::default()
+            .build_blockchain_agent_result(Ok(Box::new(agent)))
+            .transfer_funds_params(&transfer_funds_params_arc)
+            .transfer_funds_result(Ok(FundsTransferOutcome {
+                hash_opt: Some(make_tx_hash(456)),
+                amount_wei: 12_000_000_000_000_000_000,
+                fee_estimate_wei: 2_100_000_000_000_000,
+                gas_price_wei: 40_000_000_000,
+                nonce: U256::from(33),
+            }));
+        let persistent_config = PersistentConfigurationMock::default()
+            .consuming_wallet_params(&consuming_wallet_params_arc)
+            .consuming_wallet_result(Ok(Some(consuming_wallet)));
+        let funds_transfer_dao = FundsTransferDaoMock::default()
+            .record_transfer_params(&record_transfer_params_arc)
+            .record_transfer_result(Ok(()));
+        let subject = BlockchainBridge::new(
+            Box::new(blockchain_interface),
+            Box::new(persistent_config),
+            Box::new(funds_transfer_dao),
+            false,
+        );
+        let addr = subject.start();
+        let subject_subs = BlockchainBridge::make_subs_from(&addr);
+        let peer_actors = peer_actors_builder().ui_gateway(ui_gateway).build();
+        send_bind_message!(subject_subs, peer_actors);
+
+        addr.try_send(NodeFromUiMessage {
+            client_id: 1234,
+            body: UiTransferRequest {
+                db_password: "password".to_string(),
+                recipient: recipient.to_string(),
+                currency: TransferCurrency::Masq,
+                amount_gwei_opt: Some(12_000_000_000),
+                dry_run: false,
+            }
+            .tmb(4321),
+        })
+        .unwrap();
+
+        let system = System::new("transfer");
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        assert_eq!(
+            ui_gateway_recording.get_record::<NodeToUiMessage>(0),
+            &NodeToUiMessage {
+                target: MessageTarget::ClientId(1234),
+                body: UiTransferResponse {
+                    transaction_hash_opt: Some(format!("{:?}", make_tx_hash(456))),
+                    recipient: format!("{:?}", recipient.address()),
+                    currency: TransferCurrency::Masq,
+                    amount_gwei: 12_000_000_000,
+                    fee_estimate_gwei: 2_100_000,
+                    gas_price_gwei: 40,
+                    nonce: 33,
+                }
+                .tmb(4321),
+            }
+        );
+        let consuming_wallet_params = consuming_wallet_params_arc.lock().unwrap();
+        assert_eq!(*consuming_wallet_params, vec!["password".to_string()]);
+        let transfer_funds_params = transfer_funds_params_arc.lock().unwrap();
+        assert_eq!(
+            *transfer_funds_params,
+            vec![(
+                agent_id_stamp,
+                FundsTransferOrder {
+                    recipient: recipient.clone(),
+                    currency: TransferCurrency::Masq,
+                    amount_wei_opt: Some(12_000_000_000_000_000_000),
+                    dry_run: false,
+                }
+            )]
+        );
+        let mut record_transfer_params = record_transfer_params_arc.lock().unwrap();
+        let record = record_transfer_params.remove(0);
+        assert!(record_transfer_params.is_empty());
+        assert_eq!(record.hash, make_tx_hash(456));
+        assert_eq!(record.recipient, recipient);
+        assert_eq!(record.currency, TransferCurrency::Masq);
+        assert_eq!(record.amount_wei, 12_000_000_000_000_000_000);
+        assert_eq!(record.gas_price_wei, 40_000_000_000);
+        assert_eq!(record.nonce, 33);
+    }
+
+    #[test]
+    fn transfer_in_dry_run_is_neither_recorded_nor_remembered() {
+        let blockchain_interface = BlockchainInterfaceMock::default()
+            .build_blockchain_agent_result(Ok(Box::new(BlockchainAgentMock::default())))
+            .transfer_funds_result(Ok(FundsTransferOutcome {
+                hash_opt: None,
+                amount_wei: 1_500_000_000_000_000_000,
+                fee_estimate_wei: 21_000_000_000_000,
+                gas_price_wei: 1_000_000_000,
+                nonce: U256::from(5),
+            }));
+        let persistent_config = PersistentConfigurationMock::default()
+            .consuming_wallet_result(Ok(Some(make_paying_wallet(b"consuming"))));
+        let mut subject = BlockchainBridge::new(
+            Box::new(blockchain_interface),
+            Box::new(persistent_config),
+            Box::new(FundsTransferDaoMock::default()),
+            false,
+        );
+        let recipient = make_wallet("exchange");
+
+        let result = subject.transfer_funds(&UiTransferRequest {
+            db_password: "password".to_string(),
+            recipient: recipient.to_string(),
+            currency: TransferCurrency::Native,
+            amount_gwei_opt: None,
+            dry_run: true,
+        });
+
+        assert_eq!(
+            result,
+            Ok(UiTransferResponse {
+                transaction_hash_opt: None,
+                recipient: format!("{:?}", recipient.address()),
+                currency: TransferCurrency::Native,
+                amount_gwei: 1_500_000_000,
+                fee_estimate_gwei: 21_000,
+                gas_price_gwei: 1,
+                nonce: 5,
+            })
+        );
+        assert_eq!(subject.last_transfer_nonce_opt, None);
+    }
+
+    #[test]
+    fn transfer_is_answered_with_success_even_if_recording_it_failed() {
+        init_test_logging();
+        let test_name = "transfer_is_answered_with_success_even_if_recording_it_failed";
+        let blockchain_interface = BlockchainInterfaceMock::default()
+            .build_blockchain_agent_result(Ok(Box::new(BlockchainAgentMock::default())))
+            .transfer_funds_result(Ok(FundsTransferOutcome {
+                hash_opt: Some(make_tx_hash(789)),
+                amount_wei: 1_000_000_000,
+                fee_estimate_wei: 21_000_000_000_000,
+                gas_price_wei: 1_000_000_000,
+                nonce: U256::from(5),
+            }));
+        let persistent_config = PersistentConfigurationMock::default()
+            .consuming_wallet_result(Ok(Some(make_paying_wallet(b"consuming"))));
+        let funds_transfer_dao = FundsTransferDaoMock::default().record_transfer_result(Err(
+            FundsTransferDaoError::InsertionFailed("disk full".to_string()),
+        ));
+        let mut subject = BlockchainBridge::new(
+            Box::new(blockchain_interface),
+            Box::new(persistent_config),
+            Box::new(funds_transfer_dao),
+            false,
+        );
+        subject.logger = Logger::new(test_name);
+
+        let result = subject.transfer_funds(&UiTransferRequest {
+            db_password: "password".to_string(),
+            recipient: make_wallet("exchange").to_string(),
+            currency: TransferCurrency::Native,
+            amount_gwei_opt: Some(1),
+            dry_run: false,
+        });
+
+        assert_eq!(
+            result.unwrap().transaction_hash_opt,
+            Some(format!("{:?}", make_tx_hash(789)))
+        );
+        assert_eq!(subject.last_transfer_nonce_opt, Some(U256::from(5)));
+        TestLogHandler::new().exists_log_containing(&format!(
+            "ERROR: {test_name}: Failed to record funds transfer {:?} in the history: \
+            InsertionFailed(\"disk full\")",
+            make_tx_hash(789)
+        ));
+    }
+
+    #[test]
+    fn transfer_request_reports_failed_transfer() {
+        init_test_logging();
+        let test_name = "transfer_request_reports_failed_transfer";
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let blockchain_interface = BlockchainInterfaceMock::default()
+            .build_blockchain_agent_result(Ok(Box::new(BlockchainAgentMock::default())))
+            .transfer_funds_result(Err(FundsTransferError::NothingToTransfer(
+                TransferCurrency::Masq,
+            )));
+        let persistent_config = PersistentConfigurationMock::default()
+            .consuming_wallet_result(Ok(Some(make_paying_wallet(b"consuming"))));
+        let mut subject = BlockchainBridge::new(
+            Box::new(blockchain_interface),
+            Box::new(persistent_config),
+            Box::new(FundsTransferDaoMock::default()),
+            false,
+        );
+        subject.logger = Logger::new(test_name);
+        let addr = subject.start();
+        let subject_subs = BlockchainBridge::make_subs_from(&addr);
+        let peer_actors = peer_actors_builder().ui_gateway(ui_gateway).build();
+        send_bind_message!(subject_subs, peer_actors);
+
+        addr.try_send(NodeFromUiMessage {
+            client_id: 1234,
+            body: UiTransferRequest {
+                db_password: "password".to_string(),
+                recipient: make_wallet("exchange").to_string(),
+                currency: TransferCurrency::Masq,
+                amount_gwei_opt: None,
+                dry_run: false,
+            }
+            .tmb(4321),
+        })
+        .unwrap();
+
+        let system = System::new(test_name);
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        assert_eq!(
+            ui_gateway_recording.get_record::<NodeToUiMessage>(0),
+            &NodeToUiMessage {
+                target: MessageTarget::ClientId(1234),
+                body: MessageBody {
+                    opcode: "transfer".to_string(),
+                    path: MessagePath::Conversation(4321),
+                    payload: Err((
+                        FUNDS_TRANSFER_ERROR,
+                        "There is no MASQ balance left to transfer".to_string()
+                    )),
+                },
+            }
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: {test_name}: Failed to transfer funds: {}, There is no MASQ balance left to \
+            transfer",
+            FUNDS_TRANSFER_ERROR
+        ));
+    }
+
+    #[test]
+    fn transfer_request_refuses_bad_recipient_address() {
+        let persistent_config = PersistentConfigurationMock::default()
+            .consuming_wallet_result(Ok(Some(make_paying_wallet(b"consuming"))));
+        let mut subject = BlockchainBridge::new(
+            Box::new(BlockchainInterfaceMock::default()),
+            Box::new(persistent_config),
+            Box::new(FundsTransferDaoMock::default()),
+            false,
+        );
+
+        let result = subject.transfer_funds(&UiTransferRequest {
+            db_password: "password".to_string(),
+            recipient: "booga".to_string(),
+            currency: TransferCurrency::Masq,
+            amount_gwei_opt: None,
+            dry_run: false,
+        });
+
+        let (code, msg) = result.unwrap_err();
+        assert_eq!(code, NON_PARSABLE_VALUE);
+        assert!(msg.starts_with("Recipient address 'booga': "), "{}", msg);
+    }
+
+    #[test]
+    fn transfer_request_reports_missing_consuming_wallet() {
+        let persistent_config =
+            PersistentConfigurationMock::default().consuming_wallet_result(Ok(None));
+        let mut subject = BlockchainBridge::new(
+            Box::new(BlockchainInterfaceMock::default()),
+            Box::new(persistent_config),
+            Box::new(FundsTransferDaoMock::default()),
+            false,
+        );
+
+        let result = subject.transfer_funds(&UiTransferRequest {
+            db_password: "password".to_string(),
+            recipient: make_wallet("exchange").to_string(),
+            currency: TransferCurrency::Native,
+            amount_gwei_opt: None,
+            dry_run: true,
+        });
+
+        assert_eq!(
+            result,
+            Err((
+                MISSING_DATA,
+                "Consuming wallet not yet configured".to_string()
+            ))
+        );
+    }
+
     #[test]
     fn blockchain_bridge_logs_error_from_retrieving_received_payments() {
         init_test_logging();
@@ -1025,6 +2254,7 @@ mod tests {
         let mut subject = BlockchainBridge::new(
             Box::new(blockchain_interface),
             Box::new(persistent_config),
+            Box::new(FundsTransferDaoMock::default()),
             false,
         );
         subject.scan_error_subs_opt = Some(scan_error_recipient);
@@ -1111,6 +2341,7 @@ mod tests {
         let mut subject = BlockchainBridge::new(
             Box::new(blockchain_interface_mock),
             Box::new(PersistentConfigurationMock::default()),
+            Box::new(FundsTransferDaoMock::default()),
             false,
         );
         subject
@@ -1124,6 +2355,7 @@ mod tests {
                 fingerprint_3,
                 fingerprint_4,
             ],
+            replaced_transactions: vec![],
             response_skeleton_opt: Some(ResponseSkeleton {
                 client_id: 1234,
                 context_id: 4321,
@@ -1173,6 +2405,7 @@ mod tests {
         let mut subject = BlockchainBridge::new(
             Box::new(BlockchainInterfaceMock::default()),
             Box::new(PersistentConfigurationMock::default()),
+            Box::new(FundsTransferDaoMock::default()),
             false,
         );
         subject
@@ -1180,6 +2413,7 @@ mod tests {
             .report_transaction_receipts_sub_opt = Some(recipient);
         let msg = RequestTransactionReceipts {
             pending_payable: vec![],
+            replaced_transactions: vec![],
             response_skeleton_opt: None,
         };
         let system = System::new(
@@ -1200,6 +2434,90 @@ mod tests {
         )
     }
 
+    #[test]
+    fn handle_request_transaction_receipts_confirms_a_fee_bumped_payable_on_a_replaced_transaction()
+    {
+        let get_transaction_receipt_params_arc = Arc::new(Mutex::new(vec![]));
+        let (accountant, _, accountant_recording) = make_recorder();
+        let recipient = accountant.start().recipient();
+        let mut mined_receipt = TransactionReceipt::default();
+        mined_receipt.block_number = Some(U64::from(4545454));
+        mined_receipt.status = Some(U64::from(1));
+        let blockchain_interface_mock = BlockchainInterfaceMock::default()
+            .get_transaction_receipt_params(&get_transaction_receipt_params_arc)
+            .get_transaction_receipt_result(Ok(None))
+            .get_transaction_receipt_result(Ok(None))
+            .get_transaction_receipt_result(Ok(Some(mined_receipt.clone())))
+            .get_transaction_receipt_result(Ok(None));
+        let mut subject = BlockchainBridge::new(
+            Box::new(blockchain_interface_mock),
+            Box::new(PersistentConfigurationMock::default()),
+            Box::new(FundsTransferDaoMock::default()),
+            false,
+        );
+        subject
+            .pending_payable_confirmation
+            .report_transaction_receipts_sub_opt = Some(recipient);
+        let bumped_fingerprint = PendingPayableFingerprint {
+            rowid: 1,
+            timestamp: from_time_t(230_000_000),
+            hash: make_tx_hash(0x13),
+            attempt: 6,
+            amount: 3333,
+            process_error: None,
+        };
+        let other_fingerprint = PendingPayableFingerprint {
+            rowid: 2,
+            timestamp: from_time_t(230_000_000),
+            hash: make_tx_hash(0x21),
+            attempt: 6,
+            amount: 4444,
+            process_error: None,
+        };
+        let msg = RequestTransactionReceipts {
+            pending_payable: vec![bumped_fingerprint.clone(), other_fingerprint.clone()],
+            replaced_transactions: vec![
+                PendingPayableId::new(1, make_tx_hash(0x11)),
+                PendingPayableId::new(3, make_tx_hash(0x31)),
+                PendingPayableId::new(1, make_tx_hash(0x12)),
+            ],
+            response_skeleton_opt: None,
+        };
+        let system = System::new("test");
+
+        let result = subject.handle_request_transaction_receipts(msg);
+
+        System::current().stop();
+        system.run();
+        assert_eq!(result, Ok(()));
+        assert_eq!(
+            *get_transaction_receipt_params_arc.lock().unwrap(),
+            vec![
+                make_tx_hash(0x13),
+                make_tx_hash(0x12),
+                make_tx_hash(0x11),
+                make_tx_hash(0x21)
+            ]
+        );
+        let recording = accountant_recording.lock().unwrap();
+        assert_eq!(
+            recording.get_record::<ReportTransactionReceipts>(0),
+            &ReportTransactionReceipts {
+                fingerprints_with_receipts: vec![
+                    (
+                        Some(mined_receipt),
+                        PendingPayableFingerprint {
+                            hash: make_tx_hash(0x11),
+                            ..bumped_fingerprint
+                        }
+                    ),
+                    (None, other_fingerprint)
+                ],
+                response_skeleton_opt: None
+            }
+        )
+    }
+
     #[test]
     fn handle_request_transaction_receipts_short_circuits_on_failure_of_the_first_payment_and_it_sends_a_message_with_empty_vector_and_logs(
     ) {
@@ -1233,6 +2551,7 @@ mod tests {
         let mut subject = BlockchainBridge::new(
             Box::new(blockchain_interface_mock),
             Box::new(PersistentConfigurationMock::default()),
+            Box::new(FundsTransferDaoMock::default()),
             false,
         );
         subject
@@ -1242,6 +2561,7 @@ mod tests {
         subject.scan_error_subs_opt = Some(scan_error_recipient);
         let msg = RequestTransactionReceipts {
             pending_payable: vec![fingerprint_1, fingerprint_2],
+            replaced_transactions: vec![],
             response_skeleton_opt: None,
         };
         let system = System::new("test");
@@ -1294,11 +2614,17 @@ mod tests {
             transactions: vec![
                 BlockchainTransaction {
                     block_number: 7,
+                    block_hash: H256::from_low_u64_be(7),
+                    transaction_hash: H256::from_low_u64_be(1),
+                    log_index: 0,
                     from: earning_wallet.clone(),
                     wei_amount: amount,
                 },
                 BlockchainTransaction {
                     block_number: 9,
+                    block_hash: H256::from_low_u64_be(9),
+                    transaction_hash: H256::from_low_u64_be(2),
+                    log_index: 0,
                     from: earning_wallet.clone(),
                     wei_amount: amount2,
                 },
@@ -1318,6 +2644,7 @@ mod tests {
         let subject = BlockchainBridge::new(
             Box::new(blockchain_interface_mock),
             Box::new(persistent_config),
+            Box::new(FundsTransferDaoMock::default()),
             false,
         );
         let addr = subject.start();
@@ -1357,6 +2684,7 @@ mod tests {
                 timestamp: received_payments.timestamp,
                 payments: expected_transactions.transactions,
                 new_start_block: 8675309u64,
+                scanned_blocks_opt: None,
                 response_skeleton_opt: Some(ResponseSkeleton {
                     client_id: 1234,
                     context_id: 4321
@@ -1382,11 +2710,17 @@ mod tests {
             transactions: vec![
                 BlockchainTransaction {
                     block_number: 8675308u64,
+                    block_hash: H256::from_low_u64_be(8675308),
+                    transaction_hash: H256::from_low_u64_be(3),
+                    log_index: 0,
                     from: earning_wallet.clone(),
                     wei_amount: amount,
                 },
                 BlockchainTransaction {
                     block_number: 8675309u64,
+                    block_hash: H256::from_low_u64_be(8675309),
+                    transaction_hash: H256::from_low_u64_be(4),
+                    log_index: 0,
                     from: earning_wallet.clone(),
                     wei_amount: amount2,
                 },
@@ -1407,6 +2741,7 @@ mod tests {
         let subject = BlockchainBridge::new(
             Box::new(blockchain_interface_mock),
             Box::new(persistent_config),
+            Box::new(FundsTransferDaoMock::default()),
             false,
         );
         let addr = subject.start();
@@ -1442,6 +2777,7 @@ mod tests {
                 timestamp: received_payments.timestamp,
                 payments: expected_transactions.transactions,
                 new_start_block: 8675309u64,
+                scanned_blocks_opt: None,
                 response_skeleton_opt: Some(ResponseSkeleton {
                     client_id: 1234,
                     context_id: 4321
@@ -1462,11 +2798,17 @@ mod tests {
             transactions: vec![
                 BlockchainTransaction {
                     block_number: 77,
+                    block_hash: H256::from_low_u64_be(77),
+                    transaction_hash: H256::from_low_u64_be(5),
+                    log_index: 0,
                     from: earning_wallet.clone(),
                     wei_amount: amount,
                 },
                 BlockchainTransaction {
                     block_number: 99,
+                    block_hash: H256::from_low_u64_be(99),
+                    transaction_hash: H256::from_low_u64_be(6),
+                    log_index: 0,
                     from: earning_wallet.clone(),
                     wei_amount: amount2,
                 },
@@ -1492,6 +2834,7 @@ mod tests {
         let subject = BlockchainBridge::new(
             Box::new(blockchain_interface),
             Box::new(persistent_config),
+            Box::new(FundsTransferDaoMock::default()),
             false,
         );
         let addr = subject.start();
@@ -1527,6 +2870,7 @@ mod tests {
                 timestamp: received_payments.timestamp,
                 payments: expected_transactions.transactions,
                 new_start_block: 98765,
+                scanned_blocks_opt: None,
                 response_skeleton_opt: Some(ResponseSkeleton {
                     client_id: 1234,
                     context_id: 4321
@@ -1549,11 +2893,17 @@ mod tests {
             transactions: vec![
                 BlockchainTransaction {
                     block_number: 7,
+                    block_hash: H256::from_low_u64_be(7),
+                    transaction_hash: H256::from_low_u64_be(7),
+                    log_index: 0,
                     from: earning_wallet.clone(),
                     wei_amount: amount,
                 },
                 BlockchainTransaction {
                     block_number: 9,
+                    block_hash: H256::from_low_u64_be(9),
+                    transaction_hash: H256::from_low_u64_be(8),
+                    log_index: 0,
                     from: earning_wallet.clone(),
                     wei_amount: amount2,
                 },
@@ -1572,6 +2922,7 @@ mod tests {
         let subject = BlockchainBridge::new(
             Box::new(blockchain_interface_mock),
             Box::new(persistent_config),
+            Box::new(FundsTransferDaoMock::default()),
             false,
         );
         let addr = subject.start();
@@ -1611,6 +2962,11 @@ mod tests {
                 timestamp: received_payments.timestamp,
                 payments: expected_transactions.transactions,
                 new_start_block: 9876,
+                scanned_blocks_opt: Some(ScannedBlocks {
+                    from: 6,
+                    to: 1024,
+                    latest: 1024
+                }),
                 response_skeleton_opt: Some(ResponseSkeleton {
                     client_id: 1234,
                     context_id: 4321
@@ -1640,6 +2996,7 @@ mod tests {
         let subject = BlockchainBridge::new(
             Box::new(blockchain_interface_mock),
             Box::new(persistent_config),
+            Box::new(FundsTransferDaoMock::default()),
             false,
         );
         let addr = subject.start();
@@ -1669,6 +3026,11 @@ mod tests {
                 timestamp: received_payments.timestamp,
                 payments: vec![],
                 new_start_block: 7,
+                scanned_blocks_opt: Some(ScannedBlocks {
+                    from: 6,
+                    to: 0,
+                    latest: 0
+                }),
                 response_skeleton_opt: Some(ResponseSkeleton {
                     client_id: 1234,
                     context_id: 4321
@@ -1693,6 +3055,7 @@ mod tests {
         let mut subject = BlockchainBridge::new(
             Box::new(blockchain_interface),
             Box::new(persistent_config),
+            Box::new(FundsTransferDaoMock::default()),
             false,
         );
         let retrieve_transactions = RetrieveTransactions {
@@ -1723,6 +3086,7 @@ mod tests {
         let mut subject = BlockchainBridge::new(
             Box::new(BlockchainInterfaceMock::default()),
             Box::new(PersistentConfigurationMock::new()),
+            Box::new(FundsTransferDaoMock::default()),
             false,
         );
         let system = System::new("test");
@@ -1754,6 +3118,7 @@ mod tests {
         let mut subject = BlockchainBridge::new(
             Box::new(BlockchainInterfaceMock::default()),
             Box::new(PersistentConfigurationMock::new()),
+            Box::new(FundsTransferDaoMock::default()),
             false,
         );
         let system = System::new("test");
@@ -1792,6 +3157,7 @@ mod tests {
         let mut subject = BlockchainBridge::new(
             Box::new(BlockchainInterfaceMock::default()),
             Box::new(PersistentConfigurationMock::new()),
+            Box::new(FundsTransferDaoMock::default()),
             false,
         );
         let system = System::new("test");
@@ -1836,6 +3202,7 @@ mod tests {
         let subject = BlockchainBridge::new(
             Box::new(BlockchainInterfaceMock::default()),
             Box::new(PersistentConfigurationMock::default()),
+            Box::new(FundsTransferDaoMock::default()),
             crashable,
         );
 
@@ -1848,6 +3215,7 @@ mod tests {
         let subject = BlockchainBridge::new(
             Box::new(BlockchainInterfaceMock::default()),
             Box::new(PersistentConfigurationMock::default()),
+            Box::new(FundsTransferDaoMock::default()),
             false,
         );
         let max_block_count = subject.extract_max_block_count(result);
@@ -1861,6 +3229,7 @@ mod tests {
         let subject = BlockchainBridge::new(
             Box::new(BlockchainInterfaceMock::default()),
             Box::new(PersistentConfigurationMock::default()),
+            Box::new(FundsTransferDaoMock::default()),
             false,
         );
         let max_block_count = subject.extract_max_block_count(result);
@@ -1881,6 +3250,7 @@ mod tests {
         let subject = BlockchainBridge::new(
             Box::new(BlockchainInterfaceMock::default()),
             Box::new(PersistentConfigurationMock::default()),
+            Box::new(FundsTransferDaoMock::default()),
             false,
         );
         let max_block_count = subject.extract_max_block_count(result);
@@ -1898,6 +3268,7 @@ mod tests {
         let subject = BlockchainBridge::new(
             Box::new(BlockchainInterfaceMock::default()),
             Box::new(PersistentConfigurationMock::default()),
+            Box::new(FundsTransferDaoMock::default()),
             false,
         );
         let max_block_count = subject.extract_max_block_count(result);
@@ -1915,6 +3286,7 @@ mod tests {
         let subject = BlockchainBridge::new(
             Box::new(BlockchainInterfaceMock::default()),
             Box::new(PersistentConfigurationMock::default()),
+            Box::new(FundsTransferDaoMock::default()),
             false,
         );
         let max_block_count = subject.extract_max_block_count(result);
@@ -1934,6 +3306,7 @@ mod tests {
         let subject = BlockchainBridge::new(
             Box::new(BlockchainInterfaceMock::default()),
             Box::new(PersistentConfigurationMock::default()),
+            Box::new(FundsTransferDaoMock::default()),
             false,
         );
         let max_block_count = subject.extract_max_block_count(result);
@@ -1947,6 +3320,7 @@ mod tests {
         let subject = BlockchainBridge::new(
             Box::new(BlockchainInterfaceMock::default()),
             Box::new(PersistentConfigurationMock::default()),
+            Box::new(FundsTransferDaoMock::default()),
             false,
         );
         let max_block_count = subject.extract_max_block_count(result);
@@ -1962,6 +3336,7 @@ mod tests {
         let subject = BlockchainBridge::new(
             Box::new(BlockchainInterfaceMock::default()),
             Box::new(PersistentConfigurationMock::default()),
+            Box::new(FundsTransferDaoMock::default()),
             false,
         );
         let max_block_count = subject.extract_max_block_count(result);
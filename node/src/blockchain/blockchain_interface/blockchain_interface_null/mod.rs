@@ -3,6 +3,7 @@
 pub mod lower_level_interface_null;
 
 use crate::accountant::db_access_objects::payable_dao::PayableAccount;
+use crate::accountant::db_access_objects::pending_payable_dao::StuckTransaction;
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::BlockchainAgent;
 use crate::blockchain::blockchain_bridge::PendingPayableFingerprintSeeds;
 use crate::blockchain::blockchain_interface::blockchain_interface_null::lower_level_interface_null::LowBlockChainIntNull;
@@ -13,8 +14,8 @@ use actix::Recipient;
 use masq_lib::logger::Logger;
 use web3::types::{Address, BlockNumber, H160, H256};
 use crate::blockchain::blockchain_interface::BlockchainInterface;
-use crate::blockchain::blockchain_interface::data_structures::errors::{BlockchainAgentBuildError, BlockchainError, PayableTransactionError, ResultForReceipt};
-use crate::blockchain::blockchain_interface::data_structures::{ProcessedPayableFallible, RetrievedBlockchainTransactions};
+use crate::blockchain::blockchain_interface::data_structures::errors::{BlockchainAgentBuildError, BlockchainError, FeeBumpError, FundsTransferError, PayableTransactionError, ResultForReceipt};
+use crate::blockchain::blockchain_interface::data_structures::{FeeBumpedTransaction, FundsTransferOrder, FundsTransferOutcome, ProcessedPayableFallible, RetrievedBlockchainTransactions};
 
 pub struct BlockchainInterfaceNull {
     logger: Logger,
@@ -57,6 +58,23 @@ impl BlockchainInterface for BlockchainInterfaceNull {
         self.handle_uninitialized_interface("get transaction receipt")
     }
 
+    fn bump_transaction_fee(
+        &self,
+        _consuming_wallet: &Wallet,
+        _stuck_transaction: &StuckTransaction,
+        _gas_price_increase_percent: u64,
+    ) -> Result<FeeBumpedTransaction, FeeBumpError> {
+        self.handle_uninitialized_interface("bump transaction fee")
+    }
+
+    fn transfer_funds(
+        &self,
+        _agent: &dyn BlockchainAgent,
+        _order: &FundsTransferOrder,
+    ) -> Result<FundsTransferOutcome, FundsTransferError> {
+        self.handle_uninitialized_interface("transfer funds")
+    }
+
     fn lower_interface(&self) -> &dyn LowBlockchainInt {
         error!(
             self.logger,
@@ -93,7 +111,9 @@ macro_rules! impl_bci_uninitialized {
 impl_bci_uninitialized!(
     PayableTransactionError,
     BlockchainError,
-    BlockchainAgentBuildError
+    BlockchainAgentBuildError,
+    FeeBumpError,
+    FundsTransferError
 );
 
 impl BlockchainInterfaceNull {
@@ -136,7 +156,7 @@ mod tests {
     use crate::blockchain::blockchain_interface::blockchain_interface_null::{
         BlockchainInterfaceNull, BlockchainInterfaceUninitializedError,
     };
-    use crate::blockchain::test_utils::make_tx_hash;
+    use crate::blockchain::test_utils::{make_stuck_transaction, make_tx_hash};
     use crate::test_utils::make_wallet;
     use crate::test_utils::persistent_configuration_mock::PersistentConfigurationMock;
     use crate::test_utils::recorder::make_recorder;
@@ -146,7 +166,10 @@ mod tests {
     use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
     use web3::types::{BlockNumber, H160};
     use crate::blockchain::blockchain_interface::BlockchainInterface;
-    use crate::blockchain::blockchain_interface::data_structures::errors::{BlockchainAgentBuildError, BlockchainError, PayableTransactionError};
+    use crate::blockchain::blockchain_interface::data_structures::errors::{BlockchainAgentBuildError, BlockchainError, FeeBumpError, FundsTransferError, PayableTransactionError};
+    use crate::blockchain::blockchain_interface::data_structures::FundsTransferOrder;
+    use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::test_utils::BlockchainAgentMock;
+    use masq_lib::messages::TransferCurrency;
 
     fn make_subject(test_name: &str) -> BlockchainInterfaceNull {
         let logger = Logger::new(test_name);
@@ -252,6 +275,48 @@ mod tests {
         TestLogHandler::new().exists_log_containing(expected_log_msg.as_str());
     }
 
+    #[test]
+    fn blockchain_interface_null_cannot_bump_transaction_fee() {
+        init_test_logging();
+        let test_name = "blockchain_interface_null_cannot_bump_transaction_fee";
+        let wallet = make_wallet("abc");
+
+        let result =
+            make_subject(test_name).bump_transaction_fee(&wallet, &make_stuck_transaction(123), 20);
+
+        assert_eq!(result, Err(FeeBumpError::UninitializedBlockchainInterface));
+        let expected_log_msg = format!(
+            "ERROR: {test_name}: Failed to bump transaction fee with uninitialized \
+            blockchain interface. Parameter blockchain-service-url is missing."
+        );
+        TestLogHandler::new().exists_log_containing(expected_log_msg.as_str());
+    }
+
+    #[test]
+    fn blockchain_interface_null_cannot_transfer_funds() {
+        init_test_logging();
+        let test_name = "blockchain_interface_null_cannot_transfer_funds";
+        let agent = BlockchainAgentMock::default();
+        let order = FundsTransferOrder {
+            recipient: make_wallet("abc"),
+            currency: TransferCurrency::Masq,
+            amount_wei_opt: None,
+            dry_run: false,
+        };
+
+        let result = make_subject(test_name).transfer_funds(&agent, &order);
+
+        assert_eq!(
+            result,
+            Err(FundsTransferError::UninitializedBlockchainInterface)
+        );
+        let expected_log_msg = format!(
+            "ERROR: {test_name}: Failed to transfer funds with uninitialized \
+            blockchain interface. Parameter blockchain-service-url is missing."
+        );
+        TestLogHandler::new().exists_log_containing(expected_log_msg.as_str());
+    }
+
     #[test]
     fn blockchain_interface_null_gives_null_lower_interface() {
         init_test_logging();
@@ -288,4 +353,12 @@ mod tests {
             PayableTransactionError::UninitializedBlockchainInterface
         )
     }
+
+    #[test]
+    fn blockchain_interface_null_error_is_implemented_for_funds_transfer_error() {
+        assert_eq!(
+            FundsTransferError::error(),
+            FundsTransferError::UninitializedBlockchainInterface
+        )
+    }
 }
@@ -1,6 +1,7 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
 use crate::blockchain::blockchain_bridge::PendingPayableFingerprintSeeds;
+use crate::sub_lib::wallet::Wallet;
 use actix::Recipient;
 use futures::Future;
 use serde_json::Value;
@@ -28,6 +29,9 @@ where
         batch_wide_timestamp: SystemTime,
         new_pp_fingerprints_sub: &Recipient<PendingPayableFingerprintSeeds>,
         hashes_and_balances: &[(H256, u128)],
+        batch_payments: &[(H256, Vec<(Wallet, u128)>)],
+        hashes_and_nonces: &[(H256, u64)],
+        gas_price_wei: u64,
     );
     fn submit_batch(
         &self,
@@ -73,11 +77,17 @@ impl<T: BatchTransport + Debug> BatchPayableTools<T> for BatchPayableToolsReal<T
         batch_wide_timestamp: SystemTime,
         pp_fingerprint_sub: &Recipient<PendingPayableFingerprintSeeds>,
         hashes_and_balances: &[(H256, u128)],
+        batch_payments: &[(H256, Vec<(Wallet, u128)>)],
+        hashes_and_nonces: &[(H256, u64)],
+        gas_price_wei: u64,
     ) {
         pp_fingerprint_sub
             .try_send(PendingPayableFingerprintSeeds {
                 batch_wide_timestamp,
                 hashes_and_balances: hashes_and_balances.to_vec(),
+                batch_payments: batch_payments.to_vec(),
+                hashes_and_nonces: hashes_and_nonces.to_vec(),
+                gas_price_wei,
             })
             .expect("Accountant is dead");
     }
@@ -97,6 +107,7 @@ mod tests {
         BatchPayableTools, BatchPayableToolsReal,
     };
     use crate::blockchain::test_utils::{make_tx_hash, TestTransport};
+    use crate::test_utils::make_wallet;
     use crate::test_utils::recorder::make_recorder;
     use actix::{Actor, System};
     use std::time::SystemTime;
@@ -107,9 +118,21 @@ mod tests {
         let recipient = accountant.start().recipient();
         let timestamp = SystemTime::now();
         let hashes_and_balances = vec![(make_tx_hash(123), 5), (make_tx_hash(45466), 444444)];
+        let batch_payments = vec![(
+            make_tx_hash(45466),
+            vec![(make_wallet("abc"), 400000), (make_wallet("def"), 44444)],
+        )];
+        let hashes_and_nonces = vec![(make_tx_hash(123), 7), (make_tx_hash(45466), 8)];
 
         let _ = BatchPayableToolsReal::<TestTransport>::default()
-            .send_new_payable_fingerprints_seeds(timestamp, &recipient, &hashes_and_balances);
+            .send_new_payable_fingerprints_seeds(
+                timestamp,
+                &recipient,
+                &hashes_and_balances,
+                &batch_payments,
+                &hashes_and_nonces,
+                2_000_000_000,
+            );
 
         let system = System::new("new fingerprints");
         System::current().stop();
@@ -120,7 +143,10 @@ mod tests {
             message,
             &PendingPayableFingerprintSeeds {
                 batch_wide_timestamp: timestamp,
-                hashes_and_balances
+                hashes_and_balances,
+                batch_payments,
+                hashes_and_nonces,
+                gas_price_wei: 2_000_000_000
             }
         )
     }
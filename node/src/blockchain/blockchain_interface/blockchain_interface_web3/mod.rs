@@ -5,7 +5,8 @@ pub mod lower_level_interface_web3;
 mod test_utils;
 
 use crate::accountant::db_access_objects::payable_dao::{PayableAccount};
-use crate::accountant::{gwei_to_wei};
+use crate::accountant::db_access_objects::pending_payable_dao::StuckTransaction;
+use crate::accountant::{checked_conversion, gwei_to_wei};
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::agent_web3::BlockchainAgentWeb3;
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::BlockchainAgent;
 use crate::blockchain::blockchain_bridge::PendingPayableFingerprintSeeds;
@@ -14,16 +15,19 @@ use crate::blockchain::blockchain_interface::blockchain_interface_web3::batch_pa
 };
 use crate::blockchain::blockchain_interface::blockchain_interface_web3::lower_level_interface_web3::LowBlockchainIntWeb3;
 use crate::blockchain::blockchain_interface::lower_level_interface::LowBlockchainInt;
-use crate::blockchain::blockchain_interface::{BlockchainAgentBuildError, BlockchainError, BlockchainInterface, PayableTransactionError, ResultForReceipt, RetrievedBlockchainTransactions};
+use crate::blockchain::external_signer::ExternalSigner;
+use crate::blockchain::blockchain_interface::{BlockchainAgentBuildError, BlockchainError, BlockchainInterface, FeeBumpError, FeeBumpedTransaction, FundsTransferError, FundsTransferOrder, FundsTransferOutcome, PayableTransactionError, ResultForReceipt, RetrievedBlockchainTransactions};
 use crate::db_config::persistent_configuration::PersistentConfiguration;
 use crate::masq_lib::utils::ExpectValue;
 use crate::sub_lib::blockchain_bridge::ConsumingWalletBalances;
 use crate::sub_lib::wallet::Wallet;
 use actix::Recipient;
+use ethabi::Token;
 use futures::Future;
 use indoc::indoc;
 use masq_lib::blockchains::chains::Chain;
 use masq_lib::logger::Logger;
+use masq_lib::messages::TransferCurrency;
 use serde_json::Value;
 use std::fmt::Debug;
 use std::iter::once;
@@ -66,6 +70,16 @@ const TRANSACTION_LITERAL: H256 = H256([
 
 const TRANSFER_METHOD_ID: [u8; 4] = [0xa9, 0x05, 0x9c, 0xbb];
 
+// disperseToken(address,address[],uint256[]) of a Disperse contract: it pulls the total from
+// the sender with a single transferFrom, then hands the shares out by plain transfers
+const DISPERSE_TOKEN_METHOD_ID: [u8; 4] = [0xc7, 0x3a, 0x2d, 0x60];
+
+// Paid by every transaction; the constant part of the gas limit includes it
+const TRANSACTION_BASE_GAS: u64 = 21_000;
+
+// Spending the allowance the consuming wallet granted to the disperse contract
+const DISPERSE_ALLOWANCE_GAS: u64 = 10_000;
+
 pub const REQUESTS_IN_PARALLEL: usize = 1;
 
 pub struct BlockchainInterfaceWeb3<T>
@@ -81,6 +95,12 @@ where
     web3_batch: Rc<Web3<Batch<T>>>,
     batch_payable_tools: Box<dyn BatchPayableTools<T>>,
     lower_interface: Box<dyn LowBlockchainInt>,
+    pub(in crate::blockchain) external_signer_opt: Option<Box<dyn ExternalSigner>>,
+}
+
+enum SigningError {
+    UnusableWallet(String),
+    Signing(String),
 }
 
 impl<T> BlockchainInterface for BlockchainInterfaceWeb3<T>
@@ -285,18 +305,47 @@ where
             gas_price
         );
 
-        let hashes_and_paid_amounts = self.sign_and_append_multiple_payments(
-            consuming_wallet,
-            gas_price,
-            pending_nonce,
-            accounts,
-        )?;
+        let (hashes_and_paid_amounts, batch_payments) = match self.disperse_contract_opt(accounts) {
+            Some(disperse_contract) => {
+                let hash_and_total = self.sign_and_append_dispersed_payment(
+                    consuming_wallet,
+                    gas_price,
+                    pending_nonce,
+                    disperse_contract,
+                    accounts,
+                )?;
+                let shares = accounts
+                    .iter()
+                    .map(|account| (account.wallet.clone(), account.balance_wei))
+                    .collect();
+                (vec![hash_and_total], vec![(hash_and_total.0, shares)])
+            }
+            None => (
+                self.sign_and_append_multiple_payments(
+                    consuming_wallet,
+                    gas_price,
+                    pending_nonce,
+                    accounts,
+                )?,
+                vec![],
+            ),
+        };
+        // Each transaction of the batch took the nonce following the one before it
+        let first_nonce = checked_conversion::<U256, u64>(pending_nonce);
+        let hashes_and_nonces = hashes_and_paid_amounts
+            .iter()
+            .zip(first_nonce..)
+            .map(|((hash, _), nonce)| (*hash, nonce))
+            .collect::<Vec<_>>();
         let timestamp = self.batch_payable_tools.batch_wide_timestamp();
         self.batch_payable_tools
             .send_new_payable_fingerprints_seeds(
                 timestamp,
                 new_fingerprints_recipient,
                 &hashes_and_paid_amounts,
+                &batch_payments,
+                &hashes_and_nonces,
+                gwei_to_wei(gas_price),
             );
 
         info!(
@@ -306,11 +355,14 @@ where
         );
 
         match self.batch_payable_tools.submit_batch(&self.web3_batch) {
-            Ok(responses) => Ok(Self::merged_output_data(
+            Ok(responses) if batch_payments.is_empty() => Ok(Self::merged_output_data(
                 responses,
                 hashes_and_paid_amounts,
                 accounts,
             )),
+            Ok(responses) => {
+                Self::dispersed_output_data(responses, hashes_and_paid_amounts, accounts)
+            }
             Err(e) => Err(Self::error_with_hashes(e, hashes_and_paid_amounts)),
         }
     }
@@ -323,9 +375,201 @@ where
             .wait()
     }
 
+    fn bump_transaction_fee(
+        &self,
+        consuming_wallet: &Wallet,
+        stuck_transaction: &StuckTransaction,
+        gas_price_increase_percent: u64,
+    ) -> Result<FeeBumpedTransaction, FeeBumpError> {
+        // The blockchain node may have dropped the underpriced original already, so the
+        // replacement is put together from what we recorded when sending it
+        let gas_price = Self::increased_gas_price(
+            U256::from(stuck_transaction.gas_price_wei),
+            gas_price_increase_percent,
+        );
+        let gas_price_wei =
+            u64::try_from(gas_price).map_err(|_| FeeBumpError::GasPriceOverflow(gas_price))?;
+        let (to, data, gas) = match stuck_transaction.payments.as_slice() {
+            [(recipient, amount)] => {
+                let data = Self::transaction_data(recipient, *amount).to_vec();
+                let gas_limit = self.compute_gas_limit(data.as_slice());
+                (H160(self.contract_address().0), data, gas_limit)
+            }
+            shares => {
+                let disperse_contract = self
+                    .chain
+                    .rec()
+                    .disperse_contract_opt
+                    .ok_or(FeeBumpError::NoDisperseContract)?;
+                let data = self.disperse_transaction_data(shares);
+                let gas_limit = self.compute_disperse_gas_limit(data.as_slice(), shares.len());
+                (disperse_contract, data, gas_limit)
+            }
+        };
+        let transaction_parameters = TransactionParameters {
+            nonce: Some(U256::from(stuck_transaction.nonce)),
+            to: Some(to),
+            gas,
+            gas_price: Some(gas_price),
+            value: U256::zero(),
+            data: Bytes(data),
+            chain_id: Some(self.chain.rec().num_chain_id),
+        };
+        let signed_tx = self
+            .sign_for_consuming_wallet(transaction_parameters, consuming_wallet)
+            .map_err(|e| match e {
+                SigningError::UnusableWallet(msg) => FeeBumpError::UnusableWallet(msg),
+                SigningError::Signing(msg) => FeeBumpError::Signing(msg),
+            })?;
+
+        info!(
+            self.logger,
+            "Replacing pending transaction {:?} (nonce {}) by {:?} with gas price raised from {} to {} wei",
+            stuck_transaction.hash,
+            stuck_transaction.nonce,
+            signed_tx.transaction_hash,
+            stuck_transaction.gas_price_wei.separate_with_commas(),
+            gas_price_wei.separate_with_commas()
+        );
+
+        self.batch_payable_tools
+            .append_transaction_to_batch(signed_tx.raw_transaction, &self.web3_batch);
+        match self.batch_payable_tools.submit_batch(&self.web3_batch) {
+            Ok(mut responses) => match responses.pop() {
+                Some(Ok(_)) => Ok(FeeBumpedTransaction {
+                    hash: signed_tx.transaction_hash,
+                    gas_price_wei,
+                }),
+                Some(Err(e)) => Err(FeeBumpError::Sending(e.to_string())),
+                None => Err(FeeBumpError::Sending("No response".to_string())),
+            },
+            Err(e) => Err(FeeBumpError::Sending(e.to_string())),
+        }
+    }
+
+    fn transfer_funds(
+        &self,
+        agent: &dyn BlockchainAgent,
+        order: &FundsTransferOrder,
+    ) -> Result<FundsTransferOutcome, FundsTransferError> {
+        let gas_price_wei = gwei_to_wei::<u64, _>(agent.agreed_fee_per_computation_unit());
+        let balances = agent.consuming_wallet_balances();
+        let fee_balance_wei =
+            Self::saturating_u128(balances.transaction_fee_balance_in_minor_units);
+        let (amount_wei, gas_limit, to, value, data) = match order.currency {
+            TransferCurrency::Masq => {
+                let masq_balance_wei =
+                    Self::saturating_u128(balances.masq_token_balance_in_minor_units);
+                let amount_wei = match order.amount_wei_opt {
+                    None if masq_balance_wei == 0 => {
+                        return Err(FundsTransferError::NothingToTransfer(order.currency))
+                    }
+                    None => masq_balance_wei,
+                    Some(amount_wei) if amount_wei > masq_balance_wei => {
+                        return Err(FundsTransferError::InsufficientBalance {
+                            currency: order.currency,
+                            needed_wei: amount_wei,
+                            available_wei: masq_balance_wei,
+                        })
+                    }
+                    Some(amount_wei) => amount_wei,
+                };
+                let data = Self::transaction_data(&order.recipient, amount_wei);
+                let gas_limit = self.compute_gas_limit(data.as_slice());
+                (
+                    amount_wei,
+                    gas_limit,
+                    H160(self.contract_address().0),
+                    U256::zero(),
+                    data.to_vec(),
+                )
+            }
+            TransferCurrency::Native => {
+                let native_transfer_gas_limit = self.chain.rec().native_transfer_gas_limit;
+                let fee_wei = native_transfer_gas_limit as u128 * gas_price_wei as u128;
+                let amount_wei = match order.amount_wei_opt {
+                    None if fee_balance_wei <= fee_wei => {
+                        return Err(FundsTransferError::NothingToTransfer(order.currency))
+                    }
+                    None => fee_balance_wei - fee_wei,
+                    Some(amount_wei) => amount_wei,
+                };
+                (
+                    amount_wei,
+                    U256::from(native_transfer_gas_limit),
+                    order.recipient.address(),
+                    U256::from(amount_wei),
+                    vec![],
+                )
+            }
+        };
+        let fee_estimate_wei = gas_limit.as_u128() * gas_price_wei as u128;
+        let needed_fee_currency_wei = fee_estimate_wei + value.as_u128();
+        if needed_fee_currency_wei > fee_balance_wei {
+            return Err(FundsTransferError::InsufficientBalance {
+                currency: TransferCurrency::Native,
+                needed_wei: needed_fee_currency_wei,
+                available_wei: fee_balance_wei,
+            });
+        }
+        let nonce = agent.pending_transaction_id();
+        let outcome = FundsTransferOutcome {
+            hash_opt: None,
+            amount_wei,
+            fee_estimate_wei,
+            gas_price_wei,
+            nonce,
+        };
+        if order.dry_run {
+            return Ok(outcome);
+        }
+
+        let transaction_parameters = TransactionParameters {
+            nonce: Some(nonce),
+            to: Some(to),
+            gas: gas_limit,
+            gas_price: Some(U256::from(gas_price_wei)),
+            value,
+            data: Bytes(data),
+            chain_id: Some(self.chain.rec().num_chain_id),
+        };
+        let signed_tx = self
+            .sign_for_consuming_wallet(transaction_parameters, agent.consuming_wallet())
+            .map_err(|e| match e {
+                SigningError::UnusableWallet(msg) => FundsTransferError::UnusableWallet(msg),
+                SigningError::Signing(msg) => FundsTransferError::Signing(msg),
+            })?;
+
+        info!(
+            self.logger,
+            "Transferring {} wei of {:?} to {} in transaction {:?} (nonce {})",
+            amount_wei.separate_with_commas(),
+            order.currency,
+            order.recipient,
+            signed_tx.transaction_hash,
+            nonce
+        );
+
+        self.batch_payable_tools
+            .append_transaction_to_batch(signed_tx.raw_transaction, &self.web3_batch);
+        match self.batch_payable_tools.submit_batch(&self.web3_batch) {
+            Ok(mut responses) => match responses.pop() {
+                Some(Ok(_)) => Ok(FundsTransferOutcome {
+                    hash_opt: Some(signed_tx.transaction_hash),
+                    ..outcome
+                }),
+                Some(Err(e)) => Err(FundsTransferError::Sending(e.to_string())),
+                None => Err(FundsTransferError::Sending("No response".to_string())),
+            },
+            Err(e) => Err(FundsTransferError::Sending(e.to_string())),
+        }
+    }
+
     fn lower_interface(&self) -> &dyn LowBlockchainInt {
         &*self.lower_interface
     }
+
+    as_any_ref_in_trait_impl!();
 }
 
 impl<T> BlockchainInterfaceWeb3<T>
@@ -355,9 +599,14 @@ where
             web3_batch,
             lower_interface: lower_level_blockchain_interface,
             batch_payable_tools,
+            external_signer_opt: None,
         }
     }
 
+    pub fn set_external_signer(&mut self, external_signer: Box<dyn ExternalSigner>) {
+        self.external_signer_opt = Some(external_signer);
+    }
+
     fn sign_and_append_multiple_payments(
         &self,
         consuming_wallet: &Wallet,
@@ -441,6 +690,60 @@ where
         }
     }
 
+    // One payable is paid more cheaply by a plain transfer than through a disperse contract
+    fn disperse_contract_opt(&self, accounts: &[PayableAccount]) -> Option<Address> {
+        match self.chain.rec().disperse_contract_opt {
+            Some(disperse_contract) if accounts.len() > 1 => Some(disperse_contract),
+            _ => None,
+        }
+    }
+
+    fn sign_and_append_dispersed_payment(
+        &self,
+        consuming_wallet: &Wallet,
+        gas_price: u64,
+        nonce: U256,
+        disperse_contract: Address,
+        accounts: &[PayableAccount],
+    ) -> Result<(H256, u128), PayableTransactionError> {
+        let total_wei = accounts
+            .iter()
+            .map(|account| account.balance_wei)
+            .sum::<u128>();
+        debug!(
+            self.logger,
+            "Preparing payment of {} wei to {} creditors through the disperse contract {:?} with nonce {}",
+            total_wei.separate_with_commas(),
+            accounts.len(),
+            disperse_contract,
+            nonce
+        );
+
+        let payments = accounts
+            .iter()
+            .map(|account| (account.wallet.clone(), account.balance_wei))
+            .collect::<Vec<_>>();
+        let data = self.disperse_transaction_data(&payments);
+        let gas_limit = self.compute_disperse_gas_limit(data.as_slice(), accounts.len());
+        let signed_tx = self.sign_contract_call(
+            disperse_contract,
+            data,
+            gas_limit,
+            consuming_wallet,
+            nonce,
+            gas_price,
+        )?;
+        self.batch_payable_tools
+            .append_transaction_to_batch(signed_tx.raw_transaction, &self.web3_batch);
+        Ok((signed_tx.transaction_hash, total_wei))
+    }
+
+    fn increased_gas_price(original_gas_price: U256, increase_percent: u64) -> U256 {
+        let increased = original_gas_price * U256::from(100 + increase_percent) / U256::from(100);
+        // A replacement with the same price would be refused by the mempool
+        increased.max(original_gas_price + U256::one())
+    }
+
     fn advance_used_nonce(current_nonce: U256) -> U256 {
         current_nonce
             .checked_add(U256::one())
@@ -471,6 +774,27 @@ where
             .collect()
     }
 
+    // The whole batch is a single transaction here, so it either went out for everybody or
+    // for nobody
+    fn dispersed_output_data(
+        mut responses: Vec<web3::transports::Result<Value>>,
+        hashes_and_paid_amounts: Vec<(H256, u128)>,
+        accounts: &[PayableAccount],
+    ) -> Result<Vec<ProcessedPayableFallible>, PayableTransactionError> {
+        let hash = hashes_and_paid_amounts[0].0;
+        match responses.pop() {
+            Some(Ok(_)) => Ok(accounts
+                .iter()
+                .map(|account| Ok(PendingPayable::new(account.wallet.clone(), hash)))
+                .collect()),
+            Some(Err(e)) => Err(Self::error_with_hashes(e, hashes_and_paid_amounts)),
+            None => Err(Self::error_with_hashes(
+                Error::InvalidResponse("no response to the disperse transaction".to_string()),
+                hashes_and_paid_amounts,
+            )),
+        }
+    }
+
     fn error_with_hashes(
         error: Error,
         hashes_and_paid_amounts: Vec<(H256, u128)>,
@@ -510,25 +834,63 @@ where
     ) -> Result<SignedTransaction, PayableTransactionError> {
         let data = Self::transaction_data(recipient, amount);
         let gas_limit = self.compute_gas_limit(data.as_slice());
+        self.sign_contract_call(
+            H160(self.contract_address().0),
+            data.to_vec(),
+            gas_limit,
+            consuming_wallet,
+            nonce,
+            gas_price,
+        )
+    }
+
+    fn sign_contract_call(
+        &self,
+        contract: Address,
+        data: Vec<u8>,
+        gas_limit: U256,
+        consuming_wallet: &Wallet,
+        nonce: U256,
+        gas_price: u64,
+    ) -> Result<SignedTransaction, PayableTransactionError> {
         let gas_price = gwei_to_wei::<U256, _>(gas_price);
         let transaction_parameters = TransactionParameters {
             nonce: Some(nonce),
-            to: Some(H160(self.contract_address().0)),
+            to: Some(contract),
             gas: gas_limit,
             gas_price: Some(gas_price),
             value: ethereum_types::U256::zero(),
-            data: Bytes(data.to_vec()),
+            data: Bytes(data),
             chain_id: Some(self.chain.rec().num_chain_id),
         };
 
-        let key = match consuming_wallet.prepare_secp256k1_secret() {
-            Ok(secret) => secret,
-            Err(e) => return Err(PayableTransactionError::UnusableWallet(e.to_string())),
-        };
+        self.sign_for_consuming_wallet(transaction_parameters, consuming_wallet)
+            .map_err(|e| match e {
+                SigningError::UnusableWallet(msg) => PayableTransactionError::UnusableWallet(msg),
+                SigningError::Signing(msg) => PayableTransactionError::Signing(msg),
+            })
+    }
 
-        self.batch_payable_tools
-            .sign_transaction(transaction_parameters, &self.web3_batch, &key)
-            .map_err(|e| PayableTransactionError::Signing(e.to_string()))
+    // A consuming wallet that holds its own key signs locally; one that is only an address
+    // needs the external signer that holds the key for it.
+    fn sign_for_consuming_wallet(
+        &self,
+        transaction_parameters: TransactionParameters,
+        consuming_wallet: &Wallet,
+    ) -> Result<SignedTransaction, SigningError> {
+        match (
+            consuming_wallet.prepare_secp256k1_secret(),
+            &self.external_signer_opt,
+        ) {
+            (Ok(key), _) => self
+                .batch_payable_tools
+                .sign_transaction(transaction_parameters, &self.web3_batch, &key)
+                .map_err(|e| SigningError::Signing(e.to_string())),
+            (Err(_), Some(external_signer)) => external_signer
+                .sign_transaction(consuming_wallet.address(), &transaction_parameters)
+                .map_err(|e| SigningError::Signing(e.to_string())),
+            (Err(e), None) => Err(SigningError::UnusableWallet(e.to_string())),
+        }
     }
 
     fn transmission_log(&self, accounts: &[PayableAccount], gas_price: u64) -> String {
@@ -571,6 +933,24 @@ where
         data
     }
 
+    fn disperse_transaction_data(&self, payments: &[(Wallet, u128)]) -> Vec<u8> {
+        let recipients = payments
+            .iter()
+            .map(|(wallet, _)| Token::Address(wallet.address()))
+            .collect();
+        let values = payments
+            .iter()
+            .map(|(_, amount)| Token::Uint(U256::from(*amount)))
+            .collect();
+        let mut data = DISPERSE_TOKEN_METHOD_ID.to_vec();
+        data.extend(ethabi::encode(&[
+            Token::Address(self.contract_address()),
+            Token::Array(recipients),
+            Token::Array(values),
+        ]));
+        data
+    }
+
     fn compute_gas_limit(&self, data: &[u8]) -> U256 {
         ethereum_types::U256::try_from(data.iter().fold(self.gas_limit_const_part, |acc, v| {
             acc + if v == &0u8 { 4 } else { 68 }
@@ -578,26 +958,59 @@ where
         .expect("Internal error")
     }
 
-    fn web3_gas_limit_const_part(chain: Chain) -> u64 {
-        match chain {
-            Chain::EthMainnet | Chain::EthRopsten | Chain::Dev => 55_000,
-            Chain::PolyMainnet | Chain::PolyAmoy | Chain::BaseMainnet | Chain::BaseSepolia => {
-                70_000
-            }
+    // The constant part covers pulling the total into the disperse contract, which is a transfer
+    // of its own; every share handed out from there costs what a plain payment does without
+    // the base fee of a transaction
+    fn compute_disperse_gas_limit(&self, data: &[u8], transfers: usize) -> U256 {
+        let transfer_gas = self
+            .gas_limit_const_part
+            .saturating_sub(TRANSACTION_BASE_GAS);
+        self.compute_gas_limit(data)
+            + U256::from(DISPERSE_ALLOWANCE_GAS)
+            + U256::from(transfer_gas) * U256::from(transfers)
+    }
+
+    fn saturating_u128(value: U256) -> u128 {
+        if value > U256::from(u128::MAX) {
+            u128::MAX
+        } else {
+            value.as_u128()
         }
     }
 
+    fn web3_gas_limit_const_part(chain: Chain) -> u64 {
+        chain.rec().gas_limit_const_part
+    }
+
     fn extract_transactions_from_logs(&self, logs: Vec<Log>) -> Vec<BlockchainTransaction> {
+        // Logs of pending blocks come without their position in the chain and logs flagged as
+        // removed were dropped by a reorganization; neither of them can be trusted
         logs.iter()
-            .filter_map(|log: &Log| match log.block_number {
-                None => None,
-                Some(block_number) => {
-                    let wei_amount = U256::from(log.data.0.as_slice()).as_u128();
-                    Some(BlockchainTransaction {
-                        block_number: block_number.as_u64(),
-                        from: Wallet::from(log.topics[1]),
-                        wei_amount,
-                    })
+            .filter(|log: &&Log| log.removed != Some(true))
+            .filter_map(|log: &Log| {
+                match (
+                    log.block_number,
+                    log.block_hash,
+                    log.transaction_hash,
+                    log.log_index,
+                ) {
+                    (
+                        Some(block_number),
+                        Some(block_hash),
+                        Some(transaction_hash),
+                        Some(log_index),
+                    ) => {
+                        let wei_amount = U256::from(log.data.0.as_slice()).as_u128();
+                        Some(BlockchainTransaction {
+                            block_number: block_number.as_u64(),
+                            block_hash,
+                            transaction_hash,
+                            log_index: log_index.as_u64(),
+                            from: Wallet::from(log.topics[1]),
+                            wei_amount,
+                        })
+                    }
+                    _ => None,
                 }
             })
             .collect()
@@ -634,20 +1047,24 @@ mod tests {
     };
     use crate::blockchain::bip32::Bip32EncryptionKeyProvider;
     use crate::blockchain::blockchain_bridge::PendingPayableFingerprintSeeds;
+    use masq_lib::blockchains::custom_chains::{register_custom_chains, CustomChainSpec};
 
     use crate::blockchain::blockchain_interface::blockchain_interface_web3::{
-        BlockchainInterfaceWeb3, CONTRACT_ABI, REQUESTS_IN_PARALLEL, TRANSACTION_LITERAL,
-        TRANSFER_METHOD_ID,
+        BlockchainInterfaceWeb3, CONTRACT_ABI, DISPERSE_TOKEN_METHOD_ID, REQUESTS_IN_PARALLEL,
+        TRANSACTION_LITERAL, TRANSFER_METHOD_ID,
     };
     use crate::blockchain::blockchain_interface::test_utils::{
         test_blockchain_interface_is_connected_and_functioning, LowBlockchainIntMock,
     };
     use crate::blockchain::blockchain_interface::{
-        BlockchainAgentBuildError, BlockchainError, BlockchainInterface, PayableTransactionError,
-        RetrievedBlockchainTransactions,
+        BlockchainAgentBuildError, BlockchainError, BlockchainInterface, FeeBumpError,
+        FeeBumpedTransaction, FundsTransferError, FundsTransferOrder, FundsTransferOutcome,
+        PayableTransactionError, RetrievedBlockchainTransactions,
     };
+    use crate::blockchain::external_signer::ExternalSignerError;
     use crate::blockchain::test_utils::{
-        all_chains, make_fake_event_loop_handle, make_tx_hash, TestTransport,
+        all_chains, make_fake_event_loop_handle, make_stuck_transaction, make_tx_hash,
+        ExternalSignerMock, TestTransport,
     };
     use crate::db_config::persistent_configuration::PersistentConfigError;
     use crate::sub_lib::blockchain_bridge::ConsumingWalletBalances;
@@ -666,6 +1083,7 @@ mod tests {
     use jsonrpc_core::{Call, Error as RPCError, ErrorCode, Id, MethodCall, Params};
     use masq_lib::blockchains::chains::Chain;
     use masq_lib::logger::Logger;
+    use masq_lib::messages::TransferCurrency;
     use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
     use masq_lib::test_utils::utils::TEST_DEFAULT_CHAIN;
     use masq_lib::utils::find_free_port;
@@ -682,6 +1100,7 @@ mod tests {
     use crate::blockchain::blockchain_interface::data_structures::{
         BlockchainTransaction, RpcPayablesFailure,
     };
+    use ethabi::Token;
     use indoc::indoc;
     use sodiumoxide::hex;
     use std::str::FromStr;
@@ -689,7 +1108,8 @@ mod tests {
     use std::time::SystemTime;
     use web3::transports::{Batch, Http};
     use web3::types::{
-        Address, BlockNumber, Bytes, TransactionParameters, TransactionReceipt, H2048, H256, U256,
+        Address, BlockNumber, Bytes, SignedTransaction, TransactionParameters, TransactionReceipt,
+        H160, H2048, H256, U256,
     };
     use web3::Error as Web3Error;
     use web3::Web3;
@@ -842,12 +1262,30 @@ mod tests {
                 transactions: vec![
                     BlockchainTransaction {
                         block_number: 0x4be663,
+                        block_hash: H256::from_str(
+                            "1a24b9169cbaec3f6effa1f600b70c7ab9e8e86db44062b49132a4415d26732a"
+                        )
+                        .unwrap(),
+                        transaction_hash: H256::from_str(
+                            "955cec6ac4f832911ab894ce16aa22c3003f46deff3f7165b32700d2f5ff0681"
+                        )
+                        .unwrap(),
+                        log_index: 0,
                         from: Wallet::from_str("0x3ab28ecedea6cdb6feed398e93ae8c7b316b1182")
                             .unwrap(),
                         wei_amount: 4_503_599_627_370_496u128,
                     },
                     BlockchainTransaction {
                         block_number: 0x4be662,
+                        block_hash: H256::from_str(
+                            "1a24b9169cbaec3f6effa1f600b70c7ab9e8e86db44062b49132a4415d26732b"
+                        )
+                        .unwrap(),
+                        transaction_hash: H256::from_str(
+                            "955cec6ac4f832911ab894ce16aa22c3003f46deff3f7165b32700d2f5ff0680"
+                        )
+                        .unwrap(),
+                        log_index: 0,
                         from: Wallet::from_str("0x3f69f9efd4f2592fd70be8c32ecd9dce71c472fc")
                             .unwrap(),
                         wei_amount: 4_503_599_627_370_496u128,
@@ -1430,7 +1868,14 @@ mod tests {
                     (expected_hash_1, gwei_to_wei(900_000_000_u64)),
                     (expected_hash_2, 123_456_789),
                     (expected_hash_3, gwei_to_wei(33_355_666_u64))
-                ]
+                ],
+                batch_payments: vec![],
+                hashes_and_nonces: vec![
+                    (expected_hash_1, 6),
+                    (expected_hash_2, 7),
+                    (expected_hash_3, 8)
+                ],
+                gas_price_wei: 120_000_000_000
             }
         );
         let log_handler = TestLogHandler::new();
@@ -1608,8 +2053,14 @@ mod tests {
         );
         assert!(sign_transaction_params.is_empty());
         let new_payable_fingerprint_params = new_payable_fingerprint_params_arc.lock().unwrap();
-        let (batch_wide_timestamp, recipient, actual_pending_payables) =
-            &new_payable_fingerprint_params[0];
+        let (
+            batch_wide_timestamp,
+            recipient,
+            actual_pending_payables,
+            actual_batch_payments,
+            actual_hashes_and_nonces,
+            actual_gas_price_wei,
+        ) = &new_payable_fingerprint_params[0];
         assert_eq!(batch_wide_timestamp, &batch_wide_timestamp_expected);
         assert_eq!(
             actual_pending_payables,
@@ -1618,6 +2069,12 @@ mod tests {
                 (second_hash, second_payment_amount)
             ]
         );
+        assert_eq!(actual_batch_payments, &vec![]);
+        assert_eq!(
+            actual_hashes_and_nonces,
+            &vec![(first_hash, 4), (second_hash, 5)]
+        );
+        assert_eq!(actual_gas_price_wei, &123_000_000_000);
         let mut append_transaction_to_batch_params =
             append_transaction_to_batch_params_arc.lock().unwrap();
         let (bytes_first_payment, web3_from_ertb_call_1) =
@@ -1645,6 +2102,9 @@ mod tests {
         let probe_message = PendingPayableFingerprintSeeds {
             batch_wide_timestamp: SystemTime::now(),
             hashes_and_balances: vec![],
+            batch_payments: vec![],
+            hashes_and_nonces: vec![],
+            gas_price_wei: 0,
         };
         recipient.try_send(probe_message).unwrap();
         System::current().stop();
@@ -1676,6 +2136,27 @@ mod tests {
         assert_eq!(Subject::web3_gas_limit_const_part(Chain::Dev), 55_000);
     }
 
+    #[test]
+    fn web3_gas_limit_const_part_of_a_custom_chain_comes_from_its_record() {
+        register_custom_chains(vec![CustomChainSpec {
+            chain_id: 7_200_001,
+            identifier: "gas-limit-devnet".to_string(),
+            contract_address: "0x00000000000000000000000000000000000000cc".to_string(),
+            contract_creation_block: 0,
+            confirmation_depth: 0,
+            gas_limit_const_part: 91_000,
+            native_transfer_gas_limit: 23_000,
+            disperse_contract_address: None,
+            mainnet: false,
+        }])
+        .unwrap();
+
+        let result =
+            BlockchainInterfaceWeb3::<Http>::web3_gas_limit_const_part(Chain::Custom(7_200_001));
+
+        assert_eq!(result, 91_000);
+    }
+
     #[test]
     fn gas_limit_for_polygon_mainnet_lies_within_limits_for_raw_transaction() {
         test_gas_limit_is_between_limits(Chain::PolyMainnet);
@@ -1857,6 +2338,97 @@ mod tests {
         );
     }
 
+    #[test]
+    fn sign_transaction_delegates_address_only_wallet_to_external_signer() {
+        let transport = TestTransport::default();
+        let sign_transaction_params_arc = Arc::new(Mutex::new(vec![]));
+        let signed_transaction = make_default_signed_transaction();
+        let external_signer = ExternalSignerMock::default()
+            .sign_transaction_params(&sign_transaction_params_arc)
+            .sign_transaction_result(Ok(signed_transaction.clone()));
+        let chain = Chain::PolyAmoy;
+        let mut subject =
+            BlockchainInterfaceWeb3::new(transport, make_fake_event_loop_handle(), chain);
+        subject.batch_payable_tools = Box::new(BatchPayableToolsMock::default());
+        subject.set_external_signer(Box::new(external_signer));
+        let recipient = make_wallet("recipient");
+        let consuming_wallet =
+            Wallet::from_str("0x3f69f9efd4f2592fd70be8c32ecd9dce71c472fc").unwrap();
+        let nonce = U256::from(5);
+
+        let result = subject.sign_transaction(&recipient, &consuming_wallet, 444444, nonce, 123);
+
+        assert_eq!(result, Ok(signed_transaction));
+        let sign_transaction_params = sign_transaction_params_arc.lock().unwrap();
+        let data = BlockchainInterfaceWeb3::<TestTransport>::transaction_data(&recipient, 444444);
+        assert_eq!(
+            *sign_transaction_params,
+            vec![(
+                consuming_wallet.address(),
+                TransactionParameters {
+                    nonce: Some(nonce),
+                    to: Some(chain.rec().contract),
+                    gas: U256::from(subject.compute_gas_limit(data.as_slice())),
+                    gas_price: Some(gwei_to_wei(123_u64)),
+                    value: U256::zero(),
+                    data: Bytes(data.to_vec()),
+                    chain_id: Some(chain.rec().num_chain_id),
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn sign_transaction_prefers_local_key_over_external_signer() {
+        let transport = TestTransport::default();
+        let signed_transaction = make_default_signed_transaction();
+        let batch_payable_tools = BatchPayableToolsMock::<TestTransport>::default()
+            .sign_transaction_result(Ok(signed_transaction.clone()));
+        let mut subject =
+            BlockchainInterfaceWeb3::new(transport, make_fake_event_loop_handle(), Chain::PolyAmoy);
+        subject.batch_payable_tools = Box::new(batch_payable_tools);
+        subject.set_external_signer(Box::new(ExternalSignerMock::default()));
+        let consuming_wallet = make_paying_wallet(b"okay-wallet");
+
+        let result = subject.sign_transaction(
+            &make_wallet("recipient"),
+            &consuming_wallet,
+            444444,
+            U256::from(1),
+            123,
+        );
+
+        assert_eq!(result, Ok(signed_transaction));
+    }
+
+    #[test]
+    fn sign_transaction_reports_external_signer_failure() {
+        let transport = TestTransport::default();
+        let external_signer = ExternalSignerMock::default().sign_transaction_result(Err(
+            ExternalSignerError::Refused("Request denied".to_string()),
+        ));
+        let mut subject =
+            BlockchainInterfaceWeb3::new(transport, make_fake_event_loop_handle(), Chain::PolyAmoy);
+        subject.set_external_signer(Box::new(external_signer));
+        let consuming_wallet =
+            Wallet::from_str("0x3f69f9efd4f2592fd70be8c32ecd9dce71c472fc").unwrap();
+
+        let result = subject.sign_transaction(
+            &make_wallet("recipient"),
+            &consuming_wallet,
+            444444,
+            U256::from(1),
+            123,
+        );
+
+        assert_eq!(
+            result,
+            Err(PayableTransactionError::Signing(
+                "External signer refused to sign: Request denied".to_string()
+            ))
+        );
+    }
+
     fn test_consuming_wallet_with_secret() -> Wallet {
         let key_pair = Bip32EncryptionKeyProvider::from_raw_secret(
             &decode_hex("97923d8fd8de4a00f912bfb77ef483141dec551bd73ea59343ef5c4aac965d04")
@@ -1889,6 +2461,7 @@ mod tests {
             // It performs on even cheaper fees, but we're
             // limited by the units here
             Chain::BaseMainnet | Chain::BaseSepolia => 1,
+            Chain::Custom(_) => panic!("No signed transaction templates for custom chains"),
         };
         let payment_size_wei = gwei_to_wei(1_000_u64);
         let payable_account = make_payable_account_with_wallet_and_balance_and_timestamp_opt(
@@ -2228,38 +2801,474 @@ mod tests {
     }
 
     #[test]
-    fn advance_used_nonce() {
-        let initial_nonce = U256::from(55);
+    fn bump_transaction_fee_resends_the_transaction_with_the_same_nonce_and_a_higher_gas_price() {
+        init_test_logging();
+        let test_name = "bump_transaction_fee_resends_the_transaction_with_the_same_nonce_and_a_higher_gas_price";
+        let sign_transaction_params_arc = Arc::new(Mutex::new(vec![]));
+        let append_transaction_to_batch_params_arc = Arc::new(Mutex::new(vec![]));
+        let consuming_wallet = make_paying_wallet(b"stuck_sender");
+        let stuck_transaction = make_stuck_transaction(111);
+        let mut signed_transaction = make_default_signed_transaction();
+        signed_transaction.transaction_hash = make_tx_hash(222);
+        signed_transaction.raw_transaction = Bytes(vec![1, 2, 3]);
+        let batch_payable_tools = BatchPayableToolsMock::<TestTransport>::default()
+            .sign_transaction_params(&sign_transaction_params_arc)
+            .sign_transaction_result(Ok(signed_transaction))
+            .append_transaction_to_batch_params(&append_transaction_to_batch_params_arc)
+            .submit_batch_result(Ok(vec![Ok(json!("irrelevant"))]));
+        let chain = TEST_DEFAULT_CHAIN;
+        let mut subject = BlockchainInterfaceWeb3::new(
+            TestTransport::default(),
+            make_fake_event_loop_handle(),
+            chain,
+        );
+        subject.logger = Logger::new(test_name);
+        subject.batch_payable_tools = Box::new(batch_payable_tools);
 
-        let result = BlockchainInterfaceWeb3::<TestTransport>::advance_used_nonce(initial_nonce);
+        let result = subject.bump_transaction_fee(&consuming_wallet, &stuck_transaction, 20);
 
-        assert_eq!(result, U256::from(56))
+        assert_eq!(
+            result,
+            Ok(FeeBumpedTransaction {
+                hash: make_tx_hash(222),
+                gas_price_wei: 1_200_000_000
+            })
+        );
+        let mut sign_transaction_params = sign_transaction_params_arc.lock().unwrap();
+        let (transaction_params, _, _) = sign_transaction_params.remove(0);
+        assert!(sign_transaction_params.is_empty());
+        let expected_data = BlockchainInterfaceWeb3::<TestTransport>::transaction_data(
+            &make_wallet("creditor111"),
+            1_000_000,
+        );
+        assert_eq!(
+            transaction_params,
+            TransactionParameters {
+                nonce: Some(U256::from(211)),
+                to: Some(chain.rec().contract),
+                gas: subject.compute_gas_limit(&expected_data),
+                gas_price: Some(U256::from(1_200_000_000)),
+                value: U256::zero(),
+                data: Bytes(expected_data.to_vec()),
+                chain_id: Some(chain.rec().num_chain_id),
+            }
+        );
+        let append_transaction_to_batch_params =
+            append_transaction_to_batch_params_arc.lock().unwrap();
+        assert_eq!(append_transaction_to_batch_params.len(), 1);
+        assert_eq!(
+            append_transaction_to_batch_params[0].0,
+            Bytes(vec![1, 2, 3])
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "INFO: {test_name}: Replacing pending transaction {:?} (nonce 211) by {:?} with gas \
+            price raised from 1,000,000,000 to 1,200,000,000 wei",
+            stuck_transaction.hash,
+            make_tx_hash(222)
+        ));
     }
 
     #[test]
-    fn output_by_joining_sources_works() {
-        let accounts = vec![
-            PayableAccount {
-                wallet: make_wallet("4567"),
-                balance_wei: 2_345_678,
-                last_paid_timestamp: from_time_t(4500000),
-                pending_payable_opt: None,
-            },
-            PayableAccount {
-                wallet: make_wallet("5656"),
-                balance_wei: 6_543_210,
-                last_paid_timestamp: from_time_t(333000),
-                pending_payable_opt: None,
-            },
-        ];
-        let fingerprint_inputs = vec![
-            (make_tx_hash(444), 2_345_678),
-            (make_tx_hash(333), 6_543_210),
+    fn bump_transaction_fee_resends_a_batch_transaction_through_the_disperse_contract() {
+        let sign_transaction_params_arc = Arc::new(Mutex::new(vec![]));
+        let chain = register_disperse_chain(7_200_005, "bumping-disperse-devnet");
+        let consuming_wallet = make_paying_wallet(b"stuck_sender");
+        let mut stuck_transaction = make_stuck_transaction(111);
+        stuck_transaction.payments = vec![
+            (make_wallet("creditor1"), 1_000_000),
+            (make_wallet("creditor2"), 2_500_000),
         ];
-        let responses = vec![
-            Ok(Value::String(String::from("blah"))),
-            Err(web3::Error::Rpc(RPCError {
-                code: ErrorCode::ParseError,
+        let batch_payable_tools = BatchPayableToolsMock::<TestTransport>::default()
+            .sign_transaction_params(&sign_transaction_params_arc)
+            .sign_transaction_result(Ok(make_signed_transaction(make_tx_hash(222), vec![4, 5])))
+            .submit_batch_result(Ok(vec![Ok(json!("irrelevant"))]));
+        let mut subject = BlockchainInterfaceWeb3::new(
+            TestTransport::default(),
+            make_fake_event_loop_handle(),
+            chain,
+        );
+        subject.batch_payable_tools = Box::new(batch_payable_tools);
+
+        let result = subject.bump_transaction_fee(&consuming_wallet, &stuck_transaction, 10);
+
+        assert_eq!(
+            result,
+            Ok(FeeBumpedTransaction {
+                hash: make_tx_hash(222),
+                gas_price_wei: 1_100_000_000
+            })
+        );
+        let mut sign_transaction_params = sign_transaction_params_arc.lock().unwrap();
+        let (transaction_params, _, _) = sign_transaction_params.remove(0);
+        let expected_data = subject.disperse_transaction_data(&stuck_transaction.payments);
+        assert_eq!(
+            transaction_params,
+            TransactionParameters {
+                nonce: Some(U256::from(211)),
+                to: chain.rec().disperse_contract_opt,
+                gas: subject.compute_disperse_gas_limit(&expected_data, 2),
+                gas_price: Some(U256::from(1_100_000_000)),
+                value: U256::zero(),
+                data: Bytes(expected_data),
+                chain_id: Some(chain.rec().num_chain_id),
+            }
+        );
+    }
+
+    #[test]
+    fn bump_transaction_fee_cannot_resend_a_batch_transaction_without_disperse_contract() {
+        let consuming_wallet = make_paying_wallet(b"stuck_sender");
+        let mut stuck_transaction = make_stuck_transaction(111);
+        stuck_transaction.payments = vec![
+            (make_wallet("creditor1"), 1_000_000),
+            (make_wallet("creditor2"), 2_500_000),
+        ];
+        let subject = BlockchainInterfaceWeb3::new(
+            TestTransport::default(),
+            make_fake_event_loop_handle(),
+            TEST_DEFAULT_CHAIN,
+        );
+
+        let result = subject.bump_transaction_fee(&consuming_wallet, &stuck_transaction, 10);
+
+        assert_eq!(result, Err(FeeBumpError::NoDisperseContract))
+    }
+
+    #[test]
+    fn bump_transaction_fee_refuses_gas_price_beyond_u64() {
+        let consuming_wallet = make_paying_wallet(b"stuck_sender");
+        let mut stuck_transaction = make_stuck_transaction(111);
+        stuck_transaction.gas_price_wei = u64::MAX - 1;
+        let subject = BlockchainInterfaceWeb3::new(
+            TestTransport::default(),
+            make_fake_event_loop_handle(),
+            TEST_DEFAULT_CHAIN,
+        );
+
+        let result = subject.bump_transaction_fee(&consuming_wallet, &stuck_transaction, 10);
+
+        let expected_gas_price = U256::from(u64::MAX - 1) * U256::from(110) / U256::from(100);
+        assert_eq!(
+            result,
+            Err(FeeBumpError::GasPriceOverflow(expected_gas_price))
+        )
+    }
+
+    #[test]
+    fn bump_transaction_fee_handles_rpc_error_of_the_replacement() {
+        let consuming_wallet = make_paying_wallet(b"stuck_sender");
+        let batch_payable_tools = BatchPayableToolsMock::<TestTransport>::default()
+            .sign_transaction_result(Ok(make_default_signed_transaction()))
+            .submit_batch_result(Ok(vec![Err(web3::Error::Rpc(RPCError {
+                code: ErrorCode::ServerError(-32000),
+                message: "replacement transaction underpriced".to_string(),
+                data: None,
+            }))]));
+        let mut subject = BlockchainInterfaceWeb3::new(
+            TestTransport::default(),
+            make_fake_event_loop_handle(),
+            TEST_DEFAULT_CHAIN,
+        );
+        subject.batch_payable_tools = Box::new(batch_payable_tools);
+
+        let result =
+            subject.bump_transaction_fee(&consuming_wallet, &make_stuck_transaction(111), 10);
+
+        assert_eq!(
+            result,
+            Err(FeeBumpError::Sending(
+                "RPC error: Error { code: ServerError(-32000), message: \"replacement transaction \
+                underpriced\", data: None }"
+                    .to_string()
+            ))
+        )
+    }
+
+    #[test]
+    fn transfer_funds_sends_masq_to_the_recipient() {
+        init_test_logging();
+        let test_name = "transfer_funds_sends_masq_to_the_recipient";
+        let sign_transaction_params_arc = Arc::new(Mutex::new(vec![]));
+        let consuming_wallet = make_paying_wallet(b"transfer_sender");
+        let recipient = make_wallet("cold storage");
+        let mut signed_transaction = make_default_signed_transaction();
+        signed_transaction.transaction_hash = make_tx_hash(222);
+        let batch_payable_tools = BatchPayableToolsMock::<TestTransport>::default()
+            .sign_transaction_params(&sign_transaction_params_arc)
+            .sign_transaction_result(Ok(signed_transaction))
+            .submit_batch_result(Ok(vec![Ok(json!("irrelevant"))]));
+        let chain = TEST_DEFAULT_CHAIN;
+        let mut subject = BlockchainInterfaceWeb3::new(
+            TestTransport::default(),
+            make_fake_event_loop_handle(),
+            chain,
+        );
+        subject.logger = Logger::new(test_name);
+        subject.batch_payable_tools = Box::new(batch_payable_tools);
+        let agent = make_transfer_agent(consuming_wallet, 1_000_000_000_000_000, 5_000_000, 2);
+        let order = FundsTransferOrder {
+            recipient: recipient.clone(),
+            currency: TransferCurrency::Masq,
+            amount_wei_opt: Some(4_000_000),
+            dry_run: false,
+        };
+
+        let result = subject.transfer_funds(&agent, &order);
+
+        let data =
+            BlockchainInterfaceWeb3::<TestTransport>::transaction_data(&recipient, 4_000_000);
+        let gas_limit = subject.compute_gas_limit(data.as_slice());
+        assert_eq!(
+            result,
+            Ok(FundsTransferOutcome {
+                hash_opt: Some(make_tx_hash(222)),
+                amount_wei: 4_000_000,
+                fee_estimate_wei: gas_limit.as_u128() * 2_000_000_000,
+                gas_price_wei: 2_000_000_000,
+                nonce: U256::from(9),
+            })
+        );
+        let mut sign_transaction_params = sign_transaction_params_arc.lock().unwrap();
+        let (transaction_params, _, _) = sign_transaction_params.remove(0);
+        assert!(sign_transaction_params.is_empty());
+        assert_eq!(
+            transaction_params,
+            TransactionParameters {
+                nonce: Some(U256::from(9)),
+                to: Some(chain.rec().contract),
+                gas: gas_limit,
+                gas_price: Some(U256::from(2_000_000_000)),
+                value: U256::zero(),
+                data: Bytes(data.to_vec()),
+                chain_id: Some(chain.rec().num_chain_id),
+            }
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "INFO: {test_name}: Transferring 4,000,000 wei of Masq to {} in transaction {:?} \
+            (nonce 9)",
+            recipient,
+            make_tx_hash(222)
+        ));
+    }
+
+    #[test]
+    fn transfer_funds_sweeps_all_native_currency_but_the_fee() {
+        let sign_transaction_params_arc = Arc::new(Mutex::new(vec![]));
+        let consuming_wallet = make_paying_wallet(b"transfer_sender");
+        let recipient = make_wallet("exchange");
+        let batch_payable_tools = BatchPayableToolsMock::<TestTransport>::default()
+            .sign_transaction_params(&sign_transaction_params_arc)
+            .sign_transaction_result(Ok(make_default_signed_transaction()))
+            .submit_batch_result(Ok(vec![Ok(json!("irrelevant"))]));
+        let mut subject = BlockchainInterfaceWeb3::new(
+            TestTransport::default(),
+            make_fake_event_loop_handle(),
+            TEST_DEFAULT_CHAIN,
+        );
+        subject.batch_payable_tools = Box::new(batch_payable_tools);
+        let fee_wei = 21_000 * 3_000_000_000_u128;
+        let agent = make_transfer_agent(consuming_wallet, fee_wei + 1_234_567, 0, 3);
+        let order = FundsTransferOrder {
+            recipient: recipient.clone(),
+            currency: TransferCurrency::Native,
+            amount_wei_opt: None,
+            dry_run: false,
+        };
+
+        let result = subject.transfer_funds(&agent, &order);
+
+        assert_eq!(result.as_ref().unwrap().amount_wei, 1_234_567);
+        assert_eq!(result.unwrap().fee_estimate_wei, fee_wei);
+        let mut sign_transaction_params = sign_transaction_params_arc.lock().unwrap();
+        let (transaction_params, _, _) = sign_transaction_params.remove(0);
+        assert_eq!(transaction_params.to, Some(recipient.address()));
+        assert_eq!(transaction_params.gas, U256::from(21_000));
+        assert_eq!(transaction_params.value, U256::from(1_234_567));
+        assert_eq!(transaction_params.data, Bytes(vec![]));
+    }
+
+    #[test]
+    fn transfer_funds_in_dry_run_only_estimates() {
+        let consuming_wallet = make_paying_wallet(b"transfer_sender");
+        let subject = BlockchainInterfaceWeb3::new(
+            TestTransport::default(),
+            make_fake_event_loop_handle(),
+            TEST_DEFAULT_CHAIN,
+        );
+        let agent = make_transfer_agent(consuming_wallet, 1_000_000_000_000_000, 0, 1);
+        let order = FundsTransferOrder {
+            recipient: make_wallet("exchange"),
+            currency: TransferCurrency::Native,
+            amount_wei_opt: Some(500),
+            dry_run: true,
+        };
+
+        let result = subject.transfer_funds(&agent, &order);
+
+        assert_eq!(
+            result,
+            Ok(FundsTransferOutcome {
+                hash_opt: None,
+                amount_wei: 500,
+                fee_estimate_wei: 21_000_000_000_000,
+                gas_price_wei: 1_000_000_000,
+                nonce: U256::from(9),
+            })
+        );
+    }
+
+    #[test]
+    fn transfer_funds_refuses_more_masq_than_available() {
+        let consuming_wallet = make_paying_wallet(b"transfer_sender");
+        let subject = BlockchainInterfaceWeb3::new(
+            TestTransport::default(),
+            make_fake_event_loop_handle(),
+            TEST_DEFAULT_CHAIN,
+        );
+        let agent = make_transfer_agent(consuming_wallet, 1_000_000_000_000_000, 5_000, 1);
+        let order = FundsTransferOrder {
+            recipient: make_wallet("exchange"),
+            currency: TransferCurrency::Masq,
+            amount_wei_opt: Some(5_001),
+            dry_run: true,
+        };
+
+        let result = subject.transfer_funds(&agent, &order);
+
+        assert_eq!(
+            result,
+            Err(FundsTransferError::InsufficientBalance {
+                currency: TransferCurrency::Masq,
+                needed_wei: 5_001,
+                available_wei: 5_000
+            })
+        );
+    }
+
+    #[test]
+    fn transfer_funds_refuses_masq_transfer_whose_fee_is_unaffordable() {
+        let consuming_wallet = make_paying_wallet(b"transfer_sender");
+        let subject = BlockchainInterfaceWeb3::new(
+            TestTransport::default(),
+            make_fake_event_loop_handle(),
+            TEST_DEFAULT_CHAIN,
+        );
+        let agent = make_transfer_agent(consuming_wallet, 1_000, 5_000, 1);
+        let order = FundsTransferOrder {
+            recipient: make_wallet("exchange"),
+            currency: TransferCurrency::Masq,
+            amount_wei_opt: None,
+            dry_run: true,
+        };
+
+        let result = subject.transfer_funds(&agent, &order);
+
+        match result {
+            Err(FundsTransferError::InsufficientBalance {
+                currency: TransferCurrency::Native,
+                available_wei: 1_000,
+                ..
+            }) => (),
+            x => panic!(
+                "we expected insufficient balance for the fee but got {:?}",
+                x
+            ),
+        }
+    }
+
+    #[test]
+    fn transfer_funds_has_nothing_to_sweep_if_the_fee_eats_everything() {
+        let consuming_wallet = make_paying_wallet(b"transfer_sender");
+        let subject = BlockchainInterfaceWeb3::new(
+            TestTransport::default(),
+            make_fake_event_loop_handle(),
+            TEST_DEFAULT_CHAIN,
+        );
+        let agent = make_transfer_agent(consuming_wallet, 21_000_000_000_000, 5_000, 1);
+        let order = FundsTransferOrder {
+            recipient: make_wallet("exchange"),
+            currency: TransferCurrency::Native,
+            amount_wei_opt: None,
+            dry_run: true,
+        };
+
+        let result = subject.transfer_funds(&agent, &order);
+
+        assert_eq!(
+            result,
+            Err(FundsTransferError::NothingToTransfer(
+                TransferCurrency::Native
+            ))
+        );
+    }
+
+    fn make_transfer_agent(
+        consuming_wallet: Wallet,
+        transaction_fee_balance_wei: u128,
+        masq_balance_wei: u128,
+        gas_price_gwei: u64,
+    ) -> BlockchainAgentMock {
+        BlockchainAgentMock::default()
+            .consuming_wallet_result(consuming_wallet)
+            .consuming_wallet_balances_result(ConsumingWalletBalances {
+                transaction_fee_balance_in_minor_units: U256::from(transaction_fee_balance_wei),
+                masq_token_balance_in_minor_units: U256::from(masq_balance_wei),
+            })
+            .agreed_fee_per_computation_unit_result(gas_price_gwei)
+            .pending_transaction_id_result(U256::from(9))
+    }
+
+    #[test]
+    fn increased_gas_price_always_exceeds_the_original() {
+        type Subject = BlockchainInterfaceWeb3<TestTransport>;
+
+        assert_eq!(
+            Subject::increased_gas_price(U256::from(1000), 20),
+            U256::from(1200)
+        );
+        assert_eq!(
+            Subject::increased_gas_price(U256::from(5), 10),
+            U256::from(6)
+        );
+        assert_eq!(
+            Subject::increased_gas_price(U256::from(0), 10),
+            U256::from(1)
+        );
+    }
+
+    #[test]
+    fn advance_used_nonce() {
+        let initial_nonce = U256::from(55);
+
+        let result = BlockchainInterfaceWeb3::<TestTransport>::advance_used_nonce(initial_nonce);
+
+        assert_eq!(result, U256::from(56))
+    }
+
+    #[test]
+    fn output_by_joining_sources_works() {
+        let accounts = vec![
+            PayableAccount {
+                wallet: make_wallet("4567"),
+                balance_wei: 2_345_678,
+                last_paid_timestamp: from_time_t(4500000),
+                pending_payable_opt: None,
+            },
+            PayableAccount {
+                wallet: make_wallet("5656"),
+                balance_wei: 6_543_210,
+                last_paid_timestamp: from_time_t(333000),
+                pending_payable_opt: None,
+            },
+        ];
+        let fingerprint_inputs = vec![
+            (make_tx_hash(444), 2_345_678),
+            (make_tx_hash(333), 6_543_210),
+        ];
+        let responses = vec![
+            Ok(Value::String(String::from("blah"))),
+            Err(web3::Error::Rpc(RPCError {
+                code: ErrorCode::ParseError,
                 message: "I guess we've got a problem".to_string(),
                 data: None,
             })),
@@ -2291,6 +3300,230 @@ mod tests {
         )
     }
 
+    fn register_disperse_chain(chain_id: u64, identifier: &str) -> Chain {
+        register_custom_chains(vec![CustomChainSpec {
+            chain_id,
+            identifier: identifier.to_string(),
+            contract_address: "0x00000000000000000000000000000000000000cc".to_string(),
+            contract_creation_block: 0,
+            confirmation_depth: 0,
+            gas_limit_const_part: 60_000,
+            native_transfer_gas_limit: 21_000,
+            disperse_contract_address: Some(
+                "0x00000000000000000000000000000000000000dd".to_string(),
+            ),
+            mainnet: false,
+        }])
+        .unwrap()[0]
+    }
+
+    fn make_signed_transaction(hash: H256, raw_transaction: Vec<u8>) -> SignedTransaction {
+        let mut signed_transaction = make_default_signed_transaction();
+        signed_transaction.transaction_hash = hash;
+        signed_transaction.raw_transaction = Bytes(raw_transaction);
+        signed_transaction
+    }
+
+    #[test]
+    fn send_batch_of_payables_pays_all_creditors_in_one_disperse_transaction() {
+        init_test_logging();
+        let test_name = "send_batch_of_payables_pays_all_creditors_in_one_disperse_transaction";
+        let sign_transaction_params_arc = Arc::new(Mutex::new(vec![]));
+        let append_transaction_to_batch_params_arc = Arc::new(Mutex::new(vec![]));
+        let new_payable_fingerprint_params_arc = Arc::new(Mutex::new(vec![]));
+        let chain = register_disperse_chain(7_200_002, "disperse-devnet");
+        let batch_hash = make_tx_hash(0xd15);
+        let batch_payables_tools = BatchPayableToolsMock::default()
+            .sign_transaction_params(&sign_transaction_params_arc)
+            .sign_transaction_result(Ok(make_signed_transaction(batch_hash, vec![1, 2, 3])))
+            .append_transaction_to_batch_params(&append_transaction_to_batch_params_arc)
+            .batch_wide_timestamp_result(from_time_t(190_000_000))
+            .send_new_payable_fingerprint_credentials_params(&new_payable_fingerprint_params_arc)
+            .submit_batch_result(Ok(vec![Ok(json!("irrelevant"))]));
+        let mut subject = BlockchainInterfaceWeb3::new(
+            TestTransport::default(),
+            make_fake_event_loop_handle(),
+            chain,
+        );
+        subject.batch_payable_tools = Box::new(batch_payables_tools);
+        subject.logger = Logger::new(test_name);
+        let (accountant, _, _) = make_recorder();
+        let fingerprints_recipient = accountant.start().recipient();
+        let account_1 = make_payable_account_with_wallet_and_balance_and_timestamp_opt(
+            make_wallet("creditor1"),
+            1_000_000_000,
+            None,
+        );
+        let account_2 = make_payable_account_with_wallet_and_balance_and_timestamp_opt(
+            make_wallet("creditor2"),
+            2_500_000_000,
+            None,
+        );
+        let agent = make_initialized_agent(5, make_paying_wallet(b"disperser"), U256::from(12));
+
+        let result = subject.send_batch_of_payables(
+            agent,
+            &fingerprints_recipient,
+            &[account_1.clone(), account_2.clone()],
+        );
+
+        assert_eq!(
+            result,
+            Ok(vec![
+                Ok(PendingPayable::new(make_wallet("creditor1"), batch_hash)),
+                Ok(PendingPayable::new(make_wallet("creditor2"), batch_hash)),
+            ])
+        );
+        let sign_transaction_params = sign_transaction_params_arc.lock().unwrap();
+        assert_eq!(sign_transaction_params.len(), 1);
+        let transaction_params = &sign_transaction_params[0].0;
+        let mut expected_data = DISPERSE_TOKEN_METHOD_ID.to_vec();
+        expected_data.extend(ethabi::encode(&[
+            Token::Address(H160::from_low_u64_be(0xcc)),
+            Token::Array(vec![
+                Token::Address(make_wallet("creditor1").address()),
+                Token::Address(make_wallet("creditor2").address()),
+            ]),
+            Token::Array(vec![
+                Token::Uint(U256::from(1_000_000_000_u64)),
+                Token::Uint(U256::from(2_500_000_000_u64)),
+            ]),
+        ]));
+        assert_eq!(
+            transaction_params,
+            &TransactionParameters {
+                nonce: Some(U256::from(12)),
+                to: Some(H160::from_low_u64_be(0xdd)),
+                gas: subject.compute_gas_limit(&expected_data) + U256::from(10_000 + 2 * 39_000),
+                gas_price: Some(gwei_to_wei(5_u64)),
+                value: U256::zero(),
+                data: Bytes(expected_data),
+                chain_id: Some(7_200_002),
+            }
+        );
+        let append_transaction_to_batch_params =
+            append_transaction_to_batch_params_arc.lock().unwrap();
+        assert_eq!(append_transaction_to_batch_params.len(), 1);
+        assert_eq!(
+            append_transaction_to_batch_params[0].0,
+            Bytes(vec![1, 2, 3])
+        );
+        let new_payable_fingerprint_params = new_payable_fingerprint_params_arc.lock().unwrap();
+        let (timestamp, _, hashes_and_amounts, batch_payments, hashes_and_nonces, gas_price_wei) =
+            &new_payable_fingerprint_params[0];
+        assert_eq!(timestamp, &from_time_t(190_000_000));
+        assert_eq!(hashes_and_amounts, &vec![(batch_hash, 3_500_000_000)]);
+        assert_eq!(
+            batch_payments,
+            &vec![(
+                batch_hash,
+                vec![
+                    (make_wallet("creditor1"), 1_000_000_000),
+                    (make_wallet("creditor2"), 2_500_000_000)
+                ]
+            )]
+        );
+        assert_eq!(hashes_and_nonces, &vec![(batch_hash, 12)]);
+        assert_eq!(gas_price_wei, &gwei_to_wei::<u64, _>(5_u64));
+        TestLogHandler::new().exists_log_containing(&format!(
+            "DEBUG: {}: Preparing payment of 3,500,000,000 wei to 2 creditors through the \
+             disperse contract 0x00000000000000000000000000000000000000dd with nonce 12",
+            test_name
+        ));
+    }
+
+    #[test]
+    fn send_batch_of_payables_reports_failed_disperse_transaction_for_all_its_creditors() {
+        let chain = register_disperse_chain(7_200_003, "failing-disperse-devnet");
+        let batch_hash = make_tx_hash(0xbad);
+        let batch_payables_tools = BatchPayableToolsMock::default()
+            .sign_transaction_result(Ok(make_signed_transaction(batch_hash, vec![4, 5, 6])))
+            .batch_wide_timestamp_result(from_time_t(190_000_000))
+            .submit_batch_result(Ok(vec![Err(web3::Error::Rpc(RPCError {
+                code: ErrorCode::ServerError(114),
+                message: "insufficient allowance".to_string(),
+                data: None,
+            }))]));
+        let mut subject = BlockchainInterfaceWeb3::new(
+            TestTransport::default(),
+            make_fake_event_loop_handle(),
+            chain,
+        );
+        subject.batch_payable_tools = Box::new(batch_payables_tools);
+        let (accountant, _, _) = make_recorder();
+        let fingerprints_recipient = accountant.start().recipient();
+        let accounts = vec![
+            make_payable_account_with_wallet_and_balance_and_timestamp_opt(
+                make_wallet("creditor1"),
+                1_000,
+                None,
+            ),
+            make_payable_account_with_wallet_and_balance_and_timestamp_opt(
+                make_wallet("creditor2"),
+                2_000,
+                None,
+            ),
+        ];
+        let agent = make_initialized_agent(5, make_paying_wallet(b"disperser"), U256::from(1));
+
+        let result = subject.send_batch_of_payables(agent, &fingerprints_recipient, &accounts);
+
+        assert_eq!(
+            result,
+            Err(PayableTransactionError::Sending {
+                msg: "RPC error: Error { code: ServerError(114), message: \"insufficient allowance\", data: None }"
+                    .to_string(),
+                hashes: vec![batch_hash]
+            })
+        );
+    }
+
+    #[test]
+    fn send_batch_of_payables_pays_a_single_creditor_directly_even_with_disperse_contract() {
+        let sign_transaction_params_arc = Arc::new(Mutex::new(vec![]));
+        let new_payable_fingerprint_params_arc = Arc::new(Mutex::new(vec![]));
+        let chain = register_disperse_chain(7_200_004, "lonely-disperse-devnet");
+        let hash = make_tx_hash(0x1);
+        let batch_payables_tools = BatchPayableToolsMock::default()
+            .sign_transaction_params(&sign_transaction_params_arc)
+            .sign_transaction_result(Ok(make_signed_transaction(hash, vec![7])))
+            .batch_wide_timestamp_result(from_time_t(190_000_000))
+            .send_new_payable_fingerprint_credentials_params(&new_payable_fingerprint_params_arc)
+            .submit_batch_result(Ok(vec![Ok(json!("irrelevant"))]));
+        let mut subject = BlockchainInterfaceWeb3::new(
+            TestTransport::default(),
+            make_fake_event_loop_handle(),
+            chain,
+        );
+        subject.batch_payable_tools = Box::new(batch_payables_tools);
+        let (accountant, _, _) = make_recorder();
+        let fingerprints_recipient = accountant.start().recipient();
+        let account = make_payable_account_with_wallet_and_balance_and_timestamp_opt(
+            make_wallet("creditor1"),
+            1_000,
+            None,
+        );
+        let agent = make_initialized_agent(5, make_paying_wallet(b"disperser"), U256::from(1));
+
+        let result = subject.send_batch_of_payables(agent, &fingerprints_recipient, &[account]);
+
+        assert_eq!(
+            result,
+            Ok(vec![Ok(PendingPayable::new(
+                make_wallet("creditor1"),
+                hash
+            ))])
+        );
+        let sign_transaction_params = sign_transaction_params_arc.lock().unwrap();
+        assert_eq!(
+            sign_transaction_params[0].0.to,
+            Some(H160::from_low_u64_be(0xcc))
+        );
+        let new_payable_fingerprint_params = new_payable_fingerprint_params_arc.lock().unwrap();
+        assert_eq!(new_payable_fingerprint_params[0].2, vec![(hash, 1_000)]);
+        assert_eq!(new_payable_fingerprint_params[0].3, vec![]);
+    }
+
     fn make_initialized_agent(
         gas_price_gwei: u64,
         consuming_wallet: Wallet,
@@ -2311,4 +3544,12 @@ mod tests {
             TRANSFER_METHOD_ID,
         );
     }
+
+    #[test]
+    fn hash_the_disperse_contract_function_signature() {
+        assert_eq!(
+            "disperseToken(address,address[],uint256[])".keccak256()[0..4],
+            DISPERSE_TOKEN_METHOD_ID,
+        );
+    }
 }
@@ -4,6 +4,7 @@
 
 use crate::blockchain::blockchain_bridge::PendingPayableFingerprintSeeds;
 use crate::blockchain::blockchain_interface::blockchain_interface_web3::batch_payable_tools::BatchPayableTools;
+use crate::sub_lib::wallet::Wallet;
 use actix::Recipient;
 use jsonrpc_core as rpc;
 use std::cell::RefCell;
@@ -35,6 +36,9 @@ pub struct BatchPayableToolsMock<T: BatchTransport> {
                 SystemTime,
                 Recipient<PendingPayableFingerprintSeeds>,
                 Vec<(H256, u128)>,
+                Vec<(H256, Vec<(Wallet, u128)>)>,
+                Vec<(H256, u64)>,
+                u64,
             )>,
         >,
     >,
@@ -75,6 +79,9 @@ impl<T: BatchTransport> BatchPayableTools<T> for BatchPayableToolsMock<T> {
         batch_wide_timestamp: SystemTime,
         pp_fingerprint_sub: &Recipient<PendingPayableFingerprintSeeds>,
         hashes_and_balances: &[(H256, u128)],
+        batch_payments: &[(H256, Vec<(Wallet, u128)>)],
+        hashes_and_nonces: &[(H256, u64)],
+        gas_price_wei: u64,
     ) {
         self.send_new_payable_fingerprints_seeds_params
             .lock()
@@ -83,6 +90,9 @@ impl<T: BatchTransport> BatchPayableTools<T> for BatchPayableToolsMock<T> {
                 batch_wide_timestamp,
                 (*pp_fingerprint_sub).clone(),
                 hashes_and_balances.to_vec(),
+                batch_payments.to_vec(),
+                hashes_and_nonces.to_vec(),
+                gas_price_wei,
             ));
     }
 
@@ -130,6 +140,9 @@ impl<T: BatchTransport> BatchPayableToolsMock<T> {
                     SystemTime,
                     Recipient<PendingPayableFingerprintSeeds>,
                     Vec<(H256, u128)>,
+                    Vec<(H256, Vec<(Wallet, u128)>)>,
+                    Vec<(H256, u64)>,
+                    u64,
                 )>,
             >,
         >,
@@ -4,10 +4,12 @@ use crate::accountant::comma_joined_stringifiable;
 use crate::db_config::persistent_configuration::PersistentConfigError;
 use crate::sub_lib::wallet::Wallet;
 use itertools::Either;
+use masq_lib::messages::TransferCurrency;
 use std::fmt;
 use std::fmt::{Display, Formatter};
+use thousands::Separable;
 use variant_count::VariantCount;
-use web3::types::{TransactionReceipt, H256};
+use web3::types::{TransactionReceipt, H256, U256};
 
 const BLOCKCHAIN_SERVICE_URL_NOT_SPECIFIED: &str = "Uninitialized blockchain interface. To avoid \
 being delinquency-banned, you should restart the Node with a value for blockchain-service-url";
@@ -47,6 +49,7 @@ pub enum PayableTransactionError {
     UnusableWallet(String),
     Signing(String),
     Sending { msg: String, hashes: Vec<H256> },
+    NonceTakenByTransfer(U256),
     UninitializedBlockchainInterface,
 }
 
@@ -74,6 +77,12 @@ impl Display for PayableTransactionError {
                 msg,
                 comma_joined_stringifiable(hashes, |hash| format!("{:?}", hash))
             ),
+            Self::NonceTakenByTransfer(nonce) => write!(
+                f,
+                "Transaction id {} was meanwhile used by a funds transfer; the payables will \
+                wait for the next scan",
+                nonce
+            ),
             Self::UninitializedBlockchainInterface => {
                 write!(f, "{}", BLOCKCHAIN_SERVICE_URL_NOT_SPECIFIED)
             }
@@ -81,6 +90,97 @@ impl Display for PayableTransactionError {
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, VariantCount)]
+pub enum FeeBumpError {
+    GasPriceOverflow(U256),
+    NoDisperseContract,
+    UnusableWallet(String),
+    Signing(String),
+    Sending(String),
+    UninitializedBlockchainInterface,
+}
+
+impl Display for FeeBumpError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::GasPriceOverflow(gas_price) => write!(
+                f,
+                "The raised gas price of {} wei is beyond what can be paid",
+                gas_price.separate_with_commas()
+            ),
+            Self::NoDisperseContract => write!(
+                f,
+                "The original transaction paid several creditors, but the chain has no disperse \
+                contract configured anymore"
+            ),
+            Self::UnusableWallet(msg) => write!(
+                f,
+                "Unusable wallet for signing the replacement transaction: \"{}\"",
+                msg
+            ),
+            Self::Signing(msg) => write!(f, "Signing phase: \"{}\"", msg),
+            Self::Sending(msg) => write!(f, "Sending phase: \"{}\"", msg),
+            Self::UninitializedBlockchainInterface => {
+                write!(f, "{}", BLOCKCHAIN_SERVICE_URL_NOT_SPECIFIED)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, VariantCount)]
+pub enum FundsTransferError {
+    InsufficientBalance {
+        currency: TransferCurrency,
+        needed_wei: u128,
+        available_wei: u128,
+    },
+    NothingToTransfer(TransferCurrency),
+    UnusableWallet(String),
+    Signing(String),
+    Sending(String),
+    UninitializedBlockchainInterface,
+}
+
+impl Display for FundsTransferError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InsufficientBalance {
+                currency,
+                needed_wei,
+                available_wei,
+            } => write!(
+                f,
+                "Insufficient {} balance: {} wei needed but only {} wei available",
+                Self::currency_name(*currency),
+                needed_wei.separate_with_commas(),
+                available_wei.separate_with_commas()
+            ),
+            Self::NothingToTransfer(currency) => write!(
+                f,
+                "There is no {} balance left to transfer",
+                Self::currency_name(*currency)
+            ),
+            Self::UnusableWallet(msg) => {
+                write!(f, "Unusable wallet for signing the transfer: \"{}\"", msg)
+            }
+            Self::Signing(msg) => write!(f, "Signing phase: \"{}\"", msg),
+            Self::Sending(msg) => write!(f, "Sending phase: \"{}\"", msg),
+            Self::UninitializedBlockchainInterface => {
+                write!(f, "{}", BLOCKCHAIN_SERVICE_URL_NOT_SPECIFIED)
+            }
+        }
+    }
+}
+
+impl FundsTransferError {
+    fn currency_name(currency: TransferCurrency) -> &'static str {
+        match currency {
+            TransferCurrency::Masq => "MASQ",
+            TransferCurrency::Native => "transaction fee currency",
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, VariantCount)]
 pub enum BlockchainAgentBuildError {
     GasPrice(PersistentConfigError),
@@ -127,14 +227,18 @@ impl Display for BlockchainAgentBuildError {
 
 #[cfg(test)]
 mod tests {
-    use crate::blockchain::blockchain_interface::data_structures::errors::BLOCKCHAIN_SERVICE_URL_NOT_SPECIFIED;
+    use crate::blockchain::blockchain_interface::data_structures::errors::{
+        FeeBumpError, FundsTransferError, BLOCKCHAIN_SERVICE_URL_NOT_SPECIFIED,
+    };
     use crate::blockchain::blockchain_interface::{
         BlockchainAgentBuildError, BlockchainError, PayableTransactionError,
     };
     use crate::blockchain::test_utils::make_tx_hash;
     use crate::db_config::persistent_configuration::PersistentConfigError;
     use crate::test_utils::make_wallet;
+    use masq_lib::messages::TransferCurrency;
     use masq_lib::utils::{slice_of_strs_to_vec_of_strings, to_string};
+    use web3::types::U256;
 
     #[test]
     fn constants_have_correct_values() {
@@ -176,6 +280,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn fee_bump_error_implements_display() {
+        let original_errors = [
+            FeeBumpError::GasPriceOverflow(U256::from(u64::MAX) + U256::one()),
+            FeeBumpError::NoDisperseContract,
+            FeeBumpError::UnusableWallet("Cannot find the key".to_string()),
+            FeeBumpError::Signing("Bad nonce".to_string()),
+            FeeBumpError::Sending("Connection refused".to_string()),
+            FeeBumpError::UninitializedBlockchainInterface,
+        ];
+
+        let actual_error_msgs = original_errors.iter().map(to_string).collect::<Vec<_>>();
+
+        assert_eq!(
+            original_errors.len(),
+            FeeBumpError::VARIANT_COUNT,
+            "you forgot to add all variants in this test"
+        );
+        assert_eq!(
+            actual_error_msgs,
+            slice_of_strs_to_vec_of_strings(&[
+                "The raised gas price of 18,446,744,073,709,551,616 wei is beyond what can be paid",
+                "The original transaction paid several creditors, but the chain has no disperse \
+                contract configured anymore",
+                "Unusable wallet for signing the replacement transaction: \"Cannot find the key\"",
+                "Signing phase: \"Bad nonce\"",
+                "Sending phase: \"Connection refused\"",
+                BLOCKCHAIN_SERVICE_URL_NOT_SPECIFIED
+            ])
+        )
+    }
+
     #[test]
     fn payable_payment_error_implements_display() {
         let original_errors = [
@@ -194,6 +330,7 @@ mod tests {
                 msg: "Sending to cosmos belongs elsewhere".to_string(),
                 hashes: vec![make_tx_hash(0x6f), make_tx_hash(0xde)],
             },
+            PayableTransactionError::NonceTakenByTransfer(U256::from(45)),
             PayableTransactionError::UninitializedBlockchainInterface,
         ];
 
@@ -216,6 +353,43 @@ mod tests {
                 "Sending phase: \"Sending to cosmos belongs elsewhere\". Signed and hashed \
                 transactions: 0x000000000000000000000000000000000000000000000000000000000000006f, \
                 0x00000000000000000000000000000000000000000000000000000000000000de",
+                "Transaction id 45 was meanwhile used by a funds transfer; the payables will wait \
+                for the next scan",
+                BLOCKCHAIN_SERVICE_URL_NOT_SPECIFIED
+            ])
+        )
+    }
+
+    #[test]
+    fn funds_transfer_error_implements_display() {
+        let original_errors = [
+            FundsTransferError::InsufficientBalance {
+                currency: TransferCurrency::Masq,
+                needed_wei: 5_000_000,
+                available_wei: 4_999_999,
+            },
+            FundsTransferError::NothingToTransfer(TransferCurrency::Native),
+            FundsTransferError::UnusableWallet("Cannot find the key".to_string()),
+            FundsTransferError::Signing("Bad nonce".to_string()),
+            FundsTransferError::Sending("Connection refused".to_string()),
+            FundsTransferError::UninitializedBlockchainInterface,
+        ];
+
+        let actual_error_msgs = original_errors.iter().map(to_string).collect::<Vec<_>>();
+
+        assert_eq!(
+            original_errors.len(),
+            FundsTransferError::VARIANT_COUNT,
+            "you forgot to add all variants in this test"
+        );
+        assert_eq!(
+            actual_error_msgs,
+            slice_of_strs_to_vec_of_strings(&[
+                "Insufficient MASQ balance: 5,000,000 wei needed but only 4,999,999 wei available",
+                "There is no transaction fee currency balance left to transfer",
+                "Unusable wallet for signing the transfer: \"Cannot find the key\"",
+                "Signing phase: \"Bad nonce\"",
+                "Sending phase: \"Connection refused\"",
                 BLOCKCHAIN_SERVICE_URL_NOT_SPECIFIED
             ])
         )
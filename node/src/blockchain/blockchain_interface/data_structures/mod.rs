@@ -3,12 +3,16 @@
 pub mod errors;
 use crate::accountant::db_access_objects::pending_payable_dao::PendingPayable;
 use crate::sub_lib::wallet::Wallet;
-use web3::types::{BlockNumber, H256};
+use masq_lib::messages::TransferCurrency;
+use web3::types::{BlockNumber, H256, U256};
 use web3::Error;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct BlockchainTransaction {
     pub block_number: u64,
+    pub block_hash: H256,
+    pub transaction_hash: H256,
+    pub log_index: u64,
     pub from: Wallet,
     pub wei_amount: u128,
 }
@@ -27,3 +31,28 @@ pub struct RpcPayablesFailure {
     pub recipient_wallet: Wallet,
     pub hash: H256,
 }
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct FeeBumpedTransaction {
+    pub hash: H256,
+    pub gas_price_wei: u64,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct FundsTransferOrder {
+    pub recipient: Wallet,
+    pub currency: TransferCurrency,
+    // None stands for everything the consuming wallet can give away
+    pub amount_wei_opt: Option<u128>,
+    pub dry_run: bool,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct FundsTransferOutcome {
+    // None if the transaction was only estimated
+    pub hash_opt: Option<H256>,
+    pub amount_wei: u128,
+    pub fee_estimate_wei: u128,
+    pub gas_price_wei: u64,
+    pub nonce: U256,
+}
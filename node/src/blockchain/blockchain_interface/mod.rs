@@ -7,13 +7,16 @@ pub mod lower_level_interface;
 pub mod test_utils;
 
 use crate::accountant::db_access_objects::payable_dao::PayableAccount;
+use crate::accountant::db_access_objects::pending_payable_dao::StuckTransaction;
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::BlockchainAgent;
 use crate::blockchain::blockchain_bridge::PendingPayableFingerprintSeeds;
 use crate::blockchain::blockchain_interface::data_structures::errors::{
-    BlockchainAgentBuildError, BlockchainError, PayableTransactionError, ResultForReceipt,
+    BlockchainAgentBuildError, BlockchainError, FeeBumpError, FundsTransferError,
+    PayableTransactionError, ResultForReceipt,
 };
 use crate::blockchain::blockchain_interface::data_structures::{
-    ProcessedPayableFallible, RetrievedBlockchainTransactions,
+    FeeBumpedTransaction, FundsTransferOrder, FundsTransferOutcome, ProcessedPayableFallible,
+    RetrievedBlockchainTransactions,
 };
 use crate::blockchain::blockchain_interface::lower_level_interface::LowBlockchainInt;
 use crate::db_config::persistent_configuration::PersistentConfiguration;
@@ -46,6 +49,19 @@ pub trait BlockchainInterface {
 
     fn get_transaction_receipt(&self, hash: H256) -> ResultForReceipt;
 
+    fn bump_transaction_fee(
+        &self,
+        consuming_wallet: &Wallet,
+        stuck_transaction: &StuckTransaction,
+        gas_price_increase_percent: u64,
+    ) -> Result<FeeBumpedTransaction, FeeBumpError>;
+
+    fn transfer_funds(
+        &self,
+        agent: &dyn BlockchainAgent,
+        order: &FundsTransferOrder,
+    ) -> Result<FundsTransferOutcome, FundsTransferError>;
+
     fn lower_interface(&self) -> &dyn LowBlockchainInt;
 
     as_any_ref_in_trait!();
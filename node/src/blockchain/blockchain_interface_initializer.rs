@@ -4,6 +4,7 @@ use crate::blockchain::blockchain_interface::blockchain_interface_web3::{
     BlockchainInterfaceWeb3, REQUESTS_IN_PARALLEL,
 };
 use crate::blockchain::blockchain_interface::BlockchainInterface;
+use crate::blockchain::external_signer::{ExternalSignerClef, ExternalSignerEndpoint};
 use masq_lib::blockchains::chains::Chain;
 use web3::transports::Http;
 
@@ -15,22 +16,32 @@ impl BlockchainInterfaceInitializer {
     pub fn initialize_interface(
         &self,
         blockchain_service_url: &str,
+        external_signer_opt: Option<&ExternalSignerEndpoint>,
         chain: Chain,
     ) -> Box<dyn BlockchainInterface> {
-        self.initialize_web3_interface(blockchain_service_url, chain)
+        self.initialize_web3_interface(blockchain_service_url, external_signer_opt, chain)
     }
 
     fn initialize_web3_interface(
         &self,
         blockchain_service_url: &str,
+        external_signer_opt: Option<&ExternalSignerEndpoint>,
         chain: Chain,
     ) -> Box<dyn BlockchainInterface> {
         match Http::with_max_parallel(blockchain_service_url, REQUESTS_IN_PARALLEL) {
-            Ok((event_loop_handle, transport)) => Box::new(BlockchainInterfaceWeb3::new(
-                transport,
-                event_loop_handle,
-                chain,
-            )),
+            Ok((event_loop_handle, transport)) => {
+                let mut interface =
+                    BlockchainInterfaceWeb3::new(transport, event_loop_handle, chain);
+                if let Some(endpoint) = external_signer_opt {
+                    match ExternalSignerClef::new(endpoint) {
+                        Ok(external_signer) => {
+                            interface.set_external_signer(Box::new(external_signer))
+                        }
+                        Err(e) => panic!("Invalid external signer \"{}\". Error: {}", endpoint, e),
+                    }
+                }
+                Box::new(interface)
+            }
             Err(e) => panic!(
                 "Invalid blockchain service URL \"{}\". Error: {:?}. Chain: {}",
                 blockchain_service_url,
@@ -43,8 +54,12 @@ impl BlockchainInterfaceInitializer {
 
 #[cfg(test)]
 mod tests {
+    use crate::blockchain::blockchain_interface::blockchain_interface_web3::BlockchainInterfaceWeb3;
     use crate::blockchain::blockchain_interface_initializer::BlockchainInterfaceInitializer;
+    use crate::blockchain::external_signer::ExternalSignerEndpoint;
     use masq_lib::blockchains::chains::Chain;
+    use std::str::FromStr;
+    use web3::transports::Http;
 
     use std::net::Ipv4Addr;
 
@@ -57,7 +72,7 @@ mod tests {
         let subject_factory = |port: u16, chain: Chain| {
             let subject = BlockchainInterfaceInitializer {};
             let server_url = &format!("http://{}:{}", &Ipv4Addr::LOCALHOST.to_string(), port);
-            subject.initialize_web3_interface(server_url, chain)
+            subject.initialize_web3_interface(server_url, None, chain)
         };
 
         test_blockchain_interface_is_connected_and_functioning(subject_factory)
@@ -70,6 +85,34 @@ mod tests {
         let blockchain_service_url = "http://λ:8545";
         let subject = BlockchainInterfaceInitializer {};
 
-        subject.initialize_web3_interface(blockchain_service_url, DEFAULT_CHAIN);
+        subject.initialize_web3_interface(blockchain_service_url, None, DEFAULT_CHAIN);
+    }
+
+    #[test]
+    fn initialize_web3_interface_attaches_external_signer() {
+        let subject = BlockchainInterfaceInitializer {};
+        let endpoint = ExternalSignerEndpoint::from_str("ipc:///nonexistent/clef.ipc").unwrap();
+
+        let result = subject.initialize_web3_interface(
+            "http://127.0.0.1:8545",
+            Some(&endpoint),
+            DEFAULT_CHAIN,
+        );
+
+        let interface = result
+            .as_any()
+            .downcast_ref::<BlockchainInterfaceWeb3<Http>>()
+            .unwrap();
+        assert_eq!(interface.external_signer_opt.is_some(), true);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid external signer \"http://λ:8550\". \
+    Error: Could not reach the external signer: Transport error: InvalidUri(InvalidUriChar)")]
+    fn invalid_external_signer_url_produces_panic_for_web3_interface() {
+        let subject = BlockchainInterfaceInitializer {};
+        let endpoint = ExternalSignerEndpoint::Http("http://λ:8550".to_string());
+
+        subject.initialize_web3_interface("http://127.0.0.1:8545", Some(&endpoint), DEFAULT_CHAIN);
     }
 }
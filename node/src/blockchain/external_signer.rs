@@ -0,0 +1,880 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use ethsign_crypto::Keccak256;
+use futures::Future;
+use rlp::Rlp;
+use rustc_hex::ToHex;
+use serde_derive::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::cell::Cell;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+use web3::transports::{EventLoopHandle, Http};
+use web3::types::{Address, Bytes, SignedTransaction, TransactionParameters, H256, U256, U64};
+use web3::{Error as Web3Error, Transport};
+
+pub const SIGN_TRANSACTION_METHOD: &str = "account_signTransaction";
+// Long enough for a human to approve the request at the signer
+pub const DEFAULT_IPC_READ_TIMEOUT_SECS: u64 = 300;
+
+const HTTP_PREFIXES: [&str; 2] = ["http://", "https://"];
+const IPC_PREFIX: &str = "ipc://";
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ExternalSignerEndpoint {
+    Http(String),
+    Ipc {
+        path: PathBuf,
+        read_timeout: Duration,
+    },
+}
+
+impl FromStr for ExternalSignerEndpoint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if HTTP_PREFIXES.iter().any(|prefix| s.starts_with(prefix)) {
+            Ok(ExternalSignerEndpoint::Http(s.to_string()))
+        } else {
+            match s.strip_prefix(IPC_PREFIX) {
+                Some(path) if !path.is_empty() => Ok(ExternalSignerEndpoint::Ipc {
+                    path: PathBuf::from(path),
+                    read_timeout: Duration::from_secs(DEFAULT_IPC_READ_TIMEOUT_SECS),
+                }),
+                _ => Err(format!(
+                    "'{}' is neither an http(s):// URL nor an ipc:// socket path",
+                    s
+                )),
+            }
+        }
+    }
+}
+
+impl Display for ExternalSignerEndpoint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExternalSignerEndpoint::Http(url) => write!(f, "{}", url),
+            ExternalSignerEndpoint::Ipc { path, .. } => {
+                write!(f, "{}{}", IPC_PREFIX, path.display())
+            }
+        }
+    }
+}
+
+impl ExternalSignerEndpoint {
+    // Only a Unix socket connection can time out waiting for the signer's answer
+    pub fn with_read_timeout(self, read_timeout: Duration) -> Result<Self, String> {
+        match self {
+            ExternalSignerEndpoint::Ipc { path, .. } => {
+                Ok(ExternalSignerEndpoint::Ipc { path, read_timeout })
+            }
+            ExternalSignerEndpoint::Http(url) => Err(format!(
+                "A read timeout applies only to an ipc:// external signer, not '{}'",
+                url
+            )),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ExternalSignerError {
+    Unreachable(String),
+    Refused(String),
+    MalformedResponse(String),
+}
+
+impl Display for ExternalSignerError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExternalSignerError::Unreachable(msg) => {
+                write!(f, "Could not reach the external signer: {}", msg)
+            }
+            ExternalSignerError::Refused(msg) => {
+                write!(f, "External signer refused to sign: {}", msg)
+            }
+            ExternalSignerError::MalformedResponse(msg) => {
+                write!(f, "External signer sent an unusable response: {}", msg)
+            }
+        }
+    }
+}
+
+pub trait ExternalSigner {
+    fn sign_transaction(
+        &self,
+        from: Address,
+        transaction_params: &TransactionParameters,
+    ) -> Result<SignedTransaction, ExternalSignerError>;
+}
+
+// Speaks the account_signTransaction method of Clef's external API
+pub struct ExternalSignerClef {
+    rpc: Box<dyn SignerRpc>,
+}
+
+impl ExternalSigner for ExternalSignerClef {
+    fn sign_transaction(
+        &self,
+        from: Address,
+        transaction_params: &TransactionParameters,
+    ) -> Result<SignedTransaction, ExternalSignerError> {
+        let args = ClefTransactionArgs {
+            from,
+            to: transaction_params.to,
+            gas: transaction_params.gas,
+            gas_price: transaction_params.gas_price,
+            value: transaction_params.value,
+            input: transaction_params.data.clone(),
+            nonce: transaction_params.nonce,
+            chain_id: transaction_params.chain_id.map(U256::from),
+        };
+        let args = serde_json::to_value(args).expect("Transaction args failed to serialize");
+        let result = self.rpc.call(SIGN_TRANSACTION_METHOD, vec![args])?;
+        let signed_transaction = Self::interpret_result(result)?;
+        Self::verify_signed_fields(&signed_transaction.raw_transaction, transaction_params)?;
+        Ok(signed_transaction)
+    }
+}
+
+impl ExternalSignerClef {
+    pub fn new(endpoint: &ExternalSignerEndpoint) -> Result<Self, ExternalSignerError> {
+        let rpc: Box<dyn SignerRpc> = match endpoint {
+            ExternalSignerEndpoint::Http(url) => Box::new(SignerRpcHttp::new(url)?),
+            ExternalSignerEndpoint::Ipc { path, read_timeout } => {
+                Box::new(SignerRpcIpc::new(path.clone(), *read_timeout))
+            }
+        };
+        Ok(Self { rpc })
+    }
+
+    fn interpret_result(result: Value) -> Result<SignedTransaction, ExternalSignerError> {
+        let result: ClefSignTransactionResult = serde_json::from_value(result)
+            .map_err(|e| ExternalSignerError::MalformedResponse(e.to_string()))?;
+        let transaction_hash = H256::from(result.raw.0.keccak256());
+        if let Some(reported_hash) = result.tx.hash {
+            if reported_hash != transaction_hash {
+                return Err(ExternalSignerError::MalformedResponse(format!(
+                    "reported transaction hash {:?}, but the raw transaction hashes to {:?}",
+                    reported_hash, transaction_hash
+                )));
+            }
+        }
+        Ok(SignedTransaction {
+            // The signer doesn't report it, and nothing downstream of signing needs it
+            message_hash: H256::zero(),
+            v: result.tx.v.as_u64(),
+            r: Self::u256_to_h256(result.tx.r),
+            s: Self::u256_to_h256(result.tx.s),
+            raw_transaction: result.raw,
+            transaction_hash,
+        })
+    }
+
+    // The signer may alter what it signs, so before the Node broadcasts the transaction, make sure
+    // the raw transaction pays what was asked for, to whom it was asked for.
+    fn verify_signed_fields(
+        raw_transaction: &Bytes,
+        transaction_params: &TransactionParameters,
+    ) -> Result<(), ExternalSignerError> {
+        let signed = SignedLegacyFields::decode(&raw_transaction.0).map_err(|e| {
+            ExternalSignerError::MalformedResponse(format!(
+                "raw transaction could not be decoded: {}",
+                e
+            ))
+        })?;
+        let mismatch = |field: &str, signed: String, requested: String| {
+            Err(ExternalSignerError::MalformedResponse(format!(
+                "signed transaction has {} {}, but {} was requested",
+                field, signed, requested
+            )))
+        };
+        if let Some(nonce) = transaction_params.nonce {
+            if signed.nonce != nonce {
+                return mismatch("nonce", signed.nonce.to_string(), nonce.to_string());
+            }
+        }
+        if signed.to != transaction_params.to {
+            return mismatch(
+                "recipient",
+                format!("{:?}", signed.to),
+                format!("{:?}", transaction_params.to),
+            );
+        }
+        if signed.value != transaction_params.value {
+            return mismatch(
+                "value",
+                signed.value.to_string(),
+                transaction_params.value.to_string(),
+            );
+        }
+        if signed.data != transaction_params.data.0 {
+            return mismatch(
+                "data",
+                format!("0x{}", signed.data.to_hex::<String>()),
+                format!("0x{}", transaction_params.data.0.to_hex::<String>()),
+            );
+        }
+        Ok(())
+    }
+
+    fn u256_to_h256(value: U256) -> H256 {
+        let mut bytes = [0u8; 32];
+        value.to_big_endian(&mut bytes);
+        H256(bytes)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ClefTransactionArgs {
+    from: Address,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    to: Option<Address>,
+    gas: U256,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    gas_price: Option<U256>,
+    value: U256,
+    input: Bytes,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    nonce: Option<U256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    chain_id: Option<U256>,
+}
+
+#[derive(Deserialize)]
+struct ClefSignTransactionResult {
+    raw: Bytes,
+    tx: ClefSignedTransaction,
+}
+
+#[derive(Deserialize)]
+struct ClefSignedTransaction {
+    v: U64,
+    r: U256,
+    s: U256,
+    hash: Option<H256>,
+}
+
+// The fields of a legacy RLP transaction, [nonce, gasPrice, gas, to, value, data, v, r, s],
+// that must match what was asked of the signer
+struct SignedLegacyFields {
+    nonce: U256,
+    to: Option<Address>,
+    value: U256,
+    data: Vec<u8>,
+}
+
+impl SignedLegacyFields {
+    fn decode(raw_transaction: &[u8]) -> Result<Self, String> {
+        let rlp = Rlp::new(raw_transaction);
+        match rlp.item_count() {
+            Ok(9) if rlp.is_list() => (),
+            _ => return Err("not a legacy transaction of nine fields".to_string()),
+        }
+        let field = |index: usize| {
+            rlp.at(index)
+                .and_then(|item| item.data())
+                .map_err(|e| format!("field {}: {}", index, e))
+        };
+        let u256_field = |index: usize| {
+            let bytes = field(index)?;
+            if bytes.len() > 32 {
+                return Err(format!("field {} is longer than 32 bytes", index));
+            }
+            Ok(U256::from_big_endian(bytes))
+        };
+        let to = match field(3)? {
+            [] => None,
+            bytes if bytes.len() == 20 => Some(Address::from_slice(bytes)),
+            bytes => return Err(format!("recipient is {} bytes long", bytes.len())),
+        };
+        Ok(Self {
+            nonce: u256_field(0)?,
+            to,
+            value: u256_field(4)?,
+            data: field(5)?.to_vec(),
+        })
+    }
+}
+
+trait SignerRpc {
+    fn call(&self, method: &str, params: Vec<Value>) -> Result<Value, ExternalSignerError>;
+}
+
+struct SignerRpcHttp {
+    // This must not be dropped for requests to be completed
+    _event_loop_handle: EventLoopHandle,
+    transport: Http,
+}
+
+impl SignerRpc for SignerRpcHttp {
+    fn call(&self, method: &str, params: Vec<Value>) -> Result<Value, ExternalSignerError> {
+        self.transport
+            .execute(method, params)
+            .wait()
+            .map_err(|e| match e {
+                Web3Error::Rpc(rpc_error) => ExternalSignerError::Refused(rpc_error.message),
+                e => ExternalSignerError::Unreachable(e.to_string()),
+            })
+    }
+}
+
+impl SignerRpcHttp {
+    fn new(url: &str) -> Result<Self, ExternalSignerError> {
+        let (event_loop_handle, transport) =
+            Http::new(url).map_err(|e| ExternalSignerError::Unreachable(e.to_string()))?;
+        Ok(Self {
+            _event_loop_handle: event_loop_handle,
+            transport,
+        })
+    }
+}
+
+struct SignerRpcIpc {
+    path: PathBuf,
+    read_timeout: Duration,
+    next_id: Cell<u64>,
+}
+
+impl SignerRpc for SignerRpcIpc {
+    fn call(&self, method: &str, params: Vec<Value>) -> Result<Value, ExternalSignerError> {
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+        let request = json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params});
+        let response = self.exchange(&request)?;
+        match (response.get("error"), response.get("result")) {
+            (Some(error), _) => Err(ExternalSignerError::Refused(
+                error
+                    .get("message")
+                    .and_then(|message| message.as_str())
+                    .map(|message| message.to_string())
+                    .unwrap_or_else(|| error.to_string()),
+            )),
+            (None, Some(result)) => Ok(result.clone()),
+            (None, None) => Err(ExternalSignerError::MalformedResponse(format!(
+                "neither result nor error in {}",
+                response
+            ))),
+        }
+    }
+}
+
+impl SignerRpcIpc {
+    fn new(path: PathBuf, read_timeout: Duration) -> Self {
+        Self {
+            path,
+            read_timeout,
+            next_id: Cell::new(1),
+        }
+    }
+
+    #[cfg(unix)]
+    fn exchange(&self, request: &Value) -> Result<Value, ExternalSignerError> {
+        use std::io::Write;
+        use std::os::unix::net::UnixStream;
+        let unreachable = |e: std::io::Error| {
+            ExternalSignerError::Unreachable(format!("{}: {}", self.path.display(), e))
+        };
+        let mut stream = UnixStream::connect(&self.path).map_err(unreachable)?;
+        stream
+            .write_all(request.to_string().as_bytes())
+            .map_err(unreachable)?;
+        stream
+            .set_read_timeout(Some(self.read_timeout))
+            .map_err(unreachable)?;
+        match serde_json::Deserializer::from_reader(&stream)
+            .into_iter::<Value>()
+            .next()
+        {
+            Some(Ok(response)) => Ok(response),
+            Some(Err(e)) if e.is_io() => Err(ExternalSignerError::Unreachable(format!(
+                "{}: no response within {}ms: {}",
+                self.path.display(),
+                self.read_timeout.as_millis(),
+                e
+            ))),
+            Some(Err(e)) => Err(ExternalSignerError::MalformedResponse(e.to_string())),
+            None => Err(ExternalSignerError::Unreachable(format!(
+                "{}: connection closed without a response",
+                self.path.display()
+            ))),
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn exchange(&self, _request: &Value) -> Result<Value, ExternalSignerError> {
+        Err(ExternalSignerError::Unreachable(
+            "ipc:// signer endpoints are supported only on Unix-like systems".to_string(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::mock_external_signer::MockExternalSigner;
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+    use masq_lib::utils::find_free_port;
+    use rustc_hex::FromHex;
+
+    const RAW_TRANSACTION: &str = "0xf86b018504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83";
+
+    fn make_transaction_params() -> TransactionParameters {
+        TransactionParameters {
+            nonce: Some(U256::from(1)),
+            to: Some(Address::from([0x35; 20])),
+            gas: U256::from(21_000),
+            gas_price: Some(U256::from(20_000_000_000u64)),
+            value: U256::from(1_000_000_000_000_000_000u64),
+            data: Bytes(vec![]),
+            chain_id: Some(1),
+        }
+    }
+
+    fn make_raw_transaction() -> Bytes {
+        serde_json::from_value(json!(RAW_TRANSACTION)).unwrap()
+    }
+
+    fn make_sign_result(hash_opt: Option<H256>) -> Value {
+        let mut tx = json!({
+            "v": "0x25",
+            "r": "0x28ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276",
+            "s": "0x67cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83"
+        });
+        if let Some(hash) = hash_opt {
+            tx["hash"] = json!(hash);
+        }
+        json!({"raw": RAW_TRANSACTION, "tx": tx})
+    }
+
+    fn expected_signed_transaction() -> SignedTransaction {
+        let raw_transaction = make_raw_transaction();
+        SignedTransaction {
+            message_hash: H256::zero(),
+            v: 0x25,
+            r: H256::from_slice(
+                &"28ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276"
+                    .from_hex::<Vec<u8>>()
+                    .unwrap(),
+            ),
+            s: H256::from_slice(
+                &"67cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83"
+                    .from_hex::<Vec<u8>>()
+                    .unwrap(),
+            ),
+            transaction_hash: H256::from(raw_transaction.0.keccak256()),
+            raw_transaction,
+        }
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(SIGN_TRANSACTION_METHOD, "account_signTransaction");
+    }
+
+    #[test]
+    fn endpoint_can_be_parsed_and_displayed() {
+        let inputs = vec![
+            "http://localhost:8550",
+            "https://signer.example.com",
+            "ipc:///home/booga/.clef/clef.ipc",
+        ];
+
+        let endpoints: Vec<ExternalSignerEndpoint> = inputs
+            .iter()
+            .map(|input| ExternalSignerEndpoint::from_str(input).unwrap())
+            .collect();
+
+        assert_eq!(
+            endpoints,
+            vec![
+                ExternalSignerEndpoint::Http("http://localhost:8550".to_string()),
+                ExternalSignerEndpoint::Http("https://signer.example.com".to_string()),
+                ExternalSignerEndpoint::Ipc {
+                    path: PathBuf::from("/home/booga/.clef/clef.ipc"),
+                    read_timeout: Duration::from_secs(DEFAULT_IPC_READ_TIMEOUT_SECS),
+                },
+            ]
+        );
+        let displayed: Vec<String> = endpoints.iter().map(|e| e.to_string()).collect();
+        assert_eq!(displayed, inputs);
+    }
+
+    #[test]
+    fn endpoint_rejects_unknown_schemes() {
+        let inputs = vec!["ws://localhost:8550", "/home/booga/clef.ipc", "ipc://"];
+
+        let results: Vec<Result<ExternalSignerEndpoint, String>> = inputs
+            .iter()
+            .map(|input| ExternalSignerEndpoint::from_str(input))
+            .collect();
+
+        assert_eq!(
+            results,
+            inputs
+                .iter()
+                .map(|input| Err(format!(
+                    "'{}' is neither an http(s):// URL nor an ipc:// socket path",
+                    input
+                )))
+                .collect::<Vec<Result<ExternalSignerEndpoint, String>>>()
+        );
+    }
+
+    #[test]
+    fn sign_transaction_works_over_http() {
+        let signer = MockExternalSigner::builder()
+            .response(make_sign_result(Some(
+                expected_signed_transaction().transaction_hash,
+            )))
+            .start_http();
+        let subject =
+            ExternalSignerClef::new(&ExternalSignerEndpoint::from_str(&signer.url()).unwrap())
+                .unwrap();
+
+        let result =
+            subject.sign_transaction(Address::from_low_u64_be(0x1234), &make_transaction_params());
+
+        assert_eq!(result, Ok(expected_signed_transaction()));
+        let requests = signer.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0]["method"], json!("account_signTransaction"));
+        assert_eq!(
+            requests[0]["params"],
+            json!([{
+                "from": "0x0000000000000000000000000000000000001234",
+                "to": "0x3535353535353535353535353535353535353535",
+                "gas": "0x5208",
+                "gasPrice": "0x4a817c800",
+                "value": "0xde0b6b3a7640000",
+                "input": "0x",
+                "nonce": "0x1",
+                "chainId": "0x1"
+            }])
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn sign_transaction_works_over_ipc() {
+        let home_dir = ensure_node_home_directory_exists("external_signer", "ipc_signing");
+        let socket_path = home_dir.join("clef.ipc");
+        let signer = MockExternalSigner::builder()
+            .response(make_sign_result(None))
+            .start_ipc(&socket_path);
+        let subject =
+            ExternalSignerClef::new(&ExternalSignerEndpoint::from_str(&signer.url()).unwrap())
+                .unwrap();
+        let mut transaction_params = make_transaction_params();
+        transaction_params.chain_id = None;
+
+        let result =
+            subject.sign_transaction(Address::from_low_u64_be(0x1234), &transaction_params);
+
+        assert_eq!(result, Ok(expected_signed_transaction()));
+        let requests = signer.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0]["jsonrpc"], json!("2.0"));
+        assert_eq!(requests[0]["id"], json!(1));
+        assert_eq!(requests[0]["method"], json!("account_signTransaction"));
+        assert_eq!(
+            requests[0]["params"],
+            json!([{
+                "from": "0x0000000000000000000000000000000000001234",
+                "to": "0x3535353535353535353535353535353535353535",
+                "gas": "0x5208",
+                "gasPrice": "0x4a817c800",
+                "value": "0xde0b6b3a7640000",
+                "input": "0x",
+                "nonce": "0x1"
+            }])
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn sign_transaction_reports_refusal_over_ipc() {
+        let home_dir = ensure_node_home_directory_exists("external_signer", "ipc_refusal");
+        let signer = MockExternalSigner::builder()
+            .error(-32000, "Request denied")
+            .start_ipc(&home_dir.join("clef.ipc"));
+        let subject =
+            ExternalSignerClef::new(&ExternalSignerEndpoint::from_str(&signer.url()).unwrap())
+                .unwrap();
+
+        let result =
+            subject.sign_transaction(Address::from_low_u64_be(0x1234), &make_transaction_params());
+
+        assert_eq!(
+            result,
+            Err(ExternalSignerError::Refused("Request denied".to_string()))
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn sign_transaction_reports_missing_ipc_socket() {
+        let home_dir = ensure_node_home_directory_exists("external_signer", "ipc_missing");
+        let socket_path = home_dir.join("clef.ipc");
+        let subject = ExternalSignerClef::new(&ExternalSignerEndpoint::Ipc {
+            path: socket_path.clone(),
+            read_timeout: Duration::from_secs(1),
+        })
+        .unwrap();
+
+        let result =
+            subject.sign_transaction(Address::from_low_u64_be(0x1234), &make_transaction_params());
+
+        match result {
+            Err(ExternalSignerError::Unreachable(msg)) => assert_eq!(
+                msg.starts_with(&format!("{}: ", socket_path.display())),
+                true,
+                "{}",
+                msg
+            ),
+            x => panic!("Expected Unreachable, got {:?}", x),
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn sign_transaction_gives_up_on_silent_ipc_signer_after_read_timeout() {
+        let home_dir = ensure_node_home_directory_exists("external_signer", "ipc_timeout");
+        let socket_path = home_dir.join("clef.ipc");
+        let _ = std::fs::remove_file(&socket_path);
+        // Accepts connections into its backlog but never answers
+        let _listener = std::os::unix::net::UnixListener::bind(&socket_path).unwrap();
+        let subject = ExternalSignerClef::new(
+            &ExternalSignerEndpoint::from_str(&format!("ipc://{}", socket_path.display()))
+                .unwrap()
+                .with_read_timeout(Duration::from_millis(100))
+                .unwrap(),
+        )
+        .unwrap();
+        let before = std::time::Instant::now();
+
+        let result =
+            subject.sign_transaction(Address::from_low_u64_be(0x1234), &make_transaction_params());
+
+        let elapsed = before.elapsed();
+        match result {
+            Err(ExternalSignerError::Unreachable(msg)) => assert_eq!(
+                msg.starts_with(&format!(
+                    "{}: no response within 100ms: ",
+                    socket_path.display()
+                )),
+                true,
+                "{}",
+                msg
+            ),
+            x => panic!("Expected Unreachable, got {:?}", x),
+        }
+        assert!(elapsed >= Duration::from_millis(100), "{:?}", elapsed);
+        assert!(elapsed < Duration::from_secs(5), "{:?}", elapsed);
+    }
+
+    #[test]
+    fn read_timeout_applies_only_to_ipc_endpoints() {
+        let ipc = ExternalSignerEndpoint::from_str("ipc:///home/booga/.clef/clef.ipc").unwrap();
+        let http = ExternalSignerEndpoint::from_str("http://localhost:8550").unwrap();
+
+        let ipc_result = ipc.with_read_timeout(Duration::from_secs(45));
+        let http_result = http.with_read_timeout(Duration::from_secs(45));
+
+        assert_eq!(
+            ipc_result,
+            Ok(ExternalSignerEndpoint::Ipc {
+                path: PathBuf::from("/home/booga/.clef/clef.ipc"),
+                read_timeout: Duration::from_secs(45),
+            })
+        );
+        assert_eq!(
+            http_result,
+            Err(
+                "A read timeout applies only to an ipc:// external signer, not 'http://localhost:8550'"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn sign_transaction_rejects_signed_fields_that_differ_from_the_request() {
+        let mut wrong_nonce = make_transaction_params();
+        wrong_nonce.nonce = Some(U256::from(2));
+        let mut wrong_recipient = make_transaction_params();
+        wrong_recipient.to = Some(Address::from_low_u64_be(0x1234));
+        let mut contract_creation = make_transaction_params();
+        contract_creation.to = None;
+        let mut wrong_value = make_transaction_params();
+        wrong_value.value = U256::from(1);
+        let mut wrong_data = make_transaction_params();
+        wrong_data.data = Bytes(vec![0xab, 0xcd]);
+        let cases = vec![
+            (
+                wrong_nonce,
+                "signed transaction has nonce 1, but 2 was requested".to_string(),
+            ),
+            (
+                wrong_recipient,
+                format!(
+                    "signed transaction has recipient {:?}, but {:?} was requested",
+                    Some(Address::from([0x35; 20])),
+                    Some(Address::from_low_u64_be(0x1234))
+                ),
+            ),
+            (
+                contract_creation,
+                format!(
+                    "signed transaction has recipient {:?}, but None was requested",
+                    Some(Address::from([0x35; 20]))
+                ),
+            ),
+            (
+                wrong_value,
+                "signed transaction has value 1000000000000000000, but 1 was requested".to_string(),
+            ),
+            (
+                wrong_data,
+                "signed transaction has data 0x, but 0xabcd was requested".to_string(),
+            ),
+        ];
+        let signer = cases
+            .iter()
+            .fold(MockExternalSigner::builder(), |builder, _| {
+                builder.response(make_sign_result(None))
+            })
+            .start_http();
+        let subject =
+            ExternalSignerClef::new(&ExternalSignerEndpoint::from_str(&signer.url()).unwrap())
+                .unwrap();
+
+        cases.into_iter().for_each(|(transaction_params, message)| {
+            let result =
+                subject.sign_transaction(Address::from_low_u64_be(0x1234), &transaction_params);
+
+            assert_eq!(result, Err(ExternalSignerError::MalformedResponse(message)));
+        });
+    }
+
+    #[test]
+    fn sign_transaction_rejects_raw_transaction_that_cannot_be_decoded() {
+        let mut sign_result = make_sign_result(None);
+        sign_result["raw"] = json!("0xc0");
+        let signer = MockExternalSigner::builder()
+            .response(sign_result)
+            .start_http();
+        let subject =
+            ExternalSignerClef::new(&ExternalSignerEndpoint::from_str(&signer.url()).unwrap())
+                .unwrap();
+
+        let result =
+            subject.sign_transaction(Address::from_low_u64_be(0x1234), &make_transaction_params());
+
+        assert_eq!(
+            result,
+            Err(ExternalSignerError::MalformedResponse(
+                "raw transaction could not be decoded: not a legacy transaction of nine fields"
+                    .to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn sign_transaction_reports_refusal_over_http() {
+        let signer = MockExternalSigner::builder()
+            .error(-32000, "Request denied")
+            .start_http();
+        let subject =
+            ExternalSignerClef::new(&ExternalSignerEndpoint::from_str(&signer.url()).unwrap())
+                .unwrap();
+
+        let result =
+            subject.sign_transaction(Address::from_low_u64_be(0x1234), &make_transaction_params());
+
+        assert_eq!(
+            result,
+            Err(ExternalSignerError::Refused("Request denied".to_string()))
+        );
+    }
+
+    #[test]
+    fn sign_transaction_reports_unreachable_http_signer() {
+        let port = find_free_port();
+        let subject = ExternalSignerClef::new(&ExternalSignerEndpoint::Http(format!(
+            "http://127.0.0.1:{}",
+            port
+        )))
+        .unwrap();
+
+        let result =
+            subject.sign_transaction(Address::from_low_u64_be(0x1234), &make_transaction_params());
+
+        match result {
+            Err(ExternalSignerError::Unreachable(_)) => (),
+            x => panic!("Expected Unreachable, got {:?}", x),
+        }
+    }
+
+    #[test]
+    fn sign_transaction_rejects_hash_that_does_not_match_raw_transaction() {
+        let signer = MockExternalSigner::builder()
+            .response(make_sign_result(Some(H256::from_low_u64_be(1))))
+            .start_http();
+        let subject =
+            ExternalSignerClef::new(&ExternalSignerEndpoint::from_str(&signer.url()).unwrap())
+                .unwrap();
+
+        let result =
+            subject.sign_transaction(Address::from_low_u64_be(0x1234), &make_transaction_params());
+
+        assert_eq!(
+            result,
+            Err(ExternalSignerError::MalformedResponse(format!(
+                "reported transaction hash {:?}, but the raw transaction hashes to {:?}",
+                H256::from_low_u64_be(1),
+                expected_signed_transaction().transaction_hash
+            )))
+        );
+    }
+
+    #[test]
+    fn sign_transaction_rejects_result_without_raw_transaction() {
+        let signer = MockExternalSigner::builder()
+            .response(json!({"tx": {"v": "0x25", "r": "0x1", "s": "0x1"}}))
+            .start_http();
+        let subject =
+            ExternalSignerClef::new(&ExternalSignerEndpoint::from_str(&signer.url()).unwrap())
+                .unwrap();
+
+        let result =
+            subject.sign_transaction(Address::from_low_u64_be(0x1234), &make_transaction_params());
+
+        assert_eq!(
+            result,
+            Err(ExternalSignerError::MalformedResponse(
+                "missing field `raw`".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn external_signer_error_display_works() {
+        let inputs = vec![
+            ExternalSignerError::Unreachable("booga".to_string()),
+            ExternalSignerError::Refused("booga".to_string()),
+            ExternalSignerError::MalformedResponse("booga".to_string()),
+        ];
+
+        let actual: Vec<String> = inputs.into_iter().map(|e| e.to_string()).collect();
+
+        assert_eq!(
+            actual,
+            vec![
+                "Could not reach the external signer: booga",
+                "External signer refused to sign: booga",
+                "External signer sent an unusable response: booga",
+            ]
+        );
+    }
+}
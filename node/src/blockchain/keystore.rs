@@ -0,0 +1,298 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use crate::blockchain::bip32::Bip32EncryptionKeyProvider;
+use ethsign::keyfile::{Bytes, Crypto, KeyFile};
+use ethsign::{Error as EthsignError, Protected};
+use std::fmt::{Display, Formatter};
+use uuid::Uuid;
+use web3::types::Address;
+
+pub const KEYSTORE_VERSION: u64 = 3;
+// The value geth uses for its standard (non-light) key derivation
+pub const KEYSTORE_KDF_ITERATIONS: u32 = 262_144;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum KeystoreError {
+    MalformedKeystore(String),
+    UnsupportedVersion(u64),
+    WrongPassphrase,
+    DecryptionFailure(String),
+    InvalidPrivateKey(String),
+    AddressMismatch { declared: Address, actual: Address },
+    EncryptionFailure(String),
+}
+
+impl Display for KeystoreError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeystoreError::MalformedKeystore(msg) => write!(f, "Malformed keystore: {}", msg),
+            KeystoreError::UnsupportedVersion(version) => write!(
+                f,
+                "Unsupported keystore version {}; only version {} is supported",
+                version, KEYSTORE_VERSION
+            ),
+            KeystoreError::WrongPassphrase => {
+                write!(f, "Keystore passphrase is incorrect")
+            }
+            KeystoreError::DecryptionFailure(msg) => {
+                write!(f, "Failed to decrypt keystore: {}", msg)
+            }
+            KeystoreError::InvalidPrivateKey(msg) => {
+                write!(f, "Keystore holds an unusable private key: {}", msg)
+            }
+            KeystoreError::AddressMismatch { declared, actual } => write!(
+                f,
+                "Keystore declares address {:?}, but its key belongs to {:?}",
+                declared, actual
+            ),
+            KeystoreError::EncryptionFailure(msg) => {
+                write!(f, "Failed to encrypt keystore: {}", msg)
+            }
+        }
+    }
+}
+
+pub struct Keystore {}
+
+impl Keystore {
+    pub fn decrypt(
+        keystore_json: &str,
+        passphrase: &str,
+    ) -> Result<Bip32EncryptionKeyProvider, KeystoreError> {
+        let key_file = Self::parse(keystore_json)?;
+        if key_file.version != KEYSTORE_VERSION {
+            return Err(KeystoreError::UnsupportedVersion(key_file.version));
+        }
+        let secret = match key_file.crypto.decrypt(&Protected::new(passphrase)) {
+            Ok(secret) => secret,
+            Err(EthsignError::InvalidPassword) => return Err(KeystoreError::WrongPassphrase),
+            Err(e) => return Err(KeystoreError::DecryptionFailure(e.to_string())),
+        };
+        let key_pair = Bip32EncryptionKeyProvider::from_raw_secret(secret.as_slice())
+            .map_err(KeystoreError::InvalidPrivateKey)?;
+        match key_file.address {
+            Some(Bytes(declared)) if declared.len() != 20 => Err(KeystoreError::MalformedKeystore(
+                format!("address must be 20 bytes long, not {}", declared.len()),
+            )),
+            Some(Bytes(declared)) if declared.as_slice() != key_pair.address().as_bytes() => {
+                Err(KeystoreError::AddressMismatch {
+                    declared: Address::from_slice(&declared),
+                    actual: key_pair.address(),
+                })
+            }
+            _ => Ok(key_pair),
+        }
+    }
+
+    pub fn encrypt(
+        key_pair: &Bip32EncryptionKeyProvider,
+        passphrase: &str,
+    ) -> Result<String, KeystoreError> {
+        Self::encrypt_with_iterations(key_pair, passphrase, KEYSTORE_KDF_ITERATIONS)
+    }
+
+    pub(crate) fn encrypt_with_iterations(
+        key_pair: &Bip32EncryptionKeyProvider,
+        passphrase: &str,
+        iterations: u32,
+    ) -> Result<String, KeystoreError> {
+        let crypto = Crypto::encrypt(
+            key_pair.clone_secret().as_slice(),
+            &Protected::new(passphrase),
+            iterations,
+        )
+        .map_err(|e| KeystoreError::EncryptionFailure(e.to_string()))?;
+        let key_file = KeyFile {
+            id: Uuid::new_v4().to_string(),
+            version: KEYSTORE_VERSION,
+            crypto,
+            address: Some(Bytes(key_pair.address().as_bytes().to_vec())),
+        };
+        serde_json::to_string(&key_file)
+            .map_err(|e| KeystoreError::EncryptionFailure(e.to_string()))
+    }
+
+    fn parse(keystore_json: &str) -> Result<KeyFile, KeystoreError> {
+        let mut value: serde_json::Value = serde_json::from_str(keystore_json)
+            .map_err(|e| KeystoreError::MalformedKeystore(e.to_string()))?;
+        // Some wallets (older geth versions, MyEtherWallet) capitalize the "crypto" key
+        if let Some(object) = value.as_object_mut() {
+            if !object.contains_key("crypto") {
+                if let Some(crypto) = object.remove("Crypto") {
+                    object.insert("crypto".to_string(), crypto);
+                }
+            }
+        }
+        serde_json::from_value(value).map_err(|e| KeystoreError::MalformedKeystore(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustc_hex::FromHex;
+
+    // Test vector from the Web3 Secret Storage Definition (PBKDF2-SHA-256); passphrase "testpassword"
+    const PBKDF2_KEYSTORE: &str = r#"{
+        "crypto": {
+            "cipher": "aes-128-ctr",
+            "cipherparams": {"iv": "6087dab2f9fdbbfaddc31a909735c1e6"},
+            "ciphertext": "5318b4d5bcd28de64ee5559e671353e16f075ecae9f99c7a79a38af5f869aa46",
+            "kdf": "pbkdf2",
+            "kdfparams": {
+                "c": 262144,
+                "dklen": 32,
+                "prf": "hmac-sha256",
+                "salt": "ae3cd4e7013836a3df6bd7241b12db061dbe2c6785853cce422d148a624ce0bd"
+            },
+            "mac": "517ead924a9d0dc3124507e3393d175ce3ff7c1e96529c6c555ce9e51205e9b2"
+        },
+        "id": "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+        "version": 3
+    }"#;
+    const PBKDF2_SECRET: &str = "7a28b5ba57c53603b0b07b56bba752f7784bf506fa95edc395f5cf6c7514fe9d";
+
+    // Same key and passphrase, with geth's "light" scrypt parameters (N=4096, r=8, p=1)
+    const SCRYPT_KEYSTORE: &str = r#"{
+        "Crypto": {
+            "cipher": "aes-128-ctr",
+            "cipherparams": {"iv": "83dbcc02d8ccb40e466191a123791e0e"},
+            "ciphertext": "3b4309355ad643f2b15cfb6a83a7f6f328e7a6459a56ab8c6e25a89c8f43eb80",
+            "kdf": "scrypt",
+            "kdfparams": {
+                "dklen": 32,
+                "n": 4096,
+                "r": 8,
+                "p": 1,
+                "salt": "ab0c7876052600dd703518d6fc3fe8984592145b591fc8fb5c6d43190334ba19"
+            },
+            "mac": "994d83f6bfb7e6e3aa95980f72b6ad87db9d352789d0f2e433cf777425db3a42"
+        },
+        "id": "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+        "version": 3
+    }"#;
+
+    fn make_key_pair() -> Bip32EncryptionKeyProvider {
+        let secret: Vec<u8> = PBKDF2_SECRET.from_hex().unwrap();
+        Bip32EncryptionKeyProvider::from_raw_secret(&secret).unwrap()
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(KEYSTORE_VERSION, 3);
+        assert_eq!(KEYSTORE_KDF_ITERATIONS, 262_144);
+    }
+
+    #[test]
+    fn decrypt_handles_pbkdf2_keystore() {
+        let result = Keystore::decrypt(PBKDF2_KEYSTORE, "testpassword").unwrap();
+
+        assert_eq!(result.address(), make_key_pair().address());
+    }
+
+    #[test]
+    fn decrypt_handles_scrypt_keystore_with_capitalized_crypto_key() {
+        let result = Keystore::decrypt(SCRYPT_KEYSTORE, "testpassword").unwrap();
+
+        assert_eq!(result.address(), make_key_pair().address());
+    }
+
+    #[test]
+    fn decrypt_complains_about_wrong_passphrase() {
+        let result = Keystore::decrypt(PBKDF2_KEYSTORE, "wrongpassword");
+
+        assert_eq!(result.err(), Some(KeystoreError::WrongPassphrase));
+    }
+
+    #[test]
+    fn decrypt_complains_about_unsupported_version() {
+        let keystore = PBKDF2_KEYSTORE.replace("\"version\": 3", "\"version\": 1");
+
+        let result = Keystore::decrypt(&keystore, "testpassword");
+
+        assert_eq!(result.err(), Some(KeystoreError::UnsupportedVersion(1)));
+    }
+
+    #[test]
+    fn decrypt_complains_about_malformed_keystore() {
+        let result = Keystore::decrypt("{\"version\": 3}", "testpassword");
+
+        assert_eq!(
+            result.err(),
+            Some(KeystoreError::MalformedKeystore(
+                "missing field `id`".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn decrypt_complains_about_declared_address_belonging_to_another_key() {
+        let keystore = PBKDF2_KEYSTORE.replace(
+            "\"version\": 3",
+            "\"version\": 3, \"address\": \"0000000000000000000000000000000000000001\"",
+        );
+
+        let result = Keystore::decrypt(&keystore, "testpassword");
+
+        assert_eq!(
+            result.err(),
+            Some(KeystoreError::AddressMismatch {
+                declared: Address::from_low_u64_be(1),
+                actual: make_key_pair().address(),
+            })
+        );
+    }
+
+    #[test]
+    fn encrypted_keystore_can_be_decrypted_again() {
+        let key_pair = make_key_pair();
+
+        let keystore =
+            Keystore::encrypt_with_iterations(&key_pair, "export passphrase", 1024).unwrap();
+
+        let key_file: KeyFile = serde_json::from_str(&keystore).unwrap();
+        assert_eq!(key_file.version, 3);
+        assert!(Uuid::parse_str(&key_file.id).is_ok());
+        assert_eq!(
+            key_file.address,
+            Some(Bytes(key_pair.address().as_bytes().to_vec()))
+        );
+        let result = Keystore::decrypt(&keystore, "export passphrase").unwrap();
+        assert_eq!(result.clone_secret(), key_pair.clone_secret());
+        assert_eq!(
+            Keystore::decrypt(&keystore, "testpassword").err(),
+            Some(KeystoreError::WrongPassphrase)
+        );
+    }
+
+    #[test]
+    fn keystore_error_display_works() {
+        let address = Address::from_low_u64_be(1);
+        let inputs = vec![
+            KeystoreError::MalformedKeystore("booga".to_string()),
+            KeystoreError::UnsupportedVersion(1),
+            KeystoreError::WrongPassphrase,
+            KeystoreError::DecryptionFailure("booga".to_string()),
+            KeystoreError::InvalidPrivateKey("booga".to_string()),
+            KeystoreError::AddressMismatch {
+                declared: address,
+                actual: Address::from_low_u64_be(2),
+            },
+            KeystoreError::EncryptionFailure("booga".to_string()),
+        ];
+
+        let actual: Vec<String> = inputs.into_iter().map(|e| e.to_string()).collect();
+
+        assert_eq!(
+            actual,
+            vec![
+                "Malformed keystore: booga",
+                "Unsupported keystore version 1; only version 3 is supported",
+                "Keystore passphrase is incorrect",
+                "Failed to decrypt keystore: booga",
+                "Keystore holds an unusable private key: booga",
+                "Keystore declares address 0x0000000000000000000000000000000000000001, but its key belongs to 0x0000000000000000000000000000000000000002",
+                "Failed to encrypt keystore: booga",
+            ]
+        );
+    }
+}
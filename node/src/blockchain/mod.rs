@@ -5,6 +5,8 @@ pub mod bip39;
 pub mod blockchain_bridge;
 pub mod blockchain_interface;
 pub mod blockchain_interface_initializer;
+pub mod external_signer;
+pub mod keystore;
 pub mod payer;
 pub mod signature;
 
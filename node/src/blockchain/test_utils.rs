@@ -3,21 +3,26 @@
 #![cfg(test)]
 
 use crate::accountant::db_access_objects::payable_dao::PayableAccount;
+use crate::accountant::db_access_objects::pending_payable_dao::StuckTransaction;
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::BlockchainAgent;
 use crate::blockchain::blockchain_bridge::PendingPayableFingerprintSeeds;
 use crate::blockchain::blockchain_interface::blockchain_interface_web3::REQUESTS_IN_PARALLEL;
 use crate::blockchain::blockchain_interface::data_structures::errors::{
-    BlockchainAgentBuildError, BlockchainError, PayableTransactionError, ResultForReceipt,
+    BlockchainAgentBuildError, BlockchainError, FeeBumpError, FundsTransferError,
+    PayableTransactionError, ResultForReceipt,
 };
 use crate::blockchain::blockchain_interface::data_structures::{
-    ProcessedPayableFallible, RetrievedBlockchainTransactions,
+    FeeBumpedTransaction, FundsTransferOrder, FundsTransferOutcome, ProcessedPayableFallible,
+    RetrievedBlockchainTransactions,
 };
 use crate::blockchain::blockchain_interface::lower_level_interface::LowBlockchainInt;
 use crate::blockchain::blockchain_interface::test_utils::LowBlockchainIntMock;
 use crate::blockchain::blockchain_interface::BlockchainInterface;
+use crate::blockchain::external_signer::{ExternalSigner, ExternalSignerError};
 use crate::db_config::persistent_configuration::PersistentConfiguration;
 use crate::set_arbitrary_id_stamp_in_mock_impl;
 use crate::sub_lib::wallet::Wallet;
+use crate::test_utils::make_wallet;
 use crate::test_utils::unshared_test_utils::arbitrary_id_stamp::ArbitraryIdStamp;
 use actix::Recipient;
 use bip39::{Language, Mnemonic, Seed};
@@ -31,7 +36,7 @@ use std::collections::VecDeque;
 use std::fmt::Debug;
 use std::sync::{Arc, Mutex};
 use web3::transports::{EventLoopHandle, Http};
-use web3::types::{Address, BlockNumber, U256};
+use web3::types::{Address, BlockNumber, SignedTransaction, TransactionParameters, U256};
 use web3::{BatchTransport, Error as Web3Error};
 use web3::{RequestId, Transport};
 
@@ -77,6 +82,10 @@ pub struct BlockchainInterfaceMock {
         RefCell<Vec<Result<Vec<ProcessedPayableFallible>, PayableTransactionError>>>,
     get_transaction_receipt_params: Arc<Mutex<Vec<H256>>>,
     get_transaction_receipt_results: RefCell<Vec<ResultForReceipt>>,
+    bump_transaction_fee_params: Arc<Mutex<Vec<(Wallet, StuckTransaction, u64)>>>,
+    bump_transaction_fee_results: RefCell<Vec<Result<FeeBumpedTransaction, FeeBumpError>>>,
+    transfer_funds_params: Arc<Mutex<Vec<(ArbitraryIdStamp, FundsTransferOrder)>>>,
+    transfer_funds_results: RefCell<Vec<Result<FundsTransferOutcome, FundsTransferError>>>,
     lower_interface_result: Option<Box<LowBlockchainIntMock>>,
     arbitrary_id_stamp_opt: Option<ArbitraryIdStamp>,
 }
@@ -134,6 +143,32 @@ impl BlockchainInterface for BlockchainInterfaceMock {
         self.get_transaction_receipt_results.borrow_mut().remove(0)
     }
 
+    fn bump_transaction_fee(
+        &self,
+        consuming_wallet: &Wallet,
+        stuck_transaction: &StuckTransaction,
+        gas_price_increase_percent: u64,
+    ) -> Result<FeeBumpedTransaction, FeeBumpError> {
+        self.bump_transaction_fee_params.lock().unwrap().push((
+            consuming_wallet.clone(),
+            stuck_transaction.clone(),
+            gas_price_increase_percent,
+        ));
+        self.bump_transaction_fee_results.borrow_mut().remove(0)
+    }
+
+    fn transfer_funds(
+        &self,
+        agent: &dyn BlockchainAgent,
+        order: &FundsTransferOrder,
+    ) -> Result<FundsTransferOutcome, FundsTransferError> {
+        self.transfer_funds_params
+            .lock()
+            .unwrap()
+            .push((agent.arbitrary_id_stamp(), order.clone()));
+        self.transfer_funds_results.borrow_mut().remove(0)
+    }
+
     fn lower_interface(&self) -> &dyn LowBlockchainInt {
         self.lower_interface_result.as_ref().unwrap().as_ref()
     }
@@ -212,6 +247,38 @@ impl BlockchainInterfaceMock {
         self
     }
 
+    pub fn bump_transaction_fee_params(
+        mut self,
+        params: &Arc<Mutex<Vec<(Wallet, StuckTransaction, u64)>>>,
+    ) -> Self {
+        self.bump_transaction_fee_params = params.clone();
+        self
+    }
+
+    pub fn bump_transaction_fee_result(
+        self,
+        result: Result<FeeBumpedTransaction, FeeBumpError>,
+    ) -> Self {
+        self.bump_transaction_fee_results.borrow_mut().push(result);
+        self
+    }
+
+    pub fn transfer_funds_params(
+        mut self,
+        params: &Arc<Mutex<Vec<(ArbitraryIdStamp, FundsTransferOrder)>>>,
+    ) -> Self {
+        self.transfer_funds_params = params.clone();
+        self
+    }
+
+    pub fn transfer_funds_result(
+        self,
+        result: Result<FundsTransferOutcome, FundsTransferError>,
+    ) -> Self {
+        self.transfer_funds_results.borrow_mut().push(result);
+        self
+    }
+
     pub fn lower_interface_results(
         mut self,
         aggregated_results: Box<LowBlockchainIntMock>,
@@ -327,6 +394,16 @@ pub fn make_tx_hash(base: u32) -> H256 {
     H256::from_uint(&U256::from(base))
 }
 
+pub fn make_stuck_transaction(rowid: u64) -> StuckTransaction {
+    StuckTransaction {
+        rowid,
+        hash: make_tx_hash(rowid as u32),
+        nonce: rowid + 100,
+        gas_price_wei: 1_000_000_000,
+        payments: vec![(make_wallet(&format!("creditor{}", rowid)), 1_000_000)],
+    }
+}
+
 pub fn all_chains() -> [Chain; 4] {
     [
         Chain::EthMainnet,
@@ -335,3 +412,41 @@ pub fn all_chains() -> [Chain; 4] {
         Chain::Dev,
     ]
 }
+
+#[derive(Default)]
+pub struct ExternalSignerMock {
+    sign_transaction_params: Arc<Mutex<Vec<(Address, TransactionParameters)>>>,
+    sign_transaction_results: RefCell<Vec<Result<SignedTransaction, ExternalSignerError>>>,
+}
+
+impl ExternalSigner for ExternalSignerMock {
+    fn sign_transaction(
+        &self,
+        from: Address,
+        transaction_params: &TransactionParameters,
+    ) -> Result<SignedTransaction, ExternalSignerError> {
+        self.sign_transaction_params
+            .lock()
+            .unwrap()
+            .push((from, transaction_params.clone()));
+        self.sign_transaction_results.borrow_mut().remove(0)
+    }
+}
+
+impl ExternalSignerMock {
+    pub fn sign_transaction_params(
+        mut self,
+        params: &Arc<Mutex<Vec<(Address, TransactionParameters)>>>,
+    ) -> Self {
+        self.sign_transaction_params = params.clone();
+        self
+    }
+
+    pub fn sign_transaction_result(
+        self,
+        result: Result<SignedTransaction, ExternalSignerError>,
+    ) -> Self {
+        self.sign_transaction_results.borrow_mut().push(result);
+        self
+    }
+}
@@ -15,17 +15,21 @@ use crate::json_discriminator_factory::JsonDiscriminatorFactory;
 use crate::listener_handler::ListenerHandler;
 use crate::listener_handler::ListenerHandlerFactory;
 use crate::listener_handler::ListenerHandlerFactoryReal;
+use crate::masquerader::MasqueraderKind;
 use crate::neighborhood::DEFAULT_MIN_HOPS;
 use crate::node_configurator::node_configurator_standard::{
     NodeConfiguratorStandardPrivileged, NodeConfiguratorStandardUnprivileged,
 };
 use crate::node_configurator::{initialize_database, DirsWrapper, NodeConfigurator};
 use crate::privilege_drop::{IdWrapper, IdWrapperReal};
+use crate::proxy_server::route_isolation::DEFAULT_ROUTE_LIFETIME;
 use crate::server_initializer::LoggerInitializerWrapper;
 use crate::stream_handler_pool::StreamHandlerPoolSubs;
 use crate::sub_lib::accountant;
-use crate::sub_lib::accountant::{PaymentThresholds, ScanIntervals};
+use crate::sub_lib::accountant::{FeeBumping, PaymentThresholds, ScanIntervals, SpendingBudget};
+use crate::sub_lib::bandwidth_shaper::BandwidthShaper;
 use crate::sub_lib::blockchain_bridge::BlockchainBridgeConfig;
+use crate::sub_lib::clandestine_tls::ClandestineTls;
 use crate::sub_lib::cryptde::CryptDE;
 use crate::sub_lib::cryptde_null::CryptDENull;
 use crate::sub_lib::cryptde_real::CryptDEReal;
@@ -36,15 +40,17 @@ use crate::sub_lib::socket_server::ConfiguredByPrivilege;
 use crate::sub_lib::ui_gateway::UiGatewayConfig;
 use crate::sub_lib::utils::db_connection_launch_panic;
 use crate::sub_lib::wallet::Wallet;
+use crate::websocket_discriminator_factory::WebSocketDiscriminatorFactory;
 use futures::try_ready;
 use itertools::Itertools;
-use log::LevelFilter;
+use log::{Level, LevelFilter};
 use masq_lib::blockchains::chains::Chain;
 use masq_lib::command::StdStreams;
 use masq_lib::constants::DEFAULT_UI_PORT;
 use masq_lib::crash_point::CrashPoint;
 use masq_lib::logger::Logger;
 use masq_lib::multi_config::MultiConfig;
+use masq_lib::redaction::set_redacted_levels;
 use masq_lib::shared_schema::ConfiguratorError;
 use masq_lib::test_utils::utils::TEST_DEFAULT_CHAIN;
 use masq_lib::utils::AutomapProtocol;
@@ -55,6 +61,7 @@ use std::fmt::{Debug, Display, Error, Formatter};
 use std::net::{Ipv4Addr, SocketAddr};
 use std::path::PathBuf;
 use std::str::FromStr;
+use std::time::Duration;
 use std::vec::Vec;
 use tokio::prelude::stream::futures_unordered::FuturesUnordered;
 use tokio::prelude::Async;
@@ -333,12 +340,16 @@ impl Display for RealUser {
 pub struct BootstrapperConfig {
     // These fields can be set while privileged without penalty
     pub log_level: LevelFilter,
+    pub log_redaction_levels: Vec<Level>,
     pub dns_servers: Vec<SocketAddr>,
     pub scan_intervals_opt: Option<ScanIntervals>,
     pub suppress_initial_scans: bool,
     pub when_pending_too_long_sec: u64,
+    pub fee_bumping: FeeBumping,
     pub crash_point: CrashPoint,
     pub clandestine_discriminator_factories: Vec<Box<dyn DiscriminatorFactory>>,
+    pub clandestine_masquerader: MasqueraderKind,
+    pub clandestine_tls: bool,
     pub ui_gateway_config: UiGatewayConfig,
     pub blockchain_bridge_config: BlockchainBridgeConfig,
     pub port_configurations: HashMap<u16, PortConfiguration>,
@@ -349,6 +360,7 @@ pub struct BootstrapperConfig {
     pub mapping_protocol_opt: Option<AutomapProtocol>,
     pub real_user: RealUser,
     pub payment_thresholds_opt: Option<PaymentThresholds>,
+    pub route_lifetime: Duration,
 
     // These fields must be set without privilege: otherwise the database will be created as root
     pub db_password_opt: Option<String>,
@@ -356,6 +368,11 @@ pub struct BootstrapperConfig {
     pub consuming_wallet_opt: Option<Wallet>,
     pub earning_wallet: Wallet,
     pub neighborhood_config: NeighborhoodConfig,
+    pub bandwidth_shaper: BandwidthShaper,
+    pub spending_budget: SpendingBudget,
+
+    // This field is filled in by the Bootstrapper once the CryptDEs exist
+    pub clandestine_tls_opt: Option<ClandestineTls>,
 }
 
 impl Default for BootstrapperConfig {
@@ -369,16 +386,21 @@ impl BootstrapperConfig {
         BootstrapperConfig {
             // These fields can be set while privileged without penalty
             log_level: LevelFilter::Off,
+            log_redaction_levels: vec![],
             dns_servers: vec![],
             scan_intervals_opt: None,
             suppress_initial_scans: false,
             crash_point: CrashPoint::None,
             clandestine_discriminator_factories: vec![],
+            clandestine_masquerader: MasqueraderKind::Json,
+            clandestine_tls: false,
             ui_gateway_config: UiGatewayConfig {
                 ui_port: DEFAULT_UI_PORT,
+                remote_ui_opt: None,
             },
             blockchain_bridge_config: BlockchainBridgeConfig {
                 blockchain_service_url_opt: None,
+                external_signer_opt: None,
                 chain: TEST_DEFAULT_CHAIN,
                 gas_price: 1,
             },
@@ -390,6 +412,7 @@ impl BootstrapperConfig {
             mapping_protocol_opt: None,
             real_user: RealUser::new(None, None, None),
             payment_thresholds_opt: Default::default(),
+            route_lifetime: DEFAULT_ROUTE_LIFETIME,
 
             // These fields must be set without privilege: otherwise the database will be created as root
             db_password_opt: None,
@@ -401,6 +424,10 @@ impl BootstrapperConfig {
                 min_hops: DEFAULT_MIN_HOPS,
             },
             when_pending_too_long_sec: DEFAULT_PENDING_TOO_LONG_SEC,
+            fee_bumping: FeeBumping::default(),
+            bandwidth_shaper: BandwidthShaper::default(),
+            spending_budget: SpendingBudget::default(),
+            clandestine_tls_opt: None,
         }
     }
 
@@ -418,6 +445,9 @@ impl BootstrapperConfig {
         self.suppress_initial_scans = unprivileged.suppress_initial_scans;
         self.payment_thresholds_opt = unprivileged.payment_thresholds_opt;
         self.when_pending_too_long_sec = unprivileged.when_pending_too_long_sec;
+        self.fee_bumping = unprivileged.fee_bumping;
+        self.bandwidth_shaper = unprivileged.bandwidth_shaper;
+        self.spending_budget = unprivileged.spending_budget;
     }
 
     pub fn exit_service_rate(&self) -> u64 {
@@ -465,6 +495,7 @@ impl ConfiguredByPrivilege for Bootstrapper {
         multi_config: &MultiConfig,
     ) -> Result<(), ConfiguratorError> {
         self.config = NodeConfiguratorStandardPrivileged::new().configure(multi_config)?;
+        set_redacted_levels(&self.config.log_redaction_levels);
         self.logger_initializer.init(
             self.config.data_directory.clone(),
             &self.config.real_user,
@@ -519,6 +550,9 @@ impl ConfiguredByPrivilege for Bootstrapper {
                 if node_addr.ip_addr() == Ipv4Addr::new(0, 0, 0, 0) => {} // node_addr still coming
             _ => Bootstrapper::report_local_descriptor(cryptdes.main, &self.config.node_descriptor), // here or not coming
         }
+        if self.config.clandestine_tls {
+            self.set_up_clandestine_tls(cryptdes.main);
+        }
         let stream_handler_pool_subs = self.start_actors_and_return_shp_subs();
         self.listener_handlers
             .iter_mut()
@@ -650,6 +684,7 @@ impl Bootstrapper {
                         PortConfiguration {
                             discriminator_factories: vec![
                                 Box::new(JsonDiscriminatorFactory::new()),
+                                Box::new(WebSocketDiscriminatorFactory::new()),
                             ],
                             is_clandestine: true,
                         },
@@ -668,9 +703,24 @@ impl Bootstrapper {
         self.config
             .clandestine_discriminator_factories
             .push(Box::new(JsonDiscriminatorFactory::new()));
+        self.config
+            .clandestine_discriminator_factories
+            .push(Box::new(WebSocketDiscriminatorFactory::new()));
         clandestine_port_opt
     }
 
+    fn set_up_clandestine_tls(&mut self, cryptde: &'static dyn CryptDE) {
+        let clandestine_tls = ClandestineTls::new(cryptde)
+            .unwrap_or_else(|e| panic!("Could not set up clandestine TLS: {}", e));
+        let acceptor = clandestine_tls
+            .acceptor()
+            .unwrap_or_else(|e| panic!("Could not set up clandestine TLS: {}", e));
+        self.listener_handlers
+            .iter_mut()
+            .for_each(|f| f.enable_clandestine_tls(acceptor.clone()));
+        self.config.clandestine_tls_opt = Some(clandestine_tls);
+    }
+
     fn establish_clandestine_port(
         &self,
         persistent_config: &mut dyn PersistentConfiguration,
@@ -724,13 +774,15 @@ mod tests {
     use crate::discriminator::Discriminator;
     use crate::discriminator::UnmaskedChunk;
     use crate::listener_handler::{ListenerHandler, ListenerHandlerFactory};
+    use crate::masquerader::Masquerader;
     use crate::node_test_utils::{extract_log, DirsWrapperMock, IdWrapperMock};
     use crate::node_test_utils::{make_stream_handler_pool_subs_from_recorder, TestLogOwner};
     use crate::server_initializer::test_utils::LoggerInitializerWrapperMock;
     use crate::server_initializer::LoggerInitializerWrapper;
     use crate::stream_handler_pool::StreamHandlerPoolSubs;
     use crate::stream_messages::AddStreamMsg;
-    use crate::sub_lib::accountant::ScanIntervals;
+    use crate::sub_lib::accountant::{FeeBumping, ScanIntervals, SpendingBudget};
+    use crate::sub_lib::clandestine_tls::ClandestineTls;
     use crate::sub_lib::cryptde::PublicKey;
     use crate::sub_lib::cryptde::{CryptDE, PlainData};
     use crate::sub_lib::cryptde_null::CryptDENull;
@@ -752,6 +804,7 @@ mod tests {
     };
     use crate::test_utils::{assert_contains, rate_pack};
     use crate::test_utils::{main_cryptde, make_wallet};
+    use crate::websocket_masquerader::WebSocketMasquerader;
     use actix::System;
     use actix::{Actor, Recipient};
     use crossbeam_channel::unbounded;
@@ -767,6 +820,7 @@ mod tests {
     use masq_lib::test_utils::logging::{init_test_logging, TestLog, TestLogHandler};
     use masq_lib::test_utils::utils::{ensure_node_home_directory_exists, TEST_DEFAULT_CHAIN};
     use masq_lib::utils::{find_free_port, to_string};
+    use openssl::ssl::SslAcceptor;
     use std::cell::RefCell;
     use std::collections::HashMap;
     use std::io;
@@ -852,6 +906,13 @@ mod tests {
 
             self.add_stream_sub = Some(add_stream_sub);
         }
+
+        fn enable_clandestine_tls(&mut self, _acceptor: SslAcceptor) {
+            self.log
+                .lock()
+                .unwrap()
+                .log(String::from("enable_clandestine_tls (...)"));
+        }
     }
 
     impl Future for ListenerHandlerNull {
@@ -1252,6 +1313,17 @@ mod tests {
         unprivileged_config.scan_intervals_opt = Some(ScanIntervals::default());
         unprivileged_config.suppress_initial_scans = false;
         unprivileged_config.when_pending_too_long_sec = DEFAULT_PENDING_TOO_LONG_SEC;
+        unprivileged_config.fee_bumping = FeeBumping {
+            bump_after_sec: 600,
+            gas_price_increase_percent: 50,
+            max_bumps: 1,
+        };
+        unprivileged_config.spending_budget = SpendingBudget {
+            daily_gwei: 1_000_000,
+            monthly_gwei: 20_000_000,
+            first_warning_percent: 50,
+            second_warning_percent: 75,
+        };
 
         privileged_config.merge_unprivileged(unprivileged_config);
 
@@ -1280,6 +1352,23 @@ mod tests {
             privileged_config.when_pending_too_long_sec,
             DEFAULT_PENDING_TOO_LONG_SEC
         );
+        assert_eq!(
+            privileged_config.fee_bumping,
+            FeeBumping {
+                bump_after_sec: 600,
+                gas_price_increase_percent: 50,
+                max_bumps: 1,
+            }
+        );
+        assert_eq!(
+            privileged_config.spending_budget,
+            SpendingBudget {
+                daily_gwei: 1_000_000,
+                monthly_gwei: 20_000_000,
+                first_warning_percent: 50,
+                second_warning_percent: 75,
+            }
+        );
         //some values from the privileged config
         assert_eq!(privileged_config.log_level, Off);
         assert_eq!(
@@ -1694,6 +1783,69 @@ mod tests {
         );
     }
 
+    #[test]
+    fn initialize_as_unprivileged_enables_clandestine_tls_when_asked() {
+        let _lock = INITIALIZATION.lock();
+        let data_dir = ensure_node_home_directory_exists(
+            "bootstrapper",
+            "initialize_as_unprivileged_enables_clandestine_tls_when_asked",
+        );
+        let (one_listener_handler, one_listener_handler_log_arc) =
+            extract_log(ListenerHandlerNull::new(vec![]).bind_port_result(Ok(())));
+        let (another_listener_handler, _) =
+            extract_log(ListenerHandlerNull::new(vec![]).bind_port_result(Ok(())));
+        let (clandestine_listener_handler, clandestine_listener_handler_log_arc) =
+            extract_log(ListenerHandlerNull::new(vec![]).bind_port_result(Ok(())));
+        let mut subject = BootstrapperBuilder::new()
+            .add_listener_handler(Box::new(one_listener_handler))
+            .add_listener_handler(Box::new(another_listener_handler))
+            .add_listener_handler(Box::new(clandestine_listener_handler))
+            .build();
+        let mut holder = FakeStreamHolder::new();
+        subject
+            .initialize_as_privileged(&make_simplified_multi_config([
+                "--data-directory",
+                data_dir.to_str().unwrap(),
+                "--clandestine-tls",
+                "on",
+            ]))
+            .unwrap();
+
+        subject
+            .initialize_as_unprivileged(
+                &make_simplified_multi_config([
+                    "--clandestine-port",
+                    "1234",
+                    "--ip",
+                    "1.2.3.4",
+                    "--data-directory",
+                    data_dir.to_str().unwrap(),
+                ]),
+                &mut holder.streams(),
+            )
+            .unwrap();
+
+        let calls = clandestine_listener_handler_log_arc.lock().unwrap().dump();
+        assert_eq!(
+            calls,
+            vec![
+                "bind_port_and_configuration (1234, PortConfiguration {is_clandestine: true, ...})"
+                    .to_string(),
+                "enable_clandestine_tls (...)".to_string(),
+            ],
+        );
+        let calls = one_listener_handler_log_arc.lock().unwrap().dump();
+        assert_eq!(
+            calls.last(),
+            Some(&"enable_clandestine_tls (...)".to_string())
+        );
+        let clandestine_tls = subject.config.clandestine_tls_opt.unwrap();
+        assert_eq!(
+            ClandestineTls::certified_public_key(main_cryptde_ref(), clandestine_tls.certificate()),
+            Ok(main_cryptde_ref().public_key().clone())
+        );
+    }
+
     #[test]
     fn initialize_as_unprivileged_moves_streams_from_listener_handlers_to_stream_handler_pool() {
         let _lock = INITIALIZATION.lock();
@@ -1899,7 +2051,17 @@ mod tests {
             }),
             discriminator.take_chunk(),
         );
-        assert_eq!(0, clandestine_discriminators.len()); // Used to be 1, now 0 after removal
+        let mut discriminator = clandestine_discriminators.remove(0);
+        discriminator.add_data(&WebSocketMasquerader::new().mask(b"Booga").unwrap());
+        assert_eq!(
+            Some(UnmaskedChunk {
+                chunk: b"Booga".to_vec(),
+                last_chunk: true,
+                sequenced: false,
+            }),
+            discriminator.take_chunk(),
+        );
+        assert_eq!(0, clandestine_discriminators.len()); // Used to be 2, now 0 after removal
     }
 
     #[test]
@@ -49,6 +49,7 @@ lazy_static! {
         vec![
             ("db-password".to_string(), 16),
             ("consuming-private-key".to_string(), 64),
+            ("consuming-keystore-passphrase".to_string(), 16),
         ]
         .into_iter()
         .collect()
@@ -461,6 +462,7 @@ mod tests {
             vec![
                 ("db-password".to_string(), 16),
                 ("consuming-private-key".to_string(), 64),
+                ("consuming-keystore-passphrase".to_string(), 16),
             ]
             .into_iter()
             .collect()
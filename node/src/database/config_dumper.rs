@@ -3,12 +3,15 @@
 use crate::apps::app_config_dumper;
 use crate::blockchain::bip39::Bip39;
 use crate::bootstrapper::RealUser;
+use crate::database::db_backup::integrity_check;
 use crate::database::db_initializer::DbInitializationConfig;
 use crate::database::db_initializer::{
     DbInitializer, DbInitializerReal, InitializationError, DATABASE_FILE,
 };
+use crate::database::rusqlite_wrappers::ConnectionWrapper;
 use crate::db_config::config_dao::{ConfigDao, ConfigDaoReal, ConfigDaoRecord};
 use crate::db_config::typed_config_layer::{decode_bytes, encode_bytes};
+use crate::neighborhood::gossip::GossipNodeRecord;
 use crate::node_configurator::{
     data_directory_from_context, real_user_data_directory_path_and_chain, DirsWrapper,
 };
@@ -40,12 +43,14 @@ impl DumpConfigRunner for DumpConfigRunnerReal {
             distill_args(dirs_wrapper_ref, args)?;
         let cryptde = CryptDEReal::new(chain);
         PrivilegeDropperReal::new().drop_privileges(&real_user);
-        let config_dao = make_config_dao(
+        let conn = open_database(
             &data_directory,
             DbInitializationConfig::migration_suppressed(),
         ); //dump config is not supposed to migrate db
+        let integrity = integrity_report(conn.as_ref());
+        let config_dao = ConfigDaoReal::new(conn);
         let configuration = config_dao.get_all().expect("Couldn't fetch configuration");
-        let json = configuration_to_json(configuration, password_opt, &cryptde);
+        let json = configuration_to_json(configuration, integrity, password_opt, &cryptde);
         write_string(streams, json);
         Ok(())
     }
@@ -63,6 +68,7 @@ fn write_string(streams: &mut StdStreams, json: String) {
 
 fn configuration_to_json(
     configuration: Vec<ConfigDaoRecord>,
+    integrity: String,
     password_opt: Option<String>,
     cryptde: &dyn CryptDE,
 ) -> String {
@@ -84,6 +90,7 @@ fn configuration_to_json(
         };
         map.insert(json_name, json_value);
     });
+    map.insert("integrityCheck".to_string(), json!(integrity));
     let value: Value = Value::Object(map);
     serde_json::to_string_pretty(&value).expect("Couldn't serialize configuration to JSON")
 }
@@ -115,13 +122,34 @@ fn translate_bytes(json_name: &str, input: PlainData, cryptde: &dyn CryptDE) ->
                 .collect::<Vec<String>>()
                 .join(",")
         }
+        "neighborhoodSnapshot" => {
+            let string = to_utf8(input);
+            let bytes = decode_bytes(Some(string))
+                .expect("Database is corrupt: neighborhood_snapshot cannot be decoded")
+                .expect("Value disappeared");
+            let node_records =
+                serde_cbor::de::from_slice::<Vec<GossipNodeRecord>>(bytes.as_slice())
+                    .expect("Database is corrupt: neighborhood_snapshot contains bad CBOR");
+            format!("{} Node records", node_records.len())
+        }
         "consumingWalletPrivateKey" => input.as_slice().to_hex(),
         _ => to_utf8(input),
     }
 }
 
-fn make_config_dao(data_directory: &Path, init_config: DbInitializationConfig) -> ConfigDaoReal {
-    let conn = DbInitializerReal::default()
+// "ok" for a healthy database; otherwise whatever SQLite found wrong
+fn integrity_report(conn: &dyn ConnectionWrapper) -> String {
+    match integrity_check(conn) {
+        Ok(rows) => rows.join("; "),
+        Err(e) => format!("Integrity check failed to run: {}", e),
+    }
+}
+
+fn open_database(
+    data_directory: &Path,
+    init_config: DbInitializationConfig,
+) -> Box<dyn ConnectionWrapper> {
+    DbInitializerReal::default()
         .initialize(data_directory,init_config)
         .unwrap_or_else(|e| if e == InitializationError::Nonexistent {panic!("\
         Could not find database at: {}. It is created when the Node operates the first time. Running \
@@ -131,8 +159,7 @@ fn make_config_dao(data_directory: &Path, init_config: DbInitializationConfig) -
                 data_directory.join(DATABASE_FILE),
                 e
             )
-        });
-    ConfigDaoReal::new(conn)
+        })
 }
 
 fn distill_args(
@@ -146,7 +173,7 @@ fn distill_args(
     ];
     let multi_config = make_new_multi_config(&app, vcls)?;
     let (real_user, data_directory_path, chain) =
-        real_user_data_directory_path_and_chain(dirs_wrapper, &multi_config);
+        real_user_data_directory_path_and_chain(dirs_wrapper, &multi_config)?;
     let directory = match data_directory_path {
         Some(data_dir) => data_dir,
         None => data_directory_from_context(dirs_wrapper, &real_user, chain),
@@ -161,6 +188,7 @@ mod tests {
     use crate::blockchain::bip39::Bip39;
     use crate::database::db_initializer::ExternalData;
     use crate::database::rusqlite_wrappers::ConnectionWrapperReal;
+    use crate::database::test_utils::ConnectionWrapperMock;
     use crate::db_config::config_dao::ConfigDao;
     use crate::db_config::persistent_configuration::{
         PersistentConfiguration, PersistentConfigurationReal,
@@ -172,6 +200,7 @@ mod tests {
     use crate::sub_lib::cryptde::PlainData;
     use crate::sub_lib::neighborhood::{NodeDescriptor, DEFAULT_RATE_PACK};
     use crate::test_utils::database_utils::bring_db_0_back_to_life_and_return_connection;
+    use crate::test_utils::neighborhood_test_utils::{db_from_node, make_node_record};
     use crate::test_utils::{main_cryptde, ArgsBuilder};
     use masq_lib::constants::CURRENT_SCHEMA_VERSION;
     use masq_lib::constants::DEFAULT_CHAIN;
@@ -366,6 +395,7 @@ mod tests {
         );
         assert_value("ratePack", &DEFAULT_RATE_PACK.to_string(), &map);
         assert_value("scanIntervals", &DEFAULT_SCAN_INTERVALS.to_string(), &map);
+        assert_value("integrityCheck", "ok", &map);
         assert!(output.ends_with("\n}\n")) //asserting that there is a blank line at the end
     }
 
@@ -511,6 +541,7 @@ mod tests {
         );
         assert_value("ratePack", &DEFAULT_RATE_PACK.to_string(), &map);
         assert_value("scanIntervals", &DEFAULT_SCAN_INTERVALS.to_string(), &map);
+        assert_value("integrityCheck", "ok", &map);
     }
 
     #[test]
@@ -625,6 +656,7 @@ mod tests {
         );
         assert_value("ratePack", &DEFAULT_RATE_PACK.to_string(), &map);
         assert_value("scanIntervals", &DEFAULT_SCAN_INTERVALS.to_string(), &map);
+        assert_value("integrityCheck", "ok", &map);
     }
 
     #[test]
@@ -656,6 +688,18 @@ mod tests {
         let _ = translate_bytes("pastNeighbors", data, cryptde);
     }
 
+    #[test]
+    fn integrity_report_explains_why_the_check_could_not_run() {
+        let conn = ConnectionWrapperMock::new().prepare_result(Err(rusqlite::Error::InvalidQuery));
+
+        let result = integrity_report(&conn);
+
+        assert_eq!(
+            result,
+            "Integrity check failed to run: Query is not read-only".to_string()
+        );
+    }
+
     fn assert_value(key: &str, expected_value: &str, map: &Map<String, Value>) {
         let actual_value = match map
             .get(key)
@@ -696,4 +740,26 @@ mod tests {
         let actual_value: String = decrypted_value_bytes.as_slice().to_hex();
         assert_eq!(actual_value.to_uppercase(), expected_value.to_uppercase());
     }
+
+    #[test]
+    fn translate_bytes_summarizes_the_neighborhood_snapshot() {
+        let one_node = make_node_record(1234, true);
+        let another_node = make_node_record(2345, true);
+        let mut db = db_from_node(&one_node);
+        db.add_node(another_node.clone()).unwrap();
+        let node_records = vec![
+            GossipNodeRecord::from((&db, one_node.public_key(), true)),
+            GossipNodeRecord::from((&db, another_node.public_key(), true)),
+        ];
+        let serialized = PlainData::new(&serde_cbor::ser::to_vec(&node_records).unwrap());
+        let encoded = encode_bytes(Some(serialized)).unwrap().unwrap();
+
+        let result = translate_bytes(
+            "neighborhoodSnapshot",
+            PlainData::new(encoded.as_bytes()),
+            main_cryptde(),
+        );
+
+        assert_eq!(result, "2 Node records".to_string());
+    }
 }
@@ -0,0 +1,392 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::blockchain::bip39::{Bip39, Bip39Error};
+use crate::database::rusqlite_wrappers::ConnectionWrapper;
+use std::fmt::{Display, Formatter};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+// Every plain SQLite database file starts with these bytes; an encrypted backup never does
+pub const SQLITE_FILE_HEADER: &[u8] = b"SQLite format 3\0";
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum DatabaseBackupError {
+    DestinationExists(PathBuf),
+    Backup(String),
+    Io(String),
+    Encryption(String),
+    PasswordRequired,
+    BadPassword,
+    NotABackup(PathBuf),
+}
+
+impl Display for DatabaseBackupError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DatabaseBackupError::DestinationExists(path) => write!(
+                f,
+                "{} already exists; choose a new file for the backup",
+                path.display()
+            ),
+            DatabaseBackupError::Backup(msg) => write!(f, "Database backup failed: {}", msg),
+            DatabaseBackupError::Io(msg) => write!(f, "Could not handle backup file: {}", msg),
+            DatabaseBackupError::Encryption(msg) => {
+                write!(f, "Could not encrypt backup: {}", msg)
+            }
+            DatabaseBackupError::PasswordRequired => write!(
+                f,
+                "The backup is encrypted; supply the database password it was made with"
+            ),
+            DatabaseBackupError::BadPassword => {
+                write!(f, "The password does not decrypt the backup")
+            }
+            DatabaseBackupError::NotABackup(path) => {
+                write!(f, "{} is not a database backup", path.display())
+            }
+        }
+    }
+}
+
+pub trait DatabaseBackup {
+    // Returns the size in bytes of the file written
+    fn back_up(
+        &self,
+        destination: &Path,
+        db_password_opt: Option<&str>,
+    ) -> Result<u64, DatabaseBackupError>;
+}
+
+pub struct DatabaseBackupReal {
+    conn: Box<dyn ConnectionWrapper>,
+}
+
+impl DatabaseBackup for DatabaseBackupReal {
+    fn back_up(
+        &self,
+        destination: &Path,
+        db_password_opt: Option<&str>,
+    ) -> Result<u64, DatabaseBackupError> {
+        if destination.exists() {
+            return Err(DatabaseBackupError::DestinationExists(
+                destination.to_path_buf(),
+            ));
+        }
+        match db_password_opt {
+            None => self.snapshot(destination)?,
+            Some(db_password) => {
+                // The snapshot has to hit the disk before it can be encrypted, but it never
+                // survives this function
+                let snapshot_path = Self::snapshot_path(destination);
+                self.snapshot(&snapshot_path)?;
+                let result = std::fs::read(&snapshot_path)
+                    .map_err(|e| DatabaseBackupError::Io(e.to_string()))
+                    .and_then(|bytes| {
+                        Bip39::encrypt_bytes(&bytes, db_password)
+                            .map_err(|e| DatabaseBackupError::Encryption(format!("{:?}", e)))
+                    })
+                    .and_then(|encrypted| write_new_file(destination, encrypted.as_bytes()));
+                let _ = std::fs::remove_file(&snapshot_path);
+                result?
+            }
+        }
+        std::fs::metadata(destination)
+            .map(|metadata| metadata.len())
+            .map_err(|e| DatabaseBackupError::Io(e.to_string()))
+    }
+}
+
+impl DatabaseBackupReal {
+    pub fn new(conn: Box<dyn ConnectionWrapper>) -> Self {
+        Self { conn }
+    }
+
+    fn snapshot(&self, destination: &Path) -> Result<(), DatabaseBackupError> {
+        self.conn.backup(destination).map_err(|e| {
+            let _ = std::fs::remove_file(destination);
+            DatabaseBackupError::Backup(e.to_string())
+        })
+    }
+
+    fn snapshot_path(destination: &Path) -> PathBuf {
+        let mut file_name = destination
+            .file_name()
+            .map(|name| name.to_os_string())
+            .unwrap_or_default();
+        file_name.push(".snapshot");
+        destination.with_file_name(file_name)
+    }
+}
+
+// Produces the plain SQLite bytes held in a backup, decrypting them if necessary
+pub fn read_backup(
+    backup_path: &Path,
+    db_password_opt: Option<&str>,
+) -> Result<Vec<u8>, DatabaseBackupError> {
+    let not_a_backup = || DatabaseBackupError::NotABackup(backup_path.to_path_buf());
+    let bytes = std::fs::read(backup_path)
+        .map_err(|e| DatabaseBackupError::Io(format!("{}: {}", backup_path.display(), e)))?;
+    if bytes.starts_with(SQLITE_FILE_HEADER) {
+        return Ok(bytes);
+    }
+    let encrypted = String::from_utf8(bytes).map_err(|_| not_a_backup())?;
+    let db_password = db_password_opt.ok_or(DatabaseBackupError::PasswordRequired)?;
+    match Bip39::decrypt_bytes(encrypted.trim(), db_password) {
+        Ok(plain) if plain.as_slice().starts_with(SQLITE_FILE_HEADER) => Ok(plain.into()),
+        Ok(_) => Err(not_a_backup()),
+        Err(Bip39Error::DecryptionFailure(_)) => Err(DatabaseBackupError::BadPassword),
+        Err(_) => Err(not_a_backup()),
+    }
+}
+
+// The rows of PRAGMA integrity_check: a single "ok" for a healthy database
+pub fn integrity_check(conn: &dyn ConnectionWrapper) -> Result<Vec<String>, rusqlite::Error> {
+    let mut stmt = conn.prepare("pragma integrity_check")?;
+    let rows = stmt.query_map([], |row| row.get::<usize, String>(0))?;
+    rows.collect()
+}
+
+pub fn write_new_file(path: &Path, contents: &[u8]) -> Result<(), DatabaseBackupError> {
+    let mut file: File = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .map_err(|e| DatabaseBackupError::Io(format!("{}: {}", path.display(), e)))?;
+    file.write_all(contents)
+        .and_then(|_| file.sync_all())
+        .map_err(|e| DatabaseBackupError::Io(format!("{}: {}", path.display(), e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::db_initializer::{
+        DbInitializationConfig, DbInitializer, DbInitializerReal, DATABASE_FILE,
+    };
+    use crate::database::rusqlite_wrappers::ConnectionWrapperReal;
+    use crate::database::test_utils::ConnectionWrapperMock;
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+    use rusqlite::{Connection, Error};
+    use std::sync::{Arc, Mutex};
+
+    fn make_populated_database(home_dir: &Path) -> Box<dyn ConnectionWrapper> {
+        let conn = DbInitializerReal::default()
+            .initialize(home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        conn.prepare(
+            "insert into config (name, value, encrypted) values ('backup_marker', 'here', 0)",
+        )
+        .unwrap()
+        .execute([])
+        .unwrap();
+        conn
+    }
+
+    fn read_marker(path: &Path) -> String {
+        let conn = Connection::open(path).unwrap();
+        conn.query_row(
+            "select value from config where name = 'backup_marker'",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(SQLITE_FILE_HEADER, b"SQLite format 3\0");
+    }
+
+    #[test]
+    fn unencrypted_backup_is_a_working_copy_of_the_database() {
+        let home_dir = ensure_node_home_directory_exists(
+            "db_backup",
+            "unencrypted_backup_is_a_working_copy_of_the_database",
+        );
+        let subject = DatabaseBackupReal::new(make_populated_database(&home_dir));
+        let destination = home_dir.join("backup.db");
+
+        let result = subject.back_up(&destination, None);
+
+        let size = std::fs::metadata(&destination).unwrap().len();
+        assert_eq!(result, Ok(size));
+        assert_eq!(read_marker(&destination), "here".to_string());
+        assert_eq!(read_backup(&destination, None).unwrap().len() as u64, size);
+    }
+
+    #[test]
+    fn encrypted_backup_can_be_read_only_with_the_password() {
+        let home_dir = ensure_node_home_directory_exists(
+            "db_backup",
+            "encrypted_backup_can_be_read_only_with_the_password",
+        );
+        let subject = DatabaseBackupReal::new(make_populated_database(&home_dir));
+        let destination = home_dir.join("backup.enc");
+
+        let result = subject.back_up(&destination, Some("password"));
+
+        assert!(result.is_ok());
+        let on_disk = std::fs::read(&destination).unwrap();
+        assert!(!on_disk.starts_with(SQLITE_FILE_HEADER));
+        assert!(!DatabaseBackupReal::snapshot_path(&destination).exists());
+        assert_eq!(
+            read_backup(&destination, None),
+            Err(DatabaseBackupError::PasswordRequired)
+        );
+        assert_eq!(
+            read_backup(&destination, Some("booga")),
+            Err(DatabaseBackupError::BadPassword)
+        );
+        let plain = read_backup(&destination, Some("password")).unwrap();
+        let restored = home_dir.join("restored.db");
+        std::fs::write(&restored, plain).unwrap();
+        assert_eq!(read_marker(&restored), "here".to_string());
+    }
+
+    #[test]
+    fn back_up_refuses_to_overwrite_an_existing_file() {
+        let home_dir = ensure_node_home_directory_exists(
+            "db_backup",
+            "back_up_refuses_to_overwrite_an_existing_file",
+        );
+        let destination = home_dir.join("precious.txt");
+        std::fs::write(&destination, b"precious").unwrap();
+        let backup_params_arc = Arc::new(Mutex::new(vec![]));
+        let conn = ConnectionWrapperMock::new().backup_params(&backup_params_arc);
+        let subject = DatabaseBackupReal::new(Box::new(conn));
+
+        let result = subject.back_up(&destination, None);
+
+        assert_eq!(
+            result,
+            Err(DatabaseBackupError::DestinationExists(destination.clone()))
+        );
+        assert_eq!(std::fs::read(&destination).unwrap(), b"precious".to_vec());
+        assert!(backup_params_arc.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn back_up_reports_failure_of_the_snapshot_and_leaves_nothing_behind() {
+        let home_dir = ensure_node_home_directory_exists(
+            "db_backup",
+            "back_up_reports_failure_of_the_snapshot_and_leaves_nothing_behind",
+        );
+        let destination = home_dir.join("backup.enc");
+        let backup_params_arc = Arc::new(Mutex::new(vec![]));
+        let conn = ConnectionWrapperMock::new()
+            .backup_params(&backup_params_arc)
+            .backup_result(Err(Error::InvalidQuery));
+        let subject = DatabaseBackupReal::new(Box::new(conn));
+
+        let result = subject.back_up(&destination, Some("password"));
+
+        assert_eq!(
+            result,
+            Err(DatabaseBackupError::Backup(
+                "Query is not read-only".to_string()
+            ))
+        );
+        assert_eq!(
+            *backup_params_arc.lock().unwrap(),
+            vec![home_dir.join("backup.enc.snapshot")]
+        );
+        assert!(!destination.exists());
+    }
+
+    #[test]
+    fn read_backup_rejects_files_that_are_not_backups() {
+        let home_dir = ensure_node_home_directory_exists(
+            "db_backup",
+            "read_backup_rejects_files_that_are_not_backups",
+        );
+        let binary = home_dir.join("binary");
+        std::fs::write(&binary, [0xFFu8, 0xFE, 0x00]).unwrap();
+        let text = home_dir.join("text");
+        std::fs::write(&text, "not a backup at all").unwrap();
+        let missing = home_dir.join("missing");
+
+        assert_eq!(
+            read_backup(&binary, Some("password")),
+            Err(DatabaseBackupError::NotABackup(binary.clone()))
+        );
+        assert_eq!(
+            read_backup(&text, Some("password")),
+            Err(DatabaseBackupError::NotABackup(text.clone()))
+        );
+        match read_backup(&missing, None) {
+            Err(DatabaseBackupError::Io(msg)) => {
+                assert!(msg.starts_with(&missing.display().to_string()), "{}", msg)
+            }
+            x => panic!("Expected Io error; got {:?}", x),
+        }
+    }
+
+    #[test]
+    fn integrity_check_reports_a_healthy_database() {
+        let home_dir = ensure_node_home_directory_exists(
+            "db_backup",
+            "integrity_check_reports_a_healthy_database",
+        );
+        let conn = make_populated_database(&home_dir);
+
+        let result = integrity_check(conn.as_ref());
+
+        assert_eq!(result, Ok(vec!["ok".to_string()]));
+    }
+
+    #[test]
+    fn integrity_check_reports_a_damaged_database() {
+        let home_dir = ensure_node_home_directory_exists(
+            "db_backup",
+            "integrity_check_reports_a_damaged_database",
+        );
+        drop(make_populated_database(&home_dir));
+        let db_path = home_dir.join(DATABASE_FILE);
+        let mut bytes = std::fs::read(&db_path).unwrap();
+        // Scribble over the second page, leaving the header alone
+        let page_size = u16::from_be_bytes([bytes[16], bytes[17]]) as usize;
+        bytes[page_size..page_size + 64]
+            .iter_mut()
+            .for_each(|byte| *byte = 0xAA);
+        std::fs::write(&db_path, bytes).unwrap();
+        let conn = ConnectionWrapperReal::new(Connection::open(&db_path).unwrap());
+
+        let result = integrity_check(&conn);
+
+        assert_ne!(result, Ok(vec!["ok".to_string()]));
+    }
+
+    #[test]
+    fn database_backup_errors_are_displayable() {
+        let path = PathBuf::from("/tmp/backup");
+
+        assert_eq!(
+            DatabaseBackupError::DestinationExists(path.clone()).to_string(),
+            "/tmp/backup already exists; choose a new file for the backup"
+        );
+        assert_eq!(
+            DatabaseBackupError::Backup("disk full".to_string()).to_string(),
+            "Database backup failed: disk full"
+        );
+        assert_eq!(
+            DatabaseBackupError::Io("denied".to_string()).to_string(),
+            "Could not handle backup file: denied"
+        );
+        assert_eq!(
+            DatabaseBackupError::Encryption("bad".to_string()).to_string(),
+            "Could not encrypt backup: bad"
+        );
+        assert_eq!(
+            DatabaseBackupError::PasswordRequired.to_string(),
+            "The backup is encrypted; supply the database password it was made with"
+        );
+        assert_eq!(
+            DatabaseBackupError::BadPassword.to_string(),
+            "The password does not decrypt the backup"
+        );
+        assert_eq!(
+            DatabaseBackupError::NotABackup(path).to_string(),
+            "/tmp/backup is not a database backup"
+        );
+    }
+}
@@ -4,7 +4,10 @@ use crate::database::rusqlite_wrappers::{ConnectionWrapper, ConnectionWrapperRea
 use crate::database::db_migrations::db_migrator::{DbMigrator, DbMigratorReal};
 use crate::db_config::secure_config_layer::EXAMPLE_ENCRYPTED;
 use crate::neighborhood::DEFAULT_MIN_HOPS;
-use crate::sub_lib::accountant::{DEFAULT_PAYMENT_THRESHOLDS, DEFAULT_SCAN_INTERVALS};
+use crate::sub_lib::accountant::{
+    DEFAULT_PAYMENT_THRESHOLDS, DEFAULT_SCAN_INTERVALS, DEFAULT_SPENDING_BUDGET,
+};
+use crate::sub_lib::bandwidth_shaper::{DEFAULT_BANDWIDTH_LIMITS, DEFAULT_DATA_QUOTA};
 use crate::sub_lib::neighborhood::DEFAULT_RATE_PACK;
 use crate::sub_lib::utils::db_connection_launch_panic;
 use masq_lib::blockchains::chains::Chain;
@@ -136,8 +139,15 @@ impl DbInitializerReal {
         Self::initialize_config(conn, external_params);
         Self::create_payable_table(conn);
         Self::create_pending_payable_table(conn);
+        Self::create_pending_payable_fee_bump_table(conn);
+        Self::create_pending_payable_batch_payment_table(conn);
+        Self::create_pending_payable_nonce_table(conn);
         Self::create_receivable_table(conn);
+        Self::create_pending_receivable_table(conn);
         Self::create_banned_table(conn);
+        Self::create_funds_transfer_table(conn);
+        Self::create_financial_history_table(conn);
+        Self::create_ledger_adjustment_table(conn);
     }
 
     pub fn create_config_table(conn: &Connection) {
@@ -220,6 +230,13 @@ impl DbInitializerReal {
             "gas price",
         );
         Self::set_config_value(conn, "past_neighbors", None, true, "past neighbors");
+        Self::set_config_value(
+            conn,
+            "neighborhood_snapshot",
+            None,
+            true,
+            "neighborhood snapshot",
+        );
         Self::set_config_value(
             conn,
             "mapping_protocol",
@@ -256,6 +273,28 @@ impl DbInitializerReal {
             "scan intervals",
         );
         Self::set_config_value(conn, "max_block_count", None, false, "maximum block count");
+        Self::set_config_value(
+            conn,
+            "bandwidth_limits",
+            Some(&DEFAULT_BANDWIDTH_LIMITS.to_string()),
+            false,
+            "bandwidth limits",
+        );
+        Self::set_config_value(
+            conn,
+            "data_quota",
+            Some(&DEFAULT_DATA_QUOTA.to_string()),
+            false,
+            "data quota",
+        );
+        Self::set_config_value(conn, "data_usage", None, false, "data usage");
+        Self::set_config_value(
+            conn,
+            "spending_budget",
+            Some(&DEFAULT_SPENDING_BUDGET.to_string()),
+            false,
+            "spending budget",
+        );
     }
 
     pub fn create_pending_payable_table(conn: &Connection) {
@@ -279,6 +318,134 @@ impl DbInitializerReal {
         .expect("Can't create transaction hash index in pending payments");
     }
 
+    pub fn create_pending_payable_fee_bump_table(conn: &Connection) {
+        conn.execute(
+            "create table if not exists pending_payable_fee_bump (
+                    rowid integer primary key,
+                    pending_payable_rowid integer not null,
+                    replaced_hash text not null,
+                    replacement_hash text not null,
+                    gas_price_wei integer not null,
+                    bumped_at integer not null
+            ) strict",
+            [],
+        )
+        .expect("Can't create pending_payable_fee_bump table");
+        conn.execute(
+            "CREATE INDEX pending_payable_fee_bump_rowid_idx ON pending_payable_fee_bump (pending_payable_rowid)",
+            [],
+        )
+        .expect("Can't create pending payable rowid index in fee bumps");
+    }
+
+    pub fn create_pending_payable_batch_payment_table(conn: &Connection) {
+        conn.execute(
+            "create table if not exists pending_payable_batch_payment (
+                    rowid integer primary key,
+                    pending_payable_rowid integer not null,
+                    wallet_address text not null,
+                    amount_high_b integer not null,
+                    amount_low_b integer not null
+            ) strict",
+            [],
+        )
+        .expect("Can't create pending_payable_batch_payment table");
+        conn.execute(
+            "CREATE INDEX pending_payable_batch_payment_rowid_idx ON pending_payable_batch_payment (pending_payable_rowid)",
+            [],
+        )
+        .expect("Can't create pending payable rowid index in batch payments");
+    }
+
+    pub fn create_pending_payable_nonce_table(conn: &Connection) {
+        conn.execute(
+            "create table if not exists pending_payable_nonce (
+                    pending_payable_rowid integer primary key,
+                    nonce integer not null,
+                    gas_price_wei integer not null
+            ) strict",
+            [],
+        )
+        .expect("Can't create pending_payable_nonce table");
+    }
+
+    pub fn create_funds_transfer_table(conn: &Connection) {
+        conn.execute(
+            "create table if not exists funds_transfer (
+                    rowid integer primary key,
+                    transaction_hash text not null,
+                    recipient_address text not null,
+                    currency text not null,
+                    amount_high_b integer not null,
+                    amount_low_b integer not null,
+                    gas_price_wei integer not null,
+                    nonce integer not null,
+                    transferred_at integer not null
+            ) strict",
+            [],
+        )
+        .expect("Can't create funds_transfer table");
+        conn.execute(
+            "CREATE UNIQUE INDEX funds_transfer_hash_idx ON funds_transfer (transaction_hash)",
+            [],
+        )
+        .expect("Can't create transaction hash index in funds transfers");
+    }
+
+    pub fn create_financial_history_table(conn: &Connection) {
+        conn.execute(
+            "create table if not exists financial_history (
+                    rowid integer primary key,
+                    hour_start integer not null,
+                    direction text not null,
+                    service text not null,
+                    wallet_address text not null,
+                    amount_high_b integer not null,
+                    amount_low_b integer not null
+            ) strict",
+            [],
+        )
+        .expect("Can't create financial_history table");
+        conn.execute(
+            "CREATE UNIQUE INDEX financial_history_bucket_idx ON financial_history (hour_start, direction, service, wallet_address)",
+            [],
+        )
+        .expect("Can't create bucket index in financial history");
+    }
+
+    pub fn create_ledger_adjustment_table(conn: &Connection) {
+        conn.execute(
+            "create table if not exists ledger_adjustment (
+                    rowid integer primary key,
+                    timestamp integer not null,
+                    operator text not null,
+                    reason text not null,
+                    ledger text not null,
+                    kind text not null,
+                    wallet_address text not null,
+                    balance_before_high_b integer not null,
+                    balance_before_low_b integer not null,
+                    balance_after_high_b integer not null,
+                    balance_after_low_b integer not null
+            ) strict",
+            [],
+        )
+        .expect("Can't create ledger_adjustment table");
+        // The audit trail is kept append-only by the database itself
+        ["UPDATE", "DELETE"].into_iter().for_each(|operation| {
+            conn.execute(
+                &format!(
+                    "CREATE TRIGGER ledger_adjustment_no_{} BEFORE {} ON ledger_adjustment \
+                     BEGIN SELECT RAISE(ABORT, 'ledger adjustments are append-only'); END",
+                    operation.to_lowercase(),
+                    operation
+                ),
+                [],
+            )
+            .expect("Can't create trigger guarding ledger adjustments");
+        })
+    }
+
     pub fn create_payable_table(conn: &Connection) {
         conn.execute(
             "create table if not exists payable (
@@ -306,6 +473,29 @@ impl DbInitializerReal {
         .expect("Can't create receivable table");
     }
 
+    pub fn create_pending_receivable_table(conn: &Connection) {
+        conn.execute(
+            "create table if not exists pending_receivable (
+                    rowid integer primary key,
+                    transaction_hash text not null,
+                    log_index integer not null,
+                    block_number integer not null,
+                    block_hash text not null,
+                    wallet_address text not null,
+                    amount_high_b integer not null,
+                    amount_low_b integer not null,
+                    credited integer not null
+            ) strict",
+            [],
+        )
+        .expect("Can't create pending_receivable table");
+        conn.execute(
+            "CREATE UNIQUE INDEX pending_receivable_log_idx ON pending_receivable (transaction_hash, log_index)",
+            [],
+        )
+        .expect("Can't create log index in pending receivables");
+    }
+
     pub fn create_banned_table(conn: &Connection) {
         conn.execute(
             "create table banned ( wallet_address text primary key )",
@@ -652,7 +842,7 @@ mod tests {
     #[test]
     fn constants_have_correct_values() {
         assert_eq!(DATABASE_FILE, "node-data.db");
-        assert_eq!(CURRENT_SCHEMA_VERSION, 10);
+        assert_eq!(CURRENT_SCHEMA_VERSION, 21);
     }
 
     #[test]
@@ -713,6 +903,243 @@ mod tests {
         )
     }
 
+    #[test]
+    fn db_initialize_creates_pending_payable_fee_bump_table() {
+        let home_dir = ensure_node_home_directory_does_not_exist(
+            "db_initializer",
+            "db_initialize_creates_pending_payable_fee_bump_table",
+        );
+        let subject = DbInitializerReal::default();
+
+        let conn = subject
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+
+        let mut stmt = conn.prepare("select rowid, pending_payable_rowid, replaced_hash, replacement_hash, gas_price_wei, bumped_at from pending_payable_fee_bump").unwrap();
+        let mut fee_bump_contents = stmt.query_map([], |_| Ok(42)).unwrap();
+        assert!(fee_bump_contents.next().is_none());
+        assert_table_created_as_strict(&*conn, "pending_payable_fee_bump");
+        let expected_key_words: &[&[&str]] = &[
+            &["rowid", "integer", "primary", "key"],
+            &["pending_payable_rowid", "integer", "not", "null"],
+            &["replaced_hash", "text", "not", "null"],
+            &["replacement_hash", "text", "not", "null"],
+            &["gas_price_wei", "integer", "not", "null"],
+            &["bumped_at", "integer", "not", "null"],
+        ];
+        assert_create_table_stm_contains_all_parts(
+            &*conn,
+            "pending_payable_fee_bump",
+            expected_key_words,
+        );
+        let expected_key_words: &[&[&str]] = &[&["pending_payable_rowid"]];
+        assert_index_stm_is_coupled_with_right_parameter(
+            conn.as_ref(),
+            "pending_payable_fee_bump_rowid_idx",
+            expected_key_words,
+        )
+    }
+
+    #[test]
+    fn db_initialize_creates_funds_transfer_table() {
+        let home_dir = ensure_node_home_directory_does_not_exist(
+            "db_initializer",
+            "db_initialize_creates_funds_transfer_table",
+        );
+        let subject = DbInitializerReal::default();
+
+        let conn = subject
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+
+        let mut stmt = conn.prepare("select rowid, transaction_hash, recipient_address, currency, amount_high_b, amount_low_b, gas_price_wei, nonce, transferred_at from funds_transfer").unwrap();
+        let mut transfer_contents = stmt.query_map([], |_| Ok(42)).unwrap();
+        assert!(transfer_contents.next().is_none());
+        assert_table_created_as_strict(&*conn, "funds_transfer");
+        let expected_key_words: &[&[&str]] = &[
+            &["rowid", "integer", "primary", "key"],
+            &["transaction_hash", "text", "not", "null"],
+            &["recipient_address", "text", "not", "null"],
+            &["currency", "text", "not", "null"],
+            &["amount_high_b", "integer", "not", "null"],
+            &["amount_low_b", "integer", "not", "null"],
+            &["gas_price_wei", "integer", "not", "null"],
+            &["nonce", "integer", "not", "null"],
+            &["transferred_at", "integer", "not", "null"],
+        ];
+        assert_create_table_stm_contains_all_parts(&*conn, "funds_transfer", expected_key_words);
+        let expected_key_words: &[&[&str]] = &[&["transaction_hash"]];
+        assert_index_stm_is_coupled_with_right_parameter(
+            conn.as_ref(),
+            "funds_transfer_hash_idx",
+            expected_key_words,
+        )
+    }
+
+    #[test]
+    fn db_initialize_creates_pending_payable_batch_payment_table() {
+        let home_dir = ensure_node_home_directory_does_not_exist(
+            "db_initializer",
+            "db_initialize_creates_pending_payable_batch_payment_table",
+        );
+        let subject = DbInitializerReal::default();
+
+        let conn = subject
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+
+        let mut stmt = conn.prepare("select rowid, pending_payable_rowid, wallet_address, amount_high_b, amount_low_b from pending_payable_batch_payment").unwrap();
+        let mut batch_payment_contents = stmt.query_map([], |_| Ok(42)).unwrap();
+        assert!(batch_payment_contents.next().is_none());
+        assert_table_created_as_strict(&*conn, "pending_payable_batch_payment");
+        let expected_key_words: &[&[&str]] = &[
+            &["rowid", "integer", "primary", "key"],
+            &["pending_payable_rowid", "integer", "not", "null"],
+            &["wallet_address", "text", "not", "null"],
+            &["amount_high_b", "integer", "not", "null"],
+            &["amount_low_b", "integer", "not", "null"],
+        ];
+        assert_create_table_stm_contains_all_parts(
+            &*conn,
+            "pending_payable_batch_payment",
+            expected_key_words,
+        );
+        let expected_key_words: &[&[&str]] = &[&["pending_payable_rowid"]];
+        assert_index_stm_is_coupled_with_right_parameter(
+            conn.as_ref(),
+            "pending_payable_batch_payment_rowid_idx",
+            expected_key_words,
+        )
+    }
+
+    #[test]
+    fn db_initialize_creates_pending_payable_nonce_table() {
+        let home_dir = ensure_node_home_directory_does_not_exist(
+            "db_initializer",
+            "db_initialize_creates_pending_payable_nonce_table",
+        );
+        let subject = DbInitializerReal::default();
+
+        let conn = subject
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+
+        let mut stmt = conn
+            .prepare(
+                "select pending_payable_rowid, nonce, gas_price_wei from pending_payable_nonce",
+            )
+            .unwrap();
+        let mut nonce_contents = stmt.query_map([], |_| Ok(42)).unwrap();
+        assert!(nonce_contents.next().is_none());
+        assert_table_created_as_strict(&*conn, "pending_payable_nonce");
+        let expected_key_words: &[&[&str]] = &[
+            &["pending_payable_rowid", "integer", "primary", "key"],
+            &["nonce", "integer", "not", "null"],
+            &["gas_price_wei", "integer", "not", "null"],
+        ];
+        assert_create_table_stm_contains_all_parts(
+            &*conn,
+            "pending_payable_nonce",
+            expected_key_words,
+        )
+    }
+
+    #[test]
+    fn db_initialize_creates_financial_history_table() {
+        let home_dir = ensure_node_home_directory_does_not_exist(
+            "db_initializer",
+            "db_initialize_creates_financial_history_table",
+        );
+        let subject = DbInitializerReal::default();
+
+        let conn = subject
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+
+        let mut stmt = conn.prepare("select rowid, hour_start, direction, service, wallet_address, amount_high_b, amount_low_b from financial_history").unwrap();
+        let mut history_contents = stmt.query_map([], |_| Ok(42)).unwrap();
+        assert!(history_contents.next().is_none());
+        assert_table_created_as_strict(&*conn, "financial_history");
+        let expected_key_words: &[&[&str]] = &[
+            &["rowid", "integer", "primary", "key"],
+            &["hour_start", "integer", "not", "null"],
+            &["direction", "text", "not", "null"],
+            &["service", "text", "not", "null"],
+            &["wallet_address", "text", "not", "null"],
+            &["amount_high_b", "integer", "not", "null"],
+            &["amount_low_b", "integer", "not", "null"],
+        ];
+        assert_create_table_stm_contains_all_parts(&*conn, "financial_history", expected_key_words);
+        let expected_key_words: &[&[&str]] = &[
+            &["hour_start"],
+            &["direction"],
+            &["service"],
+            &["wallet_address"],
+        ];
+        assert_index_stm_is_coupled_with_right_parameter(
+            conn.as_ref(),
+            "financial_history_bucket_idx",
+            expected_key_words,
+        )
+    }
+
+    #[test]
+    fn db_initialize_creates_append_only_ledger_adjustment_table() {
+        let home_dir = ensure_node_home_directory_does_not_exist(
+            "db_initializer",
+            "db_initialize_creates_append_only_ledger_adjustment_table",
+        );
+        let subject = DbInitializerReal::default();
+
+        let conn = subject
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+
+        assert_table_created_as_strict(&*conn, "ledger_adjustment");
+        let expected_key_words: &[&[&str]] = &[
+            &["rowid", "integer", "primary", "key"],
+            &["timestamp", "integer", "not", "null"],
+            &["operator", "text", "not", "null"],
+            &["reason", "text", "not", "null"],
+            &["ledger", "text", "not", "null"],
+            &["kind", "text", "not", "null"],
+            &["wallet_address", "text", "not", "null"],
+            &["balance_before_high_b", "integer", "not", "null"],
+            &["balance_before_low_b", "integer", "not", "null"],
+            &["balance_after_high_b", "integer", "not", "null"],
+            &["balance_after_low_b", "integer", "not", "null"],
+        ];
+        assert_create_table_stm_contains_all_parts(&*conn, "ledger_adjustment", expected_key_words);
+        conn.prepare(
+            "insert into ledger_adjustment (timestamp, operator, reason, ledger, kind, \
+             wallet_address, balance_before_high_b, balance_before_low_b, balance_after_high_b, \
+             balance_after_low_b) values (1, 'op', 'why', 'receivable', 'forgiveness', '0x1234', \
+             0, 5, 0, 0)",
+        )
+        .unwrap()
+        .execute([])
+        .unwrap();
+        [
+            "update ledger_adjustment set reason = 'because'",
+            "delete from ledger_adjustment",
+        ]
+        .into_iter()
+        .for_each(|sql| {
+            let error = conn.prepare(sql).unwrap().execute([]).unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                "ledger adjustments are append-only",
+                "{}",
+                sql
+            )
+        });
+        let mut stmt = conn
+            .prepare("select count(*) from ledger_adjustment")
+            .unwrap();
+        let count: i64 = stmt.query_row([], |row| row.get(0)).unwrap();
+        assert_eq!(count, 1);
+    }
+
     #[test]
     fn db_initialize_creates_payable_table() {
         let home_dir = ensure_node_home_directory_does_not_exist(
@@ -768,6 +1195,46 @@ mod tests {
         assert_no_index_exists_for_table(conn.as_ref(), "receivable")
     }
 
+    #[test]
+    fn db_initialize_creates_pending_receivable_table() {
+        let home_dir = ensure_node_home_directory_does_not_exist(
+            "db_initializer",
+            "db_initialize_creates_pending_receivable_table",
+        );
+        let subject = DbInitializerReal::default();
+
+        let conn = subject
+            .initialize(&home_dir, DbInitializationConfig::test_default())
+            .unwrap();
+
+        let mut stmt = conn.prepare("select rowid, transaction_hash, log_index, block_number, block_hash, wallet_address, amount_high_b, amount_low_b, credited from pending_receivable").unwrap();
+        let mut pending_receivable_contents = stmt.query_map([], |_| Ok(42)).unwrap();
+        assert!(pending_receivable_contents.next().is_none());
+        assert_table_created_as_strict(&*conn, "pending_receivable");
+        let expected_key_words: &[&[&str]] = &[
+            &["rowid", "integer", "primary", "key"],
+            &["transaction_hash", "text", "not", "null"],
+            &["log_index", "integer", "not", "null"],
+            &["block_number", "integer", "not", "null"],
+            &["block_hash", "text", "not", "null"],
+            &["wallet_address", "text", "not", "null"],
+            &["amount_high_b", "integer", "not", "null"],
+            &["amount_low_b", "integer", "not", "null"],
+            &["credited", "integer", "not", "null"],
+        ];
+        assert_create_table_stm_contains_all_parts(
+            &*conn,
+            "pending_receivable",
+            expected_key_words,
+        );
+        let expected_key_words: &[&[&str]] = &[&["transaction_hash"], &["log_index"]];
+        assert_index_stm_is_coupled_with_right_parameter(
+            conn.as_ref(),
+            "pending_receivable_log_idx",
+            expected_key_words,
+        )
+    }
+
     #[test]
     fn db_initialize_creates_banned_table() {
         init_test_logging();
@@ -906,6 +1373,12 @@ mod tests {
             assert_eq!(actual_encrypted, expected_encrypted);
             value
         };
+        verify(
+            &mut config_vec,
+            "bandwidth_limits",
+            Some(&DEFAULT_BANDWIDTH_LIMITS.to_string()),
+            false,
+        );
         verify(&mut config_vec, "blockchain_service_url", None, false);
         verify(
             &mut config_vec,
@@ -918,6 +1391,13 @@ mod tests {
         assert!(clandestine_port >= 1025);
         assert!(clandestine_port < 10000);
         verify(&mut config_vec, "consuming_wallet_private_key", None, true);
+        verify(
+            &mut config_vec,
+            "data_quota",
+            Some(&DEFAULT_DATA_QUOTA.to_string()),
+            false,
+        );
+        verify(&mut config_vec, "data_usage", None, false);
         verify(&mut config_vec, "earning_wallet_address", None, false);
         verify(&mut config_vec, EXAMPLE_ENCRYPTED, None, true);
         verify(
@@ -935,6 +1415,7 @@ mod tests {
             Some("standard"),
             false,
         );
+        verify(&mut config_vec, "neighborhood_snapshot", None, true);
         verify(&mut config_vec, "past_neighbors", None, true);
         verify(
             &mut config_vec,
@@ -961,6 +1442,12 @@ mod tests {
             Some(&CURRENT_SCHEMA_VERSION.to_string()),
             false,
         );
+        verify(
+            &mut config_vec,
+            "spending_budget",
+            Some(&DEFAULT_SPENDING_BUDGET.to_string()),
+            false,
+        );
         verify(&mut config_vec, "start_block", None, false);
         assert_eq!(config_vec, vec![]);
     }
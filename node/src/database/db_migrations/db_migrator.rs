@@ -2,7 +2,18 @@
 
 use crate::database::db_initializer::ExternalData;
 use crate::database::db_migrations::migrations::migration_0_to_1::Migrate_0_to_1;
+use crate::database::db_migrations::migrations::migration_10_to_11::Migrate_10_to_11;
+use crate::database::db_migrations::migrations::migration_11_to_12::Migrate_11_to_12;
+use crate::database::db_migrations::migrations::migration_12_to_13::Migrate_12_to_13;
+use crate::database::db_migrations::migrations::migration_13_to_14::Migrate_13_to_14;
+use crate::database::db_migrations::migrations::migration_14_to_15::Migrate_14_to_15;
+use crate::database::db_migrations::migrations::migration_15_to_16::Migrate_15_to_16;
+use crate::database::db_migrations::migrations::migration_16_to_17::Migrate_16_to_17;
+use crate::database::db_migrations::migrations::migration_17_to_18::Migrate_17_to_18;
+use crate::database::db_migrations::migrations::migration_18_to_19::Migrate_18_to_19;
+use crate::database::db_migrations::migrations::migration_19_to_20::Migrate_19_to_20;
 use crate::database::db_migrations::migrations::migration_1_to_2::Migrate_1_to_2;
+use crate::database::db_migrations::migrations::migration_20_to_21::Migrate_20_to_21;
 use crate::database::db_migrations::migrations::migration_2_to_3::Migrate_2_to_3;
 use crate::database::db_migrations::migrations::migration_3_to_4::Migrate_3_to_4;
 use crate::database::db_migrations::migrations::migration_4_to_5::Migrate_4_to_5;
@@ -15,6 +26,7 @@ use crate::database::db_migrations::migrator_utils::{
     DBMigDeclarator, DBMigrationUtilities, DBMigrationUtilitiesReal, DBMigratorInnerConfiguration,
 };
 use crate::database::rusqlite_wrappers::{ConnectionWrapper, TransactionSafeWrapper};
+use masq_lib::constants::CURRENT_SCHEMA_VERSION;
 use masq_lib::logger::Logger;
 
 pub trait DbMigrator {
@@ -68,6 +80,14 @@ impl DbMigratorReal {
         }
     }
 
+    // True if a database of this schema version is current or can be brought up to date
+    pub fn is_migratable(schema_version: usize) -> bool {
+        schema_version == CURRENT_SCHEMA_VERSION
+            || Self::list_of_migrations()
+                .iter()
+                .any(|migration| migration.old_version() == schema_version)
+    }
+
     const fn list_of_migrations<'a>() -> &'a [&'a dyn DatabaseMigration] {
         &[
             &Migrate_0_to_1,
@@ -80,6 +100,17 @@ impl DbMigratorReal {
             &Migrate_7_to_8,
             &Migrate_8_to_9,
             &Migrate_9_to_10,
+            &Migrate_10_to_11,
+            &Migrate_11_to_12,
+            &Migrate_12_to_13,
+            &Migrate_13_to_14,
+            &Migrate_14_to_15,
+            &Migrate_15_to_16,
+            &Migrate_16_to_17,
+            &Migrate_17_to_18,
+            &Migrate_18_to_19,
+            &Migrate_19_to_20,
+            &Migrate_20_to_21,
         ]
     }
 
@@ -442,6 +473,13 @@ mod tests {
         assert!(two_numbers_are_sequential(result, CURRENT_SCHEMA_VERSION))
     }
 
+    #[test]
+    fn is_migratable_accepts_every_version_up_to_the_current_one() {
+        (0..=CURRENT_SCHEMA_VERSION)
+            .for_each(|version| assert!(DbMigratorReal::is_migratable(version), "{}", version));
+        assert!(!DbMigratorReal::is_migratable(CURRENT_SCHEMA_VERSION + 1))
+    }
+
     #[test]
     fn migrate_semi_automated_returns_an_error_from_update_schema_version() {
         let update_schema_version_params_arc = Arc::new(Mutex::new(vec![]));
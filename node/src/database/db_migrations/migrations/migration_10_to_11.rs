@@ -0,0 +1,81 @@
+use crate::database::db_migrations::db_migrator::DatabaseMigration;
+use crate::database::db_migrations::migrator_utils::DBMigDeclarator;
+use crate::sub_lib::bandwidth_shaper::{DEFAULT_BANDWIDTH_LIMITS, DEFAULT_DATA_QUOTA};
+
+#[allow(non_camel_case_types)]
+pub struct Migrate_10_to_11;
+
+impl DatabaseMigration for Migrate_10_to_11 {
+    fn migrate<'a>(
+        &self,
+        declaration_utils: Box<dyn DBMigDeclarator + 'a>,
+    ) -> rusqlite::Result<()> {
+        let statement_1 = format!(
+            "INSERT INTO config (name, value, encrypted) VALUES ('bandwidth_limits', '{}', 0)",
+            DEFAULT_BANDWIDTH_LIMITS
+        );
+        let statement_2 = format!(
+            "INSERT INTO config (name, value, encrypted) VALUES ('data_quota', '{}', 0)",
+            DEFAULT_DATA_QUOTA
+        );
+        declaration_utils.execute_upon_transaction(&[&statement_1, &statement_2])
+    }
+
+    fn old_version(&self) -> usize {
+        10
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::db_initializer::{
+        DbInitializationConfig, DbInitializer, DbInitializerReal, DATABASE_FILE,
+    };
+    use crate::test_utils::database_utils::{
+        bring_db_0_back_to_life_and_return_connection, make_external_data, retrieve_config_row,
+    };
+    use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+    use std::fs::create_dir_all;
+
+    #[test]
+    fn migration_from_10_to_11_is_properly_set() {
+        init_test_logging();
+        let dir_path = ensure_node_home_directory_exists(
+            "db_migrations",
+            "migration_from_10_to_11_is_properly_set",
+        );
+        create_dir_all(&dir_path).unwrap();
+        let db_path = dir_path.join(DATABASE_FILE);
+        let _ = bring_db_0_back_to_life_and_return_connection(&db_path);
+        let subject = DbInitializerReal::default();
+
+        let result = subject.initialize_to_version(
+            &dir_path,
+            10,
+            DbInitializationConfig::create_or_migrate(make_external_data()),
+        );
+
+        assert!(result.is_ok());
+
+        let result = subject.initialize_to_version(
+            &dir_path,
+            11,
+            DbInitializationConfig::create_or_migrate(make_external_data()),
+        );
+
+        let connection = result.unwrap();
+        let (bl_value, bl_encrypted) = retrieve_config_row(connection.as_ref(), "bandwidth_limits");
+        let (dq_value, dq_encrypted) = retrieve_config_row(connection.as_ref(), "data_quota");
+        let (cs_value, cs_encrypted) = retrieve_config_row(connection.as_ref(), "schema_version");
+        assert_eq!(bl_value, Some("0|0|0|0|0".to_string()));
+        assert_eq!(bl_encrypted, false);
+        assert_eq!(dq_value, Some("0|0".to_string()));
+        assert_eq!(dq_encrypted, false);
+        assert_eq!(cs_value, Some(11.to_string()));
+        assert_eq!(cs_encrypted, false);
+        TestLogHandler::new().assert_logs_contain_in_order(vec![
+            "DbMigrator: Database successfully migrated from version 10 to 11",
+        ]);
+    }
+}
@@ -0,0 +1,97 @@
+use crate::database::db_migrations::db_migrator::DatabaseMigration;
+use crate::database::db_migrations::migrator_utils::DBMigDeclarator;
+
+#[allow(non_camel_case_types)]
+pub struct Migrate_12_to_13;
+
+impl DatabaseMigration for Migrate_12_to_13 {
+    fn migrate<'a>(
+        &self,
+        declaration_utils: Box<dyn DBMigDeclarator + 'a>,
+    ) -> rusqlite::Result<()> {
+        let statement_1 = "create table if not exists pending_payable_fee_bump (
+                rowid integer primary key,
+                pending_payable_rowid integer not null,
+                replaced_hash text not null,
+                replacement_hash text not null,
+                gas_price_wei integer not null,
+                bumped_at integer not null
+            ) strict";
+        let statement_2 = "CREATE INDEX pending_payable_fee_bump_rowid_idx ON pending_payable_fee_bump (pending_payable_rowid)";
+        declaration_utils.execute_upon_transaction(&[&statement_1, &statement_2])
+    }
+
+    fn old_version(&self) -> usize {
+        12
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::db_initializer::{
+        DbInitializationConfig, DbInitializer, DbInitializerReal, DATABASE_FILE,
+    };
+    use crate::test_utils::database_utils::{
+        assert_create_table_stm_contains_all_parts,
+        assert_index_stm_is_coupled_with_right_parameter, assert_table_created_as_strict,
+        bring_db_0_back_to_life_and_return_connection, make_external_data, retrieve_config_row,
+    };
+    use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+    use std::fs::create_dir_all;
+
+    #[test]
+    fn migration_from_12_to_13_is_properly_set() {
+        init_test_logging();
+        let dir_path = ensure_node_home_directory_exists(
+            "db_migrations",
+            "migration_from_12_to_13_is_properly_set",
+        );
+        create_dir_all(&dir_path).unwrap();
+        let db_path = dir_path.join(DATABASE_FILE);
+        let _ = bring_db_0_back_to_life_and_return_connection(&db_path);
+        let subject = DbInitializerReal::default();
+
+        let result = subject.initialize_to_version(
+            &dir_path,
+            12,
+            DbInitializationConfig::create_or_migrate(make_external_data()),
+        );
+
+        assert!(result.is_ok());
+
+        let result = subject.initialize_to_version(
+            &dir_path,
+            13,
+            DbInitializationConfig::create_or_migrate(make_external_data()),
+        );
+
+        let connection = result.unwrap();
+        let (cs_value, cs_encrypted) = retrieve_config_row(connection.as_ref(), "schema_version");
+        assert_eq!(cs_value, Some(13.to_string()));
+        assert_eq!(cs_encrypted, false);
+        assert_table_created_as_strict(connection.as_ref(), "pending_payable_fee_bump");
+        let expected_key_words: &[&[&str]] = &[
+            &["rowid", "integer", "primary", "key"],
+            &["pending_payable_rowid", "integer", "not", "null"],
+            &["replaced_hash", "text", "not", "null"],
+            &["replacement_hash", "text", "not", "null"],
+            &["gas_price_wei", "integer", "not", "null"],
+            &["bumped_at", "integer", "not", "null"],
+        ];
+        assert_create_table_stm_contains_all_parts(
+            connection.as_ref(),
+            "pending_payable_fee_bump",
+            expected_key_words,
+        );
+        let expected_key_words: &[&[&str]] = &[&["pending_payable_rowid"]];
+        assert_index_stm_is_coupled_with_right_parameter(
+            connection.as_ref(),
+            "pending_payable_fee_bump_rowid_idx",
+            expected_key_words,
+        );
+        TestLogHandler::new().assert_logs_contain_in_order(vec![
+            "DbMigrator: Database successfully migrated from version 12 to 13",
+        ]);
+    }
+}
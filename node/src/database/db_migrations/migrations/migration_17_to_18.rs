@@ -0,0 +1,133 @@
+use crate::database::db_migrations::db_migrator::DatabaseMigration;
+use crate::database::db_migrations::migrator_utils::DBMigDeclarator;
+
+#[allow(non_camel_case_types)]
+pub struct Migrate_17_to_18;
+
+impl DatabaseMigration for Migrate_17_to_18 {
+    fn migrate<'a>(
+        &self,
+        declaration_utils: Box<dyn DBMigDeclarator + 'a>,
+    ) -> rusqlite::Result<()> {
+        let statement_1 = "create table if not exists ledger_adjustment (
+                rowid integer primary key,
+                timestamp integer not null,
+                operator text not null,
+                reason text not null,
+                ledger text not null,
+                kind text not null,
+                wallet_address text not null,
+                balance_before_high_b integer not null,
+                balance_before_low_b integer not null,
+                balance_after_high_b integer not null,
+                balance_after_low_b integer not null
+            ) strict";
+        let statement_2 =
+            "CREATE TRIGGER ledger_adjustment_no_update BEFORE UPDATE ON ledger_adjustment \
+                BEGIN SELECT RAISE(ABORT, 'ledger adjustments are append-only'); END";
+        let statement_3 =
+            "CREATE TRIGGER ledger_adjustment_no_delete BEFORE DELETE ON ledger_adjustment \
+                BEGIN SELECT RAISE(ABORT, 'ledger adjustments are append-only'); END";
+        declaration_utils.execute_upon_transaction(&[&statement_1, &statement_2, &statement_3])
+    }
+
+    fn old_version(&self) -> usize {
+        17
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::db_initializer::{
+        DbInitializationConfig, DbInitializer, DbInitializerReal, DATABASE_FILE,
+    };
+    use crate::test_utils::database_utils::{
+        assert_create_table_stm_contains_all_parts, assert_table_created_as_strict,
+        bring_db_0_back_to_life_and_return_connection, make_external_data, retrieve_config_row,
+    };
+    use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+    use std::fs::create_dir_all;
+
+    #[test]
+    fn migration_from_17_to_18_is_properly_set() {
+        init_test_logging();
+        let dir_path = ensure_node_home_directory_exists(
+            "db_migrations",
+            "migration_from_17_to_18_is_properly_set",
+        );
+        create_dir_all(&dir_path).unwrap();
+        let db_path = dir_path.join(DATABASE_FILE);
+        let _ = bring_db_0_back_to_life_and_return_connection(&db_path);
+        let subject = DbInitializerReal::default();
+
+        let result = subject.initialize_to_version(
+            &dir_path,
+            17,
+            DbInitializationConfig::create_or_migrate(make_external_data()),
+        );
+
+        assert!(result.is_ok());
+
+        let result = subject.initialize_to_version(
+            &dir_path,
+            18,
+            DbInitializationConfig::create_or_migrate(make_external_data()),
+        );
+
+        let connection = result.unwrap();
+        let (cs_value, cs_encrypted) = retrieve_config_row(connection.as_ref(), "schema_version");
+        assert_eq!(cs_value, Some(18.to_string()));
+        assert_eq!(cs_encrypted, false);
+        assert_table_created_as_strict(connection.as_ref(), "ledger_adjustment");
+        let expected_key_words: &[&[&str]] = &[
+            &["rowid", "integer", "primary", "key"],
+            &["timestamp", "integer", "not", "null"],
+            &["operator", "text", "not", "null"],
+            &["reason", "text", "not", "null"],
+            &["ledger", "text", "not", "null"],
+            &["kind", "text", "not", "null"],
+            &["wallet_address", "text", "not", "null"],
+            &["balance_before_high_b", "integer", "not", "null"],
+            &["balance_before_low_b", "integer", "not", "null"],
+            &["balance_after_high_b", "integer", "not", "null"],
+            &["balance_after_low_b", "integer", "not", "null"],
+        ];
+        assert_create_table_stm_contains_all_parts(
+            connection.as_ref(),
+            "ledger_adjustment",
+            expected_key_words,
+        );
+        connection
+            .prepare(
+                "insert into ledger_adjustment (timestamp, operator, reason, ledger, kind, \
+                 wallet_address, balance_before_high_b, balance_before_low_b, \
+                 balance_after_high_b, balance_after_low_b) \
+                 values (1, 'op', 'why', 'payable', 'correction', '0x1234', 0, 5, 0, 3)",
+            )
+            .unwrap()
+            .execute([])
+            .unwrap();
+        let update_error = connection
+            .prepare("update ledger_adjustment set reason = 'because'")
+            .unwrap()
+            .execute([])
+            .unwrap_err();
+        assert_eq!(
+            update_error.to_string(),
+            "ledger adjustments are append-only"
+        );
+        let delete_error = connection
+            .prepare("delete from ledger_adjustment")
+            .unwrap()
+            .execute([])
+            .unwrap_err();
+        assert_eq!(
+            delete_error.to_string(),
+            "ledger adjustments are append-only"
+        );
+        TestLogHandler::new().assert_logs_contain_in_order(vec![
+            "DbMigrator: Database successfully migrated from version 17 to 18",
+        ]);
+    }
+}
@@ -0,0 +1,541 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::apps::app_database_restorer;
+use crate::bootstrapper::RealUser;
+use crate::database::db_backup::{integrity_check, read_backup, write_new_file};
+use crate::database::db_initializer::DATABASE_FILE;
+use crate::database::db_migrations::db_migrator::DbMigratorReal;
+use crate::database::rusqlite_wrappers::{ConnectionWrapper, ConnectionWrapperReal};
+use crate::node_configurator::{
+    data_directory_from_context, port_is_busy, real_user_data_directory_path_and_chain, DirsWrapper,
+};
+use crate::privilege_drop::PrivilegeDropper;
+use crate::run_modes_factories::RestoreDatabaseRunner;
+use crate::sub_lib::utils::make_new_multi_config;
+use clap::value_t;
+use masq_lib::blockchains::chains::Chain;
+use masq_lib::command::StdStreams;
+use masq_lib::constants::CURRENT_SCHEMA_VERSION;
+use masq_lib::multi_config::{CommandLineVcl, EnvironmentVcl, VirtualCommandLine};
+use masq_lib::shared_schema::ConfiguratorError;
+use rusqlite::{Connection, ErrorCode, OpenFlags, OptionalExtension};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub struct RestoreDatabaseRunnerReal {
+    pub(crate) dirs_wrapper: Box<dyn DirsWrapper>,
+    pub(crate) privilege_dropper: Box<dyn PrivilegeDropper>,
+}
+
+impl RestoreDatabaseRunner for RestoreDatabaseRunnerReal {
+    fn go(&self, streams: &mut StdStreams, args: &[String]) -> Result<(), ConfiguratorError> {
+        let (real_user, data_directory, chain, backup_path, password_opt) =
+            distill_args(self.dirs_wrapper.as_ref(), args)?;
+        self.privilege_dropper.drop_privileges(&real_user);
+        let bytes = read_backup(&backup_path, password_opt.as_deref())
+            .map_err(|e| ConfiguratorError::required("restore-database", &e.to_string()))?;
+        let schema_version = install_database(&data_directory, &bytes, chain, streams)
+            .map_err(|e| ConfiguratorError::required("restore-database", &e))?;
+        short_writeln!(
+            streams.stdout,
+            "Database restored from {} into {}",
+            backup_path.display(),
+            data_directory.join(DATABASE_FILE).display()
+        );
+        if schema_version < CURRENT_SCHEMA_VERSION {
+            short_writeln!(
+                streams.stdout,
+                "It has schema version {} and will be migrated to version {} when the Node next starts",
+                schema_version,
+                CURRENT_SCHEMA_VERSION
+            );
+        }
+        Ok(())
+    }
+
+    as_any_ref_in_trait_impl!();
+}
+
+// The backup is written beside the live database and checked there; only once it has passed
+// does it take the database's place, and the database it replaces is kept under another name.
+fn install_database(
+    data_directory: &Path,
+    bytes: &[u8],
+    chain: Chain,
+    streams: &mut StdStreams,
+) -> Result<usize, String> {
+    let database_path = data_directory.join(DATABASE_FILE);
+    check_database_is_not_in_use(&database_path)?;
+    std::fs::create_dir_all(data_directory)
+        .map_err(|e| format!("Could not create {}: {}", data_directory.display(), e))?;
+    let staged_path = data_directory.join(format!("{}.restoring", DATABASE_FILE));
+    let _ = std::fs::remove_file(&staged_path);
+    write_new_file(&staged_path, bytes).map_err(|e| e.to_string())?;
+    let schema_version = match validate_staged_database(&staged_path, chain) {
+        Ok(schema_version) => schema_version,
+        Err(e) => {
+            let _ = std::fs::remove_file(&staged_path);
+            return Err(e);
+        }
+    };
+    if database_path.exists() {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("System clock is before 1970")
+            .as_secs();
+        let kept_path =
+            data_directory.join(format!("{}.before-restore-{}", DATABASE_FILE, seconds));
+        std::fs::rename(&database_path, &kept_path).map_err(|e| {
+            let _ = std::fs::remove_file(&staged_path);
+            format!("Could not move {} aside: {}", database_path.display(), e)
+        })?;
+        short_writeln!(
+            streams.stdout,
+            "Previous database kept as {}",
+            kept_path.display()
+        );
+    }
+    std::fs::rename(&staged_path, &database_path)
+        .map_err(|e| format!("Could not install {}: {}", database_path.display(), e))?;
+    Ok(schema_version)
+}
+
+// A running Node listens on the clandestine port recorded in its database, and one in the middle of
+// a write holds a lock on it. A file that SQLite can't use as a database is in use by no Node.
+fn check_database_is_not_in_use(database_path: &Path) -> Result<(), String> {
+    if !database_path.exists() {
+        return Ok(());
+    }
+    let conn = match Connection::open_with_flags(database_path, OpenFlags::SQLITE_OPEN_READ_WRITE) {
+        Ok(conn) => conn,
+        Err(_) => return Ok(()),
+    };
+    conn.busy_timeout(Duration::from_secs(1))
+        .expect("Could not set busy timeout");
+    if let Err(rusqlite::Error::SqliteFailure(e, _)) =
+        conn.execute_batch("begin exclusive; rollback")
+    {
+        if e.code == ErrorCode::DatabaseBusy || e.code == ErrorCode::DatabaseLocked {
+            return Err(format!(
+                "{} is locked; shut down the Node that is using it before restoring",
+                database_path.display()
+            ));
+        }
+    }
+    let clandestine_port_opt = conn
+        .query_row(
+            "select value from config where name = 'clandestine_port'",
+            [],
+            |row| row.get::<usize, Option<String>>(0),
+        )
+        .ok()
+        .flatten()
+        .and_then(|port_str| port_str.parse::<u16>().ok());
+    match clandestine_port_opt {
+        Some(port) if port_is_busy(port) => Err(format!(
+            "A Node appears to be running on {}: its clandestine port {} is in use. Shut it down \
+             before restoring",
+            database_path.display(),
+            port
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn validate_staged_database(staged_path: &Path, chain: Chain) -> Result<usize, String> {
+    let conn = ConnectionWrapperReal::new(
+        Connection::open_with_flags(staged_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .map_err(|e| format!("Backup cannot be opened as a database: {}", e))?,
+    );
+    let report =
+        integrity_check(&conn).map_err(|e| format!("Backup failed its integrity check: {}", e))?;
+    if report != vec!["ok".to_string()] {
+        return Err(format!(
+            "Backup failed its integrity check: {}",
+            report.join("; ")
+        ));
+    }
+    let read_config = |name: &str| -> Result<Option<String>, String> {
+        conn.prepare("select value from config where name = ?")
+            .and_then(|mut stmt| {
+                stmt.query_row([name], |row| row.get::<usize, String>(0))
+                    .optional()
+            })
+            .map_err(|e| format!("Backup has no usable {}: {}", name, e))
+    };
+    let schema_version_str =
+        read_config("schema_version")?.ok_or_else(|| "Backup has no schema version".to_string())?;
+    let schema_version = schema_version_str
+        .parse::<usize>()
+        .map_err(|_| format!("Backup has a bad schema version: '{}'", schema_version_str))?;
+    if !DbMigratorReal::is_migratable(schema_version) {
+        return Err(format!(
+            "Backup has schema version {}, but this Node can handle only versions up to {}",
+            schema_version, CURRENT_SCHEMA_VERSION
+        ));
+    }
+    // The oldest schemas predate the chain_name row; migration supplies it
+    if let Some(backup_chain) = read_config("chain_name")? {
+        if backup_chain != chain.rec().literal_identifier {
+            return Err(format!(
+                "Backup belongs to chain {}, not {}",
+                backup_chain,
+                chain.rec().literal_identifier
+            ));
+        }
+    }
+    Ok(schema_version)
+}
+
+fn distill_args(
+    dirs_wrapper: &dyn DirsWrapper,
+    args: &[String],
+) -> Result<(RealUser, PathBuf, Chain, PathBuf, Option<String>), ConfiguratorError> {
+    let app = app_database_restorer();
+    let vcls: Vec<Box<dyn VirtualCommandLine>> = vec![
+        Box::new(CommandLineVcl::new(args.to_vec())),
+        Box::new(EnvironmentVcl::new(&app)),
+    ];
+    let multi_config = make_new_multi_config(&app, vcls)?;
+    let (real_user, data_directory_path, chain) =
+        real_user_data_directory_path_and_chain(dirs_wrapper, &multi_config)?;
+    let directory = match data_directory_path {
+        Some(data_dir) => data_dir,
+        None => data_directory_from_context(dirs_wrapper, &real_user, chain),
+    };
+    let backup_path = value_m!(multi_config, "restore-database", PathBuf)
+        .expect("restore-database is required by clap");
+    let password_opt = value_m!(multi_config, "db-password", String);
+    Ok((real_user, directory, chain, backup_path, password_opt))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::db_backup::{DatabaseBackup, DatabaseBackupReal};
+    use crate::database::db_initializer::{
+        DbInitializationConfig, DbInitializer, DbInitializerReal,
+    };
+    use crate::node_configurator::DirsWrapperReal;
+    use crate::server_initializer::test_utils::PrivilegeDropperMock;
+    use crate::test_utils::database_utils::bring_db_0_back_to_life_and_return_connection;
+    use crate::test_utils::ArgsBuilder;
+    use masq_lib::test_utils::fake_stream_holder::FakeStreamHolder;
+    use masq_lib::test_utils::utils::{ensure_node_home_directory_exists, TEST_DEFAULT_CHAIN};
+    use masq_lib::utils::find_free_port;
+    use std::net::{Ipv4Addr, SocketAddr, TcpListener};
+    use std::sync::{Arc, Mutex};
+
+    fn make_backup(home_dir: &Path, marker: &str, password_opt: Option<&str>) -> PathBuf {
+        let source_dir = home_dir.join("source");
+        let conn = DbInitializerReal::default()
+            .initialize(&source_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        conn.prepare("insert into config (name, value, encrypted) values ('restore_marker', ?, 0)")
+            .unwrap()
+            .execute([marker])
+            .unwrap();
+        let backup_path = home_dir.join(format!("{}.backup", marker));
+        DatabaseBackupReal::new(conn)
+            .back_up(&backup_path, password_opt)
+            .unwrap();
+        backup_path
+    }
+
+    fn read_marker(database_path: &Path) -> String {
+        Connection::open(database_path)
+            .unwrap()
+            .query_row(
+                "select value from config where name = 'restore_marker'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap()
+    }
+
+    fn restore_args(
+        data_dir: &Path,
+        backup_path: &Path,
+        password_opt: Option<&str>,
+    ) -> Vec<String> {
+        let builder = ArgsBuilder::new()
+            .param("--restore-database", backup_path.to_str().unwrap())
+            .param("--data-directory", data_dir.to_str().unwrap())
+            .param("--real-user", "123::")
+            .param("--chain", TEST_DEFAULT_CHAIN.rec().literal_identifier);
+        match password_opt {
+            Some(password) => builder.param("--db-password", password),
+            None => builder,
+        }
+        .into()
+    }
+
+    fn make_subject() -> RestoreDatabaseRunnerReal {
+        RestoreDatabaseRunnerReal {
+            dirs_wrapper: Box::new(DirsWrapperReal::default()),
+            privilege_dropper: Box::new(PrivilegeDropperMock::new()),
+        }
+    }
+
+    #[test]
+    fn restore_database_installs_an_encrypted_backup_and_keeps_the_old_database() {
+        let home_dir = ensure_node_home_directory_exists(
+            "db_restorer",
+            "restore_database_installs_an_encrypted_backup_and_keeps_the_old_database",
+        );
+        let backup_path = make_backup(&home_dir, "restored", Some("password"));
+        let data_dir = home_dir.join("data");
+        std::fs::create_dir_all(&data_dir).unwrap();
+        std::fs::write(data_dir.join(DATABASE_FILE), b"old database").unwrap();
+        let drop_privileges_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut subject = make_subject();
+        subject.privilege_dropper = Box::new(
+            PrivilegeDropperMock::new().drop_privileges_params(&drop_privileges_params_arc),
+        );
+        let mut holder = FakeStreamHolder::new();
+
+        let result = subject.go(
+            &mut holder.streams(),
+            &restore_args(&data_dir, &backup_path, Some("password")),
+        );
+
+        assert_eq!(result, Ok(()));
+        let drop_privileges_params = drop_privileges_params_arc.lock().unwrap();
+        assert_eq!(drop_privileges_params.len(), 1);
+        assert_eq!(drop_privileges_params[0].uid_opt, Some(123));
+        assert_eq!(read_marker(&data_dir.join(DATABASE_FILE)), "restored");
+        let kept: Vec<PathBuf> = std::fs::read_dir(&data_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| {
+                path.file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .starts_with("node-data.db.before-restore-")
+            })
+            .collect();
+        assert_eq!(kept.len(), 1);
+        assert_eq!(std::fs::read(&kept[0]).unwrap(), b"old database".to_vec());
+        let stdout = holder.stdout.get_string();
+        assert!(
+            stdout.contains(&format!("Previous database kept as {}", kept[0].display())),
+            "{}",
+            stdout
+        );
+        assert!(stdout.contains(&format!(
+            "Database restored from {} into {}",
+            backup_path.display(),
+            data_dir.join(DATABASE_FILE).display()
+        )));
+        assert!(!data_dir.join("node-data.db.restoring").exists());
+    }
+
+    #[test]
+    fn restore_database_needs_the_password_for_an_encrypted_backup() {
+        let home_dir = ensure_node_home_directory_exists(
+            "db_restorer",
+            "restore_database_needs_the_password_for_an_encrypted_backup",
+        );
+        let backup_path = make_backup(&home_dir, "restored", Some("password"));
+        let data_dir = home_dir.join("data");
+        let mut holder = FakeStreamHolder::new();
+
+        let result = make_subject().go(
+            &mut holder.streams(),
+            &restore_args(&data_dir, &backup_path, Some("wrong")),
+        );
+
+        assert_eq!(
+            result,
+            Err(ConfiguratorError::required(
+                "restore-database",
+                "The password does not decrypt the backup"
+            ))
+        );
+        assert!(!data_dir.join(DATABASE_FILE).exists());
+    }
+
+    #[test]
+    fn restore_database_accepts_an_old_schema_and_says_it_will_be_migrated() {
+        let home_dir = ensure_node_home_directory_exists(
+            "db_restorer",
+            "restore_database_accepts_an_old_schema_and_says_it_will_be_migrated",
+        );
+        let backup_path = home_dir.join("old.db");
+        drop(bring_db_0_back_to_life_and_return_connection(&backup_path));
+        let data_dir = home_dir.join("data");
+        let mut holder = FakeStreamHolder::new();
+
+        let result = make_subject().go(
+            &mut holder.streams(),
+            &restore_args(&data_dir, &backup_path, None),
+        );
+
+        assert_eq!(result, Ok(()));
+        assert!(holder.stdout.get_string().contains(&format!(
+            "It has schema version 0 and will be migrated to version {} when the Node next starts",
+            CURRENT_SCHEMA_VERSION
+        )));
+    }
+
+    #[test]
+    fn restore_database_refuses_a_schema_newer_than_this_node() {
+        let home_dir = ensure_node_home_directory_exists(
+            "db_restorer",
+            "restore_database_refuses_a_schema_newer_than_this_node",
+        );
+        let source_dir = home_dir.join("source");
+        let conn = DbInitializerReal::default()
+            .initialize(&source_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        conn.prepare("update config set value = ? where name = 'schema_version'")
+            .unwrap()
+            .execute([(CURRENT_SCHEMA_VERSION + 1).to_string()])
+            .unwrap();
+        let backup_path = home_dir.join("future.db");
+        DatabaseBackupReal::new(conn)
+            .back_up(&backup_path, None)
+            .unwrap();
+        let data_dir = home_dir.join("data");
+        std::fs::create_dir_all(&data_dir).unwrap();
+        std::fs::write(data_dir.join(DATABASE_FILE), b"old database").unwrap();
+        let mut holder = FakeStreamHolder::new();
+
+        let result = make_subject().go(
+            &mut holder.streams(),
+            &restore_args(&data_dir, &backup_path, None),
+        );
+
+        assert_eq!(
+            result,
+            Err(ConfiguratorError::required(
+                "restore-database",
+                &format!(
+                    "Backup has schema version {}, but this Node can handle only versions up to {}",
+                    CURRENT_SCHEMA_VERSION + 1,
+                    CURRENT_SCHEMA_VERSION
+                )
+            ))
+        );
+        assert_eq!(
+            std::fs::read(data_dir.join(DATABASE_FILE)).unwrap(),
+            b"old database".to_vec()
+        );
+        assert!(!data_dir.join("node-data.db.restoring").exists());
+    }
+
+    #[test]
+    fn restore_database_refuses_a_backup_from_another_chain() {
+        let home_dir = ensure_node_home_directory_exists(
+            "db_restorer",
+            "restore_database_refuses_a_backup_from_another_chain",
+        );
+        let backup_path = make_backup(&home_dir, "restored", None);
+        let data_dir = home_dir.join("data");
+        let mut args = restore_args(&data_dir, &backup_path, None);
+        let chain_index = args.iter().position(|arg| arg == "--chain").unwrap() + 1;
+        args[chain_index] = "polygon-mainnet".to_string();
+        let mut holder = FakeStreamHolder::new();
+
+        let result = make_subject().go(&mut holder.streams(), &args);
+
+        assert_eq!(
+            result,
+            Err(ConfiguratorError::required(
+                "restore-database",
+                &format!(
+                    "Backup belongs to chain {}, not polygon-mainnet",
+                    TEST_DEFAULT_CHAIN.rec().literal_identifier
+                )
+            ))
+        );
+        assert!(!data_dir.join(DATABASE_FILE).exists());
+    }
+
+    fn make_live_database(data_dir: &Path, clandestine_port: u16) {
+        let conn = DbInitializerReal::default()
+            .initialize(data_dir, DbInitializationConfig::test_default())
+            .unwrap();
+        conn.prepare(
+            "insert into config (name, value, encrypted) values ('restore_marker', 'live', 0)",
+        )
+        .unwrap()
+        .execute([])
+        .unwrap();
+        conn.prepare("update config set value = ? where name = 'clandestine_port'")
+            .unwrap()
+            .execute([clandestine_port.to_string()])
+            .unwrap();
+    }
+
+    #[test]
+    fn restore_database_refuses_while_a_node_is_listening_on_the_databases_clandestine_port() {
+        let home_dir = ensure_node_home_directory_exists(
+            "db_restorer",
+            "restore_database_refuses_while_a_node_is_listening_on_the_databases_clandestine_port",
+        );
+        let backup_path = make_backup(&home_dir, "restored", None);
+        let data_dir = home_dir.join("data");
+        let clandestine_port = find_free_port();
+        make_live_database(&data_dir, clandestine_port);
+        let _running_node = TcpListener::bind(SocketAddr::new(
+            Ipv4Addr::UNSPECIFIED.into(),
+            clandestine_port,
+        ))
+        .unwrap();
+        let mut holder = FakeStreamHolder::new();
+
+        let result = make_subject().go(
+            &mut holder.streams(),
+            &restore_args(&data_dir, &backup_path, None),
+        );
+
+        assert_eq!(
+            result,
+            Err(ConfiguratorError::required(
+                "restore-database",
+                &format!(
+                    "A Node appears to be running on {}: its clandestine port {} is in use. Shut \
+                     it down before restoring",
+                    data_dir.join(DATABASE_FILE).display(),
+                    clandestine_port
+                )
+            ))
+        );
+        assert_eq!(read_marker(&data_dir.join(DATABASE_FILE)), "live");
+        assert!(!data_dir.join("node-data.db.restoring").exists());
+    }
+
+    #[test]
+    fn restore_database_refuses_while_the_database_is_locked() {
+        let home_dir = ensure_node_home_directory_exists(
+            "db_restorer",
+            "restore_database_refuses_while_the_database_is_locked",
+        );
+        let backup_path = make_backup(&home_dir, "restored", None);
+        let data_dir = home_dir.join("data");
+        make_live_database(&data_dir, find_free_port());
+        let locking_conn = Connection::open(data_dir.join(DATABASE_FILE)).unwrap();
+        locking_conn.execute_batch("begin exclusive").unwrap();
+        let mut holder = FakeStreamHolder::new();
+
+        let result = make_subject().go(
+            &mut holder.streams(),
+            &restore_args(&data_dir, &backup_path, None),
+        );
+
+        assert_eq!(
+            result,
+            Err(ConfiguratorError::required(
+                "restore-database",
+                &format!(
+                    "{} is locked; shut down the Node that is using it before restoring",
+                    data_dir.join(DATABASE_FILE).display()
+                )
+            ))
+        );
+        locking_conn.execute_batch("rollback").unwrap();
+        assert_eq!(read_marker(&data_dir.join(DATABASE_FILE)), "live");
+        assert!(!data_dir.join("node-data.db.restoring").exists());
+    }
+}
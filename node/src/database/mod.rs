@@ -1,7 +1,9 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
 pub mod config_dumper;
+pub mod db_backup;
 pub mod db_initializer;
 pub mod db_migrations;
+pub mod db_restorer;
 pub mod rusqlite_wrappers;
 pub mod test_utils;
@@ -2,8 +2,9 @@
 
 use crate::arbitrary_id_stamp_in_trait;
 use crate::masq_lib::utils::ExpectValue;
-use rusqlite::{Connection, Error, Statement, ToSql, Transaction};
+use rusqlite::{Connection, DatabaseName, Error, Statement, ToSql, Transaction};
 use std::fmt::Debug;
+use std::path::Path;
 
 // We were challenged multiple times to device mocks for testing stubborn, hard to tame, data
 // structures from the 'rusqlite' library. After all, we've adopted two of them, the Connection,
@@ -52,6 +53,9 @@ use std::fmt::Debug;
 pub trait ConnectionWrapper: Debug + Send {
     fn prepare(&self, query: &str) -> Result<Statement, rusqlite::Error>;
     fn transaction(&mut self) -> Result<TransactionSafeWrapper, rusqlite::Error>;
+    // Writes a consistent snapshot of the main database into a new file, even while other
+    // connections keep using it
+    fn backup(&self, destination: &Path) -> Result<(), rusqlite::Error>;
 }
 
 #[derive(Debug)]
@@ -66,6 +70,9 @@ impl ConnectionWrapper for ConnectionWrapperReal {
     fn transaction(&mut self) -> Result<TransactionSafeWrapper, Error> {
         self.conn.transaction().map(TransactionSafeWrapper::new)
     }
+    fn backup(&self, destination: &Path) -> Result<(), Error> {
+        self.conn.backup(DatabaseName::Main, destination, None)
+    }
 }
 
 impl ConnectionWrapperReal {
@@ -4,7 +4,10 @@ use crate::database::db_initializer::DbInitializerReal;
 use crate::database::rusqlite_wrappers::TransactionSafeWrapper;
 use crate::db_config::config_dao::{ConfigDao, ConfigDaoError, ConfigDaoRecord};
 use crate::neighborhood::DEFAULT_MIN_HOPS;
-use crate::sub_lib::accountant::{DEFAULT_PAYMENT_THRESHOLDS, DEFAULT_SCAN_INTERVALS};
+use crate::sub_lib::accountant::{
+    DEFAULT_PAYMENT_THRESHOLDS, DEFAULT_SCAN_INTERVALS, DEFAULT_SPENDING_BUDGET,
+};
+use crate::sub_lib::bandwidth_shaper::{DEFAULT_BANDWIDTH_LIMITS, DEFAULT_DATA_QUOTA};
 use crate::sub_lib::neighborhood::DEFAULT_RATE_PACK;
 use itertools::Itertools;
 use masq_lib::blockchains::chains::Chain;
@@ -119,6 +122,7 @@ impl Default for ConfigDaoNull {
         );
         data.insert("blockchain_service_url".to_string(), (None, false));
         data.insert("past_neighbors".to_string(), (None, true));
+        data.insert("neighborhood_snapshot".to_string(), (None, true));
         data.insert("mapping_protocol".to_string(), (None, false));
         data.insert(
             "min_hops".to_string(),
@@ -142,6 +146,19 @@ impl Default for ConfigDaoNull {
             (Some(DEFAULT_SCAN_INTERVALS.to_string()), false),
         );
         data.insert("max_block_count".to_string(), (None, false));
+        data.insert(
+            "bandwidth_limits".to_string(),
+            (Some(DEFAULT_BANDWIDTH_LIMITS.to_string()), false),
+        );
+        data.insert(
+            "data_quota".to_string(),
+            (Some(DEFAULT_DATA_QUOTA.to_string()), false),
+        );
+        data.insert("data_usage".to_string(), (None, false));
+        data.insert(
+            "spending_budget".to_string(),
+            (Some(DEFAULT_SPENDING_BUDGET.to_string()), false),
+        );
         Self { data }
     }
 }
@@ -212,6 +229,18 @@ mod tests {
                 false
             )
         );
+        assert_eq!(
+            subject.get("bandwidth_limits").unwrap(),
+            ConfigDaoRecord::new("bandwidth_limits", Some("0|0|0|0|0"), false)
+        );
+        assert_eq!(
+            subject.get("data_quota").unwrap(),
+            ConfigDaoRecord::new("data_quota", Some("0|0"), false)
+        );
+        assert_eq!(
+            subject.get("spending_budget").unwrap(),
+            ConfigDaoRecord::new("spending_budget", Some("0|0|80|95"), false)
+        );
         assert_eq!(
             subject.get("start_block").unwrap(),
             ConfigDaoRecord::new(
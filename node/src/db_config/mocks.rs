@@ -50,6 +50,12 @@ impl ConfigDao for ConfigDaoMock {
     }
 }
 
+impl Default for ConfigDaoMock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ConfigDaoMock {
     pub fn new() -> Self {
         Self {
@@ -10,7 +10,9 @@ use crate::db_config::typed_config_layer::{
     decode_bytes, decode_combined_params, decode_u64, encode_bytes, encode_u64,
     TypedConfigLayerError,
 };
-use crate::sub_lib::accountant::{PaymentThresholds, ScanIntervals};
+use crate::neighborhood::gossip::GossipNodeRecord;
+use crate::sub_lib::accountant::{PaymentThresholds, ScanIntervals, SpendingBudget};
+use crate::sub_lib::bandwidth_shaper::{BandwidthLimits, DataQuota, DataUsage};
 use crate::sub_lib::cryptde::PlainData;
 use crate::sub_lib::neighborhood::{Hops, NodeDescriptor, RatePack};
 use crate::sub_lib::wallet::Wallet;
@@ -131,6 +133,15 @@ pub trait PersistentConfiguration {
         node_descriptors_opt: Option<Vec<NodeDescriptor>>,
         db_password: &str,
     ) -> Result<(), PersistentConfigError>;
+    fn neighborhood_snapshot(
+        &self,
+        db_password: &str,
+    ) -> Result<Option<Vec<GossipNodeRecord>>, PersistentConfigError>;
+    fn set_neighborhood_snapshot(
+        &mut self,
+        node_records_opt: Option<Vec<GossipNodeRecord>>,
+        db_password: &str,
+    ) -> Result<(), PersistentConfigError>;
     fn start_block(&self) -> Result<Option<u64>, PersistentConfigError>;
     fn set_start_block(&mut self, value_opt: Option<u64>) -> Result<(), PersistentConfigError>;
     fn max_block_count(&self) -> Result<Option<u64>, PersistentConfigError>;
@@ -152,6 +163,14 @@ pub trait PersistentConfiguration {
     fn set_rate_pack(&mut self, rate_pack: String) -> Result<(), PersistentConfigError>;
     fn scan_intervals(&self) -> Result<ScanIntervals, PersistentConfigError>;
     fn set_scan_intervals(&mut self, intervals: String) -> Result<(), PersistentConfigError>;
+    fn bandwidth_limits(&self) -> Result<BandwidthLimits, PersistentConfigError>;
+    fn set_bandwidth_limits(&mut self, limits: String) -> Result<(), PersistentConfigError>;
+    fn data_quota(&self) -> Result<DataQuota, PersistentConfigError>;
+    fn set_data_quota(&mut self, quota: String) -> Result<(), PersistentConfigError>;
+    fn data_usage(&self) -> Result<Option<DataUsage>, PersistentConfigError>;
+    fn set_data_usage(&mut self, usage: DataUsage) -> Result<(), PersistentConfigError>;
+    fn spending_budget(&self) -> Result<SpendingBudget, PersistentConfigError>;
+    fn set_spending_budget(&mut self, budget: String) -> Result<(), PersistentConfigError>;
 
     arbitrary_id_stamp_in_trait!();
 }
@@ -406,6 +425,47 @@ impl PersistentConfiguration for PersistentConfigurationReal {
         )?)
     }
 
+    fn neighborhood_snapshot(
+        &self,
+        db_password: &str,
+    ) -> Result<Option<Vec<GossipNodeRecord>>, PersistentConfigError> {
+        let bytes_opt = decode_bytes(self.scl.decrypt(
+            self.get_record("neighborhood_snapshot")?,
+            Some(db_password.to_string()),
+            &self.dao,
+        )?)?;
+        match bytes_opt {
+            None => Ok(None),
+            Some(bytes) => serde_cbor::de::from_slice::<Vec<GossipNodeRecord>>(bytes.as_slice())
+                .map(Some)
+                .map_err(|e| {
+                    PersistentConfigError::UninterpretableValue(format!(
+                        "Neighborhood snapshot is corrupt: {}",
+                        e
+                    ))
+                }),
+        }
+    }
+
+    fn set_neighborhood_snapshot(
+        &mut self,
+        node_records_opt: Option<Vec<GossipNodeRecord>>,
+        db_password: &str,
+    ) -> Result<(), PersistentConfigError> {
+        let plain_data_opt = node_records_opt.map(|node_records| {
+            PlainData::new(&serde_cbor::ser::to_vec(&node_records).expect("Serialization failed"))
+        });
+        Ok(self.dao.set(
+            "neighborhood_snapshot",
+            self.scl.encrypt(
+                "neighborhood_snapshot",
+                encode_bytes(plain_data_opt)?,
+                Some(db_password.to_string()),
+                &self.dao,
+            )?,
+        )?)
+    }
+
     fn start_block(&self) -> Result<Option<u64>, PersistentConfigError> {
         Ok(decode_u64(self.get("start_block")?)?)
     }
@@ -504,6 +564,48 @@ impl PersistentConfiguration for PersistentConfigurationReal {
     fn set_scan_intervals(&mut self, intervals: String) -> Result<(), PersistentConfigError> {
         self.simple_set_method("scan_intervals", intervals)
     }
+
+    fn bandwidth_limits(&self) -> Result<BandwidthLimits, PersistentConfigError> {
+        self.combined_params_get_method(
+            |str: &str| BandwidthLimits::try_from(str),
+            "bandwidth_limits",
+        )
+    }
+
+    fn set_bandwidth_limits(&mut self, limits: String) -> Result<(), PersistentConfigError> {
+        self.simple_set_method("bandwidth_limits", limits)
+    }
+
+    fn data_quota(&self) -> Result<DataQuota, PersistentConfigError> {
+        self.combined_params_get_method(|str: &str| DataQuota::try_from(str), "data_quota")
+    }
+
+    fn set_data_quota(&mut self, quota: String) -> Result<(), PersistentConfigError> {
+        self.simple_set_method("data_quota", quota)
+    }
+
+    fn data_usage(&self) -> Result<Option<DataUsage>, PersistentConfigError> {
+        match self.get("data_usage")?.map(|val| DataUsage::from_str(&val)) {
+            None => Ok(None),
+            Some(Ok(usage)) => Ok(Some(usage)),
+            Some(Err(msg)) => Err(PersistentConfigError::DatabaseError(msg)),
+        }
+    }
+
+    fn set_data_usage(&mut self, usage: DataUsage) -> Result<(), PersistentConfigError> {
+        self.simple_set_method("data_usage", usage)
+    }
+
+    fn spending_budget(&self) -> Result<SpendingBudget, PersistentConfigError> {
+        self.combined_params_get_method(
+            |str: &str| SpendingBudget::try_from(str),
+            "spending_budget",
+        )
+    }
+
+    fn set_spending_budget(&mut self, budget: String) -> Result<(), PersistentConfigError> {
+        self.simple_set_method("spending_budget", budget)
+    }
 }
 
 impl From<Box<dyn ConnectionWrapper>> for PersistentConfigurationReal {
@@ -612,6 +714,7 @@ mod tests {
     use crate::db_config::mocks::ConfigDaoMock;
     use crate::db_config::secure_config_layer::EXAMPLE_ENCRYPTED;
     use crate::test_utils::main_cryptde;
+    use crate::test_utils::neighborhood_test_utils::{db_from_node, make_node_record};
     use crate::test_utils::unshared_test_utils::arbitrary_id_stamp::ArbitraryIdStamp;
     use bip39::{Language, MnemonicType};
     use lazy_static::lazy_static;
@@ -621,7 +724,7 @@ mod tests {
     use std::convert::TryFrom;
     use std::net::SocketAddr;
     use std::sync::{Arc, Mutex};
-    use std::time::Duration;
+    use std::time::{Duration, SystemTime};
     use tiny_hderive::bip32::ExtendedPrivKey;
 
     lazy_static! {
@@ -1711,6 +1814,138 @@ mod tests {
         assert_eq!(set_params.len(), 1);
     }
 
+    fn make_snapshot_records() -> Vec<GossipNodeRecord> {
+        let root = make_node_record(1234, true);
+        let one = make_node_record(2345, true);
+        let another = make_node_record(3456, false);
+        let mut db = db_from_node(&root);
+        db.add_node(one.clone()).unwrap();
+        db.add_node(another.clone()).unwrap();
+        db.add_arbitrary_full_neighbor(one.public_key(), another.public_key());
+        vec![
+            GossipNodeRecord::from((&db, one.public_key(), true)),
+            GossipNodeRecord::from((&db, another.public_key(), true)),
+        ]
+    }
+
+    #[test]
+    fn neighborhood_snapshot_success() {
+        let example = "Aside from that, Mrs. Lincoln, how was the play?".as_bytes();
+        let example_encrypted = Bip39::encrypt_bytes(&example, "password").unwrap();
+        let node_records = make_snapshot_records();
+        let node_records_bytes = PlainData::new(&serde_cbor::ser::to_vec(&node_records).unwrap());
+        let node_records_string = encode_bytes(Some(node_records_bytes)).unwrap().unwrap();
+        let node_records_enc =
+            Bip39::encrypt_bytes(&node_records_string.as_bytes(), "password").unwrap();
+        let get_params_arc = Arc::new(Mutex::new(vec![]));
+        let config_dao = Box::new(
+            ConfigDaoMock::new()
+                .get_params(&get_params_arc)
+                .get_result(Ok(ConfigDaoRecord::new(
+                    "neighborhood_snapshot",
+                    Some(&node_records_enc),
+                    true,
+                )))
+                .get_result(Ok(ConfigDaoRecord::new(
+                    EXAMPLE_ENCRYPTED,
+                    Some(&example_encrypted),
+                    true,
+                ))),
+        );
+        let subject = PersistentConfigurationReal::new(config_dao);
+
+        let result = subject.neighborhood_snapshot("password").unwrap();
+
+        assert_eq!(result, Some(node_records));
+        let get_params = get_params_arc.lock().unwrap();
+        assert_eq!(
+            *get_params,
+            vec![
+                "neighborhood_snapshot".to_string(),
+                EXAMPLE_ENCRYPTED.to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn neighborhood_snapshot_reports_corruption_instead_of_panicking() {
+        let example = "Aside from that, Mrs. Lincoln, how was the play?".as_bytes();
+        let example_encrypted = Bip39::encrypt_bytes(&example, "password").unwrap();
+        let garbage_string = encode_bytes(Some(PlainData::new(&[0xFF, 0xFE, 0xFD])))
+            .unwrap()
+            .unwrap();
+        let garbage_enc = Bip39::encrypt_bytes(&garbage_string.as_bytes(), "password").unwrap();
+        let config_dao = Box::new(
+            ConfigDaoMock::new()
+                .get_result(Ok(ConfigDaoRecord::new(
+                    "neighborhood_snapshot",
+                    Some(&garbage_enc),
+                    true,
+                )))
+                .get_result(Ok(ConfigDaoRecord::new(
+                    EXAMPLE_ENCRYPTED,
+                    Some(&example_encrypted),
+                    true,
+                ))),
+        );
+        let subject = PersistentConfigurationReal::new(config_dao);
+
+        let result = subject.neighborhood_snapshot("password");
+
+        match result {
+            Err(PersistentConfigError::UninterpretableValue(msg)) => assert!(
+                msg.starts_with("Neighborhood snapshot is corrupt: "),
+                "{}",
+                msg
+            ),
+            x => panic!("Expected UninterpretableValue, got {:?}", x),
+        }
+    }
+
+    #[test]
+    fn set_neighborhood_snapshot_success() {
+        let example = "Aside from that, Mrs. Lincoln, how was the play?".as_bytes();
+        let example_encrypted = Bip39::encrypt_bytes(&example, "password").unwrap();
+        let node_records = make_snapshot_records();
+        let set_params_arc = Arc::new(Mutex::new(vec![]));
+        let config_dao = Box::new(
+            ConfigDaoMock::new()
+                .get_result(Ok(ConfigDaoRecord::new(
+                    EXAMPLE_ENCRYPTED,
+                    Some(&example_encrypted),
+                    true,
+                )))
+                .get_result(Ok(ConfigDaoRecord::new(
+                    "neighborhood_snapshot",
+                    Some("irrelevant"),
+                    true,
+                )))
+                .set_params(&set_params_arc)
+                .set_result(Ok(())),
+        );
+        let mut subject = PersistentConfigurationReal::new(config_dao);
+
+        subject
+            .set_neighborhood_snapshot(Some(node_records.clone()), "password")
+            .unwrap();
+
+        let set_params = set_params_arc.lock().unwrap();
+        assert_eq!(set_params[0].0, "neighborhood_snapshot".to_string());
+        let encrypted_serialized_records = set_params[0].1.clone().unwrap();
+        let encoded_serialized_records =
+            Bip39::decrypt_bytes(&encrypted_serialized_records, "password").unwrap();
+        let serialized_records = decode_bytes(Some(
+            String::from_utf8(encoded_serialized_records.into()).unwrap(),
+        ))
+        .unwrap()
+        .unwrap();
+        let actual_records =
+            serde_cbor::de::from_slice::<Vec<GossipNodeRecord>>(&serialized_records.as_slice())
+                .unwrap();
+        assert_eq!(actual_records, node_records);
+        assert_eq!(set_params.len(), 1);
+    }
+
     #[test]
     fn mapping_protocol_works() {
         let get_params_arc = Arc::new(Mutex::new(vec![]));
@@ -1972,6 +2207,141 @@ mod tests {
         getter_method_plain_data_does_not_tolerate_none_value!("scan_intervals");
     }
 
+    #[test]
+    fn bandwidth_limits_get_method_works() {
+        persistent_config_plain_data_assertions_for_simple_get_method!(
+            "bandwidth_limits",
+            "100|200|300|400|50",
+            BandwidthLimits {
+                upload_kb_per_sec: 100,
+                download_kb_per_sec: 200,
+                relay_kb_per_sec: 300,
+                exit_kb_per_sec: 400,
+                per_neighbor_kb_per_sec: 50,
+            }
+        );
+    }
+
+    #[test]
+    fn bandwidth_limits_set_method_works() {
+        persistent_config_plain_data_assertions_for_simple_set_method!(
+            "bandwidth_limits",
+            "100|200|300|400|50".to_string()
+        );
+    }
+
+    #[test]
+    fn data_quota_get_method_works() {
+        persistent_config_plain_data_assertions_for_simple_get_method!(
+            "data_quota",
+            "1000|20000",
+            DataQuota {
+                daily_mb: 1000,
+                monthly_mb: 20000,
+            }
+        );
+    }
+
+    #[test]
+    fn data_quota_set_method_works() {
+        persistent_config_plain_data_assertions_for_simple_set_method!(
+            "data_quota",
+            "1000|20000".to_string()
+        );
+    }
+
+    #[test]
+    fn data_usage_get_method_works() {
+        let get_params_arc = Arc::new(Mutex::new(vec![]));
+        let config_dao = ConfigDaoMock::new()
+            .get_params(&get_params_arc)
+            .get_result(Ok(ConfigDaoRecord::new(
+                "data_usage",
+                Some("1700000000|1234|56789"),
+                false,
+            )));
+        let subject = PersistentConfigurationReal::new(Box::new(config_dao));
+
+        let result = subject.data_usage();
+
+        assert_eq!(
+            result,
+            Ok(Some(DataUsage {
+                as_of: SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+                daily_bytes: 1_234,
+                monthly_bytes: 56_789,
+            }))
+        );
+        let get_params = get_params_arc.lock().unwrap();
+        assert_eq!(*get_params, vec!["data_usage".to_string()]);
+    }
+
+    #[test]
+    fn data_usage_get_method_handles_absent_and_bad_values() {
+        let config_dao = ConfigDaoMock::new()
+            .get_result(Ok(ConfigDaoRecord::new("data_usage", None, false)))
+            .get_result(Ok(ConfigDaoRecord::new("data_usage", Some("booga"), false)));
+        let subject = PersistentConfigurationReal::new(Box::new(config_dao));
+
+        let absent = subject.data_usage();
+        let bad = subject.data_usage();
+
+        assert_eq!(absent, Ok(None));
+        assert_eq!(
+            bad,
+            Err(PersistentConfigError::DatabaseError(
+                "Data usage should be three numbers separated by '|', not 'booga'".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn data_usage_set_method_works() {
+        let set_params_arc = Arc::new(Mutex::new(vec![]));
+        let config_dao = ConfigDaoMock::new()
+            .set_params(&set_params_arc)
+            .set_result(Ok(()));
+        let mut subject = PersistentConfigurationReal::new(Box::new(config_dao));
+
+        let result = subject.set_data_usage(DataUsage {
+            as_of: SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+            daily_bytes: 1_234,
+            monthly_bytes: 56_789,
+        });
+
+        assert_eq!(result, Ok(()));
+        let set_params = set_params_arc.lock().unwrap();
+        assert_eq!(
+            *set_params,
+            vec![(
+                "data_usage".to_string(),
+                Some("1700000000|1234|56789".to_string())
+            )]
+        );
+    }
+
+    #[test]
+    fn spending_budget_get_method_works() {
+        persistent_config_plain_data_assertions_for_simple_get_method!(
+            "spending_budget",
+            "5000000|90000000|70|90",
+            SpendingBudget {
+                daily_gwei: 5_000_000,
+                monthly_gwei: 90_000_000,
+                first_warning_percent: 70,
+                second_warning_percent: 90,
+            }
+        );
+    }
+
+    #[test]
+    fn spending_budget_set_method_works() {
+        persistent_config_plain_data_assertions_for_simple_set_method!(
+            "spending_budget",
+            "5000000|90000000|70|90".to_string()
+        );
+    }
+
     #[test]
     fn payment_thresholds_get_method_works() {
         persistent_config_plain_data_assertions_for_simple_get_method!(
@@ -398,6 +398,7 @@ mod tests {
             last_data: false,
             sequence_number: Some(0),
             data: data.clone(),
+            relayed: false,
         };
 
         subject_obcd.try_send(obcd).unwrap();
@@ -422,6 +423,7 @@ mod tests {
             last_data: false,
             sequence_number: None,
             data: data.clone(),
+            relayed: false,
         };
         let mut peer_actors = peer_actors_builder().build();
         peer_actors.dispatcher = Dispatcher::make_subs_from(&subject_addr);
@@ -116,6 +116,7 @@ impl ConsumingService {
             last_data: false, // Hopper-to-Hopper clandestine streams are never remotely killed
             data: encrypted_package.into(),
             sequence_number: None,
+            relayed: false,
         };
 
         debug!(
@@ -182,6 +183,7 @@ mod tests {
                 last_data: false,
                 sequence_number: None,
                 data: encodex(main_cryptde(), &target_key, &lcp).unwrap().into(),
+                relayed: false,
             },
             transmit_data_msg
         );
@@ -254,6 +256,7 @@ mod tests {
                 last_data: false,
                 sequence_number: None,
                 data: expected_lcp_enc.into(),
+                relayed: false,
             },
             *record,
         );
@@ -2,7 +2,7 @@
 use super::live_cores_package::LiveCoresPackage;
 use crate::blockchain::payer::Payer;
 use crate::bootstrapper::CryptDEPair;
-use crate::neighborhood::gossip::Gossip_0v1;
+use crate::neighborhood::gossip::Gossip_0v2;
 use crate::sub_lib::accountant::ReportRoutingServiceProvidedMessage;
 use crate::sub_lib::cryptde::{decodex, encodex, CryptData, CryptdecError};
 use crate::sub_lib::dispatcher::{Component, Endpoint, InboundClientData};
@@ -10,12 +10,13 @@ use crate::sub_lib::hop::LiveHop;
 use crate::sub_lib::hopper::{ExpiredCoresPackage, HopperSubs, MessageType};
 use crate::sub_lib::neighborhood::{GossipFailure_0v1, NeighborhoodSubs};
 use crate::sub_lib::proxy_client::{
-    ClientResponsePayload_0v1, DnsResolveFailure_0v1, ProxyClientSubs,
+    ClientResponsePayload_0v2, DnsResolveFailure_0v1, ProxyClientSubs,
 };
-use crate::sub_lib::proxy_server::{ClientRequestPayload_0v1, ProxyServerSubs};
+use crate::sub_lib::proxy_server::{ClientRequestPayload_0v2, ProxyServerSubs};
 use crate::sub_lib::stream_handler_pool::TransmitDataMsg;
 use actix::Recipient;
 use masq_lib::logger::Logger;
+use masq_lib::redaction::MarkSensitive;
 use std::borrow::Borrow;
 use std::convert::TryFrom;
 use std::net::SocketAddr;
@@ -63,7 +64,7 @@ impl RoutingService {
             self.logger,
             "Instructed to route {} bytes of InboundClientData ({}) from Dispatcher",
             data_size,
-            ibcd.peer_addr
+            ibcd.peer_addr.sensitive()
         );
         let peer_addr = ibcd.peer_addr;
         let last_data = ibcd.last_data;
@@ -79,7 +80,7 @@ impl RoutingService {
                     self.logger,
                     "Couldn't decode CORES package in {}-byte buffer from {}: {:?}",
                     ibcd.data.len(),
-                    ibcd.peer_addr,
+                    ibcd.peer_addr.sensitive(),
                     e
                 );
                 return;
@@ -113,7 +114,7 @@ impl RoutingService {
                 self.logger,
                 "Routing LiveCoresPackage with {}-byte payload to {}",
                 live_package.payload.len(),
-                next_hop.public_key
+                next_hop.public_key.sensitive()
             );
             self.route_data_externally(live_package, next_hop.payer, last_data);
         } else {
@@ -268,7 +269,7 @@ impl RoutingService {
                             return;
                         }
                     };
-                    let client_request = match ClientRequestPayload_0v1::try_from(vd) {
+                    let client_request = match ClientRequestPayload_0v2::try_from(vd) {
                         Ok(crp) => crp,
                         Err(e) => {
                             error!(
@@ -302,7 +303,7 @@ impl RoutingService {
                 }
             }
             (Component::ProxyServer, MessageType::ClientResponse(vd)) => {
-                let client_response = match ClientResponsePayload_0v1::try_from(vd) {
+                let client_response = match ClientResponsePayload_0v2::try_from(vd) {
                     Ok(crp) => crp,
                     Err(e) => {
                         error!(
@@ -348,7 +349,7 @@ impl RoutingService {
                     .expect("ProxyServer is dead")
             }
             (Component::Neighborhood, MessageType::Gossip(vd)) => {
-                let gossip = match Gossip_0v1::try_from(vd) {
+                let gossip = match Gossip_0v2::try_from(vd) {
                     Ok(g) => g,
                     Err(e) => {
                         error!(self.logger, "Received unmigratable Gossip: {:?}", e);
@@ -493,6 +494,7 @@ impl RoutingService {
             last_data,
             data: next_live_package_enc.into(),
             sequence_number: None,
+            relayed: true,
         })
     }
 }
@@ -502,7 +504,7 @@ mod tests {
     use super::*;
     use crate::accountant::db_access_objects::banned_dao::BAN_CACHE;
     use crate::bootstrapper::Bootstrapper;
-    use crate::neighborhood::gossip::{GossipBuilder, Gossip_0v1};
+    use crate::neighborhood::gossip::{GossipBuilder, Gossip_0v2};
     use crate::node_test_utils::check_timestamp;
     use crate::sub_lib::accountant::ReportRoutingServiceProvidedMessage;
     use crate::sub_lib::cryptde::{encodex, CryptDE, PlainData, PublicKey};
@@ -511,8 +513,8 @@ mod tests {
     use crate::sub_lib::hopper::{IncipientCoresPackage, MessageType, MessageType::ClientRequest};
     use crate::sub_lib::neighborhood::GossipFailure_0v1;
     use crate::sub_lib::peer_actors::PeerActors;
-    use crate::sub_lib::proxy_client::{ClientResponsePayload_0v1, DnsResolveFailure_0v1};
-    use crate::sub_lib::proxy_server::{ClientRequestPayload_0v1, ProxyProtocol};
+    use crate::sub_lib::proxy_client::{ClientResponsePayload_0v2, DnsResolveFailure_0v1};
+    use crate::sub_lib::proxy_server::{ClientRequestPayload_0v2, ProxyProtocol};
     use crate::sub_lib::route::{Route, RouteSegment};
     use crate::sub_lib::sequence_buffer::SequencedPacket;
     use crate::sub_lib::stream_key::StreamKey;
@@ -789,7 +791,7 @@ mod tests {
         system.run();
         let component_recording = component_recording_arc.lock().unwrap();
         let record =
-            component_recording.get_record::<ExpiredCoresPackage<ClientRequestPayload_0v1>>(0);
+            component_recording.get_record::<ExpiredCoresPackage<ClientRequestPayload_0v2>>(0);
         let expected_ecp = lcp_a
             .to_expired(
                 SocketAddr::from_str("1.2.3.4:5678").unwrap(),
@@ -921,7 +923,7 @@ mod tests {
         system.run();
         let proxy_server_recording = proxy_server_recording_arc.lock().unwrap();
         let record =
-            proxy_server_recording.get_record::<ExpiredCoresPackage<ClientResponsePayload_0v1>>(0);
+            proxy_server_recording.get_record::<ExpiredCoresPackage<ClientResponsePayload_0v2>>(0);
         let expected_ecp = lcp_a
             .to_expired(
                 SocketAddr::from_str("1.3.2.4:5678").unwrap(),
@@ -1001,7 +1003,7 @@ mod tests {
         System::current().stop();
         system.run();
         let component_recording = component_recording_arc.lock().unwrap();
-        let record = component_recording.get_record::<ExpiredCoresPackage<Gossip_0v1>>(0);
+        let record = component_recording.get_record::<ExpiredCoresPackage<Gossip_0v2>>(0);
         let expected_ecp = lcp_a
             .to_expired(
                 SocketAddr::from_str("1.3.2.4:5678").unwrap(),
@@ -1173,6 +1175,7 @@ mod tests {
                 last_data: true,
                 sequence_number: None,
                 data: expected_lcp_enc.into(),
+                relayed: true,
             }
         );
         let accountant_recording = accountant_recording_arc.lock().unwrap();
@@ -1902,13 +1905,14 @@ mod tests {
                 cryptdes.main.public_key(),
                 &MessageType::ClientRequest(VersionedData::new(
                     &crate::sub_lib::migrations::client_request_payload::MIGRATIONS,
-                    &ClientRequestPayload_0v1 {
+                    &ClientRequestPayload_0v2 {
                         stream_key: StreamKey::make_meaningless_stream_key(),
                         sequenced_packet: SequencedPacket::new(vec![1, 2, 3, 4], 1234, false),
                         target_hostname: Some("hostname".to_string()),
                         target_port: 1234,
                         protocol: ProxyProtocol::TLS,
                         originator_public_key: PublicKey::new(b"1234"),
+                        next_expected_sequence_opt: None,
                     },
                 )),
             )
@@ -1968,9 +1972,10 @@ mod tests {
                 cryptdes.main.public_key(),
                 &MessageType::ClientResponse(VersionedData::new(
                     &crate::sub_lib::migrations::client_request_payload::MIGRATIONS,
-                    &ClientResponsePayload_0v1 {
+                    &ClientResponsePayload_0v2 {
                         stream_key: StreamKey::make_meaningless_stream_key(),
                         sequenced_packet: SequencedPacket::new(vec![1, 2, 3, 4], 1234, false),
+                        next_expected_sequence_opt: None,
                     },
                 )),
             )
@@ -2017,7 +2022,7 @@ mod tests {
         let neighborhood_recording = neighborhood_recording_arc.lock().unwrap();
         assert_eq!(neighborhood_recording.len(), 0);
         TestLogHandler::new().exists_log_containing(
-            "ERROR: RoutingService: Received unmigratable Gossip: MigrationNotFound(DataVersion { major: 0, minor: 0 }, DataVersion { major: 0, minor: 1 })",
+            "ERROR: RoutingService: Received unmigratable Gossip: MigrationNotFound(DataVersion { major: 0, minor: 0 }, DataVersion { major: 0, minor: 2 })",
         );
     }
 
@@ -2056,7 +2061,7 @@ mod tests {
         let proxy_client_recording = proxy_client_recording_arc.lock().unwrap();
         assert_eq!(proxy_client_recording.len(), 0);
         TestLogHandler::new().exists_log_containing(
-            "ERROR: RoutingService: Received unmigratable ClientRequestPayload: MigrationNotFound(DataVersion { major: 0, minor: 0 }, DataVersion { major: 0, minor: 1 })",
+            "ERROR: RoutingService: Received unmigratable ClientRequestPayload: MigrationNotFound(DataVersion { major: 0, minor: 0 }, DataVersion { major: 0, minor: 2 })",
         );
     }
 
@@ -2095,7 +2100,7 @@ mod tests {
         let proxy_server_recording = proxy_server_recording_arc.lock().unwrap();
         assert_eq!(proxy_server_recording.len(), 0);
         TestLogHandler::new().exists_log_containing(
-            "ERROR: RoutingService: Received unmigratable ClientResponsePayload: MigrationNotFound(DataVersion { major: 0, minor: 0 }, DataVersion { major: 0, minor: 1 })",
+            "ERROR: RoutingService: Received unmigratable ClientResponsePayload: MigrationNotFound(DataVersion { major: 0, minor: 0 }, DataVersion { major: 0, minor: 2 })",
         );
     }
 
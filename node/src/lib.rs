@@ -46,3 +46,6 @@ mod stream_writer_unsorted;
 pub mod test_utils; //TODO we should make some effort for collections of testing utils to be really test conditioned.
 pub mod tls_discriminator_factory;
 pub mod ui_gateway;
+pub mod websocket_discriminator_factory;
+pub mod websocket_framer;
+pub mod websocket_masquerader;
@@ -1,19 +1,26 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 use crate::bootstrapper::PortConfiguration;
 use crate::stream_messages::AddStreamMsg;
-use crate::sub_lib::stream_connector::StreamConnector;
+use crate::sub_lib::clandestine_tls::ClandestineTls;
 use crate::sub_lib::stream_connector::StreamConnectorReal;
+use crate::sub_lib::stream_connector::{split_tls_stream, ConnectionInfo, StreamConnector};
 use crate::sub_lib::tokio_wrappers::TokioListenerWrapper;
 use crate::sub_lib::tokio_wrappers::TokioListenerWrapperReal;
 use actix::Recipient;
+use futures::future::poll_fn;
+use futures::try_ready;
 use masq_lib::logger::Logger;
+use masq_lib::redaction::MarkSensitive;
+use openssl::ssl::SslAcceptor;
 use std::io;
 use std::marker::Send;
 use std::net::IpAddr;
 use std::net::Ipv4Addr;
 use std::net::SocketAddr;
+use tokio::net::TcpStream;
 use tokio::prelude::Async;
 use tokio::prelude::Future;
+use tokio_openssl::SslAcceptorExt;
 
 pub trait ListenerHandler: Send + Future {
     fn bind_port_and_configuration(
@@ -22,6 +29,7 @@ pub trait ListenerHandler: Send + Future {
         port_configuration: PortConfiguration,
     ) -> io::Result<()>;
     fn bind_subs(&mut self, add_stream_sub: Recipient<AddStreamMsg>);
+    fn enable_clandestine_tls(&mut self, acceptor: SslAcceptor);
 }
 
 pub trait ListenerHandlerFactory: Send {
@@ -34,6 +42,7 @@ pub struct ListenerHandlerReal {
     listener: Box<dyn TokioListenerWrapper>,
     add_stream_sub: Option<Recipient<AddStreamMsg>>,
     stream_connector: Box<dyn StreamConnector>,
+    tls_acceptor_opt: Option<SslAcceptor>,
     logger: Logger,
 }
 
@@ -58,6 +67,10 @@ impl ListenerHandler for ListenerHandlerReal {
     fn bind_subs(&mut self, add_stream_sub: Recipient<AddStreamMsg>) {
         self.add_stream_sub = Some(add_stream_sub);
     }
+
+    fn enable_clandestine_tls(&mut self, acceptor: SslAcceptor) {
+        self.tls_acceptor_opt = Some(acceptor);
+    }
 }
 
 impl Future for ListenerHandlerReal {
@@ -69,6 +82,10 @@ impl Future for ListenerHandlerReal {
             let result = self.listener.poll_accept();
             match result {
                 Ok(Async::Ready((stream, socket_addr))) => {
+                    if let Some(acceptor) = self.clandestine_tls_acceptor_opt() {
+                        self.accept_tls_or_plain(stream, socket_addr, acceptor);
+                        continue;
+                    }
                     let connection_info =
                         match self.stream_connector.split_stream(stream, &self.logger) {
                             Some(ci) => ci,
@@ -76,7 +93,7 @@ impl Future for ListenerHandlerReal {
                                 error!(
                                     self.logger,
                                     "Connection from {} was closed before it could be accepted",
-                                    socket_addr
+                                    socket_addr.sensitive()
                                 );
                                 return Ok(Async::NotReady);
                             }
@@ -113,9 +130,89 @@ impl ListenerHandlerReal {
             listener: Box::new(TokioListenerWrapperReal::new()),
             add_stream_sub: None,
             stream_connector: Box::new(StreamConnectorReal {}),
+            tls_acceptor_opt: None,
             logger: Logger::new("Uninitialized Listener"),
         }
     }
+
+    fn clandestine_tls_acceptor_opt(&self) -> Option<SslAcceptor> {
+        match &self.port_configuration {
+            Some(port_configuration) if port_configuration.is_clandestine => {
+                self.tls_acceptor_opt.clone()
+            }
+            _ => None,
+        }
+    }
+
+    // Peers that have TLS turned on open with a ClientHello; everybody else is served in the clear.
+    fn accept_tls_or_plain(
+        &self,
+        stream: TcpStream,
+        socket_addr: SocketAddr,
+        acceptor: SslAcceptor,
+    ) {
+        let add_stream_sub = self
+            .add_stream_sub
+            .clone()
+            .expect("Internal error: StreamHandlerPool unbound");
+        let port = self.port;
+        let port_configuration = self
+            .port_configuration
+            .clone()
+            .expect("Internal error: port_configuration is None");
+        let logger = self.logger.clone();
+        let split_logger = self.logger.clone();
+        let mut stream_opt = Some(stream);
+        let peek = poll_fn(move || {
+            let mut buf = [0u8; 1];
+            let len = try_ready!(stream_opt
+                .as_mut()
+                .expect("Stream already taken")
+                .poll_peek(&mut buf));
+            let stream = stream_opt.take().expect("Stream already taken");
+            Ok(Async::Ready((
+                stream,
+                ClandestineTls::is_client_hello(&buf[..len]),
+            )))
+        });
+        let accept = peek.and_then(
+            move |(stream, is_tls)| -> Box<dyn Future<Item = ConnectionInfo, Error = io::Error> + Send> {
+                if is_tls {
+                    Box::new(
+                        acceptor
+                            .accept_async(stream)
+                            .map_err(|e| {
+                                io::Error::new(
+                                    io::ErrorKind::ConnectionAborted,
+                                    format!("TLS handshake failed: {}", e),
+                                )
+                            })
+                            .and_then(split_tls_stream),
+                    )
+                } else {
+                    Box::new(futures::future::result(
+                        StreamConnectorReal {}
+                            .split_stream(stream, &split_logger)
+                            .ok_or_else(|| io::Error::from(io::ErrorKind::NotConnected)),
+                    ))
+                }
+            },
+        );
+        tokio::spawn(accept.then(move |result| {
+            match result {
+                Ok(connection_info) => add_stream_sub
+                    .try_send(AddStreamMsg::new(connection_info, port, port_configuration))
+                    .expect("Internal error: StreamHandlerPool is dead"),
+                Err(e) => error!(
+                    logger,
+                    "Connection from {} could not be accepted: {}",
+                    socket_addr.sensitive(),
+                    e
+                ),
+            }
+            Ok(())
+        }));
+    }
 }
 
 pub struct ListenerHandlerFactoryReal {}
@@ -140,6 +237,7 @@ mod tests {
     use crate::test_utils::recorder::make_recorder;
     use crate::test_utils::recorder::Recorder;
     use crate::test_utils::stream_connector_mock::StreamConnectorMock;
+    use crate::test_utils::{alias_cryptde, main_cryptde};
     use actix::Actor;
     use actix::Addr;
     use actix::System;
@@ -149,8 +247,10 @@ mod tests {
     use masq_lib::test_utils::logging::TestLogHandler;
     use masq_lib::utils::{find_free_port, localhost};
     use std::cell::RefCell;
+    use std::collections::HashSet;
     use std::io::Error;
     use std::io::ErrorKind;
+    use std::io::Write;
     use std::net;
     use std::net::Shutdown;
     use std::net::TcpStream as StdTcpStream;
@@ -432,6 +532,77 @@ mod tests {
         assert_eq!(recording.len(), 3);
     }
 
+    #[test]
+    fn clandestine_listener_with_tls_accepts_both_tls_and_plain_connections() {
+        let (stream_handler_pool, awaiter, recording_arc) = make_recorder();
+        let (tx, rx) = unbounded();
+        thread::spawn(move || {
+            let system =
+                System::new("clandestine_listener_with_tls_accepts_both_tls_and_plain_connections");
+            let add_stream_sub = start_recorder(stream_handler_pool);
+            tx.send(add_stream_sub).expect("Internal Error");
+            system.run();
+        });
+        let port = find_free_port();
+        thread::spawn(move || {
+            let add_stream_sub = rx.recv().unwrap();
+            let mut subject = ListenerHandlerReal::new();
+            subject.bind_subs(add_stream_sub);
+            subject.enable_clandestine_tls(
+                ClandestineTls::new(main_cryptde())
+                    .unwrap()
+                    .acceptor()
+                    .unwrap(),
+            );
+            subject
+                .bind_port_and_configuration(port, PortConfiguration::new(vec![], true))
+                .unwrap();
+            tokio::run(subject)
+        });
+        thread::sleep(Duration::from_millis(500));
+        let socket_addr = SocketAddr::new(localhost(), port);
+
+        let tls_stream = StdTcpStream::connect(socket_addr).unwrap();
+        let tls_addr = tls_stream.local_addr().unwrap();
+        let _tls_stream = ClandestineTls::new(alias_cryptde())
+            .unwrap()
+            .connect_configuration(main_cryptde().public_key())
+            .unwrap()
+            .connect("127.0.0.1", tls_stream)
+            .unwrap();
+        let mut plain_stream = StdTcpStream::connect(socket_addr).unwrap();
+        let plain_addr = plain_stream.local_addr().unwrap();
+        plain_stream.write_all(b"{").unwrap();
+
+        awaiter.await_message_count(2);
+        let recording = recording_arc.lock().unwrap();
+        let peer_addrs = (0..2)
+            .map(|idx| {
+                recording
+                    .get_record::<AddStreamMsg>(idx)
+                    .connection_info
+                    .peer_addr
+            })
+            .collect::<HashSet<SocketAddr>>();
+        assert_eq!(peer_addrs, vec![tls_addr, plain_addr].into_iter().collect());
+    }
+
+    #[test]
+    fn tls_acceptor_is_not_used_on_non_clandestine_ports() {
+        let mut subject = ListenerHandlerReal::new();
+        subject.enable_clandestine_tls(
+            ClandestineTls::new(main_cryptde())
+                .unwrap()
+                .acceptor()
+                .unwrap(),
+        );
+        subject.port_configuration = Some(PortConfiguration::new(vec![], false));
+
+        let result = subject.clandestine_tls_acceptor_opt();
+
+        assert!(result.is_none());
+    }
+
     fn start_recorder(recorder: Recorder) -> Recipient<AddStreamMsg> {
         let recorder_addr: Addr<Recorder> = recorder.start();
         recorder_addr.recipient::<AddStreamMsg>()
@@ -1,9 +1,12 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 use crate::discriminator::UnmaskedChunk;
+use crate::json_masquerader::JsonMasquerader;
+use crate::websocket_masquerader::WebSocketMasquerader;
 use std::fmt;
 use std::fmt::Display;
 use std::fmt::Formatter;
 use std::marker::Send;
+use std::str::FromStr;
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum MasqueradeError {
@@ -29,6 +32,55 @@ pub trait Masquerader: Send {
     fn mask(&self, data: &[u8]) -> Result<Vec<u8>, MasqueradeError>;
 }
 
+// The masquerade a Node uses on a clandestine connection. Every Node can read all of them;
+// which one is written to a given neighbor is negotiated when the connection is opened.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MasqueraderKind {
+    Json,
+    WebSocket,
+}
+
+// Which end of a clandestine connection this Node is. Some masquerades frame data differently
+// depending on whether it travels from the side that opened the connection or toward it.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ConnectionSide {
+    Connecting,
+    Accepting,
+}
+
+impl Display for MasqueraderKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            MasqueraderKind::Json => write!(f, "json"),
+            MasqueraderKind::WebSocket => write!(f, "websocket"),
+        }
+    }
+}
+
+impl FromStr for MasqueraderKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(MasqueraderKind::Json),
+            "websocket" => Ok(MasqueraderKind::WebSocket),
+            _ => Err(format!(
+                "Valid masquerader names are json and websocket; not '{}'",
+                s
+            )),
+        }
+    }
+}
+
+impl MasqueraderKind {
+    pub fn make(&self, side: ConnectionSide) -> Box<dyn Masquerader> {
+        match self {
+            MasqueraderKind::Json => Box::new(JsonMasquerader::new()),
+            MasqueraderKind::WebSocket => Box::new(WebSocketMasquerader::for_side(side)),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -61,4 +113,43 @@ mod tests {
             "Data not for this masquerader"
         );
     }
+
+    #[test]
+    fn masquerader_kinds_round_trip_through_strings() {
+        vec![MasqueraderKind::Json, MasqueraderKind::WebSocket]
+            .into_iter()
+            .for_each(|kind| {
+                assert_eq!(MasqueraderKind::from_str(&kind.to_string()), Ok(kind));
+            });
+        assert_eq!(
+            MasqueraderKind::from_str("WebSocket"),
+            Ok(MasqueraderKind::WebSocket)
+        );
+        assert_eq!(
+            MasqueraderKind::from_str("booga"),
+            Err("Valid masquerader names are json and websocket; not 'booga'".to_string())
+        );
+    }
+
+    #[test]
+    fn masquerader_kinds_make_masqueraders_that_speak_their_own_language() {
+        let json = MasqueraderKind::Json.make(ConnectionSide::Connecting);
+        let websocket = MasqueraderKind::WebSocket.make(ConnectionSide::Accepting);
+
+        let json_data = json.mask(b"booga").unwrap();
+        let websocket_data = websocket.mask(b"booga").unwrap();
+
+        assert_eq!(
+            json.try_unmask(&json_data).unwrap().chunk,
+            b"booga".to_vec()
+        );
+        assert_eq!(
+            websocket.try_unmask(&websocket_data).unwrap().chunk,
+            b"booga".to_vec()
+        );
+        assert_eq!(
+            websocket.try_unmask(&json_data),
+            Err(MasqueradeError::NotThisMasquerader)
+        );
+    }
 }
@@ -2,6 +2,7 @@
 
 use crate::sub_lib::cryptde::PublicKey;
 use crate::sub_lib::node_addr::NodeAddr;
+use masq_lib::redaction::{is_redacting, MarkSensitive};
 use std::fmt::Write as _;
 
 pub trait DotRenderable {
@@ -26,7 +27,7 @@ pub struct NodeRenderable {
 impl DotRenderable for NodeRenderable {
     fn render(&self) -> String {
         let mut result = String::new();
-        let _ = write!(result, "\"{}\"", self.public_key);
+        let _ = write!(result, "\"{}\"", self.public_key.sensitive());
         let _ = write!(result, "{}", &self.render_label());
         if !self.is_present {
             let _ = write!(result, " [shape=none]");
@@ -52,15 +53,16 @@ impl NodeRenderable {
             ),
             None => String::new(),
         };
-        let public_key_str = format!("{}", self.public_key);
-        let public_key_trunc = if public_key_str.len() > 8 {
+        let public_key_str = format!("{}", self.public_key.sensitive());
+        // A redaction token is already short, and truncating it would make it useless
+        let public_key_trunc = if public_key_str.len() > 8 && !is_redacting() {
             &public_key_str[0..8]
         } else {
             &public_key_str
         };
         let node_addr_string = match self.node_addr {
             None => String::new(),
-            Some(ref na) => format!("\\n{}", na),
+            Some(ref na) => format!("\\n{}", na.sensitive()),
         };
 
         format!(
@@ -78,7 +80,12 @@ pub struct EdgeRenderable {
 impl DotRenderable for EdgeRenderable {
     fn render(&self) -> String {
         let mut result = String::new();
-        let _ = write!(result, "\"{}\" -> \"{}\";", self.from, self.to);
+        let _ = write!(
+            result,
+            "\"{}\" -> \"{}\";",
+            self.from.sensitive(),
+            self.to.sensitive()
+        );
         result
     }
 }
@@ -96,6 +103,12 @@ pub fn render_dot_graph(renderables: Vec<Box<dyn DotRenderable>>) -> String {
 mod tests {
     use super::*;
     use crate::test_utils::assert_string_contains;
+    use log::Level;
+    use masq_lib::redaction::{
+        compose_log_message, redaction_token, set_redacted_levels_for_test_thread,
+    };
+    use std::net::IpAddr;
+    use std::str::FromStr;
 
     #[test]
     fn truncation_works_for_long_keys() {
@@ -153,4 +166,43 @@ mod tests {
             ),
         );
     }
+
+    #[test]
+    fn keys_and_addresses_are_redacted_whole_at_redacted_levels() {
+        set_redacted_levels_for_test_thread(&[Level::Trace]);
+        let public_key = PublicKey::new(&b"ABCDEFGHIJKLMNOPQRSTUVWXYZ"[..]);
+        let neighbor_key = PublicKey::new(&b"ZYXWVUTSRQPONMLKJIHGFEDCBA"[..]);
+        let node_addr = NodeAddr::new(&IpAddr::from_str("1.2.3.4").unwrap(), &[1234]);
+        let node = NodeRenderable {
+            inner: None,
+            public_key: public_key.clone(),
+            node_addr: Some(node_addr.clone()),
+            known_source: false,
+            known_target: false,
+            is_present: true,
+        };
+        let edge = EdgeRenderable {
+            from: public_key.clone(),
+            to: neighbor_key.clone(),
+        };
+
+        let result = compose_log_message(Level::Trace, || {
+            render_dot_graph(vec![Box::new(node), Box::new(edge)])
+        });
+
+        let key_token = redaction_token(&public_key.to_string());
+        assert_eq!(
+            result,
+            format!(
+                "digraph db {{ \"{}\" [label=\"{}\\n{}\"]; \"{}\" -> \"{}\"; }}",
+                key_token,
+                key_token,
+                redaction_token(&node_addr.to_string()),
+                key_token,
+                redaction_token(&neighbor_key.to_string())
+            )
+        );
+        assert_eq!(result.contains("1.2.3.4"), false);
+        assert_eq!(result.contains(&public_key.to_string()[0..8]), false);
+    }
 }
@@ -10,17 +10,26 @@ use crate::sub_lib::cryptde::{CryptDE, CryptData, PlainData, PublicKey};
 use crate::sub_lib::hopper::MessageType;
 use crate::sub_lib::node_addr::NodeAddr;
 use crate::sub_lib::versioned_data::StepError;
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
 use pretty_hex::PrettyHex;
 use serde_cbor::Value;
 use serde_derive::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::convert::{TryFrom, TryInto};
 use std::fmt::Debug;
 use std::fmt::Error;
 use std::fmt::Formatter;
 use std::fmt::Write as _;
+use std::io::{Read, Write};
+use std::iter::FromIterator;
 use std::net::{IpAddr, SocketAddr};
 
+// Far more than a whole database patch's worth of records. A neighbor can deflate gigabytes of
+// zeroes into a few kilobytes, so decompression stops here rather than at the end of the stream.
+pub const MAX_DECOMPRESSED_GOSSIP: usize = 4 * 1024 * 1024;
+
 #[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct GossipNodeRecord {
     pub signed_data: PlainData,
@@ -149,19 +158,43 @@ impl GossipNodeRecord {
     }
 }
 
+// The version of every Node record the sender of a Gossip knew about when it produced it
+#[derive(Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub struct VersionVector {
+    pub versions: BTreeMap<PublicKey, u32>,
+}
+
+impl VersionVector {
+    pub fn knows(&self, public_key: &PublicKey, version: u32) -> bool {
+        matches!(self.versions.get(public_key), Some(known) if *known >= version)
+    }
+}
+
+impl FromIterator<(PublicKey, u32)> for VersionVector {
+    fn from_iter<I: IntoIterator<Item = (PublicKey, u32)>>(iter: I) -> Self {
+        VersionVector {
+            versions: iter.into_iter().collect(),
+        }
+    }
+}
+
 #[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
 #[allow(non_camel_case_types)]
-pub struct Gossip_0v1 {
+pub struct Gossip_0v2 {
     pub node_records: Vec<GossipNodeRecord>,
+    // Lists the records of a Standard Gossip, including those left out because the target has them
+    pub version_vector_opt: Option<VersionVector>,
+    // Deflated node_records; Nodes older than 0v2 would read it as empty Gossip, so they never get it
+    pub compressed_records_opt: Option<PlainData>,
 }
 
-impl From<Gossip_0v1> for MessageType {
-    fn from(gossip: Gossip_0v1) -> Self {
+impl From<Gossip_0v2> for MessageType {
+    fn from(gossip: Gossip_0v2) -> Self {
         MessageType::Gossip(gossip.into())
     }
 }
 
-impl TryInto<Vec<AccessibleGossipRecord>> for Gossip_0v1 {
+impl TryInto<Vec<AccessibleGossipRecord>> for Gossip_0v2 {
     type Error = String;
 
     fn try_into(self) -> Result<Vec<AccessibleGossipRecord>, Self::Error> {
@@ -320,9 +353,104 @@ impl DotRenderable for SrcDestEdgeRenderable {
     }
 }
 
-impl Gossip_0v1 {
+impl Gossip_0v2 {
     pub fn new(node_records: Vec<GossipNodeRecord>) -> Self {
-        Self { node_records }
+        Self {
+            node_records,
+            version_vector_opt: None,
+            compressed_records_opt: None,
+        }
+    }
+
+    pub fn with_version_vector(self, version_vector: VersionVector) -> Self {
+        Self {
+            version_vector_opt: Some(version_vector),
+            ..self
+        }
+    }
+
+    pub fn compress(self) -> Self {
+        let serialized = serde_cbor::ser::to_vec(&self.node_records).expect("Serialization failed");
+        let mut encoder = DeflateEncoder::new(vec![], Compression::default());
+        encoder
+            .write_all(&serialized)
+            .expect("Compression into memory failed");
+        let compressed = encoder.finish().expect("Compression into memory failed");
+        Self {
+            node_records: vec![],
+            compressed_records_opt: Some(PlainData::from(compressed)),
+            ..self
+        }
+    }
+
+    pub fn decompress(self) -> Result<Self, String> {
+        let compressed = match &self.compressed_records_opt {
+            None => return Ok(self),
+            Some(compressed) => compressed,
+        };
+        let mut serialized = vec![];
+        DeflateDecoder::new(compressed.as_slice())
+            .take(MAX_DECOMPRESSED_GOSSIP as u64 + 1)
+            .read_to_end(&mut serialized)
+            .map_err(|e| format!("Compressed Gossip records are corrupt: {}", e))?;
+        if serialized.len() > MAX_DECOMPRESSED_GOSSIP {
+            return Err(format!(
+                "Compressed Gossip records decompress to more than {} bytes",
+                MAX_DECOMPRESSED_GOSSIP
+            ));
+        }
+        let mut node_records = serde_cbor::de::from_slice::<Vec<GossipNodeRecord>>(&serialized)
+            .map_err(|e| format!("Compressed Gossip records are inscrutable: {}", e))?;
+        let mut all_records = self.node_records;
+        all_records.append(&mut node_records);
+        Ok(Self {
+            node_records: all_records,
+            compressed_records_opt: None,
+            ..self
+        })
+    }
+
+    // Restores the records the sender left out because its version vector showed we had them
+    // already. Records we no longer have at that version are simply missing; the sender will
+    // include them next time, once our own version vector has told it that we lack them.
+    pub fn expand(self, database: &NeighborhoodDatabase) -> Result<Self, String> {
+        let gossip = self.decompress()?;
+        let version_vector = match &gossip.version_vector_opt {
+            None => return Ok(gossip),
+            Some(version_vector) => version_vector,
+        };
+        let included_keys = gossip
+            .node_records
+            .iter()
+            .flat_map(|gnr| NodeRecordInner_0v1::try_from(gnr).ok())
+            .map(|inner| inner.public_key)
+            .collect::<HashSet<PublicKey>>();
+        let root = database.root();
+        let restored_records = version_vector
+            .versions
+            .iter()
+            .filter(|(public_key, _)| !included_keys.contains(*public_key))
+            .flat_map(
+                |(public_key, version)| match database.node_by_key(public_key) {
+                    Some(node_record) if node_record.version() == *version => {
+                        let reveal_node_addr = node_record.accepts_connections()
+                            && root.has_half_neighbor(node_record.public_key());
+                        Some(GossipNodeRecord::from((
+                            database,
+                            node_record.public_key(),
+                            reveal_node_addr,
+                        )))
+                    }
+                    _ => None,
+                },
+            )
+            .collect::<Vec<GossipNodeRecord>>();
+        let mut node_records = gossip.node_records;
+        node_records.extend(restored_records);
+        Ok(Self {
+            node_records,
+            ..gossip
+        })
     }
 
     // Pass in:
@@ -417,7 +545,7 @@ impl Gossip_0v1 {
 
 pub struct GossipBuilder<'a> {
     db: &'a NeighborhoodDatabase,
-    gossip: Gossip_0v1,
+    gossip: Gossip_0v2,
     keys_so_far: HashSet<PublicKey>,
 }
 
@@ -425,13 +553,13 @@ impl<'a> GossipBuilder<'a> {
     pub fn new(db: &NeighborhoodDatabase) -> GossipBuilder {
         GossipBuilder {
             db,
-            gossip: Gossip_0v1::new(vec![]),
+            gossip: Gossip_0v2::new(vec![]),
             keys_so_far: HashSet::new(),
         }
     }
 
-    pub fn empty() -> Gossip_0v1 {
-        Gossip_0v1::new(vec![])
+    pub fn empty() -> Gossip_0v2 {
+        Gossip_0v2::new(vec![])
     }
 
     pub fn node(mut self, public_key_ref: &PublicKey, reveal_node_addr: bool) -> GossipBuilder<'a> {
@@ -455,7 +583,7 @@ impl<'a> GossipBuilder<'a> {
         self
     }
 
-    pub fn build(self) -> Gossip_0v1 {
+    pub fn build(self) -> Gossip_0v2 {
         self.gossip
     }
 }
@@ -704,12 +832,13 @@ Length: 4 (0x4) bytes
         db.root_mut().resign();
         let neighbor_gnr = GossipNodeRecord::from((&db, neighbor.public_key(), true));
 
-        let gossip = Gossip_0v1 {
+        let gossip = Gossip_0v2 {
             node_records: vec![
                 GossipNodeRecord::from((&db, db.root().public_key(), true)),
                 GossipNodeRecord::from((&db, target_node.public_key(), true)),
                 neighbor_gnr,
             ],
+            ..Gossip_0v2::new(vec![])
         };
 
         let result = gossip.to_dot_graph(&source_node, &target_node);
@@ -812,4 +941,161 @@ Length: 4 (0x4) bytes
 
         assert_eq!(String::from("digraph db { \"src\" [label=\"Gossip From:\\n\\n1.2.3.4\"]; \"dest\" [label=\"Gossip To:\\n\\n2.3.4.5\"]; \"src\" -> \"dest\" [arrowhead=empty]; }"), result)
     }
+
+    #[test]
+    fn version_vector_knows_records_at_or_below_the_versions_it_lists() {
+        let known = make_node_record(1234, true);
+        let unknown = make_node_record(2345, true);
+        let subject = vec![(known.public_key().clone(), 5)]
+            .into_iter()
+            .collect::<VersionVector>();
+
+        assert_eq!(subject.knows(known.public_key(), 4), true);
+        assert_eq!(subject.knows(known.public_key(), 5), true);
+        assert_eq!(subject.knows(known.public_key(), 6), false);
+        assert_eq!(subject.knows(unknown.public_key(), 0), false);
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(MAX_DECOMPRESSED_GOSSIP, 4_194_304);
+    }
+
+    #[test]
+    fn compressed_gossip_decompresses_to_the_original() {
+        let root = make_node_record(1234, true);
+        let neighbor = make_node_record(2345, true);
+        let mut db = db_from_node(&root);
+        db.add_node(neighbor.clone()).unwrap();
+        db.add_arbitrary_full_neighbor(root.public_key(), neighbor.public_key());
+        let version_vector = vec![(neighbor.public_key().clone(), 0)]
+            .into_iter()
+            .collect::<VersionVector>();
+        let original = GossipBuilder::new(&db)
+            .node(root.public_key(), true)
+            .node(neighbor.public_key(), false)
+            .build()
+            .with_version_vector(version_vector);
+
+        let compressed = original.clone().compress();
+
+        assert_eq!(compressed.node_records, vec![]);
+        assert_eq!(compressed.version_vector_opt, original.version_vector_opt);
+        let result = compressed.decompress().unwrap();
+        assert_eq!(result, original);
+    }
+
+    #[test]
+    fn decompress_complains_about_corrupt_records() {
+        let subject = Gossip_0v2 {
+            compressed_records_opt: Some(PlainData::new(b"not deflated")),
+            ..Gossip_0v2::new(vec![])
+        };
+
+        let result = subject.decompress();
+
+        assert_eq!(
+            result,
+            Err("Compressed Gossip records are corrupt: corrupt deflate stream".to_string())
+        );
+    }
+
+    #[test]
+    fn decompress_rejects_records_that_decompress_past_the_limit() {
+        let mut encoder = DeflateEncoder::new(vec![], Compression::best());
+        encoder
+            .write_all(&vec![0u8; MAX_DECOMPRESSED_GOSSIP + 1])
+            .unwrap();
+        let bomb = encoder.finish().unwrap();
+        let subject = Gossip_0v2 {
+            compressed_records_opt: Some(PlainData::from(bomb)),
+            ..Gossip_0v2::new(vec![])
+        };
+
+        let result = subject.decompress();
+
+        assert_eq!(
+            result,
+            Err(format!(
+                "Compressed Gossip records decompress to more than {} bytes",
+                MAX_DECOMPRESSED_GOSSIP
+            ))
+        );
+    }
+
+    #[test]
+    fn expand_restores_records_that_the_version_vector_says_we_have_already() {
+        let root = make_node_record(1234, true);
+        let sender = make_node_record(2345, true);
+        let known = make_node_record(3456, true);
+        let mut db = db_from_node(&root);
+        db.add_node(sender.clone()).unwrap();
+        db.add_node(known.clone()).unwrap();
+        db.add_arbitrary_full_neighbor(root.public_key(), sender.public_key());
+        db.add_arbitrary_full_neighbor(sender.public_key(), known.public_key());
+        let version_vector = vec![
+            (sender.public_key().clone(), sender.version()),
+            (known.public_key().clone(), known.version()),
+        ]
+        .into_iter()
+        .collect::<VersionVector>();
+        let subject = GossipBuilder::new(&db)
+            .node(sender.public_key(), true)
+            .build()
+            .with_version_vector(version_vector)
+            .compress();
+
+        let result = subject.expand(&db).unwrap();
+
+        assert_eq!(
+            result.node_records,
+            vec![
+                GossipNodeRecord::from((&db, sender.public_key(), true)),
+                GossipNodeRecord::from((&db, known.public_key(), false)),
+            ]
+        );
+        assert_eq!(result.compressed_records_opt, None);
+    }
+
+    #[test]
+    fn expand_cannot_restore_records_we_lack_or_have_at_a_different_version() {
+        let root = make_node_record(1234, true);
+        let sender = make_node_record(2345, true);
+        let stale = make_node_record(3456, true);
+        let missing = make_node_record(4567, true);
+        let mut db = db_from_node(&root);
+        db.add_node(sender.clone()).unwrap();
+        db.add_node(stale.clone()).unwrap();
+        db.add_arbitrary_full_neighbor(root.public_key(), sender.public_key());
+        let version_vector = vec![
+            (sender.public_key().clone(), sender.version()),
+            (stale.public_key().clone(), stale.version() + 1),
+            (missing.public_key().clone(), missing.version()),
+        ]
+        .into_iter()
+        .collect::<VersionVector>();
+        let subject = GossipBuilder::new(&db)
+            .node(sender.public_key(), true)
+            .build()
+            .with_version_vector(version_vector);
+
+        let result = subject.clone().expand(&db).unwrap();
+
+        assert_eq!(result, subject);
+    }
+
+    #[test]
+    fn expand_leaves_gossip_without_a_version_vector_alone() {
+        let root = make_node_record(1234, true);
+        let neighbor = make_node_record(2345, true);
+        let mut db = db_from_node(&root);
+        db.add_node(neighbor.clone()).unwrap();
+        let subject = GossipBuilder::new(&db)
+            .node(neighbor.public_key(), true)
+            .build();
+
+        let result = subject.clone().expand(&db).unwrap();
+
+        assert_eq!(result, subject);
+    }
 }
@@ -1,6 +1,6 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
-use crate::neighborhood::gossip::{GossipBuilder, Gossip_0v1};
+use crate::neighborhood::gossip::{GossipBuilder, Gossip_0v2};
 use crate::neighborhood::neighborhood_database::{NeighborhoodDatabase, NeighborhoodDatabaseError};
 use crate::neighborhood::node_record::NodeRecord;
 use crate::neighborhood::AccessibleGossipRecord;
@@ -10,6 +10,7 @@ use crate::sub_lib::neighborhood::{
 };
 use crate::sub_lib::node_addr::NodeAddr;
 use masq_lib::logger::Logger;
+use masq_lib::redaction::MarkSensitive;
 use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
 use std::net::{IpAddr, SocketAddr};
@@ -28,7 +29,7 @@ pub enum GossipAcceptanceResult {
     // The incoming Gossip produced database changes. Generate standard Gossip and broadcast.
     Accepted,
     // Don't generate Gossip from the database: instead, send this Gossip to the provided key and NodeAddr.
-    Reply(Gossip_0v1, PublicKey, NodeAddr),
+    Reply(Gossip_0v2, PublicKey, NodeAddr),
     // The incoming Gossip was proper, and we tried to accept it, but couldn't.
     Failed(GossipFailure_0v1, PublicKey, NodeAddr),
     // The incoming Gossip contained nothing we didn't know. Don't send out any Gossip because of it.
@@ -154,10 +155,10 @@ impl GossipHandler for DebutHandler {
                 .ip_addr();
             debug!(self.logger,
                 "DebutHandler is commissioning Pass of {} at {} to more appropriate neighbor {} at {}",
-                source_key,
-                gossip_source,
-                preferred_key,
-                preferred_ip,
+                source_key.sensitive(),
+                gossip_source.sensitive(),
+                preferred_key.sensitive(),
+                preferred_ip.sensitive(),
             );
             return GossipAcceptanceResult::Reply(
                 Self::make_pass_gossip(database, preferred_key),
@@ -176,7 +177,7 @@ impl GossipHandler for DebutHandler {
             None => {
                 debug!(self.logger,
                     "Neighbor count at maximum, but no non-common neighbors. DebutHandler is reluctantly ignoring debut from {} at {}",
-                    source_key, source_node_addr
+                    source_key.sensitive(), source_node_addr.sensitive()
                 );
                 return GossipAcceptanceResult::Failed(
                     GossipFailure_0v1::NoSuitableNeighbors,
@@ -200,10 +201,10 @@ impl GossipHandler for DebutHandler {
         debug!(
             self.logger,
             "DebutHandler is commissioning Pass of {} at {} to {} at {}",
-            source_key,
-            source_node_addr.ip_addr(),
-            lcn_key,
-            lcn_ip_str
+            source_key.sensitive(),
+            source_node_addr.ip_addr().sensitive(),
+            lcn_key.sensitive(),
+            lcn_ip_str.sensitive()
         );
         GossipAcceptanceResult::Reply(
             Self::make_pass_gossip(database, lcn_key),
@@ -295,8 +296,8 @@ impl DebutHandler {
                         None => "?.?.?.?".to_string(),
                     };
                     debug!(self.logger, "Node {} at {} is responding to first introduction: sending update Gossip instead of further introduction",
-                                              debuting_agr.inner.public_key,
-                                              ip_addr_str);
+                                              debuting_agr.inner.public_key.sensitive(),
+                                              ip_addr_str.sensitive());
                     Ok(GossipAcceptanceResult::Accepted)
                 } else {
                     match self.make_introduction(database, debuting_agr, gossip_source) {
@@ -311,8 +312,8 @@ impl DebutHandler {
                             debug!(
                                 self.logger,
                                 "DebutHandler can't make an introduction, but is accepting {} at {} and broadcasting change",
-                                &debut_node_key,
-                                gossip_source,
+                                debut_node_key.sensitive(),
+                                gossip_source.sensitive(),
                             );
                             Ok(GossipAcceptanceResult::Accepted)
                         }
@@ -328,7 +329,7 @@ impl DebutHandler {
         database: &NeighborhoodDatabase,
         debuting_agr: &AccessibleGossipRecord,
         gossip_source: SocketAddr,
-    ) -> Option<(Gossip_0v1, PublicKey, NodeAddr)> {
+    ) -> Option<(Gossip_0v2, PublicKey, NodeAddr)> {
         if let Some(lcn_key) =
             Self::find_least_connected_full_neighbor_excluding(database, debuting_agr)
         {
@@ -347,10 +348,10 @@ impl DebutHandler {
             debug!(
                 self.logger,
                 "DebutHandler commissioning Introduction of {} at {} to {} at {}",
-                lcn_key,
-                lcn_node_addr_str,
-                &debuting_agr.inner.public_key,
-                debut_node_addr
+                lcn_key.sensitive(),
+                lcn_node_addr_str.sensitive(),
+                debuting_agr.inner.public_key.sensitive(),
+                debut_node_addr.sensitive()
             );
             Some((
                 GossipBuilder::new(database)
@@ -447,7 +448,7 @@ impl DebutHandler {
         !debuting_agr.inner.neighbors.is_empty()
     }
 
-    fn make_pass_gossip(database: &NeighborhoodDatabase, pass_target: &PublicKey) -> Gossip_0v1 {
+    fn make_pass_gossip(database: &NeighborhoodDatabase, pass_target: &PublicKey) -> Gossip_0v2 {
         GossipBuilder::new(database).node(pass_target, true).build()
     }
 }
@@ -813,7 +814,7 @@ impl IntroductionHandler {
                     debug!(
                         self.logger,
                         "Updating obsolete introducer {} from version {} to version {}",
-                        introducer_key,
+                        introducer_key.sensitive(),
                         existing_introducer_ref.version(),
                         introducer.inner.version
                     );
@@ -822,7 +823,7 @@ impl IntroductionHandler {
                     debug!(
                         self.logger,
                         "Preserving existing introducer {} at version {}",
-                        introducer_key,
+                        introducer_key.sensitive(),
                         existing_introducer_ref.version()
                     );
                     return Ok(false);
@@ -832,7 +833,8 @@ impl IntroductionHandler {
                 let new_introducer = NodeRecord::from(introducer);
                 debug!(
                     self.logger,
-                    "Adding introducer {} to database", introducer_key
+                    "Adding introducer {} to database",
+                    introducer_key.sensitive()
                 );
                 database
                     .add_node(new_introducer)
@@ -1026,7 +1028,7 @@ impl StandardGossipHandler {
                     trace!(
                         self.logger,
                         "While computing patch no AGR record found for public key {:?}",
-                        current_node_key
+                        current_node_key.sensitive()
                     );
                     return;
                 }
@@ -1076,7 +1078,7 @@ impl StandardGossipHandler {
                 trace!(
                     self.logger,
                     "Discovered new Node {:?}: {:?}",
-                    node_record.public_key(),
+                    node_record.public_key().sensitive(),
                     node_record.full_neighbor_keys(database)
                 );
                 database
@@ -1097,7 +1099,7 @@ impl StandardGossipHandler {
                     trace!(
                         self.logger,
                         "Updating Node {:?} from v{} to v{}",
-                        existing_node.public_key(),
+                        existing_node.public_key().sensitive(),
                         existing_node.version(),
                         agr.inner.version
                     );
@@ -1150,7 +1152,7 @@ impl StandardGossipHandler {
                 error!(
                     self.logger,
                     "Failed to update {} from v{} to v{}: {}",
-                    existing_node_record.public_key(),
+                    existing_node_record.public_key().sensitive(),
                     existing_node_record.version(),
                     new_version,
                     e
@@ -1293,7 +1295,7 @@ impl<'a> GossipAcceptorReal<'a> {
     fn make_debut_triple(
         database: &NeighborhoodDatabase,
         debut_target: &AccessibleGossipRecord,
-    ) -> Result<(Gossip_0v1, PublicKey, NodeAddr), String> {
+    ) -> Result<(Gossip_0v2, PublicKey, NodeAddr), String> {
         let debut_target_node_addr = match &debut_target.node_addr_opt {
             None => {
                 return Err(format!(
@@ -1332,6 +1334,7 @@ mod tests {
     use crate::sub_lib::cryptde_null::CryptDENull;
     use crate::sub_lib::neighborhood::{ConnectionProgressEvent, ConnectionProgressMessage};
     use crate::sub_lib::utils::time_t_timestamp;
+    use crate::sub_lib::versioned_data::VersionedData;
     use crate::test_utils::neighborhood_test_utils::{
         db_from_node, gossip_about_nodes_from_database, linearly_connect_nodes,
         make_meaningless_db, make_node_record, make_node_record_f, make_node_records,
@@ -1340,6 +1343,9 @@ mod tests {
     use crate::test_utils::unshared_test_utils::make_cpm_recipient;
     use crate::test_utils::{assert_contains, main_cryptde, vec_to_set};
     use actix::System;
+    use itertools::Itertools;
+    use log::Level;
+    use masq_lib::redaction::{set_redacted_levels_for_test_thread, REDACTION_TOKEN_PREFIX};
     use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
     use masq_lib::test_utils::utils::TEST_DEFAULT_CHAIN;
     use std::convert::TryInto;
@@ -1405,6 +1411,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn debut_handling_leaks_no_addresses_or_keys_to_a_redacted_log() {
+        init_test_logging();
+        set_redacted_levels_for_test_thread(&[Level::Debug, Level::Trace]);
+        let test_name = "debut_handling_leaks_no_addresses_or_keys_to_a_redacted_log";
+        let (gossip, new_node, gossip_source) = make_debut(2345, Mode::Standard);
+        let root_node = make_node_record(1234, true);
+        let mut db = db_from_node(&root_node);
+        let neighbor = make_node_record(3456, true);
+        let neighbor_key = &db.add_node(neighbor.clone()).unwrap();
+        db.add_arbitrary_full_neighbor(root_node.public_key(), neighbor_key);
+        let cryptde = CryptDENull::from(db.root().public_key(), TEST_DEFAULT_CHAIN);
+        let agrs_vec: Vec<AccessibleGossipRecord> = gossip.try_into().unwrap();
+        let subject = DebutHandler::new(Logger::new(test_name));
+
+        subject.handle(
+            &cryptde,
+            &mut db,
+            agrs_vec,
+            gossip_source,
+            make_default_neighborhood_metadata(),
+        );
+
+        let tlh = TestLogHandler::new();
+        tlh.exists_log_containing(&format!(
+            "DEBUG: {}: DebutHandler commissioning Introduction of {}",
+            test_name, REDACTION_TOKEN_PREFIX
+        ));
+        [&new_node, &neighbor].iter().for_each(|node| {
+            let ip_addr = node.node_addr_opt().unwrap().ip_addr().to_string();
+            let public_key = node.public_key().to_string();
+            [ip_addr, public_key].iter().for_each(|secret| {
+                tlh.exists_no_log_matching(&format!("{}: .*{}", test_name, regex::escape(secret)))
+            })
+        });
+    }
+
     #[test]
     fn proper_debut_of_non_accepting_node_with_populated_database_is_identified_and_handled() {
         let (gossip, new_node, gossip_source) = make_debut(2345, Mode::OriginateOnly);
@@ -2782,7 +2825,7 @@ mod tests {
         dest_db.add_node(src_root.clone()).unwrap();
         dest_db.add_arbitrary_full_neighbor(five_neighbors[0].public_key(), src_root.public_key());
         let gossip = GossipProducerReal::new()
-            .produce(&mut src_db, dest_root.public_key())
+            .produce(&mut src_db, dest_root.public_key(), None)
             .unwrap();
         let subject = make_subject(&dest_cryptde);
 
@@ -2796,6 +2839,141 @@ mod tests {
         assert_eq!(result, GossipAcceptanceResult::Ignored);
     }
 
+    fn gossip_bytes(gossip: &Gossip_0v2) -> usize {
+        serde_cbor::ser::to_vec(&VersionedData::from(gossip.clone()))
+            .unwrap()
+            .len()
+    }
+
+    fn db_digest(db: &NeighborhoodDatabase) -> Vec<NodeRecord> {
+        db.keys()
+            .into_iter()
+            .sorted()
+            .map(|key| db.node_by_key(key).unwrap().clone())
+            .collect()
+    }
+
+    // Sets up a 60-Node line in which the source and the destination are the first two Nodes
+    // and the destination has already heard everything except fresh versions of two records.
+    fn make_large_neighborhood_with_two_updates(
+    ) -> (NodeRecord, NeighborhoodDatabase, NeighborhoodDatabase) {
+        let nodes = make_node_records(60);
+        let mut src_db = linearly_connect_nodes(&nodes);
+        let mut dest_db = db_from_node(src_db.node_by_key(nodes[1].public_key()).unwrap());
+        nodes
+            .iter()
+            .filter(|node| node != &&nodes[1])
+            .for_each(|node| {
+                dest_db
+                    .add_node(src_db.node_by_key(node.public_key()).unwrap().clone())
+                    .unwrap();
+            });
+        dest_db.add_arbitrary_half_neighbor(nodes[1].public_key(), nodes[0].public_key());
+        dest_db.add_arbitrary_half_neighbor(nodes[1].public_key(), nodes[2].public_key());
+        src_db.root_mut().increment_version();
+        src_db.root_mut().resign();
+        let updated_node = src_db.node_by_key_mut(nodes[3].public_key()).unwrap();
+        updated_node.increment_version();
+        updated_node.resign();
+        (nodes[1].clone(), src_db, dest_db)
+    }
+
+    #[test]
+    fn delta_gossip_sends_a_fraction_of_the_bytes_and_is_accepted_like_full_gossip() {
+        let (dest_node, mut src_db, dest_db) = make_large_neighborhood_with_two_updates();
+        let src_node = src_db.root().clone();
+        let dest_versions = GossipProducerReal::new()
+            .produce(&mut dest_db.clone(), src_node.public_key(), None)
+            .unwrap()
+            .version_vector_opt
+            .unwrap();
+        let producer = GossipProducerReal::new();
+        let full_gossip = producer
+            .produce(&mut src_db, dest_node.public_key(), None)
+            .unwrap();
+        let delta_gossip = producer
+            .produce(&mut src_db, dest_node.public_key(), Some(&dest_versions))
+            .unwrap()
+            .compress();
+        let dest_cryptde = CryptDENull::from(dest_node.public_key(), TEST_DEFAULT_CHAIN);
+        let subject = make_subject(&dest_cryptde);
+        let gossip_source: SocketAddr = src_node.node_addr_opt().unwrap().into();
+        let mut full_dest_db = dest_db.clone();
+        let mut delta_dest_db = dest_db.clone();
+
+        let full_bytes = gossip_bytes(&full_gossip);
+        let delta_bytes = gossip_bytes(&delta_gossip);
+        let expanded_gossip = delta_gossip.expand(&delta_dest_db).unwrap();
+        let full_result = subject.handle(
+            &mut full_dest_db,
+            full_gossip.clone().try_into().unwrap(),
+            gossip_source,
+            make_default_neighborhood_metadata(),
+        );
+        let delta_result = subject.handle(
+            &mut delta_dest_db,
+            expanded_gossip.clone().try_into().unwrap(),
+            gossip_source,
+            make_default_neighborhood_metadata(),
+        );
+
+        assert_eq!(full_gossip.node_records.len(), 59);
+        assert!(
+            delta_bytes * 10 < full_bytes,
+            "Delta Gossip took {} bytes; full Gossip took {}",
+            delta_bytes,
+            full_bytes
+        );
+        let sorted_records = |gossip: Gossip_0v2| {
+            let mut agrs: Vec<AccessibleGossipRecord> = gossip.try_into().unwrap();
+            agrs.sort_by(|a, b| a.inner.public_key.cmp(&b.inner.public_key));
+            agrs
+        };
+        assert_eq!(sorted_records(expanded_gossip), sorted_records(full_gossip));
+        assert_eq!(delta_result, full_result);
+        assert_eq!(db_digest(&delta_dest_db), db_digest(&full_dest_db));
+    }
+
+    #[test]
+    fn compressed_full_gossip_is_smaller_than_plain_full_gossip_and_is_accepted_the_same_way() {
+        let (dest_node, mut src_db, dest_db) = make_large_neighborhood_with_two_updates();
+        let src_node = src_db.root().clone();
+        let plain_gossip = GossipProducerReal::new()
+            .produce(&mut src_db, dest_node.public_key(), None)
+            .unwrap();
+        let compressed_gossip = plain_gossip.clone().compress();
+        let dest_cryptde = CryptDENull::from(dest_node.public_key(), TEST_DEFAULT_CHAIN);
+        let subject = make_subject(&dest_cryptde);
+        let gossip_source: SocketAddr = src_node.node_addr_opt().unwrap().into();
+        let mut plain_dest_db = dest_db.clone();
+        let mut compressed_dest_db = dest_db;
+
+        let plain_bytes = gossip_bytes(&plain_gossip);
+        let compressed_bytes = gossip_bytes(&compressed_gossip);
+        let expanded_gossip = compressed_gossip.expand(&compressed_dest_db).unwrap();
+        let plain_result = subject.handle(
+            &mut plain_dest_db,
+            plain_gossip.try_into().unwrap(),
+            gossip_source,
+            make_default_neighborhood_metadata(),
+        );
+        let compressed_result = subject.handle(
+            &mut compressed_dest_db,
+            expanded_gossip.try_into().unwrap(),
+            gossip_source,
+            make_default_neighborhood_metadata(),
+        );
+
+        assert!(
+            compressed_bytes * 2 < plain_bytes,
+            "Compressed Gossip took {} bytes; plain Gossip took {}",
+            compressed_bytes,
+            plain_bytes
+        );
+        assert_eq!(compressed_result, plain_result);
+        assert_eq!(db_digest(&compressed_dest_db), db_digest(&plain_dest_db));
+    }
+
     #[test]
     fn last_gossip_handler_rejects_everything() {
         let subject = make_subject(main_cryptde());
@@ -4181,7 +4359,7 @@ mod tests {
             .for_each(|n| db.node_by_key_mut(n.public_key()).unwrap().resign());
     }
 
-    fn make_debut(n: u16, mode: Mode) -> (Gossip_0v1, NodeRecord, SocketAddr) {
+    fn make_debut(n: u16, mode: Mode) -> (Gossip_0v2, NodeRecord, SocketAddr) {
         let (gossip, debut_node) = make_single_node_gossip(n, mode);
         let gossip_source: SocketAddr = match debut_node.node_addr_opt() {
             Some(node_addr) => node_addr.into(),
@@ -4190,7 +4368,7 @@ mod tests {
         (gossip, debut_node, gossip_source)
     }
 
-    fn make_pass(n: u16) -> (Gossip_0v1, NodeRecord, SocketAddr) {
+    fn make_pass(n: u16) -> (Gossip_0v2, NodeRecord, SocketAddr) {
         let (gossip, debut_node) = make_single_node_gossip(n, Mode::Standard);
         (
             gossip,
@@ -4199,7 +4377,7 @@ mod tests {
         )
     }
 
-    fn make_single_node_gossip(n: u16, mode: Mode) -> (Gossip_0v1, NodeRecord) {
+    fn make_single_node_gossip(n: u16, mode: Mode) -> (Gossip_0v2, NodeRecord) {
         let mut debut_node = make_node_record(n, true);
         adjust_for_mode(&mut debut_node, mode);
         let src_db = db_from_node(&debut_node);
@@ -4209,7 +4387,7 @@ mod tests {
         (gossip, debut_node)
     }
 
-    fn make_introduction(introducer_n: u16, introducee_n: u16) -> (Gossip_0v1, SocketAddr) {
+    fn make_introduction(introducer_n: u16, introducee_n: u16) -> (Gossip_0v2, SocketAddr) {
         let mut introducer_node: NodeRecord = make_node_record(introducer_n, true);
         adjust_for_mode(&mut introducer_node, Mode::Standard);
         introducer_node.set_version(10);
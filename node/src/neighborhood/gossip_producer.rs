@@ -1,10 +1,11 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
 use super::gossip::GossipBuilder;
-use super::gossip::Gossip_0v1;
+use super::gossip::{Gossip_0v2, VersionVector};
 use super::neighborhood_database::NeighborhoodDatabase;
 use crate::sub_lib::cryptde::PublicKey;
 use crate::sub_lib::utils::time_t_timestamp;
+use itertools::Itertools;
 use masq_lib::logger::Logger;
 use std::cell::Cell;
 
@@ -15,8 +16,9 @@ pub trait GossipProducer: Send {
         &self,
         database: &mut NeighborhoodDatabase,
         target: &PublicKey,
-    ) -> Option<Gossip_0v1>;
-    fn produce_debut(&self, database: &NeighborhoodDatabase) -> Gossip_0v1;
+        target_versions_opt: Option<&VersionVector>,
+    ) -> Option<Gossip_0v2>;
+    fn produce_debut(&self, database: &NeighborhoodDatabase) -> Gossip_0v2;
 }
 
 pub struct GossipProducerReal {
@@ -28,12 +30,17 @@ impl GossipProducer for GossipProducerReal {
     /*
         `produce`
             the purpose of `produce` is to convert the raw neighborhood from the DB into a Gossip message for a target Node
-            the Gossip that `produce` returns includes the entire neighborhood, but masks the NodeAddrs of Nodes whose
+            the Gossip that `produce` returns describes the entire neighborhood, but masks the NodeAddrs of Nodes whose
             NodeAddrs the target Node does not know (except that the NodeAddr of this Node is never masked).
+            Records restored from a snapshot are not passed along until some other Node has confirmed them.
+            Its version vector lists every record it describes; records the target is known to have already are
+            listed there but left out of the Gossip itself.
         params:
             `database`: the DB that contains the whole neighborhood
             `target`: the Node to produce the gossip for
                 allows `produce` to determine which NodeAddrs to mask/reveal, based on which other Nodes `target` has as half neighbors
+            `target_versions_opt`: the version vector of the last Gossip received from `target`, if it sent one
+                allows `produce` to leave out the records `target` already has
         returns:
             a Gossip message representing the current neighborhood for a target Node
     */
@@ -41,7 +48,8 @@ impl GossipProducer for GossipProducerReal {
         &self,
         database: &mut NeighborhoodDatabase,
         target: &PublicKey,
-    ) -> Option<Gossip_0v1> {
+        target_versions_opt: Option<&VersionVector>,
+    ) -> Option<Gossip_0v2> {
         if time_t_timestamp() - self.last_dead_node_check.get() >= DEAD_NODE_CHECK_INTERVAL_SECS {
             debug!(self.logger, "Checking for dead Nodes");
             database.cull_dead_nodes();
@@ -58,12 +66,31 @@ impl GossipProducer for GossipProducerReal {
             }
         };
         let referenced_keys = database.referenced_node_keys();
-        let builder = database
+        let described_nodes = database
             .keys()
             .into_iter()
             .filter(|k| *k != target)
             .filter(|k| referenced_keys.contains(k))
             .flat_map(|k| database.node_by_key(k))
+            .filter(|node_record_ref| !node_record_ref.metadata.unverified)
+            .collect_vec();
+        let version_vector = described_nodes
+            .iter()
+            .map(|node_record_ref| {
+                (
+                    node_record_ref.public_key().clone(),
+                    node_record_ref.version(),
+                )
+            })
+            .collect::<VersionVector>();
+        let builder = described_nodes
+            .into_iter()
+            .filter(|node_record_ref| match target_versions_opt {
+                Some(target_versions) => {
+                    !target_versions.knows(node_record_ref.public_key(), node_record_ref.version())
+                }
+                None => true,
+            })
             .fold(GossipBuilder::new(database), |so_far, node_record_ref| {
                 let reveal_node_addr = node_record_ref.accepts_connections()
                     && (
@@ -73,10 +100,10 @@ impl GossipProducer for GossipProducerReal {
                     );
                 so_far.node(node_record_ref.public_key(), reveal_node_addr)
             });
-        Some(builder.build())
+        Some(builder.build().with_version_vector(version_vector))
     }
 
-    fn produce_debut(&self, database: &NeighborhoodDatabase) -> Gossip_0v1 {
+    fn produce_debut(&self, database: &NeighborhoodDatabase) -> Gossip_0v2 {
         GossipBuilder::new(database)
             .node(database.root().public_key(), true)
             .build()
@@ -110,7 +137,6 @@ mod tests {
     use crate::sub_lib::utils::time_t_timestamp;
     use crate::test_utils::assert_contains;
     use crate::test_utils::neighborhood_test_utils::{db_from_node, make_node_record};
-    use itertools::Itertools;
     use masq_lib::test_utils::utils::TEST_DEFAULT_CHAIN;
     use std::collections::btree_set::BTreeSet;
     use std::convert::TryFrom;
@@ -165,7 +191,7 @@ mod tests {
         db.add_arbitrary_half_neighbor(referencer, knows_root_key);
         let subject = GossipProducerReal::new();
 
-        let gossip = subject.produce(&mut db, target_node_key).unwrap();
+        let gossip = subject.produce(&mut db, target_node_key, None).unwrap();
 
         type Digest = (PublicKey, Vec<u8>, bool, BTreeSet<PublicKey>);
         let gnr_digest = |gnr: GossipNodeRecord| {
@@ -224,7 +250,7 @@ mod tests {
         db.add_arbitrary_full_neighbor(root_node.public_key(), target_node_key);
         let subject = GossipProducerReal::new();
 
-        let gossip = subject.produce(&mut db, target_node_key).unwrap();
+        let gossip = subject.produce(&mut db, target_node_key, None).unwrap();
 
         let gossip_root = gossip
             .node_records
@@ -235,6 +261,90 @@ mod tests {
         assert_eq!(gossip_root.node_addr_opt, None);
     }
 
+    #[test]
+    fn produce_leaves_out_records_the_target_has_already_but_lists_them_in_the_version_vector() {
+        let root_node = make_node_record(1234, true);
+        let mut db: NeighborhoodDatabase = db_from_node(&root_node);
+        let target_node_key = &db.add_node(make_node_record(1235, true)).unwrap();
+        let known_key = &db.add_node(make_node_record(1236, true)).unwrap();
+        let outdated_key = &db.add_node(make_node_record(1237, true)).unwrap();
+        let unknown_key = &db.add_node(make_node_record(1238, true)).unwrap();
+        db.add_arbitrary_full_neighbor(root_node.public_key(), target_node_key);
+        db.add_arbitrary_full_neighbor(root_node.public_key(), known_key);
+        db.add_arbitrary_full_neighbor(root_node.public_key(), outdated_key);
+        db.add_arbitrary_full_neighbor(root_node.public_key(), unknown_key);
+        db.node_by_key_mut(outdated_key)
+            .unwrap()
+            .increment_version();
+        let version_of =
+            |db: &NeighborhoodDatabase, key: &PublicKey| db.node_by_key(key).unwrap().version();
+        let target_versions = vec![
+            (known_key.clone(), version_of(&db, known_key)),
+            (outdated_key.clone(), version_of(&db, outdated_key) - 1),
+        ]
+        .into_iter()
+        .collect::<VersionVector>();
+        let subject = GossipProducerReal::new();
+
+        let gossip = subject
+            .produce(&mut db, target_node_key, Some(&target_versions))
+            .unwrap();
+
+        let gossip_keys = gossip
+            .node_records
+            .into_iter()
+            .map(|gnr| NodeRecordInner_0v1::try_from(gnr).unwrap().public_key)
+            .collect::<BTreeSet<PublicKey>>();
+        assert_eq!(
+            gossip_keys,
+            vec![
+                root_node.public_key().clone(),
+                outdated_key.clone(),
+                unknown_key.clone()
+            ]
+            .into_iter()
+            .collect::<BTreeSet<PublicKey>>()
+        );
+        let expected_version_vector =
+            vec![root_node.public_key(), known_key, outdated_key, unknown_key]
+                .into_iter()
+                .map(|key| (key.clone(), version_of(&db, key)))
+                .collect::<VersionVector>();
+        assert_eq!(gossip.version_vector_opt, Some(expected_version_vector));
+    }
+
+    #[test]
+    fn produce_does_not_pass_along_unverified_records() {
+        let root_node = make_node_record(1234, true);
+        let mut db: NeighborhoodDatabase = db_from_node(&root_node);
+        let target_node_key = &db.add_node(make_node_record(1235, true)).unwrap();
+        let verified_key = &db.add_node(make_node_record(1236, true)).unwrap();
+        let unverified_key = &db.add_node(make_node_record(1237, true)).unwrap();
+        db.add_arbitrary_full_neighbor(root_node.public_key(), target_node_key);
+        db.add_arbitrary_full_neighbor(root_node.public_key(), verified_key);
+        db.add_arbitrary_full_neighbor(verified_key, unverified_key);
+        db.node_by_key_mut(unverified_key)
+            .unwrap()
+            .metadata
+            .unverified = true;
+        let subject = GossipProducerReal::new();
+
+        let gossip = subject.produce(&mut db, target_node_key, None).unwrap();
+
+        let gossip_keys = gossip
+            .node_records
+            .into_iter()
+            .map(|gnr| NodeRecordInner_0v1::try_from(gnr).unwrap().public_key)
+            .collect::<BTreeSet<PublicKey>>();
+        assert_eq!(
+            gossip_keys,
+            vec![root_node.public_key().clone(), verified_key.clone()]
+                .into_iter()
+                .collect::<BTreeSet<PublicKey>>()
+        );
+        assert!(!gossip.version_vector_opt.unwrap().knows(unverified_key, 0));
+    }
+
     #[test]
     fn produce_does_not_make_gossip_about_nonexistent_or_removed_nodes() {
         let root_node: NodeRecord = make_node_record(1234, true);
@@ -242,7 +352,7 @@ mod tests {
         let nonexistent_node = make_node_record(2345, false);
         let subject = GossipProducerReal::new();
 
-        let result = subject.produce(&mut db, nonexistent_node.public_key());
+        let result = subject.produce(&mut db, nonexistent_node.public_key(), None);
 
         assert_eq!(result, None);
     }
@@ -260,7 +370,7 @@ mod tests {
         db.add_arbitrary_full_neighbor(&gossip_target, root_node.public_key());
         let subject = GossipProducerReal::new();
 
-        let gossip = subject.produce(&mut db, &gossip_target).unwrap();
+        let gossip = subject.produce(&mut db, &gossip_target, None).unwrap();
 
         let gossipped_keys = gossip
             .node_records
@@ -297,7 +407,7 @@ mod tests {
             .set(time_t_timestamp() - DEAD_NODE_CHECK_INTERVAL_SECS - 2);
 
         let begin_at = time_t_timestamp();
-        let gossip = subject.produce(&mut db, &gossip_target).unwrap();
+        let gossip = subject.produce(&mut db, &gossip_target, None).unwrap();
         let end_at = time_t_timestamp();
 
         assert!(subject.last_dead_node_check.get() >= begin_at);
@@ -322,7 +432,7 @@ mod tests {
         db.add_arbitrary_half_neighbor(root_node.public_key(), &gossip_target);
         let subject = GossipProducerReal::new();
 
-        let gossip = subject.produce(&mut db, &gossip_target).unwrap();
+        let gossip = subject.produce(&mut db, &gossip_target, None).unwrap();
 
         let gossipped_keys = gossip
             .node_records
@@ -340,7 +450,7 @@ mod tests {
         let db = db_from_node(&our_node_record);
         let subject = GossipProducerReal::new();
 
-        let result_gossip: Gossip_0v1 = subject.produce_debut(&db);
+        let result_gossip: Gossip_0v2 = subject.produce_debut(&db);
 
         assert_eq!(result_gossip.node_records.len(), 1);
         let result_gossip_record = result_gossip.node_records.first().unwrap();
@@ -368,7 +478,7 @@ mod tests {
         let db = db_from_node(&our_node_record);
         let subject = GossipProducerReal::new();
 
-        let result_gossip: Gossip_0v1 = subject.produce_debut(&db);
+        let result_gossip: Gossip_0v2 = subject.produce_debut(&db);
 
         assert_eq!(result_gossip.node_records.len(), 1);
         let result_gossip_record = result_gossip.node_records.first().unwrap();
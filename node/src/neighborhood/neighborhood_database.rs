@@ -21,6 +21,7 @@ use std::fmt::Formatter;
 use std::net::IpAddr;
 
 pub const ISOLATED_NODE_GRACE_PERIOD_SECS: u32 = 30;
+pub const UNVERIFIED_NODE_GRACE_PERIOD_SECS: u32 = 600;
 
 #[derive(Clone)]
 pub struct NeighborhoodDatabase {
@@ -238,6 +239,30 @@ impl NeighborhoodDatabase {
         }
     }
 
+    // Records restored from a snapshot that no Gossip has vouched for since are presumed gone
+    pub fn cull_unverified_nodes(&mut self) {
+        let deadline = time_t_timestamp() - UNVERIFIED_NODE_GRACE_PERIOD_SECS;
+        let to_cull: BTreeSet<PublicKey> = self
+            .keys()
+            .into_iter()
+            .filter(|k| *k != self.root().public_key())
+            .filter(|k| {
+                let node = self.node_by_key(k).expect("Node disappeared");
+                node.metadata.unverified && node.last_updated() <= deadline
+            })
+            .cloned()
+            .collect();
+        if !to_cull.is_empty() {
+            info!(self.logger, "Culling from the neighborhood {} restored Node{} that Gossip has not confirmed in {} seconds: {:?}",
+                  to_cull.len(),
+                  if to_cull.len() == 1 { "" } else { "s" },
+                  UNVERIFIED_NODE_GRACE_PERIOD_SECS,
+                  to_cull
+            );
+            to_cull.iter().for_each(|k| self.remove_node(k));
+        }
+    }
+
     pub fn to_dot_graph(&self) -> String {
         let renderables = self.to_dot_renderables();
         render_dot_graph(renderables)
@@ -374,6 +399,7 @@ mod tests {
     #[test]
     fn constants_have_correct_values() {
         assert_eq!(ISOLATED_NODE_GRACE_PERIOD_SECS, 30);
+        assert_eq!(UNVERIFIED_NODE_GRACE_PERIOD_SECS, 600);
     }
 
     #[test]
@@ -939,4 +965,35 @@ mod tests {
         assert!(subject.node_by_key(referenced_by_dead).is_some());
         assert!(subject.node_by_key(dead).is_none());
     }
+
+    #[test]
+    fn cull_unverified_nodes_culls_only_stale_unverified_nodes() {
+        let root_node = make_node_record(1234, true);
+        let mut subject: NeighborhoodDatabase = db_from_node(&root_node);
+        let verified_stale_key = &subject.add_node(make_node_record(2345, true)).unwrap();
+        let unverified_fresh_key = &subject.add_node(make_node_record(3456, true)).unwrap();
+        let unverified_stale_key = &subject.add_node(make_node_record(4567, true)).unwrap();
+        let stale_time = time_t_timestamp() - UNVERIFIED_NODE_GRACE_PERIOD_SECS - 2;
+        subject
+            .node_by_key_mut(verified_stale_key)
+            .unwrap()
+            .set_last_updated(stale_time);
+        subject
+            .node_by_key_mut(unverified_fresh_key)
+            .unwrap()
+            .metadata
+            .unverified = true;
+        let unverified_stale = subject.node_by_key_mut(unverified_stale_key).unwrap();
+        unverified_stale.metadata.unverified = true;
+        unverified_stale.set_last_updated(stale_time);
+        subject.root_mut().metadata.unverified = true;
+        subject.root_mut().set_last_updated(stale_time);
+
+        subject.cull_unverified_nodes();
+
+        assert!(subject.node_by_key(root_node.public_key()).is_some());
+        assert!(subject.node_by_key(verified_stale_key).is_some());
+        assert!(subject.node_by_key(unverified_fresh_key).is_some());
+        assert!(subject.node_by_key(unverified_stale_key).is_none());
+    }
 }
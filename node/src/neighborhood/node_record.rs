@@ -24,6 +24,9 @@ pub struct NodeRecordInner_0v1 {
     pub accepts_connections: bool,
     pub routes_data: bool,
     pub version: u32,
+    // Left out unless true, so Nodes without TLS gossip exactly what Nodes that predate it do
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub accepts_tls: bool,
 }
 
 impl TryFrom<GossipNodeRecord> for NodeRecordInner_0v1 {
@@ -78,6 +81,7 @@ impl NodeRecord {
                 routes_data,
                 neighbors: BTreeSet::new(),
                 version,
+                accepts_tls: false,
             },
             signed_gossip: PlainData::new(&[]),
             signature: CryptData::new(&[]),
@@ -202,6 +206,10 @@ impl NodeRecord {
         self.inner.accepts_connections
     }
 
+    pub fn accepts_tls(&self) -> bool {
+        self.inner.accepts_tls
+    }
+
     pub fn routes_data(&self) -> bool {
         self.inner.routes_data
     }
@@ -321,6 +329,7 @@ pub struct NodeRecordMetadata {
     pub last_update: u32,
     pub node_addr_opt: Option<NodeAddr>,
     pub unreachable_hosts: HashSet<String>,
+    pub unverified: bool,
 }
 
 impl NodeRecordMetadata {
@@ -329,6 +338,7 @@ impl NodeRecordMetadata {
             last_update: time_t_timestamp(),
             node_addr_opt: None,
             unreachable_hosts: Default::default(),
+            unverified: false,
         }
     }
 }
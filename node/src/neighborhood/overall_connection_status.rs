@@ -9,6 +9,7 @@ use crate::sub_lib::neighborhood::{
 use actix::Recipient;
 use masq_lib::logger::Logger;
 use masq_lib::messages::{ToMessageBody, UiConnectionChangeBroadcast, UiConnectionStage};
+use masq_lib::redaction::MarkSensitive;
 use masq_lib::ui_gateway::{MessageTarget, NodeToUiMessage};
 use std::net::IpAddr;
 use std::string::String;
@@ -84,7 +85,7 @@ impl ConnectionProgress {
         debug!(
             logger,
             "The connection stage for Node with IP address {:?} has been updated from {:?} to {:?}.",
-            self.current_peer_addr,
+            self.current_peer_addr.sensitive(),
             self.connection_stage,
             connection_stage
         );
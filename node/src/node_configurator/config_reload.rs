@@ -0,0 +1,272 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::apps::app_node;
+use crate::node_configurator::node_configurator_standard::server_initializer_collected_params;
+use crate::node_configurator::DirsWrapperReal;
+use clap::value_t;
+use masq_lib::multi_config::MultiConfig;
+use masq_lib::shared_schema::ConfiguratorError;
+use masq_lib::value_m;
+use std::collections::BTreeMap;
+
+// Parameters a running Node can take on without a restart. Any other parameter that changes in
+// the config file or the environment is only reported.
+pub const LIVE_PARAMETERS: [&str; 8] = [
+    "blockchain-service-url",
+    "dns-servers",
+    "log-level",
+    "min-hops",
+    "payment-thresholds",
+    "rate-pack",
+    "scan-intervals",
+    "spending-budget",
+];
+
+// Parameter name to value, for every parameter that has one
+pub type ConfigSnapshot = BTreeMap<String, String>;
+
+pub trait ConfigReader {
+    fn read(&self) -> Result<ConfigSnapshot, ConfiguratorError>;
+}
+
+// Reads the config file and the environment again, under the same command line the Node was
+// started with; so a parameter given on the command line can't be changed by a reload.
+pub struct ConfigReaderReal {
+    args: Vec<String>,
+}
+
+impl ConfigReader for ConfigReaderReal {
+    fn read(&self) -> Result<ConfigSnapshot, ConfiguratorError> {
+        let multi_config =
+            server_initializer_collected_params(&DirsWrapperReal::default(), &self.args)?;
+        Ok(snapshot_of(&multi_config))
+    }
+}
+
+impl Default for ConfigReaderReal {
+    fn default() -> Self {
+        Self {
+            args: std::env::args().collect(),
+        }
+    }
+}
+
+pub fn snapshot_of(multi_config: &MultiConfig) -> ConfigSnapshot {
+    app_node()
+        .p
+        .opts
+        .iter()
+        .filter_map(|opt| {
+            let name = opt.b.name;
+            value_m!(multi_config, name, String).map(|value| (name.to_string(), value))
+        })
+        .collect()
+}
+
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct ReloadPlan {
+    // Live parameters with new values, in name order
+    pub live_changes: Vec<(String, String)>,
+    pub restart_required: Vec<String>,
+}
+
+// A live parameter that has disappeared falls back to a default only a restart can work out,
+// so it is treated like any other parameter that needs a restart.
+pub fn plan_reload(running: &ConfigSnapshot, reloaded: &ConfigSnapshot) -> ReloadPlan {
+    let mut names = running.keys().chain(reloaded.keys()).collect::<Vec<_>>();
+    names.sort();
+    names.dedup();
+    names
+        .into_iter()
+        .filter(|name| running.get(*name) != reloaded.get(*name))
+        .fold(ReloadPlan::default(), |mut plan, name| {
+            match reloaded.get(name) {
+                Some(value) if LIVE_PARAMETERS.contains(&name.as_str()) => {
+                    plan.live_changes.push((name.clone(), value.clone()))
+                }
+                _ => plan.restart_required.push(name.clone()),
+            }
+            plan
+        })
+}
+
+pub fn describe_configurator_error(error: &ConfiguratorError) -> String {
+    error
+        .param_errors
+        .iter()
+        .map(|param_error| format!("{}: {}", param_error.parameter, param_error.reason))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+#[cfg(test)]
+pub mod test_utils {
+    use super::*;
+    use std::cell::RefCell;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default)]
+    pub struct ConfigReaderMock {
+        read_params: Arc<Mutex<Vec<()>>>,
+        read_results: RefCell<Vec<Result<ConfigSnapshot, ConfiguratorError>>>,
+    }
+
+    impl ConfigReader for ConfigReaderMock {
+        fn read(&self) -> Result<ConfigSnapshot, ConfiguratorError> {
+            self.read_params.lock().unwrap().push(());
+            self.read_results.borrow_mut().remove(0)
+        }
+    }
+
+    impl ConfigReaderMock {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        pub fn read_params(mut self, params: &Arc<Mutex<Vec<()>>>) -> Self {
+            self.read_params = params.clone();
+            self
+        }
+
+        pub fn read_result(self, result: Result<ConfigSnapshot, ConfiguratorError>) -> Self {
+            self.read_results.borrow_mut().push(result);
+            self
+        }
+    }
+
+    pub fn make_snapshot(pairs: &[(&str, &str)]) -> ConfigSnapshot {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_utils::make_snapshot;
+    use super::*;
+    use crate::node_test_utils::DirsWrapperMock;
+    use crate::test_utils::ArgsBuilder;
+    use masq_lib::test_utils::environment_guard::{ClapGuard, EnvironmentGuard};
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+    use std::fs::File;
+    use std::io::Write;
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(
+            LIVE_PARAMETERS,
+            [
+                "blockchain-service-url",
+                "dns-servers",
+                "log-level",
+                "min-hops",
+                "payment-thresholds",
+                "rate-pack",
+                "scan-intervals",
+                "spending-budget",
+            ]
+        );
+    }
+
+    #[test]
+    fn every_live_parameter_is_a_node_parameter() {
+        let app = app_node();
+        let names = app.p.opts.iter().map(|opt| opt.b.name).collect::<Vec<_>>();
+
+        LIVE_PARAMETERS
+            .iter()
+            .for_each(|name| assert!(names.contains(name), "{} is unknown", name));
+    }
+
+    #[test]
+    fn snapshot_of_takes_values_from_the_config_file() {
+        let _guard = EnvironmentGuard::new();
+        let _clap_guard = ClapGuard::new();
+        let home_dir = ensure_node_home_directory_exists(
+            "config_reload",
+            "snapshot_of_takes_values_from_the_config_file",
+        );
+        {
+            let mut config_file = File::create(home_dir.join("config.toml")).unwrap();
+            config_file
+                .write_all(b"dns-servers = \"5.6.7.8\"\nrate-pack = \"2|2|2|2\"\n")
+                .unwrap();
+        }
+        let args: Vec<String> = ArgsBuilder::new()
+            .param("--data-directory", home_dir.to_str().unwrap())
+            .param("--log-level", "info")
+            .into();
+        let dirs_wrapper = DirsWrapperMock::new()
+            .home_dir_result(Some(home_dir.clone()))
+            .data_dir_result(Some(home_dir.clone()));
+        let multi_config = server_initializer_collected_params(&dirs_wrapper, &args).unwrap();
+
+        let result = snapshot_of(&multi_config);
+
+        assert_eq!(result.get("dns-servers"), Some(&"5.6.7.8".to_string()));
+        assert_eq!(result.get("rate-pack"), Some(&"2|2|2|2".to_string()));
+        assert_eq!(result.get("log-level"), Some(&"info".to_string()));
+        assert_eq!(result.get("scan-intervals"), None);
+    }
+
+    #[test]
+    fn plan_reload_sorts_changes_into_live_and_restart_required() {
+        let running = make_snapshot(&[
+            ("clandestine-port", "1234"),
+            ("dns-servers", "1.1.1.1"),
+            ("log-level", "warn"),
+            ("neighborhood-mode", "standard"),
+            ("rate-pack", "1|1|1|1"),
+            ("scan-intervals", "60|60|60"),
+        ]);
+        let reloaded = make_snapshot(&[
+            ("clandestine-port", "2345"),
+            ("dns-servers", "1.1.1.1"),
+            ("gas-price", "10"),
+            ("log-level", "debug"),
+            ("neighborhood-mode", "standard"),
+            ("rate-pack", "2|2|2|2"),
+        ]);
+
+        let result = plan_reload(&running, &reloaded);
+
+        assert_eq!(
+            result,
+            ReloadPlan {
+                live_changes: vec![
+                    ("log-level".to_string(), "debug".to_string()),
+                    ("rate-pack".to_string(), "2|2|2|2".to_string()),
+                ],
+                restart_required: vec![
+                    "clandestine-port".to_string(),
+                    "gas-price".to_string(),
+                    "scan-intervals".to_string(),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn plan_reload_finds_nothing_to_do_when_nothing_changed() {
+        let snapshot = make_snapshot(&[("log-level", "warn"), ("clandestine-port", "1234")]);
+
+        let result = plan_reload(&snapshot, &snapshot.clone());
+
+        assert_eq!(result, ReloadPlan::default());
+    }
+
+    #[test]
+    fn describe_configurator_error_lists_every_parameter() {
+        let error = ConfiguratorError::required("rate-pack", "Bad format")
+            .another_required("dns-servers", "Not an IP address");
+
+        let result = describe_configurator_error(&error);
+
+        assert_eq!(
+            result,
+            "rate-pack: Bad format; dns-servers: Not an IP address".to_string()
+        );
+    }
+}
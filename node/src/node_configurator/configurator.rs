@@ -1,17 +1,22 @@
 // Copyright (c) 2019-2021, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
+use std::net::{IpAddr, SocketAddr};
 use std::path::PathBuf;
 use std::str::FromStr;
+use std::time::Duration;
 
-use actix::{Actor, Context, Handler, Recipient};
+use actix::{Actor, Context, Handler, Message, Recipient};
 
 use masq_lib::messages::{
     FromMessageBody, ToMessageBody, UiChangePasswordRequest, UiChangePasswordResponse,
     UiCheckPasswordRequest, UiCheckPasswordResponse, UiConfigurationRequest,
-    UiConfigurationResponse, UiGenerateSeedSpec, UiGenerateWalletsRequest,
-    UiGenerateWalletsResponse, UiNewPasswordBroadcast, UiPaymentThresholds, UiRatePack,
-    UiRecoverWalletsRequest, UiRecoverWalletsResponse, UiScanIntervals, UiSetConfigurationRequest,
-    UiSetConfigurationResponse, UiWalletAddressesRequest, UiWalletAddressesResponse,
+    UiConfigurationResponse, UiDatabaseBackupRequest, UiDatabaseBackupResponse,
+    UiExportWalletRequest, UiExportWalletResponse, UiGenerateSeedSpec, UiGenerateWalletsRequest,
+    UiGenerateWalletsResponse, UiKeystoreSpec, UiLogStreamRequest, UiLogStreamResponse,
+    UiNewPasswordBroadcast, UiPaymentThresholds, UiRatePack, UiRecoverWalletsRequest,
+    UiRecoverWalletsResponse, UiReloadConfigFailure, UiReloadConfigRequest, UiReloadConfigResponse,
+    UiScanIntervals, UiSetConfigurationRequest, UiSetConfigurationResponse, UiSetLogLevelRequest,
+    UiSetLogLevelResponse, UiWalletAddressesRequest, UiWalletAddressesResponse, WalletExportFormat,
 };
 use masq_lib::ui_gateway::MessageTarget::ClientId;
 use masq_lib::ui_gateway::{
@@ -20,34 +25,58 @@ use masq_lib::ui_gateway::{
 
 use crate::blockchain::bip32::Bip32EncryptionKeyProvider;
 use crate::blockchain::bip39::Bip39;
+use crate::blockchain::keystore::Keystore;
+use crate::database::db_backup::{DatabaseBackup, DatabaseBackupReal};
 use crate::database::db_initializer::DbInitializationConfig;
 use crate::database::db_initializer::{DbInitializer, DbInitializerReal};
 use crate::db_config::config_dao::ConfigDaoReal;
 use crate::db_config::persistent_configuration::{
     PersistentConfigError, PersistentConfiguration, PersistentConfigurationReal,
 };
-use crate::sub_lib::neighborhood::{ConfigChange, ConfigChangeMsg, Hops, WalletPair};
+use crate::node_configurator::config_reload::{
+    describe_configurator_error, plan_reload, ConfigReader, ConfigSnapshot,
+};
+use crate::node_configurator::unprivileged_parse_args_configuration::check_payment_thresholds;
+use crate::server_initializer::{LogLevelSetter, LogLevelSetterReal};
+use crate::sub_lib::accountant::{PaymentThresholds, ScanIntervals, SpendingBudget};
+use crate::sub_lib::bandwidth_shaper::{BandwidthLimits, BandwidthShaper, DataQuota};
+use crate::sub_lib::neighborhood::{ConfigChange, ConfigChangeMsg, Hops, RatePack, WalletPair};
 use crate::sub_lib::peer_actors::{BindMessage, ConfigChangeSubs};
-use crate::sub_lib::utils::{db_connection_launch_panic, handle_ui_crash_request};
+use crate::sub_lib::utils::{
+    db_connection_launch_panic, handle_ui_crash_request, NotifyLaterHandle, NotifyLaterHandleReal,
+};
 use crate::sub_lib::wallet::Wallet;
 use crate::test_utils::main_cryptde;
 use bip39::{Language, Mnemonic, MnemonicType, Seed};
+use log::{Level, LevelFilter};
 use masq_lib::constants::{
-    BAD_PASSWORD_ERROR, CONFIGURATOR_READ_ERROR, CONFIGURATOR_WRITE_ERROR, DERIVATION_PATH_ERROR,
-    ILLEGAL_MNEMONIC_WORD_COUNT_ERROR, MISSING_DATA, MNEMONIC_PHRASE_ERROR, NON_PARSABLE_VALUE,
+    BAD_PASSWORD_ERROR, CONFIGURATOR_READ_ERROR, CONFIGURATOR_WRITE_ERROR, CONFIG_RELOAD_ERROR,
+    DATABASE_BACKUP_ERROR, DERIVATION_PATH_ERROR, ILLEGAL_MNEMONIC_WORD_COUNT_ERROR,
+    KEYSTORE_ERROR, LOG_LEVEL_ERROR, MISSING_DATA, MNEMONIC_PHRASE_ERROR, NON_PARSABLE_VALUE,
     UNKNOWN_ERROR, UNRECOGNIZED_MNEMONIC_LANGUAGE_ERROR, UNRECOGNIZED_PARAMETER,
 };
-use masq_lib::logger::Logger;
+use masq_lib::logger::{
+    subscribe_to_log_stream, unsubscribe_from_log_stream, LogStreamSubscription, Logger,
+};
 use masq_lib::utils::{derivation_path, to_string};
 use rustc_hex::{FromHex, ToHex};
 use tiny_hderive::bip32::ExtendedPrivKey;
 
 pub const CRASH_KEY: &str = "CONFIGURATOR";
+// Also bounds how much data usage a crash can forget
+pub const CHECK_DATA_QUOTA_INTERVAL: Duration = Duration::from_secs(60);
 
 pub struct Configurator {
     persistent_config: Box<dyn PersistentConfiguration>,
+    database_backup: Box<dyn DatabaseBackup>,
+    log_level_setter: Box<dyn LogLevelSetter>,
     node_to_ui_sub_opt: Option<Recipient<NodeToUiMessage>>,
     config_change_subs_opt: Option<ConfigChangeSubs>,
+    bandwidth_shaper: BandwidthShaper,
+    config_reader_opt: Option<Box<dyn ConfigReader>>,
+    running_config_opt: Option<ConfigSnapshot>,
+    notify_later_check_data_quota: Box<dyn NotifyLaterHandle<CheckDataQuotaMessage, Configurator>>,
+    check_data_quota_interval: Duration,
     crashable: bool,
     logger: Logger,
 }
@@ -59,9 +88,43 @@ impl Actor for Configurator {
 impl Handler<BindMessage> for Configurator {
     type Result = ();
 
-    fn handle(&mut self, msg: BindMessage, _ctx: &mut Self::Context) -> Self::Result {
+    fn handle(&mut self, msg: BindMessage, ctx: &mut Self::Context) -> Self::Result {
         self.node_to_ui_sub_opt = Some(msg.peer_actors.ui_gateway.node_to_ui_message_sub.clone());
         self.config_change_subs_opt = Some(msg.peer_actors.config_change_subs());
+        self.notify_later_check_data_quota.notify_later(
+            CheckDataQuotaMessage::default(),
+            self.check_data_quota_interval,
+            ctx,
+        );
+        #[cfg(unix)]
+        self.listen_for_reload_signal(ctx);
+    }
+}
+
+#[derive(Clone, Debug, Default, Message, PartialEq, Eq)]
+pub struct CheckDataQuotaMessage {}
+
+impl Handler<CheckDataQuotaMessage> for Configurator {
+    type Result = ();
+
+    fn handle(&mut self, msg: CheckDataQuotaMessage, ctx: &mut Self::Context) -> Self::Result {
+        self.bandwidth_shaper.check_quota();
+        self.save_data_usage();
+        self.notify_later_check_data_quota
+            .notify_later(msg, self.check_data_quota_interval, ctx);
+    }
+}
+
+#[derive(Message, Debug, PartialEq, Eq)]
+pub struct ReloadConfigOnSignal {}
+
+impl Handler<ReloadConfigOnSignal> for Configurator {
+    type Result = ();
+
+    fn handle(&mut self, _msg: ReloadConfigOnSignal, _ctx: &mut Self::Context) -> Self::Result {
+        info!(self.logger, "Received SIGHUP; reloading configuration");
+        // The outcome has already been logged, and there's nobody else to tell
+        let _ = self.reload_config();
     }
 }
 
@@ -78,12 +141,23 @@ impl Handler<NodeFromUiMessage> for Configurator {
             self.call_handler(msg, |c| c.handle_check_password(body, context_id));
         } else if let Ok((body, context_id)) = UiConfigurationRequest::fmb(msg.body.clone()) {
             self.call_handler(msg, |c| c.handle_configuration(body, context_id));
+        } else if let Ok((body, context_id)) = UiDatabaseBackupRequest::fmb(msg.body.clone()) {
+            self.call_handler(msg, |c| c.handle_database_backup(body, context_id));
+        } else if let Ok((body, context_id)) = UiExportWalletRequest::fmb(msg.body.clone()) {
+            self.call_handler(msg, |c| c.handle_export_wallet(body, context_id));
         } else if let Ok((body, context_id)) = UiGenerateWalletsRequest::fmb(msg.body.clone()) {
             self.call_handler(msg, |c| c.handle_generate_wallets(body, context_id));
+        } else if let Ok((body, context_id)) = UiLogStreamRequest::fmb(msg.body.clone()) {
+            let client_id = msg.client_id;
+            self.call_handler(msg, |c| c.handle_log_stream(body, client_id, context_id));
         } else if let Ok((body, context_id)) = UiRecoverWalletsRequest::fmb(msg.body.clone()) {
             self.call_handler(msg, |c| c.handle_recover_wallets(body, context_id));
+        } else if let Ok((body, context_id)) = UiReloadConfigRequest::fmb(msg.body.clone()) {
+            self.call_handler(msg, |c| c.handle_reload_config(body, context_id));
         } else if let Ok((body, context_id)) = UiSetConfigurationRequest::fmb(msg.body.clone()) {
             self.call_handler(msg, |c| c.handle_set_configuration(body, context_id));
+        } else if let Ok((body, context_id)) = UiSetLogLevelRequest::fmb(msg.body.clone()) {
+            self.call_handler(msg, |c| c.handle_set_log_level(body, context_id));
         } else if let Ok((body, context_id)) = UiWalletAddressesRequest::fmb(msg.body.clone()) {
             self.call_handler(msg, |c| c.handle_wallet_addresses(body, context_id));
         } else {
@@ -94,27 +168,106 @@ impl Handler<NodeFromUiMessage> for Configurator {
 
 type MessageError = (u64, String);
 
+// A parameter value from a reloaded configuration, parsed and ready to be put into force
+#[derive(Debug, PartialEq)]
+enum LiveChange {
+    BlockchainServiceUrl(String),
+    DnsServers(Vec<SocketAddr>),
+    LogLevel(LevelFilter),
+    MinHops(String),
+    PaymentThresholds(PaymentThresholds, String),
+    RatePack(RatePack, String),
+    ScanIntervals(ScanIntervals, String),
+    SpendingBudget(SpendingBudget, String),
+}
+
 impl Configurator {
-    pub fn new(data_directory: PathBuf, crashable: bool) -> Self {
+    pub fn new(
+        data_directory: PathBuf,
+        bandwidth_shaper: BandwidthShaper,
+        crashable: bool,
+    ) -> Self {
         let initializer = DbInitializerReal::default();
-        let conn = initializer
-            .initialize(
-                &data_directory,
-                DbInitializationConfig::panic_on_migration(),
-            )
-            .unwrap_or_else(|err| db_connection_launch_panic(err, &data_directory));
-        let config_dao = ConfigDaoReal::new(conn);
+        let connect = || {
+            initializer
+                .initialize(
+                    &data_directory,
+                    DbInitializationConfig::panic_on_migration(),
+                )
+                .unwrap_or_else(|err| db_connection_launch_panic(err, &data_directory))
+        };
+        let config_dao = ConfigDaoReal::new(connect());
         let persistent_config: Box<dyn PersistentConfiguration> =
             Box::new(PersistentConfigurationReal::new(Box::new(config_dao)));
-        Configurator {
+        let configurator = Configurator {
             persistent_config,
+            database_backup: Box::new(DatabaseBackupReal::new(connect())),
+            log_level_setter: Box::new(LogLevelSetterReal),
             node_to_ui_sub_opt: None,
             config_change_subs_opt: None,
+            bandwidth_shaper,
+            config_reader_opt: None,
+            running_config_opt: None,
+            notify_later_check_data_quota: Box::new(NotifyLaterHandleReal::new()),
+            check_data_quota_interval: CHECK_DATA_QUOTA_INTERVAL,
             crashable,
             logger: Logger::new("Configurator"),
+        };
+        configurator.restore_data_usage();
+        configurator
+    }
+
+    fn restore_data_usage(&self) {
+        match self.persistent_config.data_usage() {
+            Ok(Some(usage)) => self.bandwidth_shaper.restore_data_usage(usage),
+            Ok(None) => (),
+            Err(e) => warning!(
+                self.logger,
+                "Could not restore data usage; counting from zero: {:?}",
+                e
+            ),
+        }
+    }
+
+    fn save_data_usage(&mut self) {
+        let usage = self.bandwidth_shaper.data_usage();
+        if let Err(e) = self.persistent_config.set_data_usage(usage) {
+            warning!(self.logger, "Could not save data usage: {:?}", e)
         }
     }
 
+    // The configuration read here is the one later reloads are compared against
+    pub fn set_config_reader(&mut self, config_reader: Box<dyn ConfigReader>) {
+        self.running_config_opt = match config_reader.read() {
+            Ok(snapshot) => Some(snapshot),
+            Err(e) => {
+                warning!(
+                    self.logger,
+                    "Could not read the starting configuration: {}",
+                    describe_configurator_error(&e)
+                );
+                None
+            }
+        };
+        self.config_reader_opt = Some(config_reader);
+    }
+
+    #[cfg(unix)]
+    fn listen_for_reload_signal(&self, ctx: &mut Context<Self>) {
+        use actix::{ActorFuture, AsyncContext, WrapFuture};
+        use futures::{Future, Stream};
+        use tokio_signal::unix::{Signal, SIGHUP};
+
+        let logger = self.logger.clone();
+        let future = Signal::new(SIGHUP)
+            .map_err(move |e| error!(logger, "Cannot listen for SIGHUP: {:?}", e))
+            .into_actor(self)
+            .map(|signal, _act, ctx| {
+                ctx.add_message_stream(signal.map_err(|_| ()).map(|_| ReloadConfigOnSignal {}));
+            });
+        ctx.spawn(future);
+    }
+
     fn handle_check_password(
         &mut self,
         msg: UiCheckPasswordRequest,
@@ -343,8 +496,12 @@ impl Configurator {
             "recover",
             &msg.db_password,
         )?;
+        let consuming_private_key_opt = Self::resolve_consuming_private_key(
+            msg.consuming_private_key_opt,
+            msg.consuming_keystore_opt,
+        )?;
         let (consuming_wallet_private_key, earning_wallet_address) = match msg.seed_spec_opt {
-            None => match (&msg.consuming_private_key_opt, msg.earning_address_opt) {
+            None => match (&consuming_private_key_opt, msg.earning_address_opt) {
                 (Some (consuming_private_key), Some (earning_address)) => (consuming_private_key.clone(), earning_address),
                 _ => return Err ((MISSING_DATA, "If you supply no seed information, you must supply both consuming wallet private key and earning wallet address".to_string())),
             },
@@ -354,7 +511,7 @@ impl Configurator {
                     &seed_spec.mnemonic_phrase_language_opt.unwrap_or_else (|| "English".to_string()),
                     &seed_spec.mnemonic_phrase,
                 )?;
-                let consuming_private_key = match (msg.consuming_private_key_opt, msg.consuming_derivation_path_opt) {
+                let consuming_private_key = match (consuming_private_key_opt, msg.consuming_derivation_path_opt) {
                     (Some (consuming_private_key), _) => consuming_private_key,
                     (None, Some (consuming_derivation_path)) => {
                         Self::generate_private_key(seed.as_bytes(), consuming_derivation_path.as_str())?
@@ -381,6 +538,507 @@ impl Configurator {
         Ok(UiRecoverWalletsResponse {}.tmb(context_id))
     }
 
+    fn resolve_consuming_private_key(
+        private_key_opt: Option<String>,
+        keystore_opt: Option<UiKeystoreSpec>,
+    ) -> Result<Option<String>, MessageError> {
+        match (private_key_opt, keystore_opt) {
+            (Some(_), Some(_)) => Err((
+                KEYSTORE_ERROR,
+                "Supply either a consuming wallet private key or a keystore, not both".to_string(),
+            )),
+            (None, Some(keystore)) => {
+                match Keystore::decrypt(&keystore.keystore_json, &keystore.passphrase) {
+                    Ok(key_pair) => Ok(Some(
+                        key_pair.clone_secret().to_hex::<String>().to_uppercase(),
+                    )),
+                    Err(e) => Err((KEYSTORE_ERROR, e.to_string())),
+                }
+            }
+            (private_key_opt, None) => Ok(private_key_opt),
+        }
+    }
+
+    fn handle_database_backup(
+        &mut self,
+        msg: UiDatabaseBackupRequest,
+        context_id: u64,
+    ) -> MessageBody {
+        match Self::unfriendly_handle_database_backup(
+            msg,
+            context_id,
+            self.persistent_config.as_ref(),
+            self.database_backup.as_ref(),
+        ) {
+            Ok((path, message_body)) => {
+                info!(self.logger, "Database backed up to {}", path);
+                message_body
+            }
+            Err((code, e_msg)) => {
+                warning!(
+                    self.logger,
+                    "Failed to back up database: {}, {}",
+                    code,
+                    e_msg
+                );
+                MessageBody {
+                    opcode: "databaseBackup".to_string(),
+                    path: MessagePath::Conversation(context_id),
+                    payload: Err((code, e_msg)),
+                }
+            }
+        }
+    }
+
+    fn unfriendly_handle_database_backup(
+        msg: UiDatabaseBackupRequest,
+        context_id: u64,
+        persistent_config: &dyn PersistentConfiguration,
+        database_backup: &dyn DatabaseBackup,
+    ) -> Result<(String, MessageBody), MessageError> {
+        let destination = PathBuf::from(&msg.path);
+        if !destination.is_absolute() {
+            return Err((
+                DATABASE_BACKUP_ERROR,
+                format!("Backup path must be absolute, not '{}'", msg.path),
+            ));
+        }
+        if let Some(db_password) = &msg.db_password_opt {
+            match persistent_config.check_password(Some(db_password.clone())) {
+                Ok(true) => (),
+                Ok(false) => {
+                    return Err((
+                        BAD_PASSWORD_ERROR,
+                        "Bad password; can't encrypt backup".to_string(),
+                    ))
+                }
+                Err(e) => {
+                    return Err((
+                        CONFIGURATOR_READ_ERROR,
+                        format!("Error checking password: {:?}", e),
+                    ))
+                }
+            }
+        }
+        let size_bytes = database_backup
+            .back_up(&destination, msg.db_password_opt.as_deref())
+            .map_err(|e| (DATABASE_BACKUP_ERROR, e.to_string()))?;
+        let response = UiDatabaseBackupResponse {
+            path: msg.path.clone(),
+            encrypted: msg.db_password_opt.is_some(),
+            size_bytes,
+        };
+        Ok((msg.path, response.tmb(context_id)))
+    }
+
+    fn handle_log_stream(
+        &mut self,
+        msg: UiLogStreamRequest,
+        client_id: u64,
+        context_id: u64,
+    ) -> MessageBody {
+        match Self::unfriendly_handle_log_stream(msg, client_id, context_id) {
+            Ok(message_body) => message_body,
+            Err((code, e_msg)) => {
+                warning!(
+                    self.logger,
+                    "Failed to change log stream for client {}: {}, {}",
+                    client_id,
+                    code,
+                    e_msg
+                );
+                MessageBody {
+                    opcode: "logStream".to_string(),
+                    path: MessagePath::Conversation(context_id),
+                    payload: Err((code, e_msg)),
+                }
+            }
+        }
+    }
+
+    fn unfriendly_handle_log_stream(
+        msg: UiLogStreamRequest,
+        client_id: u64,
+        context_id: u64,
+    ) -> Result<MessageBody, MessageError> {
+        if msg.follow {
+            // Info and above already goes to every UI as a logBroadcast
+            let level = match Level::from_str(&msg.level) {
+                Ok(level) if level > Level::Info => level,
+                _ => {
+                    return Err((
+                        LOG_LEVEL_ERROR,
+                        format!(
+                            "Log stream level must be 'debug' or 'trace', not '{}'",
+                            msg.level
+                        ),
+                    ))
+                }
+            };
+            subscribe_to_log_stream(LogStreamSubscription::new(
+                client_id,
+                level,
+                msg.module_opt.as_deref(),
+            ));
+        } else {
+            unsubscribe_from_log_stream(client_id);
+        }
+        Ok(UiLogStreamResponse {}.tmb(context_id))
+    }
+
+    fn handle_set_log_level(&mut self, msg: UiSetLogLevelRequest, context_id: u64) -> MessageBody {
+        match Self::unfriendly_handle_set_log_level(msg, context_id, self.log_level_setter.as_ref())
+        {
+            Ok((log_spec, message_body)) => {
+                info!(self.logger, "Log levels changed to {}", log_spec);
+                message_body
+            }
+            Err((code, e_msg)) => {
+                warning!(
+                    self.logger,
+                    "Failed to change log levels: {}, {}",
+                    code,
+                    e_msg
+                );
+                MessageBody {
+                    opcode: "setLogLevel".to_string(),
+                    path: MessagePath::Conversation(context_id),
+                    payload: Err((code, e_msg)),
+                }
+            }
+        }
+    }
+
+    fn unfriendly_handle_set_log_level(
+        msg: UiSetLogLevelRequest,
+        context_id: u64,
+        log_level_setter: &dyn LogLevelSetter,
+    ) -> Result<(String, MessageBody), MessageError> {
+        let global_opt = msg
+            .level_opt
+            .as_deref()
+            .map(Self::parse_level_filter)
+            .transpose()?;
+        let module_levels = msg
+            .module_levels
+            .into_iter()
+            .map(|module_level| {
+                if module_level.module.trim().is_empty() {
+                    return Err((LOG_LEVEL_ERROR, "Module name must not be empty".to_string()));
+                }
+                let level = Self::parse_level_filter(&module_level.level)?;
+                Ok((module_level.module, level))
+            })
+            .collect::<Result<Vec<(String, LevelFilter)>, MessageError>>()?;
+        if global_opt.is_none() && module_levels.is_empty() {
+            return Err((
+                LOG_LEVEL_ERROR,
+                "Specify a global level, module levels, or both".to_string(),
+            ));
+        }
+        let log_spec = log_level_setter
+            .set_log_levels(global_opt, module_levels)
+            .map_err(|e| (LOG_LEVEL_ERROR, e))?;
+        let response = UiSetLogLevelResponse {
+            log_spec: log_spec.clone(),
+        };
+        Ok((log_spec, response.tmb(context_id)))
+    }
+
+    fn parse_level_filter(level: &str) -> Result<LevelFilter, MessageError> {
+        LevelFilter::from_str(level).map_err(|_| {
+            (
+                LOG_LEVEL_ERROR,
+                format!(
+                    "Unrecognized log level '{}'; use off, error, warn, info, debug or trace",
+                    level
+                ),
+            )
+        })
+    }
+
+    fn handle_reload_config(&mut self, msg: UiReloadConfigRequest, context_id: u64) -> MessageBody {
+        match self.reload_config() {
+            Ok(response) => response.tmb(context_id),
+            Err((code, e_msg)) => MessageBody {
+                opcode: msg.opcode().to_string(),
+                path: MessagePath::Conversation(context_id),
+                payload: Err((code, e_msg)),
+            },
+        }
+    }
+
+    fn reload_config(&mut self) -> Result<UiReloadConfigResponse, MessageError> {
+        let result = self.unfriendly_reload_config();
+        match &result {
+            Ok(response) if response.failed.is_empty() => info!(
+                self.logger,
+                "Configuration reloaded; applied: {:?}; restart required: {:?}",
+                response.applied,
+                response.restart_required
+            ),
+            Ok(response) => warning!(
+                self.logger,
+                "Configuration partly reloaded; applied: {:?}; failed: {}; restart required: {:?}",
+                response.applied,
+                response
+                    .failed
+                    .iter()
+                    .map(|failure| format!(
+                        "{} ({}, {})",
+                        failure.parameter, failure.code, failure.message
+                    ))
+                    .collect::<Vec<String>>()
+                    .join(", "),
+                response.restart_required
+            ),
+            Err((code, e_msg)) => warning!(
+                self.logger,
+                "Failed to reload configuration: {}, {}",
+                code,
+                e_msg
+            ),
+        }
+        result
+    }
+
+    fn unfriendly_reload_config(&mut self) -> Result<UiReloadConfigResponse, MessageError> {
+        let reloaded = match self.config_reader_opt.as_ref() {
+            Some(config_reader) => config_reader
+                .read()
+                .map_err(|e| (CONFIG_RELOAD_ERROR, describe_configurator_error(&e)))?,
+            None => {
+                return Err((
+                    CONFIG_RELOAD_ERROR,
+                    "This Node cannot reload its configuration".to_string(),
+                ))
+            }
+        };
+        let plan = match self.running_config_opt.as_ref() {
+            Some(running) => plan_reload(running, &reloaded),
+            None => {
+                self.running_config_opt = Some(reloaded);
+                return Err((
+                    CONFIG_RELOAD_ERROR,
+                    "The starting configuration was unknown, so there was nothing to compare with; nothing was applied, but the next reload will compare with this one".to_string(),
+                ));
+            }
+        };
+        // Nothing is applied unless every change can be
+        let live_changes = plan
+            .live_changes
+            .iter()
+            .map(|(name, value)| Self::parse_live_change(name, value))
+            .collect::<Result<Vec<LiveChange>, MessageError>>()?;
+        // A change that can't be stored stays out of the running configuration, so the next
+        // reload tries it again; it doesn't hold back the changes after it
+        let mut applied = vec![];
+        let mut failed = vec![];
+        for ((name, value), live_change) in plan.live_changes.into_iter().zip(live_changes) {
+            match self.apply_live_change(live_change) {
+                Ok(()) => {
+                    self.running_config_opt
+                        .as_mut()
+                        .expect("Running configuration disappeared")
+                        .insert(name.clone(), value);
+                    applied.push(name);
+                }
+                Err((code, message)) => failed.push(UiReloadConfigFailure {
+                    parameter: name,
+                    code,
+                    message,
+                }),
+            }
+        }
+        Ok(UiReloadConfigResponse {
+            applied,
+            failed,
+            restart_required: plan.restart_required,
+        })
+    }
+
+    fn parse_live_change(name: &str, value: &str) -> Result<LiveChange, MessageError> {
+        match name {
+            "blockchain-service-url" => Ok(LiveChange::BlockchainServiceUrl(value.to_string())),
+            "dns-servers" => value
+                .split(',')
+                .map(|ip_str| IpAddr::from_str(ip_str.trim()).map(|ip| SocketAddr::new(ip, 53)))
+                .collect::<Result<Vec<SocketAddr>, _>>()
+                .map(LiveChange::DnsServers)
+                .map_err(|e| (NON_PARSABLE_VALUE, format!("dns servers: {:?}", e))),
+            "log-level" => Self::parse_level_filter(value).map(LiveChange::LogLevel),
+            "min-hops" => match Hops::from_str(value) {
+                Ok(_) => Ok(LiveChange::MinHops(value.to_string())),
+                Err(e) => Err((NON_PARSABLE_VALUE, format!("min hops: {:?}", e))),
+            },
+            "payment-thresholds" => {
+                let payment_thresholds = PaymentThresholds::try_from(value)
+                    .map_err(|e| (NON_PARSABLE_VALUE, format!("payment thresholds: {}", e)))?;
+                check_payment_thresholds(&payment_thresholds)
+                    .map_err(|e| (NON_PARSABLE_VALUE, describe_configurator_error(&e)))?;
+                Ok(LiveChange::PaymentThresholds(
+                    payment_thresholds,
+                    value.to_string(),
+                ))
+            }
+            "rate-pack" => RatePack::try_from(value)
+                .map(|rate_pack| LiveChange::RatePack(rate_pack, value.to_string()))
+                .map_err(|e| (NON_PARSABLE_VALUE, format!("rate pack: {}", e))),
+            "scan-intervals" => ScanIntervals::try_from(value)
+                .map(|scan_intervals| LiveChange::ScanIntervals(scan_intervals, value.to_string()))
+                .map_err(|e| (NON_PARSABLE_VALUE, format!("scan intervals: {}", e))),
+            "spending-budget" => SpendingBudget::try_from(value)
+                .map(|spending_budget| {
+                    LiveChange::SpendingBudget(spending_budget, value.to_string())
+                })
+                .map_err(|e| (NON_PARSABLE_VALUE, format!("spending budget: {}", e))),
+            _ => Err((
+                UNRECOGNIZED_PARAMETER,
+                format!(
+                    "This parameter can't be changed without a restart: {}",
+                    name
+                ),
+            )),
+        }
+    }
+
+    fn apply_live_change(&mut self, live_change: LiveChange) -> Result<(), MessageError> {
+        let change = match live_change {
+            LiveChange::BlockchainServiceUrl(url) => {
+                self.persistent_config
+                    .set_blockchain_service_url(&url)
+                    .map_err(|e| {
+                        (
+                            CONFIGURATOR_WRITE_ERROR,
+                            format!("blockchain service url: {:?}", e),
+                        )
+                    })?;
+                ConfigChange::UpdateBlockchainServiceUrl(url)
+            }
+            LiveChange::DnsServers(dns_servers) => ConfigChange::UpdateDnsServers(dns_servers),
+            LiveChange::LogLevel(level) => {
+                self.log_level_setter
+                    .set_log_levels(Some(level), vec![])
+                    .map_err(|e| (LOG_LEVEL_ERROR, e))?;
+                return Ok(());
+            }
+            LiveChange::MinHops(min_hops_value) => return self.set_min_hops(min_hops_value),
+            LiveChange::PaymentThresholds(payment_thresholds, string) => {
+                self.persistent_config
+                    .set_payment_thresholds(string)
+                    .map_err(|e| {
+                        (
+                            CONFIGURATOR_WRITE_ERROR,
+                            format!("payment thresholds: {:?}", e),
+                        )
+                    })?;
+                ConfigChange::UpdatePaymentThresholds(payment_thresholds)
+            }
+            LiveChange::RatePack(rate_pack, string) => {
+                self.persistent_config
+                    .set_rate_pack(string)
+                    .map_err(|e| (CONFIGURATOR_WRITE_ERROR, format!("rate pack: {:?}", e)))?;
+                ConfigChange::UpdateRatePack(rate_pack)
+            }
+            LiveChange::ScanIntervals(scan_intervals, string) => {
+                self.persistent_config
+                    .set_scan_intervals(string)
+                    .map_err(|e| (CONFIGURATOR_WRITE_ERROR, format!("scan intervals: {:?}", e)))?;
+                ConfigChange::UpdateScanIntervals(scan_intervals)
+            }
+            LiveChange::SpendingBudget(spending_budget, string) => {
+                self.persistent_config
+                    .set_spending_budget(string)
+                    .map_err(|e| {
+                        (
+                            CONFIGURATOR_WRITE_ERROR,
+                            format!("spending budget: {:?}", e),
+                        )
+                    })?;
+                ConfigChange::UpdateSpendingBudget(spending_budget)
+            }
+        };
+        self.send_config_change_msg(ConfigChangeMsg { change });
+        Ok(())
+    }
+
+    fn handle_export_wallet(&mut self, msg: UiExportWalletRequest, context_id: u64) -> MessageBody {
+        match Self::unfriendly_handle_export_wallet(
+            msg,
+            context_id,
+            self.persistent_config.as_ref(),
+        ) {
+            Ok((wallet_address, message_body)) => {
+                info!(self.logger, "Consuming wallet {} exported", wallet_address);
+                message_body
+            }
+            Err((code, e_msg)) => {
+                warning!(
+                    self.logger,
+                    "Failed to export consuming wallet: {}, {}",
+                    code,
+                    e_msg
+                );
+                MessageBody {
+                    opcode: "exportWallet".to_string(),
+                    path: MessagePath::Conversation(context_id),
+                    payload: Err((code, e_msg)),
+                }
+            }
+        }
+    }
+
+    fn unfriendly_handle_export_wallet(
+        msg: UiExportWalletRequest,
+        context_id: u64,
+        persistent_config: &dyn PersistentConfiguration,
+    ) -> Result<(String, MessageBody), MessageError> {
+        Self::check_database_preconditions(persistent_config, "export", &msg.db_password)?;
+        if msg.passphrase.is_empty() {
+            return Err((
+                KEYSTORE_ERROR,
+                "The export passphrase must not be empty".to_string(),
+            ));
+        }
+        let private_key = match persistent_config.consuming_wallet_private_key(&msg.db_password) {
+            Ok(Some(private_key)) => private_key,
+            Ok(None) => {
+                return Err((
+                    MISSING_DATA,
+                    "Consuming wallet not yet configured".to_string(),
+                ))
+            }
+            Err(e) => {
+                return Err((
+                    CONFIGURATOR_READ_ERROR,
+                    format!("Consuming wallet error: {:?}", e),
+                ))
+            }
+        };
+        let key_pair = private_key
+            .from_hex::<Vec<u8>>()
+            .map_err(|e| format!("{:?}", e))
+            .and_then(|key_bytes| Bip32EncryptionKeyProvider::from_raw_secret(&key_bytes))
+            .map_err(|e| {
+                (
+                    CONFIGURATOR_READ_ERROR,
+                    format!("Consuming wallet private key is unusable: {}", e),
+                )
+            })?;
+        let exported = match msg.format {
+            WalletExportFormat::Keystore => Keystore::encrypt(&key_pair, &msg.passphrase)
+                .map_err(|e| (KEYSTORE_ERROR, e.to_string()))?,
+        };
+        let wallet_address = format!("{:?}", key_pair.address());
+        let message_body = UiExportWalletResponse {
+            wallet_address: wallet_address.clone(),
+            format: msg.format,
+            exported,
+        }
+        .tmb(context_id);
+        Ok((wallet_address, message_body))
+    }
+
     fn check_database_preconditions(
         persistent_config: &dyn PersistentConfiguration,
         operation: &str,
@@ -734,8 +1392,11 @@ impl Configurator {
 
         match password {
             None => match msg.name.as_str() {
+                "bandwidth-limits" => self.set_bandwidth_limits(msg.value)?,
+                "data-quota" => self.set_data_quota(msg.value)?,
                 "gas-price" => self.set_gas_price(msg.value)?,
                 "min-hops" => self.set_min_hops(msg.value)?,
+                "spending-budget" => self.set_spending_budget(msg.value)?,
                 "start-block" => self.set_start_block(msg.value)?,
                 _ => {
                     return Err((
@@ -752,6 +1413,42 @@ impl Configurator {
         Ok(UiSetConfigurationResponse {}.tmb(context_id))
     }
 
+    fn set_bandwidth_limits(&mut self, string_limits: String) -> Result<(), (u64, String)> {
+        let limits = match BandwidthLimits::try_from(string_limits.as_str()) {
+            Ok(limits) => limits,
+            Err(e) => return Err((NON_PARSABLE_VALUE, format!("bandwidth limits: {}", e))),
+        };
+        match self.persistent_config.set_bandwidth_limits(string_limits) {
+            Ok(_) => {
+                self.bandwidth_shaper.update_limits(limits);
+                Ok(())
+            }
+            Err(e) => Err((
+                CONFIGURATOR_WRITE_ERROR,
+                format!("bandwidth limits: {:?}", e),
+            )),
+        }
+    }
+
+    fn set_data_quota(&mut self, string_quota: String) -> Result<(), (u64, String)> {
+        let quota = match DataQuota::try_from(string_quota.as_str()) {
+            Ok(quota) => quota,
+            Err(e) => return Err((NON_PARSABLE_VALUE, format!("data quota: {}", e))),
+        };
+        match self.persistent_config.set_data_quota(string_quota) {
+            Ok(_) => {
+                self.bandwidth_shaper.update_data_quota(quota);
+                Ok(())
+            }
+            Err(e) => Err((CONFIGURATOR_WRITE_ERROR, format!("data quota: {:?}", e))),
+        }
+    }
+
+    fn set_spending_budget(&mut self, string_budget: String) -> Result<(), (u64, String)> {
+        let live_change = Self::parse_live_change("spending-budget", &string_budget)?;
+        self.apply_live_change(live_change)
+    }
+
     fn set_gas_price(&mut self, string_price: String) -> Result<(), (u64, String)> {
         let price_number = match string_price.parse::<u64>() {
             Ok(num) => num,
@@ -876,15 +1573,17 @@ impl Configurator {
 mod tests {
     use actix::System;
     use masq_lib::messages::{
-        ToMessageBody, UiCheckPasswordRequest, UiCheckPasswordResponse, UiGenerateSeedSpec,
-        UiGenerateWalletsResponse, UiPaymentThresholds, UiRatePack, UiRecoverSeedSpec,
-        UiScanIntervals, UiStartOrder, UiWalletAddressesRequest, UiWalletAddressesResponse,
+        FromMessageBody, ToMessageBody, UiCheckPasswordRequest, UiCheckPasswordResponse,
+        UiExportWalletRequest, UiExportWalletResponse, UiGenerateSeedSpec,
+        UiGenerateWalletsResponse, UiModuleLogLevel, UiPaymentThresholds, UiRatePack,
+        UiRecoverSeedSpec, UiScanIntervals, UiStartOrder, UiWalletAddressesRequest,
+        UiWalletAddressesResponse,
     };
     use masq_lib::ui_gateway::{MessagePath, MessageTarget};
     use std::path::Path;
     use std::str::FromStr;
     use std::sync::{Arc, Mutex};
-    use std::time::Duration;
+    use std::time::{Duration, SystemTime};
 
     use crate::db_config::persistent_configuration::{
         PersistentConfigError, PersistentConfigurationReal,
@@ -896,14 +1595,23 @@ mod tests {
     use super::*;
     use crate::blockchain::bip32::Bip32EncryptionKeyProvider;
     use crate::blockchain::bip39::Bip39;
+    use crate::blockchain::keystore::Keystore;
     use crate::blockchain::test_utils::make_meaningless_phrase_words;
+    use crate::database::db_backup::DatabaseBackupError;
     use crate::database::db_initializer::{DbInitializer, DbInitializerReal};
+    use crate::database::test_utils::DatabaseBackupMock;
+    use crate::match_every_type_id;
+    use crate::node_configurator::config_reload::test_utils::{make_snapshot, ConfigReaderMock};
+    use crate::server_initializer::test_utils::LogLevelSetterMock;
     use crate::sub_lib::accountant::{PaymentThresholds, ScanIntervals};
+    use crate::sub_lib::bandwidth_shaper::DataUsage;
     use crate::sub_lib::cryptde::PublicKey as PK;
     use crate::sub_lib::cryptde::{CryptDE, PlainData};
     use crate::sub_lib::neighborhood::{ConfigChange, NodeDescriptor, RatePack};
     use crate::sub_lib::node_addr::NodeAddr;
     use crate::sub_lib::wallet::Wallet;
+    use crate::test_utils::recorder_stop_conditions::{StopCondition, StopConditions};
+    use crate::test_utils::unshared_test_utils::notify_handlers::NotifyLaterHandleMock;
     use crate::test_utils::unshared_test_utils::{
         assert_on_initialization_with_panic_on_migration, configure_default_persistent_config,
         prove_that_crash_request_handler_is_hooked_up, ZERO,
@@ -912,9 +1620,11 @@ mod tests {
     use bip39::{Language, Mnemonic};
     use masq_lib::blockchains::chains::Chain;
     use masq_lib::constants::MISSING_DATA;
+    use masq_lib::shared_schema::ConfiguratorError;
     use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
     use masq_lib::utils::{derivation_path, AutomapProtocol, NeighborhoodModeLight};
     use rustc_hex::FromHex;
+    use std::any::TypeId;
     use tiny_hderive::bip32::ExtendedPrivKey;
 
     #[test]
@@ -932,7 +1642,7 @@ mod tests {
                 .unwrap(),
         )));
         let peer_actors = peer_actors_builder().build();
-        let mut subject = Configurator::new(data_dir, false);
+        let mut subject = Configurator::new(data_dir, BandwidthShaper::default(), false);
         subject.config_change_subs_opt = Some(peer_actors.config_change_subs());
         subject.node_to_ui_sub_opt = Some(peer_actors.ui_gateway.node_to_ui_message_sub);
 
@@ -959,24 +1669,139 @@ mod tests {
         );
 
         let act = |data_dir: &Path| {
-            Configurator::new(data_dir.to_path_buf(), false);
+            Configurator::new(data_dir.to_path_buf(), BandwidthShaper::default(), false);
         };
 
         assert_on_initialization_with_panic_on_migration(&data_dir, &act);
     }
 
     #[test]
-    fn ignores_unexpected_message() {
-        let system = System::new("test");
-        let subject = make_subject(None);
+    fn bind_message_schedules_the_first_data_quota_check() {
+        let notify_later_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut subject = make_subject(None);
+        subject.notify_later_check_data_quota = Box::new(
+            NotifyLaterHandleMock::default().notify_later_params(&notify_later_params_arc),
+        );
+        subject.check_data_quota_interval = Duration::from_millis(12345);
+        let system = System::new("bind_message_schedules_the_first_data_quota_check");
         let subject_addr = subject.start();
-        let (ui_gateway, _, ui_gateway_recording) = make_recorder();
-        let peer_actors = peer_actors_builder().ui_gateway(ui_gateway).build();
-        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
 
         subject_addr
-            .try_send(NodeFromUiMessage {
-                client_id: 1234,
+            .try_send(BindMessage {
+                peer_actors: peer_actors_builder().build(),
+            })
+            .unwrap();
+
+        System::current().stop();
+        system.run();
+        assert_eq!(
+            *notify_later_params_arc.lock().unwrap(),
+            vec![(
+                CheckDataQuotaMessage::default(),
+                Duration::from_millis(12345)
+            )]
+        );
+    }
+
+    #[test]
+    fn check_data_quota_message_checks_saves_and_reschedules() {
+        let set_data_usage_params_arc = Arc::new(Mutex::new(vec![]));
+        let notify_later_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut subject = make_subject(Some(
+            PersistentConfigurationMock::new()
+                .set_data_usage_params(&set_data_usage_params_arc)
+                .set_data_usage_result(Ok(())),
+        ));
+        let yesterday = SystemTime::now() - Duration::from_secs(86_400);
+        subject.bandwidth_shaper = BandwidthShaper::new(
+            BandwidthLimits::default(),
+            DataQuota {
+                daily_mb: 1,
+                monthly_mb: 0,
+            },
+        );
+        subject.bandwidth_shaper.restore_data_usage(DataUsage {
+            as_of: SystemTime::now(),
+            daily_bytes: 1_000_000,
+            monthly_bytes: 1_000_000,
+        });
+        let mut usage = subject.bandwidth_shaper.data_usage();
+        usage.as_of = yesterday;
+        subject.bandwidth_shaper.restore_data_usage(usage);
+        subject.notify_later_check_data_quota = Box::new(
+            NotifyLaterHandleMock::default().notify_later_params(&notify_later_params_arc),
+        );
+        subject.check_data_quota_interval = Duration::from_millis(12345);
+        let system = System::new("check_data_quota_message_checks_saves_and_reschedules");
+        let subject_addr = subject.start();
+
+        subject_addr
+            .try_send(CheckDataQuotaMessage::default())
+            .unwrap();
+
+        System::current().stop();
+        system.run();
+        let set_data_usage_params = set_data_usage_params_arc.lock().unwrap();
+        assert_eq!(set_data_usage_params.len(), 1);
+        assert_eq!(set_data_usage_params[0].daily_bytes, 0);
+        assert!(set_data_usage_params[0].as_of > yesterday);
+        assert_eq!(
+            *notify_later_params_arc.lock().unwrap(),
+            vec![(
+                CheckDataQuotaMessage::default(),
+                Duration::from_millis(12345)
+            )]
+        );
+    }
+
+    #[test]
+    fn restore_data_usage_hands_the_saved_usage_to_the_bandwidth_shaper() {
+        let now = SystemTime::now();
+        let usage = DataUsage {
+            as_of: now,
+            daily_bytes: 1_234,
+            monthly_bytes: 56_789,
+        };
+        let subject = make_subject(Some(
+            PersistentConfigurationMock::new().data_usage_result(Ok(Some(usage))),
+        ));
+
+        subject.restore_data_usage();
+
+        let restored = subject.bandwidth_shaper.data_usage();
+        assert_eq!(restored.daily_bytes, 1_234);
+        assert_eq!(restored.monthly_bytes, 56_789);
+    }
+
+    #[test]
+    fn restore_data_usage_counts_from_zero_if_the_saved_usage_cannot_be_read() {
+        init_test_logging();
+        let mut subject = make_subject(Some(PersistentConfigurationMock::new().data_usage_result(
+            Err(PersistentConfigError::DatabaseError("booga".to_string())),
+        )));
+        subject.logger = Logger::new("restore_data_usage_counts_from_zero");
+
+        subject.restore_data_usage();
+
+        assert_eq!(subject.bandwidth_shaper.data_usage().daily_bytes, 0);
+        TestLogHandler::new().exists_log_containing(
+            "WARN: restore_data_usage_counts_from_zero: Could not restore data usage; counting from \
+             zero: DatabaseError(\"booga\")",
+        );
+    }
+
+    #[test]
+    fn ignores_unexpected_message() {
+        let system = System::new("test");
+        let subject = make_subject(None);
+        let subject_addr = subject.start();
+        let (ui_gateway, _, ui_gateway_recording) = make_recorder();
+        let peer_actors = peer_actors_builder().ui_gateway(ui_gateway).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+        subject_addr
+            .try_send(NodeFromUiMessage {
+                client_id: 1234,
                 body: UiStartOrder {}.tmb(4321),
             })
             .unwrap();
@@ -1955,24 +2780,36 @@ mod tests {
         )
     }
 
-    #[test]
-    fn unfriendly_handle_recover_wallets_handles_useless_seed_spec_with_key_and_address() {
-        let db_password = "password".to_string();
-        let consuming_private_key =
-            "0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF".to_string();
-        let earning_address = "0x0123456789012345678901234567890123456789".to_string();
-        let msg = UiRecoverWalletsRequest {
-            db_password: db_password.clone(),
-            seed_spec_opt: Some(UiRecoverSeedSpec {
-                mnemonic_phrase: make_meaningless_phrase_words(),
-                mnemonic_phrase_language_opt: Some("English".to_string()),
-                mnemonic_passphrase_opt: None,
-            }),
+    const KEYSTORE_TEST_PRIVATE_KEY: &str =
+        "CC46BEFE8D169B89DB447BD725FC2368B12542113555302598430CB5D5C74EA9";
+
+    fn make_keystore_spec(passphrase: &str) -> UiKeystoreSpec {
+        let key_bytes: Vec<u8> = KEYSTORE_TEST_PRIVATE_KEY.from_hex().unwrap();
+        let key_pair = Bip32EncryptionKeyProvider::from_raw_secret(&key_bytes).unwrap();
+        UiKeystoreSpec {
+            keystore_json: Keystore::encrypt_with_iterations(&key_pair, "Open Sesame", 1024)
+                .unwrap(),
+            passphrase: passphrase.to_string(),
+        }
+    }
+
+    fn make_keystore_recover_wallets_request(
+        consuming_private_key_opt: Option<String>,
+        keystore_passphrase: &str,
+    ) -> UiRecoverWalletsRequest {
+        UiRecoverWalletsRequest {
+            db_password: "password".to_string(),
+            seed_spec_opt: None,
             consuming_derivation_path_opt: None,
-            consuming_private_key_opt: Some(consuming_private_key.clone()),
+            consuming_private_key_opt,
+            consuming_keystore_opt: Some(make_keystore_spec(keystore_passphrase)),
             earning_derivation_path_opt: None,
-            earning_address_opt: Some(earning_address.clone()),
-        };
+            earning_address_opt: Some("0x0123456789012345678901234567890123456789".to_string()),
+        }
+    }
+
+    #[test]
+    fn unfriendly_handle_recover_wallets_takes_consuming_key_from_keystore() {
         let set_wallet_info_params_arc = Arc::new(Mutex::new(vec![]));
         let mut persistent_config: Box<dyn PersistentConfiguration> = Box::new(
             configure_default_persistent_config(ZERO)
@@ -1980,192 +2817,1220 @@ mod tests {
                 .set_wallet_info_params(&set_wallet_info_params_arc)
                 .set_wallet_info_result(Ok(())),
         );
+        let msg = make_keystore_recover_wallets_request(None, "Open Sesame");
 
-        let _ = Configurator::unfriendly_handle_recover_wallets(msg, 1234, &mut persistent_config)
-            .unwrap();
+        let result =
+            Configurator::unfriendly_handle_recover_wallets(msg, 1234, &mut persistent_config);
 
+        assert_eq!(result, Ok(UiRecoverWalletsResponse {}.tmb(1234)));
         let set_wallet_info_params = set_wallet_info_params_arc.lock().unwrap();
         assert_eq!(
             *set_wallet_info_params,
-            vec![(consuming_private_key, earning_address, db_password)]
+            vec![(
+                KEYSTORE_TEST_PRIVATE_KEY.to_string(),
+                "0x0123456789012345678901234567890123456789".to_string(),
+                "password".to_string()
+            )]
         )
     }
 
     #[test]
-    fn unfriendly_handle_recover_wallets_handles_no_seed_spec_and_only_earning_wallet_address() {
-        let msg = UiRecoverWalletsRequest {
-            db_password: "password".to_string(),
-            seed_spec_opt: None,
-            consuming_derivation_path_opt: None,
-            consuming_private_key_opt: None,
-            earning_derivation_path_opt: None,
-            earning_address_opt: Some("0x0123456789012345678901234567890123456789".to_string()),
-        };
-        let mut persistent_config: Box<dyn PersistentConfiguration> =
-            Box::new(configure_default_persistent_config(ZERO).check_password_result(Ok(true)));
-
-        let result =
-            Configurator::unfriendly_handle_recover_wallets(msg, 1234, &mut persistent_config);
-
-        assert_eq! (result, Err((MISSING_DATA, "If you supply no seed information, you must supply both consuming wallet private key and earning wallet address".to_string())));
-    }
-
-    #[test]
-    fn unfriendly_handle_recover_wallets_handles_seed_but_nothing_about_consuming_wallet() {
-        let db_password = "password".to_string();
-        let earning_address = "0x0123456789012345678901234567890123456789".to_string();
-        let msg = UiRecoverWalletsRequest {
-            db_password,
-            seed_spec_opt: Some(UiRecoverSeedSpec {
-                mnemonic_phrase: make_meaningless_phrase_words(),
-                mnemonic_phrase_language_opt: Some("English".to_string()),
-                mnemonic_passphrase_opt: None,
-            }),
-            consuming_derivation_path_opt: None,
-            consuming_private_key_opt: None,
-            earning_derivation_path_opt: None,
-            earning_address_opt: Some(earning_address),
-        };
+    fn unfriendly_handle_recover_wallets_complains_about_wrong_keystore_passphrase() {
         let mut persistent_config: Box<dyn PersistentConfiguration> =
             Box::new(configure_default_persistent_config(ZERO).check_password_result(Ok(true)));
+        let msg = make_keystore_recover_wallets_request(None, "Close Sesame");
 
         let result =
             Configurator::unfriendly_handle_recover_wallets(msg, 1234, &mut persistent_config);
 
-        assert_eq! (result, Err((MISSING_DATA, "If you supply seed information, you must supply either the consuming wallet derivation path or the consuming wallet private key".to_string())));
+        assert_eq!(
+            result,
+            Err((
+                KEYSTORE_ERROR,
+                "Keystore passphrase is incorrect".to_string()
+            ))
+        );
     }
 
     #[test]
-    fn unfriendly_handle_recover_wallets_handles_seed_but_nothing_about_earning_wallet() {
-        let db_password = "password".to_string();
-        let consuming_private_key =
-            "0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF".to_string();
-        let msg = UiRecoverWalletsRequest {
-            db_password,
-            seed_spec_opt: Some(UiRecoverSeedSpec {
-                mnemonic_phrase: make_meaningless_phrase_words(),
-                mnemonic_phrase_language_opt: Some("English".to_string()),
-                mnemonic_passphrase_opt: None,
-            }),
-            consuming_derivation_path_opt: None,
-            consuming_private_key_opt: Some(consuming_private_key),
-            earning_derivation_path_opt: None,
-            earning_address_opt: None,
-        };
+    fn unfriendly_handle_recover_wallets_rejects_both_private_key_and_keystore() {
         let mut persistent_config: Box<dyn PersistentConfiguration> =
             Box::new(configure_default_persistent_config(ZERO).check_password_result(Ok(true)));
-
-        let result =
-            Configurator::unfriendly_handle_recover_wallets(msg, 1234, &mut persistent_config);
-
-        assert_eq! (result, Err((MISSING_DATA, "If you supply seed information, you must supply either the earning wallet derivation path or the earning wallet address".to_string())));
-    }
-
-    #[test]
-    fn unfriendly_handle_recover_wallets_defaults_language_to_english() {
-        let db_password = "password".to_string();
-        let msg = UiRecoverWalletsRequest {
-            db_password,
-            seed_spec_opt: Some(UiRecoverSeedSpec {
-                mnemonic_phrase: make_meaningless_phrase_words(),
-                mnemonic_phrase_language_opt: None,
-                mnemonic_passphrase_opt: None,
-            }),
-            consuming_derivation_path_opt: Some(derivation_path(10, 20)),
-            consuming_private_key_opt: None,
-            earning_derivation_path_opt: None,
-            earning_address_opt: Some("0x0123456789012345678901234567890123456789".to_string()),
-        };
-        let mut persistent_config: Box<dyn PersistentConfiguration> = Box::new(
-            configure_default_persistent_config(ZERO)
-                .check_password_result(Ok(true))
-                .set_wallet_info_result(Ok(())),
+        let msg = make_keystore_recover_wallets_request(
+            Some(KEYSTORE_TEST_PRIVATE_KEY.to_string()),
+            "Open Sesame",
         );
 
         let result =
             Configurator::unfriendly_handle_recover_wallets(msg, 1234, &mut persistent_config);
 
-        assert_eq!(result.is_ok(), true); // phrase is in English; if language didn't default there, test would blow up
+        assert_eq!(
+            result,
+            Err((
+                KEYSTORE_ERROR,
+                "Supply either a consuming wallet private key or a keystore, not both".to_string()
+            ))
+        );
     }
 
     #[test]
-    fn handle_set_configuration_works() {
-        init_test_logging();
-        let test_name = "handle_set_configuration_works";
-        let set_start_block_params_arc = Arc::new(Mutex::new(vec![]));
-        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
-        let persistent_config = PersistentConfigurationMock::new()
-            .set_start_block_params(&set_start_block_params_arc)
-            .set_start_block_result(Ok(()));
-        let mut subject = make_subject(Some(persistent_config));
-        subject.logger = Logger::new(test_name);
+    fn export_wallet_request_is_routed_to_its_handler() {
+        let system = System::new("test");
+        let persistent_config = PersistentConfigurationMock::new().check_password_result(Ok(false));
+        let subject = make_subject(Some(persistent_config));
         let subject_addr = subject.start();
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
         let peer_actors = peer_actors_builder().ui_gateway(ui_gateway).build();
         subject_addr.try_send(BindMessage { peer_actors }).unwrap();
-        let msg = UiSetConfigurationRequest {
-            name: "start-block".to_string(),
-            value: "166666".to_string(),
-        };
-        let context_id = 4444;
 
         subject_addr
             .try_send(NodeFromUiMessage {
                 client_id: 1234,
-                body: msg.clone().tmb(context_id),
+                body: UiExportWalletRequest {
+                    db_password: "wrong password".to_string(),
+                    format: WalletExportFormat::Keystore,
+                    passphrase: "Open Sesame".to_string(),
+                }
+                .tmb(4321),
             })
             .unwrap();
 
-        let system = System::new("test");
         System::current().stop();
         system.run();
         let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
-        let response = ui_gateway_recording.get_record::<NodeToUiMessage>(0);
-        let (_, context_id) = UiSetConfigurationResponse::fmb(response.body.clone()).unwrap();
-        assert_eq!(context_id, 4444);
-        let check_start_block_params = set_start_block_params_arc.lock().unwrap();
-        assert_eq!(*check_start_block_params, vec![Some(166666)]);
+        assert_eq!(
+            ui_gateway_recording.get_record::<NodeToUiMessage>(0),
+            &NodeToUiMessage {
+                target: MessageTarget::ClientId(1234),
+                body: MessageBody {
+                    opcode: "exportWallet".to_string(),
+                    path: MessagePath::Conversation(4321),
+                    payload: Err((
+                        BAD_PASSWORD_ERROR,
+                        "Bad password; can't export wallets".to_string()
+                    )),
+                }
+            }
+        );
     }
 
     #[test]
-    fn handle_none_cases() {
-        vec!["none", "None", "nOnE", "NoNe", "NONE"]
-            .iter()
-            .for_each(|value| handle_set_configuration_accepts_none_to_unset_start_block(value));
-    }
-
-    fn handle_set_configuration_accepts_none_to_unset_start_block(cfg_value: &str) {
+    fn handle_export_wallet_reencrypts_consuming_key_with_passphrase() {
         init_test_logging();
-        let test_name = format!(
-            "handle_set_configuration_accepts_{}_to_unset_start_block",
-            &cfg_value
-        );
-        let set_start_block_params_arc = Arc::new(Mutex::new(vec![]));
-        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let check_password_params_arc = Arc::new(Mutex::new(vec![]));
+        let consuming_wallet_private_key_params_arc = Arc::new(Mutex::new(vec![]));
         let persistent_config = PersistentConfigurationMock::new()
-            .set_start_block_params(&set_start_block_params_arc)
-            .set_start_block_result(Ok(()));
+            .check_password_params(&check_password_params_arc)
+            .check_password_result(Ok(true))
+            .consuming_wallet_private_key_params(&consuming_wallet_private_key_params_arc)
+            .consuming_wallet_private_key_result(Ok(Some(KEYSTORE_TEST_PRIVATE_KEY.to_string())));
         let mut subject = make_subject(Some(persistent_config));
-        subject.logger = Logger::new(test_name.as_str());
-        let subject_addr = subject.start();
-        let peer_actors = peer_actors_builder().ui_gateway(ui_gateway).build();
-        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
-        let msg = UiSetConfigurationRequest {
-            name: "start-block".to_string(),
-            value: cfg_value.to_string(),
+        let msg = UiExportWalletRequest {
+            db_password: "password".to_string(),
+            format: WalletExportFormat::Keystore,
+            passphrase: "Open Sesame".to_string(),
         };
-        let context_id = 4444;
 
-        subject_addr
-            .try_send(NodeFromUiMessage {
-                client_id: 1234,
-                body: msg.clone().tmb(context_id),
-            })
-            .unwrap();
+        let result = subject.handle_export_wallet(msg, 4321);
 
-        let system = System::new("test");
-        System::current().stop();
-        system.run();
-        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        let (response, context_id) = UiExportWalletResponse::fmb(result).unwrap();
+        assert_eq!(context_id, 4321);
+        assert_eq!(
+            response.wallet_address,
+            "0x8e4d2317e56c8fd1fc9f13ba2aa62df1c5a542a7"
+        );
+        assert_eq!(response.format, WalletExportFormat::Keystore);
+        let key_pair = Keystore::decrypt(&response.exported, "Open Sesame").unwrap();
+        assert_eq!(
+            key_pair.clone_secret().to_hex::<String>().to_uppercase(),
+            KEYSTORE_TEST_PRIVATE_KEY
+        );
+        let check_password_params = check_password_params_arc.lock().unwrap();
+        assert_eq!(*check_password_params, vec![Some("password".to_string())]);
+        let consuming_wallet_private_key_params =
+            consuming_wallet_private_key_params_arc.lock().unwrap();
+        assert_eq!(
+            *consuming_wallet_private_key_params,
+            vec!["password".to_string()]
+        );
+        TestLogHandler::new().exists_log_containing(
+            "INFO: Configurator: Consuming wallet 0x8e4d2317e56c8fd1fc9f13ba2aa62df1c5a542a7 exported",
+        );
+    }
+
+    #[test]
+    fn handle_export_wallet_complains_about_missing_consuming_wallet() {
+        init_test_logging();
+        let persistent_config = PersistentConfigurationMock::new()
+            .check_password_result(Ok(true))
+            .consuming_wallet_private_key_result(Ok(None));
+        let mut subject = make_subject(Some(persistent_config));
+        let msg = UiExportWalletRequest {
+            db_password: "password".to_string(),
+            format: WalletExportFormat::Keystore,
+            passphrase: "Open Sesame".to_string(),
+        };
+
+        let result = subject.handle_export_wallet(msg, 4321);
+
+        assert_eq!(
+            result,
+            MessageBody {
+                opcode: "exportWallet".to_string(),
+                path: MessagePath::Conversation(4321),
+                payload: Err((
+                    MISSING_DATA,
+                    "Consuming wallet not yet configured".to_string()
+                )),
+            }
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: Configurator: Failed to export consuming wallet: {}, Consuming wallet not yet configured",
+            MISSING_DATA
+        ));
+    }
+
+    #[test]
+    fn handle_export_wallet_complains_about_database_error() {
+        let persistent_config = PersistentConfigurationMock::new()
+            .check_password_result(Ok(true))
+            .consuming_wallet_private_key_result(Err(PersistentConfigError::NotPresent));
+        let mut subject = make_subject(Some(persistent_config));
+        let msg = UiExportWalletRequest {
+            db_password: "password".to_string(),
+            format: WalletExportFormat::Keystore,
+            passphrase: "Open Sesame".to_string(),
+        };
+
+        let result = subject.handle_export_wallet(msg, 4321);
+
+        assert_eq!(
+            result.payload,
+            Err((
+                CONFIGURATOR_READ_ERROR,
+                "Consuming wallet error: NotPresent".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn handle_export_wallet_refuses_empty_passphrase() {
+        let persistent_config = PersistentConfigurationMock::new().check_password_result(Ok(true));
+        let mut subject = make_subject(Some(persistent_config));
+        let msg = UiExportWalletRequest {
+            db_password: "password".to_string(),
+            format: WalletExportFormat::Keystore,
+            passphrase: "".to_string(),
+        };
+
+        let result = subject.handle_export_wallet(msg, 4321);
+
+        assert_eq!(
+            result.payload,
+            Err((
+                KEYSTORE_ERROR,
+                "The export passphrase must not be empty".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn database_backup_request_is_answered_with_the_size_of_the_encrypted_backup() {
+        init_test_logging();
+        let system = System::new("test");
+        let check_password_params_arc = Arc::new(Mutex::new(vec![]));
+        let back_up_params_arc = Arc::new(Mutex::new(vec![]));
+        let persistent_config = PersistentConfigurationMock::new()
+            .check_password_params(&check_password_params_arc)
+            .check_password_result(Ok(true));
+        let mut subject = make_subject(Some(persistent_config));
+        subject.database_backup = Box::new(
+            DatabaseBackupMock::new()
+                .back_up_params(&back_up_params_arc)
+                .back_up_result(Ok(4096)),
+        );
+        let subject_addr = subject.start();
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let peer_actors = peer_actors_builder().ui_gateway(ui_gateway).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+        subject_addr
+            .try_send(NodeFromUiMessage {
+                client_id: 1234,
+                body: UiDatabaseBackupRequest {
+                    path: "/backups/node.db".to_string(),
+                    db_password_opt: Some("password".to_string()),
+                }
+                .tmb(4321),
+            })
+            .unwrap();
+
+        System::current().stop();
+        system.run();
+        let check_password_params = check_password_params_arc.lock().unwrap();
+        assert_eq!(*check_password_params, vec![Some("password".to_string())]);
+        let back_up_params = back_up_params_arc.lock().unwrap();
+        assert_eq!(
+            *back_up_params,
+            vec![(
+                PathBuf::from("/backups/node.db"),
+                Some("password".to_string())
+            )]
+        );
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        assert_eq!(
+            ui_gateway_recording.get_record::<NodeToUiMessage>(0),
+            &NodeToUiMessage {
+                target: MessageTarget::ClientId(1234),
+                body: UiDatabaseBackupResponse {
+                    path: "/backups/node.db".to_string(),
+                    encrypted: true,
+                    size_bytes: 4096,
+                }
+                .tmb(4321)
+            }
+        );
+        TestLogHandler::new()
+            .exists_log_containing("INFO: Configurator: Database backed up to /backups/node.db");
+    }
+
+    #[test]
+    fn handle_database_backup_without_password_makes_a_plain_backup() {
+        let back_up_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut subject = make_subject(None);
+        subject.database_backup = Box::new(
+            DatabaseBackupMock::new()
+                .back_up_params(&back_up_params_arc)
+                .back_up_result(Ok(2048)),
+        );
+        let msg = UiDatabaseBackupRequest {
+            path: "/backups/node.db".to_string(),
+            db_password_opt: None,
+        };
+
+        let result = subject.handle_database_backup(msg, 4321);
+
+        let (response, _) = UiDatabaseBackupResponse::fmb(result).unwrap();
+        assert_eq!(response.encrypted, false);
+        assert_eq!(response.size_bytes, 2048);
+        let back_up_params = back_up_params_arc.lock().unwrap();
+        assert_eq!(
+            *back_up_params,
+            vec![(PathBuf::from("/backups/node.db"), None)]
+        );
+    }
+
+    #[test]
+    fn handle_database_backup_refuses_bad_password() {
+        let back_up_params_arc = Arc::new(Mutex::new(vec![]));
+        let persistent_config = PersistentConfigurationMock::new().check_password_result(Ok(false));
+        let mut subject = make_subject(Some(persistent_config));
+        subject.database_backup =
+            Box::new(DatabaseBackupMock::new().back_up_params(&back_up_params_arc));
+        let msg = UiDatabaseBackupRequest {
+            path: "/backups/node.db".to_string(),
+            db_password_opt: Some("booga".to_string()),
+        };
+
+        let result = subject.handle_database_backup(msg, 4321);
+
+        assert_eq!(
+            result.payload,
+            Err((
+                BAD_PASSWORD_ERROR,
+                "Bad password; can't encrypt backup".to_string()
+            ))
+        );
+        assert!(back_up_params_arc.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn handle_database_backup_refuses_relative_path() {
+        let mut subject = make_subject(None);
+        let msg = UiDatabaseBackupRequest {
+            path: "backups/node.db".to_string(),
+            db_password_opt: None,
+        };
+
+        let result = subject.handle_database_backup(msg, 4321);
+
+        assert_eq!(
+            result.payload,
+            Err((
+                DATABASE_BACKUP_ERROR,
+                "Backup path must be absolute, not 'backups/node.db'".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn handle_database_backup_reports_failure_of_the_backup() {
+        init_test_logging();
+        let mut subject = make_subject(None);
+        subject.database_backup = Box::new(DatabaseBackupMock::new().back_up_result(Err(
+            DatabaseBackupError::DestinationExists(PathBuf::from("/backups/node.db")),
+        )));
+        let msg = UiDatabaseBackupRequest {
+            path: "/backups/node.db".to_string(),
+            db_password_opt: None,
+        };
+
+        let result = subject.handle_database_backup(msg, 4321);
+
+        assert_eq!(
+            result,
+            MessageBody {
+                opcode: "databaseBackup".to_string(),
+                path: MessagePath::Conversation(4321),
+                payload: Err((
+                    DATABASE_BACKUP_ERROR,
+                    "/backups/node.db already exists; choose a new file for the backup".to_string()
+                )),
+            }
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: Configurator: Failed to back up database: {}, /backups/node.db already exists",
+            DATABASE_BACKUP_ERROR
+        ));
+    }
+
+    #[test]
+    fn handle_set_log_level_passes_parsed_levels_along() {
+        init_test_logging();
+        let set_log_levels_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut subject = make_subject(None);
+        subject.logger = Logger::new("handle_set_log_level_passes_parsed_levels_along");
+        subject.log_level_setter = Box::new(
+            LogLevelSetterMock::new()
+                .set_log_levels_params(&set_log_levels_params_arc)
+                .set_log_levels_result(Ok("warn, neighborhood=trace".to_string())),
+        );
+        let msg = UiSetLogLevelRequest {
+            level_opt: Some("WARN".to_string()),
+            module_levels: vec![UiModuleLogLevel {
+                module: "node::neighborhood".to_string(),
+                level: "trace".to_string(),
+            }],
+        };
+
+        let result = subject.handle_set_log_level(msg, 4321);
+
+        assert_eq!(
+            result,
+            UiSetLogLevelResponse {
+                log_spec: "warn, neighborhood=trace".to_string()
+            }
+            .tmb(4321)
+        );
+        let set_log_levels_params = set_log_levels_params_arc.lock().unwrap();
+        assert_eq!(
+            *set_log_levels_params,
+            vec![(
+                Some(LevelFilter::Warn),
+                vec![("node::neighborhood".to_string(), LevelFilter::Trace)]
+            )]
+        );
+        TestLogHandler::new().exists_log_containing(
+            "INFO: handle_set_log_level_passes_parsed_levels_along: Log levels changed to warn, neighborhood=trace",
+        );
+    }
+
+    #[test]
+    fn handle_set_log_level_rejects_unrecognized_level() {
+        let set_log_levels_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut subject = make_subject(None);
+        subject.log_level_setter =
+            Box::new(LogLevelSetterMock::new().set_log_levels_params(&set_log_levels_params_arc));
+        let msg = UiSetLogLevelRequest {
+            level_opt: None,
+            module_levels: vec![UiModuleLogLevel {
+                module: "hopper".to_string(),
+                level: "verbose".to_string(),
+            }],
+        };
+
+        let result = subject.handle_set_log_level(msg, 4321);
+
+        assert_eq!(
+            result,
+            MessageBody {
+                opcode: "setLogLevel".to_string(),
+                path: MessagePath::Conversation(4321),
+                payload: Err((
+                    LOG_LEVEL_ERROR,
+                    "Unrecognized log level 'verbose'; use off, error, warn, info, debug or trace"
+                        .to_string()
+                )),
+            }
+        );
+        assert!(set_log_levels_params_arc.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn handle_set_log_level_requires_some_level() {
+        let mut subject = make_subject(None);
+        let msg = UiSetLogLevelRequest {
+            level_opt: None,
+            module_levels: vec![],
+        };
+
+        let result = subject.handle_set_log_level(msg, 4321);
+
+        assert_eq!(
+            result.payload,
+            Err((
+                LOG_LEVEL_ERROR,
+                "Specify a global level, module levels, or both".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn handle_set_log_level_reports_failure_to_apply_levels() {
+        let mut subject = make_subject(None);
+        subject.log_level_setter = Box::new(
+            LogLevelSetterMock::new().set_log_levels_result(Err("Not started".to_string())),
+        );
+        let msg = UiSetLogLevelRequest {
+            level_opt: Some("debug".to_string()),
+            module_levels: vec![],
+        };
+
+        let result = subject.handle_set_log_level(msg, 4321);
+
+        assert_eq!(
+            result.payload,
+            Err((LOG_LEVEL_ERROR, "Not started".to_string()))
+        );
+    }
+
+    #[test]
+    fn handle_log_stream_subscribes_and_unsubscribes_the_client() {
+        let client_id = 8_675_309;
+        let mut subject = make_subject(None);
+
+        let follow_result = subject.handle_log_stream(
+            UiLogStreamRequest {
+                follow: true,
+                level: "debug".to_string(),
+                module_opt: Some("hopper".to_string()),
+            },
+            client_id,
+            4321,
+        );
+        let stop_result = subject.handle_log_stream(
+            UiLogStreamRequest {
+                follow: false,
+                level: "debug".to_string(),
+                module_opt: None,
+            },
+            client_id,
+            4322,
+        );
+
+        assert_eq!(follow_result, UiLogStreamResponse {}.tmb(4321));
+        assert_eq!(stop_result, UiLogStreamResponse {}.tmb(4322));
+        assert_eq!(unsubscribe_from_log_stream(client_id), false);
+    }
+
+    #[test]
+    fn handle_log_stream_rejects_levels_that_are_already_broadcast() {
+        let client_id = 8_675_310;
+        let mut subject = make_subject(None);
+
+        let result = subject.handle_log_stream(
+            UiLogStreamRequest {
+                follow: true,
+                level: "info".to_string(),
+                module_opt: None,
+            },
+            client_id,
+            4321,
+        );
+
+        assert_eq!(
+            result.payload,
+            Err((
+                LOG_LEVEL_ERROR,
+                "Log stream level must be 'debug' or 'trace', not 'info'".to_string()
+            ))
+        );
+        assert_eq!(unsubscribe_from_log_stream(client_id), false);
+    }
+
+    #[test]
+    fn set_config_reader_reads_the_starting_configuration() {
+        let starting_config = make_snapshot(&[("log-level", "warn")]);
+        let mut subject = make_subject(None);
+
+        subject.set_config_reader(Box::new(
+            ConfigReaderMock::new().read_result(Ok(starting_config.clone())),
+        ));
+
+        assert_eq!(subject.running_config_opt, Some(starting_config));
+        assert_eq!(subject.config_reader_opt.is_some(), true);
+    }
+
+    #[test]
+    fn set_config_reader_warns_if_the_starting_configuration_cannot_be_read() {
+        init_test_logging();
+        let test_name = "set_config_reader_warns_if_the_starting_configuration_cannot_be_read";
+        let mut subject = make_subject(None);
+        subject.logger = Logger::new(test_name);
+
+        subject.set_config_reader(Box::new(ConfigReaderMock::new().read_result(Err(
+            ConfiguratorError::required("config-file", "Not valid TOML"),
+        ))));
+
+        assert_eq!(subject.running_config_opt, None);
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: {}: Could not read the starting configuration: config-file: Not valid TOML",
+            test_name
+        ));
+    }
+
+    #[test]
+    fn reload_config_request_applies_live_changes_and_reports_the_others() {
+        init_test_logging();
+        let test_name = "reload_config_request_applies_live_changes_and_reports_the_others";
+        let system = System::new(test_name);
+        let (neighborhood, _, neighborhood_recording_arc) = make_recorder();
+        let neighborhood = neighborhood.system_stop_conditions(match_every_type_id!(
+            ConfigChangeMsg,
+            ConfigChangeMsg,
+            ConfigChangeMsg
+        ));
+        let peer_actors = peer_actors_builder().neighborhood(neighborhood).build();
+        let set_min_hops_params_arc = Arc::new(Mutex::new(vec![]));
+        let set_rate_pack_params_arc = Arc::new(Mutex::new(vec![]));
+        let set_log_levels_params_arc = Arc::new(Mutex::new(vec![]));
+        let persistent_config = PersistentConfigurationMock::new()
+            .set_min_hops_params(&set_min_hops_params_arc)
+            .set_min_hops_result(Ok(()))
+            .set_rate_pack_params(&set_rate_pack_params_arc)
+            .set_rate_pack_result(Ok(()));
+        let reloaded = make_snapshot(&[
+            ("clandestine-port", "2345"),
+            ("dns-servers", "5.6.7.8,6.7.8.9"),
+            ("log-level", "debug"),
+            ("min-hops", "4"),
+            ("rate-pack", "2|3|4|5"),
+        ]);
+        let mut subject = make_subject(Some(persistent_config));
+        subject.logger = Logger::new(test_name);
+        subject.log_level_setter = Box::new(
+            LogLevelSetterMock::new()
+                .set_log_levels_params(&set_log_levels_params_arc)
+                .set_log_levels_result(Ok("debug".to_string())),
+        );
+        subject.config_reader_opt = Some(Box::new(
+            ConfigReaderMock::new().read_result(Ok(reloaded.clone())),
+        ));
+        subject.running_config_opt = Some(make_snapshot(&[
+            ("clandestine-port", "1234"),
+            ("dns-servers", "1.1.1.1"),
+            ("log-level", "warn"),
+            ("min-hops", "3"),
+            ("rate-pack", "1|1|1|1"),
+        ]));
+        subject.config_change_subs_opt = Some(peer_actors.config_change_subs());
+
+        let result = subject.handle_reload_config(UiReloadConfigRequest {}, 4321);
+
+        system.run();
+        assert_eq!(
+            result,
+            UiReloadConfigResponse {
+                applied: vec![
+                    "dns-servers".to_string(),
+                    "log-level".to_string(),
+                    "min-hops".to_string(),
+                    "rate-pack".to_string(),
+                ],
+                failed: vec![],
+                restart_required: vec!["clandestine-port".to_string()],
+            }
+            .tmb(4321)
+        );
+        let neighborhood_recording = neighborhood_recording_arc.lock().unwrap();
+        assert_eq!(
+            neighborhood_recording.get_record::<ConfigChangeMsg>(0),
+            &ConfigChangeMsg {
+                change: ConfigChange::UpdateDnsServers(vec![
+                    SocketAddr::from_str("5.6.7.8:53").unwrap(),
+                    SocketAddr::from_str("6.7.8.9:53").unwrap(),
+                ])
+            }
+        );
+        assert_eq!(
+            neighborhood_recording.get_record::<ConfigChangeMsg>(1),
+            &ConfigChangeMsg {
+                change: ConfigChange::UpdateMinHops(Hops::FourHops)
+            }
+        );
+        assert_eq!(
+            neighborhood_recording.get_record::<ConfigChangeMsg>(2),
+            &ConfigChangeMsg {
+                change: ConfigChange::UpdateRatePack(RatePack::try_from("2|3|4|5").unwrap())
+            }
+        );
+        assert_eq!(neighborhood_recording.len(), 3);
+        assert_eq!(
+            *set_log_levels_params_arc.lock().unwrap(),
+            vec![(Some(LevelFilter::Debug), vec![])]
+        );
+        assert_eq!(
+            *set_min_hops_params_arc.lock().unwrap(),
+            vec![Hops::FourHops]
+        );
+        assert_eq!(
+            *set_rate_pack_params_arc.lock().unwrap(),
+            vec!["2|3|4|5".to_string()]
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "INFO: {}: Configuration reloaded; applied: [\"dns-servers\", \"log-level\", \
+            \"min-hops\", \"rate-pack\"]; restart required: [\"clandestine-port\"]",
+            test_name
+        ));
+    }
+
+    #[test]
+    fn reload_config_request_is_routed_to_its_handler() {
+        let system = System::new("reload_config_request_is_routed_to_its_handler");
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let ui_gateway = ui_gateway.system_stop_conditions(match_every_type_id!(NodeToUiMessage));
+        let peer_actors = peer_actors_builder().ui_gateway(ui_gateway).build();
+        let subject = make_subject(None);
+        let subject_addr = subject.start();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+        subject_addr
+            .try_send(NodeFromUiMessage {
+                client_id: 1234,
+                body: UiReloadConfigRequest {}.tmb(4321),
+            })
+            .unwrap();
+
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        assert_eq!(
+            ui_gateway_recording.get_record::<NodeToUiMessage>(0),
+            &NodeToUiMessage {
+                target: MessageTarget::ClientId(1234),
+                body: MessageBody {
+                    opcode: "reloadConfig".to_string(),
+                    path: MessagePath::Conversation(4321),
+                    payload: Err((
+                        CONFIG_RELOAD_ERROR,
+                        "This Node cannot reload its configuration".to_string()
+                    ))
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn reload_config_persists_and_broadcasts_payment_thresholds_scan_intervals_and_blockchain_service_url(
+    ) {
+        let system = System::new("reload_config_persists_and_broadcasts");
+        let (accountant, _, accountant_recording_arc) = make_recorder();
+        let peer_actors = peer_actors_builder().accountant(accountant).build();
+        let set_payment_thresholds_params_arc = Arc::new(Mutex::new(vec![]));
+        let set_scan_intervals_params_arc = Arc::new(Mutex::new(vec![]));
+        let set_blockchain_service_url_params_arc = Arc::new(Mutex::new(vec![]));
+        let persistent_config = PersistentConfigurationMock::new()
+            .set_payment_thresholds_params(&set_payment_thresholds_params_arc)
+            .set_payment_thresholds_result(Ok(()))
+            .set_scan_intervals_params(&set_scan_intervals_params_arc)
+            .set_scan_intervals_result(Ok(()))
+            .set_blockchain_service_url_params(&set_blockchain_service_url_params_arc)
+            .set_blockchain_service_url_result(Ok(()));
+        let mut subject = make_subject(Some(persistent_config));
+        subject.config_change_subs_opt = Some(peer_actors.config_change_subs());
+        subject.config_reader_opt = Some(Box::new(ConfigReaderMock::new().read_result(Ok(
+            make_snapshot(&[
+                ("blockchain-service-url", "https://example.com"),
+                ("payment-thresholds", "2000|1000|2000|1000|20000|1000"),
+                ("scan-intervals", "111|222|333"),
+            ]),
+        ))));
+        subject.running_config_opt = Some(make_snapshot(&[]));
+
+        let result = subject.reload_config();
+
+        System::current().stop();
+        system.run();
+        assert_eq!(
+            result,
+            Ok(UiReloadConfigResponse {
+                applied: vec![
+                    "blockchain-service-url".to_string(),
+                    "payment-thresholds".to_string(),
+                    "scan-intervals".to_string(),
+                ],
+                failed: vec![],
+                restart_required: vec![],
+            })
+        );
+        assert_eq!(
+            *set_blockchain_service_url_params_arc.lock().unwrap(),
+            vec!["https://example.com".to_string()]
+        );
+        assert_eq!(
+            *set_payment_thresholds_params_arc.lock().unwrap(),
+            vec!["2000|1000|2000|1000|20000|1000".to_string()]
+        );
+        assert_eq!(
+            *set_scan_intervals_params_arc.lock().unwrap(),
+            vec!["111|222|333".to_string()]
+        );
+        let accountant_recording = accountant_recording_arc.lock().unwrap();
+        assert_eq!(
+            accountant_recording.get_record::<ConfigChangeMsg>(0),
+            &ConfigChangeMsg {
+                change: ConfigChange::UpdateBlockchainServiceUrl("https://example.com".to_string())
+            }
+        );
+        assert_eq!(
+            accountant_recording.get_record::<ConfigChangeMsg>(1),
+            &ConfigChangeMsg {
+                change: ConfigChange::UpdatePaymentThresholds(
+                    PaymentThresholds::try_from("2000|1000|2000|1000|20000|1000").unwrap()
+                )
+            }
+        );
+        assert_eq!(
+            accountant_recording.get_record::<ConfigChangeMsg>(2),
+            &ConfigChangeMsg {
+                change: ConfigChange::UpdateScanIntervals(
+                    ScanIntervals::try_from("111|222|333").unwrap()
+                )
+            }
+        );
+    }
+
+    #[test]
+    fn reload_config_applies_nothing_if_any_live_change_is_bad() {
+        let running = make_snapshot(&[("log-level", "warn"), ("rate-pack", "1|1|1|1")]);
+        let mut subject = make_subject(None);
+        subject.config_change_subs_opt = Some(vec![]);
+        subject.config_reader_opt = Some(Box::new(ConfigReaderMock::new().read_result(Ok(
+            make_snapshot(&[("log-level", "loud"), ("rate-pack", "2|2|2|2")]),
+        ))));
+        subject.running_config_opt = Some(running.clone());
+
+        let result = subject.reload_config();
+
+        assert_eq!(
+            result,
+            Err((
+                LOG_LEVEL_ERROR,
+                "Unrecognized log level 'loud'; use off, error, warn, info, debug or trace"
+                    .to_string()
+            ))
+        );
+        assert_eq!(subject.running_config_opt, Some(running));
+    }
+
+    #[test]
+    fn reload_config_refuses_payment_thresholds_that_fail_the_startup_checks() {
+        let result =
+            Configurator::parse_live_change("payment-thresholds", "1000|1000|2000|1000|20000|1000");
+
+        assert_eq!(
+            result,
+            Err((
+                NON_PARSABLE_VALUE,
+                "payment-thresholds: Value of DebtThresholdGwei (1000) must be bigger than \
+                PermanentDebtAllowedGwei (1000)"
+                    .to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn reload_config_reports_a_change_it_cannot_store_and_applies_the_others() {
+        init_test_logging();
+        let test_name = "reload_config_reports_a_change_it_cannot_store_and_applies_the_others";
+        let set_log_levels_params_arc = Arc::new(Mutex::new(vec![]));
+        let set_rate_pack_params_arc = Arc::new(Mutex::new(vec![]));
+        let persistent_config = PersistentConfigurationMock::new()
+            .set_min_hops_result(Err(PersistentConfigError::TransactionError))
+            .set_rate_pack_params(&set_rate_pack_params_arc)
+            .set_rate_pack_result(Ok(()));
+        let mut subject = make_subject(Some(persistent_config));
+        subject.logger = Logger::new(test_name);
+        subject.log_level_setter = Box::new(
+            LogLevelSetterMock::new()
+                .set_log_levels_params(&set_log_levels_params_arc)
+                .set_log_levels_result(Ok("debug".to_string())),
+        );
+        subject.config_change_subs_opt = Some(vec![]);
+        subject.config_reader_opt = Some(Box::new(ConfigReaderMock::new().read_result(Ok(
+            make_snapshot(&[
+                ("log-level", "debug"),
+                ("min-hops", "4"),
+                ("rate-pack", "2|2|2|2"),
+            ]),
+        ))));
+        subject.running_config_opt = Some(make_snapshot(&[
+            ("log-level", "warn"),
+            ("min-hops", "3"),
+            ("rate-pack", "1|1|1|1"),
+        ]));
+
+        let result = subject.reload_config();
+
+        assert_eq!(
+            result,
+            Ok(UiReloadConfigResponse {
+                applied: vec!["log-level".to_string(), "rate-pack".to_string()],
+                failed: vec![UiReloadConfigFailure {
+                    parameter: "min-hops".to_string(),
+                    code: CONFIGURATOR_WRITE_ERROR,
+                    message: "min hops: TransactionError".to_string(),
+                }],
+                restart_required: vec![],
+            })
+        );
+        assert_eq!(
+            *set_log_levels_params_arc.lock().unwrap(),
+            vec![(Some(LevelFilter::Debug), vec![])]
+        );
+        assert_eq!(
+            *set_rate_pack_params_arc.lock().unwrap(),
+            vec!["2|2|2|2".to_string()]
+        );
+        assert_eq!(
+            subject.running_config_opt,
+            Some(make_snapshot(&[
+                ("log-level", "debug"),
+                ("min-hops", "3"),
+                ("rate-pack", "2|2|2|2"),
+            ]))
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: {}: Configuration partly reloaded; applied: [\"log-level\", \"rate-pack\"]; \
+             failed: min-hops ({}, min hops: TransactionError); restart required: []",
+            test_name, CONFIGURATOR_WRITE_ERROR
+        ));
+    }
+
+    #[test]
+    fn reload_config_request_reports_a_configuration_that_cannot_be_read() {
+        init_test_logging();
+        let test_name = "reload_config_request_reports_a_configuration_that_cannot_be_read";
+        let mut subject = make_subject(None);
+        subject.logger = Logger::new(test_name);
+        subject.config_reader_opt = Some(Box::new(
+            ConfigReaderMock::new()
+                .read_result(Err(ConfiguratorError::required("rate-pack", "Bad format"))),
+        ));
+        subject.running_config_opt = Some(make_snapshot(&[]));
+
+        let result = subject.handle_reload_config(UiReloadConfigRequest {}, 4321);
+
+        assert_eq!(
+            result,
+            MessageBody {
+                opcode: "reloadConfig".to_string(),
+                path: MessagePath::Conversation(4321),
+                payload: Err((CONFIG_RELOAD_ERROR, "rate-pack: Bad format".to_string()))
+            }
+        );
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: {}: Failed to reload configuration: {}, rate-pack: Bad format",
+            test_name, CONFIG_RELOAD_ERROR
+        ));
+    }
+
+    #[test]
+    fn reload_config_without_a_starting_configuration_takes_the_reloaded_one_as_the_baseline() {
+        let reloaded = make_snapshot(&[("log-level", "debug")]);
+        let mut subject = make_subject(None);
+        subject.config_reader_opt = Some(Box::new(
+            ConfigReaderMock::new().read_result(Ok(reloaded.clone())),
+        ));
+
+        let result = subject.reload_config();
+
+        assert_eq!(result.unwrap_err().0, CONFIG_RELOAD_ERROR);
+        assert_eq!(subject.running_config_opt, Some(reloaded));
+    }
+
+    #[test]
+    fn reload_config_is_refused_without_a_config_reader() {
+        let mut subject = make_subject(None);
+
+        let result = subject.reload_config();
+
+        assert_eq!(
+            result,
+            Err((
+                CONFIG_RELOAD_ERROR,
+                "This Node cannot reload its configuration".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn reload_config_on_signal_applies_the_changes() {
+        init_test_logging();
+        let test_name = "reload_config_on_signal_applies_the_changes";
+        let system = System::new(test_name);
+        let (neighborhood, _, neighborhood_recording_arc) = make_recorder();
+        let neighborhood =
+            neighborhood.system_stop_conditions(match_every_type_id!(ConfigChangeMsg));
+        let peer_actors = peer_actors_builder().neighborhood(neighborhood).build();
+        let mut subject = make_subject(None);
+        subject.logger = Logger::new(test_name);
+        subject.config_reader_opt = Some(Box::new(
+            ConfigReaderMock::new().read_result(Ok(make_snapshot(&[("dns-servers", "5.6.7.8")]))),
+        ));
+        subject.running_config_opt = Some(make_snapshot(&[("dns-servers", "1.1.1.1")]));
+        let subject_addr = subject.start();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+        subject_addr.try_send(ReloadConfigOnSignal {}).unwrap();
+
+        system.run();
+        let neighborhood_recording = neighborhood_recording_arc.lock().unwrap();
+        assert_eq!(
+            neighborhood_recording.get_record::<ConfigChangeMsg>(0),
+            &ConfigChangeMsg {
+                change: ConfigChange::UpdateDnsServers(vec![
+                    SocketAddr::from_str("5.6.7.8:53").unwrap()
+                ])
+            }
+        );
+        TestLogHandler::new().assert_logs_contain_in_order(vec![
+            &format!(
+                "INFO: {}: Received SIGHUP; reloading configuration",
+                test_name
+            ),
+            &format!(
+                "INFO: {}: Configuration reloaded; applied: [\"dns-servers\"]; restart required: []",
+                test_name
+            ),
+        ]);
+    }
+
+    #[test]
+    fn unfriendly_handle_recover_wallets_handles_useless_seed_spec_with_key_and_address() {
+        let db_password = "password".to_string();
+        let consuming_private_key =
+            "0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF".to_string();
+        let earning_address = "0x0123456789012345678901234567890123456789".to_string();
+        let msg = UiRecoverWalletsRequest {
+            db_password: db_password.clone(),
+            seed_spec_opt: Some(UiRecoverSeedSpec {
+                mnemonic_phrase: make_meaningless_phrase_words(),
+                mnemonic_phrase_language_opt: Some("English".to_string()),
+                mnemonic_passphrase_opt: None,
+            }),
+            consuming_derivation_path_opt: None,
+            consuming_private_key_opt: Some(consuming_private_key.clone()),
+            consuming_keystore_opt: None,
+            earning_derivation_path_opt: None,
+            earning_address_opt: Some(earning_address.clone()),
+        };
+        let set_wallet_info_params_arc = Arc::new(Mutex::new(vec![]));
+        let mut persistent_config: Box<dyn PersistentConfiguration> = Box::new(
+            configure_default_persistent_config(ZERO)
+                .check_password_result(Ok(true))
+                .set_wallet_info_params(&set_wallet_info_params_arc)
+                .set_wallet_info_result(Ok(())),
+        );
+
+        let _ = Configurator::unfriendly_handle_recover_wallets(msg, 1234, &mut persistent_config)
+            .unwrap();
+
+        let set_wallet_info_params = set_wallet_info_params_arc.lock().unwrap();
+        assert_eq!(
+            *set_wallet_info_params,
+            vec![(consuming_private_key, earning_address, db_password)]
+        )
+    }
+
+    #[test]
+    fn unfriendly_handle_recover_wallets_handles_no_seed_spec_and_only_earning_wallet_address() {
+        let msg = UiRecoverWalletsRequest {
+            db_password: "password".to_string(),
+            seed_spec_opt: None,
+            consuming_derivation_path_opt: None,
+            consuming_private_key_opt: None,
+            consuming_keystore_opt: None,
+            earning_derivation_path_opt: None,
+            earning_address_opt: Some("0x0123456789012345678901234567890123456789".to_string()),
+        };
+        let mut persistent_config: Box<dyn PersistentConfiguration> =
+            Box::new(configure_default_persistent_config(ZERO).check_password_result(Ok(true)));
+
+        let result =
+            Configurator::unfriendly_handle_recover_wallets(msg, 1234, &mut persistent_config);
+
+        assert_eq! (result, Err((MISSING_DATA, "If you supply no seed information, you must supply both consuming wallet private key and earning wallet address".to_string())));
+    }
+
+    #[test]
+    fn unfriendly_handle_recover_wallets_handles_seed_but_nothing_about_consuming_wallet() {
+        let db_password = "password".to_string();
+        let earning_address = "0x0123456789012345678901234567890123456789".to_string();
+        let msg = UiRecoverWalletsRequest {
+            db_password,
+            seed_spec_opt: Some(UiRecoverSeedSpec {
+                mnemonic_phrase: make_meaningless_phrase_words(),
+                mnemonic_phrase_language_opt: Some("English".to_string()),
+                mnemonic_passphrase_opt: None,
+            }),
+            consuming_derivation_path_opt: None,
+            consuming_private_key_opt: None,
+            consuming_keystore_opt: None,
+            earning_derivation_path_opt: None,
+            earning_address_opt: Some(earning_address),
+        };
+        let mut persistent_config: Box<dyn PersistentConfiguration> =
+            Box::new(configure_default_persistent_config(ZERO).check_password_result(Ok(true)));
+
+        let result =
+            Configurator::unfriendly_handle_recover_wallets(msg, 1234, &mut persistent_config);
+
+        assert_eq! (result, Err((MISSING_DATA, "If you supply seed information, you must supply either the consuming wallet derivation path or the consuming wallet private key".to_string())));
+    }
+
+    #[test]
+    fn unfriendly_handle_recover_wallets_handles_seed_but_nothing_about_earning_wallet() {
+        let db_password = "password".to_string();
+        let consuming_private_key =
+            "0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF".to_string();
+        let msg = UiRecoverWalletsRequest {
+            db_password,
+            seed_spec_opt: Some(UiRecoverSeedSpec {
+                mnemonic_phrase: make_meaningless_phrase_words(),
+                mnemonic_phrase_language_opt: Some("English".to_string()),
+                mnemonic_passphrase_opt: None,
+            }),
+            consuming_derivation_path_opt: None,
+            consuming_private_key_opt: Some(consuming_private_key),
+            consuming_keystore_opt: None,
+            earning_derivation_path_opt: None,
+            earning_address_opt: None,
+        };
+        let mut persistent_config: Box<dyn PersistentConfiguration> =
+            Box::new(configure_default_persistent_config(ZERO).check_password_result(Ok(true)));
+
+        let result =
+            Configurator::unfriendly_handle_recover_wallets(msg, 1234, &mut persistent_config);
+
+        assert_eq! (result, Err((MISSING_DATA, "If you supply seed information, you must supply either the earning wallet derivation path or the earning wallet address".to_string())));
+    }
+
+    #[test]
+    fn unfriendly_handle_recover_wallets_defaults_language_to_english() {
+        let db_password = "password".to_string();
+        let msg = UiRecoverWalletsRequest {
+            db_password,
+            seed_spec_opt: Some(UiRecoverSeedSpec {
+                mnemonic_phrase: make_meaningless_phrase_words(),
+                mnemonic_phrase_language_opt: None,
+                mnemonic_passphrase_opt: None,
+            }),
+            consuming_derivation_path_opt: Some(derivation_path(10, 20)),
+            consuming_private_key_opt: None,
+            consuming_keystore_opt: None,
+            earning_derivation_path_opt: None,
+            earning_address_opt: Some("0x0123456789012345678901234567890123456789".to_string()),
+        };
+        let mut persistent_config: Box<dyn PersistentConfiguration> = Box::new(
+            configure_default_persistent_config(ZERO)
+                .check_password_result(Ok(true))
+                .set_wallet_info_result(Ok(())),
+        );
+
+        let result =
+            Configurator::unfriendly_handle_recover_wallets(msg, 1234, &mut persistent_config);
+
+        assert_eq!(result.is_ok(), true); // phrase is in English; if language didn't default there, test would blow up
+    }
+
+    #[test]
+    fn handle_set_configuration_works() {
+        init_test_logging();
+        let test_name = "handle_set_configuration_works";
+        let set_start_block_params_arc = Arc::new(Mutex::new(vec![]));
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let persistent_config = PersistentConfigurationMock::new()
+            .set_start_block_params(&set_start_block_params_arc)
+            .set_start_block_result(Ok(()));
+        let mut subject = make_subject(Some(persistent_config));
+        subject.logger = Logger::new(test_name);
+        let subject_addr = subject.start();
+        let peer_actors = peer_actors_builder().ui_gateway(ui_gateway).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+        let msg = UiSetConfigurationRequest {
+            name: "start-block".to_string(),
+            value: "166666".to_string(),
+        };
+        let context_id = 4444;
+
+        subject_addr
+            .try_send(NodeFromUiMessage {
+                client_id: 1234,
+                body: msg.clone().tmb(context_id),
+            })
+            .unwrap();
+
+        let system = System::new("test");
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        let response = ui_gateway_recording.get_record::<NodeToUiMessage>(0);
+        let (_, context_id) = UiSetConfigurationResponse::fmb(response.body.clone()).unwrap();
+        assert_eq!(context_id, 4444);
+        let check_start_block_params = set_start_block_params_arc.lock().unwrap();
+        assert_eq!(*check_start_block_params, vec![Some(166666)]);
+    }
+
+    #[test]
+    fn handle_none_cases() {
+        vec!["none", "None", "nOnE", "NoNe", "NONE"]
+            .iter()
+            .for_each(|value| handle_set_configuration_accepts_none_to_unset_start_block(value));
+    }
+
+    fn handle_set_configuration_accepts_none_to_unset_start_block(cfg_value: &str) {
+        init_test_logging();
+        let test_name = format!(
+            "handle_set_configuration_accepts_{}_to_unset_start_block",
+            &cfg_value
+        );
+        let set_start_block_params_arc = Arc::new(Mutex::new(vec![]));
+        let (ui_gateway, _, ui_gateway_recording_arc) = make_recorder();
+        let persistent_config = PersistentConfigurationMock::new()
+            .set_start_block_params(&set_start_block_params_arc)
+            .set_start_block_result(Ok(()));
+        let mut subject = make_subject(Some(persistent_config));
+        subject.logger = Logger::new(test_name.as_str());
+        let subject_addr = subject.start();
+        let peer_actors = peer_actors_builder().ui_gateway(ui_gateway).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+        let msg = UiSetConfigurationRequest {
+            name: "start-block".to_string(),
+            value: cfg_value.to_string(),
+        };
+        let context_id = 4444;
+
+        subject_addr
+            .try_send(NodeFromUiMessage {
+                client_id: 1234,
+                body: msg.clone().tmb(context_id),
+            })
+            .unwrap();
+
+        let system = System::new("test");
+        System::current().stop();
+        system.run();
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
         let response = ui_gateway_recording.get_record::<NodeToUiMessage>(0);
         let (_, context_id) = UiSetConfigurationResponse::fmb(response.body.clone()).unwrap();
         assert_eq!(context_id, 4444);
@@ -2201,6 +4066,235 @@ mod tests {
         assert_eq!(*set_gas_price_params, vec![68])
     }
 
+    #[test]
+    fn handle_set_configuration_works_for_bandwidth_limits() {
+        let set_bandwidth_limits_params_arc = Arc::new(Mutex::new(vec![]));
+        let persistent_config = PersistentConfigurationMock::new()
+            .set_bandwidth_limits_params(&set_bandwidth_limits_params_arc)
+            .set_bandwidth_limits_result(Ok(()));
+        let mut subject = make_subject(Some(persistent_config));
+        let shaper = BandwidthShaper::default();
+        subject.bandwidth_shaper = shaper.clone();
+
+        let result = subject.handle_set_configuration(
+            UiSetConfigurationRequest {
+                name: "bandwidth-limits".to_string(),
+                value: "100|200|300|400|50".to_string(),
+            },
+            4000,
+        );
+
+        assert_eq!(
+            result,
+            MessageBody {
+                opcode: "setConfiguration".to_string(),
+                path: MessagePath::Conversation(4000),
+                payload: Ok(r#"{}"#.to_string())
+            }
+        );
+        let set_bandwidth_limits_params = set_bandwidth_limits_params_arc.lock().unwrap();
+        assert_eq!(
+            *set_bandwidth_limits_params,
+            vec!["100|200|300|400|50".to_string()]
+        );
+        assert_eq!(
+            shaper.limits(),
+            BandwidthLimits {
+                upload_kb_per_sec: 100,
+                download_kb_per_sec: 200,
+                relay_kb_per_sec: 300,
+                exit_kb_per_sec: 400,
+                per_neighbor_kb_per_sec: 50,
+            }
+        );
+    }
+
+    #[test]
+    fn handle_set_configuration_handles_bandwidth_limits_non_parsable_value_issue() {
+        let persistent_config = PersistentConfigurationMock::new();
+        let mut subject = make_subject(Some(persistent_config));
+        let shaper = BandwidthShaper::default();
+        subject.bandwidth_shaper = shaper.clone();
+
+        let result = subject.handle_set_configuration(
+            UiSetConfigurationRequest {
+                name: "bandwidth-limits".to_string(),
+                value: "100|200".to_string(),
+            },
+            4000,
+        );
+
+        let (code, message) = result.payload.unwrap_err();
+        assert_eq!(code, NON_PARSABLE_VALUE);
+        assert!(message.starts_with("bandwidth limits: "), "{}", message);
+        assert_eq!(shaper.limits(), BandwidthLimits::default());
+    }
+
+    #[test]
+    fn handle_set_configuration_works_for_data_quota() {
+        let set_data_quota_params_arc = Arc::new(Mutex::new(vec![]));
+        let persistent_config = PersistentConfigurationMock::new()
+            .set_data_quota_params(&set_data_quota_params_arc)
+            .set_data_quota_result(Ok(()));
+        let mut subject = make_subject(Some(persistent_config));
+        let shaper = BandwidthShaper::default();
+        subject.bandwidth_shaper = shaper.clone();
+
+        let result = subject.handle_set_configuration(
+            UiSetConfigurationRequest {
+                name: "data-quota".to_string(),
+                value: "1000|20000".to_string(),
+            },
+            4000,
+        );
+
+        assert_eq!(
+            result,
+            MessageBody {
+                opcode: "setConfiguration".to_string(),
+                path: MessagePath::Conversation(4000),
+                payload: Ok(r#"{}"#.to_string())
+            }
+        );
+        let set_data_quota_params = set_data_quota_params_arc.lock().unwrap();
+        assert_eq!(*set_data_quota_params, vec!["1000|20000".to_string()]);
+        assert_eq!(
+            shaper.data_quota(),
+            DataQuota {
+                daily_mb: 1000,
+                monthly_mb: 20000,
+            }
+        );
+    }
+
+    #[test]
+    fn handle_set_configuration_handles_failure_on_data_quota_database_issue() {
+        let persistent_config = PersistentConfigurationMock::new()
+            .set_data_quota_result(Err(PersistentConfigError::TransactionError));
+        let mut subject = make_subject(Some(persistent_config));
+        let shaper = BandwidthShaper::default();
+        subject.bandwidth_shaper = shaper.clone();
+
+        let result = subject.handle_set_configuration(
+            UiSetConfigurationRequest {
+                name: "data-quota".to_string(),
+                value: "1000|20000".to_string(),
+            },
+            4000,
+        );
+
+        assert_eq!(
+            result,
+            MessageBody {
+                opcode: "setConfiguration".to_string(),
+                path: MessagePath::Conversation(4000),
+                payload: Err((
+                    CONFIGURATOR_WRITE_ERROR,
+                    "data quota: TransactionError".to_string()
+                ))
+            }
+        );
+        assert_eq!(shaper.data_quota(), DataQuota::default());
+    }
+
+    #[test]
+    fn handle_set_configuration_works_for_spending_budget() {
+        let system = System::new("handle_set_configuration_works_for_spending_budget");
+        let (accountant, _, accountant_recording_arc) = make_recorder();
+        let peer_actors = peer_actors_builder().accountant(accountant).build();
+        let set_spending_budget_params_arc = Arc::new(Mutex::new(vec![]));
+        let persistent_config = PersistentConfigurationMock::new()
+            .set_spending_budget_params(&set_spending_budget_params_arc)
+            .set_spending_budget_result(Ok(()));
+        let mut subject = make_subject(Some(persistent_config));
+        subject.config_change_subs_opt = Some(peer_actors.config_change_subs());
+
+        let result = subject.handle_set_configuration(
+            UiSetConfigurationRequest {
+                name: "spending-budget".to_string(),
+                value: "3000000|50000000|60|90".to_string(),
+            },
+            4000,
+        );
+
+        System::current().stop();
+        system.run();
+        assert_eq!(
+            result,
+            MessageBody {
+                opcode: "setConfiguration".to_string(),
+                path: MessagePath::Conversation(4000),
+                payload: Ok(r#"{}"#.to_string())
+            }
+        );
+        let set_spending_budget_params = set_spending_budget_params_arc.lock().unwrap();
+        assert_eq!(
+            *set_spending_budget_params,
+            vec!["3000000|50000000|60|90".to_string()]
+        );
+        let accountant_recording = accountant_recording_arc.lock().unwrap();
+        assert_eq!(
+            accountant_recording.get_record::<ConfigChangeMsg>(0),
+            &ConfigChangeMsg {
+                change: ConfigChange::UpdateSpendingBudget(SpendingBudget {
+                    daily_gwei: 3_000_000,
+                    monthly_gwei: 50_000_000,
+                    first_warning_percent: 60,
+                    second_warning_percent: 90,
+                })
+            }
+        );
+    }
+
+    #[test]
+    fn handle_set_configuration_handles_spending_budget_non_parsable_value_issue() {
+        let persistent_config = PersistentConfigurationMock::new();
+        let mut subject = make_subject(Some(persistent_config));
+        subject.config_change_subs_opt = Some(vec![]);
+
+        let result = subject.handle_set_configuration(
+            UiSetConfigurationRequest {
+                name: "spending-budget".to_string(),
+                value: "1000|0|95|80".to_string(),
+            },
+            4000,
+        );
+
+        assert_eq!(
+            result.payload,
+            Err((
+                NON_PARSABLE_VALUE,
+                "spending budget: Warning percentages must not decrease and must not exceed 100, \
+                but 95 and 80 supplied"
+                    .to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn handle_set_configuration_handles_failure_on_spending_budget_database_issue() {
+        let persistent_config = PersistentConfigurationMock::new()
+            .set_spending_budget_result(Err(PersistentConfigError::TransactionError));
+        let mut subject = make_subject(Some(persistent_config));
+        subject.config_change_subs_opt = Some(vec![]);
+
+        let result = subject.handle_set_configuration(
+            UiSetConfigurationRequest {
+                name: "spending-budget".to_string(),
+                value: "1000|0|80|95".to_string(),
+            },
+            4000,
+        );
+
+        assert_eq!(
+            result.payload,
+            Err((
+                CONFIGURATOR_WRITE_ERROR,
+                "spending budget: TransactionError".to_string()
+            ))
+        );
+    }
+
     #[test]
     fn handle_set_configuration_handles_failure_on_gas_price_database_issue() {
         let persistent_config = PersistentConfigurationMock::new()
@@ -2985,6 +5079,7 @@ mod tests {
             }),
             consuming_derivation_path_opt: Some(derivation_path(0, 4)),
             consuming_private_key_opt: None,
+            consuming_keystore_opt: None,
             earning_derivation_path_opt: Some(derivation_path(0, 5)),
             earning_address_opt: None,
         }
@@ -2994,8 +5089,15 @@ mod tests {
         fn from(persistent_config: Box<dyn PersistentConfiguration>) -> Self {
             Configurator {
                 persistent_config,
+                database_backup: Box::new(DatabaseBackupMock::new()),
+                log_level_setter: Box::new(LogLevelSetterMock::new()),
                 node_to_ui_sub_opt: None,
                 config_change_subs_opt: None,
+                bandwidth_shaper: BandwidthShaper::default(),
+                config_reader_opt: None,
+                running_config_opt: None,
+                notify_later_check_data_quota: Box::new(NotifyLaterHandleReal::new()),
+                check_data_quota_interval: CHECK_DATA_QUOTA_INTERVAL,
                 crashable: false,
                 logger: Logger::new("Configurator"),
             }
@@ -1,5 +1,6 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
+pub mod config_reload;
 pub mod configurator;
 pub mod node_configurator_initialization;
 pub mod node_configurator_standard;
@@ -15,13 +16,14 @@ use crate::sub_lib::utils::{db_connection_launch_panic, make_new_multi_config};
 use clap::{value_t, App};
 use core::option::Option;
 use dirs::{data_local_dir, home_dir};
-use masq_lib::blockchains::chains::Chain;
+use masq_lib::blockchains::chains::{chain_from_chain_identifier_opt, Chain};
+use masq_lib::blockchains::custom_chains::{load_custom_chains, CUSTOM_CHAINS_FILE_NAME};
 use masq_lib::constants::DEFAULT_CHAIN;
 use masq_lib::multi_config::{
     merge, CommandLineVcl, EnvironmentVcl, MultiConfig, VirtualCommandLine,
 };
 use masq_lib::shared_schema::ConfiguratorError;
-use masq_lib::utils::{add_masq_and_chain_directories, localhost};
+use masq_lib::utils::localhost;
 use std::env::current_dir;
 use std::net::{SocketAddr, TcpListener};
 use std::path::{Path, PathBuf};
@@ -53,14 +55,34 @@ pub struct ConfigInitializationData {
     pub(crate) config_file: FieldPair<PathBuf>,
 }
 
-fn get_chain_from_mc(multi_config: &MultiConfig) -> FieldPair<Chain> {
-    let chain = value_m!(multi_config, "chain", String);
-    match chain {
-        Some(chain) => FieldPair::new(Chain::from(&*chain), true),
-        None => FieldPair::new(DEFAULT_CHAIN, false),
+fn get_chain_name_from_mc(multi_config: &MultiConfig) -> FieldPair<String> {
+    let chain_name = value_m!(multi_config, "chain", String);
+    match chain_name {
+        Some(chain_name) => FieldPair::new(chain_name, true),
+        None => FieldPair::new(DEFAULT_CHAIN.rec().literal_identifier.to_string(), false),
     }
 }
 
+// The chains file of the data directory is read before the name is looked up, so that a
+// custom chain is known by the time its name is, and so is a custom chain named only in a
+// neighbor's descriptor
+pub fn chain_from_name(
+    chain_name: &str,
+    data_directory: &Path,
+) -> Result<Chain, ConfiguratorError> {
+    load_custom_chains(data_directory).map_err(|e| ConfiguratorError::required("chain", &e))?;
+    chain_from_chain_identifier_opt(chain_name).ok_or_else(|| {
+        ConfiguratorError::required(
+            "chain",
+            &format!(
+                "'{}' is neither an official chain nor defined in {}",
+                chain_name,
+                data_directory.join(CUSTOM_CHAINS_FILE_NAME).display()
+            ),
+        )
+    })
+}
+
 fn get_real_user_from_mc(
     multi_config: &MultiConfig,
     dirs_wrapper: &dyn DirsWrapper,
@@ -91,7 +113,7 @@ fn get_data_directory_from_mc(
     multi_config: &MultiConfig,
     dirs_wrapper: &dyn DirsWrapper,
     real_user: &RealUser,
-    chain: &Chain,
+    chain_name: &str,
 ) -> FieldPair<PathBuf> {
     let data_directory = value_m!(multi_config, "data-directory", PathBuf);
     match data_directory {
@@ -113,7 +135,7 @@ fn get_data_directory_from_mc(
             false => FieldPair::new(data_dir, true),
         },
         None => FieldPair::new(
-            data_directory_from_context(dirs_wrapper, real_user, *chain),
+            data_directory_from_context_and_chain_name(dirs_wrapper, real_user, chain_name),
             false,
         ),
     }
@@ -198,29 +220,31 @@ fn get_config_file_from_mc(
 fn config_file_data_dir_real_user_chain_from_mc(
     dirs_wrapper: &dyn DirsWrapper,
     multi_config: MultiConfig,
-) -> ConfigInitializationData {
-    let mut initialization_data = ConfigInitializationData {
-        chain: Default::default(),
-        real_user: Default::default(),
-        data_directory: Default::default(),
-        config_file: Default::default(),
-    };
-
-    initialization_data.chain = get_chain_from_mc(&multi_config);
-    initialization_data.real_user = get_real_user_from_mc(&multi_config, dirs_wrapper);
-    initialization_data.data_directory = get_data_directory_from_mc(
+) -> Result<ConfigInitializationData, ConfiguratorError> {
+    let chain_name = get_chain_name_from_mc(&multi_config);
+    let real_user = get_real_user_from_mc(&multi_config, dirs_wrapper);
+    let data_directory = get_data_directory_from_mc(
         &multi_config,
         dirs_wrapper,
-        &initialization_data.real_user.item,
-        &initialization_data.chain.item,
+        &real_user.item,
+        &chain_name.item,
     );
-    initialization_data.config_file = get_config_file_from_mc(
+    let chain = FieldPair::new(
+        chain_from_name(&chain_name.item, &data_directory.item)?,
+        chain_name.user_specified,
+    );
+    let config_file = get_config_file_from_mc(
         &multi_config,
-        &initialization_data.data_directory.item,
-        initialization_data.data_directory.user_specified,
+        &data_directory.item,
+        data_directory.user_specified,
         dirs_wrapper,
     );
-    initialization_data
+    Ok(ConfigInitializationData {
+        chain,
+        real_user,
+        data_directory,
+        config_file,
+    })
 }
 
 pub fn determine_user_specific_data(
@@ -235,10 +259,7 @@ pub fn determine_user_specific_data(
     /* We create multiconfig to retrieve chain, real-user, data-directory and config file, to establish ConfigVcl */
     let first_multi_config =
         make_new_multi_config(app, vec![orientation_args]).expect("expected MultiConfig");
-    let initialization_data =
-        config_file_data_dir_real_user_chain_from_mc(dirs_wrapper, first_multi_config);
-
-    Ok(initialization_data)
+    config_file_data_dir_real_user_chain_from_mc(dirs_wrapper, first_multi_config)
 }
 
 pub fn initialize_database(
@@ -264,22 +285,37 @@ pub fn real_user_from_multi_config_or_populate(
 pub fn real_user_data_directory_path_and_chain(
     dirs_wrapper: &dyn DirsWrapper,
     multi_config: &MultiConfig,
-) -> (RealUser, Option<PathBuf>, Chain) {
+) -> Result<(RealUser, Option<PathBuf>, Chain), ConfiguratorError> {
     let real_user = real_user_from_multi_config_or_populate(multi_config, dirs_wrapper);
     let chain_name = value_m!(multi_config, "chain", String)
         .unwrap_or_else(|| DEFAULT_CHAIN.rec().literal_identifier.to_string());
     let data_directory_path = value_m!(multi_config, "data-directory", PathBuf);
-    (
-        real_user,
-        data_directory_path,
-        Chain::from(chain_name.as_str()),
-    )
+    let chain = chain_from_name(
+        &chain_name,
+        &data_directory_path.clone().unwrap_or_else(|| {
+            data_directory_from_context_and_chain_name(dirs_wrapper, &real_user, &chain_name)
+        }),
+    )?;
+    Ok((real_user, data_directory_path, chain))
 }
 
 pub fn data_directory_from_context(
     dirs_wrapper: &dyn DirsWrapper,
     real_user: &RealUser,
     chain: Chain,
+) -> PathBuf {
+    data_directory_from_context_and_chain_name(
+        dirs_wrapper,
+        real_user,
+        chain.rec().literal_identifier,
+    )
+}
+
+// The default data directory of a chain whose name may not be resolvable yet
+pub fn data_directory_from_context_and_chain_name(
+    dirs_wrapper: &dyn DirsWrapper,
+    real_user: &RealUser,
+    chain_name: &str,
 ) -> PathBuf {
     let right_home_dir = real_user
         .home_dir_opt
@@ -294,8 +330,10 @@ pub fn data_directory_from_context(
     let adjusted_local_data_dir: &Path = wrong_local_data_dir
         .strip_prefix(wrong_home_dir)
         .expect("std lib failed");
-    let homedir = right_home_dir.join(adjusted_local_data_dir);
-    add_masq_and_chain_directories(chain, &homedir)
+    right_home_dir
+        .join(adjusted_local_data_dir)
+        .join("MASQ")
+        .join(chain_name)
 }
 
 pub fn port_is_busy(port: u16) -> bool {
@@ -339,10 +377,14 @@ mod tests {
     use super::*;
     use crate::node_test_utils::DirsWrapperMock;
     use crate::test_utils::ArgsBuilder;
-    use masq_lib::shared_schema::{config_file_arg, data_directory_arg, DATA_DIRECTORY_HELP};
+    use masq_lib::shared_schema::{
+        chain_arg, config_file_arg, data_directory_arg, real_user_arg, DATA_DIRECTORY_HELP,
+    };
     use masq_lib::test_utils::environment_guard::EnvironmentGuard;
     use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
     use masq_lib::utils::find_free_port;
+    use std::fs::File;
+    use std::io::Write;
     use std::net::{SocketAddr, TcpListener};
 
     fn determine_config_file_path_app() -> App<'static, 'static> {
@@ -576,6 +618,125 @@ mod tests {
         assert_eq!(user_specific_data.real_user.user_specified, false);
     }
 
+    fn write_chains_file(data_dir: &Path, chain_id: u64, identifier: &str) {
+        let mut file = File::create(data_dir.join(CUSTOM_CHAINS_FILE_NAME)).unwrap();
+        write!(
+            file,
+            "[[chain]]\nchain-id = {}\nidentifier = \"{}\"\n\
+             contract-address = \"0x00000000000000000000000000000000000000ef\"\n\
+             contract-creation-block = 0\nconfirmation-depth = 0\n\
+             gas-limit-const-part = 52000\nnative-transfer-gas-limit = 21000\nmainnet = false\n",
+            chain_id, identifier
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn determine_user_specific_data_finds_a_custom_chain_in_the_data_directory() {
+        let _guard = EnvironmentGuard::new();
+        let data_dir = ensure_node_home_directory_exists(
+            "node_configurator",
+            "determine_user_specific_data_finds_a_custom_chain_in_the_data_directory",
+        );
+        write_chains_file(&data_dir, 8_000_001, "configured-devnet");
+        let args: Vec<String> = ArgsBuilder::new()
+            .param("--chain", "configured-devnet")
+            .param("--data-directory", data_dir.to_str().unwrap())
+            .into();
+        let app = determine_config_file_path_app().arg(chain_arg());
+
+        let result =
+            determine_user_specific_data(&DirsWrapperReal::default(), &app, &args).unwrap();
+
+        assert_eq!(result.chain.item, Chain::Custom(8_000_001));
+        assert_eq!(result.chain.user_specified, true);
+        assert_eq!(result.data_directory.item, data_dir);
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn determine_user_specific_data_looks_for_a_custom_chain_in_its_default_data_directory() {
+        let _guard = EnvironmentGuard::new();
+        let home_dir = ensure_node_home_directory_exists(
+            "node_configurator",
+            "determine_user_specific_data_looks_for_a_custom_chain_in_its_default_data_directory",
+        );
+        let data_dir = home_dir.join("MASQ").join("defaulted-devnet");
+        std::fs::create_dir_all(&data_dir).unwrap();
+        write_chains_file(&data_dir, 8_000_002, "defaulted-devnet");
+        let dirs_wrapper = DirsWrapperMock::new()
+            .home_dir_result(Some(home_dir.clone()))
+            .data_dir_result(Some(home_dir.clone()));
+        let args: Vec<String> = ArgsBuilder::new()
+            .param("--chain", "defaulted-devnet")
+            .param(
+                "--real-user",
+                &format!("123:456:{}", home_dir.to_str().unwrap()),
+            )
+            .into();
+        let app = determine_config_file_path_app()
+            .arg(chain_arg())
+            .arg(real_user_arg());
+
+        let result = determine_user_specific_data(&dirs_wrapper, &app, &args).unwrap();
+
+        assert_eq!(result.chain.item, Chain::Custom(8_000_002));
+        assert_eq!(result.data_directory.item, data_dir);
+        assert_eq!(result.data_directory.user_specified, false);
+    }
+
+    #[test]
+    fn determine_user_specific_data_rejects_a_chain_defined_nowhere() {
+        let _guard = EnvironmentGuard::new();
+        let data_dir = ensure_node_home_directory_exists(
+            "node_configurator",
+            "determine_user_specific_data_rejects_a_chain_defined_nowhere",
+        );
+        let args: Vec<String> = ArgsBuilder::new()
+            .param("--chain", "nowhere-devnet")
+            .param("--data-directory", data_dir.to_str().unwrap())
+            .into();
+        let app = determine_config_file_path_app().arg(chain_arg());
+
+        let result = determine_user_specific_data(&DirsWrapperReal::default(), &app, &args);
+
+        assert_eq!(
+            result.err().unwrap(),
+            ConfiguratorError::required(
+                "chain",
+                &format!(
+                    "'nowhere-devnet' is neither an official chain nor defined in {}",
+                    data_dir.join("chains.toml").display()
+                )
+            )
+        );
+    }
+
+    #[test]
+    fn chain_from_name_reports_a_broken_chains_file() {
+        let data_dir = ensure_node_home_directory_exists(
+            "node_configurator",
+            "chain_from_name_reports_a_broken_chains_file",
+        );
+        let path = data_dir.join(CUSTOM_CHAINS_FILE_NAME);
+        File::create(&path)
+            .unwrap()
+            .write_all(b"[[chain]]\nidentifier = 5\n")
+            .unwrap();
+
+        let result = chain_from_name("polygon-mainnet", &data_dir);
+
+        let error = result.err().unwrap();
+        assert_eq!(error.param_errors[0].parameter, "chain");
+        assert!(
+            error.param_errors[0]
+                .reason
+                .starts_with(&format!("Couldn't parse {}: ", path.display())),
+            "{:?}",
+            error
+        );
+    }
+
     #[test]
     pub fn port_is_busy_detects_free_port() {
         let port = find_free_port();
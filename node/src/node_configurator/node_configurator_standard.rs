@@ -12,13 +12,14 @@ use std::net::SocketAddr;
 use std::net::{IpAddr, Ipv4Addr};
 
 use clap::value_t;
-use log::LevelFilter;
+use log::{Level, LevelFilter};
 
 use crate::apps::app_node;
 use crate::bootstrapper::PortConfiguration;
 use crate::database::db_initializer::{DbInitializationConfig, ExternalData};
 use crate::db_config::persistent_configuration::PersistentConfiguration;
 use crate::http_request_start_finder::HttpRequestDiscriminatorFactory;
+use crate::masquerader::MasqueraderKind;
 use crate::node_configurator::unprivileged_parse_args_configuration::{
     UnprivilegedParseArgsConfiguration, UnprivilegedParseArgsConfigurationDaoReal,
 };
@@ -26,13 +27,18 @@ use crate::node_configurator::{
     data_directory_from_context, determine_user_specific_data,
     real_user_data_directory_path_and_chain,
 };
+use crate::proxy_server::route_isolation::DEFAULT_ROUTE_LIFETIME;
 use crate::sub_lib::cryptde::PublicKey;
 use crate::sub_lib::cryptde_null::CryptDENull;
+use crate::sub_lib::ui_gateway::RemoteUiConfig;
 use crate::sub_lib::utils::make_new_multi_config;
 use crate::tls_discriminator_factory::TlsDiscriminatorFactory;
+use crate::ui_gateway::remote_access::read_access_file;
 use masq_lib::constants::{DEFAULT_UI_PORT, HTTP_PORT, TLS_PORT};
 use masq_lib::multi_config::{CommandLineVcl, ConfigFileVcl, EnvironmentVcl};
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::time::Duration;
 
 pub struct NodeConfiguratorStandardPrivileged {
     dirs_wrapper: Box<dyn DirsWrapper>,
@@ -220,8 +226,7 @@ pub fn server_initializer_collected_params<'a>(
             Box::new(EnvironmentVcl::new(&app)),
             Box::new(CommandLineVcl::new(commandline_vcl.args())),
         ],
-    )
-    .expect("expexted MultiConfig");
+    )?;
     let specified_vec = extract_values_vcl_fill_multiconfig_vec(
         multiconfig_for_values_extraction,
         initialization_data,
@@ -265,7 +270,7 @@ pub fn privileged_parse_args(
     privileged_config: &mut BootstrapperConfig,
 ) -> Result<(), ConfiguratorError> {
     let (real_user, data_directory_path, chain) =
-        real_user_data_directory_path_and_chain(dirs_wrapper, multi_config);
+        real_user_data_directory_path_and_chain(dirs_wrapper, multi_config)?;
     let directory = match data_directory_path {
         Some(data_directory_path) => data_directory_path,
         None => data_directory_from_context(dirs_wrapper, &real_user, chain),
@@ -290,13 +295,42 @@ pub fn privileged_parse_args(
 
     privileged_config.log_level =
         value_m!(multi_config, "log-level", LevelFilter).unwrap_or(LevelFilter::Warn);
+    privileged_config.log_redaction_levels = match value_m!(multi_config, "log-redaction", String) {
+        Some(levels) if levels.to_lowercase() == "all" => vec![
+            Level::Error,
+            Level::Warn,
+            Level::Info,
+            Level::Debug,
+            Level::Trace,
+        ],
+        Some(levels) => levels
+            .split(',')
+            .map(|level| {
+                Level::from_str(level.trim()).expect("Bad clap validation for log-redaction")
+            })
+            .collect(),
+        None => vec![],
+    };
 
     privileged_config.ui_gateway_config.ui_port =
         value_m!(multi_config, "ui-port", u16).unwrap_or(DEFAULT_UI_PORT);
+    privileged_config.ui_gateway_config.remote_ui_opt =
+        remote_ui_config(multi_config, &privileged_config.data_directory)?;
 
     privileged_config.crash_point =
         value_m!(multi_config, "crash-point", CrashPoint).unwrap_or(CrashPoint::None);
 
+    privileged_config.clandestine_masquerader =
+        value_m!(multi_config, "masquerader", MasqueraderKind).unwrap_or(MasqueraderKind::Json);
+
+    privileged_config.route_lifetime = value_m!(multi_config, "route-lifetime", u64)
+        .map(|minutes| Duration::from_secs(minutes * 60))
+        .unwrap_or(DEFAULT_ROUTE_LIFETIME);
+
+    privileged_config.clandestine_tls = value_m!(multi_config, "clandestine-tls", String)
+        .unwrap_or_else(|| "off".to_string())
+        == *"on";
+
     if let Some(public_key_str) = value_m!(multi_config, "fake-public-key", String) {
         let (main_public_key, alias_public_key) = match base64::decode(&public_key_str) {
             Ok(mut key) => {
@@ -321,6 +355,64 @@ pub fn privileged_parse_args(
     Ok(())
 }
 
+fn remote_ui_config(
+    multi_config: &MultiConfig,
+    data_directory: &Path,
+) -> Result<Option<RemoteUiConfig>, ConfiguratorError> {
+    let access_file_opt = value_m!(multi_config, "ui-access-file", PathBuf);
+    let tls_cert_opt = value_m!(multi_config, "ui-tls-cert", PathBuf);
+    let tls_key_opt = value_m!(multi_config, "ui-tls-key", PathBuf);
+    let port = match value_m!(multi_config, "ui-remote-port", u16) {
+        Some(port) => port,
+        None => {
+            let orphans = [
+                ("ui-access-file", access_file_opt.is_some()),
+                ("ui-tls-cert", tls_cert_opt.is_some()),
+                ("ui-tls-key", tls_key_opt.is_some()),
+            ];
+            return match orphans.iter().find(|(_, present)| *present) {
+                Some((name, _)) => Err(ConfiguratorError::required(
+                    name,
+                    "Means nothing without --ui-remote-port",
+                )),
+                None => Ok(None),
+            };
+        }
+    };
+    match (&tls_cert_opt, &tls_key_opt) {
+        (Some(_), None) => {
+            return Err(ConfiguratorError::required(
+                "ui-tls-key",
+                "Must be supplied along with --ui-tls-cert",
+            ))
+        }
+        (None, Some(_)) => {
+            return Err(ConfiguratorError::required(
+                "ui-tls-cert",
+                "Must be supplied along with --ui-tls-key",
+            ))
+        }
+        _ => (),
+    }
+    let grants = match access_file_opt {
+        Some(access_file) => read_access_file(&access_file)
+            .map_err(|e| ConfiguratorError::required("ui-access-file", &e))?,
+        None => {
+            return Err(ConfiguratorError::required(
+                "ui-access-file",
+                "Required with --ui-remote-port, to say which tokens may connect",
+            ))
+        }
+    };
+    Ok(Some(RemoteUiConfig {
+        port,
+        tls_cert_opt,
+        tls_key_opt,
+        data_directory: data_directory.to_path_buf(),
+        grants,
+    }))
+}
+
 fn configure_database(
     config: &BootstrapperConfig,
     persistent_config: &mut dyn PersistentConfiguration,
@@ -370,6 +462,7 @@ mod tests {
     use crate::sub_lib::neighborhood::{
         Hops, NeighborhoodConfig, NeighborhoodMode, NodeDescriptor,
     };
+    use crate::sub_lib::ui_gateway::{UiAccessGrant, UiPermissions};
     use crate::sub_lib::wallet::Wallet;
     use crate::test_utils::persistent_configuration_mock::PersistentConfigurationMock;
     use crate::test_utils::unshared_test_utils::{
@@ -386,7 +479,7 @@ mod tests {
     use rustc_hex::FromHex;
     use std::convert::TryFrom;
     use std::env::current_dir;
-    use std::fs::{canonicalize, create_dir_all, File};
+    use std::fs::{self, canonicalize, create_dir_all, File};
     use std::io::Write;
     use std::path::{Path, PathBuf};
     use std::sync::{Arc, Mutex};
@@ -652,6 +745,7 @@ mod tests {
             .param("--data-directory", home_dir.to_str().unwrap())
             .param("--blockchain-service-url", "http://127.0.0.1:8545")
             .param("--log-level", "trace")
+            .param("--log-redaction", "Warn,info")
             .param("--fake-public-key", "AQIDBA")
             .param("--db-password", "secret-db-password")
             .param(
@@ -663,6 +757,7 @@ mod tests {
                 "ABCDEF01ABCDEF01ABCDEF01ABCDEF01ABCDEF01ABCDEF01ABCDEF01ABCDEF01",
             )
             .param("--real-user", "999:999:/home/booga")
+            .param("--route-lifetime", "15")
             .param("--chain", "polygon-amoy");
         let mut config = BootstrapperConfig::new();
         let vcls: Vec<Box<dyn VirtualCommandLine>> =
@@ -683,6 +778,8 @@ mod tests {
             ),
         );
         assert_eq!(config.ui_gateway_config.ui_port, 5335);
+        assert_eq!(config.log_redaction_levels, vec![Level::Warn, Level::Info]);
+        assert_eq!(config.route_lifetime, Duration::from_secs(900));
         assert_eq!(
             config.neighborhood_config,
             NeighborhoodConfig {
@@ -723,6 +820,8 @@ mod tests {
         );
         assert_eq!(config.crash_point, CrashPoint::None);
         assert_eq!(config.ui_gateway_config.ui_port, DEFAULT_UI_PORT);
+        assert_eq!(config.log_redaction_levels, Vec::<Level>::new());
+        assert_eq!(config.route_lifetime, DEFAULT_ROUTE_LIFETIME);
         assert!(config.main_cryptde_null_opt.is_none());
         assert_eq!(
             config.real_user,
@@ -730,6 +829,149 @@ mod tests {
         );
     }
 
+    #[test]
+    fn privileged_parse_args_redacts_every_level_for_all() {
+        running_test();
+        let args = ArgsBuilder::new()
+            .param("--ip", "1.2.3.4")
+            .param("--log-redaction", "ALL");
+        let mut config = BootstrapperConfig::new();
+        let vcls: Vec<Box<dyn VirtualCommandLine>> =
+            vec![Box::new(CommandLineVcl::new(args.into()))];
+        let multi_config = make_new_multi_config(&app_node(), vcls).unwrap();
+
+        privileged_parse_args(&DirsWrapperReal::default(), &multi_config, &mut config).unwrap();
+
+        assert_eq!(
+            config.log_redaction_levels,
+            vec![
+                Level::Error,
+                Level::Warn,
+                Level::Info,
+                Level::Debug,
+                Level::Trace
+            ]
+        );
+    }
+
+    #[test]
+    fn privileged_parse_args_configures_remote_ui_access() {
+        running_test();
+        let home_dir = ensure_node_home_directory_exists(
+            "node_configurator_standard",
+            "privileged_parse_args_configures_remote_ui_access",
+        );
+        let access_file = home_dir.join("ui_access.toml");
+        fs::write(
+            &access_file,
+            "[[token]]\ntoken = \"let-me-in\"\naccess = \"admin\"\n",
+        )
+        .unwrap();
+        let args = ArgsBuilder::new()
+            .param("--ip", "1.2.3.4")
+            .param("--data-directory", home_dir.to_str().unwrap())
+            .param("--ui-remote-port", "5336")
+            .param("--ui-access-file", access_file.to_str().unwrap())
+            .param("--ui-tls-cert", "/certs/ui.pem")
+            .param("--ui-tls-key", "/certs/ui.key");
+        let mut config = BootstrapperConfig::new();
+        let vcls: Vec<Box<dyn VirtualCommandLine>> =
+            vec![Box::new(CommandLineVcl::new(args.into()))];
+        let multi_config = make_new_multi_config(&app_node(), vcls).unwrap();
+
+        privileged_parse_args(&DirsWrapperReal::default(), &multi_config, &mut config).unwrap();
+
+        assert_eq!(
+            config.ui_gateway_config.remote_ui_opt,
+            Some(RemoteUiConfig {
+                port: 5336,
+                tls_cert_opt: Some(PathBuf::from("/certs/ui.pem")),
+                tls_key_opt: Some(PathBuf::from("/certs/ui.key")),
+                data_directory: home_dir,
+                grants: vec![UiAccessGrant {
+                    token: "let-me-in".to_string(),
+                    permissions: UiPermissions::Admin,
+                }],
+            })
+        );
+    }
+
+    #[test]
+    fn privileged_parse_args_leaves_remote_ui_off_by_default() {
+        running_test();
+        let args = ArgsBuilder::new().param("--ip", "1.2.3.4");
+        let mut config = BootstrapperConfig::new();
+        let vcls: Vec<Box<dyn VirtualCommandLine>> =
+            vec![Box::new(CommandLineVcl::new(args.into()))];
+        let multi_config = make_new_multi_config(&app_node(), vcls).unwrap();
+
+        privileged_parse_args(&DirsWrapperReal::default(), &multi_config, &mut config).unwrap();
+
+        assert_eq!(config.ui_gateway_config.remote_ui_opt, None);
+    }
+
+    #[test]
+    fn privileged_parse_args_rejects_incomplete_remote_ui_configuration() {
+        running_test();
+        let home_dir = ensure_node_home_directory_exists(
+            "node_configurator_standard",
+            "privileged_parse_args_rejects_incomplete_remote_ui_configuration",
+        );
+        let access_file = home_dir.join("ui_access.toml");
+        fs::write(
+            &access_file,
+            "[[token]]\ntoken = \"\"\naccess = \"admin\"\n",
+        )
+        .unwrap();
+        let access_file = access_file.to_str().unwrap();
+        let check = |params: Vec<(&str, &str)>, expected: ConfiguratorError| {
+            let args = params.into_iter().fold(
+                ArgsBuilder::new().param("--ip", "1.2.3.4"),
+                |args, (name, value)| args.param(name, value),
+            );
+            let vcls: Vec<Box<dyn VirtualCommandLine>> =
+                vec![Box::new(CommandLineVcl::new(args.into()))];
+            let multi_config = make_new_multi_config(&app_node(), vcls).unwrap();
+
+            let result = privileged_parse_args(
+                &DirsWrapperReal::default(),
+                &multi_config,
+                &mut BootstrapperConfig::new(),
+            );
+
+            assert_eq!(result, Err(expected));
+        };
+
+        check(
+            vec![("--ui-remote-port", "5336")],
+            ConfiguratorError::required(
+                "ui-access-file",
+                "Required with --ui-remote-port, to say which tokens may connect",
+            ),
+        );
+        check(
+            vec![("--ui-tls-key", "/certs/ui.key")],
+            ConfiguratorError::required("ui-tls-key", "Means nothing without --ui-remote-port"),
+        );
+        check(
+            vec![
+                ("--ui-remote-port", "5336"),
+                ("--ui-tls-cert", "/certs/ui.pem"),
+            ],
+            ConfiguratorError::required("ui-tls-key", "Must be supplied along with --ui-tls-cert"),
+        );
+        check(
+            vec![
+                ("--ui-remote-port", "5336"),
+                ("--ui-access-file", access_file),
+            ],
+            ConfiguratorError::required(
+                "ui-access-file",
+                &format!("{}: Token #1 is blank", access_file),
+            ),
+        );
+    }
+
     #[test]
     #[cfg(not(target_os = "windows"))]
     fn privileged_parse_args_with_real_user_defaults_data_directory_properly() {
@@ -810,6 +1052,58 @@ mod tests {
         assert_eq!(config.crash_point, CrashPoint::Panic);
     }
 
+    #[test]
+    fn no_parameters_produces_configuration_for_masquerader() {
+        running_test();
+        let args = make_default_cli_params();
+        let mut config = BootstrapperConfig::new();
+        let vcl = Box::new(CommandLineVcl::new(args.into()));
+        let multi_config = make_new_multi_config(&app_node(), vec![vcl]).unwrap();
+
+        privileged_parse_args(&DirsWrapperReal::default(), &multi_config, &mut config).unwrap();
+
+        assert_eq!(config.clandestine_masquerader, MasqueraderKind::Json);
+    }
+
+    #[test]
+    fn with_parameters_produces_configuration_for_masquerader() {
+        running_test();
+        let args = make_default_cli_params().param("--masquerader", "WebSocket");
+        let mut config = BootstrapperConfig::new();
+        let vcl = Box::new(CommandLineVcl::new(args.into()));
+        let multi_config = make_new_multi_config(&app_node(), vec![vcl]).unwrap();
+
+        privileged_parse_args(&DirsWrapperReal::default(), &multi_config, &mut config).unwrap();
+
+        assert_eq!(config.clandestine_masquerader, MasqueraderKind::WebSocket);
+    }
+
+    #[test]
+    fn no_parameters_produces_configuration_without_clandestine_tls() {
+        running_test();
+        let args = make_default_cli_params();
+        let mut config = BootstrapperConfig::new();
+        let vcl = Box::new(CommandLineVcl::new(args.into()));
+        let multi_config = make_new_multi_config(&app_node(), vec![vcl]).unwrap();
+
+        privileged_parse_args(&DirsWrapperReal::default(), &multi_config, &mut config).unwrap();
+
+        assert_eq!(config.clandestine_tls, false);
+    }
+
+    #[test]
+    fn with_parameters_produces_configuration_for_clandestine_tls() {
+        running_test();
+        let args = make_default_cli_params().param("--clandestine-tls", "on");
+        let mut config = BootstrapperConfig::new();
+        let vcl = Box::new(CommandLineVcl::new(args.into()));
+        let multi_config = make_new_multi_config(&app_node(), vec![vcl]).unwrap();
+
+        privileged_parse_args(&DirsWrapperReal::default(), &multi_config, &mut config).unwrap();
+
+        assert_eq!(config.clandestine_tls, true);
+    }
+
     fn fill_up_config_file(mut config_file: File) {
         {
             config_file
@@ -2,9 +2,14 @@
 
 use crate::accountant::DEFAULT_PENDING_TOO_LONG_SEC;
 use crate::blockchain::bip32::Bip32EncryptionKeyProvider;
+use crate::blockchain::external_signer::ExternalSignerEndpoint;
+use crate::blockchain::keystore::Keystore;
 use crate::bootstrapper::BootstrapperConfig;
 use crate::db_config::persistent_configuration::{PersistentConfigError, PersistentConfiguration};
-use crate::sub_lib::accountant::{PaymentThresholds, ScanIntervals, DEFAULT_EARNING_WALLET};
+use crate::sub_lib::accountant::{
+    FeeBumping, PaymentThresholds, ScanIntervals, SpendingBudget, DEFAULT_EARNING_WALLET,
+};
+use crate::sub_lib::bandwidth_shaper::{BandwidthLimits, BandwidthShaper, DataQuota};
 use crate::sub_lib::cryptde::CryptDE;
 use crate::sub_lib::cryptde_null::CryptDENull;
 use crate::sub_lib::cryptde_real::CryptDEReal;
@@ -21,9 +26,11 @@ use masq_lib::logger::Logger;
 use masq_lib::multi_config::MultiConfig;
 use masq_lib::shared_schema::{ConfiguratorError, ParamError};
 use masq_lib::utils::{to_string, AutomapProtocol, ExpectValue};
-use rustc_hex::FromHex;
+use rustc_hex::{FromHex, ToHex};
+use std::fs;
 use std::net::{IpAddr, Ipv4Addr};
 use std::str::FromStr;
+use std::time::Duration;
 
 pub trait UnprivilegedParseArgsConfiguration {
     // Only initialization that cannot be done with privilege should happen here.
@@ -58,6 +65,7 @@ pub trait UnprivilegedParseArgsConfiguration {
             };
         unprivileged_config.db_password_opt = value_m!(multi_config, "db-password", String);
         configure_accountant_config(multi_config, unprivileged_config, persistent_config)?;
+        configure_bandwidth_shaper(multi_config, unprivileged_config, persistent_config)?;
         unprivileged_config.mapping_protocol_opt =
             compute_mapping_protocol_opt(multi_config, persistent_config, logger);
         let mnc_result = {
@@ -124,7 +132,19 @@ pub fn get_wallets(
     persistent_config: &mut dyn PersistentConfiguration,
     config: &mut BootstrapperConfig,
 ) -> Result<(), ConfiguratorError> {
-    let mc_consuming_opt = value_m!(multi_config, "consuming-private-key", String);
+    let mc_consuming_opt = match (
+        value_m!(multi_config, "consuming-private-key", String),
+        consuming_private_key_from_keystore(multi_config)?,
+    ) {
+        (Some(_), Some(_)) => {
+            return Err(ConfiguratorError::required(
+                "consuming-keystore",
+                "Cannot be combined with consuming-private-key",
+            ))
+        }
+        (Some(private_key), None) => Some(private_key),
+        (None, keystore_key_opt) => keystore_key_opt,
+    };
     let mc_earning_opt = value_m!(multi_config, "earning-wallet", String);
     let pc_consuming_opt = if let Some(db_password) = &config.db_password_opt {
         match persistent_config.consuming_wallet_private_key(db_password.as_str()) {
@@ -139,6 +159,25 @@ pub fn get_wallets(
         Ok(peo) => peo,
         Err(e) => return Err(e.into_configurator_error("earning-wallet")),
     };
+    if let Some((endpoint, account)) = external_signer_from_args(multi_config)? {
+        if mc_consuming_opt.is_some() {
+            return Err(ConfiguratorError::required(
+                "external-signer",
+                "Cannot be combined with a consuming wallet private key or keystore",
+            ));
+        }
+        if pc_consuming_opt.is_some() {
+            return Err(ConfiguratorError::required(
+                "external-signer",
+                "Cannot be used while the database holds a consuming wallet private key",
+            ));
+        }
+        config.blockchain_bridge_config.external_signer_opt = Some(endpoint);
+        config.consuming_wallet_opt = Some(account);
+        config.earning_wallet = choose_earning_wallet(mc_earning_opt, pc_earning_opt)?
+            .unwrap_or_else(|| DEFAULT_EARNING_WALLET.clone());
+        return Ok(());
+    }
     let consuming_opt = match (&mc_consuming_opt, &pc_consuming_opt) {
         (None, _) => pc_consuming_opt,
         (Some(_), None) => mc_consuming_opt,
@@ -150,20 +189,6 @@ pub fn get_wallets(
             ))
         }
     };
-    let earning_opt = match (&mc_earning_opt, &pc_earning_opt) {
-        (None, _) => pc_earning_opt,
-        (Some(_), None) => mc_earning_opt,
-        (Some(m), Some(c)) if wallet_params_are_equal(m, c) => pc_earning_opt,
-        (Some(m), Some(c)) => {
-            return Err(ConfiguratorError::required(
-                "earning-wallet",
-                &format!(
-                    "Cannot change to an address ({}) different from that previously set ({})",
-                    m, c
-                ),
-            ))
-        }
-    };
     let consuming_wallet_opt = consuming_opt.map(|consuming_private_key| {
         let key_bytes = consuming_private_key
             .from_hex::<Vec<u8>>()
@@ -182,17 +207,101 @@ pub fn get_wallets(
             });
         Wallet::from(key_pair)
     });
-    let earning_wallet_opt = earning_opt.map(|earning_address| {
+    config.consuming_wallet_opt = consuming_wallet_opt;
+    config.earning_wallet = choose_earning_wallet(mc_earning_opt, pc_earning_opt)?
+        .unwrap_or_else(|| DEFAULT_EARNING_WALLET.clone());
+    Ok(())
+}
+
+fn choose_earning_wallet(
+    mc_earning_opt: Option<String>,
+    pc_earning_opt: Option<String>,
+) -> Result<Option<Wallet>, ConfiguratorError> {
+    let earning_opt = match (&mc_earning_opt, &pc_earning_opt) {
+        (None, _) => pc_earning_opt,
+        (Some(_), None) => mc_earning_opt,
+        (Some(m), Some(c)) if wallet_params_are_equal(m, c) => pc_earning_opt,
+        (Some(m), Some(c)) => {
+            return Err(ConfiguratorError::required(
+                "earning-wallet",
+                &format!(
+                    "Cannot change to an address ({}) different from that previously set ({})",
+                    m, c
+                ),
+            ))
+        }
+    };
+    Ok(earning_opt.map(|earning_address| {
         Wallet::from_str(&earning_address).unwrap_or_else(|_| {
             panic!(
                 "Wallet corruption: bad value for earning wallet address: {}",
                 earning_address
             )
         })
-    });
-    config.consuming_wallet_opt = consuming_wallet_opt;
-    config.earning_wallet = earning_wallet_opt.unwrap_or_else(|| DEFAULT_EARNING_WALLET.clone());
-    Ok(())
+    }))
+}
+
+fn external_signer_from_args(
+    multi_config: &MultiConfig,
+) -> Result<Option<(ExternalSignerEndpoint, Wallet)>, ConfiguratorError> {
+    let timeout_secs_opt = value_m!(multi_config, "external-signer-timeout", u64);
+    match (
+        value_m!(multi_config, "external-signer", String),
+        value_m!(multi_config, "external-signer-account", String),
+    ) {
+        (None, None) if timeout_secs_opt.is_some() => Err(ConfiguratorError::required(
+            "external-signer",
+            "Required to make use of the external-signer-timeout",
+        )),
+        (None, None) => Ok(None),
+        (Some(_), None) => Err(ConfiguratorError::required(
+            "external-signer-account",
+            "Required to identify the consuming wallet whose key the external-signer holds",
+        )),
+        (None, Some(_)) => Err(ConfiguratorError::required(
+            "external-signer",
+            "Required to sign for the external-signer-account",
+        )),
+        (Some(url), Some(account)) => {
+            let mut endpoint = ExternalSignerEndpoint::from_str(&url)
+                .map_err(|msg| ConfiguratorError::required("external-signer", &msg))?;
+            if let Some(timeout_secs) = timeout_secs_opt {
+                endpoint = endpoint
+                    .with_read_timeout(Duration::from_secs(timeout_secs))
+                    .map_err(|msg| ConfiguratorError::required("external-signer-timeout", &msg))?;
+            }
+            let account = Wallet::from_str(&account)
+                .expect("external-signer-account not properly validated by clap");
+            Ok(Some((endpoint, account)))
+        }
+    }
+}
+
+fn consuming_private_key_from_keystore(
+    multi_config: &MultiConfig,
+) -> Result<Option<String>, ConfiguratorError> {
+    let keystore_path = match value_m!(multi_config, "consuming-keystore", String) {
+        Some(path) => path,
+        None => return Ok(None),
+    };
+    let passphrase = match value_m!(multi_config, "consuming-keystore-passphrase", String) {
+        Some(passphrase) => passphrase,
+        None => {
+            return Err(ConfiguratorError::required(
+                "consuming-keystore-passphrase",
+                "Required to unlock the consuming-keystore",
+            ))
+        }
+    };
+    let keystore_json = fs::read_to_string(&keystore_path).map_err(|e| {
+        ConfiguratorError::required(
+            "consuming-keystore",
+            &format!("Could not read '{}': {}", keystore_path, e),
+        )
+    })?;
+    let key_pair = Keystore::decrypt(&keystore_json, &passphrase)
+        .map_err(|e| ConfiguratorError::required("consuming-keystore", &e.to_string()))?;
+    Ok(Some(key_pair.clone_secret().to_hex::<String>()))
 }
 
 fn wallet_params_are_equal(a: &str, b: &str) -> bool {
@@ -504,17 +613,43 @@ fn configure_accountant_config(
         |pc: &dyn PersistentConfiguration| pc.scan_intervals(),
         |pc: &mut dyn PersistentConfiguration, intervals| pc.set_scan_intervals(intervals),
     )?;
+    let spending_budget = process_combined_params(
+        "spending-budget",
+        multi_config,
+        persist_config,
+        |str: &str| SpendingBudget::try_from(str),
+        |pc: &dyn PersistentConfiguration| pc.spending_budget(),
+        |pc: &mut dyn PersistentConfiguration, budget| pc.set_spending_budget(budget),
+    )?;
     let suppress_initial_scans =
         value_m!(multi_config, "scans", String).unwrap_or_else(|| "on".to_string()) == *"off";
 
     config.payment_thresholds_opt = Some(payment_thresholds);
     config.scan_intervals_opt = Some(scan_intervals);
+    config.spending_budget = spending_budget;
     config.suppress_initial_scans = suppress_initial_scans;
     config.when_pending_too_long_sec = DEFAULT_PENDING_TOO_LONG_SEC;
+    config.fee_bumping = match value_m!(multi_config, "fee-bumping", String) {
+        Some(fee_bumping_str) => FeeBumping::try_from(fee_bumping_str.as_str())
+            .map_err(|e| ConfiguratorError::required("fee-bumping", &e))?,
+        None => FeeBumping::default(),
+    };
+    check_fee_bumping(&config.fee_bumping)?;
     Ok(())
 }
 
-fn check_payment_thresholds(
+fn check_fee_bumping(fee_bumping: &FeeBumping) -> Result<(), ConfiguratorError> {
+    if fee_bumping.gas_price_increase_percent < 10 {
+        let msg = format!(
+            "Value of GasPriceIncreasePercent ({}) must be at least 10",
+            fee_bumping.gas_price_increase_percent
+        );
+        return Err(ConfiguratorError::required("fee-bumping", &msg));
+    }
+    Ok(())
+}
+
+pub fn check_payment_thresholds(
     payment_thresholds: &PaymentThresholds,
 ) -> Result<(), ConfiguratorError> {
     if payment_thresholds.debt_threshold_gwei <= payment_thresholds.permanent_debt_allowed_gwei {
@@ -533,6 +668,32 @@ fn check_payment_thresholds(
     Ok(())
 }
 
+fn configure_bandwidth_shaper(
+    multi_config: &MultiConfig,
+    config: &mut BootstrapperConfig,
+    persist_config: &mut dyn PersistentConfiguration,
+) -> Result<(), ConfiguratorError> {
+    let bandwidth_limits = process_combined_params(
+        "bandwidth-limits",
+        multi_config,
+        persist_config,
+        |str: &str| BandwidthLimits::try_from(str),
+        |pc: &dyn PersistentConfiguration| pc.bandwidth_limits(),
+        |pc: &mut dyn PersistentConfiguration, limits| pc.set_bandwidth_limits(limits),
+    )?;
+    let data_quota = process_combined_params(
+        "data-quota",
+        multi_config,
+        persist_config,
+        |str: &str| DataQuota::try_from(str),
+        |pc: &dyn PersistentConfiguration| pc.data_quota(),
+        |pc: &mut dyn PersistentConfiguration, quota| pc.set_data_quota(quota),
+    )?;
+
+    config.bandwidth_shaper = BandwidthShaper::new(bandwidth_limits, data_quota);
+    Ok(())
+}
+
 fn configure_rate_pack(
     multi_config: &MultiConfig,
     persist_config: &mut dyn PersistentConfiguration,
@@ -624,12 +785,14 @@ mod tests {
     use crate::accountant::db_access_objects::utils::ThresholdUtils;
     use crate::apps::app_node;
     use crate::blockchain::bip32::Bip32EncryptionKeyProvider;
+    use crate::blockchain::external_signer::DEFAULT_IPC_READ_TIMEOUT_SECS;
     use crate::database::db_initializer::DbInitializationConfig;
     use crate::database::db_initializer::{DbInitializer, DbInitializerReal};
     use crate::db_config::config_dao::{ConfigDao, ConfigDaoReal};
     use crate::db_config::persistent_configuration::PersistentConfigError::NotPresent;
     use crate::db_config::persistent_configuration::PersistentConfigurationReal;
     use crate::sub_lib::accountant::DEFAULT_PAYMENT_THRESHOLDS;
+    use crate::sub_lib::bandwidth_shaper::{DEFAULT_BANDWIDTH_LIMITS, DEFAULT_DATA_QUOTA};
     use crate::sub_lib::cryptde::{PlainData, PublicKey};
     use crate::sub_lib::neighborhood::{Hops, DEFAULT_RATE_PACK};
     use crate::sub_lib::utils::make_new_multi_config;
@@ -1808,12 +1971,72 @@ mod tests {
         );
     }
 
+    #[test]
+    fn unprivileged_parse_args_configures_bandwidth_shaper_with_values_from_command_line() {
+        running_test();
+        let set_bandwidth_limits_params_arc = Arc::new(Mutex::new(vec![]));
+        let set_data_quota_params_arc = Arc::new(Mutex::new(vec![]));
+        let args = [
+            "--ip",
+            "1.2.3.4",
+            "--bandwidth-limits",
+            "500|400|300|200|100",
+            "--data-quota",
+            "1000|25000",
+        ];
+        let mut config = BootstrapperConfig::new();
+        let multi_config = make_simplified_multi_config(args);
+        let mut persistent_configuration = configure_default_persistent_config(
+            ACCOUNTANT_CONFIG_PARAMS | RATE_PACK | MAPPING_PROTOCOL,
+        )
+        .set_bandwidth_limits_params(&set_bandwidth_limits_params_arc)
+        .set_bandwidth_limits_result(Ok(()))
+        .set_data_quota_params(&set_data_quota_params_arc)
+        .set_data_quota_result(Ok(()));
+        let subject = UnprivilegedParseArgsConfigurationDaoReal {};
+
+        subject
+            .unprivileged_parse_args(
+                &multi_config,
+                &mut config,
+                &mut persistent_configuration,
+                &Logger::new("test"),
+            )
+            .unwrap();
+
+        assert_eq!(
+            config.bandwidth_shaper.limits(),
+            BandwidthLimits {
+                upload_kb_per_sec: 500,
+                download_kb_per_sec: 400,
+                relay_kb_per_sec: 300,
+                exit_kb_per_sec: 200,
+                per_neighbor_kb_per_sec: 100,
+            }
+        );
+        assert_eq!(
+            config.bandwidth_shaper.data_quota(),
+            DataQuota {
+                daily_mb: 1000,
+                monthly_mb: 25000,
+            }
+        );
+        let set_bandwidth_limits_params = set_bandwidth_limits_params_arc.lock().unwrap();
+        assert_eq!(
+            *set_bandwidth_limits_params,
+            vec!["500|400|300|200|100".to_string()]
+        );
+        let set_data_quota_params = set_data_quota_params_arc.lock().unwrap();
+        assert_eq!(*set_data_quota_params, vec!["1000|25000".to_string()]);
+    }
+
     #[test]
     fn unprivileged_parse_args_accountant_config_with_combined_params_from_command_line_different_from_database(
     ) {
         running_test();
         let set_scan_intervals_params_arc = Arc::new(Mutex::new(vec![]));
         let set_payment_thresholds_params_arc = Arc::new(Mutex::new(vec![]));
+        let set_spending_budget_params_arc = Arc::new(Mutex::new(vec![]));
         let args = [
             "--ip",
             "1.2.3.4",
@@ -1821,6 +2044,8 @@ mod tests {
             "180|150|130",
             "--payment-thresholds",
             "100000|10000|1000|20000|1000|20000",
+            "--spending-budget",
+            "3000000|50000000|60|90",
         ];
         let mut config = BootstrapperConfig::new();
         let multi_config = make_simplified_multi_config(args);
@@ -1841,8 +2066,11 @@ mod tests {
                 }))
                 .set_scan_intervals_params(&set_scan_intervals_params_arc)
                 .set_scan_intervals_result(Ok(()))
+                .spending_budget_result(Ok(SpendingBudget::default()))
                 .set_payment_thresholds_params(&set_payment_thresholds_params_arc)
-                .set_payment_thresholds_result(Ok(()));
+                .set_payment_thresholds_result(Ok(()))
+                .set_spending_budget_params(&set_spending_budget_params_arc)
+                .set_spending_budget_result(Ok(()));
         let subject = UnprivilegedParseArgsConfigurationDaoReal {};
 
         subject
@@ -1872,6 +2100,15 @@ mod tests {
             Some(expected_payment_thresholds)
         );
         assert_eq!(config.scan_intervals_opt, Some(expected_scan_intervals));
+        assert_eq!(
+            config.spending_budget,
+            SpendingBudget {
+                daily_gwei: 3_000_000,
+                monthly_gwei: 50_000_000,
+                first_warning_percent: 60,
+                second_warning_percent: 90,
+            }
+        );
         assert_eq!(config.suppress_initial_scans, false);
         assert_eq!(
             config.when_pending_too_long_sec,
@@ -1883,6 +2120,11 @@ mod tests {
         assert_eq!(
             *set_payment_thresholds_params,
             vec!["100000|10000|1000|20000|1000|20000".to_string()]
+        );
+        let set_spending_budget_params = set_spending_budget_params_arc.lock().unwrap();
+        assert_eq!(
+            *set_spending_budget_params,
+            vec!["3000000|50000000|60|90".to_string()]
         )
     }
 
@@ -1914,7 +2156,8 @@ mod tests {
                     maturity_threshold_sec: 1000,
                     permanent_debt_allowed_gwei: 20000,
                     unban_below_gwei: 20000,
-                }));
+                }))
+                .spending_budget_result(Ok(SpendingBudget::default()));
         let subject = UnprivilegedParseArgsConfigurationDaoReal {};
 
         subject
@@ -2185,6 +2428,54 @@ mod tests {
         assert_eq!(last_value_possible, -1)
     }
 
+    #[test]
+    fn unprivileged_parse_args_configures_fee_bumping_from_command_line() {
+        running_test();
+        let args = ["--ip", "1.2.3.4", "--fee-bumping", "900|25|5"];
+        let mut config = BootstrapperConfig::new();
+        let multi_config = make_simplified_multi_config(args);
+        let mut persistent_configuration = configure_default_persistent_config(
+            ACCOUNTANT_CONFIG_PARAMS | RATE_PACK | MAPPING_PROTOCOL,
+        );
+        let subject = UnprivilegedParseArgsConfigurationDaoReal {};
+
+        subject
+            .unprivileged_parse_args(
+                &multi_config,
+                &mut config,
+                &mut persistent_configuration,
+                &Logger::new("test"),
+            )
+            .unwrap();
+
+        assert_eq!(
+            config.fee_bumping,
+            FeeBumping {
+                bump_after_sec: 900,
+                gas_price_increase_percent: 25,
+                max_bumps: 5
+            }
+        );
+    }
+
+    #[test]
+    fn check_fee_bumping_rejects_too_small_gas_price_increase() {
+        let mut fee_bumping = FeeBumping::default();
+        fee_bumping.gas_price_increase_percent = 9;
+
+        let result = check_fee_bumping(&fee_bumping);
+
+        assert_eq!(
+            result,
+            Err(ConfiguratorError::required(
+                "fee-bumping",
+                "Value of GasPriceIncreasePercent (9) must be at least 10"
+            ))
+        );
+        fee_bumping.gas_price_increase_percent = 10;
+        assert_eq!(check_fee_bumping(&fee_bumping), Ok(()))
+    }
+
     #[test]
     fn unprivileged_parse_args_with_invalid_consuming_wallet_private_key_reacts_correctly() {
         running_test();
@@ -2414,6 +2705,410 @@ mod tests {
         );
     }
 
+    fn write_consuming_keystore(test_name: &str, private_key_hex: &str) -> String {
+        let home_dir =
+            ensure_node_home_directory_exists("unprivileged_parse_args_configuration", test_name);
+        let key_bytes: Vec<u8> = private_key_hex.from_hex().unwrap();
+        let key_pair = Bip32EncryptionKeyProvider::from_raw_secret(&key_bytes).unwrap();
+        let keystore_json =
+            Keystore::encrypt_with_iterations(&key_pair, "Open Sesame", 1024).unwrap();
+        let keystore_path = home_dir.join("keystore.json");
+        fs::write(&keystore_path, keystore_json).unwrap();
+        keystore_path.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn get_wallets_takes_consuming_wallet_from_keystore() {
+        running_test();
+        let keystore_path = write_consuming_keystore(
+            "get_wallets_takes_consuming_wallet_from_keystore",
+            "cc46befe8d169b89db447bd725fc2368b12542113555302598430cb5d5c74ea9",
+        );
+        let args = [
+            "--consuming-keystore",
+            keystore_path.as_str(),
+            "--consuming-keystore-passphrase",
+            "Open Sesame",
+        ];
+        let multi_config = make_simplified_multi_config(args);
+        let mut persistent_config = make_persistent_config(
+            Some("password"),
+            Some("CC46BEFE8D169B89DB447BD725FC2368B12542113555302598430CB5D5C74EA9"),
+            Some("0x0123456789012345678901234567890123456789"),
+            None,
+            None,
+            None,
+            None,
+        );
+        let mut config = BootstrapperConfig::new();
+        config.db_password_opt = Some("password".to_string());
+
+        get_wallets(&multi_config, &mut persistent_config, &mut config).unwrap();
+
+        assert_eq!(
+            format!("{}", config.consuming_wallet_opt.unwrap()),
+            "0x8e4d2317e56c8fd1fc9f13ba2aa62df1c5a542a7".to_string()
+        );
+    }
+
+    #[test]
+    fn get_wallets_requires_passphrase_for_keystore() {
+        running_test();
+        let keystore_path = write_consuming_keystore(
+            "get_wallets_requires_passphrase_for_keystore",
+            "cc46befe8d169b89db447bd725fc2368b12542113555302598430cb5d5c74ea9",
+        );
+        let args = ["--consuming-keystore", keystore_path.as_str()];
+        let multi_config = make_simplified_multi_config(args);
+        let mut persistent_config =
+            make_persistent_config(None, None, None, None, None, None, None);
+        let mut config = BootstrapperConfig::new();
+
+        let result = get_wallets(&multi_config, &mut persistent_config, &mut config);
+
+        assert_eq!(
+            result,
+            Err(ConfiguratorError::required(
+                "consuming-keystore-passphrase",
+                "Required to unlock the consuming-keystore"
+            ))
+        );
+    }
+
+    #[test]
+    fn get_wallets_complains_about_wrong_keystore_passphrase() {
+        running_test();
+        let keystore_path = write_consuming_keystore(
+            "get_wallets_complains_about_wrong_keystore_passphrase",
+            "cc46befe8d169b89db447bd725fc2368b12542113555302598430cb5d5c74ea9",
+        );
+        let args = [
+            "--consuming-keystore",
+            keystore_path.as_str(),
+            "--consuming-keystore-passphrase",
+            "Close Sesame",
+        ];
+        let multi_config = make_simplified_multi_config(args);
+        let mut persistent_config =
+            make_persistent_config(None, None, None, None, None, None, None);
+        let mut config = BootstrapperConfig::new();
+
+        let result = get_wallets(&multi_config, &mut persistent_config, &mut config);
+
+        assert_eq!(
+            result,
+            Err(ConfiguratorError::required(
+                "consuming-keystore",
+                "Keystore passphrase is incorrect"
+            ))
+        );
+    }
+
+    #[test]
+    fn get_wallets_complains_about_unreadable_keystore() {
+        running_test();
+        let home_dir = ensure_node_home_directory_exists(
+            "unprivileged_parse_args_configuration",
+            "get_wallets_complains_about_unreadable_keystore",
+        );
+        let keystore_path = home_dir.join("nonexistent.json");
+        let keystore_path = keystore_path.to_str().unwrap();
+        let args = [
+            "--consuming-keystore",
+            keystore_path,
+            "--consuming-keystore-passphrase",
+            "Open Sesame",
+        ];
+        let multi_config = make_simplified_multi_config(args);
+        let mut persistent_config =
+            make_persistent_config(None, None, None, None, None, None, None);
+        let mut config = BootstrapperConfig::new();
+
+        let result = get_wallets(&multi_config, &mut persistent_config, &mut config)
+            .err()
+            .unwrap();
+
+        assert_eq!(result.param_errors.len(), 1);
+        assert_eq!(result.param_errors[0].parameter, "consuming-keystore");
+        let expected_start = format!("Could not read '{}': ", keystore_path);
+        assert!(
+            result.param_errors[0].reason.starts_with(&expected_start),
+            "{}",
+            result.param_errors[0].reason
+        );
+    }
+
+    #[test]
+    fn get_wallets_rejects_keystore_combined_with_private_key() {
+        running_test();
+        let keystore_path = write_consuming_keystore(
+            "get_wallets_rejects_keystore_combined_with_private_key",
+            "cc46befe8d169b89db447bd725fc2368b12542113555302598430cb5d5c74ea9",
+        );
+        let args = [
+            "--consuming-private-key",
+            "cc46befe8d169b89db447bd725fc2368b12542113555302598430cb5d5c74ea9",
+            "--consuming-keystore",
+            keystore_path.as_str(),
+            "--consuming-keystore-passphrase",
+            "Open Sesame",
+        ];
+        let multi_config = make_simplified_multi_config(args);
+        let mut persistent_config =
+            make_persistent_config(None, None, None, None, None, None, None);
+        let mut config = BootstrapperConfig::new();
+
+        let result = get_wallets(&multi_config, &mut persistent_config, &mut config);
+
+        assert_eq!(
+            result,
+            Err(ConfiguratorError::required(
+                "consuming-keystore",
+                "Cannot be combined with consuming-private-key"
+            ))
+        );
+    }
+
+    #[test]
+    fn get_wallets_takes_consuming_wallet_address_from_external_signer() {
+        running_test();
+        let args = [
+            "--external-signer",
+            "ipc:///home/booga/.clef/clef.ipc",
+            "--external-signer-account",
+            "0x8e4d2317e56c8fd1fc9f13ba2aa62df1c5a542a7",
+            "--earning-wallet",
+            "0x0123456789012345678901234567890123456789",
+        ];
+        let multi_config = make_simplified_multi_config(args);
+        let mut persistent_config =
+            make_persistent_config(None, None, None, None, None, None, None);
+        let mut config = BootstrapperConfig::new();
+
+        get_wallets(&multi_config, &mut persistent_config, &mut config).unwrap();
+
+        assert_eq!(
+            config.blockchain_bridge_config.external_signer_opt,
+            Some(ExternalSignerEndpoint::Ipc {
+                path: PathBuf::from("/home/booga/.clef/clef.ipc"),
+                read_timeout: Duration::from_secs(DEFAULT_IPC_READ_TIMEOUT_SECS),
+            })
+        );
+        let consuming_wallet = config.consuming_wallet_opt.unwrap();
+        assert_eq!(
+            consuming_wallet,
+            Wallet::from_str("0x8e4d2317e56c8fd1fc9f13ba2aa62df1c5a542a7").unwrap()
+        );
+        assert_eq!(consuming_wallet.prepare_secp256k1_secret().is_err(), true);
+        assert_eq!(
+            config.earning_wallet,
+            Wallet::from_str("0x0123456789012345678901234567890123456789").unwrap()
+        );
+    }
+
+    #[test]
+    fn get_wallets_applies_external_signer_timeout_to_ipc_endpoint() {
+        running_test();
+        let args = [
+            "--external-signer",
+            "ipc:///home/booga/.clef/clef.ipc",
+            "--external-signer-account",
+            "0x8e4d2317e56c8fd1fc9f13ba2aa62df1c5a542a7",
+            "--external-signer-timeout",
+            "45",
+        ];
+        let multi_config = make_simplified_multi_config(args);
+        let mut persistent_config =
+            make_persistent_config(None, None, None, None, None, None, None);
+        let mut config = BootstrapperConfig::new();
+
+        get_wallets(&multi_config, &mut persistent_config, &mut config).unwrap();
+
+        assert_eq!(
+            config.blockchain_bridge_config.external_signer_opt,
+            Some(ExternalSignerEndpoint::Ipc {
+                path: PathBuf::from("/home/booga/.clef/clef.ipc"),
+                read_timeout: Duration::from_secs(45),
+            })
+        );
+    }
+
+    #[test]
+    fn get_wallets_rejects_external_signer_timeout_for_http_endpoint() {
+        running_test();
+        let args = [
+            "--external-signer",
+            "http://localhost:8550",
+            "--external-signer-account",
+            "0x8e4d2317e56c8fd1fc9f13ba2aa62df1c5a542a7",
+            "--external-signer-timeout",
+            "45",
+        ];
+        let multi_config = make_simplified_multi_config(args);
+        let mut persistent_config =
+            make_persistent_config(None, None, None, None, None, None, None);
+        let mut config = BootstrapperConfig::new();
+
+        let result = get_wallets(&multi_config, &mut persistent_config, &mut config);
+
+        assert_eq!(
+            result,
+            Err(ConfiguratorError::required(
+                "external-signer-timeout",
+                "A read timeout applies only to an ipc:// external signer, not 'http://localhost:8550'"
+            ))
+        );
+    }
+
+    #[test]
+    fn get_wallets_requires_external_signer_for_timeout() {
+        running_test();
+        let args = ["--external-signer-timeout", "45"];
+        let multi_config = make_simplified_multi_config(args);
+        let mut persistent_config =
+            make_persistent_config(None, None, None, None, None, None, None);
+        let mut config = BootstrapperConfig::new();
+
+        let result = get_wallets(&multi_config, &mut persistent_config, &mut config);
+
+        assert_eq!(
+            result,
+            Err(ConfiguratorError::required(
+                "external-signer",
+                "Required to make use of the external-signer-timeout"
+            ))
+        );
+    }
+
+    #[test]
+    fn get_wallets_requires_account_for_external_signer() {
+        running_test();
+        let args = ["--external-signer", "http://localhost:8550"];
+        let multi_config = make_simplified_multi_config(args);
+        let mut persistent_config =
+            make_persistent_config(None, None, None, None, None, None, None);
+        let mut config = BootstrapperConfig::new();
+
+        let result = get_wallets(&multi_config, &mut persistent_config, &mut config);
+
+        assert_eq!(
+            result,
+            Err(ConfiguratorError::required(
+                "external-signer-account",
+                "Required to identify the consuming wallet whose key the external-signer holds"
+            ))
+        );
+    }
+
+    #[test]
+    fn get_wallets_requires_external_signer_for_account() {
+        running_test();
+        let args = [
+            "--external-signer-account",
+            "0x8e4d2317e56c8fd1fc9f13ba2aa62df1c5a542a7",
+        ];
+        let multi_config = make_simplified_multi_config(args);
+        let mut persistent_config =
+            make_persistent_config(None, None, None, None, None, None, None);
+        let mut config = BootstrapperConfig::new();
+
+        let result = get_wallets(&multi_config, &mut persistent_config, &mut config);
+
+        assert_eq!(
+            result,
+            Err(ConfiguratorError::required(
+                "external-signer",
+                "Required to sign for the external-signer-account"
+            ))
+        );
+    }
+
+    #[test]
+    fn get_wallets_rejects_unrecognized_external_signer_endpoint() {
+        running_test();
+        let args = [
+            "--external-signer",
+            "ws://localhost:8550",
+            "--external-signer-account",
+            "0x8e4d2317e56c8fd1fc9f13ba2aa62df1c5a542a7",
+        ];
+        let multi_config = make_simplified_multi_config(args);
+        let mut persistent_config =
+            make_persistent_config(None, None, None, None, None, None, None);
+        let mut config = BootstrapperConfig::new();
+
+        let result = get_wallets(&multi_config, &mut persistent_config, &mut config);
+
+        assert_eq!(
+            result,
+            Err(ConfiguratorError::required(
+                "external-signer",
+                "'ws://localhost:8550' is neither an http(s):// URL nor an ipc:// socket path"
+            ))
+        );
+    }
+
+    #[test]
+    fn get_wallets_rejects_external_signer_combined_with_private_key() {
+        running_test();
+        let args = [
+            "--external-signer",
+            "http://localhost:8550",
+            "--external-signer-account",
+            "0x8e4d2317e56c8fd1fc9f13ba2aa62df1c5a542a7",
+            "--consuming-private-key",
+            "cc46befe8d169b89db447bd725fc2368b12542113555302598430cb5d5c74ea9",
+        ];
+        let multi_config = make_simplified_multi_config(args);
+        let mut persistent_config =
+            make_persistent_config(None, None, None, None, None, None, None);
+        let mut config = BootstrapperConfig::new();
+
+        let result = get_wallets(&multi_config, &mut persistent_config, &mut config);
+
+        assert_eq!(
+            result,
+            Err(ConfiguratorError::required(
+                "external-signer",
+                "Cannot be combined with a consuming wallet private key or keystore"
+            ))
+        );
+    }
+
+    #[test]
+    fn get_wallets_rejects_external_signer_while_database_holds_private_key() {
+        running_test();
+        let args = [
+            "--external-signer",
+            "http://localhost:8550",
+            "--external-signer-account",
+            "0x8e4d2317e56c8fd1fc9f13ba2aa62df1c5a542a7",
+        ];
+        let multi_config = make_simplified_multi_config(args);
+        let mut persistent_config = make_persistent_config(
+            Some("password"),
+            Some("CC46BEFE8D169B89DB447BD725FC2368B12542113555302598430CB5D5C74EA9"),
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+        let mut config = BootstrapperConfig::new();
+        config.db_password_opt = Some("password".to_string());
+
+        let result = get_wallets(&multi_config, &mut persistent_config, &mut config);
+
+        assert_eq!(
+            result,
+            Err(ConfiguratorError::required(
+                "external-signer",
+                "Cannot be used while the database holds a consuming wallet private key"
+            ))
+        );
+        assert_eq!(config.blockchain_bridge_config.external_signer_opt, None);
+    }
+
     #[test]
     fn configure_rate_pack_command_line_absent_config_dao_null_so_all_defaults() {
         running_test();
@@ -2658,5 +3353,7 @@ mod tests {
             .mapping_protocol_result(Ok(Some(AutomapProtocol::Pcp)))
             .rate_pack_result(Ok(rate_pack))
             .min_hops_result(Ok(min_hops))
+            .bandwidth_limits_result(Ok(DEFAULT_BANDWIDTH_LIMITS))
+            .data_quota_result(Ok(DEFAULT_DATA_QUOTA))
     }
 }
@@ -8,22 +8,26 @@ mod stream_handler_pool;
 mod stream_reader;
 mod stream_writer;
 
+use crate::proxy_client::resolver_wrapper::ResolverWrapper;
 use crate::proxy_client::resolver_wrapper::ResolverWrapperFactory;
 use crate::proxy_client::resolver_wrapper::ResolverWrapperFactoryReal;
 use crate::proxy_client::stream_handler_pool::StreamHandlerPool;
 use crate::proxy_client::stream_handler_pool::StreamHandlerPoolFactory;
 use crate::proxy_client::stream_handler_pool::StreamHandlerPoolFactoryReal;
 use crate::sub_lib::accountant::ReportExitServiceProvidedMessage;
+use crate::sub_lib::bandwidth_shaper::BandwidthShaper;
 use crate::sub_lib::cryptde::CryptDE;
 use crate::sub_lib::cryptde::PublicKey;
 use crate::sub_lib::hopper::MessageType;
 use crate::sub_lib::hopper::{ExpiredCoresPackage, IncipientCoresPackage};
+use crate::sub_lib::neighborhood::{ConfigChange, ConfigChangeMsg};
 use crate::sub_lib::peer_actors::BindMessage;
 use crate::sub_lib::proxy_client::InboundServerData;
 use crate::sub_lib::proxy_client::ProxyClientConfig;
 use crate::sub_lib::proxy_client::ProxyClientSubs;
-use crate::sub_lib::proxy_client::{ClientResponsePayload_0v1, DnsResolveFailure_0v1};
-use crate::sub_lib::proxy_server::ClientRequestPayload_0v1;
+use crate::sub_lib::proxy_client::{ClientResponsePayload_0v2, DnsResolveFailure_0v1};
+use crate::sub_lib::proxy_server::ClientRequestPayload_0v2;
+use crate::sub_lib::replay_window::{ArrivalCounter, ReplayWindow, REPLAY_WINDOW_CAPACITY};
 use crate::sub_lib::route::Route;
 use crate::sub_lib::sequence_buffer::SequencedPacket;
 use crate::sub_lib::stream_key::StreamKey;
@@ -36,6 +40,7 @@ use actix::Context;
 use actix::Handler;
 use actix::Recipient;
 use masq_lib::logger::Logger;
+use masq_lib::redaction::MarkSensitive;
 use masq_lib::ui_gateway::NodeFromUiMessage;
 use pretty_hex::PrettyHex;
 use std::collections::HashMap;
@@ -60,6 +65,7 @@ pub struct ProxyClient {
     exit_service_rate: u64,
     exit_byte_rate: u64,
     is_decentralized: bool,
+    bandwidth_shaper: BandwidthShaper,
     crashable: bool,
     logger: Logger,
 }
@@ -76,17 +82,7 @@ impl Handler<BindMessage> for ProxyClient {
         ctx.set_mailbox_capacity(NODE_MAILBOX_CAPACITY);
         self.to_hopper = Some(msg.peer_actors.hopper.from_hopper_client);
         self.to_accountant = Some(msg.peer_actors.accountant.report_exit_service_provided);
-        let mut config = ResolverConfig::new();
-        for dns_server_ref in &self.dns_servers {
-            info!(self.logger, "Adding DNS server: {}", dns_server_ref.ip());
-            config.add_name_server(NameServerConfig {
-                socket_addr: *dns_server_ref,
-                protocol: Protocol::Udp,
-                tls_dns_name: None,
-            })
-        }
-        let opts = ResolverOpts::default();
-        let resolver = self.resolver_wrapper_factory.make(config, opts);
+        let resolver = self.make_resolver();
         self.pool = Some(self.stream_handler_pool_factory.make(
             resolver,
             self.cryptde,
@@ -94,28 +90,23 @@ impl Handler<BindMessage> for ProxyClient {
             msg.peer_actors.proxy_client_opt.unwrap(),
             self.exit_service_rate,
             self.exit_byte_rate,
+            self.bandwidth_shaper.clone(),
         ));
     }
 }
 
-impl Handler<ExpiredCoresPackage<ClientRequestPayload_0v1>> for ProxyClient {
+impl Handler<ExpiredCoresPackage<ClientRequestPayload_0v2>> for ProxyClient {
     type Result = ();
 
     fn handle(
         &mut self,
-        msg: ExpiredCoresPackage<ClientRequestPayload_0v1>,
+        msg: ExpiredCoresPackage<ClientRequestPayload_0v2>,
         _ctx: &mut Self::Context,
     ) -> Self::Result {
         let payload = msg.payload;
         let paying_wallet = msg.paying_wallet;
         if paying_wallet.is_some() || !self.is_decentralized {
-            let pool = self.pool.as_mut().expect("StreamHandlerPool unbound");
             let return_route = msg.remaining_route;
-            let latest_stream_context = StreamContext {
-                return_route,
-                payload_destination_key: payload.originator_public_key.clone(),
-                paying_wallet: paying_wallet.clone(),
-            };
             debug!(
                 self.logger,
                 "Received ClientRequestPayload: stream {}, sequence {}, length {}",
@@ -123,9 +114,34 @@ impl Handler<ExpiredCoresPackage<ClientRequestPayload_0v1>> for ProxyClient {
                 payload.sequenced_packet.sequence_number,
                 payload.sequenced_packet.data.len()
             );
-            self.stream_contexts
-                .insert(payload.stream_key, latest_stream_context);
-            pool.process_package(payload, paying_wallet);
+            let stream_context = self
+                .stream_contexts
+                .entry(payload.stream_key)
+                .or_insert_with(|| StreamContext::new(return_route.clone()));
+            let route_changed = stream_context.return_route != return_route;
+            stream_context.return_route = return_route;
+            stream_context.payload_destination_key = payload.originator_public_key.clone();
+            stream_context.paying_wallet = paying_wallet.clone();
+            if let Some(next_expected) = payload.next_expected_sequence_opt {
+                stream_context.responses.acknowledge(next_expected);
+            }
+            let is_new = stream_context
+                .requests
+                .arrive(payload.sequenced_packet.sequence_number);
+            if route_changed && !stream_context.responses.is_empty() {
+                self.replay_responses(payload.stream_key);
+            }
+            if is_new {
+                let pool = self.pool.as_mut().expect("StreamHandlerPool unbound");
+                pool.process_package(payload, paying_wallet);
+            } else {
+                debug!(
+                    self.logger,
+                    "Dropping replayed ClientRequestPayload: stream {}, sequence {}",
+                    payload.stream_key,
+                    payload.sequenced_packet.sequence_number
+                );
+            }
         } else {
             warning!(self.logger, "Refusing to provide exit services for CORES package with {}-byte payload without paying wallet", payload.sequenced_packet.data.len());
         }
@@ -141,14 +157,14 @@ impl Handler<InboundServerData> for ProxyClient {
         let msg_sequence_number = msg.sequence_number;
         let msg_last_data = msg.last_data;
         let msg_stream_key = msg.stream_key;
-        let stream_context = match self.stream_contexts.get(&msg.stream_key) {
+        let stream_context = match self.stream_contexts.get_mut(&msg.stream_key) {
             Some(sc) => sc,
             None => {
                 error!(
                     self.logger,
                     "Received InboundServerData{} from {}: stream {}, sequence {}, length {}; but no such known stream - ignoring\n{}",
                     if msg_last_data {" (last_data)"} else {""},
-                    msg_source,
+                    msg_source.sensitive(),
                     msg_stream_key,
                     msg_sequence_number,
                     msg_data_len,
@@ -157,7 +173,25 @@ impl Handler<InboundServerData> for ProxyClient {
                 return;
             }
         };
-        if self.send_response_to_hopper(msg, stream_context).is_err() {
+        let payload = ClientResponsePayload_0v2 {
+            stream_key: msg.stream_key,
+            sequenced_packet: SequencedPacket {
+                data: msg.data,
+                sequence_number: msg.sequence_number,
+                last_data: msg.last_data,
+                relayed: false,
+            },
+            next_expected_sequence_opt: Some(stream_context.requests.next_expected()),
+        };
+        stream_context
+            .responses
+            .push(msg_sequence_number, payload.clone());
+        let stream_context = self
+            .stream_contexts
+            .get(&msg_stream_key)
+            .expect("Stream context disappeared");
+        if let Err(e) = self.send_response_to_hopper(payload, stream_context) {
+            error!(self.logger, "Could not create CORES package for {}-byte response from {}, seq {}: {} - ignoring", msg_data_len, msg_source.sensitive(), msg_sequence_number, e);
             return;
         };
         self.report_response_exit_to_accountant(stream_context, msg_data_len);
@@ -216,6 +250,14 @@ impl Handler<NodeFromUiMessage> for ProxyClient {
     }
 }
 
+impl Handler<ConfigChangeMsg> for ProxyClient {
+    type Result = ();
+
+    fn handle(&mut self, msg: ConfigChangeMsg, _ctx: &mut Self::Context) -> Self::Result {
+        self.handle_config_change_msg(msg)
+    }
+}
+
 impl ProxyClient {
     pub fn new(config: ProxyClientConfig) -> ProxyClient {
         if config.dns_servers.is_empty() {
@@ -233,6 +275,7 @@ impl ProxyClient {
             exit_service_rate: config.exit_service_rate,
             exit_byte_rate: config.exit_byte_rate,
             is_decentralized: config.is_decentralized,
+            bandwidth_shaper: config.bandwidth_shaper,
             crashable: config.crashable,
             logger: Logger::new("ProxyClient"),
         }
@@ -241,52 +284,68 @@ impl ProxyClient {
     pub fn make_subs_from(addr: &Addr<ProxyClient>) -> ProxyClientSubs {
         ProxyClientSubs {
             bind: recipient!(addr, BindMessage),
-            from_hopper: recipient!(addr, ExpiredCoresPackage<ClientRequestPayload_0v1>),
+            from_hopper: recipient!(addr, ExpiredCoresPackage<ClientRequestPayload_0v2>),
             inbound_server_data: recipient!(addr, InboundServerData),
             dns_resolve_failed: recipient!(addr, DnsResolveFailure_0v1),
             node_from_ui: recipient!(addr, NodeFromUiMessage),
+            config_change_msg_sub: recipient!(addr, ConfigChangeMsg),
+        }
+    }
+
+    fn make_resolver(&self) -> Box<dyn ResolverWrapper> {
+        let mut config = ResolverConfig::new();
+        for dns_server_ref in &self.dns_servers {
+            info!(
+                self.logger,
+                "Adding DNS server: {}",
+                dns_server_ref.ip().sensitive()
+            );
+            config.add_name_server(NameServerConfig {
+                socket_addr: *dns_server_ref,
+                protocol: Protocol::Udp,
+                tls_dns_name: None,
+            })
+        }
+        let opts = ResolverOpts::default();
+        self.resolver_wrapper_factory.make(config, opts)
+    }
+
+    fn handle_config_change_msg(&mut self, msg: ConfigChangeMsg) {
+        match msg.change {
+            ConfigChange::UpdateDnsServers(dns_servers) => {
+                self.dns_servers = dns_servers;
+                let resolver = self.make_resolver();
+                self.pool
+                    .as_ref()
+                    .expect("StreamHandlerPool unbound")
+                    .update_resolver(resolver);
+                info!(self.logger, "DNS servers have been updated");
+            }
+            _ => trace!(self.logger, "Ignored irrelevant message: {:?}", msg),
         }
     }
 
     fn send_response_to_hopper(
         &self,
-        msg: InboundServerData,
+        payload: ClientResponsePayload_0v2,
         stream_context: &StreamContext,
-    ) -> Result<(), ()> {
-        let msg_stream_key = msg.stream_key;
-        let msg_data_len = msg.data.len() as u32;
-        let msg_source = msg.source;
-        let msg_sequence_number = msg.sequence_number;
-        let payload = MessageType::ClientResponse(VersionedData::new(
-            &crate::sub_lib::migrations::client_response_payload::MIGRATIONS,
-            &ClientResponsePayload_0v1 {
-                stream_key: msg.stream_key,
-                sequenced_packet: SequencedPacket {
-                    data: msg.data,
-                    sequence_number: msg.sequence_number,
-                    last_data: msg.last_data,
-                },
-            },
-        ));
+    ) -> Result<(), String> {
         debug!(
             self.logger,
             "Sending ClientResponsePayload to Hopper: stream {}, sequence {}, length {}",
-            msg_stream_key,
-            msg_sequence_number,
-            msg_data_len
+            payload.stream_key,
+            payload.sequenced_packet.sequence_number,
+            payload.sequenced_packet.data.len()
         );
-        let icp = match IncipientCoresPackage::new(
+        let icp = IncipientCoresPackage::new(
             self.cryptde,
             stream_context.return_route.clone(),
-            payload,
+            MessageType::ClientResponse(VersionedData::new(
+                &crate::sub_lib::migrations::client_response_payload::MIGRATIONS,
+                &payload,
+            )),
             &stream_context.payload_destination_key,
-        ) {
-            Ok(icp) => icp,
-            Err(err) => {
-                error!(self.logger, "Could not create CORES package for {}-byte response from {}, seq {}: {} - ignoring", msg_data_len, msg_source, msg_sequence_number, err);
-                return Err(());
-            }
-        };
+        )?;
         self.to_hopper
             .as_ref()
             .expect("Hopper unbound")
@@ -295,6 +354,35 @@ impl ProxyClient {
         Ok(())
     }
 
+    // The originating Node has moved the stream to a new route: whatever it hasn't acknowledged
+    // may have been lost on the old one.
+    fn replay_responses(&self, stream_key: StreamKey) {
+        let stream_context = self
+            .stream_contexts
+            .get(&stream_key)
+            .expect("Stream context disappeared");
+        let replays = stream_context.responses.unacknowledged();
+        debug!(
+            self.logger,
+            "Route for stream {} changed; replaying {} unacknowledged responses",
+            stream_key,
+            replays.len()
+        );
+        replays.into_iter().for_each(|payload| {
+            let sequence_number = payload.sequenced_packet.sequence_number;
+            let payload = ClientResponsePayload_0v2 {
+                next_expected_sequence_opt: Some(stream_context.requests.next_expected()),
+                ..payload
+            };
+            if let Err(e) = self.send_response_to_hopper(payload, stream_context) {
+                error!(
+                    self.logger,
+                    "Could not replay response {} on stream {}: {}", sequence_number, stream_key, e
+                );
+            }
+        });
+    }
+
     fn report_response_exit_to_accountant(
         &self,
         stream_context: &StreamContext,
@@ -326,6 +414,20 @@ struct StreamContext {
     return_route: Route,
     payload_destination_key: PublicKey,
     paying_wallet: Option<Wallet>,
+    requests: ArrivalCounter,
+    responses: ReplayWindow<ClientResponsePayload_0v2>,
+}
+
+impl StreamContext {
+    fn new(return_route: Route) -> Self {
+        Self {
+            return_route,
+            payload_destination_key: PublicKey::new(&[]),
+            paying_wallet: None,
+            requests: ArrivalCounter::new(),
+            responses: ReplayWindow::new(REPLAY_WINDOW_CAPACITY),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -338,12 +440,13 @@ mod tests {
     use crate::proxy_client::stream_handler_pool::StreamHandlerPool;
     use crate::proxy_client::stream_handler_pool::StreamHandlerPoolFactory;
     use crate::sub_lib::accountant::ReportExitServiceProvidedMessage;
+    use crate::sub_lib::bandwidth_shaper::{BandwidthLimits, DataQuota};
     use crate::sub_lib::cryptde::CryptData;
     use crate::sub_lib::cryptde::PublicKey;
     use crate::sub_lib::dispatcher::Component;
     use crate::sub_lib::hopper::MessageType;
-    use crate::sub_lib::proxy_client::ClientResponsePayload_0v1;
-    use crate::sub_lib::proxy_server::ClientRequestPayload_0v1;
+    use crate::sub_lib::proxy_client::ClientResponsePayload_0v2;
+    use crate::sub_lib::proxy_server::ClientRequestPayload_0v2;
     use crate::sub_lib::proxy_server::ProxyProtocol;
     use crate::sub_lib::route::{Route, RouteSegment};
     use crate::sub_lib::sequence_buffer::SequencedPacket;
@@ -377,13 +480,14 @@ mod tests {
     }
 
     pub struct StreamHandlerPoolMock {
-        process_package_parameters: Arc<Mutex<Vec<(ClientRequestPayload_0v1, Option<Wallet>)>>>,
+        process_package_parameters: Arc<Mutex<Vec<(ClientRequestPayload_0v2, Option<Wallet>)>>>,
+        update_resolver_parameters: Arc<Mutex<Vec<Box<dyn ResolverWrapper>>>>,
     }
 
     impl StreamHandlerPool for StreamHandlerPoolMock {
         fn process_package(
             &self,
-            payload: ClientRequestPayload_0v1,
+            payload: ClientRequestPayload_0v2,
             paying_wallet: Option<Wallet>,
         ) {
             self.process_package_parameters
@@ -391,22 +495,38 @@ mod tests {
                 .unwrap()
                 .push((payload, paying_wallet));
         }
+
+        fn update_resolver(&self, resolver: Box<dyn ResolverWrapper>) {
+            self.update_resolver_parameters
+                .lock()
+                .unwrap()
+                .push(resolver);
+        }
     }
 
     impl StreamHandlerPoolMock {
         pub fn new() -> StreamHandlerPoolMock {
             StreamHandlerPoolMock {
                 process_package_parameters: Arc::new(Mutex::new(vec![])),
+                update_resolver_parameters: Arc::new(Mutex::new(vec![])),
             }
         }
 
         pub fn process_package_parameters(
             self,
-            parameters: &mut Arc<Mutex<Vec<(ClientRequestPayload_0v1, Option<Wallet>)>>>,
+            parameters: &mut Arc<Mutex<Vec<(ClientRequestPayload_0v2, Option<Wallet>)>>>,
         ) -> StreamHandlerPoolMock {
             *parameters = self.process_package_parameters.clone();
             self
         }
+
+        pub fn update_resolver_parameters(
+            self,
+            parameters: &mut Arc<Mutex<Vec<Box<dyn ResolverWrapper>>>>,
+        ) -> StreamHandlerPoolMock {
+            *parameters = self.update_resolver_parameters.clone();
+            self
+        }
     }
 
     pub struct StreamHandlerPoolFactoryMock {
@@ -419,6 +539,7 @@ mod tests {
                     ProxyClientSubs,
                     u64,
                     u64,
+                    BandwidthShaper,
                 )>,
             >,
         >,
@@ -434,6 +555,7 @@ mod tests {
             proxy_client_subs: ProxyClientSubs,
             exit_service_rate: u64,
             exit_byte_rate: u64,
+            bandwidth_shaper: BandwidthShaper,
         ) -> Box<dyn StreamHandlerPool> {
             self.make_parameters.lock().unwrap().push((
                 resolver,
@@ -442,6 +564,7 @@ mod tests {
                 proxy_client_subs,
                 exit_service_rate,
                 exit_byte_rate,
+                bandwidth_shaper,
             ));
             self.make_results.borrow_mut().remove(0)
         }
@@ -466,6 +589,7 @@ mod tests {
                         ProxyClientSubs,
                         u64,
                         u64,
+                        BandwidthShaper,
                     )>,
                 >,
             >,
@@ -493,6 +617,7 @@ mod tests {
             exit_service_rate: 100,
             exit_byte_rate: 200,
             is_decentralized,
+            bandwidth_shaper: BandwidthShaper::default(),
             crashable: false,
         };
 
@@ -516,6 +641,7 @@ mod tests {
             exit_service_rate: 100,
             exit_byte_rate: 200,
             is_decentralized: true,
+            bandwidth_shaper: BandwidthShaper::default(),
             crashable: true,
         });
 
@@ -533,8 +659,72 @@ mod tests {
             exit_service_rate: 100,
             exit_byte_rate: 200,
             is_decentralized: true,
+            bandwidth_shaper: BandwidthShaper::default(),
+            crashable: false,
+        });
+    }
+
+    #[test]
+    fn new_dns_servers_give_the_pool_a_new_resolver() {
+        init_test_logging();
+        let system = System::new("new_dns_servers_give_the_pool_a_new_resolver");
+        let mut resolver_wrapper_new_parameters_arc: Arc<
+            Mutex<Vec<(ResolverConfig, ResolverOpts)>>,
+        > = Arc::new(Mutex::new(vec![]));
+        let resolver_wrapper_factory = ResolverWrapperFactoryMock::new()
+            .new_parameters(&mut resolver_wrapper_new_parameters_arc)
+            .new_result(Box::new(ResolverWrapperMock::new()))
+            .new_result(Box::new(ResolverWrapperMock::new()));
+        let mut update_resolver_parameters_arc = Arc::new(Mutex::new(vec![]));
+        let pool = StreamHandlerPoolMock::new()
+            .update_resolver_parameters(&mut update_resolver_parameters_arc);
+        let pool_factory = StreamHandlerPoolFactoryMock::new().make_result(Box::new(pool));
+        let mut subject = ProxyClient::new(ProxyClientConfig {
+            cryptde: main_cryptde(),
+            dns_servers: vec![SocketAddr::from_str("4.3.2.1:53").unwrap()],
+            exit_service_rate: 100,
+            exit_byte_rate: 200,
+            is_decentralized: true,
+            bandwidth_shaper: BandwidthShaper::default(),
             crashable: false,
         });
+        subject.resolver_wrapper_factory = Box::new(resolver_wrapper_factory);
+        subject.stream_handler_pool_factory = Box::new(pool_factory);
+        let subject_addr: Addr<ProxyClient> = subject.start();
+        subject_addr
+            .try_send(BindMessage {
+                peer_actors: peer_actors_builder().build(),
+            })
+            .unwrap();
+
+        subject_addr
+            .try_send(ConfigChangeMsg {
+                change: ConfigChange::UpdateDnsServers(vec![
+                    SocketAddr::from_str("5.6.7.8:53").unwrap(),
+                    SocketAddr::from_str("6.7.8.9:53").unwrap(),
+                ]),
+            })
+            .unwrap();
+
+        System::current().stop_with_code(0);
+        system.run();
+        let resolver_wrapper_new_parameters = resolver_wrapper_new_parameters_arc.lock().unwrap();
+        let (config, _) = &resolver_wrapper_new_parameters[1];
+        assert_eq!(
+            config
+                .name_servers()
+                .iter()
+                .map(|name_server| name_server.socket_addr)
+                .collect::<Vec<SocketAddr>>(),
+            vec![
+                SocketAddr::from_str("5.6.7.8:53").unwrap(),
+                SocketAddr::from_str("6.7.8.9:53").unwrap(),
+            ]
+        );
+        assert_eq!(resolver_wrapper_new_parameters.len(), 2);
+        assert_eq!(update_resolver_parameters_arc.lock().unwrap().len(), 1);
+        TestLogHandler::new()
+            .exists_log_containing("INFO: ProxyClient: DNS servers have been updated");
     }
 
     #[test]
@@ -553,6 +743,10 @@ mod tests {
             .make_parameters(&mut pool_factory_make_parameters)
             .make_result(Box::new(pool));
         let peer_actors = peer_actors_builder().build();
+        let bandwidth_limits = BandwidthLimits {
+            exit_kb_per_sec: 123,
+            ..BandwidthLimits::default()
+        };
         let mut subject = ProxyClient::new(ProxyClientConfig {
             cryptde: main_cryptde(),
             dns_servers: vec![
@@ -562,6 +756,7 @@ mod tests {
             exit_service_rate: 100,
             exit_byte_rate: 200,
             is_decentralized: true,
+            bandwidth_shaper: BandwidthShaper::new(bandwidth_limits, DataQuota::default()),
             crashable: false,
         });
         subject.resolver_wrapper_factory = Box::new(resolver_wrapper_factory);
@@ -595,22 +790,30 @@ mod tests {
         );
         assert_eq!(opts, ResolverOpts::default());
         assert_eq!(resolver_wrapper_new_parameters.is_empty(), true);
+        let pool_factory_make_parameters = pool_factory_make_parameters.lock().unwrap();
+        let (_, _, _, _, exit_service_rate, exit_byte_rate, bandwidth_shaper) =
+            &pool_factory_make_parameters[0];
+        assert_eq!(*exit_service_rate, 100);
+        assert_eq!(*exit_byte_rate, 200);
+        assert_eq!(bandwidth_shaper.limits(), bandwidth_limits);
     }
 
     #[test]
     #[should_panic(expected = "StreamHandlerPool unbound")]
     fn panics_if_unbound() {
-        let request = ClientRequestPayload_0v1 {
+        let request = ClientRequestPayload_0v2 {
             stream_key: StreamKey::make_meaningless_stream_key(),
             sequenced_packet: SequencedPacket {
                 data: b"HEAD http://www.nyan.cat/ HTTP/1.1\r\n\r\n".to_vec(),
                 sequence_number: 0,
                 last_data: false,
+                relayed: false,
             },
             target_hostname: Some(String::from("target.hostname.com")),
             target_port: 1234,
             protocol: ProxyProtocol::HTTP,
             originator_public_key: PublicKey::new(&b"originator_public_key"[..]),
+            next_expected_sequence_opt: None,
         };
         let cryptde = main_cryptde();
         let package = ExpiredCoresPackage::new(
@@ -627,6 +830,7 @@ mod tests {
             exit_service_rate: 100,
             exit_byte_rate: 200,
             is_decentralized: true,
+            bandwidth_shaper: BandwidthShaper::default(),
             crashable: false,
         });
         let subject_addr: Addr<ProxyClient> = subject.start();
@@ -651,6 +855,7 @@ mod tests {
                 exit_service_rate: 0,
                 exit_byte_rate: 0,
                 is_decentralized: true,
+                bandwidth_shaper: BandwidthShaper::default(),
                 crashable: false,
             });
             let subject_addr = subject.start();
@@ -692,6 +897,7 @@ mod tests {
                 exit_service_rate: 0,
                 exit_byte_rate: 0,
                 is_decentralized: true,
+                bandwidth_shaper: BandwidthShaper::default(),
                 crashable: false,
             });
             subject.stream_contexts.insert(
@@ -700,6 +906,8 @@ mod tests {
                     return_route: return_route_inner,
                     payload_destination_key: originator_key_inner,
                     paying_wallet: None,
+                    requests: ArrivalCounter::new(),
+                    responses: ReplayWindow::new(REPLAY_WINDOW_CAPACITY),
                 },
             );
             let subject_addr = subject.start();
@@ -743,17 +951,19 @@ mod tests {
     #[test]
     fn data_from_hopper_is_relayed_to_stream_handler_pool() {
         let cryptde = main_cryptde();
-        let request = ClientRequestPayload_0v1 {
+        let request = ClientRequestPayload_0v2 {
             stream_key: StreamKey::make_meaningless_stream_key(),
             sequenced_packet: SequencedPacket {
                 data: b"inbound data".to_vec(),
                 sequence_number: 0,
                 last_data: false,
+                relayed: false,
             },
             target_hostname: None,
             target_port: 0,
             protocol: ProxyProtocol::HTTP,
             originator_public_key: PublicKey::new(&b"originator"[..]),
+            next_expected_sequence_opt: None,
         };
         let key1 = make_meaningless_public_key();
         let key2 = make_meaningless_public_key();
@@ -784,6 +994,7 @@ mod tests {
             exit_service_rate: 100,
             exit_byte_rate: 200,
             is_decentralized: true,
+            bandwidth_shaper: BandwidthShaper::default(),
             crashable: false,
         });
         subject.resolver_wrapper_factory = Box::new(resolver_factory);
@@ -803,17 +1014,19 @@ mod tests {
     fn refuse_to_provide_exit_services_with_no_paying_wallet() {
         init_test_logging();
         let cryptde = main_cryptde();
-        let request = ClientRequestPayload_0v1 {
+        let request = ClientRequestPayload_0v2 {
             stream_key: StreamKey::make_meaningless_stream_key(),
             sequenced_packet: SequencedPacket {
                 data: b"inbound data".to_vec(),
                 sequence_number: 0,
                 last_data: false,
+                relayed: false,
             },
             target_hostname: None,
             target_port: 0,
             protocol: ProxyProtocol::HTTP,
             originator_public_key: PublicKey::new(&b"originator"[..]),
+            next_expected_sequence_opt: None,
         };
         let package = ExpiredCoresPackage::new(
             SocketAddr::from_str("1.2.3.4:1234").unwrap(),
@@ -841,6 +1054,7 @@ mod tests {
             exit_service_rate: rate_pack_exit(100),
             exit_byte_rate: rate_pack_exit_byte(100),
             is_decentralized: true,
+            bandwidth_shaper: BandwidthShaper::default(),
             crashable: false,
         });
         subject.resolver_wrapper_factory = Box::new(resolver_factory);
@@ -860,17 +1074,19 @@ mod tests {
     fn does_provide_zero_hop_exit_services_with_no_paying_wallet() {
         let main_cryptde = main_cryptde();
         let alias_cryptde = alias_cryptde();
-        let request = ClientRequestPayload_0v1 {
+        let request = ClientRequestPayload_0v2 {
             stream_key: StreamKey::make_meaningless_stream_key(),
             sequenced_packet: SequencedPacket {
                 data: b"inbound data".to_vec(),
                 sequence_number: 0,
                 last_data: false,
+                relayed: false,
             },
             target_hostname: None,
             target_port: 0,
             protocol: ProxyProtocol::HTTP,
             originator_public_key: alias_cryptde.public_key().clone(),
+            next_expected_sequence_opt: None,
         };
         let zero_hop_remaining_route = Route::one_way(
             RouteSegment::new(
@@ -908,6 +1124,7 @@ mod tests {
             exit_service_rate: rate_pack_exit(100),
             exit_byte_rate: rate_pack_exit_byte(100),
             is_decentralized: false,
+            bandwidth_shaper: BandwidthShaper::default(),
             crashable: false,
         });
         subject.resolver_wrapper_factory = Box::new(resolver_factory);
@@ -939,6 +1156,7 @@ mod tests {
             exit_service_rate: 100,
             exit_byte_rate: 200,
             is_decentralized: true,
+            bandwidth_shaper: BandwidthShaper::default(),
             crashable: false,
         });
         subject.stream_contexts.insert(
@@ -947,6 +1165,8 @@ mod tests {
                 return_route: route.clone(),
                 payload_destination_key: PublicKey::new(&b"abcd"[..]),
                 paying_wallet: Some(make_wallet("paying")),
+                requests: ArrivalCounter::new(),
+                responses: ReplayWindow::new(REPLAY_WINDOW_CAPACITY),
             },
         );
         subject.logger = Logger::new(test_name);
@@ -1006,13 +1226,15 @@ mod tests {
                 route.clone(),
                 MessageType::ClientResponse(VersionedData::new(
                     &crate::sub_lib::migrations::client_response_payload::MIGRATIONS,
-                    &ClientResponsePayload_0v1 {
+                    &ClientResponsePayload_0v2 {
                         stream_key: stream_key.clone(),
                         sequenced_packet: SequencedPacket {
                             data: Vec::from(data),
                             sequence_number: 1234,
                             last_data: false,
+                            relayed: false,
                         },
+                        next_expected_sequence_opt: Some(0),
                     }
                 )),
                 &PublicKey::new(&b"abcd"[..]),
@@ -1026,13 +1248,15 @@ mod tests {
                 route.clone(),
                 MessageType::ClientResponse(VersionedData::new(
                     &crate::sub_lib::migrations::client_response_payload::MIGRATIONS,
-                    &ClientResponsePayload_0v1 {
+                    &ClientResponsePayload_0v2 {
                         stream_key: stream_key.clone(),
                         sequenced_packet: SequencedPacket {
                             data: Vec::from(data),
                             sequence_number: 1235,
                             last_data: true,
+                            relayed: false,
                         },
+                        next_expected_sequence_opt: Some(0),
                     }
                 )),
                 &PublicKey::new(&b"abcd"[..]),
@@ -1088,6 +1312,7 @@ mod tests {
             exit_service_rate: 100,
             exit_byte_rate: 200,
             is_decentralized: true,
+            bandwidth_shaper: BandwidthShaper::default(),
             crashable: false,
         });
         subject.stream_contexts.insert(
@@ -1096,6 +1321,8 @@ mod tests {
                 return_route: make_meaningless_route(),
                 payload_destination_key: PublicKey::new(&b"abcd"[..]),
                 paying_wallet: None,
+                requests: ArrivalCounter::new(),
+                responses: ReplayWindow::new(REPLAY_WINDOW_CAPACITY),
             },
         );
         let subject_addr: Addr<ProxyClient> = subject.start();
@@ -1140,6 +1367,7 @@ mod tests {
             exit_service_rate: 100,
             exit_byte_rate: 200,
             is_decentralized: true,
+            bandwidth_shaper: BandwidthShaper::default(),
             crashable: false,
         });
         subject.stream_contexts.insert(
@@ -1148,6 +1376,8 @@ mod tests {
                 return_route: make_meaningless_route(),
                 payload_destination_key: PublicKey::new(&[]),
                 paying_wallet: Some(make_wallet("consuming")),
+                requests: ArrivalCounter::new(),
+                responses: ReplayWindow::new(REPLAY_WINDOW_CAPACITY),
             },
         );
         let subject_addr: Addr<ProxyClient> = subject.start();
@@ -1190,6 +1420,7 @@ mod tests {
             exit_service_rate: 100,
             exit_byte_rate: 200,
             is_decentralized: true,
+            bandwidth_shaper: BandwidthShaper::default(),
             crashable: false,
         });
         let mut process_package_params_arc = Arc::new(Mutex::new(vec![]));
@@ -1207,6 +1438,8 @@ mod tests {
                 return_route: old_return_route,
                 payload_destination_key: originator_public_key.clone(),
                 paying_wallet: Some(make_wallet("consuming")),
+                requests: ArrivalCounter::new(),
+                responses: ReplayWindow::new(REPLAY_WINDOW_CAPACITY),
             },
         );
         subject.stream_handler_pool_factory = Box::new(pool_factory);
@@ -1216,17 +1449,19 @@ mod tests {
             .accountant(accountant)
             .build();
         subject_addr.try_send(BindMessage { peer_actors }).unwrap();
-        let payload = ClientRequestPayload_0v1 {
+        let payload = ClientRequestPayload_0v2 {
             stream_key: stream_key.clone(),
             sequenced_packet: SequencedPacket {
                 data: vec![],
                 sequence_number: 0,
                 last_data: false,
+                relayed: false,
             },
             target_hostname: None,
             target_port: 0,
             protocol: ProxyProtocol::HTTP,
             originator_public_key: originator_public_key.clone(),
+            next_expected_sequence_opt: None,
         };
         let before = SystemTime::now();
 
@@ -1262,13 +1497,15 @@ mod tests {
             new_return_route,
             MessageType::ClientResponse(VersionedData::new(
                 &crate::sub_lib::migrations::client_response_payload::MIGRATIONS,
-                &ClientResponsePayload_0v1 {
+                &ClientResponsePayload_0v2 {
                     stream_key,
                     sequenced_packet: SequencedPacket {
                         data: Vec::from(data.clone()),
                         sequence_number: 1234,
                         last_data: false,
+                        relayed: false,
                     },
+                    next_expected_sequence_opt: Some(1),
                 },
             )),
             &originator_public_key,
@@ -1294,4 +1531,195 @@ mod tests {
             }
         )
     }
+
+    fn make_response_payload(
+        stream_key: StreamKey,
+        sequence_number: u64,
+        next_expected_sequence_opt: Option<u64>,
+    ) -> ClientResponsePayload_0v2 {
+        ClientResponsePayload_0v2 {
+            stream_key,
+            sequenced_packet: SequencedPacket {
+                data: format!("response {}", sequence_number).into_bytes(),
+                sequence_number,
+                last_data: false,
+                relayed: false,
+            },
+            next_expected_sequence_opt,
+        }
+    }
+
+    fn make_request_payload(
+        stream_key: StreamKey,
+        sequence_number: u64,
+        next_expected_sequence_opt: Option<u64>,
+    ) -> ClientRequestPayload_0v2 {
+        ClientRequestPayload_0v2 {
+            stream_key,
+            sequenced_packet: SequencedPacket {
+                data: format!("request {}", sequence_number).into_bytes(),
+                sequence_number,
+                last_data: false,
+                relayed: false,
+            },
+            target_hostname: Some("example.com".to_string()),
+            target_port: 80,
+            protocol: ProxyProtocol::HTTP,
+            originator_public_key: PublicKey::new(&[4, 3, 2, 1]),
+            next_expected_sequence_opt,
+        }
+    }
+
+    #[test]
+    fn unacknowledged_responses_are_replayed_when_the_return_route_changes() {
+        let cryptde = main_cryptde();
+        let (hopper, _, hopper_recording_arc) = make_recorder();
+        let stream_key = StreamKey::make_meaningless_stream_key();
+        let system =
+            System::new("unacknowledged_responses_are_replayed_when_the_return_route_changes");
+        let mut subject = ProxyClient::new(ProxyClientConfig {
+            cryptde,
+            dns_servers: vec![SocketAddr::from_str("8.7.6.5:4321").unwrap()],
+            exit_service_rate: 100,
+            exit_byte_rate: 200,
+            is_decentralized: true,
+            bandwidth_shaper: BandwidthShaper::default(),
+            crashable: false,
+        });
+        let mut process_package_params_arc = Arc::new(Mutex::new(vec![]));
+        let pool = StreamHandlerPoolMock::new()
+            .process_package_parameters(&mut process_package_params_arc);
+        let pool_factory = StreamHandlerPoolFactoryMock::new().make_result(Box::new(pool));
+        let old_return_route = Route {
+            hops: vec![CryptData::new(&[1, 2, 3, 4])],
+        };
+        let new_return_route = make_meaningless_route();
+        let mut requests = ArrivalCounter::new();
+        requests.arrive(0);
+        let mut responses = ReplayWindow::new(REPLAY_WINDOW_CAPACITY);
+        (0..3).for_each(|sequence_number| {
+            responses.push(
+                sequence_number,
+                make_response_payload(stream_key, sequence_number, Some(1)),
+            )
+        });
+        subject.stream_contexts.insert(
+            stream_key,
+            StreamContext {
+                return_route: old_return_route,
+                payload_destination_key: PublicKey::new(&[4, 3, 2, 1]),
+                paying_wallet: Some(make_wallet("consuming")),
+                requests,
+                responses,
+            },
+        );
+        subject.stream_handler_pool_factory = Box::new(pool_factory);
+        let subject_addr: Addr<ProxyClient> = subject.start();
+        let peer_actors = peer_actors_builder().hopper(hopper).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+        let payload = make_request_payload(stream_key, 1, Some(1));
+
+        subject_addr
+            .try_send(ExpiredCoresPackage::new(
+                SocketAddr::from_str("2.3.4.5:1235").unwrap(),
+                Some(make_wallet("consuming")),
+                new_return_route.clone(),
+                payload.clone().into(),
+                0,
+            ))
+            .unwrap();
+
+        System::current().stop_with_code(0);
+        system.run();
+        let hopper_recording = hopper_recording_arc.lock().unwrap();
+        let replayed = (1..3)
+            .map(|sequence_number| {
+                IncipientCoresPackage::new(
+                    cryptde,
+                    new_return_route.clone(),
+                    MessageType::ClientResponse(VersionedData::new(
+                        &crate::sub_lib::migrations::client_response_payload::MIGRATIONS,
+                        &make_response_payload(stream_key, sequence_number, Some(2)),
+                    )),
+                    &PublicKey::new(&[4, 3, 2, 1]),
+                )
+                .unwrap()
+            })
+            .collect::<Vec<IncipientCoresPackage>>();
+        assert_eq!(
+            hopper_recording.get_record::<IncipientCoresPackage>(0),
+            &replayed[0]
+        );
+        assert_eq!(
+            hopper_recording.get_record::<IncipientCoresPackage>(1),
+            &replayed[1]
+        );
+        assert_eq!(hopper_recording.len(), 2);
+        let process_package_params = process_package_params_arc.lock().unwrap();
+        assert_eq!(
+            *process_package_params,
+            vec![(payload, Some(make_wallet("consuming")))]
+        );
+    }
+
+    #[test]
+    fn replayed_requests_are_not_passed_on_to_the_server() {
+        init_test_logging();
+        let test_name = "replayed_requests_are_not_passed_on_to_the_server";
+        let cryptde = main_cryptde();
+        let (hopper, _, hopper_recording_arc) = make_recorder();
+        let stream_key = StreamKey::make_meaningless_stream_key();
+        let system = System::new(test_name);
+        let mut subject = ProxyClient::new(ProxyClientConfig {
+            cryptde,
+            dns_servers: vec![SocketAddr::from_str("8.7.6.5:4321").unwrap()],
+            exit_service_rate: 100,
+            exit_byte_rate: 200,
+            is_decentralized: true,
+            bandwidth_shaper: BandwidthShaper::default(),
+            crashable: false,
+        });
+        subject.logger = Logger::new(test_name);
+        let mut process_package_params_arc = Arc::new(Mutex::new(vec![]));
+        let pool = StreamHandlerPoolMock::new()
+            .process_package_parameters(&mut process_package_params_arc);
+        let pool_factory = StreamHandlerPoolFactoryMock::new().make_result(Box::new(pool));
+        subject.stream_handler_pool_factory = Box::new(pool_factory);
+        let return_route = make_meaningless_route();
+        let subject_addr: Addr<ProxyClient> = subject.start();
+        let peer_actors = peer_actors_builder().hopper(hopper).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+        let first = make_request_payload(stream_key, 0, Some(0));
+        let second = make_request_payload(stream_key, 1, Some(0));
+        vec![first.clone(), second.clone(), first.clone()]
+            .into_iter()
+            .for_each(|payload| {
+                subject_addr
+                    .try_send(ExpiredCoresPackage::new(
+                        SocketAddr::from_str("2.3.4.5:1235").unwrap(),
+                        Some(make_wallet("consuming")),
+                        return_route.clone(),
+                        payload.into(),
+                        0,
+                    ))
+                    .unwrap()
+            });
+
+        System::current().stop_with_code(0);
+        system.run();
+        let process_package_params = process_package_params_arc.lock().unwrap();
+        assert_eq!(
+            *process_package_params,
+            vec![
+                (first, Some(make_wallet("consuming"))),
+                (second, Some(make_wallet("consuming")))
+            ]
+        );
+        let hopper_recording = hopper_recording_arc.lock().unwrap();
+        assert_eq!(hopper_recording.len(), 0);
+        TestLogHandler::new().exists_log_containing(&format!(
+            "DEBUG: {}: Dropping replayed ClientRequestPayload: stream {}, sequence 0",
+            test_name, stream_key
+        ));
+    }
 }
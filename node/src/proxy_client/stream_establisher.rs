@@ -3,12 +3,13 @@
 use crate::proxy_client::stream_handler_pool::StreamSenders;
 use crate::proxy_client::stream_reader::StreamReader;
 use crate::proxy_client::stream_writer::StreamWriter;
+use crate::sub_lib::bandwidth_shaper::BandwidthShaper;
 use crate::sub_lib::channel_wrappers::FuturesChannelFactory;
 use crate::sub_lib::channel_wrappers::FuturesChannelFactoryReal;
 use crate::sub_lib::channel_wrappers::SenderWrapper;
 use crate::sub_lib::cryptde::CryptDE;
 use crate::sub_lib::proxy_client::{InboundServerData, ProxyClientSubs};
-use crate::sub_lib::proxy_server::ClientRequestPayload_0v1;
+use crate::sub_lib::proxy_server::ClientRequestPayload_0v2;
 use crate::sub_lib::sequence_buffer::SequencedPacket;
 use crate::sub_lib::stream_connector::StreamConnector;
 use crate::sub_lib::stream_connector::StreamConnectorReal;
@@ -17,6 +18,7 @@ use crate::sub_lib::tokio_wrappers::ReadHalfWrapper;
 use actix::Recipient;
 use crossbeam_channel::{unbounded, Receiver, Sender};
 use masq_lib::logger::Logger;
+use masq_lib::redaction::MarkSensitive;
 use std::io;
 use std::net::IpAddr;
 use std::net::SocketAddr;
@@ -29,6 +31,7 @@ pub struct StreamEstablisher {
     pub proxy_client_sub: Recipient<InboundServerData>,
     pub logger: Logger,
     pub channel_factory: Box<dyn FuturesChannelFactory<SequencedPacket>>,
+    pub bandwidth_shaper: BandwidthShaper,
 }
 
 impl Clone for StreamEstablisher {
@@ -41,6 +44,7 @@ impl Clone for StreamEstablisher {
             proxy_client_sub: self.proxy_client_sub.clone(),
             logger: self.logger.clone(),
             channel_factory: Box::new(FuturesChannelFactoryReal {}),
+            bandwidth_shaper: self.bandwidth_shaper.clone(),
         }
     }
 }
@@ -48,7 +52,7 @@ impl Clone for StreamEstablisher {
 impl StreamEstablisher {
     pub fn establish_stream(
         &mut self,
-        payload: &ClientRequestPayload_0v1,
+        payload: &ClientRequestPayload_0v2,
         ip_addrs: Vec<IpAddr>,
         target_hostname: String,
     ) -> io::Result<Box<dyn SenderWrapper<SequencedPacket>>> {
@@ -89,7 +93,7 @@ impl StreamEstablisher {
 
     fn spawn_stream_reader(
         &self,
-        payload: &ClientRequestPayload_0v1,
+        payload: &ClientRequestPayload_0v2,
         read_stream: Box<dyn ReadHalfWrapper>,
         peer_addr: SocketAddr,
         shutdown_signal: Receiver<()>,
@@ -101,8 +105,13 @@ impl StreamEstablisher {
             self.stream_killer_tx.clone(),
             shutdown_signal,
             peer_addr,
+            self.bandwidth_shaper.clone(),
+        );
+        debug!(
+            self.logger,
+            "Spawning StreamReader for {}",
+            peer_addr.sensitive()
         );
-        debug!(self.logger, "Spawning StreamReader for {}", peer_addr);
         tokio::spawn(stream_reader);
     }
 }
@@ -117,6 +126,7 @@ pub struct StreamEstablisherFactoryReal {
     pub stream_killer_tx: Sender<(StreamKey, u64)>,
     pub proxy_client_subs: ProxyClientSubs,
     pub logger: Logger,
+    pub bandwidth_shaper: BandwidthShaper,
 }
 
 impl StreamEstablisherFactory for StreamEstablisherFactoryReal {
@@ -129,6 +139,7 @@ impl StreamEstablisherFactory for StreamEstablisherFactoryReal {
             proxy_client_sub: self.proxy_client_subs.inbound_server_data.clone(),
             logger: self.logger.clone(),
             channel_factory: Box::new(FuturesChannelFactoryReal {}),
+            bandwidth_shaper: self.bandwidth_shaper.clone(),
         }
     }
 }
@@ -185,19 +196,22 @@ mod tests {
                 proxy_client_sub,
                 logger: Logger::new("ProxyClient"),
                 channel_factory: Box::new(FuturesChannelFactoryReal {}),
+                bandwidth_shaper: BandwidthShaper::default(),
             };
             subject.spawn_stream_reader(
-                &ClientRequestPayload_0v1 {
+                &ClientRequestPayload_0v2 {
                     stream_key: StreamKey::make_meaningless_stream_key(),
                     sequenced_packet: SequencedPacket {
                         data: vec![],
                         sequence_number: 0,
                         last_data: false,
+                        relayed: false,
                     },
                     target_hostname: Some("blah".to_string()),
                     target_port: 0,
                     protocol: ProxyProtocol::HTTP,
                     originator_public_key: subject.cryptde.public_key().clone(),
+                    next_expected_sequence_opt: None,
                 },
                 read_stream,
                 SocketAddr::from_str("1.2.3.4:5678").unwrap(),
@@ -5,11 +5,12 @@ use crate::proxy_client::resolver_wrapper::ResolverWrapper;
 use crate::proxy_client::stream_establisher::StreamEstablisherFactoryReal;
 use crate::proxy_client::stream_establisher::{StreamEstablisher, StreamEstablisherFactory};
 use crate::sub_lib::accountant::ReportExitServiceProvidedMessage;
+use crate::sub_lib::bandwidth_shaper::BandwidthShaper;
 use crate::sub_lib::channel_wrappers::SenderWrapper;
 use crate::sub_lib::cryptde::CryptDE;
 use crate::sub_lib::proxy_client::{error_socket_addr, ProxyClientSubs};
 use crate::sub_lib::proxy_client::{DnsResolveFailure_0v1, InboundServerData};
-use crate::sub_lib::proxy_server::ClientRequestPayload_0v1;
+use crate::sub_lib::proxy_server::ClientRequestPayload_0v2;
 use crate::sub_lib::sequence_buffer::SequencedPacket;
 use crate::sub_lib::stream_key::StreamKey;
 use crate::sub_lib::wallet::Wallet;
@@ -18,6 +19,7 @@ use crossbeam_channel::{unbounded, Receiver, Sender};
 use futures::future;
 use futures::future::Future;
 use masq_lib::logger::Logger;
+use masq_lib::redaction::MarkSensitive;
 use std::collections::HashMap;
 use std::io;
 use std::net::{AddrParseError, IpAddr, Ipv4Addr, SocketAddr};
@@ -32,7 +34,9 @@ use trust_dns_resolver::lookup_ip::LookupIp;
 // TODO: This should be renamed to differentiate it from the other StreamHandlerPool,
 // which, unlike this, is an actor.
 pub trait StreamHandlerPool {
-    fn process_package(&self, payload: ClientRequestPayload_0v1, paying_wallet_opt: Option<Wallet>);
+    fn process_package(&self, payload: ClientRequestPayload_0v2, paying_wallet_opt: Option<Wallet>);
+    // Streams already established keep their connections; only new lookups use the new resolver
+    fn update_resolver(&self, resolver: Box<dyn ResolverWrapper>);
 }
 
 #[derive(Debug)]
@@ -61,12 +65,19 @@ struct StreamHandlerPoolRealInner {
 impl StreamHandlerPool for StreamHandlerPoolReal {
     fn process_package(
         &self,
-        payload: ClientRequestPayload_0v1,
+        payload: ClientRequestPayload_0v2,
         paying_wallet_opt: Option<Wallet>,
     ) {
         self.do_housekeeping();
         Self::process_package(payload, paying_wallet_opt, self.inner.clone())
     }
+
+    fn update_resolver(&self, resolver: Box<dyn ResolverWrapper>) {
+        self.inner
+            .lock()
+            .expect("Stream handler pool is poisoned")
+            .resolver = resolver;
+    }
 }
 
 type StreamEstablisherResult =
@@ -80,6 +91,7 @@ impl StreamHandlerPoolReal {
         proxy_client_subs: ProxyClientSubs,
         exit_service_rate: u64,
         exit_byte_rate: u64,
+        bandwidth_shaper: BandwidthShaper,
     ) -> StreamHandlerPoolReal {
         let (stream_killer_tx, stream_killer_rx) = unbounded();
         let (stream_adder_tx, stream_adder_rx) = unbounded();
@@ -91,6 +103,7 @@ impl StreamHandlerPoolReal {
                     stream_killer_tx,
                     proxy_client_subs: proxy_client_subs.clone(),
                     logger: Logger::new("ProxyClient"),
+                    bandwidth_shaper,
                 }),
                 accountant_sub,
                 proxy_client_subs,
@@ -106,7 +119,7 @@ impl StreamHandlerPoolReal {
     }
 
     fn process_package(
-        payload: ClientRequestPayload_0v1,
+        payload: ClientRequestPayload_0v2,
         paying_wallet_opt: Option<Wallet>,
         inner_arc: Arc<Mutex<StreamHandlerPoolRealInner>>,
     ) {
@@ -195,7 +208,7 @@ impl StreamHandlerPoolReal {
             debug!(
                 inner.logger,
                 "Removing stream writer for {}",
-                stream_senders.writer_data.peer_addr()
+                stream_senders.writer_data.peer_addr().sensitive()
             );
             Self::send_shutdown_signal_to_stream_reader(
                 stream_senders.reader_shutdown_tx,
@@ -212,7 +225,7 @@ impl StreamHandlerPoolReal {
 
     fn write_and_tend(
         sender_wrapper: Box<dyn SenderWrapper<SequencedPacket>>,
-        payload: ClientRequestPayload_0v1,
+        payload: ClientRequestPayload_0v2,
         paying_wallet_opt: Option<Wallet>,
         inner_arc: Arc<Mutex<StreamHandlerPoolRealInner>>,
     ) -> impl Future<Item = (), Error = String> {
@@ -248,7 +261,7 @@ impl StreamHandlerPoolReal {
                             inner.logger,
                             "Removing StreamWriter and Shutting down StreamReader for {:?} to {}",
                             stream_key,
-                            stream_senders.writer_data.peer_addr()
+                            stream_senders.writer_data.peer_addr().sensitive()
                         );
                         Self::send_shutdown_signal_to_stream_reader(
                             stream_senders.reader_shutdown_tx,
@@ -270,7 +283,7 @@ impl StreamHandlerPoolReal {
     }
 
     fn make_stream_with_key(
-        payload: &ClientRequestPayload_0v1,
+        payload: &ClientRequestPayload_0v2,
         inner_arc: Arc<Mutex<StreamHandlerPoolRealInner>>,
     ) -> StreamEstablisherResult {
         // TODO: Figure out what to do if a flurry of requests for a particular stream key
@@ -279,7 +292,8 @@ impl StreamHandlerPoolReal {
         let logger = Self::make_logger_copy(&inner_arc);
         debug!(
             logger,
-            "No stream to {:?} exists; resolving host", &payload.target_hostname
+            "No stream to {:?} exists; resolving host",
+            payload.target_hostname.sensitive()
         );
 
         match payload.target_hostname {
@@ -323,7 +337,7 @@ impl StreamHandlerPoolReal {
     }
 
     fn handle_ip(
-        payload: ClientRequestPayload_0v1,
+        payload: ClientRequestPayload_0v2,
         ip_addr: IpAddr,
         inner_arc: Arc<Mutex<StreamHandlerPoolRealInner>>,
         target_hostname: String,
@@ -340,7 +354,7 @@ impl StreamHandlerPoolReal {
     fn lookup_dns(
         inner_arc: Arc<Mutex<StreamHandlerPoolRealInner>>,
         target_hostname: String,
-        payload: ClientRequestPayload_0v1,
+        payload: ClientRequestPayload_0v2,
     ) -> StreamEstablisherResult {
         let fqdn = Self::make_fqdn(&target_hostname);
         let dns_resolve_failed_sub = inner_arc
@@ -389,7 +403,7 @@ impl StreamHandlerPoolReal {
 
     fn handle_lookup_ip(
         target_hostname: String,
-        payload: &ClientRequestPayload_0v1,
+        payload: &ClientRequestPayload_0v2,
         lookup_result: Result<LookupIp, ResolveError>,
         logger: Logger,
         establisher: &mut StreamEstablisher,
@@ -398,7 +412,9 @@ impl StreamHandlerPoolReal {
             Err(e) => {
                 error!(
                     logger,
-                    "Could not find IP address for host {}: {}", target_hostname, e
+                    "Could not find IP address for host {}: {}",
+                    target_hostname.sensitive(),
+                    e
                 );
                 return Err(io::Error::from(e));
             }
@@ -410,14 +426,18 @@ impl StreamHandlerPoolReal {
         if filtered_ip_addrs.is_empty() {
             info!(
                 logger,
-                "Unable to find valid IP addresses for host {}: {:?}", target_hostname, &ip_addrs
+                "Unable to find valid IP addresses for host {}: {:?}",
+                target_hostname.sensitive(),
+                ip_addrs.sensitive()
             );
             return Err(io::Error::from(io::ErrorKind::NotFound));
         }
 
         debug!(
             logger,
-            "Found IP addresses for {}: {:?}", target_hostname, &filtered_ip_addrs
+            "Found IP addresses for {}: {:?}",
+            target_hostname.sensitive(),
+            filtered_ip_addrs.sensitive()
         );
         establisher.establish_stream(payload, filtered_ip_addrs, target_hostname)
     }
@@ -499,7 +519,7 @@ impl StreamHandlerPoolReal {
                         inner.logger,
                         "Killed StreamWriter and StreamReader for the stream key {:?} to {} and sent server-drop report",
                         stream_key,
-                        stream_senders.writer_data.peer_addr()
+                        stream_senders.writer_data.peer_addr().sensitive()
                     )
                 }
                 None => debug!(
@@ -519,7 +539,7 @@ impl StreamHandlerPoolReal {
                     debug!(
                         inner.logger,
                         "Persisting StreamWriter to {} under key {:?}",
-                        stream_senders.writer_data.peer_addr(),
+                        stream_senders.writer_data.peer_addr().sensitive(),
                         stream_key
                     );
                     inner
@@ -532,6 +552,7 @@ impl StreamHandlerPoolReal {
 }
 
 pub trait StreamHandlerPoolFactory {
+    #[allow(clippy::too_many_arguments)]
     fn make(
         &self,
         resolver: Box<dyn ResolverWrapper>,
@@ -540,6 +561,7 @@ pub trait StreamHandlerPoolFactory {
         proxy_client_subs: ProxyClientSubs,
         exit_service_rate: u64,
         exit_byte_rate: u64,
+        bandwidth_shaper: BandwidthShaper,
     ) -> Box<dyn StreamHandlerPool>;
 }
 
@@ -554,6 +576,7 @@ impl StreamHandlerPoolFactory for StreamHandlerPoolFactoryReal {
         proxy_client_subs: ProxyClientSubs,
         exit_service_rate: u64,
         exit_byte_rate: u64,
+        bandwidth_shaper: BandwidthShaper,
     ) -> Box<dyn StreamHandlerPool> {
         Box::new(StreamHandlerPoolReal::new(
             resolver,
@@ -562,6 +585,7 @@ impl StreamHandlerPoolFactory for StreamHandlerPoolFactoryReal {
             proxy_client_subs,
             exit_service_rate,
             exit_byte_rate,
+            bandwidth_shaper,
         ))
     }
 }
@@ -657,6 +681,7 @@ mod tests {
                     .inbound_server_data,
                 logger: logger.clone(),
                 channel_factory: Box::new(FuturesChannelFactoryMock::default()),
+                bandwidth_shaper: BandwidthShaper::default(),
             };
             let inner = StreamHandlerPoolRealInner {
                 accountant_sub: peer_actors.accountant.report_exit_service_provided.clone(),
@@ -670,13 +695,14 @@ mod tests {
                 exit_service_rate: Default::default(),
                 exit_byte_rate: Default::default(),
             };
-            let payload = ClientRequestPayload_0v1 {
+            let payload = ClientRequestPayload_0v2 {
                 stream_key,
                 sequenced_packet: SequencedPacket::new(b"booga".to_vec(), 0, false),
                 target_hostname: Some("www.example.com".to_string()),
                 target_port: HTTP_PORT,
                 protocol: ProxyProtocol::HTTP,
                 originator_public_key: cryptde.public_key().clone(),
+                next_expected_sequence_opt: None,
             };
 
             StreamHandlerPoolReal::process_package(payload, None, Arc::new(Mutex::new(inner)));
@@ -699,17 +725,19 @@ mod tests {
     fn non_terminal_payload_can_be_sent_over_existing_connection() {
         let cryptde = main_cryptde();
         let stream_key = StreamKey::make_meaningless_stream_key();
-        let client_request_payload = ClientRequestPayload_0v1 {
+        let client_request_payload = ClientRequestPayload_0v2 {
             stream_key: stream_key.clone(),
             sequenced_packet: SequencedPacket {
                 data: b"These are the times".to_vec(),
                 sequence_number: 0,
                 last_data: false,
+                relayed: false,
             },
             target_hostname: None,
             target_port: HTTP_PORT,
             protocol: ProxyProtocol::HTTP,
             originator_public_key: PublicKey::new(&b"men's souls"[..]),
+            next_expected_sequence_opt: None,
         };
         let write_parameters = Arc::new(Mutex::new(vec![]));
         let peer_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
@@ -736,6 +764,7 @@ mod tests {
                 peer_actors.proxy_client_opt.unwrap().clone(),
                 100,
                 200,
+                BandwidthShaper::default(),
             );
             subject.inner.lock().unwrap().stream_writer_channels.insert(
                 stream_key,
@@ -756,6 +785,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn update_resolver_makes_later_lookups_use_the_new_resolver() {
+        let _system = System::new("update_resolver_makes_later_lookups_use_the_new_resolver");
+        let old_lookup_ip_parameters = Arc::new(Mutex::new(vec![]));
+        let new_lookup_ip_parameters = Arc::new(Mutex::new(vec![]));
+        let peer_actors = peer_actors_builder().build();
+        let subject = StreamHandlerPoolReal::new(
+            Box::new(ResolverWrapperMock::new().lookup_ip_parameters(&old_lookup_ip_parameters)),
+            main_cryptde(),
+            peer_actors.accountant.report_exit_service_provided.clone(),
+            peer_actors.proxy_client_opt.unwrap().clone(),
+            100,
+            200,
+            BandwidthShaper::default(),
+        );
+        let new_resolver = ResolverWrapperMock::new()
+            .lookup_ip_success(vec![IpAddr::from_str("2.3.4.5").unwrap()])
+            .lookup_ip_parameters(&new_lookup_ip_parameters);
+
+        subject.update_resolver(Box::new(new_resolver));
+
+        let _ = subject.inner.lock().unwrap().resolver.lookup_ip("that.try");
+        assert_eq!(
+            *new_lookup_ip_parameters.lock().unwrap(),
+            vec!["that.try".to_string()]
+        );
+        assert_eq!(old_lookup_ip_parameters.lock().unwrap().is_empty(), true);
+    }
+
     #[test]
     fn write_failure_for_nonexistent_stream_generates_termination_message() {
         init_test_logging();
@@ -765,17 +823,19 @@ mod tests {
         let originator_key = PublicKey::new(&b"men's souls"[..]);
         let (reader_shutdown_tx, reader_shutdown_rx) = unbounded();
         thread::spawn(move || {
-            let client_request_payload = ClientRequestPayload_0v1 {
+            let client_request_payload = ClientRequestPayload_0v2 {
                 stream_key: StreamKey::make_meaningless_stream_key(),
                 sequenced_packet: SequencedPacket {
                     data: b"These are the times".to_vec(),
                     sequence_number: 0,
                     last_data: false,
+                    relayed: false,
                 },
                 target_hostname: Some(String::from("that.try")),
                 target_port: HTTP_PORT,
                 protocol: ProxyProtocol::HTTP,
                 originator_public_key: originator_key,
+                next_expected_sequence_opt: None,
             };
             let package = ExpiredCoresPackage::new(
                 SocketAddr::from_str("1.2.3.4:1234").unwrap(),
@@ -798,6 +858,7 @@ mod tests {
                 peer_actors.proxy_client_opt.unwrap().clone(),
                 100,
                 200,
+                BandwidthShaper::default(),
             );
             {
                 let mut inner = subject.inner.lock().unwrap();
@@ -841,17 +902,19 @@ mod tests {
         let (proxy_client, proxy_client_awaiter, proxy_client_recording_arc) = make_recorder();
         thread::spawn(move || {
             let peer_actors = peer_actors_builder().proxy_client(proxy_client).build();
-            let client_request_payload = ClientRequestPayload_0v1 {
+            let client_request_payload = ClientRequestPayload_0v2 {
                 stream_key: StreamKey::make_meaningless_stream_key(),
                 sequenced_packet: SequencedPacket {
                     data: b"These are the times".to_vec(),
                     sequence_number: 0,
                     last_data: false,
+                    relayed: false,
                 },
                 target_hostname: Some(String::from("3.4.5.6:80")),
                 target_port: HTTP_PORT,
                 protocol: ProxyProtocol::HTTP,
                 originator_public_key: PublicKey::new(&b"men's souls"[..]),
+                next_expected_sequence_opt: None,
             };
             let package = ExpiredCoresPackage::new(
                 SocketAddr::from_str("1.2.3.4:1234").unwrap(),
@@ -883,6 +946,7 @@ mod tests {
                 peer_actors.proxy_client_opt.unwrap().clone(),
                 100,
                 200,
+                BandwidthShaper::default(),
             );
             let (stream_killer_tx, stream_killer_rx) = unbounded();
             subject.stream_killer_rx = stream_killer_rx;
@@ -902,6 +966,7 @@ mod tests {
                     proxy_client_sub: inner.proxy_client_subs.inbound_server_data.clone(),
                     logger: inner.logger.clone(),
                     channel_factory: Box::new(FuturesChannelFactoryReal {}),
+                    bandwidth_shaper: BandwidthShaper::default(),
                 };
 
                 inner.establisher_factory = Box::new(StreamEstablisherFactoryMock {
@@ -937,17 +1002,19 @@ mod tests {
         let (shutdown_tx, shutdown_rx) = unbounded();
         thread::spawn(move || {
             let stream_key = StreamKey::make_meaningful_stream_key("I should die");
-            let client_request_payload = ClientRequestPayload_0v1 {
+            let client_request_payload = ClientRequestPayload_0v2 {
                 stream_key,
                 sequenced_packet: SequencedPacket {
                     data: b"I'm gonna kill you stream key".to_vec(),
                     sequence_number: 0,
                     last_data: true,
+                    relayed: false,
                 },
                 target_hostname: Some(String::from("3.4.5.6:80")),
                 target_port: HTTP_PORT,
                 protocol: ProxyProtocol::HTTP,
                 originator_public_key: PublicKey::new(&b"brutal death"[..]),
+                next_expected_sequence_opt: None,
             };
             let package = ExpiredCoresPackage::new(
                 SocketAddr::from_str("1.2.3.4:1234").unwrap(),
@@ -965,6 +1032,7 @@ mod tests {
                 peer_actors.proxy_client_opt.unwrap().clone(),
                 100,
                 200,
+                BandwidthShaper::default(),
             );
             {
                 let mut inner = subject.inner.lock().unwrap();
@@ -998,17 +1066,19 @@ mod tests {
         let broken_shutdown_channel_tx = unbounded().0;
         thread::spawn(move || {
             let stream_key = StreamKey::make_meaningful_stream_key("I should die");
-            let client_request_payload = ClientRequestPayload_0v1 {
+            let client_request_payload = ClientRequestPayload_0v2 {
                 stream_key,
                 sequenced_packet: SequencedPacket {
                     data: b"I'm gonna kill you stream key".to_vec(),
                     sequence_number: 0,
                     last_data: true,
+                    relayed: false,
                 },
                 target_hostname: Some(String::from("3.4.5.6:80")),
                 target_port: HTTP_PORT,
                 protocol: ProxyProtocol::HTTP,
                 originator_public_key: PublicKey::new(&b"brutal death"[..]),
+                next_expected_sequence_opt: None,
             };
             let package = ExpiredCoresPackage::new(
                 SocketAddr::from_str("1.2.3.4:1234").unwrap(),
@@ -1026,6 +1096,7 @@ mod tests {
                 peer_actors.proxy_client_opt.unwrap().clone(),
                 100,
                 200,
+                BandwidthShaper::default(),
             );
             {
                 let mut inner = subject.inner.lock().unwrap();
@@ -1060,17 +1131,19 @@ mod tests {
         let (proxy_client, proxy_client_awaiter, proxy_client_recording_arc) = make_recorder();
         thread::spawn(move || {
             let peer_actors = peer_actors_builder().proxy_client(proxy_client).build();
-            let client_request_payload = ClientRequestPayload_0v1 {
+            let client_request_payload = ClientRequestPayload_0v2 {
                 stream_key: StreamKey::make_meaningless_stream_key(),
                 sequenced_packet: SequencedPacket {
                     data: b"These are the times".to_vec(),
                     sequence_number: 0,
                     last_data: false,
+                    relayed: false,
                 },
                 target_hostname: Some(String::from("3.4.5.6")),
                 target_port: HTTP_PORT,
                 protocol: ProxyProtocol::HTTP,
                 originator_public_key: PublicKey::new(&b"men's souls"[..]),
+                next_expected_sequence_opt: None,
             };
             let package = ExpiredCoresPackage::new(
                 SocketAddr::from_str("1.2.3.4:1234").unwrap(),
@@ -1108,6 +1181,7 @@ mod tests {
                 peer_actors.proxy_client_opt.unwrap().clone(),
                 100,
                 200,
+                BandwidthShaper::default(),
             );
             let (stream_killer_tx, stream_killer_rx) = unbounded();
             subject.stream_killer_rx = stream_killer_rx;
@@ -1127,6 +1201,7 @@ mod tests {
                     proxy_client_sub: inner.proxy_client_subs.inbound_server_data.clone(),
                     logger: inner.logger.clone(),
                     channel_factory: Box::new(FuturesChannelFactoryReal {}),
+                    bandwidth_shaper: BandwidthShaper::default(),
                 };
 
                 inner.establisher_factory = Box::new(StreamEstablisherFactoryMock {
@@ -1170,17 +1245,19 @@ mod tests {
         let stream_key = StreamKey::make_meaningful_stream_key(test_name);
         thread::spawn(move || {
             let peer_actors = peer_actors_builder().proxy_client(proxy_client).build();
-            let client_request_payload = ClientRequestPayload_0v1 {
+            let client_request_payload = ClientRequestPayload_0v2 {
                 stream_key: stream_key.clone(),
                 sequenced_packet: SequencedPacket {
                     data: b"These are the times".to_vec(),
                     sequence_number: 0,
                     last_data: false,
+                    relayed: false,
                 },
                 target_hostname: None,
                 target_port: HTTP_PORT,
                 protocol: ProxyProtocol::HTTP,
                 originator_public_key: originator_key,
+                next_expected_sequence_opt: None,
             };
             let package = ExpiredCoresPackage::new(
                 SocketAddr::from_str("1.2.3.4:1234").unwrap(),
@@ -1198,6 +1275,7 @@ mod tests {
                 peer_actors.proxy_client_opt.unwrap().clone(),
                 100,
                 200,
+                BandwidthShaper::default(),
             );
 
             run_process_package_in_actix(subject, package);
@@ -1239,17 +1317,19 @@ mod tests {
                 .proxy_client(proxy_client)
                 .accountant(accountant)
                 .build();
-            let client_request_payload = ClientRequestPayload_0v1 {
+            let client_request_payload = ClientRequestPayload_0v2 {
                 stream_key: StreamKey::make_meaningless_stream_key(),
                 sequenced_packet: SequencedPacket {
                     data: b"These are the times".to_vec(),
                     sequence_number: 0,
                     last_data: false,
+                    relayed: false,
                 },
                 target_hostname: Some(String::from("that.try")),
                 target_port: HTTP_PORT,
                 protocol: ProxyProtocol::HTTP,
                 originator_public_key: PublicKey::new(&b"men's souls"[..]),
+                next_expected_sequence_opt: None,
             };
             let package = ExpiredCoresPackage::new(
                 SocketAddr::from_str("1.2.3.4:1234").unwrap(),
@@ -1287,6 +1367,7 @@ mod tests {
                 peer_actors.proxy_client_opt.unwrap().clone(),
                 100,
                 200,
+                BandwidthShaper::default(),
             );
             let (stream_killer_tx, stream_killer_rx) = unbounded();
             subject.stream_killer_rx = stream_killer_rx;
@@ -1306,6 +1387,7 @@ mod tests {
                     proxy_client_sub: inner.proxy_client_subs.inbound_server_data.clone(),
                     logger: inner.logger.clone(),
                     channel_factory: Box::new(FuturesChannelFactoryReal {}),
+                    bandwidth_shaper: BandwidthShaper::default(),
                 };
 
                 inner.establisher_factory = Box::new(StreamEstablisherFactoryMock {
@@ -1352,17 +1434,19 @@ mod tests {
         let originator_key = PublicKey::new(&b"men's souls"[..]);
         thread::spawn(move || {
             let peer_actors = peer_actors_builder().proxy_client(proxy_client).build();
-            let client_request_payload = ClientRequestPayload_0v1 {
+            let client_request_payload = ClientRequestPayload_0v2 {
                 stream_key,
                 sequenced_packet: SequencedPacket {
                     data: b"These are the times".to_vec(),
                     sequence_number: 0,
                     last_data: false,
+                    relayed: false,
                 },
                 target_hostname: Some(String::from("that.try")),
                 target_port: HTTP_PORT,
                 protocol: ProxyProtocol::HTTP,
                 originator_public_key: originator_key,
+                next_expected_sequence_opt: None,
             };
             let package = ExpiredCoresPackage::new(
                 SocketAddr::from_str("1.2.3.4:1234").unwrap(),
@@ -1389,6 +1473,7 @@ mod tests {
                 peer_actors.proxy_client_opt.clone().unwrap(),
                 100,
                 200,
+                BandwidthShaper::default(),
             );
             let (stream_killer_tx, stream_killer_rx) = unbounded();
             subject.stream_killer_rx = stream_killer_rx;
@@ -1404,6 +1489,7 @@ mod tests {
                 proxy_client_sub,
                 logger: subject.inner.lock().unwrap().logger.clone(),
                 channel_factory: Box::new(FuturesChannelFactoryReal {}),
+                bandwidth_shaper: BandwidthShaper::default(),
             };
 
             subject.inner.lock().unwrap().establisher_factory =
@@ -1474,17 +1560,19 @@ mod tests {
         let originator_key = PublicKey::new(&b"men's souls"[..]);
         thread::spawn(move || {
             let peer_actors = peer_actors_builder().proxy_client(proxy_client).build();
-            let client_request_payload = ClientRequestPayload_0v1 {
+            let client_request_payload = ClientRequestPayload_0v2 {
                 stream_key,
                 sequenced_packet: SequencedPacket {
                     data: b"These are the times".to_vec(),
                     sequence_number: 0,
                     last_data: false,
+                    relayed: false,
                 },
                 target_hostname: Some(String::from("blockedwebsite.com")),
                 target_port: HTTP_PORT,
                 protocol: ProxyProtocol::HTTP,
                 originator_public_key: originator_key,
+                next_expected_sequence_opt: None,
             };
             let package = ExpiredCoresPackage::new(
                 SocketAddr::from_str("1.2.3.4:1234").unwrap(),
@@ -1508,6 +1596,7 @@ mod tests {
                 peer_actors.proxy_client_opt.clone().unwrap(),
                 100,
                 200,
+                BandwidthShaper::default(),
             );
             let (stream_killer_tx, stream_killer_rx) = unbounded();
             subject.stream_killer_rx = stream_killer_rx;
@@ -1526,6 +1615,7 @@ mod tests {
                 proxy_client_sub,
                 logger: subject.inner.lock().unwrap().logger.clone(),
                 channel_factory: Box::new(FuturesChannelFactoryReal {}),
+                bandwidth_shaper: BandwidthShaper::default(),
             };
 
             subject.inner.lock().unwrap().establisher_factory =
@@ -1573,15 +1663,17 @@ mod tests {
                 data: b"These are the times".to_vec(),
                 sequence_number: 0,
                 last_data: false,
+                relayed: false,
             };
 
-            let client_request_payload = ClientRequestPayload_0v1 {
+            let client_request_payload = ClientRequestPayload_0v2 {
                 stream_key,
                 sequenced_packet: sequenced_packet.clone(),
                 target_hostname: Some(String::from("that.try")),
                 target_port: HTTP_PORT,
                 protocol: ProxyProtocol::HTTP,
                 originator_public_key: PublicKey::new(&b"men's souls"[..]),
+                next_expected_sequence_opt: None,
             };
 
             let package = ExpiredCoresPackage::new(
@@ -1618,6 +1710,7 @@ mod tests {
                 peer_actors.proxy_client_opt.clone().unwrap(),
                 100,
                 200,
+                BandwidthShaper::default(),
             );
 
             let peer_addr = SocketAddr::from_str("3.4.5.6:80").unwrap();
@@ -1645,6 +1738,7 @@ mod tests {
                         .unwrap()
                         .inbound_server_data,
                     logger: inner.logger.clone(),
+                    bandwidth_shaper: BandwidthShaper::default(),
                     channel_factory: Box::new(FuturesChannelFactoryMock {
                         results: vec![(
                             disconnected_sender,
@@ -1684,17 +1778,19 @@ mod tests {
         let (proxy_client, proxy_client_awaiter, proxy_client_recording_arc) = make_recorder();
         let originator_key = PublicKey::new(&b"men's souls"[..]);
         thread::spawn(move || {
-            let client_request_payload = ClientRequestPayload_0v1 {
+            let client_request_payload = ClientRequestPayload_0v2 {
                 stream_key,
                 sequenced_packet: SequencedPacket {
                     data: b"These are the times".to_vec(),
                     sequence_number: 0,
                     last_data: true,
+                    relayed: false,
                 },
                 target_hostname: Some(String::from("that.try")),
                 target_port: HTTP_PORT,
                 protocol: ProxyProtocol::HTTP,
                 originator_public_key: originator_key,
+                next_expected_sequence_opt: None,
             };
             let package = ExpiredCoresPackage::new(
                 SocketAddr::from_str("1.2.3.4:1234").unwrap(),
@@ -1715,6 +1811,7 @@ mod tests {
                 peer_actors.proxy_client_opt.unwrap().clone(),
                 100,
                 200,
+                BandwidthShaper::default(),
             );
             subject.inner.lock().unwrap().logger =
                 Logger::new("bad_dns_lookup_produces_log_and_sends_error_response");
@@ -1749,14 +1846,16 @@ mod tests {
             data: b"These are the times".to_vec(),
             sequence_number: 0,
             last_data: true,
+            relayed: false,
         };
-        let client_request_payload = ClientRequestPayload_0v1 {
+        let client_request_payload = ClientRequestPayload_0v2 {
             stream_key: stream_key.clone(),
             sequenced_packet: sequenced_packet.clone(),
             target_hostname: Some(String::from("that.try")),
             target_port: HTTP_PORT,
             protocol: ProxyProtocol::HTTP,
             originator_public_key: PublicKey::new(&b"men's souls"[..]),
+            next_expected_sequence_opt: None,
         };
         let package = ExpiredCoresPackage::new(
             SocketAddr::from_str("1.2.3.4:1234").unwrap(),
@@ -1784,6 +1883,7 @@ mod tests {
                 peer_actors.proxy_client_opt.unwrap().clone(),
                 100,
                 200,
+                BandwidthShaper::default(),
             );
             subject.inner.lock().unwrap().stream_writer_channels.insert(
                 stream_key,
@@ -1817,17 +1917,19 @@ mod tests {
                 .hopper(hopper)
                 .accountant(accountant)
                 .build();
-            let client_request_payload = ClientRequestPayload_0v1 {
+            let client_request_payload = ClientRequestPayload_0v2 {
                 stream_key: stream_key.clone(),
                 sequenced_packet: SequencedPacket {
                     data: vec![],
                     sequence_number: 0,
                     last_data: false,
+                    relayed: false,
                 },
                 target_hostname: None,
                 target_port: HTTP_PORT,
                 protocol: ProxyProtocol::HTTP,
                 originator_public_key: PublicKey::new(&b"booga"[..]),
+                next_expected_sequence_opt: None,
             };
             let package = ExpiredCoresPackage::new(
                 SocketAddr::from_str("1.2.3.4:1234").unwrap(),
@@ -1844,6 +1946,7 @@ mod tests {
                 peer_actors.proxy_client_opt.unwrap().clone(),
                 100,
                 200,
+                BandwidthShaper::default(),
             );
 
             subject.inner.lock().unwrap().establisher_factory =
@@ -1880,6 +1983,7 @@ mod tests {
             peer_actors.proxy_client_opt.unwrap(),
             0,
             0,
+            BandwidthShaper::default(),
         );
         let (stream_killer_tx, stream_killer_rx) = unbounded();
         subject.stream_killer_rx = stream_killer_rx;
@@ -1931,6 +2035,7 @@ mod tests {
             peer_actors.proxy_client_opt.unwrap(),
             0,
             0,
+            BandwidthShaper::default(),
         );
         let (stream_killer_tx, stream_killer_rx) = unbounded();
         subject.stream_killer_rx = stream_killer_rx;
@@ -1973,6 +2078,7 @@ mod tests {
             peer_actors.proxy_client_opt.unwrap(),
             0,
             0,
+            BandwidthShaper::default(),
         );
         let (stream_killer_tx, stream_killer_rx) = unbounded();
         subject.stream_killer_rx = stream_killer_rx;
@@ -2000,6 +2106,7 @@ mod tests {
             peer_actors.proxy_client_opt.unwrap(),
             0,
             0,
+            BandwidthShaper::default(),
         );
         subject.stream_adder_rx = stream_adder_rx;
         {
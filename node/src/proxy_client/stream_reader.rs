@@ -1,4 +1,5 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use crate::sub_lib::bandwidth_shaper::{BandwidthShaper, BandwidthThrottle, TrafficKind};
 use crate::sub_lib::proxy_client::InboundServerData;
 use crate::sub_lib::sequencer::Sequencer;
 use crate::sub_lib::stream_key::StreamKey;
@@ -7,6 +8,7 @@ use crate::sub_lib::utils::indicates_dead_stream;
 use actix::Recipient;
 use crossbeam_channel::{Receiver, Sender};
 use masq_lib::logger::Logger;
+use masq_lib::redaction::MarkSensitive;
 use std::net::SocketAddr;
 use tokio::prelude::Async;
 use tokio::prelude::Future;
@@ -20,6 +22,7 @@ pub struct StreamReader {
     peer_addr: SocketAddr,
     logger: Logger,
     sequencer: Sequencer,
+    throttle: BandwidthThrottle,
 }
 
 impl Future for StreamReader {
@@ -36,24 +39,29 @@ impl Future for StreamReader {
                 );
                 return Ok(Async::Ready(()));
             }
+            if self.throttle.poll_permit() == Async::NotReady {
+                return Ok(Async::NotReady);
+            }
             match self.stream.poll_read(&mut buf) {
                 Ok(Async::NotReady) => return Ok(Async::NotReady),
                 Ok(Async::Ready(0)) => {
                     // see RETURN VALUE section of recv man page (Unix)
                     debug!(
                         self.logger,
-                        "Stream from {} was closed: (0-byte read)", self.peer_addr
+                        "Stream from {} was closed: (0-byte read)",
+                        self.peer_addr.sensitive()
                     );
                     self.shutdown();
                     return Ok(Async::Ready(()));
                 }
                 Ok(Async::Ready(len)) => {
+                    self.throttle.record_transfer(len);
                     if self.logger.trace_enabled() {
                         trace!(
                             self.logger,
                             "Read {}-byte chunk from {}",
                             len,
-                            self.peer_addr
+                            self.peer_addr.sensitive()
                         );
                     }
                     let stream_key = self.stream_key;
@@ -63,7 +71,9 @@ impl Future for StreamReader {
                     if indicates_dead_stream(e.kind()) {
                         debug!(
                             self.logger,
-                            "Stream from {} was closed: {}", self.peer_addr, e
+                            "Stream from {} was closed: {}",
+                            self.peer_addr.sensitive(),
+                            e
                         );
                         self.shutdown();
                         return Err(());
@@ -72,7 +82,7 @@ impl Future for StreamReader {
                         warning!(
                             self.logger,
                             "Continuing after read error on stream from {}: {}",
-                            self.peer_addr,
+                            self.peer_addr.sensitive(),
                             e
                         );
                     }
@@ -90,9 +100,12 @@ impl StreamReader {
         stream_killer: Sender<(StreamKey, u64)>,
         shutdown_signal: Receiver<()>,
         peer_addr: SocketAddr,
+        bandwidth_shaper: BandwidthShaper,
     ) -> StreamReader {
         let logger = Logger::new(&format!("StreamReader for {:?}/{}", stream_key, peer_addr)[..]);
         debug!(logger, "Initialised StreamReader");
+        let throttle =
+            BandwidthThrottle::new(bandwidth_shaper, TrafficKind::Exit, None, logger.clone());
         StreamReader {
             stream_key,
             proxy_client_sub,
@@ -102,6 +115,7 @@ impl StreamReader {
             peer_addr,
             logger,
             sequencer: Sequencer::new(),
+            throttle,
         }
     }
 
@@ -127,6 +141,7 @@ impl StreamReader {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::sub_lib::bandwidth_shaper::{BandwidthLimits, DataQuota};
     use crate::test_utils::recorder::make_recorder;
     use crate::test_utils::recorder::peer_actors_builder;
     use crate::test_utils::tokio_wrapper_mocks::ReadHalfWrapperMock;
@@ -139,6 +154,7 @@ mod tests {
     use std::net::SocketAddr;
     use std::str::FromStr;
     use std::thread;
+    use tokio::prelude::future;
 
     #[test]
     fn stream_reader_assigns_a_sequence_to_client_response_payloads() {
@@ -189,6 +205,7 @@ mod tests {
             peer_addr: SocketAddr::from_str("8.7.4.3:50").unwrap(),
             logger: Logger::new("test"),
             sequencer: Sequencer::new(),
+            throttle: make_unlimited_throttle(),
         };
 
         let _res = subject.poll();
@@ -340,6 +357,7 @@ mod tests {
             peer_addr,
             logger: Logger::new(test_name),
             sequencer,
+            throttle: make_unlimited_throttle(),
         };
         System::current().stop();
         system.run();
@@ -393,6 +411,7 @@ mod tests {
             peer_addr,
             logger: Logger::new(test_name),
             sequencer: Sequencer::new(),
+            throttle: make_unlimited_throttle(),
         };
 
         let result = subject.poll();
@@ -444,6 +463,47 @@ mod tests {
             peer_addr: SocketAddr::from_str("9.8.7.6:5432").unwrap(),
             logger: Logger::new("test"),
             sequencer: Sequencer::new(),
+            throttle: make_unlimited_throttle(),
         }
     }
+
+    fn make_unlimited_throttle() -> BandwidthThrottle {
+        BandwidthThrottle::new(
+            BandwidthShaper::default(),
+            TrafficKind::Exit,
+            None,
+            Logger::new("test"),
+        )
+    }
+
+    #[test]
+    fn stream_reader_stops_reading_while_exit_bandwidth_is_exhausted() {
+        let shaper = BandwidthShaper::new(
+            BandwidthLimits {
+                exit_kb_per_sec: 1,
+                ..BandwidthLimits::default()
+            },
+            DataQuota::default(),
+        );
+        let mut stream = ReadHalfWrapperMock::new();
+        stream.poll_read_results = vec![
+            (vec![0u8; 3000], Ok(Async::Ready(3000))),
+            (b"held back".to_vec(), Ok(Async::Ready(9))),
+        ];
+        let mut subject = make_subject();
+        subject.stream = Box::new(stream);
+        subject.throttle =
+            BandwidthThrottle::new(shaper, TrafficKind::Exit, None, Logger::new("test"));
+
+        let (result, mut subject) = tokio::runtime::current_thread::Runtime::new()
+            .unwrap()
+            .block_on(future::lazy(move || {
+                let result = subject.poll();
+                Ok::<_, ()>((result, subject))
+            }))
+            .unwrap();
+
+        assert_eq!(result, Ok(Async::NotReady));
+        assert_eq!(subject.sequencer.next_sequence_number(), 1);
+    }
 }
@@ -6,6 +6,7 @@ use crate::sub_lib::stream_key::StreamKey;
 use crate::sub_lib::tokio_wrappers::WriteHalfWrapper;
 use crate::sub_lib::utils::indicates_dead_stream;
 use masq_lib::logger::Logger;
+use masq_lib::redaction::MarkSensitive;
 use std::net::SocketAddr;
 use tokio::prelude::Async;
 use tokio::prelude::Future;
@@ -138,7 +139,7 @@ impl StreamWriter {
                                     packet.last_data,
                                 ));
                             } else if packet.last_data {
-                                debug!(self.logger, "Shutting down stream to server at {} in response to client-drop report", self.peer_addr);
+                                debug!(self.logger, "Shutting down stream to server at {} in response to client-drop report", self.peer_addr.sensitive());
                                 self.shutting_down = true;
                                 return self.shutdown();
                             }
@@ -178,21 +179,25 @@ mod tests {
                 data: packet_c.to_vec(),
                 sequence_number: 2,
                 last_data: false,
+                relayed: false,
             }))),
             Ok(Async::Ready(Some(SequencedPacket {
                 data: packet_b.to_vec(),
                 sequence_number: 1,
                 last_data: false,
+                relayed: false,
             }))),
             Ok(Async::Ready(Some(SequencedPacket {
                 data: vec![],
                 sequence_number: 3,
                 last_data: false,
+                relayed: false,
             }))),
             Ok(Async::Ready(Some(SequencedPacket {
                 data: packet_a.to_vec(),
                 sequence_number: 0,
                 last_data: false,
+                relayed: false,
             }))),
             Ok(Async::Ready(None)),
         ];
@@ -272,6 +277,7 @@ mod tests {
                 data: b"These are the times".to_vec(),
                 sequence_number: 0,
                 last_data: false,
+                relayed: false,
             }))),
             Ok(Async::Ready(None)),
         ];
@@ -323,6 +329,7 @@ mod tests {
                 data: text_data.to_vec(),
                 sequence_number: 0,
                 last_data: false,
+                relayed: false,
             }))),
             Ok(Async::NotReady),
         ];
@@ -358,11 +365,13 @@ mod tests {
                 data: first_data.to_vec(),
                 sequence_number: 0,
                 last_data: false,
+                relayed: false,
             }))),
             Ok(Async::Ready(Some(SequencedPacket {
                 data: second_data.to_vec(),
                 sequence_number: 1,
                 last_data: false,
+                relayed: false,
             }))),
             Ok(Async::NotReady),
         ];
@@ -397,6 +406,7 @@ mod tests {
                 data: b"These are the times".to_vec(),
                 sequence_number: 0,
                 last_data: false,
+                relayed: false,
             }))),
             Ok(Async::Ready(None)),
         ];
@@ -438,6 +448,7 @@ mod tests {
                 data: b"These are the times".to_vec(),
                 sequence_number: 0,
                 last_data: false,
+                relayed: false,
             }))),
             Ok(Async::Ready(None)),
         ];
@@ -471,6 +482,7 @@ mod tests {
                 data: b"These are the times".to_vec(),
                 sequence_number: 0,
                 last_data: false,
+                relayed: false,
             }))),
             Ok(Async::NotReady),
             Ok(Async::NotReady),
@@ -549,6 +561,7 @@ mod tests {
                 data: packet_a.to_vec(),
                 sequence_number: 0,
                 last_data: true,
+                relayed: false,
             }))),
             Ok(Async::Ready(None)),
         ];
@@ -601,6 +614,7 @@ mod tests {
                 data: packet_a.to_vec(),
                 sequence_number: 0,
                 last_data: true,
+                relayed: false,
             }))),
             Ok(Async::Ready(None)),
         ];
@@ -658,6 +672,7 @@ mod tests {
                 data: packet_a.to_vec(),
                 sequence_number: 0,
                 last_data: true,
+                relayed: false,
             }))),
             Ok(Async::Ready(None)),
         ];
@@ -3,7 +3,7 @@ use crate::proxy_server::protocol_pack::from_ibcd;
 use crate::sub_lib::cryptde::CryptDE;
 use crate::sub_lib::cryptde::PlainData;
 use crate::sub_lib::dispatcher::InboundClientData;
-use crate::sub_lib::proxy_server::ClientRequestPayload_0v1;
+use crate::sub_lib::proxy_server::ClientRequestPayload_0v2;
 use crate::sub_lib::sequence_buffer::SequencedPacket;
 use crate::sub_lib::stream_key::StreamKey;
 use masq_lib::logger::Logger;
@@ -15,7 +15,7 @@ pub trait ClientRequestPayloadFactory {
         stream_key: StreamKey,
         cryptde: &dyn CryptDE,
         logger: &Logger,
-    ) -> Option<ClientRequestPayload_0v1>;
+    ) -> Option<ClientRequestPayload_0v2>;
 }
 
 #[derive(Default)]
@@ -28,7 +28,7 @@ impl ClientRequestPayloadFactory for ClientRequestPayloadFactoryReal {
         stream_key: StreamKey,
         cryptde: &dyn CryptDE,
         logger: &Logger,
-    ) -> Option<ClientRequestPayload_0v1> {
+    ) -> Option<ClientRequestPayload_0v2> {
         let protocol_pack = from_ibcd(ibcd).map_err(|e| error!(logger, "{}", e)).ok()?;
         let sequence_number = match ibcd.sequence_number {
             Some(sequence_number) => sequence_number,
@@ -50,17 +50,19 @@ impl ClientRequestPayloadFactory for ClientRequestPayloadFactoryReal {
             },
             None => (None, protocol_pack.standard_port()),
         };
-        Some(ClientRequestPayload_0v1 {
+        Some(ClientRequestPayload_0v2 {
             stream_key,
             sequenced_packet: SequencedPacket {
                 data: ibcd.data.clone(),
                 sequence_number,
                 last_data: ibcd.last_data,
+                relayed: false,
             },
             target_hostname,
             target_port,
             protocol: protocol_pack.proxy_protocol(),
             originator_public_key: cryptde.public_key().clone(),
+            next_expected_sequence_opt: None,
         })
     }
 }
@@ -104,17 +106,19 @@ mod tests {
 
         assert_eq!(
             result,
-            Some(ClientRequestPayload_0v1 {
+            Some(ClientRequestPayload_0v2 {
                 stream_key,
                 sequenced_packet: SequencedPacket {
                     data: data.into(),
                     sequence_number: 1,
-                    last_data: false
+                    last_data: false,
+                    relayed: false,
                 },
                 target_hostname: Some(String::from("borkoed.com")),
                 target_port: 2345,
                 protocol: ProxyProtocol::HTTP,
                 originator_public_key: cryptde.public_key().clone(),
+                next_expected_sequence_opt: None,
             })
         );
     }
@@ -141,17 +145,19 @@ mod tests {
 
         assert_eq!(
             result,
-            Some(ClientRequestPayload_0v1 {
+            Some(ClientRequestPayload_0v2 {
                 stream_key,
                 sequenced_packet: SequencedPacket {
                     data: data.into(),
                     sequence_number: 1,
-                    last_data: false
+                    last_data: false,
+                    relayed: false,
                 },
                 target_hostname: Some(String::from("borkoed.com")),
                 target_port: HTTP_PORT,
                 protocol: ProxyProtocol::HTTP,
                 originator_public_key: cryptde.public_key().clone(),
+                next_expected_sequence_opt: None,
             })
         );
     }
@@ -196,17 +202,19 @@ mod tests {
 
         assert_eq!(
             result,
-            Some(ClientRequestPayload_0v1 {
+            Some(ClientRequestPayload_0v2 {
                 stream_key,
                 sequenced_packet: SequencedPacket {
                     data: data.into(),
                     sequence_number: 0,
-                    last_data: false
+                    last_data: false,
+                    relayed: false,
                 },
                 target_hostname: Some(String::from("server.com")),
                 target_port: 443,
                 protocol: ProxyProtocol::TLS,
                 originator_public_key: cryptde.public_key().clone(),
+                next_expected_sequence_opt: None,
             })
         );
     }
@@ -246,17 +254,19 @@ mod tests {
 
         assert_eq!(
             result,
-            Some(ClientRequestPayload_0v1 {
+            Some(ClientRequestPayload_0v2 {
                 stream_key,
                 sequenced_packet: SequencedPacket {
                     data: data.into(),
                     sequence_number: 0,
-                    last_data: true
+                    last_data: true,
+                    relayed: false,
                 },
                 target_hostname: None,
                 target_port: 443,
                 protocol: ProxyProtocol::TLS,
                 originator_public_key: cryptde.public_key().clone(),
+                next_expected_sequence_opt: None,
             })
         );
     }
@@ -3,6 +3,8 @@
 pub mod client_request_payload_factory;
 pub mod http_protocol_pack;
 pub mod protocol_pack;
+pub mod route_failover;
+pub mod route_isolation;
 pub mod server_impersonator_http;
 pub mod server_impersonator_tls;
 pub mod tls_protocol_pack;
@@ -11,7 +13,11 @@ use crate::proxy_server::client_request_payload_factory::{
     ClientRequestPayloadFactory, ClientRequestPayloadFactoryReal,
 };
 use crate::proxy_server::http_protocol_pack::HttpProtocolPack;
-use crate::proxy_server::protocol_pack::{from_ibcd, from_protocol, ProtocolPack};
+use crate::proxy_server::protocol_pack::{
+    from_ibcd, from_protocol, ProtocolPack, ServerImpersonator,
+};
+use crate::proxy_server::route_failover::{StreamReplay, ROUTE_STALL_TIMEOUT, STALL_SCAN_INTERVAL};
+use crate::proxy_server::route_isolation::{RouteIsolation, DEFAULT_ROUTE_LIFETIME};
 use crate::proxy_server::ExitServiceSearch::{Definite, ZeroHop};
 use crate::stream_messages::NonClandestineAttributes;
 use crate::stream_messages::RemovedStreamType;
@@ -24,15 +30,19 @@ use crate::sub_lib::dispatcher::InboundClientData;
 use crate::sub_lib::dispatcher::{Endpoint, StreamShutdownMsg};
 use crate::sub_lib::hopper::{ExpiredCoresPackage, IncipientCoresPackage};
 use crate::sub_lib::neighborhood::RouteQueryResponse;
+use crate::sub_lib::neighborhood::{ConfigChange, ConfigChangeMsg};
 use crate::sub_lib::neighborhood::{ExpectedService, UpdateNodeRecordMetadataMessage};
 use crate::sub_lib::neighborhood::{ExpectedServices, RatePack};
 use crate::sub_lib::neighborhood::{NRMetadataChange, RouteQueryMessage};
 use crate::sub_lib::peer_actors::BindMessage;
-use crate::sub_lib::proxy_client::{ClientResponsePayload_0v1, DnsResolveFailure_0v1};
+use crate::sub_lib::proxy_client::{ClientResponsePayload_0v2, DnsResolveFailure_0v1};
 use crate::sub_lib::proxy_server::ProxyServerSubs;
 use crate::sub_lib::proxy_server::{AddReturnRouteMessage, StreamKeyPurge};
 use crate::sub_lib::proxy_server::{
-    AddRouteResultMessage, ClientRequestPayload_0v1, ProxyProtocol,
+    AddRouteResultMessage, ClientRequestPayload_0v2, ProxyProtocol,
+};
+use crate::sub_lib::proxy_server::{
+    RouteFailoverResult, ScanForStalledRoutes, SpendingBudgetStatusMsg,
 };
 use crate::sub_lib::route::Route;
 use crate::sub_lib::stream_handler_pool::TransmitDataMsg;
@@ -46,6 +56,7 @@ use actix::Recipient;
 use actix::{Actor, MailboxError};
 use actix::{Addr, AsyncContext};
 use masq_lib::logger::Logger;
+use masq_lib::redaction::MarkSensitive;
 use masq_lib::ui_gateway::NodeFromUiMessage;
 use masq_lib::utils::MutabilityConflictHelper;
 use regex::Regex;
@@ -81,8 +92,13 @@ pub struct ProxyServer {
     dns_failure_retries: HashMap<StreamKey, DNSFailureRetry>,
     stream_key_routes: HashMap<StreamKey, RouteQueryResponse>,
     stream_key_ttl: HashMap<StreamKey, SystemTime>,
+    route_isolation: RouteIsolation,
+    stream_replays: HashMap<StreamKey, StreamReplay>,
+    route_stall_timeout: Duration,
+    stall_scan_interval: Duration,
     is_decentralized: bool,
     consuming_wallet_balance: Option<i64>,
+    spending_blocked_until_opt: Option<SystemTime>,
     main_cryptde: &'static dyn CryptDE,
     alias_cryptde: &'static dyn CryptDE,
     crashable: bool,
@@ -114,6 +130,9 @@ impl Handler<BindMessage> for ProxyServer {
             schedule_stream_key_purge: msg.peer_actors.proxy_server.schedule_stream_key_purge,
         };
         self.subs = Some(subs);
+        if self.is_decentralized {
+            ctx.notify_later(ScanForStalledRoutes {}, self.stall_scan_interval);
+        }
     }
 }
 
@@ -172,15 +191,20 @@ impl Handler<AddRouteResultMessage> for ProxyServer {
                 debug!(
                     self.logger,
                     "Found a new route for hostname: {:?} - stream key: {}  retries left: {}",
-                    dns_failure.unsuccessful_request.target_hostname,
+                    dns_failure.unsuccessful_request.target_hostname.sensitive(),
                     msg.stream_key,
                     dns_failure.retries_left
                 );
+                self.route_isolation.record_route(
+                    dns_failure.unsuccessful_request.target_hostname.as_deref(),
+                    &route_query_response,
+                    SystemTime::now(),
+                );
                 self.stream_key_routes
                     .insert(msg.stream_key, route_query_response);
             }
             Err(e) => {
-                warning!(self.logger, "No route found for hostname: {:?} - stream key {} - retries left: {} - AddRouteResultMessage Error: {}",dns_failure.unsuccessful_request.target_hostname, msg.stream_key, dns_failure.retries_left, e);
+                warning!(self.logger, "No route found for hostname: {:?} - stream key {} - retries left: {} - AddRouteResultMessage Error: {}",dns_failure.unsuccessful_request.target_hostname.sensitive(), msg.stream_key, dns_failure.retries_left, e);
             }
         }
     }
@@ -198,12 +222,12 @@ impl Handler<ExpiredCoresPackage<DnsResolveFailure_0v1>> for ProxyServer {
     }
 }
 
-impl Handler<ExpiredCoresPackage<ClientResponsePayload_0v1>> for ProxyServer {
+impl Handler<ExpiredCoresPackage<ClientResponsePayload_0v2>> for ProxyServer {
     type Result = ();
 
     fn handle(
         &mut self,
-        msg: ExpiredCoresPackage<ClientResponsePayload_0v1>,
+        msg: ExpiredCoresPackage<ClientResponsePayload_0v2>,
         _ctx: &mut Self::Context,
     ) -> Self::Result {
         self.handle_client_response_payload(msg)
@@ -234,6 +258,39 @@ impl Handler<StreamKeyPurge> for ProxyServer {
     }
 }
 
+impl Handler<ScanForStalledRoutes> for ProxyServer {
+    type Result = ();
+
+    fn handle(&mut self, _msg: ScanForStalledRoutes, ctx: &mut Self::Context) -> Self::Result {
+        self.fail_over_stalled_streams(ctx.address());
+        ctx.notify_later(ScanForStalledRoutes {}, self.stall_scan_interval);
+    }
+}
+
+impl Handler<RouteFailoverResult> for ProxyServer {
+    type Result = ();
+
+    fn handle(&mut self, msg: RouteFailoverResult, _ctx: &mut Self::Context) -> Self::Result {
+        self.handle_route_failover_result(msg)
+    }
+}
+
+impl Handler<SpendingBudgetStatusMsg> for ProxyServer {
+    type Result = ();
+
+    fn handle(&mut self, msg: SpendingBudgetStatusMsg, _ctx: &mut Self::Context) -> Self::Result {
+        self.handle_spending_budget_status(msg)
+    }
+}
+
+impl Handler<ConfigChangeMsg> for ProxyServer {
+    type Result = ();
+
+    fn handle(&mut self, msg: ConfigChangeMsg, _ctx: &mut Self::Context) -> Self::Result {
+        self.handle_config_change_msg(msg)
+    }
+}
+
 impl<M: actix::Message + 'static> Handler<MessageScheduler<M>> for ProxyServer
 where
     ProxyServer: Handler<M>,
@@ -262,8 +319,13 @@ impl ProxyServer {
             dns_failure_retries: HashMap::new(),
             stream_key_routes: HashMap::new(),
             stream_key_ttl: HashMap::new(),
+            route_isolation: RouteIsolation::new(DEFAULT_ROUTE_LIFETIME),
+            stream_replays: HashMap::new(),
+            route_stall_timeout: ROUTE_STALL_TIMEOUT,
+            stall_scan_interval: STALL_SCAN_INTERVAL,
             is_decentralized,
             consuming_wallet_balance,
+            spending_blocked_until_opt: None,
             main_cryptde,
             alias_cryptde,
             crashable,
@@ -275,17 +337,23 @@ impl ProxyServer {
         }
     }
 
+    pub fn set_route_lifetime(&mut self, route_lifetime: Duration) {
+        self.route_isolation = RouteIsolation::new(route_lifetime);
+    }
+
     pub fn make_subs_from(addr: &Addr<ProxyServer>) -> ProxyServerSubs {
         ProxyServerSubs {
             bind: recipient!(addr, BindMessage),
             from_dispatcher: recipient!(addr, InboundClientData),
-            from_hopper: recipient!(addr, ExpiredCoresPackage<ClientResponsePayload_0v1>),
+            from_hopper: recipient!(addr, ExpiredCoresPackage<ClientResponsePayload_0v2>),
             dns_failure_from_hopper: recipient!(addr, ExpiredCoresPackage<DnsResolveFailure_0v1>),
             add_return_route: recipient!(addr, AddReturnRouteMessage),
             stream_shutdown_sub: recipient!(addr, StreamShutdownMsg),
             node_from_ui: recipient!(addr, NodeFromUiMessage),
             route_result_sub: recipient!(addr, AddRouteResultMessage),
             schedule_stream_key_purge: recipient!(addr, MessageScheduler<StreamKeyPurge>),
+            spending_budget_status: recipient!(addr, SpendingBudgetStatusMsg),
+            config_change_msg_sub: recipient!(addr, ConfigChangeMsg),
         }
     }
 
@@ -314,6 +382,10 @@ impl ProxyServer {
             SystemTime::now(),
             false,
         );
+        let excluded_keys = self.route_isolation.keys_used_elsewhere(
+            retry.unsuccessful_request.target_hostname.as_deref(),
+            SystemTime::now(),
+        );
         let route_source = self.out_subs("Neighborhood").route_source.clone();
         let proxy_server_sub = self.out_subs("ProxyServer").route_result_sub.clone();
         let inbound_client_data_helper = self
@@ -321,7 +393,12 @@ impl ProxyServer {
             .as_ref()
             .expect("IBCDHelper uninitialized");
 
-        inbound_client_data_helper.request_route_and_transmit(args, route_source, proxy_server_sub);
+        inbound_client_data_helper.request_route_and_transmit(
+            args,
+            excluded_keys,
+            route_source,
+            proxy_server_sub,
+        );
         retry
     }
 
@@ -346,6 +423,7 @@ impl ProxyServer {
                 data: from_protocol(proxy_protocol)
                     .server_impersonator()
                     .dns_resolution_failure_response(hostname_opt),
+                relayed: false,
             })
             .expect("Dispatcher is dead");
     }
@@ -380,6 +458,8 @@ impl ProxyServer {
         match self.keys_and_addrs.a_to_b(&response.stream_key) {
             Some(client_addr) => {
                 if let Some(server_name) = hostname_opt.clone() {
+                    // New streams to this domain shouldn't share a route with a failing exit
+                    self.route_isolation.forget(&server_name);
                     self.subs
                         .as_ref()
                         .expect("Neighborhood unbound in ProxyServer")
@@ -477,7 +557,7 @@ impl ProxyServer {
 
     fn handle_client_response_payload(
         &mut self,
-        msg: ExpiredCoresPackage<ClientResponsePayload_0v1>,
+        msg: ExpiredCoresPackage<ClientResponsePayload_0v2>,
     ) {
         debug!(
             self.logger,
@@ -518,6 +598,17 @@ impl ProxyServer {
         if let Some(old_timestamp) = self.stream_key_ttl.get(&stream_key) {
             self.log_straggling_packet(&stream_key, payload_data_len, old_timestamp)
         }
+        if let Some(stream_replay) = self.stream_replays.get_mut(&stream_key) {
+            if !stream_replay.receive_response(&response, SystemTime::now()) {
+                debug!(
+                    self.logger,
+                    "Dropping replayed ClientResponsePayload: stream {}, sequence {}",
+                    stream_key,
+                    response.sequenced_packet.sequence_number
+                );
+                return;
+            }
+        }
         match self.keys_and_addrs.a_to_b(&stream_key) {
             Some(socket_addr) => {
                 let last_data = response.sequenced_packet.last_data;
@@ -534,6 +625,7 @@ impl ProxyServer {
                         last_data,
                         sequence_number,
                         data: response.sequenced_packet.data,
+                        relayed: false,
                     })
                     .expect("Dispatcher is dead");
                 if last_data {
@@ -572,6 +664,7 @@ impl ProxyServer {
                         last_data: false,
                         sequence_number: msg.sequence_number,
                         data: b"HTTP/1.1 200 OK\r\n\r\n".to_vec(),
+                        relayed: false,
                     })
                     .expect("Dispatcher is dead");
             }
@@ -585,6 +678,7 @@ impl ProxyServer {
                         last_data: true,
                         sequence_number: msg.sequence_number,
                         data: b"HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\n\r\n".to_vec(),
+                        relayed: false,
                     })
                     .expect("Dispatcher is dead");
             }
@@ -609,7 +703,7 @@ impl ProxyServer {
                 warning!(
                     self.logger,
                     "Received instruction to shut down nonexistent stream to peer {} - ignoring",
-                    msg.peer_addr
+                    msg.peer_addr.sensitive()
                 );
                 return;
             }
@@ -638,6 +732,59 @@ impl ProxyServer {
         }
     }
 
+    fn handle_spending_budget_status(&mut self, msg: SpendingBudgetStatusMsg) {
+        match (self.spending_blocked_until_opt, msg.exhausted_until_opt) {
+            (None, Some(_)) => info!(
+                self.logger,
+                "Spending budget exhausted; refusing new streams"
+            ),
+            (Some(_), None) => info!(
+                self.logger,
+                "Spending budget available again; accepting new streams"
+            ),
+            _ => (),
+        }
+        self.spending_blocked_until_opt = msg.exhausted_until_opt;
+    }
+
+    fn handle_config_change_msg(&mut self, msg: ConfigChangeMsg) {
+        match msg.change {
+            // Routes found under the old minimum may be too short for the new one
+            ConfigChange::UpdateMinHops(_) => self.route_isolation.forget_all(),
+            _ => trace!(self.logger, "Ignored irrelevant message: {:?}", msg),
+        }
+    }
+
+    // Streams that are already open may finish; only clients without a stream are turned away
+    fn refuses_new_stream_for_spending(&self, ibcd: &InboundClientData, now: SystemTime) -> bool {
+        match self.spending_blocked_until_opt {
+            Some(until) if now < until => {
+                self.is_decentralized && self.keys_and_addrs.b_to_a(&ibcd.peer_addr).is_none()
+            }
+            _ => false,
+        }
+    }
+
+    fn send_impersonated_refusal(
+        &self,
+        ibcd: &InboundClientData,
+        make_response: fn(&dyn ServerImpersonator) -> Vec<u8>,
+    ) -> Result<(), String> {
+        let protocol_pack = from_ibcd(ibcd)?;
+        let msg = TransmitDataMsg {
+            endpoint: Endpoint::Socket(ibcd.peer_addr),
+            last_data: true,
+            sequence_number: Some(0),
+            data: make_response(protocol_pack.server_impersonator().as_ref()),
+            relayed: false,
+        };
+        self.out_subs("Dispatcher")
+            .dispatcher
+            .try_send(msg)
+            .expect("Dispatcher is dead");
+        Ok(())
+    }
+
     fn find_or_generate_stream_key(&mut self, ibcd: &InboundClientData) -> StreamKey {
         match self.keys_and_addrs.b_to_a(&ibcd.peer_addr) {
             Some(stream_key) => {
@@ -645,7 +792,7 @@ impl ProxyServer {
                     self.logger,
                     "make_stream_key() retrieved existing key {} for {}",
                     &stream_key,
-                    ibcd.peer_addr
+                    ibcd.peer_addr.sensitive()
                 );
                 stream_key
             }
@@ -654,7 +801,9 @@ impl ProxyServer {
                 self.keys_and_addrs.insert(stream_key, ibcd.peer_addr);
                 debug!(
                     self.logger,
-                    "make_stream_key() inserted new key {} for {}", &stream_key, ibcd.peer_addr
+                    "make_stream_key() inserted new key {} for {}",
+                    &stream_key,
+                    ibcd.peer_addr.sensitive()
                 );
                 stream_key
             }
@@ -670,13 +819,138 @@ impl ProxyServer {
         let _ = self.stream_key_routes.remove(stream_key);
         let _ = self.tunneled_hosts.remove(stream_key);
         let _ = self.stream_key_ttl.remove(stream_key);
+        let _ = self.stream_replays.remove(stream_key);
+    }
+
+    fn prepare_for_replay(
+        &mut self,
+        payload: ClientRequestPayload_0v2,
+        now: SystemTime,
+    ) -> ClientRequestPayload_0v2 {
+        let hostname_opt = &payload.target_hostname;
+        self.stream_replays
+            .entry(payload.stream_key)
+            .or_insert_with(|| StreamReplay::new(hostname_opt.clone(), now))
+            .prepare_request(payload, now)
+    }
+
+    fn fail_over_stalled_streams(&mut self, proxy_server: Addr<ProxyServer>) {
+        let now = SystemTime::now();
+        let route_stall_timeout = self.route_stall_timeout;
+        let stalled_stream_keys = self
+            .stream_replays
+            .iter()
+            .filter(|(_, stream_replay)| stream_replay.is_stalled(now, route_stall_timeout))
+            .map(|(stream_key, _)| *stream_key)
+            .collect::<Vec<StreamKey>>();
+        stalled_stream_keys
+            .into_iter()
+            .for_each(|stream_key| self.request_failover_route(stream_key, proxy_server.clone()));
+    }
+
+    // The new route has to end at the same exit Node, since that's the one with the connection
+    // to the server.
+    fn request_failover_route(&mut self, stream_key: StreamKey, proxy_server: Addr<ProxyServer>) {
+        let old_route = match self.stream_key_routes.get(&stream_key) {
+            Some(route_query_response) => route_query_response,
+            None => return,
+        };
+        let exit_key = match route_failover::exit_key(old_route) {
+            Some(exit_key) => exit_key,
+            None => return,
+        };
+        let excluded_keys = route_failover::relay_keys(old_route);
+        let stream_replay = self
+            .stream_replays
+            .get_mut(&stream_key)
+            .expect("Stream replay disappeared");
+        stream_replay.begin_failover();
+        info!(
+            self.logger,
+            "Stream {} has stalled; looking for another route to its exit Node", stream_key
+        );
+        let route_query = RouteQueryMessage {
+            target_key_opt: Some(exit_key),
+            excluded_keys,
+            ..RouteQueryMessage::data_indefinite_route_request(
+                stream_replay
+                    .hostname_opt()
+                    .map(|hostname| hostname.to_string()),
+                0,
+            )
+        };
+        tokio::spawn(
+            self.out_subs("Neighborhood")
+                .route_source
+                .send(route_query)
+                .then(move |route_result| {
+                    proxy_server
+                        .try_send(RouteFailoverResult {
+                            stream_key,
+                            route_query_response_opt: route_result.ok().flatten(),
+                        })
+                        .expect("ProxyServer is dead");
+                    Ok(())
+                }),
+        );
+    }
+
+    fn handle_route_failover_result(&mut self, msg: RouteFailoverResult) {
+        let now = SystemTime::now();
+        let stream_key = msg.stream_key;
+        let stream_replay = match self.stream_replays.get_mut(&stream_key) {
+            Some(stream_replay) => stream_replay,
+            None => {
+                debug!(
+                    self.logger,
+                    "Stream {} was retired while its route was being replaced", stream_key
+                );
+                return;
+            }
+        };
+        let route_query_response = match msg.route_query_response_opt {
+            Some(route_query_response) => route_query_response,
+            None => {
+                stream_replay.abandon_failover(now);
+                warning!(
+                    self.logger,
+                    "Found no other route to the exit Node for stalled stream {}; will try again later",
+                    stream_key
+                );
+                return;
+            }
+        };
+        let payloads = stream_replay.complete_failover(now);
+        if let Some(hostname) = stream_replay.hostname_opt().map(|h| h.to_string()) {
+            // New streams to the domain shouldn't be opened on the route that failed
+            self.route_isolation.forget(&hostname);
+        }
+        self.stream_key_routes
+            .insert(stream_key, route_query_response.clone());
+        let client_addr = match self.keys_and_addrs.a_to_b(&stream_key) {
+            Some(client_addr) => client_addr,
+            None => return,
+        };
+        info!(
+            self.logger,
+            "Moved stream {} to a new route; resending {} unacknowledged requests",
+            stream_key,
+            payloads.len()
+        );
+        payloads.into_iter().for_each(|payload| {
+            let args = TryTransmitToHopperArgs::new(self, payload, client_addr, now, false);
+            if let Err(e) = ProxyServer::try_transmit_to_hopper(args, route_query_response.clone())
+            {
+                error!(self.logger, "{}", e)
+            }
+        });
     }
 
     fn make_payload(
         &mut self,
         ibcd: InboundClientData,
         stream_key: &StreamKey,
-    ) -> Result<ClientRequestPayload_0v1, String> {
+    ) -> Result<ClientRequestPayload_0v2, String> {
         let tunnelled_host = self.tunneled_hosts.get(stream_key);
         let new_ibcd = match tunnelled_host {
             Some(_) => InboundClientData {
@@ -693,7 +967,7 @@ impl ProxyServer {
         ) {
             None => Err("Couldn't create ClientRequestPayload".to_string()),
             Some(payload) => match tunnelled_host {
-                Some(hostname) => Ok(ClientRequestPayload_0v1 {
+                Some(hostname) => Ok(ClientRequestPayload_0v2 {
                     target_hostname: Some(hostname.clone()),
                     ..payload
                 }),
@@ -807,7 +1081,7 @@ impl ProxyServer {
         main_cryptde: &'static dyn CryptDE,
         hopper: &Recipient<IncipientCoresPackage>,
         timestamp: SystemTime,
-        payload: ClientRequestPayload_0v1,
+        payload: ClientRequestPayload_0v2,
         route: Route,
         expected_services: Vec<ExpectedService>,
         logger: &Logger,
@@ -839,7 +1113,8 @@ impl ProxyServer {
                 // Route found
                 debug!(
                     logger,
-                    "transmit to hopper with destination key {:?}", payload_destination_key
+                    "transmit to hopper with destination key {:?}",
+                    payload_destination_key.sensitive()
                 );
                 let payload_size = payload.sequenced_packet.data.len();
                 let stream_key = payload.stream_key;
@@ -890,7 +1165,7 @@ impl ProxyServer {
     }
 
     fn handle_route_failure(
-        payload: ClientRequestPayload_0v1,
+        payload: ClientRequestPayload_0v2,
         source_addr: SocketAddr,
         dispatcher: &Recipient<TransmitDataMsg>,
     ) -> String {
@@ -904,7 +1179,7 @@ impl ProxyServer {
     }
 
     fn send_route_failure(
-        payload: ClientRequestPayload_0v1,
+        payload: ClientRequestPayload_0v2,
         source_addr: SocketAddr,
         dispatcher: &Recipient<TransmitDataMsg>,
     ) {
@@ -916,11 +1191,12 @@ impl ProxyServer {
             last_data: true,
             sequence_number: Some(0),
             data,
+            relayed: false,
         };
         dispatcher.try_send(msg).expect("Dispatcher is dead");
     }
 
-    fn hostname(payload: &ClientRequestPayload_0v1) -> String {
+    fn hostname(payload: &ClientRequestPayload_0v2) -> String {
         match payload.target_hostname {
             Some(ref thn) => thn.clone(),
             None => "<unknown>".to_string(),
@@ -1031,6 +1307,7 @@ pub trait IBCDHelper {
     fn request_route_and_transmit(
         &self,
         tth_args: TryTransmitToHopperArgs,
+        excluded_keys: Vec<PublicKey>,
         route_source: Recipient<RouteQueryMessage>,
         proxy_server_sub: Recipient<AddRouteResultMessage>,
     );
@@ -1107,32 +1384,24 @@ impl IBCDHelper for IBCDHelperReal {
     ) -> Result<(), String> {
         let source_addr = msg.peer_addr;
         if proxy.consuming_wallet_balance.is_none() && proxy.is_decentralized {
-            let protocol_pack = match from_ibcd(&msg) {
-                Err(e) => return Err(e),
-                Ok(pp) => pp,
-            };
-            let data = protocol_pack
-                .server_impersonator()
-                .consuming_wallet_absent();
-            let msg = TransmitDataMsg {
-                endpoint: Endpoint::Socket(source_addr),
-                last_data: true,
-                sequence_number: Some(0),
-                data,
-            };
-            proxy
-                .out_subs("Dispatcher")
-                .dispatcher
-                .try_send(msg)
-                .expect("Dispatcher is dead");
+            proxy.send_impersonated_refusal(&msg, |si| si.consuming_wallet_absent())?;
             return Err("Browser request rejected due to missing consuming wallet".to_string());
         }
+        if proxy.refuses_new_stream_for_spending(&msg, SystemTime::now()) {
+            proxy.send_impersonated_refusal(&msg, |si| si.spending_budget_exhausted())?;
+            return Err("Browser request rejected due to exhausted spending budget".to_string());
+        }
         let stream_key = proxy.find_or_generate_stream_key(&msg);
         let timestamp = msg.timestamp;
         let payload = match proxy.make_payload(msg, &stream_key) {
             Ok(payload) => payload,
             Err(e) => return Err(e),
         };
+        let payload = if proxy.is_decentralized {
+            proxy.prepare_for_replay(payload, SystemTime::now())
+        } else {
+            payload
+        };
 
         if proxy.dns_failure_retries.get(&stream_key).is_none() {
             let dns_failure_retry = DNSFailureRetry {
@@ -1156,10 +1425,32 @@ impl IBCDHelper for IBCDHelperReal {
             );
             let route_query_response = route_query_response.clone();
             ProxyServer::try_transmit_to_hopper(tth_args, route_query_response)
+        } else if let Some(route_query_response) = proxy
+            .route_isolation
+            .shared_route(pld.target_hostname.as_deref(), SystemTime::now())
+        {
+            debug!(
+                proxy.logger,
+                "Opening new stream {} to {:?} on the route its domain is using",
+                pld.stream_key,
+                pld.target_hostname.sensitive()
+            );
+            proxy
+                .stream_key_routes
+                .insert(pld.stream_key, route_query_response.clone());
+            ProxyServer::try_transmit_to_hopper(tth_args, route_query_response)
         } else {
+            let excluded_keys = proxy
+                .route_isolation
+                .keys_used_elsewhere(pld.target_hostname.as_deref(), SystemTime::now());
             let route_source = proxy.out_subs("Neighborhood").route_source.clone();
             let proxy_server_sub = proxy.out_subs("ProxyServer").route_result_sub.clone();
-            self.request_route_and_transmit(tth_args, route_source, proxy_server_sub);
+            self.request_route_and_transmit(
+                tth_args,
+                excluded_keys,
+                route_source,
+                proxy_server_sub,
+            );
             Ok(())
         }
     }
@@ -1167,6 +1458,7 @@ impl IBCDHelper for IBCDHelperReal {
     fn request_route_and_transmit(
         &self,
         tth_args: TryTransmitToHopperArgs,
+        excluded_keys: Vec<PublicKey>,
         neighborhood_sub: Recipient<RouteQueryMessage>,
         proxy_server_sub: Recipient<AddRouteResultMessage>,
     ) {
@@ -1185,10 +1477,10 @@ impl IBCDHelper for IBCDHelperReal {
 
         tokio::spawn(
             neighborhood_sub
-                .send(RouteQueryMessage::data_indefinite_route_request(
-                    hostname_opt,
-                    payload_size,
-                ))
+                .send(RouteQueryMessage {
+                    excluded_keys,
+                    ..RouteQueryMessage::data_indefinite_route_request(hostname_opt, payload_size)
+                })
                 .then(move |route_result| {
                     message_resolver.resolve_message(tth_args, proxy_server_sub, route_result);
                     Ok(())
@@ -1199,7 +1491,7 @@ impl IBCDHelper for IBCDHelperReal {
 
 pub struct TryTransmitToHopperArgs {
     pub main_cryptde: &'static dyn CryptDE,
-    pub payload: ClientRequestPayload_0v1,
+    pub payload: ClientRequestPayload_0v2,
     pub client_addr: SocketAddr,
     pub timestamp: SystemTime,
     pub is_decentralized: bool,
@@ -1214,7 +1506,7 @@ pub struct TryTransmitToHopperArgs {
 impl TryTransmitToHopperArgs {
     pub fn new(
         proxy_server: &ProxyServer,
-        payload: ClientRequestPayload_0v1,
+        payload: ClientRequestPayload_0v2,
         client_addr: SocketAddr,
         timestamp: SystemTime,
         retire_stream_key: bool,
@@ -1267,7 +1559,7 @@ impl StreamKeyFactory for StreamKeyFactoryReal {
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 struct DNSFailureRetry {
-    unsuccessful_request: ClientRequestPayload_0v1,
+    unsuccessful_request: ClientRequestPayload_0v2,
     retries_left: usize,
 }
 
@@ -1310,9 +1602,9 @@ mod tests {
     use crate::sub_lib::hop::LiveHop;
     use crate::sub_lib::hopper::MessageType;
     use crate::sub_lib::neighborhood::ExpectedServices;
-    use crate::sub_lib::neighborhood::{ExpectedService, DEFAULT_RATE_PACK};
-    use crate::sub_lib::proxy_client::{ClientResponsePayload_0v1, DnsResolveFailure_0v1};
-    use crate::sub_lib::proxy_server::ClientRequestPayload_0v1;
+    use crate::sub_lib::neighborhood::{ExpectedService, Hops, DEFAULT_RATE_PACK};
+    use crate::sub_lib::proxy_client::{ClientResponsePayload_0v2, DnsResolveFailure_0v1};
+    use crate::sub_lib::proxy_server::ClientRequestPayload_0v2;
     use crate::sub_lib::proxy_server::ProxyProtocol;
     use crate::sub_lib::route::Route;
     use crate::sub_lib::route::RouteSegment;
@@ -1530,6 +1822,7 @@ mod tests {
         fn request_route_and_transmit(
             &self,
             _tth_args: TryTransmitToHopperArgs,
+            _excluded_keys: Vec<PublicKey>,
             _route_source: Recipient<RouteQueryMessage>,
             _proxy_server_sub: Recipient<AddRouteResultMessage>,
         ) {
@@ -1588,17 +1881,19 @@ mod tests {
         };
         let expected_http_request = PlainData::new(http_request);
         let route = Route { hops: vec![] };
-        let expected_payload = ClientRequestPayload_0v1 {
+        let expected_payload = ClientRequestPayload_0v2 {
             stream_key: stream_key.clone(),
             sequenced_packet: SequencedPacket {
                 data: expected_http_request.into(),
                 sequence_number: 0,
                 last_data: true,
+                relayed: false,
             },
             target_hostname: Some(String::from("nowhere.com")),
             target_port: HTTP_PORT,
             protocol: ProxyProtocol::HTTP,
             originator_public_key: alias_cryptde.public_key().clone(),
+            next_expected_sequence_opt: Some(0),
         };
         let expected_pkg = IncipientCoresPackage::new(
             main_cryptde,
@@ -1701,18 +1996,21 @@ mod tests {
             last_data: false,
             sequence_number: Some(0),
             data: b"HTTP/1.1 200 OK\r\n\r\n".to_vec(),
+            relayed: false,
         };
-        let expected_payload = ClientRequestPayload_0v1 {
+        let expected_payload = ClientRequestPayload_0v2 {
             stream_key: stream_key.clone(),
             sequenced_packet: SequencedPacket {
                 data: b"client hello".to_vec(),
                 sequence_number: 0,
                 last_data: false,
+                relayed: false,
             },
             target_hostname: Some(String::from("realdomain.nu")),
             target_port: 443,
             protocol: ProxyProtocol::TLS,
             originator_public_key: alias_cryptde.public_key().clone(),
+            next_expected_sequence_opt: Some(0),
         };
         let expected_pkg = IncipientCoresPackage::new(
             main_cryptde,
@@ -1815,16 +2113,18 @@ mod tests {
             data: request_data,
         };
 
-        let client_response_payload = ClientResponsePayload_0v1 {
+        let client_response_payload = ClientResponsePayload_0v2 {
             stream_key,
             sequenced_packet: SequencedPacket {
                 data: b"some data".to_vec(),
                 sequence_number: 0,
                 last_data: false,
+                relayed: false,
             },
+            next_expected_sequence_opt: None,
         };
 
-        let expired_cores_package: ExpiredCoresPackage<ClientResponsePayload_0v1> =
+        let expired_cores_package: ExpiredCoresPackage<ClientResponsePayload_0v2> =
             ExpiredCoresPackage::new(
                 SocketAddr::from_str("1.2.3.4:1234").unwrap(),
                 Some(make_wallet("irrelevant")),
@@ -1913,6 +2213,7 @@ mod tests {
             last_data: true,
             sequence_number: Some(0),
             data: b"HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\n\r\n".to_vec(),
+            relayed: false,
         };
 
         let dispatcher_recording = dispatcher_recording_arc.lock().unwrap();
@@ -1984,6 +2285,7 @@ mod tests {
             last_data: true,
             sequence_number: Some(0),
             data: b"HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\n\r\n".to_vec(),
+            relayed: false,
         };
 
         let dispatcher_recording = dispatcher_recording_arc.lock().unwrap();
@@ -2044,6 +2346,7 @@ mod tests {
                 last_data: true,
                 sequence_number: Some(0),
                 data: server_impersonator.consuming_wallet_absent(),
+                relayed: false,
             }
         );
         TestLogHandler::new().exists_log_containing(
@@ -2103,6 +2406,7 @@ mod tests {
                 last_data: true,
                 sequence_number: Some(0),
                 data: server_impersonator.consuming_wallet_absent(),
+                relayed: false,
             }
         );
         TestLogHandler::new().exists_log_containing(
@@ -2110,6 +2414,195 @@ mod tests {
         );
     }
 
+    #[test]
+    fn proxy_server_refuses_new_stream_with_impersonated_response_while_spending_budget_is_exhausted(
+    ) {
+        init_test_logging();
+        let test_name = "proxy_server_refuses_new_stream_with_impersonated_response_while_spending_budget_is_exhausted";
+        let http_request = b"GET /index.html HTTP/1.1\r\nHost: nowhere.com\r\n\r\n";
+        let (hopper, _, hopper_log_arc) = make_recorder();
+        let (neighborhood, _, neighborhood_log_arc) = make_recorder();
+        let (dispatcher, _, dispatcher_log_arc) = make_recorder();
+        let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        let msg_from_dispatcher = InboundClientData {
+            timestamp: SystemTime::now(),
+            peer_addr: socket_addr,
+            reception_port: Some(HTTP_PORT),
+            sequence_number: Some(0),
+            last_data: true,
+            is_clandestine: false,
+            data: http_request.to_vec(),
+        };
+        let stream_key_factory = StreamKeyFactoryMock::new(); // can't make any stream keys; shouldn't have to
+        let system = System::new(test_name);
+        let mut subject = ProxyServer::new(
+            main_cryptde(),
+            alias_cryptde(),
+            true,
+            Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            false,
+        );
+        subject.stream_key_factory = Box::new(stream_key_factory);
+        let subject_addr: Addr<ProxyServer> = subject.start();
+        let peer_actors = peer_actors_builder()
+            .dispatcher(dispatcher)
+            .hopper(hopper)
+            .neighborhood(neighborhood)
+            .build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+        subject_addr
+            .try_send(SpendingBudgetStatusMsg {
+                exhausted_until_opt: Some(SystemTime::now() + Duration::from_secs(3600)),
+            })
+            .unwrap();
+
+        subject_addr.try_send(msg_from_dispatcher).unwrap();
+
+        System::current().stop();
+        system.run();
+        let neighborhood_recording = neighborhood_log_arc.lock().unwrap();
+        assert!(neighborhood_recording.is_empty());
+        let hopper_recording = hopper_log_arc.lock().unwrap();
+        assert!(hopper_recording.is_empty());
+        let dispatcher_recording = dispatcher_log_arc.lock().unwrap();
+        let record = dispatcher_recording.get_record::<TransmitDataMsg>(0);
+        assert_eq!(
+            record,
+            &TransmitDataMsg {
+                endpoint: Endpoint::Socket(socket_addr),
+                last_data: true,
+                sequence_number: Some(0),
+                data: ServerImpersonatorHttp {}.spending_budget_exhausted(),
+                relayed: false,
+            }
+        );
+        let tlh = TestLogHandler::new();
+        tlh.exists_log_containing(
+            "INFO: ProxyServer: Spending budget exhausted; refusing new streams",
+        );
+        tlh.exists_log_containing(
+            "ERROR: ProxyServer: Browser request rejected due to exhausted spending budget",
+        );
+    }
+
+    #[test]
+    fn spending_budget_refusal_spares_existing_streams_and_ends_when_the_period_renews() {
+        let now = SystemTime::now();
+        let new_client = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        let existing_client = SocketAddr::from_str("1.2.3.4:5679").unwrap();
+        let ibcd = |peer_addr| InboundClientData {
+            timestamp: now,
+            peer_addr,
+            reception_port: Some(HTTP_PORT),
+            sequence_number: Some(0),
+            last_data: false,
+            is_clandestine: false,
+            data: vec![],
+        };
+        let mut subject = ProxyServer::new(
+            main_cryptde(),
+            alias_cryptde(),
+            true,
+            Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            false,
+        );
+        subject
+            .keys_and_addrs
+            .insert(StreamKey::make_meaningless_stream_key(), existing_client);
+        let unblocked = subject.refuses_new_stream_for_spending(&ibcd(new_client), now);
+        subject.spending_blocked_until_opt = Some(now + Duration::from_secs(60));
+
+        let new_stream = subject.refuses_new_stream_for_spending(&ibcd(new_client), now);
+        let existing_stream = subject.refuses_new_stream_for_spending(&ibcd(existing_client), now);
+        let after_renewal = subject
+            .refuses_new_stream_for_spending(&ibcd(new_client), now + Duration::from_secs(60));
+        subject.is_decentralized = false;
+        let zero_hop = subject.refuses_new_stream_for_spending(&ibcd(new_client), now);
+
+        assert_eq!(unblocked, false);
+        assert_eq!(new_stream, true);
+        assert_eq!(existing_stream, false);
+        assert_eq!(after_renewal, false);
+        assert_eq!(zero_hop, false);
+    }
+
+    #[test]
+    fn spending_budget_status_is_recorded_and_its_changes_logged() {
+        init_test_logging();
+        let test_name = "spending_budget_status_is_recorded_and_its_changes_logged";
+        let until = SystemTime::now() + Duration::from_secs(60);
+        let mut subject = ProxyServer::new(main_cryptde(), alias_cryptde(), true, None, false);
+        subject.logger = Logger::new(test_name);
+
+        subject.handle_spending_budget_status(SpendingBudgetStatusMsg {
+            exhausted_until_opt: Some(until),
+        });
+        subject.handle_spending_budget_status(SpendingBudgetStatusMsg {
+            exhausted_until_opt: Some(until),
+        });
+        let blocked = subject.spending_blocked_until_opt;
+        subject.handle_spending_budget_status(SpendingBudgetStatusMsg {
+            exhausted_until_opt: None,
+        });
+
+        assert_eq!(blocked, Some(until));
+        assert_eq!(subject.spending_blocked_until_opt, None);
+        TestLogHandler::new().assert_logs_contain_in_order(vec![
+            &format!(
+                "INFO: {}: Spending budget exhausted; refusing new streams",
+                test_name
+            ),
+            &format!(
+                "INFO: {}: Spending budget available again; accepting new streams",
+                test_name
+            ),
+        ]);
+    }
+
+    #[test]
+    fn min_hops_change_makes_proxy_server_forget_shared_routes() {
+        let main_cryptde = main_cryptde();
+        let now = SystemTime::now();
+        let route_query_response = zero_hop_route_response(main_cryptde.public_key(), main_cryptde);
+        let mut subject = ProxyServer::new(main_cryptde, alias_cryptde(), true, None, false);
+        subject
+            .route_isolation
+            .record_route(Some("www.nowhere.com"), &route_query_response, now);
+
+        subject.handle_config_change_msg(ConfigChangeMsg {
+            change: ConfigChange::UpdateMinHops(Hops::FourHops),
+        });
+
+        assert_eq!(
+            subject
+                .route_isolation
+                .shared_route(Some("nowhere.com"), now),
+            None
+        );
+    }
+
+    #[test]
+    fn irrelevant_config_changes_leave_shared_routes_alone() {
+        let main_cryptde = main_cryptde();
+        let now = SystemTime::now();
+        let route_query_response = zero_hop_route_response(main_cryptde.public_key(), main_cryptde);
+        let mut subject = ProxyServer::new(main_cryptde, alias_cryptde(), true, None, false);
+        subject
+            .route_isolation
+            .record_route(Some("www.nowhere.com"), &route_query_response, now);
+
+        subject.handle_config_change_msg(ConfigChangeMsg {
+            change: ConfigChange::UpdatePassword("password".to_string()),
+        });
+
+        assert_eq!(
+            subject
+                .route_isolation
+                .shared_route(Some("nowhere.com"), now),
+            Some(route_query_response)
+        );
+    }
+
     #[test]
     fn proxy_server_receives_http_request_with_no_consuming_wallet_in_zero_hop_mode_and_handles_normally(
     ) {
@@ -2161,7 +2654,8 @@ mod tests {
                 target_component: Component::ProxyClient,
                 return_component_opt: Some(Component::ProxyServer),
                 payload_size: 47,
-                hostname_opt: Some("nowhere.com".to_string())
+                hostname_opt: Some("nowhere.com".to_string()),
+                excluded_keys: vec![],
             }
         );
         let dispatcher_recording = dispatcher_log_arc.lock().unwrap();
@@ -2174,13 +2668,14 @@ mod tests {
                 expected_route.route,
                 MessageType::ClientRequest(VersionedData::new(
                     &crate::sub_lib::migrations::client_request_payload::MIGRATIONS,
-                    &ClientRequestPayload_0v1 {
+                    &ClientRequestPayload_0v2 {
                         stream_key,
                         sequenced_packet: SequencedPacket::new(expected_data, 0, true),
                         target_hostname: Some("nowhere.com".to_string()),
                         target_port: 80,
                         protocol: ProxyProtocol::HTTP,
                         originator_public_key: alias_cryptde.public_key().clone(),
+                        next_expected_sequence_opt: None,
                     }
                 )),
                 main_cryptde.public_key()
@@ -2240,7 +2735,8 @@ mod tests {
                 target_component: Component::ProxyClient,
                 return_component_opt: Some(Component::ProxyServer),
                 payload_size: 16,
-                hostname_opt: None
+                hostname_opt: None,
+                excluded_keys: vec![],
             }
         );
         let dispatcher_recording = dispatcher_log_arc.lock().unwrap();
@@ -2253,13 +2749,14 @@ mod tests {
                 expected_route.route,
                 MessageType::ClientRequest(VersionedData::new(
                     &crate::sub_lib::migrations::client_request_payload::MIGRATIONS,
-                    &ClientRequestPayload_0v1 {
+                    &ClientRequestPayload_0v2 {
                         stream_key,
                         sequenced_packet: SequencedPacket::new(expected_data, 0, true),
                         target_hostname: None,
                         target_port: 443,
                         protocol: ProxyProtocol::TLS,
                         originator_public_key: alias_cryptde.public_key().clone(),
+                        next_expected_sequence_opt: None,
                     }
                 ),),
                 main_cryptde.public_key()
@@ -2300,17 +2797,19 @@ mod tests {
         };
         let expected_http_request = PlainData::new(http_request);
         let route = Route { hops: vec![] };
-        let expected_payload = ClientRequestPayload_0v1 {
+        let expected_payload = ClientRequestPayload_0v2 {
             stream_key: stream_key.clone(),
             sequenced_packet: SequencedPacket {
                 data: expected_http_request.into(),
                 sequence_number: 0,
                 last_data: true,
+                relayed: false,
             },
             target_hostname: Some(String::from("nowhere.com")),
             target_port: HTTP_PORT,
             protocol: ProxyProtocol::HTTP,
             originator_public_key: alias_cryptde.public_key().clone(),
+            next_expected_sequence_opt: Some(0),
         };
         let expected_pkg = IncipientCoresPackage::new(
             main_cryptde,
@@ -2418,17 +2917,19 @@ mod tests {
             data: expected_data.clone(),
         };
         let expected_http_request = PlainData::new(http_request);
-        let expected_payload = ClientRequestPayload_0v1 {
+        let expected_payload = ClientRequestPayload_0v2 {
             stream_key: stream_key.clone(),
             sequenced_packet: SequencedPacket {
                 data: expected_http_request.into(),
                 sequence_number: 0,
                 last_data: true,
+                relayed: false,
             },
             target_hostname: Some(String::from("nowhere.com")),
             target_port: HTTP_PORT,
             protocol: ProxyProtocol::HTTP,
             originator_public_key: alias_cryptde.public_key().clone(),
+            next_expected_sequence_opt: Some(0),
         };
         let expected_pkg = IncipientCoresPackage::new(
             main_cryptde,
@@ -2489,85 +2990,376 @@ mod tests {
                 vec![expected_service],
                 123,
             ),
-        });
-        let (neighborhood_mock, _, _) = make_recorder();
-        let neighborhood_mock =
-            neighborhood_mock.route_query_response(route_query_response.clone());
-        let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
-        let stream_key = StreamKey::make_meaningless_stream_key();
-        let expected_data = http_request.to_vec();
+        });
+        let (neighborhood_mock, _, _) = make_recorder();
+        let neighborhood_mock =
+            neighborhood_mock.route_query_response(route_query_response.clone());
+        let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        let stream_key = StreamKey::make_meaningless_stream_key();
+        let expected_data = http_request.to_vec();
+        let msg_from_dispatcher = InboundClientData {
+            timestamp: SystemTime::now(),
+            peer_addr: socket_addr.clone(),
+            reception_port: Some(HTTP_PORT),
+            sequence_number: Some(0),
+            last_data: true,
+            is_clandestine: false,
+            data: expected_data.clone(),
+        };
+
+        thread::spawn(move || {
+            let stream_key_factory = StreamKeyFactoryMock::new().make_result(stream_key);
+            let system = System::new("proxy_server_sends_a_message_when_dns_retry_found_a_route");
+            let mut subject = ProxyServer::new(
+                cryptde,
+                alias_cryptde(),
+                true,
+                Some(STANDARD_CONSUMING_WALLET_BALANCE),
+                false,
+            );
+            subject.stream_key_factory = Box::new(stream_key_factory);
+            let subject_addr: Addr<ProxyServer> = subject.start();
+            let mut peer_actors = peer_actors_builder()
+                .proxy_server(proxy_server_mock)
+                .neighborhood(neighborhood_mock)
+                .build();
+            // Get the dns_retry_result recipient so we can partially mock it...
+            let dns_retry_result_recipient = peer_actors.proxy_server.route_result_sub;
+            peer_actors.proxy_server = ProxyServer::make_subs_from(&subject_addr);
+            peer_actors.proxy_server.route_result_sub = dns_retry_result_recipient; //Partial mocking
+            subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+            subject_addr.try_send(msg_from_dispatcher).unwrap();
+
+            system.run();
+        });
+        let expected_route_result_message = AddRouteResultMessage {
+            stream_key,
+            result: Ok(route_query_response.unwrap()),
+        };
+        proxy_server_awaiter.await_message_count(1);
+        let recording = proxy_server_recording_arc.lock().unwrap();
+        let message = recording.get_record::<AddRouteResultMessage>(0);
+        assert_eq!(message, &expected_route_result_message);
+    }
+
+    #[test]
+    fn proxy_server_sends_a_message_when_dns_retry_cannot_find_a_route() {
+        let test_name = "proxy_server_sends_a_message_when_dns_retry_cannot_find_a_route";
+        let cryptde = main_cryptde();
+        let http_request = b"GET /index.html HTTP/1.1\r\nHost: nowhere.com\r\n\r\n";
+        let (proxy_server_mock, _, proxy_server_recording_arc) = make_recorder();
+        let proxy_server_mock =
+            proxy_server_mock.system_stop_conditions(match_every_type_id!(AddRouteResultMessage));
+        let route_query_response = None;
+        let (neighborhood_mock, _, _) = make_recorder();
+        let neighborhood_mock =
+            neighborhood_mock.route_query_response(route_query_response.clone());
+        let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        let stream_key = StreamKey::make_meaningless_stream_key();
+        let expected_data = http_request.to_vec();
+        let msg_from_dispatcher = InboundClientData {
+            timestamp: SystemTime::now(),
+            peer_addr: socket_addr.clone(),
+            reception_port: Some(HTTP_PORT),
+            sequence_number: Some(0),
+            last_data: true,
+            is_clandestine: false,
+            data: expected_data.clone(),
+        };
+        let stream_key_factory = StreamKeyFactoryMock::new().make_result(stream_key);
+        let system = System::new(test_name);
+        let mut subject = ProxyServer::new(
+            cryptde,
+            alias_cryptde(),
+            true,
+            Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            false,
+        );
+        subject.logger = Logger::new(test_name);
+        subject.stream_key_factory = Box::new(stream_key_factory);
+        let subject_addr: Addr<ProxyServer> = subject.start();
+        let mut peer_actors = peer_actors_builder()
+            .proxy_server(proxy_server_mock)
+            .neighborhood(neighborhood_mock)
+            .build();
+        // Get the dns_retry_result recipient so we can partially mock it...
+        let dns_retry_result_recipient = peer_actors.proxy_server.route_result_sub;
+        peer_actors.proxy_server.route_result_sub = dns_retry_result_recipient; //Partial mocking
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+        subject_addr.try_send(msg_from_dispatcher).unwrap();
+
+        system.run();
+        let recording = proxy_server_recording_arc.lock().unwrap();
+        let message = recording.get_record::<AddRouteResultMessage>(0);
+        assert_eq!(message.stream_key, stream_key);
+        assert_eq!(
+            message.result,
+            Err(format!(
+                "Failed to find route to nowhere.com for stream key: {stream_key}"
+            ))
+        );
+    }
+
+    #[test]
+    fn proxy_server_uses_existing_route() {
+        let main_cryptde = main_cryptde();
+        let alias_cryptde = alias_cryptde();
+        let http_request = b"GET /index.html HTTP/1.1\r\nHost: nowhere.com\r\n\r\n";
+        let destination_key = PublicKey::from(&b"our destination"[..]);
+        let route_query_response = RouteQueryResponse {
+            route: Route { hops: vec![] },
+            expected_services: ExpectedServices::RoundTrip(
+                vec![make_exit_service_from_key(destination_key.clone())],
+                vec![],
+                1234,
+            ),
+        };
+        let (hopper_mock, hopper_awaiter, hopper_recording_arc) = make_recorder();
+        let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        let stream_key = StreamKey::make_meaningless_stream_key();
+        let expected_data = http_request.to_vec();
+        let msg_from_dispatcher = InboundClientData {
+            timestamp: SystemTime::now(),
+            peer_addr: socket_addr.clone(),
+            reception_port: Some(HTTP_PORT),
+            sequence_number: Some(0),
+            last_data: true,
+            is_clandestine: false,
+            data: expected_data.clone(),
+        };
+        let expected_payload = ClientRequestPayload_0v2 {
+            stream_key: stream_key.clone(),
+            sequenced_packet: SequencedPacket {
+                data: PlainData::new(http_request).into(),
+                sequence_number: 0,
+                last_data: true,
+                relayed: false,
+            },
+            target_hostname: Some(String::from("nowhere.com")),
+            target_port: HTTP_PORT,
+            protocol: ProxyProtocol::HTTP,
+            originator_public_key: alias_cryptde.public_key().clone(),
+            next_expected_sequence_opt: Some(0),
+        };
+        let expected_pkg = IncipientCoresPackage::new(
+            main_cryptde,
+            Route { hops: vec![] },
+            expected_payload.into(),
+            &destination_key,
+        )
+        .unwrap();
+
+        thread::spawn(move || {
+            let stream_key_factory = StreamKeyFactoryMock::new().make_result(stream_key);
+            let system = System::new("proxy_server_uses_existing_route");
+            let mut subject = ProxyServer::new(
+                main_cryptde,
+                alias_cryptde,
+                true,
+                Some(STANDARD_CONSUMING_WALLET_BALANCE),
+                false,
+            );
+            subject.stream_key_factory = Box::new(stream_key_factory);
+            subject
+                .stream_key_routes
+                .insert(stream_key, route_query_response);
+            let subject_addr: Addr<ProxyServer> = subject.start();
+            let peer_actors = peer_actors_builder().hopper(hopper_mock).build();
+            subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+            subject_addr.try_send(msg_from_dispatcher).unwrap();
+
+            System::current().stop();
+            system.run();
+        });
+
+        hopper_awaiter.await_message_count(1);
+        let recording = hopper_recording_arc.lock().unwrap();
+        let record = recording.get_record::<IncipientCoresPackage>(0);
+        assert_eq!(record, &expected_pkg);
+    }
+
+    #[test]
+    fn proxy_server_opens_new_stream_on_the_route_its_domain_is_using() {
+        let main_cryptde = main_cryptde();
+        let alias_cryptde = alias_cryptde();
+        let http_request = b"GET /index.html HTTP/1.1\r\nHost: nowhere.com\r\n\r\n";
+        let destination_key = PublicKey::from(&b"our destination"[..]);
+        let route_query_response = RouteQueryResponse {
+            route: Route { hops: vec![] },
+            expected_services: ExpectedServices::RoundTrip(
+                vec![make_exit_service_from_key(destination_key.clone())],
+                vec![],
+                1234,
+            ),
+        };
+        let (hopper_mock, hopper_awaiter, hopper_recording_arc) = make_recorder();
+        let (neighborhood_mock, _, neighborhood_recording_arc) = make_recorder();
+        let stream_key = StreamKey::make_meaningless_stream_key();
+        let msg_from_dispatcher = InboundClientData {
+            timestamp: SystemTime::now(),
+            peer_addr: SocketAddr::from_str("1.2.3.4:5678").unwrap(),
+            reception_port: Some(HTTP_PORT),
+            sequence_number: Some(0),
+            last_data: true,
+            is_clandestine: false,
+            data: http_request.to_vec(),
+        };
+        let expected_payload = ClientRequestPayload_0v2 {
+            stream_key: stream_key.clone(),
+            sequenced_packet: SequencedPacket {
+                data: PlainData::new(http_request).into(),
+                sequence_number: 0,
+                last_data: true,
+                relayed: false,
+            },
+            target_hostname: Some(String::from("nowhere.com")),
+            target_port: HTTP_PORT,
+            protocol: ProxyProtocol::HTTP,
+            originator_public_key: alias_cryptde.public_key().clone(),
+            next_expected_sequence_opt: Some(0),
+        };
+        let expected_pkg = IncipientCoresPackage::new(
+            main_cryptde,
+            Route { hops: vec![] },
+            expected_payload.into(),
+            &destination_key,
+        )
+        .unwrap();
+
+        thread::spawn(move || {
+            let stream_key_factory = StreamKeyFactoryMock::new().make_result(stream_key);
+            let system =
+                System::new("proxy_server_opens_new_stream_on_the_route_its_domain_is_using");
+            let mut subject = ProxyServer::new(
+                main_cryptde,
+                alias_cryptde,
+                true,
+                Some(STANDARD_CONSUMING_WALLET_BALANCE),
+                false,
+            );
+            subject.stream_key_factory = Box::new(stream_key_factory);
+            subject.route_isolation.record_route(
+                Some("www.nowhere.com"),
+                &route_query_response,
+                SystemTime::now(),
+            );
+            let subject_addr: Addr<ProxyServer> = subject.start();
+            let peer_actors = peer_actors_builder()
+                .hopper(hopper_mock)
+                .neighborhood(neighborhood_mock)
+                .build();
+            subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+            subject_addr.try_send(msg_from_dispatcher).unwrap();
+
+            System::current().stop();
+            system.run();
+        });
+
+        hopper_awaiter.await_message_count(1);
+        let recording = hopper_recording_arc.lock().unwrap();
+        let record = recording.get_record::<IncipientCoresPackage>(0);
+        assert_eq!(record, &expected_pkg);
+        let neighborhood_recording = neighborhood_recording_arc.lock().unwrap();
+        assert_eq!(neighborhood_recording.len(), 0);
+    }
+
+    #[test]
+    fn proxy_server_asks_for_a_route_clear_of_other_domains_nodes() {
+        let main_cryptde = main_cryptde();
+        let alias_cryptde = alias_cryptde();
+        let http_request = b"GET /index.html HTTP/1.1\r\nHost: nowhere.com\r\n\r\n";
+        let other_domain_response = RouteQueryResponse {
+            route: Route { hops: vec![] },
+            expected_services: ExpectedServices::RoundTrip(
+                vec![
+                    ExpectedService::Nothing,
+                    ExpectedService::Routing(
+                        PublicKey::new(&[1]),
+                        make_wallet("relay"),
+                        rate_pack(101),
+                    ),
+                    make_exit_service_from_key(PublicKey::new(&[2])),
+                ],
+                vec![],
+                1234,
+            ),
+        };
+        let (neighborhood_mock, neighborhood_awaiter, neighborhood_recording_arc) = make_recorder();
         let msg_from_dispatcher = InboundClientData {
             timestamp: SystemTime::now(),
-            peer_addr: socket_addr.clone(),
+            peer_addr: SocketAddr::from_str("1.2.3.4:5678").unwrap(),
             reception_port: Some(HTTP_PORT),
             sequence_number: Some(0),
             last_data: true,
             is_clandestine: false,
-            data: expected_data.clone(),
+            data: http_request.to_vec(),
         };
 
         thread::spawn(move || {
-            let stream_key_factory = StreamKeyFactoryMock::new().make_result(stream_key);
-            let system = System::new("proxy_server_sends_a_message_when_dns_retry_found_a_route");
+            let system = System::new("proxy_server_asks_for_a_route_clear_of_other_domains_nodes");
             let mut subject = ProxyServer::new(
-                cryptde,
-                alias_cryptde(),
+                main_cryptde,
+                alias_cryptde,
                 true,
                 Some(STANDARD_CONSUMING_WALLET_BALANCE),
                 false,
             );
-            subject.stream_key_factory = Box::new(stream_key_factory);
+            subject.route_isolation.record_route(
+                Some("somewhere.com"),
+                &other_domain_response,
+                SystemTime::now(),
+            );
             let subject_addr: Addr<ProxyServer> = subject.start();
-            let mut peer_actors = peer_actors_builder()
-                .proxy_server(proxy_server_mock)
+            let peer_actors = peer_actors_builder()
                 .neighborhood(neighborhood_mock)
                 .build();
-            // Get the dns_retry_result recipient so we can partially mock it...
-            let dns_retry_result_recipient = peer_actors.proxy_server.route_result_sub;
-            peer_actors.proxy_server = ProxyServer::make_subs_from(&subject_addr);
-            peer_actors.proxy_server.route_result_sub = dns_retry_result_recipient; //Partial mocking
             subject_addr.try_send(BindMessage { peer_actors }).unwrap();
-
             subject_addr.try_send(msg_from_dispatcher).unwrap();
 
             system.run();
         });
-        let expected_route_result_message = AddRouteResultMessage {
+
+        neighborhood_awaiter.await_message_count(1);
+        let recording = neighborhood_recording_arc.lock().unwrap();
+        let record = recording.get_record::<RouteQueryMessage>(0);
+        assert_eq!(
+            record,
+            &RouteQueryMessage {
+                excluded_keys: vec![PublicKey::new(&[1]), PublicKey::new(&[2])],
+                ..RouteQueryMessage::data_indefinite_route_request(
+                    Some("nowhere.com".to_string()),
+                    47
+                )
+            }
+        );
+    }
+
+    fn make_client_response_payload(
+        stream_key: StreamKey,
+        sequence_number: u64,
+        next_expected_sequence_opt: Option<u64>,
+    ) -> ClientResponsePayload_0v2 {
+        ClientResponsePayload_0v2 {
             stream_key,
-            result: Ok(route_query_response.unwrap()),
-        };
-        proxy_server_awaiter.await_message_count(1);
-        let recording = proxy_server_recording_arc.lock().unwrap();
-        let message = recording.get_record::<AddRouteResultMessage>(0);
-        assert_eq!(message, &expected_route_result_message);
+            sequenced_packet: SequencedPacket {
+                data: format!("response {}", sequence_number).into_bytes(),
+                sequence_number,
+                last_data: false,
+                relayed: false,
+            },
+            next_expected_sequence_opt,
+        }
     }
 
     #[test]
-    fn proxy_server_sends_a_message_when_dns_retry_cannot_find_a_route() {
-        let test_name = "proxy_server_sends_a_message_when_dns_retry_cannot_find_a_route";
-        let cryptde = main_cryptde();
-        let http_request = b"GET /index.html HTTP/1.1\r\nHost: nowhere.com\r\n\r\n";
-        let (proxy_server_mock, _, proxy_server_recording_arc) = make_recorder();
-        let proxy_server_mock =
-            proxy_server_mock.system_stop_conditions(match_every_type_id!(AddRouteResultMessage));
-        let route_query_response = None;
-        let (neighborhood_mock, _, _) = make_recorder();
-        let neighborhood_mock =
-            neighborhood_mock.route_query_response(route_query_response.clone());
-        let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
-        let stream_key = StreamKey::make_meaningless_stream_key();
-        let expected_data = http_request.to_vec();
-        let msg_from_dispatcher = InboundClientData {
-            timestamp: SystemTime::now(),
-            peer_addr: socket_addr.clone(),
-            reception_port: Some(HTTP_PORT),
-            sequence_number: Some(0),
-            last_data: true,
-            is_clandestine: false,
-            data: expected_data.clone(),
-        };
-        let stream_key_factory = StreamKeyFactoryMock::new().make_result(stream_key);
+    fn proxy_server_drops_replayed_responses() {
+        init_test_logging();
+        let test_name = "proxy_server_drops_replayed_responses";
         let system = System::new(test_name);
+        let (dispatcher_mock, _, dispatcher_recording_arc) = make_recorder();
+        let cryptde = main_cryptde();
         let mut subject = ProxyServer::new(
             cryptde,
             alias_cryptde(),
@@ -2576,81 +3368,126 @@ mod tests {
             false,
         );
         subject.logger = Logger::new(test_name);
-        subject.stream_key_factory = Box::new(stream_key_factory);
+        let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        let stream_key = StreamKey::make_meaningless_stream_key();
+        subject.keys_and_addrs.insert(stream_key, socket_addr);
+        subject
+            .stream_replays
+            .insert(stream_key, StreamReplay::new(None, SystemTime::now()));
+        subject.route_ids_to_return_routes.insert(
+            1234,
+            AddReturnRouteMessage {
+                return_route_id: 1234,
+                expected_services: vec![ExpectedService::Nothing],
+                protocol: ProxyProtocol::HTTP,
+                hostname_opt: None,
+            },
+        );
         let subject_addr: Addr<ProxyServer> = subject.start();
-        let mut peer_actors = peer_actors_builder()
-            .proxy_server(proxy_server_mock)
-            .neighborhood(neighborhood_mock)
-            .build();
-        // Get the dns_retry_result recipient so we can partially mock it...
-        let dns_retry_result_recipient = peer_actors.proxy_server.route_result_sub;
-        peer_actors.proxy_server.route_result_sub = dns_retry_result_recipient; //Partial mocking
+        let peer_actors = peer_actors_builder().dispatcher(dispatcher_mock).build();
         subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+        let expired_cores_package: ExpiredCoresPackage<ClientResponsePayload_0v2> =
+            ExpiredCoresPackage::new(
+                SocketAddr::from_str("1.2.3.4:1234").unwrap(),
+                Some(make_wallet("irrelevant")),
+                return_route_with_id(cryptde, 1234),
+                make_client_response_payload(stream_key, 0, Some(1)).into(),
+                0,
+            );
 
-        subject_addr.try_send(msg_from_dispatcher).unwrap();
+        subject_addr
+            .try_send(expired_cores_package.clone())
+            .unwrap();
+        subject_addr.try_send(expired_cores_package).unwrap();
 
+        System::current().stop();
         system.run();
-        let recording = proxy_server_recording_arc.lock().unwrap();
-        let message = recording.get_record::<AddRouteResultMessage>(0);
-        assert_eq!(message.stream_key, stream_key);
+        let dispatcher_recording = dispatcher_recording_arc.lock().unwrap();
         assert_eq!(
-            message.result,
-            Err(format!(
-                "Failed to find route to nowhere.com for stream key: {stream_key}"
-            ))
+            dispatcher_recording.get_record::<TransmitDataMsg>(0),
+            &TransmitDataMsg {
+                endpoint: Endpoint::Socket(socket_addr),
+                last_data: false,
+                sequence_number: Some(0),
+                data: b"response 0".to_vec(),
+                relayed: false,
+            }
         );
+        assert_eq!(dispatcher_recording.len(), 1);
+        TestLogHandler::new().exists_log_containing(&format!(
+            "DEBUG: {}: Dropping replayed ClientResponsePayload: stream {}, sequence 0",
+            test_name, stream_key
+        ));
     }
 
     #[test]
-    fn proxy_server_uses_existing_route() {
+    fn proxy_server_moves_stalled_stream_to_new_route_to_same_exit() {
         let main_cryptde = main_cryptde();
         let alias_cryptde = alias_cryptde();
-        let http_request = b"GET /index.html HTTP/1.1\r\nHost: nowhere.com\r\n\r\n";
-        let destination_key = PublicKey::from(&b"our destination"[..]);
-        let route_query_response = RouteQueryResponse {
+        let exit_key = PublicKey::new(b"exit");
+        let routing = |key: &[u8]| {
+            ExpectedService::Routing(PublicKey::new(key), make_wallet("relay"), rate_pack(101))
+        };
+        let old_route = RouteQueryResponse {
             route: Route { hops: vec![] },
             expected_services: ExpectedServices::RoundTrip(
-                vec![make_exit_service_from_key(destination_key.clone())],
-                vec![],
+                vec![
+                    ExpectedService::Nothing,
+                    routing(b"old over relay"),
+                    make_exit_service_from_key(exit_key.clone()),
+                ],
+                vec![
+                    make_exit_service_from_key(exit_key.clone()),
+                    routing(b"old back relay"),
+                    ExpectedService::Nothing,
+                ],
                 1234,
             ),
         };
+        let new_route = RouteQueryResponse {
+            route: make_meaningless_route(),
+            expected_services: ExpectedServices::RoundTrip(
+                vec![
+                    ExpectedService::Nothing,
+                    routing(b"new relay"),
+                    make_exit_service_from_key(exit_key.clone()),
+                ],
+                vec![
+                    make_exit_service_from_key(exit_key.clone()),
+                    routing(b"new relay"),
+                    ExpectedService::Nothing,
+                ],
+                2345,
+            ),
+        };
+        let (neighborhood_mock, _, neighborhood_recording_arc) = make_recorder();
+        let neighborhood_mock = neighborhood_mock.route_query_response(Some(new_route.clone()));
         let (hopper_mock, hopper_awaiter, hopper_recording_arc) = make_recorder();
-        let socket_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
         let stream_key = StreamKey::make_meaningless_stream_key();
-        let expected_data = http_request.to_vec();
-        let msg_from_dispatcher = InboundClientData {
-            timestamp: SystemTime::now(),
-            peer_addr: socket_addr.clone(),
-            reception_port: Some(HTTP_PORT),
-            sequence_number: Some(0),
-            last_data: true,
-            is_clandestine: false,
-            data: expected_data.clone(),
-        };
-        let expected_payload = ClientRequestPayload_0v1 {
-            stream_key: stream_key.clone(),
+        let request = ClientRequestPayload_0v2 {
+            stream_key,
             sequenced_packet: SequencedPacket {
-                data: PlainData::new(http_request).into(),
+                data: b"request 0".to_vec(),
                 sequence_number: 0,
-                last_data: true,
+                last_data: false,
+                relayed: false,
             },
-            target_hostname: Some(String::from("nowhere.com")),
+            target_hostname: Some("nowhere.com".to_string()),
             target_port: HTTP_PORT,
             protocol: ProxyProtocol::HTTP,
             originator_public_key: alias_cryptde.public_key().clone(),
+            next_expected_sequence_opt: None,
         };
-        let expected_pkg = IncipientCoresPackage::new(
-            main_cryptde,
-            Route { hops: vec![] },
-            expected_payload.into(),
-            &destination_key,
-        )
-        .unwrap();
+        let long_ago = SystemTime::now() - Duration::from_secs(60);
+        let mut stream_replay = StreamReplay::new(Some("nowhere.com".to_string()), long_ago);
+        stream_replay.prepare_request(request.clone(), long_ago);
+        stream_replay.receive_response(
+            &make_client_response_payload(stream_key, 0, Some(0)),
+            long_ago,
+        );
 
         thread::spawn(move || {
-            let stream_key_factory = StreamKeyFactoryMock::new().make_result(stream_key);
-            let system = System::new("proxy_server_uses_existing_route");
+            let system = System::new("proxy_server_moves_stalled_stream_to_new_route_to_same_exit");
             let mut subject = ProxyServer::new(
                 main_cryptde,
                 alias_cryptde,
@@ -2658,23 +3495,92 @@ mod tests {
                 Some(STANDARD_CONSUMING_WALLET_BALANCE),
                 false,
             );
-            subject.stream_key_factory = Box::new(stream_key_factory);
             subject
-                .stream_key_routes
-                .insert(stream_key, route_query_response);
+                .keys_and_addrs
+                .insert(stream_key, SocketAddr::from_str("1.2.3.4:5678").unwrap());
+            subject.stream_key_routes.insert(stream_key, old_route);
+            subject.stream_replays.insert(stream_key, stream_replay);
             let subject_addr: Addr<ProxyServer> = subject.start();
-            let peer_actors = peer_actors_builder().hopper(hopper_mock).build();
+            let peer_actors = peer_actors_builder()
+                .neighborhood(neighborhood_mock)
+                .hopper(hopper_mock)
+                .build();
             subject_addr.try_send(BindMessage { peer_actors }).unwrap();
-            subject_addr.try_send(msg_from_dispatcher).unwrap();
+            subject_addr.try_send(ScanForStalledRoutes {}).unwrap();
 
-            System::current().stop();
             system.run();
         });
 
         hopper_awaiter.await_message_count(1);
-        let recording = hopper_recording_arc.lock().unwrap();
-        let record = recording.get_record::<IncipientCoresPackage>(0);
-        assert_eq!(record, &expected_pkg);
+        let neighborhood_recording = neighborhood_recording_arc.lock().unwrap();
+        assert_eq!(
+            neighborhood_recording.get_record::<RouteQueryMessage>(0),
+            &RouteQueryMessage {
+                target_key_opt: Some(exit_key.clone()),
+                excluded_keys: vec![
+                    PublicKey::new(b"old back relay"),
+                    PublicKey::new(b"old over relay")
+                ],
+                ..RouteQueryMessage::data_indefinite_route_request(
+                    Some("nowhere.com".to_string()),
+                    0
+                )
+            }
+        );
+        let hopper_recording = hopper_recording_arc.lock().unwrap();
+        let expected_payload = ClientRequestPayload_0v2 {
+            next_expected_sequence_opt: Some(1),
+            ..request
+        };
+        assert_eq!(
+            hopper_recording.get_record::<IncipientCoresPackage>(0),
+            &IncipientCoresPackage::new(
+                main_cryptde,
+                new_route.route,
+                expected_payload.into(),
+                &exit_key
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn proxy_server_keeps_stalled_stream_on_its_route_if_there_is_no_other() {
+        init_test_logging();
+        let test_name = "proxy_server_keeps_stalled_stream_on_its_route_if_there_is_no_other";
+        let system = System::new(test_name);
+        let (hopper_mock, _, hopper_recording_arc) = make_recorder();
+        let mut subject = ProxyServer::new(
+            main_cryptde(),
+            alias_cryptde(),
+            true,
+            Some(STANDARD_CONSUMING_WALLET_BALANCE),
+            false,
+        );
+        subject.logger = Logger::new(test_name);
+        let stream_key = StreamKey::make_meaningless_stream_key();
+        let mut stream_replay = StreamReplay::new(None, SystemTime::now());
+        stream_replay.begin_failover();
+        subject.stream_replays.insert(stream_key, stream_replay);
+        let subject_addr: Addr<ProxyServer> = subject.start();
+        let peer_actors = peer_actors_builder().hopper(hopper_mock).build();
+        subject_addr.try_send(BindMessage { peer_actors }).unwrap();
+
+        subject_addr
+            .try_send(RouteFailoverResult {
+                stream_key,
+                route_query_response_opt: None,
+            })
+            .unwrap();
+
+        System::current().stop();
+        system.run();
+        let hopper_recording = hopper_recording_arc.lock().unwrap();
+        assert_eq!(hopper_recording.len(), 0);
+        TestLogHandler::new().exists_log_containing(&format!(
+            "WARN: {}: Found no other route to the exit Node for stalled stream {}; will try again later",
+            test_name, stream_key
+        ));
     }
 
     #[test]
@@ -2744,13 +3650,14 @@ mod tests {
             .proxy_server(proxy_server_mock)
             .build();
         let exit_payload_size = expected_data.len();
-        let payload = ClientRequestPayload_0v1 {
+        let payload = ClientRequestPayload_0v2 {
             stream_key,
             sequenced_packet: SequencedPacket::new(expected_data, 0, false),
             target_hostname: Some("nowhere.com".to_string()),
             target_port: HTTP_PORT,
             protocol: ProxyProtocol::HTTP,
             originator_public_key: PublicKey::new(b"originator_public_key"),
+            next_expected_sequence_opt: None,
         };
         let logger = Logger::new("test");
         let tth_args = TryTransmitToHopperArgs {
@@ -2829,13 +3736,14 @@ mod tests {
         let peer_actors = peer_actors_builder()
             .proxy_server(proxy_server_mock)
             .build();
-        let payload = ClientRequestPayload_0v1 {
+        let payload = ClientRequestPayload_0v2 {
             stream_key,
             sequenced_packet: SequencedPacket::new(expected_data, 0, false),
             target_hostname: Some("nowhere.com".to_string()),
             target_port: HTTP_PORT,
             protocol: ProxyProtocol::HTTP,
             originator_public_key: PublicKey::new(b"originator_public_key"),
+            next_expected_sequence_opt: None,
         };
         let logger = Logger::new("test");
         let tth_args = TryTransmitToHopperArgs {
@@ -3066,6 +3974,7 @@ mod tests {
             last_data: true,
             sequence_number: Some(0),
             data: ServerImpersonatorHttp {}.route_query_failure_response("nowhere.com"),
+            relayed: false,
         };
         assert_eq!(record, &expected_msg);
         let recording = neighborhood_recording_arc.lock().unwrap();
@@ -3107,17 +4016,19 @@ mod tests {
                 ),
             ]),
         };
-        let payload = ClientRequestPayload_0v1 {
+        let payload = ClientRequestPayload_0v2 {
             stream_key: StreamKey::make_meaningless_stream_key(),
             sequenced_packet: SequencedPacket {
                 data: vec![],
                 sequence_number: 0,
                 last_data: false,
+                relayed: false,
             },
             target_hostname: None,
             target_port: 0,
             protocol: ProxyProtocol::TLS,
             originator_public_key: cryptde.public_key().clone(),
+            next_expected_sequence_opt: None,
         };
         let logger = Logger::new("ProxyServer");
         let source_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
@@ -3242,6 +4153,7 @@ mod tests {
             last_data: true,
             sequence_number: Some(0),
             data: ServerImpersonatorHttp {}.route_query_failure_response("nowhere.com"),
+            relayed: false,
         };
         assert_eq!(record, &expected_msg);
         let recording = neighborhood_recording_arc.lock().unwrap();
@@ -3305,17 +4217,19 @@ mod tests {
         };
         let expected_tls_request = PlainData::new(tls_request);
         let route = Route { hops: vec![] };
-        let expected_payload = ClientRequestPayload_0v1 {
+        let expected_payload = ClientRequestPayload_0v2 {
             stream_key: stream_key.clone(),
             sequenced_packet: SequencedPacket {
                 data: expected_tls_request.into(),
                 sequence_number: 0,
                 last_data: false,
+                relayed: false,
             },
             target_hostname: Some(String::from("server.com")),
             target_port: TLS_PORT,
             protocol: ProxyProtocol::TLS,
             originator_public_key: alias_cryptde.public_key().clone(),
+            next_expected_sequence_opt: Some(0),
         };
         let expected_pkg = IncipientCoresPackage::new(
             main_cryptde,
@@ -3390,17 +4304,19 @@ mod tests {
         };
         let expected_tls_request = PlainData::new(tls_request);
         let route = Route { hops: vec![] };
-        let expected_payload = ClientRequestPayload_0v1 {
+        let expected_payload = ClientRequestPayload_0v2 {
             stream_key: stream_key.clone(),
             sequenced_packet: SequencedPacket {
                 data: expected_tls_request.into(),
                 sequence_number: 0,
                 last_data: false,
+                relayed: false,
             },
             target_hostname: None,
             target_port: TLS_PORT,
             protocol: ProxyProtocol::TLS,
             originator_public_key: alias_cryptde.public_key().clone(),
+            next_expected_sequence_opt: Some(0),
         };
         let expected_pkg = IncipientCoresPackage::new(
             main_cryptde,
@@ -3474,17 +4390,19 @@ mod tests {
         };
         let expected_tls_request = PlainData::new(tls_request);
         let route = Route { hops: vec![] };
-        let expected_payload = ClientRequestPayload_0v1 {
+        let expected_payload = ClientRequestPayload_0v2 {
             stream_key: stream_key.clone(),
             sequenced_packet: SequencedPacket {
                 data: expected_tls_request.into(),
                 sequence_number: 0,
                 last_data: true,
+                relayed: false,
             },
             target_hostname: None,
             target_port: TLS_PORT,
             protocol: ProxyProtocol::TLS,
             originator_public_key: alias_cryptde.public_key().clone(),
+            next_expected_sequence_opt: Some(0),
         };
         let expected_pkg = IncipientCoresPackage::new(
             main_cryptde,
@@ -3593,6 +4511,7 @@ mod tests {
             last_data: true,
             sequence_number: Some(0),
             data: ServerImpersonatorTls {}.route_query_failure_response("ignored"),
+            relayed: false,
         };
         assert_eq!(record, &expected_msg);
     }
@@ -3628,13 +4547,15 @@ mod tests {
         );
         let subject_addr: Addr<ProxyServer> = subject.start();
         let remaining_route = return_route_with_id(cryptde, 1234);
-        let client_response_payload = ClientResponsePayload_0v1 {
+        let client_response_payload = ClientResponsePayload_0v2 {
             stream_key: stream_key.clone(),
             sequenced_packet: SequencedPacket {
                 data: b"16 bytes of data".to_vec(),
                 sequence_number: 12345678,
                 last_data: true,
+                relayed: false,
             },
+            next_expected_sequence_opt: None,
         };
         let first_expired_cores_package = ExpiredCoresPackage::new(
             SocketAddr::from_str("1.2.3.4:1234").unwrap(),
@@ -3721,14 +4642,15 @@ mod tests {
                 hostname_opt: None,
             },
         );
-        let client_response_payload = ClientResponsePayload_0v1 {
+        let client_response_payload = ClientResponsePayload_0v2 {
             stream_key: stream_key.clone(),
             sequenced_packet: SequencedPacket::new(vec![], 1, true),
+            next_expected_sequence_opt: None,
         };
         let (dispatcher_mock, _, _) = make_recorder();
         let peer_actors = peer_actors_builder().dispatcher(dispatcher_mock).build();
         subject.subs.as_mut().unwrap().dispatcher = peer_actors.dispatcher.from_dispatcher_client;
-        let expired_cores_package: ExpiredCoresPackage<ClientResponsePayload_0v1> =
+        let expired_cores_package: ExpiredCoresPackage<ClientResponsePayload_0v2> =
             ExpiredCoresPackage::new(
                 SocketAddr::from_str("1.2.3.4:1234").unwrap(),
                 Some(make_wallet("irrelevant")),
@@ -3947,11 +4869,12 @@ mod tests {
             }),
             report_to_counterpart: true,
         };
-        let client_response_payload = ClientResponsePayload_0v1 {
+        let client_response_payload = ClientResponsePayload_0v2 {
             stream_key: stream_key.clone(),
             sequenced_packet: SequencedPacket::new(vec![], 1, true),
+            next_expected_sequence_opt: None,
         };
-        let expired_cores_package: ExpiredCoresPackage<ClientResponsePayload_0v1> =
+        let expired_cores_package: ExpiredCoresPackage<ClientResponsePayload_0v2> =
             ExpiredCoresPackage::new(
                 SocketAddr::from_str("1.2.3.4:1234").unwrap(),
                 Some(make_wallet("irrelevant")),
@@ -4056,16 +4979,18 @@ mod tests {
             },
         );
         let subject_addr: Addr<ProxyServer> = subject.start();
-        let first_client_response_payload = ClientResponsePayload_0v1 {
+        let first_client_response_payload = ClientResponsePayload_0v2 {
             stream_key,
             sequenced_packet: SequencedPacket {
                 data: b"some data".to_vec(),
                 sequence_number: 4321,
                 last_data: false,
+                relayed: false,
             },
+            next_expected_sequence_opt: None,
         };
         let first_exit_size = first_client_response_payload.sequenced_packet.data.len();
-        let first_expired_cores_package: ExpiredCoresPackage<ClientResponsePayload_0v1> =
+        let first_expired_cores_package: ExpiredCoresPackage<ClientResponsePayload_0v2> =
             ExpiredCoresPackage::new(
                 SocketAddr::from_str("1.2.3.4:1234").unwrap(),
                 Some(make_wallet("irrelevant")),
@@ -4074,16 +4999,18 @@ mod tests {
                 0,
             );
         let routing_size = first_expired_cores_package.payload_len;
-        let second_client_response_payload = ClientResponsePayload_0v1 {
+        let second_client_response_payload = ClientResponsePayload_0v2 {
             stream_key,
             sequenced_packet: SequencedPacket {
                 data: b"other data".to_vec(),
                 sequence_number: 4322,
                 last_data: false,
+                relayed: false,
             },
+            next_expected_sequence_opt: None,
         };
         let second_exit_size = second_client_response_payload.sequenced_packet.data.len();
-        let second_expired_cores_package: ExpiredCoresPackage<ClientResponsePayload_0v1> =
+        let second_expired_cores_package: ExpiredCoresPackage<ClientResponsePayload_0v2> =
             ExpiredCoresPackage::new(
                 SocketAddr::from_str("1.2.3.5:1235").unwrap(),
                 Some(make_wallet("irrelevant")),
@@ -4243,15 +5170,17 @@ mod tests {
             },
         );
         let subject_addr: Addr<ProxyServer> = subject.start();
-        let first_client_response_payload = ClientResponsePayload_0v1 {
+        let first_client_response_payload = ClientResponsePayload_0v2 {
             stream_key,
             sequenced_packet: SequencedPacket {
                 data: b"some data".to_vec(),
                 sequence_number: 4321,
                 last_data: false,
+                relayed: false,
             },
+            next_expected_sequence_opt: None,
         };
-        let first_expired_cores_package: ExpiredCoresPackage<ClientResponsePayload_0v1> =
+        let first_expired_cores_package: ExpiredCoresPackage<ClientResponsePayload_0v2> =
             ExpiredCoresPackage::new(
                 SocketAddr::from_str("1.2.3.4:1234").unwrap(),
                 Some(make_wallet("irrelevant")),
@@ -4319,16 +5248,18 @@ mod tests {
             },
         );
         let subject_addr: Addr<ProxyServer> = subject.start();
-        let client_response_payload = ClientResponsePayload_0v1 {
+        let client_response_payload = ClientResponsePayload_0v2 {
             stream_key,
             sequenced_packet: SequencedPacket {
                 data: b"some data".to_vec(),
                 sequence_number: 4321,
                 last_data: false,
+                relayed: false,
             },
+            next_expected_sequence_opt: None,
         };
         let exit_size = client_response_payload.sequenced_packet.data.len();
-        let expired_cores_package: ExpiredCoresPackage<ClientResponsePayload_0v1> =
+        let expired_cores_package: ExpiredCoresPackage<ClientResponsePayload_0v2> =
             ExpiredCoresPackage::new(
                 SocketAddr::from_str("1.2.3.4:1234").unwrap(),
                 Some(make_wallet("irrelevant")),
@@ -4448,6 +5379,7 @@ mod tests {
                 sequence_number: Some(0),
                 data: ServerImpersonatorHttp {}
                     .dns_resolution_failure_response(Some("server.com".to_string()),),
+                relayed: false,
             },
             *record
         );
@@ -5023,6 +5955,7 @@ mod tests {
                 sequence_number: Some(0),
                 data: ServerImpersonatorHttp {}
                     .dns_resolution_failure_response(Some("server.com".to_string()),),
+                relayed: false,
             },
             *record
         );
@@ -5335,13 +6268,15 @@ mod tests {
         );
         let subject_addr: Addr<ProxyServer> = subject.start();
 
-        let client_response_payload = ClientResponsePayload_0v1 {
+        let client_response_payload = ClientResponsePayload_0v2 {
             stream_key,
             sequenced_packet: SequencedPacket {
                 data: b"data".to_vec(),
                 sequence_number: 0,
                 last_data: true,
+                relayed: false,
             },
+            next_expected_sequence_opt: None,
         };
         let expired_cores_package = ExpiredCoresPackage::new(
             SocketAddr::from_str("1.2.3.4:1234").unwrap(),
@@ -5412,13 +6347,15 @@ mod tests {
             .dispatcher(dispatcher)
             .accountant(accountant)
             .build();
-        let client_response_payload = ClientResponsePayload_0v1 {
+        let client_response_payload = ClientResponsePayload_0v2 {
             stream_key,
             sequenced_packet: SequencedPacket {
                 data: b"some data".to_vec(),
                 sequence_number: 4321,
                 last_data: false,
+                relayed: false,
             },
+            next_expected_sequence_opt: None,
         };
         let expired_cores_package = ExpiredCoresPackage::new(
             SocketAddr::from_str("1.2.3.4:1234").unwrap(),
@@ -5462,13 +6399,15 @@ mod tests {
             .dispatcher(dispatcher)
             .accountant(accountant)
             .build();
-        let client_response_payload = ClientResponsePayload_0v1 {
+        let client_response_payload = ClientResponsePayload_0v2 {
             stream_key,
             sequenced_packet: SequencedPacket {
                 data: b"some data".to_vec(),
                 sequence_number: 4321,
                 last_data: false,
+                relayed: false,
             },
+            next_expected_sequence_opt: None,
         };
         let expired_cores_package = ExpiredCoresPackage::new(
             SocketAddr::from_str("1.2.3.4:1234").unwrap(),
@@ -5533,13 +6472,15 @@ mod tests {
 
         thread::sleep(Duration::from_millis(300));
 
-        let client_response_payload = ClientResponsePayload_0v1 {
+        let client_response_payload = ClientResponsePayload_0v2 {
             stream_key,
             sequenced_packet: SequencedPacket {
                 data: b"some data".to_vec(),
                 sequence_number: 4321,
                 last_data: false,
+                relayed: false,
             },
+            next_expected_sequence_opt: None,
         };
         let expired_cores_package = ExpiredCoresPackage::new(
             SocketAddr::from_str("1.2.3.4:1234").unwrap(),
@@ -5687,13 +6628,14 @@ mod tests {
             MessageType::ClientRequest(vd) => assert_eq!(
                 vd.extract(&crate::sub_lib::migrations::client_request_payload::MIGRATIONS)
                     .unwrap(),
-                ClientRequestPayload_0v1 {
+                ClientRequestPayload_0v2 {
                     stream_key: affected_stream_key,
                     sequenced_packet: SequencedPacket::new(vec![], 1234, true),
                     target_hostname: Some(String::from("tunneled.com")),
                     target_port: 443,
                     protocol: ProxyProtocol::TLS,
                     originator_public_key: alias_cryptde().public_key().clone(),
+                    next_expected_sequence_opt: Some(0),
                 }
             ),
             other => panic!("Wrong payload type: {:?}", other),
@@ -5806,13 +6748,14 @@ mod tests {
             MessageType::ClientRequest(vd) => assert_eq!(
                 vd.extract(&crate::sub_lib::migrations::client_request_payload::MIGRATIONS)
                     .unwrap(),
-                ClientRequestPayload_0v1 {
+                ClientRequestPayload_0v2 {
                     stream_key: affected_stream_key,
                     sequenced_packet: SequencedPacket::new(vec![], 1234, true),
                     target_hostname: None,
                     target_port: HTTP_PORT,
                     protocol: ProxyProtocol::HTTP,
                     originator_public_key: alias_cryptde().public_key().clone(),
+                    next_expected_sequence_opt: Some(0),
                 }
             ),
             other => panic!("Wrong payload type: {:?}", other),
@@ -5977,7 +6920,7 @@ mod tests {
 
     #[derive(Default)]
     struct ClientRequestPayloadFactoryMock {
-        make_results: RefCell<Vec<Option<ClientRequestPayload_0v1>>>,
+        make_results: RefCell<Vec<Option<ClientRequestPayload_0v2>>>,
     }
 
     impl ClientRequestPayloadFactory for ClientRequestPayloadFactoryMock {
@@ -5987,13 +6930,13 @@ mod tests {
             _stream_key: StreamKey,
             _cryptde: &dyn CryptDE,
             _logger: &Logger,
-        ) -> Option<ClientRequestPayload_0v1> {
+        ) -> Option<ClientRequestPayload_0v2> {
             self.make_results.borrow_mut().remove(0)
         }
     }
 
     impl ClientRequestPayloadFactoryMock {
-        fn make_result(self, result: Option<ClientRequestPayload_0v1>) -> Self {
+        fn make_result(self, result: Option<ClientRequestPayload_0v2>) -> Self {
             self.make_results.borrow_mut().push(result);
             self
         }
@@ -6060,14 +7003,17 @@ mod tests {
             is_clandestine: false,
             data: expected_data.clone(),
         };
-        let expected_payload = ClientRequestPayloadFactoryReal::new()
-            .make(
-                &msg_from_dispatcher,
-                stream_key.clone(),
-                alias_cryptde,
-                &Logger::new("test"),
-            )
-            .unwrap();
+        let expected_payload = ClientRequestPayload_0v2 {
+            next_expected_sequence_opt: Some(0),
+            ..ClientRequestPayloadFactoryReal::new()
+                .make(
+                    &msg_from_dispatcher,
+                    stream_key.clone(),
+                    alias_cryptde,
+                    &Logger::new("test"),
+                )
+                .unwrap()
+        };
         let stream_key_factory = StreamKeyFactoryMock::new().make_result(stream_key.clone());
         let system = System::new(
             "proxy_server_receives_http_request_from_dispatcher_then_sends_cores_package_to_hopper",
@@ -60,4 +60,5 @@ pub trait ServerImpersonator {
     fn route_query_failure_response(&self, server_name: &str) -> Vec<u8>;
     fn dns_resolution_failure_response(&self, server_name_opt: Option<String>) -> Vec<u8>;
     fn consuming_wallet_absent(&self) -> Vec<u8>;
+    fn spending_budget_exhausted(&self) -> Vec<u8>;
 }
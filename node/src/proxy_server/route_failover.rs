@@ -0,0 +1,392 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::sub_lib::cryptde::PublicKey;
+use crate::sub_lib::neighborhood::{ExpectedService, ExpectedServices, RouteQueryResponse};
+use crate::sub_lib::proxy_client::ClientResponsePayload_0v2;
+use crate::sub_lib::proxy_server::ClientRequestPayload_0v2;
+use crate::sub_lib::replay_window::{ArrivalCounter, ReplayWindow, REPLAY_WINDOW_CAPACITY};
+use std::time::{Duration, SystemTime};
+
+pub const ROUTE_STALL_TIMEOUT: Duration = Duration::from_secs(20);
+pub const STALL_SCAN_INTERVAL: Duration = Duration::from_secs(5);
+
+// What the originating end of a stream keeps so that the stream can outlive its route: the
+// requests the exit Node hasn't acknowledged yet, and how far the responses have got. A stream is
+// only failed over if its exit has shown that it keeps count too; an exit that doesn't would
+// pass the resent requests on to the server a second time.
+pub struct StreamReplay {
+    hostname_opt: Option<String>,
+    sent: ReplayWindow<ClientRequestPayload_0v2>,
+    last_request_opt: Option<ClientRequestPayload_0v2>,
+    received: ArrivalCounter,
+    exit_acknowledges: bool,
+    last_progress: SystemTime,
+    failover_pending: bool,
+}
+
+impl StreamReplay {
+    pub fn new(hostname_opt: Option<String>, now: SystemTime) -> Self {
+        Self {
+            hostname_opt,
+            sent: ReplayWindow::new(REPLAY_WINDOW_CAPACITY),
+            last_request_opt: None,
+            received: ArrivalCounter::new(),
+            exit_acknowledges: false,
+            last_progress: now,
+            failover_pending: false,
+        }
+    }
+
+    pub fn hostname_opt(&self) -> Option<&str> {
+        self.hostname_opt.as_deref()
+    }
+
+    // Stamps an outgoing request with the acknowledgement for the responses so far and keeps it
+    // in case it has to be sent again
+    pub fn prepare_request(
+        &mut self,
+        payload: ClientRequestPayload_0v2,
+        now: SystemTime,
+    ) -> ClientRequestPayload_0v2 {
+        if self.sent.is_empty() {
+            // Nothing was outstanding, so the route hasn't been keeping us waiting until now
+            self.last_progress = now;
+        }
+        let payload = self.acknowledging(payload);
+        self.sent
+            .push(payload.sequenced_packet.sequence_number, payload.clone());
+        self.last_request_opt = Some(payload.clone());
+        payload
+    }
+
+    // False if the response is a replay of one that already arrived
+    pub fn receive_response(
+        &mut self,
+        response: &ClientResponsePayload_0v2,
+        now: SystemTime,
+    ) -> bool {
+        if let Some(next_expected) = response.next_expected_sequence_opt {
+            self.sent.acknowledge(next_expected);
+            self.exit_acknowledges = true;
+        }
+        self.last_progress = now;
+        self.received
+            .arrive(response.sequenced_packet.sequence_number)
+    }
+
+    // A server that takes a long time to answer looks just like a dead route from here; failing
+    // such a stream over costs a route query and some duplicates for the exit Node to drop.
+    pub fn is_stalled(&self, now: SystemTime, stall_timeout: Duration) -> bool {
+        if !self.exit_acknowledges
+            || self.failover_pending
+            || self.sent.is_empty()
+            || !self.sent.is_complete()
+        {
+            return false;
+        }
+        match now.duration_since(self.last_progress) {
+            Ok(waited) => waited >= stall_timeout,
+            Err(_) => false,
+        }
+    }
+
+    pub fn begin_failover(&mut self) {
+        self.failover_pending = true;
+    }
+
+    // The exit Node replays its unacknowledged responses when a request arrives on a new route,
+    // so there's always at least one request to send, even if it's been acknowledged already.
+    pub fn complete_failover(&mut self, now: SystemTime) -> Vec<ClientRequestPayload_0v2> {
+        self.failover_pending = false;
+        self.last_progress = now;
+        let mut payloads = self.sent.unacknowledged();
+        if payloads.is_empty() {
+            payloads.extend(self.last_request_opt.clone());
+        }
+        payloads
+            .into_iter()
+            .map(|payload| self.acknowledging(payload))
+            .collect()
+    }
+
+    pub fn abandon_failover(&mut self, now: SystemTime) {
+        self.failover_pending = false;
+        self.last_progress = now;
+    }
+
+    fn acknowledging(&self, payload: ClientRequestPayload_0v2) -> ClientRequestPayload_0v2 {
+        ClientRequestPayload_0v2 {
+            next_expected_sequence_opt: Some(self.received.next_expected()),
+            ..payload
+        }
+    }
+}
+
+pub fn exit_key(route_query_response: &RouteQueryResponse) -> Option<PublicKey> {
+    over_services(route_query_response)
+        .iter()
+        .find_map(|service| match service {
+            ExpectedService::Exit(key, _, _) => Some(key.clone()),
+            _ => None,
+        })
+}
+
+// The relays on both legs of the route, any of which may be the one that went away
+pub fn relay_keys(route_query_response: &RouteQueryResponse) -> Vec<PublicKey> {
+    let back: &[ExpectedService] = match &route_query_response.expected_services {
+        ExpectedServices::OneWay(_) => &[],
+        ExpectedServices::RoundTrip(_, back, _) => back,
+    };
+    let mut keys = over_services(route_query_response)
+        .iter()
+        .chain(back.iter())
+        .filter_map(|service| match service {
+            ExpectedService::Routing(key, _, _) => Some(key.clone()),
+            _ => None,
+        })
+        .collect::<Vec<PublicKey>>();
+    keys.sort();
+    keys.dedup();
+    keys
+}
+
+fn over_services(route_query_response: &RouteQueryResponse) -> &[ExpectedService] {
+    match &route_query_response.expected_services {
+        ExpectedServices::OneWay(over) => over,
+        ExpectedServices::RoundTrip(over, _, _) => over,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sub_lib::neighborhood::DEFAULT_RATE_PACK;
+    use crate::sub_lib::proxy_server::ProxyProtocol;
+    use crate::sub_lib::route::Route;
+    use crate::sub_lib::sequence_buffer::SequencedPacket;
+    use crate::sub_lib::stream_key::StreamKey;
+    use crate::test_utils::make_wallet;
+
+    fn make_request(sequence_number: u64) -> ClientRequestPayload_0v2 {
+        ClientRequestPayload_0v2 {
+            stream_key: StreamKey::make_meaningless_stream_key(),
+            sequenced_packet: SequencedPacket {
+                data: format!("request {}", sequence_number).into_bytes(),
+                sequence_number,
+                last_data: false,
+                relayed: false,
+            },
+            target_hostname: Some("example.com".to_string()),
+            target_port: 80,
+            protocol: ProxyProtocol::HTTP,
+            originator_public_key: PublicKey::new(b"originator"),
+            next_expected_sequence_opt: None,
+        }
+    }
+
+    fn make_response(
+        sequence_number: u64,
+        next_expected_sequence_opt: Option<u64>,
+    ) -> ClientResponsePayload_0v2 {
+        ClientResponsePayload_0v2 {
+            stream_key: StreamKey::make_meaningless_stream_key(),
+            sequenced_packet: SequencedPacket {
+                data: format!("response {}", sequence_number).into_bytes(),
+                sequence_number,
+                last_data: false,
+                relayed: false,
+            },
+            next_expected_sequence_opt,
+        }
+    }
+
+    fn acknowledged(
+        payload: ClientRequestPayload_0v2,
+        next_expected: u64,
+    ) -> ClientRequestPayload_0v2 {
+        ClientRequestPayload_0v2 {
+            next_expected_sequence_opt: Some(next_expected),
+            ..payload
+        }
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(ROUTE_STALL_TIMEOUT, Duration::from_secs(20));
+        assert_eq!(STALL_SCAN_INTERVAL, Duration::from_secs(5));
+    }
+
+    #[test]
+    fn requests_carry_acknowledgement_of_responses() {
+        let now = SystemTime::now();
+        let mut subject = StreamReplay::new(Some("example.com".to_string()), now);
+
+        let first = subject.prepare_request(make_request(0), now);
+        subject.receive_response(&make_response(0, None), now);
+        subject.receive_response(&make_response(1, None), now);
+        let second = subject.prepare_request(make_request(1), now);
+
+        assert_eq!(first, acknowledged(make_request(0), 0));
+        assert_eq!(second, acknowledged(make_request(1), 2));
+    }
+
+    #[test]
+    fn replayed_responses_are_recognized() {
+        let now = SystemTime::now();
+        let mut subject = StreamReplay::new(None, now);
+
+        assert_eq!(
+            subject.receive_response(&make_response(0, Some(0)), now),
+            true
+        );
+        assert_eq!(
+            subject.receive_response(&make_response(1, Some(0)), now),
+            true
+        );
+        assert_eq!(
+            subject.receive_response(&make_response(0, Some(0)), now),
+            false
+        );
+    }
+
+    #[test]
+    fn stream_is_stalled_when_acknowledgements_stop_coming() {
+        let start = SystemTime::now();
+        let mut subject = StreamReplay::new(None, start);
+        subject.prepare_request(make_request(0), start);
+        subject.receive_response(&make_response(0, Some(1)), start);
+        subject.prepare_request(make_request(1), start);
+        let timeout = Duration::from_secs(10);
+
+        assert_eq!(
+            subject.is_stalled(start + Duration::from_secs(9), timeout),
+            false
+        );
+        assert_eq!(
+            subject.is_stalled(start + Duration::from_secs(10), timeout),
+            true
+        );
+        subject.begin_failover();
+        assert_eq!(
+            subject.is_stalled(start + Duration::from_secs(10), timeout),
+            false
+        );
+    }
+
+    #[test]
+    fn stream_is_not_stalled_with_nothing_outstanding() {
+        let start = SystemTime::now();
+        let mut subject = StreamReplay::new(None, start);
+        subject.prepare_request(make_request(0), start);
+        subject.receive_response(&make_response(0, Some(1)), start);
+
+        let result = subject.is_stalled(start + Duration::from_secs(60), Duration::from_secs(10));
+
+        assert_eq!(result, false);
+    }
+
+    #[test]
+    fn stream_is_not_stalled_if_exit_does_not_acknowledge() {
+        let start = SystemTime::now();
+        let mut subject = StreamReplay::new(None, start);
+        subject.prepare_request(make_request(0), start);
+        subject.receive_response(&make_response(0, None), start);
+        subject.prepare_request(make_request(1), start);
+
+        let result = subject.is_stalled(start + Duration::from_secs(60), Duration::from_secs(10));
+
+        assert_eq!(result, false);
+    }
+
+    #[test]
+    fn stream_is_not_stalled_if_window_has_lost_requests() {
+        let start = SystemTime::now();
+        let mut subject = StreamReplay::new(None, start);
+        subject.receive_response(&make_response(0, Some(0)), start);
+        (0..=REPLAY_WINDOW_CAPACITY as u64).for_each(|sequence_number| {
+            subject.prepare_request(make_request(sequence_number), start);
+        });
+
+        let result = subject.is_stalled(start + Duration::from_secs(60), Duration::from_secs(10));
+
+        assert_eq!(result, false);
+    }
+
+    #[test]
+    fn failover_resends_unacknowledged_requests_with_current_acknowledgement() {
+        let start = SystemTime::now();
+        let later = start + Duration::from_secs(30);
+        let mut subject = StreamReplay::new(None, start);
+        (0..3).for_each(|sequence_number| {
+            subject.prepare_request(make_request(sequence_number), start);
+        });
+        subject.receive_response(&make_response(0, Some(1)), start);
+        subject.begin_failover();
+
+        let result = subject.complete_failover(later);
+
+        assert_eq!(
+            result,
+            vec![
+                acknowledged(make_request(1), 1),
+                acknowledged(make_request(2), 1)
+            ]
+        );
+        assert_eq!(subject.is_stalled(later, Duration::from_secs(10)), false);
+        assert_eq!(
+            subject.is_stalled(later + Duration::from_secs(10), Duration::from_secs(10)),
+            true
+        );
+    }
+
+    #[test]
+    fn failover_with_everything_acknowledged_resends_last_request() {
+        let now = SystemTime::now();
+        let mut subject = StreamReplay::new(None, now);
+        subject.prepare_request(make_request(0), now);
+        subject.prepare_request(make_request(1), now);
+        subject.receive_response(&make_response(0, Some(2)), now);
+
+        let result = subject.complete_failover(now);
+
+        assert_eq!(result, vec![acknowledged(make_request(1), 1)]);
+    }
+
+    #[test]
+    fn exit_and_relay_keys_are_found_in_route() {
+        let service = |name: &str, exit: bool| {
+            let key = PublicKey::new(name.as_bytes());
+            if exit {
+                ExpectedService::Exit(key, make_wallet(name), DEFAULT_RATE_PACK)
+            } else {
+                ExpectedService::Routing(key, make_wallet(name), DEFAULT_RATE_PACK)
+            }
+        };
+        let route_query_response = RouteQueryResponse {
+            route: Route { hops: vec![] },
+            expected_services: ExpectedServices::RoundTrip(
+                vec![
+                    ExpectedService::Nothing,
+                    service("relay b", false),
+                    service("exit", true),
+                ],
+                vec![
+                    service("exit", true),
+                    service("relay b", false),
+                    service("relay a", false),
+                    ExpectedService::Nothing,
+                ],
+                1234,
+            ),
+        };
+
+        assert_eq!(
+            exit_key(&route_query_response),
+            Some(PublicKey::new(b"exit"))
+        );
+        assert_eq!(
+            relay_keys(&route_query_response),
+            vec![PublicKey::new(b"relay a"), PublicKey::new(b"relay b")]
+        );
+    }
+}
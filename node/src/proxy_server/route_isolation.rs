@@ -0,0 +1,323 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::sub_lib::cryptde::PublicKey;
+use crate::sub_lib::neighborhood::{ExpectedService, ExpectedServices, RouteQueryResponse};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::time::{Duration, SystemTime};
+
+pub const DEFAULT_ROUTE_LIFETIME: Duration = Duration::from_secs(600);
+
+// Second-level labels under which country registries sell names, as in example.co.uk. The Node
+// doesn't carry the public suffix list, so these stand in for it, and only for two-letter top-level
+// domains. Names under any other multi-label suffix are cut one label too short: alice.github.io
+// and bob.github.io both come out as github.io, as do all names under ltd.uk or kawasaki.jp, so
+// unrelated sites there share a route. A listed label that a registry doesn't actually use, as in
+// mail.net.de, errs the other way, giving subdomains of one site routes of their own.
+const REGISTRY_SECOND_LEVEL_LABELS: [&str; 7] = ["ac", "co", "com", "edu", "gov", "net", "org"];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct DomainRoute {
+    route_query_response: RouteQueryResponse,
+    found_at: SystemTime,
+}
+
+// Streams to the same registrable domain share a route until it's route_lifetime old; after that,
+// the next new stream gets a fresh one. Routes for different domains are asked to stay clear of
+// each other's relays and exits. Streams that are already open keep their routes, since a new
+// exit Node would have no connection to the server.
+pub struct RouteIsolation {
+    route_lifetime: Duration,
+    domain_routes: HashMap<String, DomainRoute>,
+}
+
+impl RouteIsolation {
+    pub fn new(route_lifetime: Duration) -> Self {
+        Self {
+            route_lifetime,
+            domain_routes: HashMap::new(),
+        }
+    }
+
+    pub fn shared_route(
+        &mut self,
+        hostname_opt: Option<&str>,
+        now: SystemTime,
+    ) -> Option<RouteQueryResponse> {
+        self.purge_expired(now);
+        let domain = registrable_domain(hostname_opt?);
+        self.domain_routes
+            .get(&domain)
+            .map(|domain_route| domain_route.route_query_response.clone())
+    }
+
+    pub fn record_route(
+        &mut self,
+        hostname_opt: Option<&str>,
+        route_query_response: &RouteQueryResponse,
+        now: SystemTime,
+    ) {
+        if let Some(hostname) = hostname_opt {
+            self.domain_routes.insert(
+                registrable_domain(hostname),
+                DomainRoute {
+                    route_query_response: route_query_response.clone(),
+                    found_at: now,
+                },
+            );
+        }
+    }
+
+    // Relays and exits of the live routes for every domain but this one, in the order the
+    // Neighborhood gives up avoiding them when it has to: relays first, then exits, which see the
+    // most. A Node that is an exit anywhere counts as an exit.
+    pub fn keys_used_elsewhere(
+        &mut self,
+        hostname_opt: Option<&str>,
+        now: SystemTime,
+    ) -> Vec<PublicKey> {
+        self.purge_expired(now);
+        let own_domain_opt = hostname_opt.map(registrable_domain);
+        let (mut relay_keys, mut exit_keys) = (vec![], vec![]);
+        self.domain_routes
+            .iter()
+            .filter(|(domain, _)| Some(*domain) != own_domain_opt.as_ref())
+            .for_each(|(_, domain_route)| {
+                let (relays, exits) = route_node_keys(&domain_route.route_query_response);
+                relay_keys.extend(relays);
+                exit_keys.extend(exits);
+            });
+        exit_keys.sort();
+        exit_keys.dedup();
+        relay_keys.sort();
+        relay_keys.dedup();
+        relay_keys.retain(|key| !exit_keys.contains(key));
+        relay_keys.into_iter().chain(exit_keys).collect()
+    }
+
+    pub fn forget(&mut self, hostname: &str) {
+        self.domain_routes.remove(&registrable_domain(hostname));
+    }
+
+    pub fn forget_all(&mut self) {
+        self.domain_routes.clear();
+    }
+
+    fn purge_expired(&mut self, now: SystemTime) {
+        let route_lifetime = self.route_lifetime;
+        self.domain_routes.retain(|_, domain_route| {
+            match now.duration_since(domain_route.found_at) {
+                Ok(age) => age < route_lifetime,
+                Err(_) => true,
+            }
+        });
+    }
+}
+
+// (relay keys, exit keys)
+fn route_node_keys(route_query_response: &RouteQueryResponse) -> (Vec<PublicKey>, Vec<PublicKey>) {
+    let services: Vec<&ExpectedService> = match &route_query_response.expected_services {
+        ExpectedServices::OneWay(services) => services.iter().collect(),
+        ExpectedServices::RoundTrip(over, back, _) => over.iter().chain(back.iter()).collect(),
+    };
+    let (mut relay_keys, mut exit_keys) = (vec![], vec![]);
+    services.into_iter().for_each(|service| match service {
+        ExpectedService::Routing(key, _, _) => relay_keys.push(key.clone()),
+        ExpectedService::Exit(key, _, _) => exit_keys.push(key.clone()),
+        ExpectedService::Nothing => (),
+    });
+    (relay_keys, exit_keys)
+}
+
+// Only approximates the registrable domain: see REGISTRY_SECOND_LEVEL_LABELS for where it's wrong
+pub fn registrable_domain(hostname: &str) -> String {
+    let hostname = hostname.trim_end_matches('.').to_lowercase();
+    if hostname.parse::<IpAddr>().is_ok() {
+        return hostname;
+    }
+    let labels: Vec<&str> = hostname.split('.').collect();
+    let label_count = match labels.as_slice() {
+        [.., second_level, top_level]
+            if labels.len() > 2
+                && top_level.len() == 2
+                && REGISTRY_SECOND_LEVEL_LABELS.contains(second_level) =>
+        {
+            3
+        }
+        _ => 2,
+    };
+    labels[labels.len().saturating_sub(label_count)..].join(".")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sub_lib::neighborhood::DEFAULT_RATE_PACK;
+    use crate::sub_lib::route::Route;
+    use crate::test_utils::make_wallet;
+
+    fn make_response(exit: &str, relays: &[&str], return_route_id: u32) -> RouteQueryResponse {
+        let exit_key = PublicKey::new(exit.as_bytes());
+        let routing = |relay: &&str| {
+            ExpectedService::Routing(
+                PublicKey::new(relay.as_bytes()),
+                make_wallet(relay),
+                DEFAULT_RATE_PACK,
+            )
+        };
+        let over = vec![ExpectedService::Nothing]
+            .into_iter()
+            .chain(relays.iter().map(routing))
+            .chain(vec![ExpectedService::Exit(
+                exit_key.clone(),
+                make_wallet(exit),
+                DEFAULT_RATE_PACK,
+            )])
+            .collect::<Vec<ExpectedService>>();
+        let back = over.iter().cloned().rev().collect();
+        RouteQueryResponse {
+            route: Route { hops: vec![] },
+            expected_services: ExpectedServices::RoundTrip(over, back, return_route_id),
+        }
+    }
+
+    fn keys(names: &[&str]) -> Vec<PublicKey> {
+        names
+            .iter()
+            .map(|name| PublicKey::new(name.as_bytes()))
+            .collect()
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(DEFAULT_ROUTE_LIFETIME, Duration::from_secs(600));
+        assert_eq!(
+            REGISTRY_SECOND_LEVEL_LABELS,
+            ["ac", "co", "com", "edu", "gov", "net", "org"]
+        );
+    }
+
+    #[test]
+    fn registrable_domain_drops_subdomains() {
+        assert_eq!(registrable_domain("www.example.com"), "example.com");
+        assert_eq!(registrable_domain("a.b.cdn.Example.COM."), "example.com");
+        assert_eq!(registrable_domain("example.com"), "example.com");
+        assert_eq!(registrable_domain("localhost"), "localhost");
+        assert_eq!(registrable_domain("news.bbc.co.uk"), "bbc.co.uk");
+        assert_eq!(registrable_domain("bbc.co.uk"), "bbc.co.uk");
+        assert_eq!(registrable_domain("co.uk"), "co.uk");
+        assert_eq!(registrable_domain("www.example.co"), "example.co");
+        assert_eq!(registrable_domain("1.2.3.4"), "1.2.3.4");
+        assert_eq!(registrable_domain("::1"), "::1");
+    }
+
+    #[test]
+    fn streams_to_the_same_domain_share_a_route_until_it_expires() {
+        let mut subject = RouteIsolation::new(Duration::from_secs(60));
+        let found_at = SystemTime::now();
+        let response = make_response("exit", &["relay"], 1);
+        subject.record_route(Some("www.example.com"), &response, found_at);
+
+        let before_expiry =
+            subject.shared_route(Some("cdn.example.com"), found_at + Duration::from_secs(59));
+        let other_domain =
+            subject.shared_route(Some("example.org"), found_at + Duration::from_secs(59));
+        let no_hostname = subject.shared_route(None, found_at + Duration::from_secs(59));
+        let at_expiry =
+            subject.shared_route(Some("www.example.com"), found_at + Duration::from_secs(60));
+
+        assert_eq!(before_expiry, Some(response));
+        assert_eq!(other_domain, None);
+        assert_eq!(no_hostname, None);
+        assert_eq!(at_expiry, None);
+        assert_eq!(subject.domain_routes.is_empty(), true);
+    }
+
+    #[test]
+    fn routes_without_a_hostname_are_not_shared() {
+        let mut subject = RouteIsolation::new(Duration::from_secs(60));
+        let now = SystemTime::now();
+
+        subject.record_route(None, &make_response("exit", &["relay"], 1), now);
+
+        assert_eq!(subject.domain_routes.is_empty(), true);
+    }
+
+    #[test]
+    fn keys_used_elsewhere_lists_relays_then_exits_of_other_live_domains() {
+        let mut subject = RouteIsolation::new(Duration::from_secs(60));
+        let now = SystemTime::now();
+        subject.record_route(
+            Some("example.com"),
+            &make_response("exit1", &["relay1", "relay2"], 1),
+            now,
+        );
+        subject.record_route(
+            Some("example.org"),
+            &make_response("exit2", &["relay2", "relay3"], 2),
+            now - Duration::from_secs(30),
+        );
+        subject.record_route(
+            Some("example.net"),
+            &make_response("exit3", &["relay4"], 3),
+            now - Duration::from_secs(61),
+        );
+
+        let for_example_com = subject.keys_used_elsewhere(Some("www.example.com"), now);
+        let for_newcomer = subject.keys_used_elsewhere(Some("example.edu"), now);
+        let without_hostname = subject.keys_used_elsewhere(None, now);
+
+        assert_eq!(for_example_com, keys(&["relay2", "relay3", "exit2"]));
+        let everything_live = keys(&["relay1", "relay2", "relay3", "exit1", "exit2"]);
+        assert_eq!(for_newcomer, everything_live);
+        assert_eq!(without_hostname, everything_live);
+    }
+
+    #[test]
+    fn keys_used_elsewhere_counts_a_node_that_is_an_exit_anywhere_as_an_exit() {
+        let mut subject = RouteIsolation::new(Duration::from_secs(60));
+        let now = SystemTime::now();
+        subject.record_route(
+            Some("example.com"),
+            &make_response("node_a", &["node_b"], 1),
+            now,
+        );
+        subject.record_route(
+            Some("example.org"),
+            &make_response("node_c", &["node_a"], 2),
+            now,
+        );
+
+        let result = subject.keys_used_elsewhere(None, now);
+
+        assert_eq!(result, keys(&["node_b", "node_a", "node_c"]));
+    }
+
+    #[test]
+    fn forgotten_domains_get_new_routes() {
+        let mut subject = RouteIsolation::new(Duration::from_secs(60));
+        let now = SystemTime::now();
+        subject.record_route(
+            Some("example.com"),
+            &make_response("exit", &["relay"], 1),
+            now,
+        );
+
+        subject.forget("www.example.com");
+
+        assert_eq!(subject.shared_route(Some("example.com"), now), None);
+    }
+
+    #[test]
+    fn forget_all_forgets_every_domain() {
+        let mut subject = RouteIsolation::new(Duration::from_secs(60));
+        let now = SystemTime::now();
+        subject.record_route(Some("example.com"), &make_response("exit", &[], 1), now);
+        subject.record_route(Some("other.org"), &make_response("exit", &[], 2), now);
+
+        subject.forget_all();
+
+        assert_eq!(subject.shared_route(Some("example.com"), now), None);
+        assert_eq!(subject.shared_route(Some("other.org"), now), None);
+    }
+}
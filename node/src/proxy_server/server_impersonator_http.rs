@@ -45,6 +45,18 @@ impl ServerImpersonator for ServerImpersonatorHttp {
             Set up a funded consuming wallet and try again.",
         )
     }
+
+    fn spending_budget_exhausted(&self) -> Vec<u8> {
+        ServerImpersonatorHttp::make_error_response(
+            402,
+            "Spending Budget Exhausted",
+            "Your Node has reached its spending limit",
+            "You've spent as much on routing and exit services from other Nodes as your spending \
+            budget allows for today or for this month. Your Node won't open new connections until the \
+            period (UTC) renews. If you want to spend more, raise the limits with \
+            'masq set-configuration --spending-budget'.",
+        )
+    }
 }
 
 impl ServerImpersonatorHttp {
@@ -239,4 +251,22 @@ mod tests {
         );
         assert_eq!(expected, result);
     }
+
+    #[test]
+    fn spending_budget_exhausted_response_produces_expected_error_page() {
+        let subject = ServerImpersonatorHttp {};
+
+        let result = subject.spending_budget_exhausted();
+
+        let expected = ServerImpersonatorHttp::make_error_response(
+            402,
+            "Spending Budget Exhausted",
+            "Your Node has reached its spending limit",
+            "You've spent as much on routing and exit services from other Nodes as your spending \
+            budget allows for today or for this month. Your Node won't open new connections until the \
+            period (UTC) renews. If you want to spend more, raise the limits with \
+            'masq set-configuration --spending-budget'.",
+        );
+        assert_eq!(expected, result);
+    }
 }
@@ -15,6 +15,10 @@ impl ServerImpersonator for ServerImpersonatorTls {
     fn consuming_wallet_absent(&self) -> Vec<u8> {
         Vec::from(&TLS_INTERNAL_ERROR_ALERT[..])
     }
+
+    fn spending_budget_exhausted(&self) -> Vec<u8> {
+        Vec::from(&TLS_INTERNAL_ERROR_ALERT[..])
+    }
 }
 
 const TLS_INTERNAL_ERROR_ALERT: [u8; 7] = [
@@ -88,4 +92,13 @@ mod tests {
 
         assert_eq!(Vec::from(&TLS_INTERNAL_ERROR_ALERT[..]), result);
     }
+
+    #[test]
+    fn spending_budget_exhausted_produces_internal_error_alert() {
+        let subject = ServerImpersonatorTls {};
+
+        let result = subject.spending_budget_exhausted();
+
+        assert_eq!(Vec::from(&TLS_INTERNAL_ERROR_ALERT[..]), result);
+    }
 }
@@ -5,10 +5,12 @@ use crate::daemon::daemon_initializer::{
 };
 use crate::daemon::{ChannelFactory, ChannelFactoryReal};
 use crate::database::config_dumper::DumpConfigRunnerReal;
+use crate::database::db_restorer::RestoreDatabaseRunnerReal;
 use crate::node_configurator::node_configurator_initialization::{
     InitializationConfig, NodeConfiguratorInitializationReal,
 };
 use crate::node_configurator::{DirsWrapper, DirsWrapperReal, NodeConfigurator};
+use crate::privilege_drop::PrivilegeDropperReal;
 use crate::server_initializer::{
     LoggerInitializerWrapper, LoggerInitializerWrapperReal, ServerInitializerReal,
 };
@@ -20,6 +22,7 @@ use std::cell::RefCell;
 pub type RunModeResult = Result<(), ConfiguratorError>;
 
 pub struct DumpConfigRunnerFactoryReal;
+pub struct RestoreDatabaseRunnerFactoryReal;
 pub struct ServerInitializerFactoryReal;
 pub struct DaemonInitializerFactoryReal {
     configurator: RefCell<Option<Box<dyn NodeConfigurator<InitializationConfig>>>>,
@@ -57,6 +60,9 @@ impl DaemonInitializerFactoryReal {
 pub trait DumpConfigRunnerFactory {
     fn make(&self) -> Box<dyn DumpConfigRunner>;
 }
+pub trait RestoreDatabaseRunnerFactory {
+    fn make(&self) -> Box<dyn RestoreDatabaseRunner>;
+}
 pub trait ServerInitializerFactory {
     fn make(&self) -> Box<dyn ServerInitializer<Item = (), Error = ()>>;
 }
@@ -69,6 +75,11 @@ pub trait DumpConfigRunner {
     as_any_ref_in_trait!();
 }
 
+pub trait RestoreDatabaseRunner {
+    fn go(&self, streams: &mut StdStreams, args: &[String]) -> RunModeResult;
+    as_any_ref_in_trait!();
+}
+
 pub trait ServerInitializer: futures::Future {
     fn go(&mut self, streams: &mut StdStreams, args: &[String]) -> RunModeResult;
     as_any_ref_in_trait!();
@@ -87,6 +98,15 @@ impl DumpConfigRunnerFactory for DumpConfigRunnerFactoryReal {
     }
 }
 
+impl RestoreDatabaseRunnerFactory for RestoreDatabaseRunnerFactoryReal {
+    fn make(&self) -> Box<dyn RestoreDatabaseRunner> {
+        Box::new(RestoreDatabaseRunnerReal {
+            dirs_wrapper: Box::new(DirsWrapperReal::default()),
+            privilege_dropper: Box::new(PrivilegeDropperReal::new()),
+        })
+    }
+}
+
 impl ServerInitializerFactory for ServerInitializerFactoryReal {
     fn make(&self) -> Box<dyn ServerInitializer<Item = (), Error = ()>> {
         Box::new(ServerInitializerReal::default())
@@ -131,14 +151,15 @@ pub struct DIClusteredParams {
 #[cfg(test)]
 mod tests {
     use crate::database::config_dumper::DumpConfigRunnerReal;
+    use crate::database::db_restorer::RestoreDatabaseRunnerReal;
     use crate::node_configurator::node_configurator_initialization::NodeConfiguratorInitializationReal;
     use crate::run_modes_factories::mocks::{
         test_clustered_params, NodeConfiguratorInitializationMock,
     };
     use crate::run_modes_factories::{
         DIClusteredParams, DaemonInitializerFactory, DaemonInitializerFactoryReal,
-        DumpConfigRunnerFactory, DumpConfigRunnerFactoryReal, ServerInitializerFactory,
-        ServerInitializerFactoryReal,
+        DumpConfigRunnerFactory, DumpConfigRunnerFactoryReal, RestoreDatabaseRunnerFactory,
+        RestoreDatabaseRunnerFactoryReal, ServerInitializerFactory, ServerInitializerFactoryReal,
     };
     use crate::server_initializer::ServerInitializerReal;
     use masq_lib::shared_schema::ConfiguratorError;
@@ -157,6 +178,17 @@ mod tests {
             .unwrap();
     }
 
+    #[test]
+    fn make_for_restore_database_runner_factory_produces_a_proper_object() {
+        let subject = RestoreDatabaseRunnerFactoryReal;
+        let result = subject.make();
+
+        let _ = result
+            .as_any()
+            .downcast_ref::<RestoreDatabaseRunnerReal>()
+            .unwrap();
+    }
+
     #[test]
     fn make_for_server_initializer_factory_produces_a_proper_object() {
         let subject = ServerInitializerFactoryReal;
@@ -240,7 +272,8 @@ pub mod mocks {
     use crate::node_configurator::NodeConfigurator;
     use crate::run_modes_factories::{
         DIClusteredParams, DaemonInitializer, DaemonInitializerFactory, DumpConfigRunner,
-        DumpConfigRunnerFactory, RunModeResult, ServerInitializer, ServerInitializerFactory,
+        DumpConfigRunnerFactory, RestoreDatabaseRunner, RestoreDatabaseRunnerFactory,
+        RunModeResult, ServerInitializer, ServerInitializerFactory,
     };
     use crate::server_initializer::test_utils::LoggerInitializerWrapperMock;
     use crate::server_initializer::tests::{
@@ -284,6 +317,24 @@ pub mod mocks {
         }
     }
 
+    #[derive(Default)]
+    pub struct RestoreDatabaseRunnerFactoryMock {
+        make_results: RefCell<Vec<Box<RestoreDatabaseRunnerMock>>>,
+    }
+
+    impl RestoreDatabaseRunnerFactoryMock {
+        pub fn make_result(self, result: Box<RestoreDatabaseRunnerMock>) -> Self {
+            self.make_results.borrow_mut().push(result);
+            self
+        }
+    }
+
+    impl RestoreDatabaseRunnerFactory for RestoreDatabaseRunnerFactoryMock {
+        fn make(&self) -> Box<dyn RestoreDatabaseRunner> {
+            self.make_results.borrow_mut().remove(0)
+        }
+    }
+
     #[derive(Default)]
     pub struct ServerInitializerFactoryMock {
         make_results: RefCell<Vec<Box<ServerInitializerMock>>>,
@@ -380,6 +431,37 @@ pub mod mocks {
         }
     }
 
+    #[derive(Default)]
+    pub struct RestoreDatabaseRunnerMock {
+        restore_database_params: Arc<Mutex<Vec<Vec<String>>>>,
+        restore_database_results: RefCell<Vec<Result<(), ConfiguratorError>>>,
+    }
+
+    impl RestoreDatabaseRunner for RestoreDatabaseRunnerMock {
+        fn go(&self, _streams: &mut StdStreams, args: &[String]) -> Result<(), ConfiguratorError> {
+            self.restore_database_params
+                .lock()
+                .unwrap()
+                .push(args.to_vec());
+            self.restore_database_results.borrow_mut().remove(0)
+        }
+    }
+
+    impl RestoreDatabaseRunnerMock {
+        pub fn restore_database_result(self, result: Result<(), ConfiguratorError>) -> Self {
+            self.restore_database_results.borrow_mut().push(result);
+            self
+        }
+
+        pub fn restore_database_params(
+            mut self,
+            params_arc: &Arc<Mutex<Vec<Vec<String>>>>,
+        ) -> Self {
+            self.restore_database_params = params_arc.clone();
+            self
+        }
+    }
+
     #[derive(Default)]
     pub struct ServerInitializerMock {
         go_result: RefCell<Vec<Result<(), ConfiguratorError>>>,
@@ -6,26 +6,33 @@ use crate::entry_dns::dns_socket_server::DnsSocketServer;
 use crate::node_configurator::node_configurator_standard::server_initializer_collected_params;
 use crate::node_configurator::{DirsWrapper, DirsWrapperReal};
 use crate::run_modes_factories::{RunModeResult, ServerInitializer};
+use crate::sub_lib::peer_actors::{ShutdownMsg, ShutdownSubs};
 use crate::sub_lib::socket_server::ConfiguredByPrivilege;
+use actix::System;
 use backtrace::Backtrace;
 use clap::value_t;
 use flexi_logger::{
-    Cleanup, Criterion, DeferredNow, Duplicate, LevelFilter, LogSpecBuilder, Logger, Naming, Record,
+    Cleanup, Criterion, DeferredNow, Duplicate, LevelFilter, LogSpecBuilder, LogSpecification,
+    Logger, Naming, ReconfigurationHandle, Record,
 };
+use futures::future::join_all;
+use futures::stream;
 use futures::try_ready;
 use lazy_static::lazy_static;
 use log::{log, Level};
 use masq_lib::command::StdStreams;
 use masq_lib::logger;
-use masq_lib::logger::{real_format_function, POINTER_TO_FORMAT_FUNCTION};
+use masq_lib::logger::{log_target, real_format_function, POINTER_TO_FORMAT_FUNCTION};
 use masq_lib::shared_schema::ConfiguratorError;
 use std::any::Any;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
 use std::io;
 use std::panic::{Location, PanicInfo};
 use std::path::{Path, PathBuf};
-use std::sync::{Mutex, MutexGuard};
+use std::sync::{Arc, Mutex, MutexGuard};
 use time::OffsetDateTime;
-use tokio::prelude::{Async, Future};
+use tokio::prelude::{Async, Future, Stream};
 
 pub struct ServerInitializerReal {
     #[allow(dead_code)]
@@ -33,6 +40,10 @@ pub struct ServerInitializerReal {
     bootstrapper: Box<dyn ConfiguredByPrivilege<Item = (), Error = ()>>,
     privilege_dropper: Box<dyn PrivilegeDropper>,
     dirs_wrapper: Box<dyn DirsWrapper>,
+    termination_signals: Box<dyn Stream<Item = (), Error = ()>>,
+    shutdown_subs: Arc<Mutex<ShutdownSubs>>,
+    shutdown_opt: Option<Box<dyn Future<Item = (), Error = ()>>>,
+    logger: masq_lib::logger::Logger,
 }
 
 impl ServerInitializer for ServerInitializerReal {
@@ -81,6 +92,21 @@ impl Future for ServerInitializerReal {
     type Error = ();
 
     fn poll(&mut self) -> Result<Async<<Self as Future>::Item>, <Self as Future>::Error> {
+        if self.shutdown_opt.is_none() {
+            match self.termination_signals.poll() {
+                Ok(Async::NotReady) => (),
+                Ok(Async::Ready(Some(()))) => self.shutdown_opt = Some(self.broadcast_shutdown()),
+                Ok(Async::Ready(None)) | Err(()) => {
+                    self.termination_signals = Box::new(stream::empty())
+                }
+            }
+        }
+        if let Some(shutdown) = self.shutdown_opt.as_mut() {
+            try_ready!(shutdown.poll());
+            info!(self.logger, "Shutting down");
+            System::current().stop();
+            return Ok(Async::Ready(()));
+        }
         // try_ready!(self
         //     .dns_socket_server
         //     .as_mut()
@@ -93,15 +119,92 @@ impl Future for ServerInitializerReal {
 
 impl Default for ServerInitializerReal {
     fn default() -> ServerInitializerReal {
+        let logger = masq_lib::logger::Logger::new("ServerInitializer");
         ServerInitializerReal {
             dns_socket_server: Box::new(DnsSocketServer::new()),
             bootstrapper: Box::new(Bootstrapper::new(Box::new(LoggerInitializerWrapperReal {}))),
             privilege_dropper: Box::new(PrivilegeDropperReal::new()),
             dirs_wrapper: Box::new(DirsWrapperReal::default()),
+            termination_signals: termination_signals(&logger),
+            shutdown_subs: SHUTDOWN_SUBS.clone(),
+            shutdown_opt: None,
+            logger,
         }
     }
 }
 
+impl ServerInitializerReal {
+    // The Actors answer once they have saved what they must; whether they could is their business
+    fn broadcast_shutdown(&self) -> Box<dyn Future<Item = (), Error = ()>> {
+        let subs = self
+            .shutdown_subs
+            .lock()
+            .expect("Shutdown subs poisoned")
+            .clone();
+        info!(
+            self.logger,
+            "Received termination signal: telling {} Actors to shut down",
+            subs.len()
+        );
+        Box::new(
+            join_all(
+                subs.into_iter()
+                    .map(|sub| sub.send(ShutdownMsg {}).then(|_| Ok::<(), ()>(()))),
+            )
+            .map(|_| ()),
+        )
+    }
+}
+
+// Ctrl-C and SIGTERM would otherwise end the process before the Actors could save their state.
+// The signal handlers are installed when the stream is first polled.
+fn termination_signals(
+    logger: &masq_lib::logger::Logger,
+) -> Box<dyn Stream<Item = (), Error = ()>> {
+    let ctrl_c = listen_for(tokio_signal::ctrl_c(), "Ctrl-C", logger);
+    #[cfg(unix)]
+    {
+        use tokio_signal::unix::{Signal, SIGTERM};
+
+        let sigterm = listen_for(Signal::new(SIGTERM), "SIGTERM", logger);
+        Box::new(ctrl_c.select(sigterm))
+    }
+    #[cfg(not(unix))]
+    {
+        Box::new(ctrl_c)
+    }
+}
+
+fn listen_for<F>(
+    signal: F,
+    name: &'static str,
+    logger: &masq_lib::logger::Logger,
+) -> Box<dyn Stream<Item = (), Error = ()>>
+where
+    F: Future<Error = io::Error> + 'static,
+    F::Item: Stream<Error = io::Error>,
+{
+    let logger = logger.clone();
+    Box::new(
+        signal
+            .flatten_stream()
+            .then(move |result| match result {
+                Ok(_) => Ok(true),
+                Err(e) => {
+                    error!(logger, "Cannot listen for {}: {:?}", name, e);
+                    Ok(false)
+                }
+            })
+            .filter(|received| *received)
+            .map(|_| ()),
+    )
+}
+
+// The ActorSystemFactory hands these over once the Actors are bound
+pub fn register_shutdown_subs(subs: ShutdownSubs) {
+    *SHUTDOWN_SUBS.lock().expect("Shutdown subs poisoned") = subs
+}
+
 trait ResultsCombiner {
     fn combine_results(self, additional: Self) -> Self;
 }
@@ -124,6 +227,95 @@ impl ResultsCombiner for RunModeResult {
 
 lazy_static! {
     pub static ref LOGFILE_NAME: Mutex<PathBuf> = Mutex::new(PathBuf::from("uninitialized"));
+    static ref LOG_SPEC_CONTROL_OPT: Mutex<Option<LogSpecControl>> = Mutex::new(None);
+    static ref SHUTDOWN_SUBS: Arc<Mutex<ShutdownSubs>> = Arc::new(Mutex::new(vec![]));
+}
+
+struct LogSpecControl {
+    handle: ReconfigurationHandle,
+    levels: LogLevels,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogLevels {
+    pub global: LevelFilter,
+    pub modules: BTreeMap<String, LevelFilter>,
+}
+
+impl LogLevels {
+    pub fn new(global: LevelFilter) -> Self {
+        Self {
+            global,
+            modules: BTreeMap::new(),
+        }
+    }
+
+    pub fn update(
+        &mut self,
+        global_opt: Option<LevelFilter>,
+        module_levels: Vec<(String, LevelFilter)>,
+    ) {
+        if let Some(global) = global_opt {
+            self.global = global;
+        }
+        module_levels.into_iter().for_each(|(module, level)| {
+            self.modules.insert(log_target(&module), level);
+        });
+    }
+
+    pub fn to_spec(&self) -> LogSpecification {
+        let mut builder = LogSpecBuilder::new();
+        builder
+            .default(self.global)
+            .module("tokio", LevelFilter::Off)
+            .module("mio", LevelFilter::Off);
+        self.modules.iter().for_each(|(module, level)| {
+            builder.module(module, *level);
+        });
+        builder.build()
+    }
+}
+
+impl Display for LogLevels {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.global.to_string().to_lowercase())?;
+        self.modules.iter().try_for_each(|(module, level)| {
+            write!(f, ", {}={}", module, level.to_string().to_lowercase())
+        })
+    }
+}
+
+pub trait LogLevelSetter {
+    // Returns the complete set of levels now in force, in log-specification form
+    fn set_log_levels(
+        &self,
+        global_opt: Option<LevelFilter>,
+        module_levels: Vec<(String, LevelFilter)>,
+    ) -> Result<String, String>;
+}
+
+pub struct LogLevelSetterReal;
+
+impl LogLevelSetter for LogLevelSetterReal {
+    fn set_log_levels(
+        &self,
+        global_opt: Option<LevelFilter>,
+        module_levels: Vec<(String, LevelFilter)>,
+    ) -> Result<String, String> {
+        let mut control_opt = LOG_SPEC_CONTROL_OPT
+            .lock()
+            .expect("log spec control poisoned");
+        match control_opt.as_mut() {
+            Some(control) => {
+                control.levels.update(global_opt, module_levels);
+                control.handle.set_new_spec(control.levels.to_spec());
+                Ok(control.levels.to_string())
+            }
+            None => {
+                Err("Logging has not been started; there are no log levels to change".to_string())
+            }
+        }
+    }
 }
 
 pub trait LoggerInitializerWrapper {
@@ -146,28 +338,27 @@ impl LoggerInitializerWrapper for LoggerInitializerWrapperReal {
         log_level: LevelFilter,
         discriminant_opt: Option<&str>,
     ) {
-        let mut logger = Logger::with(
-            LogSpecBuilder::new()
-                .default(log_level)
-                .module("tokio", LevelFilter::Off)
-                .module("mio", LevelFilter::Off)
-                .build(),
-        )
-        .log_to_file()
-        .directory(file_path.clone())
-        .print_message()
-        .duplicate_to_stderr(Duplicate::Info)
-        .suppress_timestamp()
-        .format(format_function)
-        .rotate(
-            Criterion::Size(100_000_000),
-            Naming::Numbers,
-            Cleanup::KeepZipFiles(50),
-        );
+        let levels = LogLevels::new(log_level);
+        let mut logger = Logger::with(levels.to_spec())
+            .log_to_file()
+            .directory(file_path.clone())
+            .print_message()
+            .duplicate_to_stderr(Duplicate::Info)
+            .suppress_timestamp()
+            .format(format_function)
+            .rotate(
+                Criterion::Size(100_000_000),
+                Naming::Numbers,
+                Cleanup::KeepZipFiles(50),
+            );
         if let Some(discriminant) = discriminant_opt {
             logger = logger.discriminant(discriminant);
         }
-        logger.start().expect("Logging subsystem failed to start");
+        let handle = logger.start().expect("Logging subsystem failed to start");
+        LOG_SPEC_CONTROL_OPT
+            .lock()
+            .expect("log spec control poisoned")
+            .replace(LogSpecControl { handle, levels });
         let privilege_dropper = PrivilegeDropperReal::new();
         let logfile_name = file_path.join(format!(
             "MASQNode_{}rCURRENT.log",
@@ -275,7 +466,7 @@ fn format_function(
 pub mod test_utils {
     use crate::bootstrapper::RealUser;
     use crate::privilege_drop::PrivilegeDropper;
-    use crate::server_initializer::LoggerInitializerWrapper;
+    use crate::server_initializer::{LogLevelSetter, LoggerInitializerWrapper};
     use log::LevelFilter;
     use std::cell::RefCell;
     use std::path::{Path, PathBuf};
@@ -343,6 +534,45 @@ pub mod test_utils {
         }
     }
 
+    #[derive(Default)]
+    pub struct LogLevelSetterMock {
+        set_log_levels_params: Arc<Mutex<Vec<(Option<LevelFilter>, Vec<(String, LevelFilter)>)>>>,
+        set_log_levels_results: RefCell<Vec<Result<String, String>>>,
+    }
+
+    impl LogLevelSetter for LogLevelSetterMock {
+        fn set_log_levels(
+            &self,
+            global_opt: Option<LevelFilter>,
+            module_levels: Vec<(String, LevelFilter)>,
+        ) -> Result<String, String> {
+            self.set_log_levels_params
+                .lock()
+                .unwrap()
+                .push((global_opt, module_levels));
+            self.set_log_levels_results.borrow_mut().remove(0)
+        }
+    }
+
+    impl LogLevelSetterMock {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        pub fn set_log_levels_params(
+            mut self,
+            params: &Arc<Mutex<Vec<(Option<LevelFilter>, Vec<(String, LevelFilter)>)>>>,
+        ) -> Self {
+            self.set_log_levels_params = params.clone();
+            self
+        }
+
+        pub fn set_log_levels_result(self, result: Result<String, String>) -> Self {
+            self.set_log_levels_results.borrow_mut().push(result);
+            self
+        }
+    }
+
     pub struct LoggerInitializerWrapperMock {
         init_parameters: Arc<Mutex<Vec<(PathBuf, RealUser, LevelFilter, Option<String>)>>>,
     }
@@ -392,7 +622,9 @@ pub mod tests {
     use crate::node_test_utils::DirsWrapperMock;
     use crate::server_initializer::test_utils::PrivilegeDropperMock;
     use crate::test_utils::logfile_name_guard::LogfileNameGuard;
+    use crate::test_utils::recorder::make_recorder;
     use crate::test_utils::unshared_test_utils::make_pre_populated_mocked_directory_wrapper;
+    use actix::Actor;
     use masq_lib::constants::DEFAULT_CHAIN;
     use masq_lib::crash_point::CrashPoint;
     use masq_lib::multi_config::MultiConfig;
@@ -693,6 +925,10 @@ pub mod tests {
             bootstrapper: Box::new(bootstrapper),
             privilege_dropper: Box::new(privilege_dropper),
             dirs_wrapper: Box::new(dirs_wrapper),
+            termination_signals: Box::new(stream::empty()),
+            shutdown_subs: Arc::new(Mutex::new(vec![])),
+            shutdown_opt: None,
+            logger: masq_lib::logger::Logger::new("test"),
         };
         let stdin = &mut ByteArrayReader::new(&[0; 0]);
         let stdout = &mut ByteArrayWriter::new();
@@ -722,12 +958,76 @@ pub mod tests {
             bootstrapper: Box::new(bootstrapper),
             privilege_dropper: Box::new(privilege_dropper),
             dirs_wrapper: Box::new(dirs_wrapper),
+            termination_signals: Box::new(stream::empty()),
+            shutdown_subs: Arc::new(Mutex::new(vec![])),
+            shutdown_opt: None,
+            logger: masq_lib::logger::Logger::new("test"),
         };
 
         let result = subject.poll();
         assert_eq!(result, Ok(Async::Ready(())))
     }
 
+    #[test]
+    fn termination_signal_broadcasts_shutdown_and_stops_the_system_once_the_actors_answer() {
+        init_test_logging();
+        let system = System::new(
+            "termination_signal_broadcasts_shutdown_and_stops_the_system_once_the_actors_answer",
+        );
+        let (neighborhood, _, neighborhood_recording_arc) = make_recorder();
+        let neighborhood_addr = neighborhood.start();
+        let subject = ServerInitializerReal {
+            dns_socket_server: Box::new(CrashTestDummy::new(CrashPoint::None, ())),
+            bootstrapper: Box::new(CrashTestDummy::new(
+                CrashPoint::None,
+                BootstrapperConfig::new(),
+            )),
+            privilege_dropper: Box::new(PrivilegeDropperMock::new()),
+            dirs_wrapper: Box::new(DirsWrapperMock::new()),
+            termination_signals: Box::new(stream::iter_ok(vec![()])),
+            shutdown_subs: Arc::new(Mutex::new(vec![recipient!(neighborhood_addr, ShutdownMsg)])),
+            shutdown_opt: None,
+            logger: masq_lib::logger::Logger::new("termination_signal_broadcasts_shutdown"),
+        };
+
+        actix::spawn(subject);
+        system.run();
+
+        let neighborhood_recording = neighborhood_recording_arc.lock().unwrap();
+        assert_eq!(
+            neighborhood_recording.get_record::<ShutdownMsg>(0),
+            &ShutdownMsg {}
+        );
+        let tlh = TestLogHandler::new();
+        tlh.exists_log_containing(
+            "INFO: termination_signal_broadcasts_shutdown: Received termination signal: \
+             telling 1 Actors to shut down",
+        );
+        tlh.exists_log_containing("INFO: termination_signal_broadcasts_shutdown: Shutting down");
+    }
+
+    #[test]
+    fn server_initializer_carries_on_without_termination_signals_it_cannot_listen_for() {
+        let mut subject = ServerInitializerReal {
+            dns_socket_server: Box::new(CrashTestDummy::new(CrashPoint::None, ())),
+            bootstrapper: Box::new(CrashTestDummy::new(
+                CrashPoint::None,
+                BootstrapperConfig::new(),
+            )),
+            privilege_dropper: Box::new(PrivilegeDropperMock::new()),
+            dirs_wrapper: Box::new(DirsWrapperMock::new()),
+            termination_signals: Box::new(stream::iter_result(vec![Err(())])),
+            shutdown_subs: Arc::new(Mutex::new(vec![])),
+            shutdown_opt: None,
+            logger: masq_lib::logger::Logger::new("test"),
+        };
+
+        let result = subject.poll();
+
+        assert_eq!(result, Ok(Async::Ready(())));
+        assert!(subject.shutdown_opt.is_none());
+    }
+
     #[test]
     // TODO: GH-525: It should panic
     // #[should_panic(expected = "EntryDnsServerMock was instructed to panic")]
@@ -744,6 +1044,10 @@ pub mod tests {
             bootstrapper: Box::new(bootstrapper),
             privilege_dropper: Box::new(privilege_dropper),
             dirs_wrapper: Box::new(dirs_wrapper),
+            termination_signals: Box::new(stream::empty()),
+            shutdown_subs: Arc::new(Mutex::new(vec![])),
+            shutdown_opt: None,
+            logger: masq_lib::logger::Logger::new("test"),
         };
 
         let _ = subject.poll();
@@ -763,6 +1067,10 @@ pub mod tests {
             )),
             privilege_dropper: Box::new(privilege_dropper),
             dirs_wrapper: Box::new(dirs_wrapper),
+            termination_signals: Box::new(stream::empty()),
+            shutdown_subs: Arc::new(Mutex::new(vec![])),
+            shutdown_opt: None,
+            logger: masq_lib::logger::Logger::new("test"),
         };
 
         let _ = subject.poll();
@@ -812,6 +1120,10 @@ pub mod tests {
             bootstrapper: Box::new(bootstrapper),
             privilege_dropper: Box::new(privilege_dropper),
             dirs_wrapper: Box::new(dirs_wrapper),
+            termination_signals: Box::new(stream::empty()),
+            shutdown_subs: Arc::new(Mutex::new(vec![])),
+            shutdown_opt: None,
+            logger: masq_lib::logger::Logger::new("test"),
         };
 
         let result = subject.go(
@@ -884,6 +1196,10 @@ pub mod tests {
             bootstrapper: Box::new(bootstrapper),
             privilege_dropper: Box::new(privilege_dropper),
             dirs_wrapper: Box::new(make_pre_populated_mocked_directory_wrapper()),
+            termination_signals: Box::new(stream::empty()),
+            shutdown_subs: Arc::new(Mutex::new(vec![])),
+            shutdown_opt: None,
+            logger: masq_lib::logger::Logger::new("test"),
         };
         let args =
             slice_of_strs_to_vec_of_strings(&["MASQNode", "--real-user", "123:123:/home/alice"]);
@@ -904,4 +1220,45 @@ pub mod tests {
             ]))
         );
     }
+
+    #[test]
+    fn log_levels_accumulate_module_levels_under_normalized_names() {
+        let mut subject = LogLevels::new(LevelFilter::Info);
+
+        subject.update(
+            None,
+            vec![
+                ("node::neighborhood".to_string(), LevelFilter::Trace),
+                ("Hopper".to_string(), LevelFilter::Warn),
+            ],
+        );
+        subject.update(
+            Some(LevelFilter::Warn),
+            vec![("hopper".to_string(), LevelFilter::Debug)],
+        );
+
+        assert_eq!(
+            subject.to_string(),
+            "warn, hopper=debug, neighborhood=trace"
+        );
+        let spec = subject.to_spec();
+        assert_eq!(spec.enabled(Level::Trace, "neighborhood"), true);
+        assert_eq!(spec.enabled(Level::Debug, "hopper"), true);
+        assert_eq!(spec.enabled(Level::Trace, "hopper"), false);
+        assert_eq!(spec.enabled(Level::Warn, "proxyserver"), true);
+        assert_eq!(spec.enabled(Level::Info, "proxyserver"), false);
+        assert_eq!(spec.enabled(Level::Error, "tokio::reactor"), false);
+    }
+
+    #[test]
+    fn log_level_setter_real_complains_if_logging_has_not_been_started() {
+        let subject = LogLevelSetterReal;
+
+        let result = subject.set_log_levels(Some(LevelFilter::Debug), vec![]);
+
+        assert_eq!(
+            result,
+            Err("Logging has not been started; there are no log levels to change".to_string())
+        );
+    }
 }
@@ -2,15 +2,16 @@
 
 use crate::bootstrapper::PortConfiguration;
 use crate::discriminator::DiscriminatorFactory;
-use crate::json_masquerader::JsonMasquerader;
-use crate::masquerader::Masquerader;
+use crate::masquerader::{ConnectionSide, Masquerader, MasqueraderKind};
 use crate::stream_messages::*;
 use crate::stream_reader::StreamReaderReal;
 use crate::stream_writer_sorted::StreamWriterSorted;
 use crate::stream_writer_unsorted::StreamWriterUnsorted;
+use crate::sub_lib::bandwidth_shaper::BandwidthShaper;
 use crate::sub_lib::channel_wrappers::FuturesChannelFactory;
 use crate::sub_lib::channel_wrappers::FuturesChannelFactoryReal;
 use crate::sub_lib::channel_wrappers::SenderWrapper;
+use crate::sub_lib::clandestine_tls::ClandestineTls;
 use crate::sub_lib::cryptde::PublicKey;
 use crate::sub_lib::dispatcher;
 use crate::sub_lib::dispatcher::Endpoint;
@@ -31,19 +32,21 @@ use crate::sub_lib::stream_handler_pool::TransmitDataMsg;
 use crate::sub_lib::tokio_wrappers::ReadHalfWrapper;
 use crate::sub_lib::tokio_wrappers::WriteHalfWrapper;
 use crate::sub_lib::utils::{handle_ui_crash_request, MessageScheduler, NODE_MAILBOX_CAPACITY};
+use crate::websocket_masquerader::WebSocketMasquerader;
 use actix::Addr;
 use actix::Context;
 use actix::Handler;
 use actix::Recipient;
 use actix::{Actor, AsyncContext};
 use masq_lib::logger::Logger;
+use masq_lib::redaction::MarkSensitive;
 use masq_lib::ui_gateway::NodeFromUiMessage;
 use masq_lib::utils::localhost;
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 use std::io;
 use std::net::SocketAddr;
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 use tokio::prelude::Future;
 
 // IMPORTANT: Nothing at or below the level of StreamHandlerPool should know about StreamKeys.
@@ -52,12 +55,14 @@ use tokio::prelude::Future;
 // concept leak down this far.
 
 pub const CRASH_KEY: &str = "STREAMHANDLERPOOL";
+pub const MASQUERADE_UPGRADE_TIMEOUT: Duration = Duration::from_secs(2);
 
 #[derive(PartialEq, Eq)]
 pub struct StreamHandlerPoolSubs {
     pub add_sub: Recipient<AddStreamMsg>,
     pub transmit_sub: Recipient<TransmitDataMsg>,
     pub remove_sub: Recipient<RemoveStreamMsg>,
+    pub masquerade_negotiated_sub: Recipient<MasqueradeNegotiatedMsg>,
     pub bind: Recipient<PoolBindMessage>,
     pub node_query_response: Recipient<DispatcherNodeQueryResponse>,
     pub node_from_ui_sub: Recipient<NodeFromUiMessage>,
@@ -70,6 +75,7 @@ impl Clone for StreamHandlerPoolSubs {
             add_sub: self.add_sub.clone(),
             transmit_sub: self.transmit_sub.clone(),
             remove_sub: self.remove_sub.clone(),
+            masquerade_negotiated_sub: self.masquerade_negotiated_sub.clone(),
             bind: self.bind.clone(),
             node_query_response: self.node_query_response.clone(),
             node_from_ui_sub: self.node_from_ui_sub.clone(),
@@ -105,6 +111,12 @@ impl Display for StreamWriterKey {
     }
 }
 
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum MasqueradeNegotiation {
+    UpgradeRequested(SystemTime),
+    Settled(MasqueraderKind, ConnectionSide),
+}
+
 // TODO: To avoid confusion with ProxyClient's StreamHandlerPool, rename this one or the other for easy identification.
 // It is used to store streams for both neighbors and browser.
 pub struct StreamHandlerPool {
@@ -119,6 +131,10 @@ pub struct StreamHandlerPool {
     stream_connector: Box<dyn StreamConnector>,
     channel_factory: Box<dyn FuturesChannelFactory<SequencedPacket>>,
     clandestine_discriminator_factories: Vec<Box<dyn DiscriminatorFactory>>,
+    masquerader_kind: MasqueraderKind,
+    masquerade_negotiations: HashMap<StreamWriterKey, MasqueradeNegotiation>,
+    clandestine_tls_opt: Option<ClandestineTls>,
+    bandwidth_shaper: BandwidthShaper,
     traffic_analyzer: Box<dyn TrafficAnalyzer>,
 }
 
@@ -142,6 +158,14 @@ impl Handler<RemoveStreamMsg> for StreamHandlerPool {
     }
 }
 
+impl Handler<MasqueradeNegotiatedMsg> for StreamHandlerPool {
+    type Result = ();
+
+    fn handle(&mut self, msg: MasqueradeNegotiatedMsg, _ctx: &mut Self::Context) -> Self::Result {
+        self.handle_masquerade_negotiated_msg(msg)
+    }
+}
+
 impl Handler<TransmitDataMsg> for StreamHandlerPool {
     type Result = ();
 
@@ -179,6 +203,8 @@ impl Handler<PoolBindMessage> for StreamHandlerPool {
         self.ask_neighborhood_opt = Some(msg.neighborhood_subs.dispatcher_node_query);
         self.remove_neighbor_sub_opt = Some(msg.neighborhood_subs.remove_neighbor);
         self.connection_progress_sub_opt = Some(msg.neighborhood_subs.connection_progress_sub);
+        self.bandwidth_shaper
+            .set_routes_data_change_sub(msg.neighborhood_subs.routes_data_change_sub);
     }
 }
 
@@ -193,6 +219,9 @@ impl Handler<NodeFromUiMessage> for StreamHandlerPool {
 impl StreamHandlerPool {
     pub fn new(
         clandestine_discriminator_factories: Vec<Box<dyn DiscriminatorFactory>>,
+        masquerader_kind: MasqueraderKind,
+        clandestine_tls_opt: Option<ClandestineTls>,
+        bandwidth_shaper: BandwidthShaper,
         crashable: bool,
     ) -> StreamHandlerPool {
         StreamHandlerPool {
@@ -207,6 +236,10 @@ impl StreamHandlerPool {
             stream_connector: Box::new(StreamConnectorReal {}),
             channel_factory: Box::new(FuturesChannelFactoryReal {}),
             clandestine_discriminator_factories,
+            masquerader_kind,
+            masquerade_negotiations: HashMap::new(),
+            clandestine_tls_opt,
+            bandwidth_shaper,
             traffic_analyzer: Box::new(TrafficAnalyzerReal {}),
         }
     }
@@ -216,6 +249,7 @@ impl StreamHandlerPool {
             add_sub: recipient!(pool_addr, AddStreamMsg),
             transmit_sub: recipient!(pool_addr, TransmitDataMsg),
             remove_sub: recipient!(pool_addr, RemoveStreamMsg),
+            masquerade_negotiated_sub: recipient!(pool_addr, MasqueradeNegotiatedMsg),
             bind: recipient!(pool_addr, PoolBindMessage),
             node_query_response: recipient!(pool_addr, DispatcherNodeQueryResponse),
             node_from_ui_sub: recipient!(pool_addr, NodeFromUiMessage),
@@ -246,6 +280,12 @@ impl StreamHandlerPool {
             .expect("StreamHandlerPool is unbound")
             .remove_sub
             .clone();
+        let masquerade_negotiated_sub: Recipient<MasqueradeNegotiatedMsg> = self
+            .self_subs_opt
+            .as_ref()
+            .expect("StreamHandlerPool is unbound")
+            .masquerade_negotiated_sub
+            .clone();
         let dispatcher_shutdown_sub: Recipient<StreamShutdownMsg> = self
             .dispatcher_subs_opt
             .as_ref()
@@ -257,11 +297,13 @@ impl StreamHandlerPool {
             origin_port,
             ibcd_sub,
             remove_sub,
+            masquerade_negotiated_sub,
             dispatcher_shutdown_sub,
             port_configuration.discriminator_factories.clone(),
             port_configuration.is_clandestine,
             peer_addr,
             local_addr,
+            self.bandwidth_shaper.clone(),
         );
         debug!(
             self.logger,
@@ -272,8 +314,8 @@ impl StreamHandlerPool {
                 "non-"
             },
             origin_port,
-            local_addr,
-            peer_addr
+            local_addr.sensitive(),
+            peer_addr.sensitive()
         );
         tokio::spawn(stream_reader);
     }
@@ -289,7 +331,12 @@ impl StreamHandlerPool {
             .insert(StreamWriterKey::from(peer_addr), Some(tx));
 
         if is_clandestine {
-            tokio::spawn(StreamWriterUnsorted::new(write_stream, peer_addr, rx));
+            tokio::spawn(StreamWriterUnsorted::new(
+                write_stream,
+                peer_addr,
+                rx,
+                self.bandwidth_shaper.clone(),
+            ));
         } else {
             tokio::spawn(StreamWriterSorted::new(write_stream, peer_addr, rx));
         };
@@ -318,7 +365,8 @@ impl StreamHandlerPool {
                 };
                 debug!(
                     self.logger,
-                    "Sending node query about {} to Neighborhood", key
+                    "Sending node query about {} to Neighborhood",
+                    key.sensitive()
                 );
                 self.ask_neighborhood_opt
                     .as_ref()
@@ -330,7 +378,8 @@ impl StreamHandlerPool {
                 // The socket_addr can either be for the Neighbor or the browser
                 debug!(
                     self.logger,
-                    "Translating TransmitDataMsg to node query response about {}", socket_addr
+                    "Translating TransmitDataMsg to node query response about {}",
+                    socket_addr.sensitive()
                 );
                 node_query_response_recipient
                     .try_send(DispatcherNodeQueryResponse {
@@ -338,6 +387,7 @@ impl StreamHandlerPool {
                             PublicKey::new(&[]),
                             Some(NodeAddr::from(&socket_addr)),
                             ZERO_RATE_PACK,
+                            false,
                         )),
                         context: msg,
                     })
@@ -356,6 +406,9 @@ impl StreamHandlerPool {
             msg.connection_info.peer_addr,
             port_config.is_clandestine,
         );
+        if port_config.is_clandestine {
+            self.request_masquerade_upgrade_if_pending(msg.connection_info.peer_addr);
+        }
         self.set_up_stream_reader(
             msg.connection_info.reader,
             msg.origin_port,
@@ -365,21 +418,125 @@ impl StreamHandlerPool {
         );
     }
 
+    fn request_masquerade_upgrade_if_pending(&mut self, peer_addr: SocketAddr) {
+        let sw_key = StreamWriterKey::from(peer_addr);
+        if let Some(MasqueradeNegotiation::UpgradeRequested(_)) =
+            self.masquerade_negotiations.get(&sw_key)
+        {
+            let request = WebSocketMasquerader::upgrade_request(&peer_addr.ip().to_string());
+            let sent = match self.stream_writers.get(&sw_key) {
+                Some(Some(tx_box)) => tx_box
+                    .unbounded_send(SequencedPacket::new(request, 0, false))
+                    .is_ok(),
+                _ => false,
+            };
+            let negotiation = if sent {
+                debug!(
+                    self.logger,
+                    "Asked {} to upgrade to {} masquerade",
+                    peer_addr.sensitive(),
+                    MasqueraderKind::WebSocket
+                );
+                MasqueradeNegotiation::UpgradeRequested(SystemTime::now())
+            } else {
+                MasqueradeNegotiation::Settled(MasqueraderKind::Json, ConnectionSide::Connecting)
+            };
+            self.masquerade_negotiations.insert(sw_key, negotiation);
+        }
+    }
+
+    fn handle_masquerade_negotiated_msg(&mut self, msg: MasqueradeNegotiatedMsg) {
+        let sw_key = StreamWriterKey::from(msg.peer_addr);
+        // A reply means the peer asked us to upgrade: it opened the connection and we accepted it.
+        let side = if msg.reply_opt.is_some() {
+            ConnectionSide::Accepting
+        } else {
+            ConnectionSide::Connecting
+        };
+        if let Some(reply) = msg.reply_opt {
+            match self.stream_writers.get(&sw_key) {
+                Some(Some(tx_box)) => {
+                    if let Err(e) = tx_box.unbounded_send(SequencedPacket::new(reply, 0, false)) {
+                        error!(
+                            self.logger,
+                            "Couldn't answer masquerade negotiation from {}: {}",
+                            msg.peer_addr.sensitive(),
+                            e
+                        );
+                        return;
+                    }
+                }
+                _ => {
+                    warning!(
+                        self.logger,
+                        "Masquerade negotiation from {} arrived with no stream to answer it on",
+                        msg.peer_addr.sensitive()
+                    );
+                    return;
+                }
+            }
+        }
+        debug!(
+            self.logger,
+            "Using {} masquerade for data to {}",
+            msg.masquerader_kind,
+            sw_key.sensitive()
+        );
+        self.masquerade_negotiations.insert(
+            sw_key,
+            MasqueradeNegotiation::Settled(msg.masquerader_kind, side),
+        );
+    }
+
+    // None means that we've asked the peer to upgrade and haven't heard back yet.
+    fn negotiated_masquerade(
+        &mut self,
+        sw_key: StreamWriterKey,
+    ) -> Option<(MasqueraderKind, ConnectionSide)> {
+        match self.masquerade_negotiations.get(&sw_key) {
+            None => Some((MasqueraderKind::Json, ConnectionSide::Connecting)),
+            Some(MasqueradeNegotiation::Settled(kind, side)) => Some((*kind, *side)),
+            Some(MasqueradeNegotiation::UpgradeRequested(requested_at)) => {
+                let elapsed = requested_at.elapsed().unwrap_or_default();
+                if elapsed < MASQUERADE_UPGRADE_TIMEOUT {
+                    return None;
+                }
+                info!(
+                    self.logger,
+                    "{} did not accept {} masquerade within {}ms; falling back to {}",
+                    sw_key.sensitive(),
+                    MasqueraderKind::WebSocket,
+                    MASQUERADE_UPGRADE_TIMEOUT.as_millis(),
+                    MasqueraderKind::Json
+                );
+                self.masquerade_negotiations.insert(
+                    sw_key,
+                    MasqueradeNegotiation::Settled(
+                        MasqueraderKind::Json,
+                        ConnectionSide::Connecting,
+                    ),
+                );
+                Some((MasqueraderKind::Json, ConnectionSide::Connecting))
+            }
+        }
+    }
+
     fn handle_remove_stream_msg(&mut self, msg: RemoveStreamMsg) {
         let stream_writer_key = StreamWriterKey::from(msg.peer_addr);
+        self.masquerade_negotiations.remove(&stream_writer_key);
         debug!(
             self.logger,
             "Stream from local {} to peer {} has closed; removing writer with key {}",
-            msg.local_addr,
-            msg.peer_addr,
-            stream_writer_key
+            msg.local_addr.sensitive(),
+            msg.peer_addr.sensitive(),
+            stream_writer_key.sensitive()
         );
         let report_to_counterpart = match self.stream_writers.remove(&stream_writer_key) {
             None => {
                 trace!(
                     self.logger,
                     "While handling RemoveStreamMsg: Stream Writers did not contain any entry for key {}",
-                    stream_writer_key
+                    stream_writer_key.sensitive()
                 );
                 false
             }
@@ -388,7 +545,7 @@ impl StreamHandlerPool {
                     self.logger,
                     "An unpopulated entry in stream_writers was found for a {:?} stream ({:?}) from \
                     a client. This shouldn't be possible. Investigate!",
-                    msg.stream_type, stream_writer_key
+                    msg.stream_type, stream_writer_key.sensitive()
                 );
                 false
             }
@@ -396,7 +553,7 @@ impl StreamHandlerPool {
                 trace!(
                     self.logger,
                     "While handling RemoveStreamMsg: Stream Writers contained an entry for key {}, also found stream writer; removing",
-                    stream_writer_key
+                    stream_writer_key.sensitive()
                 );
                 true
             }
@@ -409,7 +566,7 @@ impl StreamHandlerPool {
         debug!(
             self.logger,
             "Signaling StreamShutdownMsg to Dispatcher for stream from {} with stream type {:?}, {}report to counterpart",
-            stream_shutdown_msg.peer_addr,
+            stream_shutdown_msg.peer_addr.sensitive(),
             stream_shutdown_msg.stream_type,
             if stream_shutdown_msg.report_to_counterpart {""} else {"don't "}
         );
@@ -472,18 +629,24 @@ impl StreamHandlerPool {
         peer_addr: SocketAddr,
         sw_key: StreamWriterKey,
     ) -> Result<(), String> {
+        let masquerade_opt = self.negotiated_masquerade(sw_key);
         let tx_box_opt_opt = self.stream_writers.get(&sw_key);
-        match tx_box_opt_opt {
-            Some(Some(tx_box)) => {
-                let remove_stream_writer =
-                    self.send_packet_on_open_stream(msg, peer_addr, sw_key, tx_box.as_ref())?;
+        match (tx_box_opt_opt, masquerade_opt) {
+            (Some(Some(tx_box)), Some(masquerade)) => {
+                let remove_stream_writer = self.send_packet_on_open_stream(
+                    msg,
+                    peer_addr,
+                    sw_key,
+                    tx_box.as_ref(),
+                    masquerade,
+                )?;
                 if remove_stream_writer {
                     self.stream_writers
                         .remove(&StreamWriterKey::from(peer_addr));
                 }
             }
-            Some(None) => self.delay_packet_for_opening_stream(msg, peer_addr, sw_key),
-            None => {
+            (Some(_), _) => self.delay_packet_for_opening_stream(msg, peer_addr, sw_key),
+            (None, _) => {
                 if peer_addr.ip() == localhost() {
                     return Err(format!(
                         "Local connection {:?} not found. Discarding {} bytes.",
@@ -494,6 +657,12 @@ impl StreamHandlerPool {
 
                 self.stream_writers
                     .insert(StreamWriterKey::from(peer_addr), None);
+                if self.masquerader_kind != MasqueraderKind::Json {
+                    self.masquerade_negotiations.insert(
+                        sw_key,
+                        MasqueradeNegotiation::UpgradeRequested(SystemTime::now()),
+                    );
+                }
 
                 self.open_new_stream_and_recycle_message(msg, peer_addr, sw_key);
             }
@@ -508,18 +677,25 @@ impl StreamHandlerPool {
         peer_addr: SocketAddr,
         sw_key: StreamWriterKey,
         tx_box: &dyn SenderWrapper<SequencedPacket>,
+        masquerade: (MasqueraderKind, ConnectionSide),
     ) -> Result<bool, String> {
         debug!(
             self.logger,
             "Found already-open stream to {} keyed by {}: using",
-            tx_box.peer_addr(),
-            sw_key
+            tx_box.peer_addr().sensitive(),
+            sw_key.sensitive()
         );
         debug!(self.logger, "Masking {} bytes", msg.context.data.len());
         let packet = if msg.context.sequence_number.is_none() {
-            let masquerader = self.traffic_analyzer.get_masquerader();
+            let (masquerader_kind, side) = masquerade;
+            let masquerader = self
+                .traffic_analyzer
+                .get_masquerader(masquerader_kind, side);
             match masquerader.mask(msg.context.data.as_slice()) {
-                Ok(masked_data) => SequencedPacket::new(masked_data, 0, false),
+                Ok(masked_data) => SequencedPacket {
+                    relayed: msg.context.relayed,
+                    ..SequencedPacket::new(masked_data, 0, false)
+                },
                 Err(e) => {
                     return Err(format!(
                         "Masking failed for {}: {}. Discarding {} bytes.",
@@ -538,7 +714,10 @@ impl StreamHandlerPool {
             Err(e) => {
                 error!(
                     self.logger,
-                    "Removing channel to disabled StreamWriter {} to {}: {}", sw_key, peer_addr, e
+                    "Removing channel to disabled StreamWriter {} to {}: {}",
+                    sw_key.sensitive(),
+                    peer_addr.sensitive(),
+                    e
                 );
                 // TODO GH-667 It looks like what we should do here is inform our caller somehow
                 // that we can no longer communicate with the Node specified in the route, and
@@ -554,8 +733,8 @@ impl StreamHandlerPool {
             debug!(
                 self.logger,
                 "Removing channel to StreamWriter {} to {} in response to server-drop report",
-                sw_key,
-                peer_addr
+                sw_key.sensitive(),
+                peer_addr.sensitive()
             );
             return Ok(true);
         }
@@ -571,14 +750,14 @@ impl StreamHandlerPool {
         debug!(
             self.logger,
             "Found in-the-process-of-being-opened stream to {} keyed by {}: preparing to use",
-            peer_addr,
-            sw_key
+            peer_addr.sensitive(),
+            sw_key.sensitive()
         );
         // a connection is already in progress. resubmit this message, to give the connection time to complete
         info!(
             self.logger,
             "connection for {} in progress, resubmitting {} bytes",
-            peer_addr,
+            peer_addr.sensitive(),
             msg.context.data.len()
         );
         let scheduled_node_query_response_sub = self
@@ -604,21 +783,48 @@ impl StreamHandlerPool {
     ) {
         debug!(
             self.logger,
-            "No existing stream keyed by {}: creating one to {}", sw_key, peer_addr
+            "No existing stream keyed by {}: creating one to {}",
+            sw_key.sensitive(),
+            peer_addr.sensitive()
         );
+        // Only Nodes that advertise TLS in their Gossip get a ClientHello; older Nodes and Nodes
+        // without --clandestine-tls on would take it for garbage.
+        let tls_public_key_opt = msg
+            .result
+            .as_ref()
+            .filter(|r| r.accepts_tls)
+            .map(|r| r.public_key.clone());
         let failure_handler = StreamStartFailureHandler::new(self, &msg, peer_addr);
         let success_handler = StreamStartSuccessHandler::new(self, msg, peer_addr);
 
-        let connect_future = self
-            .stream_connector
-            .connect(peer_addr, &self.logger)
+        let connection_info_future = match (&self.clandestine_tls_opt, &tls_public_key_opt) {
+            (Some(clandestine_tls), Some(public_key)) => {
+                debug!(
+                    self.logger,
+                    "Connecting to {} over TLS",
+                    peer_addr.sensitive()
+                );
+                self.stream_connector.connect_tls(
+                    peer_addr,
+                    clandestine_tls,
+                    public_key,
+                    &self.logger,
+                )
+            }
+            _ => self.stream_connector.connect(peer_addr, &self.logger),
+        };
+        let connect_future = connection_info_future
             .map(move |connection_info| success_handler.handle(connection_info))
             .map_err(move |err| {
                 // connection was unsuccessful
                 failure_handler.handle(err)
             });
 
-        debug!(self.logger, "Beginning connection attempt to {}", peer_addr);
+        debug!(
+            self.logger,
+            "Beginning connection attempt to {}",
+            peer_addr.sensitive()
+        );
         tokio::spawn(connect_future);
     }
 }
@@ -675,7 +881,7 @@ impl StreamStartFailureHandler {
         error!(
             self.logger,
             "Stream to {} does not exist and could not be connected; discarding {} bytes: {}",
-            self.peer_addr,
+            self.peer_addr.sensitive(),
             self.msg_data_len,
             err
         );
@@ -740,7 +946,8 @@ impl StreamStartSuccessHandler {
     pub fn handle(self, connection_info: ConnectionInfo) {
         debug!(
             self.logger,
-            "Connection attempt to {} succeeded", self.peer_addr
+            "Connection attempt to {} succeeded",
+            self.peer_addr.sensitive()
         );
         let origin_port = connection_info.local_addr.port();
         self.add_stream_sub
@@ -767,14 +974,22 @@ impl StreamStartSuccessHandler {
 }
 
 trait TrafficAnalyzer {
-    fn get_masquerader(&self) -> Box<dyn Masquerader>;
+    fn get_masquerader(
+        &self,
+        masquerader_kind: MasqueraderKind,
+        side: ConnectionSide,
+    ) -> Box<dyn Masquerader>;
 }
 
 struct TrafficAnalyzerReal {}
 
 impl TrafficAnalyzer for TrafficAnalyzerReal {
-    fn get_masquerader(&self) -> Box<dyn Masquerader> {
-        Box::new(JsonMasquerader::new())
+    fn get_masquerader(
+        &self,
+        masquerader_kind: MasqueraderKind,
+        side: ConnectionSide,
+    ) -> Box<dyn Masquerader> {
+        masquerader_kind.make(side)
     }
 }
 
@@ -787,7 +1002,9 @@ mod tests {
     use crate::json_discriminator_factory::JsonDiscriminatorFactory;
     use crate::json_masquerader::JsonMasquerader;
     use crate::masquerader::Masquerader;
-    use crate::node_test_utils::{check_timestamp, FailingMasquerader};
+    use crate::node_test_utils::{
+        check_timestamp, make_stream_handler_pool_subs_from_recorder, FailingMasquerader,
+    };
     use crate::sub_lib::dispatcher::InboundClientData;
     use crate::sub_lib::neighborhood::{
         ConnectionProgressEvent, ConnectionProgressMessage, NodeQueryResponseMetadata,
@@ -832,7 +1049,11 @@ mod tests {
     struct TrafficAnalyzerMock {}
 
     impl TrafficAnalyzer for TrafficAnalyzerMock {
-        fn get_masquerader(&self) -> Box<dyn Masquerader> {
+        fn get_masquerader(
+            &self,
+            _masquerader_kind: MasqueraderKind,
+            _side: ConnectionSide,
+        ) -> Box<dyn Masquerader> {
             Box::new(FailingMasquerader {})
         }
     }
@@ -861,7 +1082,13 @@ mod tests {
 
         thread::spawn(move || {
             let system = System::new("test");
-            let mut subject = StreamHandlerPool::new(vec![], false);
+            let mut subject = StreamHandlerPool::new(
+                vec![],
+                MasqueraderKind::Json,
+                None,
+                BandwidthShaper::default(),
+                false,
+            );
             subject.stream_connector = Box::new(StreamConnectorMock::new());
             let subject_addr: Addr<StreamHandlerPool> = subject.start();
             let subject_subs = StreamHandlerPool::make_subs_from(&subject_addr);
@@ -982,7 +1209,13 @@ mod tests {
 
         thread::spawn(move || {
             let system = System::new("test");
-            let subject = StreamHandlerPool::new(vec![], false);
+            let subject = StreamHandlerPool::new(
+                vec![],
+                MasqueraderKind::Json,
+                None,
+                BandwidthShaper::default(),
+                false,
+            );
 
             let subject_addr: Addr<StreamHandlerPool> = subject.start();
             let subject_subs = StreamHandlerPool::make_subs_from(&subject_addr);
@@ -1022,6 +1255,7 @@ mod tests {
                     last_data: true,
                     sequence_number: Some(0),
                     data: b"hello".to_vec(),
+                    relayed: false,
                 })
                 .unwrap();
 
@@ -1046,7 +1280,13 @@ mod tests {
         thread::spawn(move || {
             let system = System::new("test");
 
-            let mut subject = StreamHandlerPool::new(vec![], false);
+            let mut subject = StreamHandlerPool::new(
+                vec![],
+                MasqueraderKind::Json,
+                None,
+                BandwidthShaper::default(),
+                false,
+            );
             subject.stream_connector = Box::new(
                 StreamConnectorMock::new()
                     .connect_pair_result(Err(Error::from(ErrorKind::ConnectionRefused))),
@@ -1104,6 +1344,7 @@ mod tests {
                 last_data: true,
                 sequence_number: Some(0),
                 data: vec![0x12, 0x34],
+                relayed: false,
             })
             .unwrap();
 
@@ -1127,6 +1368,7 @@ mod tests {
                 last_data: true,
                 sequence_number: Some(0),
                 data: vec![0x56, 0x78],
+                relayed: false,
             })
             .unwrap();
 
@@ -1150,8 +1392,13 @@ mod tests {
         thread::spawn(move || {
             let system = System::new("test");
 
-            let mut subject =
-                StreamHandlerPool::new(vec![Box::new(JsonDiscriminatorFactory {})], false);
+            let mut subject = StreamHandlerPool::new(
+                vec![Box::new(JsonDiscriminatorFactory {})],
+                MasqueraderKind::Json,
+                None,
+                BandwidthShaper::default(),
+                false,
+            );
             subject.stream_connector = Box::new(StreamConnectorMock::new().connection(
                 local_addr,
                 peer_addr,
@@ -1206,6 +1453,7 @@ mod tests {
                     last_data: true,
                     sequence_number: Some(0),
                     data: vec![0x12, 0x34],
+                    relayed: false,
                 })
                 .unwrap();
 
@@ -1223,7 +1471,13 @@ mod tests {
         let (recorder, _, recording_arc) = make_recorder();
         let system = System::new("test");
         let sub = recorder.start().recipient::<StreamShutdownMsg>();
-        let mut subject = StreamHandlerPool::new(vec![], false);
+        let mut subject = StreamHandlerPool::new(
+            vec![],
+            MasqueraderKind::Json,
+            None,
+            BandwidthShaper::default(),
+            false,
+        );
         let peer_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
         let local_addr = SocketAddr::from_str("127.0.0.1:0").unwrap();
         let sw_key = StreamWriterKey::from(peer_addr);
@@ -1259,7 +1513,13 @@ mod tests {
         let (recorder, _, recording_arc) = make_recorder();
         let system = System::new("test");
         let sub = recorder.start().recipient::<StreamShutdownMsg>();
-        let mut subject = StreamHandlerPool::new(vec![], false);
+        let mut subject = StreamHandlerPool::new(
+            vec![],
+            MasqueraderKind::Json,
+            None,
+            BandwidthShaper::default(),
+            false,
+        );
         let peer_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
         let local_addr = SocketAddr::from_str("127.0.0.1:0").unwrap();
         let sw_key = StreamWriterKey::from(peer_addr);
@@ -1299,7 +1559,13 @@ mod tests {
         let (recorder, _, recording_arc) = make_recorder();
         let system = System::new(test_name);
         let sub = recorder.start().recipient::<StreamShutdownMsg>();
-        let mut subject = StreamHandlerPool::new(vec![], false);
+        let mut subject = StreamHandlerPool::new(
+            vec![],
+            MasqueraderKind::Json,
+            None,
+            BandwidthShaper::default(),
+            false,
+        );
         subject.logger = Logger::new(test_name);
         let peer_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
         let local_addr = SocketAddr::from_str("127.0.0.1:0").unwrap();
@@ -1344,7 +1610,13 @@ mod tests {
         let (neighborhood, neighborhood_awaiter, neighborhood_recording_arc) = make_recorder();
         thread::spawn(move || {
             let system = System::new("when_stream_handler_pool_fails_to_create_nonexistent_stream_for_write_then_it_logs_and_notifies_neighborhood");
-            let mut subject = StreamHandlerPool::new(vec![], false);
+            let mut subject = StreamHandlerPool::new(
+                vec![],
+                MasqueraderKind::Json,
+                None,
+                BandwidthShaper::default(),
+                false,
+            );
             subject.stream_connector = Box::new(
                 StreamConnectorMock::new()
                     .connect_pair_result(Err(Error::from(ErrorKind::Other)))
@@ -1372,12 +1644,14 @@ mod tests {
                             &[7000],
                         )),
                         rate_pack(100),
+                        false,
                     )),
                     context: TransmitDataMsg {
                         endpoint: Endpoint::Key(public_key),
                         last_data: false,
                         sequence_number: None,
                         data: b"hello".to_vec(),
+                        relayed: false,
                     },
                 })
                 .unwrap();
@@ -1399,6 +1673,159 @@ mod tests {
         );
     }
 
+    #[test]
+    fn stream_handler_pool_with_clandestine_tls_connects_over_tls_to_expected_key() {
+        init_test_logging();
+        let public_key = PublicKey::from(vec![0, 1, 2, 3]);
+        let expected_key = public_key.clone();
+        let connect_pair_params_arc = Arc::new(Mutex::new(vec![]));
+        let connect_pair_params_arc_a = connect_pair_params_arc.clone();
+        let connect_tls_params_arc = Arc::new(Mutex::new(vec![]));
+        let connect_tls_params_arc_a = connect_tls_params_arc.clone();
+        let (neighborhood, neighborhood_awaiter, _) = make_recorder();
+        thread::spawn(move || {
+            let system = System::new(
+                "stream_handler_pool_with_clandestine_tls_connects_over_tls_to_expected_key",
+            );
+            let mut subject = StreamHandlerPool::new(
+                vec![],
+                MasqueraderKind::Json,
+                Some(ClandestineTls::new(main_cryptde()).unwrap()),
+                BandwidthShaper::default(),
+                false,
+            );
+            subject.stream_connector = Box::new(
+                StreamConnectorMock::new()
+                    .connect_pair_result(Err(Error::from(ErrorKind::Other)))
+                    .connect_pair_params(&connect_pair_params_arc)
+                    .connect_tls_params(&connect_tls_params_arc),
+            );
+            let subject_addr: Addr<StreamHandlerPool> = subject.start();
+            let subject_subs = StreamHandlerPool::make_subs_from(&subject_addr);
+            let peer_actors = peer_actors_builder().neighborhood(neighborhood).build();
+            subject_subs
+                .bind
+                .try_send(PoolBindMessage {
+                    dispatcher_subs: peer_actors.dispatcher,
+                    stream_handler_pool_subs: subject_subs.clone(),
+                    neighborhood_subs: peer_actors.neighborhood,
+                })
+                .unwrap();
+
+            subject_subs
+                .node_query_response
+                .try_send(DispatcherNodeQueryResponse {
+                    result: Some(NodeQueryResponseMetadata::new(
+                        public_key.clone(),
+                        Some(NodeAddr::new(
+                            &IpAddr::V4(Ipv4Addr::new(1, 2, 3, 6)),
+                            &[7000],
+                        )),
+                        rate_pack(100),
+                        true,
+                    )),
+                    context: TransmitDataMsg {
+                        endpoint: Endpoint::Key(public_key),
+                        last_data: false,
+                        sequence_number: None,
+                        data: b"hello".to_vec(),
+                        relayed: false,
+                    },
+                })
+                .unwrap();
+
+            system.run();
+        });
+
+        neighborhood_awaiter.await_message_count(1);
+        TestLogHandler::new()
+            .exists_log_containing("DEBUG: Dispatcher: Connecting to 1.2.3.6:7000 over TLS");
+        let connect_tls_params = connect_tls_params_arc_a.lock().unwrap();
+        assert_eq!(
+            *connect_tls_params,
+            vec![(SocketAddr::from_str("1.2.3.6:7000").unwrap(), expected_key)]
+        );
+        assert_eq!(connect_pair_params_arc_a.lock().unwrap().len(), 0);
+    }
+
+    #[test]
+    fn stream_handler_pool_with_clandestine_tls_connects_in_the_clear_to_peers_that_do_not_advertise_tls(
+    ) {
+        init_test_logging();
+        let public_key = PublicKey::from(vec![0, 1, 2, 3]);
+        let expected_key = public_key.clone();
+        let connect_pair_params_arc = Arc::new(Mutex::new(vec![]));
+        let connect_pair_params_arc_a = connect_pair_params_arc.clone();
+        let connect_tls_params_arc = Arc::new(Mutex::new(vec![]));
+        let connect_tls_params_arc_a = connect_tls_params_arc.clone();
+        let (neighborhood, neighborhood_awaiter, neighborhood_recording_arc) = make_recorder();
+        thread::spawn(move || {
+            let system = System::new(
+                "stream_handler_pool_with_clandestine_tls_connects_in_the_clear_to_peers_that_do_not_advertise_tls",
+            );
+            let mut subject = StreamHandlerPool::new(
+                vec![],
+                MasqueraderKind::Json,
+                Some(ClandestineTls::new(main_cryptde()).unwrap()),
+                BandwidthShaper::default(),
+                false,
+            );
+            subject.stream_connector = Box::new(
+                StreamConnectorMock::new()
+                    .connect_pair_result(Err(Error::from(ErrorKind::Other)))
+                    .connect_pair_params(&connect_pair_params_arc)
+                    .connect_tls_params(&connect_tls_params_arc),
+            );
+            let subject_addr: Addr<StreamHandlerPool> = subject.start();
+            let subject_subs = StreamHandlerPool::make_subs_from(&subject_addr);
+            let peer_actors = peer_actors_builder().neighborhood(neighborhood).build();
+            subject_subs
+                .bind
+                .try_send(PoolBindMessage {
+                    dispatcher_subs: peer_actors.dispatcher,
+                    stream_handler_pool_subs: subject_subs.clone(),
+                    neighborhood_subs: peer_actors.neighborhood,
+                })
+                .unwrap();
+
+            subject_subs
+                .node_query_response
+                .try_send(DispatcherNodeQueryResponse {
+                    result: Some(NodeQueryResponseMetadata::new(
+                        public_key.clone(),
+                        Some(NodeAddr::new(
+                            &IpAddr::V4(Ipv4Addr::new(1, 2, 3, 6)),
+                            &[7000],
+                        )),
+                        rate_pack(100),
+                        false,
+                    )),
+                    context: TransmitDataMsg {
+                        endpoint: Endpoint::Key(public_key),
+                        last_data: false,
+                        sequence_number: None,
+                        data: b"hello".to_vec(),
+                        relayed: false,
+                    },
+                })
+                .unwrap();
+
+            system.run();
+        });
+
+        neighborhood_awaiter.await_message_count(1);
+        let remove_neighbor_msg =
+            Recording::get::<RemoveNeighborMessage>(&neighborhood_recording_arc, 0);
+        assert_eq!(remove_neighbor_msg.public_key, expected_key);
+        assert_eq!(
+            *connect_pair_params_arc_a.lock().unwrap(),
+            vec![SocketAddr::from_str("1.2.3.6:7000").unwrap()]
+        );
+        assert_eq!(connect_tls_params_arc_a.lock().unwrap().len(), 0);
+        TestLogHandler::new()
+            .exists_no_log_containing("DEBUG: Dispatcher: Connecting to 1.2.3.6:7000 over TLS");
+    }
+
     #[test]
     fn stream_handler_pool_creates_nonexistent_stream_for_reading_and_writing() {
         use crossbeam_channel::unbounded;
@@ -1419,7 +1846,13 @@ mod tests {
                 "stream_handler_pool_creates_nonexistent_stream_for_reading_and_writing",
             );
             let discriminator_factory = JsonDiscriminatorFactory::new();
-            let mut subject = StreamHandlerPool::new(vec![Box::new(discriminator_factory)], false);
+            let mut subject = StreamHandlerPool::new(
+                vec![Box::new(discriminator_factory)],
+                MasqueraderKind::Json,
+                None,
+                BandwidthShaper::default(),
+                false,
+            );
             subject.stream_connector = Box::new(
                 StreamConnectorMock::new().connect_pair_result(Ok(ConnectionInfo {
                     reader: Box::new(
@@ -1467,6 +1900,7 @@ mod tests {
                 last_data: false,
                 sequence_number: None,
                 data: outgoing_unmasked,
+                relayed: false,
             })
             .unwrap();
 
@@ -1481,6 +1915,7 @@ mod tests {
                     public_key.clone(),
                     Some(NodeAddr::new(&target_ip_addr, &[7000])),
                     rate_pack(100),
+                    false,
                 )),
                 context: node_query_msg.context,
             })
@@ -1539,7 +1974,13 @@ mod tests {
 
         thread::spawn(move || {
             let system = System::new("test");
-            let subject = StreamHandlerPool::new(vec![], false);
+            let subject = StreamHandlerPool::new(
+                vec![],
+                MasqueraderKind::Json,
+                None,
+                BandwidthShaper::default(),
+                false,
+            );
 
             let subject_addr: Addr<StreamHandlerPool> = subject.start();
             let subject_subs = StreamHandlerPool::make_subs_from(&subject_addr);
@@ -1583,6 +2024,7 @@ mod tests {
                 last_data: false,
                 sequence_number: Some(0),
                 data: b"hello".to_vec(),
+                relayed: false,
             })
             .unwrap();
 
@@ -1598,6 +2040,7 @@ mod tests {
                         &[6789],
                     )),
                     rate_pack(100),
+                    false,
                 )),
                 context: node_query_msg.context,
             })
@@ -1620,7 +2063,13 @@ mod tests {
 
         thread::spawn(move || {
             let system = System::new("test");
-            let subject = StreamHandlerPool::new(vec![], false);
+            let subject = StreamHandlerPool::new(
+                vec![],
+                MasqueraderKind::Json,
+                None,
+                BandwidthShaper::default(),
+                false,
+            );
 
             let subject_addr: Addr<StreamHandlerPool> = subject.start();
             let subject_subs = StreamHandlerPool::make_subs_from(&subject_addr);
@@ -1643,6 +2092,7 @@ mod tests {
                         last_data: false,
                         sequence_number: Some(0),
                         data: b"hello".to_vec(),
+                        relayed: false,
                     },
                 })
                 .unwrap();
@@ -1668,7 +2118,13 @@ mod tests {
 
         thread::spawn(move || {
             let system = System::new("test");
-            let subject = StreamHandlerPool::new(vec![], false);
+            let subject = StreamHandlerPool::new(
+                vec![],
+                MasqueraderKind::Json,
+                None,
+                BandwidthShaper::default(),
+                false,
+            );
 
             let subject_addr: Addr<StreamHandlerPool> = subject.start();
             let subject_subs = StreamHandlerPool::make_subs_from(&subject_addr);
@@ -1689,12 +2145,14 @@ mod tests {
                         key.clone(),
                         None,
                         rate_pack(100),
+                        false,
                     )),
                     context: TransmitDataMsg {
                         endpoint: Endpoint::Key(key.clone()),
                         last_data: false,
                         sequence_number: None,
                         data: b"hello".to_vec(),
+                        relayed: false,
                     },
                 })
                 .unwrap();
@@ -1725,6 +2183,7 @@ mod tests {
             last_data: false,
             sequence_number: Some(0),
             data: b"hello".to_vec(),
+            relayed: false,
         };
         let msg_a = msg.clone();
 
@@ -1732,7 +2191,13 @@ mod tests {
 
         thread::spawn(move || {
             let system = System::new("test");
-            let mut subject = StreamHandlerPool::new(vec![], false);
+            let mut subject = StreamHandlerPool::new(
+                vec![],
+                MasqueraderKind::Json,
+                None,
+                BandwidthShaper::default(),
+                false,
+            );
             subject
                 .stream_writers
                 .insert(StreamWriterKey::from(peer_addr), None);
@@ -1755,6 +2220,7 @@ mod tests {
                         key.clone(),
                         Some(NodeAddr::new(&peer_addr.ip(), &[peer_addr.port()])),
                         rate_pack(100),
+                        false,
                     )),
                     context: msg,
                 })
@@ -1810,6 +2276,314 @@ mod tests {
         assert_eq!(poll_write_params[0], msg_a.data);
     }
 
+    fn make_transmit_response(peer_addr: SocketAddr, data: &[u8]) -> DispatcherNodeQueryResponse {
+        DispatcherNodeQueryResponse {
+            result: Some(NodeQueryResponseMetadata {
+                public_key: main_cryptde().public_key().clone(),
+                node_addr_opt: Some(NodeAddr::new(&peer_addr.ip(), &[peer_addr.port()])),
+                rate_pack: ZERO_RATE_PACK.clone(),
+                accepts_tls: false,
+            }),
+            context: TransmitDataMsg {
+                endpoint: Endpoint::Socket(peer_addr),
+                last_data: false,
+                sequence_number: None,
+                data: data.to_vec(),
+                relayed: false,
+            },
+        }
+    }
+
+    #[test]
+    fn new_clandestine_stream_asks_peer_to_upgrade_when_websocket_is_preferred() {
+        let peer_addr = SocketAddr::from_str("1.2.3.5:5673").unwrap();
+        let sw_key = StreamWriterKey::from(peer_addr);
+        let unbounded_send_params_arc = Arc::new(Mutex::new(vec![]));
+        let sender_wrapper =
+            SenderWrapperMock::new(peer_addr).unbounded_send_params(&unbounded_send_params_arc);
+        let mut subject = StreamHandlerPool::new(
+            vec![],
+            MasqueraderKind::WebSocket,
+            None,
+            BandwidthShaper::default(),
+            false,
+        );
+        subject.masquerade_negotiations.insert(
+            sw_key,
+            MasqueradeNegotiation::UpgradeRequested(SystemTime::UNIX_EPOCH),
+        );
+        subject
+            .stream_writers
+            .insert(sw_key, Some(Box::new(sender_wrapper)));
+
+        subject.request_masquerade_upgrade_if_pending(peer_addr);
+
+        let unbounded_send_params = unbounded_send_params_arc.lock().unwrap();
+        assert_eq!(unbounded_send_params.len(), 1);
+        let request = &unbounded_send_params[0].data;
+        assert!(WebSocketMasquerader::is_upgrade_request(request));
+        assert!(String::from_utf8(request.clone())
+            .unwrap()
+            .contains("Host: 1.2.3.5\r\n"));
+        match subject.masquerade_negotiations.get(&sw_key) {
+            Some(MasqueradeNegotiation::UpgradeRequested(requested_at)) => {
+                assert!(requested_at.elapsed().unwrap() < MASQUERADE_UPGRADE_TIMEOUT)
+            }
+            x => panic!("Expected UpgradeRequested, got {:?}", x),
+        }
+    }
+
+    #[test]
+    fn new_clandestine_stream_does_not_ask_peer_to_upgrade_when_json_is_preferred() {
+        let peer_addr = SocketAddr::from_str("1.2.3.5:5673").unwrap();
+        let sw_key = StreamWriterKey::from(peer_addr);
+        let unbounded_send_params_arc = Arc::new(Mutex::new(vec![]));
+        let sender_wrapper =
+            SenderWrapperMock::new(peer_addr).unbounded_send_params(&unbounded_send_params_arc);
+        let mut subject = StreamHandlerPool::new(
+            vec![],
+            MasqueraderKind::Json,
+            None,
+            BandwidthShaper::default(),
+            false,
+        );
+        subject
+            .stream_writers
+            .insert(sw_key, Some(Box::new(sender_wrapper)));
+
+        subject.request_masquerade_upgrade_if_pending(peer_addr);
+
+        assert_eq!(unbounded_send_params_arc.lock().unwrap().len(), 0);
+        assert_eq!(subject.masquerade_negotiations.get(&sw_key), None);
+    }
+
+    #[test]
+    fn clandestine_data_waits_while_masquerade_upgrade_is_pending() {
+        let system = System::new("test");
+        let peer_addr = SocketAddr::from_str("1.2.3.5:5673").unwrap();
+        let sw_key = StreamWriterKey::from(peer_addr);
+        let unbounded_send_params_arc = Arc::new(Mutex::new(vec![]));
+        let sender_wrapper =
+            SenderWrapperMock::new(peer_addr).unbounded_send_params(&unbounded_send_params_arc);
+        let mut subject = StreamHandlerPool::new(
+            vec![],
+            MasqueraderKind::WebSocket,
+            None,
+            BandwidthShaper::default(),
+            false,
+        );
+        subject
+            .stream_writers
+            .insert(sw_key, Some(Box::new(sender_wrapper)));
+        subject.masquerade_negotiations.insert(
+            sw_key,
+            MasqueradeNegotiation::UpgradeRequested(SystemTime::now()),
+        );
+        let (pool, _, pool_recording_arc) = make_recorder();
+        subject.self_subs_opt = Some(make_stream_handler_pool_subs_from_recorder(&pool.start()));
+
+        subject.handle_dispatcher_node_query_response(make_transmit_response(peer_addr, b"hi"));
+
+        System::current().stop();
+        system.run();
+        assert_eq!(unbounded_send_params_arc.lock().unwrap().len(), 0);
+        let pool_recording = pool_recording_arc.lock().unwrap();
+        let scheduled =
+            pool_recording.get_record::<MessageScheduler<DispatcherNodeQueryResponse>>(0);
+        assert_eq!(scheduled.scheduled_msg.context.data, b"hi".to_vec());
+        assert_eq!(scheduled.delay, Duration::from_millis(100));
+    }
+
+    #[test]
+    fn clandestine_data_falls_back_to_json_when_masquerade_upgrade_times_out() {
+        init_test_logging();
+        let peer_addr = SocketAddr::from_str("1.2.3.5:5673").unwrap();
+        let sw_key = StreamWriterKey::from(peer_addr);
+        let unbounded_send_params_arc = Arc::new(Mutex::new(vec![]));
+        let sender_wrapper =
+            SenderWrapperMock::new(peer_addr).unbounded_send_params(&unbounded_send_params_arc);
+        let mut subject = StreamHandlerPool::new(
+            vec![],
+            MasqueraderKind::WebSocket,
+            None,
+            BandwidthShaper::default(),
+            false,
+        );
+        subject
+            .stream_writers
+            .insert(sw_key, Some(Box::new(sender_wrapper)));
+        subject.masquerade_negotiations.insert(
+            sw_key,
+            MasqueradeNegotiation::UpgradeRequested(
+                SystemTime::now() - MASQUERADE_UPGRADE_TIMEOUT - Duration::from_millis(1),
+            ),
+        );
+
+        subject.handle_dispatcher_node_query_response(make_transmit_response(peer_addr, b"hi"));
+
+        let unbounded_send_params = unbounded_send_params_arc.lock().unwrap();
+        assert_eq!(
+            unbounded_send_params[0].data,
+            JsonMasquerader::new().mask(b"hi").unwrap()
+        );
+        assert_eq!(
+            subject.masquerade_negotiations.get(&sw_key),
+            Some(&MasqueradeNegotiation::Settled(
+                MasqueraderKind::Json,
+                ConnectionSide::Connecting
+            ))
+        );
+        TestLogHandler::new().exists_log_containing(
+            "INFO: Dispatcher: 1.2.3.5:* did not accept websocket masquerade within 2000ms; falling back to json",
+        );
+    }
+
+    #[test]
+    fn masquerade_negotiation_reply_is_sent_and_later_data_is_masked_accordingly() {
+        let peer_addr = SocketAddr::from_str("1.2.3.5:5673").unwrap();
+        let sw_key = StreamWriterKey::from(peer_addr);
+        let unbounded_send_params_arc = Arc::new(Mutex::new(vec![]));
+        let sender_wrapper =
+            SenderWrapperMock::new(peer_addr).unbounded_send_params(&unbounded_send_params_arc);
+        let mut subject = StreamHandlerPool::new(
+            vec![],
+            MasqueraderKind::Json,
+            None,
+            BandwidthShaper::default(),
+            false,
+        );
+        subject
+            .stream_writers
+            .insert(sw_key, Some(Box::new(sender_wrapper)));
+
+        subject.handle_masquerade_negotiated_msg(MasqueradeNegotiatedMsg {
+            peer_addr,
+            masquerader_kind: MasqueraderKind::WebSocket,
+            reply_opt: Some(b"reply".to_vec()),
+        });
+        subject.handle_dispatcher_node_query_response(make_transmit_response(peer_addr, b"hi"));
+
+        let unbounded_send_params = unbounded_send_params_arc.lock().unwrap();
+        assert_eq!(unbounded_send_params.len(), 2);
+        assert_eq!(
+            unbounded_send_params[0],
+            SequencedPacket::new(b"reply".to_vec(), 0, false)
+        );
+        // We accepted the connection, so the frames we send back to the peer aren't masked
+        assert_eq!(unbounded_send_params[1].data, vec![0x82, 2, b'h', b'i']);
+        let unmasked = WebSocketMasquerader::new()
+            .try_unmask(&unbounded_send_params[1].data)
+            .unwrap();
+        assert_eq!(unmasked.chunk, b"hi".to_vec());
+    }
+
+    #[test]
+    fn masquerade_negotiation_response_makes_later_data_masked_by_the_connecting_side() {
+        let peer_addr = SocketAddr::from_str("1.2.3.5:5673").unwrap();
+        let sw_key = StreamWriterKey::from(peer_addr);
+        let unbounded_send_params_arc = Arc::new(Mutex::new(vec![]));
+        let sender_wrapper =
+            SenderWrapperMock::new(peer_addr).unbounded_send_params(&unbounded_send_params_arc);
+        let mut subject = StreamHandlerPool::new(
+            vec![],
+            MasqueraderKind::WebSocket,
+            None,
+            BandwidthShaper::default(),
+            false,
+        );
+        subject
+            .stream_writers
+            .insert(sw_key, Some(Box::new(sender_wrapper)));
+        subject.masquerade_negotiations.insert(
+            sw_key,
+            MasqueradeNegotiation::UpgradeRequested(SystemTime::now()),
+        );
+
+        subject.handle_masquerade_negotiated_msg(MasqueradeNegotiatedMsg {
+            peer_addr,
+            masquerader_kind: MasqueraderKind::WebSocket,
+            reply_opt: None,
+        });
+        subject.handle_dispatcher_node_query_response(make_transmit_response(peer_addr, b"hi"));
+
+        let unbounded_send_params = unbounded_send_params_arc.lock().unwrap();
+        assert_eq!(unbounded_send_params.len(), 1);
+        let frame = &unbounded_send_params[0].data;
+        assert_eq!(frame[1], 0x80 | 2);
+        assert_eq!(frame.len(), 2 + 4 + 2);
+        let unmasked = WebSocketMasquerader::for_side(ConnectionSide::Accepting)
+            .try_unmask(frame)
+            .unwrap();
+        assert_eq!(unmasked.chunk, b"hi".to_vec());
+        assert_eq!(
+            subject.masquerade_negotiations.get(&sw_key),
+            Some(&MasqueradeNegotiation::Settled(
+                MasqueraderKind::WebSocket,
+                ConnectionSide::Connecting
+            ))
+        );
+    }
+
+    #[test]
+    fn masquerade_negotiation_without_a_stream_is_ignored() {
+        init_test_logging();
+        let peer_addr = SocketAddr::from_str("1.2.3.6:5673").unwrap();
+        let mut subject = StreamHandlerPool::new(
+            vec![],
+            MasqueraderKind::Json,
+            None,
+            BandwidthShaper::default(),
+            false,
+        );
+
+        subject.handle_masquerade_negotiated_msg(MasqueradeNegotiatedMsg {
+            peer_addr,
+            masquerader_kind: MasqueraderKind::WebSocket,
+            reply_opt: Some(b"reply".to_vec()),
+        });
+
+        assert_eq!(
+            subject
+                .masquerade_negotiations
+                .get(&StreamWriterKey::from(peer_addr)),
+            None
+        );
+        TestLogHandler::new().exists_log_containing(
+            "WARN: Dispatcher: Masquerade negotiation from 1.2.3.6:5673 arrived with no stream to answer it on",
+        );
+    }
+
+    #[test]
+    fn removing_a_stream_forgets_its_masquerade_negotiation() {
+        let system = System::new("test");
+        let (recorder, _, _) = make_recorder();
+        let sub = recorder.start().recipient::<StreamShutdownMsg>();
+        let peer_addr = SocketAddr::from_str("1.2.3.5:5673").unwrap();
+        let sw_key = StreamWriterKey::from(peer_addr);
+        let mut subject = StreamHandlerPool::new(
+            vec![],
+            MasqueraderKind::Json,
+            None,
+            BandwidthShaper::default(),
+            false,
+        );
+        subject.masquerade_negotiations.insert(
+            sw_key,
+            MasqueradeNegotiation::Settled(MasqueraderKind::WebSocket, ConnectionSide::Connecting),
+        );
+
+        subject.handle_remove_stream_msg(RemoveStreamMsg {
+            peer_addr,
+            local_addr: SocketAddr::from_str("1.2.3.4:5673").unwrap(),
+            stream_type: RemovedStreamType::Clandestine,
+            dispatcher_sub: sub,
+        });
+
+        System::current().stop();
+        system.run();
+        assert_eq!(subject.masquerade_negotiations.get(&sw_key), None);
+    }
+
     #[test]
     fn log_an_error_when_it_fails_to_send_a_packet() {
         init_test_logging();
@@ -1822,7 +2596,13 @@ mod tests {
         let sender_wrapper = SenderWrapperMock::new(peer_addr)
             .unbounded_send_params(&sender_wrapper_unbounded_send_params_arc)
             .unbounded_send_result(Err(send_error));
-        let mut subject = StreamHandlerPool::new(vec![], false);
+        let mut subject = StreamHandlerPool::new(
+            vec![],
+            MasqueraderKind::Json,
+            None,
+            BandwidthShaper::default(),
+            false,
+        );
         subject
             .stream_writers
             .insert(sw_key, Some(Box::new(sender_wrapper)));
@@ -1831,12 +2611,14 @@ mod tests {
                 public_key: key,
                 node_addr_opt: Some(NodeAddr::new(&peer_addr.ip(), &[peer_addr.port()])),
                 rate_pack: ZERO_RATE_PACK.clone(),
+                accepts_tls: false,
             }),
             context: TransmitDataMsg {
                 endpoint: Endpoint::Socket(peer_addr.clone()),
                 last_data: true,
                 sequence_number: Some(0),
                 data: b"hello".to_vec(),
+                relayed: false,
             },
         };
 
@@ -1866,12 +2648,14 @@ mod tests {
             last_data: false,
             sequence_number: None,
             data: b"hello".to_vec(),
+            relayed: false,
         };
         let msg_a = TransmitDataMsg {
             endpoint: Endpoint::Socket(peer_addr.clone()),
             last_data: false,
             sequence_number: None,
             data: b"worlds".to_vec(),
+            relayed: false,
         };
         let expected_data = JsonMasquerader::new().mask(&msg_a.data).unwrap();
 
@@ -1895,7 +2679,13 @@ mod tests {
 
         thread::spawn(move || {
             let system = System::new("test");
-            let mut subject = StreamHandlerPool::new(vec![], false);
+            let mut subject = StreamHandlerPool::new(
+                vec![],
+                MasqueraderKind::Json,
+                None,
+                BandwidthShaper::default(),
+                false,
+            );
             subject.stream_connector = Box::new(
                 StreamConnectorMock::new()
                     .connect_pair_result(Err(Error::from(ErrorKind::Other)))
@@ -1922,6 +2712,7 @@ mod tests {
                         key_bg,
                         Some(NodeAddr::new(&peer_addr.ip(), &[peer_addr.port()])),
                         rate_pack(100),
+                        false,
                     )),
                     context: msg,
                 })
@@ -1941,6 +2732,7 @@ mod tests {
                     cryptde.public_key().clone(),
                     Some(NodeAddr::new(&peer_addr.ip(), &[peer_addr.port()])),
                     rate_pack(100),
+                    false,
                 )),
                 context: msg_a,
             })
@@ -1975,7 +2767,13 @@ mod tests {
         let sender_wrapper = SenderWrapperMock::new(peer_addr)
             .unbounded_send_params(&sender_wrapper_unbounded_send_params_arc)
             .unbounded_send_result(Ok(()));
-        let mut subject = StreamHandlerPool::new(vec![], false);
+        let mut subject = StreamHandlerPool::new(
+            vec![],
+            MasqueraderKind::Json,
+            None,
+            BandwidthShaper::default(),
+            false,
+        );
         subject.stream_writers.insert(
             StreamWriterKey::from(peer_addr),
             Some(Box::new(sender_wrapper)),
@@ -1986,12 +2784,14 @@ mod tests {
                 public_key: key,
                 node_addr_opt: Some(NodeAddr::new(&peer_addr.ip(), &[peer_addr.port()])),
                 rate_pack: ZERO_RATE_PACK.clone(),
+                accepts_tls: false,
             }),
             context: TransmitDataMsg {
                 endpoint: Endpoint::Socket(peer_addr.clone()),
                 last_data: true,
                 sequence_number: Some(0),
                 data: b"hello".to_vec(),
+                relayed: false,
             },
         });
 
@@ -2032,10 +2832,17 @@ mod tests {
             last_data: false,
             sequence_number: None,
             data: b"hello".to_vec(),
+            relayed: false,
         };
 
         let system = System::new("test");
-        let subject = StreamHandlerPool::new(vec![], false);
+        let subject = StreamHandlerPool::new(
+            vec![],
+            MasqueraderKind::Json,
+            None,
+            BandwidthShaper::default(),
+            false,
+        );
         let subject_addr: Addr<StreamHandlerPool> = subject.start();
         let subject_subs = StreamHandlerPool::make_subs_from(&subject_addr);
         let peer_actors = peer_actors_builder().build();
@@ -2055,6 +2862,7 @@ mod tests {
                     key.clone(),
                     Some(NodeAddr::new(&peer_addr.ip(), &[])),
                     rate_pack(100),
+                    false,
                 )),
                 context: msg,
             })
@@ -2083,7 +2891,13 @@ mod tests {
 
         thread::spawn(move || {
             let system = System::new("test");
-            let subject = StreamHandlerPool::new(vec![], false);
+            let subject = StreamHandlerPool::new(
+                vec![],
+                MasqueraderKind::Json,
+                None,
+                BandwidthShaper::default(),
+                false,
+            );
 
             let subject_addr: Addr<StreamHandlerPool> = subject.start();
             let subject_subs = StreamHandlerPool::make_subs_from(&subject_addr);
@@ -2123,6 +2937,7 @@ mod tests {
                     last_data: false,
                     sequence_number: None,
                     data: hello,
+                    relayed: false,
                 })
                 .unwrap();
 
@@ -2133,6 +2948,7 @@ mod tests {
                     last_data: false,
                     sequence_number: None,
                     data: worlds,
+                    relayed: false,
                 })
                 .unwrap();
 
@@ -2156,7 +2972,13 @@ mod tests {
 
         thread::spawn(move || {
             let system = System::new("test");
-            let mut subject = StreamHandlerPool::new(vec![], false);
+            let mut subject = StreamHandlerPool::new(
+                vec![],
+                MasqueraderKind::Json,
+                None,
+                BandwidthShaper::default(),
+                false,
+            );
             subject.traffic_analyzer = Box::new(TrafficAnalyzerMock {});
 
             let subject_addr: Addr<StreamHandlerPool> = subject.start();
@@ -2197,6 +3019,7 @@ mod tests {
                     last_data: false,
                     sequence_number: None,
                     data: b"hello".to_vec(),
+                    relayed: false,
                 })
                 .unwrap();
 
@@ -2217,7 +3040,13 @@ mod tests {
                 "stream_handler_pool_creates_nonexistent_stream_for_reading_and_writing",
             );
             let discriminator_factory = JsonDiscriminatorFactory::new();
-            let mut subject = StreamHandlerPool::new(vec![Box::new(discriminator_factory)], false);
+            let mut subject = StreamHandlerPool::new(
+                vec![Box::new(discriminator_factory)],
+                MasqueraderKind::Json,
+                None,
+                BandwidthShaper::default(),
+                false,
+            );
             subject.stream_connector = Box::new(StreamConnectorMock::new()); // this will panic if a connection is attempted
             let subject_addr: Addr<StreamHandlerPool> = subject.start();
             let subject_subs = StreamHandlerPool::make_subs_from(&subject_addr);
@@ -2246,6 +3075,7 @@ mod tests {
                 last_data: false,
                 sequence_number: Some(0),
                 data: outgoing_unmasked,
+                relayed: false,
             })
             .unwrap();
 
@@ -2264,7 +3094,13 @@ mod tests {
         expected = "panic message (processed with: node_lib::sub_lib::utils::crash_request_analyzer)"
     )]
     fn stream_handler_can_be_crashed_properly_but_not_improperly() {
-        let stream_handler_pool = StreamHandlerPool::new(vec![], true);
+        let stream_handler_pool = StreamHandlerPool::new(
+            vec![],
+            MasqueraderKind::Json,
+            None,
+            BandwidthShaper::default(),
+            true,
+        );
 
         prove_that_crash_request_handler_is_hooked_up(stream_handler_pool, CRASH_KEY);
     }
@@ -1,5 +1,6 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 use crate::bootstrapper::PortConfiguration;
+use crate::masquerader::MasqueraderKind;
 use crate::stream_handler_pool::StreamHandlerPoolSubs;
 use crate::sub_lib::dispatcher::{DispatcherSubs, StreamShutdownMsg};
 use crate::sub_lib::neighborhood::NeighborhoodSubs;
@@ -61,6 +62,15 @@ impl Debug for RemoveStreamMsg {
     }
 }
 
+// Sent by a clandestine StreamReader that has seen its peer ask for (or agree to) a different
+// masquerade. If reply_opt is populated, it must be written to the peer before any masked data.
+#[derive(PartialEq, Eq, Debug, Message)]
+pub struct MasqueradeNegotiatedMsg {
+    pub peer_addr: SocketAddr,
+    pub masquerader_kind: MasqueraderKind,
+    pub reply_opt: Option<Vec<u8>>,
+}
+
 #[derive(Message, Clone, PartialEq, Eq)]
 pub struct PoolBindMessage {
     pub dispatcher_subs: DispatcherSubs,
@@ -1,15 +1,19 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 use crate::discriminator::Discriminator;
 use crate::discriminator::DiscriminatorFactory;
+use crate::masquerader::MasqueraderKind;
 use crate::proxy_server::http_protocol_pack::HttpProtocolPack;
 use crate::stream_messages::*;
+use crate::sub_lib::bandwidth_shaper::{BandwidthShaper, BandwidthThrottle, TrafficKind};
 use crate::sub_lib::dispatcher;
 use crate::sub_lib::dispatcher::StreamShutdownMsg;
 use crate::sub_lib::sequencer::Sequencer;
 use crate::sub_lib::tokio_wrappers::ReadHalfWrapper;
 use crate::sub_lib::utils::indicates_dead_stream;
+use crate::websocket_masquerader::WebSocketMasquerader;
 use actix::Recipient;
 use masq_lib::logger::Logger;
+use masq_lib::redaction::MarkSensitive;
 use std::net::SocketAddr;
 use std::time::SystemTime;
 use tokio::prelude::Async;
@@ -22,11 +26,15 @@ pub struct StreamReaderReal {
     reception_port: Option<u16>,
     ibcd_sub: Recipient<dispatcher::InboundClientData>,
     remove_sub: Recipient<RemoveStreamMsg>,
+    masquerade_negotiated_sub: Recipient<MasqueradeNegotiatedMsg>,
     dispatcher_stream_shutdown_sub: Recipient<StreamShutdownMsg>,
     discriminators: Vec<Discriminator>,
     is_clandestine: bool,
+    masquerade_inspected: bool,
+    websocket_upgraded: bool,
     logger: Logger,
     sequencer: Sequencer,
+    throttle_opt: Option<BandwidthThrottle>,
 }
 
 impl Future for StreamReaderReal {
@@ -36,6 +44,11 @@ impl Future for StreamReaderReal {
     fn poll(&mut self) -> Result<Async<()>, ()> {
         let mut buf = [0u8; 0x0001_0000];
         loop {
+            if let Some(throttle) = self.throttle_opt.as_mut() {
+                if throttle.poll_permit() == Async::NotReady {
+                    return Ok(Async::NotReady);
+                }
+            }
             match self.stream.poll_read(&mut buf) {
                 Ok(Async::NotReady) => return Ok(Async::NotReady),
                 Ok(Async::Ready(0)) => {
@@ -43,17 +56,20 @@ impl Future for StreamReaderReal {
                     debug!(
                         self.logger,
                         "Stream {} has shut down (0-byte read)",
-                        Self::stringify(self.local_addr, self.peer_addr)
+                        Self::stringify(self.local_addr, self.peer_addr).sensitive()
                     );
                     self.shutdown();
                     return Ok(Async::Ready(()));
                 }
                 Ok(Async::Ready(length)) => {
+                    if let Some(throttle) = self.throttle_opt.as_ref() {
+                        throttle.record_transfer(length);
+                    }
                     debug!(
                         self.logger,
                         "Read {}-byte chunk from stream {}",
                         length,
-                        Self::stringify(self.local_addr, self.peer_addr)
+                        Self::stringify(self.local_addr, self.peer_addr).sensitive()
                     );
                     self.wrangle_discriminators(&buf, length)
                 }
@@ -62,7 +78,7 @@ impl Future for StreamReaderReal {
                         debug!(
                             self.logger,
                             "Stream {} is dead: {}",
-                            Self::stringify(self.local_addr, self.peer_addr),
+                            Self::stringify(self.local_addr, self.peer_addr).sensitive(),
                             e
                         );
                         self.shutdown();
@@ -72,7 +88,7 @@ impl Future for StreamReaderReal {
                         warning!(
                             self.logger,
                             "Continuing after read error on stream {}: {}",
-                            Self::stringify(self.local_addr, self.peer_addr),
+                            Self::stringify(self.local_addr, self.peer_addr).sensitive(),
                             e.to_string()
                         )
                     }
@@ -89,13 +105,27 @@ impl StreamReaderReal {
         reception_port: Option<u16>,
         ibcd_sub: Recipient<dispatcher::InboundClientData>,
         remove_sub: Recipient<RemoveStreamMsg>,
+        masquerade_negotiated_sub: Recipient<MasqueradeNegotiatedMsg>,
         dispatcher_sub: Recipient<StreamShutdownMsg>,
         discriminator_factories: Vec<Box<dyn DiscriminatorFactory>>,
         is_clandestine: bool,
         peer_addr: SocketAddr,
         local_addr: SocketAddr,
+        bandwidth_shaper: BandwidthShaper,
     ) -> StreamReaderReal {
         let name = format!("StreamReader for {}", peer_addr);
+        let logger = Logger::new(&name);
+        // Only clandestine reads are neighbors' traffic; the browser's requests aren't shaped here
+        let throttle_opt = if is_clandestine {
+            Some(BandwidthThrottle::new(
+                bandwidth_shaper,
+                TrafficKind::Incoming,
+                Some(peer_addr.ip()),
+                logger.clone(),
+            ))
+        } else {
+            None
+        };
         if discriminator_factories.is_empty() {
             panic!("Internal error: no Discriminator factories!")
         }
@@ -110,22 +140,31 @@ impl StreamReaderReal {
             reception_port,
             ibcd_sub,
             remove_sub,
+            masquerade_negotiated_sub,
             dispatcher_stream_shutdown_sub: dispatcher_sub,
             discriminators,
             is_clandestine,
-            logger: Logger::new(&name),
+            masquerade_inspected: false,
+            websocket_upgraded: false,
+            logger,
             sequencer: Sequencer::new(),
+            throttle_opt,
         }
     }
 
     fn wrangle_discriminators(&mut self, buf: &[u8], length: usize) {
         // Although discriminators is a vec, it was never really designed to have more than one.
         let is_connect = HttpProtocolPack::is_connect(buf);
-        let chosen_discriminator = if self.discriminators.len() > 1 && is_connect {
-            &mut self.discriminators[1]
-        } else {
-            &mut self.discriminators[0]
-        };
+        if self.is_clandestine && !self.masquerade_inspected {
+            self.masquerade_inspected = true;
+            self.inspect_masquerade(&buf[..length]);
+        }
+        let chosen_discriminator =
+            if self.discriminators.len() > 1 && (is_connect || self.websocket_upgraded) {
+                &mut self.discriminators[1]
+            } else {
+                &mut self.discriminators[0]
+            };
 
         debug!(self.logger, "Adding {} bytes to discriminator", length);
         chosen_discriminator.add_data(&buf[..length]);
@@ -169,7 +208,7 @@ impl StreamReaderReal {
                         data: unmasked_chunk.chunk.clone(),
                     };
                     debug!(self.logger, "Discriminator framed and unmasked {} bytes for {}; transmitting via Hopper",
-                                              unmasked_chunk.chunk.len(), msg.peer_addr);
+                                              unmasked_chunk.chunk.len(), msg.peer_addr.sensitive());
                     self.ibcd_sub.try_send(msg).expect("Dispatcher is dead");
                 }
                 None => {
@@ -180,8 +219,47 @@ impl StreamReaderReal {
         }
     }
 
+    // A clandestine connection that begins with a WebSocket upgrade handshake (in either direction)
+    // is read with the second discriminator, and the StreamHandlerPool is told to answer in kind.
+    fn inspect_masquerade(&mut self, data: &[u8]) {
+        if self.discriminators.len() < 2 {
+            return;
+        }
+        let reply_opt = if WebSocketMasquerader::is_upgrade_request(data) {
+            match WebSocketMasquerader::upgrade_response(data) {
+                Some(reply) => Some(reply),
+                None => {
+                    warning!(
+                        self.logger,
+                        "Ignoring incomplete WebSocket upgrade request from {}",
+                        self.peer_addr.sensitive()
+                    );
+                    return;
+                }
+            }
+        } else if WebSocketMasquerader::is_upgrade_response(data) {
+            None
+        } else {
+            return;
+        };
+        debug!(
+            self.logger,
+            "{} negotiated {} masquerade",
+            self.peer_addr.sensitive(),
+            MasqueraderKind::WebSocket
+        );
+        self.websocket_upgraded = true;
+        self.masquerade_negotiated_sub
+            .try_send(MasqueradeNegotiatedMsg {
+                peer_addr: self.peer_addr,
+                masquerader_kind: MasqueraderKind::WebSocket,
+                reply_opt,
+            })
+            .expect("StreamHandlerPool is dead");
+    }
+
     fn shutdown(&mut self) {
-        debug!(self.logger, "Directing removal of {}clandestine StreamReader with reception_port {:?} on {} listening to {}", if self.is_clandestine {""} else {"non-"}, self.reception_port, self.local_addr, self.peer_addr);
+        debug!(self.logger, "Directing removal of {}clandestine StreamReader with reception_port {:?} on {} listening to {}", if self.is_clandestine {""} else {"non-"}, self.reception_port, self.local_addr.sensitive(), self.peer_addr.sensitive());
         self.remove_sub
             .try_send(RemoveStreamMsg {
                 peer_addr: self.peer_addr,
@@ -216,6 +294,7 @@ mod tests {
     use crate::node_test_utils::{check_timestamp, make_stream_handler_pool_subs_from_recorder};
     use crate::stream_handler_pool::StreamHandlerPoolSubs;
     use crate::stream_messages::RemovedStreamType::NonClandestine;
+    use crate::sub_lib::bandwidth_shaper::{BandwidthLimits, DataQuota};
     use crate::sub_lib::dispatcher::DispatcherSubs;
     use crate::test_utils::recorder::make_dispatcher_subs_from_recorder;
     use crate::test_utils::recorder::make_recorder;
@@ -223,6 +302,7 @@ mod tests {
     use crate::test_utils::recorder::Recording;
     use crate::test_utils::tokio_wrapper_mocks::ReadHalfWrapperMock;
     use crate::tls_discriminator_factory::TlsDiscriminatorFactory;
+    use crate::websocket_discriminator_factory::WebSocketDiscriminatorFactory;
     use actix::Actor;
     use actix::Addr;
     use actix::System;
@@ -235,6 +315,7 @@ mod tests {
     use std::str::FromStr;
     use std::sync::{Arc, Mutex};
     use std::time::SystemTime;
+    use tokio::prelude::future;
 
     fn stream_handler_pool_stuff() -> (Arc<Mutex<Recording>>, StreamHandlerPoolSubs) {
         let (shp, _, recording) = make_recorder();
@@ -269,11 +350,13 @@ mod tests {
             None,
             dispatcher_subs.ibcd_sub,
             stream_handler_pool_subs.remove_sub,
+            stream_handler_pool_subs.masquerade_negotiated_sub,
             dispatcher_subs.stream_shutdown_sub.clone(),
             discriminator_factories,
             true,
             peer_addr,
             local_addr,
+            make_unlimited_shaper(),
         );
 
         let result = subject.poll();
@@ -318,11 +401,13 @@ mod tests {
             None,
             dispatcher_subs.ibcd_sub,
             stream_handler_pool_subs.remove_sub,
+            stream_handler_pool_subs.masquerade_negotiated_sub,
             dispatcher_subs.stream_shutdown_sub.clone(),
             discriminator_factories,
             true,
             peer_addr,
             local_addr,
+            make_unlimited_shaper(),
         );
 
         let result = subject.poll();
@@ -367,11 +452,13 @@ mod tests {
             Some(1234 as u16),
             dispatcher_subs.ibcd_sub,
             stream_handler_pool_subs.remove_sub,
+            stream_handler_pool_subs.masquerade_negotiated_sub,
             dispatcher_subs.stream_shutdown_sub,
             discriminator_factories,
             true,
             peer_addr,
             local_addr,
+            make_unlimited_shaper(),
         );
 
         let result = subject.poll();
@@ -410,11 +497,13 @@ mod tests {
             Some(1234 as u16),
             dispatcher_subs.ibcd_sub,
             stream_handler_pool_subs.remove_sub,
+            stream_handler_pool_subs.masquerade_negotiated_sub,
             dispatcher_subs.stream_shutdown_sub,
             discriminator_factories,
             true,
             peer_addr,
             local_addr,
+            make_unlimited_shaper(),
         );
 
         let _result = subject.poll();
@@ -450,11 +539,13 @@ mod tests {
             Some(1234 as u16),
             dispatcher_subs.ibcd_sub,
             stream_handler_pool_subs.remove_sub,
+            stream_handler_pool_subs.masquerade_negotiated_sub,
             dispatcher_subs.stream_shutdown_sub,
             discriminator_factories,
             true,
             peer_addr,
             local_addr,
+            make_unlimited_shaper(),
         );
     }
 
@@ -489,11 +580,13 @@ mod tests {
             Some(1234 as u16),
             dispatcher_subs.ibcd_sub,
             stream_handler_pool_subs.remove_sub,
+            stream_handler_pool_subs.masquerade_negotiated_sub,
             dispatcher_subs.stream_shutdown_sub,
             discriminator_factories,
             true,
             peer_addr,
             local_addr,
+            make_unlimited_shaper(),
         );
         let before = SystemTime::now();
 
@@ -557,11 +650,13 @@ mod tests {
             Some(1234 as u16),
             dispatcher_subs.ibcd_sub,
             stream_handler_pool_subs.remove_sub,
+            stream_handler_pool_subs.masquerade_negotiated_sub,
             dispatcher_subs.stream_shutdown_sub,
             discriminator_factories,
             false,
             peer_addr,
             local_addr,
+            make_unlimited_shaper(),
         );
 
         subject.poll().err();
@@ -609,11 +704,13 @@ mod tests {
             Some(1234 as u16),
             dispatcher_subs.ibcd_sub,
             stream_handler_pool_subs.remove_sub,
+            stream_handler_pool_subs.masquerade_negotiated_sub,
             dispatcher_subs.stream_shutdown_sub,
             discriminator_factories,
             false,
             peer_addr,
             local_addr,
+            make_unlimited_shaper(),
         );
         let before = SystemTime::now();
 
@@ -684,11 +781,13 @@ mod tests {
             Some(1234 as u16),
             dispatcher_subs.ibcd_sub,
             stream_handler_pool_subs.remove_sub,
+            stream_handler_pool_subs.masquerade_negotiated_sub,
             dispatcher_subs.stream_shutdown_sub,
             discriminator_factories,
             true,
             peer_addr,
             local_addr,
+            make_unlimited_shaper(),
         );
         let before = SystemTime::now();
 
@@ -715,6 +814,151 @@ mod tests {
         );
     }
 
+    #[test]
+    fn stream_reader_answers_websocket_upgrade_request_and_unmasks_websocket_frames() {
+        let system = System::new("test");
+        let (shp_recording_arc, stream_handler_pool_subs) = stream_handler_pool_stuff();
+        let (d_recording_arc, dispatcher_subs) = dispatcher_stuff();
+        let peer_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        let local_addr = SocketAddr::from_str("1.2.3.5:6789").unwrap();
+        let discriminator_factories: Vec<Box<dyn DiscriminatorFactory>> = vec![
+            Box::new(JsonDiscriminatorFactory::new()),
+            Box::new(WebSocketDiscriminatorFactory::new()),
+        ];
+        let request = WebSocketMasquerader::upgrade_request("1.2.3.5");
+        let frame = WebSocketMasquerader::new().mask(b"booga").unwrap();
+        let reader = ReadHalfWrapperMock {
+            poll_read_results: vec![
+                (request.clone(), Ok(Async::Ready(request.len()))),
+                (frame.clone(), Ok(Async::Ready(frame.len()))),
+                (vec![], Ok(Async::NotReady)),
+            ],
+        };
+        let mut subject = StreamReaderReal::new(
+            Box::new(reader),
+            None,
+            dispatcher_subs.ibcd_sub,
+            stream_handler_pool_subs.remove_sub,
+            stream_handler_pool_subs.masquerade_negotiated_sub,
+            dispatcher_subs.stream_shutdown_sub,
+            discriminator_factories,
+            true,
+            peer_addr,
+            local_addr,
+            make_unlimited_shaper(),
+        );
+
+        let _ = subject.poll();
+
+        System::current().stop_with_code(0);
+        system.run();
+        let shp_recording = shp_recording_arc.lock().unwrap();
+        assert_eq!(
+            shp_recording.get_record::<MasqueradeNegotiatedMsg>(0),
+            &MasqueradeNegotiatedMsg {
+                peer_addr,
+                masquerader_kind: MasqueraderKind::WebSocket,
+                reply_opt: WebSocketMasquerader::upgrade_response(&request),
+            }
+        );
+        let d_recording = d_recording_arc.lock().unwrap();
+        let d_record = d_recording.get_record::<dispatcher::InboundClientData>(0);
+        assert_eq!(d_record.data, b"booga".to_vec());
+        assert_eq!(d_recording.len(), 1);
+    }
+
+    #[test]
+    fn stream_reader_reports_accepted_websocket_upgrade_without_reply() {
+        let system = System::new("test");
+        let (shp_recording_arc, stream_handler_pool_subs) = stream_handler_pool_stuff();
+        let (_, dispatcher_subs) = dispatcher_stuff();
+        let peer_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        let local_addr = SocketAddr::from_str("1.2.3.5:6789").unwrap();
+        let discriminator_factories: Vec<Box<dyn DiscriminatorFactory>> = vec![
+            Box::new(JsonDiscriminatorFactory::new()),
+            Box::new(WebSocketDiscriminatorFactory::new()),
+        ];
+        let response = WebSocketMasquerader::upgrade_response(
+            &WebSocketMasquerader::upgrade_request("1.2.3.4"),
+        )
+        .unwrap();
+        let reader = ReadHalfWrapperMock {
+            poll_read_results: vec![
+                (response.clone(), Ok(Async::Ready(response.len()))),
+                (vec![], Ok(Async::NotReady)),
+            ],
+        };
+        let mut subject = StreamReaderReal::new(
+            Box::new(reader),
+            None,
+            dispatcher_subs.ibcd_sub,
+            stream_handler_pool_subs.remove_sub,
+            stream_handler_pool_subs.masquerade_negotiated_sub,
+            dispatcher_subs.stream_shutdown_sub,
+            discriminator_factories,
+            true,
+            peer_addr,
+            local_addr,
+            make_unlimited_shaper(),
+        );
+
+        let _ = subject.poll();
+
+        System::current().stop_with_code(0);
+        system.run();
+        let shp_recording = shp_recording_arc.lock().unwrap();
+        assert_eq!(
+            shp_recording.get_record::<MasqueradeNegotiatedMsg>(0),
+            &MasqueradeNegotiatedMsg {
+                peer_addr,
+                masquerader_kind: MasqueraderKind::WebSocket,
+                reply_opt: None,
+            }
+        );
+    }
+
+    #[test]
+    fn stream_reader_does_not_negotiate_masquerade_for_json_data() {
+        let system = System::new("test");
+        let (shp_recording_arc, stream_handler_pool_subs) = stream_handler_pool_stuff();
+        let (d_recording_arc, dispatcher_subs) = dispatcher_stuff();
+        let peer_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        let local_addr = SocketAddr::from_str("1.2.3.5:6789").unwrap();
+        let discriminator_factories: Vec<Box<dyn DiscriminatorFactory>> = vec![
+            Box::new(JsonDiscriminatorFactory::new()),
+            Box::new(WebSocketDiscriminatorFactory::new()),
+        ];
+        let masked = JsonMasquerader::new().mask(b"booga").unwrap();
+        let reader = ReadHalfWrapperMock {
+            poll_read_results: vec![
+                (masked.clone(), Ok(Async::Ready(masked.len()))),
+                (vec![], Ok(Async::NotReady)),
+            ],
+        };
+        let mut subject = StreamReaderReal::new(
+            Box::new(reader),
+            None,
+            dispatcher_subs.ibcd_sub,
+            stream_handler_pool_subs.remove_sub,
+            stream_handler_pool_subs.masquerade_negotiated_sub,
+            dispatcher_subs.stream_shutdown_sub,
+            discriminator_factories,
+            true,
+            peer_addr,
+            local_addr,
+            make_unlimited_shaper(),
+        );
+
+        let _ = subject.poll();
+
+        System::current().stop_with_code(0);
+        system.run();
+        assert_eq!(shp_recording_arc.lock().unwrap().len(), 0);
+        let d_recording = d_recording_arc.lock().unwrap();
+        let d_record = d_recording.get_record::<dispatcher::InboundClientData>(0);
+        assert_eq!(d_record.data, b"booga".to_vec());
+    }
+
     #[test]
     fn shutdown_produces_the_correct_stream_shutdown_msg_for_clandestine_reader() {
         let (shp_recording_arc, stream_handler_pool_subs) = stream_handler_pool_stuff();
@@ -730,11 +974,13 @@ mod tests {
             None,
             dispatcher_subs.ibcd_sub,
             stream_handler_pool_subs.remove_sub,
+            stream_handler_pool_subs.masquerade_negotiated_sub,
             dispatcher_subs.stream_shutdown_sub.clone(),
             discriminator_factories,
             true,
             peer_addr,
             local_addr,
+            make_unlimited_shaper(),
         );
 
         subject.shutdown();
@@ -769,11 +1015,13 @@ mod tests {
             Some(HTTP_PORT),
             dispatcher_subs.ibcd_sub,
             stream_handler_pool_subs.remove_sub,
+            stream_handler_pool_subs.masquerade_negotiated_sub,
             dispatcher_subs.stream_shutdown_sub.clone(),
             discriminator_factories,
             false,
             peer_addr,
             local_addr,
+            make_unlimited_shaper(),
         );
         subject.sequencer.next_sequence_number(); // just so it's not 0
 
@@ -796,4 +1044,57 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn clandestine_stream_reader_holds_off_reading_while_download_bandwidth_is_exhausted() {
+        let system = System::new("test");
+        let peer_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        let local_addr = SocketAddr::from_str("1.2.3.5:6789").unwrap();
+        let shaper = BandwidthShaper::new(
+            BandwidthLimits {
+                download_kb_per_sec: 1,
+                ..BandwidthLimits::default()
+            },
+            DataQuota::default(),
+        );
+        let make_subject = |is_clandestine: bool| {
+            let (_, stream_handler_pool_subs) = stream_handler_pool_stuff();
+            let (_, dispatcher_subs) = dispatcher_stuff();
+            let reader = ReadHalfWrapperMock::new()
+                .poll_read_result(vec![0u8; 3000], Ok(Async::Ready(3000)))
+                .poll_read_result(vec![], Ok(Async::Ready(0)));
+            StreamReaderReal::new(
+                Box::new(reader),
+                Some(HTTP_PORT),
+                dispatcher_subs.ibcd_sub,
+                stream_handler_pool_subs.remove_sub,
+                stream_handler_pool_subs.masquerade_negotiated_sub,
+                dispatcher_subs.stream_shutdown_sub,
+                vec![Box::new(JsonDiscriminatorFactory::new())],
+                is_clandestine,
+                peer_addr,
+                local_addr,
+                shaper.clone(),
+            )
+        };
+        let mut clandestine_subject = make_subject(true);
+        let mut clear_subject = make_subject(false);
+
+        let (clandestine_result, clear_result) = tokio::runtime::current_thread::Runtime::new()
+            .unwrap()
+            .block_on(future::lazy(move || {
+                Ok::<_, ()>((clandestine_subject.poll(), clear_subject.poll()))
+            }))
+            .unwrap();
+
+        System::current().stop_with_code(0);
+        system.run();
+        assert_eq!(clandestine_result, Ok(Async::NotReady));
+        assert_eq!(clear_result, Ok(Async::Ready(())));
+        assert_eq!(shaper.data_usage().daily_bytes, 3000);
+    }
+
+    fn make_unlimited_shaper() -> BandwidthShaper {
+        BandwidthShaper::new(BandwidthLimits::default(), DataQuota::default())
+    }
 }
@@ -5,6 +5,7 @@ use crate::sub_lib::sequence_buffer::SequencedPacket;
 use crate::sub_lib::tokio_wrappers::WriteHalfWrapper;
 use crate::sub_lib::utils::indicates_dead_stream;
 use masq_lib::logger::Logger;
+use masq_lib::redaction::MarkSensitive;
 use std::net::SocketAddr;
 use tokio::prelude::Async;
 use tokio::prelude::Future;
@@ -95,7 +96,7 @@ impl StreamWriterSorted {
                                     self.logger,
                                     "Error writing {} bytes to {}: {}",
                                     packet.data.len(),
-                                    self.peer_addr,
+                                    self.peer_addr.sensitive(),
                                     e
                                 );
                                 return WriteBufferStatus::StreamInError;
@@ -129,7 +130,7 @@ impl StreamWriterSorted {
                                     packet.last_data,
                                 ));
                             } else if packet.last_data {
-                                debug!(self.logger, "Shutting down stream to client at {} in response to server-drop report", self.peer_addr);
+                                debug!(self.logger, "Shutting down stream to client at {} in response to server-drop report", self.peer_addr.sensitive());
                                 self.shutting_down = true;
                                 return match self.stream.shutdown() {
                                     Ok(Async::NotReady) => WriteBufferStatus::BufferNotEmpty,
@@ -516,6 +517,7 @@ mod tests {
                 data: packet_a.to_vec(),
                 sequence_number: 0,
                 last_data: true,
+                relayed: false,
             }))),
             Ok(Async::Ready(None)),
         ];
@@ -551,6 +553,7 @@ mod tests {
                 data: packet_a.to_vec(),
                 sequence_number: 0,
                 last_data: true,
+                relayed: false,
             }))),
             Ok(Async::Ready(None)),
         ];
@@ -591,6 +594,7 @@ mod tests {
                 data: packet_a.to_vec(),
                 sequence_number: 0,
                 last_data: true,
+                relayed: false,
             }))),
             Ok(Async::Ready(None)),
         ];
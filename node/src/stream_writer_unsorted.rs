@@ -1,3 +1,4 @@
+use crate::sub_lib::bandwidth_shaper::{BandwidthShaper, BandwidthThrottle, TrafficKind};
 use crate::sub_lib::channel_wrappers::ReceiverWrapper;
 use crate::sub_lib::sequence_buffer::SequencedPacket;
 use crate::sub_lib::tokio_wrappers::WriteHalfWrapper;
@@ -12,6 +13,8 @@ pub struct StreamWriterUnsorted {
     rx_to_write: Box<dyn ReceiverWrapper<SequencedPacket>>,
     logger: Logger,
     buf: Option<SequencedPacket>,
+    relay_throttle: BandwidthThrottle,
+    originating_throttle: BandwidthThrottle,
 }
 
 impl Future for StreamWriterUnsorted {
@@ -32,6 +35,10 @@ impl Future for StreamWriterUnsorted {
                     }
                 }
                 Some(packet) => {
+                    if self.throttle_for(&packet).poll_permit() == Async::NotReady {
+                        self.buf = Some(packet);
+                        return Ok(Async::NotReady);
+                    }
                     // TODO in SC-646 "Graceful Shutdown from GUI" (marked obsolete): handle packet.last_data = true here
                     debug!(
                         self.logger,
@@ -55,6 +62,7 @@ impl Future for StreamWriterUnsorted {
                             }
                         }
                         Ok(Async::Ready(len)) => {
+                            self.throttle_for(&packet).record_transfer(len);
                             debug!(
                                 self.logger,
                                 "Wrote {}/{} bytes of clandestine data",
@@ -67,11 +75,14 @@ impl Future for StreamWriterUnsorted {
                                     "rescheduling {} bytes",
                                     packet.data.len() - len
                                 );
-                                self.buf = Some(SequencedPacket::new(
-                                    packet.data.iter().skip(len).cloned().collect(),
-                                    packet.sequence_number,
-                                    false,
-                                ));
+                                self.buf = Some(SequencedPacket {
+                                    relayed: packet.relayed,
+                                    ..SequencedPacket::new(
+                                        packet.data.iter().skip(len).cloned().collect(),
+                                        packet.sequence_number,
+                                        false,
+                                    )
+                                });
                             }
                         }
                         Ok(Async::NotReady) => {
@@ -90,14 +101,39 @@ impl StreamWriterUnsorted {
         stream: Box<dyn WriteHalfWrapper>,
         peer_addr: SocketAddr,
         rx_to_write: Box<dyn ReceiverWrapper<SequencedPacket>>,
+        bandwidth_shaper: BandwidthShaper,
     ) -> StreamWriterUnsorted {
         let name = format!("StreamWriter for {}", peer_addr);
         let logger = Logger::new(&name[..]);
+        let relay_throttle = BandwidthThrottle::new(
+            bandwidth_shaper.clone(),
+            TrafficKind::Relay,
+            Some(peer_addr.ip()),
+            logger.clone(),
+        );
+        let originating_throttle = BandwidthThrottle::new(
+            bandwidth_shaper,
+            TrafficKind::Originating,
+            None,
+            logger.clone(),
+        );
         StreamWriterUnsorted {
             stream,
             rx_to_write,
             logger,
             buf: None,
+            relay_throttle,
+            originating_throttle,
+        }
+    }
+
+    // The per-neighbor cap is there to keep neighbors from using up our bandwidth, so this Node's
+    // own traffic goes by the upload cap alone
+    fn throttle_for(&mut self, packet: &SequencedPacket) -> &mut BandwidthThrottle {
+        if packet.relayed {
+            &mut self.relay_throttle
+        } else {
+            &mut self.originating_throttle
         }
     }
 }
@@ -105,6 +141,7 @@ impl StreamWriterUnsorted {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::sub_lib::bandwidth_shaper::{BandwidthLimits, DataQuota};
     use crate::test_utils::channel_wrapper_mocks::ReceiverWrapperMock;
     use crate::test_utils::tokio_wrapper_mocks::WriteHalfWrapperMock;
     use masq_lib::test_utils::logging::init_test_logging;
@@ -113,6 +150,7 @@ mod tests {
     use std::io::ErrorKind;
     use std::net::SocketAddr;
     use std::str::FromStr;
+    use tokio::prelude::future;
 
     #[test]
     fn stream_writer_returns_not_ready_when_the_stream_is_not_ready() {
@@ -134,7 +172,8 @@ mod tests {
         let write_params = writer.poll_write_params.clone();
         let peer_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
 
-        let mut subject = StreamWriterUnsorted::new(Box::new(writer), peer_addr, rx);
+        let mut subject =
+            StreamWriterUnsorted::new(Box::new(writer), peer_addr, rx, BandwidthShaper::default());
 
         let result = subject.poll();
 
@@ -150,7 +189,8 @@ mod tests {
         let writer = WriteHalfWrapperMock::new().poll_write_result(Ok(Async::Ready(5)));
         let peer_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
 
-        let mut subject = StreamWriterUnsorted::new(Box::new(writer), peer_addr, rx);
+        let mut subject =
+            StreamWriterUnsorted::new(Box::new(writer), peer_addr, rx, BandwidthShaper::default());
 
         let result = subject.poll();
 
@@ -177,7 +217,8 @@ mod tests {
         let write_params = writer.poll_write_params.clone();
         let peer_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
 
-        let mut subject = StreamWriterUnsorted::new(Box::new(writer), peer_addr, rx);
+        let mut subject =
+            StreamWriterUnsorted::new(Box::new(writer), peer_addr, rx, BandwidthShaper::default());
 
         let result = subject.poll();
 
@@ -209,7 +250,8 @@ mod tests {
         let write_params = writer.poll_write_params.clone();
         let peer_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
 
-        let mut subject = StreamWriterUnsorted::new(Box::new(writer), peer_addr, rx);
+        let mut subject =
+            StreamWriterUnsorted::new(Box::new(writer), peer_addr, rx, BandwidthShaper::default());
 
         subject.poll().unwrap();
 
@@ -245,7 +287,8 @@ mod tests {
         let write_params = writer.poll_write_params.clone();
         let peer_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
 
-        let mut subject = StreamWriterUnsorted::new(Box::new(writer), peer_addr, rx);
+        let mut subject =
+            StreamWriterUnsorted::new(Box::new(writer), peer_addr, rx, BandwidthShaper::default());
 
         let result = subject.poll();
 
@@ -283,7 +326,8 @@ mod tests {
         let write_params = writer.poll_write_params.clone();
         let peer_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
 
-        let mut subject = StreamWriterUnsorted::new(Box::new(writer), peer_addr, rx);
+        let mut subject =
+            StreamWriterUnsorted::new(Box::new(writer), peer_addr, rx, BandwidthShaper::default());
 
         let result = subject.poll();
 
@@ -313,7 +357,8 @@ mod tests {
 
         let peer_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
 
-        let mut subject = StreamWriterUnsorted::new(Box::new(writer), peer_addr, rx);
+        let mut subject =
+            StreamWriterUnsorted::new(Box::new(writer), peer_addr, rx, BandwidthShaper::default());
 
         let result = subject.poll();
 
@@ -328,7 +373,8 @@ mod tests {
         let writer = WriteHalfWrapperMock::new();
         let peer_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
 
-        let mut subject = StreamWriterUnsorted::new(Box::new(writer), peer_addr, rx);
+        let mut subject =
+            StreamWriterUnsorted::new(Box::new(writer), peer_addr, rx, BandwidthShaper::default());
 
         subject.poll().unwrap();
     }
@@ -353,7 +399,8 @@ mod tests {
         let write_params = writer.poll_write_params.clone();
         let peer_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
 
-        let mut subject = StreamWriterUnsorted::new(Box::new(writer), peer_addr, rx);
+        let mut subject =
+            StreamWriterUnsorted::new(Box::new(writer), peer_addr, rx, BandwidthShaper::default());
 
         let result = subject.poll();
         assert_eq!(result, Ok(Async::NotReady));
@@ -386,7 +433,8 @@ mod tests {
         let write_params = writer.poll_write_params.clone();
         let peer_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
 
-        let mut subject = StreamWriterUnsorted::new(Box::new(writer), peer_addr, rx);
+        let mut subject =
+            StreamWriterUnsorted::new(Box::new(writer), peer_addr, rx, BandwidthShaper::default());
 
         let result = subject.poll();
         assert_eq!(result, Ok(Async::NotReady));
@@ -402,4 +450,104 @@ mod tests {
         );
         assert_eq!(write_params.lock().unwrap().get(2).unwrap(), &b"s".to_vec());
     }
+
+    fn make_upload_limited_shaper(kb_per_sec: u64) -> BandwidthShaper {
+        BandwidthShaper::new(
+            BandwidthLimits {
+                upload_kb_per_sec: kb_per_sec,
+                ..BandwidthLimits::default()
+            },
+            DataQuota::default(),
+        )
+    }
+
+    #[test]
+    fn stream_writer_charges_written_bytes_against_the_bandwidth_limits() {
+        let mut rx = Box::new(ReceiverWrapperMock::new());
+        rx.poll_results = vec![
+            Ok(Async::Ready(Some(SequencedPacket {
+                relayed: true,
+                ..SequencedPacket::new(vec![0u8; 3000], 0, false)
+            }))),
+            Ok(Async::NotReady),
+        ];
+        let writer = WriteHalfWrapperMock::new().poll_write_result(Ok(Async::Ready(3000)));
+        let peer_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        let shaper = make_upload_limited_shaper(1);
+        let mut subject =
+            StreamWriterUnsorted::new(Box::new(writer), peer_addr, rx, shaper.clone());
+
+        let result = subject.poll();
+
+        assert_eq!(result, Ok(Async::NotReady));
+        let delay = shaper
+            .delay_for(TrafficKind::Relay, Some(peer_addr.ip()))
+            .unwrap();
+        assert!(delay.as_millis() > 1900, "{:?}", delay);
+    }
+
+    #[test]
+    fn stream_writer_does_not_charge_its_own_traffic_against_the_relay_or_neighbor_caps() {
+        let mut rx = Box::new(ReceiverWrapperMock::new());
+        rx.poll_results = vec![
+            Ok(Async::Ready(Some(SequencedPacket::new(
+                vec![0u8; 3000],
+                0,
+                false,
+            )))),
+            Ok(Async::NotReady),
+        ];
+        let writer = WriteHalfWrapperMock::new().poll_write_result(Ok(Async::Ready(3000)));
+        let write_params = writer.poll_write_params.clone();
+        let peer_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        let shaper = BandwidthShaper::new(
+            BandwidthLimits {
+                upload_kb_per_sec: 1000,
+                relay_kb_per_sec: 1,
+                per_neighbor_kb_per_sec: 1,
+                ..BandwidthLimits::default()
+            },
+            DataQuota::default(),
+        );
+        let mut subject =
+            StreamWriterUnsorted::new(Box::new(writer), peer_addr, rx, shaper.clone());
+
+        let result = subject.poll();
+
+        assert_eq!(result, Ok(Async::NotReady));
+        assert_eq!(write_params.lock().unwrap().len(), 1);
+        assert_eq!(
+            shaper.delay_for(TrafficKind::Relay, Some(peer_addr.ip())),
+            None
+        );
+        assert_eq!(shaper.data_usage().daily_bytes, 3000);
+    }
+
+    #[test]
+    fn stream_writer_holds_packets_back_instead_of_dropping_them_while_bandwidth_is_exhausted() {
+        let mut rx = Box::new(ReceiverWrapperMock::new());
+        rx.poll_results = vec![Ok(Async::Ready(Some(SequencedPacket::new(
+            b"hello".to_vec(),
+            0,
+            false,
+        ))))];
+        let writer = WriteHalfWrapperMock::new().poll_write_result(Ok(Async::Ready(5)));
+        let write_params = writer.poll_write_params.clone();
+        let peer_addr = SocketAddr::from_str("1.2.3.4:5678").unwrap();
+        let shaper = make_upload_limited_shaper(1);
+        shaper.record_transfer(TrafficKind::Relay, None, 5000);
+        let mut subject = StreamWriterUnsorted::new(Box::new(writer), peer_addr, rx, shaper);
+
+        let (result, subject) = tokio::runtime::current_thread::Runtime::new()
+            .unwrap()
+            .block_on(future::lazy(move || {
+                let result = subject.poll();
+                Ok::<_, ()>((result, subject))
+            }))
+            .unwrap();
+
+        assert_eq!(result, Ok(Async::NotReady));
+        assert_eq!(write_params.lock().unwrap().len(), 0);
+        assert_eq!(subject.buf.unwrap().data, b"hello".to_vec());
+    }
 }
@@ -1,12 +1,14 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 use crate::accountant::db_access_objects::banned_dao::BannedDaoFactory;
+use crate::accountant::db_access_objects::financial_history_dao::FinancialHistoryDaoFactory;
+use crate::accountant::db_access_objects::ledger_adjustment_dao::LedgerAdjustmentDaoFactory;
 use crate::accountant::db_access_objects::payable_dao::PayableDaoFactory;
 use crate::accountant::db_access_objects::pending_payable_dao::PendingPayableDaoFactory;
 use crate::accountant::db_access_objects::receivable_dao::ReceivableDaoFactory;
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::msgs::BlockchainAgentWithContextMessage;
 use crate::accountant::{
-    checked_conversion, Accountant, ReceivedPayments, ReportTransactionReceipts, ScanError,
-    SentPayables,
+    checked_conversion, Accountant, ReceivedPayments, ReportFeeBumps, ReportTransactionReceipts,
+    ScanError, SentPayables,
 };
 use crate::actor_system_factory::SubsFactory;
 use crate::blockchain::blockchain_bridge::PendingPayableFingerprintSeeds;
@@ -42,6 +44,17 @@ lazy_static! {
         payable_scan_interval: Duration::from_secs(600),
         receivable_scan_interval: Duration::from_secs(600)
     };
+    pub static ref DEFAULT_FEE_BUMPING: FeeBumping = FeeBumping {
+        bump_after_sec: 1800,
+        gas_price_increase_percent: 20,
+        max_bumps: 3,
+    };
+    pub static ref DEFAULT_SPENDING_BUDGET: SpendingBudget = SpendingBudget {
+        daily_gwei: 0,
+        monthly_gwei: 0,
+        first_warning_percent: 80,
+        second_warning_percent: 95,
+    };
 }
 
 //please, alphabetical order
@@ -75,6 +88,8 @@ pub struct DaoFactories {
     pub receivable_dao_factory: Box<dyn ReceivableDaoFactory>,
     pub banned_dao_factory: Box<dyn BannedDaoFactory>,
     pub config_dao_factory: Box<dyn ConfigDaoFactory>,
+    pub financial_history_dao_factory: Box<dyn FinancialHistoryDaoFactory>,
+    pub ledger_adjustment_dao_factory: Box<dyn LedgerAdjustmentDaoFactory>,
 }
 
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
@@ -90,6 +105,34 @@ impl Default for ScanIntervals {
     }
 }
 
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct FeeBumping {
+    pub bump_after_sec: u64,
+    pub gas_price_increase_percent: u64,
+    pub max_bumps: u64,
+}
+
+impl Default for FeeBumping {
+    fn default() -> Self {
+        *DEFAULT_FEE_BUMPING
+    }
+}
+
+// A limit of zero means that period is not capped
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct SpendingBudget {
+    pub daily_gwei: u64,
+    pub monthly_gwei: u64,
+    pub first_warning_percent: u64,
+    pub second_warning_percent: u64,
+}
+
+impl Default for SpendingBudget {
+    fn default() -> Self {
+        *DEFAULT_SPENDING_BUDGET
+    }
+}
+
 #[derive(Clone, PartialEq, Eq)]
 pub struct AccountantSubs {
     pub bind: Recipient<BindMessage>,
@@ -102,6 +145,7 @@ pub struct AccountantSubs {
     pub report_inbound_payments: Recipient<ReceivedPayments>,
     pub init_pending_payable_fingerprints: Recipient<PendingPayableFingerprintSeeds>,
     pub report_transaction_receipts: Recipient<ReportTransactionReceipts>,
+    pub report_fee_bumps: Recipient<ReportFeeBumps>,
     pub report_sent_payments: Recipient<SentPayables>,
     pub scan_errors: Recipient<ScanError>,
     pub ui_message_sub: Recipient<NodeFromUiMessage>,
@@ -197,8 +241,9 @@ mod tests {
     use crate::accountant::{checked_conversion, Accountant};
     use crate::sub_lib::accountant::{
         AccountantSubsFactoryReal, MessageIdGenerator, MessageIdGeneratorReal, PaymentThresholds,
-        ScanIntervals, SubsFactory, DEFAULT_EARNING_WALLET, DEFAULT_PAYMENT_THRESHOLDS,
-        DEFAULT_SCAN_INTERVALS, MSG_ID_INCREMENTER, TEMPORARY_CONSUMING_WALLET,
+        ScanIntervals, SpendingBudget, SubsFactory, DEFAULT_EARNING_WALLET,
+        DEFAULT_PAYMENT_THRESHOLDS, DEFAULT_SCAN_INTERVALS, DEFAULT_SPENDING_BUDGET,
+        MSG_ID_INCREMENTER, TEMPORARY_CONSUMING_WALLET,
     };
     use crate::sub_lib::wallet::Wallet;
     use crate::test_utils::recorder::{make_accountant_subs_from_recorder, Recorder};
@@ -235,7 +280,14 @@ mod tests {
             payable_scan_interval: Duration::from_secs(600),
             receivable_scan_interval: Duration::from_secs(600),
         };
+        let spending_budget_expected = SpendingBudget {
+            daily_gwei: 0,
+            monthly_gwei: 0,
+            first_warning_percent: 80,
+            second_warning_percent: 95,
+        };
         assert_eq!(*DEFAULT_SCAN_INTERVALS, scan_intervals_expected);
+        assert_eq!(*DEFAULT_SPENDING_BUDGET, spending_budget_expected);
         assert_eq!(*DEFAULT_PAYMENT_THRESHOLDS, payment_thresholds_expected);
         assert_eq!(*DEFAULT_EARNING_WALLET, default_earning_wallet_expected);
         assert_eq!(
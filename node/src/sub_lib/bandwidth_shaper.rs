@@ -0,0 +1,985 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crate::sub_lib::neighborhood::RoutesDataChangeMsg;
+use actix::Recipient;
+use masq_lib::logger::Logger;
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::{Debug, Display, Formatter};
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use time::{Month, OffsetDateTime};
+use tokio::prelude::{Async, Future};
+use tokio::timer::Delay;
+
+pub const BYTES_PER_KILOBYTE: u64 = 1_000;
+pub const BYTES_PER_MEGABYTE: u64 = 1_000_000;
+
+// A zero in any position means "no limit"
+pub const DEFAULT_BANDWIDTH_LIMITS: BandwidthLimits = BandwidthLimits {
+    upload_kb_per_sec: 0,
+    download_kb_per_sec: 0,
+    relay_kb_per_sec: 0,
+    exit_kb_per_sec: 0,
+    per_neighbor_kb_per_sec: 0,
+};
+
+pub const DEFAULT_DATA_QUOTA: DataQuota = DataQuota {
+    daily_mb: 0,
+    monthly_mb: 0,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BandwidthLimits {
+    pub upload_kb_per_sec: u64,
+    pub download_kb_per_sec: u64,
+    pub relay_kb_per_sec: u64,
+    pub exit_kb_per_sec: u64,
+    pub per_neighbor_kb_per_sec: u64,
+}
+
+impl Default for BandwidthLimits {
+    fn default() -> Self {
+        DEFAULT_BANDWIDTH_LIMITS
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DataQuota {
+    pub daily_mb: u64,
+    pub monthly_mb: u64,
+}
+
+impl Default for DataQuota {
+    fn default() -> Self {
+        DEFAULT_DATA_QUOTA
+    }
+}
+
+// Relay traffic is clandestine data passed on to neighbors for other Nodes; it counts against the
+// upload and relay caps. Originating traffic is clandestine data this Node writes for its own
+// sake, which includes the responses it sends back as an exit; it counts against the upload cap
+// only. Incoming traffic is clandestine data read from neighbors, whoever it turns out to be for;
+// it counts against the download cap. Exit traffic is data read from servers on behalf of
+// originators; it counts against the download and exit caps.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrafficKind {
+    Relay,
+    Originating,
+    Incoming,
+    Exit,
+}
+
+#[derive(Clone)]
+pub struct BandwidthShaper {
+    inner: Arc<Mutex<BandwidthShaperInner>>,
+}
+
+impl Debug for BandwidthShaper {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let inner = self.lock();
+        write!(
+            f,
+            "BandwidthShaper {{ limits: {:?}, data_quota: {:?} }}",
+            inner.limits, inner.data_quota
+        )
+    }
+}
+
+impl Default for BandwidthShaper {
+    fn default() -> Self {
+        Self::new(DEFAULT_BANDWIDTH_LIMITS, DEFAULT_DATA_QUOTA)
+    }
+}
+
+impl BandwidthShaper {
+    pub fn new(limits: BandwidthLimits, data_quota: DataQuota) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(BandwidthShaperInner::new(
+                limits,
+                data_quota,
+                Instant::now(),
+                SystemTime::now(),
+            ))),
+        }
+    }
+
+    pub fn limits(&self) -> BandwidthLimits {
+        self.lock().limits
+    }
+
+    pub fn data_quota(&self) -> DataQuota {
+        self.lock().data_quota
+    }
+
+    pub fn update_limits(&self, limits: BandwidthLimits) {
+        self.lock().update_limits(limits, Instant::now())
+    }
+
+    pub fn update_data_quota(&self, data_quota: DataQuota) {
+        self.lock().update_data_quota(data_quota, SystemTime::now())
+    }
+
+    // A quota already exhausted before the Neighborhood was bound is announced now
+    pub fn set_routes_data_change_sub(&self, sub: Recipient<RoutesDataChangeMsg>) {
+        let mut inner = self.lock();
+        inner.routes_data_change_sub_opt = Some(sub);
+        if inner.quota_exhausted {
+            inner.quota_exhausted = inner.announce_routes_data(false);
+        }
+    }
+
+    pub fn data_usage(&self) -> DataUsage {
+        self.lock().usage
+    }
+
+    pub fn restore_data_usage(&self, usage: DataUsage) {
+        let mut inner = self.lock();
+        inner.usage = usage;
+        inner.check_quota(SystemTime::now())
+    }
+
+    // Without this, a quota exhausted yesterday would stay exhausted until data flowed again,
+    // which it won't while the Node isn't offering to route it
+    pub fn check_quota(&self) {
+        self.lock().check_quota(SystemTime::now())
+    }
+
+    pub fn delay_for(&self, kind: TrafficKind, neighbor_opt: Option<IpAddr>) -> Option<Duration> {
+        self.lock().delay_for(kind, neighbor_opt, Instant::now())
+    }
+
+    pub fn record_transfer(&self, kind: TrafficKind, neighbor_opt: Option<IpAddr>, bytes: usize) {
+        self.lock().record_transfer(
+            kind,
+            neighbor_opt,
+            bytes as u64,
+            Instant::now(),
+            SystemTime::now(),
+        )
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BandwidthShaperInner> {
+        self.inner.lock().expect("BandwidthShaper is poisoned")
+    }
+}
+
+// Holds one stream's place in line for bandwidth. Instead of dropping data that would exceed a
+// limit, the stream stops reading or writing until its buckets are back in credit.
+pub struct BandwidthThrottle {
+    shaper: BandwidthShaper,
+    kind: TrafficKind,
+    neighbor_opt: Option<IpAddr>,
+    delay_opt: Option<Delay>,
+    logger: Logger,
+}
+
+impl BandwidthThrottle {
+    pub fn new(
+        shaper: BandwidthShaper,
+        kind: TrafficKind,
+        neighbor_opt: Option<IpAddr>,
+        logger: Logger,
+    ) -> Self {
+        Self {
+            shaper,
+            kind,
+            neighbor_opt,
+            delay_opt: None,
+            logger,
+        }
+    }
+
+    // Must be called from within a task: when it returns NotReady, the task will be awakened
+    // once enough bandwidth has accumulated.
+    pub fn poll_permit(&mut self) -> Async<()> {
+        loop {
+            if let Some(delay) = self.delay_opt.as_mut() {
+                match delay.poll() {
+                    Ok(Async::NotReady) => return Async::NotReady,
+                    Ok(Async::Ready(())) => self.delay_opt = None,
+                    Err(e) => {
+                        self.delay_opt = None;
+                        warning!(
+                            self.logger,
+                            "Bandwidth throttle timer failed ({}); proceeding unthrottled",
+                            e
+                        );
+                        return Async::Ready(());
+                    }
+                }
+            }
+            match self.shaper.delay_for(self.kind, self.neighbor_opt) {
+                None => return Async::Ready(()),
+                Some(wait) => {
+                    trace!(
+                        self.logger,
+                        "{:?} bandwidth exhausted; waiting {}ms",
+                        self.kind,
+                        wait.as_millis()
+                    );
+                    self.delay_opt = Some(Delay::new(Instant::now() + wait))
+                }
+            }
+        }
+    }
+
+    pub fn record_transfer(&self, bytes: usize) {
+        self.shaper
+            .record_transfer(self.kind, self.neighbor_opt, bytes)
+    }
+}
+
+struct BandwidthShaperInner {
+    limits: BandwidthLimits,
+    upload_bucket_opt: Option<TokenBucket>,
+    download_bucket_opt: Option<TokenBucket>,
+    relay_bucket_opt: Option<TokenBucket>,
+    exit_bucket_opt: Option<TokenBucket>,
+    neighbor_buckets: HashMap<IpAddr, TokenBucket>,
+    data_quota: DataQuota,
+    usage: DataUsage,
+    quota_exhausted: bool,
+    routes_data_change_sub_opt: Option<Recipient<RoutesDataChangeMsg>>,
+    logger: Logger,
+}
+
+impl BandwidthShaperInner {
+    fn new(
+        limits: BandwidthLimits,
+        data_quota: DataQuota,
+        now: Instant,
+        wall_clock: SystemTime,
+    ) -> Self {
+        let mut inner = Self {
+            limits,
+            upload_bucket_opt: None,
+            download_bucket_opt: None,
+            relay_bucket_opt: None,
+            exit_bucket_opt: None,
+            neighbor_buckets: HashMap::new(),
+            data_quota,
+            usage: DataUsage::new(wall_clock),
+            quota_exhausted: false,
+            routes_data_change_sub_opt: None,
+            logger: Logger::new("BandwidthShaper"),
+        };
+        inner.reset_buckets(now);
+        inner
+    }
+
+    fn update_limits(&mut self, limits: BandwidthLimits, now: Instant) {
+        self.limits = limits;
+        self.reset_buckets(now);
+        info!(self.logger, "Bandwidth limits set to {:?}", limits);
+    }
+
+    fn reset_buckets(&mut self, now: Instant) {
+        self.upload_bucket_opt = TokenBucket::new(self.limits.upload_kb_per_sec, now);
+        self.download_bucket_opt = TokenBucket::new(self.limits.download_kb_per_sec, now);
+        self.relay_bucket_opt = TokenBucket::new(self.limits.relay_kb_per_sec, now);
+        self.exit_bucket_opt = TokenBucket::new(self.limits.exit_kb_per_sec, now);
+        self.neighbor_buckets.clear();
+    }
+
+    fn update_data_quota(&mut self, data_quota: DataQuota, wall_clock: SystemTime) {
+        self.data_quota = data_quota;
+        info!(self.logger, "Data quota set to {:?}", data_quota);
+        self.check_quota(wall_clock);
+    }
+
+    fn delay_for(
+        &mut self,
+        kind: TrafficKind,
+        neighbor_opt: Option<IpAddr>,
+        now: Instant,
+    ) -> Option<Duration> {
+        let per_neighbor_kb_per_sec = self.limits.per_neighbor_kb_per_sec;
+        let neighbor_bucket_opt = match neighbor_opt {
+            Some(ip_addr) if per_neighbor_kb_per_sec > 0 => self.neighbor_buckets.get_mut(&ip_addr),
+            _ => None,
+        };
+        let (direction_bucket_opt, kind_bucket_opt) = match kind {
+            TrafficKind::Relay => (
+                self.upload_bucket_opt.as_mut(),
+                self.relay_bucket_opt.as_mut(),
+            ),
+            TrafficKind::Originating => (self.upload_bucket_opt.as_mut(), None),
+            TrafficKind::Incoming => (self.download_bucket_opt.as_mut(), None),
+            TrafficKind::Exit => (
+                self.download_bucket_opt.as_mut(),
+                self.exit_bucket_opt.as_mut(),
+            ),
+        };
+        vec![direction_bucket_opt, kind_bucket_opt, neighbor_bucket_opt]
+            .into_iter()
+            .flatten()
+            .filter_map(|bucket| bucket.delay(now))
+            .max()
+    }
+
+    fn record_transfer(
+        &mut self,
+        kind: TrafficKind,
+        neighbor_opt: Option<IpAddr>,
+        bytes: u64,
+        now: Instant,
+        wall_clock: SystemTime,
+    ) {
+        let (direction_bucket_opt, kind_bucket_opt) = match kind {
+            TrafficKind::Relay => (
+                self.upload_bucket_opt.as_mut(),
+                self.relay_bucket_opt.as_mut(),
+            ),
+            TrafficKind::Originating => (self.upload_bucket_opt.as_mut(), None),
+            TrafficKind::Incoming => (self.download_bucket_opt.as_mut(), None),
+            TrafficKind::Exit => (
+                self.download_bucket_opt.as_mut(),
+                self.exit_bucket_opt.as_mut(),
+            ),
+        };
+        direction_bucket_opt
+            .into_iter()
+            .chain(kind_bucket_opt)
+            .for_each(|bucket| bucket.spend(bytes, now));
+        if let Some(ip_addr) = neighbor_opt {
+            let per_neighbor_kb_per_sec = self.limits.per_neighbor_kb_per_sec;
+            if let Some(bucket) = TokenBucket::new(per_neighbor_kb_per_sec, now) {
+                self.neighbor_buckets
+                    .entry(ip_addr)
+                    .or_insert(bucket)
+                    .spend(bytes, now);
+            }
+        }
+        self.usage.add(bytes, wall_clock);
+        self.check_quota(wall_clock);
+    }
+
+    fn check_quota(&mut self, wall_clock: SystemTime) {
+        self.usage.roll_over(wall_clock);
+        let exceeded = |quota_mb: u64, used_bytes: u64| {
+            quota_mb > 0 && used_bytes >= quota_mb.saturating_mul(BYTES_PER_MEGABYTE)
+        };
+        let exhausted = exceeded(self.data_quota.daily_mb, self.usage.daily_bytes)
+            || exceeded(self.data_quota.monthly_mb, self.usage.monthly_bytes);
+        if exhausted == self.quota_exhausted {
+            return;
+        }
+        if exhausted {
+            warning!(
+                self.logger,
+                "Data quota {:?} exhausted ({} bytes today, {} bytes this month); no longer offering to route data",
+                self.data_quota,
+                self.usage.daily_bytes,
+                self.usage.monthly_bytes
+            );
+        } else {
+            info!(
+                self.logger,
+                "Data quota {:?} available again; offering to route data", self.data_quota
+            );
+        }
+        if self.announce_routes_data(!exhausted) {
+            self.quota_exhausted = exhausted
+        }
+    }
+
+    // This runs under the shaper's lock, in the middle of some stream's task, so an announcement
+    // that can't be delivered is left for the next quota check rather than taking the stream down.
+    // An unbound Neighborhood counts as announced: it hears the state when it gets bound.
+    fn announce_routes_data(&self, routes_data: bool) -> bool {
+        match self.routes_data_change_sub_opt.as_ref() {
+            Some(sub) => match sub.try_send(RoutesDataChangeMsg { routes_data }) {
+                Ok(()) => true,
+                Err(e) => {
+                    error!(
+                        self.logger,
+                        "Cannot announce routes_data = {} to the Neighborhood ({:?}); will try again at the next quota check",
+                        routes_data,
+                        e
+                    );
+                    false
+                }
+            },
+            None => {
+                warning!(
+                    self.logger,
+                    "Cannot announce routes_data = {}: Neighborhood is not yet bound",
+                    routes_data
+                );
+                true
+            }
+        }
+    }
+}
+
+// A deficit-style token bucket: a transfer is permitted whenever the balance is not negative,
+// and may drive the balance below zero. The bucket then stays closed until the deficit is repaid
+// at the configured rate, so long-term throughput converges on the limit without ever having to
+// split or drop a packet.
+struct TokenBucket {
+    bytes_per_sec: f64,
+    balance: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(kb_per_sec: u64, now: Instant) -> Option<Self> {
+        if kb_per_sec == 0 {
+            return None;
+        }
+        let bytes_per_sec = kb_per_sec.saturating_mul(BYTES_PER_KILOBYTE) as f64;
+        Some(Self {
+            bytes_per_sec,
+            balance: bytes_per_sec,
+            last_refill: now,
+        })
+    }
+
+    fn delay(&mut self, now: Instant) -> Option<Duration> {
+        self.refill(now);
+        if self.balance >= 0.0 {
+            None
+        } else {
+            Some(Duration::from_secs_f64(-self.balance / self.bytes_per_sec))
+        }
+    }
+
+    fn spend(&mut self, bytes: u64, now: Instant) {
+        self.refill(now);
+        self.balance -= bytes as f64;
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.balance =
+            (self.balance + elapsed.as_secs_f64() * self.bytes_per_sec).min(self.bytes_per_sec);
+        self.last_refill = now;
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DataUsage {
+    pub as_of: SystemTime,
+    pub daily_bytes: u64,
+    pub monthly_bytes: u64,
+}
+
+impl DataUsage {
+    pub fn new(wall_clock: SystemTime) -> Self {
+        Self {
+            as_of: wall_clock,
+            daily_bytes: 0,
+            monthly_bytes: 0,
+        }
+    }
+
+    fn add(&mut self, bytes: u64, wall_clock: SystemTime) {
+        self.roll_over(wall_clock);
+        self.daily_bytes = self.daily_bytes.saturating_add(bytes);
+        self.monthly_bytes = self.monthly_bytes.saturating_add(bytes);
+    }
+
+    fn roll_over(&mut self, wall_clock: SystemTime) {
+        let (day, month) = Self::periods(self.as_of);
+        let (new_day, new_month) = Self::periods(wall_clock);
+        if new_day != day {
+            self.daily_bytes = 0;
+        }
+        if new_month != month {
+            self.monthly_bytes = 0;
+        }
+        self.as_of = wall_clock;
+    }
+
+    fn periods(wall_clock: SystemTime) -> ((i32, u16), (i32, Month)) {
+        let date = OffsetDateTime::from(wall_clock);
+        ((date.year(), date.ordinal()), (date.year(), date.month()))
+    }
+}
+
+// Stored as "<seconds since the epoch when last counted>|<bytes that day>|<bytes that month>"
+impl Display for DataUsage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let seconds = self
+            .as_of
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        write!(f, "{}|{}|{}", seconds, self.daily_bytes, self.monthly_bytes)
+    }
+}
+
+impl FromStr for DataUsage {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let numbers = s
+            .split('|')
+            .map(|piece| piece.parse::<u64>())
+            .collect::<Result<Vec<u64>, _>>()
+            .map_err(|_| {
+                format!(
+                    "Data usage should be three numbers separated by '|', not '{}'",
+                    s
+                )
+            })?;
+        match numbers.as_slice() {
+            [seconds, daily_bytes, monthly_bytes] => Ok(Self {
+                as_of: UNIX_EPOCH + Duration::from_secs(*seconds),
+                daily_bytes: *daily_bytes,
+                monthly_bytes: *monthly_bytes,
+            }),
+            _ => Err(format!(
+                "Data usage should be three numbers separated by '|', not '{}'",
+                s
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::recorder::make_recorder;
+    use actix::{Actor, System};
+    use masq_lib::test_utils::logging::{init_test_logging, TestLogHandler};
+    use std::str::FromStr;
+    use tokio::prelude::future;
+
+    fn limits(
+        upload_kb_per_sec: u64,
+        download_kb_per_sec: u64,
+        relay_kb_per_sec: u64,
+        exit_kb_per_sec: u64,
+        per_neighbor_kb_per_sec: u64,
+    ) -> BandwidthLimits {
+        BandwidthLimits {
+            upload_kb_per_sec,
+            download_kb_per_sec,
+            relay_kb_per_sec,
+            exit_kb_per_sec,
+            per_neighbor_kb_per_sec,
+        }
+    }
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(BYTES_PER_KILOBYTE, 1_000);
+        assert_eq!(BYTES_PER_MEGABYTE, 1_000_000);
+        assert_eq!(DEFAULT_BANDWIDTH_LIMITS, limits(0, 0, 0, 0, 0));
+        assert_eq!(
+            DEFAULT_DATA_QUOTA,
+            DataQuota {
+                daily_mb: 0,
+                monthly_mb: 0
+            }
+        );
+    }
+
+    #[test]
+    fn unlimited_shaper_never_delays() {
+        let now = Instant::now();
+        let mut subject = BandwidthShaperInner::new(
+            limits(0, 0, 0, 0, 0),
+            DataQuota::default(),
+            now,
+            SystemTime::now(),
+        );
+        let neighbor = IpAddr::from_str("1.2.3.4").unwrap();
+
+        subject.record_transfer(
+            TrafficKind::Relay,
+            Some(neighbor),
+            1_000_000_000,
+            now,
+            SystemTime::now(),
+        );
+        subject.record_transfer(
+            TrafficKind::Exit,
+            None,
+            1_000_000_000,
+            now,
+            SystemTime::now(),
+        );
+
+        assert_eq!(
+            subject.delay_for(TrafficKind::Relay, Some(neighbor), now),
+            None
+        );
+        assert_eq!(subject.delay_for(TrafficKind::Exit, None, now), None);
+    }
+
+    #[test]
+    fn token_bucket_permits_a_transfer_while_in_credit_then_waits_out_the_deficit() {
+        let now = Instant::now();
+        let mut subject = TokenBucket::new(10, now).unwrap();
+
+        assert_eq!(subject.delay(now), None);
+        subject.spend(25_000, now);
+        assert_eq!(subject.delay(now), Some(Duration::from_millis(1500)));
+        assert_eq!(
+            subject.delay(now + Duration::from_millis(500)),
+            Some(Duration::from_millis(1000))
+        );
+        assert_eq!(subject.delay(now + Duration::from_millis(1500)), None);
+    }
+
+    #[test]
+    fn token_bucket_does_not_bank_more_than_one_second_of_credit() {
+        let now = Instant::now();
+        let mut subject = TokenBucket::new(10, now).unwrap();
+
+        subject.spend(0, now + Duration::from_secs(3600));
+        subject.spend(20_000, now + Duration::from_secs(3600));
+
+        assert_eq!(
+            subject.delay(now + Duration::from_secs(3600)),
+            Some(Duration::from_secs(1))
+        );
+    }
+
+    #[test]
+    fn relay_traffic_is_limited_by_upload_relay_and_per_neighbor_caps() {
+        let now = Instant::now();
+        let wall_clock = SystemTime::now();
+        let neighbor = IpAddr::from_str("1.2.3.4").unwrap();
+        let other_neighbor = IpAddr::from_str("2.3.4.5").unwrap();
+        let mut subject = BandwidthShaperInner::new(
+            limits(100, 0, 50, 0, 10),
+            DataQuota::default(),
+            now,
+            wall_clock,
+        );
+
+        subject.record_transfer(TrafficKind::Relay, Some(neighbor), 30_000, now, wall_clock);
+
+        assert_eq!(
+            subject.delay_for(TrafficKind::Relay, Some(neighbor), now),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(
+            subject.delay_for(TrafficKind::Relay, Some(other_neighbor), now),
+            None
+        );
+        subject.record_transfer(
+            TrafficKind::Relay,
+            Some(other_neighbor),
+            30_000,
+            now,
+            wall_clock,
+        );
+        assert_eq!(
+            subject.delay_for(TrafficKind::Relay, None, now),
+            Some(Duration::from_millis(200))
+        );
+        assert_eq!(subject.delay_for(TrafficKind::Exit, None, now), None);
+    }
+
+    #[test]
+    fn originating_and_incoming_traffic_are_limited_only_by_their_direction_caps() {
+        let now = Instant::now();
+        let wall_clock = SystemTime::now();
+        let neighbor = IpAddr::from_str("1.2.3.4").unwrap();
+        let mut subject = BandwidthShaperInner::new(
+            limits(10, 20, 1, 1, 0),
+            DataQuota::default(),
+            now,
+            wall_clock,
+        );
+
+        subject.record_transfer(TrafficKind::Originating, None, 30_000, now, wall_clock);
+        subject.record_transfer(
+            TrafficKind::Incoming,
+            Some(neighbor),
+            60_000,
+            now,
+            wall_clock,
+        );
+
+        assert_eq!(
+            subject.delay_for(TrafficKind::Originating, None, now),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(
+            subject.delay_for(TrafficKind::Relay, None, now),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(
+            subject.delay_for(TrafficKind::Incoming, Some(neighbor), now),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(
+            subject.delay_for(TrafficKind::Exit, None, now),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(subject.relay_bucket_opt.as_mut().unwrap().delay(now), None);
+        assert_eq!(subject.exit_bucket_opt.as_mut().unwrap().delay(now), None);
+    }
+
+    #[test]
+    fn exit_traffic_is_limited_by_download_and_exit_caps() {
+        let now = Instant::now();
+        let wall_clock = SystemTime::now();
+        let mut subject = BandwidthShaperInner::new(
+            limits(0, 20, 0, 40, 0),
+            DataQuota::default(),
+            now,
+            wall_clock,
+        );
+
+        subject.record_transfer(TrafficKind::Exit, None, 60_000, now, wall_clock);
+
+        assert_eq!(
+            subject.delay_for(TrafficKind::Exit, None, now),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(subject.delay_for(TrafficKind::Relay, None, now), None);
+    }
+
+    #[test]
+    fn updating_limits_starts_over_with_fresh_buckets() {
+        let now = Instant::now();
+        let subject = BandwidthShaper::new(limits(1, 0, 0, 0, 0), DataQuota::default());
+        subject.record_transfer(TrafficKind::Relay, None, 1_000_000);
+
+        subject.update_limits(limits(0, 0, 2, 0, 0));
+
+        assert_eq!(subject.limits(), limits(0, 0, 2, 0, 0));
+        assert_eq!(
+            subject.lock().delay_for(TrafficKind::Relay, None, now),
+            None
+        );
+    }
+
+    #[test]
+    fn exhausting_and_renewing_the_daily_quota_toggles_routes_data() {
+        init_test_logging();
+        let (neighborhood, _, neighborhood_recording_arc) = make_recorder();
+        let system = System::new("exhausting_and_renewing_the_daily_quota_toggles_routes_data");
+        let neighborhood_addr = neighborhood.start();
+        let now = Instant::now();
+        let today = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let tomorrow = today + Duration::from_secs(86_400);
+        let mut subject = BandwidthShaperInner::new(
+            DEFAULT_BANDWIDTH_LIMITS,
+            DataQuota {
+                daily_mb: 2,
+                monthly_mb: 0,
+            },
+            now,
+            today,
+        );
+        subject.routes_data_change_sub_opt =
+            Some(neighborhood_addr.recipient::<RoutesDataChangeMsg>());
+
+        subject.record_transfer(TrafficKind::Relay, None, 1_500_000, now, today);
+        subject.record_transfer(TrafficKind::Exit, None, 500_000, now, today);
+        subject.record_transfer(TrafficKind::Exit, None, 500_000, now, today);
+        subject.record_transfer(TrafficKind::Exit, None, 1, now, tomorrow);
+
+        System::current().stop();
+        system.run();
+        let recording = neighborhood_recording_arc.lock().unwrap();
+        assert_eq!(
+            recording.get_record::<RoutesDataChangeMsg>(0),
+            &RoutesDataChangeMsg { routes_data: false }
+        );
+        assert_eq!(
+            recording.get_record::<RoutesDataChangeMsg>(1),
+            &RoutesDataChangeMsg { routes_data: true }
+        );
+        assert_eq!(recording.len(), 2);
+        TestLogHandler::new().exists_log_containing(
+            "WARN: BandwidthShaper: Data quota DataQuota { daily_mb: 2, monthly_mb: 0 } exhausted (2000000 bytes today, 2000000 bytes this month); no longer offering to route data",
+        );
+    }
+
+    #[test]
+    fn monthly_quota_survives_a_change_of_day_but_not_of_month() {
+        let now = Instant::now();
+        let mid_month = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000); // 14 Nov 2023
+        let next_day = mid_month + Duration::from_secs(86_400);
+        let next_month = mid_month + Duration::from_secs(86_400 * 30);
+        let mut subject = BandwidthShaperInner::new(
+            DEFAULT_BANDWIDTH_LIMITS,
+            DataQuota {
+                daily_mb: 0,
+                monthly_mb: 1,
+            },
+            now,
+            mid_month,
+        );
+
+        subject.record_transfer(TrafficKind::Relay, None, 1_000_000, now, mid_month);
+        assert_eq!(subject.quota_exhausted, true);
+        subject.check_quota(next_day);
+        assert_eq!(subject.quota_exhausted, true);
+        subject.check_quota(next_month);
+        assert_eq!(subject.quota_exhausted, false);
+    }
+
+    #[test]
+    fn restored_usage_counts_against_the_quota_until_it_rolls_over() {
+        let now = SystemTime::now();
+        let subject = BandwidthShaper::new(
+            DEFAULT_BANDWIDTH_LIMITS,
+            DataQuota {
+                daily_mb: 1,
+                monthly_mb: 0,
+            },
+        );
+
+        subject.restore_data_usage(DataUsage {
+            as_of: now,
+            daily_bytes: 1_000_000,
+            monthly_bytes: 1_000_000,
+        });
+        let exhausted_after_restoring = subject.lock().quota_exhausted;
+        subject.restore_data_usage(DataUsage {
+            as_of: now - Duration::from_secs(86_400 * 2),
+            daily_bytes: 1_000_000,
+            monthly_bytes: 1_000_000,
+        });
+
+        assert_eq!(exhausted_after_restoring, true);
+        assert_eq!(subject.lock().quota_exhausted, false);
+        assert_eq!(subject.data_usage().daily_bytes, 0);
+    }
+
+    #[test]
+    fn checking_the_quota_with_no_traffic_renews_it_at_the_end_of_the_day() {
+        let (neighborhood, _, neighborhood_recording_arc) = make_recorder();
+        let system = System::new("checking_the_quota_with_no_traffic_renews_it");
+        let subject = BandwidthShaper::new(
+            DEFAULT_BANDWIDTH_LIMITS,
+            DataQuota {
+                daily_mb: 1,
+                monthly_mb: 0,
+            },
+        );
+        subject.restore_data_usage(DataUsage {
+            as_of: SystemTime::now(),
+            daily_bytes: 1_000_000,
+            monthly_bytes: 1_000_000,
+        });
+        subject.set_routes_data_change_sub(neighborhood.start().recipient());
+        subject.lock().usage.as_of -= Duration::from_secs(86_400);
+
+        subject.check_quota();
+
+        System::current().stop();
+        system.run();
+        let recording = neighborhood_recording_arc.lock().unwrap();
+        assert_eq!(
+            recording.get_record::<RoutesDataChangeMsg>(0),
+            &RoutesDataChangeMsg { routes_data: false }
+        );
+        assert_eq!(
+            recording.get_record::<RoutesDataChangeMsg>(1),
+            &RoutesDataChangeMsg { routes_data: true }
+        );
+        assert_eq!(recording.len(), 2);
+    }
+
+    #[test]
+    fn undeliverable_announcement_is_logged_and_retried_at_the_next_quota_check() {
+        init_test_logging();
+        let test_name = "undeliverable_announcement_is_logged_and_retried_at_the_next_quota_check";
+        let system = System::new(test_name);
+        let (dead_neighborhood, _, _) = make_recorder();
+        let dead_sub = dead_neighborhood.start().recipient::<RoutesDataChangeMsg>();
+        System::current().stop();
+        system.run();
+        let (neighborhood, _, neighborhood_recording_arc) = make_recorder();
+        let system = System::new(test_name);
+        let now = Instant::now();
+        let today = SystemTime::now();
+        let mut subject = BandwidthShaperInner::new(
+            DEFAULT_BANDWIDTH_LIMITS,
+            DataQuota {
+                daily_mb: 1,
+                monthly_mb: 0,
+            },
+            now,
+            today,
+        );
+        subject.routes_data_change_sub_opt = Some(dead_sub);
+
+        subject.record_transfer(TrafficKind::Relay, None, 1_000_000, now, today);
+        let exhausted_after_failure = subject.quota_exhausted;
+        subject.routes_data_change_sub_opt =
+            Some(neighborhood.start().recipient::<RoutesDataChangeMsg>());
+        subject.check_quota(today);
+
+        System::current().stop();
+        system.run();
+        assert_eq!(exhausted_after_failure, false);
+        assert_eq!(subject.quota_exhausted, true);
+        let recording = neighborhood_recording_arc.lock().unwrap();
+        assert_eq!(
+            recording.get_record::<RoutesDataChangeMsg>(0),
+            &RoutesDataChangeMsg { routes_data: false }
+        );
+        assert_eq!(recording.len(), 1);
+        TestLogHandler::new().exists_log_containing(
+            "ERROR: BandwidthShaper: Cannot announce routes_data = false to the Neighborhood \
+             (SendError::Closed(..)); will try again at the next quota check",
+        );
+    }
+
+    #[test]
+    fn data_usage_survives_a_round_trip_through_its_stored_form() {
+        let usage = DataUsage {
+            as_of: UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+            daily_bytes: 1_234,
+            monthly_bytes: 56_789,
+        };
+
+        let stored = usage.to_string();
+
+        assert_eq!(stored, "1700000000|1234|56789".to_string());
+        assert_eq!(DataUsage::from_str(&stored), Ok(usage));
+        assert_eq!(
+            DataUsage::from_str("1700000000|1234"),
+            Err(
+                "Data usage should be three numbers separated by '|', not '1700000000|1234'"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            DataUsage::from_str("1700000000|booga|1"),
+            Err(
+                "Data usage should be three numbers separated by '|', not '1700000000|booga|1'"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn throttle_refuses_permission_until_the_buckets_recover() {
+        let shaper = BandwidthShaper::new(limits(1, 0, 0, 0, 0), DataQuota::default());
+        let mut subject = BandwidthThrottle::new(
+            shaper.clone(),
+            TrafficKind::Relay,
+            None,
+            Logger::new("test"),
+        );
+
+        let results = tokio::runtime::current_thread::Runtime::new()
+            .unwrap()
+            .block_on(future::lazy(move || {
+                let before = subject.poll_permit();
+                subject.record_transfer(5_000);
+                let after = subject.poll_permit();
+                let delay_pending = subject.delay_opt.is_some();
+                Ok::<_, ()>((before, after, delay_pending))
+            }))
+            .unwrap();
+
+        assert_eq!(results, (Async::Ready(()), Async::NotReady, true));
+        assert!(shaper.delay_for(TrafficKind::Relay, None).is_some());
+    }
+}
@@ -3,8 +3,12 @@
 use crate::accountant::db_access_objects::payable_dao::PayableAccount;
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::blockchain_agent::BlockchainAgent;
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::msgs::QualifiedPayablesMessage;
-use crate::accountant::{RequestTransactionReceipts, ResponseSkeleton, SkeletonOptHolder};
+use crate::accountant::{
+    RequestFeeBumps, RequestTransactionReceipts, ResponseSkeleton, SkeletonOptHolder,
+};
 use crate::blockchain::blockchain_bridge::RetrieveTransactions;
+use crate::blockchain::external_signer::ExternalSignerEndpoint;
+use crate::sub_lib::neighborhood::ConfigChangeMsg;
 use crate::sub_lib::peer_actors::BindMessage;
 use actix::Message;
 use actix::Recipient;
@@ -17,6 +21,7 @@ use web3::types::U256;
 #[derive(Clone, PartialEq, Eq, Debug, Default)]
 pub struct BlockchainBridgeConfig {
     pub blockchain_service_url_opt: Option<String>,
+    pub external_signer_opt: Option<ExternalSignerEndpoint>,
     pub chain: Chain,
     // TODO: totally ignored during the setup of the BlockchainBridge actor!
     // Use it in the body or delete this field
@@ -31,6 +36,8 @@ pub struct BlockchainBridgeSubs {
     pub retrieve_transactions: Recipient<RetrieveTransactions>,
     pub ui_sub: Recipient<NodeFromUiMessage>,
     pub request_transaction_receipts: Recipient<RequestTransactionReceipts>,
+    pub request_fee_bumps: Recipient<RequestFeeBumps>,
+    pub config_change_msg_sub: Recipient<ConfigChangeMsg>,
 }
 
 impl Debug for BlockchainBridgeSubs {
@@ -83,6 +90,7 @@ impl ConsumingWalletBalances {
 
 #[cfg(test)]
 mod tests {
+    use crate::accountant::test_utils::FundsTransferDaoMock;
     use crate::actor_system_factory::SubsFactory;
     use crate::blockchain::blockchain_bridge::{BlockchainBridge, BlockchainBridgeSubsFactoryReal};
     use crate::blockchain::test_utils::BlockchainInterfaceMock;
@@ -107,6 +115,7 @@ mod tests {
         let accountant = BlockchainBridge::new(
             Box::new(blockchain_interface),
             Box::new(persistent_config),
+            Box::new(FundsTransferDaoMock::default()),
             false,
         );
         let addr = accountant.start();
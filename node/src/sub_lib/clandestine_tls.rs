@@ -0,0 +1,328 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use crate::sub_lib::cryptde::{CryptDE, CryptData, PlainData, PublicKey};
+use openssl::asn1::Asn1Time;
+use openssl::bn::{BigNum, MsbOption};
+use openssl::ec::{EcGroup, EcKey};
+use openssl::hash::MessageDigest;
+use openssl::nid::Nid;
+use openssl::pkey::{PKey, Private};
+use openssl::ssl::{
+    ConnectConfiguration, SslAcceptor, SslConnector, SslMethod, SslVerifyMode, SslVersion,
+};
+use openssl::x509::extension::SubjectAlternativeName;
+use openssl::x509::{X509NameBuilder, X509Ref, X509StoreContextRef, X509};
+use std::fmt::{Debug, Formatter};
+
+pub const CLANDESTINE_TLS_URI_SCHEME: &str = "masq";
+const CERTIFICATE_VALIDITY_DAYS: u32 = 3650;
+const TLS_HANDSHAKE_RECORD_TYPE: u8 = 0x16;
+
+// A Node's TLS certificate is self-signed with a throwaway key. What makes it trustworthy is a
+// subjectAltName URI of the form masq://<CryptDE public key>/<CryptDE signature of the TLS
+// public key>, which only the holder of the CryptDE private key can produce.
+#[derive(Clone)]
+pub struct ClandestineTls {
+    cryptde: &'static dyn CryptDE,
+    certificate: X509,
+    private_key: PKey<Private>,
+}
+
+impl Debug for ClandestineTls {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ClandestineTls for {}", self.cryptde.public_key())
+    }
+}
+
+impl ClandestineTls {
+    pub fn new(cryptde: &'static dyn CryptDE) -> Result<ClandestineTls, String> {
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).map_err(stringify)?;
+        let private_key =
+            PKey::from_ec_key(EcKey::generate(&group).map_err(stringify)?).map_err(stringify)?;
+        let tls_public_key = private_key.public_key_to_der().map_err(stringify)?;
+        let signature = cryptde
+            .sign(&PlainData::new(&tls_public_key))
+            .map_err(|e| format!("Could not sign TLS public key: {:?}", e))?;
+        let certificate = Self::make_certificate(cryptde.public_key(), &signature, &private_key)
+            .map_err(stringify)?;
+        Ok(ClandestineTls {
+            cryptde,
+            certificate,
+            private_key,
+        })
+    }
+
+    pub fn certificate(&self) -> &X509Ref {
+        &self.certificate
+    }
+
+    pub fn acceptor(&self) -> Result<SslAcceptor, String> {
+        let mut builder = SslAcceptor::mozilla_modern_v5(SslMethod::tls()).map_err(stringify)?;
+        builder
+            .set_private_key(&self.private_key)
+            .map_err(stringify)?;
+        builder
+            .set_certificate(&self.certificate)
+            .map_err(stringify)?;
+        let cryptde = self.cryptde;
+        builder.set_verify_callback(
+            SslVerifyMode::PEER | SslVerifyMode::FAIL_IF_NO_PEER_CERT,
+            move |_, context| Self::verify_peer(cryptde, context, None),
+        );
+        Ok(builder.build())
+    }
+
+    pub fn connect_configuration(
+        &self,
+        expected_key: &PublicKey,
+    ) -> Result<ConnectConfiguration, String> {
+        let mut builder = SslConnector::builder(SslMethod::tls()).map_err(stringify)?;
+        builder
+            .set_min_proto_version(Some(SslVersion::TLS1_3))
+            .map_err(stringify)?;
+        builder
+            .set_private_key(&self.private_key)
+            .map_err(stringify)?;
+        builder
+            .set_certificate(&self.certificate)
+            .map_err(stringify)?;
+        let mut configuration = builder.build().configure().map_err(stringify)?;
+        configuration.set_verify_hostname(false);
+        configuration.set_use_server_name_indication(false);
+        let cryptde = self.cryptde;
+        let expected_key = expected_key.clone();
+        configuration.set_verify_callback(SslVerifyMode::PEER, move |_, context| {
+            Self::verify_peer(cryptde, context, Some(&expected_key))
+        });
+        Ok(configuration)
+    }
+
+    // Returns the CryptDE public key that the certificate's subjectAltName vouches for, if the
+    // accompanying signature checks out.
+    pub fn certified_public_key(
+        cryptde: &dyn CryptDE,
+        certificate: &X509Ref,
+    ) -> Result<PublicKey, String> {
+        let prefix = format!("{}://", CLANDESTINE_TLS_URI_SCHEME);
+        let uri = certificate
+            .subject_alt_names()
+            .into_iter()
+            .flatten()
+            .find_map(|name| name.uri().map(|uri| uri.to_string()))
+            .ok_or_else(|| "Certificate has no URI subjectAltName".to_string())?;
+        let (key_str, signature_str) = uri
+            .strip_prefix(&prefix)
+            .and_then(|rest| rest.split_once('/'))
+            .ok_or_else(|| format!("Certificate URI '{}' is not a {} URI", uri, prefix))?;
+        let decode = |s: &str| {
+            base64::decode_config(s, base64::URL_SAFE_NO_PAD)
+                .map_err(|e| format!("Certificate URI '{}' is malformed: {}", uri, e))
+        };
+        let public_key = PublicKey::new(&decode(key_str)?);
+        let signature = CryptData::new(&decode(signature_str)?);
+        let tls_public_key = certificate
+            .public_key()
+            .and_then(|key| key.public_key_to_der())
+            .map_err(stringify)?;
+        if cryptde.verify_signature(&PlainData::new(&tls_public_key), &signature, &public_key) {
+            Ok(public_key)
+        } else {
+            Err(format!(
+                "Certificate is not signed by the key it names: {}",
+                public_key
+            ))
+        }
+    }
+
+    // JSON masquerade begins with '{' and WebSocket masquerade with 'G', so one byte is enough.
+    pub fn is_client_hello(data: &[u8]) -> bool {
+        data.first() == Some(&TLS_HANDSHAKE_RECORD_TYPE)
+    }
+
+    fn make_certificate(
+        public_key: &PublicKey,
+        signature: &CryptData,
+        private_key: &PKey<Private>,
+    ) -> Result<X509, openssl::error::ErrorStack> {
+        let mut name = X509NameBuilder::new()?;
+        name.append_entry_by_nid(Nid::COMMONNAME, "MASQ Node")?;
+        let name = name.build();
+        let mut serial = BigNum::new()?;
+        serial.rand(128, MsbOption::MAYBE_ZERO, false)?;
+        let mut builder = X509::builder()?;
+        builder.set_version(2)?;
+        builder.set_serial_number(serial.to_asn1_integer()?.as_ref())?;
+        builder.set_subject_name(&name)?;
+        builder.set_issuer_name(&name)?;
+        builder.set_pubkey(private_key)?;
+        builder.set_not_before(Asn1Time::days_from_now(0)?.as_ref())?;
+        builder.set_not_after(Asn1Time::days_from_now(CERTIFICATE_VALIDITY_DAYS)?.as_ref())?;
+        let san = SubjectAlternativeName::new()
+            .uri(&format!(
+                "{}://{}/{}",
+                CLANDESTINE_TLS_URI_SCHEME,
+                base64::encode_config(public_key.as_slice(), base64::URL_SAFE_NO_PAD),
+                base64::encode_config(signature.as_slice(), base64::URL_SAFE_NO_PAD)
+            ))
+            .build(&builder.x509v3_context(None, None))?;
+        builder.append_extension(san)?;
+        builder.sign(private_key, MessageDigest::sha256())?;
+        Ok(builder.build())
+    }
+
+    // The certificate is self-signed, so OpenSSL's own opinion (preverify) is disregarded.
+    fn verify_peer(
+        cryptde: &dyn CryptDE,
+        context: &mut X509StoreContextRef,
+        expected_key_opt: Option<&PublicKey>,
+    ) -> bool {
+        if context.error_depth() != 0 {
+            return false;
+        }
+        let certificate = match context.current_cert() {
+            Some(certificate) => certificate,
+            None => return false,
+        };
+        match (
+            Self::certified_public_key(cryptde, certificate),
+            expected_key_opt,
+        ) {
+            (Ok(actual_key), Some(expected_key)) => &actual_key == expected_key,
+            (Ok(_), None) => true,
+            (Err(_), _) => false,
+        }
+    }
+}
+
+fn stringify(e: openssl::error::ErrorStack) -> String {
+    format!("TLS setup failed: {}", e)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{alias_cryptde, main_cryptde};
+    use std::io::{Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::thread;
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(CLANDESTINE_TLS_URI_SCHEME, "masq");
+        assert_eq!(CERTIFICATE_VALIDITY_DAYS, 3650);
+        assert_eq!(TLS_HANDSHAKE_RECORD_TYPE, 0x16);
+    }
+
+    #[test]
+    fn certificate_vouches_for_cryptde_public_key() {
+        let subject = ClandestineTls::new(main_cryptde()).unwrap();
+
+        let result = ClandestineTls::certified_public_key(main_cryptde(), subject.certificate());
+
+        assert_eq!(result, Ok(main_cryptde().public_key().clone()));
+    }
+
+    #[test]
+    fn certificate_with_someone_elses_signature_is_rejected() {
+        let honest = ClandestineTls::new(main_cryptde()).unwrap();
+        let imposter_key = PKey::from_ec_key(
+            EcKey::generate(&EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap()).unwrap(),
+        )
+        .unwrap();
+        let stolen_signature = main_cryptde()
+            .sign(&PlainData::new(
+                &honest.private_key.public_key_to_der().unwrap(),
+            ))
+            .unwrap();
+        let forged = ClandestineTls::make_certificate(
+            main_cryptde().public_key(),
+            &stolen_signature,
+            &imposter_key,
+        )
+        .unwrap();
+
+        let result = ClandestineTls::certified_public_key(main_cryptde(), &forged);
+
+        assert_eq!(
+            result,
+            Err(format!(
+                "Certificate is not signed by the key it names: {}",
+                main_cryptde().public_key()
+            ))
+        );
+    }
+
+    #[test]
+    fn certificate_without_uri_is_rejected() {
+        let private_key = PKey::from_ec_key(
+            EcKey::generate(&EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap()).unwrap(),
+        )
+        .unwrap();
+        let mut builder = X509::builder().unwrap();
+        builder.set_pubkey(&private_key).unwrap();
+        builder.sign(&private_key, MessageDigest::sha256()).unwrap();
+        let certificate = builder.build();
+
+        let result = ClandestineTls::certified_public_key(main_cryptde(), &certificate);
+
+        assert_eq!(
+            result,
+            Err("Certificate has no URI subjectAltName".to_string())
+        );
+    }
+
+    #[test]
+    fn is_client_hello_recognizes_tls_handshake_records() {
+        assert!(ClandestineTls::is_client_hello(&[0x16, 0x03, 0x01, 0x00]));
+        assert!(ClandestineTls::is_client_hello(&[0x16]));
+        assert!(!ClandestineTls::is_client_hello(&[]));
+        assert!(!ClandestineTls::is_client_hello(b"{\"component\""));
+        assert!(!ClandestineTls::is_client_hello(b"GET / HTTP/1.1"));
+    }
+
+    fn handshake(expected_key: &PublicKey) -> Result<Vec<u8>, String> {
+        let server = ClandestineTls::new(main_cryptde()).unwrap();
+        let client = ClandestineTls::new(alias_cryptde()).unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let server_addr = listener.local_addr().unwrap();
+        let acceptor = server.acceptor().unwrap();
+        let handle = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            if let Ok(mut tls_stream) = acceptor.accept(stream) {
+                tls_stream.write_all(b"booga").unwrap();
+                tls_stream.flush().unwrap();
+            }
+        });
+        let stream = TcpStream::connect(server_addr).unwrap();
+        let result = client
+            .connect_configuration(expected_key)
+            .unwrap()
+            .connect("127.0.0.1", stream)
+            .map_err(|e| e.to_string())
+            .map(|mut tls_stream| {
+                let mut buf = [0u8; 5];
+                tls_stream.read_exact(&mut buf).unwrap();
+                buf.to_vec()
+            });
+        handle.join().unwrap();
+        result
+    }
+
+    #[test]
+    fn nodes_complete_handshake_when_server_is_who_client_expects() {
+        let result = handshake(main_cryptde().public_key());
+
+        assert_eq!(result, Ok(b"booga".to_vec()));
+    }
+
+    #[test]
+    fn client_refuses_handshake_when_server_is_somebody_else() {
+        let result = handshake(alias_cryptde().public_key());
+
+        let message = result.err().unwrap();
+        assert!(
+            message.contains("certificate verify failed"),
+            "Unexpected error: {}",
+            message
+        );
+    }
+}
@@ -1,6 +1,7 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
-use crate::sub_lib::accountant::{PaymentThresholds, ScanIntervals};
+use crate::sub_lib::accountant::{FeeBumping, PaymentThresholds, ScanIntervals, SpendingBudget};
+use crate::sub_lib::bandwidth_shaper::{BandwidthLimits, DataQuota};
 use crate::sub_lib::combined_parameters::CombinedParamsDataTypes::U64;
 use crate::sub_lib::combined_parameters::InitializationState::{Initialized, Uninitialized};
 use crate::sub_lib::neighborhood::RatePack;
@@ -93,9 +94,13 @@ impl CombinedParamsValueRetriever {
 
 #[derive(Debug)]
 enum CombinedParams {
+    BandwidthLimits(InitializationState<BandwidthLimits>),
+    DataQuota(InitializationState<DataQuota>),
+    FeeBumping(InitializationState<FeeBumping>),
     RatePack(InitializationState<RatePack>),
     PaymentThresholds(InitializationState<PaymentThresholds>),
     ScanIntervals(InitializationState<ScanIntervals>),
+    SpendingBudget(InitializationState<SpendingBudget>),
 }
 
 #[derive(Debug)]
@@ -152,6 +157,30 @@ impl CombinedParams {
         parsed_values: HashMap<String, CombinedParamsValueRetriever>,
     ) -> Self {
         match self {
+            Self::BandwidthLimits(Uninitialized) => {
+                Self::BandwidthLimits(Initialized(initiate_struct!(
+                    BandwidthLimits,
+                    &parsed_values,
+                    "upload_kb_per_sec",
+                    "download_kb_per_sec",
+                    "relay_kb_per_sec",
+                    "exit_kb_per_sec",
+                    "per_neighbor_kb_per_sec"
+                )))
+            }
+            Self::DataQuota(Uninitialized) => Self::DataQuota(Initialized(initiate_struct!(
+                DataQuota,
+                &parsed_values,
+                "daily_mb",
+                "monthly_mb"
+            ))),
+            Self::FeeBumping(Uninitialized) => Self::FeeBumping(Initialized(initiate_struct!(
+                FeeBumping,
+                &parsed_values,
+                "bump_after_sec",
+                "gas_price_increase_percent",
+                "max_bumps"
+            ))),
             Self::RatePack(Uninitialized) => Self::RatePack(Initialized(initiate_struct!(
                 RatePack,
                 &parsed_values,
@@ -182,6 +211,16 @@ impl CombinedParams {
                     "receivable_scan_interval"
                 )))
             }
+            Self::SpendingBudget(Uninitialized) => {
+                Self::SpendingBudget(Initialized(initiate_struct!(
+                    SpendingBudget,
+                    &parsed_values,
+                    "daily_gwei",
+                    "monthly_gwei",
+                    "first_warning_percent",
+                    "second_warning_percent"
+                )))
+            }
             _ => panic!(
                 "should be called only on uninitialized object, not: {:?}",
                 self
@@ -193,6 +232,19 @@ impl CombinedParams {
 impl From<&CombinedParams> for &[(&str, CombinedParamsDataTypes)] {
     fn from(params: &CombinedParams) -> &'static [(&'static str, CombinedParamsDataTypes)] {
         match params {
+            CombinedParams::BandwidthLimits(Uninitialized) => &[
+                ("upload_kb_per_sec", U64),
+                ("download_kb_per_sec", U64),
+                ("relay_kb_per_sec", U64),
+                ("exit_kb_per_sec", U64),
+                ("per_neighbor_kb_per_sec", U64),
+            ],
+            CombinedParams::DataQuota(Uninitialized) => &[("daily_mb", U64), ("monthly_mb", U64)],
+            CombinedParams::FeeBumping(Uninitialized) => &[
+                ("bump_after_sec", U64),
+                ("gas_price_increase_percent", U64),
+                ("max_bumps", U64),
+            ],
             CombinedParams::RatePack(Uninitialized) => &[
                 ("routing_byte_rate", U64),
                 ("routing_service_rate", U64),
@@ -212,6 +264,12 @@ impl From<&CombinedParams> for &[(&str, CombinedParamsDataTypes)] {
                 ("payable_scan_interval", U64),
                 ("receivable_scan_interval", U64),
             ],
+            CombinedParams::SpendingBudget(Uninitialized) => &[
+                ("daily_gwei", U64),
+                ("monthly_gwei", U64),
+                ("first_warning_percent", U64),
+                ("second_warning_percent", U64),
+            ],
             _ => panic!(
                 "should be called only on uninitialized object, not: {:?}",
                 params
@@ -298,6 +356,109 @@ impl TryFrom<&str> for RatePack {
     }
 }
 
+impl Display for BandwidthLimits {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}|{}|{}|{}|{}",
+            self.upload_kb_per_sec,
+            self.download_kb_per_sec,
+            self.relay_kb_per_sec,
+            self.exit_kb_per_sec,
+            self.per_neighbor_kb_per_sec
+        )
+    }
+}
+
+impl TryFrom<&str> for BandwidthLimits {
+    type Error = String;
+
+    fn try_from(parameters: &str) -> Result<Self, String> {
+        match CombinedParams::BandwidthLimits(Uninitialized).parse(parameters) {
+            Ok(CombinedParams::BandwidthLimits(Initialized(bandwidth_limits))) => {
+                Ok(bandwidth_limits)
+            }
+            Err(e) => Err(e),
+            _ => unreachable(),
+        }
+    }
+}
+
+impl Display for DataQuota {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}|{}", self.daily_mb, self.monthly_mb)
+    }
+}
+
+impl TryFrom<&str> for DataQuota {
+    type Error = String;
+
+    fn try_from(parameters: &str) -> Result<Self, String> {
+        match CombinedParams::DataQuota(Uninitialized).parse(parameters) {
+            Ok(CombinedParams::DataQuota(Initialized(data_quota))) => Ok(data_quota),
+            Err(e) => Err(e),
+            _ => unreachable(),
+        }
+    }
+}
+
+impl Display for FeeBumping {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}|{}|{}",
+            self.bump_after_sec, self.gas_price_increase_percent, self.max_bumps
+        )
+    }
+}
+
+impl TryFrom<&str> for FeeBumping {
+    type Error = String;
+
+    fn try_from(parameters: &str) -> Result<Self, String> {
+        match CombinedParams::FeeBumping(Uninitialized).parse(parameters) {
+            Ok(CombinedParams::FeeBumping(Initialized(fee_bumping))) => Ok(fee_bumping),
+            Err(e) => Err(e),
+            _ => unreachable(),
+        }
+    }
+}
+
+impl Display for SpendingBudget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}|{}|{}|{}",
+            self.daily_gwei,
+            self.monthly_gwei,
+            self.first_warning_percent,
+            self.second_warning_percent
+        )
+    }
+}
+
+impl TryFrom<&str> for SpendingBudget {
+    type Error = String;
+
+    fn try_from(parameters: &str) -> Result<Self, String> {
+        let spending_budget = match CombinedParams::SpendingBudget(Uninitialized).parse(parameters)
+        {
+            Ok(CombinedParams::SpendingBudget(Initialized(spending_budget))) => spending_budget,
+            Err(e) => return Err(e),
+            _ => unreachable(),
+        };
+        if spending_budget.first_warning_percent > spending_budget.second_warning_percent
+            || spending_budget.second_warning_percent > 100
+        {
+            return Err(format!(
+                "Warning percentages must not decrease and must not exceed 100, but {} and {} supplied",
+                spending_budget.first_warning_percent, spending_budget.second_warning_percent
+            ));
+        }
+        Ok(spending_budget)
+    }
+}
+
 fn unreachable() -> ! {
     unreachable!("technically shouldn't be possible")
 }
@@ -611,4 +772,144 @@ mod tests {
 
         assert_eq!(result, "5000010|120|123|20000|30020|111".to_string());
     }
+
+    #[test]
+    fn bandwidth_limits_from_combined_params() {
+        let bandwidth_limits_str = "1000|2000|300|400|50";
+
+        let result = BandwidthLimits::try_from(bandwidth_limits_str).unwrap();
+
+        assert_eq!(
+            result,
+            BandwidthLimits {
+                upload_kb_per_sec: 1000,
+                download_kb_per_sec: 2000,
+                relay_kb_per_sec: 300,
+                exit_kb_per_sec: 400,
+                per_neighbor_kb_per_sec: 50
+            }
+        )
+    }
+
+    #[test]
+    fn bandwidth_limits_to_combined_params() {
+        let bandwidth_limits = BandwidthLimits {
+            upload_kb_per_sec: 10,
+            download_kb_per_sec: 0,
+            relay_kb_per_sec: 30,
+            exit_kb_per_sec: 40,
+            per_neighbor_kb_per_sec: 5,
+        };
+
+        let result = bandwidth_limits.to_string();
+
+        assert_eq!(result, "10|0|30|40|5".to_string());
+    }
+
+    #[test]
+    fn data_quota_from_combined_params() {
+        let data_quota_str = "500|10000";
+
+        let result = DataQuota::try_from(data_quota_str).unwrap();
+
+        assert_eq!(
+            result,
+            DataQuota {
+                daily_mb: 500,
+                monthly_mb: 10000
+            }
+        )
+    }
+
+    #[test]
+    fn data_quota_to_combined_params() {
+        let data_quota = DataQuota {
+            daily_mb: 0,
+            monthly_mb: 25000,
+        };
+
+        let result = data_quota.to_string();
+
+        assert_eq!(result, "0|25000".to_string());
+    }
+
+    #[test]
+    fn fee_bumping_from_combined_params() {
+        let fee_bumping_str = "900|15|4";
+
+        let result = FeeBumping::try_from(fee_bumping_str).unwrap();
+
+        assert_eq!(
+            result,
+            FeeBumping {
+                bump_after_sec: 900,
+                gas_price_increase_percent: 15,
+                max_bumps: 4
+            }
+        )
+    }
+
+    #[test]
+    fn fee_bumping_to_combined_params() {
+        let fee_bumping = FeeBumping {
+            bump_after_sec: 3600,
+            gas_price_increase_percent: 25,
+            max_bumps: 0,
+        };
+
+        let result = fee_bumping.to_string();
+
+        assert_eq!(result, "3600|25|0".to_string());
+    }
+
+    #[test]
+    fn spending_budget_from_combined_params() {
+        let spending_budget_str = "5000000|100000000|50|90";
+
+        let result = SpendingBudget::try_from(spending_budget_str).unwrap();
+
+        assert_eq!(
+            result,
+            SpendingBudget {
+                daily_gwei: 5_000_000,
+                monthly_gwei: 100_000_000,
+                first_warning_percent: 50,
+                second_warning_percent: 90
+            }
+        )
+    }
+
+    #[test]
+    fn spending_budget_to_combined_params() {
+        let spending_budget = SpendingBudget {
+            daily_gwei: 0,
+            monthly_gwei: 250_000_000,
+            first_warning_percent: 75,
+            second_warning_percent: 100,
+        };
+
+        let result = spending_budget.to_string();
+
+        assert_eq!(result, "0|250000000|75|100".to_string());
+    }
+
+    #[test]
+    fn spending_budget_rejects_warning_percentages_out_of_order() {
+        let result = SpendingBudget::try_from("1000|0|95|80");
+
+        assert_eq!(
+            result,
+            Err("Warning percentages must not decrease and must not exceed 100, but 95 and 80 supplied".to_string())
+        )
+    }
+
+    #[test]
+    fn spending_budget_rejects_warning_percentage_above_hundred() {
+        let result = SpendingBudget::try_from("1000|0|80|101");
+
+        assert_eq!(
+            result,
+            Err("Warning percentages must not decrease and must not exceed 100, but 80 and 101 supplied".to_string())
+        )
+    }
 }
@@ -1,6 +1,6 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 use crate::bootstrapper::CryptDEPair;
-use crate::neighborhood::gossip::Gossip_0v1;
+use crate::neighborhood::gossip::Gossip_0v2;
 use crate::sub_lib::cryptde::encodex;
 use crate::sub_lib::cryptde::CryptDE;
 use crate::sub_lib::cryptde::CryptData;
@@ -9,8 +9,8 @@ use crate::sub_lib::dispatcher::InboundClientData;
 use crate::sub_lib::neighborhood::GossipFailure_0v1;
 use crate::sub_lib::node_addr::NodeAddr;
 use crate::sub_lib::peer_actors::BindMessage;
-use crate::sub_lib::proxy_client::{ClientResponsePayload_0v1, DnsResolveFailure_0v1};
-use crate::sub_lib::proxy_server::ClientRequestPayload_0v1;
+use crate::sub_lib::proxy_client::{ClientResponsePayload_0v2, DnsResolveFailure_0v1};
+use crate::sub_lib::proxy_server::ClientRequestPayload_0v2;
 use crate::sub_lib::route::Route;
 use crate::sub_lib::versioned_data::VersionedData;
 use crate::sub_lib::wallet::Wallet;
@@ -67,9 +67,9 @@ pub struct IncipientCoresPackage {
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MessageType {
-    ClientRequest(VersionedData<ClientRequestPayload_0v1>),
-    ClientResponse(VersionedData<ClientResponsePayload_0v1>),
-    Gossip(VersionedData<Gossip_0v1>),
+    ClientRequest(VersionedData<ClientRequestPayload_0v2>),
+    ClientResponse(VersionedData<ClientResponsePayload_0v2>),
+    Gossip(VersionedData<Gossip_0v2>),
     GossipFailure(VersionedData<GossipFailure_0v1>),
     DnsResolveFailed(VersionedData<DnsResolveFailure_0v1>),
 }
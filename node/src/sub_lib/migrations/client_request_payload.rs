@@ -2,24 +2,50 @@
 
 use crate::sub_lib::cryptde::PublicKey;
 use crate::sub_lib::migrations::utils::value_to_type;
-use crate::sub_lib::proxy_server::{ClientRequestPayload_0v1, ProxyProtocol};
+use crate::sub_lib::proxy_server::{ClientRequestPayload_0v2, ProxyProtocol};
 use crate::sub_lib::sequence_buffer::SequencedPacket;
 use crate::sub_lib::stream_key::StreamKey;
 use crate::sub_lib::versioned_data::Migrations;
 use crate::sub_lib::versioned_data::{MigrationError, StepError, VersionedData};
 use lazy_static::lazy_static;
 use serde_cbor::Value;
+use serde_derive::{Deserialize, Serialize};
 use std::convert::TryFrom;
 
+// What a ClientRequestPayload looked like before it carried acknowledgements
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[allow(non_camel_case_types)]
+pub struct ClientRequestPayload_0v1 {
+    pub stream_key: StreamKey,
+    pub sequenced_packet: SequencedPacket,
+    pub target_hostname: Option<String>,
+    pub target_port: u16,
+    pub protocol: ProxyProtocol,
+    pub originator_public_key: PublicKey,
+}
+
 lazy_static! {
     pub static ref MIGRATIONS: Migrations = {
         let current_version = masq_lib::constants::CLIENT_REQUEST_PAYLOAD_CURRENT_VERSION;
         let mut migrations = Migrations::new(current_version);
 
-        migrate_value!(dv!(0, 1), ClientRequestPayload_0v1, ClientRequestPayloadMF_0v1, {|value: serde_cbor::Value| {
-            ClientRequestPayload_0v1::try_from (&value)
+        migrate_value!(dv!(0, 2), ClientRequestPayload_0v2, ClientRequestPayloadMF_0v2, {|value: serde_cbor::Value| {
+            ClientRequestPayload_0v2::try_from (&value)
+        }});
+        migrations.add_step (masq_lib::data_version::FUTURE_VERSION, dv!(0, 2), Box::new (ClientRequestPayloadMF_0v2{}));
+
+        migrate_item!(dv!(0, 1), ClientRequestPayload_0v1, dv!(0, 2), ClientRequestPayload_0v2, ClientRequestPayload0v1To0v2, {|crp: ClientRequestPayload_0v1| {
+            Ok(ClientRequestPayload_0v2 {
+                stream_key: crp.stream_key,
+                sequenced_packet: crp.sequenced_packet,
+                target_hostname: crp.target_hostname,
+                target_port: crp.target_port,
+                protocol: crp.protocol,
+                originator_public_key: crp.originator_public_key,
+                next_expected_sequence_opt: None,
+            })
         }});
-        migrations.add_step (masq_lib::data_version::FUTURE_VERSION, dv!(0, 1), Box::new (ClientRequestPayloadMF_0v1{}));
+        migrations.add_step (dv!(0, 1), dv!(0, 2), Box::new (ClientRequestPayload0v1To0v2{}));
 
         // add more steps here
 
@@ -27,21 +53,21 @@ lazy_static! {
     };
 }
 
-impl From<ClientRequestPayload_0v1> for VersionedData<ClientRequestPayload_0v1> {
-    fn from(data: ClientRequestPayload_0v1) -> Self {
+impl From<ClientRequestPayload_0v2> for VersionedData<ClientRequestPayload_0v2> {
+    fn from(data: ClientRequestPayload_0v2) -> Self {
         VersionedData::new(&MIGRATIONS, &data)
     }
 }
 
-impl TryFrom<VersionedData<ClientRequestPayload_0v1>> for ClientRequestPayload_0v1 {
+impl TryFrom<VersionedData<ClientRequestPayload_0v2>> for ClientRequestPayload_0v2 {
     type Error = MigrationError;
 
-    fn try_from(vd: VersionedData<ClientRequestPayload_0v1>) -> Result<Self, Self::Error> {
+    fn try_from(vd: VersionedData<ClientRequestPayload_0v2>) -> Result<Self, Self::Error> {
         vd.extract(&MIGRATIONS)
     }
 }
 
-impl TryFrom<&Value> for ClientRequestPayload_0v1 {
+impl TryFrom<&Value> for ClientRequestPayload_0v2 {
     type Error = StepError;
 
     fn try_from(value: &Value) -> Result<Self, Self::Error> {
@@ -53,6 +79,7 @@ impl TryFrom<&Value> for ClientRequestPayload_0v1 {
                 let mut target_port_opt: Option<u16> = None;
                 let mut protocol_opt: Option<ProxyProtocol> = None;
                 let mut originator_public_key_opt: Option<PublicKey> = None;
+                let mut next_expected_sequence_opt: Option<u64> = None;
                 map.keys().for_each(|k| {
                     let v = map.get(k).expect("Disappeared");
                     if let (Value::Text(field_name), _) = (k, v) {
@@ -69,6 +96,9 @@ impl TryFrom<&Value> for ClientRequestPayload_0v1 {
                             "originator_public_key" => {
                                 originator_public_key_opt = value_to_type::<PublicKey>(v)
                             }
+                            "next_expected_sequence_opt" => {
+                                next_expected_sequence_opt = value_to_type::<u64>(v)
+                            }
                             _ => (),
                         }
                     }
@@ -100,7 +130,7 @@ impl TryFrom<&Value> for ClientRequestPayload_0v1 {
                 if !missing_fields.is_empty() {
                     unimplemented!("{:?}", missing_fields.clone())
                 }
-                Ok(ClientRequestPayload_0v1 {
+                Ok(ClientRequestPayload_0v2 {
                     stream_key: stream_key_opt.expect("stream_key disappeared"),
                     sequenced_packet: sequenced_packet_opt.expect("sequenced_packet disappeared"),
                     target_hostname: target_hostname_opt.expect("target_hostname disappeared"),
@@ -108,6 +138,7 @@ impl TryFrom<&Value> for ClientRequestPayload_0v1 {
                     protocol: protocol_opt.expect("protocol disappeared"),
                     originator_public_key: originator_public_key_opt
                         .expect("originator_public_key disappeared"),
+                    next_expected_sequence_opt,
                 })
             }
             _ => Err(StepError::SemanticError(format!(
@@ -121,9 +152,7 @@ impl TryFrom<&Value> for ClientRequestPayload_0v1 {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::sub_lib::cryptde::PublicKey;
     use masq_lib::data_version::DataVersion;
-    use serde_derive::{Deserialize, Serialize};
 
     #[test]
     fn can_migrate_from_the_future() {
@@ -135,16 +164,18 @@ mod tests {
             pub target_port: u16,
             pub protocol: ProxyProtocol,
             pub originator_public_key: PublicKey,
+            pub next_expected_sequence_opt: Option<u64>,
             pub another_field: String,
             pub yet_another_field: u64,
         }
-        let expected_crp = ClientRequestPayload_0v1 {
+        let expected_crp = ClientRequestPayload_0v2 {
             stream_key: StreamKey::make_meaningful_stream_key("All Things Must Pass"),
             sequenced_packet: SequencedPacket::new(vec![4, 3, 2, 1], 4321, false),
             target_hostname: Some("target.hostname.com".to_string()),
             target_port: 1234,
             protocol: ProxyProtocol::HTTP,
             originator_public_key: PublicKey::new(&[2, 3, 4, 5]),
+            next_expected_sequence_opt: Some(17),
         };
         let future_crp = ExampleFutureCRP {
             stream_key: expected_crp.stream_key.clone(),
@@ -153,6 +184,7 @@ mod tests {
             target_port: expected_crp.target_port.clone(),
             protocol: expected_crp.protocol.clone(),
             originator_public_key: expected_crp.originator_public_key.clone(),
+            next_expected_sequence_opt: expected_crp.next_expected_sequence_opt,
             another_field: "These are the times that try men's souls".to_string(),
             yet_another_field: 1234567890,
         };
@@ -160,19 +192,52 @@ mod tests {
         let serialized =
             serde_cbor::ser::to_vec(&VersionedData::new(&future_migrations, &future_crp)).unwrap();
         let future_vd =
-            serde_cbor::de::from_slice::<VersionedData<ClientRequestPayload_0v1>>(&serialized)
+            serde_cbor::de::from_slice::<VersionedData<ClientRequestPayload_0v2>>(&serialized)
                 .unwrap();
 
-        let actual_crp = ClientRequestPayload_0v1::try_from(future_vd).unwrap();
+        let actual_crp = ClientRequestPayload_0v2::try_from(future_vd).unwrap();
 
         assert_eq!(actual_crp, expected_crp);
     }
 
+    #[test]
+    fn can_migrate_from_0v1() {
+        let old_crp = ClientRequestPayload_0v1 {
+            stream_key: StreamKey::make_meaningful_stream_key("All Things Must Pass"),
+            sequenced_packet: SequencedPacket::new(vec![4, 3, 2, 1], 4321, false),
+            target_hostname: Some("target.hostname.com".to_string()),
+            target_port: 1234,
+            protocol: ProxyProtocol::TLS,
+            originator_public_key: PublicKey::new(&[2, 3, 4, 5]),
+        };
+        let old_migrations = Migrations::new(DataVersion::new(0, 1));
+        let serialized =
+            serde_cbor::ser::to_vec(&VersionedData::new(&old_migrations, &old_crp)).unwrap();
+        let old_vd =
+            serde_cbor::de::from_slice::<VersionedData<ClientRequestPayload_0v2>>(&serialized)
+                .unwrap();
+
+        let actual_crp = ClientRequestPayload_0v2::try_from(old_vd).unwrap();
+
+        assert_eq!(
+            actual_crp,
+            ClientRequestPayload_0v2 {
+                stream_key: old_crp.stream_key,
+                sequenced_packet: old_crp.sequenced_packet,
+                target_hostname: old_crp.target_hostname,
+                target_port: old_crp.target_port,
+                protocol: old_crp.protocol,
+                originator_public_key: old_crp.originator_public_key,
+                next_expected_sequence_opt: None,
+            }
+        );
+    }
+
     #[test]
     fn cannot_migrate_from_value_other_than_map() {
         let value = Value::Bool(true);
 
-        let result = ClientRequestPayload_0v1::try_from(&value);
+        let result = ClientRequestPayload_0v2::try_from(&value);
 
         assert_eq!(
             result,
@@ -1,24 +1,42 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
 use crate::sub_lib::migrations::utils::value_to_type;
-use crate::sub_lib::proxy_client::ClientResponsePayload_0v1;
+use crate::sub_lib::proxy_client::ClientResponsePayload_0v2;
 use crate::sub_lib::sequence_buffer::SequencedPacket;
 use crate::sub_lib::stream_key::StreamKey;
 use crate::sub_lib::versioned_data::Migrations;
 use crate::sub_lib::versioned_data::{MigrationError, StepError, VersionedData};
 use lazy_static::lazy_static;
 use serde_cbor::Value;
+use serde_derive::{Deserialize, Serialize};
 use std::convert::TryFrom;
 
+// What a ClientResponsePayload looked like before it carried acknowledgements
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[allow(non_camel_case_types)]
+pub struct ClientResponsePayload_0v1 {
+    pub stream_key: StreamKey,
+    pub sequenced_packet: SequencedPacket,
+}
+
 lazy_static! {
     pub static ref MIGRATIONS: Migrations = {
         let current_version = masq_lib::constants::CLIENT_RESPONSE_PAYLOAD_CURRENT_VERSION;
         let mut migrations = Migrations::new(current_version);
 
-        migrate_value!(dv!(0, 1), ClientResponsePayload_0v1, ClientResponsePayloadMF_0v1, {|value: serde_cbor::Value| {
-            ClientResponsePayload_0v1::try_from (&value)
+        migrate_value!(dv!(0, 2), ClientResponsePayload_0v2, ClientResponsePayloadMF_0v2, {|value: serde_cbor::Value| {
+            ClientResponsePayload_0v2::try_from (&value)
         }});
-        migrations.add_step (masq_lib::data_version::FUTURE_VERSION, dv!(0, 1), Box::new (ClientResponsePayloadMF_0v1{}));
+        migrations.add_step (masq_lib::data_version::FUTURE_VERSION, dv!(0, 2), Box::new (ClientResponsePayloadMF_0v2{}));
+
+        migrate_item!(dv!(0, 1), ClientResponsePayload_0v1, dv!(0, 2), ClientResponsePayload_0v2, ClientResponsePayload0v1To0v2, {|crp: ClientResponsePayload_0v1| {
+            Ok(ClientResponsePayload_0v2 {
+                stream_key: crp.stream_key,
+                sequenced_packet: crp.sequenced_packet,
+                next_expected_sequence_opt: None,
+            })
+        }});
+        migrations.add_step (dv!(0, 1), dv!(0, 2), Box::new (ClientResponsePayload0v1To0v2{}));
 
         // add more steps here
 
@@ -26,21 +44,21 @@ lazy_static! {
     };
 }
 
-impl From<ClientResponsePayload_0v1> for VersionedData<ClientResponsePayload_0v1> {
-    fn from(data: ClientResponsePayload_0v1) -> Self {
+impl From<ClientResponsePayload_0v2> for VersionedData<ClientResponsePayload_0v2> {
+    fn from(data: ClientResponsePayload_0v2) -> Self {
         VersionedData::new(&MIGRATIONS, &data)
     }
 }
 
-impl TryFrom<VersionedData<ClientResponsePayload_0v1>> for ClientResponsePayload_0v1 {
+impl TryFrom<VersionedData<ClientResponsePayload_0v2>> for ClientResponsePayload_0v2 {
     type Error = MigrationError;
 
-    fn try_from(vd: VersionedData<ClientResponsePayload_0v1>) -> Result<Self, Self::Error> {
+    fn try_from(vd: VersionedData<ClientResponsePayload_0v2>) -> Result<Self, Self::Error> {
         vd.extract(&MIGRATIONS)
     }
 }
 
-impl TryFrom<&Value> for ClientResponsePayload_0v1 {
+impl TryFrom<&Value> for ClientResponsePayload_0v2 {
     type Error = StepError;
 
     fn try_from(value: &Value) -> Result<Self, Self::Error> {
@@ -48,6 +66,7 @@ impl TryFrom<&Value> for ClientResponsePayload_0v1 {
             Value::Map(map) => {
                 let mut stream_key_opt: Option<StreamKey> = None;
                 let mut sequenced_packet_opt: Option<SequencedPacket> = None;
+                let mut next_expected_sequence_opt: Option<u64> = None;
                 map.keys().for_each(|k| {
                     let v = map.get(k).expect("Disappeared");
                     if let Value::Text(field_name) = k {
@@ -56,6 +75,9 @@ impl TryFrom<&Value> for ClientResponsePayload_0v1 {
                             "sequenced_packet" => {
                                 sequenced_packet_opt = value_to_type::<SequencedPacket>(v)
                             }
+                            "next_expected_sequence_opt" => {
+                                next_expected_sequence_opt = value_to_type::<u64>(v)
+                            }
                             _ => (),
                         }
                     }
@@ -79,9 +101,10 @@ impl TryFrom<&Value> for ClientResponsePayload_0v1 {
                 if !missing_fields.is_empty() {
                     unimplemented!("{:?}", missing_fields.clone())
                 }
-                Ok(ClientResponsePayload_0v1 {
+                Ok(ClientResponsePayload_0v2 {
                     stream_key: stream_key_opt.expect("stream_key disappeared"),
                     sequenced_packet: sequenced_packet_opt.expect("sequenced_packet disappeared"),
+                    next_expected_sequence_opt,
                 })
             }
             _ => Err(StepError::SemanticError(format!(
@@ -96,7 +119,6 @@ impl TryFrom<&Value> for ClientResponsePayload_0v1 {
 mod tests {
     use super::*;
     use masq_lib::data_version::DataVersion;
-    use serde_derive::{Deserialize, Serialize};
 
     #[test]
     fn can_migrate_from_the_future() {
@@ -104,16 +126,19 @@ mod tests {
         struct ExampleFutureCRP {
             pub stream_key: StreamKey,
             pub sequenced_packet: SequencedPacket,
+            pub next_expected_sequence_opt: Option<u64>,
             pub another_field: String,
             pub yet_another_field: u64,
         }
-        let expected_crp = ClientResponsePayload_0v1 {
+        let expected_crp = ClientResponsePayload_0v2 {
             stream_key: StreamKey::make_meaningful_stream_key("All Things Must Pass"),
             sequenced_packet: SequencedPacket::new(vec![4, 3, 2, 1], 4321, false),
+            next_expected_sequence_opt: Some(17),
         };
         let future_crp = ExampleFutureCRP {
             stream_key: expected_crp.stream_key.clone(),
             sequenced_packet: expected_crp.sequenced_packet.clone(),
+            next_expected_sequence_opt: expected_crp.next_expected_sequence_opt,
             another_field: "These are the times that try men's souls".to_string(),
             yet_another_field: 1234567890,
         };
@@ -121,19 +146,44 @@ mod tests {
         let serialized =
             serde_cbor::ser::to_vec(&VersionedData::new(&future_migrations, &future_crp)).unwrap();
         let future_vd =
-            serde_cbor::de::from_slice::<VersionedData<ClientResponsePayload_0v1>>(&serialized)
+            serde_cbor::de::from_slice::<VersionedData<ClientResponsePayload_0v2>>(&serialized)
                 .unwrap();
 
-        let actual_crp = ClientResponsePayload_0v1::try_from(future_vd).unwrap();
+        let actual_crp = ClientResponsePayload_0v2::try_from(future_vd).unwrap();
 
         assert_eq!(actual_crp, expected_crp);
     }
 
+    #[test]
+    fn can_migrate_from_0v1() {
+        let old_crp = ClientResponsePayload_0v1 {
+            stream_key: StreamKey::make_meaningful_stream_key("All Things Must Pass"),
+            sequenced_packet: SequencedPacket::new(vec![4, 3, 2, 1], 4321, false),
+        };
+        let old_migrations = Migrations::new(DataVersion::new(0, 1));
+        let serialized =
+            serde_cbor::ser::to_vec(&VersionedData::new(&old_migrations, &old_crp)).unwrap();
+        let old_vd =
+            serde_cbor::de::from_slice::<VersionedData<ClientResponsePayload_0v2>>(&serialized)
+                .unwrap();
+
+        let actual_crp = ClientResponsePayload_0v2::try_from(old_vd).unwrap();
+
+        assert_eq!(
+            actual_crp,
+            ClientResponsePayload_0v2 {
+                stream_key: old_crp.stream_key,
+                sequenced_packet: old_crp.sequenced_packet,
+                next_expected_sequence_opt: None,
+            }
+        );
+    }
+
     #[test]
     fn cannot_migrate_from_value_other_than_map() {
         let value = Value::Bool(true);
 
-        let result = ClientResponsePayload_0v1::try_from(&value);
+        let result = ClientResponsePayload_0v2::try_from(&value);
 
         assert_eq!(
             result,
@@ -1,20 +1,35 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
-use crate::neighborhood::gossip::{GossipNodeRecord, Gossip_0v1};
+use crate::neighborhood::gossip::{GossipNodeRecord, Gossip_0v2, VersionVector};
+use crate::sub_lib::cryptde::PlainData;
+use crate::sub_lib::migrations::utils::value_to_type;
 use crate::sub_lib::versioned_data::{MigrationError, Migrations, StepError, VersionedData};
 use lazy_static::lazy_static;
 use serde_cbor::Value;
+use serde_derive::{Deserialize, Serialize};
 use std::convert::TryFrom;
 
+// What Gossip looked like before it carried version vectors and compressed records
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[allow(non_camel_case_types)]
+pub struct Gossip_0v1 {
+    pub node_records: Vec<GossipNodeRecord>,
+}
+
 lazy_static! {
     static ref MIGRATIONS: Migrations = {
         let current_version = masq_lib::constants::GOSSIP_CURRENT_VERSION;
         let mut migrations = Migrations::new(current_version);
 
-        migrate_value!(dv!(0, 1), Gossip_0v1, GossipMF_0v1, {|value: serde_cbor::Value| {
-            Gossip_0v1::try_from (&value)
+        migrate_value!(dv!(0, 2), Gossip_0v2, GossipMF_0v2, {|value: serde_cbor::Value| {
+            Gossip_0v2::try_from (&value)
+        }});
+        migrations.add_step (masq_lib::data_version::FUTURE_VERSION, dv!(0, 2), Box::new (GossipMF_0v2{}));
+
+        migrate_item!(dv!(0, 1), Gossip_0v1, dv!(0, 2), Gossip_0v2, Gossip0v1To0v2, {|gossip: Gossip_0v1| {
+            Ok(Gossip_0v2::new(gossip.node_records))
         }});
-        migrations.add_step (masq_lib::data_version::FUTURE_VERSION, dv!(0, 1), Box::new (GossipMF_0v1{}));
+        migrations.add_step (dv!(0, 1), dv!(0, 2), Box::new (Gossip0v1To0v2{}));
 
         // add more steps here
 
@@ -22,55 +37,58 @@ lazy_static! {
     };
 }
 
-impl From<Gossip_0v1> for VersionedData<Gossip_0v1> {
-    fn from(data: Gossip_0v1) -> Self {
+impl From<Gossip_0v2> for VersionedData<Gossip_0v2> {
+    fn from(data: Gossip_0v2) -> Self {
         VersionedData::new(&MIGRATIONS, &data)
     }
 }
 
-impl TryFrom<VersionedData<Gossip_0v1>> for Gossip_0v1 {
+impl TryFrom<VersionedData<Gossip_0v2>> for Gossip_0v2 {
     type Error = MigrationError;
 
-    fn try_from(vd: VersionedData<Gossip_0v1>) -> Result<Self, Self::Error> {
+    fn try_from(vd: VersionedData<Gossip_0v2>) -> Result<Self, Self::Error> {
         vd.extract(&MIGRATIONS)
     }
 }
 
-impl TryFrom<&Value> for Gossip_0v1 {
+impl TryFrom<&Value> for Gossip_0v2 {
     type Error = StepError;
 
     fn try_from(value: &Value) -> Result<Self, Self::Error> {
         match value {
             Value::Map(map) => {
-                let init: (Option<StepError>, Vec<GossipNodeRecord>) = (None, vec![]);
-                let (error_opt, node_records) =
-                    map.keys().fold(init, |(error_opt, node_records), key| {
-                        match (key, map.get(key)) {
-                            (Value::Text(field_name), Some(Value::Array(field_value))) => {
-                                if field_name == "node_records" {
-                                    let mut mut_node_records = vec![];
-                                    let mut mut_error_opt = error_opt;
-                                    for value in field_value {
-                                        match (
-                                            mut_error_opt.clone(),
-                                            GossipNodeRecord::try_from(value),
-                                        ) {
-                                            (Some(_), _) => (),
-                                            (_, Err(e)) => mut_error_opt = Some(e),
-                                            (None, Ok(gnr)) => mut_node_records.push(gnr),
-                                        }
-                                    }
-                                    (mut_error_opt, mut_node_records)
-                                } else {
-                                    (error_opt, node_records)
+                let mut error_opt: Option<StepError> = None;
+                let mut node_records: Vec<GossipNodeRecord> = vec![];
+                let mut version_vector_opt: Option<VersionVector> = None;
+                let mut compressed_records_opt: Option<PlainData> = None;
+                map.iter()
+                    .for_each(|(key, field_value)| match (key, field_value) {
+                        (Value::Text(field_name), Value::Array(values))
+                            if field_name == "node_records" =>
+                        {
+                            for value in values {
+                                match (&error_opt, GossipNodeRecord::try_from(value)) {
+                                    (Some(_), _) => (),
+                                    (_, Err(e)) => error_opt = Some(e),
+                                    (None, Ok(gnr)) => node_records.push(gnr),
                                 }
                             }
-                            _ => (error_opt, node_records),
                         }
+                        (Value::Text(field_name), _) if field_name == "version_vector_opt" => {
+                            version_vector_opt = value_to_type::<VersionVector>(field_value)
+                        }
+                        (Value::Text(field_name), _) if field_name == "compressed_records_opt" => {
+                            compressed_records_opt = value_to_type::<PlainData>(field_value)
+                        }
+                        _ => (),
                     });
                 match error_opt {
                     Some(e) => Err(e),
-                    None => Ok(Gossip_0v1 { node_records }),
+                    None => Ok(Gossip_0v2 {
+                        node_records,
+                        version_vector_opt,
+                        compressed_records_opt,
+                    }),
                 }
             }
             _ => unimplemented!(), //Err (StepError::SemanticError("Inscrutable future version".to_string())),
@@ -113,9 +131,74 @@ mod tests {
             serde_cbor::ser::to_vec(&VersionedData::new(&future_migrations, &future_gossip))
                 .unwrap();
         let future_vd =
-            serde_cbor::de::from_slice::<VersionedData<Gossip_0v1>>(&serialized).unwrap();
+            serde_cbor::de::from_slice::<VersionedData<Gossip_0v2>>(&serialized).unwrap();
+
+        let actual_gossip = Gossip_0v2::try_from(future_vd).unwrap();
+
+        assert_eq!(actual_gossip, expected_gossip);
+    }
+
+    #[test]
+    fn can_migrate_from_0v1() {
+        let one_node = make_node_record(1234, true);
+        let another_node = make_node_record(2345, true);
+        let mut db = db_from_node(&one_node);
+        db.add_node(another_node.clone()).unwrap();
+        db.add_arbitrary_full_neighbor(one_node.public_key(), another_node.public_key());
+        let expected_gossip = GossipBuilder::new(&db)
+            .node(one_node.public_key(), true)
+            .node(another_node.public_key(), true)
+            .build();
+        let old_gossip = Gossip_0v1 {
+            node_records: expected_gossip.node_records.clone(),
+        };
+        let old_vd = VersionedData::<Gossip_0v2>::test_new(
+            dv!(0, 1),
+            serde_cbor::ser::to_vec(&old_gossip).unwrap(),
+        );
+
+        let actual_gossip = Gossip_0v2::try_from(old_vd).unwrap();
+
+        assert_eq!(actual_gossip, expected_gossip);
+    }
+
+    #[test]
+    fn future_gossip_keeps_its_version_vector_and_compressed_records() {
+        #[derive(Serialize, Deserialize)]
+        struct ExampleFutureGossip {
+            pub node_records: Vec<GossipNodeRecord>,
+            pub version_vector_opt: Option<VersionVector>,
+            pub compressed_records_opt: Option<PlainData>,
+            pub another_field: String,
+        }
+        let one_node = make_node_record(1234, true);
+        let another_node = make_node_record(2345, true);
+        let mut db = db_from_node(&one_node);
+        db.add_node(another_node.clone()).unwrap();
+        db.add_arbitrary_full_neighbor(one_node.public_key(), another_node.public_key());
+        let version_vector = vec![(another_node.public_key().clone(), 0)]
+            .into_iter()
+            .collect::<VersionVector>();
+        let expected_gossip = GossipBuilder::new(&db)
+            .node(one_node.public_key(), true)
+            .node(another_node.public_key(), true)
+            .build()
+            .with_version_vector(version_vector)
+            .compress();
+        let future_gossip = ExampleFutureGossip {
+            node_records: vec![],
+            version_vector_opt: expected_gossip.version_vector_opt.clone(),
+            compressed_records_opt: expected_gossip.compressed_records_opt.clone(),
+            another_field: "Whatever".to_string(),
+        };
+        let future_migrations = Migrations::new(DataVersion::new(4095, 4095));
+        let serialized =
+            serde_cbor::ser::to_vec(&VersionedData::new(&future_migrations, &future_gossip))
+                .unwrap();
+        let future_vd =
+            serde_cbor::de::from_slice::<VersionedData<Gossip_0v2>>(&serialized).unwrap();
 
-        let actual_gossip = Gossip_0v1::try_from(future_vd).unwrap();
+        let actual_gossip = Gossip_0v2::try_from(future_vd).unwrap();
 
         assert_eq!(actual_gossip, expected_gossip);
     }
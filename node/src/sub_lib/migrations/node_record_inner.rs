@@ -55,6 +55,7 @@ impl TryFrom<&Value> for NodeRecordInner_0v1 {
                 let mut accepts_connections_opt: Option<bool> = None;
                 let mut routes_data_opt: Option<bool> = None;
                 let mut version_opt: Option<u32> = None;
+                let mut accepts_tls = false;
                 map.keys().for_each(|k| {
                     let v = map.get(k).expect("Disappeared");
                     match (k, v) {
@@ -83,6 +84,7 @@ impl TryFrom<&Value> for NodeRecordInner_0v1 {
                                     accepts_connections_opt = Some(*field_value)
                                 }
                                 "routes_data" => routes_data_opt = Some(*field_value),
+                                "accepts_tls" => accepts_tls = *field_value,
                                 _ => (),
                             }
                         }
@@ -131,6 +133,7 @@ impl TryFrom<&Value> for NodeRecordInner_0v1 {
                     accepts_connections: accepts_connections_opt.expect("public_key disappeared"),
                     routes_data: routes_data_opt.expect("public_key disappeared"),
                     version: version_opt.expect("public_key disappeared"),
+                    accepts_tls,
                 })
             }
             _ => Err(StepError::SemanticError(format!(
@@ -173,6 +176,7 @@ mod tests {
             pub accepts_connections: bool,
             pub routes_data: bool,
             pub version: u32,
+            pub accepts_tls: bool,
             pub another_field: String,
             pub yet_another_field: u64,
         }
@@ -186,6 +190,7 @@ mod tests {
             accepts_connections: false,
             routes_data: true,
             version: 42,
+            accepts_tls: true,
         };
         let future_nri = ExampleFutureNRI {
             public_key: expected_nri.public_key.clone(),
@@ -195,6 +200,7 @@ mod tests {
             accepts_connections: expected_nri.accepts_connections,
             routes_data: expected_nri.routes_data,
             version: expected_nri.version,
+            accepts_tls: expected_nri.accepts_tls,
             another_field: "These are the times that try men's souls".to_string(),
             yet_another_field: 1234567890,
         };
@@ -209,6 +215,37 @@ mod tests {
         assert_eq!(actual_nri, expected_nri);
     }
 
+    #[test]
+    fn record_from_a_node_that_predates_clandestine_tls_does_not_accept_tls() {
+        #[derive(Serialize, Deserialize)]
+        struct ExamplePastNRI {
+            pub public_key: PublicKey,
+            pub earning_wallet: Wallet,
+            pub rate_pack: RatePack,
+            pub neighbors: BTreeSet<PublicKey>,
+            pub accepts_connections: bool,
+            pub routes_data: bool,
+            pub version: u32,
+        }
+        let past_nri = ExamplePastNRI {
+            public_key: PublicKey::new(&[1, 2, 3, 4]),
+            earning_wallet: Wallet::new("0x0123456789012345678901234567890123456789"),
+            rate_pack: DEFAULT_RATE_PACK,
+            neighbors: BTreeSet::new(),
+            accepts_connections: true,
+            routes_data: true,
+            version: 42,
+        };
+        let serialized = serde_cbor::ser::to_vec(&past_nri).unwrap();
+        let value = serde_cbor::de::from_slice::<Value>(&serialized).unwrap();
+
+        let from_value = NodeRecordInner_0v1::try_from(&value).unwrap();
+        let deserialized = serde_cbor::de::from_slice::<NodeRecordInner_0v1>(&serialized).unwrap();
+
+        assert_eq!(from_value.accepts_tls, false);
+        assert_eq!(deserialized, from_value);
+    }
+
     #[test]
     fn cannot_migrate_from_value_other_than_map() {
         let value = Value::Bool(true);
@@ -6,10 +6,12 @@ pub mod utils;
 pub mod versioned_data;
 
 pub mod accountant;
+pub mod bandwidth_shaper;
 pub mod bidi_hashmap;
 pub mod binary_traverser;
 pub mod blockchain_bridge;
 pub mod channel_wrappers;
+pub mod clandestine_tls;
 pub mod combined_parameters;
 pub mod configurator;
 pub mod cryptde;
@@ -31,6 +33,7 @@ pub mod node_addr;
 pub mod peer_actors;
 pub mod proxy_client;
 pub mod proxy_server;
+pub mod replay_window;
 pub mod route;
 pub mod sequence_buffer;
 pub mod sequencer;
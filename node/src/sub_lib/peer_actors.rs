@@ -34,12 +34,24 @@ impl Debug for PeerActors {
 }
 
 pub type ConfigChangeSubs = Vec<Recipient<ConfigChangeMsg>>;
+pub type ShutdownSubs = Vec<Recipient<ShutdownMsg>>;
 impl PeerActors {
     pub fn config_change_subs(&self) -> ConfigChangeSubs {
-        vec![
+        let mut subs = vec![
             self.accountant.config_change_msg_sub.clone(),
             self.neighborhood.config_change_msg_sub.clone(),
-        ]
+            self.blockchain_bridge.config_change_msg_sub.clone(),
+            self.proxy_server.config_change_msg_sub.clone(),
+        ];
+        // A consume-only Node has no ProxyClient
+        if let Some(proxy_client) = self.proxy_client_opt.as_ref() {
+            subs.push(proxy_client.config_change_msg_sub.clone())
+        }
+        subs
+    }
+
+    pub fn shutdown_subs(&self) -> ShutdownSubs {
+        vec![self.neighborhood.shutdown_sub.clone()]
     }
 }
 
@@ -58,6 +70,11 @@ pub struct BindMessage {
 #[derive(Debug, Message, Clone, PartialEq, Eq)]
 pub struct StartMessage {}
 
+// Broadcast by the ServerInitializer when the process gets Ctrl-C or SIGTERM. Each recipient
+// preserves whatever it must before answering; the process ends once they all have.
+#[derive(Debug, Message, Clone, PartialEq, Eq)]
+pub struct ShutdownMsg {}
+
 #[derive(Message, Clone, PartialEq, Eq, Debug)]
 pub struct NewPublicIp {
     pub new_ip: IpAddr,
@@ -65,7 +82,9 @@ pub struct NewPublicIp {
 
 #[cfg(test)]
 mod tests {
-    use crate::test_utils::recorder::peer_actors_builder;
+    use crate::sub_lib::neighborhood::{ConfigChange, ConfigChangeMsg, Hops};
+    use crate::sub_lib::peer_actors::ShutdownMsg;
+    use crate::test_utils::recorder::{make_recorder, peer_actors_builder};
     use actix::System;
 
     #[test]
@@ -77,4 +96,76 @@ mod tests {
 
         assert_eq!(result, String::from("PeerActors"))
     }
+
+    #[test]
+    fn config_change_subs_reach_every_actor_that_takes_config_changes() {
+        let system = System::new("config_change_subs_reach_every_actor_that_takes_config_changes");
+        let (accountant, _, accountant_recording_arc) = make_recorder();
+        let (neighborhood, _, neighborhood_recording_arc) = make_recorder();
+        let (blockchain_bridge, _, blockchain_bridge_recording_arc) = make_recorder();
+        let (proxy_server, _, proxy_server_recording_arc) = make_recorder();
+        let (proxy_client, _, proxy_client_recording_arc) = make_recorder();
+        let subject = peer_actors_builder()
+            .accountant(accountant)
+            .neighborhood(neighborhood)
+            .blockchain_bridge(blockchain_bridge)
+            .proxy_server(proxy_server)
+            .proxy_client(proxy_client)
+            .build();
+        let msg = ConfigChangeMsg {
+            change: ConfigChange::UpdateMinHops(Hops::FourHops),
+        };
+
+        let result = subject.config_change_subs();
+
+        assert_eq!(result.len(), 5);
+        result
+            .iter()
+            .for_each(|recipient| recipient.try_send(msg.clone()).unwrap());
+        System::current().stop();
+        system.run();
+        [
+            accountant_recording_arc,
+            neighborhood_recording_arc,
+            blockchain_bridge_recording_arc,
+            proxy_server_recording_arc,
+            proxy_client_recording_arc,
+        ]
+        .iter()
+        .for_each(|recording_arc| {
+            let recording = recording_arc.lock().unwrap();
+            assert_eq!(recording.get_record::<ConfigChangeMsg>(0), &msg);
+            assert_eq!(recording.len(), 1);
+        });
+    }
+
+    #[test]
+    fn config_change_subs_leave_out_a_missing_proxy_client() {
+        let _ = System::new("test");
+        let mut subject = peer_actors_builder().build();
+        subject.proxy_client_opt = None;
+
+        let result = subject.config_change_subs();
+
+        assert_eq!(result.len(), 4);
+    }
+
+    #[test]
+    fn shutdown_subs_reach_the_neighborhood() {
+        let system = System::new("shutdown_subs_reach_the_neighborhood");
+        let (neighborhood, _, neighborhood_recording_arc) = make_recorder();
+        let subject = peer_actors_builder().neighborhood(neighborhood).build();
+
+        let result = subject.shutdown_subs();
+
+        assert_eq!(result.len(), 1);
+        result[0].try_send(ShutdownMsg {}).unwrap();
+        System::current().stop();
+        system.run();
+        let neighborhood_recording = neighborhood_recording_arc.lock().unwrap();
+        assert_eq!(
+            neighborhood_recording.get_record::<ShutdownMsg>(0),
+            &ShutdownMsg {}
+        );
+    }
 }
@@ -1,8 +1,10 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use crate::sub_lib::bandwidth_shaper::BandwidthShaper;
 use crate::sub_lib::cryptde::CryptDE;
 use crate::sub_lib::hopper::{ExpiredCoresPackage, MessageType};
+use crate::sub_lib::neighborhood::ConfigChangeMsg;
 use crate::sub_lib::peer_actors::BindMessage;
-use crate::sub_lib::proxy_server::ClientRequestPayload_0v1;
+use crate::sub_lib::proxy_server::ClientRequestPayload_0v2;
 use crate::sub_lib::sequence_buffer::SequencedPacket;
 use crate::sub_lib::stream_key::StreamKey;
 use crate::sub_lib::versioned_data::VersionedData;
@@ -26,14 +28,18 @@ pub struct ProxyClientConfig {
     pub exit_service_rate: u64,
     pub exit_byte_rate: u64,
     pub is_decentralized: bool,
+    pub bandwidth_shaper: BandwidthShaper,
     pub crashable: bool,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
 #[allow(non_camel_case_types)]
-pub struct ClientResponsePayload_0v1 {
+pub struct ClientResponsePayload_0v2 {
     pub stream_key: StreamKey,
     pub sequenced_packet: SequencedPacket,
+    // How far the requests on this stream have arrived in order: the sequence number of the
+    // first one still missing. None from a sender that doesn't keep count.
+    pub next_expected_sequence_opt: Option<u64>,
 }
 
 #[derive(Message, Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
@@ -48,8 +54,8 @@ impl DnsResolveFailure_0v1 {
     }
 }
 
-impl From<ClientResponsePayload_0v1> for MessageType {
-    fn from(data: ClientResponsePayload_0v1) -> Self {
+impl From<ClientResponsePayload_0v2> for MessageType {
+    fn from(data: ClientResponsePayload_0v2) -> Self {
         MessageType::ClientResponse(VersionedData::new(
             &crate::sub_lib::migrations::client_response_payload::MIGRATIONS,
             &data,
@@ -69,10 +75,11 @@ impl From<DnsResolveFailure_0v1> for MessageType {
 #[derive(Clone, PartialEq, Eq)]
 pub struct ProxyClientSubs {
     pub bind: Recipient<BindMessage>,
-    pub from_hopper: Recipient<ExpiredCoresPackage<ClientRequestPayload_0v1>>,
+    pub from_hopper: Recipient<ExpiredCoresPackage<ClientRequestPayload_0v2>>,
     pub inbound_server_data: Recipient<InboundServerData>,
     pub dns_resolve_failed: Recipient<DnsResolveFailure_0v1>,
     pub node_from_ui: Recipient<NodeFromUiMessage>,
+    pub config_change_msg_sub: Recipient<ConfigChangeMsg>,
 }
 
 impl Debug for ProxyClientSubs {
@@ -81,15 +88,17 @@ impl Debug for ProxyClientSubs {
     }
 }
 
-impl ClientResponsePayload_0v1 {
-    pub fn make_terminating_payload(stream_key: StreamKey) -> ClientResponsePayload_0v1 {
-        ClientResponsePayload_0v1 {
+impl ClientResponsePayload_0v2 {
+    pub fn make_terminating_payload(stream_key: StreamKey) -> ClientResponsePayload_0v2 {
+        ClientResponsePayload_0v2 {
             stream_key,
             sequenced_packet: SequencedPacket {
                 data: vec![],
                 sequence_number: 0,
                 last_data: true,
+                relayed: false,
             },
+            next_expected_sequence_opt: None,
         }
     }
 }
@@ -114,17 +123,19 @@ mod tests {
     fn make_terminating_payload_makes_terminating_payload() {
         let stream_key: StreamKey = StreamKey::make_meaningless_stream_key();
 
-        let payload = ClientResponsePayload_0v1::make_terminating_payload(stream_key);
+        let payload = ClientResponsePayload_0v2::make_terminating_payload(stream_key);
 
         assert_eq!(
             payload,
-            ClientResponsePayload_0v1 {
+            ClientResponsePayload_0v2 {
                 stream_key,
                 sequenced_packet: SequencedPacket {
                     data: vec!(),
                     sequence_number: 0,
-                    last_data: true
+                    last_data: true,
+                    relayed: false,
                 },
+                next_expected_sequence_opt: None,
             }
         )
     }
@@ -135,10 +146,11 @@ mod tests {
 
         let subject = ProxyClientSubs {
             bind: recipient!(recorder, BindMessage),
-            from_hopper: recipient!(recorder, ExpiredCoresPackage<ClientRequestPayload_0v1>),
+            from_hopper: recipient!(recorder, ExpiredCoresPackage<ClientRequestPayload_0v2>),
             inbound_server_data: recipient!(recorder, InboundServerData),
             dns_resolve_failed: recipient!(recorder, DnsResolveFailure_0v1),
             node_from_ui: recipient!(recorder, NodeFromUiMessage),
+            config_change_msg_sub: recipient!(recorder, ConfigChangeMsg),
         };
 
         assert_eq!(format!("{:?}", subject), "ProxyClientSubs");
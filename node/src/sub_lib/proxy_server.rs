@@ -4,9 +4,9 @@ use crate::sub_lib::data_version::DataVersion;
 use crate::sub_lib::dispatcher::InboundClientData;
 use crate::sub_lib::dispatcher::StreamShutdownMsg;
 use crate::sub_lib::hopper::{ExpiredCoresPackage, MessageType};
-use crate::sub_lib::neighborhood::{ExpectedService, RouteQueryResponse};
+use crate::sub_lib::neighborhood::{ConfigChangeMsg, ExpectedService, RouteQueryResponse};
 use crate::sub_lib::peer_actors::BindMessage;
-use crate::sub_lib::proxy_client::{ClientResponsePayload_0v1, DnsResolveFailure_0v1};
+use crate::sub_lib::proxy_client::{ClientResponsePayload_0v2, DnsResolveFailure_0v1};
 use crate::sub_lib::sequence_buffer::SequencedPacket;
 use crate::sub_lib::stream_key::StreamKey;
 use crate::sub_lib::utils::MessageScheduler;
@@ -16,6 +16,7 @@ use actix::Recipient;
 use masq_lib::ui_gateway::NodeFromUiMessage;
 use serde_derive::{Deserialize, Serialize};
 use std::fmt::Debug;
+use std::time::SystemTime;
 
 pub const DEFAULT_MINIMUM_HOP_COUNT: usize = 3;
 
@@ -31,17 +32,20 @@ pub enum ProxyProtocol {
 // expensively-cloned SequencedPacket.
 #[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
 #[allow(non_camel_case_types)]
-pub struct ClientRequestPayload_0v1 {
+pub struct ClientRequestPayload_0v2 {
     pub stream_key: StreamKey,
     pub sequenced_packet: SequencedPacket,
     pub target_hostname: Option<String>,
     pub target_port: u16,
     pub protocol: ProxyProtocol,
     pub originator_public_key: PublicKey,
+    // How far the responses on this stream have arrived in order: the sequence number of the
+    // first one still missing. None from a sender that doesn't keep count.
+    pub next_expected_sequence_opt: Option<u64>,
 }
 
-impl From<ClientRequestPayload_0v1> for MessageType {
-    fn from(payload: ClientRequestPayload_0v1) -> Self {
+impl From<ClientRequestPayload_0v2> for MessageType {
+    fn from(payload: ClientRequestPayload_0v2) -> Self {
         MessageType::ClientRequest(VersionedData::new(
             &crate::sub_lib::migrations::client_request_payload::MIGRATIONS,
             &payload,
@@ -49,7 +53,7 @@ impl From<ClientRequestPayload_0v1> for MessageType {
     }
 }
 
-impl ClientRequestPayload_0v1 {
+impl ClientRequestPayload_0v2 {
     pub fn version() -> DataVersion {
         DataVersion::new(0, 0).expect("Internal Error")
     }
@@ -74,18 +78,35 @@ pub struct StreamKeyPurge {
     pub stream_key: StreamKey,
 }
 
+#[derive(Message, Debug, Default, PartialEq, Eq)]
+pub struct ScanForStalledRoutes {}
+
+#[derive(Message, Debug, PartialEq, Eq)]
+pub struct RouteFailoverResult {
+    pub stream_key: StreamKey,
+    pub route_query_response_opt: Option<RouteQueryResponse>,
+}
+
+// Sent by the Accountant whenever the spending budget runs out or becomes available again
+#[derive(Message, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpendingBudgetStatusMsg {
+    pub exhausted_until_opt: Option<SystemTime>,
+}
+
 #[derive(Clone, PartialEq, Eq)]
 pub struct ProxyServerSubs {
     // ProxyServer will handle these messages:
     pub bind: Recipient<BindMessage>,
     pub from_dispatcher: Recipient<InboundClientData>,
-    pub from_hopper: Recipient<ExpiredCoresPackage<ClientResponsePayload_0v1>>,
+    pub from_hopper: Recipient<ExpiredCoresPackage<ClientResponsePayload_0v2>>,
     pub dns_failure_from_hopper: Recipient<ExpiredCoresPackage<DnsResolveFailure_0v1>>,
     pub add_return_route: Recipient<AddReturnRouteMessage>,
     pub stream_shutdown_sub: Recipient<StreamShutdownMsg>,
     pub node_from_ui: Recipient<NodeFromUiMessage>,
     pub route_result_sub: Recipient<AddRouteResultMessage>,
     pub schedule_stream_key_purge: Recipient<MessageScheduler<StreamKeyPurge>>,
+    pub spending_budget_status: Recipient<SpendingBudgetStatusMsg>,
+    pub config_change_msg_sub: Recipient<ConfigChangeMsg>,
 }
 
 impl Debug for ProxyServerSubs {
@@ -108,7 +129,7 @@ mod tests {
         let subject = ProxyServerSubs {
             bind: recipient!(recorder, BindMessage),
             from_dispatcher: recipient!(recorder, InboundClientData),
-            from_hopper: recipient!(recorder, ExpiredCoresPackage<ClientResponsePayload_0v1>),
+            from_hopper: recipient!(recorder, ExpiredCoresPackage<ClientResponsePayload_0v2>),
             dns_failure_from_hopper: recipient!(
                 recorder,
                 ExpiredCoresPackage<DnsResolveFailure_0v1>
@@ -118,6 +139,8 @@ mod tests {
             node_from_ui: recipient!(recorder, NodeFromUiMessage),
             route_result_sub: recipient!(recorder, AddRouteResultMessage),
             schedule_stream_key_purge: recipient!(recorder, MessageScheduler<StreamKeyPurge>),
+            spending_budget_status: recipient!(recorder, SpendingBudgetStatusMsg),
+            config_change_msg_sub: recipient!(recorder, ConfigChangeMsg),
         };
 
         assert_eq!(format!("{:?}", subject), "ProxyServerSubs");
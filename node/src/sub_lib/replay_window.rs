@@ -0,0 +1,157 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use std::collections::{BTreeSet, VecDeque};
+
+pub const REPLAY_WINDOW_CAPACITY: usize = 64;
+
+// Keeps track of how far the packets arriving on one direction of a stream have got, so that the
+// other end can be told what it no longer needs to keep for replay.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ArrivalCounter {
+    next_expected: u64,
+    early: BTreeSet<u64>,
+}
+
+impl ArrivalCounter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // True the first time a sequence number arrives; false if it's a replay
+    pub fn arrive(&mut self, sequence_number: u64) -> bool {
+        if sequence_number < self.next_expected || !self.early.insert(sequence_number) {
+            return false;
+        }
+        while self.early.remove(&self.next_expected) {
+            self.next_expected += 1;
+        }
+        true
+    }
+
+    pub fn next_expected(&self) -> u64 {
+        self.next_expected
+    }
+}
+
+// Packets sent on one direction of a stream that the other end hasn't acknowledged yet. When more
+// than the capacity pile up, the oldest are dropped, and until the other end acknowledges past
+// them the window can't make good what a failed route lost.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReplayWindow<T> {
+    unacknowledged: VecDeque<(u64, T)>,
+    capacity: usize,
+    acknowledged_below: u64,
+    dropped_below: u64,
+}
+
+impl<T: Clone> ReplayWindow<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            unacknowledged: VecDeque::new(),
+            capacity,
+            acknowledged_below: 0,
+            dropped_below: 0,
+        }
+    }
+
+    pub fn push(&mut self, sequence_number: u64, item: T) {
+        if sequence_number < self.acknowledged_below {
+            return;
+        }
+        self.unacknowledged.push_back((sequence_number, item));
+        while self.unacknowledged.len() > self.capacity {
+            if let Some((dropped, _)) = self.unacknowledged.pop_front() {
+                self.dropped_below = self.dropped_below.max(dropped + 1);
+            }
+        }
+    }
+
+    pub fn acknowledge(&mut self, next_expected: u64) {
+        self.acknowledged_below = self.acknowledged_below.max(next_expected);
+        let acknowledged_below = self.acknowledged_below;
+        self.unacknowledged
+            .retain(|(sequence_number, _)| *sequence_number >= acknowledged_below);
+    }
+
+    pub fn unacknowledged(&self) -> Vec<T> {
+        self.unacknowledged
+            .iter()
+            .map(|(_, item)| item.clone())
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.unacknowledged.is_empty()
+    }
+
+    // Whether everything the other end hasn't acknowledged is still here to be replayed
+    pub fn is_complete(&self) -> bool {
+        self.dropped_below <= self.acknowledged_below
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(REPLAY_WINDOW_CAPACITY, 64);
+    }
+
+    #[test]
+    fn arrival_counter_advances_over_packets_that_arrived_early() {
+        let mut subject = ArrivalCounter::new();
+
+        assert_eq!(subject.arrive(0), true);
+        assert_eq!(subject.arrive(2), true);
+        assert_eq!(subject.next_expected(), 1);
+        assert_eq!(subject.arrive(3), true);
+        assert_eq!(subject.arrive(1), true);
+        assert_eq!(subject.next_expected(), 4);
+    }
+
+    #[test]
+    fn arrival_counter_recognizes_replays() {
+        let mut subject = ArrivalCounter::new();
+        subject.arrive(0);
+        subject.arrive(2);
+
+        assert_eq!(subject.arrive(0), false);
+        assert_eq!(subject.arrive(2), false);
+        assert_eq!(subject.next_expected(), 1);
+    }
+
+    #[test]
+    fn replay_window_forgets_what_is_acknowledged() {
+        let mut subject = ReplayWindow::new(10);
+        subject.push(0, "zero");
+        subject.push(1, "one");
+        subject.push(2, "two");
+
+        subject.acknowledge(2);
+
+        assert_eq!(subject.unacknowledged(), vec!["two"]);
+        assert_eq!(subject.is_complete(), true);
+        subject.acknowledge(1);
+        assert_eq!(subject.unacknowledged(), vec!["two"]);
+        subject.push(1, "one again");
+        assert_eq!(subject.unacknowledged(), vec!["two"]);
+        subject.acknowledge(3);
+        assert_eq!(subject.is_empty(), true);
+    }
+
+    #[test]
+    fn replay_window_is_incomplete_while_dropped_packets_are_unacknowledged() {
+        let mut subject = ReplayWindow::new(2);
+        subject.push(0, "zero");
+        subject.push(1, "one");
+        subject.push(2, "two");
+
+        assert_eq!(subject.unacknowledged(), vec!["one", "two"]);
+        assert_eq!(subject.is_complete(), false);
+        subject.acknowledge(1);
+        assert_eq!(subject.is_complete(), true);
+        assert_eq!(subject.unacknowledged(), vec!["one", "two"]);
+    }
+}
@@ -17,6 +17,8 @@ pub struct SequencedPacket {
     pub data: Vec<u8>,
     pub sequence_number: u64,
     pub last_data: bool,
+    // Stays on this Node: it only tells the clandestine StreamWriter which limits apply
+    pub relayed: bool,
 }
 
 impl Ord for SequencedPacket {
@@ -36,11 +38,14 @@ impl PartialOrd for SequencedPacket {
 
 impl<'a> From<&'a TransmitDataMsg> for SequencedPacket {
     fn from(tdm: &'a TransmitDataMsg) -> Self {
-        SequencedPacket::new(
-            tdm.data.clone(),
-            tdm.sequence_number.unwrap_or(0),
-            tdm.last_data,
-        )
+        SequencedPacket {
+            relayed: tdm.relayed,
+            ..SequencedPacket::new(
+                tdm.data.clone(),
+                tdm.sequence_number.unwrap_or(0),
+                tdm.last_data,
+            )
+        }
     }
 }
 
@@ -72,6 +77,7 @@ impl SequencedPacket {
             data,
             sequence_number,
             last_data,
+            relayed: false,
         }
     }
 }
@@ -254,6 +260,7 @@ mod tests {
             last_data: true,
             data: vec![1, 4, 5, 9],
             sequence_number: None,
+            relayed: false,
         };
 
         let result = SequencedPacket::from(&tdm);
@@ -268,6 +275,7 @@ mod tests {
             last_data: true,
             data: vec![1, 4, 5, 9],
             sequence_number: Some(1),
+            relayed: false,
         };
 
         let result = SequencedPacket::from(&tdm);
@@ -281,6 +289,7 @@ mod tests {
             last_data: false,
             data: vec![4, 2, 5, 67],
             sequence_number: Some(4),
+            relayed: false,
         };
 
         let result = SequencedPacket::from(&tdm);
@@ -290,6 +299,28 @@ mod tests {
         assert_eq!(result.last_data, false);
     }
 
+    #[test]
+    fn sequenced_packet_from_transmit_data_msg_remembers_whether_it_is_relayed() {
+        let tdm = TransmitDataMsg {
+            endpoint: Endpoint::Socket(SocketAddr::from_str("1.2.3.4:80").unwrap()),
+            last_data: false,
+            data: vec![1, 2, 3],
+            sequence_number: None,
+            relayed: true,
+        };
+
+        let result = SequencedPacket::from(&tdm);
+
+        assert_eq!(result.relayed, true);
+        assert_eq!(
+            result,
+            SequencedPacket {
+                relayed: true,
+                ..SequencedPacket::new(vec![1, 2, 3], 0, false)
+            }
+        );
+    }
+
     #[test]
     fn sequence_buffer_reorders_out_of_order_sequenced_packets() {
         let a = SequencedPacket::new(vec![1, 23, 6, 5], 0, false);
@@ -1,9 +1,13 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use crate::sub_lib::clandestine_tls::ClandestineTls;
+use crate::sub_lib::cryptde::PublicKey;
 use crate::sub_lib::tokio_wrappers::ReadHalfWrapper;
 use crate::sub_lib::tokio_wrappers::ReadHalfWrapperReal;
 use crate::sub_lib::tokio_wrappers::WriteHalfWrapper;
 use crate::sub_lib::tokio_wrappers::WriteHalfWrapperReal;
+use futures::future::err;
 use masq_lib::logger::Logger;
+use masq_lib::redaction::MarkSensitive;
 use std::io::ErrorKind;
 use std::net::IpAddr;
 use std::net::SocketAddr;
@@ -15,6 +19,7 @@ use tokio::net::TcpStream;
 use tokio::prelude::Future;
 use tokio::reactor::Handle;
 use tokio::timer::Timeout;
+use tokio_openssl::{ConnectConfigurationExt, SslStream};
 
 pub const CONNECT_TIMEOUT_MS: u64 = 5000;
 pub type ConnectionInfoFuture = Box<dyn Future<Item = ConnectionInfo, Error = io::Error> + Send>;
@@ -28,6 +33,13 @@ pub struct ConnectionInfo {
 
 pub trait StreamConnector: Send {
     fn connect(&self, socket_addr: SocketAddr, logger: &Logger) -> ConnectionInfoFuture;
+    fn connect_tls(
+        &self,
+        socket_addr: SocketAddr,
+        clandestine_tls: &ClandestineTls,
+        expected_key: &PublicKey,
+        logger: &Logger,
+    ) -> ConnectionInfoFuture;
     fn connect_one(
         &self,
         ip_addrs: Vec<IpAddr>,
@@ -60,7 +72,8 @@ impl StreamConnector for StreamConnectorReal {
                             Err(e) => {
                                 error!(
                                     future_logger,
-                                    "Newly-connected stream to {} has no peer_addr", socket_addr
+                                    "Newly-connected stream to {} has no peer_addr",
+                                    socket_addr.sensitive()
                                 );
                                 return Err(e);
                             }
@@ -76,7 +89,8 @@ impl StreamConnector for StreamConnectorReal {
                     Err(e) => {
                         error!(
                             future_logger,
-                            "Could not connect TCP stream to {}", socket_addr
+                            "Could not connect TCP stream to {}",
+                            socket_addr.sensitive()
                         );
                         Err(e)
                     }
@@ -90,6 +104,55 @@ impl StreamConnector for StreamConnectorReal {
         )
     }
 
+    fn connect_tls(
+        &self,
+        socket_addr: SocketAddr,
+        clandestine_tls: &ClandestineTls,
+        expected_key: &PublicKey,
+        logger: &Logger,
+    ) -> ConnectionInfoFuture {
+        let configuration = match clandestine_tls.connect_configuration(expected_key) {
+            Ok(configuration) => configuration,
+            Err(msg) => {
+                error!(logger, "{}", msg);
+                return Box::new(err(io::Error::new(ErrorKind::ConnectionAborted, msg)));
+            }
+        };
+        let future_logger = logger.clone();
+        Box::new(
+            Timeout::new(
+                TcpStream::connect(&socket_addr)
+                    .and_then(move |stream| {
+                        configuration
+                            .connect_async(&socket_addr.ip().to_string(), stream)
+                            .map_err(|e| {
+                                io::Error::new(
+                                    ErrorKind::ConnectionAborted,
+                                    format!("TLS handshake failed: {}", e),
+                                )
+                            })
+                    })
+                    .then(move |result| match result {
+                        Ok(tls_stream) => split_tls_stream(tls_stream),
+                        Err(e) => {
+                            error!(
+                                future_logger,
+                                "Could not connect TLS stream to {}: {}",
+                                socket_addr.sensitive(),
+                                e
+                            );
+                            Err(e)
+                        }
+                    }),
+                Duration::from_millis(CONNECT_TIMEOUT_MS),
+            )
+            .map_err(|wrapped_error| match wrapped_error.into_inner() {
+                Some(error) => error,
+                None => io::Error::from(ErrorKind::TimedOut),
+            }),
+        )
+    }
+
     fn connect_one(
         &self,
         ip_addrs: Vec<IpAddr>,
@@ -105,7 +168,11 @@ impl StreamConnector for StreamConnectorReal {
 
             match StdTcpStream::connect(&socket_addr) {
                 Ok(stream) => {
-                    debug!(logger, "Connected new stream to {}", socket_addr);
+                    debug!(
+                        logger,
+                        "Connected new stream to {}",
+                        socket_addr.sensitive()
+                    );
                     let tokio_stream = TcpStream::from_std(stream, &Handle::default())
                         .expect("Tokio could not create a TcpStream");
                     return Ok(self.split_stream(tokio_stream, logger).unwrap_or_else(|| {
@@ -123,8 +190,8 @@ impl StreamConnector for StreamConnectorReal {
         error!(
             logger,
             "Could not connect to any of the IP addresses supplied for {}: {:?}",
-            target_hostname,
-            socket_addrs_tried
+            target_hostname.sensitive(),
+            socket_addrs_tried.sensitive()
         );
         Err(last_error)
     }
@@ -150,16 +217,30 @@ impl StreamConnector for StreamConnectorReal {
     }
 }
 
+pub fn split_tls_stream(stream: SslStream<TcpStream>) -> Result<ConnectionInfo, io::Error> {
+    let local_addr = stream.get_ref().get_ref().local_addr()?;
+    let peer_addr = stream.get_ref().get_ref().peer_addr()?;
+    let (read_half, write_half) = stream.split();
+    Ok(ConnectionInfo {
+        reader: Box::new(ReadHalfWrapperReal::new(read_half)),
+        writer: Box::new(WriteHalfWrapperReal::new(write_half)),
+        local_addr,
+        peer_addr,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::test_utils::little_tcp_server::LittleTcpServer;
+    use crate::test_utils::{alias_cryptde, main_cryptde};
     use crossbeam_channel::unbounded;
     use futures::future::lazy;
     use futures::future::ok;
     use masq_lib::test_utils::logging::init_test_logging;
     use masq_lib::test_utils::logging::TestLogHandler;
     use masq_lib::utils::{find_free_port, localhost};
+    use std::io::Read;
     use std::net::{IpAddr, Shutdown};
     use std::str::FromStr;
     use std::sync::Arc;
@@ -217,6 +298,69 @@ mod tests {
         });
     }
 
+    fn start_tls_server() -> SocketAddr {
+        let acceptor = ClandestineTls::new(main_cryptde())
+            .unwrap()
+            .acceptor()
+            .unwrap();
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let socket_addr = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            if let Ok(mut tls_stream) = acceptor.accept(stream) {
+                let _ = tls_stream.read(&mut [0u8; 1]);
+            }
+        });
+        socket_addr
+    }
+
+    #[test]
+    fn stream_connector_can_connect_over_tls_to_the_expected_node() {
+        let socket_addr = start_tls_server();
+        let logger = Logger::new("test");
+        let clandestine_tls = ClandestineTls::new(alias_cryptde()).unwrap();
+        let subject = StreamConnectorReal {};
+
+        let future = subject.connect_tls(
+            socket_addr,
+            &clandestine_tls,
+            main_cryptde().public_key(),
+            &logger,
+        );
+
+        FutureAsserter::new(future).assert(move |result| {
+            let connection_info = result.unwrap();
+            assert_eq!(connection_info.local_addr.ip(), localhost());
+            assert_eq!(connection_info.peer_addr, socket_addr);
+            success()
+        });
+    }
+
+    #[test]
+    fn stream_connector_refuses_tls_connection_to_an_unexpected_node() {
+        init_test_logging();
+        let socket_addr = start_tls_server();
+        let logger = Logger::new("tls_imposter");
+        let clandestine_tls = ClandestineTls::new(alias_cryptde()).unwrap();
+        let subject = StreamConnectorReal {};
+
+        let future = subject.connect_tls(
+            socket_addr,
+            &clandestine_tls,
+            alias_cryptde().public_key(),
+            &logger,
+        );
+
+        FutureAsserter::new(future).assert(move |result| {
+            assert_eq!(result.err().unwrap().kind(), ErrorKind::ConnectionAborted);
+            success()
+        });
+        TestLogHandler::new().exists_log_containing(&format!(
+            "ERROR: tls_imposter: Could not connect TLS stream to {}: TLS handshake failed:",
+            socket_addr
+        ));
+    }
+
     #[test]
     fn stream_connector_can_try_connections_until_it_succeeds_then_use_the_successful_one() {
         init_test_logging();
@@ -10,6 +10,7 @@ pub struct TransmitDataMsg {
     pub last_data: bool,
     pub sequence_number: Option<u64>, // Some implies clear data; None implies clandestine.
     pub data: Vec<u8>,
+    pub relayed: bool, // true only for clandestine data passed on for other Nodes
 }
 
 #[derive(Message, Clone, PartialEq, Eq)]
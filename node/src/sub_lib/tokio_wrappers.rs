@@ -30,12 +30,12 @@ pub struct TokioListenerWrapperReal {
     delegate: Option<TcpListener>,
 }
 
-pub struct ReadHalfWrapperReal {
-    delegate: ReadHalf<TcpStream>,
+pub struct ReadHalfWrapperReal<S = TcpStream> {
+    delegate: ReadHalf<S>,
 }
 
-pub struct WriteHalfWrapperReal {
-    delegate: WriteHalf<TcpStream>,
+pub struct WriteHalfWrapperReal<S = TcpStream> {
+    delegate: WriteHalf<S>,
 }
 
 pub struct TokioListenerWrapperFactoryReal {}
@@ -56,27 +56,27 @@ impl TokioListenerWrapper for TokioListenerWrapperReal {
     }
 }
 
-impl Read for ReadHalfWrapperReal {
+impl<S: AsyncRead> Read for ReadHalfWrapperReal<S> {
     fn read(&mut self, buf: &mut [u8]) -> Result<usize, io::Error> {
         self.delegate.read(buf)
     }
 }
 
-impl AsyncRead for ReadHalfWrapperReal {
+impl<S: AsyncRead> AsyncRead for ReadHalfWrapperReal<S> {
     fn poll_read(&mut self, buf: &mut [u8]) -> Result<Async<usize>, io::Error> {
         self.delegate.poll_read(buf)
     }
 }
 
-impl ReadHalfWrapper for ReadHalfWrapperReal {}
+impl<S: AsyncRead + Send> ReadHalfWrapper for ReadHalfWrapperReal<S> {}
 
-impl ReadHalfWrapperReal {
-    pub fn new(reader: ReadHalf<TcpStream>) -> ReadHalfWrapperReal {
+impl<S> ReadHalfWrapperReal<S> {
+    pub fn new(reader: ReadHalf<S>) -> ReadHalfWrapperReal<S> {
         ReadHalfWrapperReal { delegate: reader }
     }
 }
 
-impl Write for WriteHalfWrapperReal {
+impl<S: AsyncWrite> Write for WriteHalfWrapperReal<S> {
     fn write(&mut self, buf: &[u8]) -> Result<usize, io::Error> {
         self.delegate.write(buf)
     }
@@ -86,7 +86,7 @@ impl Write for WriteHalfWrapperReal {
     }
 }
 
-impl AsyncWrite for WriteHalfWrapperReal {
+impl<S: AsyncWrite> AsyncWrite for WriteHalfWrapperReal<S> {
     fn poll_write(&mut self, buf: &[u8]) -> Result<Async<usize>, io::Error> {
         self.delegate.poll_write(buf)
     }
@@ -96,10 +96,10 @@ impl AsyncWrite for WriteHalfWrapperReal {
     }
 }
 
-impl WriteHalfWrapper for WriteHalfWrapperReal {}
+impl<S: AsyncWrite + Send> WriteHalfWrapper for WriteHalfWrapperReal<S> {}
 
-impl WriteHalfWrapperReal {
-    pub fn new(writer: WriteHalf<TcpStream>) -> WriteHalfWrapperReal {
+impl<S> WriteHalfWrapperReal<S> {
+    pub fn new(writer: WriteHalf<S>) -> WriteHalfWrapperReal<S> {
         WriteHalfWrapperReal { delegate: writer }
     }
 }
@@ -3,11 +3,55 @@
 use crate::sub_lib::peer_actors::BindMessage;
 use actix::Recipient;
 use masq_lib::ui_gateway::{NodeFromUiMessage, NodeToUiMessage};
+use std::collections::HashSet;
 use std::fmt::{Debug, Formatter};
+use std::path::PathBuf;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct UiGatewayConfig {
     pub ui_port: u16,
+    pub remote_ui_opt: Option<RemoteUiConfig>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RemoteUiConfig {
+    pub port: u16,
+    pub tls_cert_opt: Option<PathBuf>,
+    pub tls_key_opt: Option<PathBuf>,
+    // Where a self-signed certificate is kept when no certificate is supplied
+    pub data_directory: PathBuf,
+    pub grants: Vec<UiAccessGrant>,
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct UiAccessGrant {
+    pub token: String,
+    pub permissions: UiPermissions,
+}
+
+impl Debug for UiAccessGrant {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        write!(
+            f,
+            "UiAccessGrant {{ token: ******, permissions: {:?} }}",
+            self.permissions
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum UiPermissions {
+    Admin,
+    Opcodes(HashSet<String>),
+}
+
+impl UiPermissions {
+    pub fn allows(&self, opcode: &str) -> bool {
+        match self {
+            UiPermissions::Admin => true,
+            UiPermissions::Opcodes(opcodes) => opcodes.contains(opcode),
+        }
+    }
 }
 
 #[derive(Clone, PartialEq, Eq)]
@@ -31,6 +75,34 @@ mod tests {
     use crate::test_utils::recorder::Recorder;
     use actix::Actor;
 
+    #[test]
+    fn permissions_allow_what_they_should() {
+        let read_only = UiPermissions::Opcodes(
+            vec!["descriptor".to_string(), "financials".to_string()]
+                .into_iter()
+                .collect(),
+        );
+
+        assert_eq!(UiPermissions::Admin.allows("shutdown"), true);
+        assert_eq!(read_only.allows("financials"), true);
+        assert_eq!(read_only.allows("shutdown"), false);
+    }
+
+    #[test]
+    fn access_grant_debug_hides_the_token() {
+        let subject = UiAccessGrant {
+            token: "booga".to_string(),
+            permissions: UiPermissions::Admin,
+        };
+
+        let result = format!("{:?}", subject);
+
+        assert_eq!(
+            result,
+            "UiAccessGrant { token: ******, permissions: Admin }".to_string()
+        );
+    }
+
     #[test]
     fn ui_gateway_subs_debug() {
         let recorder = Recorder::new().start();
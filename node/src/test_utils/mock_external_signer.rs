@@ -0,0 +1,273 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use masq_lib::utils::localhost;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::io::{ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+#[cfg(unix)]
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+// Plays the part of an external signer such as Clef: answers each JSON-RPC request it receives,
+// over HTTP or a Unix socket, with the next scripted response, and records the requests.
+pub struct MockExternalSignerBuilder {
+    responses: Vec<Value>,
+}
+
+impl MockExternalSignerBuilder {
+    pub fn response<R>(mut self, result: R) -> Self
+    where
+        R: Serialize,
+    {
+        self.responses.push(json!({ "result": result }));
+        self
+    }
+
+    pub fn error(mut self, code: i64, message: &str) -> Self {
+        self.responses
+            .push(json!({"error": {"code": code, "message": message}}));
+        self
+    }
+
+    pub fn start_http(self) -> MockExternalSigner {
+        let listener = TcpListener::bind(SocketAddr::new(localhost(), 0))
+            .expect("MockExternalSigner could not bind");
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        listener.set_nonblocking(true).unwrap();
+        let accept = move || {
+            listener
+                .accept()
+                .map(|(conn, _)| Box::new(conn) as Box<dyn SignerConnection>)
+        };
+        MockExternalSigner::start(url, self.responses, Box::new(accept), Framing::Http)
+    }
+
+    #[cfg(unix)]
+    pub fn start_ipc(self, socket_path: &Path) -> MockExternalSigner {
+        let _ = std::fs::remove_file(socket_path);
+        let listener = UnixListener::bind(socket_path).expect("MockExternalSigner could not bind");
+        listener.set_nonblocking(true).unwrap();
+        let accept = move || {
+            listener
+                .accept()
+                .map(|(conn, _)| Box::new(conn) as Box<dyn SignerConnection>)
+        };
+        let mut signer = MockExternalSigner::start(
+            format!("ipc://{}", socket_path.display()),
+            self.responses,
+            Box::new(accept),
+            Framing::Ipc,
+        );
+        signer.socket_path_opt = Some(socket_path.to_path_buf());
+        signer
+    }
+}
+
+pub struct MockExternalSigner {
+    url: String,
+    requests_arc: Arc<Mutex<Vec<Value>>>,
+    stopper: Sender<()>,
+    join_handle_opt: Option<JoinHandle<()>>,
+    #[cfg(unix)]
+    socket_path_opt: Option<PathBuf>,
+}
+
+impl Drop for MockExternalSigner {
+    fn drop(&mut self) {
+        let _ = self.stopper.send(());
+        if let Some(join_handle) = self.join_handle_opt.take() {
+            if join_handle.join().is_err() && !thread::panicking() {
+                panic!("MockExternalSigner service thread panicked")
+            }
+        }
+        #[cfg(unix)]
+        if let Some(socket_path) = &self.socket_path_opt {
+            let _ = std::fs::remove_file(socket_path);
+        }
+    }
+}
+
+impl MockExternalSigner {
+    pub fn builder() -> MockExternalSignerBuilder {
+        MockExternalSignerBuilder { responses: vec![] }
+    }
+
+    // Suitable for --external-signer
+    pub fn url(&self) -> String {
+        self.url.clone()
+    }
+
+    pub fn requests(&self) -> Vec<Value> {
+        self.requests_arc.lock().unwrap().drain(..).collect()
+    }
+
+    fn start(
+        url: String,
+        responses: Vec<Value>,
+        mut accept: Box<dyn FnMut() -> std::io::Result<Box<dyn SignerConnection>> + Send>,
+        framing: Framing,
+    ) -> Self {
+        let requests_arc = Arc::new(Mutex::new(vec![]));
+        let (stopper, stopper_rx) = unbounded();
+        let mut server = ServerState {
+            framing,
+            requests_arc: requests_arc.clone(),
+            responses,
+            stopper_rx,
+        };
+        let join_handle = thread::spawn(move || loop {
+            if server.stopper_rx.try_recv().is_ok() {
+                return;
+            }
+            match accept() {
+                Ok(conn) => {
+                    if server.serve(conn) {
+                        return;
+                    }
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                    thread::sleep(Duration::from_millis(10))
+                }
+                Err(e) => panic!("MockExternalSigner accept() failed: {:?}", e),
+            }
+        });
+        Self {
+            url,
+            requests_arc,
+            stopper,
+            join_handle_opt: Some(join_handle),
+            #[cfg(unix)]
+            socket_path_opt: None,
+        }
+    }
+}
+
+trait SignerConnection: Read + Write + Send {
+    fn prepare(&self);
+}
+
+impl SignerConnection for TcpStream {
+    fn prepare(&self) {
+        self.set_nonblocking(false).unwrap();
+        self.set_read_timeout(Some(Duration::from_millis(50)))
+            .unwrap();
+    }
+}
+
+#[cfg(unix)]
+impl SignerConnection for UnixStream {
+    fn prepare(&self) {
+        self.set_nonblocking(false).unwrap();
+        self.set_read_timeout(Some(Duration::from_millis(50)))
+            .unwrap();
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Framing {
+    Http,
+    Ipc,
+}
+
+impl Framing {
+    fn extract_request(self, buffer: &mut Vec<u8>) -> Option<Vec<u8>> {
+        match self {
+            Framing::Http => {
+                let header_end = buffer.windows(4).position(|w| w == b"\r\n\r\n")? + 4;
+                let headers = String::from_utf8_lossy(&buffer[..header_end]).to_string();
+                let content_length = headers
+                    .lines()
+                    .filter_map(|line| line.split_once(':'))
+                    .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+                    .map(|(_, value)| value.trim().parse::<usize>().unwrap())
+                    .unwrap_or(0);
+                if buffer.len() < header_end + content_length {
+                    return None;
+                }
+                let request: Vec<u8> = buffer.drain(..header_end + content_length).collect();
+                Some(request[header_end..].to_vec())
+            }
+            Framing::Ipc => {
+                let mut stream = serde_json::Deserializer::from_slice(buffer).into_iter::<Value>();
+                match stream.next() {
+                    Some(Ok(_)) => {
+                        let end = stream.byte_offset();
+                        Some(buffer.drain(..end).collect())
+                    }
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    fn frame_response(self, response: &Value) -> Vec<u8> {
+        let body = response.to_string();
+        match self {
+            Framing::Http => format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .into_bytes(),
+            Framing::Ipc => format!("{}\n", body).into_bytes(),
+        }
+    }
+}
+
+struct ServerState {
+    framing: Framing,
+    requests_arc: Arc<Mutex<Vec<Value>>>,
+    responses: Vec<Value>,
+    stopper_rx: Receiver<()>,
+}
+
+impl ServerState {
+    // Returns true if the server has been told to stop
+    fn serve(&mut self, mut conn: Box<dyn SignerConnection>) -> bool {
+        conn.prepare();
+        let mut buffer = vec![];
+        loop {
+            while let Some(body) = self.framing.extract_request(&mut buffer) {
+                let request: Value = serde_json::from_slice(&body)
+                    .unwrap_or_else(|e| panic!("MockExternalSigner got bad JSON: {:?}", e));
+                let response = self.make_response(&request);
+                self.requests_arc.lock().unwrap().push(request);
+                if conn
+                    .write_all(&self.framing.frame_response(&response))
+                    .is_err()
+                {
+                    return false;
+                }
+            }
+            let mut chunk = [0u8; 4096];
+            match conn.read(&mut chunk) {
+                Ok(0) => return false,
+                Ok(len) => buffer.extend_from_slice(&chunk[..len]),
+                Err(e) if e.kind() == ErrorKind::WouldBlock || e.kind() == ErrorKind::TimedOut => {
+                    if self.stopper_rx.try_recv().is_ok() {
+                        return true;
+                    }
+                }
+                Err(_) => return false,
+            }
+        }
+    }
+
+    fn make_response(&mut self, request: &Value) -> Value {
+        let mut response = if self.responses.is_empty() {
+            json!({"error": {"code": -32603, "message": "MockExternalSigner has no more responses"}})
+        } else {
+            self.responses.remove(0)
+        };
+        response["jsonrpc"] = json!("2.0");
+        response["id"] = request["id"].clone();
+        response
+    }
+}
@@ -10,7 +10,10 @@ pub mod database_utils;
 pub mod http_test_server;
 pub mod little_tcp_server;
 pub mod logfile_name_guard;
+pub mod mock_external_signer;
 pub mod neighborhood_test_utils;
+#[cfg(test)]
+pub mod network_simulator;
 pub mod persistent_configuration_mock;
 pub mod recorder;
 pub mod recorder_stop_conditions;
@@ -31,8 +34,8 @@ use crate::sub_lib::hopper::MessageType;
 use crate::sub_lib::neighborhood::ExpectedServices;
 use crate::sub_lib::neighborhood::RouteQueryResponse;
 use crate::sub_lib::neighborhood::{ExpectedService, RatePack};
-use crate::sub_lib::proxy_client::{ClientResponsePayload_0v1, DnsResolveFailure_0v1};
-use crate::sub_lib::proxy_server::{ClientRequestPayload_0v1, ProxyProtocol};
+use crate::sub_lib::proxy_client::{ClientResponsePayload_0v2, DnsResolveFailure_0v1};
+use crate::sub_lib::proxy_server::{ClientRequestPayload_0v2, ProxyProtocol};
 use crate::sub_lib::route::Route;
 use crate::sub_lib::route::RouteSegment;
 use crate::sub_lib::sequence_buffer::SequencedPacket;
@@ -277,25 +280,28 @@ pub fn make_garbage_data(bytes: usize) -> Vec<u8> {
     data
 }
 
-pub fn make_request_payload(bytes: usize, cryptde: &dyn CryptDE) -> ClientRequestPayload_0v1 {
-    ClientRequestPayload_0v1 {
+pub fn make_request_payload(bytes: usize, cryptde: &dyn CryptDE) -> ClientRequestPayload_0v2 {
+    ClientRequestPayload_0v2 {
         stream_key: StreamKey::make_meaningful_stream_key("request"),
         sequenced_packet: SequencedPacket::new(make_garbage_data(bytes), 0, true),
         target_hostname: Some("example.com".to_string()),
         target_port: HTTP_PORT,
         protocol: ProxyProtocol::HTTP,
         originator_public_key: cryptde.public_key().clone(),
+        next_expected_sequence_opt: None,
     }
 }
 
-pub fn make_response_payload(bytes: usize) -> ClientResponsePayload_0v1 {
-    ClientResponsePayload_0v1 {
+pub fn make_response_payload(bytes: usize) -> ClientResponsePayload_0v2 {
+    ClientResponsePayload_0v2 {
         stream_key: StreamKey::make_meaningful_stream_key("response"),
         sequenced_packet: SequencedPacket {
             data: make_garbage_data(bytes),
             sequence_number: 0,
             last_data: false,
+            relayed: false,
         },
+        next_expected_sequence_opt: None,
     }
 }
 
@@ -306,6 +312,7 @@ pub fn make_send_error() -> SendError<SequencedPacket> {
         data: vec![],
         sequence_number: 0,
         last_data: false,
+        relayed: false,
     })
     .unwrap_err()
 }
@@ -549,7 +556,8 @@ pub mod unshared_test_utils {
     use crate::db_config::config_dao_null::ConfigDaoNull;
     use crate::db_config::persistent_configuration::PersistentConfigurationReal;
     use crate::node_test_utils::DirsWrapperMock;
-    use crate::sub_lib::accountant::{PaymentThresholds, ScanIntervals};
+    use crate::sub_lib::accountant::{PaymentThresholds, ScanIntervals, SpendingBudget};
+    use crate::sub_lib::bandwidth_shaper::{DEFAULT_BANDWIDTH_LIMITS, DEFAULT_DATA_QUOTA};
     use crate::sub_lib::neighborhood::{ConnectionProgressMessage, DEFAULT_RATE_PACK};
     use crate::sub_lib::utils::{
         NLSpawnHandleHolder, NLSpawnHandleHolderReal, NotifyHandle, NotifyLaterHandle,
@@ -683,6 +691,8 @@ pub mod unshared_test_utils {
             .gas_price_result(Ok(1))
             .blockchain_service_url_result(Ok(None))
             .min_hops_result(Ok(MIN_HOPS_FOR_TEST))
+            .bandwidth_limits_result(Ok(DEFAULT_BANDWIDTH_LIMITS))
+            .data_quota_result(Ok(DEFAULT_DATA_QUOTA))
     }
 
     pub fn default_persistent_config_just_accountant_config(
@@ -691,6 +701,7 @@ pub mod unshared_test_utils {
         persistent_config_mock
             .payment_thresholds_result(Ok(PaymentThresholds::default()))
             .scan_intervals_result(Ok(ScanIntervals::default()))
+            .spending_budget_result(Ok(SpendingBudget::default()))
     }
 
     pub fn make_persistent_config_real_with_config_dao_null() -> PersistentConfigurationReal {
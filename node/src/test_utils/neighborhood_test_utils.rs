@@ -1,6 +1,6 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 use crate::bootstrapper::BootstrapperConfig;
-use crate::neighborhood::gossip::{GossipBuilder, GossipNodeRecord, Gossip_0v1};
+use crate::neighborhood::gossip::{GossipBuilder, GossipNodeRecord, Gossip_0v2};
 use crate::neighborhood::neighborhood_database::NeighborhoodDatabase;
 use crate::neighborhood::node_record::{NodeRecord, NodeRecordInner_0v1};
 use crate::neighborhood::{AccessibleGossipRecord, Neighborhood, DEFAULT_MIN_HOPS};
@@ -321,7 +321,7 @@ pub fn linearly_connect_nodes(nodes: &[NodeRecord]) -> NeighborhoodDatabase {
 pub fn gossip_about_nodes_from_database(
     database: &NeighborhoodDatabase,
     nodes: &[NodeRecord],
-) -> Gossip_0v1 {
+) -> Gossip_0v2 {
     nodes
         .iter()
         .fold(GossipBuilder::new(database), |builder, node| {
@@ -0,0 +1,1247 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+// Runs a network of Nodes in one process, without Docker. Each Node gets the actor set a real
+// Node boots, made by the real ActorFactory: Neighborhood, Hopper, ProxyServer, ProxyClient and
+// Accountant, along with a Configurator that has no command line to reload. Only two stand-ins
+// remain. A simulated wire takes the place of the Dispatcher and the StreamHandlerPool, carrying
+// the clandestine data each Hopper transmits, after a configurable latency and with a configurable
+// chance of loss, to the Hopper of the Node it's addressed to. A mock BlockchainBridge answers the
+// Accountant's scans from an empty blockchain.
+
+use crate::accountant::db_access_objects::banned_dao::BannedCacheLoaderReal;
+use crate::accountant::db_access_objects::payable_dao::{
+    PayableAccount, PayableDao, PayableDaoReal,
+};
+use crate::accountant::db_access_objects::receivable_dao::{
+    ReceivableAccount, ReceivableDao, ReceivableDaoReal,
+};
+use crate::accountant::db_access_objects::utils::CustomQuery;
+use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::msgs::QualifiedPayablesMessage;
+use crate::accountant::{ReceivedPayments, RequestFeeBumps, RequestTransactionReceipts};
+use crate::actor_system_factory::{ActorFactory, ActorFactoryReal};
+use crate::blockchain::blockchain_bridge::RetrieveTransactions;
+use crate::bootstrapper::{BootstrapperConfig, CryptDEPair};
+use crate::database::db_initializer::{
+    DbInitializationConfig, DbInitializer, DbInitializerReal, ExternalData,
+};
+use crate::database::rusqlite_wrappers::ConnectionWrapper;
+use crate::db_config::persistent_configuration::{
+    PersistentConfiguration, PersistentConfigurationReal,
+};
+use crate::neighborhood::node_record::NodeRecord;
+use crate::node_configurator::configurator::Configurator;
+use crate::sub_lib::accountant::{AccountantSubsFactoryReal, PaymentThresholds, ScanIntervals};
+use crate::sub_lib::blockchain_bridge::{BlockchainBridgeSubs, OutboundPaymentsInstructions};
+use crate::sub_lib::configurator::ConfiguratorSubs;
+use crate::sub_lib::cryptde::{CryptDE, PublicKey};
+use crate::sub_lib::cryptde_null::CryptDENull;
+use crate::sub_lib::dispatcher::{DispatcherSubs, Endpoint, InboundClientData, StreamShutdownMsg};
+use crate::sub_lib::hopper::HopperConfig;
+use crate::sub_lib::neighborhood::{
+    ConfigChangeMsg, ConnectionProgressEvent, ConnectionProgressMessage,
+    DispatcherNodeQueryMessage, Hops, NeighborhoodConfig, NeighborhoodMode, NeighborhoodSubs,
+    NodeDescriptor, NodeQueryMessage, NodeQueryResponseMetadata, RouteQueryMessage,
+    RouteQueryResponse, DEFAULT_RATE_PACK,
+};
+use crate::sub_lib::peer_actors::{BindMessage, NewPublicIp, StartMessage};
+use crate::sub_lib::proxy_client::ProxyClientConfig;
+use crate::sub_lib::stream_handler_pool::{DispatcherNodeQueryResponse, TransmitDataMsg};
+use crate::sub_lib::wallet::Wallet;
+use crate::test_utils::make_paying_wallet;
+use crate::test_utils::neighborhood_test_utils::{make_node_record, MIN_HOPS_FOR_TEST};
+use crate::test_utils::recorder::peer_actors_builder;
+use actix::{Actor, Addr, AsyncContext, Context, Handler, Recipient, System};
+use futures::Future;
+use masq_lib::messages::{ToMessageBody, UiSetConfigurationRequest};
+use masq_lib::test_utils::utils::{ensure_node_home_directory_exists, TEST_DEFAULT_CHAIN};
+use masq_lib::ui_gateway::NodeFromUiMessage;
+use masq_lib::utils::{localhost, NeighborhoodModeLight};
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+use std::collections::{HashMap, HashSet};
+use std::net::{IpAddr, SocketAddr};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime};
+
+const FIRST_NODE_NUMBER: u16 = 1001;
+const FIRST_BROWSER_PORT: u16 = 40001;
+const POLL_INTERVAL_MILLIS: u64 = 20;
+// A response is taken to be complete when no more of it arrives for this long
+const RESPONSE_QUIET_MILLIS: u64 = 200;
+// How long a starting Node gets to hear back from the Nodes it debuts to, over and above the
+// time its Gossip spends on the wire
+const DEBUT_TIMEOUT_MILLIS: u64 = 1000;
+// The mock blockchain never grows
+const MOCK_BLOCKCHAIN_HEIGHT: u64 = 1000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LinkConditions {
+    pub latency: Duration,
+    // Out of every thousand packages, how many never arrive
+    pub loss_per_mille: u16,
+    // Loss is pseudorandom, so that a scenario with the same seed loses the same packages
+    pub seed: u64,
+}
+
+impl Default for LinkConditions {
+    fn default() -> Self {
+        Self {
+            latency: Duration::from_millis(0),
+            loss_per_mille: 0,
+            seed: 0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WireStats {
+    pub delivered: usize,
+    pub lost: usize,
+    pub undeliverable: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct NodeSpec {
+    name: String,
+    mode: NeighborhoodModeLight,
+    neighbor_names: Vec<String>,
+    consumes: bool,
+}
+
+// The scenario: which Nodes there are, who each one is introduced to, and what the wire is like.
+// Nodes start in the order they're added, so a Node should be added after the ones it names.
+pub struct NetworkSimulatorBuilder {
+    test_name: String,
+    link: LinkConditions,
+    min_hops: Hops,
+    nodes: Vec<NodeSpec>,
+}
+
+impl NetworkSimulatorBuilder {
+    pub fn new(test_name: &str) -> Self {
+        Self {
+            test_name: test_name.to_string(),
+            link: LinkConditions::default(),
+            min_hops: MIN_HOPS_FOR_TEST,
+            nodes: vec![],
+        }
+    }
+
+    pub fn link(mut self, link: LinkConditions) -> Self {
+        self.link = link;
+        self
+    }
+
+    pub fn min_hops(mut self, min_hops: Hops) -> Self {
+        self.min_hops = min_hops;
+        self
+    }
+
+    pub fn standard_node(self, name: &str, neighbor_names: &[&str]) -> Self {
+        self.node(name, NeighborhoodModeLight::Standard, neighbor_names, true)
+    }
+
+    // Like a standard Node, but with no consuming wallet: it earns and never pays
+    pub fn serving_node(self, name: &str, neighbor_names: &[&str]) -> Self {
+        self.node(name, NeighborhoodModeLight::Standard, neighbor_names, false)
+    }
+
+    pub fn originate_only_node(self, name: &str, neighbor_names: &[&str]) -> Self {
+        self.node(
+            name,
+            NeighborhoodModeLight::OriginateOnly,
+            neighbor_names,
+            true,
+        )
+    }
+
+    pub fn consume_only_node(self, name: &str, neighbor_names: &[&str]) -> Self {
+        self.node(
+            name,
+            NeighborhoodModeLight::ConsumeOnly,
+            neighbor_names,
+            true,
+        )
+    }
+
+    // Each Node debuts to the one added just before it
+    pub fn chain_of_standard_nodes(self, names: &[&str]) -> Self {
+        names
+            .iter()
+            .enumerate()
+            .fold(self, |builder, (index, name)| {
+                let neighbor_names = if index == 0 {
+                    vec![]
+                } else {
+                    vec![names[index - 1]]
+                };
+                builder.standard_node(name, &neighbor_names)
+            })
+    }
+
+    fn node(
+        mut self,
+        name: &str,
+        mode: NeighborhoodModeLight,
+        neighbor_names: &[&str],
+        consumes: bool,
+    ) -> Self {
+        if self.nodes.iter().any(|spec| spec.name == name) {
+            panic!("Scenario already has a Node named '{}'", name)
+        }
+        if let Some(unknown) = neighbor_names
+            .iter()
+            .find(|neighbor| !self.nodes.iter().any(|spec| &spec.name == *neighbor))
+        {
+            panic!(
+                "Node '{}' is introduced to '{}', which hasn't been added yet",
+                name, unknown
+            )
+        }
+        self.nodes.push(NodeSpec {
+            name: name.to_string(),
+            mode,
+            neighbor_names: neighbor_names.iter().map(|n| n.to_string()).collect(),
+            consumes,
+        });
+        self
+    }
+
+    pub fn start(self) -> NetworkSimulator {
+        let specs = self.nodes.clone();
+        let latency = self.link.latency;
+        let (tx, rx) = channel();
+        let join_handle = thread::spawn(move || {
+            let system = System::new("network_simulator");
+            let started = self.start_nodes();
+            tx.send((System::current(), started))
+                .expect("Simulator is gone");
+            system.run();
+        });
+        let (system, (nodes, probe, browsers, stats)) = rx.recv().expect("Simulator didn't start");
+        let simulator = NetworkSimulator {
+            nodes,
+            probe: Mutex::new(probe),
+            browsers,
+            next_browser_port: AtomicU16::new(FIRST_BROWSER_PORT),
+            stats,
+            system,
+            join_handle_opt: Some(join_handle),
+        };
+        simulator.debut_in_order(&specs, latency);
+        simulator
+    }
+
+    fn start_nodes(
+        &self,
+    ) -> (
+        HashMap<String, SimulatedNode>,
+        NodeQueryProbe,
+        Browsers,
+        Arc<Mutex<WireStats>>,
+    ) {
+        let switchboard: Arc<Mutex<HashMap<IpAddr, WireEndpoint>>> =
+            Arc::new(Mutex::new(HashMap::new()));
+        let browsers: Browsers = Arc::new(Mutex::new(HashMap::new()));
+        let stats = Arc::new(Mutex::new(WireStats::default()));
+        let actor_factory = ActorFactoryReal {};
+        let mut nodes: HashMap<String, SimulatedNode> = HashMap::new();
+        self.nodes.iter().enumerate().for_each(|(index, spec)| {
+            let record = make_node_record(FIRST_NODE_NUMBER + index as u16, true);
+            let node_addr = record.node_addr_opt().expect("Node record has no NodeAddr");
+            let cryptdes = CryptDEPair {
+                main: Box::leak(Box::new(CryptDENull::from(
+                    record.public_key(),
+                    TEST_DEFAULT_CHAIN,
+                ))),
+                alias: Box::leak(Box::new(CryptDENull::new(TEST_DEFAULT_CHAIN))),
+            };
+            let neighbors = spec
+                .neighbor_names
+                .iter()
+                .map(|neighbor_name| {
+                    let neighbor = &nodes[neighbor_name];
+                    NodeDescriptor::from((&neighbor.record, TEST_DEFAULT_CHAIN, neighbor.cryptde))
+                })
+                .collect::<Vec<NodeDescriptor>>();
+            // Real Nodes charge the default rates unless told otherwise; the test records'
+            // rates are too small to show up in the Accountant's gwei-resolution queries
+            let mode = match spec.mode {
+                NeighborhoodModeLight::Standard => {
+                    NeighborhoodMode::Standard(node_addr.clone(), neighbors, DEFAULT_RATE_PACK)
+                }
+                NeighborhoodModeLight::OriginateOnly => {
+                    NeighborhoodMode::OriginateOnly(neighbors, DEFAULT_RATE_PACK)
+                }
+                NeighborhoodModeLight::ConsumeOnly => NeighborhoodMode::ConsumeOnly(neighbors),
+                NeighborhoodModeLight::ZeroHop => {
+                    unreachable!("Scenarios have no zero-hop Nodes")
+                }
+            };
+            let config = self.make_config(spec, mode, &record);
+            let rate_pack = config.neighborhood_config.mode.rate_pack();
+            let neighborhood = actor_factory.make_and_start_neighborhood(cryptdes.main, &config);
+            let source = SocketAddr::new(node_addr.ip_addr(), node_addr.ports()[0]);
+            let wire = SimulatedWire {
+                source,
+                connection_progress_sub: neighborhood.connection_progress_sub.clone(),
+                connected_ips: HashSet::new(),
+                switchboard: switchboard.clone(),
+                browsers: browsers.clone(),
+                latency: self.link.latency,
+                loss_per_mille: self.link.loss_per_mille,
+                rng: SmallRng::seed_from_u64(self.link.seed.wrapping_add(index as u64)),
+                stats: stats.clone(),
+            }
+            .start();
+            let blockchain_queries = Arc::new(Mutex::new(vec![]));
+            let blockchain_bridge = BlockchainBridgeMock {
+                queries: blockchain_queries.clone(),
+                accountant_opt: None,
+            }
+            .start();
+            let mut peer_actors = peer_actors_builder().build();
+            peer_actors.dispatcher = SimulatedWire::make_subs_from(&wire);
+            peer_actors.proxy_server = actor_factory.make_and_start_proxy_server(cryptdes, &config);
+            peer_actors.proxy_client_opt = if config.neighborhood_config.mode.is_consume_only() {
+                None
+            } else {
+                Some(
+                    actor_factory.make_and_start_proxy_client(ProxyClientConfig {
+                        cryptde: cryptdes.main,
+                        dns_servers: config.dns_servers.clone(),
+                        exit_service_rate: rate_pack.exit_service_rate,
+                        exit_byte_rate: rate_pack.exit_byte_rate,
+                        is_decentralized: true,
+                        bandwidth_shaper: config.bandwidth_shaper.clone(),
+                        crashable: false,
+                    }),
+                )
+            };
+            peer_actors.hopper = actor_factory.make_and_start_hopper(HopperConfig {
+                cryptdes,
+                per_routing_service: rate_pack.routing_service_rate,
+                per_routing_byte: rate_pack.routing_byte_rate,
+                is_decentralized: true,
+                crashable: false,
+            });
+            peer_actors.neighborhood = neighborhood.clone();
+            peer_actors.accountant = actor_factory.make_and_start_accountant(
+                config.clone(),
+                &DbInitializerReal::default(),
+                &BannedCacheLoaderReal {},
+                &AccountantSubsFactoryReal {},
+            );
+            peer_actors.blockchain_bridge =
+                BlockchainBridgeMock::make_subs_from(&blockchain_bridge);
+            // Not from the ActorFactory: a simulated Node has no command line to reload
+            let configurator = Configurator::new(
+                config.data_directory.clone(),
+                config.bandwidth_shaper.clone(),
+                false,
+            )
+            .start();
+            peer_actors.configurator = ConfiguratorSubs {
+                bind: recipient!(configurator, BindMessage),
+                node_from_ui_sub: recipient!(configurator, NodeFromUiMessage),
+            };
+            send_bind_message!(peer_actors.dispatcher, peer_actors);
+            send_bind_message!(peer_actors.proxy_server, peer_actors);
+            send_bind_message!(peer_actors.hopper, peer_actors);
+            send_bind_message!(peer_actors.neighborhood, peer_actors);
+            send_bind_message!(peer_actors.accountant, peer_actors);
+            send_bind_message!(peer_actors.blockchain_bridge, peer_actors);
+            send_bind_message!(peer_actors.configurator, peer_actors);
+            if let Some(subs) = peer_actors.proxy_client_opt.as_ref() {
+                send_bind_message!(subs, peer_actors);
+            }
+            switchboard.lock().expect("Switchboard is poisoned").insert(
+                node_addr.ip_addr(),
+                WireEndpoint {
+                    public_key: record.public_key().clone(),
+                    socket_addr: source,
+                    hopper_sub: peer_actors.hopper.from_dispatcher.clone(),
+                },
+            );
+            nodes.insert(
+                spec.name.clone(),
+                SimulatedNode {
+                    record_key: record.public_key().clone(),
+                    record,
+                    cryptde: cryptdes.main,
+                    neighborhood,
+                    browser_sub: peer_actors.proxy_server.from_dispatcher.clone(),
+                    ui_sub: peer_actors.configurator.node_from_ui_sub.clone(),
+                    earning_wallet: config.earning_wallet.clone(),
+                    data_directory: config.data_directory.clone(),
+                    blockchain_queries,
+                },
+            );
+        });
+        let (response_tx, response_rx) = channel();
+        let probe_actor = NodeQueryProbeActor {
+            responses: response_tx,
+        }
+        .start();
+        let probe = NodeQueryProbe {
+            recipient: probe_actor.recipient(),
+            responses: response_rx,
+        };
+        (nodes, probe, browsers, stats)
+    }
+
+    fn make_config(
+        &self,
+        spec: &NodeSpec,
+        mode: NeighborhoodMode,
+        record: &NodeRecord,
+    ) -> BootstrapperConfig {
+        let data_directory = ensure_node_home_directory_exists(
+            "network_simulator",
+            &format!("{}/{}", self.test_name, spec.name),
+        );
+        let conn = DbInitializerReal::default()
+            .initialize(
+                &data_directory,
+                DbInitializationConfig::create_or_migrate(ExternalData::new(
+                    TEST_DEFAULT_CHAIN,
+                    spec.mode,
+                    None,
+                )),
+            )
+            .expect("Can't create simulated Node's database");
+        PersistentConfigurationReal::from(conn)
+            .set_min_hops(self.min_hops)
+            .expect("Can't set simulated Node's min hops");
+        let mut config = BootstrapperConfig::new();
+        config.neighborhood_config = NeighborhoodConfig {
+            mode,
+            min_hops: self.min_hops,
+        };
+        config.blockchain_bridge_config.chain = TEST_DEFAULT_CHAIN;
+        config.data_directory = data_directory;
+        config.earning_wallet = record.earning_wallet();
+        config.consuming_wallet_opt = if spec.consumes {
+            Some(make_paying_wallet(
+                format!("consuming {}", spec.name).as_bytes(),
+            ))
+        } else {
+            None
+        };
+        // Servers are addressed by IP, so the ProxyClients never actually ask this for anything
+        config.dns_servers = vec![SocketAddr::new(localhost(), 53)];
+        config.payment_thresholds_opt = Some(PaymentThresholds::default());
+        config.scan_intervals_opt = Some(ScanIntervals::default());
+        config
+    }
+}
+
+type Browsers = Arc<Mutex<HashMap<SocketAddr, Sender<Vec<u8>>>>>;
+
+struct SimulatedNode {
+    record_key: PublicKey,
+    record: NodeRecord,
+    cryptde: &'static dyn CryptDE,
+    neighborhood: NeighborhoodSubs,
+    browser_sub: Recipient<InboundClientData>,
+    ui_sub: Recipient<NodeFromUiMessage>,
+    earning_wallet: Wallet,
+    data_directory: PathBuf,
+    blockchain_queries: Arc<Mutex<Vec<Wallet>>>,
+}
+
+pub struct NetworkSimulator {
+    nodes: HashMap<String, SimulatedNode>,
+    probe: Mutex<NodeQueryProbe>,
+    browsers: Browsers,
+    next_browser_port: AtomicU16,
+    stats: Arc<Mutex<WireStats>>,
+    system: System,
+    join_handle_opt: Option<JoinHandle<()>>,
+}
+
+impl NetworkSimulator {
+    pub fn public_key(&self, name: &str) -> &PublicKey {
+        &self.node(name).record_key
+    }
+
+    pub fn earning_wallet(&self, name: &str) -> &Wallet {
+        &self.node(name).earning_wallet
+    }
+
+    pub fn stats(&self) -> WireStats {
+        *self.stats.lock().expect("Stats are poisoned")
+    }
+
+    // What the named Node's database says about the other one, if anything
+    pub fn node_query(&self, name: &str, about: &str) -> Option<NodeQueryResponseMetadata> {
+        let key = self.public_key(about).clone();
+        let probe = self.probe.lock().expect("Probe is poisoned");
+        self.node(name)
+            .neighborhood
+            .dispatcher_node_query
+            .try_send(DispatcherNodeQueryMessage {
+                query: NodeQueryMessage::PublicKey(key.clone()),
+                context: TransmitDataMsg {
+                    endpoint: Endpoint::Key(key),
+                    last_data: false,
+                    sequence_number: None,
+                    data: vec![],
+                    relayed: false,
+                },
+                recipient: probe.recipient.clone(),
+            })
+            .expect("Neighborhood is dead");
+        probe.responses.recv().expect("Probe is dead")
+    }
+
+    pub fn knows(&self, name: &str, about: &str) -> bool {
+        self.node_query(name, about).is_some()
+    }
+
+    // True when every Node's database holds every other Node. Nodes only learn about the ones
+    // within their database patch (min hops, but at least 3), so this suits small networks
+    pub fn is_fully_connected(&self) -> bool {
+        self.nodes.keys().all(|name| {
+            self.nodes
+                .keys()
+                .filter(|other| *other != name)
+                .all(|other| self.knows(name, other))
+        })
+    }
+
+    pub fn route_query(&self, name: &str, hostname: &str) -> Option<RouteQueryResponse> {
+        self.node(name)
+            .neighborhood
+            .route_query
+            .send(RouteQueryMessage::data_indefinite_route_request(
+                Some(hostname.to_string()),
+                1000,
+            ))
+            .wait()
+            .expect("Neighborhood is dead")
+    }
+
+    // Hands a request to the named Node's ProxyServer as a browser on a new connection would,
+    // and collects the response until it stops coming
+    pub fn browse(&self, name: &str, request: &[u8], timeout: Duration) -> Option<Vec<u8>> {
+        let port = self.next_browser_port.fetch_add(1, Ordering::SeqCst);
+        let browser_addr = SocketAddr::new(localhost(), port);
+        let (tx, rx) = channel();
+        self.browsers
+            .lock()
+            .expect("Browsers are poisoned")
+            .insert(browser_addr, tx);
+        self.node(name)
+            .browser_sub
+            .try_send(InboundClientData {
+                timestamp: SystemTime::now(),
+                peer_addr: browser_addr,
+                reception_port: Some(80),
+                last_data: false,
+                is_clandestine: false,
+                sequence_number: Some(0),
+                data: request.to_vec(),
+            })
+            .expect("ProxyServer is dead");
+        let mut response = rx.recv_timeout(timeout).ok()?;
+        while let Ok(chunk) = rx.recv_timeout(Duration::from_millis(RESPONSE_QUIET_MILLIS)) {
+            response.extend(chunk)
+        }
+        Some(response)
+    }
+
+    // Changes the named Node's min hops the way the UI does, through its Configurator
+    pub fn set_min_hops(&self, name: &str, min_hops: Hops) {
+        self.node(name)
+            .ui_sub
+            .try_send(NodeFromUiMessage {
+                client_id: 0,
+                body: UiSetConfigurationRequest {
+                    name: "min-hops".to_string(),
+                    value: min_hops.to_string(),
+                }
+                .tmb(0),
+            })
+            .expect("Configurator is dead")
+    }
+
+    pub fn payables(&self, name: &str) -> Vec<PayableAccount> {
+        PayableDaoReal::new(self.connect(name)).non_pending_payables()
+    }
+
+    pub fn receivables(&self, name: &str) -> Vec<ReceivableAccount> {
+        ReceivableDaoReal::new(self.connect(name))
+            .custom_query(CustomQuery::RangeQuery {
+                min_age_s: 0,
+                max_age_s: i64::MAX as u64,
+                min_amount_gwei: i64::MIN,
+                max_amount_gwei: i64::MAX,
+                timestamp: SystemTime::now(),
+            })
+            .unwrap_or_default()
+    }
+
+    // The wallets the named Node's Accountant has asked the mock blockchain about
+    pub fn blockchain_queries(&self, name: &str) -> Vec<Wallet> {
+        self.node(name)
+            .blockchain_queries
+            .lock()
+            .expect("Blockchain queries are poisoned")
+            .clone()
+    }
+
+    // Polls until the condition holds or the time is up; says whether it held
+    pub fn wait_until<F>(&self, timeout: Duration, condition: F) -> bool
+    where
+        F: Fn(&NetworkSimulator) -> bool,
+    {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if condition(self) {
+                return true;
+            }
+            if Instant::now() >= deadline {
+                return false;
+            }
+            thread::sleep(Duration::from_millis(POLL_INTERVAL_MILLIS));
+        }
+    }
+
+    // Like the containers of a multinode test, each Node debuts only after the ones before it
+    // have settled in. A Node answering two debuts at once tells the later debutant about the
+    // earlier one, and the later one ignores an answer about Nodes outside its empty patch.
+    fn debut_in_order(&self, specs: &[NodeSpec], latency: Duration) {
+        let debut_timeout = Duration::from_millis(DEBUT_TIMEOUT_MILLIS) + latency * 2;
+        specs.iter().for_each(|spec| {
+            self.node(&spec.name)
+                .neighborhood
+                .start
+                .try_send(StartMessage {})
+                .expect("Neighborhood is dead");
+            self.wait_until(debut_timeout, |sim| {
+                spec.neighbor_names
+                    .iter()
+                    .all(|neighbor_name| sim.knows(&spec.name, neighbor_name))
+            });
+        });
+    }
+
+    fn node(&self, name: &str) -> &SimulatedNode {
+        self.nodes
+            .get(name)
+            .unwrap_or_else(|| panic!("Scenario has no Node named '{}'", name))
+    }
+
+    fn connect(&self, name: &str) -> Box<dyn ConnectionWrapper> {
+        DbInitializerReal::default()
+            .initialize(
+                &self.node(name).data_directory,
+                DbInitializationConfig::panic_on_migration(),
+            )
+            .expect("Can't open simulated Node's database")
+    }
+}
+
+impl Drop for NetworkSimulator {
+    fn drop(&mut self) {
+        self.system.stop();
+        if let Some(join_handle) = self.join_handle_opt.take() {
+            let _ = join_handle.join();
+        }
+    }
+}
+
+struct WireEndpoint {
+    public_key: PublicKey,
+    socket_addr: SocketAddr,
+    hopper_sub: Recipient<InboundClientData>,
+}
+
+// Stands in for a Node's Dispatcher and StreamHandlerPool
+struct SimulatedWire {
+    source: SocketAddr,
+    connection_progress_sub: Recipient<ConnectionProgressMessage>,
+    connected_ips: HashSet<IpAddr>,
+    switchboard: Arc<Mutex<HashMap<IpAddr, WireEndpoint>>>,
+    browsers: Browsers,
+    latency: Duration,
+    loss_per_mille: u16,
+    rng: SmallRng,
+    stats: Arc<Mutex<WireStats>>,
+}
+
+impl Actor for SimulatedWire {
+    type Context = Context<Self>;
+}
+
+impl Handler<TransmitDataMsg> for SimulatedWire {
+    type Result = ();
+
+    fn handle(&mut self, msg: TransmitDataMsg, ctx: &mut Self::Context) {
+        // Clear data is a response for a browser; it never crosses the network
+        if msg.sequence_number.is_some() {
+            return self.answer_browser(msg);
+        }
+        let delivery_opt = match msg.endpoint {
+            Endpoint::Key(ref key) => self.make_delivery(|endpoint| &endpoint.public_key == key),
+            Endpoint::Socket(socket_addr) => {
+                self.report_connection(socket_addr.ip());
+                self.make_delivery(|endpoint| endpoint.socket_addr.ip() == socket_addr.ip())
+            }
+        };
+        self.transmit(delivery_opt, msg.data, ctx)
+    }
+}
+
+impl Handler<InboundClientData> for SimulatedWire {
+    type Result = ();
+
+    fn handle(&mut self, _msg: InboundClientData, _ctx: &mut Self::Context) {}
+}
+
+impl Handler<StreamShutdownMsg> for SimulatedWire {
+    type Result = ();
+
+    fn handle(&mut self, _msg: StreamShutdownMsg, _ctx: &mut Self::Context) {}
+}
+
+impl Handler<NodeFromUiMessage> for SimulatedWire {
+    type Result = ();
+
+    fn handle(&mut self, _msg: NodeFromUiMessage, _ctx: &mut Self::Context) {}
+}
+
+impl Handler<NewPublicIp> for SimulatedWire {
+    type Result = ();
+
+    fn handle(&mut self, _msg: NewPublicIp, _ctx: &mut Self::Context) {}
+}
+
+impl Handler<BindMessage> for SimulatedWire {
+    type Result = ();
+
+    fn handle(&mut self, _msg: BindMessage, _ctx: &mut Self::Context) {}
+}
+
+impl SimulatedWire {
+    fn make_subs_from(addr: &Addr<SimulatedWire>) -> DispatcherSubs {
+        DispatcherSubs {
+            ibcd_sub: recipient!(addr, InboundClientData),
+            bind: recipient!(addr, BindMessage),
+            from_dispatcher_client: recipient!(addr, TransmitDataMsg),
+            stream_shutdown_sub: recipient!(addr, StreamShutdownMsg),
+            ui_sub: recipient!(addr, NodeFromUiMessage),
+            new_ip_sub: recipient!(addr, NewPublicIp),
+        }
+    }
+
+    // A real StreamHandlerPool reports the first connection to an address, just as this does
+    fn report_connection(&mut self, peer_ip: IpAddr) {
+        if self.connected_ips.insert(peer_ip) {
+            self.connection_progress_sub
+                .try_send(ConnectionProgressMessage {
+                    peer_addr: peer_ip,
+                    event: ConnectionProgressEvent::TcpConnectionSuccessful,
+                })
+                .expect("Neighborhood is dead");
+        }
+    }
+
+    fn answer_browser(&mut self, msg: TransmitDataMsg) {
+        let browser_addr = match msg.endpoint {
+            Endpoint::Socket(socket_addr) => socket_addr,
+            Endpoint::Key(_) => return,
+        };
+        if let Some(browser) = self
+            .browsers
+            .lock()
+            .expect("Browsers are poisoned")
+            .get(&browser_addr)
+        {
+            let _ = browser.send(msg.data);
+        }
+    }
+
+    fn make_delivery<F>(&self, is_target: F) -> Option<(Recipient<InboundClientData>, u16)>
+    where
+        F: Fn(&WireEndpoint) -> bool,
+    {
+        let switchboard = self.switchboard.lock().expect("Switchboard is poisoned");
+        switchboard
+            .values()
+            .find(|endpoint| is_target(endpoint))
+            .map(|endpoint| (endpoint.hopper_sub.clone(), endpoint.socket_addr.port()))
+    }
+
+    fn transmit(
+        &mut self,
+        delivery_opt: Option<(Recipient<InboundClientData>, u16)>,
+        data: Vec<u8>,
+        ctx: &mut Context<Self>,
+    ) {
+        let (hopper_sub, reception_port) = match delivery_opt {
+            Some(delivery) => delivery,
+            None => {
+                self.stats.lock().expect("Stats are poisoned").undeliverable += 1;
+                return;
+            }
+        };
+        if self.rng.gen_range(0..1000) < self.loss_per_mille {
+            self.stats.lock().expect("Stats are poisoned").lost += 1;
+            return;
+        }
+        // What the target's Dispatcher would have handed its Hopper
+        let ibcd = InboundClientData {
+            timestamp: SystemTime::now(),
+            peer_addr: self.source,
+            reception_port: Some(reception_port),
+            last_data: false,
+            is_clandestine: true,
+            sequence_number: None,
+            data,
+        };
+        let stats = self.stats.clone();
+        ctx.run_later(self.latency, move |_, _| {
+            let sent = hopper_sub.try_send(ibcd).is_ok();
+            let mut stats = stats.lock().expect("Stats are poisoned");
+            if sent {
+                stats.delivered += 1
+            } else {
+                stats.undeliverable += 1
+            }
+        });
+    }
+}
+
+// Answers every scan for received payments from a blockchain on which nothing ever happens. The
+// scenarios run up no debts big enough to pay, so nothing else the Accountant might send needs
+// an answer.
+struct BlockchainBridgeMock {
+    queries: Arc<Mutex<Vec<Wallet>>>,
+    accountant_opt: Option<Recipient<ReceivedPayments>>,
+}
+
+impl Actor for BlockchainBridgeMock {
+    type Context = Context<Self>;
+}
+
+impl Handler<BindMessage> for BlockchainBridgeMock {
+    type Result = ();
+
+    fn handle(&mut self, msg: BindMessage, _ctx: &mut Self::Context) {
+        self.accountant_opt = Some(msg.peer_actors.accountant.report_inbound_payments);
+    }
+}
+
+impl Handler<RetrieveTransactions> for BlockchainBridgeMock {
+    type Result = ();
+
+    fn handle(&mut self, msg: RetrieveTransactions, _ctx: &mut Self::Context) {
+        self.queries
+            .lock()
+            .expect("Blockchain queries are poisoned")
+            .push(msg.recipient);
+        self.accountant_opt
+            .as_ref()
+            .expect("Accountant is unbound")
+            .try_send(ReceivedPayments {
+                timestamp: SystemTime::now(),
+                payments: vec![],
+                new_start_block: MOCK_BLOCKCHAIN_HEIGHT,
+                scanned_blocks_opt: None,
+                response_skeleton_opt: msg.response_skeleton_opt,
+            })
+            .expect("Accountant is dead")
+    }
+}
+
+impl Handler<OutboundPaymentsInstructions> for BlockchainBridgeMock {
+    type Result = ();
+
+    fn handle(&mut self, _msg: OutboundPaymentsInstructions, _ctx: &mut Self::Context) {}
+}
+
+impl Handler<QualifiedPayablesMessage> for BlockchainBridgeMock {
+    type Result = ();
+
+    fn handle(&mut self, _msg: QualifiedPayablesMessage, _ctx: &mut Self::Context) {}
+}
+
+impl Handler<RequestTransactionReceipts> for BlockchainBridgeMock {
+    type Result = ();
+
+    fn handle(&mut self, _msg: RequestTransactionReceipts, _ctx: &mut Self::Context) {}
+}
+
+impl Handler<RequestFeeBumps> for BlockchainBridgeMock {
+    type Result = ();
+
+    fn handle(&mut self, _msg: RequestFeeBumps, _ctx: &mut Self::Context) {}
+}
+
+impl Handler<NodeFromUiMessage> for BlockchainBridgeMock {
+    type Result = ();
+
+    fn handle(&mut self, _msg: NodeFromUiMessage, _ctx: &mut Self::Context) {}
+}
+
+impl Handler<ConfigChangeMsg> for BlockchainBridgeMock {
+    type Result = ();
+
+    fn handle(&mut self, _msg: ConfigChangeMsg, _ctx: &mut Self::Context) {}
+}
+
+impl BlockchainBridgeMock {
+    fn make_subs_from(addr: &Addr<BlockchainBridgeMock>) -> BlockchainBridgeSubs {
+        BlockchainBridgeSubs {
+            bind: recipient!(addr, BindMessage),
+            outbound_payments_instructions: recipient!(addr, OutboundPaymentsInstructions),
+            qualified_payables: recipient!(addr, QualifiedPayablesMessage),
+            retrieve_transactions: recipient!(addr, RetrieveTransactions),
+            ui_sub: recipient!(addr, NodeFromUiMessage),
+            request_transaction_receipts: recipient!(addr, RequestTransactionReceipts),
+            request_fee_bumps: recipient!(addr, RequestFeeBumps),
+            config_change_msg_sub: recipient!(addr, ConfigChangeMsg),
+        }
+    }
+}
+
+struct NodeQueryProbe {
+    recipient: Recipient<DispatcherNodeQueryResponse>,
+    responses: Receiver<Option<NodeQueryResponseMetadata>>,
+}
+
+struct NodeQueryProbeActor {
+    responses: Sender<Option<NodeQueryResponseMetadata>>,
+}
+
+impl Actor for NodeQueryProbeActor {
+    type Context = Context<Self>;
+}
+
+impl Handler<DispatcherNodeQueryResponse> for NodeQueryProbeActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: DispatcherNodeQueryResponse, _ctx: &mut Self::Context) {
+        let _ = self.responses.send(msg.result);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::http_test_server::TestServer;
+    use masq_lib::utils::{find_free_port, index_of};
+
+    const SETTLE_TIMEOUT: Duration = Duration::from_secs(10);
+    const BROWSE_TIMEOUT: Duration = Duration::from_secs(10);
+    const EXAMPLE_PAGE: &[u8] = b"<html><body><h1>Example Domain</h1></body></html>";
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(FIRST_NODE_NUMBER, 1001);
+        assert_eq!(FIRST_BROWSER_PORT, 40001);
+        assert_eq!(POLL_INTERVAL_MILLIS, 20);
+        assert_eq!(RESPONSE_QUIET_MILLIS, 200);
+        assert_eq!(DEBUT_TIMEOUT_MILLIS, 1000);
+        assert_eq!(MOCK_BLOCKCHAIN_HEIGHT, 1000);
+    }
+
+    #[test]
+    fn graph_connects_without_containers() {
+        let subject = NetworkSimulatorBuilder::new("graph_connects_without_containers")
+            .chain_of_standard_nodes(&["first", "second", "third", "fourth"])
+            .start();
+
+        let connected = subject.wait_until(SETTLE_TIMEOUT, |sim| sim.is_fully_connected());
+
+        assert_eq!(connected, true);
+        let fourth = subject.node_query("first", "fourth").unwrap();
+        assert_eq!(&fourth.public_key, subject.public_key("fourth"));
+        let stats = subject.stats();
+        assert_eq!(stats.lost, 0);
+        assert_eq!(stats.undeliverable, 0);
+        assert!(stats.delivered > 0);
+    }
+
+    #[test]
+    fn nodes_beyond_the_database_patch_stay_strangers() {
+        // Introductions pull a short chain together, but not one this long
+        let names = [
+            "n0", "n1", "n2", "n3", "n4", "n5", "n6", "n7", "n8", "n9", "n10", "n11",
+        ];
+        let subject =
+            NetworkSimulatorBuilder::new("nodes_beyond_the_database_patch_stay_strangers")
+                .chain_of_standard_nodes(&names)
+                .start();
+
+        let ends_settled = subject.wait_until(SETTLE_TIMEOUT, |sim| {
+            ["n1", "n2", "n3"]
+                .iter()
+                .all(|other| sim.knows("n0", other))
+                && ["n10", "n9", "n8"]
+                    .iter()
+                    .all(|other| sim.knows("n11", other))
+        });
+
+        assert_eq!(ends_settled, true);
+        assert_eq!(subject.knows("n0", "n11"), false);
+        assert_eq!(subject.knows("n11", "n0"), false);
+    }
+
+    #[test]
+    fn data_can_be_routed_using_different_min_hops() {
+        assert_http_end_to_end_routing(Hops::OneHop);
+        assert_http_end_to_end_routing(Hops::TwoHops);
+        assert_http_end_to_end_routing(Hops::SixHops);
+    }
+
+    fn assert_http_end_to_end_routing(min_hops: Hops) {
+        let server_port = find_free_port();
+        let _server = TestServer::start(server_port, vec![EXAMPLE_PAGE.to_vec()]);
+        // For a 1-hop route, 3 Nodes are necessary if we use the last one as the originating Node
+        let names = (0..(min_hops as usize) + 2)
+            .map(|index| format!("node_{}", index))
+            .collect::<Vec<String>>();
+        let subject = names
+            .iter()
+            .fold(
+                NetworkSimulatorBuilder::new(&format!(
+                    "data_can_be_routed_using_different_min_hops/{:?}",
+                    min_hops
+                ))
+                .min_hops(min_hops)
+                .standard_node("first", &[]),
+                |builder, name| builder.standard_node(name, &["first"]),
+            )
+            .start();
+        let originating_node = names.last().unwrap();
+        wait_for_route(&subject, originating_node);
+
+        let response = subject.browse(originating_node, &make_request(server_port), BROWSE_TIMEOUT);
+
+        assert_page(response, "<h1>Example Domain</h1>", min_hops);
+    }
+
+    #[test]
+    fn min_hops_can_be_changed_during_runtime() {
+        let server_port = find_free_port();
+        let _server = TestServer::start(server_port, vec![EXAMPLE_PAGE.to_vec()]);
+        let subject = NetworkSimulatorBuilder::new("min_hops_can_be_changed_during_runtime")
+            .min_hops(Hops::OneHop)
+            .standard_node("first", &[])
+            .standard_node("second", &["first"])
+            .start();
+        wait_for_route(&subject, "first");
+        let response = subject.browse("first", &make_request(server_port), BROWSE_TIMEOUT);
+        assert_page(response, "<h1>Example Domain</h1>", Hops::OneHop);
+
+        subject.set_min_hops("first", Hops::TwoHops);
+
+        let route_lost = subject.wait_until(SETTLE_TIMEOUT, |sim| {
+            sim.route_query("first", &localhost().to_string()).is_none()
+        });
+        assert_eq!(route_lost, true);
+        let response = subject.browse("first", &make_request(server_port), BROWSE_TIMEOUT);
+        assert_page(
+            response,
+            &format!("<h3>Subtitle: Can't find a route to {}</h3>", localhost()),
+            Hops::TwoHops,
+        );
+    }
+
+    #[test]
+    fn provided_and_consumed_services_are_recorded_in_databases() {
+        let server_port = find_free_port();
+        let _server = TestServer::start(server_port, vec![EXAMPLE_PAGE.to_vec()]);
+        let serving_names = (0..6)
+            .map(|index| format!("serving_{}", index))
+            .collect::<Vec<String>>();
+        let subject = serving_names
+            .iter()
+            .fold(
+                NetworkSimulatorBuilder::new(
+                    "provided_and_consumed_services_are_recorded_in_databases",
+                )
+                .standard_node("originating", &[]),
+                |builder, name| builder.serving_node(name, &["originating"]),
+            )
+            .start();
+        wait_for_route(&subject, "originating");
+
+        let response = subject.browse("originating", &make_request(server_port), BROWSE_TIMEOUT);
+
+        assert_page(response, "<h1>Example Domain</h1>", MIN_HOPS_FOR_TEST);
+        // Waiting until the serving Nodes have finished accruing their receivables
+        let books_balance = subject.wait_until(SETTLE_TIMEOUT, |sim| {
+            let payables = sim.payables("originating");
+            !payables.is_empty() && payables.len() == receivable_balances(sim, &serving_names).len()
+        });
+        assert_eq!(books_balance, true);
+        let payables = subject.payables("originating");
+        let receivable_balances = receivable_balances(&subject, &serving_names);
+        assert_eq!(
+            payables.len(),
+            receivable_balances.len(),
+            "Lengths of payables and receivables should match.\nPayables: {:?}\nReceivables: {:?}",
+            payables,
+            receivable_balances
+        );
+        assert!(
+            receivable_balances.len() >= 3, // minimum service list: route, route, exit.
+            "not enough receivables found {:?}",
+            receivable_balances
+        );
+        payables.iter().for_each(|payable| {
+            assert_eq!(
+                payable.balance_wei,
+                *receivable_balances.get(&payable.wallet).unwrap() as u128,
+            );
+        });
+        assert_eq!(
+            subject.blockchain_queries("originating"),
+            vec![subject.earning_wallet("originating").clone()]
+        );
+    }
+
+    fn receivable_balances(
+        subject: &NetworkSimulator,
+        serving_names: &[String],
+    ) -> HashMap<Wallet, i128> {
+        serving_names
+            .iter()
+            .flat_map(|name| {
+                subject
+                    .receivables(name)
+                    .into_iter()
+                    .map(move |receivable_account| {
+                        (
+                            subject.earning_wallet(name).clone(),
+                            receivable_account.balance_wei,
+                        )
+                    })
+            })
+            .collect()
+    }
+
+    fn wait_for_route(subject: &NetworkSimulator, originating_node: &str) {
+        let found = subject.wait_until(SETTLE_TIMEOUT, |sim| {
+            sim.route_query(originating_node, &localhost().to_string())
+                .is_some()
+        });
+        assert_eq!(found, true, "{} found no route", originating_node);
+    }
+
+    fn make_request(server_port: u16) -> Vec<u8> {
+        format!(
+            "GET / HTTP/1.1\r\nHost: {}\r\n\r\n",
+            SocketAddr::new(localhost(), server_port)
+        )
+        .into_bytes()
+    }
+
+    fn assert_page(response: Option<Vec<u8>>, expected: &str, min_hops: Hops) {
+        let response = response.unwrap_or_else(|| panic!("No response with {:?}", min_hops));
+        assert_eq!(
+            index_of(&response, expected.as_bytes()).is_some(),
+            true,
+            "Actual response with {:?}:\n{}",
+            min_hops,
+            String::from_utf8_lossy(&response)
+        );
+    }
+
+    #[test]
+    fn no_route_is_found_when_the_network_is_too_small_for_min_hops() {
+        let subject = NetworkSimulatorBuilder::new(
+            "no_route_is_found_when_the_network_is_too_small_for_min_hops",
+        )
+        .min_hops(Hops::ThreeHops)
+        .chain_of_standard_nodes(&["a", "b", "c"])
+        .start();
+        assert_eq!(
+            subject.wait_until(SETTLE_TIMEOUT, |sim| sim.is_fully_connected()),
+            true
+        );
+
+        let result = subject.route_query("a", "booga.com");
+
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn lost_gossip_leaves_a_debuting_node_alone() {
+        let subject = NetworkSimulatorBuilder::new("lost_gossip_leaves_a_debuting_node_alone")
+            .link(LinkConditions {
+                loss_per_mille: 1000,
+                ..LinkConditions::default()
+            })
+            .standard_node("old", &[])
+            .standard_node("new", &["old"])
+            .start();
+
+        let got_something = subject.wait_until(Duration::from_millis(500), |sim| {
+            sim.stats() != WireStats::default()
+        });
+
+        assert_eq!(got_something, true);
+        assert_eq!(subject.knows("old", "new"), false);
+        assert_eq!(subject.knows("new", "old"), false);
+        let stats = subject.stats();
+        assert_eq!(stats.delivered, 0);
+        assert!(stats.lost > 0);
+    }
+
+    #[test]
+    fn latency_delays_gossip() {
+        let start = Instant::now();
+        let subject = NetworkSimulatorBuilder::new("latency_delays_gossip")
+            .link(LinkConditions {
+                latency: Duration::from_millis(500),
+                ..LinkConditions::default()
+            })
+            .standard_node("old", &[])
+            .standard_node("new", &["old"])
+            .start();
+
+        let connected = subject.wait_until(SETTLE_TIMEOUT, |sim| sim.is_fully_connected());
+
+        assert_eq!(connected, true);
+        assert!(start.elapsed() >= Duration::from_millis(500));
+    }
+
+    #[test]
+    fn same_seed_loses_the_same_gossip() {
+        let run = |test_name: &str| {
+            let subject = NetworkSimulatorBuilder::new(test_name)
+                .link(LinkConditions {
+                    loss_per_mille: 300,
+                    seed: 42,
+                    ..LinkConditions::default()
+                })
+                .chain_of_standard_nodes(&["a", "b", "c", "d"])
+                .start();
+            subject.wait_until(Duration::from_secs(2), |_| false);
+            subject.stats()
+        };
+
+        let first = run("same_seed_loses_the_same_gossip/first");
+        let second = run("same_seed_loses_the_same_gossip/second");
+
+        assert_eq!(first.lost, second.lost);
+    }
+
+    #[test]
+    #[should_panic(expected = "Node 'b' is introduced to 'c', which hasn't been added yet")]
+    fn nodes_must_be_introduced_to_nodes_added_before_them() {
+        let _ = NetworkSimulatorBuilder::new("nodes_must_be_introduced_to_nodes_added_before_them")
+            .standard_node("a", &[])
+            .standard_node("b", &["c"]);
+    }
+}
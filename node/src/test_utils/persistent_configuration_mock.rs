@@ -4,7 +4,9 @@
 
 use crate::database::rusqlite_wrappers::TransactionSafeWrapper;
 use crate::db_config::persistent_configuration::{PersistentConfigError, PersistentConfiguration};
-use crate::sub_lib::accountant::{PaymentThresholds, ScanIntervals};
+use crate::neighborhood::gossip::GossipNodeRecord;
+use crate::sub_lib::accountant::{PaymentThresholds, ScanIntervals, SpendingBudget};
+use crate::sub_lib::bandwidth_shaper::{BandwidthLimits, DataQuota, DataUsage};
 use crate::sub_lib::neighborhood::{Hops, NodeDescriptor, RatePack};
 use crate::sub_lib::wallet::Wallet;
 use crate::test_utils::unshared_test_utils::arbitrary_id_stamp::ArbitraryIdStamp;
@@ -57,6 +59,11 @@ pub struct PersistentConfigurationMock {
         RefCell<Vec<Result<Option<Vec<NodeDescriptor>>, PersistentConfigError>>>,
     set_past_neighbors_params: Arc<Mutex<Vec<(Option<Vec<NodeDescriptor>>, String)>>>,
     set_past_neighbors_results: RefCell<Vec<Result<(), PersistentConfigError>>>,
+    neighborhood_snapshot_params: Arc<Mutex<Vec<String>>>,
+    neighborhood_snapshot_results:
+        RefCell<Vec<Result<Option<Vec<GossipNodeRecord>>, PersistentConfigError>>>,
+    set_neighborhood_snapshot_params: Arc<Mutex<Vec<(Option<Vec<GossipNodeRecord>>, String)>>>,
+    set_neighborhood_snapshot_results: RefCell<Vec<Result<(), PersistentConfigError>>>,
     start_block_params: Arc<Mutex<Vec<()>>>,
     start_block_results: RefCell<Vec<Result<Option<u64>, PersistentConfigError>>>,
     set_start_block_params: Arc<Mutex<Vec<Option<u64>>>>,
@@ -76,6 +83,18 @@ pub struct PersistentConfigurationMock {
     scan_intervals_results: RefCell<Vec<Result<ScanIntervals, PersistentConfigError>>>,
     set_scan_intervals_params: Arc<Mutex<Vec<String>>>,
     set_scan_intervals_results: RefCell<Vec<Result<(), PersistentConfigError>>>,
+    bandwidth_limits_results: RefCell<Vec<Result<BandwidthLimits, PersistentConfigError>>>,
+    set_bandwidth_limits_params: Arc<Mutex<Vec<String>>>,
+    set_bandwidth_limits_results: RefCell<Vec<Result<(), PersistentConfigError>>>,
+    data_quota_results: RefCell<Vec<Result<DataQuota, PersistentConfigError>>>,
+    set_data_quota_params: Arc<Mutex<Vec<String>>>,
+    set_data_quota_results: RefCell<Vec<Result<(), PersistentConfigError>>>,
+    data_usage_results: RefCell<Vec<Result<Option<DataUsage>, PersistentConfigError>>>,
+    set_data_usage_params: Arc<Mutex<Vec<DataUsage>>>,
+    set_data_usage_results: RefCell<Vec<Result<(), PersistentConfigError>>>,
+    spending_budget_results: RefCell<Vec<Result<SpendingBudget, PersistentConfigError>>>,
+    set_spending_budget_params: Arc<Mutex<Vec<String>>>,
+    set_spending_budget_results: RefCell<Vec<Result<(), PersistentConfigError>>>,
     arbitrary_id_stamp_opt: Option<ArbitraryIdStamp>,
 }
 
@@ -230,6 +249,31 @@ impl PersistentConfiguration for PersistentConfigurationMock {
         self.set_past_neighbors_results.borrow_mut().remove(0)
     }
 
+    fn neighborhood_snapshot(
+        &self,
+        db_password: &str,
+    ) -> Result<Option<Vec<GossipNodeRecord>>, PersistentConfigError> {
+        self.neighborhood_snapshot_params
+            .lock()
+            .unwrap()
+            .push(db_password.to_string());
+        self.neighborhood_snapshot_results.borrow_mut().remove(0)
+    }
+
+    fn set_neighborhood_snapshot(
+        &mut self,
+        node_records_opt: Option<Vec<GossipNodeRecord>>,
+        db_password: &str,
+    ) -> Result<(), PersistentConfigError> {
+        self.set_neighborhood_snapshot_params
+            .lock()
+            .unwrap()
+            .push((node_records_opt, db_password.to_string()));
+        self.set_neighborhood_snapshot_results
+            .borrow_mut()
+            .remove(0)
+    }
+
     fn start_block(&self) -> Result<Option<u64>, PersistentConfigError> {
         self.start_block_params.lock().unwrap().push(());
         Self::result_from(&self.start_block_results)
@@ -308,6 +352,45 @@ impl PersistentConfiguration for PersistentConfigurationMock {
         self.set_scan_intervals_results.borrow_mut().remove(0)
     }
 
+    fn bandwidth_limits(&self) -> Result<BandwidthLimits, PersistentConfigError> {
+        self.bandwidth_limits_results.borrow_mut().remove(0)
+    }
+
+    fn set_bandwidth_limits(&mut self, limits: String) -> Result<(), PersistentConfigError> {
+        self.set_bandwidth_limits_params
+            .lock()
+            .unwrap()
+            .push(limits);
+        self.set_bandwidth_limits_results.borrow_mut().remove(0)
+    }
+
+    fn data_quota(&self) -> Result<DataQuota, PersistentConfigError> {
+        self.data_quota_results.borrow_mut().remove(0)
+    }
+
+    fn set_data_quota(&mut self, quota: String) -> Result<(), PersistentConfigError> {
+        self.set_data_quota_params.lock().unwrap().push(quota);
+        self.set_data_quota_results.borrow_mut().remove(0)
+    }
+
+    fn data_usage(&self) -> Result<Option<DataUsage>, PersistentConfigError> {
+        self.data_usage_results.borrow_mut().remove(0)
+    }
+
+    fn set_data_usage(&mut self, usage: DataUsage) -> Result<(), PersistentConfigError> {
+        self.set_data_usage_params.lock().unwrap().push(usage);
+        self.set_data_usage_results.borrow_mut().remove(0)
+    }
+
+    fn spending_budget(&self) -> Result<SpendingBudget, PersistentConfigError> {
+        self.spending_budget_results.borrow_mut().remove(0)
+    }
+
+    fn set_spending_budget(&mut self, budget: String) -> Result<(), PersistentConfigError> {
+        self.set_spending_budget_params.lock().unwrap().push(budget);
+        self.set_spending_budget_results.borrow_mut().remove(0)
+    }
+
     arbitrary_id_stamp_in_trait_impl!();
 }
 
@@ -523,6 +606,38 @@ impl PersistentConfigurationMock {
         self
     }
 
+    pub fn neighborhood_snapshot_params(mut self, params: &Arc<Mutex<Vec<String>>>) -> Self {
+        self.neighborhood_snapshot_params = params.clone();
+        self
+    }
+
+    pub fn neighborhood_snapshot_result(
+        self,
+        result: Result<Option<Vec<GossipNodeRecord>>, PersistentConfigError>,
+    ) -> Self {
+        self.neighborhood_snapshot_results.borrow_mut().push(result);
+        self
+    }
+
+    #[allow(clippy::type_complexity)]
+    pub fn set_neighborhood_snapshot_params(
+        mut self,
+        params: &Arc<Mutex<Vec<(Option<Vec<GossipNodeRecord>>, String)>>>,
+    ) -> Self {
+        self.set_neighborhood_snapshot_params = params.clone();
+        self
+    }
+
+    pub fn set_neighborhood_snapshot_result(
+        self,
+        result: Result<(), PersistentConfigError>,
+    ) -> Self {
+        self.set_neighborhood_snapshot_results
+            .borrow_mut()
+            .push(result);
+        self
+    }
+
     pub fn earning_wallet_result(
         self,
         result: Result<Option<Wallet>, PersistentConfigError>,
@@ -655,6 +770,75 @@ impl PersistentConfigurationMock {
         self
     }
 
+    pub fn bandwidth_limits_result(
+        self,
+        result: Result<BandwidthLimits, PersistentConfigError>,
+    ) -> Self {
+        self.bandwidth_limits_results.borrow_mut().push(result);
+        self
+    }
+
+    pub fn set_bandwidth_limits_params(mut self, params: &Arc<Mutex<Vec<String>>>) -> Self {
+        self.set_bandwidth_limits_params = params.clone();
+        self
+    }
+
+    pub fn set_bandwidth_limits_result(self, result: Result<(), PersistentConfigError>) -> Self {
+        self.set_bandwidth_limits_results.borrow_mut().push(result);
+        self
+    }
+
+    pub fn data_quota_result(self, result: Result<DataQuota, PersistentConfigError>) -> Self {
+        self.data_quota_results.borrow_mut().push(result);
+        self
+    }
+
+    pub fn set_data_quota_params(mut self, params: &Arc<Mutex<Vec<String>>>) -> Self {
+        self.set_data_quota_params = params.clone();
+        self
+    }
+
+    pub fn set_data_quota_result(self, result: Result<(), PersistentConfigError>) -> Self {
+        self.set_data_quota_results.borrow_mut().push(result);
+        self
+    }
+
+    pub fn data_usage_result(
+        self,
+        result: Result<Option<DataUsage>, PersistentConfigError>,
+    ) -> Self {
+        self.data_usage_results.borrow_mut().push(result);
+        self
+    }
+
+    pub fn set_data_usage_params(mut self, params: &Arc<Mutex<Vec<DataUsage>>>) -> Self {
+        self.set_data_usage_params = params.clone();
+        self
+    }
+
+    pub fn set_data_usage_result(self, result: Result<(), PersistentConfigError>) -> Self {
+        self.set_data_usage_results.borrow_mut().push(result);
+        self
+    }
+
+    pub fn spending_budget_result(
+        self,
+        result: Result<SpendingBudget, PersistentConfigError>,
+    ) -> Self {
+        self.spending_budget_results.borrow_mut().push(result);
+        self
+    }
+
+    pub fn set_spending_budget_params(mut self, params: &Arc<Mutex<Vec<String>>>) -> Self {
+        self.set_spending_budget_params = params.clone();
+        self
+    }
+
+    pub fn set_spending_budget_result(self, result: Result<(), PersistentConfigError>) -> Self {
+        self.set_spending_budget_results.borrow_mut().push(result);
+        self
+    }
+
     pub fn mapping_protocol_result(
         self,
         result: Result<Option<AutomapProtocol>, PersistentConfigError>,
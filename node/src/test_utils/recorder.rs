@@ -3,17 +3,19 @@
 
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::msgs::BlockchainAgentWithContextMessage;
 use crate::accountant::scanners::mid_scan_msg_handling::payable_scanner::msgs::QualifiedPayablesMessage;
-use crate::accountant::ReportTransactionReceipts;
 use crate::accountant::{
     ReceivedPayments, RequestTransactionReceipts, ScanError, ScanForPayables,
     ScanForPendingPayables, ScanForReceivables, SentPayables,
 };
+use crate::accountant::{ReportFeeBumps, ReportTransactionReceipts, RequestFeeBumps};
 use crate::blockchain::blockchain_bridge::PendingPayableFingerprintSeeds;
 use crate::blockchain::blockchain_bridge::RetrieveTransactions;
 use crate::daemon::crash_notification::CrashNotification;
 use crate::daemon::DaemonBindMessage;
-use crate::neighborhood::gossip::Gossip_0v1;
-use crate::stream_messages::{AddStreamMsg, PoolBindMessage, RemoveStreamMsg};
+use crate::neighborhood::gossip::Gossip_0v2;
+use crate::stream_messages::{
+    AddStreamMsg, MasqueradeNegotiatedMsg, PoolBindMessage, RemoveStreamMsg,
+};
 use crate::sub_lib::accountant::AccountantSubs;
 use crate::sub_lib::accountant::ReportExitServiceProvidedMessage;
 use crate::sub_lib::accountant::ReportRoutingServiceProvidedMessage;
@@ -33,16 +35,19 @@ use crate::sub_lib::neighborhood::NodeQueryResponseMetadata;
 use crate::sub_lib::neighborhood::RemoveNeighborMessage;
 use crate::sub_lib::neighborhood::RouteQueryMessage;
 use crate::sub_lib::neighborhood::RouteQueryResponse;
+use crate::sub_lib::neighborhood::RoutesDataChangeMsg;
 use crate::sub_lib::neighborhood::UpdateNodeRecordMetadataMessage;
 use crate::sub_lib::neighborhood::{DispatcherNodeQueryMessage, GossipFailure_0v1};
 use crate::sub_lib::peer_actors::PeerActors;
-use crate::sub_lib::peer_actors::{BindMessage, NewPublicIp, StartMessage};
-use crate::sub_lib::proxy_client::{ClientResponsePayload_0v1, InboundServerData};
+use crate::sub_lib::peer_actors::{BindMessage, NewPublicIp, ShutdownMsg, StartMessage};
+use crate::sub_lib::proxy_client::{ClientResponsePayload_0v2, InboundServerData};
 use crate::sub_lib::proxy_client::{DnsResolveFailure_0v1, ProxyClientSubs};
 use crate::sub_lib::proxy_server::{
-    AddReturnRouteMessage, ClientRequestPayload_0v1, StreamKeyPurge,
+    AddReturnRouteMessage, ClientRequestPayload_0v2, StreamKeyPurge,
+};
+use crate::sub_lib::proxy_server::{
+    AddRouteResultMessage, ProxyServerSubs, SpendingBudgetStatusMsg,
 };
-use crate::sub_lib::proxy_server::{AddRouteResultMessage, ProxyServerSubs};
 use crate::sub_lib::stream_handler_pool::DispatcherNodeQueryResponse;
 use crate::sub_lib::stream_handler_pool::TransmitDataMsg;
 use crate::sub_lib::ui_gateway::UiGatewaySubs;
@@ -135,15 +140,16 @@ recorder_message_handler_t_m_p!(DaemonBindMessage);
 recorder_message_handler_t_m_p!(DispatcherNodeQueryMessage);
 recorder_message_handler_t_m_p!(DispatcherNodeQueryResponse);
 recorder_message_handler_t_m_p!(DnsResolveFailure_0v1);
-recorder_message_handler_t_m_p!(ExpiredCoresPackage<ClientRequestPayload_0v1>);
-recorder_message_handler_t_m_p!(ExpiredCoresPackage<ClientResponsePayload_0v1>);
+recorder_message_handler_t_m_p!(ExpiredCoresPackage<ClientRequestPayload_0v2>);
+recorder_message_handler_t_m_p!(ExpiredCoresPackage<ClientResponsePayload_0v2>);
 recorder_message_handler_t_m_p!(ExpiredCoresPackage<DnsResolveFailure_0v1>);
-recorder_message_handler_t_m_p!(ExpiredCoresPackage<Gossip_0v1>);
+recorder_message_handler_t_m_p!(ExpiredCoresPackage<Gossip_0v2>);
 recorder_message_handler_t_m_p!(ExpiredCoresPackage<GossipFailure_0v1>);
 recorder_message_handler_t_m_p!(ExpiredCoresPackage<MessageType>);
 recorder_message_handler_t_m_p!(InboundClientData);
 recorder_message_handler_t_m_p!(InboundServerData);
 recorder_message_handler_t_m_p!(IncipientCoresPackage);
+recorder_message_handler_t_m_p!(MasqueradeNegotiatedMsg);
 recorder_message_handler_t_m_p!(NewPublicIp);
 recorder_message_handler_t_m_p!(NodeFromUiMessage);
 recorder_message_handler_t_m_p!(NodeToUiMessage);
@@ -160,12 +166,17 @@ recorder_message_handler_t_m_p!(ReportRoutingServiceProvidedMessage);
 recorder_message_handler_t_m_p!(ReportServicesConsumedMessage);
 recorder_message_handler_t_m_p!(ReportTransactionReceipts);
 recorder_message_handler_t_m_p!(RequestTransactionReceipts);
+recorder_message_handler_t_m_p!(ReportFeeBumps);
+recorder_message_handler_t_m_p!(RequestFeeBumps);
 recorder_message_handler_t_m_p!(RetrieveTransactions);
+recorder_message_handler_t_m_p!(RoutesDataChangeMsg);
 recorder_message_handler_t_m_p!(ScanError);
 recorder_message_handler_t_m_p!(ScanForPayables);
 recorder_message_handler_t_m_p!(ScanForPendingPayables);
 recorder_message_handler_t_m_p!(ScanForReceivables);
 recorder_message_handler_t_m_p!(SentPayables);
+recorder_message_handler_t_m_p!(ShutdownMsg);
+recorder_message_handler_t_m_p!(SpendingBudgetStatusMsg);
 recorder_message_handler_t_m_p!(StartMessage);
 recorder_message_handler_t_m_p!(StreamShutdownMsg);
 recorder_message_handler_t_m_p!(TransmitDataMsg);
@@ -394,13 +405,15 @@ pub fn make_proxy_server_subs_from_recorder(addr: &Addr<Recorder>) -> ProxyServe
     ProxyServerSubs {
         bind: recipient!(addr, BindMessage),
         from_dispatcher: recipient!(addr, InboundClientData),
-        from_hopper: recipient!(addr, ExpiredCoresPackage<ClientResponsePayload_0v1>),
+        from_hopper: recipient!(addr, ExpiredCoresPackage<ClientResponsePayload_0v2>),
         dns_failure_from_hopper: recipient!(addr, ExpiredCoresPackage<DnsResolveFailure_0v1>),
         add_return_route: recipient!(addr, AddReturnRouteMessage),
         stream_shutdown_sub: recipient!(addr, StreamShutdownMsg),
         node_from_ui: recipient!(addr, NodeFromUiMessage),
         route_result_sub: recipient!(addr, AddRouteResultMessage),
         schedule_stream_key_purge: recipient!(addr, MessageScheduler<StreamKeyPurge>),
+        spending_budget_status: recipient!(addr, SpendingBudgetStatusMsg),
+        config_change_msg_sub: recipient!(addr, ConfigChangeMsg),
     }
 }
 
@@ -428,10 +441,11 @@ pub fn make_hopper_subs_from_recorder(addr: &Addr<Recorder>) -> HopperSubs {
 pub fn make_proxy_client_subs_from_recorder(addr: &Addr<Recorder>) -> ProxyClientSubs {
     ProxyClientSubs {
         bind: recipient!(addr, BindMessage),
-        from_hopper: recipient!(addr, ExpiredCoresPackage<ClientRequestPayload_0v1>),
+        from_hopper: recipient!(addr, ExpiredCoresPackage<ClientRequestPayload_0v2>),
         inbound_server_data: recipient!(addr, InboundServerData),
         dns_resolve_failed: recipient!(addr, DnsResolveFailure_0v1),
         node_from_ui: recipient!(addr, NodeFromUiMessage),
+        config_change_msg_sub: recipient!(addr, ConfigChangeMsg),
     }
 }
 
@@ -442,7 +456,7 @@ pub fn make_neighborhood_subs_from_recorder(addr: &Addr<Recorder>) -> Neighborho
         new_public_ip: recipient!(addr, NewPublicIp),
         route_query: recipient!(addr, RouteQueryMessage),
         update_node_record_metadata: recipient!(addr, UpdateNodeRecordMetadataMessage),
-        from_hopper: recipient!(addr, ExpiredCoresPackage<Gossip_0v1>),
+        from_hopper: recipient!(addr, ExpiredCoresPackage<Gossip_0v2>),
         gossip_failure: recipient!(addr, ExpiredCoresPackage<GossipFailure_0v1>),
         dispatcher_node_query: recipient!(addr, DispatcherNodeQueryMessage),
         remove_neighbor: recipient!(addr, RemoveNeighborMessage),
@@ -450,6 +464,8 @@ pub fn make_neighborhood_subs_from_recorder(addr: &Addr<Recorder>) -> Neighborho
         stream_shutdown_sub: recipient!(addr, StreamShutdownMsg),
         from_ui_message_sub: recipient!(addr, NodeFromUiMessage),
         connection_progress_sub: recipient!(addr, ConnectionProgressMessage),
+        routes_data_change_sub: recipient!(addr, RoutesDataChangeMsg),
+        shutdown_sub: recipient!(addr, ShutdownMsg),
     }
 }
 
@@ -465,6 +481,7 @@ pub fn make_accountant_subs_from_recorder(addr: &Addr<Recorder>) -> AccountantSu
         report_inbound_payments: recipient!(addr, ReceivedPayments),
         init_pending_payable_fingerprints: recipient!(addr, PendingPayableFingerprintSeeds),
         report_transaction_receipts: recipient!(addr, ReportTransactionReceipts),
+        report_fee_bumps: recipient!(addr, ReportFeeBumps),
         report_sent_payments: recipient!(addr, SentPayables),
         scan_errors: recipient!(addr, ScanError),
         ui_message_sub: recipient!(addr, NodeFromUiMessage),
@@ -487,6 +504,8 @@ pub fn make_blockchain_bridge_subs_from_recorder(addr: &Addr<Recorder>) -> Block
         retrieve_transactions: recipient!(addr, RetrieveTransactions),
         ui_sub: recipient!(addr, NodeFromUiMessage),
         request_transaction_receipts: recipient!(addr, RequestTransactionReceipts),
+        request_fee_bumps: recipient!(addr, RequestFeeBumps),
+        config_change_msg_sub: recipient!(addr, ConfigChangeMsg),
     }
 }
 
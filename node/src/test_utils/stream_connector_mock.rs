@@ -1,4 +1,6 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use crate::sub_lib::clandestine_tls::ClandestineTls;
+use crate::sub_lib::cryptde::PublicKey;
 use crate::sub_lib::stream_connector::ConnectionInfo;
 use crate::sub_lib::stream_connector::ConnectionInfoFuture;
 use crate::sub_lib::stream_connector::StreamConnector;
@@ -17,6 +19,7 @@ use tokio::prelude::Async;
 #[derive(Default)]
 pub struct StreamConnectorMock {
     connect_pair_params: Arc<Mutex<Vec<SocketAddr>>>,
+    connect_tls_params: Arc<Mutex<Vec<(SocketAddr, PublicKey)>>>,
     connect_pair_results: RefCell<Vec<Result<ConnectionInfo, io::Error>>>,
     split_stream_results: RefCell<Vec<Option<ConnectionInfo>>>,
 }
@@ -28,6 +31,21 @@ impl StreamConnector for StreamConnectorMock {
         Box::new(result(connection_info_result))
     }
 
+    fn connect_tls(
+        &self,
+        socket_addr: SocketAddr,
+        _clandestine_tls: &ClandestineTls,
+        expected_key: &PublicKey,
+        _logger: &Logger,
+    ) -> ConnectionInfoFuture {
+        self.connect_tls_params
+            .lock()
+            .unwrap()
+            .push((socket_addr, expected_key.clone()));
+        let connection_info_result = self.connect_pair_results.borrow_mut().remove(0);
+        Box::new(result(connection_info_result))
+    }
+
     fn connect_one(
         &self,
         _ip_addrs: Vec<IpAddr>,
@@ -50,6 +68,7 @@ impl StreamConnectorMock {
     pub fn new() -> StreamConnectorMock {
         Self {
             connect_pair_params: Arc::new(Mutex::new(vec![])),
+            connect_tls_params: Arc::new(Mutex::new(vec![])),
             connect_pair_results: RefCell::new(vec![]),
             split_stream_results: RefCell::new(vec![]),
         }
@@ -105,6 +124,14 @@ impl StreamConnectorMock {
         self
     }
 
+    pub fn connect_tls_params(
+        mut self,
+        params_arc: &Arc<Mutex<Vec<(SocketAddr, PublicKey)>>>,
+    ) -> StreamConnectorMock {
+        self.connect_tls_params = params_arc.clone();
+        self
+    }
+
     pub fn connect_pair_result(
         self,
         result: Result<ConnectionInfo, io::Error>,
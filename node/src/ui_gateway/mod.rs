@@ -1,5 +1,6 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
 
+pub mod remote_access;
 mod websocket_supervisor;
 
 #[cfg(test)]
@@ -28,7 +29,7 @@ use std::mem::replace;
 pub const CRASH_KEY: &str = "UIGATEWAY";
 
 pub struct UiGateway {
-    port: u16,
+    config: UiGatewayConfig,
     websocket_supervisor: Either<Box<dyn WebSocketSupervisorFactory>, Box<dyn WebSocketSupervisor>>,
     incoming_message_recipients: Vec<Recipient<NodeFromUiMessage>>,
     crashable: bool,
@@ -38,7 +39,7 @@ pub struct UiGateway {
 impl UiGateway {
     pub fn new(config: &UiGatewayConfig, crashable: bool) -> UiGateway {
         UiGateway {
-            port: config.ui_port,
+            config: config.clone(),
             websocket_supervisor: Either::Left(Box::new(WebsocketSupervisorFactoryReal)),
             incoming_message_recipients: vec![],
             crashable,
@@ -102,7 +103,7 @@ impl UiGateway {
             .left()
             .as_ref()
             .expectv("WebSocket factory")
-            .make(self.port, recipient)
+            .make(&self.config, recipient)
         {
             Ok(wss) => Either::Right(wss),
             Err(e) => panic!("Couldn't start WebSocketSupervisor: {:?}", e),
@@ -232,6 +233,7 @@ mod tests {
         let subject = UiGateway::new(
             &UiGatewayConfig {
                 ui_port: find_free_port(),
+                remote_ui_opt: None,
             },
             false,
         );
@@ -255,6 +257,7 @@ mod tests {
         let subject = UiGateway::new(
             &UiGatewayConfig {
                 ui_port: find_free_port(),
+                remote_ui_opt: None,
             },
             false,
         );
@@ -289,6 +292,7 @@ mod tests {
         let subject = UiGateway::new(
             &UiGatewayConfig {
                 ui_port: find_free_port(),
+                remote_ui_opt: None,
             },
             false,
         );
@@ -346,10 +350,16 @@ mod tests {
         let send_msg_params_arc = Arc::new(Mutex::new(vec![]));
         let websocket_supervisor =
             WebSocketSupervisorMock::new().send_msg_params(&send_msg_params_arc);
+        let make_params_arc = Arc::new(Mutex::new(vec![]));
         let websocket_supervisor_factory = WebsocketSupervisorFactoryMock::default()
+            .make_params(&make_params_arc)
             .make_result(Ok(Box::new(websocket_supervisor)));
         let port = find_free_port();
-        let mut subject = UiGateway::new(&UiGatewayConfig { ui_port: port }, false);
+        let config = UiGatewayConfig {
+            ui_port: port,
+            remote_ui_opt: None,
+        };
+        let mut subject = UiGateway::new(&config, false);
         subject.websocket_supervisor = Either::Left(
             Box::new(websocket_supervisor_factory) as Box<dyn WebSocketSupervisorFactory>
         );
@@ -374,6 +384,8 @@ mod tests {
         assert_eq!(accountant_recording.len(), 0);
         let send_parameters = send_msg_params_arc.lock().unwrap();
         assert_eq!(send_parameters[0], msg);
+        let make_params = make_params_arc.lock().unwrap();
+        assert_eq!(*make_params, vec![config]);
     }
 
     #[test]
@@ -383,6 +395,7 @@ mod tests {
         let subject = UiGateway::new(
             &UiGatewayConfig {
                 ui_port: find_free_port(),
+                remote_ui_opt: None,
             },
             false,
         );
@@ -426,7 +439,13 @@ mod tests {
             new_password: "bubbles".to_string(),
         }
         .tmb(12);
-        let subject = UiGateway::new(&UiGatewayConfig { ui_port: 123 }, false);
+        let subject = UiGateway::new(
+            &UiGatewayConfig {
+                ui_port: 123,
+                remote_ui_opt: None,
+            },
+            false,
+        );
 
         let result = subject.deserialization_validator_with_crash_request_handler(msg_body);
 
@@ -441,7 +460,13 @@ mod tests {
             path: MessagePath::Conversation(45),
             payload: Err((1234, "We did it wrong".to_string())),
         };
-        let subject = UiGateway::new(&UiGatewayConfig { ui_port: 123 }, false);
+        let subject = UiGateway::new(
+            &UiGatewayConfig {
+                ui_port: 123,
+                remote_ui_opt: None,
+            },
+            false,
+        );
 
         let result = subject.deserialization_validator_with_crash_request_handler(msg_body);
 
@@ -457,7 +482,13 @@ mod tests {
         }
         .tmb(0);
         let crashable = false;
-        let subject = UiGateway::new(&UiGatewayConfig { ui_port: 123 }, crashable);
+        let subject = UiGateway::new(
+            &UiGatewayConfig {
+                ui_port: 123,
+                remote_ui_opt: None,
+            },
+            crashable,
+        );
 
         let result = subject.deserialization_validator_with_crash_request_handler(crash_request);
 
@@ -472,7 +503,13 @@ mod tests {
         }
         .tmb(0);
         let crashable = true;
-        let subject = UiGateway::new(&UiGatewayConfig { ui_port: 123 }, crashable);
+        let subject = UiGateway::new(
+            &UiGatewayConfig {
+                ui_port: 123,
+                remote_ui_opt: None,
+            },
+            crashable,
+        );
 
         let result = subject.deserialization_validator_with_crash_request_handler(crash_request);
 
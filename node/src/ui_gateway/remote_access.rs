@@ -0,0 +1,579 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+// Access to the Node's UI gateway from other computers: which tokens may connect, what each of
+// them may do once connected, and the TLS identity the remote listener presents.
+
+use crate::sub_lib::ui_gateway::{RemoteUiConfig, UiAccessGrant, UiPermissions};
+use masq_lib::messages::{FromMessageBody, UiConfigurationRequest, UiLogStreamRequest};
+use masq_lib::ui_gateway::MessageBody;
+use openssl::asn1::Asn1Time;
+use openssl::bn::{BigNum, MsbOption};
+use openssl::ec::{EcGroup, EcKey};
+use openssl::hash::MessageDigest;
+use openssl::nid::Nid;
+use openssl::pkey::{PKey, Private};
+use openssl::ssl::{SslAcceptor, SslMethod};
+use openssl::x509::{X509NameBuilder, X509};
+use serde_derive::Deserialize;
+use std::collections::HashSet;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+// Remote UIs are authenticated by token alone; logging in remotely with the database password is
+// out of scope. That's why checkPassword isn't here: it would let a read-only token guess the
+// password as fast as it could send messages. A token that needs it must name it in 'opcodes'.
+// logStream isn't here either, since every stream it starts is admin-only (see requires_admin).
+pub const READ_ONLY_OPCODES: [&str; 7] = [
+    "configuration",
+    "connectionStatus",
+    "descriptor",
+    "financials",
+    "financialsHistory",
+    "walletAddresses",
+    "walletBalances",
+];
+pub const SELF_SIGNED_CERT_FILENAME: &str = "ui_tls_cert.pem";
+pub const SELF_SIGNED_KEY_FILENAME: &str = "ui_tls_key.pem";
+pub const AUTHORIZATION_SCHEME: &str = "Bearer";
+const CERTIFICATE_VALIDITY_DAYS: u32 = 3650;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AccessFile {
+    #[serde(default)]
+    token: Vec<AccessFileEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AccessFileEntry {
+    token: String,
+    access: Option<String>,
+    opcodes: Option<Vec<String>>,
+}
+
+pub fn read_access_file(path: &Path) -> Result<Vec<UiAccessGrant>, String> {
+    let contents =
+        fs::read_to_string(path).map_err(|e| format!("Couldn't read {}: {}", path.display(), e))?;
+    parse_access_grants(&contents).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+pub fn parse_access_grants(contents: &str) -> Result<Vec<UiAccessGrant>, String> {
+    let file: AccessFile = toml::from_str(contents).map_err(|e| e.to_string())?;
+    if file.token.is_empty() {
+        return Err("No [[token]] entries; remote UIs would have no way in".to_string());
+    }
+    let mut tokens_seen = HashSet::new();
+    file.token
+        .into_iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            let position = index + 1;
+            if entry.token.trim().is_empty() {
+                return Err(format!("Token #{} is blank", position));
+            }
+            if !tokens_seen.insert(entry.token.clone()) {
+                return Err(format!("Token #{} duplicates an earlier token", position));
+            }
+            let permissions = permissions_from_entry(position, entry.access, entry.opcodes)?;
+            Ok(UiAccessGrant {
+                token: entry.token,
+                permissions,
+            })
+        })
+        .collect()
+}
+
+fn permissions_from_entry(
+    position: usize,
+    access_opt: Option<String>,
+    opcodes_opt: Option<Vec<String>>,
+) -> Result<UiPermissions, String> {
+    let mut opcodes: HashSet<String> = opcodes_opt.unwrap_or_default().into_iter().collect();
+    match access_opt.as_deref() {
+        Some("admin") => return Ok(UiPermissions::Admin),
+        Some("read-only") => {
+            opcodes.extend(READ_ONLY_OPCODES.iter().map(|opcode| opcode.to_string()))
+        }
+        Some(other) => {
+            return Err(format!(
+                "Token #{} has access '{}'; use 'admin' or 'read-only'",
+                position, other
+            ))
+        }
+        None => (),
+    }
+    if opcodes.is_empty() {
+        Err(format!(
+            "Token #{} permits nothing; give it an 'access' or some 'opcodes'",
+            position
+        ))
+    } else {
+        Ok(UiPermissions::Opcodes(opcodes))
+    }
+}
+
+// Takes the value of an Authorization header, such as "Bearer abc123"
+pub fn permissions_for(
+    authorization_opt: Option<&str>,
+    grants: &[UiAccessGrant],
+) -> Option<UiPermissions> {
+    let authorization = authorization_opt?;
+    let (scheme, token) = authorization.trim().split_once(' ')?;
+    if !scheme.eq_ignore_ascii_case(AUTHORIZATION_SCHEME) {
+        return None;
+    }
+    let token = token.trim();
+    // Every grant is examined, so the time taken says nothing about which one matched
+    grants
+        .iter()
+        .fold(None, |found_opt, grant| {
+            match constant_time_eq(grant.token.as_bytes(), token.as_bytes()) {
+                true => Some(grant),
+                false => found_opt,
+            }
+        })
+        .map(|grant| grant.permissions.clone())
+}
+
+// Requests only an admin token may make, whatever opcodes a token has been granted. A
+// configuration request carrying a password answers a right guess with the consuming wallet's
+// private key, and a log stream carries debug and trace logs, which name hosts and peers
+// whenever redaction is off.
+pub fn requires_admin(body: &MessageBody) -> bool {
+    match body.opcode.as_str() {
+        "configuration" => match UiConfigurationRequest::fmb(body.clone()) {
+            Ok((request, _)) => request.db_password_opt.is_some(),
+            Err(_) => false,
+        },
+        "logStream" => match UiLogStreamRequest::fmb(body.clone()) {
+            Ok((request, _)) => request.follow,
+            Err(_) => false,
+        },
+        _ => false,
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter()
+        .zip(b.iter())
+        .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+        == 0
+}
+
+pub fn make_tls_acceptor(config: &RemoteUiConfig) -> Result<SslAcceptor, String> {
+    let (chain, private_key) = match (&config.tls_cert_opt, &config.tls_key_opt) {
+        (Some(cert_path), Some(key_path)) => (
+            read_certificate_chain(cert_path)?,
+            read_private_key(key_path)?,
+        ),
+        (None, None) => load_or_make_self_signed(&config.data_directory)?,
+        _ => return Err("--ui-tls-cert and --ui-tls-key must be supplied together".to_string()),
+    };
+    let mut builder = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls()).map_err(stringify)?;
+    builder.set_private_key(&private_key).map_err(stringify)?;
+    let mut chain = chain.into_iter();
+    let leaf = chain
+        .next()
+        .ok_or_else(|| "Certificate file contains no certificates".to_string())?;
+    builder.set_certificate(&leaf).map_err(stringify)?;
+    for intermediate in chain {
+        builder
+            .add_extra_chain_cert(intermediate)
+            .map_err(stringify)?;
+    }
+    builder
+        .check_private_key()
+        .map_err(|_| "The UI TLS private key doesn't match the certificate".to_string())?;
+    Ok(builder.build())
+}
+
+fn read_certificate_chain(path: &Path) -> Result<Vec<X509>, String> {
+    let pem = fs::read(path).map_err(|e| format!("Couldn't read {}: {}", path.display(), e))?;
+    X509::stack_from_pem(&pem)
+        .map_err(|e| format!("{} holds no usable PEM certificates: {}", path.display(), e))
+}
+
+fn read_private_key(path: &Path) -> Result<PKey<Private>, String> {
+    let pem = fs::read(path).map_err(|e| format!("Couldn't read {}: {}", path.display(), e))?;
+    PKey::private_key_from_pem(&pem)
+        .map_err(|e| format!("{} holds no usable PEM private key: {}", path.display(), e))
+}
+
+// The certificate is kept, so that a masq that trusts it once goes on trusting it across restarts
+fn load_or_make_self_signed(data_directory: &Path) -> Result<(Vec<X509>, PKey<Private>), String> {
+    let cert_path = data_directory.join(SELF_SIGNED_CERT_FILENAME);
+    let key_path = data_directory.join(SELF_SIGNED_KEY_FILENAME);
+    if cert_path.exists() && key_path.exists() {
+        return Ok((
+            read_certificate_chain(&cert_path)?,
+            read_private_key(&key_path)?,
+        ));
+    }
+    let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).map_err(stringify)?;
+    let private_key =
+        PKey::from_ec_key(EcKey::generate(&group).map_err(stringify)?).map_err(stringify)?;
+    let certificate = make_self_signed_certificate(&private_key).map_err(stringify)?;
+    write_private_file(
+        &key_path,
+        &private_key.private_key_to_pem_pkcs8().map_err(stringify)?,
+    )?;
+    fs::write(&cert_path, certificate.to_pem().map_err(stringify)?)
+        .map_err(|e| format!("Couldn't write {}: {}", cert_path.display(), e))?;
+    Ok((vec![certificate], private_key))
+}
+
+fn make_self_signed_certificate(
+    private_key: &PKey<Private>,
+) -> Result<X509, openssl::error::ErrorStack> {
+    let mut name = X509NameBuilder::new()?;
+    name.append_entry_by_nid(Nid::COMMONNAME, "MASQ Node UI")?;
+    let name = name.build();
+    let mut serial = BigNum::new()?;
+    serial.rand(128, MsbOption::MAYBE_ZERO, false)?;
+    let mut builder = X509::builder()?;
+    builder.set_version(2)?;
+    builder.set_serial_number(serial.to_asn1_integer()?.as_ref())?;
+    builder.set_subject_name(&name)?;
+    builder.set_issuer_name(&name)?;
+    builder.set_pubkey(private_key)?;
+    builder.set_not_before(Asn1Time::days_from_now(0)?.as_ref())?;
+    builder.set_not_after(Asn1Time::days_from_now(CERTIFICATE_VALIDITY_DAYS)?.as_ref())?;
+    builder.sign(private_key, MessageDigest::sha256())?;
+    Ok(builder.build())
+}
+
+fn write_private_file(path: &Path, contents: &[u8]) -> Result<(), String> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options
+        .open(path)
+        .and_then(|mut file| file.write_all(contents))
+        .map_err(|e| format!("Couldn't write {}: {}", path.display(), e))
+}
+
+fn stringify(e: openssl::error::ErrorStack) -> String {
+    format!("UI TLS setup failed: {}", e)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use masq_lib::messages::{ToMessageBody, UiDescriptorRequest};
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+    use std::path::PathBuf;
+
+    #[test]
+    fn constants_have_correct_values() {
+        assert_eq!(
+            READ_ONLY_OPCODES,
+            [
+                "configuration",
+                "connectionStatus",
+                "descriptor",
+                "financials",
+                "financialsHistory",
+                "walletAddresses",
+                "walletBalances",
+            ]
+        );
+        assert_eq!(SELF_SIGNED_CERT_FILENAME, "ui_tls_cert.pem");
+        assert_eq!(SELF_SIGNED_KEY_FILENAME, "ui_tls_key.pem");
+        assert_eq!(AUTHORIZATION_SCHEME, "Bearer");
+        assert_eq!(CERTIFICATE_VALIDITY_DAYS, 3650);
+    }
+
+    fn opcodes(names: &[&str]) -> UiPermissions {
+        UiPermissions::Opcodes(names.iter().map(|name| name.to_string()).collect())
+    }
+
+    #[test]
+    fn access_grants_are_parsed() {
+        let contents = r#"
+            [[token]]
+            token = "admin-token"
+            access = "admin"
+
+            [[token]]
+            token = "watcher-token"
+            access = "read-only"
+            opcodes = ["scan"]
+
+            [[token]]
+            token = "shutdown-token"
+            opcodes = ["shutdown"]
+        "#;
+
+        let result = parse_access_grants(contents).unwrap();
+
+        let mut read_only_and_scan = READ_ONLY_OPCODES.to_vec();
+        read_only_and_scan.push("scan");
+        assert_eq!(
+            result,
+            vec![
+                UiAccessGrant {
+                    token: "admin-token".to_string(),
+                    permissions: UiPermissions::Admin,
+                },
+                UiAccessGrant {
+                    token: "watcher-token".to_string(),
+                    permissions: opcodes(&read_only_and_scan),
+                },
+                UiAccessGrant {
+                    token: "shutdown-token".to_string(),
+                    permissions: opcodes(&["shutdown"]),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn read_only_tokens_cannot_check_passwords_unless_told_they_may() {
+        let contents = r#"
+            [[token]]
+            token = "watcher-token"
+            access = "read-only"
+
+            [[token]]
+            token = "checker-token"
+            access = "read-only"
+            opcodes = ["checkPassword"]
+        "#;
+
+        let result = parse_access_grants(contents).unwrap();
+
+        assert_eq!(result[0].permissions.allows("checkPassword"), false);
+        assert_eq!(result[1].permissions.allows("checkPassword"), true);
+    }
+
+    #[test]
+    fn configuration_requests_with_a_password_require_admin() {
+        let with_password = UiConfigurationRequest {
+            db_password_opt: Some("guess".to_string()),
+        }
+        .tmb(1);
+        let without_password = UiConfigurationRequest {
+            db_password_opt: None,
+        }
+        .tmb(2);
+
+        assert_eq!(requires_admin(&with_password), true);
+        assert_eq!(requires_admin(&without_password), false);
+    }
+
+    #[test]
+    fn starting_a_log_stream_requires_admin() {
+        let make_request = |follow: bool| {
+            UiLogStreamRequest {
+                follow,
+                level: "trace".to_string(),
+                module_opt: None,
+            }
+            .tmb(1)
+        };
+
+        assert_eq!(requires_admin(&make_request(true)), true);
+        assert_eq!(requires_admin(&make_request(false)), false);
+    }
+
+    #[test]
+    fn other_requests_do_not_require_admin() {
+        assert_eq!(requires_admin(&UiDescriptorRequest {}.tmb(1)), false);
+    }
+
+    #[test]
+    fn bad_access_files_are_rejected() {
+        vec![
+            ("", "No [[token]] entries; remote UIs would have no way in"),
+            (
+                "[[token]]\ntoken = \" \"\naccess = \"admin\"",
+                "Token #1 is blank",
+            ),
+            (
+                "[[token]]\ntoken = \"a\"\naccess = \"admin\"\n[[token]]\ntoken = \"a\"\naccess = \"admin\"",
+                "Token #2 duplicates an earlier token",
+            ),
+            (
+                "[[token]]\ntoken = \"a\"\naccess = \"root\"",
+                "Token #1 has access 'root'; use 'admin' or 'read-only'",
+            ),
+            (
+                "[[token]]\ntoken = \"a\"\nopcodes = []",
+                "Token #1 permits nothing; give it an 'access' or some 'opcodes'",
+            ),
+        ]
+        .into_iter()
+        .for_each(|(contents, expected)| {
+            assert_eq!(
+                parse_access_grants(contents),
+                Err(expected.to_string()),
+                "{}",
+                contents
+            )
+        });
+    }
+
+    #[test]
+    fn unknown_fields_in_access_files_are_rejected() {
+        let result = parse_access_grants("[[token]]\ntoken = \"a\"\nacess = \"admin\"");
+
+        let msg = result.err().unwrap();
+        assert_eq!(msg.contains("unknown field `acess`"), true, "{}", msg);
+    }
+
+    #[test]
+    fn unreadable_access_file_is_reported() {
+        let path = PathBuf::from("/nonexistent/ui_access.toml");
+
+        let result = read_access_file(&path);
+
+        let msg = result.err().unwrap();
+        assert_eq!(
+            msg.starts_with("Couldn't read /nonexistent/ui_access.toml: "),
+            true,
+            "{}",
+            msg
+        );
+    }
+
+    #[test]
+    fn permissions_are_found_only_for_bearers_of_known_tokens() {
+        let grants = vec![
+            UiAccessGrant {
+                token: "admin-token".to_string(),
+                permissions: UiPermissions::Admin,
+            },
+            UiAccessGrant {
+                token: "watcher-token".to_string(),
+                permissions: opcodes(&["financials"]),
+            },
+        ];
+
+        assert_eq!(
+            permissions_for(Some("Bearer admin-token"), &grants),
+            Some(UiPermissions::Admin)
+        );
+        assert_eq!(
+            permissions_for(Some("bearer  watcher-token "), &grants),
+            Some(opcodes(&["financials"]))
+        );
+        assert_eq!(permissions_for(Some("Bearer admin"), &grants), None);
+        assert_eq!(permissions_for(Some("Basic admin-token"), &grants), None);
+        assert_eq!(permissions_for(Some("admin-token"), &grants), None);
+        assert_eq!(permissions_for(None, &grants), None);
+    }
+
+    #[test]
+    fn self_signed_identity_is_made_once_and_reused() {
+        let data_directory = ensure_node_home_directory_exists(
+            "remote_access",
+            "self_signed_identity_is_made_once_and_reused",
+        );
+        let config = RemoteUiConfig {
+            port: 0,
+            tls_cert_opt: None,
+            tls_key_opt: None,
+            data_directory: data_directory.clone(),
+            grants: vec![],
+        };
+
+        make_tls_acceptor(&config).unwrap();
+        let first_cert = fs::read(data_directory.join(SELF_SIGNED_CERT_FILENAME)).unwrap();
+        make_tls_acceptor(&config).unwrap();
+        let second_cert = fs::read(data_directory.join(SELF_SIGNED_CERT_FILENAME)).unwrap();
+
+        assert_eq!(first_cert, second_cert);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(data_directory.join(SELF_SIGNED_KEY_FILENAME))
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
+    #[test]
+    fn supplied_identity_is_used() {
+        let source_directory =
+            ensure_node_home_directory_exists("remote_access", "supplied_identity_is_used_source");
+        let data_directory =
+            ensure_node_home_directory_exists("remote_access", "supplied_identity_is_used_data");
+        make_tls_acceptor(&RemoteUiConfig {
+            port: 0,
+            tls_cert_opt: None,
+            tls_key_opt: None,
+            data_directory: source_directory.clone(),
+            grants: vec![],
+        })
+        .unwrap();
+
+        let result = make_tls_acceptor(&RemoteUiConfig {
+            port: 0,
+            tls_cert_opt: Some(source_directory.join(SELF_SIGNED_CERT_FILENAME)),
+            tls_key_opt: Some(source_directory.join(SELF_SIGNED_KEY_FILENAME)),
+            data_directory: data_directory.clone(),
+            grants: vec![],
+        });
+
+        assert_eq!(result.is_ok(), true);
+        assert_eq!(
+            data_directory.join(SELF_SIGNED_CERT_FILENAME).exists(),
+            false
+        );
+    }
+
+    #[test]
+    fn mismatched_key_is_rejected() {
+        let first_directory =
+            ensure_node_home_directory_exists("remote_access", "mismatched_key_is_rejected_1");
+        let second_directory =
+            ensure_node_home_directory_exists("remote_access", "mismatched_key_is_rejected_2");
+        let make_config = |directory: &PathBuf| RemoteUiConfig {
+            port: 0,
+            tls_cert_opt: None,
+            tls_key_opt: None,
+            data_directory: directory.clone(),
+            grants: vec![],
+        };
+        make_tls_acceptor(&make_config(&first_directory)).unwrap();
+        make_tls_acceptor(&make_config(&second_directory)).unwrap();
+
+        let result = make_tls_acceptor(&RemoteUiConfig {
+            tls_cert_opt: Some(first_directory.join(SELF_SIGNED_CERT_FILENAME)),
+            tls_key_opt: Some(second_directory.join(SELF_SIGNED_KEY_FILENAME)),
+            ..make_config(&first_directory)
+        });
+
+        assert_eq!(
+            result.err(),
+            Some("The UI TLS private key doesn't match the certificate".to_string())
+        );
+    }
+
+    #[test]
+    fn certificate_without_key_is_rejected() {
+        let result = make_tls_acceptor(&RemoteUiConfig {
+            port: 0,
+            tls_cert_opt: Some(PathBuf::from("cert.pem")),
+            tls_key_opt: None,
+            data_directory: PathBuf::from("irrelevant"),
+            grants: vec![],
+        });
+
+        assert_eq!(
+            result.err(),
+            Some("--ui-tls-cert and --ui-tls-key must be supplied together".to_string())
+        );
+    }
+}
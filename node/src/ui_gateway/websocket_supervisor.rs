@@ -1,4 +1,6 @@
 // Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use crate::sub_lib::ui_gateway::{RemoteUiConfig, UiAccessGrant, UiGatewayConfig, UiPermissions};
+use crate::ui_gateway::remote_access::{make_tls_acceptor, permissions_for, requires_admin};
 use actix::Recipient;
 use bytes::BytesMut;
 use futures::future::FutureResult;
@@ -8,9 +10,10 @@ use futures::stream::SplitSink;
 use futures::Future;
 use futures::Sink;
 use futures::Stream;
-use masq_lib::constants::UNMARSHAL_ERROR;
-use masq_lib::logger::Logger;
+use masq_lib::constants::{UNAUTHORIZED_OPCODE_ERROR, UNMARSHAL_ERROR};
+use masq_lib::logger::{unsubscribe_from_log_stream, Logger};
 use masq_lib::messages::{ToMessageBody, UiUnmarshalError, NODE_UI_PROTOCOL};
+use masq_lib::redaction::MarkSensitive;
 use masq_lib::ui_gateway::MessagePath::Conversation;
 use masq_lib::ui_gateway::MessageTarget::ClientId;
 use masq_lib::ui_gateway::{MessageBody, MessageTarget, NodeFromUiMessage, NodeToUiMessage};
@@ -21,12 +24,15 @@ use std::any::Any;
 use std::collections::HashMap;
 use std::fmt::Debug;
 use std::io::ErrorKind;
-use std::net::SocketAddr;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::reactor::Handle;
+use tokio_openssl::SslAcceptorExt;
 use websocket::client::r#async::Framed;
+use websocket::r#async::server::IntoWs;
 use websocket::r#async::MessageCodec;
-use websocket::r#async::TcpStream;
+use websocket::r#async::TcpListener;
 use websocket::server::r#async::Server;
 use websocket::server::upgrade::WsUpgrade;
 use websocket::OwnedMessage;
@@ -38,11 +44,15 @@ trait ClientWrapper: Send + Any {
     fn flush(&mut self) -> Result<(), WebSocketError>;
 }
 
-struct ClientWrapperReal {
-    delegate: Wait<SplitSink<Framed<TcpStream, MessageCodec<OwnedMessage>>>>,
+// Local UIs connect over plain TCP, remote ones over TLS
+trait UiStream: AsyncRead + AsyncWrite + Send + 'static {}
+impl<S: AsyncRead + AsyncWrite + Send + 'static> UiStream for S {}
+
+struct ClientWrapperReal<S: UiStream> {
+    delegate: Wait<SplitSink<Framed<S, MessageCodec<OwnedMessage>>>>,
 }
 
-impl ClientWrapper for ClientWrapperReal {
+impl<S: UiStream> ClientWrapper for ClientWrapperReal<S> {
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -72,6 +82,8 @@ struct WebSocketSupervisorInner {
     client_id_by_socket_addr: HashMap<SocketAddr, u64>,
     socket_addr_by_client_id: HashMap<u64, SocketAddr>,
     client_by_id: HashMap<u64, Box<dyn ClientWrapper>>,
+    // Only remote clients are restricted; local ones may send anything
+    permissions_by_client_id: HashMap<u64, UiPermissions>,
 }
 
 impl WebSocketSupervisor for WebSocketSupervisorReal {
@@ -92,6 +104,7 @@ impl WebSocketSupervisorReal {
             client_id_by_socket_addr: HashMap::new(),
             socket_addr_by_client_id: HashMap::new(),
             client_by_id: HashMap::new(),
+            permissions_by_client_id: HashMap::new(),
         }));
         let logger = Logger::new("WebSocketSupervisor");
         let logger_1 = logger.clone();
@@ -101,7 +114,7 @@ impl WebSocketSupervisorReal {
         let upgrade_tuple_stream = Self::remove_failures(server.incoming(), &logger);
         let inner_clone = inner.clone();
         let foreach_result = upgrade_tuple_stream.for_each(move |(upgrade, socket_addr)| {
-            Self::handle_upgrade_request(upgrade, socket_addr, inner_clone.clone(), &logger);
+            Self::handle_upgrade_request(upgrade, socket_addr, inner_clone.clone(), None, &logger);
             Ok(())
         });
         tokio::spawn(foreach_result.then(move |result| match result {
@@ -120,6 +133,69 @@ impl WebSocketSupervisorReal {
         Ok(Box::new(WebSocketSupervisorReal { inner }))
     }
 
+    pub fn open_remote_listener(&self, remote: &RemoteUiConfig) -> std::io::Result<()> {
+        let logger = Logger::new("WebSocketSupervisor");
+        let acceptor = make_tls_acceptor(remote)
+            .map_err(|e| std::io::Error::new(ErrorKind::InvalidInput, e))?;
+        let listener_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), remote.port);
+        let listener = TcpListener::bind(&listener_address)?;
+        info!(
+            logger,
+            "Accepting remote UI connections over TLS on port {}", remote.port
+        );
+        let grants = Arc::new(remote.grants.clone());
+        let inner = self.inner.clone();
+        let logger_clone = logger.clone();
+        let connections =
+            Self::remove_failures(listener.incoming(), &logger).for_each(move |stream| {
+                let socket_addr = match stream.peer_addr() {
+                    Ok(socket_addr) => socket_addr,
+                    Err(_) => return Ok(()),
+                };
+                let (inner, grants) = (inner.clone(), grants.clone());
+                let (logger_1, logger_2, logger_3) =
+                    (logger.clone(), logger.clone(), logger.clone());
+                let upgrade_future = acceptor
+                    .accept_async(stream)
+                    .map_err(move |e| {
+                        warning!(
+                            logger_1,
+                            "TLS handshake with remote UI at {} failed: {:?}",
+                            socket_addr.sensitive(),
+                            e
+                        )
+                    })
+                    .and_then(move |tls_stream| {
+                        tls_stream.into_ws().map_err(move |(_, _, _, e)| {
+                            warning!(
+                                logger_2,
+                                "Remote UI at {} did not ask for a WebSocket: {:?}",
+                                socket_addr.sensitive(),
+                                e
+                            )
+                        })
+                    })
+                    .map(move |upgrade| {
+                        Self::handle_remote_upgrade_request(
+                            upgrade,
+                            socket_addr,
+                            inner,
+                            &grants,
+                            &logger_3,
+                        )
+                    });
+                tokio::spawn(upgrade_future);
+                Ok(())
+            });
+        tokio::spawn(connections.map_err(move |_| {
+            error!(
+                logger_clone,
+                "WebSocketSupervisor stopped accepting remote UI connections"
+            )
+        }));
+        Ok(())
+    }
+
     fn filter_clients<'a, P>(
         locked_inner: &'a mut MutexGuard<WebSocketSupervisorInner>,
         predicate: P,
@@ -194,36 +270,85 @@ impl WebSocketSupervisorReal {
             .map(|option| option.expect("A None magically got through the filter"))
     }
 
-    fn handle_upgrade_request(
-        upgrade: WsUpgrade<TcpStream, BytesMut>,
+    fn handle_remote_upgrade_request<S: UiStream>(
+        upgrade: WsUpgrade<S, BytesMut>,
+        socket_addr: SocketAddr,
+        inner: Arc<Mutex<WebSocketSupervisorInner>>,
+        grants: &[UiAccessGrant],
+        logger: &Logger,
+    ) {
+        let authorization_opt = upgrade
+            .request
+            .headers
+            .get_raw("Authorization")
+            .and_then(|values| values.first())
+            .and_then(|value| std::str::from_utf8(value).ok());
+        match permissions_for(authorization_opt, grants) {
+            Some(permissions) => {
+                Self::handle_upgrade_request(upgrade, socket_addr, inner, Some(permissions), logger)
+            }
+            None => {
+                warning!(
+                    logger,
+                    "Remote UI at {} presented no valid token; refusing connection",
+                    socket_addr.sensitive()
+                );
+                tokio::spawn(upgrade.reject().then(|_| ok::<(), ()>(())));
+            }
+        }
+    }
+
+    fn handle_upgrade_request<S: UiStream>(
+        upgrade: WsUpgrade<S, BytesMut>,
         socket_addr: SocketAddr,
         inner: Arc<Mutex<WebSocketSupervisorInner>>,
+        permissions_opt: Option<UiPermissions>,
         logger: &Logger,
     ) {
         if upgrade
             .protocols()
             .contains(&String::from(NODE_UI_PROTOCOL))
         {
-            Self::accept_upgrade_request(upgrade, socket_addr, inner, logger);
+            Self::accept_upgrade_request(upgrade, socket_addr, inner, permissions_opt, logger);
         } else {
             Self::reject_upgrade_request(upgrade, logger);
         }
     }
 
-    fn accept_upgrade_request(
-        upgrade: WsUpgrade<TcpStream, BytesMut>,
+    fn accept_upgrade_request<S: UiStream>(
+        upgrade: WsUpgrade<S, BytesMut>,
         socket_addr: SocketAddr,
         inner: Arc<Mutex<WebSocketSupervisorInner>>,
+        permissions_opt: Option<UiPermissions>,
         logger: &Logger,
     ) {
         let logger_clone = logger.clone();
-        info!(logger_clone, "UI connected at {}", socket_addr);
+        match &permissions_opt {
+            None => info!(logger_clone, "UI connected at {}", socket_addr.sensitive()),
+            Some(UiPermissions::Admin) => info!(
+                logger_clone,
+                "Remote UI connected at {} with admin access",
+                socket_addr.sensitive()
+            ),
+            Some(UiPermissions::Opcodes(opcodes)) => info!(
+                logger_clone,
+                "Remote UI connected at {} with access to {} opcode(s)",
+                socket_addr.sensitive(),
+                opcodes.len()
+            ),
+        }
         let upgrade_future =
             upgrade
                 .use_protocol(NODE_UI_PROTOCOL)
                 .accept()
                 .map(move |(client, _)| {
-                    Self::handle_connection(client, &inner, &logger_clone, socket_addr);
+                    Self::handle_connection(
+                        client,
+                        &inner,
+                        &logger_clone,
+                        socket_addr,
+                        permissions_opt,
+                    );
                 });
         tokio::spawn(upgrade_future.then(|result| {
             match result {
@@ -233,7 +358,7 @@ impl WebSocketSupervisorReal {
         }));
     }
 
-    fn reject_upgrade_request(upgrade: WsUpgrade<TcpStream, BytesMut>, logger: &Logger) {
+    fn reject_upgrade_request<S: UiStream>(upgrade: WsUpgrade<S, BytesMut>, logger: &Logger) {
         info!(
             logger,
             "UI attempted connection without protocol {}: {:?}",
@@ -243,11 +368,12 @@ impl WebSocketSupervisorReal {
         tokio::spawn(upgrade.reject().then(|_| ok::<(), ()>(())));
     }
 
-    fn handle_connection(
-        client: Framed<TcpStream, MessageCodec<OwnedMessage>>,
+    fn handle_connection<S: UiStream>(
+        client: Framed<S, MessageCodec<OwnedMessage>>,
         inner: &Arc<Mutex<WebSocketSupervisorInner>>,
         logger: &Logger,
         socket_addr: SocketAddr,
+        permissions_opt: Option<UiPermissions>,
     ) {
         let logger_1 = logger.clone();
         let logger_2 = logger.clone();
@@ -268,6 +394,11 @@ impl WebSocketSupervisorReal {
             .socket_addr_by_client_id
             .insert(client_id, socket_addr);
         locked_inner.client_by_id.insert(client_id, client_wrapper);
+        if let Some(permissions) = permissions_opt {
+            locked_inner
+                .permissions_by_client_id
+                .insert(client_id, permissions);
+        }
         let incoming_future = incoming
             .then(move |result| Self::handle_websocket_errors(result, &logger_2, socket_addr))
             .map(move |owned_message| match owned_message {
@@ -315,6 +446,39 @@ impl WebSocketSupervisorReal {
             }
         };
         match UiTrafficConverter::new_unmarshal_from_ui(message, client_id) {
+            Ok(from_ui_message)
+                if !Self::is_permitted(&locked_inner, client_id, &from_ui_message.body) =>
+            {
+                warning!(
+                    logger,
+                    "Refusing '{}' from remote UI client {} at {}: its token does not permit that",
+                    from_ui_message.body.opcode,
+                    client_id,
+                    socket_addr.sensitive()
+                );
+                let body = from_ui_message.body;
+                if let Conversation(context_id) = body.path {
+                    Self::send_msg_safely(
+                        locked_inner,
+                        inner_arc,
+                        NodeToUiMessage {
+                            target: ClientId(client_id),
+                            body: MessageBody {
+                                payload: Err((
+                                    UNAUTHORIZED_OPCODE_ERROR,
+                                    format!(
+                                        "This connection's token does not permit '{}'",
+                                        body.opcode
+                                    ),
+                                )),
+                                opcode: body.opcode,
+                                path: Conversation(context_id),
+                            },
+                        },
+                    );
+                }
+                return ok::<(), ()>(());
+            }
             Ok(from_ui_message) => {
                 locked_inner
                     .from_ui_message_sub
@@ -326,7 +490,7 @@ impl WebSocketSupervisorReal {
                     logger,
                     "Bad message from client {} at {}: {}:\n{}\n",
                     client_id,
-                    socket_addr,
+                    socket_addr.sensitive(),
                     Critical(e.clone()),
                     message
                 );
@@ -349,7 +513,7 @@ impl WebSocketSupervisorReal {
                     logger,
                     "Bad message from client {} at {}: {}:\n{}\n",
                     client_id,
-                    socket_addr,
+                    socket_addr.sensitive(),
                     NonCritical(opcode.clone(), context_id_opt, e.clone()),
                     message
                 );
@@ -385,6 +549,17 @@ impl WebSocketSupervisorReal {
         ok::<(), ()>(())
     }
 
+    fn is_permitted(
+        locked_inner: &WebSocketSupervisorInner,
+        client_id: u64,
+        body: &MessageBody,
+    ) -> bool {
+        match locked_inner.permissions_by_client_id.get(&client_id) {
+            None | Some(UiPermissions::Admin) => true,
+            Some(permissions) => permissions.allows(&body.opcode) && !requires_admin(body),
+        }
+    }
+
     fn handle_close_message(
         inner_arc: &Arc<Mutex<WebSocketSupervisorInner>>,
         logger: &Logger,
@@ -404,7 +579,7 @@ impl WebSocketSupervisorReal {
         info!(
             logger,
             "UI at {} (client ID {}) disconnected from port {}",
-            socket_addr,
+            socket_addr.sensitive(),
             client_id,
             locked_inner.port
         );
@@ -420,7 +595,9 @@ impl WebSocketSupervisorReal {
     ) -> FutureResult<(), ()> {
         info!(
             logger,
-            "UI at {} sent unexpected {} message; ignoring", socket_addr, message_type
+            "UI at {} sent unexpected {} message; ignoring",
+            socket_addr.sensitive(),
+            message_type
         );
         ok::<(), ()>(())
     }
@@ -487,7 +664,9 @@ impl WebSocketSupervisorReal {
     }
 
     fn emergency_client_removal(client_id: u64, inner_arc: &Arc<Mutex<WebSocketSupervisorInner>>) {
+        unsubscribe_from_log_stream(client_id);
         let mut locked_inner = inner_arc.lock().expect("WebSocketSupervisor is poisoned");
+        locked_inner.permissions_by_client_id.remove(&client_id);
         locked_inner
             .client_by_id
             .remove(&client_id)
@@ -512,7 +691,7 @@ impl WebSocketSupervisorReal {
                 warning!(
                     logger,
                     "UI at {} violated protocol ({:?}): terminating",
-                    socket_addr,
+                    socket_addr.sensitive(),
                     e
                 );
                 err::<I, ()>(())
@@ -527,7 +706,9 @@ impl WebSocketSupervisorReal {
         socket_addr: SocketAddr,
         logger: &Logger,
     ) {
+        unsubscribe_from_log_stream(client_id);
         let _ = locked_inner.socket_addr_by_client_id.remove(&client_id);
+        let _ = locked_inner.permissions_by_client_id.remove(&client_id);
         let mut client = match locked_inner.client_by_id.remove(&client_id) {
             Some(client) => client,
             None => panic!("WebSocketSupervisor got a disconnect from a client that has disappeared from the stable!"),
@@ -536,7 +717,7 @@ impl WebSocketSupervisorReal {
             Err(e) => warning!(
                 logger,
                 "Error acknowledging connection closure from UI at {}: {:?}",
-                socket_addr,
+                socket_addr.sensitive(),
                 e
             ),
             Ok(_) => {
@@ -544,7 +725,7 @@ impl WebSocketSupervisorReal {
                     warning!(
                         logger,
                         "Couldn't flush transmission to UI at {}, client dumped anyway",
-                        socket_addr
+                        socket_addr.sensitive()
                     )
                 });
             }
@@ -568,7 +749,7 @@ enum SendToClientWebsocketError {
 pub trait WebSocketSupervisorFactory: Send {
     fn make(
         &self,
-        port: u16,
+        config: &UiGatewayConfig,
         recipient: Recipient<NodeFromUiMessage>,
     ) -> std::io::Result<Box<dyn WebSocketSupervisor>>;
 }
@@ -578,11 +759,14 @@ pub struct WebsocketSupervisorFactoryReal;
 impl WebSocketSupervisorFactory for WebsocketSupervisorFactoryReal {
     fn make(
         &self,
-        port: u16,
+        config: &UiGatewayConfig,
         recipient: Recipient<NodeFromUiMessage>,
     ) -> std::io::Result<Box<dyn WebSocketSupervisor>> {
-        WebSocketSupervisorReal::new(port, recipient)
-            .map(|positive| positive as Box<dyn WebSocketSupervisor>)
+        let supervisor = WebSocketSupervisorReal::new(config.ui_port, recipient)?;
+        if let Some(remote) = &config.remote_ui_opt {
+            supervisor.open_remote_listener(remote)?;
+        }
+        Ok(supervisor as Box<dyn WebSocketSupervisor>)
     }
 }
 
@@ -595,18 +779,23 @@ mod tests {
     use actix::{Actor, Addr};
     use crossbeam_channel::bounded;
     use futures::lazy;
+    use log::Level;
     use masq_lib::constants::UNMARSHAL_ERROR;
+    use masq_lib::logger::{subscribe_to_log_stream, LogStreamSubscription};
     use masq_lib::messages::{
-        FromMessageBody, UiDescriptorResponse, UiShutdownRequest, UiStartOrder, UiUnmarshalError,
-        NODE_UI_PROTOCOL,
+        FromMessageBody, ToMessageBody, UiConfigurationRequest, UiDescriptorRequest,
+        UiDescriptorResponse, UiLogStreamRequest, UiShutdownRequest, UiStartOrder,
+        UiUnmarshalError, NODE_UI_PROTOCOL,
     };
     use masq_lib::test_utils::logging::init_test_logging;
     use masq_lib::test_utils::logging::TestLogHandler;
     use masq_lib::test_utils::ui_connection::UiConnection;
+    use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
     use masq_lib::ui_gateway::MessagePath::FireAndForget;
     use masq_lib::ui_gateway::NodeFromUiMessage;
     use masq_lib::ui_traffic_converter::UiTrafficConverter;
     use masq_lib::utils::{find_free_port, localhost};
+    use openssl::ssl::{SslConnector, SslMethod, SslStream, SslVerifyMode};
     use std::cell::RefCell;
     use std::io::{Error, ErrorKind};
     use std::net::{IpAddr, Ipv4Addr, Shutdown};
@@ -615,6 +804,7 @@ mod tests {
     use std::time::Duration;
     use tokio::runtime::Runtime;
     use websocket::client::sync::Client;
+    use websocket::header::Headers;
     use websocket::r#async::TcpStream as TcpStreamAsync;
     use websocket::stream::sync::TcpStream;
     use websocket::ClientBuilder;
@@ -803,6 +993,7 @@ mod tests {
                     &inner_arc,
                     &logger,
                     socket_addr,
+                    None,
                 );
                 //^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
                 Ok(())
@@ -1080,6 +1271,7 @@ mod tests {
             client_id_by_socket_addr: Default::default(),
             socket_addr_by_client_id: Default::default(),
             client_by_id: Default::default(),
+            permissions_by_client_id: Default::default(),
         }
     }
 
@@ -1238,6 +1430,7 @@ mod tests {
             client_id_by_socket_addr,
             socket_addr_by_client_id,
             client_by_id,
+            permissions_by_client_id: HashMap::new(),
         }));
         let msg = NodeToUiMessage {
             target: ClientId(123),
@@ -1374,6 +1567,30 @@ mod tests {
         //the third hashmap is supposed to be cleared a step before this fn call
     }
 
+    #[test]
+    fn close_connection_ends_the_log_stream_subscription_of_the_client() {
+        let client_id = 7_654_321;
+        let mut inner = make_ordinary_inner();
+        let mock_client = ClientWrapperMock::new()
+            .send_result(Ok(()))
+            .flush_result(Ok(()));
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4)), 44444);
+        inner.client_by_id.insert(client_id, Box::new(mock_client));
+        inner
+            .socket_addr_by_client_id
+            .insert(client_id, socket_addr);
+        subscribe_to_log_stream(LogStreamSubscription::new(client_id, Level::Debug, None));
+
+        WebSocketSupervisorReal::close_connection(
+            &mut inner,
+            client_id,
+            socket_addr,
+            &Logger::new("close_connection_test"),
+        );
+
+        assert_eq!(unsubscribe_from_log_stream(client_id), false);
+    }
+
     #[test]
     fn a_client_that_violates_the_protocol_is_terminated() {
         let port = find_free_port();
@@ -1577,4 +1794,249 @@ mod tests {
             "WebsocketSupervisor: WARN: Tried to send to an absent client 7",
         );
     }
+
+    fn make_remote_client(
+        port: u16,
+        token_opt: Option<&str>,
+    ) -> Result<Client<SslStream<std::net::TcpStream>>, WebSocketError> {
+        let mut connector_builder = SslConnector::builder(SslMethod::tls()).unwrap();
+        connector_builder.set_verify(SslVerifyMode::NONE);
+        let tcp_stream = std::net::TcpStream::connect(SocketAddr::new(localhost(), port))?;
+        let tls_stream = connector_builder
+            .build()
+            .connect("localhost", tcp_stream)
+            .map_err(|e| WebSocketError::from(Error::new(ErrorKind::Other, format!("{:?}", e))))?;
+        let mut headers = Headers::new();
+        if let Some(token) = token_opt {
+            headers.set_raw(
+                "Authorization",
+                vec![format!("Bearer {}", token).into_bytes()],
+            );
+        }
+        ClientBuilder::new(format!("wss://localhost:{}", port).as_str())
+            .unwrap()
+            .add_protocol(NODE_UI_PROTOCOL)
+            .custom_headers(&headers)
+            .connect_on(tls_stream)
+    }
+
+    fn start_remote_supervisor(
+        test_name: &'static str,
+        remote_port: u16,
+        ui_gateway: Recorder,
+    ) -> Vec<UiAccessGrant> {
+        let data_directory = ensure_node_home_directory_exists("websocket_supervisor", test_name);
+        let grants = vec![
+            UiAccessGrant {
+                token: "admin-token".to_string(),
+                permissions: UiPermissions::Admin,
+            },
+            UiAccessGrant {
+                token: "viewer-token".to_string(),
+                permissions: UiPermissions::Opcodes(
+                    vec!["configuration", "descriptor", "logStream"]
+                        .into_iter()
+                        .map(|opcode| opcode.to_string())
+                        .collect(),
+                ),
+            },
+        ];
+        let config = UiGatewayConfig {
+            ui_port: find_free_port(),
+            remote_ui_opt: Some(RemoteUiConfig {
+                port: remote_port,
+                tls_cert_opt: None,
+                tls_key_opt: None,
+                data_directory,
+                grants: grants.clone(),
+            }),
+        };
+        thread::spawn(move || {
+            let system = System::new(test_name);
+            let ui_message_sub = subs(ui_gateway);
+            let subject = lazy(move || {
+                let _subject = WebsocketSupervisorFactoryReal
+                    .make(&config, ui_message_sub)
+                    .unwrap();
+                Ok(())
+            });
+            actix::spawn(subject);
+            system.run();
+        });
+        grants
+    }
+
+    #[test]
+    fn remote_ui_without_a_valid_token_is_refused() {
+        init_test_logging();
+        let remote_port = find_free_port();
+        let (ui_gateway, _, _) = make_recorder();
+        start_remote_supervisor(
+            "remote_ui_without_a_valid_token_is_refused",
+            remote_port,
+            ui_gateway,
+        );
+        await_value(None, || {
+            make_remote_client(remote_port, Some("admin-token"))
+        })
+        .unwrap();
+
+        let wrong_token_result = make_remote_client(remote_port, Some("guessed-token"));
+        let no_token_result = make_remote_client(remote_port, None);
+
+        assert_eq!(wrong_token_result.is_err(), true);
+        assert_eq!(no_token_result.is_err(), true);
+        let tlh = TestLogHandler::new();
+        tlh.await_log_matching(
+            "Remote UI at 127\\.0\\.0\\.1:\\d+ presented no valid token; refusing connection",
+            1000,
+        );
+        tlh.exists_no_log_containing("guessed-token");
+    }
+
+    #[test]
+    fn remote_ui_is_held_to_the_opcodes_its_token_permits() {
+        init_test_logging();
+        let remote_port = find_free_port();
+        let (ui_gateway, ui_gateway_awaiter, ui_gateway_recording_arc) = make_recorder();
+        start_remote_supervisor(
+            "remote_ui_is_held_to_the_opcodes_its_token_permits",
+            remote_port,
+            ui_gateway,
+        );
+        let mut client = await_value(None, || {
+            make_remote_client(remote_port, Some("viewer-token"))
+        })
+        .unwrap();
+
+        client
+            .send_message(&OwnedMessage::Text(UiTrafficConverter::new_marshal(
+                UiShutdownRequest {}.tmb(1),
+            )))
+            .unwrap();
+        let refusal = match client.recv_message().unwrap() {
+            OwnedMessage::Text(json) => {
+                UiTrafficConverter::new_unmarshal_to_ui(&json, ClientId(0)).unwrap()
+            }
+            x => panic!("Expected OwnedMessage::Text, got {:?}", x),
+        };
+        client
+            .send_message(&OwnedMessage::Text(UiTrafficConverter::new_marshal(
+                UiDescriptorRequest {}.tmb(2),
+            )))
+            .unwrap();
+
+        assert_eq!(
+            refusal.body,
+            MessageBody {
+                opcode: "shutdown".to_string(),
+                path: Conversation(1),
+                payload: Err((
+                    UNAUTHORIZED_OPCODE_ERROR,
+                    "This connection's token does not permit 'shutdown'".to_string()
+                )),
+            }
+        );
+        ui_gateway_awaiter.await_message_count(1);
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        let forwarded = ui_gateway_recording.get_record::<NodeFromUiMessage>(0);
+        assert_eq!(forwarded.body, UiDescriptorRequest {}.tmb(2));
+        assert_eq!(ui_gateway_recording.len(), 1);
+        TestLogHandler::new().exists_log_matching(
+            "Refusing 'shutdown' from remote UI client \\d+ at 127\\.0\\.0\\.1:\\d+: its token \
+             does not permit that",
+        );
+    }
+
+    #[test]
+    fn remote_ui_needs_an_admin_token_for_passwords_and_log_streams() {
+        init_test_logging();
+        let remote_port = find_free_port();
+        let (ui_gateway, ui_gateway_awaiter, ui_gateway_recording_arc) = make_recorder();
+        start_remote_supervisor(
+            "remote_ui_needs_an_admin_token_for_passwords_and_log_streams",
+            remote_port,
+            ui_gateway,
+        );
+        let mut viewer = await_value(None, || {
+            make_remote_client(remote_port, Some("viewer-token"))
+        })
+        .unwrap();
+        let mut admin = make_remote_client(remote_port, Some("admin-token")).unwrap();
+        let password_request = UiConfigurationRequest {
+            db_password_opt: Some("guess".to_string()),
+        };
+        let log_stream_request = UiLogStreamRequest {
+            follow: true,
+            level: "trace".to_string(),
+            module_opt: None,
+        };
+        let exchange = |client: &mut Client<SslStream<std::net::TcpStream>>, body: MessageBody| {
+            client
+                .send_message(&OwnedMessage::Text(UiTrafficConverter::new_marshal(body)))
+                .unwrap();
+            match client.recv_message().unwrap() {
+                OwnedMessage::Text(json) => {
+                    UiTrafficConverter::new_unmarshal_to_ui(&json, ClientId(0))
+                        .unwrap()
+                        .body
+                }
+                x => panic!("Expected OwnedMessage::Text, got {:?}", x),
+            }
+        };
+
+        let password_refusal = exchange(&mut viewer, password_request.clone().tmb(1));
+        let log_stream_refusal = exchange(&mut viewer, log_stream_request.clone().tmb(2));
+        viewer
+            .send_message(&OwnedMessage::Text(UiTrafficConverter::new_marshal(
+                UiConfigurationRequest {
+                    db_password_opt: None,
+                }
+                .tmb(3),
+            )))
+            .unwrap();
+        ui_gateway_awaiter.await_message_count(1);
+        admin
+            .send_message(&OwnedMessage::Text(UiTrafficConverter::new_marshal(
+                password_request.clone().tmb(4),
+            )))
+            .unwrap();
+        admin
+            .send_message(&OwnedMessage::Text(UiTrafficConverter::new_marshal(
+                log_stream_request.clone().tmb(5),
+            )))
+            .unwrap();
+
+        let refusal = |opcode: &str, context_id: u64| MessageBody {
+            opcode: opcode.to_string(),
+            path: Conversation(context_id),
+            payload: Err((
+                UNAUTHORIZED_OPCODE_ERROR,
+                format!("This connection's token does not permit '{}'", opcode),
+            )),
+        };
+        assert_eq!(password_refusal, refusal("configuration", 1));
+        assert_eq!(log_stream_refusal, refusal("logStream", 2));
+        ui_gateway_awaiter.await_message_count(3);
+        let ui_gateway_recording = ui_gateway_recording_arc.lock().unwrap();
+        let forwarded = (0..3)
+            .map(|index| {
+                ui_gateway_recording
+                    .get_record::<NodeFromUiMessage>(index)
+                    .body
+                    .clone()
+            })
+            .collect::<Vec<MessageBody>>();
+        assert_eq!(
+            forwarded,
+            vec![
+                UiConfigurationRequest {
+                    db_password_opt: None
+                }
+                .tmb(3),
+                password_request.tmb(4),
+                log_stream_request.tmb(5),
+            ]
+        );
+    }
 }
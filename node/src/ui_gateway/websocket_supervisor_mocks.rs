@@ -1,6 +1,7 @@
 // Copyright (c) 2019-2021, MASQ (https://masq.ai). All rights reserved.
 #![cfg(test)]
 
+use crate::sub_lib::ui_gateway::UiGatewayConfig;
 use crate::ui_gateway::websocket_supervisor::{WebSocketSupervisor, WebSocketSupervisorFactory};
 use actix::Recipient;
 use masq_lib::ui_gateway::{NodeFromUiMessage, NodeToUiMessage};
@@ -36,10 +37,16 @@ impl WebSocketSupervisorMock {
 
 #[derive(Default)]
 pub struct WebsocketSupervisorFactoryMock {
+    make_params: Arc<Mutex<Vec<UiGatewayConfig>>>,
     make_results: RefCell<Vec<std::io::Result<Box<dyn WebSocketSupervisor>>>>,
 }
 
 impl WebsocketSupervisorFactoryMock {
+    pub fn make_params(mut self, params: &Arc<Mutex<Vec<UiGatewayConfig>>>) -> Self {
+        self.make_params = params.clone();
+        self
+    }
+
     pub fn make_result(self, result: std::io::Result<Box<dyn WebSocketSupervisor>>) -> Self {
         self.make_results.borrow_mut().push(result);
         self
@@ -49,9 +56,10 @@ impl WebsocketSupervisorFactoryMock {
 impl WebSocketSupervisorFactory for WebsocketSupervisorFactoryMock {
     fn make(
         &self,
-        _port: u16,
+        config: &UiGatewayConfig,
         _recipient: Recipient<NodeFromUiMessage>,
     ) -> std::io::Result<Box<dyn WebSocketSupervisor>> {
+        self.make_params.lock().unwrap().push(config.clone());
         self.make_results.borrow_mut().remove(0)
     }
 }
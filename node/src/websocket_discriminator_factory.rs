@@ -0,0 +1,58 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use crate::discriminator::Discriminator;
+use crate::discriminator::DiscriminatorFactory;
+use crate::websocket_framer::WebSocketFramer;
+use crate::websocket_masquerader::WebSocketMasquerader;
+
+#[derive(Debug, Default)]
+pub struct WebSocketDiscriminatorFactory {}
+
+impl DiscriminatorFactory for WebSocketDiscriminatorFactory {
+    fn make(&self) -> Discriminator {
+        Discriminator::new(
+            Box::new(WebSocketFramer::new()),
+            vec![Box::new(WebSocketMasquerader::new())],
+        )
+    }
+
+    fn duplicate(&self) -> Box<dyn DiscriminatorFactory> {
+        Box::new(WebSocketDiscriminatorFactory {})
+    }
+}
+
+impl WebSocketDiscriminatorFactory {
+    pub fn new() -> WebSocketDiscriminatorFactory {
+        WebSocketDiscriminatorFactory {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discriminator::UnmaskedChunk;
+    use crate::masquerader::Masquerader;
+
+    #[test]
+    fn discriminator_factory_duplicate_works() {
+        let subject = WebSocketDiscriminatorFactory::new();
+
+        subject.duplicate();
+
+        // no panic; test passes
+    }
+
+    #[test]
+    fn factory_makes_discriminator_that_unmasks_websocket_data_after_handshake() {
+        let data = &b"I am contained in a WebSocket frame!"[..];
+        let masquerader = WebSocketMasquerader::new();
+        let frame = masquerader.mask(data).unwrap();
+        let request = WebSocketMasquerader::upgrade_request("1.2.3.4");
+        let subject = WebSocketDiscriminatorFactory::new();
+        let mut discriminator = subject.make();
+
+        discriminator.add_data(&[request, frame].concat());
+        let result = discriminator.take_chunk();
+
+        assert_eq!(result, Some(UnmaskedChunk::new(data.to_vec(), true, false)))
+    }
+}
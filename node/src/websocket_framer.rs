@@ -0,0 +1,154 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use crate::sub_lib::framer::FramedChunk;
+use crate::sub_lib::framer::Framer;
+use crate::websocket_masquerader::{WebSocketMasquerader, MAX_WEBSOCKET_PAYLOAD_LEN};
+
+#[derive(Default)]
+pub struct WebSocketFramer {
+    data_so_far: Vec<u8>,
+}
+
+impl Framer for WebSocketFramer {
+    fn add_data(&mut self, data: &[u8]) {
+        self.data_so_far.extend(data.iter());
+    }
+
+    fn take_frame(&mut self) -> Option<FramedChunk> {
+        if !self.skip_handshake() {
+            return None;
+        }
+        let frame_len = match Self::frame_len(&self.data_so_far) {
+            Ok(frame_len_opt) => frame_len_opt?,
+            Err(()) => {
+                // Waiting for a frame this big would buffer without bound; drop what we have
+                self.data_so_far.clear();
+                return None;
+            }
+        };
+        let remainder = self.data_so_far.split_off(frame_len);
+        let chunk = std::mem::replace(&mut self.data_so_far, remainder);
+        Some(FramedChunk {
+            chunk,
+            last_chunk: true,
+        })
+    }
+}
+
+impl WebSocketFramer {
+    pub fn new() -> WebSocketFramer {
+        WebSocketFramer {
+            data_so_far: vec![],
+        }
+    }
+
+    // The upgrade handshake is not data; drop it. Returns false if only part of it has arrived.
+    fn skip_handshake(&mut self) -> bool {
+        if WebSocketMasquerader::is_upgrade_request(&self.data_so_far)
+            || WebSocketMasquerader::is_upgrade_response(&self.data_so_far)
+        {
+            match WebSocketMasquerader::header_block_len(&self.data_so_far) {
+                Some(len) => {
+                    self.data_so_far.drain(..len);
+                }
+                None => return false,
+            }
+        }
+        true
+    }
+
+    // Err if the frame announces more payload than we are willing to wait for
+    fn frame_len(data: &[u8]) -> Result<Option<usize>, ()> {
+        let (payload_len, header_len) = match WebSocketMasquerader::payload_len(data) {
+            Some(lengths) => lengths,
+            None => return Ok(None),
+        };
+        if payload_len > MAX_WEBSOCKET_PAYLOAD_LEN {
+            return Err(());
+        }
+        let masking_key_len = if (data[1] & 0x80) != 0 { 4 } else { 0 };
+        let frame_len = (header_len + masking_key_len)
+            .checked_add(payload_len as usize)
+            .ok_or(())?;
+        if data.len() < frame_len {
+            Ok(None)
+        } else {
+            Ok(Some(frame_len))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::masquerader::Masquerader;
+
+    #[test]
+    fn websocket_framer_frames_consecutive_frames() {
+        let masquerader = WebSocketMasquerader::new();
+        let first = masquerader.mask(b"booga").unwrap();
+        let second = masquerader.mask(&[0xA5u8; 300]).unwrap();
+        let mut subject = WebSocketFramer::new();
+
+        subject.add_data(&[first.clone(), second.clone()].concat());
+
+        assert_eq!(subject.take_frame().unwrap().chunk, first);
+        assert_eq!(subject.take_frame().unwrap().chunk, second);
+        assert_eq!(subject.take_frame(), None);
+    }
+
+    #[test]
+    fn websocket_framer_handles_badly_fragmented_input() {
+        let masquerader = WebSocketMasquerader::new();
+        let frame = masquerader.mask(&[0x5Au8; 70000]).unwrap();
+        let mut subject = WebSocketFramer::new();
+
+        subject.add_data(&frame[..1]);
+        assert_eq!(subject.take_frame(), None);
+        subject.add_data(&frame[1..5]);
+        assert_eq!(subject.take_frame(), None);
+        subject.add_data(&frame[5..60000]);
+        assert_eq!(subject.take_frame(), None);
+        subject.add_data(&frame[60000..]);
+        assert_eq!(subject.take_frame().unwrap().chunk, frame);
+        assert_eq!(subject.take_frame(), None);
+    }
+
+    #[test]
+    fn websocket_framer_drops_frames_announcing_a_maximal_length() {
+        let masquerader = WebSocketMasquerader::new();
+        let hostile = [
+            &[0x82u8, 0x80 | 127][..],
+            &[0xFF; 8],
+            &[1, 2, 3, 4],
+            b"booga",
+        ]
+        .concat();
+        let frame = masquerader.mask(b"booga").unwrap();
+        let mut subject = WebSocketFramer::new();
+
+        subject.add_data(&hostile);
+        assert_eq!(subject.take_frame(), None);
+        assert!(subject.data_so_far.is_empty());
+        subject.add_data(&frame);
+
+        assert_eq!(subject.take_frame().unwrap().chunk, frame);
+    }
+
+    #[test]
+    fn websocket_framer_skips_upgrade_request_and_response() {
+        let masquerader = WebSocketMasquerader::new();
+        let request = WebSocketMasquerader::upgrade_request("1.2.3.4");
+        let response = WebSocketMasquerader::upgrade_response(&request).unwrap();
+        let frame = masquerader.mask(b"booga").unwrap();
+        let mut request_subject = WebSocketFramer::new();
+        let mut response_subject = WebSocketFramer::new();
+
+        request_subject.add_data(&request[..10]);
+        assert_eq!(request_subject.take_frame(), None);
+        request_subject.add_data(&[&request[10..], &frame[..]].concat());
+        response_subject.add_data(&[response, frame.clone()].concat());
+
+        assert_eq!(request_subject.take_frame().unwrap().chunk, frame);
+        assert_eq!(response_subject.take_frame().unwrap().chunk, frame);
+    }
+}
@@ -0,0 +1,440 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+use crate::discriminator::UnmaskedChunk;
+use crate::masquerader::ConnectionSide;
+use crate::masquerader::MasqueradeError;
+use crate::masquerader::Masquerader;
+use masq_lib::logger::Logger;
+use rand::Rng;
+
+pub const WEBSOCKET_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+const UPGRADE_REQUEST_PREFIX: &[u8] = b"GET / HTTP/1.1\r\n";
+const UPGRADE_RESPONSE_PREFIX: &[u8] = b"HTTP/1.1 101 ";
+const HEADER_TERMINATOR: &[u8] = b"\r\n\r\n";
+const FIN_BIT: u8 = 0x80;
+const MASK_BIT: u8 = 0x80;
+const OPCODE_MASK: u8 = 0x0F;
+const TEXT_OPCODE: u8 = 0x1;
+const BINARY_OPCODE: u8 = 0x2;
+// The peer announces the payload length; anything beyond this is refused rather than buffered
+pub const MAX_WEBSOCKET_PAYLOAD_LEN: u64 = 16 * 1024 * 1024;
+
+// RFC 6455 section 5.1: only the side that opened the connection masks the frames it sends.
+// Frames arriving from the peer are accepted whether they are masked or not.
+pub struct WebSocketMasquerader {
+    masks_frames: bool,
+    logger: Logger,
+}
+
+impl Masquerader for WebSocketMasquerader {
+    fn try_unmask(&self, item: &[u8]) -> Result<UnmaskedChunk, MasqueradeError> {
+        match self.unmask(item) {
+            Ok(chunk) => Ok(chunk),
+            Err(MasqueradeError::NotThisMasquerader) => Err(MasqueradeError::NotThisMasquerader),
+            Err(err) => {
+                error!(self.logger, "{}", err);
+                Err(err)
+            }
+        }
+    }
+
+    fn mask(&self, data: &[u8]) -> Result<Vec<u8>, MasqueradeError> {
+        let mut frame = Vec::with_capacity(data.len() + 14);
+        frame.push(FIN_BIT | BINARY_OPCODE);
+        let mask_bit = if self.masks_frames { MASK_BIT } else { 0 };
+        match data.len() {
+            len if len < 126 => frame.push(mask_bit | len as u8),
+            len if len <= u16::MAX as usize => {
+                frame.push(mask_bit | 126);
+                frame.extend_from_slice(&(len as u16).to_be_bytes());
+            }
+            len => {
+                frame.push(mask_bit | 127);
+                frame.extend_from_slice(&(len as u64).to_be_bytes());
+            }
+        }
+        if !self.masks_frames {
+            frame.extend_from_slice(data);
+            return Ok(frame);
+        }
+        let masking_key: [u8; 4] = rand::thread_rng().gen();
+        frame.extend_from_slice(&masking_key);
+        frame.extend(
+            data.iter()
+                .enumerate()
+                .map(|(idx, byte)| byte ^ masking_key[idx % 4]),
+        );
+        Ok(frame)
+    }
+}
+
+impl Default for WebSocketMasquerader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WebSocketMasquerader {
+    pub fn new() -> WebSocketMasquerader {
+        Self::for_side(ConnectionSide::Connecting)
+    }
+
+    pub fn for_side(side: ConnectionSide) -> WebSocketMasquerader {
+        WebSocketMasquerader {
+            masks_frames: side == ConnectionSide::Connecting,
+            logger: Logger::new("WebSocketMasquerader"),
+        }
+    }
+
+    pub fn upgrade_request(host: &str) -> Vec<u8> {
+        let key: [u8; 16] = rand::thread_rng().gen();
+        format!(
+            "GET / HTTP/1.1\r\n\
+             Host: {}\r\n\
+             Upgrade: websocket\r\n\
+             Connection: Upgrade\r\n\
+             Sec-WebSocket-Key: {}\r\n\
+             Sec-WebSocket-Version: 13\r\n\r\n",
+            host,
+            base64::encode(key)
+        )
+        .into_bytes()
+    }
+
+    pub fn upgrade_response(request: &[u8]) -> Option<Vec<u8>> {
+        let header = Self::header_block(request)?;
+        let key = header
+            .lines()
+            .filter_map(|line| line.split_once(':'))
+            .find(|(name, _)| name.trim().eq_ignore_ascii_case("Sec-WebSocket-Key"))
+            .map(|(_, value)| value.trim().to_string())?;
+        Some(
+            format!(
+                "HTTP/1.1 101 Switching Protocols\r\n\
+                 Upgrade: websocket\r\n\
+                 Connection: Upgrade\r\n\
+                 Sec-WebSocket-Accept: {}\r\n\r\n",
+                Self::accept_value(&key)
+            )
+            .into_bytes(),
+        )
+    }
+
+    pub fn is_upgrade_request(data: &[u8]) -> bool {
+        data.starts_with(UPGRADE_REQUEST_PREFIX)
+    }
+
+    pub fn is_upgrade_response(data: &[u8]) -> bool {
+        data.starts_with(UPGRADE_RESPONSE_PREFIX)
+    }
+
+    // Returns the length of the HTTP header block at the front of the data, if there is a complete one.
+    pub fn header_block_len(data: &[u8]) -> Option<usize> {
+        data.windows(HEADER_TERMINATOR.len())
+            .position(|window| window == HEADER_TERMINATOR)
+            .map(|pos| pos + HEADER_TERMINATOR.len())
+    }
+
+    fn header_block(data: &[u8]) -> Option<String> {
+        let len = Self::header_block_len(data)?;
+        String::from_utf8(data[..len].to_vec()).ok()
+    }
+
+    fn accept_value(key: &str) -> String {
+        let mut hash = sha1::Sha1::new();
+        hash.update(key.as_bytes());
+        hash.update(WEBSOCKET_GUID.as_bytes());
+        base64::encode(hash.digest().bytes())
+    }
+
+    // Announced payload length and the length of the header that announced it, if the header is complete
+    pub fn payload_len(frame: &[u8]) -> Option<(u64, usize)> {
+        if frame.len() < 2 {
+            return None;
+        }
+        match frame[1] & !MASK_BIT {
+            126 if frame.len() >= 4 => Some((u16::from_be_bytes([frame[2], frame[3]]) as u64, 4)),
+            127 if frame.len() >= 10 => {
+                let mut len_bytes = [0u8; 8];
+                len_bytes.copy_from_slice(&frame[2..10]);
+                Some((u64::from_be_bytes(len_bytes), 10))
+            }
+            126 | 127 => None,
+            len => Some((len as u64, 2)),
+        }
+    }
+
+    fn unmask(&self, frame: &[u8]) -> Result<UnmaskedChunk, MasqueradeError> {
+        if frame.len() < 2 || (frame[0] & FIN_BIT) == 0 {
+            return Err(MasqueradeError::NotThisMasquerader);
+        }
+        match frame[0] & OPCODE_MASK {
+            TEXT_OPCODE | BINARY_OPCODE => (),
+            opcode => {
+                return Err(MasqueradeError::MidLevelDataError(format!(
+                    "Unexpected WebSocket opcode {}",
+                    opcode
+                )))
+            }
+        }
+        let (payload_len, mut offset) = match Self::payload_len(frame) {
+            Some((payload_len, _)) if payload_len > MAX_WEBSOCKET_PAYLOAD_LEN => {
+                return Err(MasqueradeError::LowLevelDataError(format!(
+                    "WebSocket frame announced {} bytes of payload; the limit is {}",
+                    payload_len, MAX_WEBSOCKET_PAYLOAD_LEN
+                )))
+            }
+            Some((payload_len, header_len)) => (payload_len as usize, header_len),
+            None => {
+                return Err(MasqueradeError::LowLevelDataError(
+                    "WebSocket frame header was truncated".to_string(),
+                ))
+            }
+        };
+        let masking_key_opt = if (frame[1] & MASK_BIT) != 0 {
+            if frame.len() < offset + 4 {
+                return Err(MasqueradeError::LowLevelDataError(
+                    "WebSocket masking key was truncated".to_string(),
+                ));
+            }
+            let masking_key = [
+                frame[offset],
+                frame[offset + 1],
+                frame[offset + 2],
+                frame[offset + 3],
+            ];
+            offset += 4;
+            Some(masking_key)
+        } else {
+            None
+        };
+        if offset.checked_add(payload_len) != Some(frame.len()) {
+            return Err(MasqueradeError::LowLevelDataError(format!(
+                "WebSocket frame announced {} bytes of payload but contained {}",
+                payload_len,
+                frame.len().saturating_sub(offset)
+            )));
+        }
+        let payload = &frame[offset..];
+        let data = match masking_key_opt {
+            Some(masking_key) => payload
+                .iter()
+                .enumerate()
+                .map(|(idx, byte)| byte ^ masking_key[idx % 4])
+                .collect(),
+            None => payload.to_vec(),
+        };
+        Ok(UnmaskedChunk::new(data, true, false))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use masq_lib::test_utils::logging::init_test_logging;
+    use masq_lib::test_utils::logging::TestLogHandler;
+
+    #[test]
+    fn websocket_masquerader_can_mask_and_unmask_small_payload() {
+        let subject = WebSocketMasquerader::new();
+        let data = subject.mask(b"Fourscore and seven years ago").unwrap();
+
+        let unmasked_chunk = subject.try_unmask(&data[..]).unwrap();
+
+        assert_eq!(data[0], 0x82);
+        assert_eq!(data[1], 0x80 | 29);
+        assert_eq!(data.len(), 2 + 4 + 29);
+        assert_eq!(
+            unmasked_chunk,
+            UnmaskedChunk::new(b"Fourscore and seven years ago".to_vec(), true, false)
+        );
+    }
+
+    #[test]
+    fn websocket_masquerader_can_mask_and_unmask_medium_and_large_payloads() {
+        let subject = WebSocketMasquerader::new();
+        let medium = vec![0xA5u8; 300];
+        let large = vec![0x5Au8; 70000];
+
+        let masked_medium = subject.mask(&medium).unwrap();
+        let masked_large = subject.mask(&large).unwrap();
+
+        assert_eq!(masked_medium[1], 0x80 | 126);
+        assert_eq!(&masked_medium[2..4], &300u16.to_be_bytes());
+        assert_eq!(masked_large[1], 0x80 | 127);
+        assert_eq!(&masked_large[2..10], &70000u64.to_be_bytes());
+        assert_eq!(subject.try_unmask(&masked_medium).unwrap().chunk, medium);
+        assert_eq!(subject.try_unmask(&masked_large).unwrap().chunk, large);
+    }
+
+    #[test]
+    fn accepting_side_sends_unmasked_frames_that_the_connecting_side_can_read() {
+        let server = WebSocketMasquerader::for_side(ConnectionSide::Accepting);
+        let client = WebSocketMasquerader::for_side(ConnectionSide::Connecting);
+        let small = b"Fourscore and seven years ago".to_vec();
+        let large = vec![0x5Au8; 70000];
+
+        let small_frame = server.mask(&small).unwrap();
+        let large_frame = server.mask(&large).unwrap();
+
+        assert_eq!(small_frame[0], 0x82);
+        assert_eq!(small_frame[1], 29);
+        assert_eq!(&small_frame[2..], &small[..]);
+        assert_eq!(large_frame[1], 127);
+        assert_eq!(&large_frame[2..10], &70000u64.to_be_bytes());
+        assert_eq!(&large_frame[10..], &large[..]);
+        assert_eq!(
+            client.try_unmask(&small_frame).unwrap(),
+            UnmaskedChunk::new(small, true, false)
+        );
+        assert_eq!(client.try_unmask(&large_frame).unwrap().chunk, large);
+    }
+
+    #[test]
+    fn connecting_side_sends_masked_frames_that_the_accepting_side_can_read() {
+        let client = WebSocketMasquerader::for_side(ConnectionSide::Connecting);
+        let server = WebSocketMasquerader::for_side(ConnectionSide::Accepting);
+
+        let frame = client.mask(b"booga").unwrap();
+
+        assert_eq!(frame[1], 0x80 | 5);
+        assert_eq!(frame.len(), 2 + 4 + 5);
+        assert_eq!(server.try_unmask(&frame).unwrap().chunk, b"booga".to_vec());
+    }
+
+    #[test]
+    fn websocket_masquerader_unmasks_unmasked_server_frames() {
+        let subject = WebSocketMasquerader::new();
+        let frame = [&[0x82u8, 5][..], b"booga"].concat();
+
+        let result = subject.try_unmask(&frame).unwrap();
+
+        assert_eq!(result, UnmaskedChunk::new(b"booga".to_vec(), true, false));
+    }
+
+    #[test]
+    fn websocket_masquerader_declines_json() {
+        let subject = WebSocketMasquerader::new();
+
+        let result = subject.try_unmask(b"{\"bodyText\": \"booga\"}");
+
+        assert_eq!(result, Err(MasqueradeError::NotThisMasquerader));
+    }
+
+    #[test]
+    fn websocket_masquerader_complains_about_control_frames() {
+        init_test_logging();
+        let subject = WebSocketMasquerader::new();
+
+        let result = subject.try_unmask(&[0x89, 0x00]);
+
+        assert_eq!(
+            result,
+            Err(MasqueradeError::MidLevelDataError(
+                "Unexpected WebSocket opcode 9".to_string()
+            ))
+        );
+        TestLogHandler::new().exists_log_containing(
+            "ERROR: WebSocketMasquerader: Mid-level data error: Unexpected WebSocket opcode 9",
+        );
+    }
+
+    #[test]
+    fn websocket_masquerader_complains_about_length_mismatch() {
+        let subject = WebSocketMasquerader::new();
+        let frame = [&[0x82u8, 10][..], b"booga"].concat();
+
+        let result = subject.try_unmask(&frame);
+
+        assert_eq!(
+            result,
+            Err(MasqueradeError::LowLevelDataError(
+                "WebSocket frame announced 10 bytes of payload but contained 5".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn websocket_masquerader_refuses_frames_announcing_a_maximal_length() {
+        let subject = WebSocketMasquerader::new();
+        let frame = [
+            &[0x82u8, 0x80 | 127][..],
+            &[0xFF; 8],
+            &[1, 2, 3, 4],
+            b"booga",
+        ]
+        .concat();
+
+        let result = subject.try_unmask(&frame);
+
+        assert_eq!(
+            result,
+            Err(MasqueradeError::LowLevelDataError(
+                "WebSocket frame announced 18446744073709551615 bytes of payload; the limit is 16777216"
+                    .to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn websocket_masquerader_accepts_frames_at_the_length_limit() {
+        let subject = WebSocketMasquerader::new();
+        let data = vec![0x33u8; MAX_WEBSOCKET_PAYLOAD_LEN as usize];
+        let frame = subject.mask(&data).unwrap();
+
+        let result = subject.try_unmask(&frame).unwrap();
+
+        assert_eq!(result.chunk, data);
+    }
+
+    #[test]
+    fn upgrade_request_and_response_follow_rfc_6455() {
+        let request = WebSocketMasquerader::upgrade_request("1.2.3.4");
+        let request_string = String::from_utf8(request.clone()).unwrap();
+
+        let response = WebSocketMasquerader::upgrade_response(&request).unwrap();
+
+        assert!(WebSocketMasquerader::is_upgrade_request(&request));
+        assert!(request_string.contains("Host: 1.2.3.4\r\n"));
+        assert!(request_string.contains("Upgrade: websocket\r\n"));
+        assert!(!request_string.contains('{'));
+        assert_eq!(
+            WebSocketMasquerader::header_block_len(&request),
+            Some(request.len())
+        );
+        assert!(WebSocketMasquerader::is_upgrade_response(&response));
+        assert!(!WebSocketMasquerader::is_upgrade_request(&response));
+    }
+
+    #[test]
+    fn upgrade_response_computes_accept_value_from_key() {
+        // Example taken from RFC 6455, section 1.3
+        let request = b"GET / HTTP/1.1\r\n\
+            Host: server.example.com\r\n\
+            Upgrade: websocket\r\n\
+            Connection: Upgrade\r\n\
+            Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\
+            Sec-WebSocket-Version: 13\r\n\r\n";
+
+        let result = WebSocketMasquerader::upgrade_response(request).unwrap();
+
+        assert_eq!(
+            String::from_utf8(result).unwrap(),
+            "HTTP/1.1 101 Switching Protocols\r\n\
+             Upgrade: websocket\r\n\
+             Connection: Upgrade\r\n\
+             Sec-WebSocket-Accept: s3pPLMBiTxaQ9kYGzzhZRbK+xOo=\r\n\r\n"
+        );
+    }
+
+    #[test]
+    fn upgrade_response_needs_a_complete_request_with_a_key() {
+        assert_eq!(
+            WebSocketMasquerader::upgrade_response(b"GET / HTTP/1.1\r\nHost: booga\r\n"),
+            None
+        );
+        assert_eq!(
+            WebSocketMasquerader::upgrade_response(b"GET / HTTP/1.1\r\nHost: booga\r\n\r\n"),
+            None
+        );
+    }
+}
@@ -0,0 +1,115 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use futures::Future;
+use masq_lib::test_utils::utils::ensure_node_home_directory_exists;
+use node_lib::blockchain::external_signer::{
+    ExternalSigner, ExternalSignerClef, ExternalSignerEndpoint,
+};
+use node_lib::test_utils::mock_external_signer::MockExternalSigner;
+use rustc_hex::FromHex;
+use serde_json::{json, Value};
+use std::str::FromStr;
+use web3::transports::Http;
+use web3::types::{Address, Bytes, SignedTransaction, TransactionParameters, U256};
+use web3::Web3;
+
+const SIGNER_PRIVATE_KEY: &str = "97923d8fd8de4a00f912bfb77ef483141dec551bd73ea59343ef5c4aac965d04";
+
+fn make_transaction_params() -> TransactionParameters {
+    TransactionParameters {
+        nonce: Some(U256::from(7)),
+        to: Some(Address::from_low_u64_be(0x3535)),
+        gas: U256::from(21_000),
+        gas_price: Some(U256::from(20_000_000_000u64)),
+        value: U256::from(1_000_000_000u64),
+        data: Bytes(vec![]),
+        chain_id: Some(80002),
+    }
+}
+
+// What a real signer holding SIGNER_PRIVATE_KEY would produce
+fn sign_like_the_signer(transaction_params: &TransactionParameters) -> SignedTransaction {
+    let key_bytes: Vec<u8> = SIGNER_PRIVATE_KEY.from_hex().unwrap();
+    let key = secp256k1secrets::key::SecretKey::from_slice(&key_bytes).unwrap();
+    // With nonce, gas price and chain id all given, signing never touches the network
+    let (_event_loop_handle, transport) = Http::new("http://127.0.0.1:1").unwrap();
+    Web3::new(transport)
+        .accounts()
+        .sign_transaction(transaction_params.clone(), &key)
+        .wait()
+        .unwrap()
+}
+
+fn clef_result(signed: &SignedTransaction) -> Value {
+    json!({
+        "raw": signed.raw_transaction,
+        "tx": {
+            "v": format!("{:#x}", signed.v),
+            "r": signed.r,
+            "s": signed.s,
+            "hash": signed.transaction_hash,
+        }
+    })
+}
+
+fn assert_signature_round_trip(signer: MockExternalSigner, expected: &SignedTransaction) {
+    let endpoint = ExternalSignerEndpoint::from_str(&signer.url()).unwrap();
+    let subject = ExternalSignerClef::new(&endpoint).unwrap();
+    let from = Address::from_low_u64_be(0x1234);
+
+    let first = subject.sign_transaction(from, &make_transaction_params());
+    let second = subject.sign_transaction(from, &make_transaction_params());
+
+    let first = first.unwrap();
+    assert_eq!(first.raw_transaction, expected.raw_transaction);
+    assert_eq!(first.transaction_hash, expected.transaction_hash);
+    assert_eq!(first.v, expected.v);
+    assert_eq!(first.r, expected.r);
+    assert_eq!(first.s, expected.s);
+    assert_eq!(
+        second.err().unwrap().to_string(),
+        "External signer refused to sign: Request denied".to_string()
+    );
+    let requests = signer.requests();
+    assert_eq!(requests.len(), 2);
+    requests.iter().for_each(|request| {
+        assert_eq!(request["method"], json!("account_signTransaction"));
+        assert_eq!(
+            request["params"],
+            json!([{
+                "from": "0x0000000000000000000000000000000000001234",
+                "to": "0x0000000000000000000000000000000000003535",
+                "gas": "0x5208",
+                "gasPrice": "0x4a817c800",
+                "value": "0x3b9aca00",
+                "input": "0x",
+                "nonce": "0x7",
+                "chainId": "0x13882"
+            }])
+        );
+    });
+}
+
+#[test]
+fn clef_signer_over_http_integration() {
+    let expected = sign_like_the_signer(&make_transaction_params());
+    let signer = MockExternalSigner::builder()
+        .response(clef_result(&expected))
+        .error(-32000, "Request denied")
+        .start_http();
+
+    assert_signature_round_trip(signer, &expected);
+}
+
+#[cfg(unix)]
+#[test]
+fn clef_signer_over_ipc_integration() {
+    let home_dir = ensure_node_home_directory_exists("external_signer_test", "ipc");
+    let expected = sign_like_the_signer(&make_transaction_params());
+    let signer = MockExternalSigner::builder()
+        .response(clef_result(&expected))
+        .error(-32000, "Request denied")
+        .start_ipc(&home_dir.join("clef.ipc"));
+
+    assert_signature_round_trip(signer, &expected);
+}
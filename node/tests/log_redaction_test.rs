@@ -0,0 +1,167 @@
+// Copyright (c) 2019, MASQ (https://masq.ai) and/or its affiliates. All rights reserved.
+
+use regex::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Any value logged whose expression mentions one of these is taken to be a secret
+const SECRET_WORDS: &str = r"(?i)password|mnemonic|seed|phrase|private_?key|secret|passphrase";
+
+#[test]
+fn log_sites_wrap_passwords_mnemonics_and_keys_in_sensitive() {
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let mut log_site_count = 0;
+    let mut offenders = vec![];
+    for source_dir in [
+        manifest_dir.join("src"),
+        manifest_dir.join("../masq_lib/src"),
+    ] {
+        for file in rust_files(&source_dir) {
+            let source = fs::read_to_string(&file).unwrap();
+            for (line, arguments) in log_site_arguments(&source) {
+                log_site_count += 1;
+                arguments
+                    .iter()
+                    .filter(|argument| is_unwrapped_secret(argument))
+                    .for_each(|argument| {
+                        offenders.push(format!("{}:{}: {}", file.display(), line, argument))
+                    });
+            }
+        }
+    }
+
+    assert!(
+        log_site_count > 100,
+        "Only {} log sites found; the scan is broken",
+        log_site_count
+    );
+    assert!(
+        offenders.is_empty(),
+        "These logged values look secret but aren't marked .sensitive():\n{}",
+        offenders.join("\n")
+    );
+}
+
+#[test]
+fn the_scan_finds_a_secret_logged_in_the_clear() {
+    let source = r#"
+        fn example(&self) {
+            info!(self.logger, "Password is {}", self.db_password);
+            debug!(self.logger, "Mnemonic: {:?}", mnemonic.phrase().sensitive());
+            warning!(self.logger, "No password supplied");
+        }
+    "#;
+
+    let unwrapped = log_site_arguments(source)
+        .into_iter()
+        .flat_map(|(_, arguments)| arguments)
+        .filter(|argument| is_unwrapped_secret(argument))
+        .collect::<Vec<String>>();
+
+    assert_eq!(unwrapped, vec!["self.db_password".to_string()]);
+}
+
+fn is_unwrapped_secret(argument: &str) -> bool {
+    Regex::new(SECRET_WORDS).unwrap().is_match(argument) && !argument.contains(".sensitive()")
+}
+
+fn rust_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = vec![];
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            files.extend(rust_files(&path));
+        } else if path.extension().map(|ext| ext == "rs").unwrap_or(false) {
+            files.push(path);
+        }
+    }
+    files
+}
+
+// Yields the line number of every logging macro call and the arguments that follow its format
+// string. String literals are dropped, so only expressions that are actually logged are left.
+fn log_site_arguments(source: &str) -> Vec<(usize, Vec<String>)> {
+    let log_macro = Regex::new(r"\b(trace|debug|info|warning|error|fatal)!\s*\(").unwrap();
+    log_macro
+        .find_iter(source)
+        .map(|found| {
+            let line = source[..found.start()].matches('\n').count() + 1;
+            let body = macro_body(&source[found.end()..]);
+            let arguments = split_top_level(&body)
+                .into_iter()
+                .skip(1) // the Logger
+                .skip_while(|argument| !argument.starts_with('"') && !argument.starts_with("r#"))
+                .skip(1) // the format string
+                .collect();
+            (line, arguments)
+        })
+        .collect()
+}
+
+fn macro_body(rest: &str) -> String {
+    let mut depth = 1;
+    let mut in_string = false;
+    let mut previous = ' ';
+    let mut body = String::new();
+    for c in rest.chars() {
+        if in_string {
+            if c == '"' && previous != '\\' {
+                in_string = false;
+            }
+        } else {
+            match c {
+                '"' => in_string = true,
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' => depth -= 1,
+                _ => (),
+            }
+            if depth == 0 {
+                break;
+            }
+        }
+        body.push(c);
+        previous = if previous == '\\' && c == '\\' {
+            ' '
+        } else {
+            c
+        };
+    }
+    body
+}
+
+fn split_top_level(body: &str) -> Vec<String> {
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut previous = ' ';
+    let mut pieces = vec![String::new()];
+    for c in body.chars() {
+        if in_string {
+            if c == '"' && previous != '\\' {
+                in_string = false;
+            }
+        } else {
+            match c {
+                '"' => in_string = true,
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' => depth -= 1,
+                ',' if depth == 0 => {
+                    pieces.push(String::new());
+                    previous = c;
+                    continue;
+                }
+                _ => (),
+            }
+        }
+        pieces.last_mut().unwrap().push(c);
+        previous = if previous == '\\' && c == '\\' {
+            ' '
+        } else {
+            c
+        };
+    }
+    pieces
+        .into_iter()
+        .map(|piece| piece.split_whitespace().collect::<Vec<&str>>().join(" "))
+        .filter(|piece| !piece.is_empty())
+        .collect()
+}